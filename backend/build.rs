@@ -0,0 +1,233 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // API gRPC das urnas
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/fortis/urna/v1/urna.proto"], &["proto"])?;
+
+    // Inventário das rotas do actix, conferido com a documentação OpenAPI
+    let mut routes = Vec::new();
+    collect_routes(Path::new("src/main.rs"), "main", "", &mut routes)?;
+    routes.sort();
+    routes.dedup();
+
+    let mut code = String::from("/// Rotas registradas no actix (método, caminho), extraídas pelo build.rs\n");
+    code.push_str("pub const REGISTERED_ROUTES: &[(&str, &str)] = &[\n");
+    for (method, path) in &routes {
+        code.push_str(&format!("    ({:?}, {:?}),\n", method, path));
+    }
+    code.push_str("];\n");
+    std::fs::write(PathBuf::from(std::env::var("OUT_DIR")?).join("route_inventory.rs"), code)?;
+    println!("cargo:rerun-if-changed=src");
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+/// Percorre a função de configuração, seguindo escopos, recursos e `.configure(...)`
+fn collect_routes(file: &Path, function: &str, prefix: &str, routes: &mut Vec<(String, String)>) -> Result<(), Box<dyn Error>> {
+    let source = std::fs::read_to_string(file)?;
+    let tokens = tokenize(&source);
+    let body = function_body(&tokens, function)
+        .ok_or_else(|| format!("{}: fn {} not found", file.display(), function))?;
+
+    let mut scopes: Vec<(usize, String)> = Vec::new();
+    let mut depth = 0usize;
+    for (k, token) in body.iter().enumerate() {
+        let current = scopes.last().map(|(_, path)| path.clone()).unwrap_or_else(|| prefix.to_string());
+        let after_dot = k > 0 && body[k - 1] == Token::Punct('.');
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => {
+                depth = depth.saturating_sub(1);
+                while scopes.last().is_some_and(|(open, _)| *open > depth) {
+                    scopes.pop();
+                }
+            }
+            Token::Ident(name) if name == "web::scope" || name == "web::resource" => {
+                if let [Token::Punct('('), Token::Str(path), ..] = &body[k + 1..] {
+                    scopes.push((depth, format!("{}{}", current, path)));
+                }
+            }
+            Token::Ident(name) if name == "route" && after_dot => match &body[k + 1..] {
+                [Token::Punct('('), Token::Str(path), Token::Punct(','), Token::Ident(method), ..] => {
+                    if let Some(method) = http_method(method) {
+                        routes.push((method, format!("{}{}", current, path)));
+                    }
+                }
+                [Token::Punct('('), Token::Ident(method), ..] => {
+                    if let Some(method) = http_method(method) {
+                        routes.push((method, current));
+                    }
+                }
+                _ => {}
+            },
+            Token::Ident(name) if name == "configure" && after_dot => {
+                if let [Token::Punct('('), Token::Ident(target), Token::Punct(')'), ..] = &body[k + 1..] {
+                    let (module, function) = target.rsplit_once("::").unwrap_or(("", target));
+                    let target_file = resolve_module(file, module)
+                        .ok_or_else(|| format!("{}: module {} not found", file.display(), module))?;
+                    collect_routes(&target_file, function, &current, routes)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn http_method(constructor: &str) -> Option<String> {
+    let method = constructor.strip_prefix("web::")?;
+    matches!(method, "get" | "post" | "put" | "delete" | "patch" | "head").then(|| method.to_uppercase())
+}
+
+/// Tokens entre as chaves de `fn <name>`
+fn function_body<'a>(tokens: &'a [Token], name: &str) -> Option<&'a [Token]> {
+    let start = tokens.windows(2)
+        .position(|pair| pair[0] == Token::Ident("fn".to_string()) && pair[1] == Token::Ident(name.to_string()))?;
+    let open = start + tokens[start..].iter().position(|token| *token == Token::Punct('{'))?;
+    let mut depth = 0usize;
+    for (offset, token) in tokens[open..].iter().enumerate() {
+        match token {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&tokens[open + 1..open + offset]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Arquivo do módulo `a::b`, relativo ao módulo atual ou à raiz do crate
+fn resolve_module(file: &Path, module: &str) -> Option<PathBuf> {
+    let stem = file.file_stem()?.to_str()?;
+    let parent = file.parent()?;
+    let own_dir = if matches!(stem, "main" | "lib" | "mod") { parent.to_path_buf() } else { parent.join(stem) };
+
+    let segments: Vec<&str> = module.split("::").filter(|segment| !segment.is_empty() && *segment != "self").collect();
+    let (bases, segments) = match segments.first() {
+        Some(&"crate") => (vec![PathBuf::from("src")], &segments[1..]),
+        _ => (vec![own_dir, PathBuf::from("src")], &segments[..]),
+    };
+    if segments.is_empty() {
+        return Some(file.to_path_buf());
+    }
+    bases.into_iter().find_map(|base| {
+        let dir = segments.iter().fold(base, |dir, segment| dir.join(segment));
+        let as_file = dir.with_extension("rs");
+        let as_dir = dir.join("mod.rs");
+        if as_file.is_file() {
+            Some(as_file)
+        } else if as_dir.is_file() {
+            Some(as_dir)
+        } else {
+            None
+        }
+    })
+}
+
+/// Léxico mínimo de Rust: caminhos, strings e pontuação, sem comentários
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if c == 'r' && matches!(next, Some('"') | Some('#')) {
+            let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+            let open = i + 1 + hashes;
+            if chars.get(open) != Some(&'"') {
+                tokens.push(Token::Ident("r".to_string()));
+                i += 1;
+                continue;
+            }
+            let mut end = open + 1;
+            while end < chars.len()
+                && !(chars[end] == '"' && chars[end + 1..].iter().take(hashes).filter(|c| **c == '#').count() == hashes)
+            {
+                end += 1;
+            }
+            tokens.push(Token::Str(chars[open + 1..end.min(chars.len())].iter().collect()));
+            i = end + 1 + hashes;
+        } else if c == '"' {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                if let Some(c) = chars.get(i) {
+                    value.push(*c);
+                }
+                i += 1;
+            }
+            tokens.push(Token::Str(value));
+            i += 1;
+        } else if c == '\'' {
+            // Literal de caractere ('(' não pode contar como parêntese); tempo de vida segue como identificador
+            if next == Some('\\') {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            } else if chars.get(i + 2) == Some(&'\'') {
+                i += 3;
+            } else {
+                i += 1;
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut ident = String::new();
+            while i < chars.len() {
+                if chars[i].is_alphanumeric() || chars[i] == '_' {
+                    ident.push(chars[i]);
+                    i += 1;
+                } else if chars[i] == ':' && chars.get(i + 1) == Some(&':') {
+                    ident.push_str("::");
+                    i += 2;
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Ident(ident));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
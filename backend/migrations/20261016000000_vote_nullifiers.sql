@@ -0,0 +1,11 @@
+-- Nullifiers usados: fonte de verdade do filtro de Bloom do backend.
+-- O índice único impede voto duplo mesmo com o filtro desatualizado.
+CREATE SCHEMA IF NOT EXISTS voting;
+
+CREATE TABLE IF NOT EXISTS voting.vote_nullifiers (
+    nullifier VARCHAR(128) PRIMARY KEY,
+    election_id UUID,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_vote_nullifiers_election_id ON voting.vote_nullifiers(election_id);
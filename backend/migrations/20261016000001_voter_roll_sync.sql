@@ -0,0 +1,26 @@
+-- Sincronização incremental do cadastro eleitoral a partir da API do TSE.
+-- source_updated_at guarda a versão do TSE; versões antigas não sobrescrevem as novas.
+ALTER TABLE tse.voters ADD COLUMN IF NOT EXISTS polling_place_id VARCHAR(32);
+ALTER TABLE tse.voters ADD COLUMN IF NOT EXISTS source_updated_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS tse.polling_places (
+    id VARCHAR(32) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    address TEXT NOT NULL,
+    city VARCHAR(100) NOT NULL,
+    state VARCHAR(2) NOT NULL,
+    zone VARCHAR(10) NOT NULL,
+    sections TEXT[] NOT NULL DEFAULT '{}',
+    source_updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Execuções da sincronização; a última bem-sucedida define o próximo updated_since
+CREATE TABLE IF NOT EXISTS tse.voter_roll_sync_runs (
+    id UUID PRIMARY KEY,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    finished_at TIMESTAMP WITH TIME ZONE,
+    status VARCHAR(20) NOT NULL,
+    summary JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_voter_roll_sync_runs_status ON tse.voter_roll_sync_runs(status, started_at);
@@ -0,0 +1,12 @@
+-- Dashboards de auditoria; a definição completa (widgets e layout) fica em JSONB.
+CREATE SCHEMA IF NOT EXISTS audit;
+
+CREATE TABLE IF NOT EXISTS audit.dashboards (
+    id VARCHAR(64) PRIMARY KEY,
+    owner VARCHAR(128) NOT NULL,
+    definition JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_dashboards_owner ON audit.dashboards(owner);
@@ -0,0 +1,22 @@
+-- Série temporal dos heartbeats das urnas; a amostra completa fica em JSONB.
+CREATE SCHEMA IF NOT EXISTS monitoring;
+
+CREATE TABLE IF NOT EXISTS monitoring.urna_heartbeats (
+    id BIGSERIAL PRIMARY KEY,
+    urna_id VARCHAR(64) NOT NULL,
+    received_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    reported_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    state VARCHAR(2),
+    municipality_code VARCHAR(16),
+    zone VARCHAR(16),
+    section VARCHAR(16),
+    battery_level REAL,
+    on_battery BOOLEAN,
+    printer VARCHAR(32),
+    pending_votes BIGINT NOT NULL,
+    last_error_code VARCHAR(64),
+    sample JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_urna_heartbeats_urna ON monitoring.urna_heartbeats(urna_id, received_at DESC);
+CREATE INDEX IF NOT EXISTS idx_urna_heartbeats_zone ON monitoring.urna_heartbeats(state, zone, received_at DESC);
@@ -0,0 +1,19 @@
+-- Ocorrências de códigos de falha reportadas nos heartbeats das urnas
+CREATE TABLE IF NOT EXISTS monitoring.urna_faults (
+    id BIGSERIAL PRIMARY KEY,
+    urna_id VARCHAR(64) NOT NULL,
+    model VARCHAR(64),
+    state VARCHAR(2),
+    municipality_code VARCHAR(16),
+    zone VARCHAR(16),
+    section VARCHAR(16),
+    code VARCHAR(64) NOT NULL,
+    occurrences BIGINT NOT NULL,
+    first_seen TIMESTAMP WITH TIME ZONE NOT NULL,
+    last_seen TIMESTAMP WITH TIME ZONE NOT NULL,
+    received_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_urna_faults_code ON monitoring.urna_faults(code, received_at DESC);
+CREATE INDEX IF NOT EXISTS idx_urna_faults_model ON monitoring.urna_faults(model, code, received_at DESC);
+CREATE INDEX IF NOT EXISTS idx_urna_faults_urna ON monitoring.urna_faults(urna_id, received_at DESC);
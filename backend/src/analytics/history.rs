@@ -0,0 +1,511 @@
+//! Consulta Histórica das Eleições Arquivadas
+//!
+//! Responde perguntas que cruzam eleições (evolução do comparecimento por
+//! município, votação de um número ao longo dos anos) a partir do índice
+//! dos pacotes de dados abertos publicados, sem consultar os dados vivos
+//! da apuração. O índice é montado do diretório de arquivo: só entram
+//! pacotes com manifesto de assinatura válida e arquivos com o resumo do
+//! manifesto. Cada página de resposta é assinada com Ed25519, como os
+//! manifestos, e traz o resumo dos manifestos indexados, para que o
+//! pesquisador confira a resposta sem baixar os pacotes.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use sha2::{Sha256, Digest};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+
+use super::open_data::{OpenDataService, SignedOpenDataManifest};
+use crate::crypto::hsm::SigningKey;
+
+/// Itens por página quando a consulta não informa o limite
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Limite máximo de itens por página
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// Eleição presente no índice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedElection {
+    pub election_id: String,
+    pub snapshot_id: String,
+    pub certified_at: DateTime<Utc>,
+    pub sections: usize,
+    /// Resumo do manifesto assinado do pacote de origem
+    pub manifest_hash: String,
+}
+
+/// Comparecimento de um município em uma eleição arquivada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MunicipalityTurnout {
+    pub election_id: String,
+    pub certified_at: DateTime<Utc>,
+    pub state: String,
+    pub municipality_code: String,
+    pub municipality: String,
+    pub registered_voters: u64,
+    pub votes_cast: u64,
+    pub turnout_rate: f64,
+}
+
+/// Votação de um número em um município de uma eleição arquivada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateHistoryEntry {
+    pub election_id: String,
+    pub certified_at: DateTime<Utc>,
+    /// Número do votável, como no pacote (`NR_VOTAVEL`)
+    pub votable: String,
+    pub state: String,
+    pub municipality_code: String,
+    pub municipality: String,
+    pub votes: u64,
+    /// Parcela dos votos apurados no município
+    pub vote_share: f64,
+}
+
+/// Filtro e paginação da consulta
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    pub state: Option<String>,
+    pub municipality_code: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, state: &str, municipality_code: &str) -> bool {
+        self.state.as_deref().map_or(true, |s| s.eq_ignore_ascii_case(state))
+            && self.municipality_code.as_deref().map_or(true, |code| code == municipality_code)
+    }
+}
+
+/// Página de resultados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPage<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub next_offset: Option<usize>,
+    /// Resumo dos manifestos indexados quando a página foi gerada
+    pub index_digest: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Página assinada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedHistoryPage<T> {
+    pub page: HistoryPage<T>,
+    pub page_hash: String,
+    pub signature: String,
+    pub public_key: String,
+}
+
+/// Eleição indexada: comparecimento e votos por município
+#[derive(Debug, Clone)]
+struct IndexedElection {
+    election: ArchivedElection,
+    /// Por (UF, código do município)
+    turnout: BTreeMap<(String, String), MunicipalityTurnout>,
+    /// Por (número do votável, UF, código do município)
+    votes: BTreeMap<(String, String, String), u64>,
+}
+
+/// Índice das eleições arquivadas e consultas sobre ele
+pub struct ElectionHistoryService {
+    archive_path: PathBuf,
+    signing_key: Box<dyn SigningKey>,
+    index: RwLock<BTreeMap<String, IndexedElection>>,
+}
+
+impl ElectionHistoryService {
+    pub fn new(archive_path: PathBuf, signing_key: impl SigningKey + 'static) -> Self {
+        Self {
+            archive_path,
+            signing_key: Box::new(signing_key),
+            index: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Reconstrói o índice com o pacote mais recente de cada eleição arquivada
+    pub async fn rebuild(&self) -> Result<usize> {
+        let mut entries = match tokio::fs::read_dir(&self.archive_path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut indexed = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+            let election_id = entry.file_name().to_string_lossy().to_string();
+            match self.index_election(&election_id).await {
+                Ok(()) => indexed += 1,
+                Err(e) => log::warn!("Pacote arquivado de {} fora do índice histórico: {}", election_id, e),
+            }
+        }
+        log::info!("Índice histórico montado com {} eleições", indexed);
+        Ok(indexed)
+    }
+
+    /// Indexa o pacote mais recente da eleição, substituindo o anterior
+    pub async fn index_election(&self, election_id: &str) -> Result<()> {
+        let election_dir = self.archive_path.join(election_id);
+        let signed: SignedOpenDataManifest =
+            serde_json::from_slice(&tokio::fs::read(election_dir.join("latest.json")).await?)?;
+        if signed.manifest.election_id != election_id {
+            return Err(anyhow!("Manifest belongs to election {}", signed.manifest.election_id));
+        }
+        if !OpenDataService::verify_manifest(&signed)? {
+            return Err(anyhow!("Invalid manifest signature for snapshot {}", signed.manifest.snapshot_id));
+        }
+
+        let snapshot_dir = election_dir.join(&signed.manifest.snapshot_id);
+        let turnout_csv = read_verified(&snapshot_dir, &signed, &format!("detalhe_votacao_secao_{}.csv", election_id)).await?;
+        let votes_csv = read_verified(&snapshot_dir, &signed, &format!("votacao_secao_{}.csv", election_id)).await?;
+
+        let election = ArchivedElection {
+            election_id: election_id.to_string(),
+            snapshot_id: signed.manifest.snapshot_id.clone(),
+            certified_at: signed.manifest.certification.certified_at,
+            sections: signed.manifest.sections,
+            manifest_hash: signed.manifest_hash.clone(),
+        };
+
+        let mut turnout: BTreeMap<(String, String), MunicipalityTurnout> = BTreeMap::new();
+        for row in Csv::parse(&turnout_csv)?.rows() {
+            let key = (row.get("SG_UF")?.to_string(), row.get("CD_MUNICIPIO")?.to_string());
+            let entry = turnout.entry(key.clone()).or_insert_with(|| MunicipalityTurnout {
+                election_id: election.election_id.clone(),
+                certified_at: election.certified_at,
+                state: key.0.clone(),
+                municipality_code: key.1.clone(),
+                municipality: row.get("NM_MUNICIPIO").unwrap_or_default().to_string(),
+                registered_voters: 0,
+                votes_cast: 0,
+                turnout_rate: 0.0,
+            });
+            entry.registered_voters += row.number("QT_APTOS")?;
+            entry.votes_cast += row.number("QT_COMPARECIMENTO")?;
+        }
+        for entry in turnout.values_mut() {
+            entry.turnout_rate = ratio(entry.votes_cast, entry.registered_voters);
+        }
+
+        let mut votes: BTreeMap<(String, String, String), u64> = BTreeMap::new();
+        for row in Csv::parse(&votes_csv)?.rows() {
+            let key = (
+                row.get("NR_VOTAVEL")?.to_string(),
+                row.get("SG_UF")?.to_string(),
+                row.get("CD_MUNICIPIO")?.to_string(),
+            );
+            *votes.entry(key).or_insert(0) += row.number("QT_VOTOS")?;
+        }
+
+        self.index.write().await.insert(
+            election_id.to_string(),
+            IndexedElection { election, turnout, votes },
+        );
+        Ok(())
+    }
+
+    /// Eleições indexadas, da mais antiga à mais recente
+    pub async fn elections(&self, query: &HistoryQuery) -> Result<SignedHistoryPage<ArchivedElection>> {
+        let index = self.index.read().await;
+        let mut items: Vec<ArchivedElection> = index.values().map(|indexed| indexed.election.clone()).collect();
+        items.sort_by(|a, b| (a.certified_at, &a.election_id).cmp(&(b.certified_at, &b.election_id)));
+        self.page(&index, items, query)
+    }
+
+    /// Comparecimento por município ao longo das eleições
+    pub async fn turnout_trend(&self, query: &HistoryQuery) -> Result<SignedHistoryPage<MunicipalityTurnout>> {
+        let index = self.index.read().await;
+        let mut items: Vec<MunicipalityTurnout> = index
+            .values()
+            .flat_map(|indexed| indexed.turnout.values())
+            .filter(|entry| query.matches(&entry.state, &entry.municipality_code))
+            .cloned()
+            .collect();
+        items.sort_by(|a, b| {
+            (&a.state, &a.municipality_code, a.certified_at, &a.election_id)
+                .cmp(&(&b.state, &b.municipality_code, b.certified_at, &b.election_id))
+        });
+        self.page(&index, items, query)
+    }
+
+    /// Votação do número nas eleições arquivadas, por município
+    pub async fn candidate_history(&self, votable: &str, query: &HistoryQuery) -> Result<SignedHistoryPage<CandidateHistoryEntry>> {
+        let index = self.index.read().await;
+        let mut items = Vec::new();
+        for indexed in index.values() {
+            let matching = indexed.votes.range(
+                (votable.to_string(), String::new(), String::new())..,
+            ).take_while(|((number, _, _), _)| number == votable);
+            for ((number, state, municipality_code), votes) in matching {
+                if !query.matches(state, municipality_code) {
+                    continue;
+                }
+                let municipality = indexed.turnout.get(&(state.clone(), municipality_code.clone()));
+                items.push(CandidateHistoryEntry {
+                    election_id: indexed.election.election_id.clone(),
+                    certified_at: indexed.election.certified_at,
+                    votable: number.clone(),
+                    state: state.clone(),
+                    municipality_code: municipality_code.clone(),
+                    municipality: municipality.map(|m| m.municipality.clone()).unwrap_or_default(),
+                    votes: *votes,
+                    vote_share: ratio(*votes, municipality.map_or(0, |m| m.votes_cast)),
+                });
+            }
+        }
+        items.sort_by(|a, b| {
+            (a.certified_at, &a.election_id, &a.state, &a.municipality_code)
+                .cmp(&(b.certified_at, &b.election_id, &b.state, &b.municipality_code))
+        });
+        self.page(&index, items, query)
+    }
+
+    /// Verifica a assinatura da página
+    pub fn verify_page<T: Serialize>(signed: &SignedHistoryPage<T>) -> Result<bool> {
+        let page_bytes = serde_json::to_vec(&signed.page)?;
+        if hex::encode(Sha256::digest(&page_bytes)) != signed.page_hash {
+            return Ok(false);
+        }
+
+        let public_key = hex::decode(&signed.public_key)?;
+        let signature = hex::decode(&signed.signature)?;
+        let verifier = UnparsedPublicKey::new(&ED25519, &public_key);
+        Ok(verifier.verify(&page_bytes, &signature).is_ok())
+    }
+
+    /// Recorta a página pedida e assina
+    fn page<T: Serialize>(
+        &self,
+        index: &BTreeMap<String, IndexedElection>,
+        items: Vec<T>,
+        query: &HistoryQuery,
+    ) -> Result<SignedHistoryPage<T>> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(anyhow!("Page limit must be between 1 and {}", MAX_PAGE_SIZE));
+        }
+        let offset = query.offset.unwrap_or(0);
+        let total = items.len();
+        let next_offset = (offset.saturating_add(limit) < total).then_some(offset + limit);
+
+        let mut digest = Sha256::new();
+        for indexed in index.values() {
+            digest.update(indexed.election.manifest_hash.as_bytes());
+        }
+
+        let page = HistoryPage {
+            items: items.into_iter().skip(offset).take(limit).collect(),
+            total,
+            offset,
+            limit,
+            next_offset,
+            index_digest: hex::encode(digest.finalize()),
+            generated_at: Utc::now(),
+        };
+        let page_bytes = serde_json::to_vec(&page)?;
+        let signature = self.signing_key.try_sign(&page_bytes)?;
+        Ok(SignedHistoryPage {
+            page,
+            page_hash: hex::encode(Sha256::digest(&page_bytes)),
+            signature: hex::encode(signature),
+            public_key: hex::encode(self.signing_key.public_key_bytes()),
+        })
+    }
+}
+
+/// Lê o arquivo do pacote e confere o resumo do manifesto
+async fn read_verified(snapshot_dir: &Path, signed: &SignedOpenDataManifest, name: &str) -> Result<String> {
+    let file = signed.manifest.files.iter()
+        .find(|file| file.name == name)
+        .ok_or_else(|| anyhow!("File {} missing from manifest", name))?;
+    let content = tokio::fs::read(snapshot_dir.join(name)).await?;
+    if hex::encode(Sha256::digest(&content)) != file.sha256 {
+        return Err(anyhow!("File {} does not match the manifest digest", name));
+    }
+    Ok(String::from_utf8(content)?)
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+/// Linha do CSV, acessada pelo nome da coluna
+struct CsvRow<'a> {
+    header: &'a [String],
+    fields: &'a [String],
+}
+
+impl CsvRow<'_> {
+    fn get(&self, column: &str) -> Result<&'a str> {
+        self.header.iter()
+            .position(|name| name == column)
+            .and_then(|i| self.fields.get(i))
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("Column {} missing", column))
+    }
+
+    fn number(&self, column: &str) -> Result<u64> {
+        let value = self.get(column)?;
+        value.parse().map_err(|_| anyhow!("Invalid {} value: {}", column, value))
+    }
+}
+
+/// Arquivo CSV lido
+struct Csv {
+    header: Vec<String>,
+    lines: Vec<Vec<String>>,
+}
+
+impl Csv {
+    /// CSV no padrão TSE: separador `;`, campos entre aspas, aspas internas duplicadas
+    fn parse(content: &str) -> Result<Self> {
+        let mut lines = content.split("\r\n").filter(|line| !line.is_empty());
+        let header = parse_csv_line(lines.next().ok_or_else(|| anyhow!("Empty CSV file"))?)?;
+        let lines = lines.map(parse_csv_line).collect::<Result<_>>()?;
+        Ok(Self { header, lines })
+    }
+
+    fn rows(&self) -> impl Iterator<Item = CsvRow<'_>> {
+        self.lines.iter().map(|fields| CsvRow { header: &self.header, fields })
+    }
+}
+
+fn parse_csv_line(line: &str) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        if chars.next() != Some('"') {
+            return Err(anyhow!("Unquoted CSV field in line: {}", line));
+        }
+        let mut field = String::new();
+        loop {
+            match chars.next() {
+                Some('"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                Some('"') => break,
+                Some(c) => field.push(c),
+                None => return Err(anyhow!("Unterminated CSV field in line: {}", line)),
+            }
+        }
+        fields.push(field);
+        match chars.next() {
+            Some(';') => continue,
+            None => return Ok(fields),
+            Some(c) => return Err(anyhow!("Unexpected {:?} after CSV field", c)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::open_data::{OpenDataConfig, SectionResult};
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+
+    fn section(municipality_code: &str, section: &str, registered: u64, votes: &[(&str, u64)]) -> SectionResult {
+        SectionResult {
+            state: "SP".to_string(),
+            municipality_code: municipality_code.to_string(),
+            municipality: format!("MUNICÍPIO {}", municipality_code),
+            zone: "0001".to_string(),
+            section: section.to_string(),
+            registered_voters: registered,
+            votes_cast: votes.iter().map(|(_, v)| v).sum(),
+            votes_by_candidate: votes.iter().map(|(c, v)| (c.to_string(), *v)).collect(),
+            bu_digest: format!("digest-{}", section),
+        }
+    }
+
+    async fn publish(dir: &tempfile::TempDir, election_id: &str, sections: Vec<SectionResult>) {
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+        let open_data = OpenDataService::new(
+            OpenDataConfig {
+                archive_path: dir.path().to_path_buf(),
+                min_section_size: 20,
+            },
+            signing_key,
+        );
+        for section in sections {
+            open_data.record_section_result(election_id, section).await.unwrap();
+        }
+        open_data.certify(election_id, "TRE-SP").await.unwrap();
+        open_data.generate_snapshot(election_id).await.unwrap();
+    }
+
+    fn history(dir: &tempfile::TempDir) -> ElectionHistoryService {
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+        ElectionHistoryService::new(dir.path().to_path_buf(), signing_key)
+    }
+
+    #[tokio::test]
+    async fn test_turnout_trend_across_archived_elections() {
+        let dir = tempfile::tempdir().unwrap();
+        publish(&dir, "e2018", vec![
+            section("71072", "0001", 300, &[("13", 100), ("22", 90)]),
+            section("71072", "0002", 200, &[("13", 60)]),
+            section("62910", "0001", 100, &[("22", 50)]),
+        ]).await;
+        publish(&dir, "e2022", vec![
+            section("71072", "0001", 300, &[("13", 150), ("22", 120)]),
+        ]).await;
+
+        let history = history(&dir);
+        assert_eq!(history.rebuild().await.unwrap(), 2);
+
+        let query = HistoryQuery { municipality_code: Some("71072".to_string()), ..Default::default() };
+        let signed = history.turnout_trend(&query).await.unwrap();
+        assert!(ElectionHistoryService::verify_page(&signed).unwrap());
+        let trend = &signed.page.items;
+        assert_eq!(trend.len(), 2);
+        assert_eq!((trend[0].election_id.as_str(), trend[0].registered_voters, trend[0].votes_cast), ("e2018", 500, 250));
+        assert_eq!((trend[1].election_id.as_str(), trend[1].votes_cast), ("e2022", 270));
+        assert!((trend[1].turnout_rate - 0.9).abs() < 1e-9);
+
+        let candidate = history.candidate_history("13", &query).await.unwrap();
+        let votes: Vec<u64> = candidate.page.items.iter().map(|entry| entry.votes).collect();
+        assert_eq!(votes, vec![160, 150]);
+
+        // Paginação
+        let first = history.elections(&HistoryQuery { limit: Some(1), ..Default::default() }).await.unwrap();
+        assert_eq!((first.page.total, first.page.next_offset), (2, Some(1)));
+        let second = history.elections(&HistoryQuery { offset: first.page.next_offset, limit: Some(1), ..Default::default() }).await.unwrap();
+        assert_eq!(second.page.items[0].election_id, "e2022");
+        assert_eq!(second.page.next_offset, None);
+        assert!(history.elections(&HistoryQuery { limit: Some(0), ..Default::default() }).await.is_err());
+
+        let mut tampered = signed.clone();
+        tampered.page.items[0].votes_cast += 1;
+        assert!(!ElectionHistoryService::verify_page(&tampered).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_altered_archive_is_left_out_of_the_index() {
+        let dir = tempfile::tempdir().unwrap();
+        publish(&dir, "e2018", vec![section("71072", "0001", 300, &[("13", 100)])]).await;
+
+        let latest: SignedOpenDataManifest =
+            serde_json::from_slice(&std::fs::read(dir.path().join("e2018").join("latest.json")).unwrap()).unwrap();
+        let turnout_file = dir.path().join("e2018").join(&latest.manifest.snapshot_id).join("detalhe_votacao_secao_e2018.csv");
+        let altered = std::fs::read_to_string(&turnout_file).unwrap().replace("\"300\"", "\"900\"");
+        std::fs::write(&turnout_file, altered).unwrap();
+
+        let history = history(&dir);
+        assert_eq!(history.rebuild().await.unwrap(), 0);
+        assert!(history.index_election("e2018").await.unwrap_err().to_string().contains("manifest digest"));
+    }
+}
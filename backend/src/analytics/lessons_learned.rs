@@ -0,0 +1,537 @@
+//! Relatório de Lições Aprendidas pós-eleição
+//!
+//! Depois da certificação, compila as estatísticas operacionais da eleição
+//! para a retrospectiva do TSE: latência por etapa (p50/p95/máximo),
+//! incidentes por severidade e origem, causas de falha de sincronização,
+//! taxa de falha de hardware por modelo de urna, tempos de consenso e as
+//! diferenças entre a primeira e a última rodada de contagem.
+//!
+//! A geração roda em segundo plano: a cada intervalo, toda eleição
+//! certificada sem relatório recebe um. São gravados o conjunto de dados
+//! estruturado (`dataset.json`, mais um CSV por seção) e o `relatorio.pdf`.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use fortis_domain::document::pdf::{self, PdfStyle};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::open_data::{Certification, OpenDataService};
+use crate::services::incident::IncidentService;
+use crate::services::results::{ResultsDiff, ResultsService};
+
+/// Configuração do relatório
+#[derive(Debug, Clone)]
+pub struct LessonsLearnedConfig {
+    pub output_path: PathBuf,
+    pub interval_seconds: u64,
+}
+
+/// Sinal operacional registrado durante a eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OperationalSignal {
+    /// Duração de uma etapa (ex.: "preparacao", "votacao", "transmissao", "apuracao")
+    StageLatency { stage: String, latency_ms: f64 },
+    SyncFailure { urna_id: String, cause: String },
+    UrnasDeployed { model: String, count: u64 },
+    HardwareFailure { urna_id: String, model: String, component: String },
+    ConsensusRound { duration_ms: f64, participants: u32 },
+}
+
+/// Resumo de latência de uma etapa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub stage: String,
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Incidentes do período da eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentSummary {
+    pub total: usize,
+    pub by_severity: BTreeMap<String, usize>,
+    pub by_source: BTreeMap<String, usize>,
+}
+
+/// Causa de falha de sincronização
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFailureCause {
+    pub cause: String,
+    pub failures: usize,
+    pub urnas: usize,
+}
+
+/// Falhas de hardware por modelo de urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareFailureRate {
+    pub model: String,
+    pub deployed: u64,
+    pub failed_urnas: usize,
+    /// Urnas com falha / urnas em uso (0 sem urnas registradas)
+    pub failure_rate: f64,
+    pub by_component: BTreeMap<String, usize>,
+}
+
+/// Tempos das rodadas de consenso
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusTiming {
+    pub rounds: usize,
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    pub mean_participants: Option<f64>,
+}
+
+/// Conjunto de dados do relatório
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LessonsLearnedReport {
+    pub election_id: String,
+    pub certification: Certification,
+    pub period_start: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+    pub stage_latencies: Vec<LatencySummary>,
+    pub incidents: IncidentSummary,
+    pub sync_failures: Vec<SyncFailureCause>,
+    pub hardware_failures: Vec<HardwareFailureRate>,
+    pub consensus: ConsensusTiming,
+    /// Recontagens e conciliações: diferenças entre a primeira e a última rodada
+    pub result_changes: Option<ResultsDiff>,
+}
+
+#[derive(Debug, Default)]
+struct ElectionOperations {
+    first_signal_at: Option<DateTime<Utc>>,
+    stage_latencies_ms: BTreeMap<String, Vec<f64>>,
+    sync_failures: Vec<(String, String)>,
+    deployed: BTreeMap<String, u64>,
+    hardware_failures: Vec<(String, String, String)>,
+    consensus_rounds: Vec<(f64, u32)>,
+}
+
+/// Serviço do relatório de lições aprendidas
+#[derive(Clone)]
+pub struct LessonsLearnedService {
+    config: LessonsLearnedConfig,
+    incidents: Arc<IncidentService>,
+    results: Option<Arc<ResultsService>>,
+    operations: Arc<RwLock<HashMap<String, ElectionOperations>>>,
+    reports: Arc<RwLock<BTreeMap<String, LessonsLearnedReport>>>,
+}
+
+impl LessonsLearnedService {
+    pub fn new(config: LessonsLearnedConfig, incidents: Arc<IncidentService>) -> Self {
+        Self {
+            config,
+            incidents,
+            results: None,
+            operations: Arc::new(RwLock::new(HashMap::new())),
+            reports: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+
+    /// Inclui no relatório as diferenças entre rodadas de contagem
+    pub fn with_results(mut self, results: Arc<ResultsService>) -> Self {
+        self.results = Some(results);
+        self
+    }
+
+    /// Gera periodicamente o relatório das eleições certificadas
+    pub fn start(&self, open_data: Arc<OpenDataService>) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(
+                std::time::Duration::from_secs(service.config.interval_seconds),
+            );
+            loop {
+                interval.tick().await;
+                for certification in open_data.certifications().await {
+                    if service.reports.read().await.contains_key(&certification.election_id) {
+                        continue;
+                    }
+                    if let Err(e) = service.generate(certification).await {
+                        log::error!("Failed to generate lessons-learned report: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Registra sinal operacional de uma eleição
+    pub async fn record(&self, election_id: &str, signal: OperationalSignal) {
+        let mut operations = self.operations.write().await;
+        let election = operations.entry(election_id.to_string()).or_default();
+        election.first_signal_at.get_or_insert_with(Utc::now);
+
+        match signal {
+            OperationalSignal::StageLatency { stage, latency_ms } => {
+                election.stage_latencies_ms.entry(stage).or_default().push(latency_ms);
+            }
+            OperationalSignal::SyncFailure { urna_id, cause } => {
+                election.sync_failures.push((urna_id, cause));
+            }
+            OperationalSignal::UrnasDeployed { model, count } => {
+                *election.deployed.entry(model).or_default() += count;
+            }
+            OperationalSignal::HardwareFailure { urna_id, model, component } => {
+                election.hardware_failures.push((urna_id, model, component));
+            }
+            OperationalSignal::ConsensusRound { duration_ms, participants } => {
+                election.consensus_rounds.push((duration_ms, participants));
+            }
+        }
+    }
+
+    /// Relatório já gerado
+    pub async fn get_report(&self, election_id: &str) -> Option<LessonsLearnedReport> {
+        self.reports.read().await.get(election_id).cloned()
+    }
+
+    /// PDF do relatório já gerado
+    pub async fn get_pdf(&self, election_id: &str) -> Result<Vec<u8>> {
+        let path = self.config.output_path.join(election_id).join("relatorio.pdf");
+        tokio::fs::read(&path).await
+            .map_err(|_| anyhow!("No lessons-learned report for election: {}", election_id))
+    }
+
+    /// Compila o relatório e grava conjunto de dados e PDF
+    pub async fn generate(&self, certification: Certification) -> Result<LessonsLearnedReport> {
+        let report = self.compile(certification).await;
+
+        let dir = self.config.output_path.join(&report.election_id);
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::write(dir.join("dataset.json"), serde_json::to_vec_pretty(&report)?).await?;
+        for (name, content) in render_csv_files(&report) {
+            tokio::fs::write(dir.join(name), content).await?;
+        }
+        tokio::fs::write(dir.join("relatorio.pdf"), render_pdf(&report_lines(&report))).await?;
+
+        self.reports.write().await.insert(report.election_id.clone(), report.clone());
+        log::info!("Lessons-learned report generated for election {}", report.election_id);
+        Ok(report)
+    }
+
+    async fn compile(&self, certification: Certification) -> LessonsLearnedReport {
+        let operations = self.operations.read().await;
+        let empty = ElectionOperations::default();
+        let election = operations.get(&certification.election_id).unwrap_or(&empty);
+        let period_start = election.first_signal_at;
+
+        let stage_latencies = election.stage_latencies_ms.iter()
+            .map(|(stage, samples)| LatencySummary {
+                stage: stage.clone(),
+                samples: samples.len(),
+                p50_ms: percentile(samples, 0.50).unwrap_or_default(),
+                p95_ms: percentile(samples, 0.95).unwrap_or_default(),
+                max_ms: samples.iter().copied().fold(0.0, f64::max),
+            })
+            .collect();
+
+        // Incidentes abertos entre o primeiro sinal e a certificação
+        let mut incidents = IncidentSummary { total: 0, by_severity: BTreeMap::new(), by_source: BTreeMap::new() };
+        for incident in self.incidents.list_incidents(None).await {
+            if incident.created_at > certification.certified_at
+                || period_start.is_some_and(|start| incident.created_at < start)
+            {
+                continue;
+            }
+            incidents.total += 1;
+            let severity = serde_json::to_value(incident.severity).ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            *incidents.by_severity.entry(severity).or_default() += 1;
+            *incidents.by_source.entry(incident.source).or_default() += 1;
+        }
+
+        let mut by_cause: BTreeMap<&str, (usize, Vec<&str>)> = BTreeMap::new();
+        for (urna_id, cause) in &election.sync_failures {
+            let entry = by_cause.entry(cause.as_str()).or_default();
+            entry.0 += 1;
+            if !entry.1.contains(&urna_id.as_str()) {
+                entry.1.push(urna_id);
+            }
+        }
+        let mut sync_failures: Vec<SyncFailureCause> = by_cause.into_iter()
+            .map(|(cause, (failures, urnas))| SyncFailureCause {
+                cause: cause.to_string(),
+                failures,
+                urnas: urnas.len(),
+            })
+            .collect();
+        sync_failures.sort_by(|a, b| b.failures.cmp(&a.failures));
+
+        let mut models: BTreeMap<String, (Vec<&str>, BTreeMap<String, usize>)> = election.deployed.keys()
+            .map(|model| (model.clone(), Default::default()))
+            .collect();
+        for (urna_id, model, component) in &election.hardware_failures {
+            let entry = models.entry(model.clone()).or_default();
+            if !entry.0.contains(&urna_id.as_str()) {
+                entry.0.push(urna_id);
+            }
+            *entry.1.entry(component.clone()).or_default() += 1;
+        }
+        let hardware_failures = models.into_iter()
+            .map(|(model, (failed, by_component))| {
+                let deployed = election.deployed.get(&model).copied().unwrap_or(0);
+                HardwareFailureRate {
+                    failure_rate: ratio(failed.len(), deployed),
+                    failed_urnas: failed.len(),
+                    deployed,
+                    model,
+                    by_component,
+                }
+            })
+            .collect();
+
+        let durations: Vec<f64> = election.consensus_rounds.iter().map(|(d, _)| *d).collect();
+        let consensus = ConsensusTiming {
+            rounds: durations.len(),
+            p50_ms: percentile(&durations, 0.50),
+            p95_ms: percentile(&durations, 0.95),
+            max_ms: durations.iter().copied().reduce(f64::max),
+            mean_participants: (!election.consensus_rounds.is_empty()).then(|| {
+                election.consensus_rounds.iter().map(|(_, p)| *p as f64).sum::<f64>()
+                    / election.consensus_rounds.len() as f64
+            }),
+        };
+
+        let result_changes = match (&self.results, certification.election_id.parse()) {
+            (Some(results), Ok(election_id)) => results.latest_diff(election_id).await,
+            _ => None,
+        };
+
+        LessonsLearnedReport {
+            election_id: certification.election_id.clone(),
+            certification,
+            period_start,
+            generated_at: Utc::now(),
+            stage_latencies,
+            incidents,
+            sync_failures,
+            hardware_failures,
+            consensus,
+            result_changes,
+        }
+    }
+}
+
+fn ratio(part: usize, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn percentile(samples: &[f64], p: f64) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let index = ((sorted.len() as f64 * p).ceil() as usize).saturating_sub(1);
+    Some(sorted[index.min(sorted.len() - 1)])
+}
+
+/// CSVs por seção do relatório (separador `;`, como nos dados abertos)
+fn render_csv_files(report: &LessonsLearnedReport) -> Vec<(&'static str, String)> {
+    let mut latencies = String::from("\"ETAPA\";\"AMOSTRAS\";\"P50_MS\";\"P95_MS\";\"MAX_MS\"\n");
+    for stage in &report.stage_latencies {
+        let _ = writeln!(latencies, "\"{}\";{};{:.1};{:.1};{:.1}",
+            stage.stage, stage.samples, stage.p50_ms, stage.p95_ms, stage.max_ms);
+    }
+
+    let mut sync = String::from("\"CAUSA\";\"FALHAS\";\"URNAS\"\n");
+    for cause in &report.sync_failures {
+        let _ = writeln!(sync, "\"{}\";{};{}", cause.cause.replace('"', "'"), cause.failures, cause.urnas);
+    }
+
+    let mut hardware = String::from("\"MODELO\";\"EM_USO\";\"URNAS_COM_FALHA\";\"TAXA\"\n");
+    for model in &report.hardware_failures {
+        let _ = writeln!(hardware, "\"{}\";{};{};{:.4}",
+            model.model, model.deployed, model.failed_urnas, model.failure_rate);
+    }
+
+    let mut changes = String::from("\"SECAO\";\"UF\";\"ALTERACAO\";\"DIF_COMPARECIMENTO\";\"JUSTIFICATIVAS\"\n");
+    for section in report.result_changes.iter().flat_map(|diff| &diff.sections) {
+        let references: Vec<&str> = section.justifications.iter().map(|j| j.reference.as_str()).collect();
+        let _ = writeln!(changes, "\"{}\";\"{}\";\"{:?}\";{};\"{}\"",
+            section.section_key, section.state, section.change, section.votes_cast_delta,
+            references.join(",").replace('"', "'"));
+    }
+
+    vec![
+        ("latencia_etapas.csv", latencies),
+        ("falhas_sincronizacao.csv", sync),
+        ("falhas_hardware.csv", hardware),
+        ("diferencas_apuracao.csv", changes),
+    ]
+}
+
+/// Texto do relatório, uma linha por item
+fn report_lines(report: &LessonsLearnedReport) -> Vec<String> {
+    let mut lines = vec![
+        format!("FORTIS - Relatorio de Licoes Aprendidas - Eleicao {}", report.election_id),
+        format!(
+            "Certificada por {} em {}; gerado em {}",
+            report.certification.certified_by,
+            report.certification.certified_at.format("%d/%m/%Y %H:%M UTC"),
+            report.generated_at.format("%d/%m/%Y %H:%M UTC"),
+        ),
+        String::new(),
+        "Latencia por etapa".to_string(),
+    ];
+    for stage in &report.stage_latencies {
+        lines.push(format!(
+            "  {}: {} amostras, p50 {:.0} ms, p95 {:.0} ms, max {:.0} ms",
+            stage.stage, stage.samples, stage.p50_ms, stage.p95_ms, stage.max_ms,
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("Incidentes: {}", report.incidents.total));
+    for (severity, count) in &report.incidents.by_severity {
+        lines.push(format!("  severidade {}: {}", severity, count));
+    }
+    for (source, count) in &report.incidents.by_source {
+        lines.push(format!("  origem {}: {}", source, count));
+    }
+
+    lines.push(String::new());
+    lines.push("Falhas de sincronizacao por causa".to_string());
+    for cause in &report.sync_failures {
+        lines.push(format!("  {}: {} falhas em {} urnas", cause.cause, cause.failures, cause.urnas));
+    }
+
+    lines.push(String::new());
+    lines.push("Falhas de hardware por modelo".to_string());
+    for model in &report.hardware_failures {
+        lines.push(format!(
+            "  {}: {} de {} urnas ({:.2}%)",
+            model.model, model.failed_urnas, model.deployed, model.failure_rate * 100.0,
+        ));
+        for (component, count) in &model.by_component {
+            lines.push(format!("    {}: {}", component, count));
+        }
+    }
+
+    lines.push(String::new());
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.0} ms", v));
+    lines.push(format!(
+        "Consenso: {} rodadas, p50 {}, p95 {}, max {}",
+        report.consensus.rounds,
+        ms(report.consensus.p50_ms),
+        ms(report.consensus.p95_ms),
+        ms(report.consensus.max_ms),
+    ));
+
+    if let Some(diff) = &report.result_changes {
+        lines.push(String::new());
+        lines.push(format!(
+            "Diferencas entre rodadas de contagem ({} a {}): {} secoes, comparecimento {:+}",
+            diff.from, diff.to, diff.sections.len(), diff.votes_cast_delta,
+        ));
+        for candidate in &diff.candidates {
+            lines.push(format!("  {} ({}): {:+} votos", candidate.name, candidate.number, candidate.delta));
+        }
+        if !diff.unjustified_sections.is_empty() {
+            lines.push(format!("  Secoes alteradas sem justificativa: {}", diff.unjustified_sections.join(", ")));
+        }
+    }
+    lines
+}
+
+/// PDF do relatório (Helvetica 10, A4), paginado a cada 50 linhas
+fn render_pdf(lines: &[String]) -> Vec<u8> {
+    const STYLE: PdfStyle = PdfStyle {
+        font: "Helvetica",
+        font_size: 10,
+        leading: 14,
+        lines_per_page: 50,
+    };
+    pdf::render(lines, STYLE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::incident::IncidentSeverity;
+
+    fn service(incidents: Arc<IncidentService>) -> LessonsLearnedService {
+        LessonsLearnedService::new(
+            LessonsLearnedConfig {
+                output_path: std::env::temp_dir().join(format!("fortis-lessons-{}", uuid::Uuid::new_v4())),
+                interval_seconds: 60,
+            },
+            incidents,
+        )
+    }
+
+    fn certification(election_id: &str) -> Certification {
+        Certification {
+            election_id: election_id.to_string(),
+            certified_by: "TSE".to_string(),
+            certified_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_compiles_operational_statistics() {
+        let incidents = Arc::new(IncidentService::new());
+        let service = service(incidents.clone());
+
+        service.record("e1", OperationalSignal::UrnasDeployed { model: "UE2022".to_string(), count: 4 }).await;
+        for (urna, component) in [("u1", "teclado"), ("u1", "impressora"), ("u2", "teclado")] {
+            service.record("e1", OperationalSignal::HardwareFailure {
+                urna_id: urna.to_string(),
+                model: "UE2022".to_string(),
+                component: component.to_string(),
+            }).await;
+        }
+        for (urna, cause) in [("u1", "timeout"), ("u2", "timeout"), ("u3", "tls")] {
+            service.record("e1", OperationalSignal::SyncFailure {
+                urna_id: urna.to_string(),
+                cause: cause.to_string(),
+            }).await;
+        }
+        for latency in [100.0, 200.0, 900.0] {
+            service.record("e1", OperationalSignal::StageLatency { stage: "transmissao".to_string(), latency_ms: latency }).await;
+        }
+        incidents.open_incident("Falha de link", IncidentSeverity::High, "monitoring", None).await;
+
+        let report = service.generate(certification("e1")).await.unwrap();
+        assert_eq!(report.hardware_failures[0].failed_urnas, 2);
+        assert_eq!(report.hardware_failures[0].failure_rate, 0.5);
+        assert_eq!(report.sync_failures[0].cause, "timeout");
+        assert_eq!(report.sync_failures[0].urnas, 2);
+        assert_eq!(report.stage_latencies[0].p95_ms, 900.0);
+        assert_eq!(report.incidents.by_severity.get("high"), Some(&1));
+        assert_eq!(report.consensus.rounds, 0);
+
+        let pdf = service.get_pdf("e1").await.unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+    }
+
+    #[test]
+    fn test_pdf_xref_points_at_objects() {
+        let lines: Vec<String> = (0..120).map(|i| format!("linha ({})", i)).collect();
+        let pdf = render_pdf(&lines);
+        let text = String::from_utf8_lossy(&pdf);
+
+        // 3 objetos fixos + página e conteúdo para cada uma das 3 páginas
+        assert!(text.contains("/Count 3"));
+        let xref = text.find("xref\n").unwrap();
+        let first_entry = &text[xref..].lines().nth(3).unwrap()[..10];
+        let offset: usize = first_entry.parse().unwrap();
+        assert!(text[offset..].starts_with("1 0 obj"));
+        assert!(text.contains("linha \\(7\\)"));
+    }
+}
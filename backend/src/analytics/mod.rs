@@ -0,0 +1,18 @@
+//! Módulo de Análises Públicas do FORTIS
+//! 
+//! Estatísticas agregadas para painéis públicos, protegidas por
+//! privacidade diferencial com contabilização do orçamento de privacidade,
+//! relatórios pós-eleição (dados abertos e lições aprendidas) e a consulta
+//! histórica sobre as eleições arquivadas.
+
+pub mod privacy_budget;
+pub mod turnout;
+pub mod open_data;
+pub mod lessons_learned;
+pub mod history;
+
+pub use privacy_budget::*;
+pub use turnout::*;
+pub use open_data::*;
+pub use lessons_learned::*;
+pub use history::*;
@@ -0,0 +1,521 @@
+//! Pacote de Dados Abertos da Eleição
+//!
+//! Após a certificação, gera o pacote oficial de dados abertos nos leiautes
+//! CSV usados pelo TSE (separador `;`, campos entre aspas), com votos por
+//! votável e seção, comparecimento e resumos dos boletins de urna, além
+//! da certidão do resultado em texto diagramado. Nenhum identificador de
+//! eleitor entra no pacote, e seções com poucos eleitores são agregadas na
+//! zona. O manifesto é assinado com Ed25519 e o pacote é publicado no
+//! diretório de arquivo (origem dos espelhos) e, opcionalmente, no IPFS.
+//!
+//! Com o acompanhamento da transmissão configurado, a certificação exige a
+//! declaração de fim de transmissão de todas as zonas.
+//!
+//! Cada pacote publicado entra no índice da consulta histórica
+//! (`history`), quando configurado.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use sha2::{Sha256, Digest};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use fortis_domain::document::templates::{self, CertificationData};
+use fortis_domain::document::PAGE_WIDTH;
+use fortis_domain::region::{RegionError, SectionRef};
+use fortis_domain::{split_tally_key, Votable};
+use super::history::ElectionHistoryService;
+use crate::crypto::hsm::SigningKey;
+use crate::services::transmission::TransmissionService;
+use crate::storage::DistributedStorage;
+
+/// Número de seção usado para o agregado de seções pequenas da zona
+pub const AGGREGATED_SECTION: &str = "AGREGADO";
+
+/// Resultado apurado de uma seção
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionResult {
+    pub state: String,
+    pub municipality_code: String,
+    pub municipality: String,
+    pub zone: String,
+    pub section: String,
+    pub registered_voters: u64,
+    pub votes_cast: u64,
+    /// Votos por número do votável (candidato ou legenda), com brancos e
+    /// nulos em contadores próprios (`BRANCO`, `NULO`, com ou sem o cargo)
+    pub votes_by_candidate: BTreeMap<String, u64>,
+    /// Resumo SHA-256 do boletim de urna
+    pub bu_digest: String,
+}
+
+impl SectionResult {
+    /// Seção do boletim na hierarquia eleitoral
+    pub fn section_ref(&self) -> Result<SectionRef, RegionError> {
+        SectionRef::new(&self.state, &self.municipality_code, &self.zone, &self.section)
+    }
+
+    /// Votos em branco, somados em todos os cargos
+    pub fn blank_votes(&self) -> u64 {
+        self.count_votable(Votable::Blank)
+    }
+
+    /// Votos nulos, somados em todos os cargos
+    pub fn null_votes(&self) -> u64 {
+        self.count_votable(Votable::Null)
+    }
+
+    fn count_votable(&self, votable: Votable<'_>) -> u64 {
+        self.votes_by_candidate.iter()
+            .filter(|(key, _)| split_tally_key(key).1 == votable)
+            .map(|(_, votes)| votes)
+            .sum()
+    }
+}
+
+/// Certificação da eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Certification {
+    pub election_id: String,
+    pub certified_by: String,
+    pub certified_at: DateTime<Utc>,
+}
+
+/// Arquivo do pacote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenDataFile {
+    pub name: String,
+    pub sha256: String,
+    pub size_bytes: usize,
+    pub ipfs_cid: Option<String>,
+}
+
+/// Manifesto do pacote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenDataManifest {
+    pub snapshot_id: String,
+    pub election_id: String,
+    pub certification: Certification,
+    pub generated_at: DateTime<Utc>,
+    pub sections: usize,
+    pub aggregated_sections: usize,
+    pub files: Vec<OpenDataFile>,
+}
+
+/// Manifesto assinado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedOpenDataManifest {
+    pub manifest: OpenDataManifest,
+    pub manifest_hash: String,
+    pub signature: String,
+    pub public_key: String,
+}
+
+/// Configuração do gerador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenDataConfig {
+    pub archive_path: PathBuf,
+    /// Seções com menos eleitores aptos são agregadas na zona
+    pub min_section_size: u64,
+}
+
+/// Gerador do pacote de dados abertos
+pub struct OpenDataService {
+    config: OpenDataConfig,
+    signing_key: Arc<dyn SigningKey>,
+    storage: Option<Arc<DistributedStorage>>,
+    transmission: Option<Arc<TransmissionService>>,
+    history: Option<Arc<ElectionHistoryService>>,
+    results: RwLock<HashMap<String, BTreeMap<String, SectionResult>>>,
+    certifications: RwLock<HashMap<String, Certification>>,
+    snapshots: RwLock<HashMap<String, Vec<SignedOpenDataManifest>>>,
+}
+
+impl OpenDataService {
+    pub fn new(config: OpenDataConfig, signing_key: impl SigningKey + 'static) -> Self {
+        Self {
+            config,
+            signing_key: Arc::new(signing_key),
+            storage: None,
+            transmission: None,
+            history: None,
+            results: RwLock::new(HashMap::new()),
+            certifications: RwLock::new(HashMap::new()),
+            snapshots: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Publica também os arquivos no IPFS, endereçados pelo conteúdo
+    pub fn with_storage(mut self, storage: Arc<DistributedStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Exige o fim de transmissão declarado em todas as zonas para certificar
+    pub fn with_transmission(mut self, transmission: Arc<TransmissionService>) -> Self {
+        self.transmission = Some(transmission);
+        self
+    }
+
+    /// Indexa cada pacote publicado para a consulta histórica
+    pub fn with_history(mut self, history: Arc<ElectionHistoryService>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Registra resultado apurado de uma seção
+    pub async fn record_section_result(&self, election_id: &str, result: SectionResult) -> Result<()> {
+        if self.certifications.read().await.contains_key(election_id) {
+            return Err(anyhow!("Election already certified: {}", election_id));
+        }
+
+        let key = format!("{}/{}/{}", result.municipality_code, result.zone, result.section);
+        self.results.write().await
+            .entry(election_id.to_string())
+            .or_default()
+            .insert(key, result);
+        Ok(())
+    }
+
+    /// Registra a certificação da eleição, que libera a geração do pacote
+    pub async fn certify(&self, election_id: &str, certified_by: &str) -> Result<Certification> {
+        let mut certifications = self.certifications.write().await;
+        if let Some(existing) = certifications.get(election_id) {
+            return Ok(existing.clone());
+        }
+        if let Some(transmission) = &self.transmission {
+            let election_uuid = election_id.parse()
+                .map_err(|e| anyhow!("Invalid election id {}: {}", election_id, e))?;
+            transmission.ensure_all_declared(election_uuid).await?;
+        }
+
+        let certification = Certification {
+            election_id: election_id.to_string(),
+            certified_by: certified_by.to_string(),
+            certified_at: Utc::now(),
+        };
+        certifications.insert(election_id.to_string(), certification.clone());
+        Ok(certification)
+    }
+
+    /// Eleições certificadas
+    pub async fn certifications(&self) -> Vec<Certification> {
+        self.certifications.read().await.values().cloned().collect()
+    }
+
+    /// Gera, assina e publica o pacote de dados abertos
+    pub async fn generate_snapshot(&self, election_id: &str) -> Result<SignedOpenDataManifest> {
+        let certification = self.certifications.read().await
+            .get(election_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Election not certified: {}", election_id))?;
+
+        let (rows, aggregated_sections, totalized_sections) = {
+            let results = self.results.read().await;
+            let sections = results
+                .get(election_id)
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| anyhow!("No section results for election: {}", election_id))?;
+            let (rows, aggregated_sections) = self.anonymize(sections);
+            (rows, aggregated_sections, sections.len())
+        };
+
+        let generated_at = Utc::now();
+        let certificate = templates::certification(&CertificationData {
+            election: election_id.to_string(),
+            certified_by: certification.certified_by.clone(),
+            certified_at: certification.certified_at,
+            sections: totalized_sections as u64,
+            registered_voters: rows.iter().map(|row| row.registered_voters).sum(),
+            votes_cast: rows.iter().map(|row| row.votes_cast).sum(),
+            issued_at: generated_at,
+        });
+        let csv_files = vec![
+            (format!("votacao_secao_{}.csv", election_id), render_votes_csv(election_id, generated_at, &rows)),
+            (format!("detalhe_votacao_secao_{}.csv", election_id), render_turnout_csv(election_id, generated_at, &rows)),
+            (format!("hash_bu_{}.csv", election_id), render_bu_digest_csv(election_id, generated_at, &rows)),
+            (format!("certidao_{}.txt", election_id), certificate.render(PAGE_WIDTH)),
+        ];
+
+        let snapshot_id = format!("{}-{}", election_id, generated_at.format("%Y%m%dT%H%M%SZ"));
+        let snapshot_dir = self.config.archive_path.join(election_id).join(&snapshot_id);
+        tokio::fs::create_dir_all(&snapshot_dir).await?;
+
+        let mut files = Vec::new();
+        for (name, content) in csv_files {
+            tokio::fs::write(snapshot_dir.join(&name), &content).await?;
+            let ipfs_cid = match &self.storage {
+                Some(storage) => Some(storage.store_audit_bundle(election_id, content.as_bytes()).await?.cid),
+                None => None,
+            };
+            files.push(OpenDataFile {
+                sha256: hex::encode(Sha256::digest(content.as_bytes())),
+                size_bytes: content.len(),
+                name,
+                ipfs_cid,
+            });
+        }
+
+        let manifest = OpenDataManifest {
+            snapshot_id,
+            election_id: election_id.to_string(),
+            certification,
+            generated_at,
+            sections: rows.len(),
+            aggregated_sections,
+            files,
+        };
+        let signed = self.sign_manifest(manifest)?;
+
+        let manifest_json = serde_json::to_vec_pretty(&signed)?;
+        tokio::fs::write(snapshot_dir.join("manifest.json"), &manifest_json).await?;
+        tokio::fs::write(
+            self.config.archive_path.join(election_id).join("latest.json"),
+            &manifest_json,
+        ).await?;
+
+        log::info!("Pacote de dados abertos publicado: {}", signed.manifest.snapshot_id);
+        if let Some(history) = &self.history {
+            if let Err(e) = history.index_election(election_id).await {
+                log::warn!("Pacote {} fora do índice histórico: {}", signed.manifest.snapshot_id, e);
+            }
+        }
+        self.snapshots.write().await
+            .entry(election_id.to_string())
+            .or_default()
+            .push(signed.clone());
+        Ok(signed)
+    }
+
+    /// Lista pacotes publicados da eleição
+    pub async fn list_snapshots(&self, election_id: &str) -> Vec<SignedOpenDataManifest> {
+        self.snapshots.read().await.get(election_id).cloned().unwrap_or_default()
+    }
+
+    /// Verifica assinatura do manifesto
+    pub fn verify_manifest(signed: &SignedOpenDataManifest) -> Result<bool> {
+        let manifest_bytes = serde_json::to_vec(&signed.manifest)?;
+        if hex::encode(Sha256::digest(&manifest_bytes)) != signed.manifest_hash {
+            return Ok(false);
+        }
+
+        let public_key = hex::decode(&signed.public_key)?;
+        let signature = hex::decode(&signed.signature)?;
+        let verifier = UnparsedPublicKey::new(&ED25519, &public_key);
+        Ok(verifier.verify(&manifest_bytes, &signature).is_ok())
+    }
+
+    /// Agrega seções pequenas por zona
+    fn anonymize(&self, sections: &BTreeMap<String, SectionResult>) -> (Vec<SectionResult>, usize) {
+        let mut rows = Vec::new();
+        let mut aggregates: BTreeMap<(String, String), SectionResult> = BTreeMap::new();
+        let mut aggregated_sections = 0;
+
+        for section in sections.values() {
+            if section.registered_voters >= self.config.min_section_size {
+                rows.push(section.clone());
+                continue;
+            }
+
+            aggregated_sections += 1;
+            let aggregate = aggregates
+                .entry((section.municipality_code.clone(), section.zone.clone()))
+                .or_insert_with(|| SectionResult {
+                    section: AGGREGATED_SECTION.to_string(),
+                    registered_voters: 0,
+                    votes_cast: 0,
+                    votes_by_candidate: BTreeMap::new(),
+                    bu_digest: String::new(),
+                    ..section.clone()
+                });
+            aggregate.registered_voters += section.registered_voters;
+            aggregate.votes_cast += section.votes_cast;
+            for (candidate, votes) in &section.votes_by_candidate {
+                *aggregate.votes_by_candidate.entry(candidate.clone()).or_insert(0) += votes;
+            }
+        }
+
+        rows.extend(aggregates.into_values());
+        (rows, aggregated_sections)
+    }
+
+    fn sign_manifest(&self, manifest: OpenDataManifest) -> Result<SignedOpenDataManifest> {
+        let manifest_bytes = serde_json::to_vec(&manifest)?;
+        let signature = self.signing_key.try_sign(&manifest_bytes)?;
+        Ok(SignedOpenDataManifest {
+            manifest,
+            manifest_hash: hex::encode(Sha256::digest(&manifest_bytes)),
+            signature: hex::encode(signature),
+            public_key: hex::encode(self.signing_key.public_key_bytes()),
+        })
+    }
+}
+
+/// Campo CSV no padrão TSE: entre aspas, aspas internas duplicadas
+fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn csv_line(fields: &[String]) -> String {
+    let mut line = fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(";");
+    line.push_str("\r\n");
+    line
+}
+
+fn generation_fields(election_id: &str, generated_at: DateTime<Utc>) -> Vec<String> {
+    vec![
+        generated_at.format("%d/%m/%Y").to_string(),
+        generated_at.format("%H:%M:%S").to_string(),
+        election_id.to_string(),
+    ]
+}
+
+fn section_fields(row: &SectionResult) -> Vec<String> {
+    vec![
+        row.state.to_uppercase(),
+        row.municipality_code.clone(),
+        row.municipality.to_uppercase(),
+        row.zone.clone(),
+        row.section.clone(),
+    ]
+}
+
+const HEADER_PREFIX: [&str; 8] = [
+    "DT_GERACAO", "HH_GERACAO", "CD_ELEICAO", "SG_UF", "CD_MUNICIPIO", "NM_MUNICIPIO", "NR_ZONA", "NR_SECAO",
+];
+
+fn header(extra: &[&str]) -> String {
+    let fields: Vec<String> = HEADER_PREFIX.iter().chain(extra).map(|f| f.to_string()).collect();
+    csv_line(&fields)
+}
+
+fn render_votes_csv(election_id: &str, generated_at: DateTime<Utc>, rows: &[SectionResult]) -> String {
+    let mut csv = header(&["NR_VOTAVEL", "QT_VOTOS"]);
+    for row in rows {
+        for (candidate, votes) in &row.votes_by_candidate {
+            let mut fields = generation_fields(election_id, generated_at);
+            fields.extend(section_fields(row));
+            fields.push(candidate.clone());
+            fields.push(votes.to_string());
+            csv.push_str(&csv_line(&fields));
+        }
+    }
+    csv
+}
+
+fn render_turnout_csv(election_id: &str, generated_at: DateTime<Utc>, rows: &[SectionResult]) -> String {
+    let mut csv = header(&["QT_APTOS", "QT_COMPARECIMENTO", "QT_ABSTENCOES", "QT_VOTOS_BRANCOS", "QT_VOTOS_NULOS"]);
+    for row in rows {
+        let mut fields = generation_fields(election_id, generated_at);
+        fields.extend(section_fields(row));
+        fields.push(row.registered_voters.to_string());
+        fields.push(row.votes_cast.to_string());
+        fields.push(row.registered_voters.saturating_sub(row.votes_cast).to_string());
+        fields.push(row.blank_votes().to_string());
+        fields.push(row.null_votes().to_string());
+        csv.push_str(&csv_line(&fields));
+    }
+    csv
+}
+
+fn render_bu_digest_csv(election_id: &str, generated_at: DateTime<Utc>, rows: &[SectionResult]) -> String {
+    let mut csv = header(&["HASH_BU"]);
+    // Agregados não possuem boletim próprio
+    for row in rows.iter().filter(|r| r.section != AGGREGATED_SECTION) {
+        let mut fields = generation_fields(election_id, generated_at);
+        fields.extend(section_fields(row));
+        fields.push(row.bu_digest.clone());
+        csv.push_str(&csv_line(&fields));
+    }
+    csv
+}
+
+impl Default for OpenDataConfig {
+    fn default() -> Self {
+        Self {
+            archive_path: PathBuf::from("./data/open-data"),
+            min_section_size: 20,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+
+    fn section(section: &str, registered: u64, votes: &[(&str, u64)]) -> SectionResult {
+        SectionResult {
+            state: "sp".to_string(),
+            municipality_code: "71072".to_string(),
+            municipality: "São Paulo".to_string(),
+            zone: "0001".to_string(),
+            section: section.to_string(),
+            registered_voters: registered,
+            votes_cast: votes.iter().map(|(_, v)| v).sum(),
+            votes_by_candidate: votes.iter().map(|(c, v)| (c.to_string(), *v)).collect(),
+            bu_digest: format!("digest-{}", section),
+        }
+    }
+
+    fn service(dir: &tempfile::TempDir) -> OpenDataService {
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+        OpenDataService::new(
+            OpenDataConfig {
+                archive_path: dir.path().to_path_buf(),
+                min_section_size: 20,
+            },
+            signing_key,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_requires_certification() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = service(&dir);
+        service.record_section_result("e1", section("0001", 300, &[("13", 100)])).await.unwrap();
+
+        assert!(service.generate_snapshot("e1").await.is_err());
+
+        service.certify("e1", "TRE-SP").await.unwrap();
+        assert!(service.record_section_result("e1", section("0002", 300, &[])).await.is_err());
+        assert!(service.generate_snapshot("e1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_small_sections_are_aggregated_and_manifest_is_signed() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = service(&dir);
+        service.record_section_result("e1", section("0001", 300, &[("13", 100), ("22", 90)])).await.unwrap();
+        service.record_section_result("e1", section("0002", 8, &[("13", 5)])).await.unwrap();
+        service.record_section_result("e1", section("0003", 5, &[("13", 1), ("22", 3)])).await.unwrap();
+        service.certify("e1", "TRE-SP").await.unwrap();
+
+        let signed = service.generate_snapshot("e1").await.unwrap();
+        assert_eq!(signed.manifest.sections, 2);
+        assert_eq!(signed.manifest.aggregated_sections, 2);
+        assert!(OpenDataService::verify_manifest(&signed).unwrap());
+
+        let snapshot_dir = dir.path().join("e1").join(&signed.manifest.snapshot_id);
+        let votes = std::fs::read_to_string(snapshot_dir.join("votacao_secao_e1.csv")).unwrap();
+        assert!(votes.starts_with("\"DT_GERACAO\";\"HH_GERACAO\";\"CD_ELEICAO\";\"SG_UF\""));
+        assert!(votes.contains("\"SP\";\"71072\";\"SÃO PAULO\";\"0001\";\"AGREGADO\";\"13\";\"6\""));
+        assert!(!votes.contains("\"0002\""));
+
+        let digests = std::fs::read_to_string(snapshot_dir.join("hash_bu_e1.csv")).unwrap();
+        assert!(digests.contains("digest-0001"));
+        assert!(!digests.contains("digest-0002"));
+
+        let certificate = std::fs::read_to_string(snapshot_dir.join("certidao_e1.txt")).unwrap();
+        assert!(certificate.contains("CERTIDÃO DE RESULTADO"));
+        assert!(certificate.contains("com 3 (três) seções totalizadas, 313 (trezentos e treze) eleitores aptos"));
+
+        let mut tampered = signed.clone();
+        tampered.manifest.sections = 3;
+        assert!(!OpenDataService::verify_manifest(&tampered).unwrap());
+    }
+}
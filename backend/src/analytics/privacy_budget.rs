@@ -0,0 +1,126 @@
+//! Contabilização do Orçamento de Privacidade Diferencial
+//!
+//! Cada conjunto de dados (ex.: comparecimento de uma eleição) tem um
+//! orçamento total de epsilon. Toda publicação com ruído consome parte do
+//! orçamento; quando ele se esgota, novas publicações são recusadas.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use rand::Rng;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Lançamento de consumo do orçamento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetCharge {
+    pub dataset: String,
+    pub query: String,
+    pub epsilon: f64,
+    pub charged_at: DateTime<Utc>,
+}
+
+/// Situação do orçamento de um conjunto de dados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub dataset: String,
+    pub total_epsilon: f64,
+    pub spent_epsilon: f64,
+    pub remaining_epsilon: f64,
+    pub charges: usize,
+}
+
+/// Contador do orçamento de privacidade (composição sequencial)
+pub struct PrivacyBudgetAccountant {
+    total_epsilon: f64,
+    charges: RwLock<HashMap<String, Vec<BudgetCharge>>>,
+}
+
+impl PrivacyBudgetAccountant {
+    pub fn new(total_epsilon: f64) -> Self {
+        Self {
+            total_epsilon,
+            charges: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Consome epsilon do orçamento do conjunto de dados
+    pub async fn charge(&self, dataset: &str, query: &str, epsilon: f64) -> Result<()> {
+        if epsilon <= 0.0 || !epsilon.is_finite() {
+            return Err(anyhow!("Invalid epsilon: {}", epsilon));
+        }
+
+        let mut charges = self.charges.write().await;
+        let ledger = charges.entry(dataset.to_string()).or_default();
+        let spent: f64 = ledger.iter().map(|c| c.epsilon).sum();
+
+        if spent + epsilon > self.total_epsilon + f64::EPSILON {
+            return Err(anyhow!(
+                "Privacy budget exhausted for {}: spent {:.3} of {:.3}",
+                dataset, spent, self.total_epsilon
+            ));
+        }
+
+        ledger.push(BudgetCharge {
+            dataset: dataset.to_string(),
+            query: query.to_string(),
+            epsilon,
+            charged_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Obtém situação do orçamento
+    pub async fn get_status(&self, dataset: &str) -> BudgetStatus {
+        let charges = self.charges.read().await;
+        let ledger = charges.get(dataset);
+        let spent: f64 = ledger.map(|l| l.iter().map(|c| c.epsilon).sum()).unwrap_or(0.0);
+
+        BudgetStatus {
+            dataset: dataset.to_string(),
+            total_epsilon: self.total_epsilon,
+            spent_epsilon: spent,
+            remaining_epsilon: (self.total_epsilon - spent).max(0.0),
+            charges: ledger.map(|l| l.len()).unwrap_or(0),
+        }
+    }
+
+    /// Lista lançamentos de um conjunto de dados
+    pub async fn get_charges(&self, dataset: &str) -> Vec<BudgetCharge> {
+        let charges = self.charges.read().await;
+        charges.get(dataset).cloned().unwrap_or_default()
+    }
+}
+
+/// Amostra ruído de Laplace com escala `sensitivity / epsilon`
+pub fn laplace_noise(sensitivity: f64, epsilon: f64) -> f64 {
+    let scale = sensitivity / epsilon;
+    let u: f64 = rand::thread_rng().gen_range(-0.5..0.5);
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_budget_is_enforced_per_dataset() {
+        let accountant = PrivacyBudgetAccountant::new(1.0);
+
+        accountant.charge("eleicao-1", "heatmap", 0.5).await.unwrap();
+        accountant.charge("eleicao-1", "heatmap", 0.5).await.unwrap();
+        assert!(accountant.charge("eleicao-1", "heatmap", 0.1).await.is_err());
+        accountant.charge("eleicao-2", "heatmap", 0.5).await.unwrap();
+
+        let status = accountant.get_status("eleicao-1").await;
+        assert_eq!(status.charges, 2);
+        assert!(status.remaining_epsilon.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_laplace_noise_is_centered() {
+        let samples = 20_000;
+        let mean: f64 = (0..samples).map(|_| laplace_noise(1.0, 1.0)).sum::<f64>() / samples as f64;
+        assert!(mean.abs() < 0.1);
+    }
+}
@@ -0,0 +1,280 @@
+//! Mapa de Calor de Comparecimento
+//!
+//! Agrega o comparecimento por município para painéis públicos. Seções com
+//! poucos eleitores são suprimidas, o total de votos de cada município recebe
+//! ruído de Laplace e cada publicação consome orçamento de privacidade.
+//!
+//! A UF do recorte é conferida contra as 27 UFs e normalizada antes de
+//! cobrar o orçamento, para que grafias diferentes do mesmo recorte não
+//! gerem publicações (e ruídos) independentes.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use fortis_domain::region::federative_unit;
+
+use super::privacy_budget::{laplace_noise, PrivacyBudgetAccountant};
+
+/// Configuração de privacidade do mapa de calor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeatMapConfig {
+    pub epsilon_per_release: f64,
+    /// Seções com menos eleitores aptos são suprimidas
+    pub min_section_size: u64,
+    /// Municípios com menos eleitores aptos (após supressão) não são publicados
+    pub min_cell_size: u64,
+}
+
+/// Comparecimento de uma seção eleitoral
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionTurnout {
+    pub state: String,
+    pub municipality_code: String,
+    pub municipality: String,
+    pub zone: String,
+    pub section: String,
+    pub registered_voters: u64,
+    pub votes_cast: u64,
+}
+
+/// Célula municipal do mapa de calor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeatMapCell {
+    pub state: String,
+    pub municipality_code: String,
+    pub municipality: String,
+    pub registered_voters: u64,
+    pub noisy_votes_cast: u64,
+    pub turnout_rate: f64,
+    /// Intensidade normalizada (0.0 a 1.0) entre os municípios publicados
+    pub intensity: f64,
+}
+
+/// Mapa de calor publicado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnoutHeatMap {
+    pub election_id: String,
+    pub state: Option<String>,
+    pub cells: Vec<HeatMapCell>,
+    pub suppressed_sections: usize,
+    pub suppressed_municipalities: usize,
+    pub epsilon: f64,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Serviço de mapa de calor de comparecimento
+pub struct TurnoutHeatMapService {
+    config: HeatMapConfig,
+    budget: Arc<PrivacyBudgetAccountant>,
+    sections: RwLock<HashMap<String, HashMap<String, SectionTurnout>>>,
+    versions: RwLock<HashMap<String, u64>>,
+    releases: RwLock<HashMap<(String, Option<String>), (u64, TurnoutHeatMap)>>,
+}
+
+impl TurnoutHeatMapService {
+    pub fn new(config: HeatMapConfig, budget: Arc<PrivacyBudgetAccountant>) -> Self {
+        Self {
+            config,
+            budget,
+            sections: RwLock::new(HashMap::new()),
+            versions: RwLock::new(HashMap::new()),
+            releases: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registra ou atualiza o comparecimento de uma seção
+    pub async fn record_section(&self, election_id: &str, mut section: SectionTurnout) -> Result<()> {
+        section.state = federative_unit(&section.state)?.to_string();
+        let key = format!("{}/{}/{}", section.municipality_code, section.zone, section.section);
+        self.sections.write().await
+            .entry(election_id.to_string())
+            .or_default()
+            .insert(key, section);
+        *self.versions.write().await.entry(election_id.to_string()).or_insert(0) += 1;
+        Ok(())
+    }
+
+    /// Obtém o mapa de calor. A mesma versão dos dados é sempre servida a
+    /// partir da publicação anterior, para que consultas repetidas não
+    /// consumam orçamento nem permitam tirar a média do ruído.
+    pub async fn get_heat_map(&self, election_id: &str, state: Option<&str>) -> Result<TurnoutHeatMap> {
+        let version = self.versions.read().await.get(election_id).copied().unwrap_or(0);
+        let state = state.map(federative_unit).transpose()?;
+        let release_key = (election_id.to_string(), state.map(str::to_string));
+
+        if let Some((released_version, heat_map)) = self.releases.read().await.get(&release_key) {
+            if *released_version == version {
+                return Ok(heat_map.clone());
+            }
+        }
+
+        let query = format!("turnout_heatmap:{}", release_key.1.as_deref().unwrap_or("BR"));
+        self.budget.charge(election_id, &query, self.config.epsilon_per_release).await?;
+
+        let heat_map = self.build_heat_map(election_id, release_key.1.as_deref()).await;
+        self.releases.write().await.insert(release_key, (version, heat_map.clone()));
+        Ok(heat_map)
+    }
+
+    async fn build_heat_map(&self, election_id: &str, state: Option<&str>) -> TurnoutHeatMap {
+        let sections = self.sections.read().await;
+        let empty = HashMap::new();
+        let election_sections = sections.get(election_id).unwrap_or(&empty);
+
+        // Agregar por município, suprimindo seções pequenas
+        let mut municipalities: BTreeMap<String, (SectionTurnout, u64, u64)> = BTreeMap::new();
+        let mut suppressed_sections = 0;
+
+        for section in election_sections.values() {
+            if state.map_or(false, |s| section.state != s) {
+                continue;
+            }
+            if section.registered_voters < self.config.min_section_size {
+                suppressed_sections += 1;
+                continue;
+            }
+
+            let entry = municipalities
+                .entry(section.municipality_code.clone())
+                .or_insert_with(|| (section.clone(), 0, 0));
+            entry.1 += section.registered_voters;
+            entry.2 += section.votes_cast.min(section.registered_voters);
+        }
+
+        // Ruído de Laplace sobre os votos (sensibilidade 1: um eleitor altera
+        // o total de um único município em no máximo uma unidade)
+        let mut suppressed_municipalities = 0;
+        let mut cells = Vec::new();
+        for (_, (sample, registered, votes)) in municipalities {
+            if registered < self.config.min_cell_size {
+                suppressed_municipalities += 1;
+                continue;
+            }
+
+            let noisy = (votes as f64 + laplace_noise(1.0, self.config.epsilon_per_release))
+                .round()
+                .clamp(0.0, registered as f64);
+
+            cells.push(HeatMapCell {
+                state: sample.state,
+                municipality_code: sample.municipality_code,
+                municipality: sample.municipality,
+                registered_voters: registered,
+                noisy_votes_cast: noisy as u64,
+                turnout_rate: noisy / registered as f64,
+                intensity: 0.0,
+            });
+        }
+
+        let min_rate = cells.iter().map(|c| c.turnout_rate).fold(f64::INFINITY, f64::min);
+        let max_rate = cells.iter().map(|c| c.turnout_rate).fold(f64::NEG_INFINITY, f64::max);
+        for cell in cells.iter_mut() {
+            cell.intensity = if max_rate > min_rate {
+                (cell.turnout_rate - min_rate) / (max_rate - min_rate)
+            } else {
+                1.0
+            };
+        }
+
+        TurnoutHeatMap {
+            election_id: election_id.to_string(),
+            state: state.map(|s| s.to_string()),
+            cells,
+            suppressed_sections,
+            suppressed_municipalities,
+            epsilon: self.config.epsilon_per_release,
+            generated_at: Utc::now(),
+        }
+    }
+}
+
+impl Default for HeatMapConfig {
+    fn default() -> Self {
+        Self {
+            epsilon_per_release: 0.5,
+            min_section_size: 20,
+            min_cell_size: 100,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(municipality_code: &str, section: &str, registered: u64, votes: u64) -> SectionTurnout {
+        SectionTurnout {
+            state: "SP".to_string(),
+            municipality_code: municipality_code.to_string(),
+            municipality: format!("Município {}", municipality_code),
+            zone: "001".to_string(),
+            section: section.to_string(),
+            registered_voters: registered,
+            votes_cast: votes,
+        }
+    }
+
+    fn service(total_epsilon: f64) -> TurnoutHeatMapService {
+        TurnoutHeatMapService::new(
+            HeatMapConfig::default(),
+            Arc::new(PrivacyBudgetAccountant::new(total_epsilon)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_small_sections_and_municipalities_are_suppressed() {
+        let service = service(10.0);
+        service.record_section("e1", section("3550308", "0001", 400, 320)).await.unwrap();
+        service.record_section("e1", section("3550308", "0002", 5, 5)).await.unwrap();
+        service.record_section("e1", section("3509502", "0001", 50, 40)).await.unwrap();
+
+        let heat_map = service.get_heat_map("e1", Some("sp")).await.unwrap();
+
+        assert_eq!(heat_map.cells.len(), 1);
+        assert_eq!(heat_map.cells[0].registered_voters, 400);
+        assert_eq!(heat_map.suppressed_sections, 1);
+        assert_eq!(heat_map.suppressed_municipalities, 1);
+        assert!(heat_map.cells[0].turnout_rate <= 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_queries_reuse_release_until_data_changes() {
+        let service = service(1.0);
+        service.record_section("e1", section("3550308", "0001", 400, 320)).await.unwrap();
+
+        let first = service.get_heat_map("e1", None).await.unwrap();
+        let second = service.get_heat_map("e1", None).await.unwrap();
+        assert_eq!(first.cells[0].noisy_votes_cast, second.cells[0].noisy_votes_cast);
+        assert_eq!(service.budget.get_status("e1").await.charges, 1);
+
+        service.record_section("e1", section("3550308", "0002", 300, 200)).await.unwrap();
+        service.get_heat_map("e1", None).await.unwrap();
+
+        // Orçamento de 1.0 esgotado após duas publicações de 0.5
+        service.record_section("e1", section("3550308", "0003", 300, 200)).await.unwrap();
+        assert!(service.get_heat_map("e1", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_state_is_validated_and_normalized_before_charging() {
+        let service = service(10.0);
+        service.record_section("e1", section("3550308", "0001", 400, 320)).await.unwrap();
+        let mut foreign = section("9999999", "0001", 400, 320);
+        foreign.state = "XX".to_string();
+        assert!(service.record_section("e1", foreign).await.is_err());
+
+        assert!(service.get_heat_map("e1", Some("XX")).await.is_err());
+        assert!(service.get_heat_map("e1", Some("São Paulo")).await.is_err());
+        assert_eq!(service.budget.get_status("e1").await.charges, 0);
+
+        let first = service.get_heat_map("e1", Some("sp")).await.unwrap();
+        let second = service.get_heat_map("e1", Some(" SP ")).await.unwrap();
+        assert_eq!(first.state.as_deref(), Some("SP"));
+        assert_eq!(first.cells[0].noisy_votes_cast, second.cells[0].noisy_votes_cast);
+        assert_eq!(service.budget.get_status("e1").await.charges, 1);
+    }
+}
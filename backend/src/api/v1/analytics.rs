@@ -0,0 +1,268 @@
+//! APIs de análises públicas com privacidade diferencial
+
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+use crate::auth::rbac::{Permission, Principal};
+use crate::middleware::device_identity::connection_device;
+use crate::models::ApiResponse;
+use fortis_domain::region::federative_unit;
+use crate::analytics::{
+    ElectionHistoryService, HistoryQuery, LessonsLearnedService, OpenDataService, OperationalSignal,
+    PrivacyBudgetAccountant, SectionResult, SectionTurnout, TurnoutHeatMapService
+};
+
+/// Filtro do mapa de calor
+#[derive(Debug, Deserialize)]
+pub struct HeatMapQuery {
+    pub state: Option<String>,
+}
+
+/// Configurar rotas de análises
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/turnout/{election_id}/heatmap", web::get().to(get_turnout_heat_map))
+        .route("/turnout/{election_id}/sections", web::post().to(record_sections))
+        .route("/privacy-budget/{election_id}", web::get().to(get_privacy_budget))
+        .route("/open-data/{election_id}/results", web::post().to(record_section_results))
+        .route("/open-data/{election_id}/certify", web::post().to(certify_election))
+        .route("/open-data/{election_id}/snapshots", web::post().to(generate_snapshot))
+        .route("/open-data/{election_id}/snapshots", web::get().to(list_snapshots))
+        .route("/history/elections", web::get().to(list_archived_elections))
+        .route("/history/turnout", web::get().to(get_turnout_history))
+        .route("/history/candidates/{votable}", web::get().to(get_candidate_history))
+        .route("/lessons-learned/{election_id}/signals", web::post().to(record_operational_signals))
+        .route("/lessons-learned/{election_id}", web::get().to(get_lessons_learned))
+        .route("/lessons-learned/{election_id}/report.pdf", web::get().to(get_lessons_learned_pdf));
+}
+
+/// Obter mapa de calor de comparecimento por município
+async fn get_turnout_heat_map(
+    service: web::Data<TurnoutHeatMapService>,
+    path: web::Path<String>,
+    query: web::Query<HeatMapQuery>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+
+    let state = match query.state.as_deref().map(federative_unit).transpose() {
+        Ok(state) => state,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("UF inválida: {}", e))
+        )),
+    };
+
+    match service.get_heat_map(&election_id, state).await {
+        Ok(heat_map) => Ok(HttpResponse::Ok().json(ApiResponse::success(heat_map))),
+        Err(e) => Ok(HttpResponse::TooManyRequests().json(
+            ApiResponse::<()>::error(format!("Falha ao publicar mapa de calor: {}", e))
+        )),
+    }
+}
+
+/// Registrar comparecimento apurado por seção
+async fn record_sections(
+    principal: Principal,
+    service: web::Data<TurnoutHeatMapService>,
+    path: web::Path<String>,
+    sections: web::Json<Vec<SectionTurnout>>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    principal.require_for(Permission::RecordResults, &election_id)?;
+    let sections = sections.into_inner();
+    let recorded = sections.len();
+
+    if let Some(invalid) = sections.iter().find(|s| federative_unit(&s.state).is_err()) {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("UF inválida na seção {}: {}", invalid.section, invalid.state))
+        ));
+    }
+    for section in sections {
+        if let Err(e) = service.record_section(&election_id, section).await {
+            return Ok(HttpResponse::BadRequest().json(
+                ApiResponse::<()>::error(format!("Seção rejeitada: {}", e))
+            ));
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "recorded": recorded
+    }))))
+}
+
+/// Obter situação do orçamento de privacidade da eleição
+async fn get_privacy_budget(
+    accountant: web::Data<PrivacyBudgetAccountant>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    let status = accountant.get_status(&election_id).await;
+    let charges = accountant.get_charges(&election_id).await;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "status": status,
+        "charges": charges
+    }))))
+}
+
+/// Registrar resultados apurados por seção
+async fn record_section_results(
+    principal: Principal,
+    service: web::Data<OpenDataService>,
+    path: web::Path<String>,
+    results: web::Json<Vec<SectionResult>>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    principal.require_for(Permission::PublishResults, &election_id)?;
+    let results = results.into_inner();
+    let recorded = results.len();
+
+    for result in results {
+        if let Err(e) = service.record_section_result(&election_id, result).await {
+            return Ok(HttpResponse::Conflict().json(
+                ApiResponse::<()>::error(format!("Falha ao registrar resultado: {}", e))
+            ));
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "recorded": recorded
+    }))))
+}
+
+/// Registrar certificação da eleição em nome de quem a certifica
+async fn certify_election(
+    principal: Principal,
+    service: web::Data<OpenDataService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let certified_by = principal.require_for(Permission::PublishResults, &path)?;
+    match service.certify(&path, certified_by).await {
+        Ok(certification) => Ok(HttpResponse::Ok().json(ApiResponse::success(certification))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Falha ao certificar eleição: {}", e))
+        )),
+    }
+}
+
+/// Gerar e publicar pacote de dados abertos
+async fn generate_snapshot(
+    principal: Principal,
+    service: web::Data<OpenDataService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageElections, &path)?;
+    match service.generate_snapshot(&path).await {
+        Ok(manifest) => Ok(HttpResponse::Ok().json(ApiResponse::success(manifest))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Falha ao gerar pacote de dados abertos: {}", e))
+        )),
+    }
+}
+
+/// Listar pacotes de dados abertos publicados
+async fn list_snapshots(
+    service: web::Data<OpenDataService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let snapshots = service.list_snapshots(&path).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(snapshots)))
+}
+
+/// Listar eleições arquivadas no índice histórico
+async fn list_archived_elections(
+    history: web::Data<ElectionHistoryService>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse> {
+    match history.elections(&query).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(ApiResponse::success(page))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Consulta histórica inválida: {}", e))
+        )),
+    }
+}
+
+/// Comparecimento por município ao longo das eleições arquivadas
+async fn get_turnout_history(
+    history: web::Data<ElectionHistoryService>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse> {
+    match history.turnout_trend(&query).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(ApiResponse::success(page))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Consulta histórica inválida: {}", e))
+        )),
+    }
+}
+
+/// Votação de um número nas eleições arquivadas
+async fn get_candidate_history(
+    history: web::Data<ElectionHistoryService>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse> {
+    match history.candidate_history(&path, &query).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(ApiResponse::success(page))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Consulta histórica inválida: {}", e))
+        )),
+    }
+}
+
+/// Registrar sinais operacionais da eleição; falhas de uma urna podem vir
+/// da própria urna, os demais sinais exigem `ManageElections`
+async fn record_operational_signals(
+    http_req: HttpRequest,
+    principal: Principal,
+    service: web::Data<LessonsLearnedService>,
+    path: web::Path<String>,
+    signals: web::Json<Vec<OperationalSignal>>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    let signals = signals.into_inner();
+    let recorded = signals.len();
+
+    let device = connection_device(&http_req).await.ok();
+    let from_urna = |signal: &OperationalSignal| match signal {
+        OperationalSignal::SyncFailure { urna_id, .. }
+        | OperationalSignal::HardwareFailure { urna_id, .. } => {
+            device.as_ref().is_some_and(|device| device.urna_id == *urna_id)
+        }
+        _ => false,
+    };
+    if !signals.iter().all(from_urna) {
+        principal.require_for(Permission::ManageElections, &election_id)?;
+    }
+
+    for signal in signals {
+        service.record(&election_id, signal).await;
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "recorded": recorded
+    }))))
+}
+
+/// Obter conjunto de dados do relatório de lições aprendidas
+async fn get_lessons_learned(
+    service: web::Data<LessonsLearnedService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    match service.get_report(&path).await {
+        Some(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Relatório ainda não gerado para a eleição".to_string())
+        )),
+    }
+}
+
+/// Baixar PDF do relatório de lições aprendidas
+async fn get_lessons_learned_pdf(
+    service: web::Data<LessonsLearnedService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    match service.get_pdf(&path).await {
+        Ok(pdf) => Ok(HttpResponse::Ok().content_type("application/pdf").body(pdf)),
+        Err(e) => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Relatório indisponível: {}", e))
+        )),
+    }
+}
@@ -1,6 +1,7 @@
 //! Módulo de autenticação da API v1
 
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use crate::auth::jwt::{self, Claims, JwtService, TokenPair};
 use crate::models::{AuthRequest, AuthResponse, ApiResponse, ApiError};
 use utoipa::OpenApi;
 use crate::services::auth::AuthService;
@@ -11,6 +12,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .route("/login", web::post().to(login))
         .route("/refresh", web::post().to(refresh))
         .route("/logout", web::post().to(logout))
+        .route("/sessions", web::get().to(list_sessions))
+        .route("/sessions/{session_id}", web::delete().to(end_session))
         .route("/verify", web::post().to(verify));
 }
 
@@ -42,32 +45,91 @@ async fn login(
     path = "/api/v1/auth/refresh",
     request_body = RefreshTokenRequest,
     responses(
-        (status = 200, description = "Token renovado com sucesso", body = ApiResponse<AuthResponse>),
+        (status = 200, description = "Token renovado com sucesso", body = ApiResponse<serde_json::Value>),
         (status = 401, description = "Token inválido", body = ApiResponse<String>)
     ),
     tag = "Autenticação"
 )]
 async fn refresh(
-    auth_service: web::Data<AuthService>,
+    jwt: web::Data<JwtService>,
     req: web::Json<RefreshTokenRequest>,
 ) -> Result<HttpResponse> {
-    match auth_service.refresh_token(&req.refresh_token).await {
-        Ok(response) => Ok(HttpResponse::Ok().json(ApiResponse::success(response))),
+    match jwt.refresh_session(&req.refresh_token).await {
+        Ok(tokens) => Ok(HttpResponse::Ok().json(ApiResponse::<TokenPair>::success(tokens))),
         Err(e) => Ok(HttpResponse::Unauthorized().json(ApiResponse::<()>::error(e.to_string()))),
     }
 }
 
-/// Endpoint de logout
+/// Endpoint de logout: revoga o access token e encerra a sessão
 async fn logout(
-    auth_service: web::Data<AuthService>,
-    req: web::Json<LogoutRequest>,
+    http_req: HttpRequest,
+    jwt: web::Data<JwtService>,
+    req: Option<web::Json<LogoutRequest>>,
 ) -> Result<HttpResponse> {
-    match auth_service.logout(&req.refresh_token).await {
-        Ok(_) => Ok(HttpResponse::Ok().json(ApiResponse::success("Logout realizado com sucesso"))),
-        Err(e) => Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string()))),
+    let claims = match authenticated(&http_req, &jwt).await {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+    let all_sessions = req.map_or(false, |r| r.all_sessions);
+
+    match jwt.logout(&claims, all_sessions).await {
+        Ok(ended) => Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "message": "Logout realizado com sucesso",
+            "sessions_ended": ended,
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao encerrar sessão: {}", e))
+        )),
+    }
+}
+
+/// Sessões ativas do usuário autenticado
+async fn list_sessions(
+    http_req: HttpRequest,
+    jwt: web::Data<JwtService>,
+) -> Result<HttpResponse> {
+    let claims = match authenticated(&http_req, &jwt).await {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+
+    match jwt.sessions_of(&claims.sub).await {
+        Ok(sessions) => Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "current_session": claims.sid,
+            "sessions": sessions,
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao listar sessões: {}", e))
+        )),
     }
 }
 
+/// Encerra outra sessão do usuário (ex.: console esquecido aberto)
+async fn end_session(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    jwt: web::Data<JwtService>,
+) -> Result<HttpResponse> {
+    let claims = match authenticated(&http_req, &jwt).await {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+
+    match jwt.end_session(&claims.sub, &path).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Sessão encerrada".to_string()))),
+        Err(e) => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// Claims do access token do cabeçalho, ou a resposta 401
+async fn authenticated(http_req: &HttpRequest, jwt: &JwtService) -> std::result::Result<Claims, HttpResponse> {
+    let Some(token) = jwt::bearer_token(http_req.headers()) else {
+        return Err(HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Token de acesso ausente".to_string())));
+    };
+    jwt.authenticate(token).await
+        .map_err(|e| HttpResponse::Unauthorized().json(ApiResponse::<()>::error(e.to_string())))
+}
+
 /// Endpoint de verificação de token
 async fn verify(
     auth_service: web::Data<AuthService>,
@@ -80,13 +142,15 @@ async fn verify(
 }
 
 #[derive(serde::Deserialize, utoipa::ToSchema)]
-struct RefreshTokenRequest {
+pub(crate) struct RefreshTokenRequest {
     refresh_token: String,
 }
 
 #[derive(serde::Deserialize, utoipa::ToSchema)]
 struct LogoutRequest {
-    refresh_token: String,
+    /// Encerra também as demais sessões do usuário
+    #[serde(default)]
+    all_sessions: bool,
 }
 
 #[derive(serde::Deserialize, utoipa::ToSchema)]
@@ -0,0 +1,102 @@
+//! APIs do beacon público de aleatoriedade
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::auth::rbac::{Permission, Principal};
+use crate::models::ApiResponse;
+use crate::services::beacon::RandomnessBeacon;
+
+/// Requisição de compromisso ou de sorteio
+#[derive(Debug, Deserialize)]
+pub struct DrawRequest {
+    pub purpose: String,
+}
+
+/// Configurar rotas do beacon
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/latest", web::get().to(get_latest))
+        .route("/rounds/{round}", web::get().to(get_round))
+        .route("/commitments/{election_id}", web::get().to(list_commitments))
+        .route("/commitments/{election_id}", web::post().to(create_commitment))
+        .route("/draws/{election_id}", web::get().to(list_draws))
+        .route("/draws/{election_id}", web::post().to(create_draw));
+}
+
+/// Obter aleatoriedade mais recente
+async fn get_latest(
+    beacon: web::Data<RandomnessBeacon>,
+) -> Result<HttpResponse> {
+    match beacon.latest().await {
+        Ok(value) => Ok(HttpResponse::Ok().json(ApiResponse::success(value))),
+        Err(e) => Ok(HttpResponse::ServiceUnavailable().json(
+            ApiResponse::<()>::error(format!("Beacon indisponível: {}", e))
+        )),
+    }
+}
+
+/// Obter pulso verificado de uma rodada do drand
+async fn get_round(
+    beacon: web::Data<RandomnessBeacon>,
+    path: web::Path<u64>,
+) -> Result<HttpResponse> {
+    match beacon.drand_round(path.into_inner()).await {
+        Ok(pulse) => Ok(HttpResponse::Ok().json(ApiResponse::success(pulse))),
+        Err(e) => Ok(HttpResponse::ServiceUnavailable().json(
+            ApiResponse::<()>::error(format!("Rodada indisponível: {}", e))
+        )),
+    }
+}
+
+/// Listar compromissos de sorteio de uma eleição
+async fn list_commitments(
+    beacon: web::Data<RandomnessBeacon>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let commitments = beacon.get_commitments(path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(commitments)))
+}
+
+/// Comprometer o sorteio de uma finalidade com uma rodada futura do drand
+async fn create_commitment(
+    beacon: web::Data<RandomnessBeacon>,
+    principal: Principal,
+    path: web::Path<Uuid>,
+    req: web::Json<DrawRequest>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    let actor = principal.require_for(Permission::ManageElections, &election_id.to_string())?;
+    match beacon.commit(election_id, &req.purpose, actor).await {
+        Ok(commitment) => Ok(HttpResponse::Created().json(ApiResponse::success(commitment))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Falha no compromisso do sorteio: {}", e))
+        )),
+    }
+}
+
+/// Listar sorteios de uma eleição
+async fn list_draws(
+    beacon: web::Data<RandomnessBeacon>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let draws = beacon.get_draws(path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(draws)))
+}
+
+/// Realizar sorteio com a rodada comprometida, depois de publicada
+async fn create_draw(
+    beacon: web::Data<RandomnessBeacon>,
+    principal: Principal,
+    path: web::Path<Uuid>,
+    req: web::Json<DrawRequest>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    principal.require_for(Permission::ManageElections, &election_id.to_string())?;
+    match beacon.draw(election_id, &req.purpose).await {
+        Ok(draw) => Ok(HttpResponse::Ok().json(ApiResponse::success(draw))),
+        Err(e) => Ok(HttpResponse::ServiceUnavailable().json(
+            ApiResponse::<()>::error(format!("Falha no sorteio: {}", e))
+        )),
+    }
+}
@@ -0,0 +1,111 @@
+//! APIs do livro de consentimento biométrico (auditorias LGPD)
+//!
+//! As finalidades declaradas são públicas; consultas exigem `ReadAudits` e
+//! concessão ou revogação, `ManageConsent`, registrando quem as fez.
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use crate::auth::rbac::{Permission, Principal};
+use crate::models::ApiResponse;
+use crate::services::consent::{
+    ConsentLedger, ConsentQuery, BiometricCategory, ProcessingPurpose
+};
+
+/// Requisição de concessão ou revogação de consentimento
+#[derive(Debug, Deserialize)]
+pub struct ConsentRequest {
+    pub voter_id: String,
+    pub category: BiometricCategory,
+    pub purpose: ProcessingPurpose,
+    pub interaction_id: Option<String>,
+    pub source: Option<String>,
+}
+
+/// Configurar rotas de consentimento
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/declarations", web::get().to(get_declarations))
+        .route("/records", web::get().to(query_records))
+        .route("/usages", web::get().to(query_usages))
+        .route("/summary", web::get().to(get_audit_summary))
+        .route("/grant", web::post().to(grant_consent))
+        .route("/revoke", web::post().to(revoke_consent));
+}
+
+/// Listar finalidades declaradas
+async fn get_declarations(
+    ledger: web::Data<ConsentLedger>,
+) -> Result<HttpResponse> {
+    let declarations = ledger.get_declarations().await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(declarations)))
+}
+
+/// Consultar registros de tratamento biométrico
+async fn query_records(
+    principal: Principal,
+    ledger: web::Data<ConsentLedger>,
+    query: web::Query<ConsentQuery>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ReadAudits)?;
+    let records = ledger.query_records(&query).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(records)))
+}
+
+/// Consultar tentativas de uso, inclusive as negadas
+async fn query_usages(
+    principal: Principal,
+    ledger: web::Data<ConsentLedger>,
+    query: web::Query<ConsentQuery>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ReadAudits)?;
+    let usages = ledger.query_usages(&query).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(usages)))
+}
+
+/// Obter resumo para auditoria LGPD
+async fn get_audit_summary(
+    principal: Principal,
+    ledger: web::Data<ConsentLedger>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ReadAudits)?;
+    let summary = ledger.get_audit_summary().await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(summary)))
+}
+
+/// Registrar consentimento explícito
+async fn grant_consent(
+    principal: Principal,
+    ledger: web::Data<ConsentLedger>,
+    req: web::Json<ConsentRequest>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageConsent)?;
+    let interaction_id = req.interaction_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let source = format!("{}:{}", req.source.as_deref().unwrap_or("api"), actor);
+
+    match ledger.grant_consent(&req.voter_id, req.category, req.purpose, &interaction_id, &source).await {
+        Ok(record) => Ok(HttpResponse::Ok().json(ApiResponse::success(record))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Falha ao registrar consentimento: {}", e))
+        )),
+    }
+}
+
+/// Revogar consentimento explícito
+async fn revoke_consent(
+    principal: Principal,
+    ledger: web::Data<ConsentLedger>,
+    req: web::Json<ConsentRequest>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageConsent)?;
+    match ledger.revoke_consent(&req.voter_id, req.category, req.purpose).await {
+        Ok(revoked) => {
+            log::info!("Biometric consent {:?}/{:?} revoked by {}", req.category, req.purpose, actor);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+                "revoked": revoked
+            }))))
+        }
+        Err(e) => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Falha ao revogar consentimento: {}", e))
+        )),
+    }
+}
@@ -0,0 +1,129 @@
+//! Dashboards de auditoria da API v1
+
+use actix_web::{web, HttpResponse, Result};
+use crate::auth::rbac::{Permission, Principal};
+use crate::models::ApiResponse;
+use crate::services::audit::dashboards::{DashboardQuery, DashboardRequest, DashboardService};
+
+/// Configurar rotas de dashboards de auditoria
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("", web::get().to(list_dashboards))
+        .route("", web::post().to(create_dashboard))
+        .route("/{id}", web::get().to(get_dashboard))
+        .route("/{id}", web::put().to(update_dashboard))
+        .route("/{id}", web::delete().to(delete_dashboard))
+        .route("/{id}/data", web::get().to(get_dashboard_data))
+        .route("/{id}/widgets/{widget_id}/data", web::get().to(get_widget_data));
+}
+
+/// Listar dashboards
+async fn list_dashboards(
+    principal: Principal,
+    service: web::Data<DashboardService>,
+) -> Result<HttpResponse> {
+    principal.require_scope(Permission::ReadAudits)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(service.list().await)))
+}
+
+/// Criar dashboard
+async fn create_dashboard(
+    principal: Principal,
+    req: web::Json<DashboardRequest>,
+    service: web::Data<DashboardService>,
+) -> Result<HttpResponse> {
+    let (actor, _) = principal.require_scope(Permission::ReadAudits)?;
+
+    match service.create(req.into_inner(), actor).await {
+        Ok(dashboard) => Ok(HttpResponse::Created().json(ApiResponse::success(dashboard))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao criar dashboard: {}", e))
+        )),
+    }
+}
+
+/// Obter definição do dashboard
+async fn get_dashboard(
+    principal: Principal,
+    path: web::Path<String>,
+    service: web::Data<DashboardService>,
+) -> Result<HttpResponse> {
+    principal.require_scope(Permission::ReadAudits)?;
+
+    match service.get(&path).await {
+        Some(dashboard) => Ok(HttpResponse::Ok().json(ApiResponse::success(dashboard))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Dashboard não encontrado: {}", path))
+        )),
+    }
+}
+
+/// Atualizar dashboard; só o autor ou quem gerencia auditorias
+async fn update_dashboard(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<DashboardRequest>,
+    service: web::Data<DashboardService>,
+) -> Result<HttpResponse> {
+    let (actor, _) = principal.require_scope(Permission::ReadAudits)?;
+    let manages_audits = principal.require(Permission::ManageAudits).is_ok();
+
+    match service.update(&path, req.into_inner(), actor, manages_audits).await {
+        Ok(dashboard) => Ok(HttpResponse::Ok().json(ApiResponse::success(dashboard))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao atualizar dashboard: {}", e))
+        )),
+    }
+}
+
+/// Remover dashboard; só o autor ou quem gerencia auditorias
+async fn delete_dashboard(
+    principal: Principal,
+    path: web::Path<String>,
+    service: web::Data<DashboardService>,
+) -> Result<HttpResponse> {
+    let (actor, _) = principal.require_scope(Permission::ReadAudits)?;
+    let manages_audits = principal.require(Permission::ManageAudits).is_ok();
+
+    match service.delete(&path, actor, manages_audits).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Dashboard removido".to_string()))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao remover dashboard: {}", e))
+        )),
+    }
+}
+
+/// Dados de todos os widgets, no recorte de quem consulta
+async fn get_dashboard_data(
+    principal: Principal,
+    path: web::Path<String>,
+    query: web::Query<DashboardQuery>,
+    service: web::Data<DashboardService>,
+) -> Result<HttpResponse> {
+    let (_, scope) = principal.require_scope(Permission::ReadAudits)?;
+
+    match service.data(&path, &scope, &query).await {
+        Ok(data) => Ok(HttpResponse::Ok().json(ApiResponse::success(data))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao consultar dashboard: {}", e))
+        )),
+    }
+}
+
+/// Dados de um widget, no recorte de quem consulta
+async fn get_widget_data(
+    principal: Principal,
+    path: web::Path<(String, String)>,
+    query: web::Query<DashboardQuery>,
+    service: web::Data<DashboardService>,
+) -> Result<HttpResponse> {
+    let (_, scope) = principal.require_scope(Permission::ReadAudits)?;
+    let (dashboard_id, widget_id) = path.into_inner();
+
+    match service.widget_data(&dashboard_id, &widget_id, &scope, &query).await {
+        Ok(data) => Ok(HttpResponse::Ok().json(ApiResponse::success(data))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao consultar widget: {}", e))
+        )),
+    }
+}
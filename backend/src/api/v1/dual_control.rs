@@ -0,0 +1,83 @@
+//! APIs de controle duplo das operações destrutivas
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use crate::auth::rbac::Principal;
+use crate::errors::FortisError;
+use crate::models::ApiResponse;
+use crate::services::dual_control::{DualControlService, DualControlStatus};
+
+/// Filtro da listagem de pedidos
+#[derive(Debug, Deserialize)]
+pub struct DualControlQuery {
+    pub status: Option<DualControlStatus>,
+}
+
+/// Configurar rotas de controle duplo
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/requests", web::get().to(list_requests))
+        .route("/requests/{id}", web::get().to(get_request))
+        .route("/requests/{id}/approve", web::post().to(approve_request))
+        .route("/requests/{id}/reject", web::post().to(reject_request));
+}
+
+/// Pedidos que o sujeito pode decidir
+async fn list_requests(
+    principal: Principal,
+    query: web::Query<DualControlQuery>,
+    dual_control: web::Data<DualControlService>,
+) -> Result<HttpResponse> {
+    if principal.subject.is_none() {
+        return Err(FortisError::AuthenticationRequired.into());
+    }
+    let requests: Vec<_> = dual_control.list(query.status).await.into_iter()
+        .filter(|request| principal.require(request.permission).is_ok())
+        .collect();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(requests)))
+}
+
+/// Consultar pedido
+async fn get_request(
+    principal: Principal,
+    path: web::Path<String>,
+    dual_control: web::Data<DualControlService>,
+) -> Result<HttpResponse> {
+    match dual_control.get(&path.into_inner()).await {
+        Some(request) => {
+            principal.require(request.permission)?;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(request)))
+        }
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Pedido de controle duplo não encontrado".to_string())
+        )),
+    }
+}
+
+/// Segunda aprovação do pedido
+async fn approve_request(
+    principal: Principal,
+    path: web::Path<String>,
+    dual_control: web::Data<DualControlService>,
+) -> Result<HttpResponse> {
+    match dual_control.approve(&path.into_inner(), &principal).await {
+        Ok(request) => Ok(HttpResponse::Ok().json(ApiResponse::success(request))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Aprovação recusada: {}", e))
+        )),
+    }
+}
+
+/// Recusar o pedido
+async fn reject_request(
+    principal: Principal,
+    path: web::Path<String>,
+    dual_control: web::Data<DualControlService>,
+) -> Result<HttpResponse> {
+    match dual_control.reject(&path.into_inner(), &principal).await {
+        Ok(request) => Ok(HttpResponse::Ok().json(ApiResponse::success(request))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Recusa não registrada: {}", e))
+        )),
+    }
+}
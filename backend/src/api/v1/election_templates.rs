@@ -0,0 +1,61 @@
+//! Modelos de eleição da API v1
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Serialize;
+use crate::models::ApiResponse;
+use crate::services::election_templates::{ElectionTemplateService, InstantiateRequest, TemplateInstance};
+use crate::auth::rbac::{Permission, Principal};
+
+/// Configurar rotas de modelos de eleição
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("", web::get().to(list_templates))
+        .route("/{id}", web::get().to(get_template))
+        .route("/{id}/instantiate", web::post().to(instantiate_template));
+}
+
+/// Eleição criada e o modelo aplicado
+#[derive(Debug, Serialize)]
+pub struct InstantiateResponse {
+    pub election_id: uuid::Uuid,
+    pub instance: TemplateInstance,
+}
+
+/// Listar modelos aprovados
+async fn list_templates(service: web::Data<ElectionTemplateService>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(service.list().await)))
+}
+
+/// Obter modelo
+async fn get_template(
+    path: web::Path<String>,
+    service: web::Data<ElectionTemplateService>,
+) -> Result<HttpResponse> {
+    match service.get(&path).await {
+        Some(template) => Ok(HttpResponse::Ok().json(ApiResponse::success(template))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Modelo de eleição não encontrado: {}", path))
+        )),
+    }
+}
+
+/// Criar eleição a partir do modelo
+async fn instantiate_template(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<InstantiateRequest>,
+    service: web::Data<ElectionTemplateService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let actor = principal.subject.clone().unwrap_or_else(|| "api".to_string());
+
+    match service.instantiate(&path, req.into_inner(), &actor).await {
+        Ok((election, instance)) => Ok(HttpResponse::Created().json(ApiResponse::success(InstantiateResponse {
+            election_id: election.id,
+            instance,
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao instanciar modelo: {}", e))
+        )),
+    }
+}
@@ -1,33 +1,97 @@
 //! Módulo de eleições da API v1
 
 use actix_web::{web, HttpResponse, Result};
-use crate::models::{CreateElectionRequest, ApiResponse};
-use sqlx::{Pool, Postgres};
+use serde::Deserialize;
+use crate::database::Election;
+use crate::models::{CreateElectionRequest, CreateCandidateRequest, ElectionResponse, ApiResponse};
+use crate::services::candidate::CandidateService;
+use crate::services::election::{ElectionService, ElectionStatus};
+use crate::services::election_package::ElectionPackageService;
+use crate::services::election_templates::ElectionTemplateService;
+use crate::services::results::ResultsService;
+use crate::services::tally::ShadowTallyService;
+use crate::analytics::SectionResult;
+use crate::audit::rla::{AuditedBallot, PlanRequest, RlaService};
+use crate::auth::rbac::{Permission, Principal};
 
 /// Configurar rotas de eleições
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg
         .route("", web::get().to(list_elections))
         .route("", web::post().to(create_election))
+        .route("/package-key", web::get().to(get_package_key))
         .route("/{id}", web::get().to(get_election))
         .route("/{id}", web::put().to(update_election))
         .route("/{id}", web::delete().to(delete_election))
+        .route("/{id}/transitions", web::post().to(transition_election))
+        .route("/{id}/transitions", web::get().to(list_transitions))
+        .route("/{id}/events", web::get().to(list_events))
+        .route("/{id}/template", web::get().to(get_template_conformance))
+        .route("/{id}/package", web::get().to(export_package))
+        .route("/{id}/results", web::get().to(get_results))
+        .route("/{id}/results/bulletins", web::post().to(record_bulletin))
+        .route("/{id}/results/bulletins", web::get().to(list_bulletins))
+        .route("/{id}/results/publish", web::post().to(publish_results))
+        .route("/{id}/results/manifest", web::get().to(get_results_manifest))
+        .route("/{id}/results/shadow", web::post().to(run_shadow_tally))
+        .route("/{id}/results/shadow", web::get().to(list_shadow_tallies))
+        .route("/{id}/audits/rla", web::post().to(plan_rla))
+        .route("/{id}/audits/rla/{plan_id}", web::get().to(get_rla_plan))
+        .route("/{id}/audits/rla/{plan_id}/ballots", web::post().to(record_rla_ballot))
+        .route("/{id}/audits/rla/{plan_id}/ballots", web::get().to(list_rla_ballots))
+        .route("/{id}/audits/rla/{plan_id}/escalate", web::post().to(escalate_rla))
+        .route("/{id}/audits/rla/{plan_id}/report", web::post().to(report_rla))
         .route("/{id}/candidates", web::get().to(get_candidates))
-        .route("/{id}/candidates", web::post().to(add_candidate));
+        .route("/{id}/candidates", web::post().to(add_candidate))
+        .route("/{id}/candidates/{candidate_id}", web::get().to(get_candidate))
+        .route("/{id}/candidates/{candidate_id}", web::put().to(update_candidate))
+        .route("/{id}/candidates/{candidate_id}", web::delete().to(delete_candidate))
+        .route("/{id}/candidates/{candidate_id}/photo", web::get().to(get_candidate_photo));
+}
+
+/// Solicitação de transição de status
+#[derive(Debug, Deserialize)]
+pub struct TransitionRequest {
+    pub status: ElectionStatus,
+    pub actor: String,
+    pub reason: Option<String>,
+}
+
+fn to_response(election: Election) -> ElectionResponse {
+    ElectionResponse {
+        id: election.id,
+        title: election.name,
+        description: election.description,
+        start_date: election.start_date,
+        end_date: election.end_date,
+        status: election.status,
+        created_at: election.created_at,
+        updated_at: election.updated_at,
+        candidates: None,
+        stats: None,
+    }
 }
 
 /// Listar eleições
-async fn list_elections(_pool: web::Data<Pool<Postgres>>) -> Result<HttpResponse> {
-    // Implementação simplificada
-    let responses: Vec<String> = vec![];
-    Ok(HttpResponse::Ok().json(ApiResponse::success(responses)))
+async fn list_elections(service: web::Data<ElectionService>) -> Result<HttpResponse> {
+    match service.list_elections().await {
+        Ok(elections) => {
+            let responses: Vec<ElectionResponse> = elections.into_iter().map(to_response).collect();
+            Ok(HttpResponse::Ok().json(ApiResponse::success(responses)))
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao listar eleições: {}", e))
+        )),
+    }
 }
 
 /// Criar eleição
 async fn create_election(
+    principal: Principal,
     req: web::Json<CreateElectionRequest>,
-    _pool: web::Data<Pool<Postgres>>,
+    service: web::Data<ElectionService>,
 ) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
     // Validar dados
     if req.start_date >= req.end_date {
         return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Data de início deve ser anterior à data de fim".to_string())));
@@ -37,53 +101,430 @@ async fn create_election(
         return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Título da eleição é obrigatório".to_string())));
     }
 
-    // Implementação simplificada
-    Ok(HttpResponse::Created().json(ApiResponse::success("Eleição criada com sucesso".to_string())))
+    match service.create_election(req.into_inner(), "api").await {
+        Ok(election) => Ok(HttpResponse::Created().json(ApiResponse::success(to_response(election)))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao criar eleição: {}", e))
+        )),
+    }
 }
 
 /// Obter eleição
 async fn get_election(
-    _path: web::Path<uuid::Uuid>,
-    _pool: web::Data<Pool<Postgres>>,
+    path: web::Path<uuid::Uuid>,
+    service: web::Data<ElectionService>,
 ) -> Result<HttpResponse> {
-    // Implementação simplificada
-    Ok(HttpResponse::Ok().json(ApiResponse::success("Eleição encontrada".to_string())))
+    match service.get_election(path.into_inner()).await {
+        Ok(Some(election)) => Ok(HttpResponse::Ok().json(ApiResponse::success(to_response(election)))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Eleição não encontrada".to_string())
+        )),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao obter eleição: {}", e))
+        )),
+    }
 }
 
-/// Atualizar eleição
+/// Atualizar eleição (somente em rascunho)
 async fn update_election(
-    _path: web::Path<uuid::Uuid>,
-    _req: web::Json<CreateElectionRequest>,
-    _pool: web::Data<Pool<Postgres>>,
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<CreateElectionRequest>,
+    service: web::Data<ElectionService>,
 ) -> Result<HttpResponse> {
-    // Implementação simplificada
-    Ok(HttpResponse::Ok().json(ApiResponse::success("Eleição atualizada com sucesso".to_string())))
+    principal.require_for(Permission::ManageElections, &path.to_string())?;
+    match service.update_election(path.into_inner(), req.into_inner()).await {
+        Ok(election) => Ok(HttpResponse::Ok().json(ApiResponse::success(to_response(election)))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao atualizar eleição: {}", e))
+        )),
+    }
 }
 
-/// Deletar eleição
+/// Deletar eleição (somente em rascunho)
 async fn delete_election(
-    _path: web::Path<uuid::Uuid>,
-    _pool: web::Data<Pool<Postgres>>,
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    service: web::Data<ElectionService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageElections, &path.to_string())?;
+    match service.delete_election(path.into_inner()).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Eleição deletada com sucesso".to_string()))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao deletar eleição: {}", e))
+        )),
+    }
+}
+
+/// Aplicar transição de status da eleição
+async fn transition_election(
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<TransitionRequest>,
+    service: web::Data<ElectionService>,
 ) -> Result<HttpResponse> {
-    // Implementação simplificada
-    Ok(HttpResponse::Ok().json(ApiResponse::success("Eleição deletada com sucesso".to_string())))
+    principal.require_for(Permission::TransitionElections, &path.to_string())?;
+    let req = req.into_inner();
+
+    match service.transition(path.into_inner(), req.status, &req.actor, req.reason).await {
+        Ok(election) => Ok(HttpResponse::Ok().json(ApiResponse::success(to_response(election)))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Transição rejeitada: {}", e))
+        )),
+    }
+}
+
+/// Listar transições de status da eleição
+async fn list_transitions(
+    path: web::Path<uuid::Uuid>,
+    service: web::Data<ElectionService>,
+) -> Result<HttpResponse> {
+    let transitions = service.get_transitions(path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(transitions)))
+}
+
+/// Listar os eventos que formam o estado da eleição
+async fn list_events(
+    path: web::Path<uuid::Uuid>,
+    service: web::Data<ElectionService>,
+) -> Result<HttpResponse> {
+    let events = service.events(path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(events)))
+}
+
+/// Conferir a eleição com o modelo do qual foi criada
+async fn get_template_conformance(
+    path: web::Path<uuid::Uuid>,
+    templates: web::Data<ElectionTemplateService>,
+) -> Result<HttpResponse> {
+    match templates.check_conformance(path.into_inner()).await {
+        Ok(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        Err(e) => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Erro ao conferir modelo da eleição: {}", e))
+        )),
+    }
+}
+
+/// Filtro de candidatos por região
+#[derive(Debug, Deserialize)]
+pub struct CandidateQuery {
+    pub region: Option<String>,
 }
 
 /// Obter candidatos da eleição
 async fn get_candidates(
-    _path: web::Path<uuid::Uuid>,
-    _pool: web::Data<Pool<Postgres>>,
+    path: web::Path<uuid::Uuid>,
+    query: web::Query<CandidateQuery>,
+    service: web::Data<CandidateService>,
 ) -> Result<HttpResponse> {
-    // Implementação simplificada
-    Ok(HttpResponse::Ok().json(ApiResponse::success("Candidatos da eleição".to_string())))
+    let candidates = service.list_candidates(path.into_inner(), query.region.as_deref()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(candidates)))
 }
 
 /// Adicionar candidato à eleição
 async fn add_candidate(
-    _path: web::Path<uuid::Uuid>,
-    _req: web::Json<crate::models::CreateCandidateRequest>,
-    _pool: web::Data<Pool<Postgres>>,
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<CreateCandidateRequest>,
+    service: web::Data<CandidateService>,
 ) -> Result<HttpResponse> {
-    // Implementação simplificada
-    Ok(HttpResponse::Ok().json(ApiResponse::success("Candidato adicionado com sucesso".to_string())))
-}
\ No newline at end of file
+    principal.require_for(Permission::ManageCandidates, &path.to_string())?;
+    match service.create_candidate(path.into_inner(), req.into_inner()).await {
+        Ok(candidate) => Ok(HttpResponse::Created().json(ApiResponse::success(candidate))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao adicionar candidato: {}", e))
+        )),
+    }
+}
+
+/// Obter candidato
+async fn get_candidate(
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    service: web::Data<CandidateService>,
+) -> Result<HttpResponse> {
+    let (election_id, candidate_id) = path.into_inner();
+
+    match service.get_candidate(election_id, candidate_id).await {
+        Some(candidate) => Ok(HttpResponse::Ok().json(ApiResponse::success(candidate))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Candidato não encontrado".to_string())
+        )),
+    }
+}
+
+/// Atualizar candidato
+async fn update_candidate(
+    principal: Principal,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    req: web::Json<CreateCandidateRequest>,
+    service: web::Data<CandidateService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageCandidates, &path.0.to_string())?;
+    let (election_id, candidate_id) = path.into_inner();
+
+    match service.update_candidate(election_id, candidate_id, req.into_inner()).await {
+        Ok(candidate) => Ok(HttpResponse::Ok().json(ApiResponse::success(candidate))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao atualizar candidato: {}", e))
+        )),
+    }
+}
+
+/// Remover candidato
+async fn delete_candidate(
+    principal: Principal,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    service: web::Data<CandidateService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageCandidates, &path.0.to_string())?;
+    let (election_id, candidate_id) = path.into_inner();
+
+    match service.delete_candidate(election_id, candidate_id).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Candidato removido com sucesso".to_string()))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao remover candidato: {}", e))
+        )),
+    }
+}
+
+/// Obter foto do candidato
+async fn get_candidate_photo(
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    service: web::Data<CandidateService>,
+) -> Result<HttpResponse> {
+    let (election_id, candidate_id) = path.into_inner();
+
+    match service.get_photo(election_id, candidate_id).await {
+        Some(photo) => Ok(HttpResponse::Ok().content_type("image/jpeg").body(photo)),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Foto não encontrada".to_string())
+        )),
+    }
+}
+
+/// Obter chave pública de assinatura dos pacotes de eleição
+async fn get_package_key(
+    service: web::Data<ElectionPackageService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(service.signing_public_key())))
+}
+
+/// Exportar pacote assinado da eleição para preparação offline das urnas
+async fn export_package(
+    path: web::Path<uuid::Uuid>,
+    query: web::Query<CandidateQuery>,
+    service: web::Data<ElectionPackageService>,
+) -> Result<HttpResponse> {
+    match service.export_package(path.into_inner(), query.region.as_deref()).await {
+        Ok(package) => Ok(HttpResponse::Ok().json(ApiResponse::success(package))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao gerar pacote da eleição: {}", e))
+        )),
+    }
+}
+
+/// Filtro do resultado por UF, município ou recorte da hierarquia (`SP/71072/0001`)
+#[derive(Debug, Deserialize)]
+pub struct ResultsQuery {
+    pub region: Option<String>,
+}
+
+/// Obter resultado apurado da eleição
+async fn get_results(
+    path: web::Path<uuid::Uuid>,
+    query: web::Query<ResultsQuery>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    match service.results(path.into_inner(), query.region.as_deref()).await {
+        Ok(results) => Ok(HttpResponse::Ok()
+            .insert_header(("Cache-Control", format!("public, max-age={}", service.cache_ttl_seconds())))
+            .json(ApiResponse::success(results))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao obter resultado: {}", e))
+        )),
+    }
+}
+
+/// Totais dos boletins recebidos na região
+async fn list_bulletins(
+    path: web::Path<uuid::Uuid>,
+    query: web::Query<ResultsQuery>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    let totals = service.bulletin_totals_in(path.into_inner(), query.region.as_deref()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(totals)))
+}
+
+/// Receber boletim de urna de uma seção
+async fn record_bulletin(
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<SectionResult>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::RecordResults, &path.to_string())?;
+    match service.record_bulletin(path.into_inner(), req.into_inner()).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Boletim registrado".to_string()))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao registrar boletim: {}", e))
+        )),
+    }
+}
+
+/// Publicar manifesto assinado do resultado, após a comparação com a apuração-sombra
+async fn publish_results(
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    service: web::Data<ResultsService>,
+    shadow: web::Data<ShadowTallyService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::PublishResults, &path.to_string())?;
+    if let Err(e) = shadow.ensure_certifiable(*path).await {
+        return Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Certificação bloqueada pela apuração-sombra: {}", e))
+        ));
+    }
+    match service.publish(path.into_inner()).await {
+        Ok(manifest) => Ok(HttpResponse::Ok().json(ApiResponse::success(manifest))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao publicar resultado: {}", e))
+        )),
+    }
+}
+
+/// Comparar a apuração oficial com a apuração-sombra
+async fn run_shadow_tally(
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    shadow: web::Data<ShadowTallyService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageAudits, &path.to_string())?;
+    match shadow.run(path.into_inner()).await {
+        Ok(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro na apuração-sombra: {}", e))
+        )),
+    }
+}
+
+/// Listar comparações com a apuração-sombra
+async fn list_shadow_tallies(
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    shadow: web::Data<ShadowTallyService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ReadAudits, &path.to_string())?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(shadow.reports(path.into_inner()).await)))
+}
+
+/// Obter manifesto publicado do resultado
+async fn get_results_manifest(
+    path: web::Path<uuid::Uuid>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    match service.manifest(path.into_inner()).await {
+        Some(manifest) => Ok(HttpResponse::Ok().json(ApiResponse::success(manifest))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Resultado ainda não publicado".to_string())
+        )),
+    }
+}
+
+/// Ampliação da amostra da auditoria
+#[derive(Debug, Deserialize)]
+pub struct EscalateRequest {
+    pub additional: u64,
+}
+
+/// Planejar auditoria de limitação de risco
+async fn plan_rla(
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<PlanRequest>,
+    service: web::Data<RlaService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageAudits, &path.to_string())?;
+    match service.plan(path.into_inner(), req.into_inner()).await {
+        Ok(plan) => Ok(HttpResponse::Created().json(ApiResponse::success(plan))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao planejar auditoria: {}", e))
+        )),
+    }
+}
+
+/// Obter plano da auditoria e cédulas sorteadas
+async fn get_rla_plan(
+    principal: Principal,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    service: web::Data<RlaService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ReadAudits, &path.0.to_string())?;
+    let (election_id, plan_id) = path.into_inner();
+    match service.get_plan(plan_id).await {
+        Some(plan) if plan.election_id == election_id => Ok(HttpResponse::Ok().json(ApiResponse::success(plan))),
+        _ => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Plano de auditoria não encontrado".to_string())
+        )),
+    }
+}
+
+/// Registrar interpretação de uma cédula sorteada
+async fn record_rla_ballot(
+    principal: Principal,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    req: web::Json<AuditedBallot>,
+    service: web::Data<RlaService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::RecordAuditBallots, &path.0.to_string())?;
+    let (_, plan_id) = path.into_inner();
+    match service.record(plan_id, req.into_inner()).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Cédula auditada registrada".to_string()))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao registrar cédula auditada: {}", e))
+        )),
+    }
+}
+
+/// Listar cédulas já auditadas
+async fn list_rla_ballots(
+    principal: Principal,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    service: web::Data<RlaService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ReadAudits, &path.0.to_string())?;
+    let (_, plan_id) = path.into_inner();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(service.audited_ballots(plan_id).await)))
+}
+
+/// Ampliar a amostra da auditoria
+async fn escalate_rla(
+    principal: Principal,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    req: web::Json<EscalateRequest>,
+    service: web::Data<RlaService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageAudits, &path.0.to_string())?;
+    let (_, plan_id) = path.into_inner();
+    match service.escalate(plan_id, req.additional).await {
+        Ok(plan) => Ok(HttpResponse::Ok().json(ApiResponse::success(plan))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao ampliar amostra: {}", e))
+        )),
+    }
+}
+
+/// Emitir relatório estatístico da auditoria
+async fn report_rla(
+    principal: Principal,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    service: web::Data<RlaService>,
+) -> Result<HttpResponse> {
+    principal.require_for(Permission::ManageAudits, &path.0.to_string())?;
+    let (_, plan_id) = path.into_inner();
+    match service.report(plan_id).await {
+        Ok(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao gerar relatório da auditoria: {}", e))
+        )),
+    }
+}
@@ -0,0 +1,200 @@
+//! APIs da conferência de elegibilidade em lote
+//!
+//! Os registros vão no corpo em NDJSON e os resultados voltam no mesmo
+//! formato, linha a linha, conforme são conferidos.
+
+use actix_web::{web, HttpResponse, Result};
+use futures::stream::{LocalBoxStream, StreamExt};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::auth::rbac::{Permission, Principal};
+use crate::models::ApiResponse;
+use crate::services::eligibility::EligibilityService;
+
+/// Tipo de conteúdo do NDJSON
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Maior linha aceita no corpo
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+/// Abertura de lote
+#[derive(Debug, Deserialize)]
+pub struct CreateBatchRequest {
+    pub election_id: Uuid,
+    pub zone: String,
+}
+
+/// Linha do primeiro registro do envio; sem ela, continua de onde o lote parou
+#[derive(Debug, Deserialize)]
+pub struct RecordsQuery {
+    pub offset: Option<u64>,
+}
+
+/// Configurar rotas da conferência de elegibilidade
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/batches", web::post().to(create_batch))
+        .route("/batches/{batch_id}", web::get().to(get_batch))
+        .route("/batches/{batch_id}/records", web::post().to(check_records))
+        .route("/batches/{batch_id}/complete", web::post().to(complete_batch))
+        .route("/elections/{election_id}/zones/{zone}/report", web::get().to(get_zone_report));
+}
+
+/// Abrir lote de conferência para a zona
+async fn create_batch(
+    principal: Principal,
+    eligibility: web::Data<EligibilityService>,
+    req: web::Json<CreateBatchRequest>,
+) -> Result<HttpResponse> {
+    let operator = principal.require_for(Permission::TransitionElections, &req.election_id.to_string())?;
+    let batch = eligibility.create_batch(req.election_id, &req.zone, operator).await;
+    Ok(HttpResponse::Created().json(ApiResponse::success(batch)))
+}
+
+/// Situação do lote e linha de retomada
+async fn get_batch(
+    principal: Principal,
+    eligibility: web::Data<EligibilityService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    match eligibility.batch(path.into_inner()).await {
+        Some(batch) => {
+            principal.require_for(Permission::TransitionElections, &batch.election_id.to_string())?;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(batch)))
+        }
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Lote de conferência não encontrado".to_string())
+        )),
+    }
+}
+
+/// Conferir registros em NDJSON, devolvendo um resultado por linha
+async fn check_records(
+    principal: Principal,
+    eligibility: web::Data<EligibilityService>,
+    path: web::Path<Uuid>,
+    query: web::Query<RecordsQuery>,
+    payload: web::Payload,
+) -> Result<HttpResponse> {
+    let batch_id = path.into_inner();
+    let Some(batch) = eligibility.batch(batch_id).await else {
+        return Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Lote de conferência não encontrado".to_string())
+        ));
+    };
+    principal.require_for(Permission::TransitionElections, &batch.election_id.to_string())?;
+
+    let offset = query.offset.unwrap_or(batch.next_seq);
+    if let Err(e) = eligibility.ensure_resumable(batch_id, offset).await {
+        return Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao retomar conferência: {}", e))
+        ));
+    }
+
+    let results = futures::stream::unfold(
+        Some((ndjson_lines(payload), offset, eligibility.into_inner())),
+        move |state| async move {
+            let (mut lines, mut seq, service) = state?;
+            loop {
+                let line = match lines.next().await {
+                    Some(Ok(line)) => line,
+                    Some(Err(e)) => return Some((error_line(seq, &e), None)),
+                    None => return None,
+                };
+                let current = seq;
+                seq += 1;
+                match service.check_line(batch_id, current, &line).await {
+                    Ok(Some(result)) => {
+                        let mut bytes = serde_json::to_vec(&result).unwrap_or_default();
+                        bytes.push(b'\n');
+                        return Some((Ok(web::Bytes::from(bytes)), Some((lines, seq, service))));
+                    }
+                    Ok(None) => continue,
+                    Err(e) => return Some((error_line(current, &e.to_string()), None)),
+                }
+            }
+        },
+    );
+
+    Ok(HttpResponse::Ok().content_type(NDJSON_CONTENT_TYPE).streaming(results))
+}
+
+/// Concluir o lote e obter o relatório da zona
+async fn complete_batch(
+    principal: Principal,
+    eligibility: web::Data<EligibilityService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let batch_id = path.into_inner();
+    let Some(batch) = eligibility.batch(batch_id).await else {
+        return Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Lote de conferência não encontrado".to_string())
+        ));
+    };
+    principal.require_for(Permission::TransitionElections, &batch.election_id.to_string())?;
+
+    match eligibility.complete(batch_id).await {
+        Ok(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao concluir conferência: {}", e))
+        )),
+    }
+}
+
+/// Relatório de elegibilidade mais recente da zona
+async fn get_zone_report(
+    principal: Principal,
+    eligibility: web::Data<EligibilityService>,
+    path: web::Path<(Uuid, String)>,
+) -> Result<HttpResponse> {
+    let (election_id, zone) = path.into_inner();
+    principal.require_for(Permission::TransitionElections, &election_id.to_string())?;
+
+    match eligibility.zone_report(election_id, &zone).await {
+        Some(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Zona sem conferência de elegibilidade concluída".to_string())
+        )),
+    }
+}
+
+/// Linha final do NDJSON quando o envio é interrompido; indica onde retomar
+fn error_line(seq: u64, error: &str) -> Result<web::Bytes, actix_web::Error> {
+    let mut bytes = serde_json::to_vec(&serde_json::json!({
+        "seq": seq,
+        "error": error,
+        "resume_at": seq,
+    })).unwrap_or_default();
+    bytes.push(b'\n');
+    Ok(web::Bytes::from(bytes))
+}
+
+/// Linhas não vazias do corpo, sem esperar o corpo inteiro
+fn ndjson_lines(payload: web::Payload) -> LocalBoxStream<'static, std::result::Result<Vec<u8>, String>> {
+    futures::stream::unfold(Some((payload, Vec::new())), |state| async move {
+        let (mut payload, mut buffer) = state?;
+        loop {
+            if let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = line.trim_ascii().to_vec();
+                if line.is_empty() {
+                    continue;
+                }
+                return Some((Ok(line), Some((payload, buffer))));
+            }
+            if buffer.len() > MAX_LINE_BYTES {
+                return Some((Err(format!("Line longer than {} bytes", MAX_LINE_BYTES)), None));
+            }
+            match payload.next().await {
+                Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                Some(Err(e)) => return Some((Err(e.to_string()), None)),
+                None => {
+                    let line = buffer.trim_ascii().to_vec();
+                    return (!line.is_empty()).then(|| (Ok(line), None));
+                }
+            }
+        }
+    })
+    .boxed_local()
+}
+
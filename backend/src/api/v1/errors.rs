@@ -0,0 +1,57 @@
+//! Catálogo de mensagens de erro e pacotes de idioma para as urnas
+
+use actix_web::{http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE}, web, HttpRequest, HttpResponse, Result};
+use crate::i18n::LanguagePacks;
+use crate::models::ApiResponse;
+
+/// Configurar rotas do catálogo de erros
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/catalog", web::get().to(get_catalog))
+        .route("/languages", web::get().to(list_languages))
+        .route("/languages/{locale}", web::get().to(get_language_pack));
+}
+
+/// Obter catálogo de mensagens ao eleitor no idioma do `Accept-Language`
+///
+/// A versão é enviada como ETag para que a urna só baixe alterações.
+async fn get_catalog(
+    req: HttpRequest,
+    packs: web::Data<LanguagePacks>,
+) -> Result<HttpResponse> {
+    let accept_language = req.headers().get(ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok());
+    let pack = packs.negotiate(accept_language);
+    let catalog = &pack.errors;
+    let etag = format!("\"{}\"", catalog.version);
+    let unchanged = req.headers()
+        .get("If-None-Match")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value == etag);
+
+    if unchanged {
+        return Ok(HttpResponse::NotModified().insert_header(("ETag", etag)).finish());
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("ETag", etag))
+        .insert_header((CONTENT_LANGUAGE, pack.locale.clone()))
+        .json(catalog))
+}
+
+/// Idiomas disponíveis, com os textos que faltam em cada pacote
+async fn list_languages(packs: web::Data<LanguagePacks>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(packs.summaries())))
+}
+
+/// Pacote completo de um idioma
+async fn get_language_pack(
+    packs: web::Data<LanguagePacks>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    match packs.get(&path) {
+        Some(pack) => Ok(HttpResponse::Ok().json(ApiResponse::success(pack))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Idioma não disponível: {}", path))
+        )),
+    }
+}
@@ -0,0 +1,149 @@
+//! APIs da cerimônia de chaves da eleição
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::auth::rbac::{Permission, Principal};
+use crate::errors::FortisError;
+use crate::models::ApiResponse;
+use crate::services::key_ceremony::{KeyCeremonyService, TrusteeShare};
+
+/// Abertura de cerimônia
+#[derive(Debug, Deserialize)]
+pub struct OpenCeremonyRequest {
+    pub election_id: Uuid,
+    pub trustees: Vec<String>,
+    pub threshold: usize,
+}
+
+/// Configurar rotas da cerimônia de chaves
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("", web::post().to(open_ceremony))
+        .route("/{ceremony_id}", web::get().to(get_ceremony))
+        .route("/{ceremony_id}/share", web::post().to(deliver_share))
+        .route("/{ceremony_id}/acknowledgments", web::post().to(acknowledge_share))
+        .route("/{ceremony_id}/shares", web::post().to(submit_share))
+        .route("/{ceremony_id}/reconstruct", web::post().to(reconstruct));
+}
+
+/// Custodiante autenticado
+fn trustee(principal: &Principal) -> std::result::Result<&str, FortisError> {
+    principal.subject.as_deref().ok_or(FortisError::AuthenticationRequired)
+}
+
+/// Gerar e dividir a chave da eleição
+async fn open_ceremony(
+    principal: Principal,
+    ceremonies: web::Data<KeyCeremonyService>,
+    req: web::Json<OpenCeremonyRequest>,
+) -> Result<HttpResponse> {
+    let operator = principal.require_for(Permission::ManageElections, &req.election_id.to_string())?;
+    let req = req.into_inner();
+
+    match ceremonies.open(req.election_id, req.trustees, req.threshold, operator).await {
+        Ok(ceremony) => Ok(HttpResponse::Created().json(ApiResponse::success(ceremony))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao abrir cerimônia: {}", e))
+        )),
+    }
+}
+
+/// Obter compromissos e trilha da cerimônia
+async fn get_ceremony(
+    ceremonies: web::Data<KeyCeremonyService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    match ceremonies.get(path.into_inner()).await {
+        Some(ceremony) => Ok(HttpResponse::Ok().json(ApiResponse::success(ceremony))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Cerimônia não encontrada".to_string())
+        )),
+    }
+}
+
+/// Retirar a própria parte (uma única vez)
+async fn deliver_share(
+    principal: Principal,
+    ceremonies: web::Data<KeyCeremonyService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let trustee_id = trustee(&principal)?;
+
+    match ceremonies.deliver_share(path.into_inner(), trustee_id).await {
+        Ok(share) => Ok(HttpResponse::Ok().json(ApiResponse::success(share))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao entregar parte: {}", e))
+        )),
+    }
+}
+
+/// Conferir a parte recebida contra os compromissos
+async fn acknowledge_share(
+    principal: Principal,
+    ceremonies: web::Data<KeyCeremonyService>,
+    path: web::Path<Uuid>,
+    req: web::Json<TrusteeShare>,
+) -> Result<HttpResponse> {
+    let trustee_id = trustee(&principal)?;
+    if req.ceremony_id != path.into_inner() {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error("Parte de outra cerimônia".to_string())
+        ));
+    }
+
+    match ceremonies.acknowledge_share(&req, trustee_id).await {
+        Ok(valid) => Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "valid": valid,
+        })))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao conferir parte: {}", e))
+        )),
+    }
+}
+
+/// Apresentar a parte para reconstrução
+async fn submit_share(
+    principal: Principal,
+    ceremonies: web::Data<KeyCeremonyService>,
+    path: web::Path<Uuid>,
+    req: web::Json<TrusteeShare>,
+) -> Result<HttpResponse> {
+    let trustee_id = trustee(&principal)?;
+    if req.ceremony_id != path.into_inner() {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error("Parte de outra cerimônia".to_string())
+        ));
+    }
+
+    match ceremonies.submit_share(req.into_inner(), trustee_id).await {
+        Ok(submitted) => Ok(HttpResponse::Accepted().json(ApiResponse::success(serde_json::json!({
+            "submitted_shares": submitted,
+        })))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Parte rejeitada: {}", e))
+        )),
+    }
+}
+
+/// Reconstruir a chave após o quórum; a chave não sai do servidor
+async fn reconstruct(
+    principal: Principal,
+    ceremonies: web::Data<KeyCeremonyService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let ceremony_id = path.into_inner();
+    let Some(ceremony) = ceremonies.get(ceremony_id).await else {
+        return Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Cerimônia não encontrada".to_string())
+        ));
+    };
+    let operator = principal.require_for(Permission::ManageElections, &ceremony.election_id.to_string())?;
+
+    match ceremonies.reconstruct(ceremony_id, operator).await {
+        Ok(ceremony) => Ok(HttpResponse::Ok().json(ApiResponse::success(ceremony))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao reconstruir chave: {}", e))
+        )),
+    }
+}
@@ -0,0 +1,95 @@
+//! APIs da mixnet de anonimização das cédulas
+
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError, Result};
+use uuid::Uuid;
+use crate::auth::rbac::{Permission, Principal};
+use crate::errors::FortisError;
+use crate::middleware::device_identity::connection_device;
+use crate::models::ApiResponse;
+use crate::services::mixnet::{Ciphertext, MixnetService};
+
+/// Configurar rotas da mixnet
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/public-key", web::get().to(get_public_key))
+        .route("/{election_id}/ballots", web::post().to(submit_ballots))
+        .route("/{election_id}", web::get().to(get_transcript))
+        .route("/{election_id}/run", web::post().to(run_mix))
+        .route("/{election_id}/verify", web::get().to(verify_mix));
+}
+
+/// Obter chave pública de mistura
+async fn get_public_key(
+    mixnet: web::Data<MixnetService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(mixnet.public_key())))
+}
+
+/// Enviar cédulas cifradas para mistura; só urnas com certificado credenciado
+async fn submit_ballots(
+    http_req: HttpRequest,
+    mixnet: web::Data<MixnetService>,
+    path: web::Path<Uuid>,
+    req: web::Json<Vec<Ciphertext>>,
+) -> Result<HttpResponse> {
+    let device = match connection_device(&http_req).await {
+        Ok(device) => device,
+        Err(e) => {
+            log::warn!("Mixnet ballots rejected without urna identity: {}", e);
+            return Ok(FortisError::UrnaNotAuthorized.error_response());
+        }
+    };
+    let election_id = path.into_inner();
+    let ballots = req.into_inner();
+    log::info!("Urna {} submitted {} ballots for mixing in election {}", device.urna_id, ballots.len(), election_id);
+
+    match mixnet.submit_ballots(election_id, ballots).await {
+        Ok(total) => Ok(HttpResponse::Accepted().json(ApiResponse::success(serde_json::json!({
+            "total_ballots": total,
+        })))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Cédulas rejeitadas: {}", e))
+        )),
+    }
+}
+
+/// Obter transcrição da mistura com as provas de cada estágio
+async fn get_transcript(
+    mixnet: web::Data<MixnetService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    match mixnet.get_batch(path.into_inner()).await {
+        Some(batch) => Ok(HttpResponse::Ok().json(ApiResponse::success(batch))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Lote de cédulas não encontrado".to_string())
+        )),
+    }
+}
+
+/// Executar os estágios de mistura
+async fn run_mix(
+    principal: Principal,
+    mixnet: web::Data<MixnetService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    principal.require_for(Permission::ManageElections, &election_id.to_string())?;
+    match mixnet.run(election_id).await {
+        Ok(batch) => Ok(HttpResponse::Ok().json(ApiResponse::success(batch))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Falha na mistura: {}", e))
+        )),
+    }
+}
+
+/// Verificar provas de embaralhamento
+async fn verify_mix(
+    mixnet: web::Data<MixnetService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let result = mixnet.verify(path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "valid": result.is_ok(),
+        "error": result.err().map(|e| e.to_string()),
+    }))))
+}
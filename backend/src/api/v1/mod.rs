@@ -10,6 +10,22 @@ pub mod nodes;
 pub mod zkp;
 pub mod tse;
 pub mod urnas;
+pub mod consent;
+pub mod analytics;
+pub mod security;
+pub mod errors;
+pub mod mixnet;
+pub mod key_ceremonies;
+pub mod beacon;
+pub mod support;
+pub mod rbac;
+pub mod transmission;
+pub mod eligibility;
+pub mod results;
+pub mod regions;
+pub mod dual_control;
+pub mod election_templates;
+pub mod dashboards;
 
 /// Configurar rotas da API v1
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -44,6 +60,71 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         )
         .service(
             web::scope("/urnas")
+                .wrap(crate::middleware::device_identity::DeviceIdentityGuard)
                 .configure(urnas::configure)
+        )
+        .service(
+            web::scope("/consent")
+                .configure(consent::configure)
+        )
+        .service(
+            web::scope("/analytics")
+                .configure(analytics::configure)
+        )
+        .service(
+            web::scope("/security")
+                .configure(security::configure)
+        )
+        .service(
+            web::scope("/errors")
+                .configure(errors::configure)
+        )
+        .service(
+            web::scope("/mixnet")
+                .configure(mixnet::configure)
+        )
+        .service(
+            web::scope("/key-ceremonies")
+                .configure(key_ceremonies::configure)
+        )
+        .service(
+            web::scope("/beacon")
+                .configure(beacon::configure)
+        )
+        .service(
+            web::scope("/support")
+                .configure(support::configure)
+        )
+        .service(
+            web::scope("/rbac")
+                .configure(rbac::configure)
+        )
+        .service(
+            web::scope("/transmission")
+                .configure(transmission::configure)
+        )
+        .service(
+            web::scope("/eligibility")
+                .configure(eligibility::configure)
+        )
+        .service(
+            web::scope("/results")
+                .configure(results::configure)
+        )
+        .service(
+            web::scope("/regions")
+                .configure(regions::configure)
+        )
+        .service(
+            web::scope("/dual-control")
+                .configure(dual_control::configure)
+        )
+        .service(
+            web::scope("/election-templates")
+                .configure(election_templates::configure)
+        )
+        .service(
+            web::scope("/audit-dashboards")
+                .configure(dashboards::configure)
         );
 }
@@ -0,0 +1,100 @@
+//! APIs de papéis e atribuições do RBAC
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use crate::auth::rbac::{self, Permission, Principal, RbacService, Role, RoleGrant};
+use crate::models::ApiResponse;
+use crate::services::regions::RegionScope;
+
+/// Atribuição ou revogação de papel
+#[derive(Debug, Deserialize)]
+pub struct RoleAssignmentRequest {
+    pub subject: String,
+    pub role: Role,
+    pub election_id: Option<String>,
+    /// Recorte regional (`UF[/município[/zona]]`), para papéis de leitura por região
+    #[serde(default)]
+    pub region: Option<RegionScope>,
+}
+
+impl RoleAssignmentRequest {
+    fn grant(&self) -> RoleGrant {
+        RoleGrant {
+            role: self.role,
+            election_id: self.election_id.clone(),
+            region: self.region.clone(),
+        }
+    }
+}
+
+/// Configurar rotas do RBAC
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/roles", web::get().to(list_roles))
+        .route("/me", web::get().to(get_own_roles))
+        .route("/assignments", web::get().to(list_assignments))
+        .route("/assignments", web::post().to(assign_role))
+        .route("/assignments/revoke", web::post().to(revoke_role));
+}
+
+/// Papéis e suas permissões
+async fn list_roles() -> Result<HttpResponse> {
+    let roles: Vec<serde_json::Value> = rbac::role_matrix().into_iter()
+        .map(|(role, permissions)| serde_json::json!({ "role": role, "permissions": permissions }))
+        .collect();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(roles)))
+}
+
+/// Papéis do sujeito autenticado
+async fn get_own_roles(principal: Principal) -> Result<HttpResponse> {
+    match principal.subject {
+        Some(subject) => Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "subject": subject,
+            "grants": principal.grants,
+        })))),
+        None => Ok(HttpResponse::Unauthorized().json(
+            ApiResponse::<()>::error("Autenticação necessária".to_string())
+        )),
+    }
+}
+
+/// Listar atribuições
+async fn list_assignments(
+    principal: Principal,
+    rbac: web::Data<RbacService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageRoles)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(rbac.list().await)))
+}
+
+/// Atribuir papel a um sujeito
+async fn assign_role(
+    principal: Principal,
+    req: web::Json<RoleAssignmentRequest>,
+    rbac: web::Data<RbacService>,
+) -> Result<HttpResponse> {
+    let admin = principal.require(Permission::ManageRoles)?;
+
+    match rbac.assign(&req.subject, req.grant(), admin).await {
+        Ok(assignment) => Ok(HttpResponse::Created().json(ApiResponse::success(assignment))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao atribuir papel: {}", e))
+        )),
+    }
+}
+
+/// Revogar papel de um sujeito
+async fn revoke_role(
+    principal: Principal,
+    req: web::Json<RoleAssignmentRequest>,
+    rbac: web::Data<RbacService>,
+) -> Result<HttpResponse> {
+    let admin = principal.require(Permission::ManageRoles)?;
+
+    match rbac.revoke(&req.subject, &req.grant(), admin).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Papel revogado".to_string()))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao revogar papel: {}", e))
+        )),
+    }
+}
@@ -0,0 +1,231 @@
+//! APIs do cadastro de UFs, municípios, zonas e seções
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use crate::auth::rbac::{Permission, Principal};
+use crate::models::ApiResponse;
+use crate::services::regions::{RegionScope, RegionService, SectionRef};
+
+/// Recorte da consulta (`SP`, `SP/71072`, `SP/71072/0001`)
+#[derive(Debug, Deserialize)]
+pub struct ScopeQuery {
+    pub scope: Option<String>,
+}
+
+/// Cadastro de UF
+#[derive(Debug, Deserialize)]
+pub struct CreateStateRequest {
+    pub code: String,
+    pub name: String,
+}
+
+/// Cadastro de município
+#[derive(Debug, Deserialize)]
+pub struct CreateMunicipalityRequest {
+    pub state: String,
+    pub code: String,
+    pub name: String,
+}
+
+/// Cadastro de zona
+#[derive(Debug, Deserialize)]
+pub struct CreateZoneRequest {
+    pub state: String,
+    pub municipality_code: String,
+    pub number: String,
+    pub name: Option<String>,
+}
+
+/// Cadastro de seção
+#[derive(Debug, Deserialize)]
+pub struct CreateSectionRequest {
+    #[serde(flatten)]
+    pub section: SectionRef,
+    pub polling_place: Option<String>,
+    #[serde(default)]
+    pub registered_voters: u64,
+}
+
+/// Designação de urna
+#[derive(Debug, Deserialize)]
+pub struct AssignUrnaRequest {
+    pub urna_id: String,
+}
+
+/// Configurar rotas do cadastro de regiões
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("", web::get().to(list_regions))
+        .route("/states", web::post().to(create_state))
+        .route("/municipalities", web::post().to(create_municipality))
+        .route("/zones", web::post().to(create_zone))
+        .route("/sections", web::post().to(create_section))
+        .route("/sections/{state}/{municipality}/{zone}/{section}", web::get().to(get_section))
+        .route("/sections/{state}/{municipality}/{zone}/{section}", web::delete().to(delete_section))
+        .route("/sections/{state}/{municipality}/{zone}/{section}/urna", web::put().to(assign_urna))
+        .route("/urnas/{urna_id}/section", web::get().to(get_urna_section))
+        .route("/urnas/{urna_id}/section", web::delete().to(unassign_urna));
+}
+
+fn bad_request(context: &str, e: impl std::fmt::Display) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!("{}: {}", context, e)))
+}
+
+fn section_path(path: (String, String, String, String)) -> std::result::Result<SectionRef, HttpResponse> {
+    let (state, municipality, zone, section) = path;
+    SectionRef::new(&state, &municipality, &zone, &section).map_err(|e| bad_request("Seção inválida", e))
+}
+
+/// Listar o cadastro dentro do recorte
+async fn list_regions(
+    regions: web::Data<RegionService>,
+    query: web::Query<ScopeQuery>,
+) -> Result<HttpResponse> {
+    let scope = match query.scope.as_deref().map(RegionScope::parse).transpose() {
+        Ok(scope) => scope,
+        Err(e) => return Ok(bad_request("Recorte inválido", e)),
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success(regions.listing(scope.as_ref()).await)))
+}
+
+/// Cadastrar UF
+async fn create_state(
+    principal: Principal,
+    regions: web::Data<RegionService>,
+    req: web::Json<CreateStateRequest>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match regions.add_state(&req.code, &req.name).await {
+        Ok(state) => Ok(HttpResponse::Created().json(ApiResponse::success(state))),
+        Err(e) => Ok(bad_request("Erro ao cadastrar UF", e)),
+    }
+}
+
+/// Cadastrar município da UF
+async fn create_municipality(
+    principal: Principal,
+    regions: web::Data<RegionService>,
+    req: web::Json<CreateMunicipalityRequest>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match regions.add_municipality(&req.state, &req.code, &req.name).await {
+        Ok(municipality) => Ok(HttpResponse::Created().json(ApiResponse::success(municipality))),
+        Err(e) => Ok(bad_request("Erro ao cadastrar município", e)),
+    }
+}
+
+/// Cadastrar zona do município
+async fn create_zone(
+    principal: Principal,
+    regions: web::Data<RegionService>,
+    req: web::Json<CreateZoneRequest>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let req = req.into_inner();
+    match regions.add_zone(&req.state, &req.municipality_code, &req.number, req.name).await {
+        Ok(zone) => Ok(HttpResponse::Created().json(ApiResponse::success(zone))),
+        Err(e) => Ok(bad_request("Erro ao cadastrar zona", e)),
+    }
+}
+
+/// Cadastrar ou atualizar seção da zona
+async fn create_section(
+    principal: Principal,
+    regions: web::Data<RegionService>,
+    req: web::Json<CreateSectionRequest>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let req = req.into_inner();
+    let section = match SectionRef::new(&req.section.state, &req.section.municipality_code, &req.section.zone, &req.section.section) {
+        Ok(section) => section,
+        Err(e) => return Ok(bad_request("Seção inválida", e)),
+    };
+    match regions.add_section(section, req.polling_place, req.registered_voters).await {
+        Ok(section) => Ok(HttpResponse::Created().json(ApiResponse::success(section))),
+        Err(e) => Ok(bad_request("Erro ao cadastrar seção", e)),
+    }
+}
+
+/// Obter seção e urna designada
+async fn get_section(
+    regions: web::Data<RegionService>,
+    path: web::Path<(String, String, String, String)>,
+) -> Result<HttpResponse> {
+    let section = match section_path(path.into_inner()) {
+        Ok(section) => section,
+        Err(response) => return Ok(response),
+    };
+    match regions.section(&section).await {
+        Some(section) => Ok(HttpResponse::Ok().json(ApiResponse::success(section))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Seção não cadastrada".to_string())
+        )),
+    }
+}
+
+/// Remover seção sem urna designada
+async fn delete_section(
+    principal: Principal,
+    regions: web::Data<RegionService>,
+    path: web::Path<(String, String, String, String)>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let section = match section_path(path.into_inner()) {
+        Ok(section) => section,
+        Err(response) => return Ok(response),
+    };
+    match regions.remove_section(&section).await {
+        Ok(()) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao remover seção: {}", e))
+        )),
+    }
+}
+
+/// Designar urna para a seção
+async fn assign_urna(
+    principal: Principal,
+    regions: web::Data<RegionService>,
+    path: web::Path<(String, String, String, String)>,
+    req: web::Json<AssignUrnaRequest>,
+) -> Result<HttpResponse> {
+    let operator = principal.require(Permission::ManageElections)?;
+    let section = match section_path(path.into_inner()) {
+        Ok(section) => section,
+        Err(response) => return Ok(response),
+    };
+    match regions.assign_urna(&section, &req.urna_id, operator).await {
+        Ok(section) => Ok(HttpResponse::Ok().json(ApiResponse::success(section))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao designar urna: {}", e))
+        )),
+    }
+}
+
+/// Seção em que a urna está designada
+async fn get_urna_section(
+    regions: web::Data<RegionService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    match regions.section_of_urna(&path.into_inner()).await {
+        Some(section) => Ok(HttpResponse::Ok().json(ApiResponse::success(section))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Urna sem seção designada".to_string())
+        )),
+    }
+}
+
+/// Liberar a urna da seção
+async fn unassign_urna(
+    principal: Principal,
+    regions: web::Data<RegionService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match regions.unassign_urna(&path.into_inner()).await {
+        Ok(section) => Ok(HttpResponse::Ok().json(ApiResponse::success(section))),
+        Err(e) => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Erro ao liberar urna: {}", e))
+        )),
+    }
+}
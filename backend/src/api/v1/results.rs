@@ -0,0 +1,96 @@
+//! APIs das rodadas de contagem e das diferenças entre elas
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::analytics::SectionResult;
+use crate::auth::rbac::{Permission, Principal};
+use crate::models::ApiResponse;
+use crate::services::results::{Justification, ResultsService, RoundKind};
+
+/// Encerramento de rodada
+#[derive(Debug, Deserialize)]
+pub struct CloseRoundRequest {
+    pub kind: RoundKind,
+    #[serde(default)]
+    pub justifications: Vec<Justification>,
+}
+
+/// Boletim corrigido e o registro que justifica a correção
+#[derive(Debug, Deserialize)]
+pub struct AmendBulletinRequest {
+    pub bulletin: SectionResult,
+    pub justification: Justification,
+}
+
+/// Rodadas comparadas (`round1`, `round2`, ...)
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub from: String,
+    pub to: String,
+}
+
+/// Configurar rotas das rodadas de contagem
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/{id}/rounds", web::get().to(list_rounds))
+        .route("/{id}/rounds", web::post().to(close_round))
+        .route("/{id}/amendments", web::post().to(amend_bulletin))
+        .route("/{id}/diff", web::get().to(get_diff));
+}
+
+/// Listar rodadas encerradas
+async fn list_rounds(
+    path: web::Path<Uuid>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(service.rounds(path.into_inner()).await)))
+}
+
+/// Encerrar rodada de contagem com os boletins atuais
+async fn close_round(
+    principal: Principal,
+    path: web::Path<Uuid>,
+    req: web::Json<CloseRoundRequest>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    let operator = principal.require_for(Permission::RecordResults, &path.to_string())?;
+    let req = req.into_inner();
+    match service.close_round(path.into_inner(), req.kind, req.justifications, operator).await {
+        Ok(round) => Ok(HttpResponse::Created().json(ApiResponse::success(round))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao encerrar rodada: {}", e))
+        )),
+    }
+}
+
+/// Substituir boletim de seção na recontagem ou conciliação
+async fn amend_bulletin(
+    principal: Principal,
+    path: web::Path<Uuid>,
+    req: web::Json<AmendBulletinRequest>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    let operator = principal.require_for(Permission::RecordResults, &path.to_string())?;
+    let req = req.into_inner();
+    match service.amend_bulletin(path.into_inner(), req.bulletin, req.justification, operator).await {
+        Ok(amendment) => Ok(HttpResponse::Created().json(ApiResponse::success(amendment))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao corrigir boletim: {}", e))
+        )),
+    }
+}
+
+/// Diferenças por candidato e por seção entre duas rodadas
+async fn get_diff(
+    path: web::Path<Uuid>,
+    query: web::Query<DiffQuery>,
+    service: web::Data<ResultsService>,
+) -> Result<HttpResponse> {
+    match service.diff(path.into_inner(), &query.from, &query.to).await {
+        Ok(diff) => Ok(HttpResponse::Ok().json(ApiResponse::success(diff))),
+        Err(e) => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Erro ao comparar rodadas: {}", e))
+        )),
+    }
+}
@@ -0,0 +1,248 @@
+//! APIs de divulgação de vulnerabilidades (bug bounty), incidentes e credenciais
+//!
+//! Só o envio de relatórios e a chave PGP são públicos; relatórios e
+//! incidentes exigem `ManageSecurity`, e o inventário de credenciais,
+//! `ManageElections`.
+
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use crate::auth::rbac::{Permission, Principal};
+use crate::middleware::client_puzzle::ClientPuzzle;
+use crate::models::ApiResponse;
+use crate::services::credentials::{Credential, CredentialWatchdog};
+use crate::services::incident::{IncidentService, IncidentStatus};
+use crate::tls::TransportPolicy;
+use crate::services::security_reports::{
+    SecurityReportService, SubmitReportRequest, TriageDecision, ReportStatus
+};
+
+/// Filtro por estado
+#[derive(Debug, Deserialize)]
+pub struct StatusQuery<S> {
+    pub status: Option<S>,
+}
+
+/// Janela de votação a verificar
+#[derive(Debug, Deserialize)]
+pub struct WindowQuery {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Dificuldade mínima do desafio das rotas públicas
+#[derive(Debug, Deserialize)]
+pub struct PuzzleFloorRequest {
+    pub floor_difficulty: Option<u8>,
+}
+
+/// Configurar rotas de segurança
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/pgp-key", web::get().to(get_pgp_key))
+        .route("/reports", web::post().to(submit_report))
+        .route("/reports", web::get().to(list_reports))
+        .route("/reports/{id}", web::get().to(get_report))
+        .route("/reports/{id}/triage", web::post().to(triage_report))
+        .route("/incidents", web::get().to(list_incidents))
+        .route("/incidents/{id}", web::get().to(get_incident))
+        .route("/credentials", web::get().to(list_credentials))
+        .route("/credentials", web::post().to(register_credential))
+        .route("/credentials/window-check", web::get().to(check_credential_window))
+        .route("/credentials/{id}", web::delete().to(remove_credential))
+        .route("/crypto-policy", web::get().to(get_crypto_policy))
+        .route("/client-puzzle", web::get().to(get_client_puzzle))
+        .route("/client-puzzle", web::put().to(set_client_puzzle_floor));
+}
+
+/// Obter chave PGP pública para cifrar relatórios
+async fn get_pgp_key(
+    service: web::Data<SecurityReportService>,
+) -> Result<HttpResponse> {
+    match service.pgp_public_key() {
+        Some(key) => Ok(HttpResponse::Ok()
+            .content_type("application/pgp-keys")
+            .body(key.to_string())),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Chave PGP não configurada".to_string())
+        )),
+    }
+}
+
+/// Receber relatório de vulnerabilidade
+async fn submit_report(
+    service: web::Data<SecurityReportService>,
+    req: web::Json<SubmitReportRequest>,
+) -> Result<HttpResponse> {
+    match service.submit_report(req.into_inner()).await {
+        Ok(acknowledgment) => Ok(HttpResponse::Accepted().json(ApiResponse::success(acknowledgment))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Relatório inválido: {}", e))
+        )),
+    }
+}
+
+/// Listar relatórios recebidos
+async fn list_reports(
+    principal: Principal,
+    service: web::Data<SecurityReportService>,
+    query: web::Query<StatusQuery<ReportStatus>>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageSecurity)?;
+    let reports = service.list_reports(query.status).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(reports)))
+}
+
+/// Obter relatório
+async fn get_report(
+    principal: Principal,
+    service: web::Data<SecurityReportService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageSecurity)?;
+    match service.get_report(&path).await {
+        Some(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Relatório não encontrado".to_string())
+        )),
+    }
+}
+
+/// Aplicar decisão de triagem
+async fn triage_report(
+    principal: Principal,
+    service: web::Data<SecurityReportService>,
+    path: web::Path<String>,
+    req: web::Json<TriageDecision>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageSecurity)?;
+    match service.triage(&path, req.into_inner()).await {
+        Ok(report) => {
+            log::info!("Security report {} triaged by {}: {:?}", report.id, actor, report.status);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(report)))
+        }
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Falha na triagem: {}", e))
+        )),
+    }
+}
+
+/// Listar incidentes de segurança
+async fn list_incidents(
+    principal: Principal,
+    incidents: web::Data<IncidentService>,
+    query: web::Query<StatusQuery<IncidentStatus>>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageSecurity)?;
+    let incidents = incidents.list_incidents(query.status).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(incidents)))
+}
+
+/// Obter incidente
+async fn get_incident(
+    principal: Principal,
+    incidents: web::Data<IncidentService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageSecurity)?;
+    match incidents.get_incident(&path).await {
+        Some(incident) => Ok(HttpResponse::Ok().json(ApiResponse::success(incident))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Incidente não encontrado".to_string())
+        )),
+    }
+}
+
+/// Inventário de credenciais com horizonte de expiração
+async fn list_credentials(
+    principal: Principal,
+    watchdog: web::Data<CredentialWatchdog>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(watchdog.inventory().await)))
+}
+
+/// Registrar ou renovar credencial
+async fn register_credential(
+    principal: Principal,
+    watchdog: web::Data<CredentialWatchdog>,
+    req: web::Json<Credential>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    let credential = req.into_inner();
+    match watchdog.register(credential.clone()).await {
+        Ok(()) => {
+            log::info!("Credential {} registered by {}", credential.id, actor);
+            Ok(HttpResponse::Created().json(ApiResponse::success(credential)))
+        }
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Credencial inválida: {}", e))
+        )),
+    }
+}
+
+/// Remover credencial do inventário
+async fn remove_credential(
+    principal: Principal,
+    watchdog: web::Data<CredentialWatchdog>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    match watchdog.remove(&path).await {
+        Some(credential) => {
+            log::warn!("Credential {} removed by {}", credential.id, actor);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(credential)))
+        }
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Credencial não encontrada".to_string())
+        )),
+    }
+}
+
+/// Credenciais críticas que expirariam durante a janela de votação
+async fn check_credential_window(
+    principal: Principal,
+    watchdog: web::Data<CredentialWatchdog>,
+    query: web::Query<WindowQuery>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let blocking = watchdog.blocking_credentials(query.start, query.end).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "can_open": blocking.is_empty(),
+        "blocking": blocking,
+    }))))
+}
+
+/// Política criptográfica de transporte ativa, para auditoria
+async fn get_crypto_policy(
+    transport: web::Data<TransportPolicy>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(transport.report())))
+}
+
+/// Carga e dificuldade do desafio das rotas públicas
+async fn get_client_puzzle(
+    principal: Principal,
+    puzzle: web::Data<ClientPuzzle>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ReadAudits)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(puzzle.status(Utc::now()))))
+}
+
+/// Fixar ou liberar a dificuldade mínima do desafio
+async fn set_client_puzzle_floor(
+    principal: Principal,
+    puzzle: web::Data<ClientPuzzle>,
+    req: web::Json<PuzzleFloorRequest>,
+) -> Result<HttpResponse> {
+    let subject = principal.require(Permission::ManageElections)?;
+    match puzzle.set_floor(req.floor_difficulty) {
+        Ok(()) => {
+            log::info!("{} set client puzzle floor to {:?}", subject, req.floor_difficulty);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(puzzle.status(Utc::now()))))
+        }
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Dificuldade inválida: {}", e))
+        )),
+    }
+}
@@ -0,0 +1,136 @@
+//! APIs de acesso de suporte com consentimento do administrador
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use crate::auth::rbac::{Permission, Principal};
+use crate::errors::FortisError;
+use crate::models::ApiResponse;
+use crate::services::support_access::{SupportAccessService, SupportScope};
+
+/// Pedido de acesso feito pelo suporte
+#[derive(Debug, Deserialize)]
+pub struct SupportAccessRequest {
+    pub admin_id: String,
+    pub scopes: Vec<SupportScope>,
+    pub reason: String,
+    pub duration_minutes: i64,
+}
+
+/// Configurar rotas de acesso de suporte
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/grants", web::post().to(request_access))
+        .route("/grants/{id}/approve", web::post().to(approve_grant))
+        .route("/grants/{id}/deny", web::post().to(deny_grant))
+        .route("/grants/{id}/revoke", web::post().to(revoke_grant))
+        .route("/grants/{id}/token", web::post().to(claim_token))
+        .route("/admins/{admin_id}/grants", web::get().to(list_admin_grants))
+        .route("/admins/{admin_id}/access-log", web::get().to(get_admin_access_log));
+}
+
+/// Engenheiro de suporte autenticado
+fn support_engineer(principal: &Principal) -> std::result::Result<&str, FortisError> {
+    principal.subject.as_deref().ok_or(FortisError::AuthenticationRequired)
+}
+
+/// Solicitar acesso à visão de um administrador
+async fn request_access(
+    principal: Principal,
+    req: web::Json<SupportAccessRequest>,
+    support: web::Data<SupportAccessService>,
+) -> Result<HttpResponse> {
+    let support_engineer = support_engineer(&principal)?;
+    let req = req.into_inner();
+
+    match support.request_access(support_engineer, &req.admin_id, req.scopes, &req.reason, req.duration_minutes).await {
+        Ok(grant) => Ok(HttpResponse::Created().json(ApiResponse::success(grant))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Pedido de acesso inválido: {}", e))
+        )),
+    }
+}
+
+/// Aprovar pedido de acesso
+async fn approve_grant(
+    principal: Principal,
+    path: web::Path<String>,
+    support: web::Data<SupportAccessService>,
+) -> Result<HttpResponse> {
+    let admin_id = principal.require(Permission::ManageSupportAccess)?;
+    match support.approve(&path.into_inner(), admin_id).await {
+        Ok(grant) => Ok(HttpResponse::Ok().json(ApiResponse::success(grant))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Não foi possível aprovar o acesso: {}", e))
+        )),
+    }
+}
+
+/// Recusar pedido de acesso
+async fn deny_grant(
+    principal: Principal,
+    path: web::Path<String>,
+    support: web::Data<SupportAccessService>,
+) -> Result<HttpResponse> {
+    let admin_id = principal.require(Permission::ManageSupportAccess)?;
+    match support.deny(&path.into_inner(), admin_id).await {
+        Ok(grant) => Ok(HttpResponse::Ok().json(ApiResponse::success(grant))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Não foi possível recusar o acesso: {}", e))
+        )),
+    }
+}
+
+/// Revogar acesso concedido
+async fn revoke_grant(
+    principal: Principal,
+    path: web::Path<String>,
+    support: web::Data<SupportAccessService>,
+) -> Result<HttpResponse> {
+    let admin_id = principal.require(Permission::ManageSupportAccess)?;
+    match support.revoke(&path.into_inner(), admin_id).await {
+        Ok(grant) => Ok(HttpResponse::Ok().json(ApiResponse::success(grant))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Não foi possível revogar o acesso: {}", e))
+        )),
+    }
+}
+
+/// Retirar o token de acesso aprovado (entregue uma única vez)
+async fn claim_token(
+    principal: Principal,
+    path: web::Path<String>,
+    support: web::Data<SupportAccessService>,
+) -> Result<HttpResponse> {
+    let support_engineer = support_engineer(&principal)?;
+    match support.claim_token(&path.into_inner(), support_engineer).await {
+        Ok(token) => Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "token": token,
+            "header": crate::middleware::support_access::SUPPORT_GRANT_HEADER,
+        })))),
+        Err(e) => Ok(HttpResponse::Forbidden().json(
+            ApiResponse::<()>::error(format!("Token indisponível: {}", e))
+        )),
+    }
+}
+
+/// Listar pedidos e concessões de um administrador
+async fn list_admin_grants(
+    principal: Principal,
+    path: web::Path<String>,
+    support: web::Data<SupportAccessService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageSupportAccess)?;
+    let grants = support.grants_for_admin(&path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(grants)))
+}
+
+/// Registro de usos das concessões do administrador
+async fn get_admin_access_log(
+    principal: Principal,
+    path: web::Path<String>,
+    support: web::Data<SupportAccessService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageSupportAccess)?;
+    let log = support.access_log_for_admin(&path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(log)))
+}
@@ -0,0 +1,107 @@
+//! APIs do fim de transmissão por zona
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::auth::rbac::{Permission, Principal};
+use crate::models::ApiResponse;
+use crate::services::transmission::{DeclarationSubmission, TransmissionService};
+
+/// Cadastro da autoridade zonal
+#[derive(Debug, Deserialize)]
+pub struct RegisterAuthorityRequest {
+    pub zone: String,
+    /// Chave pública Ed25519 em hex
+    pub public_key: String,
+}
+
+/// Configurar rotas do fim de transmissão
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/{election_id}/authorities", web::post().to(register_authority))
+        .route("/{election_id}/zones", web::get().to(list_zones))
+        .route("/{election_id}/zones/{zone}", web::get().to(get_zone))
+        .route("/{election_id}/zones/{zone}/declaration", web::post().to(declare))
+        .route("/{election_id}/zones/{zone}/declaration", web::get().to(get_declaration));
+}
+
+/// Cadastrar a chave da autoridade de uma zona
+async fn register_authority(
+    principal: Principal,
+    transmission: web::Data<TransmissionService>,
+    path: web::Path<Uuid>,
+    req: web::Json<RegisterAuthorityRequest>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    principal.require_for(Permission::ManageElections, &election_id.to_string())?;
+
+    match transmission.register_authority(election_id, &req.zone, &req.public_key).await {
+        Ok(()) => Ok(HttpResponse::Created().json(ApiResponse::success(serde_json::json!({
+            "zone": req.zone,
+        })))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao cadastrar autoridade zonal: {}", e))
+        )),
+    }
+}
+
+/// Situação da transmissão de todas as zonas
+async fn list_zones(
+    transmission: web::Data<TransmissionService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    match transmission.zones(path.into_inner()).await {
+        Ok(zones) => Ok(HttpResponse::Ok().json(ApiResponse::success(zones))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao obter transmissão das zonas: {}", e))
+        )),
+    }
+}
+
+/// Situação da zona e declaração a assinar, quando completa
+async fn get_zone(
+    transmission: web::Data<TransmissionService>,
+    path: web::Path<(Uuid, String)>,
+) -> Result<HttpResponse> {
+    let (election_id, zone) = path.into_inner();
+
+    match transmission.status(election_id, &zone).await {
+        Ok(status) => Ok(HttpResponse::Ok().json(ApiResponse::success(status))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao obter transmissão da zona: {}", e))
+        )),
+    }
+}
+
+/// Entregar a declaração assinada pela autoridade zonal
+async fn declare(
+    principal: Principal,
+    transmission: web::Data<TransmissionService>,
+    path: web::Path<(Uuid, String)>,
+    req: web::Json<DeclarationSubmission>,
+) -> Result<HttpResponse> {
+    let (election_id, zone) = path.into_inner();
+    let operator = principal.require_for(Permission::RecordResults, &election_id.to_string())?;
+
+    match transmission.declare(election_id, &zone, req.into_inner(), operator).await {
+        Ok(declaration) => Ok(HttpResponse::Created().json(ApiResponse::success(declaration))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao declarar fim de transmissão: {}", e))
+        )),
+    }
+}
+
+/// Obter a declaração aceita da zona
+async fn get_declaration(
+    transmission: web::Data<TransmissionService>,
+    path: web::Path<(Uuid, String)>,
+) -> Result<HttpResponse> {
+    let (election_id, zone) = path.into_inner();
+
+    match transmission.declaration(election_id, &zone).await {
+        Some(declaration) => Ok(HttpResponse::Ok().json(ApiResponse::success(declaration))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Zona sem declaração de fim de transmissão".to_string())
+        )),
+    }
+}
@@ -7,7 +7,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 
-use crate::services::tse::{GovBrService, VoterValidationService, DigitalCertificateService, ElectionSyncService};
+use crate::auth::rbac::{Permission, Principal};
+use crate::services::tse::{GovBrService, VoterValidationService, DigitalCertificateService, ElectionSyncService, BiometricDedupService};
+use crate::services::tse::biometric_dedup::MatchReviewStatus;
+use crate::services::tse::data_quality::{ImportKind, QuarantineStatus};
+use crate::services::candidate::CandidateService;
 use crate::config::Config;
 
 /// Configura rotas TSE
@@ -16,6 +20,7 @@ pub fn config_tse_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/tse")
             .route("/auth/gov-br/url", web::get().to(get_gov_br_auth_url))
             .route("/auth/gov-br/callback", web::post().to(gov_br_callback))
+            .route("/auth/gov-br/refresh", web::post().to(gov_br_refresh))
             .route("/auth/gov-br/user", web::get().to(get_gov_br_user))
             .route("/voter/validate/cpf/{cpf}", web::get().to(validate_voter_cpf))
             .route("/voter/validate/id/{voter_id}", web::get().to(validate_voter_id))
@@ -27,9 +32,18 @@ pub fn config_tse_routes(cfg: &mut web::ServiceConfig) {
             .route("/certificate/sign", web::post().to(sign_data))
             .route("/certificate/verify", web::post().to(verify_signature))
             .route("/elections/sync", web::post().to(sync_elections))
+            .route("/voter-roll/sync", web::post().to(sync_voter_roll))
+            .route("/voter-roll/runs", web::get().to(get_voter_roll_runs))
+            .route("/voter-roll/duplicates", web::get().to(list_duplicate_matches))
+            .route("/voter-roll/duplicates/{match_id}/review", web::post().to(review_duplicate_match))
+            .route("/quarantine", web::get().to(list_quarantined))
+            .route("/quarantine/{record_id}/correct", web::post().to(correct_quarantined))
+            .route("/quarantine/{record_id}/approve", web::post().to(approve_quarantined))
+            .route("/quarantine/{record_id}/reject", web::post().to(reject_quarantined))
             .route("/elections/active", web::get().to(get_active_elections))
             .route("/elections/{election_id}", web::get().to(get_election))
             .route("/elections/{election_id}/candidates", web::get().to(get_election_candidates))
+            .route("/elections/{election_id}/candidates/import", web::post().to(import_election_candidates))
             .route("/elections/{election_id}/zones", web::get().to(get_election_zones))
             .route("/elections/{election_id}/rules", web::get().to(get_election_rules))
             .route("/elections/{election_id}/stats", web::get().to(get_election_stats))
@@ -66,23 +80,14 @@ impl<T> ApiResponse<T> {
     }
 }
 
-/// Gera URL de autorização Gov.br
+/// Gera URL de autorização Gov.br (código de autorização com PKCE)
 async fn get_gov_br_auth_url(
-    config: web::Data<Config>,
-    query: web::Query<HashMap<String, String>>,
+    gov_br: web::Data<GovBrService>,
 ) -> ActixResult<HttpResponse> {
-    let default_state = "default".to_string();
-    let state = query.get("state").unwrap_or(&default_state);
-    
-    let gov_br_service = GovBrService::new(&config);
-    let auth_url = gov_br_service.get_authorization_url(state);
-    
-    let response = HashMap::from([
-        ("auth_url", auth_url),
-        ("state", state.clone()),
-    ]);
-    
-    Ok(HttpResponse::Ok().json(ApiResponse::success(response)))
+    match gov_br.begin_authorization().await {
+        Ok(request) => Ok(HttpResponse::Ok().json(ApiResponse::success(request))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))),
+    }
 }
 
 /// Callback de autorização Gov.br
@@ -93,20 +98,34 @@ pub struct GovBrCallbackRequest {
 }
 
 async fn gov_br_callback(
-    config: web::Data<Config>,
+    gov_br: web::Data<GovBrService>,
     req: web::Json<GovBrCallbackRequest>,
 ) -> ActixResult<HttpResponse> {
-    let gov_br_service = GovBrService::new(&config);
-    
-    match gov_br_service.exchange_code_for_token(&req.code).await {
-        Ok(token) => Ok(HttpResponse::Ok().json(ApiResponse::success(token))),
+    match gov_br.complete_authorization(&req.code, &req.state).await {
+        Ok(session) => Ok(HttpResponse::Ok().json(ApiResponse::success(session))),
         Err(e) => Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string()))),
     }
 }
 
+/// Renovação de token Gov.br
+#[derive(Debug, Deserialize)]
+pub struct GovBrRefreshRequest {
+    pub refresh_token: String,
+}
+
+async fn gov_br_refresh(
+    gov_br: web::Data<GovBrService>,
+    req: web::Json<GovBrRefreshRequest>,
+) -> ActixResult<HttpResponse> {
+    match gov_br.refresh_token(&req.refresh_token).await {
+        Ok(token) => Ok(HttpResponse::Ok().json(ApiResponse::success(token))),
+        Err(e) => Ok(HttpResponse::Unauthorized().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
 /// Obtém dados do usuário Gov.br
 async fn get_gov_br_user(
-    config: web::Data<Config>,
+    gov_br: web::Data<GovBrService>,
     query: web::Query<HashMap<String, String>>,
 ) -> ActixResult<HttpResponse> {
     let access_token = match query.get("access_token") {
@@ -114,9 +133,7 @@ async fn get_gov_br_user(
         None => return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Token de acesso necessário".to_string()))),
     };
     
-    let gov_br_service = GovBrService::new(&config);
-    
-    match gov_br_service.get_user_info(access_token).await {
+    match gov_br.get_user_info(access_token).await {
         Ok(user) => Ok(HttpResponse::Ok().json(ApiResponse::success(user))),
         Err(e) => Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string()))),
     }
@@ -323,6 +340,161 @@ async fn sync_elections(
     }
 }
 
+/// Sincroniza o cadastro eleitoral sob demanda (sob controle duplo)
+async fn sync_voter_roll(
+    principal: Principal,
+    sync_service: web::Data<ElectionSyncService>,
+) -> ActixResult<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    log::info!("Voter roll sync requested by {}", actor);
+    match sync_service.sync_voter_roll().await {
+        Ok(run) => Ok(HttpResponse::Ok().json(ApiResponse::success(run))),
+        Err(e) => Ok(HttpResponse::BadGateway().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// Lista as execuções da sincronização do cadastro eleitoral
+async fn get_voter_roll_runs(
+    sync_service: web::Data<ElectionSyncService>,
+) -> ActixResult<HttpResponse> {
+    let runs = sync_service.voter_roll_runs().await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(runs)))
+}
+
+/// Filtro das suspeitas de inscrição duplicada
+#[derive(Debug, Deserialize)]
+pub struct DuplicateMatchQuery {
+    pub status: Option<MatchReviewStatus>,
+}
+
+/// Decisão sobre a suspeita de inscrição duplicada
+#[derive(Debug, Deserialize)]
+pub struct ReviewDuplicateRequest {
+    pub decision: MatchReviewStatus,
+    pub notes: Option<String>,
+}
+
+/// Lista as suspeitas de inscrição duplicada da deduplicação biométrica
+async fn list_duplicate_matches(
+    principal: Principal,
+    dedup_service: web::Data<BiometricDedupService>,
+    query: web::Query<DuplicateMatchQuery>,
+) -> ActixResult<HttpResponse> {
+    principal.require(Permission::ReadAudits)?;
+    let matches = dedup_service.matches(query.status).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(matches)))
+}
+
+/// Confirma ou descarta a suspeita de inscrição duplicada
+async fn review_duplicate_match(
+    principal: Principal,
+    dedup_service: web::Data<BiometricDedupService>,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<ReviewDuplicateRequest>,
+) -> ActixResult<HttpResponse> {
+    let reviewer = principal.require(Permission::ManageElections)?;
+    let req = req.into_inner();
+    match dedup_service.review(path.into_inner(), req.decision, reviewer, req.notes).await {
+        Ok(reviewed) => Ok(HttpResponse::Ok().json(ApiResponse::success(reviewed))),
+        Err(e) => Ok(HttpResponse::Conflict().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// Filtro da fila de quarentena
+#[derive(Debug, Deserialize)]
+pub struct QuarantineQuery {
+    pub status: Option<QuarantineStatus>,
+    pub kind: Option<ImportKind>,
+}
+
+/// Versão corrigida do registro retido
+#[derive(Debug, Deserialize)]
+pub struct CorrectQuarantinedRequest {
+    pub record: serde_json::Value,
+}
+
+/// Decisão sobre o registro retido
+#[derive(Debug, Deserialize)]
+pub struct QuarantineDecisionRequest {
+    pub notes: Option<String>,
+}
+
+/// Eleição de destino dos candidatos importados
+#[derive(Debug, Deserialize)]
+pub struct ImportCandidatesRequest {
+    pub target_election_id: uuid::Uuid,
+}
+
+/// Lista os registros retidos pela checagem de qualidade das importações
+async fn list_quarantined(
+    principal: Principal,
+    sync_service: web::Data<ElectionSyncService>,
+    query: web::Query<QuarantineQuery>,
+) -> ActixResult<HttpResponse> {
+    principal.require(Permission::ReadAudits)?;
+    let records = sync_service.quarantined(query.status, query.kind).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(records)))
+}
+
+/// Corrige o registro retido e refaz a checagem
+async fn correct_quarantined(
+    principal: Principal,
+    sync_service: web::Data<ElectionSyncService>,
+    candidates: web::Data<CandidateService>,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<CorrectQuarantinedRequest>,
+) -> ActixResult<HttpResponse> {
+    let reviewer = principal.require(Permission::ManageElections)?;
+    match sync_service.correct_quarantined(path.into_inner(), req.into_inner().record, reviewer, &candidates).await {
+        Ok(record) => Ok(HttpResponse::Ok().json(ApiResponse::success(record))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// Importa o registro retido
+async fn approve_quarantined(
+    principal: Principal,
+    sync_service: web::Data<ElectionSyncService>,
+    candidates: web::Data<CandidateService>,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<QuarantineDecisionRequest>,
+) -> ActixResult<HttpResponse> {
+    let reviewer = principal.require(Permission::ManageElections)?;
+    match sync_service.approve_quarantined(path.into_inner(), reviewer, req.into_inner().notes, &candidates).await {
+        Ok(record) => Ok(HttpResponse::Ok().json(ApiResponse::success(record))),
+        Err(e) => Ok(HttpResponse::Conflict().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// Descarta o registro retido
+async fn reject_quarantined(
+    principal: Principal,
+    sync_service: web::Data<ElectionSyncService>,
+    path: web::Path<uuid::Uuid>,
+    req: web::Json<QuarantineDecisionRequest>,
+) -> ActixResult<HttpResponse> {
+    let reviewer = principal.require(Permission::ManageElections)?;
+    match sync_service.reject_quarantined(path.into_inner(), reviewer, req.into_inner().notes).await {
+        Ok(record) => Ok(HttpResponse::Ok().json(ApiResponse::success(record))),
+        Err(e) => Ok(HttpResponse::Conflict().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// Importa os candidatos da eleição do TSE, retendo os reprovados na quarentena
+async fn import_election_candidates(
+    principal: Principal,
+    sync_service: web::Data<ElectionSyncService>,
+    candidates: web::Data<CandidateService>,
+    path: web::Path<String>,
+    req: web::Json<ImportCandidatesRequest>,
+) -> ActixResult<HttpResponse> {
+    principal.require(Permission::ManageCandidates)?;
+    match sync_service.import_candidates(&path.into_inner(), req.target_election_id, &candidates).await {
+        Ok(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        Err(e) => Ok(HttpResponse::BadGateway().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
 /// Obtém eleições ativas
 async fn get_active_elections(
     config: web::Data<Config>,
@@ -1,15 +1,35 @@
 //! APIs para comunicação com urnas eletrônicas
 
-use actix_web::{web, HttpResponse, Result, HttpRequest};
+use actix_web::{web, HttpMessage, HttpResponse, Result, HttpRequest, ResponseError};
 use crate::models::{
     UrnaVoteRequest, UrnaVoteResponse, UrnaSyncRequest, UrnaSyncResponse,
     UrnaStatusRequest, UrnaStatusResponse, Urna, UrnaHealthCheck, UrnaStatus,
     PerformanceMetrics, VoteReceipt, VoteSyncStatus, ApiResponse
 };
-use crate::services::{urna::{UrnaAuthService, UrnaSyncService}, vote::VoteService};
+use crate::services::{urna::{UrnaAuthService, UrnaMonitoringService, UrnaSyncService}, vote::VoteService};
+use crate::services::urna::auth::DeviceIdentity;
+use crate::services::urna::contingency::{ContingencyService, SealedUrnaState};
+use crate::services::urna::decommission::{DecommissionService, SignedDecommissionReport};
+use crate::services::urna::diagnostics::{DiagnosticsService, FrequencyQuery};
+use crate::services::urna::fleet::{FleetQuery, FleetService, HistoryQuery};
+use crate::services::receipts::{self, ReceiptService};
+use crate::services::vote_batches::{SignedVoteBatch, VoteBatchService};
+use crate::services::vote_sync::{VoteSyncChunk, VoteSyncService, IDEMPOTENCY_KEY_HEADER};
+use crate::services::provisioning::{BundleRequest, ProvisioningService};
+use crate::services::regions::RegionService;
+use crate::services::urna::provisioning::{
+    EnrollUrnaRequest, ProvisioningAck, ProvisioningState, PushConfigurationRequest, RegisterUrnaRequest,
+    UrnaProvisioningService,
+};
+use crate::services::urna::security::{BaselineRequest, UrnaSecurityService};
+use crate::services::updates::{InstallReport, PublishUpdateRequest, UpdateComponent, UpdateService, UpdateVersion};
+use crate::auth::rbac::{Permission, Principal};
+use crate::errors::FortisError;
+use fortis_domain::{fault_code, AttestationReport, TamperEvent, UrnaHeartbeat, FAULT_CODES};
+use serde::Deserialize;
 use anyhow::Result as AnyResult;
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
 /// Configurar rotas de urnas
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -19,19 +39,164 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .route("/sync/{sync_id}", web::get().to(get_sync_status))
         .route("/status/{urna_id}", web::get().to(get_urna_status))
         .route("/health/{urna_id}", web::get().to(get_urna_health))
+        .route("/heartbeat", web::post().to(receive_heartbeat))
+        .route("/fleet/zones", web::get().to(get_fleet_zones))
+        .route("/fleet/urnas", web::get().to(get_fleet_urnas))
+        .route("/diagnostics/codes", web::get().to(list_fault_codes))
+        .route("/diagnostics/codes/{code}", web::get().to(get_fault_code))
+        .route("/diagnostics/frequency", web::get().to(get_fault_frequency))
+        .route("/endpoints", web::get().to(get_backend_reachability))
         .route("/register", web::post().to(register_urna))
+        .route("/updates", web::post().to(publish_update))
+        .route("/updates", web::get().to(list_updates))
+        .route("/updates/latest", web::get().to(get_latest_update))
+        .route("/updates/signing-key", web::get().to(get_update_signing_key))
+        .route("/{urna_id}/updates", web::post().to(report_update_installation))
+        .route("/{urna_id}/updates", web::get().to(list_update_installations))
+        .route("/attestation-baselines", web::post().to(register_attestation_baseline))
+        .route("/attestation-baselines", web::get().to(list_attestation_baselines))
+        .route("/attestation-baselines/{baseline_id}", web::delete().to(remove_attestation_baseline))
+        .route("/{urna_id}/attestation-key", web::post().to(register_attestation_key))
+        .route("/{urna_id}/attestation/challenge", web::post().to(issue_attestation_challenge))
+        .route("/{urna_id}/attestation", web::post().to(report_attestation))
+        .route("/{urna_id}/attestation", web::get().to(get_attestation))
+        .route("/{urna_id}/tamper", web::post().to(report_tamper_events))
+        .route("/{urna_id}/tamper", web::get().to(list_tamper_events))
+        .route("/{urna_id}/heartbeat", web::post().to(receive_urna_heartbeat))
+        .route("/{urna_id}/heartbeats", web::get().to(get_urna_heartbeats))
+        .route("/{urna_id}/diagnostics", web::get().to(get_urna_diagnostics))
+        .route("/provision", web::post().to(provision_urna))
+        .route("/provision", web::get().to(list_provisioning))
+        .route("/provision/{urna_id}", web::get().to(get_provisioning))
+        .route("/provision/{urna_id}/credentials", web::post().to(issue_provisioning_credentials))
+        .route("/provision/{urna_id}/enroll", web::post().to(enroll_provisioned_urna))
+        .route("/provision/{urna_id}/configuration", web::post().to(push_provisioning_configuration))
+        .route("/provision/{urna_id}/acknowledge", web::post().to(acknowledge_provisioning))
+        .route("/provision/{urna_id}/revoke", web::post().to(revoke_provisioning))
         .route("/{urna_id}/votes", web::get().to(get_urna_votes))
-        .route("/{urna_id}/audit", web::get().to(get_urna_audit_logs));
+        .route("/{urna_id}/audit", web::get().to(get_urna_audit_logs))
+        .route("/{urna_id}/public-key", web::post().to(register_urna_key))
+        .route("/{urna_id}/public-key/rotate", web::post().to(rotate_urna_key))
+        .route("/{urna_id}/certificates", web::post().to(enroll_device_certificate))
+        .route("/{urna_id}/certificates", web::get().to(list_device_certificates))
+        .route("/{urna_id}/certificates/revoke", web::post().to(revoke_device_certificates))
+        .route("/provisioning-key", web::get().to(get_provisioning_key))
+        .route("/{urna_id}/sealing-key", web::post().to(register_sealing_key))
+        .route("/{urna_id}/provisioning-bundles", web::post().to(create_provisioning_bundle))
+        .route("/{urna_id}/provisioning-bundles", web::get().to(list_provisioning_bundles))
+        .route("/{urna_id}/decommission", web::post().to(report_decommission))
+        .route("/{urna_id}/decommission", web::get().to(get_decommission))
+        .route("/decommissions", web::get().to(list_decommissions))
+        .service(
+            web::resource("/votes/batch")
+                .app_data(web::JsonConfig::default().limit(VOTE_SYNC_BODY_LIMIT))
+                .route(web::post().to(sync_vote_chunk)),
+        )
+        .route("/batches", web::post().to(submit_vote_batch))
+        .route("/batches/disputes/{tracking_code}", web::get().to(locate_batch_discrepancy))
+        .route("/{urna_id}/batches", web::get().to(get_urna_batches))
+        .route("/contingency/transfers", web::post().to(begin_state_transfer))
+        .route("/contingency/transfers", web::get().to(list_state_transfers))
+        .route("/contingency/transfers/{id}", web::get().to(get_state_transfer))
+        .route("/contingency/transfers/{id}/approve", web::post().to(approve_state_transfer))
+        .route("/contingency/transfers/{id}/import", web::post().to(import_state_transfer))
+        .route("/contingency/transfers/{id}/reconcile", web::post().to(reconcile_state_transfer));
+}
+
+/// Tamanho máximo (descomprimido) de uma parte de votos
+const VOTE_SYNC_BODY_LIMIT: usize = 32 * 1024 * 1024;
+
+/// Chave pública da urna
+#[derive(Debug, Deserialize)]
+pub struct RegisterUrnaKeyRequest {
+    pub public_key_pem: String,
+}
+
+/// Troca da chave pública da urna
+#[derive(Debug, Deserialize)]
+pub struct RotateUrnaKeyRequest {
+    pub public_key_pem: String,
+    pub reason: String,
+}
+
+/// Chave pública de selagem exportada do TPM da urna
+#[derive(Debug, Deserialize)]
+pub struct RegisterSealingKeyRequest {
+    pub public_key_pem: String,
+}
+
+/// Chave de atestação (AK) exportada do TPM da urna
+#[derive(Debug, Deserialize)]
+pub struct RegisterAttestationKeyRequest {
+    pub public_key_pem: String,
+}
+
+/// Certificado X.509 de dispositivo emitido no credenciamento
+#[derive(Debug, Deserialize)]
+pub struct EnrollDeviceCertificateRequest {
+    pub certificate_pem: String,
+}
+
+/// Revogação dos certificados da urna
+#[derive(Debug, Deserialize)]
+pub struct RevokeDeviceCertificateRequest {
+    pub reason: String,
+}
+
+/// Filtro da lista de provisionamento
+#[derive(Debug, Deserialize)]
+pub struct ProvisioningListQuery {
+    pub state: Option<ProvisioningState>,
+}
+
+/// Consulta da urna pela versão mais recente do seu canal
+#[derive(Debug, Deserialize)]
+pub struct LatestUpdateQuery {
+    pub component: UpdateComponent,
+    pub channel: String,
+    pub installed_version: Option<UpdateVersion>,
+}
+
+/// Filtro da lista de atualizações
+#[derive(Debug, Deserialize)]
+pub struct UpdateListQuery {
+    pub channel: Option<String>,
+}
+
+/// Revogação da urna no provisionamento
+#[derive(Debug, Deserialize)]
+pub struct RevokeProvisioningRequest {
+    pub reason: String,
 }
 
+/// Início de transferência para urna de contingência
+#[derive(Debug, Deserialize)]
+pub struct BeginTransferRequest {
+    pub sealed_state: SealedUrnaState,
+    pub replacement_urna_id: String,
+}
+
+/// Aprovação de mesário
 /// Registrar voto na urna
 async fn cast_urna_vote(
+    http_req: HttpRequest,
     req: web::Json<UrnaVoteRequest>,
     auth_service: web::Data<UrnaAuthService>,
     sync_service: web::Data<UrnaSyncService>,
     vote_service: web::Data<VoteService>,
+    contingency: web::Data<ContingencyService>,
+    receipts: web::Data<ReceiptService>,
+    regions: web::Data<RegionService>,
 ) -> Result<HttpResponse> {
     let vote_request = req.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &vote_request.urna_id.to_string()) {
+        return Ok(denied);
+    }
+    
+    // Urna substituída por contingência não pode mais registrar votos
+    if contingency.is_retired(&vote_request.urna_id.to_string()).await {
+        return Ok(FortisError::UrnaRetired.error_response());
+    }
     
     // Autenticar eleitor
     let auth_result = auth_service
@@ -48,9 +213,8 @@ async fn cast_urna_vote(
     let auth = match auth_result {
         Ok(auth) => auth,
         Err(e) => {
-            return Ok(HttpResponse::Unauthorized().json(
-                ApiResponse::<()>::error(format!("Falha na autenticação: {}", e))
-            ));
+            log::warn!("Falha na autenticação: {}", e);
+            return Ok(FortisError::BiometricNotRecognized.error_response());
         }
     };
 
@@ -61,9 +225,7 @@ async fn cast_urna_vote(
         .unwrap_or(false);
 
     if !is_eligible {
-        return Ok(HttpResponse::Forbidden().json(
-            ApiResponse::<()>::error("Eleitor não elegível para esta eleição".to_string())
-        ));
+        return Ok(FortisError::VoterNotEligible.error_response());
     }
 
     // Verificar se já votou
@@ -73,13 +235,15 @@ async fn cast_urna_vote(
         .unwrap_or(false);
 
     if already_voted {
-        return Ok(HttpResponse::Conflict().json(
-            ApiResponse::<()>::error("Eleitor já votou nesta eleição".to_string())
-        ));
+        return Ok(FortisError::VoterAlreadyVoted.error_response());
     }
 
+    // Seção da urna designada, registrada com o voto no log transparente
+    let section = regions.section_of_urna(&vote_request.urna_id.to_string()).await;
+
     // Processar voto
     let vote_id = Uuid::new_v4();
+    let ballot_hash = receipts::ballot_hash(vote_request.vote_proof.as_bytes());
     let vote_result = vote_service.cast_vote(&crate::models::VoteRequest {
         election_id: vote_request.election_id,
         candidate_id: vote_request.candidate_id,
@@ -88,6 +252,18 @@ async fn cast_urna_vote(
 
     match vote_result {
         Ok(_) => {
+            // Registrar no log transparente e gerar conteúdo do QR code
+            let payload = match receipts.record_vote(vote_id, vote_request.election_id, &ballot_hash, section.as_ref()).await {
+                Ok(payload) => payload,
+                Err(e) => {
+                    return Ok(HttpResponse::InternalServerError().json(
+                        ApiResponse::<()>::error(format!("Erro ao registrar comprovante: {}", e))
+                    ));
+                }
+            };
+
+            contingency.record_ballot(&vote_request.urna_id.to_string(), &vote_request.election_id.to_string()).await;
+
             // Criar comprovante
             let receipt = VoteReceipt {
                 vote_id,
@@ -95,7 +271,7 @@ async fn cast_urna_vote(
                 candidate_number: 123, // Em implementação real, buscaria do banco
                 candidate_name: "Candidato Exemplo".to_string(),
                 timestamp: Utc::now(),
-                qr_code: format!("QR_CODE_{}", vote_id),
+                qr_code: serde_json::to_string(&payload).unwrap_or_default(),
                 blockchain_hash: None,
             };
 
@@ -119,10 +295,14 @@ async fn cast_urna_vote(
 
 /// Iniciar sincronização da urna
 async fn start_urna_sync(
+    http_req: HttpRequest,
     req: web::Json<UrnaSyncRequest>,
     sync_service: web::Data<UrnaSyncService>,
 ) -> Result<HttpResponse> {
     let sync_request = req.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &sync_request.urna_id.to_string()) {
+        return Ok(denied);
+    }
     
     match sync_service.start_sync(sync_request).await {
         Ok(response) => Ok(HttpResponse::Ok().json(ApiResponse::success(response))),
@@ -230,10 +410,244 @@ async fn get_urna_health(
     Ok(HttpResponse::Ok().json(ApiResponse::success(health)))
 }
 
+/// Receber heartbeat da urna (rota anterior, sem o identificador no caminho)
+async fn receive_heartbeat(
+    http_req: HttpRequest,
+    req: web::Json<UrnaHeartbeat>,
+    monitoring: web::Data<UrnaMonitoringService>,
+    contingency: web::Data<ContingencyService>,
+    fleet: web::Data<FleetService>,
+    provisioning: web::Data<UrnaProvisioningService>,
+    diagnostics: web::Data<DiagnosticsService>,
+) -> Result<HttpResponse> {
+    let services = HeartbeatServices {
+        monitoring: &monitoring,
+        contingency: &contingency,
+        fleet: &fleet,
+        provisioning: &provisioning,
+        diagnostics: &diagnostics,
+    };
+    accept_heartbeat(&http_req, req.into_inner(), services).await
+}
+
+/// Receber heartbeat da urna: saúde, medições de cada backend e amostra da série da frota
+async fn receive_urna_heartbeat(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    req: web::Json<UrnaHeartbeat>,
+    monitoring: web::Data<UrnaMonitoringService>,
+    contingency: web::Data<ContingencyService>,
+    fleet: web::Data<FleetService>,
+    provisioning: web::Data<UrnaProvisioningService>,
+    diagnostics: web::Data<DiagnosticsService>,
+) -> Result<HttpResponse> {
+    if req.urna_id != path.into_inner() {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error("Heartbeat de outra urna".to_string())
+        ));
+    }
+    let services = HeartbeatServices {
+        monitoring: &monitoring,
+        contingency: &contingency,
+        fleet: &fleet,
+        provisioning: &provisioning,
+        diagnostics: &diagnostics,
+    };
+    accept_heartbeat(&http_req, req.into_inner(), services).await
+}
+
+/// Serviços alimentados pelo heartbeat
+struct HeartbeatServices<'a> {
+    monitoring: &'a UrnaMonitoringService,
+    contingency: &'a ContingencyService,
+    fleet: &'a FleetService,
+    provisioning: &'a UrnaProvisioningService,
+    diagnostics: &'a DiagnosticsService,
+}
+
+async fn accept_heartbeat(
+    http_req: &HttpRequest,
+    heartbeat: UrnaHeartbeat,
+    services: HeartbeatServices<'_>,
+) -> Result<HttpResponse> {
+    let HeartbeatServices { monitoring, contingency, fleet, provisioning, diagnostics } = services;
+    if let Some(denied) = ensure_device(http_req, &heartbeat.urna_id) {
+        return Ok(denied);
+    }
+    let Ok(urna_id) = Uuid::parse_str(&heartbeat.urna_id) else {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error("Identificador de urna inválido".to_string())
+        ));
+    };
+    let retired = contingency.is_retired(&heartbeat.urna_id).await;
+    let latency = heartbeat.endpoints.iter()
+        .find(|endpoint| endpoint.active)
+        .and_then(|endpoint| endpoint.latency_ms);
+
+    let health = UrnaHealthCheck {
+        urna_id,
+        timestamp: heartbeat.timestamp,
+        status: if retired {
+            UrnaStatus::Inactive
+        } else if heartbeat.errors.is_empty() {
+            UrnaStatus::Active
+        } else {
+            UrnaStatus::Error
+        },
+        battery_level: heartbeat.battery_level,
+        storage_usage: heartbeat.storage_usage,
+        network_connectivity: heartbeat.network_connectivity,
+        last_sync: heartbeat.last_sync,
+        errors: heartbeat.errors.clone(),
+        performance_metrics: PerformanceMetrics {
+            cpu_usage: 0.0,
+            memory_usage: 0.0,
+            disk_usage: 0.0,
+            network_latency: latency.map(|ms| ms.round() as u64),
+            response_time: 0,
+        },
+    };
+    if let Err(e) = monitoring.record_heartbeat(health).await {
+        return Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao registrar heartbeat: {}", e))
+        ));
+    }
+    monitoring.record_endpoints(urna_id, heartbeat.endpoints.clone(), heartbeat.failovers).await;
+    let entry = match fleet.record(heartbeat.clone()).await {
+        Ok(entry) => entry,
+        Err(e) => return Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao registrar heartbeat: {}", e))
+        )),
+    };
+    // Frequência dos códigos de falha por modelo da urna
+    let model = provisioning.get(&heartbeat.urna_id).await.and_then(|urna| urna.model);
+    if let Err(e) = diagnostics.record(&heartbeat, model, entry.section.as_ref()).await {
+        return Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao registrar falhas: {}", e))
+        ));
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "server_time": Utc::now(),
+        "retired": retired,
+        "status": entry.status,
+        "reasons": entry.reasons
+    }))))
+}
+
+/// Série temporal dos heartbeats da urna
+async fn get_urna_heartbeats(
+    principal: Principal,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+    fleet: web::Data<FleetService>,
+) -> Result<HttpResponse> {
+    let (_, scope) = principal.require_scope(Permission::ReadAudits)?;
+    let urna_id = path.into_inner();
+    let section = fleet.section(&urna_id).await;
+    if !scope.allows(None, section.map(|section| section.scope()).as_ref()) {
+        return Ok(HttpResponse::Forbidden().json(
+            ApiResponse::<()>::error("Urna fora do recorte de acesso".to_string())
+        ));
+    }
+
+    match fleet.history(&urna_id, &query).await {
+        Ok(samples) => Ok(HttpResponse::Ok().json(ApiResponse::success(samples))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao consultar heartbeats: {}", e))
+        )),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FleetZonesQuery {
+    pub state: Option<String>,
+}
+
+/// Urnas online, degradadas e offline por zona eleitoral, no recorte de quem consulta
+async fn get_fleet_zones(
+    principal: Principal,
+    query: web::Query<FleetZonesQuery>,
+    fleet: web::Data<FleetService>,
+) -> Result<HttpResponse> {
+    let (_, scope) = principal.require_scope(Permission::ReadAudits)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(fleet.zones(&scope, query.state.as_deref()).await)))
+}
+
+/// Situação de cada urna, filtrada por UF, zona e situação
+async fn get_fleet_urnas(
+    principal: Principal,
+    query: web::Query<FleetQuery>,
+    fleet: web::Data<FleetService>,
+) -> Result<HttpResponse> {
+    let (_, scope) = principal.require_scope(Permission::ReadAudits)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(fleet.urnas(&scope, &query).await)))
+}
+
+/// Catálogo de códigos de falha da urna, com o runbook de cada um
+async fn list_fault_codes(principal: Principal) -> Result<HttpResponse> {
+    principal.require_scope(Permission::ReadAudits)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(FAULT_CODES)))
+}
+
+/// Entrada do catálogo para um código
+async fn get_fault_code(principal: Principal, path: web::Path<String>) -> Result<HttpResponse> {
+    principal.require_scope(Permission::ReadAudits)?;
+    match fault_code(&path) {
+        Some(fault) => Ok(HttpResponse::Ok().json(ApiResponse::success(fault))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Código de falha desconhecido: {}", path))
+        )),
+    }
+}
+
+/// Frequência dos códigos de falha por modelo de urna, no recorte de quem consulta
+async fn get_fault_frequency(
+    principal: Principal,
+    query: web::Query<FrequencyQuery>,
+    diagnostics: web::Data<DiagnosticsService>,
+) -> Result<HttpResponse> {
+    let (_, scope) = principal.require_scope(Permission::ReadAudits)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(diagnostics.frequency(&scope, &query).await)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UrnaDiagnosticsQuery {
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Códigos de falha reportados pela urna, com o runbook de cada um
+async fn get_urna_diagnostics(
+    principal: Principal,
+    path: web::Path<String>,
+    query: web::Query<UrnaDiagnosticsQuery>,
+    fleet: web::Data<FleetService>,
+    diagnostics: web::Data<DiagnosticsService>,
+) -> Result<HttpResponse> {
+    let (_, scope) = principal.require_scope(Permission::ReadAudits)?;
+    let urna_id = path.into_inner();
+    let section = fleet.section(&urna_id).await;
+    if !scope.allows(None, section.map(|section| section.scope()).as_ref()) {
+        return Ok(HttpResponse::Forbidden().json(
+            ApiResponse::<()>::error("Urna fora do recorte de acesso".to_string())
+        ));
+    }
+    Ok(HttpResponse::Ok().json(ApiResponse::success(diagnostics.urna(&urna_id, query.since).await)))
+}
+
+/// Situação de cada backend segundo os heartbeats das urnas
+async fn get_backend_reachability(
+    monitoring: web::Data<UrnaMonitoringService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(monitoring.backend_reachability().await)))
+}
+
 /// Registrar nova urna
 async fn register_urna(
+    principal: Principal,
     req: web::Json<Urna>,
 ) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
     let urna = req.into_inner();
     
     // Em implementação real, salvaria no banco de dados
@@ -273,3 +687,722 @@ async fn get_urna_audit_logs(
 
     Ok(HttpResponse::Ok().json(ApiResponse::success(logs)))
 }
+
+/// Registrar chave pública da urna para verificação de estado lacrado
+///
+/// A chave precisa ser a do certificado de dispositivo ativo; uma chave já
+/// registrada só é trocada por `/public-key/rotate`, sob controle duplo.
+async fn register_urna_key(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<RegisterUrnaKeyRequest>,
+    contingency: web::Data<ContingencyService>,
+    auth_service: web::Data<UrnaAuthService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let urna_id = path.into_inner();
+    let device_key = match auth_service.active_device_key(&urna_id).await {
+        Ok(device_key) => device_key,
+        Err(e) => return Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Urna sem certificado de dispositivo ativo: {}", e))
+        )),
+    };
+
+    match contingency.register_urna_key(&urna_id, &req.public_key_pem, &device_key).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success(urna_id))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Chave pública inválida: {}", e))
+        )),
+    }
+}
+
+/// Trocar a chave pública registrada da urna
+async fn rotate_urna_key(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<RotateUrnaKeyRequest>,
+    contingency: web::Data<ContingencyService>,
+    auth_service: web::Data<UrnaAuthService>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    let urna_id = path.into_inner();
+    let device_key = match auth_service.active_device_key(&urna_id).await {
+        Ok(device_key) => device_key,
+        Err(e) => return Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Urna sem certificado de dispositivo ativo: {}", e))
+        )),
+    };
+
+    match contingency.rotate_urna_key(&urna_id, &req.public_key_pem, &device_key, &req.reason, actor).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success(urna_id))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Troca de chave rejeitada: {}", e))
+        )),
+    }
+}
+
+/// Receber lote de votos com sub-raiz Merkle assinada pela urna
+async fn submit_vote_batch(
+    http_req: HttpRequest,
+    req: web::Json<SignedVoteBatch>,
+    batches: web::Data<VoteBatchService>,
+    decommissions: web::Data<DecommissionService>,
+) -> Result<HttpResponse> {
+    if let Some(denied) = ensure_device(&http_req, &req.batch.urna_id) {
+        return Ok(denied);
+    }
+    if let Some(denied) = ensure_active(&decommissions, &req.batch.urna_id).await {
+        return Ok(denied);
+    }
+    match batches.submit(req.into_inner()).await {
+        Ok(stored) => Ok(HttpResponse::Created().json(ApiResponse::success(stored))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Lote de votos rejeitado: {}", e))
+        )),
+    }
+}
+
+/// Receber uma parte de votos pendentes da urna (corpo pode vir em zstd)
+async fn sync_vote_chunk(
+    http_req: HttpRequest,
+    req: web::Json<VoteSyncChunk>,
+    vote_sync: web::Data<VoteSyncService>,
+    decommissions: web::Data<DecommissionService>,
+) -> Result<HttpResponse> {
+    if let Some(denied) = ensure_device(&http_req, &req.urna_id) {
+        return Ok(denied);
+    }
+    if let Some(denied) = ensure_active(&decommissions, &req.urna_id).await {
+        return Ok(denied);
+    }
+    let Some(key) = http_req.headers().get(IDEMPOTENCY_KEY_HEADER).and_then(|v| v.to_str().ok()) else {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Cabeçalho {} obrigatório", IDEMPOTENCY_KEY_HEADER))
+        ));
+    };
+    match vote_sync.ingest(key, req.into_inner()).await {
+        Ok(ack) => Ok(HttpResponse::Ok().json(ApiResponse::success(ack))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Parte de votos rejeitada: {}", e))
+        )),
+    }
+}
+
+/// Listar lotes recebidos de uma urna
+async fn get_urna_batches(
+    path: web::Path<String>,
+    batches: web::Data<VoteBatchService>,
+) -> Result<HttpResponse> {
+    let stored = batches.batches_for_urna(&path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(stored)))
+}
+
+/// Indicar se a discrepância de um voto surgiu na urna ou no backend
+async fn locate_batch_discrepancy(
+    path: web::Path<String>,
+    batches: web::Data<VoteBatchService>,
+) -> Result<HttpResponse> {
+    match batches.locate_discrepancy(&path.into_inner()).await {
+        Ok(finding) => Ok(HttpResponse::Ok().json(ApiResponse::success(finding))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Código de rastreamento inválido: {}", e))
+        )),
+    }
+}
+
+/// Iniciar transferência de estado para urna de contingência; quem envia o
+/// estado lacrado é a urna substituta
+async fn begin_state_transfer(
+    http_req: HttpRequest,
+    req: web::Json<BeginTransferRequest>,
+    contingency: web::Data<ContingencyService>,
+) -> Result<HttpResponse> {
+    let req = req.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &req.replacement_urna_id) {
+        return Ok(denied);
+    }
+    
+    match contingency.begin_transfer(req.sealed_state, &req.replacement_urna_id).await {
+        Ok(transfer) => Ok(HttpResponse::Created().json(ApiResponse::success(transfer))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Estado lacrado rejeitado: {}", e))
+        )),
+    }
+}
+
+/// Listar transferências de contingência
+async fn list_state_transfers(
+    contingency: web::Data<ContingencyService>,
+) -> Result<HttpResponse> {
+    let transfers = contingency.list_transfers().await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(transfers)))
+}
+
+/// Obter transferência de contingência
+async fn get_state_transfer(
+    path: web::Path<String>,
+    contingency: web::Data<ContingencyService>,
+) -> Result<HttpResponse> {
+    match contingency.get_transfer(&path).await {
+        Some(transfer) => Ok(HttpResponse::Ok().json(ApiResponse::success(transfer))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Transferência não encontrada".to_string())
+        )),
+    }
+}
+
+/// Registrar aprovação de mesário; o mesário é o usuário autenticado
+async fn approve_state_transfer(
+    principal: Principal,
+    path: web::Path<String>,
+    contingency: web::Data<ContingencyService>,
+) -> Result<HttpResponse> {
+    let Some(transfer) = contingency.get_transfer(&path).await else {
+        return Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Transferência não encontrada".to_string())
+        ));
+    };
+    let mesario_id = principal.require_for(Permission::TransitionElections, &transfer.sealed_state.snapshot.election_id)?;
+    match contingency.approve_transfer(&transfer.id, mesario_id).await {
+        Ok(transfer) => Ok(HttpResponse::Ok().json(ApiResponse::success(transfer))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Aprovação rejeitada: {}", e))
+        )),
+    }
+}
+
+/// Importar estado na urna substituta, pela própria urna substituta
+async fn import_state_transfer(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    contingency: web::Data<ContingencyService>,
+) -> Result<HttpResponse> {
+    let Some(transfer) = contingency.get_transfer(&path).await else {
+        return Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Transferência não encontrada".to_string())
+        ));
+    };
+    if let Some(denied) = ensure_device(&http_req, &transfer.replacement_urna_id) {
+        return Ok(denied);
+    }
+    match contingency.import_state(&transfer.id).await {
+        Ok(baseline) => Ok(HttpResponse::Ok().json(ApiResponse::success(baseline))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Falha na importação: {}", e))
+        )),
+    }
+}
+
+/// Conciliar estado lacrado com os votos recebidos pelo backend
+async fn reconcile_state_transfer(
+    principal: Principal,
+    path: web::Path<String>,
+    contingency: web::Data<ContingencyService>,
+) -> Result<HttpResponse> {
+    let Some(transfer) = contingency.get_transfer(&path).await else {
+        return Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Transferência não encontrada".to_string())
+        ));
+    };
+    let actor = principal.require_for(Permission::TransitionElections, &transfer.sealed_state.snapshot.election_id)?;
+    match contingency.reconcile(&transfer.id, actor).await {
+        Ok(report) => Ok(HttpResponse::Ok().json(ApiResponse::success(report))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Falha na conciliação: {}", e))
+        )),
+    }
+}
+
+/// Chave pública que as urnas fixam para verificar pacotes de provisionamento
+async fn get_provisioning_key(
+    principal: Principal,
+    provisioning: web::Data<ProvisioningService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "algorithm": "Ed25519",
+        "public_key": provisioning.signing_public_key(),
+    }))))
+}
+
+/// Registrar chave de selagem da urna
+async fn register_sealing_key(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<RegisterSealingKeyRequest>,
+    provisioning: web::Data<ProvisioningService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match provisioning.register_sealing_key(&path.into_inner(), &req.public_key_pem).await {
+        Ok(key) => Ok(HttpResponse::Ok().json(ApiResponse::success(key))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Chave de selagem inválida: {}", e))
+        )),
+    }
+}
+
+/// Gerar pacote de provisionamento cifrado para a urna
+async fn create_provisioning_bundle(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<BundleRequest>,
+    provisioning: web::Data<ProvisioningService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match provisioning.create_bundle(&path.into_inner(), req.into_inner()).await {
+        Ok(bundle) => Ok(HttpResponse::Created().json(ApiResponse::success(bundle))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao gerar pacote de provisionamento: {}", e))
+        )),
+    }
+}
+
+/// Pacotes de provisionamento emitidos para a urna
+async fn list_provisioning_bundles(
+    principal: Principal,
+    path: web::Path<String>,
+    provisioning: web::Data<ProvisioningService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let bundles = provisioning.issued_bundles(&path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(bundles)))
+}
+
+/// Receber o relatório de descomissionamento assinado pela urna
+async fn report_decommission(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    req: web::Json<SignedDecommissionReport>,
+    decommissions: web::Data<DecommissionService>,
+) -> Result<HttpResponse> {
+    let urna_id = path.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &urna_id) {
+        return Ok(denied);
+    }
+    if req.report.urna_id != urna_id {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error("Relatório de outra urna".to_string())
+        ));
+    }
+    match decommissions.record(req.into_inner()).await {
+        Ok(record) => Ok(HttpResponse::Created().json(ApiResponse::success(record))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Relatório de descomissionamento rejeitado: {}", e))
+        )),
+    }
+}
+
+/// Descomissionamento registrado para a urna
+async fn get_decommission(
+    path: web::Path<String>,
+    decommissions: web::Data<DecommissionService>,
+) -> Result<HttpResponse> {
+    match decommissions.get(&path.into_inner()).await {
+        Some(record) => Ok(HttpResponse::Ok().json(ApiResponse::success(record))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Urna não descomissionada".to_string())
+        )),
+    }
+}
+
+/// Descomissionamentos recebidos da frota
+async fn list_decommissions(
+    decommissions: web::Data<DecommissionService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(decommissions.list().await)))
+}
+
+/// Recusa votos e lotes de urna já descomissionada
+async fn ensure_active(decommissions: &DecommissionService, urna_id: &str) -> Option<HttpResponse> {
+    if !decommissions.is_decommissioned(urna_id).await {
+        return None;
+    }
+    log::warn!("Decommissioned urna {} attempted to send votes", urna_id);
+    Some(HttpResponse::Gone().json(
+        ApiResponse::<()>::error(format!("Urna {} descomissionada", urna_id))
+    ))
+}
+
+/// Recusa pedidos sem certificado de urna ou em nome de outra urna que não
+/// a do certificado da conexão
+fn ensure_device(req: &HttpRequest, urna_id: &str) -> Option<HttpResponse> {
+    let extensions = req.extensions();
+    let Some(identity) = extensions.get::<DeviceIdentity>() else {
+        log::warn!("Request for urna {} without device certificate", urna_id);
+        return Some(FortisError::UrnaNotAuthorized.error_response());
+    };
+    if identity.urna_id == urna_id {
+        return None;
+    }
+    log::warn!("Urna {} attempted to act as urna {}", identity.urna_id, urna_id);
+    Some(FortisError::UrnaNotAuthorized.error_response())
+}
+
+/// Credenciar certificado de dispositivo da urna
+async fn enroll_device_certificate(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<EnrollDeviceCertificateRequest>,
+    auth_service: web::Data<UrnaAuthService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match auth_service.enroll_device(&path.into_inner(), &req.certificate_pem).await {
+        Ok(certificate) => Ok(HttpResponse::Created().json(ApiResponse::success(certificate))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Certificado de urna inválido: {}", e))
+        )),
+    }
+}
+
+/// Histórico de certificados da urna
+async fn list_device_certificates(
+    principal: Principal,
+    path: web::Path<String>,
+    auth_service: web::Data<UrnaAuthService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let certificates = auth_service.device_certificates(&path.into_inner()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(certificates)))
+}
+
+/// Revogar os certificados ativos da urna
+async fn revoke_device_certificates(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<RevokeDeviceCertificateRequest>,
+    auth_service: web::Data<UrnaAuthService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let revoked = auth_service.revoke_device(&path.into_inner(), &req.reason).await;
+    if revoked.is_empty() {
+        return Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Nenhum certificado ativo para a urna".to_string())
+        ));
+    }
+    Ok(HttpResponse::Ok().json(ApiResponse::success(revoked)))
+}
+
+/// Cadastrar urna para provisionamento remoto
+async fn provision_urna(
+    principal: Principal,
+    req: web::Json<RegisterUrnaRequest>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    match provisioning.register(req.into_inner(), actor).await {
+        Ok(urna) => Ok(HttpResponse::Created().json(ApiResponse::success(urna))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao cadastrar urna: {}", e))
+        )),
+    }
+}
+
+/// Urnas em provisionamento, opcionalmente por etapa
+async fn list_provisioning(
+    principal: Principal,
+    query: web::Query<ProvisioningListQuery>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(provisioning.list(query.state).await)))
+}
+
+/// Situação do provisionamento da urna
+async fn get_provisioning(
+    principal: Principal,
+    path: web::Path<String>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match provisioning.get(&path).await {
+        Some(urna) => Ok(HttpResponse::Ok().json(ApiResponse::success(urna))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Urna não cadastrada: {}", path))
+        )),
+    }
+}
+
+/// Emitir credencial de credenciamento da urna
+async fn issue_provisioning_credentials(
+    principal: Principal,
+    path: web::Path<String>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    match provisioning.issue_credentials(&path, actor).await {
+        Ok(credential) => Ok(HttpResponse::Created().json(ApiResponse::success(credential))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao emitir credencial: {}", e))
+        )),
+    }
+}
+
+/// Credenciamento feito pela urna com a credencial emitida
+async fn enroll_provisioned_urna(
+    path: web::Path<String>,
+    req: web::Json<EnrollUrnaRequest>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    match provisioning.enroll(&path, req.into_inner()).await {
+        Ok(urna) => Ok(HttpResponse::Ok().json(ApiResponse::success(urna))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Credenciamento da urna rejeitado: {}", e))
+        )),
+    }
+}
+
+/// Enviar pacote de configuração para a urna
+async fn push_provisioning_configuration(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<PushConfigurationRequest>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    match provisioning.push_configuration(&path, req.into_inner(), actor).await {
+        Ok(bundle) => Ok(HttpResponse::Created().json(ApiResponse::success(bundle))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao enviar configuração: {}", e))
+        )),
+    }
+}
+
+/// Confirmação da urna sobre o pacote aplicado
+async fn acknowledge_provisioning(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    req: web::Json<ProvisioningAck>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    let urna_id = path.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &urna_id) {
+        return Ok(denied);
+    }
+    match provisioning.acknowledge(&urna_id, req.into_inner()).await {
+        Ok(urna) => Ok(HttpResponse::Ok().json(ApiResponse::success(urna))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Confirmação rejeitada: {}", e))
+        )),
+    }
+}
+
+/// Revogar a urna no provisionamento
+async fn revoke_provisioning(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<RevokeProvisioningRequest>,
+    provisioning: web::Data<UrnaProvisioningService>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    match provisioning.revoke(&path, &req.reason, actor).await {
+        Ok(urna) => Ok(HttpResponse::Ok().json(ApiResponse::success(urna))),
+        Err(e) => Ok(HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("Erro ao revogar urna: {}", e))
+        )),
+    }
+}
+
+/// Assinar e publicar uma atualização das urnas
+async fn publish_update(
+    principal: Principal,
+    req: web::Json<PublishUpdateRequest>,
+    updates: web::Data<UpdateService>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    match updates.publish(req.into_inner(), actor).await {
+        Ok(update) => Ok(HttpResponse::Created().json(ApiResponse::success(update))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Erro ao publicar atualização: {}", e))
+        )),
+    }
+}
+
+/// Atualizações publicadas, opcionalmente por canal
+async fn list_updates(
+    query: web::Query<UpdateListQuery>,
+    updates: web::Data<UpdateService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(updates.list(query.channel.as_deref()).await)))
+}
+
+/// Manifesto mais recente do canal, se houver versão mais nova que a instalada
+async fn get_latest_update(
+    query: web::Query<LatestUpdateQuery>,
+    updates: web::Data<UpdateService>,
+) -> Result<HttpResponse> {
+    let latest = updates.check(query.component, &query.channel, query.installed_version).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(latest)))
+}
+
+/// Chave pública que as urnas fixam para verificar os manifestos
+async fn get_update_signing_key(
+    updates: web::Data<UpdateService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "algorithm": "Ed25519",
+        "public_key": updates.signing_public_key(),
+    }))))
+}
+
+/// Receber o resultado da instalação informado pela urna
+async fn report_update_installation(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    req: web::Json<InstallReport>,
+    updates: web::Data<UpdateService>,
+) -> Result<HttpResponse> {
+    let urna_id = path.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &urna_id) {
+        return Ok(denied);
+    }
+    match updates.report_installation(&urna_id, req.into_inner()).await {
+        Ok(report) => Ok(HttpResponse::Created().json(ApiResponse::success(report))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Relatório de instalação rejeitado: {}", e))
+        )),
+    }
+}
+
+/// Instalações de atualização informadas pela urna
+async fn list_update_installations(
+    path: web::Path<String>,
+    updates: web::Data<UpdateService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(updates.installations(&path).await)))
+}
+
+/// Cadastrar linha de base dos PCRs para uma versão de firmware e aplicação
+async fn register_attestation_baseline(
+    principal: Principal,
+    req: web::Json<BaselineRequest>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    match security.register_baseline(req.into_inner(), actor).await {
+        Ok(baseline) => Ok(HttpResponse::Created().json(ApiResponse::success(baseline))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Linha de base inválida: {}", e))
+        )),
+    }
+}
+
+/// Linhas de base aceitas na atestação
+async fn list_attestation_baselines(
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(security.baselines().await)))
+}
+
+/// Retirar linha de base; urnas com essas medições deixam de abrir sessão
+async fn remove_attestation_baseline(
+    principal: Principal,
+    path: web::Path<Uuid>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    match security.remove_baseline(path.into_inner()).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Linha de base removida".to_string()))),
+        Err(e) => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error(format!("Linha de base não encontrada: {}", e))
+        )),
+    }
+}
+
+/// Registrar chave de atestação da urna
+async fn register_attestation_key(
+    principal: Principal,
+    path: web::Path<String>,
+    req: web::Json<RegisterAttestationKeyRequest>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageElections)?;
+    let urna_id = path.into_inner();
+    match security.register_attestation_key(&urna_id, &req.public_key_pem).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success(urna_id))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Chave de atestação inválida: {}", e))
+        )),
+    }
+}
+
+/// Emitir desafio de atestação para a abertura da sessão
+async fn issue_attestation_challenge(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    let urna_id = path.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &urna_id) {
+        return Ok(denied);
+    }
+    match security.issue_challenge(&urna_id).await {
+        Ok(challenge) => Ok(HttpResponse::Ok().json(ApiResponse::success(challenge))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao emitir desafio: {}", e))
+        )),
+    }
+}
+
+/// Receber as medições do boot; a urna só abre a sessão com veredito confiável
+async fn report_attestation(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    req: web::Json<AttestationReport>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    let urna_id = path.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &urna_id) {
+        return Ok(denied);
+    }
+    if req.urna_id != urna_id {
+        return Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error("Relatório de outra urna".to_string())
+        ));
+    }
+    match security.verify_attestation(req.into_inner()).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(ApiResponse::success(result))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Relatório de atestação rejeitado: {}", e))
+        )),
+    }
+}
+
+/// Última atestação da urna
+async fn get_attestation(
+    path: web::Path<String>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    match security.attestation(&path.into_inner()).await {
+        Some(result) => Ok(HttpResponse::Ok().json(ApiResponse::success(result))),
+        None => Ok(HttpResponse::NotFound().json(
+            ApiResponse::<()>::error("Urna sem atestação registrada".to_string())
+        )),
+    }
+}
+
+/// Receber os eventos de violação da urna; reenvios são descartados
+async fn report_tamper_events(
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    req: web::Json<Vec<TamperEvent>>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    let urna_id = path.into_inner();
+    if let Some(denied) = ensure_device(&http_req, &urna_id) {
+        return Ok(denied);
+    }
+    match security.record_tamper_events(&urna_id, req.into_inner()).await {
+        Ok(recorded) => Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({ "recorded": recorded })))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(
+            ApiResponse::<()>::error(format!("Eventos de violação rejeitados: {}", e))
+        )),
+    }
+}
+
+/// Eventos de violação recebidos da urna
+async fn list_tamper_events(
+    path: web::Path<String>,
+    security: web::Data<UrnaSecurityService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(security.tamper_events(&path.into_inner()).await)))
+}
@@ -1,7 +1,11 @@
 //! Módulo de votos da API v1
 
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
 use crate::models::{VoteRequest, ApiResponse};
+use crate::services::receipts::ReceiptService;
+use crate::services::verification_limits::{self, Admission, VerificationLimiter};
+use crate::auth::rbac::{Permission, Principal};
 use sqlx::{Pool, Postgres};
 
 /// Configurar rotas de votos
@@ -9,15 +13,27 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg
         .route("", web::post().to(cast_vote))
         .route("/stats/{election_id}", web::get().to(get_vote_stats))
-        .route("/verify/{vote_id}", web::get().to(verify_vote))
+        .route("/verify/tokens/key", web::get().to(get_token_key))
+        .route("/verify/tokens", web::post().to(issue_tokens))
+        .route("/verify/{tracking_code}", web::get().to(verify_vote))
         .route("/audit/{election_id}", web::get().to(audit_election));
 }
 
+/// Números cegados pelo eleitor, em hex, para assinatura
+#[derive(Debug, Deserialize)]
+pub struct IssueTokensRequest {
+    /// Época da chave usada para cegar os números
+    pub epoch: u64,
+    pub blinded: Vec<String>,
+}
+
 /// Votar
 async fn cast_vote(
+    principal: Principal,
     req: web::Json<VoteRequest>,
     _pool: web::Data<Pool<Postgres>>,
 ) -> Result<HttpResponse> {
+    principal.require(Permission::CastVotes)?;
     // Implementação simplificada
     Ok(HttpResponse::Ok().json(ApiResponse::success("Voto registrado com sucesso".to_string())))
 }
@@ -35,20 +51,101 @@ async fn get_vote_stats(
     Ok(HttpResponse::Ok().json(ApiResponse::success(stats)))
 }
 
-/// Verificar voto
+/// Verificar voto pelo código de rastreamento do comprovante
+///
+/// Confirma que o voto consta no log transparente, com prova de inclusão,
+/// sem revelar a escolha do eleitor. A consulta passa pelos baldes de
+/// fichas do portal; uma ficha anônima no `Authorization` substitui o
+/// balde do IP.
 async fn verify_vote(
-    _path: web::Path<uuid::Uuid>,
-    _pool: web::Data<Pool<Postgres>>,
+    http: HttpRequest,
+    path: web::Path<String>,
+    receipts: web::Data<ReceiptService>,
+    limiter: web::Data<VerificationLimiter>,
 ) -> Result<HttpResponse> {
-    // Implementação simplificada
-    Ok(HttpResponse::Ok().json(ApiResponse::success("Voto verificado com sucesso".to_string())))
+    let token = http.headers().get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(verification_limits::private_token);
+    let client = match token {
+        Some(token) => match limiter.redeem(token).await {
+            Ok(client) => client,
+            Err(e) => return Ok(HttpResponse::Unauthorized().json(
+                ApiResponse::<()>::error(format!("Ficha de verificação recusada: {}", e))
+            )),
+        },
+        None => {
+            let address = http.connection_info().remote_addr().unwrap_or("unknown").to_string();
+            limiter.client_for_address(&address).await
+        }
+    };
+
+    if let Admission::Throttled { retry_after } = limiter.admit(&client, &path).await {
+        let retry_after = retry_after.as_secs().max(1);
+        return Ok(HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+            .json(serde_json::json!({
+                "success": false,
+                "error": {
+                    "code": "RATE_LIMIT_EXCEEDED",
+                    "message": "Muitas consultas. Tente novamente mais tarde.",
+                    "retry_after": retry_after
+                },
+                "timestamp": chrono::Utc::now()
+            })));
+    }
+
+    match receipts.verify(&path).await {
+        Ok(Some(verification)) => Ok(HttpResponse::Ok().json(ApiResponse::success(verification))),
+        Ok(None) => {
+            limiter.record_miss(&client).await;
+            Ok(HttpResponse::NotFound().json(
+                ApiResponse::<()>::error("Código de rastreamento não encontrado".to_string())
+            ))
+        }
+        Err(e) => {
+            limiter.record_miss(&client).await;
+            Ok(HttpResponse::BadRequest().json(
+                ApiResponse::<()>::error(format!("Código de rastreamento inválido: {}", e))
+            ))
+        }
+    }
+}
+
+/// Chave pública das fichas anônimas de verificação
+async fn get_token_key(limiter: web::Data<VerificationLimiter>) -> Result<HttpResponse> {
+    match limiter.token_key().await {
+        Ok(key) => Ok(HttpResponse::Ok().json(ApiResponse::success(key))),
+        Err(e) => Ok(HttpResponse::ServiceUnavailable().json(
+            ApiResponse::<()>::error(format!("Erro ao carregar a chave das fichas: {}", e))
+        )),
+    }
+}
+
+/// Assinar às cegas fichas anônimas para o eleitor autenticado
+async fn issue_tokens(
+    principal: Principal,
+    limiter: web::Data<VerificationLimiter>,
+    req: web::Json<IssueTokensRequest>,
+) -> Result<HttpResponse> {
+    let subject = principal.require(Permission::CastVotes)?;
+
+    match limiter.issue(subject, req.epoch, &req.blinded).await {
+        Ok(signatures) => Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "signatures": signatures,
+        })))),
+        Err(e) => Ok(HttpResponse::TooManyRequests().json(
+            ApiResponse::<()>::error(format!("Erro ao emitir fichas de verificação: {}", e))
+        )),
+    }
 }
 
 /// Auditoria da eleição
 async fn audit_election(
-    _path: web::Path<uuid::Uuid>,
+    principal: Principal,
+    path: web::Path<uuid::Uuid>,
     _pool: web::Data<Pool<Postgres>>,
 ) -> Result<HttpResponse> {
+    principal.require_for(Permission::ReadAudits, &path.to_string())?;
     // Implementação simplificada
     Ok(HttpResponse::Ok().json(ApiResponse::success("Auditoria da eleição concluída".to_string())))
 }
\ No newline at end of file
@@ -7,7 +7,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::Utc;
 
-use crate::zkp::{VotingProofSystem, VoterData, CircuitConfig, NullifierManager};
+use crate::auth::rbac::{Permission, Principal};
+use crate::services::compute::ComputePool;
+use crate::zkp::{VotingProofSystem, VoterData, CircuitConfig, NullifierSet};
 
 /// Configura rotas ZKP
 pub fn config_zkp_routes(cfg: &mut web::ServiceConfig) {
@@ -18,7 +20,8 @@ pub fn config_zkp_routes(cfg: &mut web::ServiceConfig) {
             .route("/eligibility/prove", web::post().to(generate_eligibility_proof))
             .route("/eligibility/verify", web::post().to(verify_eligibility_proof))
             .route("/nullifier/check", web::post().to(check_nullifier))
-            .route("/nullifier/add", web::post().to(add_nullifier))
+            .route("/nullifier/stats", web::get().to(nullifier_stats))
+            .route("/nullifier/rebuild", web::post().to(rebuild_nullifiers))
     );
 }
 
@@ -86,12 +89,6 @@ pub struct CheckNullifierRequest {
     pub nullifier: String,
 }
 
-/// Requisição para adicionar nullifier
-#[derive(Debug, Deserialize)]
-pub struct AddNullifierRequest {
-    pub nullifier: String,
-}
-
 /// Gera prova de votação
 async fn generate_voting_proof(
     req: web::Json<GenerateVotingProofRequest>,
@@ -119,6 +116,7 @@ async fn generate_voting_proof(
 /// Verifica prova de votação
 async fn verify_voting_proof(
     req: web::Json<VerifyVotingProofRequest>,
+    compute: web::Data<ComputePool>,
 ) -> Result<HttpResponse> {
     let config = CircuitConfig {
         trusted_setup: "trusted_setup".to_string(),
@@ -137,7 +135,13 @@ async fn verify_voting_proof(
         public_inputs: req.public_inputs.clone(),
     };
     
-    match proof_system.verify_voting_proof(&proof) {
+    let verification = compute.run("zkp_verify_voting", move || proof_system.verify_voting_proof(&proof)).await;
+    let verification = match verification {
+        Ok(verification) => verification,
+        Err(e) => return Ok(HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error(e.to_string()))),
+    };
+    
+    match verification {
         Ok(is_valid) => {
             let response = HashMap::from([
                 ("is_valid", is_valid.to_string()),
@@ -175,6 +179,7 @@ async fn generate_eligibility_proof(
 /// Verifica prova de elegibilidade
 async fn verify_eligibility_proof(
     req: web::Json<VerifyEligibilityProofRequest>,
+    compute: web::Data<ComputePool>,
 ) -> Result<HttpResponse> {
     let config = CircuitConfig {
         trusted_setup: "trusted_setup".to_string(),
@@ -193,7 +198,13 @@ async fn verify_eligibility_proof(
         public_inputs: req.public_inputs.clone(),
     };
     
-    match proof_system.verify_eligibility_proof(&proof) {
+    let verification = compute.run("zkp_verify_eligibility", move || proof_system.verify_eligibility_proof(&proof)).await;
+    let verification = match verification {
+        Ok(verification) => verification,
+        Err(e) => return Ok(HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error(e.to_string()))),
+    };
+    
+    match verification {
         Ok(is_valid) => {
             let response = HashMap::from([
                 ("is_valid", is_valid.to_string()),
@@ -207,12 +218,13 @@ async fn verify_eligibility_proof(
 
 /// Verifica se nullifier já foi usado
 async fn check_nullifier(
+    nullifiers: web::Data<NullifierSet>,
     req: web::Json<CheckNullifierRequest>,
 ) -> Result<HttpResponse> {
-    // Em produção, usar um gerenciador global de nullifiers
-    let mut nullifier_manager = NullifierManager::new();
-    
-    let is_used = nullifier_manager.is_nullifier_used(&req.nullifier);
+    let is_used = match nullifiers.contains(&req.nullifier).await {
+        Ok(is_used) => is_used,
+        Err(e) => return Ok(HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error(e.to_string()))),
+    };
     
     let response = HashMap::from([
         ("nullifier", req.nullifier.clone()),
@@ -222,19 +234,26 @@ async fn check_nullifier(
     Ok(HttpResponse::Ok().json(ApiResponse::success(response)))
 }
 
-/// Adiciona nullifier
-async fn add_nullifier(
-    req: web::Json<AddNullifierRequest>,
+/// Estatísticas do filtro de Bloom, incluindo a taxa de falsos positivos
+async fn nullifier_stats(
+    nullifiers: web::Data<NullifierSet>,
 ) -> Result<HttpResponse> {
-    // Em produção, usar um gerenciador global de nullifiers
-    let mut nullifier_manager = NullifierManager::new();
-    
-    let was_added = nullifier_manager.add_nullifier(req.nullifier.clone());
-    
-    let response = HashMap::from([
-        ("nullifier", req.nullifier.clone()),
-        ("was_added", was_added.to_string()),
-    ]);
-    
-    Ok(HttpResponse::Ok().json(ApiResponse::success(response)))
+    Ok(HttpResponse::Ok().json(ApiResponse::success(nullifiers.stats())))
+}
+
+/// Reconstrói o filtro a partir da tabela (após restauração de backup)
+///
+/// Sob controle duplo; os nullifiers só entram na tabela pelo registro do voto.
+async fn rebuild_nullifiers(
+    principal: Principal,
+    nullifiers: web::Data<NullifierSet>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageElections)?;
+    log::warn!("Nullifier filter rebuild requested by {}", actor);
+    match nullifiers.rebuild().await {
+        Ok(count) => Ok(HttpResponse::Ok().json(ApiResponse::success(HashMap::from([
+            ("nullifiers", count),
+        ])))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))),
+    }
 }
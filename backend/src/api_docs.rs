@@ -5,9 +5,12 @@ use utoipa::{
     Modify, OpenApi,
 };
 
+pub mod drift;
+mod undocumented;
+
 use crate::models::{
     CreateElectionRequest, ElectionResponse, ApiResponse, AuthRequest, AuthResponse,
-    VoteRequest, UserInfo, BiometricData, Candidate, ElectionStats, CreateCandidateRequest,
+    VoteRequest, UserInfo, BiometricData, Candidate, CandidatePosition, ElectionStats, CreateCandidateRequest,
 };
 
 /// Estrutura principal da documentação OpenAPI
@@ -15,6 +18,8 @@ use crate::models::{
 #[openapi(
     paths(
         crate::api::v1::auth::login,
+        crate::api::v1::auth::refresh,
+        drift::spec_diff,
         crate::health_check,
         crate::ready_check,
     ),
@@ -31,15 +36,18 @@ use crate::models::{
             UserInfo,
             BiometricData,
             Candidate,
+            CandidatePosition,
             ElectionStats,
             CreateCandidateRequest,
             VoteRequest,
+            crate::api::v1::auth::RefreshTokenRequest,
         )
     ),
     modifiers(&SecurityAddon),
     tags(
         (name = "Autenticação", description = "Endpoints de autenticação e autorização"),
         (name = "Health", description = "Health checks e monitoramento"),
+        (name = "Documentação", description = "Conferência do documento OpenAPI"),
     )
 )]
 pub struct ApiDoc;
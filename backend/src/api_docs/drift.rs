@@ -0,0 +1,338 @@
+//! Conferência entre o roteador e a documentação OpenAPI
+//!
+//! O `build.rs` percorre as funções de configuração do actix a partir do
+//! `main` e gera o inventário das rotas registradas. Na subida, cada
+//! operação do `ApiDoc` precisa existir no roteador com o mesmo método, e
+//! toda referência `$ref` precisa apontar para um schema declarado. Rota sem
+//! documentação também barra a subida, salvo se estiver na lista explícita de
+//! [`UNDOCUMENTED_ROUTES`]; sob os prefixos exigidos em `ApiDocsConfig` nem a
+//! lista vale.
+//!
+//! `/api-docs/diff` compara o documento em execução com a última versão
+//! publicada, para revisar o que muda antes de publicar de novo.
+
+use actix_web::{web, HttpResponse};
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use utoipa::OpenApi;
+
+use super::undocumented::UNDOCUMENTED_ROUTES;
+use super::ApiDoc;
+use crate::config::{ApiDocsConfig, Config};
+use crate::models::ApiResponse;
+
+include!(concat!(env!("OUT_DIR"), "/route_inventory.rs"));
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+const HTTP_METHODS: [&str; 8] = ["get", "post", "put", "delete", "patch", "head", "options", "trace"];
+
+/// Operação identificada pelo método e pelo caminho com parâmetros anônimos
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct RouteKey {
+    pub method: String,
+    pub path: String,
+}
+
+impl RouteKey {
+    pub fn new(method: &str, path: &str) -> Self {
+        Self { method: method.to_uppercase(), path: normalize_path(path) }
+    }
+}
+
+/// Divergências entre o roteador e a documentação
+#[derive(Debug, Default, Serialize)]
+pub struct RouterDrift {
+    /// Registradas no actix e ausentes do documento
+    pub undocumented: Vec<RouteKey>,
+    /// Documentadas sem rota com o mesmo método
+    pub not_routed: Vec<RouteKey>,
+    /// `$ref` para schemas que o documento não declara
+    pub unresolved_schemas: Vec<String>,
+    /// Schemas declarados que nenhuma operação usa
+    pub unused_schemas: Vec<String>,
+}
+
+impl RouterDrift {
+    /// Divergências que impedem a subida
+    pub fn blocking(&self, required_prefixes: &[String], allowlist: &[(&str, &str)]) -> Vec<String> {
+        let allowed: BTreeSet<RouteKey> = allowlist.iter().map(|(method, path)| RouteKey::new(method, path)).collect();
+        let required = |route: &RouteKey| required_prefixes.iter().any(|prefix| route.path.starts_with(prefix.as_str()));
+
+        let mut problems = Vec::new();
+        problems.extend(self.not_routed.iter().map(|route| format!("documented but not routed: {} {}", route.method, route.path)));
+        problems.extend(self.unresolved_schemas.iter().map(|schema| format!("unresolved schema reference: {}", schema)));
+        problems.extend(
+            self.undocumented.iter()
+                .filter(|route| !allowed.contains(*route) || required(route))
+                .map(|route| format!("routed but not documented: {} {}", route.method, route.path)),
+        );
+        // A lista só cobre rotas existentes e ainda sem documentação
+        problems.extend(
+            allowed.iter()
+                .filter(|route| !self.undocumented.contains(*route))
+                .map(|route| format!("stale undocumented-route allowlist entry: {} {}", route.method, route.path)),
+        );
+        problems
+    }
+}
+
+/// Diferença entre a versão publicada e o documento em execução
+#[derive(Debug, Default, Serialize)]
+pub struct SpecDiff {
+    /// Há versão publicada para comparar
+    pub published: bool,
+    pub added_operations: Vec<RouteKey>,
+    pub removed_operations: Vec<RouteKey>,
+    pub changed_operations: Vec<RouteKey>,
+    pub added_schemas: Vec<String>,
+    pub removed_schemas: Vec<String>,
+    pub changed_schemas: Vec<String>,
+}
+
+impl SpecDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_operations.is_empty()
+            && self.removed_operations.is_empty()
+            && self.changed_operations.is_empty()
+            && self.added_schemas.is_empty()
+            && self.removed_schemas.is_empty()
+            && self.changed_schemas.is_empty()
+    }
+}
+
+/// Documento OpenAPI em execução, como JSON
+pub fn current_spec() -> Value {
+    serde_json::to_value(ApiDoc::openapi()).unwrap_or(Value::Null)
+}
+
+/// Confere o documento com o inventário de rotas
+pub fn check_router(spec: &Value, routes: &[(&str, &str)]) -> RouterDrift {
+    let registered: BTreeSet<RouteKey> = routes.iter().map(|(method, path)| RouteKey::new(method, path)).collect();
+    let operations = operations(spec);
+    let documented: BTreeSet<RouteKey> = operations.keys().cloned().collect();
+
+    let declared: BTreeSet<String> = schemas(spec).into_keys().collect();
+    let mut referenced = BTreeSet::new();
+    collect_refs(spec.get("paths").unwrap_or(&Value::Null), &mut referenced);
+    // Schemas usados por outros schemas também contam como usados
+    if let Some(components) = spec.pointer("/components/schemas") {
+        collect_refs(components, &mut referenced);
+    }
+
+    RouterDrift {
+        undocumented: registered.difference(&documented).cloned().collect(),
+        not_routed: documented.difference(&registered).cloned().collect(),
+        unresolved_schemas: referenced.difference(&declared).cloned().collect(),
+        unused_schemas: declared.difference(&referenced).cloned().collect(),
+    }
+}
+
+/// Falha a subida se o documento divergir do roteador
+pub fn verify_startup(config: &ApiDocsConfig) -> Result<()> {
+    let drift = check_router(&current_spec(), REGISTERED_ROUTES);
+    let blocking = drift.blocking(&config.required_prefixes, UNDOCUMENTED_ROUTES);
+    if !blocking.is_empty() {
+        return Err(anyhow!("OpenAPI document out of sync with router: {}", blocking.join("; ")));
+    }
+    if !drift.undocumented.is_empty() {
+        log::warn!(
+            "{} of {} routes are allowlisted without OpenAPI documentation",
+            drift.undocumented.len(),
+            REGISTERED_ROUTES.len()
+        );
+    }
+    if !drift.unused_schemas.is_empty() {
+        log::warn!("OpenAPI schemas not used by any operation: {}", drift.unused_schemas.join(", "));
+    }
+    Ok(())
+}
+
+/// Compara a versão publicada com a atual
+pub fn diff_specs(published: Option<&Value>, current: &Value) -> SpecDiff {
+    let Some(published) = published else {
+        return SpecDiff {
+            published: false,
+            added_operations: operations(current).into_keys().collect(),
+            added_schemas: schemas(current).into_keys().collect(),
+            ..SpecDiff::default()
+        };
+    };
+
+    let (before, after) = (operations(published), operations(current));
+    let (schemas_before, schemas_after) = (schemas(published), schemas(current));
+    SpecDiff {
+        published: true,
+        added_operations: after.keys().filter(|key| !before.contains_key(*key)).cloned().collect(),
+        removed_operations: before.keys().filter(|key| !after.contains_key(*key)).cloned().collect(),
+        changed_operations: after.iter()
+            .filter(|(key, operation)| before.get(*key).is_some_and(|old| old != *operation))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        added_schemas: schemas_after.keys().filter(|name| !schemas_before.contains_key(*name)).cloned().collect(),
+        removed_schemas: schemas_before.keys().filter(|name| !schemas_after.contains_key(*name)).cloned().collect(),
+        changed_schemas: schemas_after.iter()
+            .filter(|(name, schema)| schemas_before.get(*name).is_some_and(|old| old != *schema))
+            .map(|(name, _)| name.clone())
+            .collect(),
+    }
+}
+
+/// Diferença entre o documento em execução e a última versão publicada
+#[utoipa::path(
+    get,
+    path = "/api-docs/diff",
+    responses(
+        (status = 200, description = "Diferença entre as versões do documento", body = ApiResponse<serde_json::Value>),
+        (status = 500, description = "Versão publicada ilegível", body = ApiResponse<String>)
+    ),
+    tag = "Documentação"
+)]
+pub async fn spec_diff(config: web::Data<Config>) -> actix_web::Result<HttpResponse> {
+    let path = &config.api_docs.published_spec_path;
+    let published = match std::fs::read_to_string(path) {
+        Ok(contents) => match serde_json::from_str::<Value>(&contents) {
+            Ok(spec) => Some(spec),
+            Err(e) => return Ok(HttpResponse::InternalServerError().json(
+                ApiResponse::<()>::error(format!("Erro ao ler documento publicado: {}", e))
+            )),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Ok(HttpResponse::InternalServerError().json(
+            ApiResponse::<()>::error(format!("Erro ao ler documento publicado: {}", e))
+        )),
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(diff_specs(published.as_ref(), &current_spec()))))
+}
+
+fn operations(spec: &Value) -> BTreeMap<RouteKey, Value> {
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return BTreeMap::new();
+    };
+    paths.iter()
+        .flat_map(|(path, item)| {
+            HTTP_METHODS.iter().filter_map(move |method| {
+                item.get(*method).map(|operation| (RouteKey::new(method, path), operation.clone()))
+            })
+        })
+        .collect()
+}
+
+fn schemas(spec: &Value) -> BTreeMap<String, Value> {
+    spec.pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .map(|schemas| schemas.iter().map(|(name, schema)| (name.clone(), schema.clone())).collect())
+        .unwrap_or_default()
+}
+
+fn collect_refs(value: &Value, refs: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => {
+                        if let Some(name) = reference.strip_prefix(SCHEMA_REF_PREFIX) {
+                            refs.insert(name.to_string());
+                        }
+                    }
+                    _ => collect_refs(value, refs),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_refs(item, refs)),
+        _ => {}
+    }
+}
+
+/// `/a/{id}/` e `/a/{election_id}` viram `/a/{}`
+fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| if segment.starts_with('{') && segment.ends_with('}') { "{}" } else { segment })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_router_drift_in_both_directions() {
+        let spec = json!({
+            "paths": {
+                "/api/v1/votes/verify/{code}": {
+                    "get": { "responses": { "200": { "content": { "application/json": {
+                        "schema": { "$ref": "#/components/schemas/VoteVerification" }
+                    } } } } }
+                },
+                "/api/v1/votes": { "put": { "responses": {} } }
+            },
+            "components": { "schemas": { "Orphan": { "type": "object" } } }
+        });
+        let routes = [("GET", "/api/v1/votes/verify/{tracking_code}"), ("POST", "/api/v1/votes"), ("GET", "/api/v1/admin")];
+
+        let drift = check_router(&spec, &routes);
+        assert_eq!(drift.not_routed, vec![RouteKey::new("PUT", "/api/v1/votes")]);
+        assert_eq!(drift.undocumented, vec![RouteKey::new("GET", "/api/v1/admin"), RouteKey::new("POST", "/api/v1/votes")]);
+        assert_eq!(drift.unresolved_schemas, vec!["VoteVerification".to_string()]);
+        assert_eq!(drift.unused_schemas, vec!["Orphan".to_string()]);
+        let allowlist = [("GET", "/api/v1/admin")];
+        assert_eq!(drift.blocking(&["/api/v1/votes".to_string()], &allowlist).len(), 3);
+
+        // O documento do backend precisa bater com o roteador sob a configuração padrão
+        let defaults = Config::new().api_docs;
+        let blocking = check_router(&current_spec(), REGISTERED_ROUTES)
+            .blocking(&defaults.required_prefixes, UNDOCUMENTED_ROUTES);
+        assert!(blocking.is_empty(), "{:?}", blocking);
+    }
+
+    #[test]
+    fn test_undocumented_route_outside_allowlist_blocks_startup() {
+        let spec = json!({ "paths": { "/health": { "get": { "responses": {} } } } });
+        let routes = [("GET", "/health"), ("POST", "/api/v1/nodes/{id}/drain")];
+        let drift = check_router(&spec, &routes);
+
+        // Fora dos prefixos exigidos e fora da lista: barra a subida
+        let blocking = drift.blocking(&[], &[]);
+        assert_eq!(blocking, vec!["routed but not documented: POST /api/v1/nodes/{}/drain".to_string()]);
+
+        assert!(drift.blocking(&[], &[("POST", "/api/v1/nodes/{node_id}/drain")]).is_empty());
+        // Prefixo exigido não pode ser dispensado pela lista
+        assert_eq!(drift.blocking(&["/api/v1/nodes".to_string()], &[("POST", "/api/v1/nodes/{id}/drain")]).len(), 1);
+        // Entrada para rota documentada ou inexistente também barra
+        let stale = drift.blocking(&[], &[("POST", "/api/v1/nodes/{id}/drain"), ("GET", "/health"), ("GET", "/gone")]);
+        assert_eq!(stale.len(), 2);
+    }
+
+    #[test]
+    fn test_spec_diff_against_published_version() {
+        let published = json!({
+            "paths": {
+                "/health": { "get": { "summary": "Health" } },
+                "/old": { "get": {} }
+            },
+            "components": { "schemas": { "A": { "type": "string" }, "B": { "type": "object" } } }
+        });
+        let current = json!({
+            "paths": {
+                "/health": { "get": { "summary": "Health check" } },
+                "/new/{id}": { "post": {} }
+            },
+            "components": { "schemas": { "A": { "type": "string" }, "C": { "type": "object" } } }
+        });
+
+        let diff = diff_specs(Some(&published), &current);
+        assert!(diff.published);
+        assert_eq!(diff.added_operations, vec![RouteKey::new("POST", "/new/{}")]);
+        assert_eq!(diff.removed_operations, vec![RouteKey::new("GET", "/old")]);
+        assert_eq!(diff.changed_operations, vec![RouteKey::new("GET", "/health")]);
+        assert_eq!((diff.added_schemas, diff.removed_schemas), (vec!["C".to_string()], vec!["B".to_string()]));
+        assert!(diff_specs(Some(&current), &current).is_empty());
+        assert!(!diff_specs(None, &current).published);
+    }
+}
@@ -0,0 +1,308 @@
+//! Rotas ainda sem documentação OpenAPI
+//!
+//! Lista explícita das rotas que podem subir sem constar do `ApiDoc`. Rota
+//! nova precisa ser documentada ou entrar aqui na revisão; ao documentar uma
+//! rota, a entrada sai da lista, e entrada que não corresponda a uma rota sem
+//! documentação barra a subida.
+
+/// (método, caminho) como registrados no actix
+pub const UNDOCUMENTED_ROUTES: &[(&str, &str)] = &[
+    ("GET", "/api/v1/admin/dr-drills"),
+    ("POST", "/api/v1/admin/dr-drills"),
+    ("GET", "/api/v1/admin/rollout"),
+    ("POST", "/api/v1/admin/rollout"),
+    ("POST", "/api/v1/admin/rollout/halt"),
+    ("GET", "/api/v1/admin/rollout/shards"),
+    ("GET", "/api/v1/analytics/history/candidates/{votable}"),
+    ("GET", "/api/v1/analytics/history/elections"),
+    ("GET", "/api/v1/analytics/history/turnout"),
+    ("GET", "/api/v1/analytics/lessons-learned/{election_id}"),
+    ("GET", "/api/v1/analytics/lessons-learned/{election_id}/report.pdf"),
+    ("POST", "/api/v1/analytics/lessons-learned/{election_id}/signals"),
+    ("POST", "/api/v1/analytics/open-data/{election_id}/certify"),
+    ("POST", "/api/v1/analytics/open-data/{election_id}/results"),
+    ("GET", "/api/v1/analytics/open-data/{election_id}/snapshots"),
+    ("POST", "/api/v1/analytics/open-data/{election_id}/snapshots"),
+    ("GET", "/api/v1/analytics/privacy-budget/{election_id}"),
+    ("GET", "/api/v1/analytics/turnout/{election_id}/heatmap"),
+    ("POST", "/api/v1/analytics/turnout/{election_id}/sections"),
+    ("GET", "/api/v1/audit-dashboards"),
+    ("POST", "/api/v1/audit-dashboards"),
+    ("DELETE", "/api/v1/audit-dashboards/{id}"),
+    ("GET", "/api/v1/audit-dashboards/{id}"),
+    ("PUT", "/api/v1/audit-dashboards/{id}"),
+    ("GET", "/api/v1/audit-dashboards/{id}/data"),
+    ("GET", "/api/v1/audit-dashboards/{id}/widgets/{widget_id}/data"),
+    ("POST", "/api/v1/auth/logout"),
+    ("GET", "/api/v1/auth/sessions"),
+    ("DELETE", "/api/v1/auth/sessions/{session_id}"),
+    ("POST", "/api/v1/auth/verify"),
+    ("GET", "/api/v1/beacon/commitments/{election_id}"),
+    ("POST", "/api/v1/beacon/commitments/{election_id}"),
+    ("GET", "/api/v1/beacon/draws/{election_id}"),
+    ("POST", "/api/v1/beacon/draws/{election_id}"),
+    ("GET", "/api/v1/beacon/latest"),
+    ("GET", "/api/v1/beacon/rounds/{round}"),
+    ("GET", "/api/v1/consent/declarations"),
+    ("POST", "/api/v1/consent/grant"),
+    ("GET", "/api/v1/consent/records"),
+    ("POST", "/api/v1/consent/revoke"),
+    ("GET", "/api/v1/consent/summary"),
+    ("GET", "/api/v1/consent/usages"),
+    ("GET", "/api/v1/dual-control/requests"),
+    ("GET", "/api/v1/dual-control/requests/{id}"),
+    ("POST", "/api/v1/dual-control/requests/{id}/approve"),
+    ("POST", "/api/v1/dual-control/requests/{id}/reject"),
+    ("GET", "/api/v1/election-templates"),
+    ("GET", "/api/v1/election-templates/{id}"),
+    ("POST", "/api/v1/election-templates/{id}/instantiate"),
+    ("GET", "/api/v1/elections"),
+    ("POST", "/api/v1/elections"),
+    ("GET", "/api/v1/elections/package-key"),
+    ("DELETE", "/api/v1/elections/{id}"),
+    ("GET", "/api/v1/elections/{id}"),
+    ("PUT", "/api/v1/elections/{id}"),
+    ("POST", "/api/v1/elections/{id}/audits/rla"),
+    ("GET", "/api/v1/elections/{id}/audits/rla/{plan_id}"),
+    ("GET", "/api/v1/elections/{id}/audits/rla/{plan_id}/ballots"),
+    ("POST", "/api/v1/elections/{id}/audits/rla/{plan_id}/ballots"),
+    ("POST", "/api/v1/elections/{id}/audits/rla/{plan_id}/escalate"),
+    ("POST", "/api/v1/elections/{id}/audits/rla/{plan_id}/report"),
+    ("GET", "/api/v1/elections/{id}/candidates"),
+    ("POST", "/api/v1/elections/{id}/candidates"),
+    ("DELETE", "/api/v1/elections/{id}/candidates/{candidate_id}"),
+    ("GET", "/api/v1/elections/{id}/candidates/{candidate_id}"),
+    ("PUT", "/api/v1/elections/{id}/candidates/{candidate_id}"),
+    ("GET", "/api/v1/elections/{id}/candidates/{candidate_id}/photo"),
+    ("GET", "/api/v1/elections/{id}/events"),
+    ("GET", "/api/v1/elections/{id}/package"),
+    ("GET", "/api/v1/elections/{id}/results"),
+    ("GET", "/api/v1/elections/{id}/results/bulletins"),
+    ("POST", "/api/v1/elections/{id}/results/bulletins"),
+    ("GET", "/api/v1/elections/{id}/results/manifest"),
+    ("POST", "/api/v1/elections/{id}/results/publish"),
+    ("GET", "/api/v1/elections/{id}/results/shadow"),
+    ("POST", "/api/v1/elections/{id}/results/shadow"),
+    ("GET", "/api/v1/elections/{id}/template"),
+    ("GET", "/api/v1/elections/{id}/transitions"),
+    ("POST", "/api/v1/elections/{id}/transitions"),
+    ("POST", "/api/v1/eligibility/batches"),
+    ("GET", "/api/v1/eligibility/batches/{batch_id}"),
+    ("POST", "/api/v1/eligibility/batches/{batch_id}/complete"),
+    ("POST", "/api/v1/eligibility/batches/{batch_id}/records"),
+    ("GET", "/api/v1/eligibility/elections/{election_id}/zones/{zone}/report"),
+    ("GET", "/api/v1/errors/catalog"),
+    ("GET", "/api/v1/errors/languages"),
+    ("GET", "/api/v1/errors/languages/{locale}"),
+    ("POST", "/api/v1/key-ceremonies"),
+    ("GET", "/api/v1/key-ceremonies/{ceremony_id}"),
+    ("POST", "/api/v1/key-ceremonies/{ceremony_id}/acknowledgments"),
+    ("POST", "/api/v1/key-ceremonies/{ceremony_id}/reconstruct"),
+    ("POST", "/api/v1/key-ceremonies/{ceremony_id}/share"),
+    ("POST", "/api/v1/key-ceremonies/{ceremony_id}/shares"),
+    ("GET", "/api/v1/mixnet/public-key"),
+    ("GET", "/api/v1/mixnet/{election_id}"),
+    ("POST", "/api/v1/mixnet/{election_id}/ballots"),
+    ("POST", "/api/v1/mixnet/{election_id}/run"),
+    ("GET", "/api/v1/mixnet/{election_id}/verify"),
+    ("GET", "/api/v1/monitoring/command-center/{election_id}"),
+    ("POST", "/api/v1/monitoring/command-center/{election_id}/placements"),
+    ("GET", "/api/v1/monitoring/compute"),
+    ("GET", "/api/v1/monitoring/health-score"),
+    ("GET", "/api/v1/monitoring/health-score/{election_id}"),
+    ("GET", "/api/v1/monitoring/health-score/{election_id}/factors/{factor}"),
+    ("POST", "/api/v1/monitoring/health-score/{election_id}/signals"),
+    ("GET", "/api/v1/nodes"),
+    ("POST", "/api/v1/nodes"),
+    ("POST", "/api/v1/nodes/sync"),
+    ("DELETE", "/api/v1/nodes/{id}"),
+    ("GET", "/api/v1/nodes/{id}"),
+    ("PUT", "/api/v1/nodes/{id}"),
+    ("GET", "/api/v1/nodes/{id}/status"),
+    ("GET", "/api/v1/rbac/assignments"),
+    ("POST", "/api/v1/rbac/assignments"),
+    ("POST", "/api/v1/rbac/assignments/revoke"),
+    ("GET", "/api/v1/rbac/me"),
+    ("GET", "/api/v1/rbac/roles"),
+    ("GET", "/api/v1/regions"),
+    ("POST", "/api/v1/regions/municipalities"),
+    ("POST", "/api/v1/regions/sections"),
+    ("DELETE", "/api/v1/regions/sections/{state}/{municipality}/{zone}/{section}"),
+    ("GET", "/api/v1/regions/sections/{state}/{municipality}/{zone}/{section}"),
+    ("PUT", "/api/v1/regions/sections/{state}/{municipality}/{zone}/{section}/urna"),
+    ("POST", "/api/v1/regions/states"),
+    ("DELETE", "/api/v1/regions/urnas/{urna_id}/section"),
+    ("GET", "/api/v1/regions/urnas/{urna_id}/section"),
+    ("POST", "/api/v1/regions/zones"),
+    ("POST", "/api/v1/results/{id}/amendments"),
+    ("GET", "/api/v1/results/{id}/diff"),
+    ("GET", "/api/v1/results/{id}/rounds"),
+    ("POST", "/api/v1/results/{id}/rounds"),
+    ("GET", "/api/v1/security/client-puzzle"),
+    ("PUT", "/api/v1/security/client-puzzle"),
+    ("GET", "/api/v1/security/credentials"),
+    ("POST", "/api/v1/security/credentials"),
+    ("GET", "/api/v1/security/credentials/window-check"),
+    ("DELETE", "/api/v1/security/credentials/{id}"),
+    ("GET", "/api/v1/security/crypto-policy"),
+    ("GET", "/api/v1/security/incidents"),
+    ("GET", "/api/v1/security/incidents/{id}"),
+    ("GET", "/api/v1/security/pgp-key"),
+    ("GET", "/api/v1/security/reports"),
+    ("POST", "/api/v1/security/reports"),
+    ("GET", "/api/v1/security/reports/{id}"),
+    ("POST", "/api/v1/security/reports/{id}/triage"),
+    ("GET", "/api/v1/stream"),
+    ("GET", "/api/v1/stream/stats"),
+    ("GET", "/api/v1/support/admins/{admin_id}/access-log"),
+    ("GET", "/api/v1/support/admins/{admin_id}/grants"),
+    ("POST", "/api/v1/support/grants"),
+    ("POST", "/api/v1/support/grants/{id}/approve"),
+    ("POST", "/api/v1/support/grants/{id}/deny"),
+    ("POST", "/api/v1/support/grants/{id}/revoke"),
+    ("POST", "/api/v1/support/grants/{id}/token"),
+    ("POST", "/api/v1/transmission/{election_id}/authorities"),
+    ("GET", "/api/v1/transmission/{election_id}/zones"),
+    ("GET", "/api/v1/transmission/{election_id}/zones/{zone}"),
+    ("GET", "/api/v1/transmission/{election_id}/zones/{zone}/declaration"),
+    ("POST", "/api/v1/transmission/{election_id}/zones/{zone}/declaration"),
+    ("GET", "/api/v1/transparency/audit"),
+    ("POST", "/api/v1/transparency/audit-camera/cameras"),
+    ("POST", "/api/v1/transparency/audit-camera/health"),
+    ("POST", "/api/v1/transparency/audit-camera/segments"),
+    ("POST", "/api/v1/transparency/audit-camera/segments/verify"),
+    ("GET", "/api/v1/transparency/audit-camera/{camera_id}/segments"),
+    ("POST", "/api/v1/transparency/cleanup"),
+    ("GET", "/api/v1/transparency/config"),
+    ("PUT", "/api/v1/transparency/config"),
+    ("GET", "/api/v1/transparency/consistency"),
+    ("POST", "/api/v1/transparency/consistency/verify"),
+    ("POST", "/api/v1/transparency/events"),
+    ("POST", "/api/v1/transparency/events/search"),
+    ("GET", "/api/v1/transparency/events/{index}"),
+    ("GET", "/api/v1/transparency/export"),
+    ("GET", "/api/v1/transparency/health"),
+    ("GET", "/api/v1/transparency/metrics"),
+    ("GET", "/api/v1/transparency/stats"),
+    ("GET", "/api/v1/transparency/sth"),
+    ("POST", "/api/v1/transparency/sth/cosign"),
+    ("GET", "/api/v1/transparency/sth/history"),
+    ("POST", "/api/v1/transparency/sth/observed"),
+    ("GET", "/api/v1/transparency/sth/split-views"),
+    ("POST", "/api/v1/transparency/verify"),
+    ("GET", "/api/v1/transparency/visibility"),
+    ("POST", "/api/v1/tse/tse/auth/gov-br/callback"),
+    ("POST", "/api/v1/tse/tse/auth/gov-br/refresh"),
+    ("GET", "/api/v1/tse/tse/auth/gov-br/url"),
+    ("GET", "/api/v1/tse/tse/auth/gov-br/user"),
+    ("POST", "/api/v1/tse/tse/certificate/sign"),
+    ("POST", "/api/v1/tse/tse/certificate/validate"),
+    ("POST", "/api/v1/tse/tse/certificate/verify"),
+    ("GET", "/api/v1/tse/tse/elections/active"),
+    ("POST", "/api/v1/tse/tse/elections/sync"),
+    ("GET", "/api/v1/tse/tse/elections/{election_id}"),
+    ("GET", "/api/v1/tse/tse/elections/{election_id}/candidates"),
+    ("POST", "/api/v1/tse/tse/elections/{election_id}/candidates/import"),
+    ("GET", "/api/v1/tse/tse/elections/{election_id}/rules"),
+    ("GET", "/api/v1/tse/tse/elections/{election_id}/stats"),
+    ("GET", "/api/v1/tse/tse/elections/{election_id}/zones"),
+    ("GET", "/api/v1/tse/tse/quarantine"),
+    ("POST", "/api/v1/tse/tse/quarantine/{record_id}/approve"),
+    ("POST", "/api/v1/tse/tse/quarantine/{record_id}/correct"),
+    ("POST", "/api/v1/tse/tse/quarantine/{record_id}/reject"),
+    ("GET", "/api/v1/tse/tse/voter-roll/duplicates"),
+    ("POST", "/api/v1/tse/tse/voter-roll/duplicates/{match_id}/review"),
+    ("GET", "/api/v1/tse/tse/voter-roll/runs"),
+    ("POST", "/api/v1/tse/tse/voter-roll/sync"),
+    ("GET", "/api/v1/tse/tse/voter/can-vote/{cpf}/{election_id}"),
+    ("GET", "/api/v1/tse/tse/voter/data/{cpf}"),
+    ("GET", "/api/v1/tse/tse/voter/has-voted/{cpf}/{election_id}"),
+    ("GET", "/api/v1/tse/tse/voter/history/{cpf}"),
+    ("GET", "/api/v1/tse/tse/voter/validate/cpf/{cpf}"),
+    ("GET", "/api/v1/tse/tse/voter/validate/id/{voter_id}"),
+    ("POST", "/api/v1/tse/tse/votes"),
+    ("GET", "/api/v1/urnas/attestation-baselines"),
+    ("POST", "/api/v1/urnas/attestation-baselines"),
+    ("DELETE", "/api/v1/urnas/attestation-baselines/{baseline_id}"),
+    ("POST", "/api/v1/urnas/batches"),
+    ("GET", "/api/v1/urnas/batches/disputes/{tracking_code}"),
+    ("GET", "/api/v1/urnas/contingency/transfers"),
+    ("POST", "/api/v1/urnas/contingency/transfers"),
+    ("GET", "/api/v1/urnas/contingency/transfers/{id}"),
+    ("POST", "/api/v1/urnas/contingency/transfers/{id}/approve"),
+    ("POST", "/api/v1/urnas/contingency/transfers/{id}/import"),
+    ("POST", "/api/v1/urnas/contingency/transfers/{id}/reconcile"),
+    ("GET", "/api/v1/urnas/decommissions"),
+    ("GET", "/api/v1/urnas/diagnostics/codes"),
+    ("GET", "/api/v1/urnas/diagnostics/codes/{code}"),
+    ("GET", "/api/v1/urnas/diagnostics/frequency"),
+    ("GET", "/api/v1/urnas/endpoints"),
+    ("GET", "/api/v1/urnas/fleet/urnas"),
+    ("GET", "/api/v1/urnas/fleet/zones"),
+    ("GET", "/api/v1/urnas/health/{urna_id}"),
+    ("POST", "/api/v1/urnas/heartbeat"),
+    ("GET", "/api/v1/urnas/provision"),
+    ("POST", "/api/v1/urnas/provision"),
+    ("GET", "/api/v1/urnas/provision/{urna_id}"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/acknowledge"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/configuration"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/credentials"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/enroll"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/revoke"),
+    ("GET", "/api/v1/urnas/provisioning-key"),
+    ("POST", "/api/v1/urnas/register"),
+    ("GET", "/api/v1/urnas/status/{urna_id}"),
+    ("POST", "/api/v1/urnas/sync"),
+    ("GET", "/api/v1/urnas/sync/{sync_id}"),
+    ("GET", "/api/v1/urnas/updates"),
+    ("POST", "/api/v1/urnas/updates"),
+    ("GET", "/api/v1/urnas/updates/latest"),
+    ("GET", "/api/v1/urnas/updates/signing-key"),
+    ("POST", "/api/v1/urnas/vote"),
+    ("POST", "/api/v1/urnas/votes/batch"),
+    ("GET", "/api/v1/urnas/{urna_id}/attestation"),
+    ("POST", "/api/v1/urnas/{urna_id}/attestation"),
+    ("POST", "/api/v1/urnas/{urna_id}/attestation-key"),
+    ("POST", "/api/v1/urnas/{urna_id}/attestation/challenge"),
+    ("GET", "/api/v1/urnas/{urna_id}/audit"),
+    ("GET", "/api/v1/urnas/{urna_id}/batches"),
+    ("GET", "/api/v1/urnas/{urna_id}/certificates"),
+    ("POST", "/api/v1/urnas/{urna_id}/certificates"),
+    ("POST", "/api/v1/urnas/{urna_id}/certificates/revoke"),
+    ("GET", "/api/v1/urnas/{urna_id}/decommission"),
+    ("POST", "/api/v1/urnas/{urna_id}/decommission"),
+    ("GET", "/api/v1/urnas/{urna_id}/diagnostics"),
+    ("POST", "/api/v1/urnas/{urna_id}/heartbeat"),
+    ("GET", "/api/v1/urnas/{urna_id}/heartbeats"),
+    ("GET", "/api/v1/urnas/{urna_id}/provisioning-bundles"),
+    ("POST", "/api/v1/urnas/{urna_id}/provisioning-bundles"),
+    ("POST", "/api/v1/urnas/{urna_id}/public-key"),
+    ("POST", "/api/v1/urnas/{urna_id}/public-key/rotate"),
+    ("POST", "/api/v1/urnas/{urna_id}/sealing-key"),
+    ("GET", "/api/v1/urnas/{urna_id}/tamper"),
+    ("POST", "/api/v1/urnas/{urna_id}/tamper"),
+    ("GET", "/api/v1/urnas/{urna_id}/updates"),
+    ("POST", "/api/v1/urnas/{urna_id}/updates"),
+    ("GET", "/api/v1/urnas/{urna_id}/votes"),
+    ("POST", "/api/v1/votes"),
+    ("GET", "/api/v1/votes/audit/{election_id}"),
+    ("GET", "/api/v1/votes/stats/{election_id}"),
+    ("POST", "/api/v1/votes/verify/tokens"),
+    ("GET", "/api/v1/votes/verify/tokens/key"),
+    ("GET", "/api/v1/votes/verify/{tracking_code}"),
+    ("POST", "/api/v1/zkp/zkp/eligibility/prove"),
+    ("POST", "/api/v1/zkp/zkp/eligibility/verify"),
+    ("POST", "/api/v1/zkp/zkp/nullifier/check"),
+    ("POST", "/api/v1/zkp/zkp/nullifier/rebuild"),
+    ("GET", "/api/v1/zkp/zkp/nullifier/stats"),
+    ("POST", "/api/v1/zkp/zkp/voting/prove"),
+    ("POST", "/api/v1/zkp/zkp/voting/verify"),
+    ("POST", "/cluster/gossip"),
+    ("POST", "/cluster/leave"),
+    ("GET", "/cluster/members"),
+    ("GET", "/cluster/members/{member_id}"),
+    ("GET", "/cluster/self"),
+    ("GET", "/cluster/shards/{shard}"),
+    ("GET", "/cluster/summary"),
+    ("GET", "/metrics"),
+];
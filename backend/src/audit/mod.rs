@@ -5,6 +5,7 @@
 //! apropriadas para cada problema.
 
 pub mod transparent_logs;
+pub mod rla;
 // pub mod audit_service;
 // pub mod verification;
 
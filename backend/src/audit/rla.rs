@@ -0,0 +1,561 @@
+//! Auditoria de Limitação de Risco (RLA)
+//!
+//! Planeja auditorias por comparação de cédulas (cada cédula sorteada é
+//! confrontada com o registro digital correspondente) ou por votação
+//! amostral (BRAVO), calcula o tamanho de amostra esperado para o limite
+//! de risco, acompanha as cédulas auditadas e mede o risco remanescente.
+//!
+//! O universo auditado são as entradas `VoteCast` da eleição no log
+//! transparente, na ordem do log; cada cédula da amostra é identificada
+//! pelo índice da sua entrada. A amostra vem do beacon público (finalidade
+//! `audit_selection`), de modo que qualquer auditor reproduz o sorteio, e o
+//! plano e cada relatório ficam registrados no log. Com uma região no plano,
+//! o universo fica restrito aos votos registrados com seção dentro dela.
+//!
+//! As fórmulas seguem Stark, "Super-Simple Simultaneous Single-Ballot
+//! Risk-Limiting Audits" (comparação, com fator de inflação γ) e Lindeman,
+//! Stark e Yates, "BRAVO" (votação amostral), para o par vencedor e
+//! segundo colocado, que tem a menor margem.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::services::regions::RegionScope;
+use crate::services::beacon::{derive_seed, purposes, RandomnessBeacon, SeededStream};
+use crate::transparency::election_logs::{
+    ElectionEvent, ElectionEventType, ElectionTransparencyLog, SearchCriteria
+};
+
+/// Fator de inflação do erro na auditoria por comparação
+pub const DEFAULT_GAMMA: f64 = 1.03905;
+
+/// Método da auditoria
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditMethod {
+    BallotComparison,
+    BallotPolling,
+}
+
+/// Apuração oficial do cargo auditado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportedContest {
+    pub contest: String,
+    pub ballots_cast: u64,
+    /// Votos por votável
+    pub votes: BTreeMap<String, u64>,
+}
+
+impl ReportedContest {
+    /// Vencedor e segundo colocado declarados
+    pub fn leaders(&self) -> Result<(String, u64, String, u64)> {
+        let mut ranked: Vec<(&String, &u64)> = self.votes.iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        match ranked.as_slice() {
+            [(winner, w), (runner_up, l), ..] if w > l => {
+                Ok(((*winner).clone(), **w, (*runner_up).clone(), **l))
+            }
+            [_, _, ..] => Err(anyhow!("Contest {} has no margin to audit", self.contest)),
+            _ => Err(anyhow!("Contest {} needs at least two choices", self.contest)),
+        }
+    }
+}
+
+/// Parâmetros do planejamento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanRequest {
+    pub contest: ReportedContest,
+    pub method: AuditMethod,
+    /// Limite de risco (ex.: 0.05)
+    pub risk_limit: f64,
+    /// Taxa esperada de sobrecontagens de 1 voto, por cédula (comparação)
+    #[serde(default)]
+    pub expected_overstatement_rate: f64,
+    /// Recorte da hierarquia auditado (`SP`, `SP/71072/0001`); sem ele, a eleição inteira
+    #[serde(default)]
+    pub region: Option<RegionScope>,
+}
+
+/// Plano de auditoria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditPlan {
+    pub id: Uuid,
+    pub election_id: Uuid,
+    pub contest: ReportedContest,
+    pub method: AuditMethod,
+    pub risk_limit: f64,
+    pub winner: String,
+    pub runner_up: String,
+    #[serde(default)]
+    pub region: Option<RegionScope>,
+    /// Cédulas no universo auditado (entradas `VoteCast` do log)
+    pub manifest_size: u64,
+    /// Margem dividida pelo universo
+    pub diluted_margin: f64,
+    pub expected_sample_size: u64,
+    /// Índices das entradas do log sorteadas, na ordem do sorteio
+    pub sample: Vec<u64>,
+    pub seed: String,
+    pub draw_log_index: Option<u64>,
+    pub log_index: Option<u64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Cédula auditada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditedBallot {
+    /// Índice da entrada `VoteCast` no log
+    pub log_index: u64,
+    /// Interpretação do registro digital (obrigatória na comparação)
+    pub reported: Option<String>,
+    /// Interpretação da cédula física
+    pub observed: String,
+    pub auditor: String,
+    #[serde(default = "Utc::now")]
+    pub audited_at: DateTime<Utc>,
+}
+
+/// Divergências entre registro e cédula, em votos da margem
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscrepancyCounts {
+    pub one_vote_overstatements: u64,
+    pub two_vote_overstatements: u64,
+    pub one_vote_understatements: u64,
+    pub two_vote_understatements: u64,
+}
+
+/// Situação da auditoria
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RlaOutcome {
+    /// Risco medido dentro do limite: resultado confirmado
+    Confirmed,
+    /// Amostra ainda não concluída
+    InProgress,
+    /// Amostra concluída sem atingir o limite: ampliar
+    Escalate,
+    /// Todo o universo auditado sem atingir o limite: recontagem integral
+    FullHandCount,
+}
+
+/// Relatório estatístico da auditoria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RlaReport {
+    pub plan_id: Uuid,
+    pub election_id: Uuid,
+    pub contest: String,
+    pub method: AuditMethod,
+    pub risk_limit: f64,
+    pub sample_size: usize,
+    pub audited: usize,
+    /// Probabilidade máxima de o resultado declarado estar errado e a auditoria confirmá-lo
+    pub measured_risk: f64,
+    pub confidence: f64,
+    pub outcome: RlaOutcome,
+    pub discrepancies: DiscrepancyCounts,
+    pub winner_votes_observed: u64,
+    pub runner_up_votes_observed: u64,
+    pub plan_log_index: Option<u64>,
+    pub draw_log_index: Option<u64>,
+    pub log_index: Option<u64>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Tamanho de amostra esperado da auditoria por votação amostral (BRAVO)
+pub fn ballot_polling_sample_size(winner_votes: u64, runner_up_votes: u64, ballots: u64, risk_limit: f64) -> Result<u64> {
+    check_risk_limit(risk_limit)?;
+    let relevant = winner_votes + runner_up_votes;
+    if relevant == 0 || winner_votes <= runner_up_votes {
+        return Err(anyhow!("Ballot polling needs a reported winner with a positive margin"));
+    }
+
+    let share = winner_votes as f64 / relevant as f64;
+    let increment = share * (2.0 * share).ln() + (1.0 - share) * (2.0 * (1.0 - share)).ln();
+    let relevant_draws = (1.0 / risk_limit).ln() / increment;
+    let draws = relevant_draws * ballots as f64 / relevant as f64;
+    Ok((draws.ceil() as u64).min(ballots))
+}
+
+/// Tamanho de amostra esperado da auditoria por comparação, com a taxa de
+/// sobrecontagens de 1 voto esperada
+pub fn ballot_comparison_sample_size(
+    diluted_margin: f64,
+    risk_limit: f64,
+    gamma: f64,
+    overstatement_rate: f64,
+) -> Result<u64> {
+    check_risk_limit(risk_limit)?;
+    if diluted_margin <= 0.0 || diluted_margin > 2.0 * gamma {
+        return Err(anyhow!("Diluted margin {} out of range", diluted_margin));
+    }
+
+    let per_ballot = (1.0 - diluted_margin / (2.0 * gamma)).ln()
+        - overstatement_rate * (1.0 - 1.0 / (2.0 * gamma)).ln();
+    if per_ballot >= 0.0 {
+        return Err(anyhow!("Expected overstatement rate too high for margin {}", diluted_margin));
+    }
+    Ok((risk_limit.ln() / per_ballot).ceil() as u64)
+}
+
+/// Risco medido da auditoria por comparação (Kaplan–Markov)
+pub fn comparison_risk(diluted_margin: f64, gamma: f64, sample_size: u64, counts: &DiscrepancyCounts) -> f64 {
+    let log_risk = sample_size as f64 * (1.0 - diluted_margin / (2.0 * gamma)).ln()
+        - counts.one_vote_overstatements as f64 * (1.0 - 1.0 / (2.0 * gamma)).ln()
+        - counts.two_vote_overstatements as f64 * (1.0 - 1.0 / gamma).ln()
+        - counts.one_vote_understatements as f64 * (1.0 + 1.0 / (2.0 * gamma)).ln()
+        - counts.two_vote_understatements as f64 * (1.0 + 1.0 / gamma).ln();
+    log_risk.exp().min(1.0)
+}
+
+/// Risco medido da auditoria por votação amostral (BRAVO)
+pub fn polling_risk(reported_share: f64, winner_draws: u64, runner_up_draws: u64) -> f64 {
+    let log_statistic = winner_draws as f64 * (2.0 * reported_share).ln()
+        + runner_up_draws as f64 * (2.0 * (1.0 - reported_share)).ln();
+    (-log_statistic).exp().min(1.0)
+}
+
+fn check_risk_limit(risk_limit: f64) -> Result<()> {
+    if risk_limit <= 0.0 || risk_limit >= 1.0 {
+        return Err(anyhow!("Risk limit must be between 0 and 1, got {}", risk_limit));
+    }
+    Ok(())
+}
+
+/// Serviço de auditorias de limitação de risco
+pub struct RlaService {
+    transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
+    beacon: Arc<RandomnessBeacon>,
+    gamma: f64,
+    plans: RwLock<HashMap<Uuid, AuditPlan>>,
+    audited: RwLock<HashMap<Uuid, Vec<AuditedBallot>>>,
+}
+
+impl RlaService {
+    pub fn new(transparency_log: Arc<RwLock<ElectionTransparencyLog>>, beacon: Arc<RandomnessBeacon>) -> Self {
+        Self {
+            transparency_log,
+            beacon,
+            gamma: DEFAULT_GAMMA,
+            plans: RwLock::new(HashMap::new()),
+            audited: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Calcula a amostra, sorteia as cédulas pelo beacon e registra o plano no log
+    pub async fn plan(&self, election_id: Uuid, request: PlanRequest) -> Result<AuditPlan> {
+        let (winner, winner_votes, runner_up, runner_up_votes) = request.contest.leaders()?;
+        let manifest = self.manifest(election_id, request.region.as_ref()).await;
+        if manifest.is_empty() {
+            return Err(anyhow!("No VoteCast entries in the log for election {}", election_id));
+        }
+        let manifest_size = manifest.len() as u64;
+        if manifest_size != request.contest.ballots_cast {
+            log::warn!(
+                "RLA manifest for election {} has {} ballots, reported {}",
+                election_id, manifest_size, request.contest.ballots_cast
+            );
+        }
+
+        let diluted_margin = (winner_votes - runner_up_votes) as f64 / manifest_size as f64;
+        let expected_sample_size = match request.method {
+            AuditMethod::BallotComparison => ballot_comparison_sample_size(
+                diluted_margin, request.risk_limit, self.gamma, request.expected_overstatement_rate,
+            )?,
+            AuditMethod::BallotPolling => ballot_polling_sample_size(
+                winner_votes, runner_up_votes, manifest_size, request.risk_limit,
+            )?,
+        }
+        .min(manifest_size);
+
+        let draw = self.beacon.draw(election_id, purposes::AUDIT_SELECTION).await?;
+        let seed = derive_seed(
+            &draw.value.randomness,
+            election_id,
+            &format!("{}/{}", purposes::AUDIT_SELECTION, request.contest.contest),
+        );
+        let sample = SeededStream::new(seed)
+            .sample_indices(manifest.len(), expected_sample_size as usize)
+            .into_iter()
+            .map(|i| manifest[i])
+            .collect();
+
+        let mut plan = AuditPlan {
+            id: Uuid::new_v4(),
+            election_id,
+            contest: request.contest,
+            method: request.method,
+            risk_limit: request.risk_limit,
+            winner,
+            runner_up,
+            region: request.region,
+            manifest_size,
+            diluted_margin,
+            expected_sample_size,
+            sample,
+            seed: hex::encode(seed),
+            draw_log_index: draw.log_index,
+            log_index: None,
+            created_at: Utc::now(),
+        };
+        plan.log_index = Some(self.log_event(election_id, ElectionEventType::AuditTriggered, serde_json::json!({
+            "kind": "rla_plan",
+            "plan_id": plan.id,
+            "contest": plan.contest.contest,
+            "region": plan.region,
+            "method": plan.method,
+            "risk_limit": plan.risk_limit,
+            "diluted_margin": plan.diluted_margin,
+            "expected_sample_size": plan.expected_sample_size,
+            "manifest_size": plan.manifest_size,
+            "seed": plan.seed,
+            "draw_log_index": plan.draw_log_index,
+        })).await?);
+
+        self.plans.write().await.insert(plan.id, plan.clone());
+        Ok(plan)
+    }
+
+    /// Amplia a amostra; as cédulas já sorteadas continuam no início
+    pub async fn escalate(&self, plan_id: Uuid, additional: u64) -> Result<AuditPlan> {
+        let manifest = {
+            let plans = self.plans.read().await;
+            let plan = plans.get(&plan_id).ok_or_else(|| anyhow!("Audit plan {} not found", plan_id))?;
+            self.manifest(plan.election_id).await
+        };
+
+        let mut plans = self.plans.write().await;
+        let plan = plans.get_mut(&plan_id).ok_or_else(|| anyhow!("Audit plan {} not found", plan_id))?;
+        let seed: [u8; 32] = hex::decode(&plan.seed)?
+            .try_into()
+            .map_err(|_| anyhow!("Invalid seed for plan {}", plan_id))?;
+        let size = (plan.sample.len() as u64 + additional).min(plan.manifest_size) as usize;
+        plan.sample = SeededStream::new(seed)
+            .sample_indices(plan.manifest_size as usize, size)
+            .into_iter()
+            .map(|i| manifest[i])
+            .collect();
+        Ok(plan.clone())
+    }
+
+    /// Registra a interpretação de uma cédula da amostra
+    pub async fn record(&self, plan_id: Uuid, ballot: AuditedBallot) -> Result<()> {
+        let plans = self.plans.read().await;
+        let plan = plans.get(&plan_id).ok_or_else(|| anyhow!("Audit plan {} not found", plan_id))?;
+        if !plan.sample.contains(&ballot.log_index) {
+            return Err(anyhow!("Ballot {} is not in the sample of plan {}", ballot.log_index, plan_id));
+        }
+        if plan.method == AuditMethod::BallotComparison && ballot.reported.is_none() {
+            return Err(anyhow!("Ballot comparison requires the reported interpretation"));
+        }
+
+        let mut audited = self.audited.write().await;
+        let ballots = audited.entry(plan_id).or_default();
+        if ballots.iter().any(|b| b.log_index == ballot.log_index) {
+            return Err(anyhow!("Ballot {} already audited", ballot.log_index));
+        }
+        ballots.push(ballot);
+        Ok(())
+    }
+
+    /// Mede o risco com as cédulas auditadas e registra o relatório no log
+    pub async fn report(&self, plan_id: Uuid) -> Result<RlaReport> {
+        let plan = self.plans.read().await.get(&plan_id).cloned()
+            .ok_or_else(|| anyhow!("Audit plan {} not found", plan_id))?;
+        let audited = self.audited.read().await.get(&plan_id).cloned().unwrap_or_default();
+
+        let mut report = evaluate(&plan, &audited, self.gamma);
+        report.log_index = Some(self.log_event(plan.election_id, ElectionEventType::RiskLimitingAuditReport, serde_json::json!({
+            "plan_id": plan.id,
+            "plan_log_index": plan.log_index,
+            "audited": report.audited,
+            "measured_risk": report.measured_risk,
+            "confidence": report.confidence,
+            "outcome": report.outcome,
+            "discrepancies": report.discrepancies,
+        })).await?);
+        Ok(report)
+    }
+
+    pub async fn get_plan(&self, plan_id: Uuid) -> Option<AuditPlan> {
+        self.plans.read().await.get(&plan_id).cloned()
+    }
+
+    pub async fn audited_ballots(&self, plan_id: Uuid) -> Vec<AuditedBallot> {
+        self.audited.read().await.get(&plan_id).cloned().unwrap_or_default()
+    }
+
+    /// Índices das entradas `VoteCast` da eleição na região, na ordem do log
+    async fn manifest(&self, election_id: Uuid, region: Option<&RegionScope>) -> Vec<u64> {
+        let log = self.transparency_log.read().await;
+        log.search_events(SearchCriteria {
+            event_type: Some(ElectionEventType::VoteCast),
+            start_time: None,
+            end_time: None,
+            election_id: Some(election_id.to_string()),
+            verification_status: None,
+            region: region.cloned(),
+        })
+        .map(|entries| entries.iter().map(|e| e.index).collect())
+        .unwrap_or_default()
+    }
+
+    async fn log_event(&self, election_id: Uuid, event_type: ElectionEventType, data: serde_json::Value) -> Result<u64> {
+        let proof = self.transparency_log.write().await.append_election_event(ElectionEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type,
+            election_id: election_id.to_string(),
+            data,
+            timestamp: Utc::now(),
+            source: "RLA".to_string(),
+        })?;
+        Ok(proof.log_index)
+    }
+}
+
+/// Mede o risco do plano com as cédulas auditadas
+pub fn evaluate(plan: &AuditPlan, audited: &[AuditedBallot], gamma: f64) -> RlaReport {
+    // Contribuição de uma escolha para a margem vencedor − segundo colocado
+    let contribution = |choice: &str| -> i64 {
+        if choice == plan.winner { 1 } else if choice == plan.runner_up { -1 } else { 0 }
+    };
+
+    let mut discrepancies = DiscrepancyCounts::default();
+    let mut winner_votes_observed = 0;
+    let mut runner_up_votes_observed = 0;
+    for ballot in audited {
+        match contribution(&ballot.observed) {
+            1 => winner_votes_observed += 1,
+            -1 => runner_up_votes_observed += 1,
+            _ => {}
+        }
+        if let Some(reported) = &ballot.reported {
+            match contribution(reported) - contribution(&ballot.observed) {
+                1 => discrepancies.one_vote_overstatements += 1,
+                2 => discrepancies.two_vote_overstatements += 1,
+                -1 => discrepancies.one_vote_understatements += 1,
+                -2 => discrepancies.two_vote_understatements += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let measured_risk = match plan.method {
+        AuditMethod::BallotComparison => {
+            comparison_risk(plan.diluted_margin, gamma, audited.len() as u64, &discrepancies)
+        }
+        AuditMethod::BallotPolling => {
+            let winner = plan.contest.votes.get(&plan.winner).copied().unwrap_or(0) as f64;
+            let runner_up = plan.contest.votes.get(&plan.runner_up).copied().unwrap_or(0) as f64;
+            polling_risk(winner / (winner + runner_up), winner_votes_observed, runner_up_votes_observed)
+        }
+    };
+
+    let outcome = if measured_risk <= plan.risk_limit {
+        RlaOutcome::Confirmed
+    } else if audited.len() < plan.sample.len() {
+        RlaOutcome::InProgress
+    } else if plan.sample.len() as u64 >= plan.manifest_size {
+        RlaOutcome::FullHandCount
+    } else {
+        RlaOutcome::Escalate
+    };
+
+    RlaReport {
+        plan_id: plan.id,
+        election_id: plan.election_id,
+        contest: plan.contest.contest.clone(),
+        method: plan.method,
+        risk_limit: plan.risk_limit,
+        sample_size: plan.sample.len(),
+        audited: audited.len(),
+        measured_risk,
+        confidence: 1.0 - measured_risk,
+        outcome,
+        discrepancies,
+        winner_votes_observed,
+        runner_up_votes_observed,
+        plan_log_index: plan.log_index,
+        draw_log_index: plan.draw_log_index,
+        log_index: None,
+        generated_at: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(method: AuditMethod, sample: u64) -> AuditPlan {
+        AuditPlan {
+            id: Uuid::new_v4(),
+            election_id: Uuid::new_v4(),
+            contest: ReportedContest {
+                contest: "prefeito".to_string(),
+                ballots_cast: 10_000,
+                votes: BTreeMap::from([("13".to_string(), 5_500), ("22".to_string(), 4_500)]),
+            },
+            method,
+            risk_limit: 0.05,
+            winner: "13".to_string(),
+            runner_up: "22".to_string(),
+            region: None,
+            manifest_size: 10_000,
+            diluted_margin: 0.1,
+            expected_sample_size: sample,
+            sample: (0..sample).collect(),
+            seed: String::new(),
+            draw_log_index: None,
+            log_index: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn ballot(index: u64, reported: Option<&str>, observed: &str) -> AuditedBallot {
+        AuditedBallot {
+            log_index: index,
+            reported: reported.map(str::to_string),
+            observed: observed.to_string(),
+            auditor: "auditor-1".to_string(),
+            audited_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_sample_sizes_shrink_with_margin_and_risk_limit() {
+        // 10% de margem diluída e α = 5%: ln(α)/ln(1 − μ/2γ) ≈ 61 cédulas
+        let comparison = ballot_comparison_sample_size(0.1, 0.05, DEFAULT_GAMMA, 0.0).unwrap();
+        assert!((60..=64).contains(&comparison));
+        assert!(ballot_comparison_sample_size(0.2, 0.05, DEFAULT_GAMMA, 0.0).unwrap() < comparison);
+        assert!(ballot_comparison_sample_size(0.1, 0.05, DEFAULT_GAMMA, 0.001).unwrap() > comparison);
+
+        let polling = ballot_polling_sample_size(5_500, 4_500, 10_000, 0.05).unwrap();
+        assert!(polling > comparison);
+        assert!(ballot_polling_sample_size(5_500, 4_500, 10_000, 0.10).unwrap() < polling);
+        assert!(ballot_polling_sample_size(5_000, 5_000, 10_000, 0.05).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_confirms_clean_sample_and_escalates_on_overstatements() {
+        let comparison = plan(AuditMethod::BallotComparison, 62);
+        let clean: Vec<AuditedBallot> = (0..62).map(|i| ballot(i, Some("13"), "13")).collect();
+        let report = evaluate(&comparison, &clean, DEFAULT_GAMMA);
+        assert_eq!(report.outcome, RlaOutcome::Confirmed);
+        assert!(report.measured_risk <= 0.05);
+
+        let mut with_errors = clean.clone();
+        with_errors[0] = ballot(0, Some("13"), "22");
+        with_errors[1] = ballot(1, Some("13"), "nulo");
+        let report = evaluate(&comparison, &with_errors, DEFAULT_GAMMA);
+        assert_eq!(report.discrepancies.two_vote_overstatements, 1);
+        assert_eq!(report.discrepancies.one_vote_overstatements, 1);
+        assert_eq!(report.outcome, RlaOutcome::Escalate);
+
+        let partial = evaluate(&comparison, &clean[..10], DEFAULT_GAMMA);
+        assert_eq!(partial.outcome, RlaOutcome::InProgress);
+    }
+}
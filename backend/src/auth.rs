@@ -3,3 +3,4 @@
 pub mod jwt;
 pub mod biometric;
 pub mod certificate;
+pub mod rbac;
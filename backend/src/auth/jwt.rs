@@ -1,11 +1,33 @@
 //! Módulo JWT do FORTIS
+//!
+//! Além dos tokens avulsos, mantém sessões para os consoles administrativos:
+//! o login entrega um access token curto e um refresh token. Cada renovação
+//! troca o refresh token (o anterior deixa de valer) e estende a sessão pelo
+//! tempo de inatividade permitido, sem passar da duração máxima. Reapresentar
+//! um refresh token já trocado indica vazamento e encerra a sessão inteira.
+//! Tokens revogados no logout ficam numa lista de bloqueio até expirarem;
+//! sessões e lista ficam no Redis quando configurado, para valerem em todos
+//! os nós.
 
+use actix_web::http::header::{HeaderMap, AUTHORIZATION};
 use jsonwebtoken::{encode, decode, Header, Algorithm, Validation, EncodingKey, DecodingKey};
 use serde::{Deserialize, Serialize};
-use chrono::{Duration, Utc};
-use anyhow::Result;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{OnceCell, RwLock};
 use uuid::Uuid;
 
+/// Tipo do token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    #[default]
+    Access,
+    Refresh,
+}
+
 /// Claims do JWT
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
@@ -19,14 +41,84 @@ pub struct Claims {
     pub voter_id: Option<String>,
     pub zone: Option<String>,
     pub section: Option<String>,
+    #[serde(default)]
+    pub token_type: TokenType,
+    /// Sessão à qual o token pertence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+/// Validade dos tokens e das sessões
+#[derive(Debug, Clone)]
+pub struct SessionPolicy {
+    pub access_ttl_minutes: i64,
+    /// Inatividade que encerra a sessão; cada renovação reinicia a contagem
+    pub idle_timeout_minutes: i64,
+    /// Duração máxima da sessão, mesmo com renovações
+    pub max_session_hours: i64,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        Self {
+            access_ttl_minutes: 15,
+            idle_timeout_minutes: 120,
+            max_session_hours: 12,
+        }
+    }
+}
+
+/// Sessão aberta no login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub session_id: String,
+    pub subject: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: DateTime<Utc>,
+    /// Fim por inatividade, estendido a cada renovação
+    pub expires_at: DateTime<Utc>,
+    pub absolute_expires_at: DateTime<Utc>,
+    /// Único refresh token aceito na próxima renovação
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    refresh_jti: String,
+}
+
+/// Tokens entregues no login e em cada renovação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    /// Validade do access token em segundos
+    pub expires_in: i64,
+    pub session_id: String,
+    pub session_expires_at: DateTime<Utc>,
 }
 
+/// Onde sessões e tokens revogados são mantidos
+enum SessionBackend {
+    Memory {
+        sessions: RwLock<HashMap<String, Session>>,
+        /// jti revogado e sua expiração
+        revoked: RwLock<HashMap<String, DateTime<Utc>>>,
+    },
+    Redis {
+        client: redis::Client,
+        connection: OnceCell<redis::aio::MultiplexedConnection>,
+    },
+}
+
+const REDIS_PREFIX: &str = "fortis:auth";
+
 /// Serviço JWT
 #[derive(Clone)]
 pub struct JwtService {
     secret: String,
     issuer: String,
     audience: String,
+    policy: SessionPolicy,
+    backend: Arc<SessionBackend>,
 }
 
 impl JwtService {
@@ -35,9 +127,25 @@ impl JwtService {
             secret: secret.to_string(),
             issuer: issuer.to_string(),
             audience: audience.to_string(),
+            policy: SessionPolicy::default(),
+            backend: Arc::new(SessionBackend::Memory {
+                sessions: RwLock::new(HashMap::new()),
+                revoked: RwLock::new(HashMap::new()),
+            }),
         }
     }
 
+    pub fn with_session_policy(mut self, policy: SessionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sessões e lista de bloqueio no Redis, compartilhadas entre os nós
+    pub fn with_redis(mut self, client: redis::Client) -> Self {
+        self.backend = Arc::new(SessionBackend::Redis { client, connection: OnceCell::new() });
+        self
+    }
+
     /// Gerar token JWT
     pub fn generate_token(&self, cpf: &str, name: &str) -> Result<String> {
         let now = Utc::now();
@@ -52,6 +160,8 @@ impl JwtService {
             voter_id: None,
             zone: None,
             section: None,
+            token_type: TokenType::Access,
+            sid: None,
         };
 
         let header = Header::new(Algorithm::HS256);
@@ -75,6 +185,8 @@ impl JwtService {
             voter_id: Some(voter_id.to_string()),
             zone: Some(zone.to_string()),
             section: Some(section.to_string()),
+            token_type: TokenType::Access,
+            sid: None,
         };
 
         let header = Header::new(Algorithm::HS256);
@@ -115,6 +227,8 @@ impl JwtService {
             voter_id: old_claims.voter_id.clone(),
             zone: old_claims.zone.clone(),
             section: old_claims.section.clone(),
+            token_type: TokenType::Access,
+            sid: old_claims.sid.clone(),
         };
 
         let header = Header::new(Algorithm::HS256);
@@ -144,4 +258,340 @@ impl JwtService {
         let now = Utc::now().timestamp();
         Ok(claims.exp - now)
     }
-}
\ No newline at end of file
+
+    /// Abre uma sessão e entrega o primeiro par de tokens
+    pub async fn start_session(&self, subject: &str, name: &str) -> Result<TokenPair> {
+        let now = Utc::now();
+        let absolute_expires_at = now + Duration::hours(self.policy.max_session_hours);
+        let mut session = Session {
+            session_id: Uuid::new_v4().to_string(),
+            subject: subject.to_string(),
+            name: name.to_string(),
+            created_at: now,
+            last_refreshed_at: now,
+            expires_at: (now + Duration::minutes(self.policy.idle_timeout_minutes)).min(absolute_expires_at),
+            absolute_expires_at,
+            refresh_jti: String::new(),
+        };
+        let pair = self.issue_pair(&mut session)?;
+        self.backend.put_session(&session).await?;
+        log::info!("Session {} opened for {}", session.session_id, subject);
+        Ok(pair)
+    }
+
+    /// Troca o refresh token por um novo par e estende a sessão
+    pub async fn refresh_session(&self, refresh_token: &str) -> Result<TokenPair> {
+        let claims = self.validate_token(refresh_token)?;
+        if claims.token_type != TokenType::Refresh || self.is_expired(&claims) {
+            return Err(anyhow!("Refresh token inválido ou expirado"));
+        }
+        let sid = claims.sid.as_deref().ok_or_else(|| anyhow!("Refresh token sem sessão"))?;
+        let mut session = self.backend.get_session(sid).await?
+            .ok_or_else(|| anyhow!("Sessão encerrada ou expirada"))?;
+
+        if session.refresh_jti != claims.jti {
+            log::warn!("Refresh token reuse on session {} of {}; revoking session", sid, session.subject);
+            self.backend.delete_session(&session).await?;
+            return Err(anyhow!("Refresh token já utilizado; sessão encerrada"));
+        }
+
+        let now = Utc::now();
+        session.last_refreshed_at = now;
+        session.expires_at = (now + Duration::minutes(self.policy.idle_timeout_minutes))
+            .min(session.absolute_expires_at);
+        let pair = self.issue_pair(&mut session)?;
+        self.backend.put_session(&session).await?;
+        Ok(pair)
+    }
+
+    /// Valida um access token: assinatura, validade, lista de bloqueio e sessão ativa
+    pub async fn authenticate(&self, token: &str) -> Result<Claims> {
+        let claims = self.validate_token(token)?;
+        if claims.token_type != TokenType::Access || self.is_expired(&claims) {
+            return Err(anyhow!("Token inválido ou expirado"));
+        }
+        if self.backend.is_revoked(&claims.jti).await? {
+            return Err(anyhow!("Token revogado"));
+        }
+        if let Some(sid) = &claims.sid {
+            if self.backend.get_session(sid).await?.is_none() {
+                return Err(anyhow!("Sessão encerrada ou expirada"));
+            }
+        }
+        Ok(claims)
+    }
+
+    /// Bloqueia o token até sua expiração
+    pub async fn revoke_token(&self, claims: &Claims) -> Result<()> {
+        let expires_at = Utc.timestamp_opt(claims.exp, 0).single().unwrap_or_else(Utc::now);
+        self.backend.revoke(&claims.jti, expires_at).await
+    }
+
+    /// Encerra o token apresentado e a sessão dele; com `all_sessions`, todas as do sujeito
+    pub async fn logout(&self, claims: &Claims, all_sessions: bool) -> Result<usize> {
+        self.revoke_token(claims).await?;
+        let sessions = self.backend.sessions_of(&claims.sub).await?;
+        let mut ended = 0;
+        for session in sessions.iter().filter(|s| all_sessions || claims.sid.as_deref() == Some(&s.session_id)) {
+            self.backend.delete_session(session).await?;
+            ended += 1;
+        }
+        log::info!("{} logged out ({} session(s) ended)", claims.sub, ended);
+        Ok(ended)
+    }
+
+    /// Encerra uma sessão do sujeito
+    pub async fn end_session(&self, subject: &str, session_id: &str) -> Result<()> {
+        let session = self.backend.get_session(session_id).await?
+            .filter(|s| s.subject == subject)
+            .ok_or_else(|| anyhow!("Sessão não encontrada"))?;
+        self.backend.delete_session(&session).await
+    }
+
+    /// Sessões ativas do sujeito
+    pub async fn sessions_of(&self, subject: &str) -> Result<Vec<Session>> {
+        let mut sessions = self.backend.sessions_of(subject).await?;
+        for session in &mut sessions {
+            // O jti do refresh token não sai do serviço
+            session.refresh_jti.clear();
+        }
+        sessions.sort_by(|a, b| b.last_refreshed_at.cmp(&a.last_refreshed_at));
+        Ok(sessions)
+    }
+
+    fn issue_pair(&self, session: &mut Session) -> Result<TokenPair> {
+        let now = Utc::now();
+        let access_exp = (now + Duration::minutes(self.policy.access_ttl_minutes)).min(session.absolute_expires_at);
+        let access_token = self.sign(&self.session_claims(session, TokenType::Access, access_exp.timestamp()))?;
+
+        let refresh_claims = self.session_claims(session, TokenType::Refresh, session.expires_at.timestamp());
+        session.refresh_jti = refresh_claims.jti.clone();
+        let refresh_token = self.sign(&refresh_claims)?;
+
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: access_exp.timestamp() - now.timestamp(),
+            session_id: session.session_id.clone(),
+            session_expires_at: session.expires_at,
+        })
+    }
+
+    fn session_claims(&self, session: &Session, token_type: TokenType, exp: i64) -> Claims {
+        Claims {
+            sub: session.subject.clone(),
+            name: session.name.clone(),
+            exp,
+            iat: Utc::now().timestamp(),
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
+            jti: Uuid::new_v4().to_string(),
+            voter_id: None,
+            zone: None,
+            section: None,
+            token_type,
+            sid: Some(session.session_id.clone()),
+        }
+    }
+
+    fn sign(&self, claims: &Claims) -> Result<String> {
+        let key = EncodingKey::from_secret(self.secret.as_ref());
+        encode(&Header::new(Algorithm::HS256), claims, &key)
+            .map_err(|e| anyhow!("Erro ao gerar token: {}", e))
+    }
+}
+
+/// Token do cabeçalho `Authorization: Bearer`
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers.get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+impl SessionBackend {
+    async fn redis_connection(
+        client: &redis::Client,
+        connection: &OnceCell<redis::aio::MultiplexedConnection>,
+    ) -> Result<redis::aio::MultiplexedConnection> {
+        Ok(connection
+            .get_or_try_init(|| client.get_multiplexed_tokio_connection())
+            .await?
+            .clone())
+    }
+
+    async fn put_session(&self, session: &Session) -> Result<()> {
+        match self {
+            SessionBackend::Memory { sessions, .. } => {
+                sessions.write().await.insert(session.session_id.clone(), session.clone());
+                Ok(())
+            }
+            SessionBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                let ttl = (session.expires_at - Utc::now()).num_seconds().max(1);
+                let mut pipe = redis::pipe();
+                pipe.cmd("SET")
+                    .arg(format!("{}:session:{}", REDIS_PREFIX, session.session_id))
+                    .arg(serde_json::to_string(session)?)
+                    .arg("EX")
+                    .arg(ttl)
+                    .ignore()
+                    .cmd("SADD")
+                    .arg(format!("{}:sessions:{}", REDIS_PREFIX, session.subject))
+                    .arg(&session.session_id)
+                    .ignore();
+                pipe.query_async::<_, ()>(&mut conn).await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn get_session(&self, session_id: &str) -> Result<Option<Session>> {
+        let session = match self {
+            SessionBackend::Memory { sessions, .. } => sessions.read().await.get(session_id).cloned(),
+            SessionBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                let value: Option<String> = redis::cmd("GET")
+                    .arg(format!("{}:session:{}", REDIS_PREFIX, session_id))
+                    .query_async(&mut conn)
+                    .await?;
+                value.map(|v| serde_json::from_str(&v)).transpose()?
+            }
+        };
+        Ok(session.filter(|s| Utc::now() < s.expires_at))
+    }
+
+    async fn delete_session(&self, session: &Session) -> Result<()> {
+        match self {
+            SessionBackend::Memory { sessions, .. } => {
+                sessions.write().await.remove(&session.session_id);
+                Ok(())
+            }
+            SessionBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                let mut pipe = redis::pipe();
+                pipe.cmd("DEL")
+                    .arg(format!("{}:session:{}", REDIS_PREFIX, session.session_id))
+                    .ignore()
+                    .cmd("SREM")
+                    .arg(format!("{}:sessions:{}", REDIS_PREFIX, session.subject))
+                    .arg(&session.session_id)
+                    .ignore();
+                pipe.query_async::<_, ()>(&mut conn).await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn sessions_of(&self, subject: &str) -> Result<Vec<Session>> {
+        let now = Utc::now();
+        match self {
+            SessionBackend::Memory { sessions, .. } => {
+                let mut sessions = sessions.write().await;
+                sessions.retain(|_, s| now < s.expires_at);
+                Ok(sessions.values().filter(|s| s.subject == subject).cloned().collect())
+            }
+            SessionBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                let index = format!("{}:sessions:{}", REDIS_PREFIX, subject);
+                let ids: Vec<String> = redis::cmd("SMEMBERS").arg(&index).query_async(&mut conn).await?;
+
+                let mut active = Vec::new();
+                for id in ids {
+                    match self.get_session(&id).await? {
+                        Some(session) => active.push(session),
+                        // Sessão expirada pelo TTL: remove do índice
+                        None => redis::cmd("SREM").arg(&index).arg(&id).query_async::<_, ()>(&mut conn).await?,
+                    }
+                }
+                Ok(active)
+            }
+        }
+    }
+
+    async fn revoke(&self, jti: &str, expires_at: DateTime<Utc>) -> Result<()> {
+        let now = Utc::now();
+        if expires_at <= now {
+            return Ok(());
+        }
+        match self {
+            SessionBackend::Memory { revoked, .. } => {
+                let mut revoked = revoked.write().await;
+                revoked.retain(|_, exp| *exp > now);
+                revoked.insert(jti.to_string(), expires_at);
+                Ok(())
+            }
+            SessionBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                redis::cmd("SET")
+                    .arg(format!("{}:revoked:{}", REDIS_PREFIX, jti))
+                    .arg(1)
+                    .arg("EX")
+                    .arg((expires_at - now).num_seconds().max(1))
+                    .query_async::<_, ()>(&mut conn)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn is_revoked(&self, jti: &str) -> Result<bool> {
+        match self {
+            SessionBackend::Memory { revoked, .. } => {
+                Ok(revoked.read().await.get(jti).is_some_and(|exp| *exp > Utc::now()))
+            }
+            SessionBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                let exists: bool = redis::cmd("EXISTS")
+                    .arg(format!("{}:revoked:{}", REDIS_PREFIX, jti))
+                    .query_async(&mut conn)
+                    .await?;
+                Ok(exists)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> JwtService {
+        JwtService::new("test-secret", "fortis-voting-system", "fortis-voters")
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_and_reuse_ends_session() {
+        let jwt = service();
+        let first = jwt.start_session("12345678909", "Admin").await.unwrap();
+        assert!(jwt.authenticate(&first.access_token).await.is_ok());
+        // Refresh token não serve como access token
+        assert!(jwt.authenticate(&first.refresh_token).await.is_err());
+
+        let second = jwt.refresh_session(&first.refresh_token).await.unwrap();
+        assert_eq!(second.session_id, first.session_id);
+
+        // Reapresentar o refresh token trocado encerra a sessão
+        assert!(jwt.refresh_session(&first.refresh_token).await.is_err());
+        assert!(jwt.refresh_session(&second.refresh_token).await.is_err());
+        assert!(jwt.authenticate(&second.access_token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_logout_revokes_token_and_session() {
+        let jwt = service();
+        let console = jwt.start_session("12345678909", "Admin").await.unwrap();
+        let laptop = jwt.start_session("12345678909", "Admin").await.unwrap();
+        assert_eq!(jwt.sessions_of("12345678909").await.unwrap().len(), 2);
+
+        let claims = jwt.authenticate(&console.access_token).await.unwrap();
+        assert_eq!(jwt.logout(&claims, false).await.unwrap(), 1);
+        assert!(jwt.authenticate(&console.access_token).await.is_err());
+        assert!(jwt.refresh_session(&console.refresh_token).await.is_err());
+        assert!(jwt.authenticate(&laptop.access_token).await.is_ok());
+
+        let claims = jwt.authenticate(&laptop.access_token).await.unwrap();
+        jwt.logout(&claims, true).await.unwrap();
+        assert!(jwt.sessions_of("12345678909").await.unwrap().is_empty());
+    }
+}
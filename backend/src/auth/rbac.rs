@@ -0,0 +1,439 @@
+//! Controle de acesso baseado em papéis (RBAC)
+//!
+//! Cada sujeito (CPF do token JWT ou identificador da urna no mTLS) recebe
+//! papéis, globais ou restritos a uma eleição. Os papéis dão permissões
+//! fixas; os handlers exigem a permissão da operação pelo extrator
+//! `Principal`. As atribuições ficam no `RbacService`, de modo que uma
+//! revogação vale na requisição seguinte, sem esperar o token expirar.
+//!
+//! Um papel pode ainda ser restrito a uma região (UF, município ou zona).
+//! Papéis regionais não passam em `require`/`require_for`: só valem nas
+//! rotas que filtram os dados pelo recorte, via `require_scope`.
+
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use tokio::sync::RwLock;
+
+use fortis_domain::region::RegionScope;
+
+use crate::errors::FortisError;
+use crate::services::support_access::SupportGrant;
+use crate::services::urna::auth::DeviceIdentity;
+
+/// Papéis do sistema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    TseAdmin,
+    ElectionOfficial,
+    Auditor,
+    Urna,
+    Observer,
+}
+
+/// Permissões exigidas pelas rotas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ManageElections,
+    TransitionElections,
+    ManageCandidates,
+    RecordResults,
+    PublishResults,
+    CastVotes,
+    ReadAudits,
+    ManageAudits,
+    RecordAuditBallots,
+    ManageTransparencyLog,
+    ManageRoles,
+    ManageSupportAccess,
+    /// Rollout dos shards e exercícios de DR
+    ManageDeployment,
+    /// Relatórios de vulnerabilidade e incidentes de segurança
+    ManageSecurity,
+    /// Registro e revogação de consentimento biométrico do eleitor
+    ManageConsent,
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [Role::TseAdmin, Role::ElectionOfficial, Role::Auditor, Role::Urna, Role::Observer];
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            Role::TseAdmin => &[
+                ManageElections, TransitionElections, ManageCandidates, RecordResults,
+                PublishResults, ReadAudits, ManageAudits, ManageTransparencyLog, ManageRoles,
+                ManageSupportAccess, ManageDeployment, ManageSecurity, ManageConsent,
+            ],
+            Role::ElectionOfficial => &[TransitionElections, ManageCandidates, RecordResults, ReadAudits, ManageConsent],
+            Role::Auditor => &[ReadAudits, ManageAudits, RecordAuditBallots],
+            Role::Urna => &[CastVotes, RecordResults],
+            Role::Observer => &[ReadAudits],
+        }
+    }
+
+    pub fn grants(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+/// Papel concedido a um sujeito; sem eleição ou região, vale para todas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleGrant {
+    pub role: Role,
+    pub election_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<RegionScope>,
+}
+
+impl RoleGrant {
+    fn covers(&self, permission: Permission, election_id: Option<&str>) -> bool {
+        if !self.role.grants(permission) || self.region.is_some() {
+            return false;
+        }
+        match (&self.election_id, election_id) {
+            (None, _) => true,
+            (Some(scope), Some(election_id)) => scope == election_id,
+            (Some(_), None) => false,
+        }
+    }
+
+    /// O papel alcança um dado da eleição e região informadas; dado sem
+    /// região fica fora de papel regional
+    fn reaches(&self, election_id: Option<&str>, region: Option<&RegionScope>) -> bool {
+        let election = match &self.election_id {
+            None => true,
+            Some(scope) => election_id == Some(scope.as_str()),
+        };
+        let region = match &self.region {
+            None => true,
+            Some(scope) => region.is_some_and(|region| scope.covers(region)),
+        };
+        election && region
+    }
+}
+
+/// Papéis que concedem a permissão, usados para filtrar os dados devolvidos
+#[derive(Debug, Clone, Default)]
+pub struct DataScope {
+    grants: Vec<RoleGrant>,
+}
+
+impl DataScope {
+    pub fn is_unrestricted(&self) -> bool {
+        self.grants.iter().any(|grant| grant.election_id.is_none() && grant.region.is_none())
+    }
+
+    /// Algum dos papéis alcança o dado
+    pub fn allows(&self, election_id: Option<&str>, region: Option<&RegionScope>) -> bool {
+        self.grants.iter().any(|grant| grant.reaches(election_id, region))
+    }
+
+    /// Recortes regionais alcançados, para exibição; vazio quando irrestrito
+    pub fn regions(&self) -> Vec<RegionScope> {
+        if self.is_unrestricted() {
+            return Vec::new();
+        }
+        self.grants.iter().filter_map(|grant| grant.region.clone()).collect()
+    }
+}
+
+/// Atribuição registrada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleAssignment {
+    pub subject: String,
+    pub grant: RoleGrant,
+    pub assigned_by: String,
+    pub assigned_at: DateTime<Utc>,
+}
+
+/// Atribuições de papéis por sujeito
+pub struct RbacService {
+    assignments: RwLock<HashMap<String, Vec<RoleAssignment>>>,
+}
+
+impl RbacService {
+    pub fn new() -> Self {
+        Self {
+            assignments: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Administradores iniciais, que concedem os demais papéis
+    pub fn with_bootstrap_admins(self, subjects: &[String]) -> Self {
+        let now = Utc::now();
+        let assignments = subjects.iter()
+            .map(|subject| (subject.clone(), vec![RoleAssignment {
+                subject: subject.clone(),
+                grant: RoleGrant { role: Role::TseAdmin, election_id: None, region: None },
+                assigned_by: "bootstrap".to_string(),
+                assigned_at: now,
+            }]))
+            .collect();
+        Self {
+            assignments: RwLock::new(assignments),
+        }
+    }
+
+    pub async fn assign(&self, subject: &str, grant: RoleGrant, assigned_by: &str) -> Result<RoleAssignment> {
+        if grant.role == Role::Urna {
+            return Err(anyhow!("Urna role comes from the device certificate and cannot be assigned"));
+        }
+        let mut assignments = self.assignments.write().await;
+        let entries = assignments.entry(subject.to_string()).or_default();
+        if let Some(existing) = entries.iter().find(|a| a.grant == grant) {
+            return Ok(existing.clone());
+        }
+
+        let assignment = RoleAssignment {
+            subject: subject.to_string(),
+            grant,
+            assigned_by: assigned_by.to_string(),
+            assigned_at: Utc::now(),
+        };
+        entries.push(assignment.clone());
+        log::info!("Role {:?} assigned to {} by {}", assignment.grant.role, subject, assigned_by);
+        Ok(assignment)
+    }
+
+    pub async fn revoke(&self, subject: &str, grant: &RoleGrant, revoked_by: &str) -> Result<()> {
+        let mut assignments = self.assignments.write().await;
+        let entries = assignments.get_mut(subject)
+            .ok_or_else(|| anyhow!("No roles assigned to {}", subject))?;
+        let before = entries.len();
+        entries.retain(|a| &a.grant != grant);
+        if entries.len() == before {
+            return Err(anyhow!("Role {:?} not assigned to {}", grant.role, subject));
+        }
+
+        // O último administrador global não pode ser removido
+        if grant.role == Role::TseAdmin && grant.election_id.is_none() && grant.region.is_none() {
+            let admins = assignments.values()
+                .flatten()
+                .filter(|a| a.grant.role == Role::TseAdmin && a.grant.election_id.is_none() && a.grant.region.is_none())
+                .count();
+            if admins == 0 {
+                assignments.entry(subject.to_string()).or_default().push(RoleAssignment {
+                    subject: subject.to_string(),
+                    grant: grant.clone(),
+                    assigned_by: revoked_by.to_string(),
+                    assigned_at: Utc::now(),
+                });
+                return Err(anyhow!("Cannot revoke the last TSE administrator"));
+            }
+        }
+        log::info!("Role {:?} revoked from {} by {}", grant.role, subject, revoked_by);
+        Ok(())
+    }
+
+    pub async fn grants_of(&self, subject: &str) -> Vec<RoleGrant> {
+        self.assignments.read().await
+            .get(subject)
+            .map(|entries| entries.iter().map(|a| a.grant.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn list(&self) -> Vec<RoleAssignment> {
+        let mut all: Vec<RoleAssignment> = self.assignments.read().await.values().flatten().cloned().collect();
+        all.sort_by(|a, b| a.subject.cmp(&b.subject).then(a.grant.role.cmp(&b.grant.role)));
+        all
+    }
+}
+
+/// Sujeito autenticado da requisição, resolvido pelo middleware `RbacAuthentication`
+#[derive(Debug, Clone, Default)]
+pub struct Principal {
+    pub subject: Option<String>,
+    pub grants: Vec<RoleGrant>,
+}
+
+impl Principal {
+    pub fn anonymous() -> Self {
+        Self::default()
+    }
+
+    /// Visão do administrador pelo acesso de suporte: só leitura, no
+    /// alcance dos papéis do administrador que concedeu o acesso
+    pub fn support_view(grant: &SupportGrant, admin_grants: &[RoleGrant]) -> Self {
+        let grants = admin_grants.iter()
+            .filter(|admin| admin.role.grants(Permission::ReadAudits))
+            .map(|admin| RoleGrant { role: Role::Observer, ..admin.clone() })
+            .collect();
+        Self {
+            subject: Some(grant.support_engineer.clone()),
+            grants,
+        }
+    }
+
+    /// Exige a permissão em papel global
+    pub fn require(&self, permission: Permission) -> std::result::Result<&str, FortisError> {
+        self.check(permission, None)
+    }
+
+    /// Exige a permissão em papel global ou restrito à eleição
+    pub fn require_for(&self, permission: Permission, election_id: &str) -> std::result::Result<&str, FortisError> {
+        self.check(permission, Some(election_id))
+    }
+
+    /// Exige a permissão em qualquer papel, inclusive eleitoral ou regional,
+    /// e devolve o recorte dos dados que o sujeito pode ver
+    pub fn require_scope(&self, permission: Permission) -> std::result::Result<(&str, DataScope), FortisError> {
+        let subject = self.subject.as_deref().ok_or(FortisError::AuthenticationRequired)?;
+        let grants: Vec<RoleGrant> = self.grants.iter()
+            .filter(|grant| grant.role.grants(permission))
+            .cloned()
+            .collect();
+        if grants.is_empty() {
+            log::warn!("{} denied {:?} (any scope)", subject, permission);
+            return Err(FortisError::PermissionDenied(format!("{:?}", permission)));
+        }
+        Ok((subject, DataScope { grants }))
+    }
+
+    fn check(&self, permission: Permission, election_id: Option<&str>) -> std::result::Result<&str, FortisError> {
+        let subject = self.subject.as_deref().ok_or(FortisError::AuthenticationRequired)?;
+        if self.grants.iter().any(|grant| grant.covers(permission, election_id)) {
+            Ok(subject)
+        } else {
+            log::warn!("{} denied {:?} (election {:?})", subject, permission, election_id);
+            Err(FortisError::PermissionDenied(format!("{:?}", permission)))
+        }
+    }
+}
+
+/// A urna identificada pelo certificado de dispositivo recebe o papel `urna`;
+/// com acesso de suporte, valem só as permissões de leitura do administrador
+/// que o concedeu
+impl FromRequest for Principal {
+    type Error = actix_web::Error;
+    type Future = Ready<std::result::Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let extensions = req.extensions();
+        if let Some(grant) = extensions.get::<SupportGrant>() {
+            let admin_grants = extensions.get::<SupportAdminGrants>()
+                .map(|admin| admin.0.as_slice())
+                .unwrap_or_default();
+            return ready(Ok(Principal::support_view(grant, admin_grants)));
+        }
+        let principal = match (extensions.get::<Principal>(), extensions.get::<DeviceIdentity>()) {
+            (Some(principal), _) => principal.clone(),
+            (None, Some(device)) => Principal {
+                subject: Some(device.urna_id.clone()),
+                grants: vec![RoleGrant { role: Role::Urna, election_id: None, region: None }],
+            },
+            (None, None) => Principal::anonymous(),
+        };
+        ready(Ok(principal))
+    }
+}
+
+/// Papéis do administrador que concedeu o acesso de suporte da requisição
+#[derive(Debug, Clone)]
+pub struct SupportAdminGrants(pub Vec<RoleGrant>);
+
+/// Papéis e suas permissões, para a rota de consulta
+pub fn role_matrix() -> Vec<(Role, &'static [Permission])> {
+    Role::ALL.iter().map(|role| (*role, role.permissions())).collect()
+}
+
+/// Resolve os papéis do sujeito do token; usado pelo middleware
+pub async fn resolve(rbac: &web::Data<RbacService>, subject: String) -> Principal {
+    let grants = rbac.grants_of(&subject).await;
+    Principal {
+        subject: Some(subject),
+        grants,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn principal(grants: Vec<RoleGrant>) -> Principal {
+        Principal { subject: Some("12345678909".to_string()), grants }
+    }
+
+    #[test]
+    fn test_scoped_official_only_acts_on_own_election() {
+        let official = principal(vec![RoleGrant {
+            role: Role::ElectionOfficial,
+            election_id: Some("eleicao-sp".to_string()),
+            region: None,
+        }]);
+        assert!(official.require_for(Permission::RecordResults, "eleicao-sp").is_ok());
+        assert!(matches!(
+            official.require_for(Permission::RecordResults, "eleicao-rj"),
+            Err(FortisError::PermissionDenied(_))
+        ));
+        assert!(official.require(Permission::RecordResults).is_err());
+        assert!(official.require_for(Permission::PublishResults, "eleicao-sp").is_err());
+        assert!(matches!(
+            Principal::anonymous().require(Permission::ReadAudits),
+            Err(FortisError::AuthenticationRequired)
+        ));
+    }
+
+    #[test]
+    fn test_regional_grant_only_passes_scoped_checks() {
+        let zonal = principal(vec![RoleGrant {
+            role: Role::Auditor,
+            election_id: None,
+            region: Some(RegionScope::parse("SP/71072/0001").unwrap()),
+        }]);
+        assert!(zonal.require(Permission::ReadAudits).is_err());
+        assert!(zonal.require_for(Permission::ReadAudits, "eleicao-sp").is_err());
+
+        let (_, scope) = zonal.require_scope(Permission::ReadAudits).unwrap();
+        assert!(!scope.is_unrestricted());
+        let section = RegionScope::parse("SP/71072/0001/0042").unwrap();
+        let other_zone = RegionScope::parse("SP/71072/0002").unwrap();
+        assert!(scope.allows(Some("eleicao-sp"), Some(&section)));
+        assert!(!scope.allows(Some("eleicao-sp"), Some(&other_zone)));
+        assert!(!scope.allows(Some("eleicao-sp"), None));
+        assert!(zonal.require_scope(Permission::ManageRoles).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_last_admin_cannot_be_revoked() {
+        let rbac = RbacService::new().with_bootstrap_admins(&["admin".to_string()]);
+        let admin = RoleGrant { role: Role::TseAdmin, election_id: None, region: None };
+        assert!(rbac.revoke("admin", &admin, "admin").await.is_err());
+        assert_eq!(rbac.grants_of("admin").await, vec![admin.clone()]);
+
+        rbac.assign("auditor", RoleGrant { role: Role::Auditor, election_id: None, region: None }, "admin").await.unwrap();
+        assert!(rbac.assign("urna-1", RoleGrant { role: Role::Urna, election_id: None, region: None }, "admin").await.is_err());
+        rbac.assign("second", admin.clone(), "admin").await.unwrap();
+        rbac.revoke("admin", &admin, "second").await.unwrap();
+        assert!(rbac.grants_of("admin").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_support_view_is_read_only_within_admin_grants() {
+        use crate::services::support_access::{SupportAccessService, SupportScope};
+
+        let support = SupportAccessService::new();
+        let grant = support
+            .request_access("suporte-1", "admin-sp", vec![SupportScope::Elections], "Painel sem dados", 30)
+            .await
+            .unwrap();
+        let admin_grants = vec![RoleGrant {
+            role: Role::TseAdmin,
+            election_id: Some("eleicao-sp".to_string()),
+            region: None,
+        }];
+
+        let view = Principal::support_view(&grant, &admin_grants);
+        assert_eq!(view.subject.as_deref(), Some("suporte-1"));
+        assert!(view.require_for(Permission::ReadAudits, "eleicao-sp").is_ok());
+        assert!(view.require_for(Permission::ReadAudits, "eleicao-rj").is_err());
+        assert!(view.require_for(Permission::ManageElections, "eleicao-sp").is_err());
+        assert!(view.require(Permission::ManageSupportAccess).is_err());
+        assert!(Principal::support_view(&grant, &[]).require_scope(Permission::ReadAudits).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! API Interna do Cluster
+//!
+//! Endpoints `/cluster` usados pelas réplicas para gossip e por outros
+//! subsistemas (sharding, upgrades, cache) para consultar membros. Servidos
+//! só no listener interno do cluster, nunca no público; gossip e saída
+//! exigem a assinatura com o segredo do cluster.
+
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::Utc;
+
+use super::membership::{purposes, GossipMessage, MembershipService, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+/// Lista membros conhecidos
+pub async fn list_members(
+    membership: web::Data<MembershipService>,
+) -> Result<HttpResponse> {
+    let members = membership.members().await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "members": members,
+        "total": members.len()
+    })))
+}
+
+/// Obtém membro por ID
+pub async fn get_member(
+    membership: web::Data<MembershipService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let member_id = path.into_inner();
+    match membership.get_member(&member_id).await {
+        Some(member) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "member": member
+        }))),
+        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "error": format!("Member not found: {}", member_id)
+        }))),
+    }
+}
+
+/// Obtém a réplica local
+pub async fn get_self(
+    membership: web::Data<MembershipService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "member": membership.local_member().await
+    })))
+}
+
+/// Lista membros vivos de um shard
+pub async fn get_shard_members(
+    membership: web::Data<MembershipService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let shard = path.into_inner();
+    let members = membership.members_in_shard(&shard).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "shard": shard,
+        "members": members
+    })))
+}
+
+/// Obtém resumo do cluster
+pub async fn get_summary(
+    membership: web::Data<MembershipService>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "summary": membership.get_summary().await
+    })))
+}
+
+/// Confere a assinatura de outra réplica; recusa com 401 quem não tem o segredo
+fn authenticate_peer(
+    membership: &MembershipService,
+    req: &HttpRequest,
+    purpose: &str,
+    body: &[u8],
+) -> std::result::Result<(), HttpResponse> {
+    let header = |name: &str| req.headers().get(name).and_then(|value| value.to_str().ok());
+    let result = match (header(TIMESTAMP_HEADER).and_then(|t| t.parse::<i64>().ok()), header(SIGNATURE_HEADER)) {
+        (Some(timestamp), Some(signature)) => membership.authenticate(purpose, timestamp, body, signature),
+        _ => Err(anyhow::anyhow!("Missing cluster signature")),
+    };
+    result.map_err(|e| {
+        log::warn!("Mensagem de cluster recusada de {:?}: {}", req.peer_addr(), e);
+        HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Réplica não autenticada"
+        }))
+    })
+}
+
+/// Recebe mensagem de gossip e responde com a visão local, assinada
+pub async fn gossip(
+    membership: web::Data<MembershipService>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse> {
+    if let Err(response) = authenticate_peer(&membership, &req, purposes::GOSSIP, &body) {
+        return Ok(response);
+    }
+    let message: GossipMessage = match serde_json::from_slice(&body) {
+        Ok(message) => message,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "error": format!("Mensagem de gossip inválida: {}", e)
+        }))),
+    };
+
+    let reply = membership.handle_gossip(message).await;
+    let reply = serde_json::to_vec(&reply).map_err(actix_web::error::ErrorInternalServerError)?;
+    let timestamp = Utc::now().timestamp();
+    let signature = membership.sign(purposes::GOSSIP_REPLY, timestamp, &reply)
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header((TIMESTAMP_HEADER, timestamp.to_string()))
+        .insert_header((SIGNATURE_HEADER, signature))
+        .body(reply))
+}
+
+/// Anuncia a saída da réplica local, a pedido de quem tem o segredo do cluster
+pub async fn leave(
+    membership: web::Data<MembershipService>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse> {
+    if let Err(response) = authenticate_peer(&membership, &req, purposes::LEAVE, &body) {
+        return Ok(response);
+    }
+    match membership.leave().await {
+        Ok(()) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "Réplica saiu do cluster"
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }))),
+    }
+}
+
+/// Configura as rotas internas do cluster
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/cluster")
+            .route("/members", web::get().to(list_members))
+            .route("/members/{member_id}", web::get().to(get_member))
+            .route("/self", web::get().to(get_self))
+            .route("/shards/{shard}", web::get().to(get_shard_members))
+            .route("/summary", web::get().to(get_summary))
+            .route("/gossip", web::post().to(gossip))
+            .route("/leave", web::post().to(leave))
+    );
+}
@@ -0,0 +1,556 @@
+//! Serviço de Membros do Cluster
+//!
+//! Protocolo de gossip simples: cada réplica incrementa periodicamente seu
+//! contador de heartbeat e troca sua visão do cluster com alguns pares
+//! aleatórios. Membros sem heartbeat novo passam a `Suspect` e depois a `Dead`.
+//!
+//! As réplicas se autenticam com o segredo do cluster: cada mensagem, e a
+//! resposta a ela, leva um HMAC sobre a finalidade, o instante e o corpo.
+//! Mensagens fora da janela de relógio ou sem o segredo são recusadas.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc, Duration};
+use anyhow::{Result, anyhow};
+use rand::seq::SliceRandom;
+use ring::hmac;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Configuração de membros do cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipConfig {
+    pub node_id: String,
+    pub advertise_address: String,
+    pub seeds: Vec<String>,
+    pub gossip_interval_ms: u64,
+    pub gossip_fanout: usize,
+    pub suspect_timeout_seconds: i64,
+    pub dead_timeout_seconds: i64,
+    pub reap_timeout_seconds: i64,
+    /// Segredo compartilhado pelas réplicas; sem ele nenhuma mensagem é aceita
+    #[serde(default, skip_serializing)]
+    pub secret: String,
+}
+
+/// Cabeçalhos da autenticação entre réplicas
+pub const TIMESTAMP_HEADER: &str = "x-fortis-cluster-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-fortis-cluster-signature";
+
+/// Finalidades assinadas, para que uma mensagem não valha por outra
+pub mod purposes {
+    pub const GOSSIP: &str = "gossip";
+    pub const GOSSIP_REPLY: &str = "gossip-reply";
+    pub const LEAVE: &str = "leave";
+}
+
+/// Diferença máxima de relógio aceita entre réplicas
+const MAX_CLOCK_SKEW_SECONDS: i64 = 30;
+
+/// Metadados anunciados por um membro
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MemberMetadata {
+    pub shard: String,
+    pub version: String,
+    pub region: String,
+    pub tags: HashMap<String, String>,
+}
+
+/// Estado de vivacidade de um membro
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum MemberStatus {
+    Alive,
+    Suspect,
+    Dead,
+    Left,
+}
+
+/// Membro do cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub id: String,
+    pub address: String,
+    pub metadata: MemberMetadata,
+    pub status: MemberStatus,
+    pub heartbeat: u64,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Mensagem trocada entre réplicas a cada rodada de gossip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GossipMessage {
+    pub sender_id: String,
+    pub members: Vec<Member>,
+}
+
+/// Resumo do cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterSummary {
+    pub local_id: String,
+    pub total_members: usize,
+    pub alive_members: usize,
+    pub suspect_members: usize,
+    pub dead_members: usize,
+    pub shards: HashMap<String, usize>,
+    pub versions: HashMap<String, usize>,
+}
+
+/// Serviço de membros do cluster
+#[derive(Clone)]
+pub struct MembershipService {
+    config: MembershipConfig,
+    members: Arc<RwLock<HashMap<String, Member>>>,
+    http_client: reqwest::Client,
+}
+
+impl MembershipService {
+    /// Cria novo serviço de membros registrando a réplica local
+    pub fn new(config: MembershipConfig, metadata: MemberMetadata) -> Self {
+        let local = Member {
+            id: config.node_id.clone(),
+            address: config.advertise_address.clone(),
+            metadata,
+            status: MemberStatus::Alive,
+            heartbeat: 1,
+            last_seen: Utc::now(),
+        };
+
+        let mut members = HashMap::new();
+        members.insert(local.id.clone(), local);
+
+        Self {
+            config,
+            members: Arc::new(RwLock::new(members)),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Identificador da réplica local
+    pub fn local_id(&self) -> &str {
+        &self.config.node_id
+    }
+
+    /// Obtém o membro local
+    pub async fn local_member(&self) -> Member {
+        let members = self.members.read().await;
+        members[&self.config.node_id].clone()
+    }
+
+    /// Atualiza os metadados anunciados pela réplica local (ex.: nova versão)
+    pub async fn update_local_metadata(&self, metadata: MemberMetadata) {
+        let mut members = self.members.write().await;
+        if let Some(local) = members.get_mut(&self.config.node_id) {
+            local.metadata = metadata;
+            local.heartbeat += 1;
+            local.last_seen = Utc::now();
+        }
+    }
+
+    /// Lista todos os membros conhecidos
+    pub async fn members(&self) -> Vec<Member> {
+        let members = self.members.read().await;
+        let mut list: Vec<Member> = members.values().cloned().collect();
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        list
+    }
+
+    /// Lista membros vivos
+    pub async fn alive_members(&self) -> Vec<Member> {
+        self.members().await
+            .into_iter()
+            .filter(|m| m.status == MemberStatus::Alive)
+            .collect()
+    }
+
+    /// Lista membros vivos de um shard
+    pub async fn members_in_shard(&self, shard: &str) -> Vec<Member> {
+        self.alive_members().await
+            .into_iter()
+            .filter(|m| m.metadata.shard == shard)
+            .collect()
+    }
+
+    /// Obtém membro por ID
+    pub async fn get_member(&self, member_id: &str) -> Option<Member> {
+        let members = self.members.read().await;
+        members.get(member_id).cloned()
+    }
+
+    /// Incrementa o heartbeat local e reavalia a vivacidade dos demais membros
+    pub async fn tick(&self) {
+        let now = Utc::now();
+        {
+            let mut members = self.members.write().await;
+            if let Some(local) = members.get_mut(&self.config.node_id) {
+                local.heartbeat += 1;
+                local.last_seen = now;
+            }
+        }
+        self.update_liveness(now).await;
+    }
+
+    /// Reavalia o estado dos membros remotos com base no último heartbeat visto
+    pub async fn update_liveness(&self, now: DateTime<Utc>) {
+        let suspect_after = Duration::seconds(self.config.suspect_timeout_seconds);
+        let dead_after = Duration::seconds(self.config.dead_timeout_seconds);
+        let reap_after = Duration::seconds(self.config.reap_timeout_seconds);
+        let local_id = self.config.node_id.clone();
+
+        let mut members = self.members.write().await;
+        members.retain(|id, member| {
+            let silent_for = now - member.last_seen;
+            *id == local_id
+                || !matches!(member.status, MemberStatus::Dead | MemberStatus::Left)
+                || silent_for < reap_after
+        });
+
+        for member in members.values_mut() {
+            if member.id == local_id || member.status == MemberStatus::Left {
+                continue;
+            }
+
+            let silent_for = now - member.last_seen;
+            let new_status = if silent_for >= dead_after {
+                MemberStatus::Dead
+            } else if silent_for >= suspect_after {
+                MemberStatus::Suspect
+            } else {
+                MemberStatus::Alive
+            };
+
+            if new_status != member.status {
+                log::warn!("Membro {} mudou de {:?} para {:?}", member.id, member.status, new_status);
+                member.status = new_status;
+            }
+        }
+    }
+
+    /// Incorpora a visão recebida de outra réplica
+    pub async fn merge(&self, remote: Vec<Member>) {
+        let now = Utc::now();
+        let mut members = self.members.write().await;
+
+        for incoming in remote {
+            if incoming.id == self.config.node_id {
+                // Refuta rumores de falha sobre a réplica local
+                if let Some(local) = members.get_mut(&self.config.node_id) {
+                    if incoming.heartbeat >= local.heartbeat && incoming.status != MemberStatus::Alive {
+                        local.heartbeat = incoming.heartbeat + 1;
+                    }
+                }
+                continue;
+            }
+
+            match members.get_mut(&incoming.id) {
+                Some(known) if incoming.heartbeat <= known.heartbeat => {}
+                Some(known) => {
+                    known.address = incoming.address;
+                    known.metadata = incoming.metadata;
+                    known.heartbeat = incoming.heartbeat;
+                    known.last_seen = now;
+                    known.status = if incoming.status == MemberStatus::Left {
+                        MemberStatus::Left
+                    } else {
+                        MemberStatus::Alive
+                    };
+                }
+                None => {
+                    if matches!(incoming.status, MemberStatus::Dead | MemberStatus::Left) {
+                        continue;
+                    }
+                    log::info!("Novo membro no cluster: {} ({})", incoming.id, incoming.address);
+                    members.insert(incoming.id.clone(), Member {
+                        status: MemberStatus::Alive,
+                        last_seen: now,
+                        ..incoming
+                    });
+                }
+            }
+        }
+    }
+
+    /// Processa mensagem de gossip recebida e devolve a visão local
+    pub async fn handle_gossip(&self, message: GossipMessage) -> GossipMessage {
+        self.merge(message.members).await;
+        self.gossip_message().await
+    }
+
+    /// Anuncia saída voluntária do cluster
+    pub async fn leave(&self) -> Result<()> {
+        {
+            let mut members = self.members.write().await;
+            let local = members.get_mut(&self.config.node_id)
+                .ok_or_else(|| anyhow!("Local member not registered"))?;
+            local.status = MemberStatus::Left;
+            local.heartbeat += 1;
+        }
+        self.gossip_round().await
+    }
+
+    /// Executa uma rodada de gossip com pares aleatórios
+    pub async fn gossip_round(&self) -> Result<()> {
+        let targets = self.select_gossip_targets().await;
+        let message = self.gossip_message().await;
+
+        for address in targets {
+            match self.send_gossip(&address, &message).await {
+                Ok(reply) => self.merge(reply.members).await,
+                Err(e) => log::debug!("Falha no gossip com {}: {}", address, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Inicia o laço de gossip em segundo plano
+    pub fn start(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(
+                std::time::Duration::from_millis(service.config.gossip_interval_ms),
+            );
+            loop {
+                interval.tick().await;
+                service.tick().await;
+                if let Err(e) = service.gossip_round().await {
+                    log::error!("Erro na rodada de gossip: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Assina uma mensagem entre réplicas com o segredo do cluster
+    pub fn sign(&self, purpose: &str, timestamp: i64, body: &[u8]) -> Result<String> {
+        let key = self.cluster_key()?;
+        Ok(hex::encode(hmac::sign(&key, &Self::signed_bytes(purpose, timestamp, body))))
+    }
+
+    /// Confere que a mensagem é recente e veio de quem tem o segredo do cluster
+    pub fn authenticate(&self, purpose: &str, timestamp: i64, body: &[u8], signature: &str) -> Result<()> {
+        if (Utc::now().timestamp() - timestamp).abs() > MAX_CLOCK_SKEW_SECONDS {
+            return Err(anyhow!("Cluster message timestamp is outside the accepted window"));
+        }
+        let key = self.cluster_key()?;
+        let signature = hex::decode(signature).map_err(|_| anyhow!("Malformed cluster signature"))?;
+        hmac::verify(&key, &Self::signed_bytes(purpose, timestamp, body), &signature)
+            .map_err(|_| anyhow!("Invalid cluster signature"))
+    }
+
+    fn cluster_key(&self) -> Result<hmac::Key> {
+        if self.config.secret.is_empty() {
+            return Err(anyhow!("Cluster secret is not configured"));
+        }
+        Ok(hmac::Key::new(hmac::HMAC_SHA256, self.config.secret.as_bytes()))
+    }
+
+    fn signed_bytes(purpose: &str, timestamp: i64, body: &[u8]) -> Vec<u8> {
+        let mut bytes = format!("fortis-cluster-v1\n{}\n{}\n", purpose, timestamp).into_bytes();
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    /// Obtém resumo do cluster
+    pub async fn get_summary(&self) -> ClusterSummary {
+        let members = self.members().await;
+        let mut shards = HashMap::new();
+        let mut versions = HashMap::new();
+
+        for member in members.iter().filter(|m| m.status == MemberStatus::Alive) {
+            *shards.entry(member.metadata.shard.clone()).or_insert(0) += 1;
+            *versions.entry(member.metadata.version.clone()).or_insert(0) += 1;
+        }
+
+        let count = |status: MemberStatus| members.iter().filter(|m| m.status == status).count();
+
+        ClusterSummary {
+            local_id: self.config.node_id.clone(),
+            total_members: members.len(),
+            alive_members: count(MemberStatus::Alive),
+            suspect_members: count(MemberStatus::Suspect),
+            dead_members: count(MemberStatus::Dead),
+            shards,
+            versions,
+        }
+    }
+
+    async fn gossip_message(&self) -> GossipMessage {
+        GossipMessage {
+            sender_id: self.config.node_id.clone(),
+            members: self.members().await,
+        }
+    }
+
+    async fn select_gossip_targets(&self) -> Vec<String> {
+        let mut peers: Vec<String> = self.members().await
+            .into_iter()
+            .filter(|m| m.id != self.config.node_id)
+            .filter(|m| matches!(m.status, MemberStatus::Alive | MemberStatus::Suspect))
+            .map(|m| m.address)
+            .collect();
+
+        // Sem pares conhecidos, recorre às sementes para entrar no cluster
+        if peers.is_empty() {
+            peers = self.config.seeds
+                .iter()
+                .filter(|s| **s != self.config.advertise_address)
+                .cloned()
+                .collect();
+        }
+
+        peers.shuffle(&mut rand::thread_rng());
+        peers.truncate(self.config.gossip_fanout.max(1));
+        peers
+    }
+
+    async fn send_gossip(&self, address: &str, message: &GossipMessage) -> Result<GossipMessage> {
+        let body = serde_json::to_vec(message)?;
+        let timestamp = Utc::now().timestamp();
+        let signature = self.sign(purposes::GOSSIP, timestamp, &body)?;
+        let response = self.http_client
+            .post(format!("http://{}/cluster/gossip", address))
+            .timeout(std::time::Duration::from_millis(self.config.gossip_interval_ms.max(500)))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Gossip rejected by {}: {}", address, response.status()));
+        }
+
+        // A resposta também precisa vir de uma réplica do cluster
+        let header = |name: &str| response.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let reply_timestamp = header(TIMESTAMP_HEADER)
+            .and_then(|value| value.parse::<i64>().ok())
+            .ok_or_else(|| anyhow!("Gossip reply from {} is not signed", address))?;
+        let reply_signature = header(SIGNATURE_HEADER)
+            .ok_or_else(|| anyhow!("Gossip reply from {} is not signed", address))?;
+        let reply = response.bytes().await?;
+        self.authenticate(purposes::GOSSIP_REPLY, reply_timestamp, &reply, &reply_signature)?;
+
+        Ok(serde_json::from_slice(&reply)?)
+    }
+}
+
+impl Default for MembershipConfig {
+    fn default() -> Self {
+        Self {
+            node_id: "node-1".to_string(),
+            advertise_address: "127.0.0.1:8080".to_string(),
+            seeds: Vec::new(),
+            gossip_interval_ms: 1000,
+            gossip_fanout: 3,
+            suspect_timeout_seconds: 5,
+            dead_timeout_seconds: 30,
+            reap_timeout_seconds: 300,
+            secret: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(shard: &str, version: &str) -> MemberMetadata {
+        MemberMetadata {
+            shard: shard.to_string(),
+            version: version.to_string(),
+            region: "sudeste".to_string(),
+            tags: HashMap::new(),
+        }
+    }
+
+    fn service(id: &str, shard: &str) -> MembershipService {
+        let config = MembershipConfig {
+            node_id: id.to_string(),
+            advertise_address: format!("{}:8080", id),
+            secret: "cluster-secret".to_string(),
+            ..MembershipConfig::default()
+        };
+        MembershipService::new(config, metadata(shard, "1.0.0"))
+    }
+
+    #[tokio::test]
+    async fn test_merge_discovers_members() {
+        let a = service("node-a", "shard-1");
+        let b = service("node-b", "shard-2");
+
+        let reply = b.handle_gossip(GossipMessage {
+            sender_id: "node-a".to_string(),
+            members: a.members().await,
+        }).await;
+        a.merge(reply.members).await;
+
+        assert_eq!(a.members().await.len(), 2);
+        assert_eq!(b.members().await.len(), 2);
+        assert_eq!(a.members_in_shard("shard-2").await[0].id, "node-b");
+    }
+
+    #[tokio::test]
+    async fn test_stale_heartbeat_is_ignored() {
+        let a = service("node-a", "shard-1");
+        let b = service("node-b", "shard-1");
+        b.tick().await;
+        a.merge(b.members().await).await;
+
+        let mut stale = b.local_member().await;
+        stale.heartbeat = 1;
+        stale.metadata.version = "0.9.0".to_string();
+        a.merge(vec![stale]).await;
+
+        assert_eq!(a.get_member("node-b").await.unwrap().metadata.version, "1.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_liveness_transitions() {
+        let a = service("node-a", "shard-1");
+        let b = service("node-b", "shard-1");
+        a.merge(b.members().await).await;
+
+        let now = Utc::now();
+        a.update_liveness(now + Duration::seconds(10)).await;
+        assert_eq!(a.get_member("node-b").await.unwrap().status, MemberStatus::Suspect);
+
+        a.update_liveness(now + Duration::seconds(60)).await;
+        assert_eq!(a.get_member("node-b").await.unwrap().status, MemberStatus::Dead);
+        assert_eq!(a.alive_members().await.len(), 1);
+
+        a.update_liveness(now + Duration::seconds(600)).await;
+        assert!(a.get_member("node-b").await.is_none());
+        assert_eq!(a.local_member().await.status, MemberStatus::Alive);
+    }
+
+    #[test]
+    fn test_peer_messages_require_cluster_secret() {
+        let a = service("node-a", "shard-1");
+        let b = service("node-b", "shard-1");
+        let body = br#"{"sender_id":"node-a","members":[]}"#;
+        let now = Utc::now().timestamp();
+
+        let signature = a.sign(purposes::GOSSIP, now, body).unwrap();
+        assert!(b.authenticate(purposes::GOSSIP, now, body, &signature).is_ok());
+        // Outra finalidade, corpo alterado ou mensagem antiga não passam
+        assert!(b.authenticate(purposes::LEAVE, now, body, &signature).is_err());
+        assert!(b.authenticate(purposes::GOSSIP, now, b"{}", &signature).is_err());
+        let old = now - 300;
+        let stale = a.sign(purposes::GOSSIP, old, body).unwrap();
+        assert!(b.authenticate(purposes::GOSSIP, old, body, &stale).is_err());
+
+        let outsider = MembershipService::new(
+            MembershipConfig { secret: "other-secret".to_string(), ..MembershipConfig::default() },
+            metadata("shard-1", "1.0.0"),
+        );
+        let forged = outsider.sign(purposes::GOSSIP, now, body).unwrap();
+        assert!(b.authenticate(purposes::GOSSIP, now, body, &forged).is_err());
+
+        let unconfigured = MembershipService::new(MembershipConfig::default(), metadata("shard-1", "1.0.0"));
+        assert!(unconfigured.sign(purposes::GOSSIP, now, body).is_err());
+        assert!(unconfigured.authenticate(purposes::GOSSIP, now, body, &signature).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+//! Módulo de Cluster do FORTIS
+//! 
+//! Mantém a visão de membros do cluster de réplicas do backend por meio de
+//! gossip, com detecção de falhas e metadados (shard, versão, região)
+//! consumidos por sharding, coordenação de upgrades e invalidação de cache.
+
+pub mod membership;
+pub mod api;
+
+pub use membership::*;
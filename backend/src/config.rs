@@ -1,14 +1,50 @@
 use serde::{Deserialize, Serialize};
 
+use crate::auth::rbac::Permission;
+use crate::crypto::hsm::HsmConfig;
+use crate::monitoring::{NotificationsConfig, RetryPolicy};
+use crate::storage::ErasurePolicy;
+use crate::transparency::witness::WitnessPeer;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
+    pub nullifiers: NullifierConfig,
     pub security: SecurityConfig,
     pub tse: TSEConfig,
     pub transparency: TransparencyConfig,
     pub consensus: ConsensusConfig,
+    pub deployment: DeploymentConfig,
+    pub cluster: ClusterConfig,
+    pub analytics: AnalyticsConfig,
+    pub notifications: NotificationsConfig,
+    pub beacon: BeaconConfig,
+    pub transport: TransportConfig,
+    pub compute: ComputeConfig,
+    pub stream: StreamConfig,
+    pub command_center: CommandCenterConfig,
+    pub hsm: HsmConfig,
+    pub logging: LoggingConfig,
+    pub api_docs: ApiDocsConfig,
+    pub storage: StorageConfig,
+    pub i18n: I18nConfig,
+    pub election_templates: ElectionTemplatesConfig,
+}
+
+/// Pacotes de idioma além dos embutidos (pt-BR e es)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct I18nConfig {
+    /// Diretório com um JSON por idioma
+    pub packs_dir: String,
+}
+
+/// Modelos de eleição aprovados, além dos embutidos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionTemplatesConfig {
+    /// Diretório com um JSON por modelo
+    pub templates_dir: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +53,120 @@ pub struct ServerConfig {
     pub port: u16,
 }
 
+/// Armazenamento distribuído (IPFS + DHT)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    /// API HTTP do nó IPFS (Kubo); sem ela nada é publicado no IPFS
+    pub ipfs_api_url: Option<String>,
+    /// Gateways de leitura, tentados em ordem quando o nó não responde
+    pub ipfs_gateways: Vec<String>,
+    pub ipfs_timeout_seconds: u64,
+    pub node_id: String,
+    /// Entradas no cache em memória
+    pub cache_size: usize,
+    pub cache_memory_bytes: usize,
+    /// Diretório da camada em disco do cache; sem ele o cache fica só em memória
+    pub cache_disk_path: Option<String>,
+    pub cache_disk_bytes: u64,
+    pub cache_ttl_seconds: u64,
+    /// Região deste nó, anunciada junto com os CIDs na DHT
+    pub region: String,
+    /// Endereço libp2p de escuta da DHT (ex.: `/ip4/0.0.0.0/tcp/4101`); sem ele a DHT não sobe
+    pub dht_listen_address: Option<String>,
+    /// Nós de bootstrap no formato `/ip4/.../tcp/.../p2p/<peer id>`
+    pub dht_bootstrap_nodes: Vec<String>,
+    pub dht_bootstrap_interval_seconds: u64,
+    /// Validade dos registros de provedor e de localização
+    pub dht_provider_ttl_seconds: u64,
+    /// Reanúncio dos registros, bem antes de expirarem
+    pub dht_provider_republish_seconds: u64,
+    /// Fragmentos Reed–Solomon por artefato; sem política, cada artefato é publicado inteiro
+    pub erasure: Option<ErasurePolicy>,
+    /// APIs HTTP dos nós IPFS que recebem os fragmentos
+    pub storage_nodes: Vec<String>,
+    pub shard_repair_interval_seconds: u64,
+    /// Índice em disco dos artefatos codificados por este nó, base do reparo após reinício
+    pub shard_index_path: String,
+}
+
+/// Conferência do documento OpenAPI com o roteador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiDocsConfig {
+    /// Prefixos cujas rotas precisam estar documentadas, sem dispensa pela lista de rotas não documentadas
+    pub required_prefixes: Vec<String>,
+    /// Última versão publicada do documento, base do `/api-docs/diff`
+    pub published_spec_path: String,
+}
+
+/// Logs estruturados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Uma linha JSON por evento; sem isso, texto legível para desenvolvimento
+    pub json: bool,
+    /// Filtro no formato do `RUST_LOG`, que tem precedência quando definido
+    pub filter: String,
+    /// Emite a duração de cada etapa ao fechar o span
+    pub span_timing: bool,
+    /// Coletor OTLP (gRPC) dos rastros distribuídos; sem ele nada é exportado
+    pub otlp_endpoint: Option<String>,
+    /// Filtro dos spans exportados, independente do filtro dos logs
+    pub trace_filter: String,
+}
+
+/// Fluxo WebSocket de eventos para os painéis de observação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamConfig {
+    /// Eventos retidos para cada conexão antes de descartar os mais antigos
+    pub channel_capacity: usize,
+    /// Descartes acumulados que levam ao encerramento da conexão
+    pub max_lagged_events: u64,
+    pub heartbeat_seconds: u64,
+    /// Silêncio do cliente (inclusive pongs) que encerra a conexão
+    pub client_timeout_seconds: u64,
+}
+
+/// Painel agregado do centro de comando no dia da eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandCenterConfig {
+    /// Validade do painel em cache; consultas nesse intervalo não recalculam
+    pub cache_ttl_ms: u64,
+    /// Urnas sem sincronização nesse intervalo não contam como transmitindo
+    pub transmission_window_seconds: i64,
+}
+
+/// Pool de criptografia pesada, separado dos workers HTTP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComputeConfig {
+    /// Threads do pool (0 = núcleos disponíveis)
+    pub threads: usize,
+    /// Tarefas aguardando além das em execução; acima disso, rejeita
+    pub max_queued: usize,
+    /// Núcleos reservados às threads do pool nos nós de apuração (vazio = sem fixação)
+    pub cpu_affinity: Vec<usize>,
+    /// Workers HTTP do actix (0 = um por núcleo)
+    pub http_workers: usize,
+}
+
+/// Política de transporte aplicada ao servidor, à urna e ao gRPC interno
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransportConfig {
+    /// Perfil TLS: modern, intermediate ou restricted (semelhante ao FIPS)
+    pub tls_profile: String,
+    pub tls_enabled: bool,
+    pub internal_tls_enabled: bool,
+    /// Servidor gRPC das urnas, ao lado do servidor HTTP
+    pub grpc_enabled: bool,
+    pub grpc_port: u16,
+    pub cert_path: String,
+    pub key_path: String,
+    /// Autoridades aceitas nas conexões internas
+    pub ca_path: String,
+    /// CA que emite os certificados de dispositivo das urnas
+    pub device_ca_path: String,
+    /// Exige certificado de urna (mTLS) nas rotas de urnas
+    pub require_device_certificates: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     pub url: String,
@@ -27,11 +177,26 @@ pub struct RedisConfig {
     pub url: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NullifierConfig {
+    pub bloom_expected_items: u64,
+    pub bloom_false_positive_rate: f64,
+    pub bloom_in_redis: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransparencyConfig {
     pub log_storage_path: String,
     pub merkle_tree_depth: u32,
     pub verification_nodes: Vec<String>,
+    pub sth_interval_seconds: u64,
+    pub audit_camera_enabled: bool,
+    /// Backends regionais e testemunhas externas que cossinam cada STH
+    pub witnesses: Vec<WitnessPeer>,
+    /// Cossinaturas exigidas para considerar a STH endossada
+    pub witness_threshold: usize,
+    /// SHA-256 (hex) das chaves dos auditores com acesso à visão completa do log
+    pub auditor_key_hashes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,21 +206,179 @@ pub struct ConsensusConfig {
     pub signature_timeout: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentConfig {
+    pub shards: Vec<String>,
+    pub drain_timeout_seconds: u64,
+    pub canary_window_seconds: u64,
+    pub max_error_rate_increase: f64,
+    pub max_latency_increase_percent: f64,
+    pub dr_drill_enabled: bool,
+    pub dr_primary_region: String,
+    pub dr_evidence_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    pub node_id: String,
+    /// Endereço em que as outras réplicas alcançam o listener interno
+    pub advertise_address: String,
+    /// Listener interno do cluster, separado do público
+    pub listen_address: String,
+    /// Segredo compartilhado que autentica as réplicas entre si
+    pub secret: String,
+    pub shard: String,
+    pub region: String,
+    pub seeds: Vec<String>,
+    pub gossip_interval_ms: u64,
+    pub suspect_timeout_seconds: i64,
+    pub dead_timeout_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsConfig {
+    pub dp_total_epsilon: f64,
+    pub dp_epsilon_per_release: f64,
+    pub min_section_size: u64,
+    pub min_cell_size: u64,
+    pub open_data_archive_path: String,
+    pub lessons_learned_path: String,
+    pub lessons_learned_interval_seconds: u64,
+    /// Validade do resultado apurado em cache
+    pub results_cache_seconds: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfig {
     pub encryption_key: String,
     pub jwt_secret: String,
+    pub disclosure_pgp_key_path: String,
+    pub disclosure_triage_sla_hours: i64,
+    pub credential_lead_times_days: Vec<i64>,
+    pub credential_check_interval: u64,
+    pub jwt_key_max_age_days: i64,
+    /// Registro das datas de emissão e expiração da chave JWT em uso
+    pub jwt_key_record_path: String,
+    pub rbac_bootstrap_admins: Vec<String>,
+    pub access_token_ttl_minutes: i64,
+    /// Inatividade que encerra a sessão administrativa
+    pub session_idle_timeout_minutes: i64,
+    pub session_max_hours: i64,
+    /// Limite padrão por IP e por sujeito autenticado
+    pub rate_limit: RateLimitRule,
+    /// Limites por prefixo de rota; vale o prefixo mais longo
+    pub route_rate_limits: Vec<RateLimitRule>,
+    /// Prefixos com limite próprio, fora da janela deslizante por IP
+    pub rate_limit_exempt_prefixes: Vec<String>,
+    pub verification_limits: VerificationLimitConfig,
+    pub client_puzzle: ClientPuzzleConfig,
+    pub dual_control: DualControlConfig,
+}
+
+/// Requisições aceitas na janela deslizante para um prefixo de rota
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitRule {
+    pub path_prefix: String,
+    pub requests: u32,
+    pub window_seconds: u64,
+}
+
+/// Baldes de fichas do portal de verificação de comprovantes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationLimitConfig {
+    /// Códigos distintos que um cliente consulta de uma vez
+    pub client_burst: u32,
+    /// Intervalo para repor uma ficha do cliente
+    pub client_refill_seconds: u64,
+    pub tracker_burst: u32,
+    pub tracker_refill_seconds: u64,
+    /// Fichas extras gastas quando o código não existe
+    pub miss_cost: u32,
+    /// Período da chave dos HMACs; ao trocá-la os baldes são descartados
+    pub key_rotation_seconds: u64,
+    /// Fichas anônimas por eleitor autenticado em cada período
+    pub tokens_per_period: u32,
+    pub token_key_bits: usize,
+    /// Época da chave das fichas; uma ficha vale na sua época e na seguinte
+    pub token_epoch_seconds: u64,
+}
+
+/// Desafio de prova de trabalho nas rotas públicas anônimas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientPuzzleConfig {
+    pub enabled: bool,
+    pub path_prefixes: Vec<String>,
+    /// Requisições anônimas por segundo a partir das quais o desafio é exigido
+    pub activation_per_second: u32,
+    /// Bits exigidos ao atingir o limiar; cada dobra da carga soma um bit
+    pub base_difficulty: u8,
+    pub max_difficulty: u8,
+    /// Dificuldade mínima inicial, mesmo sem carga
+    pub floor_difficulty: Option<u8>,
+    /// Janela de medição da carga
+    pub window_seconds: i64,
+    /// Peso de cada recusa do limitador de requisições na carga
+    pub throttled_weight: u32,
+    pub challenge_ttl_seconds: i64,
+    /// Requisições liberadas por desafio resolvido
+    pub uses_per_solution: u32,
+}
+
+/// Operações destrutivas que exigem um segundo aprovador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DualControlConfig {
+    /// Prazo para a segunda aprovação e a execução, a partir do pedido
+    pub approval_window_minutes: i64,
+    pub operations: Vec<DualControlRule>,
+}
+
+/// Rota protegida; `{nome}` casa com um segmento qualquer do caminho
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DualControlRule {
+    pub method: String,
+    pub path: String,
+    /// Permissão global exigida de quem pede e de quem aprova
+    pub permission: Permission,
+}
+
+impl DualControlRule {
+    fn new(method: &str, path: &str, permission: Permission) -> Self {
+        Self { method: method.to_string(), path: path.to_string(), permission }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconConfig {
+    pub drand_urls: Vec<String>,
+    pub drand_chain_hash: String,
+    pub drand_public_key: String,
+    pub drand_genesis_time: i64,
+    pub drand_period_seconds: i64,
+    /// Rodadas entre o compromisso de um sorteio e a rodada do drand que o decide
+    pub commit_rounds_ahead: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TSEConfig {
     pub base_url: String,
     pub gov_br_base_url: String,
+    /// API de confiabilidades (níveis da conta) do Gov.br
+    pub gov_br_api_url: String,
+    /// Nível mínimo da conta Gov.br para validar elegibilidade (bronze, silver, gold)
+    pub gov_br_min_account_level: String,
     pub client_id: String,
     pub client_secret: String,
     pub redirect_uri: String,
     pub api_key: String,
     pub sync_interval: u64,
+    /// Intervalo da sincronização incremental do cadastro eleitoral, em segundos
+    pub voter_roll_sync_interval: u64,
+    /// Itens por página pedidos à API do cadastro eleitoral
+    pub voter_roll_page_size: u32,
+    /// ABIS do TSE para a deduplicação biométrica; sem ele, índice vetorial local
+    pub abis_url: Option<String>,
+    /// Pontuação mínima para abrir suspeita de inscrição duplicada (0.0 a 1.0)
+    pub biometric_match_threshold: f32,
 }
 
 impl Config {
@@ -71,6 +394,12 @@ impl Config {
             redis: RedisConfig {
                 url: "redis://localhost:6379".to_string(),
             },
+            // Eleitorado brasileiro (~156 milhões) com folga
+            nullifiers: NullifierConfig {
+                bloom_expected_items: 160_000_000,
+                bloom_false_positive_rate: 0.001,
+                bloom_in_redis: true,
+            },
             transparency: TransparencyConfig {
                 log_storage_path: "./logs/transparent".to_string(),
                 merkle_tree_depth: 20,
@@ -79,6 +408,11 @@ impl Config {
                     "node2.tse.gov.br".to_string(),
                     "node3.tse.gov.br".to_string(),
                 ],
+                sth_interval_seconds: 60,
+                audit_camera_enabled: false,
+                witnesses: Vec::new(),
+                witness_threshold: 2,
+                auditor_key_hashes: Vec::new(),
             },
             consensus: ConsensusConfig {
                 threshold_nodes: vec![
@@ -89,18 +423,252 @@ impl Config {
                 threshold_required: 2,
                 signature_timeout: 30,
             },
+            deployment: DeploymentConfig {
+                shards: vec![
+                    "127.0.0.1:8080".to_string(),
+                ],
+                drain_timeout_seconds: 300,
+                canary_window_seconds: 60,
+                max_error_rate_increase: 1.0,
+                max_latency_increase_percent: 25.0,
+                dr_drill_enabled: false,
+                dr_primary_region: "sa-east-1".to_string(),
+                dr_evidence_path: "./compliance/evidence/dr-drills".to_string(),
+            },
+            cluster: ClusterConfig {
+                node_id: "node-1".to_string(),
+                advertise_address: "127.0.0.1:7946".to_string(),
+                listen_address: std::env::var("CLUSTER_LISTEN_ADDRESS").unwrap_or_else(|_| "127.0.0.1:7946".to_string()),
+                secret: std::env::var("FORTIS_CLUSTER_SECRET").unwrap_or_default(),
+                shard: "shard-1".to_string(),
+                region: "sudeste".to_string(),
+                seeds: vec![],
+                gossip_interval_ms: 1000,
+                suspect_timeout_seconds: 5,
+                dead_timeout_seconds: 30,
+            },
+            analytics: AnalyticsConfig {
+                dp_total_epsilon: 10.0,
+                dp_epsilon_per_release: 0.5,
+                min_section_size: 20,
+                min_cell_size: 100,
+                open_data_archive_path: "./data/open-data".to_string(),
+                lessons_learned_path: "./data/lessons-learned".to_string(),
+                lessons_learned_interval_seconds: 300,
+                results_cache_seconds: 30,
+            },
+            // Canais de alerta desabilitados por padrão
+            notifications: NotificationsConfig {
+                email: None,
+                webhooks: vec![],
+                sms: None,
+                retry: RetryPolicy {
+                    max_attempts: 5,
+                    initial_backoff_ms: 500,
+                    max_backoff_ms: 30_000,
+                },
+            },
             security: SecurityConfig {
                 encryption_key: "fortis_encryption_key_32_chars_long".to_string(),
                 jwt_secret: "fortis_jwt_secret_key_very_long_and_secure".to_string(),
+                disclosure_pgp_key_path: "./security/fortis-disclosure.asc".to_string(),
+                disclosure_triage_sla_hours: 72,
+                credential_lead_times_days: vec![90, 30, 7, 1],
+                credential_check_interval: 3600,
+                jwt_key_max_age_days: 90,
+                jwt_key_record_path: "./data/credentials/jwt-signing-key.json".to_string(),
+                rbac_bootstrap_admins: Vec::new(),
+                access_token_ttl_minutes: 15,
+                session_idle_timeout_minutes: 120,
+                session_max_hours: 12,
+                rate_limit: RateLimitRule {
+                    path_prefix: "/".to_string(),
+                    requests: 100,
+                    window_seconds: 60,
+                },
+                route_rate_limits: vec![
+                    RateLimitRule {
+                        path_prefix: "/api/v1/auth".to_string(),
+                        requests: 10,
+                        window_seconds: 60,
+                    },
+                    // Urnas enviam em rajadas na sincronização
+                    RateLimitRule {
+                        path_prefix: "/api/v1/urnas".to_string(),
+                        requests: 600,
+                        window_seconds: 60,
+                    },
+                ],
+                rate_limit_exempt_prefixes: vec!["/api/v1/votes/verify".to_string()],
+                verification_limits: VerificationLimitConfig {
+                    client_burst: 10,
+                    client_refill_seconds: 30,
+                    tracker_burst: 5,
+                    tracker_refill_seconds: 60,
+                    miss_cost: 4,
+                    key_rotation_seconds: 86_400,
+                    tokens_per_period: 20,
+                    token_key_bits: 2048,
+                    token_epoch_seconds: 86_400,
+                },
+                client_puzzle: ClientPuzzleConfig {
+                    enabled: true,
+                    path_prefixes: vec![
+                        "/api/v1/votes/verify".to_string(),
+                        "/api/v1/results".to_string(),
+                        "/api/v1/transparency".to_string(),
+                    ],
+                    activation_per_second: 200,
+                    base_difficulty: 16,
+                    max_difficulty: 22,
+                    floor_difficulty: None,
+                    window_seconds: 10,
+                    throttled_weight: 5,
+                    challenge_ttl_seconds: 120,
+                    uses_per_solution: 20,
+                },
+                dual_control: DualControlConfig {
+                    approval_window_minutes: std::env::var("DUAL_CONTROL_WINDOW_MINUTES").ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(30),
+                    operations: vec![
+                        DualControlRule::new("DELETE", "/api/v1/elections/{id}", Permission::ManageElections),
+                        DualControlRule::new("POST", "/api/v1/urnas/{urna_id}/certificates/revoke", Permission::ManageElections),
+                        DualControlRule::new("POST", "/api/v1/urnas/{urna_id}/public-key/rotate", Permission::ManageElections),
+                        DualControlRule::new("POST", "/api/v1/zkp/zkp/nullifier/rebuild", Permission::ManageElections),
+                        DualControlRule::new("POST", "/api/v1/tse/tse/voter-roll/sync", Permission::ManageElections),
+                        DualControlRule::new("POST", "/api/v1/admin/rollout", Permission::ManageDeployment),
+                        DualControlRule::new("POST", "/api/v1/admin/rollout/halt", Permission::ManageDeployment),
+                        DualControlRule::new("DELETE", "/api/v1/nodes/{id}", Permission::ManageTransparencyLog),
+                        DualControlRule::new("DELETE", "/api/v1/security/credentials/{id}", Permission::ManageRoles),
+                        DualControlRule::new("DELETE", "/api/v1/regions/sections/{state}/{municipality}/{zone}/{section}", Permission::ManageElections),
+                    ],
+                },
             },
             tse: TSEConfig {
                 base_url: "https://api.tse.jus.br".to_string(),
                 gov_br_base_url: "https://sso.acesso.gov.br".to_string(),
+                gov_br_api_url: "https://api.acesso.gov.br".to_string(),
+                gov_br_min_account_level: "silver".to_string(),
                 client_id: "fortis_client_id".to_string(),
                 client_secret: "fortis_client_secret".to_string(),
                 redirect_uri: "http://localhost:3000/auth/callback".to_string(),
                 api_key: "fortis_api_key".to_string(),
                 sync_interval: 3600,
+                voter_roll_sync_interval: 900,
+                voter_roll_page_size: 1000,
+                abis_url: std::env::var("TSE_ABIS_URL").ok(),
+                biometric_match_threshold: 0.4,
+            },
+            beacon: BeaconConfig {
+                drand_urls: vec![
+                    "https://api.drand.sh".to_string(),
+                    "https://api2.drand.sh".to_string(),
+                    "https://drand.cloudflare.com".to_string(),
+                ],
+                drand_chain_hash: "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce".to_string(),
+                drand_public_key: "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31".to_string(),
+                drand_genesis_time: 1595431050,
+                drand_period_seconds: 30,
+                commit_rounds_ahead: 10,
+            },
+            transport: TransportConfig {
+                tls_profile: "restricted".to_string(),
+                tls_enabled: false,
+                internal_tls_enabled: true,
+                grpc_enabled: true,
+                grpc_port: 50051,
+                cert_path: "/etc/fortis/tls/server.crt".to_string(),
+                key_path: "/etc/fortis/tls/server.key".to_string(),
+                ca_path: "/etc/fortis/tls/ca.crt".to_string(),
+                device_ca_path: "/etc/fortis/tls/device-ca.crt".to_string(),
+                require_device_certificates: true,
+            },
+            compute: ComputeConfig {
+                threads: 0,
+                max_queued: 256,
+                cpu_affinity: vec![],
+                http_workers: 0,
+            },
+            stream: StreamConfig {
+                channel_capacity: 1024,
+                max_lagged_events: 4096,
+                heartbeat_seconds: 15,
+                client_timeout_seconds: 45,
+            },
+            command_center: CommandCenterConfig {
+                cache_ttl_ms: 1000,
+                transmission_window_seconds: 900,
+            },
+            hsm: HsmConfig {
+                backend: "software".to_string(),
+                module_path: "/usr/lib/softhsm/libsofthsm2.so".to_string(),
+                token_label: "fortis".to_string(),
+                pin_env: "FORTIS_HSM_PIN".to_string(),
+                log_key_label: "fortis-transparency-log".to_string(),
+                node_key_prefix: "fortis-consensus-node".to_string(),
+                update_key_label: "fortis-urna-updates".to_string(),
+                provisioning_key_label: "fortis-urna-provisioning".to_string(),
+                package_key_label: "fortis-election-package".to_string(),
+                results_key_label: "fortis-results".to_string(),
+                open_data_key_label: "fortis-open-data".to_string(),
+                history_key_label: "fortis-election-history".to_string(),
+                dr_drill_key_label: "fortis-dr-drills".to_string(),
+                verification_token_key_prefix: "fortis-verification-tokens".to_string(),
+            },
+            logging: LoggingConfig {
+                json: true,
+                filter: "info".to_string(),
+                span_timing: true,
+                otlp_endpoint: std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok(),
+                trace_filter: "info,fortis_backend=debug".to_string(),
+            },
+            api_docs: ApiDocsConfig {
+                required_prefixes: vec![
+                    "/health".to_string(),
+                    "/api-docs".to_string(),
+                    "/api/v1/auth/login".to_string(),
+                    "/api/v1/auth/refresh".to_string(),
+                ],
+                published_spec_path: "./docs/openapi.json".to_string(),
+            },
+            storage: StorageConfig {
+                ipfs_api_url: std::env::var("IPFS_API_URL").ok(),
+                ipfs_gateways: vec![
+                    "https://ipfs.io".to_string(),
+                    "https://dweb.link".to_string(),
+                ],
+                ipfs_timeout_seconds: 30,
+                node_id: "fortis-node-1".to_string(),
+                cache_size: 1000,
+                cache_memory_bytes: 64 * 1024 * 1024,
+                cache_disk_path: std::env::var("STORAGE_CACHE_DIR").ok(),
+                cache_disk_bytes: 1024 * 1024 * 1024,
+                cache_ttl_seconds: 24 * 3600,
+                region: std::env::var("FORTIS_REGION").unwrap_or_else(|_| "df".to_string()),
+                dht_listen_address: std::env::var("DHT_LISTEN_ADDRESS").ok(),
+                dht_bootstrap_nodes: std::env::var("DHT_BOOTSTRAP_NODES")
+                    .map(|nodes| nodes.split(',').map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).collect())
+                    .unwrap_or_default(),
+                dht_bootstrap_interval_seconds: 300,
+                dht_provider_ttl_seconds: 48 * 3600,
+                dht_provider_republish_seconds: 12 * 3600,
+                // Formato `k+m`, ex.: `4+2`
+                erasure: std::env::var("STORAGE_ERASURE_CODING").ok()
+                    .map(|spec| ErasurePolicy::parse(&spec).expect("Invalid STORAGE_ERASURE_CODING")),
+                storage_nodes: std::env::var("STORAGE_NODES")
+                    .map(|nodes| nodes.split(',').map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).collect())
+                    .unwrap_or_default(),
+                shard_repair_interval_seconds: 3600,
+                shard_index_path: std::env::var("STORAGE_SHARD_INDEX")
+                    .unwrap_or_else(|_| "./data/storage/shard-index.json".to_string()),
+            },
+            i18n: I18nConfig {
+                packs_dir: std::env::var("FORTIS_LANGUAGE_PACKS_DIR").unwrap_or_else(|_| "./i18n".to_string()),
+            },
+            election_templates: ElectionTemplatesConfig {
+                templates_dir: std::env::var("FORTIS_ELECTION_TEMPLATES_DIR")
+                    .unwrap_or_else(|_| "./election_templates".to_string()),
             },
         }
     }
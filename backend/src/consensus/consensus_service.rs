@@ -11,6 +11,7 @@ use tokio::sync::RwLock;
 use std::sync::Arc;
 
 use crate::consensus::threshold_signatures::*;
+use crate::crypto::hsm::{Hsm, SigningKey};
 use crate::transparency::election_logs::*;
 use sha2::{Sha256, Digest};
 
@@ -48,6 +49,8 @@ pub enum ConsensusOperation {
     SystemMaintenance,
     SecurityAlert,
     DataIntegrityCheck,
+    /// Declaração de fim de transmissão de uma zona
+    ZoneTransmissionClosed,
 }
 
 /// Requisição de consenso
@@ -94,6 +97,9 @@ pub struct ConsensusService {
     transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
     metrics: Arc<RwLock<ConsensusMetrics>>,
     pending_requests: Arc<RwLock<HashMap<String, ConsensusRequest>>>,
+    /// HSM com as chaves dos nós e prefixo dos rótulos; sem HSM as chaves
+    /// são efêmeras
+    node_keys: Option<(Hsm, String)>,
 }
 
 impl ConsensusService {
@@ -122,9 +128,16 @@ impl ConsensusService {
             transparency_log,
             metrics,
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
+            node_keys: None,
         }
     }
 
+    /// Gera e usa as chaves dos nós dentro do HSM, rotuladas `{prefixo}-{nó}`
+    pub fn with_hsm(mut self, hsm: Hsm, key_prefix: &str) -> Self {
+        self.node_keys = Some((hsm, key_prefix.to_string()));
+        self
+    }
+
     /// Inicializa o serviço de consenso
     pub async fn initialize(&self) -> Result<()> {
         // Adicionar nós iniciais (simulado)
@@ -141,10 +154,9 @@ impl ConsensusService {
         let mut threshold_service = self.threshold_service.write().await;
         
         for i in 1..=self.config.threshold_config.total_nodes {
-            let (key_pair, public_key) = ThresholdUtils::generate_key_pair()?;
-            
-            let node = ConsensusNode {
-                id: format!("node_{}", i),
+            let node_id = format!("node_{}", i);
+            let node = |public_key| ConsensusNode {
+                id: node_id.clone(),
                 name: format!("Consensus Node {}", i),
                 public_key,
                 is_active: true,
@@ -153,13 +165,27 @@ impl ConsensusService {
                 signature_count: 0,
             };
 
-            threshold_service.add_node(node, key_pair)?;
+            match &self.node_keys {
+                Some((hsm, prefix)) => {
+                    let key = hsm.key_or_generate(&format!("{}-{}", prefix, node_id))?;
+                    threshold_service.add_node(node(hex::encode(key.public_key_bytes())), key)?;
+                }
+                None => {
+                    let (key_pair, public_key) = ThresholdUtils::generate_key_pair()?;
+                    threshold_service.add_node(node(public_key), key_pair)?;
+                }
+            }
         }
 
         Ok(())
     }
 
     /// Inicia processo de consenso
+    #[tracing::instrument(
+        name = "consensus.start",
+        skip_all,
+        fields(request_id = %request.id, operation = ?request.operation, reached = tracing::field::Empty),
+    )]
     pub async fn start_consensus(&self, request: ConsensusRequest) -> Result<ConsensusResult> {
         let start_time = Utc::now();
         let request_id = request.id.clone();
@@ -189,6 +215,7 @@ impl ConsensusService {
 
         // Processar consenso
         let result = self.process_consensus(signature_request, request, start_time).await?;
+        tracing::Span::current().record("reached", result.consensus_reached);
 
         // Registrar resultado
         self.record_consensus_result(&result).await?;
@@ -209,7 +236,10 @@ impl ConsensusService {
         threshold_service.create_signature_request(signature_request)?;
 
         // Coletar assinaturas
-        let threshold_signature = threshold_service.collect_signatures(&consensus_request.id)?;
+        let threshold_signature = {
+            let _span = tracing::info_span!("consensus.collect_signatures").entered();
+            threshold_service.collect_signatures(&consensus_request.id)?
+        };
 
         let consensus_time = Utc::now() - start_time;
         let consensus_reached = threshold_signature.threshold_met;
@@ -411,7 +441,9 @@ impl ConsensusUtils {
     pub fn calculate_priority(operation: &ConsensusOperation) -> SignaturePriority {
         match operation {
             ConsensusOperation::SecurityAlert => SignaturePriority::Critical,
-            ConsensusOperation::ElectionStart | ConsensusOperation::ElectionEnd => SignaturePriority::High,
+            ConsensusOperation::ElectionStart
+            | ConsensusOperation::ElectionEnd
+            | ConsensusOperation::ZoneTransmissionClosed => SignaturePriority::High,
             ConsensusOperation::VoteValidation | ConsensusOperation::AuditTrigger => SignaturePriority::Normal,
             _ => SignaturePriority::Low,
         }
@@ -424,7 +456,8 @@ impl ConsensusUtils {
             | ConsensusOperation::ElectionEnd
             | ConsensusOperation::VoteValidation
             | ConsensusOperation::AuditTrigger
-            | ConsensusOperation::SecurityAlert => true,
+            | ConsensusOperation::SecurityAlert
+            | ConsensusOperation::ZoneTransmissionClosed => true,
             _ => false,
         }
     }
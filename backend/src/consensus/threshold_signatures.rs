@@ -12,6 +12,8 @@ use ring::signature::{Ed25519KeyPair, UnparsedPublicKey, KeyPair};
 use sha2::{Sha256, Digest};
 use rand::rngs::OsRng;
 
+use crate::crypto::hsm::SigningKey;
+
 /// Configuração do threshold signature
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThresholdConfig {
@@ -135,7 +137,8 @@ pub enum SignaturePriority {
 pub struct ThresholdSignatureService {
     config: ThresholdConfig,
     nodes: HashMap<String, ConsensusNode>,
-    key_pairs: HashMap<String, Ed25519KeyPair>,
+    /// Chaves dos nós, em memória ou residentes em HSM
+    key_pairs: HashMap<String, Box<dyn SigningKey>>,
     pending_requests: HashMap<String, SignatureRequest>,
     completed_signatures: HashMap<String, ThresholdSignature>,
 }
@@ -153,14 +156,14 @@ impl ThresholdSignatureService {
     }
 
     /// Adiciona um nó ao consenso
-    pub fn add_node(&mut self, node: ConsensusNode, key_pair: Ed25519KeyPair) -> Result<()> {
+    pub fn add_node(&mut self, node: ConsensusNode, key_pair: impl SigningKey + 'static) -> Result<()> {
         if self.nodes.len() >= self.config.total_nodes {
             return Err(anyhow!("Maximum nodes reached"));
         }
 
         let node_id = node.id.clone();
         self.nodes.insert(node_id.clone(), node);
-        self.key_pairs.insert(node_id, key_pair);
+        self.key_pairs.insert(node_id, Box::new(key_pair));
         Ok(())
     }
 
@@ -214,7 +217,7 @@ impl ThresholdSignatureService {
             .ok_or_else(|| anyhow!("Key pair not found"))?;
 
         // Assinar a mensagem
-        let signature = self.sign_with_key(key_pair, &request.message_hash)?;
+        let signature = self.sign_with_key(key_pair.as_ref(), &request.message_hash)?;
         let signature_hex = hex::encode(signature);
 
         // Criar assinatura do nó
@@ -383,10 +386,8 @@ impl ThresholdSignatureService {
     }
 
     /// Assina mensagem com chave privada
-    fn sign_with_key(&self, key_pair: &Ed25519KeyPair, message: &str) -> Result<Vec<u8>> {
-        let message_bytes = message.as_bytes();
-        let signature = key_pair.sign(message_bytes);
-        Ok(signature.as_ref().to_vec())
+    fn sign_with_key(&self, key_pair: &dyn SigningKey, message: &str) -> Result<Vec<u8>> {
+        key_pair.try_sign(message.as_bytes())
     }
 
     /// Calcula hash da mensagem
@@ -1,3 +1,5 @@
+pub mod hsm;
+
 use anyhow::Result;
 use base64::{Engine as _, engine::general_purpose};
 
@@ -46,3 +46,64 @@ pub async fn get_elections(pool: &PgPool) -> Result<Vec<Election>> {
     
     Ok(elections)
 }
+
+pub async fn get_election(pool: &PgPool, id: Uuid) -> Result<Option<Election>> {
+    let election = sqlx::query_as::<_, Election>(
+        "SELECT * FROM elections WHERE id = $1"
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+    
+    Ok(election)
+}
+
+pub async fn update_election(pool: &PgPool, election: &Election) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE elections
+        SET name = $2, description = $3, start_date = $4, end_date = $5, updated_at = $6
+        WHERE id = $1
+        "#
+    )
+    .bind(election.id)
+    .bind(&election.name)
+    .bind(&election.description)
+    .bind(election.start_date)
+    .bind(election.end_date)
+    .bind(election.updated_at)
+    .execute(pool)
+    .await?;
+    
+    Ok(())
+}
+
+/// Atualiza o status somente se ainda estiver no status esperado
+pub async fn update_election_status(
+    pool: &PgPool,
+    id: Uuid,
+    expected_status: &str,
+    new_status: &str,
+    updated_at: DateTime<Utc>,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE elections SET status = $3, updated_at = $4 WHERE id = $1 AND status = $2"
+    )
+    .bind(id)
+    .bind(expected_status)
+    .bind(new_status)
+    .bind(updated_at)
+    .execute(pool)
+    .await?;
+    
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn delete_election(pool: &PgPool, id: Uuid) -> Result<()> {
+    sqlx::query("DELETE FROM elections WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    
+    Ok(())
+}
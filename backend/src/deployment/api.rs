@@ -0,0 +1,203 @@
+//! API Administrativa de Rollout
+//!
+//! Endpoints para operadores iniciarem, acompanharem e interromperem
+//! upgrades graduais dos shards do backend e executarem exercícios de DR.
+//! Iniciar e interromper um rollout passam pelo controle duplo. Com o
+//! controlador local, que só simula os shards, as rotas de rollout
+//! respondem 503.
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+
+use crate::auth::rbac::{Permission, Principal};
+use crate::config::Config;
+use super::rolling_upgrade::UpgradeCoordinator;
+use super::dr_drill::{DrillRunner, DrillScenario};
+
+/// Requisição para iniciar rollout
+#[derive(Debug, Deserialize)]
+pub struct StartRolloutRequest {
+    pub target_version: String,
+}
+
+/// Requisição para interromper rollout
+#[derive(Debug, Deserialize)]
+pub struct HaltRolloutRequest {
+    pub reason: String,
+}
+
+/// Obtém o estado do rollout e a versão de cada shard
+pub async fn get_rollout_state(
+    principal: Principal,
+    coordinator: web::Data<UpgradeCoordinator>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageDeployment)?;
+    if let Some(unavailable) = simulated_controller(&coordinator) {
+        return Ok(unavailable);
+    }
+    match coordinator.get_state().await {
+        Ok(state) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "rollout": state
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }))),
+    }
+}
+
+/// Lista os shards com versão e estado atuais
+pub async fn list_shards(
+    principal: Principal,
+    coordinator: web::Data<UpgradeCoordinator>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageDeployment)?;
+    if let Some(unavailable) = simulated_controller(&coordinator) {
+        return Ok(unavailable);
+    }
+    match coordinator.get_state().await {
+        Ok(state) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "shards": state.shards,
+            "total": state.shards.len()
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }))),
+    }
+}
+
+/// Inicia rollout em segundo plano
+pub async fn start_rollout(
+    principal: Principal,
+    coordinator: web::Data<UpgradeCoordinator>,
+    req: web::Json<StartRolloutRequest>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageDeployment)?;
+    if let Some(unavailable) = simulated_controller(&coordinator) {
+        return Ok(unavailable);
+    }
+    log::info!("Rollout to {} requested by {}", req.target_version, actor);
+    let rollout_id = match coordinator.start_rollout(&req.target_version).await {
+        Ok(id) => id,
+        Err(e) => {
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "success": false,
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    let runner = coordinator.get_ref().clone();
+    tokio::spawn(async move {
+        if let Err(e) = runner.run().await {
+            log::error!("Erro ao executar rollout: {}", e);
+        }
+    });
+
+    Ok(HttpResponse::Accepted().json(serde_json::json!({
+        "success": true,
+        "rollout_id": rollout_id,
+        "target_version": req.target_version,
+        "message": "Rollout iniciado"
+    })))
+}
+
+/// Interrompe o rollout em andamento
+pub async fn halt_rollout(
+    principal: Principal,
+    coordinator: web::Data<UpgradeCoordinator>,
+    req: web::Json<HaltRolloutRequest>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageDeployment)?;
+    if let Some(unavailable) = simulated_controller(&coordinator) {
+        return Ok(unavailable);
+    }
+    log::warn!("Rollout halt requested by {}: {}", actor, req.reason);
+    match coordinator.halt(&req.reason).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "Rollout interrompido"
+        }))),
+        Err(e) => Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }))),
+    }
+}
+
+/// Recusa o rollout quando só o controlador simulado está configurado
+fn simulated_controller(coordinator: &UpgradeCoordinator) -> Option<HttpResponse> {
+    if !coordinator.is_simulated() {
+        return None;
+    }
+    Some(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+        "success": false,
+        "error": "Rolling upgrades are disabled: no shard controller is configured"
+    })))
+}
+
+/// Executa exercício de DR em homologação
+pub async fn run_dr_drill(
+    principal: Principal,
+    config: web::Data<Config>,
+    runner: web::Data<DrillRunner>,
+    req: Option<web::Json<DrillScenario>>,
+) -> Result<HttpResponse> {
+    let actor = principal.require(Permission::ManageDeployment)?;
+    if !config.deployment.dr_drill_enabled {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "success": false,
+            "error": "DR drill mode is disabled"
+        })));
+    }
+
+    let scenario = req
+        .map(|r| r.into_inner())
+        .unwrap_or_else(|| DrillRunner::default_scenario(&config.deployment.dr_primary_region));
+
+    log::info!("DR drill {} started by {}", scenario.name, actor);
+    match runner.run(&scenario).await {
+        Ok(signed) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "report": signed,
+            "message": if signed.report.passed { "Exercício aprovado" } else { "Exercício reprovado" }
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }))),
+    }
+}
+
+/// Lista relatórios de exercícios de DR
+pub async fn list_dr_drills(
+    principal: Principal,
+    runner: web::Data<DrillRunner>,
+) -> Result<HttpResponse> {
+    principal.require(Permission::ManageDeployment)?;
+    let reports = runner.list_reports().await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "reports": reports,
+        "total": reports.len()
+    })))
+}
+
+/// Configura as rotas administrativas de rollout e DR
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/admin/rollout")
+            .route("", web::get().to(get_rollout_state))
+            .route("", web::post().to(start_rollout))
+            .route("/shards", web::get().to(list_shards))
+            .route("/halt", web::post().to(halt_rollout))
+    );
+    cfg.service(
+        web::scope("/api/v1/admin/dr-drills")
+            .route("", web::get().to(list_dr_drills))
+            .route("", web::post().to(run_dr_drill))
+    );
+}
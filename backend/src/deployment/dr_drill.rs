@@ -0,0 +1,431 @@
+//! Exercícios de Recuperação de Desastres (DR Drills)
+//!
+//! Executa failovers roteirizados contra o ambiente de homologação (queda da
+//! região primária, restauração de backup, replay do barramento de eventos),
+//! mede RTO/RPO automaticamente e gera um relatório assinado que é arquivado
+//! junto às evidências de conformidade. O relatório é conferido contra a
+//! chave fixada do executor, nunca contra a chave que ele próprio carrega.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use sha2::{Sha256, Digest};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::crypto::hsm::SigningKey;
+
+/// Ambiente no qual exercícios podem ser executados
+pub const DRILL_ENVIRONMENT: &str = "staging";
+
+/// Etapa roteirizada do exercício
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DrillStep {
+    KillRegion { region: String },
+    RestoreFromBackup { backup_id: Option<String> },
+    ReplayEventBus,
+    VerifyHealth,
+}
+
+/// Roteiro de exercício de DR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrillScenario {
+    pub name: String,
+    pub steps: Vec<DrillStep>,
+    pub rto_target_seconds: i64,
+    pub rpo_target_seconds: i64,
+}
+
+/// Backup restaurado durante o exercício
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub backup_id: String,
+    pub taken_at: DateTime<Utc>,
+}
+
+/// Resultado de uma etapa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrillStepResult {
+    pub step: DrillStep,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub success: bool,
+    pub detail: String,
+}
+
+/// Relatório do exercício
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrillReport {
+    pub drill_id: String,
+    pub scenario: String,
+    pub environment: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub steps: Vec<DrillStepResult>,
+    pub failure_injected_at: Option<DateTime<Utc>>,
+    pub recovered_at: Option<DateTime<Utc>>,
+    pub rto_seconds: Option<i64>,
+    pub rpo_seconds: Option<i64>,
+    pub rto_target_seconds: i64,
+    pub rpo_target_seconds: i64,
+    pub passed: bool,
+}
+
+/// Relatório assinado, arquivado como evidência de conformidade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedDrillReport {
+    pub report: DrillReport,
+    pub report_hash: String,
+    pub signature: String,
+    pub public_key: String,
+}
+
+/// Ambiente alvo do exercício
+pub trait DrillTarget: Send + Sync {
+    fn environment(&self) -> &str;
+    fn kill_region(&self, region: &str) -> Result<()>;
+    fn restore_backup(&self, backup_id: Option<&str>) -> Result<BackupInfo>;
+    fn replay_event_bus(&self, since: DateTime<Utc>) -> Result<u64>;
+    fn is_healthy(&self) -> Result<bool>;
+    fn last_committed_event(&self) -> Result<Option<DateTime<Utc>>>;
+}
+
+/// Alvo simulado em memória, usado em desenvolvimento e testes
+pub struct SimulatedDrillTarget {
+    state: std::sync::RwLock<SimulatedState>,
+}
+
+struct SimulatedState {
+    primary_up: bool,
+    events: Vec<DateTime<Utc>>,
+    backup: BackupInfo,
+}
+
+/// Executor de exercícios de DR
+pub struct DrillRunner {
+    target: Arc<dyn DrillTarget>,
+    signing_key: Arc<dyn SigningKey>,
+    evidence_path: PathBuf,
+    recovery_timeout_seconds: u64,
+    poll_interval_ms: u64,
+    reports: Arc<RwLock<Vec<SignedDrillReport>>>,
+}
+
+impl SimulatedDrillTarget {
+    pub fn new(events: Vec<DateTime<Utc>>, backup: BackupInfo) -> Self {
+        Self {
+            state: std::sync::RwLock::new(SimulatedState {
+                primary_up: true,
+                events,
+                backup,
+            }),
+        }
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut SimulatedState) -> T) -> Result<T> {
+        let mut state = self.state.write().map_err(|_| anyhow!("Simulated target poisoned"))?;
+        Ok(f(&mut state))
+    }
+}
+
+impl DrillTarget for SimulatedDrillTarget {
+    fn environment(&self) -> &str {
+        DRILL_ENVIRONMENT
+    }
+
+    fn kill_region(&self, _region: &str) -> Result<()> {
+        self.with_state(|s| s.primary_up = false)
+    }
+
+    fn restore_backup(&self, _backup_id: Option<&str>) -> Result<BackupInfo> {
+        self.with_state(|s| {
+            let taken_at = s.backup.taken_at;
+            s.events.retain(|e| *e <= taken_at);
+            s.primary_up = true;
+            s.backup.clone()
+        })
+    }
+
+    fn replay_event_bus(&self, _since: DateTime<Utc>) -> Result<u64> {
+        // Em implementação real, reprocessaria o barramento a partir de `since`
+        Ok(0)
+    }
+
+    fn is_healthy(&self) -> Result<bool> {
+        self.with_state(|s| s.primary_up)
+    }
+
+    fn last_committed_event(&self) -> Result<Option<DateTime<Utc>>> {
+        self.with_state(|s| s.events.iter().max().cloned())
+    }
+}
+
+impl DrillRunner {
+    /// Cria novo executor de exercícios
+    pub fn new(target: Arc<dyn DrillTarget>, signing_key: impl SigningKey + 'static, evidence_path: PathBuf) -> Self {
+        Self {
+            target,
+            signing_key: Arc::new(signing_key),
+            evidence_path,
+            recovery_timeout_seconds: 900,
+            poll_interval_ms: 1000,
+            reports: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Define tempo máximo de espera pela recuperação
+    pub fn with_recovery_timeout(mut self, timeout_seconds: u64, poll_interval_ms: u64) -> Self {
+        self.recovery_timeout_seconds = timeout_seconds;
+        self.poll_interval_ms = poll_interval_ms;
+        self
+    }
+
+    /// Chave pública (hex) que confere os relatórios deste executor
+    pub fn signing_public_key(&self) -> String {
+        hex::encode(self.signing_key.public_key_bytes())
+    }
+
+    /// Roteiro padrão: queda da região primária, restauração e replay
+    pub fn default_scenario(primary_region: &str) -> DrillScenario {
+        DrillScenario {
+            name: "primary-region-loss".to_string(),
+            steps: vec![
+                DrillStep::KillRegion { region: primary_region.to_string() },
+                DrillStep::RestoreFromBackup { backup_id: None },
+                DrillStep::ReplayEventBus,
+                DrillStep::VerifyHealth,
+            ],
+            rto_target_seconds: 900,
+            rpo_target_seconds: 60,
+        }
+    }
+
+    /// Executa o roteiro, mede RTO/RPO e arquiva o relatório assinado
+    pub async fn run(&self, scenario: &DrillScenario) -> Result<SignedDrillReport> {
+        if self.target.environment() != DRILL_ENVIRONMENT {
+            return Err(anyhow!(
+                "DR drills may only run against {}, got {}",
+                DRILL_ENVIRONMENT,
+                self.target.environment()
+            ));
+        }
+        if scenario.steps.is_empty() {
+            return Err(anyhow!("Drill scenario has no steps"));
+        }
+
+        let started_at = Utc::now();
+        let last_event_before = self.target.last_committed_event()?;
+        let mut failure_injected_at = None;
+        let mut restored_backup: Option<BackupInfo> = None;
+        let mut steps = Vec::new();
+
+        for step in &scenario.steps {
+            let step_started = Utc::now();
+            let outcome = match step {
+                DrillStep::KillRegion { region } => {
+                    failure_injected_at = Some(step_started);
+                    self.target.kill_region(region).map(|_| format!("Região {} derrubada", region))
+                }
+                DrillStep::RestoreFromBackup { backup_id } => {
+                    self.target.restore_backup(backup_id.as_deref()).map(|backup| {
+                        let detail = format!("Backup {} de {} restaurado", backup.backup_id, backup.taken_at);
+                        restored_backup = Some(backup);
+                        detail
+                    })
+                }
+                DrillStep::ReplayEventBus => {
+                    let since = restored_backup.as_ref()
+                        .map(|b| b.taken_at)
+                        .or(failure_injected_at)
+                        .unwrap_or(started_at);
+                    self.target.replay_event_bus(since).map(|n| format!("{} eventos reprocessados", n))
+                }
+                DrillStep::VerifyHealth => {
+                    self.wait_for_recovery().await.map(|_| "Ambiente saudável".to_string())
+                }
+            };
+
+            let success = outcome.is_ok();
+            steps.push(DrillStepResult {
+                step: step.clone(),
+                started_at: step_started,
+                finished_at: Utc::now(),
+                success,
+                detail: outcome.unwrap_or_else(|e| e.to_string()),
+            });
+
+            if !success {
+                break;
+            }
+        }
+
+        let all_steps_ok = steps.len() == scenario.steps.len() && steps.iter().all(|s| s.success);
+        let recovered_at = if all_steps_ok && self.target.is_healthy()? {
+            Some(Utc::now())
+        } else {
+            None
+        };
+
+        let rto_seconds = match (failure_injected_at, recovered_at) {
+            (Some(failed), Some(recovered)) => Some((recovered - failed).num_seconds()),
+            _ => None,
+        };
+
+        // RPO: janela de eventos confirmados antes da falha que não sobreviveram à recuperação
+        let rpo_seconds = if recovered_at.is_some() {
+            let last_event_after = self.target.last_committed_event()?;
+            match (last_event_before, last_event_after) {
+                (Some(before), Some(after)) => Some((before - after).num_seconds().max(0)),
+                (Some(before), None) => restored_backup.as_ref()
+                    .map(|b| (before - b.taken_at).num_seconds().max(0)),
+                (None, _) => Some(0),
+            }
+        } else {
+            None
+        };
+
+        let passed = matches!(rto_seconds, Some(rto) if rto <= scenario.rto_target_seconds)
+            && matches!(rpo_seconds, Some(rpo) if rpo <= scenario.rpo_target_seconds);
+
+        let report = DrillReport {
+            drill_id: Uuid::new_v4().to_string(),
+            scenario: scenario.name.clone(),
+            environment: self.target.environment().to_string(),
+            started_at,
+            finished_at: Utc::now(),
+            steps,
+            failure_injected_at,
+            recovered_at,
+            rto_seconds,
+            rpo_seconds,
+            rto_target_seconds: scenario.rto_target_seconds,
+            rpo_target_seconds: scenario.rpo_target_seconds,
+            passed,
+        };
+
+        let signed = self.sign_report(report)?;
+        self.store_evidence(&signed).await?;
+        self.reports.write().await.push(signed.clone());
+
+        Ok(signed)
+    }
+
+    /// Lista relatórios gerados por este executor
+    pub async fn list_reports(&self) -> Vec<SignedDrillReport> {
+        self.reports.read().await.clone()
+    }
+
+    /// Verifica hash e assinatura de um relatório com a chave confiável (hex)
+    pub fn verify_report(signed: &SignedDrillReport, trusted_public_key: &str) -> Result<bool> {
+        let report_bytes = serde_json::to_vec(&signed.report)?;
+        if hex::encode(Sha256::digest(&report_bytes)) != signed.report_hash {
+            return Ok(false);
+        }
+        if !signed.public_key.eq_ignore_ascii_case(trusted_public_key) {
+            return Ok(false);
+        }
+
+        let public_key = hex::decode(trusted_public_key)?;
+        let signature = hex::decode(&signed.signature)?;
+        let verifier = UnparsedPublicKey::new(&ED25519, public_key);
+        Ok(verifier.verify(&report_bytes, &signature).is_ok())
+    }
+
+    async fn wait_for_recovery(&self) -> Result<()> {
+        let deadline = Utc::now() + chrono::Duration::seconds(self.recovery_timeout_seconds as i64);
+        loop {
+            if self.target.is_healthy()? {
+                return Ok(());
+            }
+            if Utc::now() >= deadline {
+                return Err(anyhow!("Environment not healthy after {}s", self.recovery_timeout_seconds));
+            }
+            tokio::time::sleep(Duration::from_millis(self.poll_interval_ms)).await;
+        }
+    }
+
+    fn sign_report(&self, report: DrillReport) -> Result<SignedDrillReport> {
+        let report_bytes = serde_json::to_vec(&report)?;
+        let signature = self.signing_key.try_sign(&report_bytes)?;
+
+        Ok(SignedDrillReport {
+            report_hash: hex::encode(Sha256::digest(&report_bytes)),
+            signature: hex::encode(signature),
+            public_key: self.signing_public_key(),
+            report,
+        })
+    }
+
+    async fn store_evidence(&self, signed: &SignedDrillReport) -> Result<()> {
+        tokio::fs::create_dir_all(&self.evidence_path).await?;
+        let file = self.evidence_path.join(format!(
+            "dr-drill-{}-{}.json",
+            signed.report.started_at.format("%Y%m%dT%H%M%SZ"),
+            signed.report.drill_id
+        ));
+        tokio::fs::write(&file, serde_json::to_vec_pretty(signed)?).await?;
+        log::info!("Relatório de DR arquivado em {}", file.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+
+    fn runner(events: Vec<DateTime<Utc>>, backup_taken_at: DateTime<Utc>) -> (DrillRunner, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let target = SimulatedDrillTarget::new(events, BackupInfo {
+            backup_id: "bkp-001".to_string(),
+            taken_at: backup_taken_at,
+        });
+        let (key_pair, _) = ThresholdUtils::generate_key_pair().unwrap();
+        let runner = DrillRunner::new(Arc::new(target), key_pair, dir.path().to_path_buf())
+            .with_recovery_timeout(1, 1);
+        (runner, dir)
+    }
+
+    #[tokio::test]
+    async fn test_drill_measures_rpo_and_signs_report() {
+        let now = Utc::now();
+        let events = vec![now - chrono::Duration::seconds(120), now - chrono::Duration::seconds(30)];
+        let (runner, dir) = runner(events, now - chrono::Duration::seconds(90));
+
+        let signed = runner.run(&DrillRunner::default_scenario("sa-east-1")).await.unwrap();
+
+        assert_eq!(signed.report.rpo_seconds, Some(90));
+        assert!(signed.report.rto_seconds.is_some());
+        assert!(!signed.report.passed, "RPO of 90s exceeds the 60s target");
+        let trusted = runner.signing_public_key();
+        assert!(DrillRunner::verify_report(&signed, &trusted).unwrap());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let mut tampered = signed.clone();
+        tampered.report.passed = true;
+        assert!(!DrillRunner::verify_report(&tampered, &trusted).unwrap());
+
+        // Relatório forjado com outra chave, que vai embutida nele, não passa
+        let (forger, _forger_dir) = self::runner(vec![], now);
+        let forged = forger.sign_report(tampered.report).unwrap();
+        assert!(DrillRunner::verify_report(&forged, &forged.public_key).unwrap());
+        assert!(!DrillRunner::verify_report(&forged, &trusted).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_drill_passes_with_fresh_backup() {
+        let now = Utc::now();
+        let (runner, _dir) = runner(vec![now - chrono::Duration::seconds(10)], now);
+
+        let signed = runner.run(&DrillRunner::default_scenario("sa-east-1")).await.unwrap();
+
+        assert_eq!(signed.report.rpo_seconds, Some(0));
+        assert!(signed.report.passed);
+        assert_eq!(runner.list_reports().await.len(), 1);
+    }
+}
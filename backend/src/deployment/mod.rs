@@ -0,0 +1,12 @@
+//! Módulo de Implantação do FORTIS
+//! 
+//! Coordena atualizações graduais (rolling upgrades) dos shards do backend,
+//! garantindo que apenas um shard fique fora de serviço por vez, e executa
+//! exercícios de recuperação de desastres em homologação.
+
+pub mod rolling_upgrade;
+pub mod dr_drill;
+pub mod api;
+
+pub use rolling_upgrade::*;
+pub use dr_drill::*;
@@ -0,0 +1,574 @@
+//! Coordenador de Upgrades Graduais (Rolling Upgrade)
+//!
+//! Atualiza os shards do backend um de cada vez: drena o tráfego do shard,
+//! implanta a nova versão, verifica a saúde e compara métricas canário com a
+//! linha de base coletada antes do upgrade. Qualquer regressão interrompe o
+//! rollout e gera um alerta crítico no sistema de monitoramento.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::monitoring::{MonitoringSystem, AlertSeverity};
+
+/// Configuração do rollout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutConfig {
+    pub drain_timeout_seconds: u64,
+    pub poll_interval_ms: u64,
+    pub health_check_retries: u32,
+    pub canary_window_seconds: u64,
+    pub max_error_rate_increase: f64,
+    pub max_latency_increase_percent: f64,
+    pub max_response_mismatches: u64,
+}
+
+/// Estado de um shard durante o rollout
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ShardState {
+    Serving,
+    Draining,
+    Upgrading,
+    Verifying,
+    Failed,
+}
+
+/// Informações de um shard do backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardInfo {
+    pub id: String,
+    pub address: String,
+    pub version: String,
+    pub state: ShardState,
+    pub last_updated: DateTime<Utc>,
+}
+
+/// Amostra de métricas canário de um shard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanarySample {
+    pub error_rate_percent: f64,
+    pub p99_latency_ms: f64,
+    /// Respostas divergentes em relação a um shard ainda na versão anterior
+    pub response_mismatches: u64,
+}
+
+/// Status geral do rollout
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RolloutStatus {
+    Idle,
+    InProgress,
+    Completed,
+    Halted,
+}
+
+/// Resultado do upgrade de um shard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutStep {
+    pub shard_id: String,
+    pub from_version: String,
+    pub to_version: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Estado do rollout exposto na API administrativa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutState {
+    pub rollout_id: Option<String>,
+    pub target_version: Option<String>,
+    pub status: RolloutStatus,
+    pub current_shard: Option<String>,
+    pub halt_reason: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub steps: Vec<RolloutStep>,
+    pub shards: Vec<ShardInfo>,
+}
+
+/// Controlador dos shards (orquestrador, balanceador, etc.)
+pub trait ShardController: Send + Sync {
+    fn list_shards(&self) -> Result<Vec<ShardInfo>>;
+    fn start_drain(&self, shard_id: &str) -> Result<()>;
+    fn active_connections(&self, shard_id: &str) -> Result<u64>;
+    fn deploy(&self, shard_id: &str, version: &str) -> Result<()>;
+    fn resume_traffic(&self, shard_id: &str) -> Result<()>;
+    fn is_healthy(&self, shard_id: &str) -> Result<bool>;
+    fn canary_sample(&self, shard_id: &str) -> Result<CanarySample>;
+
+    /// Controlador que só simula drenagem, implantação e saúde
+    fn is_simulated(&self) -> bool {
+        false
+    }
+}
+
+/// Controlador local em memória, usado em desenvolvimento e testes; não
+/// implanta nada, por isso a API de rollout fica desabilitada com ele
+pub struct LocalShardController {
+    shards: std::sync::RwLock<HashMap<String, ShardInfo>>,
+}
+
+/// Coordenador de upgrades graduais
+#[derive(Clone)]
+pub struct UpgradeCoordinator {
+    config: RolloutConfig,
+    controller: Arc<dyn ShardController>,
+    state: Arc<RwLock<RolloutState>>,
+    monitoring: Option<Arc<MonitoringSystem>>,
+}
+
+impl LocalShardController {
+    pub fn new(shards: Vec<(String, String)>, version: &str) -> Self {
+        let shards = shards
+            .into_iter()
+            .map(|(id, address)| {
+                let info = ShardInfo {
+                    id: id.clone(),
+                    address,
+                    version: version.to_string(),
+                    state: ShardState::Serving,
+                    last_updated: Utc::now(),
+                };
+                (id, info)
+            })
+            .collect();
+
+        Self {
+            shards: std::sync::RwLock::new(shards),
+        }
+    }
+
+    fn with_shard<T>(&self, shard_id: &str, f: impl FnOnce(&mut ShardInfo) -> T) -> Result<T> {
+        let mut shards = self.shards.write().map_err(|_| anyhow!("Shard registry poisoned"))?;
+        let shard = shards.get_mut(shard_id)
+            .ok_or_else(|| anyhow!("Shard not found: {}", shard_id))?;
+        Ok(f(shard))
+    }
+}
+
+impl ShardController for LocalShardController {
+    fn list_shards(&self) -> Result<Vec<ShardInfo>> {
+        let shards = self.shards.read().map_err(|_| anyhow!("Shard registry poisoned"))?;
+        Ok(shards.values().cloned().collect())
+    }
+
+    fn start_drain(&self, shard_id: &str) -> Result<()> {
+        self.with_shard(shard_id, |shard| shard.state = ShardState::Draining)
+    }
+
+    fn active_connections(&self, shard_id: &str) -> Result<u64> {
+        // Em implementação real, consultaria o balanceador de carga
+        self.with_shard(shard_id, |_| 0)
+    }
+
+    fn deploy(&self, shard_id: &str, version: &str) -> Result<()> {
+        self.with_shard(shard_id, |shard| {
+            shard.version = version.to_string();
+            shard.last_updated = Utc::now();
+        })
+    }
+
+    fn resume_traffic(&self, shard_id: &str) -> Result<()> {
+        self.with_shard(shard_id, |shard| shard.state = ShardState::Serving)
+    }
+
+    fn is_healthy(&self, shard_id: &str) -> Result<bool> {
+        self.with_shard(shard_id, |_| true)
+    }
+
+    fn canary_sample(&self, shard_id: &str) -> Result<CanarySample> {
+        self.with_shard(shard_id, |_| CanarySample {
+            error_rate_percent: 0.0,
+            p99_latency_ms: 0.0,
+            response_mismatches: 0,
+        })
+    }
+
+    fn is_simulated(&self) -> bool {
+        true
+    }
+}
+
+impl UpgradeCoordinator {
+    /// Cria novo coordenador de upgrades
+    pub fn new(config: RolloutConfig, controller: Arc<dyn ShardController>) -> Self {
+        Self {
+            config,
+            controller,
+            state: Arc::new(RwLock::new(RolloutState::default())),
+            monitoring: None,
+        }
+    }
+
+    /// Define o sistema de monitoramento usado para alertas
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringSystem>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
+    }
+
+    /// O controlador configurado só simula os shards
+    pub fn is_simulated(&self) -> bool {
+        self.controller.is_simulated()
+    }
+
+    /// Obtém o estado atual do rollout e a versão de cada shard
+    pub async fn get_state(&self) -> Result<RolloutState> {
+        self.refresh_shards().await?;
+        Ok(self.state.read().await.clone())
+    }
+
+    /// Inicia um novo rollout para a versão informada
+    pub async fn start_rollout(&self, target_version: &str) -> Result<String> {
+        if target_version.trim().is_empty() {
+            return Err(anyhow!("Target version must not be empty"));
+        }
+
+        let mut state = self.state.write().await;
+        if state.status == RolloutStatus::InProgress {
+            return Err(anyhow!(
+                "Rollout already in progress: {}",
+                state.rollout_id.clone().unwrap_or_default()
+            ));
+        }
+
+        let rollout_id = Uuid::new_v4().to_string();
+        state.rollout_id = Some(rollout_id.clone());
+        state.target_version = Some(target_version.to_string());
+        state.status = RolloutStatus::InProgress;
+        state.current_shard = None;
+        state.halt_reason = None;
+        state.started_at = Some(Utc::now());
+        state.finished_at = None;
+        state.steps.clear();
+
+        Ok(rollout_id)
+    }
+
+    /// Executa o rollout iniciado, um shard por vez
+    pub async fn run(&self) -> Result<RolloutStatus> {
+        let target_version = {
+            let state = self.state.read().await;
+            if state.status != RolloutStatus::InProgress {
+                return Err(anyhow!("No rollout in progress"));
+            }
+            state.target_version.clone().unwrap_or_default()
+        };
+
+        let mut shards = self.controller.list_shards()?;
+        shards.sort_by(|a, b| a.id.cmp(&b.id));
+        self.refresh_shards().await?;
+
+        for shard in shards.into_iter().filter(|s| s.version != target_version) {
+            // Interrupção manual solicitada pelo operador
+            if self.state.read().await.status != RolloutStatus::InProgress {
+                return Ok(RolloutStatus::Halted);
+            }
+
+            self.state.write().await.current_shard = Some(shard.id.clone());
+            let started_at = Utc::now();
+            let result = self.upgrade_shard(&shard, &target_version).await;
+
+            let step = RolloutStep {
+                shard_id: shard.id.clone(),
+                from_version: shard.version.clone(),
+                to_version: target_version.clone(),
+                started_at,
+                finished_at: Utc::now(),
+                success: result.is_ok(),
+                message: match &result {
+                    Ok(()) => "Upgrade concluído".to_string(),
+                    Err(e) => e.to_string(),
+                },
+            };
+            self.state.write().await.steps.push(step);
+
+            if let Err(e) = result {
+                self.set_shard_state(&shard.id, ShardState::Failed).await;
+                // O shard com regressão não deve voltar a receber tráfego
+                let _ = self.controller.start_drain(&shard.id);
+                let reason = format!("Shard {} failed upgrade to {}: {}", shard.id, target_version, e);
+                self.halt_with_alert(&reason).await;
+                return Ok(RolloutStatus::Halted);
+            }
+        }
+
+        let mut state = self.state.write().await;
+        if state.status == RolloutStatus::InProgress {
+            state.status = RolloutStatus::Completed;
+            state.current_shard = None;
+            state.finished_at = Some(Utc::now());
+        }
+
+        Ok(state.status.clone())
+    }
+
+    /// Interrompe o rollout em andamento
+    pub async fn halt(&self, reason: &str) -> Result<()> {
+        let mut state = self.state.write().await;
+        if state.status != RolloutStatus::InProgress {
+            return Err(anyhow!("No rollout in progress"));
+        }
+
+        state.status = RolloutStatus::Halted;
+        state.halt_reason = Some(reason.to_string());
+        state.finished_at = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Atualiza um único shard: drenagem, implantação, saúde e canário
+    async fn upgrade_shard(&self, shard: &ShardInfo, target_version: &str) -> Result<()> {
+        let baseline = self.controller.canary_sample(&shard.id)?;
+
+        self.set_shard_state(&shard.id, ShardState::Draining).await;
+        self.controller.start_drain(&shard.id)?;
+        self.wait_for_drain(&shard.id).await?;
+
+        self.set_shard_state(&shard.id, ShardState::Upgrading).await;
+        self.controller.deploy(&shard.id, target_version)?;
+
+        self.set_shard_state(&shard.id, ShardState::Verifying).await;
+        self.wait_for_health(&shard.id).await?;
+
+        self.controller.resume_traffic(&shard.id)?;
+        tokio::time::sleep(Duration::from_secs(self.config.canary_window_seconds)).await;
+
+        let sample = self.controller.canary_sample(&shard.id)?;
+        if let Some(regression) = self.detect_regression(&baseline, &sample) {
+            return Err(anyhow!("Canary regression: {}", regression));
+        }
+
+        self.set_shard_state(&shard.id, ShardState::Serving).await;
+        self.refresh_shards().await?;
+        Ok(())
+    }
+
+    /// Aguarda o encerramento das conexões ativas do shard
+    async fn wait_for_drain(&self, shard_id: &str) -> Result<()> {
+        let deadline = Utc::now() + chrono::Duration::seconds(self.config.drain_timeout_seconds as i64);
+
+        loop {
+            let connections = self.controller.active_connections(shard_id)?;
+            if connections == 0 {
+                return Ok(());
+            }
+            if Utc::now() >= deadline {
+                return Err(anyhow!("Drain timeout with {} active connections", connections));
+            }
+            tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+        }
+    }
+
+    /// Aguarda o shard reportar saúde após a implantação
+    async fn wait_for_health(&self, shard_id: &str) -> Result<()> {
+        for _ in 0..self.config.health_check_retries.max(1) {
+            if self.controller.is_healthy(shard_id)? {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+        }
+
+        Err(anyhow!("Health check failed after {} attempts", self.config.health_check_retries))
+    }
+
+    /// Compara a amostra canário com a linha de base anterior ao upgrade
+    fn detect_regression(&self, baseline: &CanarySample, sample: &CanarySample) -> Option<String> {
+        let error_delta = sample.error_rate_percent - baseline.error_rate_percent;
+        if error_delta > self.config.max_error_rate_increase {
+            return Some(format!(
+                "error rate increased from {:.2}% to {:.2}%",
+                baseline.error_rate_percent, sample.error_rate_percent
+            ));
+        }
+
+        if baseline.p99_latency_ms > 0.0 {
+            let latency_increase = (sample.p99_latency_ms - baseline.p99_latency_ms)
+                / baseline.p99_latency_ms * 100.0;
+            if latency_increase > self.config.max_latency_increase_percent {
+                return Some(format!(
+                    "p99 latency increased from {:.1}ms to {:.1}ms",
+                    baseline.p99_latency_ms, sample.p99_latency_ms
+                ));
+            }
+        }
+
+        if sample.response_mismatches > self.config.max_response_mismatches {
+            return Some(format!("{} canary responses diverged", sample.response_mismatches));
+        }
+
+        None
+    }
+
+    async fn halt_with_alert(&self, reason: &str) {
+        {
+            let mut state = self.state.write().await;
+            state.status = RolloutStatus::Halted;
+            state.halt_reason = Some(reason.to_string());
+            state.finished_at = Some(Utc::now());
+        }
+
+        log::error!("Rollout interrompido: {}", reason);
+        if let Some(monitoring) = &self.monitoring {
+            if let Err(e) = monitoring.create_alert(AlertSeverity::Critical, "rolling_upgrade", reason).await {
+                log::error!("Falha ao criar alerta de rollout: {}", e);
+            }
+        }
+    }
+
+    async fn set_shard_state(&self, shard_id: &str, shard_state: ShardState) {
+        let mut state = self.state.write().await;
+        if let Some(shard) = state.shards.iter_mut().find(|s| s.id == shard_id) {
+            shard.state = shard_state;
+            shard.last_updated = Utc::now();
+        }
+    }
+
+    /// Sincroniza versões dos shards com o controlador, preservando o estado do rollout
+    async fn refresh_shards(&self) -> Result<()> {
+        let mut listed = self.controller.list_shards()?;
+        listed.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut state = self.state.write().await;
+        let previous: HashMap<String, ShardState> = state.shards
+            .iter()
+            .map(|s| (s.id.clone(), s.state.clone()))
+            .collect();
+
+        for shard in listed.iter_mut() {
+            if let Some(shard_state) = previous.get(&shard.id) {
+                shard.state = shard_state.clone();
+            }
+        }
+        state.shards = listed;
+        Ok(())
+    }
+}
+
+impl Default for RolloutConfig {
+    fn default() -> Self {
+        Self {
+            drain_timeout_seconds: 300,
+            poll_interval_ms: 1000,
+            health_check_retries: 10,
+            canary_window_seconds: 60,
+            max_error_rate_increase: 1.0,
+            max_latency_increase_percent: 25.0,
+            max_response_mismatches: 0,
+        }
+    }
+}
+
+impl Default for RolloutState {
+    fn default() -> Self {
+        Self {
+            rollout_id: None,
+            target_version: None,
+            status: RolloutStatus::Idle,
+            current_shard: None,
+            halt_reason: None,
+            started_at: None,
+            finished_at: None,
+            steps: Vec::new(),
+            shards: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> RolloutConfig {
+        RolloutConfig {
+            drain_timeout_seconds: 1,
+            poll_interval_ms: 1,
+            health_check_retries: 2,
+            canary_window_seconds: 0,
+            ..RolloutConfig::default()
+        }
+    }
+
+    /// Controlador que simula regressão ao implantar uma versão específica
+    struct RegressingController {
+        inner: LocalShardController,
+        bad_version: String,
+    }
+
+    impl ShardController for RegressingController {
+        fn list_shards(&self) -> Result<Vec<ShardInfo>> { self.inner.list_shards() }
+        fn start_drain(&self, id: &str) -> Result<()> { self.inner.start_drain(id) }
+        fn active_connections(&self, id: &str) -> Result<u64> { self.inner.active_connections(id) }
+        fn deploy(&self, id: &str, v: &str) -> Result<()> { self.inner.deploy(id, v) }
+        fn resume_traffic(&self, id: &str) -> Result<()> { self.inner.resume_traffic(id) }
+        fn is_healthy(&self, id: &str) -> Result<bool> { self.inner.is_healthy(id) }
+
+        fn canary_sample(&self, id: &str) -> Result<CanarySample> {
+            let version = self.inner.with_shard(id, |s| s.version.clone())?;
+            let error_rate_percent = if version == self.bad_version { 10.0 } else { 0.1 };
+            Ok(CanarySample { error_rate_percent, p99_latency_ms: 50.0, response_mismatches: 0 })
+        }
+    }
+
+    fn shards() -> Vec<(String, String)> {
+        vec![
+            ("shard-a".to_string(), "10.0.0.1:8080".to_string()),
+            ("shard-b".to_string(), "10.0.0.2:8080".to_string()),
+            ("shard-c".to_string(), "10.0.0.3:8080".to_string()),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_rollout_upgrades_all_shards() {
+        let controller = Arc::new(LocalShardController::new(shards(), "1.0.0"));
+        let coordinator = UpgradeCoordinator::new(test_config(), controller);
+        assert!(coordinator.is_simulated());
+
+        coordinator.start_rollout("1.1.0").await.unwrap();
+        assert_eq!(coordinator.run().await.unwrap(), RolloutStatus::Completed);
+
+        let state = coordinator.get_state().await.unwrap();
+        assert_eq!(state.steps.len(), 3);
+        assert!(state.shards.iter().all(|s| s.version == "1.1.0" && s.state == ShardState::Serving));
+    }
+
+    #[tokio::test]
+    async fn test_rollout_halts_on_canary_regression() {
+        let controller = Arc::new(RegressingController {
+            inner: LocalShardController::new(shards(), "1.0.0"),
+            bad_version: "2.0.0".to_string(),
+        });
+        let monitoring = Arc::new(MonitoringSystem::new());
+        let coordinator = UpgradeCoordinator::new(test_config(), controller)
+            .with_monitoring(monitoring.clone());
+
+        coordinator.start_rollout("2.0.0").await.unwrap();
+        assert_eq!(coordinator.run().await.unwrap(), RolloutStatus::Halted);
+
+        let state = coordinator.get_state().await.unwrap();
+        assert_eq!(state.steps.len(), 1);
+        assert!(!state.steps[0].success);
+
+        // Apenas o primeiro shard foi atualizado; os demais seguem na versão anterior
+        let versions: Vec<&str> = state.shards.iter().map(|s| s.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.0.0", "1.0.0", "1.0.0"]);
+        assert_eq!(state.shards[0].state, ShardState::Failed);
+        assert_eq!(monitoring.get_active_alerts().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_concurrent_rollout() {
+        let controller = Arc::new(LocalShardController::new(shards(), "1.0.0"));
+        let coordinator = UpgradeCoordinator::new(test_config(), controller);
+
+        coordinator.start_rollout("1.1.0").await.unwrap();
+        assert!(coordinator.start_rollout("1.2.0").await.is_err());
+
+        coordinator.halt("manual").await.unwrap();
+        assert!(coordinator.run().await.is_err());
+    }
+}
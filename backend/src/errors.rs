@@ -0,0 +1,280 @@
+//! Erros unificados do FORTIS
+//!
+//! `FortisError` associa cada falha a um código estável, devolvido pela API
+//! junto da mensagem técnica. As urnas traduzem o código para a mensagem
+//! exibida ao eleitor usando o catálogo de mensagens distribuído no pacote
+//! de configuração, sem depender de nova versão do software da urna. Há um
+//! catálogo por idioma (ver `crate::i18n`).
+
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::models::ApiResponse;
+
+/// Erro unificado da API
+#[derive(Debug, Error)]
+pub enum FortisError {
+    #[error("Biometric data not recognized")]
+    BiometricNotRecognized,
+    #[error("Digital certificate is invalid or revoked")]
+    CertificateInvalid,
+    #[error("Voter not found")]
+    VoterNotFound,
+    #[error("Voter not eligible for this election")]
+    VoterNotEligible,
+    #[error("Voter has already voted")]
+    VoterAlreadyVoted,
+    #[error("Election not found: {0}")]
+    ElectionNotFound(String),
+    #[error("Election is not active")]
+    ElectionNotActive,
+    #[error("Candidate not found")]
+    CandidateNotFound,
+    #[error("Urna was replaced by a contingency urna")]
+    UrnaRetired,
+    #[error("Urna is not authorized")]
+    UrnaNotAuthorized,
+    #[error("Authentication required")]
+    AuthenticationRequired,
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Rate limit exceeded")]
+    RateLimitExceeded,
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl FortisError {
+    /// Código estável usado pelo catálogo de mensagens
+    pub fn code(&self) -> &'static str {
+        match self {
+            FortisError::BiometricNotRecognized => "BIOMETRIC_NOT_RECOGNIZED",
+            FortisError::CertificateInvalid => "CERTIFICATE_INVALID",
+            FortisError::VoterNotFound => "VOTER_NOT_FOUND",
+            FortisError::VoterNotEligible => "VOTER_NOT_ELIGIBLE",
+            FortisError::VoterAlreadyVoted => "VOTER_ALREADY_VOTED",
+            FortisError::ElectionNotFound(_) => "ELECTION_NOT_FOUND",
+            FortisError::ElectionNotActive => "ELECTION_NOT_ACTIVE",
+            FortisError::CandidateNotFound => "CANDIDATE_NOT_FOUND",
+            FortisError::UrnaRetired => "URNA_RETIRED",
+            FortisError::UrnaNotAuthorized => "URNA_NOT_AUTHORIZED",
+            FortisError::AuthenticationRequired => "AUTHENTICATION_REQUIRED",
+            FortisError::PermissionDenied(_) => "PERMISSION_DENIED",
+            FortisError::InvalidRequest(_) => "INVALID_REQUEST",
+            FortisError::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            FortisError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            FortisError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl ResponseError for FortisError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            FortisError::BiometricNotRecognized
+            | FortisError::CertificateInvalid
+            | FortisError::AuthenticationRequired => StatusCode::UNAUTHORIZED,
+            FortisError::VoterNotEligible
+            | FortisError::UrnaRetired
+            | FortisError::UrnaNotAuthorized
+            | FortisError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+            FortisError::VoterNotFound
+            | FortisError::ElectionNotFound(_)
+            | FortisError::CandidateNotFound => StatusCode::NOT_FOUND,
+            FortisError::VoterAlreadyVoted | FortisError::ElectionNotActive => StatusCode::CONFLICT,
+            FortisError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            FortisError::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            FortisError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            FortisError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ApiResponse::<()>::from_error(self))
+    }
+}
+
+/// Orientação ao eleitor que acompanha a mensagem
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VoterAction {
+    /// O eleitor pode tentar novamente
+    Retry,
+    /// O eleitor deve procurar o mesário
+    CallMesario,
+    /// O eleitor não pode votar nesta urna
+    None,
+}
+
+/// Mensagem exibida ao eleitor para um código de erro
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoterMessage {
+    pub message: String,
+    pub action: VoterAction,
+}
+
+/// Catálogo de mensagens distribuído no pacote de configuração da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorCatalog {
+    pub locale: String,
+    /// SHA-256 das mensagens, usado pela urna para detectar atualizações
+    pub version: String,
+    pub fallback: VoterMessage,
+    pub messages: BTreeMap<String, VoterMessage>,
+}
+
+impl ErrorCatalog {
+    /// Catálogo padrão em português
+    pub fn pt_br() -> Self {
+        let entries = [
+            ("BIOMETRIC_NOT_RECOGNIZED", "Biometria não reconhecida, procure o mesário", VoterAction::CallMesario),
+            ("CERTIFICATE_INVALID", "Certificado digital inválido, procure o mesário", VoterAction::CallMesario),
+            ("VOTER_NOT_FOUND", "Eleitor não encontrado nesta seção, procure o mesário", VoterAction::CallMesario),
+            ("VOTER_NOT_ELIGIBLE", "Eleitor não habilitado para votar nesta eleição", VoterAction::CallMesario),
+            ("VOTER_ALREADY_VOTED", "Consta que este eleitor já votou, procure o mesário", VoterAction::CallMesario),
+            ("ELECTION_NOT_FOUND", "Eleição não disponível nesta urna", VoterAction::None),
+            ("ELECTION_NOT_ACTIVE", "A votação não está aberta neste momento", VoterAction::None),
+            ("CANDIDATE_NOT_FOUND", "Número inválido, confira e digite novamente", VoterAction::Retry),
+            ("URNA_RETIRED", "Urna desativada, procure o mesário", VoterAction::CallMesario),
+            ("URNA_NOT_AUTHORIZED", "Urna não autorizada, procure o mesário", VoterAction::CallMesario),
+            ("AUTHENTICATION_REQUIRED", "Operação não autorizada, procure o mesário", VoterAction::CallMesario),
+            ("PERMISSION_DENIED", "Operação não autorizada, procure o mesário", VoterAction::CallMesario),
+            ("INVALID_REQUEST", "Não foi possível concluir a operação, tente novamente", VoterAction::Retry),
+            ("RATE_LIMIT_EXCEEDED", "Aguarde alguns instantes e tente novamente", VoterAction::Retry),
+            ("SERVICE_UNAVAILABLE", "Sistema temporariamente indisponível, seu voto será registrado localmente", VoterAction::Retry),
+            ("INTERNAL_ERROR", "Ocorreu um erro, procure o mesário", VoterAction::CallMesario),
+            // Códigos gerados pela própria urna
+            ("PRINTER_PAPER_OUT", "Impressora sem papel, procure o mesário", VoterAction::CallMesario),
+            ("RECEIPT_ALREADY_PRINTED", "O comprovante deste voto já foi impresso", VoterAction::None),
+            ("FINGERPRINT_TIMEOUT", "Digital não lida, posicione o dedo no leitor", VoterAction::Retry),
+            ("FINGERPRINT_LOW_QUALITY", "Digital não lida com clareza, limpe o dedo e tente novamente", VoterAction::Retry),
+            ("FINGERPRINT_LIVENESS_FAILED", "Digital não aceita, procure o mesário", VoterAction::CallMesario),
+            ("CONFIG_SEALED", "Urna em votação, configuração bloqueada", VoterAction::CallMesario),
+            ("CONFIG_SEAL_VIOLATION", "Urna bloqueada por segurança, procure o mesário", VoterAction::CallMesario),
+            ("CERTIFICATE_MISMATCH", "Cartão não pertence ao eleitor identificado, procure o mesário", VoterAction::CallMesario),
+            ("FEEDBACK_CHECK_FAILED", "Som ou tela da urna fora do padrão, procure o mesário", VoterAction::CallMesario),
+            ("STORAGE_INSUFFICIENT", "Urna sem espaço de armazenamento, procure o mesário", VoterAction::CallMesario),
+            ("ATTESTATION_FAILED", "Urna bloqueada por segurança, procure o mesário", VoterAction::CallMesario),
+            ("VOTER_NOT_ADMITTED", "Eleitor diferente do liberado pelo mesário, procure o mesário", VoterAction::CallMesario),
+        ];
+
+        Self::from_entries("pt-BR", "Ocorreu um erro, procure o mesário", &entries)
+    }
+
+    /// Catálogo em espanhol
+    pub fn es() -> Self {
+        let entries = [
+            ("BIOMETRIC_NOT_RECOGNIZED", "Biometría no reconocida, busque al mesario", VoterAction::CallMesario),
+            ("CERTIFICATE_INVALID", "Certificado digital inválido, busque al mesario", VoterAction::CallMesario),
+            ("VOTER_NOT_FOUND", "Elector no encontrado en esta sección, busque al mesario", VoterAction::CallMesario),
+            ("VOTER_NOT_ELIGIBLE", "Elector no habilitado para votar en esta elección", VoterAction::CallMesario),
+            ("VOTER_ALREADY_VOTED", "Consta que este elector ya votó, busque al mesario", VoterAction::CallMesario),
+            ("ELECTION_NOT_FOUND", "Elección no disponible en esta urna", VoterAction::None),
+            ("ELECTION_NOT_ACTIVE", "La votación no está abierta en este momento", VoterAction::None),
+            ("CANDIDATE_NOT_FOUND", "Número inválido, verifique y digite nuevamente", VoterAction::Retry),
+            ("URNA_RETIRED", "Urna desactivada, busque al mesario", VoterAction::CallMesario),
+            ("URNA_NOT_AUTHORIZED", "Urna no autorizada, busque al mesario", VoterAction::CallMesario),
+            ("AUTHENTICATION_REQUIRED", "Operación no autorizada, busque al mesario", VoterAction::CallMesario),
+            ("PERMISSION_DENIED", "Operación no autorizada, busque al mesario", VoterAction::CallMesario),
+            ("INVALID_REQUEST", "No fue posible concluir la operación, inténtelo de nuevo", VoterAction::Retry),
+            ("RATE_LIMIT_EXCEEDED", "Espere unos instantes e inténtelo de nuevo", VoterAction::Retry),
+            ("SERVICE_UNAVAILABLE", "Sistema temporalmente no disponible, su voto se registrará localmente", VoterAction::Retry),
+            ("INTERNAL_ERROR", "Ocurrió un error, busque al mesario", VoterAction::CallMesario),
+            ("PRINTER_PAPER_OUT", "Impresora sin papel, busque al mesario", VoterAction::CallMesario),
+            ("RECEIPT_ALREADY_PRINTED", "El comprobante de este voto ya fue impreso", VoterAction::None),
+            ("FINGERPRINT_TIMEOUT", "Huella no leída, coloque el dedo en el lector", VoterAction::Retry),
+            ("FINGERPRINT_LOW_QUALITY", "Huella poco clara, limpie el dedo e inténtelo de nuevo", VoterAction::Retry),
+            ("FINGERPRINT_LIVENESS_FAILED", "Huella no aceptada, busque al mesario", VoterAction::CallMesario),
+            ("CONFIG_SEALED", "Urna en votación, configuración bloqueada", VoterAction::CallMesario),
+            ("CONFIG_SEAL_VIOLATION", "Urna bloqueada por seguridad, busque al mesario", VoterAction::CallMesario),
+            ("CERTIFICATE_MISMATCH", "La tarjeta no pertenece al elector identificado, busque al mesario", VoterAction::CallMesario),
+            ("FEEDBACK_CHECK_FAILED", "Sonido o pantalla de la urna fuera del estándar, busque al mesario", VoterAction::CallMesario),
+            ("STORAGE_INSUFFICIENT", "Urna sin espacio de almacenamiento, busque al mesario", VoterAction::CallMesario),
+            ("ATTESTATION_FAILED", "Urna bloqueada por seguridad, busque al mesario", VoterAction::CallMesario),
+            ("VOTER_NOT_ADMITTED", "Elector distinto del habilitado por el mesario, busque al mesario", VoterAction::CallMesario),
+        ];
+
+        Self::from_entries("es", "Ocurrió un error, busque al mesario", &entries)
+    }
+
+    fn from_entries(locale: &str, fallback: &str, entries: &[(&str, &str, VoterAction)]) -> Self {
+        let messages: BTreeMap<String, VoterMessage> = entries
+            .iter()
+            .map(|(code, message, action)| (code.to_string(), VoterMessage {
+                message: message.to_string(),
+                action: *action,
+            }))
+            .collect();
+
+        Self::new(locale, VoterMessage {
+            message: fallback.to_string(),
+            action: VoterAction::CallMesario,
+        }, messages)
+    }
+
+    pub fn new(locale: &str, fallback: VoterMessage, messages: BTreeMap<String, VoterMessage>) -> Self {
+        let digest = Sha256::digest(serde_json::to_vec(&(&fallback, &messages)).unwrap_or_default());
+        Self {
+            locale: locale.to_string(),
+            version: hex::encode(digest),
+            fallback,
+            messages,
+        }
+    }
+
+    /// Mensagem ao eleitor para o código, com mensagem genérica se ausente
+    pub fn voter_message(&self, code: &str) -> &VoterMessage {
+        self.messages.get(code).unwrap_or(&self.fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_ERRORS: &[FortisError] = &[
+        FortisError::BiometricNotRecognized,
+        FortisError::CertificateInvalid,
+        FortisError::VoterNotFound,
+        FortisError::VoterNotEligible,
+        FortisError::VoterAlreadyVoted,
+        FortisError::ElectionNotActive,
+        FortisError::CandidateNotFound,
+        FortisError::UrnaRetired,
+        FortisError::UrnaNotAuthorized,
+        FortisError::AuthenticationRequired,
+        FortisError::RateLimitExceeded,
+    ];
+
+    #[test]
+    fn test_every_code_has_pt_br_message() {
+        let catalog = ErrorCatalog::pt_br();
+        for error in ALL_ERRORS {
+            assert!(catalog.messages.contains_key(error.code()), "missing {}", error.code());
+        }
+        assert!(catalog.messages.contains_key(FortisError::Internal(String::new()).code()));
+    }
+
+    #[test]
+    fn test_es_catalog_has_the_same_codes() {
+        let pt_br = ErrorCatalog::pt_br();
+        let es = ErrorCatalog::es();
+        assert!(pt_br.messages.keys().eq(es.messages.keys()));
+        assert_ne!(pt_br.version, es.version);
+    }
+
+    #[test]
+    fn test_unknown_code_uses_fallback() {
+        let catalog = ErrorCatalog::pt_br();
+        let message = catalog.voter_message("SOMETHING_NEW");
+        assert_eq!(message.action, VoterAction::CallMesario);
+        assert_eq!(catalog.version, ErrorCatalog::pt_br().version);
+    }
+}
@@ -0,0 +1,69 @@
+//! API gRPC para comunicação urna → backend
+//!
+//! Servidor tonic que roda ao lado do servidor HTTP (actix), em porta
+//! própria. Com TLS interno habilitado a conexão exige certificado de
+//! cliente (mTLS) e negocia apenas o que o perfil TLS ativo permite.
+
+pub mod urna_link;
+
+use anyhow::Result;
+use futures::StreamExt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+
+use crate::tls::TransportPolicy;
+pub use urna_link::UrnaLinkService;
+
+/// Tipos gerados a partir de `proto/fortis/urna/v1/urna.proto`
+pub mod pb {
+    tonic::include_proto!("fortis.urna.v1");
+}
+
+/// Tempo máximo do handshake TLS de uma urna
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sobe o servidor gRPC; retorna quando o servidor para
+pub async fn serve(address: SocketAddr, service: UrnaLinkService, transport: &TransportPolicy) -> Result<()> {
+    let router = Server::builder()
+        .add_service(pb::urna_link_server::UrnaLinkServer::new(service));
+
+    if !transport.internal_tls_enabled() {
+        log::warn!("gRPC server on {} without TLS", address);
+        router.serve(address).await?;
+        return Ok(());
+    }
+
+    let acceptor = TlsAcceptor::from(Arc::new(transport.mtls_server_config()?));
+    let listener = TcpListener::bind(address).await?;
+    log::info!("gRPC server on {} (mTLS, profile {})", address, transport.profile());
+
+    // Handshakes em paralelo, para que uma urna lenta não bloqueie as demais
+    let incoming = TcpListenerStream::new(listener)
+        .filter_map(|conn| async move { conn.ok() })
+        .map(move |tcp| {
+            let acceptor = acceptor.clone();
+            async move { tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(tcp)).await }
+        })
+        .buffer_unordered(64)
+        .filter_map(|handshake| async move {
+            match handshake {
+                Ok(Ok(stream)) => Some(Ok::<_, std::io::Error>(stream)),
+                Ok(Err(e)) => {
+                    log::warn!("gRPC TLS handshake failed: {}", e);
+                    None
+                }
+                Err(_) => {
+                    log::warn!("gRPC TLS handshake timed out");
+                    None
+                }
+            }
+        });
+
+    router.serve_with_incoming(incoming).await?;
+    Ok(())
+}
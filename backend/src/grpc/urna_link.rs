@@ -0,0 +1,375 @@
+//! Serviço gRPC `UrnaLink`
+//!
+//! Mesmas regras das rotas HTTP de urnas e votos: urna aposentada por
+//! contingência não registra votos, o voto precisa da assinatura da urna e
+//! o comprovante vem do `ReceiptService`.
+
+use chrono::{DateTime, Utc};
+use prost_types::Timestamp;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use super::pb;
+use crate::models::{
+    PerformanceMetrics, SyncType, UrnaHealthCheck, UrnaStatus, UrnaSyncRequest,
+};
+use crate::services::receipts::{tracking_code, ReceiptService};
+use crate::services::urna::{ContingencyService, UrnaMonitoringService, UrnaSyncService};
+use crate::services::vote_batches::{SignedVoteBatch, VoteBatch, VoteBatchService};
+use crate::services::regions::RegionService;
+use fortis_domain::{CandidatePosition, ContestChoice, EncryptedVote, EndpointMetrics, EndpointTier, RegionScope, SectionRef};
+
+pub struct UrnaLinkService {
+    receipts: Arc<ReceiptService>,
+    batches: Arc<VoteBatchService>,
+    contingency: Arc<ContingencyService>,
+    sync: Arc<UrnaSyncService>,
+    monitoring: Arc<UrnaMonitoringService>,
+    regions: Option<Arc<RegionService>>,
+}
+
+impl UrnaLinkService {
+    pub fn new(
+        receipts: Arc<ReceiptService>,
+        batches: Arc<VoteBatchService>,
+        contingency: Arc<ContingencyService>,
+        sync: Arc<UrnaSyncService>,
+        monitoring: Arc<UrnaMonitoringService>,
+    ) -> Self {
+        Self { receipts, batches, contingency, sync, monitoring, regions: None }
+    }
+
+    /// Registra os votos com a seção da urna designada
+    pub fn with_regions(mut self, regions: Arc<RegionService>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+}
+
+#[tonic::async_trait]
+impl pb::urna_link_server::UrnaLink for UrnaLinkService {
+    async fn submit_vote(
+        &self,
+        request: Request<pb::SubmitVoteRequest>,
+    ) -> Result<Response<pb::VoteReceipt>, Status> {
+        let request = request.into_inner();
+        let vote = encrypted_vote_from_pb(
+            request.vote.ok_or_else(|| Status::invalid_argument("missing vote"))?,
+        )?;
+
+        if self.contingency.is_retired(&request.urna_id).await {
+            return Err(Status::failed_precondition("urna retired by contingency transfer"));
+        }
+        self.contingency
+            .verify_urna_signature(&request.urna_id, &vote.encrypted_data, &vote.signature)
+            .await
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+        let ballot_hash = vote.ballot_hash();
+        if self.receipts.recorded_entry(&tracking_code(vote.id, &ballot_hash)).await.is_some() {
+            return Err(Status::already_exists(format!("vote {} already recorded", vote.id)));
+        }
+
+        let section = match &self.regions {
+            Some(regions) => regions
+                .vote_section(&request.urna_id, vote.section.as_ref())
+                .await
+                .map_err(|e| Status::failed_precondition(e.to_string()))?,
+            None => None,
+        };
+
+        let payload = self.receipts
+            .record_vote(vote.id, vote.election_id, &ballot_hash, section.as_ref())
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(pb::VoteReceipt {
+            vote_id: payload.vote_id.to_string(),
+            election_id: payload.election_id.to_string(),
+            tracking_code: payload.tracking_code,
+            inclusion: payload.inclusion.map(|inclusion| pb::InclusionData {
+                log_index: inclusion.log_index,
+                event_hash: inclusion.event_hash,
+                root_hash: inclusion.root_hash,
+                tree_size: inclusion.tree_size,
+            }),
+        }))
+    }
+
+    async fn verify_vote(
+        &self,
+        request: Request<pb::VerifyVoteRequest>,
+    ) -> Result<Response<pb::VoteVerification>, Status> {
+        let verification = self.receipts
+            .verify(&request.into_inner().tracking_code)
+            .await
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .ok_or_else(|| Status::not_found("tracking code not found"))?;
+
+        Ok(Response::new(pb::VoteVerification {
+            tracking_code: verification.tracking_code,
+            election_id: verification.election_id.to_string(),
+            counted: verification.counted,
+            log_index: verification.log_index,
+            event_hash: verification.event_hash,
+            leaf_index: verification.inclusion_proof.leaf_index,
+            proof_path: verification.inclusion_proof.path,
+            root_hash: verification.inclusion_proof.root_hash,
+            tree_size: verification.inclusion_proof.tree_size,
+            recorded_at: Some(timestamp(verification.recorded_at)),
+        }))
+    }
+
+    async fn submit_batch(
+        &self,
+        request: Request<pb::SignedVoteBatch>,
+    ) -> Result<Response<pb::BatchAck>, Status> {
+        let signed = signed_batch_from_pb(request.into_inner())?;
+        let stored = self.batches
+            .submit(signed)
+            .await
+            .map_err(|e| Status::failed_precondition(e.to_string()))?;
+
+        Ok(Response::new(pb::BatchAck {
+            log_index: stored.log_index,
+            missing_from_log: stored.missing_from_log,
+        }))
+    }
+
+    async fn start_sync(
+        &self,
+        request: Request<pb::SyncRequest>,
+    ) -> Result<Response<pb::SyncResponse>, Status> {
+        let request = request.into_inner();
+        let sync_type = match pb::SyncType::try_from(request.sync_type) {
+            Ok(pb::SyncType::Full) => SyncType::Full,
+            Ok(pb::SyncType::Incremental) => SyncType::Incremental,
+            Ok(pb::SyncType::Emergency) => SyncType::Emergency,
+            Ok(pb::SyncType::Offline) => SyncType::Offline,
+            _ => return Err(Status::invalid_argument("invalid sync type")),
+        };
+
+        let response = self.sync
+            .start_sync(UrnaSyncRequest {
+                urna_id: parse_uuid(&request.urna_id, "urna_id")?,
+                sync_type,
+                force_full_sync: request.force_full_sync,
+            })
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(pb::SyncResponse {
+            sync_id: response.sync_id.to_string(),
+            status: format!("{:?}", response.status),
+            votes_synced: response.votes_synced,
+            errors: response.errors,
+            estimated_completion: response.estimated_completion.map(timestamp),
+        }))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<pb::UrnaHeartbeat>,
+    ) -> Result<Response<pb::HeartbeatAck>, Status> {
+        let heartbeat = request.into_inner();
+        let urna_id = parse_uuid(&heartbeat.urna_id, "urna_id")?;
+        let retired = self.contingency.is_retired(&heartbeat.urna_id).await;
+        let metrics = heartbeat.metrics.unwrap_or_default();
+        let endpoints = heartbeat.endpoints.into_iter()
+            .map(endpoint_metrics)
+            .collect::<Result<Vec<_>, Status>>()?;
+
+        self.monitoring
+            .record_heartbeat(UrnaHealthCheck {
+                urna_id,
+                timestamp: heartbeat.timestamp.map(datetime).transpose()?.unwrap_or_else(Utc::now),
+                status: if retired {
+                    UrnaStatus::Inactive
+                } else if heartbeat.errors.is_empty() {
+                    UrnaStatus::Active
+                } else {
+                    UrnaStatus::Error
+                },
+                battery_level: heartbeat.battery_level,
+                storage_usage: heartbeat.storage_usage,
+                network_connectivity: heartbeat.network_connectivity,
+                last_sync: None,
+                errors: heartbeat.errors,
+                performance_metrics: PerformanceMetrics {
+                    cpu_usage: metrics.cpu_usage,
+                    memory_usage: metrics.memory_usage,
+                    disk_usage: metrics.disk_usage,
+                    network_latency: metrics.network_latency,
+                    response_time: metrics.response_time,
+                },
+            })
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        self.monitoring.record_endpoints(urna_id, endpoints, heartbeat.failovers).await;
+
+        Ok(Response::new(pb::HeartbeatAck {
+            server_time: Some(timestamp(Utc::now())),
+            retired,
+        }))
+    }
+}
+
+fn endpoint_metrics(endpoint: pb::EndpointMetrics) -> Result<EndpointMetrics, Status> {
+    let tier = match pb::EndpointTier::try_from(endpoint.tier) {
+        Ok(pb::EndpointTier::Regional) => EndpointTier::Regional,
+        Ok(pb::EndpointTier::National) => EndpointTier::National,
+        _ => return Err(Status::invalid_argument("invalid endpoint tier")),
+    };
+    Ok(EndpointMetrics {
+        url: endpoint.url,
+        tier,
+        active: endpoint.active,
+        available: endpoint.available,
+        latency_ms: endpoint.latency_ms,
+        successes: endpoint.successes,
+        failures: endpoint.failures,
+        consecutive_failures: endpoint.consecutive_failures,
+        last_error: endpoint.last_error,
+        last_success: endpoint.last_success.map(datetime).transpose()?,
+    })
+}
+
+fn contest_choice(choice: pb::ContestChoice) -> Result<ContestChoice, Status> {
+    let position = match pb::CandidatePosition::try_from(choice.position) {
+        Ok(pb::CandidatePosition::President) => CandidatePosition::President,
+        Ok(pb::CandidatePosition::Governor) => CandidatePosition::Governor,
+        Ok(pb::CandidatePosition::Senator) => CandidatePosition::Senator,
+        Ok(pb::CandidatePosition::FederalDeputy) => CandidatePosition::FederalDeputy,
+        Ok(pb::CandidatePosition::StateDeputy) => CandidatePosition::StateDeputy,
+        Ok(pb::CandidatePosition::Mayor) => CandidatePosition::Mayor,
+        Ok(pb::CandidatePosition::Councilor) => CandidatePosition::Councilor,
+        _ => return Err(Status::invalid_argument("invalid candidate position")),
+    };
+    match pb::ChoiceKind::try_from(choice.kind) {
+        Ok(pb::ChoiceKind::Unspecified | pb::ChoiceKind::Candidate) => Ok(ContestChoice::candidate(
+            position,
+            parse_uuid(&choice.candidate_id, "choice candidate_id")?,
+        )),
+        Ok(pb::ChoiceKind::Blank) => Ok(ContestChoice::blank(position)),
+        Ok(pb::ChoiceKind::Null) => Ok(ContestChoice::null(position)),
+        Err(_) => Err(Status::invalid_argument("invalid choice kind")),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(value).map_err(|_| Status::invalid_argument(format!("invalid {}", field)))
+}
+
+fn timestamp(value: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: value.timestamp(),
+        nanos: value.timestamp_subsec_nanos() as i32,
+    }
+}
+
+fn datetime(value: Timestamp) -> Result<DateTime<Utc>, Status> {
+    DateTime::from_timestamp(value.seconds, value.nanos.max(0) as u32)
+        .ok_or_else(|| Status::invalid_argument("invalid timestamp"))
+}
+
+fn section_from_key(key: &str) -> Result<SectionRef, Status> {
+    RegionScope::parse(key)
+        .ok()
+        .and_then(|scope| scope.section_ref())
+        .ok_or_else(|| Status::invalid_argument(format!("invalid section {}", key)))
+}
+
+fn required_datetime(value: Option<Timestamp>, field: &str) -> Result<DateTime<Utc>, Status> {
+    datetime(value.ok_or_else(|| Status::invalid_argument(format!("missing {}", field)))?)
+}
+
+pub(crate) fn encrypted_vote_from_pb(vote: pb::EncryptedVote) -> Result<EncryptedVote, Status> {
+    Ok(EncryptedVote {
+        id: parse_uuid(&vote.id, "vote id")?,
+        election_id: parse_uuid(&vote.election_id, "election_id")?,
+        voter_id: parse_uuid(&vote.voter_id, "voter_id")?,
+        candidate_id: parse_uuid(&vote.candidate_id, "candidate_id")?,
+        choices: vote.choices.into_iter().map(contest_choice).collect::<Result<_, _>>()?,
+        encrypted_data: vote.encrypted_data,
+        zk_proof: vote.zk_proof,
+        signature: vote.signature,
+        timestamp: required_datetime(vote.timestamp, "timestamp")?,
+        section: match vote.section.as_str() {
+            "" => None,
+            key => Some(section_from_key(key)?),
+        },
+    })
+}
+
+pub(crate) fn signed_batch_from_pb(signed: pb::SignedVoteBatch) -> Result<SignedVoteBatch, Status> {
+    let batch = signed.batch.ok_or_else(|| Status::invalid_argument("missing batch"))?;
+    Ok(SignedVoteBatch {
+        batch: VoteBatch {
+            urna_id: batch.urna_id,
+            election_id: parse_uuid(&batch.election_id, "election_id")?,
+            sequence: batch.sequence,
+            tracking_codes: batch.tracking_codes,
+            merkle_root: batch.merkle_root,
+            closed_at: required_datetime(batch.closed_at, "closed_at")?,
+        },
+        signature: signed.signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_vote_keeps_ballot_hash() {
+        let now = Utc::now();
+        let pb_vote = pb::EncryptedVote {
+            id: Uuid::new_v4().to_string(),
+            election_id: Uuid::new_v4().to_string(),
+            voter_id: Uuid::new_v4().to_string(),
+            candidate_id: Uuid::new_v4().to_string(),
+            encrypted_data: vec![1, 2, 3, 4],
+            zk_proof: "proof".to_string(),
+            signature: "c2ln".to_string(),
+            timestamp: Some(timestamp(now)),
+            section: "SP/71072/0001/0123".to_string(),
+            choices: vec![
+                pb::ContestChoice {
+                    position: pb::CandidatePosition::Governor as i32,
+                    candidate_id: Uuid::new_v4().to_string(),
+                    kind: pb::ChoiceKind::Unspecified as i32,
+                },
+                pb::ContestChoice {
+                    position: pb::CandidatePosition::President as i32,
+                    candidate_id: String::new(),
+                    kind: pb::ChoiceKind::Blank as i32,
+                },
+            ],
+        };
+
+        let vote = encrypted_vote_from_pb(pb_vote).unwrap();
+        assert_eq!(vote.timestamp, now);
+        assert_eq!(vote.ballot_hash(), fortis_domain::receipt::ballot_hash(&[1, 2, 3, 4]));
+        assert_eq!(vote.section.unwrap().zone, "0001");
+        assert_eq!(vote.choices[0].position, CandidatePosition::Governor);
+        assert!(vote.choices[0].candidate_id.is_some());
+        assert_eq!(vote.choices[1], ContestChoice::blank(CandidatePosition::President));
+    }
+
+    #[test]
+    fn test_batch_without_close_time_is_rejected() {
+        let signed = pb::SignedVoteBatch {
+            batch: Some(pb::VoteBatch {
+                urna_id: "URNA-1".to_string(),
+                election_id: Uuid::new_v4().to_string(),
+                sequence: 1,
+                tracking_codes: vec!["ABC".to_string()],
+                merkle_root: String::new(),
+                closed_at: None,
+            }),
+            signature: String::new(),
+        };
+        assert_eq!(signed_batch_from_pb(signed).unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+}
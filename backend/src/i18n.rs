@@ -0,0 +1,235 @@
+//! Pacotes de idioma da urna e das mensagens de erro da API
+//!
+//! Cada pacote traz os textos das telas e do comprovante da urna (chaves de
+//! `fortis_domain::i18n::keys`), o catálogo de mensagens ao eleitor e as
+//! mensagens das respostas de erro da API. Português e espanhol acompanham
+//! o backend; outros pacotes, como os das línguas indígenas, são arquivos
+//! JSON no diretório configurado, preparados pelos tradutores do TSE. Todos
+//! vão no pacote de eleição, e a API escolhe o idioma do erro pelo
+//! `Accept-Language`.
+
+use anyhow::{Result, anyhow};
+use fortis_domain::i18n::{self as texts, DEFAULT_LOCALE};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::errors::ErrorCatalog;
+
+const PT_BR_API_ERRORS: &[(&str, &str)] = &[
+    ("BIOMETRIC_NOT_RECOGNIZED", "Biometria não reconhecida"),
+    ("CERTIFICATE_INVALID", "Certificado digital inválido ou revogado"),
+    ("VOTER_NOT_FOUND", "Eleitor não encontrado"),
+    ("VOTER_NOT_ELIGIBLE", "Eleitor não habilitado para esta eleição"),
+    ("VOTER_ALREADY_VOTED", "Consta que o eleitor já votou"),
+    ("ELECTION_NOT_FOUND", "Eleição não encontrada"),
+    ("ELECTION_NOT_ACTIVE", "A eleição não está ativa"),
+    ("CANDIDATE_NOT_FOUND", "Candidato não encontrado"),
+    ("URNA_RETIRED", "Urna substituída por urna de contingência"),
+    ("URNA_NOT_AUTHORIZED", "Urna não autorizada"),
+    ("AUTHENTICATION_REQUIRED", "Autenticação necessária"),
+    ("PERMISSION_DENIED", "Permissão negada"),
+    ("INVALID_REQUEST", "Requisição inválida"),
+    ("RATE_LIMIT_EXCEEDED", "Muitas requisições. Tente novamente mais tarde."),
+    ("SERVICE_UNAVAILABLE", "Serviço temporariamente indisponível"),
+    ("INTERNAL_ERROR", "Erro interno"),
+];
+
+const ES_API_ERRORS: &[(&str, &str)] = &[
+    ("BIOMETRIC_NOT_RECOGNIZED", "Biometría no reconocida"),
+    ("CERTIFICATE_INVALID", "Certificado digital inválido o revocado"),
+    ("VOTER_NOT_FOUND", "Elector no encontrado"),
+    ("VOTER_NOT_ELIGIBLE", "Elector no habilitado para esta elección"),
+    ("VOTER_ALREADY_VOTED", "Consta que el elector ya votó"),
+    ("ELECTION_NOT_FOUND", "Elección no encontrada"),
+    ("ELECTION_NOT_ACTIVE", "La elección no está activa"),
+    ("CANDIDATE_NOT_FOUND", "Candidato no encontrado"),
+    ("URNA_RETIRED", "Urna sustituida por una urna de contingencia"),
+    ("URNA_NOT_AUTHORIZED", "Urna no autorizada"),
+    ("AUTHENTICATION_REQUIRED", "Autenticación requerida"),
+    ("PERMISSION_DENIED", "Permiso denegado"),
+    ("INVALID_REQUEST", "Solicitud inválida"),
+    ("RATE_LIMIT_EXCEEDED", "Demasiadas solicitudes. Inténtelo de nuevo más tarde."),
+    ("SERVICE_UNAVAILABLE", "Servicio temporalmente no disponible"),
+    ("INTERNAL_ERROR", "Error interno"),
+];
+
+/// Pacote de um idioma
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguagePack {
+    /// Etiqueta BCP 47 (`pt-BR`, `es`, `gn`)
+    pub locale: String,
+    /// Nome do idioma no próprio idioma
+    pub name: String,
+    /// Voz do sintetizador de fala da urna, quando houver
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tts_voice: Option<String>,
+    /// Textos das telas, do guia por voz e do comprovante
+    pub texts: BTreeMap<String, String>,
+    /// Mensagens ao eleitor por código de erro
+    pub errors: ErrorCatalog,
+    /// Mensagens das respostas de erro da API por código
+    #[serde(default)]
+    pub api_errors: BTreeMap<String, String>,
+}
+
+impl LanguagePack {
+    fn builtin(locale: &str, name: &str, tts_voice: &str, errors: ErrorCatalog, api_errors: &[(&str, &str)]) -> Self {
+        Self {
+            locale: locale.to_string(),
+            name: name.to_string(),
+            tts_voice: Some(tts_voice.to_string()),
+            texts: texts::builtin_texts(locale).unwrap_or_default(),
+            errors,
+            api_errors: api_errors.iter().map(|(code, message)| (code.to_string(), message.to_string())).collect(),
+        }
+    }
+
+    pub fn api_message(&self, code: &str) -> Option<&str> {
+        self.api_errors.get(code).map(String::as_str)
+    }
+}
+
+/// Resumo do pacote para a listagem de idiomas
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageSummary {
+    pub locale: String,
+    pub name: String,
+    pub error_catalog_version: String,
+    /// Textos da urna que cairão no português
+    pub missing_texts: Vec<&'static str>,
+}
+
+/// Pacotes de idioma disponíveis
+pub struct LanguagePacks {
+    packs: BTreeMap<String, LanguagePack>,
+}
+
+impl LanguagePacks {
+    /// Português e espanhol
+    pub fn builtin() -> Self {
+        let mut packs = BTreeMap::new();
+        for pack in [
+            LanguagePack::builtin(DEFAULT_LOCALE, "Português (Brasil)", "pt-br", ErrorCatalog::pt_br(), PT_BR_API_ERRORS),
+            LanguagePack::builtin("es", "Español", "es", ErrorCatalog::es(), ES_API_ERRORS),
+        ] {
+            packs.insert(pack.locale.clone(), pack);
+        }
+        Self { packs }
+    }
+
+    /// Acrescenta os pacotes `*.json` do diretório; sem diretório, só os embutidos
+    pub fn load_dir(mut self, dir: &Path) -> Result<Self> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::info!("No language packs in {}: {}", dir.display(), e);
+                return Ok(self);
+            }
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let pack: LanguagePack = serde_json::from_slice(&std::fs::read(&path)?)
+                .map_err(|e| anyhow!("Invalid language pack {}: {}", path.display(), e))?;
+            self.add(pack)?;
+        }
+        Ok(self)
+    }
+
+    /// Registra o pacote, substituindo o do mesmo idioma
+    pub fn add(&mut self, pack: LanguagePack) -> Result<()> {
+        if pack.locale.trim().is_empty() || pack.locale.contains(char::is_whitespace) {
+            return Err(anyhow!("Invalid language pack locale {:?}", pack.locale));
+        }
+        if !pack.errors.locale.eq_ignore_ascii_case(&pack.locale) {
+            return Err(anyhow!("Error catalog locale {} differs from pack {}", pack.errors.locale, pack.locale));
+        }
+        let missing = texts::missing_keys(&pack.texts);
+        if !missing.is_empty() {
+            log::warn!("Language pack {} lacks {} texts, falling back to {}", pack.locale, missing.len(), DEFAULT_LOCALE);
+        }
+        log::info!("Language pack {} ({}) loaded", pack.locale, pack.name);
+        self.packs.insert(pack.locale.clone(), pack);
+        Ok(())
+    }
+
+    pub fn get(&self, locale: &str) -> Option<&LanguagePack> {
+        self.packs.values().find(|pack| pack.locale.eq_ignore_ascii_case(locale))
+    }
+
+    /// Pacote em português, sempre presente
+    pub fn default_pack(&self) -> &LanguagePack {
+        &self.packs[DEFAULT_LOCALE]
+    }
+
+    /// Pacote que melhor atende o `Accept-Language`, ou o padrão
+    pub fn negotiate(&self, accept_language: Option<&str>) -> &LanguagePack {
+        let locales: Vec<&str> = self.packs.keys().map(String::as_str).collect();
+        accept_language
+            .and_then(|header| texts::negotiate(header, &locales))
+            .and_then(|locale| self.packs.get(locale))
+            .unwrap_or_else(|| self.default_pack())
+    }
+
+    /// Mensagem da API no idioma pedido, com o português para códigos sem tradução
+    pub fn api_message(&self, accept_language: Option<&str>, code: &str) -> (&str, Option<&str>) {
+        let pack = self.negotiate(accept_language);
+        match pack.api_message(code) {
+            Some(message) => (pack.locale.as_str(), Some(message)),
+            None => (DEFAULT_LOCALE, self.default_pack().api_message(code)),
+        }
+    }
+
+    /// Todos os pacotes, para o pacote de eleição
+    pub fn all(&self) -> Vec<LanguagePack> {
+        self.packs.values().cloned().collect()
+    }
+
+    pub fn summaries(&self) -> Vec<LanguageSummary> {
+        self.packs.values()
+            .map(|pack| LanguageSummary {
+                locale: pack.locale.clone(),
+                name: pack.name.clone(),
+                error_catalog_version: pack.errors.version.clone(),
+                missing_texts: texts::missing_keys(&pack.texts),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{VoterAction, VoterMessage};
+
+    #[test]
+    fn test_negotiation_and_fallbacks() {
+        let mut packs = LanguagePacks::builtin();
+        assert_eq!(packs.negotiate(None).locale, "pt-BR");
+        assert_eq!(packs.negotiate(Some("es-PY, gn;q=0.9")).locale, "es");
+
+        let guarani = LanguagePack {
+            locale: "gn".to_string(),
+            name: "Avañe'ẽ".to_string(),
+            tts_voice: None,
+            texts: BTreeMap::new(),
+            errors: ErrorCatalog::new("gn", VoterMessage {
+                message: "Ojejavy".to_string(),
+                action: VoterAction::CallMesario,
+            }, BTreeMap::new()),
+            api_errors: BTreeMap::new(),
+        };
+        let mut mislabeled = guarani.clone();
+        mislabeled.errors.locale = "es".to_string();
+        assert!(packs.add(mislabeled).is_err());
+        packs.add(guarani).unwrap();
+
+        assert_eq!(packs.negotiate(Some("gn")).locale, "gn");
+        assert_eq!(packs.api_message(Some("gn"), "VOTER_NOT_FOUND"), ("pt-BR", Some("Eleitor não encontrado")));
+        assert_eq!(packs.api_message(Some("es"), "VOTER_NOT_FOUND"), ("es", Some("Elector no encontrado")));
+        assert!(!packs.summaries().iter().find(|s| s.locale == "gn").unwrap().missing_texts.is_empty());
+    }
+}
@@ -3,7 +3,7 @@
 //! Este é o servidor principal do FORTIS, implementado em Rust para máxima
 //! performance e segurança.
 
-use actix_web::{web, App, HttpServer, middleware::Logger};
+use actix_web::{web, App, HttpServer};
 use actix_cors::Cors;
 use std::env;
 use std::sync::Arc;
@@ -13,6 +13,8 @@ use utoipa_swagger_ui::SwaggerUi;
 mod auth;
 mod crypto;
 mod database;
+mod errors;
+mod i18n;
 mod models;
 mod services;
 mod utils;
@@ -24,9 +26,16 @@ mod storage;
 mod monitoring;
 mod transparency;
 mod consensus;
-// mod middleware;
+mod deployment;
+mod cluster;
+mod analytics;
+mod middleware;
 mod config;
 mod api_docs;
+mod tls;
+mod grpc;
+mod stream;
+mod telemetry;
 
 use config::Config;
 use api_docs::ApiDoc;
@@ -34,14 +43,24 @@ use utoipa::OpenApi;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // Inicializar logging
-    env_logger::init();
-    
     // Carregar configurações
     let config = Config::new();
     
+    // Logs estruturados em JSON e rastros exportados por OTLP, com as macros `log::` encaminhadas ao tracing
+    telemetry::init(&config.logging).expect("Failed to initialize logging");
+    
     log::info!("🚀 Iniciando FORTIS Backend v{}", env!("CARGO_PKG_VERSION"));
     log::info!("🌐 Servidor rodando em: http://{}:{}", config.server.host, config.server.port);
+
+    // Documento OpenAPI divergente do roteador impede a inicialização
+    api_docs::drift::verify_startup(&config.api_docs).expect("OpenAPI document out of sync with router");
+    
+    // Política TLS: perfil inválido ou certificado fora da política impede a inicialização
+    let transport_policy = tls::TransportPolicy::from_config(&config.transport)
+        .expect("Invalid transport security policy");
+    log::info!("🔒 Perfil TLS ativo: {}", transport_policy.profile());
+    let server_tls = transport_policy.server_config();
+    let transport_policy = web::Data::new(transport_policy);
     
     // Inicializar banco de dados
     // database::init(&config.database).await
@@ -51,6 +70,28 @@ async fn main() -> std::io::Result<()> {
     let redis_client = redis::Client::open(config.redis.url.as_str())
         .expect("Failed to create Redis client");
     
+    // Fluxo de eventos em tempo real para os painéis de observação
+    let event_hub = stream::EventHub::new(config.stream.channel_capacity);
+    
+    // Inicializar monitoramento, canais de alerta e exportador Prometheus
+    let mut monitoring_system = monitoring::MonitoringSystem::new();
+    monitoring_system.add_notification_channel(Box::new(event_hub.clone()));
+    for channel in monitoring::build_channels(&config.notifications)
+        .expect("Failed to configure notification channels")
+    {
+        log::info!("📣 Canal de alertas habilitado: {}", channel.get_name());
+        monitoring_system.add_notification_channel(channel);
+    }
+    let monitoring_system = Arc::new(monitoring_system);
+    let prometheus_exporter = monitoring::PrometheusExporter::default();
+    
+    // Índice de saúde por eleição
+    let election_health = monitoring::ElectionHealthService::new(
+        monitoring::HealthScoreConfig::default(),
+        monitoring_system.clone(),
+    );
+    election_health.start();
+    
     // Inicializar serviços de transparência e consenso
     let transparency_config = transparency::election_logs::LogConfig {
         min_verifiers: 1,
@@ -62,12 +103,593 @@ async fn main() -> std::io::Result<()> {
         max_entries_per_batch: 100,
         verification_timeout_seconds: 30,
     };
-    let consensus_service = consensus::threshold_signatures::ThresholdSignature::new(
-        "node_1".to_string(),
-        "initial_message".to_string(),
-        2,
+    let signature_collector = transparency::signature_collector::SignatureCollector::new(
+        transparency::signature_collector::CollectorConfig {
+            timeout_seconds: transparency_config.verification_timeout_seconds,
+            ..Default::default()
+        }
+    );
+    // Chaves eleitorais ficam no HSM; só a chave pública sai do token
+    let hsm = crypto::hsm::Hsm::from_config(&config.hsm)
+        .expect("Failed to open HSM");
+    log::info!("🔐 HSM em uso: {}", hsm.backend_name());
+    let log_signing_key = hsm.key_or_generate(&config.hsm.log_key_label)
+        .expect("Failed to load transparency log signing key");
+    let transparency_log = Arc::new(RwLock::new(
+        transparency::election_logs::ElectionTransparencyLog::new(transparency_config)
+            .with_signing_key(Arc::new(log_signing_key))
+            .with_event_hub(event_hub.clone())
+    ));
+    
+    // Este nó testemunha os logs dos demais backends regionais
+    for peer in &config.transparency.witnesses {
+        if peer.kind != transparency::witness::WitnessKind::Regional {
+            continue;
+        }
+        match hex::decode(&peer.public_key) {
+            Ok(public_key) => {
+                transparency_log.write().await.trust_peer_log(public_key);
+            }
+            Err(_) => log::warn!("Chave pública inválida para a testemunha {}", peer.id),
+        }
+    }
+    
+    // Publicar Signed Tree Heads periodicamente, com cossinatura das testemunhas
+    let sth_log = transparency_log.clone();
+    let sth_interval = config.transparency.sth_interval_seconds;
+    let witness_collector = transparency::witness::WitnessCollector::new(
+        config.transparency.witnesses.clone(),
+        config.transparency.witness_threshold,
+        signature_collector.clone(),
+    );
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(sth_interval));
+        loop {
+            interval.tick().await;
+            if let Err(e) = witness_collector.publish(&sth_log).await {
+                log::error!("Falha ao publicar STH: {}", e);
+            }
+        }
+    });
+    
+    // Ganchos opcionais da câmera de auditoria
+    let audit_camera_hooks = web::Data::new(
+        transparency::audit_camera::AuditCameraHooks::new(config.transparency.audit_camera_enabled)
+    );
+    
+    // Inventário de certificados e chaves com alertas de expiração
+    let credential_watchdog = services::credentials::CredentialWatchdog::new(
+        services::credentials::ExpiryPolicy {
+            lead_times_days: config.security.credential_lead_times_days.clone(),
+            check_interval_seconds: config.security.credential_check_interval,
+        }
+    ).with_monitoring(monitoring_system.clone());
+    // A chave JWT é datada na primeira inicialização em que aparece; as
+    // seguintes reaproveitam as datas gravadas enquanto a chave for a mesma
+    let jwt_key_fingerprint = hex::encode(ring::hmac::sign(
+        &ring::hmac::Key::new(ring::hmac::HMAC_SHA256, config.security.jwt_secret.as_bytes()),
+        b"fortis-jwt-key-fingerprint-v1",
+    ).as_ref());
+    let jwt_key_created_at = chrono::Utc::now();
+    credential_watchdog.register_recorded(
+        std::path::Path::new(&config.security.jwt_key_record_path),
+        services::credentials::Credential {
+            id: "jwt-signing-key".to_string(),
+            kind: services::credentials::CredentialKind::JwtSigningKey,
+            subject: "FORTIS API JWT".to_string(),
+            fingerprint: Some(jwt_key_fingerprint),
+            not_before: jwt_key_created_at,
+            not_after: jwt_key_created_at + chrono::Duration::days(config.security.jwt_key_max_age_days),
+            critical: true,
+            source: "config".to_string(),
+        },
+    ).await.expect("Failed to register JWT signing key");
+    credential_watchdog.start();
+    
+    // Ciclo de vida das eleições
+    // Em implementação real, usaria with_database após inicializar o banco
+    let election_service = Arc::new(
+        services::election::ElectionService::new()
+            .with_transparency_log(transparency_log.clone())
+            .with_credential_watchdog(credential_watchdog.clone())
+    );
+    election_service.replay_log().await.expect("Failed to rebuild elections from the transparency log");
+    let candidate_service = Arc::new(services::candidate::CandidateService::new(election_service.clone()));
+
+    // Modelos aprovados dos tipos recorrentes de eleição
+    let election_templates = Arc::new(
+        services::election_templates::ElectionTemplateService::new(election_service.clone(), candidate_service.clone())
+            .with_templates(
+                services::election_templates::load_templates(std::path::Path::new(&config.election_templates.templates_dir))
+                    .expect("Failed to load election templates")
+            )
+            .expect("Invalid election template")
+    );
+    
+    // Pool de criptografia pesada, fora dos workers HTTP
+    let compute_pool = Arc::new(
+        services::compute::ComputePool::new(&config.compute)
+            .expect("Failed to start compute pool")
+            .with_monitoring(monitoring_system.clone())
+    );
+    
+    // Mixnet com provas de embaralhamento antes da apuração
+    let mixnet_service = web::Data::new(
+        services::mixnet::MixnetService::new(
+            services::mixnet::MixGroup::rfc3526_2048(),
+            services::mixnet::MixnetConfig::default(),
+        ).with_transparency_log(transparency_log.clone())
+        .with_compute_pool(compute_pool.clone())
+    );
+    
+    // Cerimônia de chaves: chave de decifração dividida entre custodiantes
+    let key_ceremony_service = web::Data::new(
+        services::key_ceremony::KeyCeremonyService::new(services::mixnet::MixGroup::rfc3526_2048())
+            .with_transparency_log(transparency_log.clone())
+    );
+    let ceremony_sweeper = key_ceremony_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = ceremony_sweeper.expire_distributions().await {
+                log::error!("Falha ao encerrar cerimônias de chaves vencidas: {}", e);
+            }
+        }
+    });
+    
+    // Cadastro de UFs, municípios, zonas e seções, com a urna de cada seção
+    let region_service = Arc::new(services::regions::RegionService::new());
+    
+    // Comprovantes de votação verificáveis por código de rastreamento
+    let receipt_service = web::Data::new(services::receipts::ReceiptService::new(transparency_log.clone()));
+
+    // Baldes de fichas e fichas anônimas do portal de verificação
+    let verification_limiter = web::Data::new(
+        services::verification_limits::VerificationLimiter::new(
+            config.security.verification_limits.clone(),
+            hsm.clone(),
+            &config.hsm.verification_token_key_prefix,
+        ).expect("Failed to start verification rate limiting")
+    );
+    
+    // Nullifiers com filtro de Bloom e tabela autoritativa
+    // Em implementação real, usaria with_database após inicializar o banco
+    let mut nullifier_set = zkp::NullifierSet::new(zkp::NullifierSetConfig {
+        expected_items: config.nullifiers.bloom_expected_items,
+        false_positive_rate: config.nullifiers.bloom_false_positive_rate,
+    })
+    .expect("Invalid nullifier Bloom filter configuration")
+    .with_monitoring(monitoring_system.clone());
+    if config.nullifiers.bloom_in_redis {
+        nullifier_set = nullifier_set.with_redis(redis_client.clone());
+    }
+    let nullifier_set = web::Data::new(nullifier_set);
+    
+    // Pacotes de idioma da urna e das mensagens de erro da API
+    let language_packs = i18n::LanguagePacks::builtin()
+        .load_dir(std::path::Path::new(&config.i18n.packs_dir))
+        .expect("Failed to load language packs");
+    let error_catalog = language_packs.default_pack().errors.clone();
+    
+    // Beacon público de aleatoriedade para sorteios auditáveis
+    let randomness_beacon = Arc::new(
+        services::beacon::RandomnessBeacon::new(config.beacon.clone())
+            .expect("Failed to configure randomness beacon")
+            .with_transparency_log(transparency_log.clone())
+    );
+    
+    // IPFS para pacotes de eleição e de dados abertos, quando há nó configurado,
+    // com a DHT anunciando onde cada artefato está
+    let distributed_storage = match &config.storage.ipfs_api_url {
+        Some(endpoint) => {
+            let ipfs = storage::IpfsClient::from_config(&config.storage, endpoint)
+                .expect("Failed to configure IPFS client");
+            let cache = storage::LocalCache::from_config(&config.storage)
+                .expect("Failed to configure storage cache")
+                .with_monitoring(monitoring_system.clone());
+            let mut storage = storage::DistributedStorage::with_ipfs_client(
+                ipfs,
+                config.storage.node_id.clone(),
+                config.storage.cache_size,
+            ).with_cache(cache, chrono::Duration::seconds(config.storage.cache_ttl_seconds as i64));
+            // DHT entre os nós regionais para localizar os artefatos publicados
+            if config.storage.dht_listen_address.is_some() {
+                let dht = storage::DhtClient::start(&config.storage, Some(monitoring_system.clone()))
+                    .expect("Failed to start storage DHT");
+                storage = storage.with_dht(Arc::new(dht));
+            }
+            // Fragmentos Reed–Solomon entre os nós de armazenamento, com reparo periódico
+            if let Some(policy) = config.storage.erasure {
+                let nodes = config.storage.storage_nodes.iter()
+                    .map(|endpoint| storage::StorageNode {
+                        name: endpoint.clone(),
+                        client: storage::IpfsClient::from_config(&config.storage, endpoint)
+                            .expect("Failed to configure storage node"),
+                    })
+                    .collect();
+                storage = storage.with_erasure(policy, nodes)
+                    .with_shard_index(&config.storage.shard_index_path)
+                    .expect("Failed to load shard index")
+                    .with_monitoring(monitoring_system.clone());
+            }
+            let storage = Arc::new(storage);
+            if config.storage.erasure.is_some() {
+                let repair = storage.clone();
+                let repair_interval = config.storage.shard_repair_interval_seconds;
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(std::time::Duration::from_secs(repair_interval));
+                    loop {
+                        interval.tick().await;
+                        repair.repair_shards().await;
+                    }
+                });
+            }
+            Some(storage)
+        }
+        None => None,
+    };
+
+    // Pacote de eleição assinado para preparação offline das urnas; leva a
+    // chave do log para a urna conferir as provas de inclusão na sincronização
+    let log_public_key = transparency_log.read().await.log_public_key();
+    let package_signing_key = hsm.key_or_generate(&config.hsm.package_key_label)
+        .expect("Failed to load election package signing key");
+    let mut election_package_service = services::election_package::ElectionPackageService::new(
+        election_service.clone(),
+        candidate_service.clone(),
+        package_signing_key,
+        error_catalog,
+    ).with_beacon(randomness_beacon.clone())
+        .with_language_packs(language_packs.all())
+        .with_templates(election_templates.clone())
+        .with_public_key(fortis_domain::TRANSPARENCY_LOG_KEY_PURPOSE, &log_public_key);
+    if let Some(storage) = &distributed_storage {
+        election_package_service = election_package_service.with_storage(storage.clone());
+    }
+    let election_package_service = web::Data::new(election_package_service);
+    
+    // Apuração dos boletins de urna e manifesto assinado do resultado
+    let results_signing_key = hsm.key_or_generate(&config.hsm.results_key_label)
+        .expect("Failed to load results signing key");
+    let results_service = web::Data::new(services::results::ResultsService::new(
+        election_service.clone(),
+        candidate_service.clone(),
+        results_signing_key,
+        services::results::ResultsConfig {
+            cache_ttl_seconds: config.analytics.results_cache_seconds,
+        },
+    ).with_transparency_log(transparency_log.clone())
+        .with_regions(region_service.clone()));
+    
+    // Apuração-sombra pela mixnet, comparada com a oficial antes da certificação
+    let shadow_tally = web::Data::new(services::tally::ShadowTallyService::new(
+        results_service.clone().into_inner(),
+        mixnet_service.clone().into_inner(),
+        candidate_service.clone(),
+    ).with_transparency_log(transparency_log.clone()));
+    
+    // Auditorias de limitação de risco com amostra sorteada pelo beacon
+    let rla_service = web::Data::new(audit::rla::RlaService::new(
+        transparency_log.clone(),
+        randomness_beacon.clone(),
+    ));
+    let randomness_beacon = web::Data::from(randomness_beacon);
+    let election_service = web::Data::from(election_service);
+    let candidate_service = web::Data::from(candidate_service);
+    let election_templates = web::Data::from(election_templates);
+    let language_packs = web::Data::new(language_packs);
+    
+    // Transferência de estado para urnas de contingência
+    let contingency_service = web::Data::new(
+        services::urna::ContingencyService::new()
+            .with_transparency_log(transparency_log.clone())
+            .with_health_score(election_health.clone())
     );
     
+    // Acesso de suporte somente leitura, concedido pelo administrador
+    let support_access = web::Data::new(services::support_access::SupportAccessService::new());
+    let dual_control = web::Data::new(services::dual_control::DualControlService::new(&config.security.dual_control));
+    
+    // Papéis e permissões; os administradores iniciais vêm da configuração
+    let rbac_service = web::Data::new(
+        auth::rbac::RbacService::new().with_bootstrap_admins(&config.security.rbac_bootstrap_admins)
+    );
+    
+    // Login Gov.br: autorizações PKCE pendentes ficam no serviço
+    let gov_br_service = web::Data::new(services::tse::GovBrService::new(&config));
+    
+    // Livro de consentimento biométrico (LGPD)
+    let consent_ledger = web::Data::new(services::consent::ConsentLedger::new(
+        config.security.encryption_key.as_bytes(),
+    ));
+    // Deduplicação biométrica 1:N dos eleitores recebidos na sincronização,
+    // condicionada ao livro de consentimento
+    let biometric_dedup = Arc::new(
+        services::tse::BiometricDedupService::from_config(&config)
+            .with_consent_ledger(consent_ledger.clone().into_inner())
+    );
+    log::info!("Biometric deduplication engine: {}", biometric_dedup.engine_name());
+    // Sincronização incremental do cadastro eleitoral e dos locais de votação
+    let election_sync = services::tse::ElectionSyncService::new(&config)
+        .with_deduplication(biometric_dedup.clone());
+    if let Err(e) = election_sync.start_auto_sync().await {
+        log::warn!("Voter roll sync not started: {}", e);
+    }
+    let election_sync = web::Data::new(election_sync);
+    let biometric_dedup = web::Data::from(biometric_dedup);
+    
+    // Pacotes de provisionamento cifrados para a chave de selagem de cada urna
+    let provisioning_signing_key = hsm.key_or_generate(&config.hsm.provisioning_key_label)
+        .expect("Failed to load provisioning signing key");
+    // Conferência de elegibilidade das zonas, exigida antes de provisionar as urnas
+    let eligibility_service = Arc::new(services::eligibility::EligibilityService::new(election_sync.get_ref().clone()));
+    let provisioning_service = web::Data::new(services::provisioning::ProvisioningService::new(
+        election_package_service.clone().into_inner(),
+        election_sync.get_ref().clone(),
+        provisioning_signing_key,
+    ).with_eligibility(eligibility_service.clone())
+        .with_regions(region_service.clone()));
+    let eligibility_service = web::Data::from(eligibility_service);
+    
+    // Manifestos assinados de atualização de firmware e aplicação das urnas
+    // A chave fica no HSM: as urnas a fixam na imagem e ela sobrevive à reinicialização
+    let update_signing_key = hsm.key_or_generate(&config.hsm.update_key_label)
+        .expect("Failed to load update signing key");
+    let update_service = web::Data::new(services::updates::UpdateService::new(update_signing_key)
+        .with_transparency_log(transparency_log.clone()));
+    
+    // Dashboards de auditoria sobre a trilha e o log transparente
+    let dashboard_service = web::Data::new(services::audit::DashboardService::new(transparency_log.clone()));
+    
+    // Sub-raízes Merkle dos lotes de votos assinadas pelas urnas
+    let vote_batch_service = web::Data::new(services::vote_batches::VoteBatchService::new(
+        transparency_log.clone(),
+        contingency_service.clone().into_inner(),
+        receipt_service.clone().into_inner(),
+    ));
+    
+    // Votos pendentes enviados em partes comprimidas, com idempotência por parte
+    let vote_sync_service = web::Data::new(services::vote_sync::VoteSyncService::new(
+        receipt_service.clone().into_inner(),
+        contingency_service.clone().into_inner(),
+    ).with_regions(region_service.clone()));
+    
+    // Sincronização e saúde das urnas, compartilhadas entre HTTP e gRPC
+    let urna_sync_service = web::Data::new(services::urna::UrnaSyncService::new());
+    let urna_monitoring = web::Data::new(services::urna::UrnaMonitoringService::new());
+    
+    // Série temporal dos heartbeats e painel da frota por zona eleitoral
+    let fleet_service = web::Data::new(services::urna::FleetService::new(Default::default())
+        .with_regions(region_service.clone()));
+    
+    // API gRPC das urnas, em porta própria ao lado do servidor HTTP
+    if config.transport.grpc_enabled {
+        let grpc_address = format!("{}:{}", config.server.host, config.transport.grpc_port)
+            .parse()
+            .expect("Invalid gRPC address");
+        let urna_link = grpc::UrnaLinkService::new(
+            receipt_service.clone().into_inner(),
+            vote_batch_service.clone().into_inner(),
+            contingency_service.clone().into_inner(),
+            urna_sync_service.clone().into_inner(),
+            urna_monitoring.clone().into_inner(),
+        ).with_regions(region_service.clone());
+        let transport = transport_policy.clone();
+        tokio::spawn(async move {
+            if let Err(e) = grpc::serve(grpc_address, urna_link, &transport).await {
+                log::error!("gRPC server stopped: {}", e);
+            }
+        });
+    }
+    
+    // Relatórios de descomissionamento das urnas ao fim do ciclo
+    let decommission_service = web::Data::new(services::urna::DecommissionService::new(
+        contingency_service.clone().into_inner(),
+        vote_batch_service.clone().into_inner(),
+        transparency_log.clone(),
+    ).with_regions(region_service.clone()));
+    
+    let region_service = web::Data::from(region_service);
+    
+    // Consenso dos nós, com as chaves dos nós no HSM
+    let consensus_service = Arc::new(consensus::consensus_service::ConsensusService::new(
+        Default::default(),
+        transparency_log.clone(),
+    ).with_hsm(hsm.clone(), &config.hsm.node_key_prefix));
+    if let Err(e) = consensus_service.initialize().await {
+        log::error!("Consensus service not initialized: {}", e);
+    }
+    
+    // Fim de transmissão por zona, declarado pela autoridade zonal e endossado pelo consenso
+    let transmission_service = Arc::new(services::transmission::TransmissionService::new(
+        results_service.clone().into_inner(),
+        provisioning_service.clone().into_inner(),
+    ).with_consensus(consensus_service.clone())
+        .with_transparency_log(transparency_log.clone()));
+    
+    // Inicializar membros do cluster
+    let membership_service = cluster::MembershipService::new(
+        cluster::MembershipConfig {
+            node_id: config.cluster.node_id.clone(),
+            advertise_address: config.cluster.advertise_address.clone(),
+            seeds: config.cluster.seeds.clone(),
+            gossip_interval_ms: config.cluster.gossip_interval_ms,
+            suspect_timeout_seconds: config.cluster.suspect_timeout_seconds,
+            dead_timeout_seconds: config.cluster.dead_timeout_seconds,
+            secret: config.cluster.secret.clone(),
+            ..cluster::MembershipConfig::default()
+        },
+        cluster::MemberMetadata {
+            shard: config.cluster.shard.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            region: config.cluster.region.clone(),
+            tags: std::collections::HashMap::new(),
+        },
+    );
+    if config.cluster.secret.is_empty() {
+        log::error!("FORTIS_CLUSTER_SECRET não configurado: mensagens entre réplicas serão recusadas");
+    }
+    membership_service.start();
+
+    // Listener interno do cluster: gossip e consulta de membros ficam fora do público
+    let cluster_membership = web::Data::new(membership_service.clone());
+    let cluster_server = HttpServer::new(move || {
+        App::new()
+            .app_data(cluster_membership.clone())
+            .configure(cluster::api::configure_routes)
+    })
+    .workers(1)
+    .bind(&config.cluster.listen_address)?
+    .run();
+    tokio::spawn(async move {
+        if let Err(e) = cluster_server.await {
+            log::error!("Listener interno do cluster encerrado: {}", e);
+        }
+    });
+    
+    // Inicializar coordenador de upgrades graduais; sem um orquestrador
+    // integrado, o controlador local só simula os shards e a API de
+    // rollout fica desabilitada
+    let shard_controller = deployment::LocalShardController::new(
+        config.deployment.shards
+            .iter()
+            .enumerate()
+            .map(|(i, address)| (format!("shard-{}", i + 1), address.clone()))
+            .collect(),
+        env!("CARGO_PKG_VERSION"),
+    );
+    let upgrade_coordinator = deployment::UpgradeCoordinator::new(
+        deployment::RolloutConfig {
+            drain_timeout_seconds: config.deployment.drain_timeout_seconds,
+            canary_window_seconds: config.deployment.canary_window_seconds,
+            max_error_rate_increase: config.deployment.max_error_rate_increase,
+            max_latency_increase_percent: config.deployment.max_latency_increase_percent,
+            ..deployment::RolloutConfig::default()
+        },
+        Arc::new(shard_controller),
+    )
+    .with_monitoring(monitoring_system.clone());
+    if upgrade_coordinator.is_simulated() {
+        log::warn!("Rolling upgrade API disabled: only the simulated local shard controller is configured");
+    }
+    
+    // Inicializar executor de exercícios de DR
+    // Em implementação real, o alvo apontaria para o ambiente de homologação
+    let drill_signing_key = hsm.key_or_generate(&config.hsm.dr_drill_key_label)
+        .expect("Failed to load DR drill signing key");
+    let drill_runner = web::Data::new(deployment::DrillRunner::new(
+        Arc::new(deployment::SimulatedDrillTarget::new(
+            vec![],
+            deployment::BackupInfo {
+                backup_id: "staging-latest".to_string(),
+                taken_at: chrono::Utc::now(),
+            },
+        )),
+        drill_signing_key,
+        std::path::PathBuf::from(&config.deployment.dr_evidence_path),
+    ));
+    
+    // Identidade das urnas: certificados de dispositivo emitidos pela CA de urnas
+    let mut urna_auth = services::urna::UrnaAuthService::new()
+        .with_consent_ledger(consent_ledger.clone().into_inner());
+    match std::fs::read_to_string(&config.transport.device_ca_path) {
+        Ok(ca_pem) => {
+            urna_auth = urna_auth.with_device_ca(&ca_pem)
+                .expect("Invalid device CA");
+        }
+        Err(e) => log::warn!("⚠️ CA de dispositivos indisponível ({}): credenciamento de urnas desabilitado", e),
+    }
+    let urna_auth = web::Data::new(urna_auth);
+    
+    // Provisionamento remoto: credenciais, pacotes de configuração e etapa de cada urna
+    let urna_provisioning = web::Data::new(services::urna::UrnaProvisioningService::new(
+        provisioning_service.clone().into_inner(),
+        urna_auth.clone().into_inner(),
+    ));
+    
+    // Frequência dos códigos de falha das urnas por modelo e runbook de cada código
+    let urna_diagnostics = web::Data::new(services::urna::DiagnosticsService::new());
+    
+    // Atestação remota do boot seguro na abertura da sessão de votação
+    let urna_security = web::Data::new(
+        services::urna::UrnaSecurityService::new().with_monitoring(monitoring_system.clone())
+    );
+    
+    // Recebimento de relatórios de vulnerabilidade e incidentes
+    let incident_service = Arc::new(services::incident::IncidentService::new());
+    let mut security_reports = services::security_reports::SecurityReportService::new(
+        incident_service.clone(),
+        config.security.disclosure_triage_sla_hours,
+    );
+    match std::fs::read_to_string(&config.security.disclosure_pgp_key_path) {
+        Ok(key) => security_reports = security_reports.with_pgp_public_key(key),
+        Err(e) => log::warn!("Chave PGP de divulgação não carregada: {}", e),
+    }
+    let security_reports = web::Data::new(security_reports);
+    
+    // Painel do centro de comando, a partir dos modelos de leitura em memória
+    let command_center = web::Data::new(monitoring::CommandCenterService::new(
+        config.command_center.clone(),
+        urna_monitoring.clone().into_inner(),
+        results_service.clone().into_inner(),
+        incident_service.clone(),
+        election_health.clone(),
+        membership_service.clone(),
+    ));
+    let incident_service = web::Data::from(incident_service);
+    
+    // Análises públicas com privacidade diferencial
+    let privacy_budget = Arc::new(analytics::PrivacyBudgetAccountant::new(config.analytics.dp_total_epsilon));
+    let turnout_heat_map = web::Data::new(analytics::TurnoutHeatMapService::new(
+        analytics::HeatMapConfig {
+            epsilon_per_release: config.analytics.dp_epsilon_per_release,
+            min_section_size: config.analytics.min_section_size,
+            min_cell_size: config.analytics.min_cell_size,
+        },
+        privacy_budget.clone(),
+    ));
+    let privacy_budget = web::Data::from(privacy_budget);
+    
+    // Pacote de dados abertos publicado após a certificação
+    let open_data_signing_key = hsm.key_or_generate(&config.hsm.open_data_key_label)
+        .expect("Failed to load open data signing key");
+    // Consulta histórica sobre o arquivo dos pacotes publicados
+    let history_signing_key = hsm.key_or_generate(&config.hsm.history_key_label)
+        .expect("Failed to load election history signing key");
+    let election_history = Arc::new(analytics::ElectionHistoryService::new(
+        std::path::PathBuf::from(&config.analytics.open_data_archive_path),
+        history_signing_key,
+    ));
+    if let Err(e) = election_history.rebuild().await {
+        log::error!("Failed to build election history index: {}", e);
+    }
+    let mut open_data_service = analytics::OpenDataService::new(
+        analytics::OpenDataConfig {
+            archive_path: std::path::PathBuf::from(&config.analytics.open_data_archive_path),
+            min_section_size: config.analytics.min_section_size,
+        },
+        open_data_signing_key,
+    ).with_transmission(transmission_service.clone())
+        .with_history(election_history.clone());
+    if let Some(storage) = &distributed_storage {
+        open_data_service = open_data_service.with_storage(storage.clone());
+    }
+    let election_history = web::Data::from(election_history);
+    let open_data_service = web::Data::new(open_data_service);
+    let transmission_service = web::Data::from(transmission_service);
+    
+    // Relatório de lições aprendidas, gerado após a certificação
+    let lessons_learned = analytics::LessonsLearnedService::new(
+        analytics::LessonsLearnedConfig {
+            output_path: std::path::PathBuf::from(&config.analytics.lessons_learned_path),
+            interval_seconds: config.analytics.lessons_learned_interval_seconds,
+        },
+        incident_service.clone().into_inner(),
+    ).with_results(results_service.clone().into_inner());
+    lessons_learned.start(open_data_service.clone().into_inner());
+    let lessons_learned = web::Data::new(lessons_learned);
+    
     // Inicializar serviços
     let crypto_service = crypto::CryptoService::new(&config.security.encryption_key)
         .expect("Failed to initialize crypto service");
@@ -76,23 +698,113 @@ async fn main() -> std::io::Result<()> {
         &config.security.jwt_secret,
         "fortis-voting-system",
         "fortis-voters",
-    );
+    )
+    .with_session_policy(auth::jwt::SessionPolicy {
+        access_ttl_minutes: config.security.access_token_ttl_minutes,
+        idle_timeout_minutes: config.security.session_idle_timeout_minutes,
+        max_session_hours: config.security.session_max_hours,
+    })
+    .with_redis(redis_client.clone());
+    
+    let monitoring_system = web::Data::from(monitoring_system);
+    let event_hub = web::Data::new(event_hub);
     
     // Salvar configurações para uso posterior
     let server_host = config.server.host.clone();
     let server_port = config.server.port;
+    let http_workers = config.compute.http_workers;
     
+    // Limite de requisições compartilhado entre as réplicas
+    let rate_limiter = web::Data::new(
+        middleware::rate_limit::RateLimiter::from_config(&config.security).with_redis(redis_client.clone())
+    );
+
+    // Desafio das rotas públicas sob carga, com a chave comum às réplicas
+    let client_puzzle = web::Data::new(middleware::client_puzzle::ClientPuzzle::new(
+        config.security.client_puzzle.clone(),
+        config.security.encryption_key.as_bytes(),
+    ));
+
     // Configurar e iniciar servidor HTTP
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         App::new()
-            .wrap(Logger::default())
+            .wrap(middleware::dual_control::DualControlGuard)
             .wrap(Cors::permissive())
+            .wrap(middleware::client_puzzle::ClientPuzzleGuard)
+            .wrap(middleware::rate_limit::RateLimitMiddleware)
+            .wrap(middleware::support_access::SupportAccessGuard)
+            .wrap(middleware::rbac::RbacAuthentication)
+            .wrap(middleware::localized_errors::LocalizedErrors)
+            .wrap(middleware::request_id::RequestIdMiddleware)
             .app_data(web::Data::new(config.clone()))
+            .app_data(gov_br_service.clone())
+            .app_data(election_sync.clone())
+            .app_data(biometric_dedup.clone())
+            .app_data(provisioning_service.clone())
+            .app_data(update_service.clone())
+            .app_data(dashboard_service.clone())
+            .app_data(eligibility_service.clone())
+            .app_data(transport_policy.clone())
+            .app_data(support_access.clone())
+            .app_data(dual_control.clone())
+            .app_data(rbac_service.clone())
             .app_data(web::Data::new(redis_client.clone()))
+            .app_data(rate_limiter.clone())
+            .app_data(client_puzzle.clone())
             .app_data(web::Data::new(crypto_service.clone()))
             .app_data(web::Data::new(jwt_service.clone()))
-            .app_data(web::Data::new(Arc::new(RwLock::new(transparency::election_logs::ElectionTransparencyLog::new(transparency_config.clone())))))
-            .app_data(web::Data::new(consensus_service.clone()))
+            .app_data(web::Data::new(transparency_log.clone()))
+            .app_data(web::Data::new(signature_collector.clone()))
+            .app_data(web::Data::from(consensus_service.clone()))
+            .app_data(web::Data::new(upgrade_coordinator.clone()))
+            .app_data(drill_runner.clone())
+            .app_data(consent_ledger.clone())
+            .app_data(urna_auth.clone())
+            .app_data(urna_provisioning.clone())
+            .app_data(urna_security.clone())
+            .app_data(turnout_heat_map.clone())
+            .app_data(security_reports.clone())
+            .app_data(incident_service.clone())
+            .app_data(privacy_budget.clone())
+            .app_data(open_data_service.clone())
+            .app_data(election_history.clone())
+            .app_data(lessons_learned.clone())
+            .configure(deployment::api::configure_routes)
+            .app_data(audit_camera_hooks.clone())
+            .app_data(contingency_service.clone())
+            .app_data(election_service.clone())
+            .app_data(results_service.clone())
+            .app_data(shadow_tally.clone())
+            .app_data(region_service.clone())
+            .app_data(transmission_service.clone())
+            .app_data(rla_service.clone())
+            .app_data(web::Data::new(credential_watchdog.clone()))
+            .app_data(mixnet_service.clone())
+            .app_data(key_ceremony_service.clone())
+            .app_data(web::Data::from(compute_pool.clone()))
+            .app_data(receipt_service.clone())
+            .app_data(verification_limiter.clone())
+            .app_data(vote_batch_service.clone())
+            .app_data(decommission_service.clone())
+            .app_data(vote_sync_service.clone())
+            .app_data(urna_sync_service.clone())
+            .app_data(urna_monitoring.clone())
+            .app_data(fleet_service.clone())
+            .app_data(urna_diagnostics.clone())
+            .app_data(nullifier_set.clone())
+            .app_data(candidate_service.clone())
+            .app_data(election_templates.clone())
+            .app_data(language_packs.clone())
+            .app_data(election_package_service.clone())
+            .app_data(randomness_beacon.clone())
+            .configure(transparency::api::configure_routes)
+            .app_data(monitoring_system.clone())
+            .app_data(web::Data::new(prometheus_exporter.clone()))
+            .app_data(web::Data::new(election_health.clone()))
+            .app_data(command_center.clone())
+            .configure(monitoring::api::configure_routes)
+            .app_data(event_hub.clone())
+            .configure(stream::api::configure_routes)
             .service(
                 web::scope("/api/v1")
                     .configure(api::v1::configure)
@@ -102,14 +814,25 @@ async fn main() -> std::io::Result<()> {
                     .route("", web::get().to(health_check))
                     .route("/ready", web::get().to(ready_check))
             )
+            .route("/api-docs/diff", web::get().to(api_docs::drift::spec_diff))
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}")
                     .url("/api-docs/openapi.json", ApiDoc::openapi())
             )
     })
-    .bind(format!("{}:{}", server_host, server_port))?
-    .run()
-    .await
+    .on_connect(middleware::device_identity::capture_peer_certificate);
+    let server = match http_workers {
+        0 => server,
+        workers => server.workers(workers),
+    };
+    
+    let address = format!("{}:{}", server_host, server_port);
+    let result = match server_tls {
+        Some(tls) => server.bind_rustls_021(address, tls)?.run().await,
+        None => server.bind(address)?.run().await,
+    };
+    telemetry::shutdown();
+    result
 }
 
 /// Health check endpoint
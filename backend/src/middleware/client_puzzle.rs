@@ -0,0 +1,408 @@
+//! Desafio de prova de trabalho nas rotas públicas sob carga
+//!
+//! Portal de verificação, resultados e log transparente são anônimos e não
+//! podem identificar quem consulta. Quando a carga anônima nessas rotas
+//! passa do limiar, o middleware passa a exigir a solução de um desafio:
+//! achar um número que, junto com o desafio, dê um SHA-256 com o número
+//! pedido de bits zero no início. A dificuldade cresce com a carga, e o
+//! limitador de requisições (controle de admissão) soma as recusas dele à
+//! carga medida; o operador também pode fixar uma dificuldade mínima.
+//!
+//! O desafio não guarda estado no servidor: leva a dificuldade e o prazo,
+//! assinados com HMAC. Uma solução vale para algumas requisições dentro do
+//! prazo; só o número aleatório do desafio é guardado até vencer, nunca IP
+//! ou outro dado do cliente. Requisições autenticadas não passam pelo
+//! desafio.
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::StatusCode,
+    web, Error, HttpMessage, HttpResponse,
+};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use futures::future::LocalBoxFuture;
+use rand::RngCore;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use crate::auth::rbac::Principal;
+use crate::config::ClientPuzzleConfig;
+
+/// Cabeçalho com o desafio, na resposta e no reenvio
+pub const PUZZLE_HEADER: &str = "X-Fortis-Puzzle";
+
+/// Cabeçalho com o número que resolve o desafio
+pub const PUZZLE_SOLUTION_HEADER: &str = "X-Fortis-Puzzle-Solution";
+
+/// Algoritmo anunciado ao cliente
+pub const PUZZLE_ALGORITHM: &str = "sha256-leading-zero-bits";
+
+const PUZZLE_VERSION: &str = "v1";
+
+/// Desafio emitido ao cliente
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PuzzleChallenge {
+    pub challenge: String,
+    pub difficulty: u8,
+    pub algorithm: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Situação da válvula, para o operador
+#[derive(Debug, Clone, Serialize)]
+pub struct PuzzleStatus {
+    pub enabled: bool,
+    /// Requisições anônimas por segundo, na janela de medição
+    pub load_per_second: f64,
+    pub activation_per_second: u32,
+    pub difficulty: u8,
+    pub floor_difficulty: Option<u8>,
+    pub max_difficulty: u8,
+    pub path_prefixes: Vec<String>,
+}
+
+/// Contagem por segundo na janela de medição
+#[derive(Default)]
+struct LoadWindow {
+    seconds: VecDeque<(i64, u64)>,
+}
+
+impl LoadWindow {
+    fn add(&mut self, now: DateTime<Utc>, weight: u64, window_seconds: i64) {
+        let second = now.timestamp();
+        match self.seconds.back_mut() {
+            Some((last, count)) if *last == second => *count += weight,
+            _ => self.seconds.push_back((second, weight)),
+        }
+        self.prune(second, window_seconds);
+    }
+
+    fn per_second(&mut self, now: DateTime<Utc>, window_seconds: i64) -> f64 {
+        self.prune(now.timestamp(), window_seconds);
+        let total: u64 = self.seconds.iter().map(|(_, count)| count).sum();
+        total as f64 / window_seconds.max(1) as f64
+    }
+
+    fn prune(&mut self, second: i64, window_seconds: i64) {
+        while self.seconds.front().is_some_and(|(at, _)| second - at >= window_seconds) {
+            self.seconds.pop_front();
+        }
+    }
+}
+
+/// Emissão e conferência dos desafios, com a dificuldade ajustada pela carga
+pub struct ClientPuzzle {
+    config: ClientPuzzleConfig,
+    key: hmac::Key,
+    load: Mutex<LoadWindow>,
+    floor: Mutex<Option<u8>>,
+    /// Usos de cada desafio resolvido, até o prazo dele
+    spent: Mutex<HashMap<String, (u32, DateTime<Utc>)>>,
+}
+
+impl ClientPuzzle {
+    /// A chave é comum às réplicas, que aceitam os desafios umas das outras
+    pub fn new(config: ClientPuzzleConfig, secret: &[u8]) -> Self {
+        let derived = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, secret), b"fortis-client-puzzle-v1");
+        Self {
+            floor: Mutex::new(config.floor_difficulty),
+            config,
+            key: hmac::Key::new(hmac::HMAC_SHA256, derived.as_ref()),
+            load: Mutex::new(LoadWindow::default()),
+            spent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Indica se a rota é uma das protegidas
+    pub fn covers(&self, path: &str) -> bool {
+        self.config.enabled && self.config.path_prefixes.iter().any(|prefix| path.starts_with(prefix.as_str()))
+    }
+
+    /// Conta uma requisição anônima na carga
+    pub fn observe(&self, now: DateTime<Utc>) {
+        self.add_load(now, 1);
+    }
+
+    /// Recusa do limitador de requisições; pesa mais que uma requisição comum
+    pub fn record_throttled(&self, now: DateTime<Utc>) {
+        self.add_load(now, self.config.throttled_weight as u64);
+    }
+
+    fn add_load(&self, now: DateTime<Utc>, weight: u64) {
+        if let Ok(mut load) = self.load.lock() {
+            load.add(now, weight, self.config.window_seconds);
+        }
+    }
+
+    /// Dificuldade mínima fixada pelo operador; `None` volta a seguir só a carga
+    pub fn set_floor(&self, floor: Option<u8>) -> Result<()> {
+        if floor.is_some_and(|bits| bits > self.config.max_difficulty) {
+            return Err(anyhow!("Puzzle difficulty is limited to {} bits", self.config.max_difficulty));
+        }
+        *self.floor.lock().map_err(|_| anyhow!("Puzzle state poisoned"))? = floor;
+        log::info!("Client puzzle floor difficulty set to {:?}", floor);
+        Ok(())
+    }
+
+    /// Bits exigidos agora; zero deixa passar sem desafio
+    pub fn difficulty(&self, now: DateTime<Utc>) -> u8 {
+        let floor = self.floor.lock().ok().and_then(|floor| *floor).unwrap_or(0);
+        let load = self.load_per_second(now);
+        let activation = self.config.activation_per_second.max(1) as f64;
+        let from_load = if load < activation {
+            0
+        } else {
+            // Cada vez que a carga dobra, um bit a mais (o dobro de trabalho)
+            self.config.base_difficulty as f64 + (load / activation).log2().floor()
+        };
+        (from_load as u8).max(floor).min(self.config.max_difficulty)
+    }
+
+    fn load_per_second(&self, now: DateTime<Utc>) -> f64 {
+        self.load.lock().map(|mut load| load.per_second(now, self.config.window_seconds)).unwrap_or(0.0)
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> PuzzleStatus {
+        PuzzleStatus {
+            enabled: self.config.enabled,
+            load_per_second: self.load_per_second(now),
+            activation_per_second: self.config.activation_per_second,
+            difficulty: self.difficulty(now),
+            floor_difficulty: self.floor.lock().ok().and_then(|floor| *floor),
+            max_difficulty: self.config.max_difficulty,
+            path_prefixes: self.config.path_prefixes.clone(),
+        }
+    }
+
+    /// Novo desafio com a dificuldade indicada
+    pub fn issue(&self, difficulty: u8, now: DateTime<Utc>) -> PuzzleChallenge {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        // O prazo vai no desafio em segundos inteiros
+        let expires = (now + Duration::seconds(self.config.challenge_ttl_seconds)).timestamp();
+        let body = format!("{}.{}.{}.{}", PUZZLE_VERSION, difficulty, expires, hex::encode(nonce));
+        let mac = hmac::sign(&self.key, body.as_bytes());
+        PuzzleChallenge {
+            challenge: format!("{}.{}", body, hex::encode(mac.as_ref())),
+            difficulty,
+            algorithm: PUZZLE_ALGORITHM.to_string(),
+            expires_at: Utc.timestamp_opt(expires, 0).single().unwrap_or(now),
+        }
+    }
+
+    /// Confere a solução e conta um uso do desafio
+    pub fn redeem(&self, challenge: &str, solution: &str, now: DateTime<Utc>) -> Result<()> {
+        let (body, mac) = challenge.rsplit_once('.').ok_or_else(|| anyhow!("Malformed puzzle challenge"))?;
+        let mac = hex::decode(mac).map_err(|_| anyhow!("Malformed puzzle challenge"))?;
+        hmac::verify(&self.key, body.as_bytes(), &mac).map_err(|_| anyhow!("Puzzle challenge was not issued here"))?;
+
+        let fields: Vec<&str> = body.split('.').collect();
+        let [version, difficulty, expires_at, nonce] = fields.as_slice() else {
+            return Err(anyhow!("Malformed puzzle challenge"));
+        };
+        if *version != PUZZLE_VERSION {
+            return Err(anyhow!("Unsupported puzzle version {}", version));
+        }
+        let difficulty: u8 = difficulty.parse().map_err(|_| anyhow!("Malformed puzzle challenge"))?;
+        let expires_at = expires_at.parse::<i64>().ok()
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+            .ok_or_else(|| anyhow!("Malformed puzzle challenge"))?;
+        if now >= expires_at {
+            return Err(anyhow!("Puzzle challenge expired"));
+        }
+        if solution.is_empty() || solution.len() > 32 {
+            return Err(anyhow!("Malformed puzzle solution"));
+        }
+        let digest = Sha256::digest(format!("{}:{}", challenge, solution).as_bytes());
+        if leading_zero_bits(&digest) < difficulty as u32 {
+            return Err(anyhow!("Puzzle solution does not meet {} bits", difficulty));
+        }
+
+        let mut spent = self.spent.lock().map_err(|_| anyhow!("Puzzle state poisoned"))?;
+        spent.retain(|_, (_, expires)| *expires > now);
+        let (uses, _) = spent.entry(nonce.to_string()).or_insert((0, expires_at));
+        if *uses >= self.config.uses_per_solution {
+            return Err(anyhow!("Puzzle solution already used {} times", uses));
+        }
+        *uses += 1;
+        Ok(())
+    }
+}
+
+/// Bits zero no início do hash
+pub fn leading_zero_bits(digest: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in digest {
+        if *byte == 0 {
+            bits += 8;
+            continue;
+        }
+        return bits + byte.leading_zeros();
+    }
+    bits
+}
+
+/// Middleware que aplica o `ClientPuzzle` registrado em `app_data`
+pub struct ClientPuzzleGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for ClientPuzzleGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ClientPuzzleGuardService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ClientPuzzleGuardService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct ClientPuzzleGuardService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for ClientPuzzleGuardService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let Some(puzzle) = req.app_data::<web::Data<ClientPuzzle>>().cloned() else {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            };
+            let anonymous = req.extensions().get::<Principal>().map_or(true, |p| p.subject.is_none());
+            if !anonymous || !puzzle.covers(req.path()) {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            }
+
+            let now = Utc::now();
+            puzzle.observe(now);
+            let difficulty = puzzle.difficulty(now);
+            if difficulty == 0 {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            }
+
+            let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+            let error = match (header(PUZZLE_HEADER), header(PUZZLE_SOLUTION_HEADER)) {
+                (Some(challenge), Some(solution)) => match puzzle.redeem(&challenge, &solution, now) {
+                    Ok(()) => return service.call(req).await.map(ServiceResponse::map_into_left_body),
+                    Err(e) => Some(e.to_string()),
+                },
+                _ => None,
+            };
+
+            let challenge = puzzle.issue(difficulty, now);
+            let response = HttpResponse::build(StatusCode::PRECONDITION_REQUIRED)
+                .insert_header((PUZZLE_HEADER, challenge.challenge.clone()))
+                .json(json!({
+                    "success": false,
+                    "error": {
+                        "code": "PUZZLE_REQUIRED",
+                        "message": "Serviço sob alta demanda. Resolva o desafio e reenvie a requisição.",
+                        "reason": error,
+                        "puzzle": challenge
+                    },
+                    "timestamp": now
+                }))
+                .map_into_right_body();
+            Ok(req.into_response(response))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ClientPuzzleConfig {
+        ClientPuzzleConfig {
+            enabled: true,
+            path_prefixes: vec!["/api/v1/votes/verify".to_string()],
+            activation_per_second: 10,
+            base_difficulty: 8,
+            max_difficulty: 12,
+            floor_difficulty: None,
+            window_seconds: 10,
+            throttled_weight: 5,
+            challenge_ttl_seconds: 60,
+            uses_per_solution: 2,
+        }
+    }
+
+    fn solve(challenge: &PuzzleChallenge) -> String {
+        (0u64..).map(|n| n.to_string())
+            .find(|n| {
+                let digest = Sha256::digest(format!("{}:{}", challenge.challenge, n).as_bytes());
+                leading_zero_bits(&digest) >= challenge.difficulty as u32
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_difficulty_follows_load_and_floor() {
+        let puzzle = ClientPuzzle::new(config(), b"secret");
+        let now = Utc::now();
+        assert!(puzzle.covers("/api/v1/votes/verify/ABCD"));
+        assert!(!puzzle.covers("/api/v1/elections"));
+        assert_eq!(puzzle.difficulty(now), 0);
+
+        for _ in 0..99 {
+            puzzle.observe(now);
+        }
+        assert_eq!(puzzle.difficulty(now), 0);
+        puzzle.observe(now);
+        assert_eq!(puzzle.difficulty(now), 8);
+        for _ in 0..20 {
+            puzzle.record_throttled(now);
+        }
+        assert_eq!(puzzle.difficulty(now), 9);
+        assert_eq!(puzzle.difficulty(now + Duration::seconds(11)), 0);
+
+        assert!(puzzle.set_floor(Some(13)).is_err());
+        puzzle.set_floor(Some(4)).unwrap();
+        assert_eq!(puzzle.difficulty(now + Duration::seconds(11)), 4);
+    }
+
+    #[test]
+    fn test_solution_is_checked_and_limited() {
+        let puzzle = ClientPuzzle::new(config(), b"secret");
+        let now = Utc::now();
+        let challenge = puzzle.issue(8, now);
+        let solution = solve(&challenge);
+
+        let other = ClientPuzzle::new(config(), b"other secret");
+        assert!(other.redeem(&challenge.challenge, &solution, now).is_err());
+        let tampered = challenge.challenge.replacen(".8.", ".1.", 1);
+        assert!(puzzle.redeem(&tampered, &solution, now).is_err());
+        assert!(puzzle.redeem(&challenge.challenge, &solution, now + Duration::seconds(61)).is_err());
+
+        puzzle.redeem(&challenge.challenge, &solution, now).unwrap();
+        puzzle.redeem(&challenge.challenge, &solution, now).unwrap();
+        assert!(puzzle.redeem(&challenge.challenge, &solution, now).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+//! Middleware de identidade das urnas (mTLS)
+//!
+//! O certificado de cliente apresentado no handshake TLS é guardado nos
+//! dados da conexão e, nas rotas de urnas, mapeado para a urna registrada
+//! pelo `UrnaAuthService`. A identidade fica disponível nas extensões da
+//! requisição como `DeviceIdentity`.
+//!
+//! Só as rotas de operador (preparação, provisionamento, frota, diagnóstico
+//! e aprovações de contingência), conferidas por método e padrão exatos,
+//! passam sem certificado; elas são protegidas pelo RBAC do handler. O
+//! credenciamento da urna provisionada também passa, porque a urna ainda
+//! não tem certificado e se autentica pela credencial de uso único.
+
+use actix_tls::accept::rustls_0_21::TlsStream;
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Extensions, Service, ServiceRequest, ServiceResponse, Transform},
+    rt::net::TcpStream,
+    web, Error, HttpMessage, HttpRequest, HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+use serde_json::json;
+use std::{
+    any::Any,
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use crate::services::dual_control::path_matches;
+use crate::services::urna::auth::DeviceIdentity;
+use crate::services::urna::UrnaAuthService;
+use crate::tls::TransportPolicy;
+
+/// Rotas de operador e de credenciamento, dispensadas do certificado de urna
+const OPERATOR_ROUTES: &[(&str, &str)] = &[
+    // Preparação
+    ("POST", "/api/v1/urnas/register"),
+    ("POST", "/api/v1/urnas/{urna_id}/public-key"),
+    ("POST", "/api/v1/urnas/{urna_id}/public-key/rotate"),
+    ("GET", "/api/v1/urnas/provisioning-key"),
+    ("POST", "/api/v1/urnas/{urna_id}/sealing-key"),
+    ("POST", "/api/v1/urnas/{urna_id}/provisioning-bundles"),
+    ("GET", "/api/v1/urnas/{urna_id}/provisioning-bundles"),
+    ("POST", "/api/v1/urnas/{urna_id}/certificates"),
+    ("GET", "/api/v1/urnas/{urna_id}/certificates"),
+    ("POST", "/api/v1/urnas/{urna_id}/certificates/revoke"),
+    ("POST", "/api/v1/urnas/{urna_id}/attestation-key"),
+    ("POST", "/api/v1/urnas/attestation-baselines"),
+    ("DELETE", "/api/v1/urnas/attestation-baselines/{baseline_id}"),
+    ("POST", "/api/v1/urnas/updates"),
+    // Provisionamento
+    ("POST", "/api/v1/urnas/provision"),
+    ("GET", "/api/v1/urnas/provision"),
+    ("GET", "/api/v1/urnas/provision/{urna_id}"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/credentials"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/enroll"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/configuration"),
+    ("POST", "/api/v1/urnas/provision/{urna_id}/revoke"),
+    // Frota e diagnóstico
+    ("GET", "/api/v1/urnas/fleet/zones"),
+    ("GET", "/api/v1/urnas/fleet/urnas"),
+    ("GET", "/api/v1/urnas/diagnostics/codes"),
+    ("GET", "/api/v1/urnas/diagnostics/codes/{code}"),
+    ("GET", "/api/v1/urnas/diagnostics/frequency"),
+    ("GET", "/api/v1/urnas/{urna_id}/heartbeats"),
+    ("GET", "/api/v1/urnas/{urna_id}/diagnostics"),
+    // Contingência
+    ("POST", "/api/v1/urnas/contingency/transfers/{id}/approve"),
+    ("POST", "/api/v1/urnas/contingency/transfers/{id}/reconcile"),
+];
+
+/// Rota de operador, dispensada do certificado de urna
+pub fn is_operator_route(method: &str, path: &str) -> bool {
+    OPERATOR_ROUTES.iter()
+        .any(|(route_method, pattern)| route_method.eq_ignore_ascii_case(method) && path_matches(pattern, path))
+}
+
+/// Certificado (DER) apresentado pelo cliente na conexão
+#[derive(Debug, Clone)]
+pub struct PeerCertificate(pub Vec<u8>);
+
+/// Guarda o certificado do cliente nos dados da conexão (`HttpServer::on_connect`)
+pub fn capture_peer_certificate(connection: &dyn Any, data: &mut Extensions) {
+    if let Some(tls) = connection.downcast_ref::<TlsStream<TcpStream>>() {
+        let (_, session) = tls.get_ref();
+        if let Some(cert) = session.peer_certificates().and_then(|certs| certs.first()) {
+            data.insert(PeerCertificate(cert.0.clone()));
+        }
+    }
+}
+
+/// Urna do certificado da conexão, para rotas de urna fora do escopo `/urnas`
+pub async fn connection_device(req: &HttpRequest) -> anyhow::Result<DeviceIdentity> {
+    let certificate = req.conn_data::<PeerCertificate>()
+        .ok_or_else(|| anyhow::anyhow!("No client certificate"))?;
+    let auth = req.app_data::<web::Data<UrnaAuthService>>()
+        .ok_or_else(|| anyhow::anyhow!("Urna authentication unavailable"))?;
+    auth.identify_device(&certificate.0).await
+}
+
+/// Exige certificado de urna credenciado nas rotas envolvidas; sem TLS no
+/// servidor (desenvolvimento), pedidos sem certificado seguem adiante, mas
+/// os handlers que agem em nome de uma urna os recusam
+pub struct DeviceIdentityGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for DeviceIdentityGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = DeviceIdentityGuardService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(DeviceIdentityGuardService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct DeviceIdentityGuardService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for DeviceIdentityGuardService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            if is_operator_route(req.method().as_str(), req.path()) {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            }
+
+            let Some(certificate) = req.conn_data::<PeerCertificate>().cloned() else {
+                let required = req.app_data::<web::Data<TransportPolicy>>()
+                    .map_or(true, |policy| policy.device_certificates_required());
+                if required {
+                    return Ok(reject(req, HttpResponse::Unauthorized(), "Certificado de cliente da urna obrigatório".to_string()));
+                }
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            };
+
+            let Some(auth) = req.app_data::<web::Data<UrnaAuthService>>().cloned() else {
+                return Ok(req.into_response(
+                    HttpResponse::ServiceUnavailable().finish().map_into_right_body(),
+                ));
+            };
+
+            match auth.identify_device(&certificate.0).await {
+                Ok(identity) => {
+                    req.extensions_mut().insert(identity);
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                }
+                Err(e) => Ok(reject(req, HttpResponse::Forbidden(), format!("Certificado de urna recusado: {}", e))),
+            }
+        })
+    }
+}
+
+fn reject<B>(
+    req: ServiceRequest,
+    mut response: actix_web::HttpResponseBuilder,
+    error: String,
+) -> ServiceResponse<EitherBody<B>> {
+    req.into_response(
+        response
+            .json(json!({
+                "success": false,
+                "error": error,
+                "timestamp": chrono::Utc::now()
+            }))
+            .map_into_right_body(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{test, App, HttpResponse};
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    use crate::config::TransportConfig;
+
+    fn pem_file(contents: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_operator_routes_match_exact_templates() {
+        assert!(is_operator_route("POST", "/api/v1/urnas/URNA-1/public-key"));
+        assert!(is_operator_route("GET", "/api/v1/urnas/provisioning-key"));
+        assert!(!is_operator_route("GET", "/api/v1/urnas/URNA-1/public-key"));
+        // Substrings das rotas de preparação não dispensam o certificado
+        assert!(!is_operator_route("POST", "/api/v1/urnas/URNA-1/heartbeat/certificates"));
+        assert!(!is_operator_route("POST", "/api/v1/urnas/URNA-1/sealing-key/votes"));
+        assert!(!is_operator_route("POST", "/api/v1/urnas/URNA-1/register"));
+
+        assert!(is_operator_route("POST", "/api/v1/urnas/provision/URNA-1/enroll"));
+        assert!(is_operator_route("GET", "/api/v1/urnas/fleet/zones"));
+        assert!(is_operator_route("POST", "/api/v1/urnas/updates"));
+        assert!(!is_operator_route("POST", "/api/v1/urnas/provision/URNA-1/acknowledge"));
+        assert!(!is_operator_route("POST", "/api/v1/urnas/URNA-1/updates"));
+        assert!(!is_operator_route("POST", "/api/v1/urnas/contingency/transfers/T-1/import"));
+    }
+
+    #[actix_web::test]
+    async fn test_guard_with_required_certificates_only_admits_operator_routes() {
+        let mut params = CertificateParams::new(vec!["localhost".to_string()]);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let server = Certificate::from_params(params).unwrap();
+        let cert = pem_file(&server.serialize_pem().unwrap());
+        let key = pem_file(&server.serialize_private_key_pem());
+        let path = |file: &NamedTempFile| file.path().to_str().unwrap().to_string();
+
+        let policy = TransportPolicy::from_config(&TransportConfig {
+            tls_profile: "modern".to_string(),
+            tls_enabled: true,
+            internal_tls_enabled: false,
+            grpc_enabled: false,
+            grpc_port: 0,
+            cert_path: path(&cert),
+            key_path: path(&key),
+            ca_path: path(&cert),
+            device_ca_path: path(&cert),
+            require_device_certificates: true,
+        }).unwrap();
+        assert!(policy.device_certificates_required());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(policy))
+                .service(
+                    web::scope("/api/v1/urnas")
+                        .wrap(DeviceIdentityGuard)
+                        .default_service(web::to(|| async { HttpResponse::Ok().finish() })),
+                ),
+        ).await;
+
+        for (method, uri) in [
+            ("POST", "/api/v1/urnas/provision/URNA-1/enroll"),
+            ("POST", "/api/v1/urnas/provision"),
+            ("GET", "/api/v1/urnas/provision/URNA-1"),
+            ("POST", "/api/v1/urnas/provision/URNA-1/credentials"),
+            ("POST", "/api/v1/urnas/provision/URNA-1/configuration"),
+            ("POST", "/api/v1/urnas/provision/URNA-1/revoke"),
+            ("POST", "/api/v1/urnas/updates"),
+            ("POST", "/api/v1/urnas/attestation-baselines"),
+            ("GET", "/api/v1/urnas/fleet/urnas"),
+            ("GET", "/api/v1/urnas/diagnostics/frequency"),
+        ] {
+            let req = test::TestRequest::default()
+                .method(method.parse().unwrap())
+                .uri(uri)
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), 200, "{} {}", method, uri);
+        }
+
+        for (method, uri) in [
+            ("POST", "/api/v1/urnas/vote"),
+            ("POST", "/api/v1/urnas/provision/URNA-1/acknowledge"),
+            ("POST", "/api/v1/urnas/contingency/transfers/T-1/import"),
+        ] {
+            let req = test::TestRequest::default()
+                .method(method.parse().unwrap())
+                .uri(uri)
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), 401, "{} {}", method, uri);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Middleware de controle duplo
+//!
+//! Nas rotas protegidas, o primeiro pedido não chega ao handler: vira um
+//! pedido pendente no `DualControlService` e a resposta é 202 com o
+//! identificador. Depois da segunda aprovação, a mesma requisição reenviada
+//! com o cabeçalho `X-Dual-Control-Approval` segue para o handler uma vez.
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage, HttpResponse, ResponseError,
+};
+use futures::future::LocalBoxFuture;
+use serde_json::json;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use crate::auth::rbac::Principal;
+use crate::models::ApiResponse;
+use crate::services::dual_control::DualControlService;
+
+/// Cabeçalho com o pedido aprovado
+pub const DUAL_CONTROL_HEADER: &str = "X-Dual-Control-Approval";
+
+/// Middleware que retém operações destrutivas até a segunda aprovação
+pub struct DualControlGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for DualControlGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = DualControlGuardService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(DualControlGuardService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct DualControlGuardService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for DualControlGuardService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let Some(dual_control) = req.app_data::<web::Data<DualControlService>>().cloned() else {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            };
+            let method = req.method().to_string();
+            let path = req.path().to_string();
+            let Some(rule) = dual_control.rule_for(&method, &path).cloned() else {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            };
+
+            let principal = req.extensions().get::<Principal>().cloned().unwrap_or_default();
+            let subject = match principal.require(rule.permission) {
+                Ok(subject) => subject.to_string(),
+                Err(e) => return Ok(req.into_response(e.error_response().map_into_right_body())),
+            };
+
+            // O corpo entra no hash do pedido e volta para o handler
+            let body = req.extract::<web::Bytes>().await?;
+            req.set_payload(body.clone().into());
+
+            let approval = req.headers()
+                .get(DUAL_CONTROL_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let Some(approval) = approval else {
+                let response = match dual_control.open(&rule, &method, &path, &body, &subject).await {
+                    Ok(pending) => HttpResponse::Accepted().json(ApiResponse::success(pending)),
+                    Err(e) => HttpResponse::BadRequest().json(
+                        ApiResponse::<()>::error(format!("Pedido de controle duplo inválido: {}", e))
+                    ),
+                };
+                return Ok(req.into_response(response.map_into_right_body()));
+            };
+
+            match dual_control.consume(&approval, &subject, &method, &path, &body).await {
+                Ok(executed) => {
+                    log::info!(
+                        "{} executing {} approved by {:?} (dual control {})",
+                        subject, executed.operation, executed.approver, executed.id
+                    );
+                    req.extensions_mut().insert(executed);
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                }
+                Err(e) => Ok(req.into_response(
+                    HttpResponse::Forbidden()
+                        .json(json!({
+                            "success": false,
+                            "error": format!("Controle duplo: {}", e),
+                            "timestamp": chrono::Utc::now()
+                        }))
+                        .map_into_right_body(),
+                )),
+            }
+        })
+    }
+}
@@ -0,0 +1,91 @@
+//! Mensagens de erro da API no idioma do cliente
+//!
+//! Respostas geradas por um `FortisError` são refeitas com a mensagem do
+//! pacote de idioma escolhido pelo `Accept-Language` (português quando não
+//! houver correspondência), o mesmo código estável e o detalhe técnico em
+//! `message`. O idioma usado vai em `Content-Language`.
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE},
+    web, Error, HttpResponse, ResponseError,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+
+use crate::errors::FortisError;
+use crate::i18n::LanguagePacks;
+use crate::models::ApiResponse;
+
+/// Middleware que traduz as respostas de erro
+pub struct LocalizedErrors;
+
+impl<S, B> Transform<S, ServiceRequest> for LocalizedErrors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = LocalizedErrorsService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocalizedErrorsService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct LocalizedErrorsService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LocalizedErrorsService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let packs = req.app_data::<web::Data<LanguagePacks>>().cloned();
+        let accept_language = req.headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            let Some(packs) = packs else {
+                return Ok(response.map_into_left_body());
+            };
+
+            let localized = response.response().error()
+                .and_then(|error| error.as_error::<FortisError>())
+                .and_then(|error| {
+                    let (locale, message) = packs.api_message(accept_language.as_deref(), error.code());
+                    message.map(|message| {
+                        HttpResponse::build(error.status_code())
+                            .insert_header((CONTENT_LANGUAGE, locale.to_string()))
+                            .json(ApiResponse::<()>::localized(error, message))
+                    })
+                });
+
+            match localized {
+                Some(localized) => Ok(response.into_response(localized).map_into_right_body()),
+                None => Ok(response.map_into_left_body()),
+            }
+        })
+    }
+}
@@ -1,6 +1,13 @@
 //! Módulo de middlewares do FORTIS Backend
 
-pub mod cors;
-pub mod auth;
+// pub mod cors;
+// pub mod auth;
+// pub mod tse_auth;
+pub mod support_access;
+pub mod dual_control;
+pub mod device_identity;
+pub mod rbac;
 pub mod rate_limit;
-pub mod tse_auth;
+pub mod client_puzzle;
+pub mod request_id;
+pub mod localized_errors;
@@ -1,37 +1,296 @@
-//! Middleware de rate limiting do FORTIS
+//! Limite de requisições por janela deslizante
+//!
+//! Cada requisição conta para o IP de origem e, quando autenticada, também
+//! para o sujeito do `Principal`; basta um dos dois passar do limite para a
+//! resposta ser 429. O limite vem da regra do prefixo de rota mais longo em
+//! `SecurityConfig`, com a regra padrão para o restante. Rotas com limite
+//! próprio (o portal de verificação de comprovantes, que não pode guardar
+//! IPs) ficam fora.
+//!
+//! No Redis a janela é um sorted set por chave, com o instante de cada
+//! requisição como score, atualizado por um script atômico que usa o
+//! relógio do próprio Redis: todas as réplicas contam na mesma janela.
+//! Se o Redis cair, a requisição segue, para não derrubar a API junto.
+//!
+//! Cada recusa também entra na carga medida pelo `ClientPuzzle`, que sobe a
+//! dificuldade do desafio das rotas públicas.
 
-use actix_web::dev::{ServiceRequest, ServiceResponse};
-use actix_web::Error;
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage, HttpResponse,
+};
+use anyhow::Result;
+use futures::future::LocalBoxFuture;
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, OnceCell};
+use uuid::Uuid;
 
-pub struct RateLimitMiddleware;
+use crate::auth::rbac::Principal;
+use crate::config::{RateLimitRule, SecurityConfig};
+use crate::middleware::client_puzzle::ClientPuzzle;
+
+const REDIS_PREFIX: &str = "fortis:ratelimit";
+
+/// Janela deslizante atômica: remove o que saiu da janela, conta e registra
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local window = tonumber(ARGV[1])
+local limit = tonumber(ARGV[2])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], 0, now - window)
+local count = redis.call('ZCARD', KEYS[1])
+if count < limit then
+    redis.call('ZADD', KEYS[1], now, ARGV[3])
+    redis.call('PEXPIRE', KEYS[1], window)
+    return {1, limit - count - 1, 0}
+end
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+return {0, 0, tonumber(oldest[2]) + window - now}
+"#;
+
+/// Resultado da contagem de uma requisição
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub remaining: u32,
+    /// Tempo até a requisição mais antiga sair da janela
+    pub retry_after: Duration,
+}
+
+/// Onde as janelas são mantidas
+enum LimiterBackend {
+    Memory {
+        windows: Mutex<HashMap<String, VecDeque<Instant>>>,
+    },
+    Redis {
+        client: redis::Client,
+        connection: OnceCell<redis::aio::MultiplexedConnection>,
+    },
+}
+
+/// Limitador por janela deslizante
+#[derive(Clone)]
+pub struct RateLimiter {
+    default_rule: RateLimitRule,
+    route_rules: Vec<RateLimitRule>,
+    exempt_prefixes: Vec<String>,
+    backend: Arc<LimiterBackend>,
+}
+
+impl RateLimiter {
+    pub fn new(default_rule: RateLimitRule, route_rules: Vec<RateLimitRule>) -> Self {
+        Self {
+            default_rule,
+            route_rules,
+            exempt_prefixes: Vec::new(),
+            backend: Arc::new(LimiterBackend::Memory { windows: Mutex::new(HashMap::new()) }),
+        }
+    }
+
+    pub fn from_config(config: &SecurityConfig) -> Self {
+        Self::new(config.rate_limit.clone(), config.route_rate_limits.clone())
+            .with_exempt_prefixes(config.rate_limit_exempt_prefixes.clone())
+    }
+
+    /// Prefixos de rota que o limitador deixa passar sem contar
+    pub fn with_exempt_prefixes(mut self, prefixes: Vec<String>) -> Self {
+        self.exempt_prefixes = prefixes;
+        self
+    }
+
+    pub fn is_exempt(&self, path: &str) -> bool {
+        self.exempt_prefixes.iter().any(|prefix| path.starts_with(prefix.as_str()))
+    }
+
+    /// Janelas no Redis, compartilhadas entre as réplicas
+    pub fn with_redis(mut self, client: redis::Client) -> Self {
+        self.backend = Arc::new(LimiterBackend::Redis { client, connection: OnceCell::new() });
+        self
+    }
+
+    /// Regra do prefixo de rota mais longo que cobre o caminho
+    pub fn rule_for(&self, path: &str) -> &RateLimitRule {
+        self.route_rules.iter()
+            .filter(|rule| path.starts_with(&rule.path_prefix))
+            .max_by_key(|rule| rule.path_prefix.len())
+            .unwrap_or(&self.default_rule)
+    }
 
-impl RateLimitMiddleware {
-    pub fn new() -> Self {
-        Self
+    /// Conta a requisição na janela da chave
+    pub async fn check(&self, key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision> {
+        let window = Duration::from_secs(rule.window_seconds);
+        match self.backend.as_ref() {
+            LimiterBackend::Memory { windows } => {
+                let now = Instant::now();
+                let mut windows = windows.lock().await;
+                let requests = windows.entry(key.to_string()).or_default();
+                while requests.front().is_some_and(|at| now.duration_since(*at) >= window) {
+                    requests.pop_front();
+                }
+
+                if requests.len() < rule.requests as usize {
+                    requests.push_back(now);
+                    return Ok(RateLimitDecision {
+                        allowed: true,
+                        remaining: rule.requests - requests.len() as u32,
+                        retry_after: Duration::ZERO,
+                    });
+                }
+                let oldest = requests.front().copied().unwrap_or(now);
+                Ok(RateLimitDecision {
+                    allowed: false,
+                    remaining: 0,
+                    retry_after: window.saturating_sub(now.duration_since(oldest)),
+                })
+            }
+            LimiterBackend::Redis { client, connection } => {
+                let mut conn = connection
+                    .get_or_try_init(|| client.get_multiplexed_tokio_connection())
+                    .await?
+                    .clone();
+                let (allowed, remaining, retry_after_ms): (i64, i64, i64) = redis::Script::new(SLIDING_WINDOW_SCRIPT)
+                    .key(format!("{}:{}", REDIS_PREFIX, key))
+                    .arg(window.as_millis() as u64)
+                    .arg(rule.requests)
+                    .arg(Uuid::new_v4().to_string())
+                    .invoke_async(&mut conn)
+                    .await?;
+                Ok(RateLimitDecision {
+                    allowed: allowed == 1,
+                    remaining: remaining.max(0) as u32,
+                    retry_after: Duration::from_millis(retry_after_ms.max(0) as u64),
+                })
+            }
+        }
     }
 }
 
-impl actix_web::dev::Transform<ServiceRequest, ServiceRequest> for RateLimitMiddleware {
-    type Response = ServiceResponse;
+/// Middleware que aplica o `RateLimiter` registrado em `app_data`
+pub struct RateLimitMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type InitError = ();
-    type Transform = RateLimitService;
-    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Transform, Self::InitError>>>>;
+    type Transform = RateLimitService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
-    fn new_transform(&self, _req: ServiceRequest) -> Self::Future {
-        Box::pin(async move { Ok(RateLimitService) })
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService {
+            service: Rc::new(service),
+        }))
     }
 }
 
-pub struct RateLimitService;
+pub struct RateLimitService<S> {
+    service: Rc<S>,
+}
 
-impl actix_web::dev::Service<ServiceRequest> for RateLimitService {
-    type Response = ServiceResponse;
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
-    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // TODO: Implementar rate limiting
-        Box::pin(async move { Ok(req.into_response(actix_web::HttpResponse::Ok().finish())) })
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let Some(limiter) = req.app_data::<web::Data<RateLimiter>>().cloned() else {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            };
+            if limiter.is_exempt(req.path()) {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            }
+
+            let rule = limiter.rule_for(req.path()).clone();
+            let client_ip = req.connection_info().remote_addr().unwrap_or("unknown").to_string();
+            let subject = req.extensions().get::<Principal>().and_then(|p| p.subject.clone());
+
+            let mut keys = vec![format!("{}:ip:{}", rule.path_prefix, client_ip)];
+            if let Some(subject) = subject {
+                keys.push(format!("{}:sub:{}", rule.path_prefix, subject));
+            }
+
+            for key in keys {
+                let decision = match limiter.check(&key, &rule).await {
+                    Ok(decision) => decision,
+                    Err(e) => {
+                        log::warn!("Rate limiter unavailable, allowing request: {}", e);
+                        break;
+                    }
+                };
+                if !decision.allowed {
+                    if let Some(puzzle) = req.app_data::<web::Data<ClientPuzzle>>() {
+                        puzzle.record_throttled(chrono::Utc::now());
+                    }
+                    let retry_after = decision.retry_after.as_secs().max(1);
+                    let response = HttpResponse::TooManyRequests()
+                        .insert_header(("Retry-After", retry_after.to_string()))
+                        .json(json!({
+                            "success": false,
+                            "error": {
+                                "code": "RATE_LIMIT_EXCEEDED",
+                                "message": "Muitas requisições. Tente novamente mais tarde.",
+                                "retry_after": retry_after
+                            },
+                            "timestamp": chrono::Utc::now()
+                        }))
+                        .map_into_right_body();
+                    return Ok(req.into_response(response));
+                }
+            }
+
+            service.call(req).await.map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path_prefix: &str, requests: u32) -> RateLimitRule {
+        RateLimitRule { path_prefix: path_prefix.to_string(), requests, window_seconds: 60 }
+    }
+
+    #[test]
+    fn test_longest_route_prefix_wins() {
+        let limiter = RateLimiter::new(rule("/", 100), vec![rule("/api/v1/votes", 50), rule("/api/v1/votes/verify", 5)]);
+
+        assert_eq!(limiter.rule_for("/api/v1/votes/verify/ABCD").requests, 5);
+        assert_eq!(limiter.rule_for("/api/v1/votes/cast").requests, 50);
+        assert_eq!(limiter.rule_for("/health").requests, 100);
+    }
+
+    #[tokio::test]
+    async fn test_window_limits_each_key_separately() {
+        let limiter = RateLimiter::new(rule("/", 2), Vec::new());
+        let rule = limiter.rule_for("/").clone();
+
+        assert!(limiter.check("ip:10.0.0.1", &rule).await.unwrap().allowed);
+        assert!(limiter.check("ip:10.0.0.1", &rule).await.unwrap().allowed);
+        let denied = limiter.check("ip:10.0.0.1", &rule).await.unwrap();
+        assert!(!denied.allowed);
+        assert!(denied.retry_after > Duration::ZERO);
+
+        assert!(limiter.check("ip:10.0.0.2", &rule).await.unwrap().allowed);
     }
 }
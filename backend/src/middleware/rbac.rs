@@ -0,0 +1,79 @@
+//! Middleware de autenticação para o RBAC
+//!
+//! Valida o JWT do cabeçalho `Authorization: Bearer` e guarda nas extensões
+//! da requisição o `Principal` com os papéis atribuídos ao sujeito. Sem
+//! token válido a requisição segue anônima: rotas públicas continuam
+//! acessíveis e as protegidas recusam pelo extrator `Principal`. Chaves de
+//! auditor do log transparente, que não são JWT, seguem sem alteração.
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage,
+};
+use futures::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use crate::auth::jwt::{self, JwtService};
+use crate::auth::rbac::{self, RbacService};
+
+/// Middleware que resolve o sujeito e os papéis da requisição
+pub struct RbacAuthentication;
+
+impl<S, B> Transform<S, ServiceRequest> for RbacAuthentication
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RbacAuthenticationService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RbacAuthenticationService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RbacAuthenticationService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RbacAuthenticationService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let token = jwt::bearer_token(req.headers()).map(str::to_string);
+            let jwt = req.app_data::<web::Data<JwtService>>().cloned();
+            let rbac_service = req.app_data::<web::Data<RbacService>>().cloned();
+
+            if let (Some(token), Some(jwt), Some(rbac_service)) = (token, jwt, rbac_service) {
+                // Tokens revogados ou de sessão encerrada seguem anônimos
+                if let Ok(claims) = jwt.authenticate(&token).await {
+                    let principal = rbac::resolve(&rbac_service, claims.sub).await;
+                    req.extensions_mut().insert(principal);
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
@@ -0,0 +1,101 @@
+//! Identificador de correlação e log de acesso das requisições
+//!
+//! Aceita o `X-Request-Id` enviado pelo cliente (as urnas mandam um por
+//! chamada de sincronização) ou gera um novo, devolve-o na resposta e roda
+//! o restante da cadeia dentro do span `http_request`, de modo que todo log
+//! emitido durante a requisição leva o identificador. Um `traceparent`
+//! recebido torna o span filho do rastro distribuído de quem chamou.
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::time::Instant;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::telemetry::{self, REQUEST_ID_HEADER};
+
+/// Middleware de correlação; deve ser o mais externo da cadeia
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestIdService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestIdService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let request_id = telemetry::request_id_or_new(
+            req.headers().get(REQUEST_ID_HEADER).and_then(|value| value.to_str().ok()),
+        );
+
+        let span = tracing::info_span!(
+            "http_request",
+            request_id = %request_id,
+            method = %req.method(),
+            path = %req.path(),
+            status = tracing::field::Empty,
+        );
+        span.set_parent(telemetry::remote_context(req.headers()));
+
+        Box::pin(telemetry::with_request_id(request_id.clone(), async move {
+            let started = Instant::now();
+            let result = service.call(req).await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+
+            match result {
+                Ok(mut response) => {
+                    let status = response.status().as_u16();
+                    tracing::Span::current().record("status", status);
+                    tracing::info!(status, elapsed_ms, "request completed");
+                    if let (Ok(name), Ok(value)) = (
+                        HeaderName::from_bytes(REQUEST_ID_HEADER.as_bytes()),
+                        HeaderValue::from_str(&request_id),
+                    ) {
+                        response.headers_mut().insert(name, value);
+                    }
+                    Ok(response)
+                }
+                Err(e) => {
+                    tracing::warn!(elapsed_ms, error = %e, "request failed");
+                    Err(e)
+                }
+            }
+        }.instrument(span)))
+    }
+}
@@ -8,142 +8,13 @@ use futures_util::future::LocalBoxFuture;
 use std::{
     future::{ready, Ready},
     rc::Rc,
-    time::{Duration, Instant},
+    time::Instant,
 };
 use serde_json::json;
-use std::collections::HashMap;
-use std::sync::Mutex;
 use std::time::SystemTime;
 
-/// Rate limiter para prevenir abuso
-#[derive(Debug, Clone)]
-pub struct RateLimiter {
-    requests: HashMap<String, Vec<Instant>>,
-    max_requests: u32,
-    window_duration: Duration,
-}
-
-impl RateLimiter {
-    pub fn new(max_requests: u32, window_duration: Duration) -> Self {
-        Self {
-            requests: HashMap::new(),
-            max_requests,
-            window_duration,
-        }
-    }
-
-    pub fn is_allowed(&mut self, key: &str) -> bool {
-        let now = Instant::now();
-        let window_start = now - self.window_duration;
-
-        // Remove requisições antigas
-        if let Some(requests) = self.requests.get_mut(key) {
-            requests.retain(|&time| time > window_start);
-            
-            if requests.len() < self.max_requests as usize {
-                requests.push(now);
-                true
-            } else {
-                false
-            }
-        } else {
-            self.requests.insert(key.to_string(), vec![now]);
-            true
-        }
-    }
-}
-
-/// Middleware de rate limiting
-pub struct RateLimitMiddleware {
-    rate_limiter: Rc<Mutex<RateLimiter>>,
-}
-
-impl RateLimitMiddleware {
-    pub fn new(max_requests: u32, window_duration: Duration) -> Self {
-        Self {
-            rate_limiter: Rc::new(Mutex::new(RateLimiter::new(max_requests, window_duration))),
-        }
-    }
-}
-
-impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
-where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
-    S::Future: 'static,
-    B: 'static,
-{
-    type Response = ServiceResponse<B>;
-    type Error = Error;
-    type InitError = ();
-    type Transform = RateLimitService<S>;
-    type Future = Ready<Result<Self::Transform, Self::InitError>>;
-
-    fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(RateLimitService {
-            service: Rc::new(service),
-            rate_limiter: self.rate_limiter.clone(),
-        }))
-    }
-}
-
-pub struct RateLimitService<S> {
-    service: Rc<S>,
-    rate_limiter: Rc<Mutex<RateLimiter>>,
-}
-
-impl<S, B> Service<ServiceRequest> for RateLimitService<S>
-where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
-    S::Future: 'static,
-    B: 'static,
-{
-    type Response = ServiceResponse<B>;
-    type Error = Error;
-    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
-
-    forward_ready!(service);
-
-    fn call(&self, req: ServiceRequest) -> Self::Future {
-        let service = self.service.clone();
-        let rate_limiter = self.rate_limiter.clone();
-
-        Box::pin(async move {
-            // Obtém IP do cliente
-            let client_ip = req
-                .connection_info()
-                .remote_addr()
-                .unwrap_or("unknown")
-                .to_string();
-
-            // Verifica rate limit
-            let is_allowed = {
-                let mut limiter = rate_limiter.lock().unwrap();
-                limiter.is_allowed(&client_ip)
-            };
-
-            if !is_allowed {
-                let response = HttpResponse::TooManyRequests()
-                    .json(json!({
-                        "success": false,
-                        "error": {
-                            "code": "RATE_LIMIT_EXCEEDED",
-                            "message": "Muitas requisições. Tente novamente mais tarde.",
-                            "retry_after": 60
-                        },
-                        "timestamp": SystemTime::now()
-                            .duration_since(SystemTime::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs()
-                    }));
-                
-                return Ok(req.into_response(response));
-            }
-
-            // Continua com a requisição
-            service.call(req).await
-        })
-    }
-}
+/// Limite de requisições compartilhado entre réplicas (ver `rate_limit`)
+pub use super::rate_limit::{RateLimitMiddleware, RateLimiter};
 
 /// Middleware de validação de headers de segurança
 pub struct SecurityHeadersMiddleware;
@@ -524,10 +395,11 @@ where
 }
 
 /// Configuração de segurança
+///
+/// Os limites de requisição ficam em `config::SecurityConfig`, lidos pelo
+/// `RateLimiter` registrado em `app_data`.
 #[derive(Debug, Clone)]
 pub struct SecurityConfig {
-    pub rate_limit_requests: u32,
-    pub rate_limit_window: Duration,
     pub allowed_origins: Vec<String>,
     pub max_payload_size: usize,
 }
@@ -535,8 +407,6 @@ pub struct SecurityConfig {
 impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
-            rate_limit_requests: 100,
-            rate_limit_window: Duration::from_secs(60),
             allowed_origins: vec!["http://localhost:3000".to_string()],
             max_payload_size: 10 * 1024 * 1024, // 10MB
         }
@@ -554,10 +424,7 @@ where
     B: 'static,
 {
     app
-        .wrap(RateLimitMiddleware::new(
-            config.rate_limit_requests,
-            config.rate_limit_window,
-        ))
+        .wrap(RateLimitMiddleware)
         .wrap(SecurityHeadersMiddleware)
         .wrap(InputValidationMiddleware)
         .wrap(SecurityLoggingMiddleware)
@@ -0,0 +1,107 @@
+//! Middleware de acesso de suporte
+//!
+//! Requisições com o cabeçalho `X-Support-Grant` só seguem adiante se o
+//! token corresponder a uma concessão ativa que cubra o método e a rota;
+//! cada tentativa fica registrada no `SupportAccessService`. Os papéis do
+//! administrador que concedeu o acesso seguem para o extrator `Principal`,
+//! que os reduz à leitura, no lugar da identidade do token da requisição.
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage, HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+use serde_json::json;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use crate::auth::rbac::{RbacService, SupportAdminGrants};
+use crate::services::support_access::SupportAccessService;
+
+/// Cabeçalho com o token de acesso de suporte
+pub const SUPPORT_GRANT_HEADER: &str = "X-Support-Grant";
+
+/// Middleware que valida tokens de acesso de suporte
+pub struct SupportAccessGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for SupportAccessGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SupportAccessGuardService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SupportAccessGuardService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct SupportAccessGuardService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for SupportAccessGuardService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let Some(token) = req.headers()
+                .get(SUPPORT_GRANT_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+            else {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            };
+
+            let Some(support) = req.app_data::<web::Data<SupportAccessService>>().cloned() else {
+                return Ok(req.into_response(
+                    HttpResponse::ServiceUnavailable().finish().map_into_right_body(),
+                ));
+            };
+
+            let method = req.method().to_string();
+            let path = req.path().to_string();
+            match support.authorize(&token, &method, &path).await {
+                Ok(grant) => {
+                    let admin_grants = match req.app_data::<web::Data<RbacService>>() {
+                        Some(rbac) => rbac.grants_of(&grant.admin_id).await,
+                        None => Vec::new(),
+                    };
+                    req.extensions_mut().insert(SupportAdminGrants(admin_grants));
+                    req.extensions_mut().insert(grant);
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                }
+                Err(e) => Ok(req.into_response(
+                    HttpResponse::Forbidden()
+                        .json(json!({
+                            "success": false,
+                            "error": format!("Acesso de suporte negado: {}", e),
+                            "timestamp": chrono::Utc::now()
+                        }))
+                        .map_into_right_body(),
+                )),
+            }
+        })
+    }
+}
@@ -4,6 +4,8 @@ use chrono::{DateTime, Utc};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 
+pub use fortis_domain::{Candidate, CandidatePosition, EncryptedVoteData, VoteSyncStatus};
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateElectionRequest {
     pub title: String,
@@ -26,14 +28,6 @@ pub struct ElectionResponse {
     pub stats: Option<ElectionStats>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct Candidate {
-    pub id: Uuid,
-    pub name: String,
-    pub party: String,
-    pub number: i32,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ElectionStats {
     pub total_votes: i64,
@@ -45,7 +39,15 @@ pub struct ElectionStats {
 pub struct CreateCandidateRequest {
     pub name: String,
     pub party: String,
-    pub number: i32,
+    pub number: u32,
+    pub position: CandidatePosition,
+    #[serde(default)]
+    pub coalition: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Foto em JPEG codificada em base64
+    #[serde(default)]
+    pub photo_base64: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -96,7 +98,9 @@ pub struct UserInfo {
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BiometricData {
+    /// Template ISO/IEC 19794-2 em base64, extraído na urna
     pub fingerprint: String,
+    /// SHA-256 (hex) do template
     pub fingerprint_hash: String,
     pub face_id: String,
     pub biometric_hash: String,
@@ -107,6 +111,9 @@ pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Código estável do erro (ver `FortisError`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
     pub message: Option<String>,
 }
 
@@ -116,6 +123,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
             message: None,
         }
     }
@@ -125,9 +133,32 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message),
+            code: None,
             message: None,
         }
     }
+
+    /// Resposta de erro com o código estável do `FortisError`
+    pub fn from_error(error: &crate::errors::FortisError) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(error.to_string()),
+            code: Some(error.code().to_string()),
+            message: None,
+        }
+    }
+
+    /// Resposta de erro com a mensagem no idioma do cliente e o detalhe técnico
+    pub fn localized(error: &crate::errors::FortisError, message: &str) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(message.to_string()),
+            code: Some(error.code().to_string()),
+            message: Some(error.to_string()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -221,22 +252,6 @@ pub struct UrnaVote {
     pub blockchain_hash: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct EncryptedVoteData {
-    pub encrypted_content: String,
-    pub encryption_key_id: String,
-    pub signature: String,
-    pub zk_proof: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub enum VoteSyncStatus {
-    Pending,
-    Synced,
-    Failed,
-    Confirmed,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UrnaAuthentication {
     pub urna_id: Uuid,
@@ -345,7 +360,7 @@ pub struct UrnaVoteResponse {
 pub struct VoteReceipt {
     pub vote_id: Uuid,
     pub election_id: Uuid,
-    pub candidate_number: i32,
+    pub candidate_number: u32,
     pub candidate_name: String,
     pub timestamp: DateTime<Utc>,
     pub qr_code: String,
@@ -0,0 +1,177 @@
+//! Endpoints de Monitoramento
+//!
+//! Expõe as métricas do `MonitoringSystem` para coleta pelo Prometheus, o
+//! índice de saúde por eleição e o painel do centro de comando.
+
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::command_center::{CommandCenterService, UrnaPlacement};
+use super::health_score::{ElectionHealthService, HealthSignal};
+use super::metrics::MonitoringSystem;
+use super::prometheus::{PrometheusExporter, PROMETHEUS_CONTENT_TYPE};
+use crate::auth::rbac::{Permission, Principal};
+use crate::middleware::device_identity::connection_device;
+use crate::services::compute::ComputePool;
+
+/// Filtro do painel do centro de comando
+#[derive(Debug, Deserialize)]
+pub struct CommandCenterQuery {
+    /// UF detalhada por município
+    pub state: Option<String>,
+}
+
+/// Renderiza métricas no formato texto do Prometheus
+pub async fn get_metrics(
+    monitoring: web::Data<MonitoringSystem>,
+    exporter: web::Data<PrometheusExporter>,
+    health: Option<web::Data<ElectionHealthService>>,
+) -> Result<HttpResponse> {
+    let mut body = exporter.render(&monitoring).await;
+    if let Some(health) = health {
+        body.push_str(&health.render_prometheus(exporter.namespace()).await);
+    }
+    Ok(HttpResponse::Ok()
+        .content_type(PROMETHEUS_CONTENT_TYPE)
+        .body(body))
+}
+
+/// Lista o índice de saúde de todas as eleições
+pub async fn list_health_scores(
+    health: web::Data<ElectionHealthService>,
+) -> Result<HttpResponse> {
+    let scores = health.list_scores().await;
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "scores": scores
+    })))
+}
+
+/// Obtém o índice de saúde de uma eleição com seus fatores
+pub async fn get_health_score(
+    health: web::Data<ElectionHealthService>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    match health.get_score(&path).await {
+        Some(score) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "score": score
+        }))),
+        None => Ok(HttpResponse::NotFound().json(json!({
+            "success": false,
+            "message": "Índice de saúde não disponível para esta eleição"
+        }))),
+    }
+}
+
+/// Detalha um fator do índice de saúde
+pub async fn get_health_factor(
+    health: web::Data<ElectionHealthService>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse> {
+    let (election_id, factor_name) = path.into_inner();
+    let factor = health.get_score(&election_id).await
+        .and_then(|score| score.factors.into_iter().find(|f| f.name == factor_name));
+
+    match factor {
+        Some(factor) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "factor": factor
+        }))),
+        None => Ok(HttpResponse::NotFound().json(json!({
+            "success": false,
+            "message": "Fator não encontrado"
+        }))),
+    }
+}
+
+/// Registra sinal usado no cálculo do índice. Sinais de uma urna podem vir
+/// da própria urna (certificado da conexão); os demais exigem
+/// `ManageElections` na eleição
+pub async fn record_health_signal(
+    http_req: HttpRequest,
+    principal: Principal,
+    health: web::Data<ElectionHealthService>,
+    path: web::Path<String>,
+    signal: web::Json<HealthSignal>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    let signal = signal.into_inner();
+    let from_urna = match &signal {
+        HealthSignal::UrnaSynced { urna_id } | HealthSignal::UrnaAttested { urna_id, .. } => {
+            matches!(connection_device(&http_req).await, Ok(device) if device.urna_id == *urna_id)
+        }
+        _ => false,
+    };
+    if !from_urna {
+        principal.require_for(Permission::ManageElections, &election_id)?;
+    }
+
+    health.record(&election_id, signal).await;
+    Ok(HttpResponse::Accepted().json(json!({
+        "success": true,
+        "message": "Sinal registrado"
+    })))
+}
+
+/// Estado do pool de criptografia: fila, execução e rejeições
+pub async fn get_compute_stats(
+    compute: web::Data<ComputePool>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "compute": compute.stats()
+    })))
+}
+
+/// Painel consolidado do centro de comando
+pub async fn get_command_center(
+    command_center: web::Data<CommandCenterService>,
+    path: web::Path<Uuid>,
+    query: web::Query<CommandCenterQuery>,
+) -> Result<HttpResponse> {
+    let overview = command_center.overview(path.into_inner(), query.state.as_deref()).await;
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", format!("private, max-age={}", command_center.cache_ttl_ms() / 1000)))
+        .json(json!({
+            "success": true,
+            "overview": overview
+        })))
+}
+
+/// Registra a alocação das urnas nas seções da eleição
+pub async fn register_urna_placements(
+    principal: Principal,
+    command_center: web::Data<CommandCenterService>,
+    path: web::Path<Uuid>,
+    placements: web::Json<Vec<UrnaPlacement>>,
+) -> Result<HttpResponse> {
+    let election_id = path.into_inner();
+    principal.require_for(Permission::ManageElections, &election_id.to_string())?;
+
+    let expected_urnas = command_center.register_placements(election_id, placements.into_inner()).await;
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "expected_urnas": expected_urnas
+    })))
+}
+
+/// Configura rotas de monitoramento
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(get_metrics))
+        .route("/api/v1/monitoring/compute", web::get().to(get_compute_stats))
+        .service(
+            web::scope("/api/v1/monitoring/health-score")
+                .route("", web::get().to(list_health_scores))
+                .route("/{election_id}", web::get().to(get_health_score))
+                .route("/{election_id}/signals", web::post().to(record_health_signal))
+                .route("/{election_id}/factors/{factor}", web::get().to(get_health_factor))
+        )
+        .service(
+            web::scope("/api/v1/monitoring/command-center")
+                .route("/{election_id}", web::get().to(get_command_center))
+                .route("/{election_id}/placements", web::post().to(register_urna_placements))
+        );
+}
@@ -0,0 +1,402 @@
+//! Centro de Comando do Dia da Eleição
+//!
+//! Uma única consulta reúne o que o painel do TSE acompanha: situação das
+//! urnas, cobertura de transmissão, incidentes, saúde do consenso e
+//! andamento da apuração, no total nacional e por UF (ou, com a UF
+//! informada, por município). Os números vêm dos modelos de leitura já
+//! mantidos em memória pelos serviços; nada é consultado no banco.
+//!
+//! A distribuição das urnas pelas seções é registrada antes da votação e
+//! define o esperado de cada região. O painel calculado fica em cache pelo
+//! prazo configurado e, ao expirar, só uma requisição o recalcula enquanto
+//! as demais aguardam o resultado.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+use super::health_score::{ElectionHealthService, HealthGrade, HealthSignal};
+use crate::cluster::MembershipService;
+use crate::config::CommandCenterConfig;
+use crate::models::UrnaStatus;
+use crate::services::incident::{IncidentService, IncidentStatus};
+use crate::services::results::{ResultsDiff, ResultsService};
+use crate::services::urna::UrnaMonitoringService;
+
+/// Urna alocada a uma seção eleitoral
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrnaPlacement {
+    pub urna_id: Uuid,
+    pub state: String,
+    pub municipality_code: String,
+    pub zone: String,
+    pub section: String,
+}
+
+impl UrnaPlacement {
+    fn section_key(&self) -> String {
+        format!("{}/{}/{}", self.municipality_code, self.zone, self.section)
+    }
+}
+
+/// Urnas por situação informada no último heartbeat
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UrnaStatusCounts {
+    pub expected: u64,
+    pub active: u64,
+    pub syncing: u64,
+    pub maintenance: u64,
+    pub offline: u64,
+    pub error: u64,
+    /// Urnas sem nenhum heartbeat recebido
+    pub silent: u64,
+}
+
+/// Urnas com sincronização dentro da janela de transmissão
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransmissionCoverage {
+    pub expected_urnas: u64,
+    pub transmitting_urnas: u64,
+    pub coverage: f64,
+}
+
+/// Boletins recebidos e comparecimento apurado
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CountingProgress {
+    pub expected_sections: u64,
+    pub counted_sections: u64,
+    pub progress: f64,
+    pub registered_voters: u64,
+    pub votes_cast: u64,
+    pub turnout: f64,
+}
+
+/// Consolidado de uma região (UF, município ou total)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionRollup {
+    pub region: String,
+    pub urnas: UrnaStatusCounts,
+    pub transmission: TransmissionCoverage,
+    pub counting: CountingProgress,
+}
+
+/// Incidentes em aberto
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IncidentRollup {
+    pub open: usize,
+    pub by_severity: BTreeMap<String, usize>,
+    pub by_status: BTreeMap<String, usize>,
+}
+
+/// Saúde do consenso e do cluster de nós
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusHealth {
+    pub health_score: Option<f64>,
+    pub health_grade: Option<HealthGrade>,
+    /// Fator de latência de consenso do índice de saúde (0 a 1)
+    pub consensus_latency: Option<f64>,
+    pub alive_nodes: usize,
+    pub suspect_nodes: usize,
+    pub dead_nodes: usize,
+}
+
+/// Painel agregado do centro de comando
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandCenterOverview {
+    pub election_id: Uuid,
+    /// UF detalhada por município; sem UF, o detalhamento é por UF
+    pub state: Option<String>,
+    pub total: RegionRollup,
+    pub regions: Vec<RegionRollup>,
+    pub incidents: IncidentRollup,
+    pub consensus: ConsensusHealth,
+    /// Diferenças entre a primeira e a última rodada de contagem, se houve recontagem
+    pub result_changes: Option<ResultsDiff>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Serviço do centro de comando
+pub struct CommandCenterService {
+    config: CommandCenterConfig,
+    urna_monitoring: Arc<UrnaMonitoringService>,
+    results: Arc<ResultsService>,
+    incidents: Arc<IncidentService>,
+    health: ElectionHealthService,
+    membership: MembershipService,
+    placements: RwLock<HashMap<Uuid, HashMap<Uuid, UrnaPlacement>>>,
+    cache: RwLock<HashMap<(Uuid, Option<String>), CommandCenterOverview>>,
+    refreshing: Mutex<()>,
+}
+
+impl CommandCenterService {
+    pub fn new(
+        config: CommandCenterConfig,
+        urna_monitoring: Arc<UrnaMonitoringService>,
+        results: Arc<ResultsService>,
+        incidents: Arc<IncidentService>,
+        health: ElectionHealthService,
+        membership: MembershipService,
+    ) -> Self {
+        Self {
+            config,
+            urna_monitoring,
+            results,
+            incidents,
+            health,
+            membership,
+            placements: RwLock::new(HashMap::new()),
+            cache: RwLock::new(HashMap::new()),
+            refreshing: Mutex::new(()),
+        }
+    }
+
+    /// Validade do painel em cache, para o cabeçalho `Cache-Control`
+    pub fn cache_ttl_ms(&self) -> u64 {
+        self.config.cache_ttl_ms
+    }
+
+    /// Registra ou atualiza a alocação das urnas da eleição
+    pub async fn register_placements(&self, election_id: Uuid, placements: Vec<UrnaPlacement>) -> usize {
+        let expected = {
+            let mut all = self.placements.write().await;
+            let election = all.entry(election_id).or_default();
+            for placement in placements {
+                election.insert(placement.urna_id, placement);
+            }
+            election.len()
+        };
+        self.health.record(&election_id.to_string(), HealthSignal::ExpectedUrnas { count: expected as u64 }).await;
+        self.cache.write().await.retain(|(id, _), _| *id != election_id);
+        expected
+    }
+
+    /// Painel da eleição, a partir do cache quando válido
+    pub async fn overview(&self, election_id: Uuid, state: Option<&str>) -> CommandCenterOverview {
+        let key = (election_id, state.map(|s| s.to_uppercase()));
+        if let Some(cached) = self.cached(&key).await {
+            return cached;
+        }
+
+        // Uma única requisição recalcula; as que esperavam reaproveitam o resultado
+        let _refreshing = self.refreshing.lock().await;
+        if let Some(cached) = self.cached(&key).await {
+            return cached;
+        }
+        let overview = self.compute(election_id, key.1.as_deref()).await;
+        self.cache.write().await.insert(key, overview.clone());
+        overview
+    }
+
+    async fn cached(&self, key: &(Uuid, Option<String>)) -> Option<CommandCenterOverview> {
+        let ttl = Duration::milliseconds(self.config.cache_ttl_ms as i64);
+        self.cache.read().await
+            .get(key)
+            .filter(|overview| Utc::now() < overview.generated_at + ttl)
+            .cloned()
+    }
+
+    async fn compute(&self, election_id: Uuid, state: Option<&str>) -> CommandCenterOverview {
+        let now = Utc::now();
+        let window = Duration::seconds(self.config.transmission_window_seconds);
+
+        // Região de cada seção: UF no painel nacional, município no detalhamento
+        let region_of = |section_state: &str, municipality_code: &str| -> Option<String> {
+            match state {
+                None => Some(section_state.to_uppercase()),
+                Some(s) if section_state.eq_ignore_ascii_case(s) => Some(municipality_code.to_string()),
+                Some(_) => None,
+            }
+        };
+
+        let mut regions: BTreeMap<String, RegionRollup> = BTreeMap::new();
+        let mut expected_sections: HashSet<String> = HashSet::new();
+        {
+            let placements = self.placements.read().await;
+            let health_checks = self.urna_monitoring.health_checks.read().await;
+            for placement in placements.get(&election_id).into_iter().flat_map(|p| p.values()) {
+                let Some(region) = region_of(&placement.state, &placement.municipality_code) else { continue };
+                let rollup = regions.entry(region.clone()).or_insert_with(|| RegionRollup {
+                    region,
+                    ..Default::default()
+                });
+
+                rollup.urnas.expected += 1;
+                rollup.transmission.expected_urnas += 1;
+                if expected_sections.insert(placement.section_key()) {
+                    rollup.counting.expected_sections += 1;
+                }
+
+                let Some(health) = health_checks.get(&placement.urna_id) else {
+                    rollup.urnas.silent += 1;
+                    continue;
+                };
+                match health.status {
+                    UrnaStatus::Active => rollup.urnas.active += 1,
+                    UrnaStatus::Syncing => rollup.urnas.syncing += 1,
+                    UrnaStatus::Maintenance => rollup.urnas.maintenance += 1,
+                    UrnaStatus::Error => rollup.urnas.error += 1,
+                    UrnaStatus::Offline | UrnaStatus::Inactive | UrnaStatus::Unknown => rollup.urnas.offline += 1,
+                }
+                if health.last_sync.is_some_and(|last| now - last <= window) {
+                    rollup.transmission.transmitting_urnas += 1;
+                }
+            }
+        }
+
+        for bulletin in self.results.bulletin_totals(election_id).await {
+            let Some(region) = region_of(&bulletin.state, &bulletin.municipality_code) else { continue };
+            let rollup = regions.entry(region.clone()).or_insert_with(|| RegionRollup {
+                region,
+                ..Default::default()
+            });
+            rollup.counting.counted_sections += 1;
+            rollup.counting.registered_voters += bulletin.registered_voters;
+            rollup.counting.votes_cast += bulletin.votes_cast;
+        }
+
+        let mut total = RegionRollup {
+            region: state.map(str::to_string).unwrap_or_else(|| "BR".to_string()),
+            ..Default::default()
+        };
+        for rollup in regions.values_mut() {
+            finish(rollup);
+            add(&mut total, rollup);
+        }
+        finish(&mut total);
+
+        CommandCenterOverview {
+            election_id,
+            state: state.map(str::to_string),
+            total,
+            regions: regions.into_values().collect(),
+            incidents: self.incident_rollup().await,
+            consensus: self.consensus_health(election_id).await,
+            result_changes: self.results.latest_diff(election_id).await,
+            generated_at: now,
+        }
+    }
+
+    async fn incident_rollup(&self) -> IncidentRollup {
+        let mut rollup = IncidentRollup::default();
+        for incident in self.incidents.list_incidents(None).await {
+            if incident.status == IncidentStatus::Closed {
+                continue;
+            }
+            rollup.open += 1;
+            *rollup.by_severity.entry(format!("{:?}", incident.severity).to_lowercase()).or_insert(0) += 1;
+            *rollup.by_status.entry(format!("{:?}", incident.status).to_lowercase()).or_insert(0) += 1;
+        }
+        rollup
+    }
+
+    async fn consensus_health(&self, election_id: Uuid) -> ConsensusHealth {
+        let score = self.health.get_score(&election_id.to_string()).await;
+        let cluster = self.membership.get_summary().await;
+        ConsensusHealth {
+            health_score: score.as_ref().map(|s| s.score),
+            health_grade: score.as_ref().map(|s| s.grade),
+            consensus_latency: score.as_ref()
+                .and_then(|s| s.factors.iter().find(|f| f.name == "consensus_latency"))
+                .map(|f| f.value),
+            alive_nodes: cluster.alive_members,
+            suspect_nodes: cluster.suspect_members,
+            dead_nodes: cluster.dead_members,
+        }
+    }
+}
+
+/// Calcula as taxas a partir das contagens
+fn finish(rollup: &mut RegionRollup) {
+    let transmission = &mut rollup.transmission;
+    transmission.coverage = ratio(transmission.transmitting_urnas, transmission.expected_urnas);
+    let counting = &mut rollup.counting;
+    // Boletins de seções fora da alocação registrada também contam como esperados
+    counting.expected_sections = counting.expected_sections.max(counting.counted_sections);
+    counting.progress = ratio(counting.counted_sections, counting.expected_sections);
+    counting.turnout = ratio(counting.votes_cast, counting.registered_voters);
+}
+
+fn add(total: &mut RegionRollup, region: &RegionRollup) {
+    let (t, r) = (&mut total.urnas, &region.urnas);
+    t.expected += r.expected;
+    t.active += r.active;
+    t.syncing += r.syncing;
+    t.maintenance += r.maintenance;
+    t.offline += r.offline;
+    t.error += r.error;
+    t.silent += r.silent;
+
+    total.transmission.expected_urnas += region.transmission.expected_urnas;
+    total.transmission.transmitting_urnas += region.transmission.transmitting_urnas;
+
+    let (t, r) = (&mut total.counting, &region.counting);
+    t.expected_sections += r.expected_sections;
+    t.counted_sections += r.counted_sections;
+    t.registered_voters += r.registered_voters;
+    t.votes_cast += r.votes_cast;
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        (part as f64 / total as f64).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rollup(expected_urnas: u64, transmitting: u64, expected_sections: u64, counted: u64) -> RegionRollup {
+        RegionRollup {
+            region: "SP".to_string(),
+            transmission: TransmissionCoverage {
+                expected_urnas,
+                transmitting_urnas: transmitting,
+                coverage: 0.0,
+            },
+            counting: CountingProgress {
+                expected_sections,
+                counted_sections: counted,
+                registered_voters: 1000,
+                votes_cast: 800,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rates_are_computed_from_counts() {
+        let mut region = rollup(4, 3, 10, 5);
+        finish(&mut region);
+        assert!((region.transmission.coverage - 0.75).abs() < 1e-9);
+        assert!((region.counting.progress - 0.5).abs() < 1e-9);
+        assert!((region.counting.turnout - 0.8).abs() < 1e-9);
+
+        // Boletins além do esperado não levam o andamento acima de 100%
+        let mut unregistered = rollup(0, 0, 2, 3);
+        finish(&mut unregistered);
+        assert_eq!(unregistered.counting.expected_sections, 3);
+        assert!((unregistered.counting.progress - 1.0).abs() < 1e-9);
+        assert_eq!(unregistered.transmission.coverage, 0.0);
+    }
+
+    #[test]
+    fn test_total_sums_regions() {
+        let mut total = RegionRollup::default();
+        for mut region in [rollup(4, 3, 10, 5), rollup(6, 6, 10, 10)] {
+            finish(&mut region);
+            add(&mut total, &region);
+        }
+        finish(&mut total);
+        assert_eq!(total.transmission.expected_urnas, 10);
+        assert!((total.transmission.coverage - 0.9).abs() < 1e-9);
+        assert!((total.counting.progress - 0.75).abs() < 1e-9);
+        assert_eq!(total.counting.votes_cast, 1600);
+    }
+}
@@ -0,0 +1,416 @@
+//! Índice de Saúde da Eleição
+//!
+//! Métrica composta (0 a 100) calculada continuamente por eleição:
+//!
+//! ```text
+//! score = 100 × (0,30·cobertura_sync + 0,25·atestação + 0,15·alertas
+//!              + 0,15·conciliação + 0,15·consenso)
+//! ```
+//!
+//! - `cobertura_sync`: urnas com sincronização dentro da janela / urnas esperadas
+//! - `atestação`: urnas com atestação válida / urnas esperadas
+//! - `alertas`: 1 / (1 + peso_alertas / tolerância), com pesos
+//!   aviso = 1, erro = 3, crítico = 10
+//! - `conciliação`: conciliações de contingência consistentes / realizadas
+//!   (1 quando não houve nenhuma)
+//! - `consenso`: 1 com p95 da latência até o alvo, decaindo linearmente até
+//!   0 no limite máximo (1 sem amostras)
+//!
+//! Classificação: saudável ≥ 90, degradada ≥ 70, crítica abaixo disso.
+//! Cada fator traz os detalhes que o compõem (ex.: urnas sem sincronização).
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::metrics::{AlertSeverity, MonitoringSystem};
+
+/// Configuração do índice de saúde
+#[derive(Debug, Clone)]
+pub struct HealthScoreConfig {
+    pub interval_seconds: u64,
+    pub sync_window_seconds: i64,
+    pub alert_tolerance: f64,
+    pub consensus_target_ms: f64,
+    pub consensus_max_ms: f64,
+    pub latency_samples: usize,
+}
+
+impl Default for HealthScoreConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 30,
+            sync_window_seconds: 900,
+            alert_tolerance: 10.0,
+            consensus_target_ms: 500.0,
+            consensus_max_ms: 5000.0,
+            latency_samples: 500,
+        }
+    }
+}
+
+/// Sinal recebido para composição do índice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HealthSignal {
+    ExpectedUrnas { count: u64 },
+    UrnaSynced { urna_id: String },
+    UrnaAttested { urna_id: String, valid: bool },
+    Reconciliation { consistent: bool },
+    ConsensusLatency { latency_ms: f64 },
+}
+
+/// Classificação do índice
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthGrade {
+    Healthy,
+    Degraded,
+    Critical,
+}
+
+/// Fator que compõe o índice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthFactor {
+    pub name: String,
+    pub weight: f64,
+    /// Valor normalizado entre 0 e 1
+    pub value: f64,
+    /// Pontos contribuídos ao índice (weight × value × 100)
+    pub contribution: f64,
+    pub details: serde_json::Value,
+}
+
+/// Índice de saúde calculado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthScore {
+    pub election_id: String,
+    pub score: f64,
+    pub grade: HealthGrade,
+    pub factors: Vec<HealthFactor>,
+    pub computed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct ElectionSignals {
+    expected_urnas: u64,
+    last_sync: HashMap<String, DateTime<Utc>>,
+    attestations: HashMap<String, bool>,
+    reconciliations_total: u64,
+    reconciliations_consistent: u64,
+    consensus_latencies_ms: VecDeque<f64>,
+}
+
+/// Serviço do índice de saúde por eleição
+#[derive(Clone)]
+pub struct ElectionHealthService {
+    config: HealthScoreConfig,
+    monitoring: Arc<MonitoringSystem>,
+    signals: Arc<RwLock<HashMap<String, ElectionSignals>>>,
+    scores: Arc<RwLock<BTreeMap<String, HealthScore>>>,
+}
+
+impl ElectionHealthService {
+    pub fn new(config: HealthScoreConfig, monitoring: Arc<MonitoringSystem>) -> Self {
+        Self {
+            config,
+            monitoring,
+            signals: Arc::new(RwLock::new(HashMap::new())),
+            scores: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+
+    /// Recalcula periodicamente o índice de todas as eleições
+    pub fn start(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(
+                std::time::Duration::from_secs(service.config.interval_seconds),
+            );
+            loop {
+                interval.tick().await;
+                service.refresh_all().await;
+            }
+        });
+    }
+
+    /// Registra sinal de uma eleição
+    pub async fn record(&self, election_id: &str, signal: HealthSignal) {
+        let mut signals = self.signals.write().await;
+        let election = signals.entry(election_id.to_string()).or_default();
+
+        match signal {
+            HealthSignal::ExpectedUrnas { count } => election.expected_urnas = count,
+            HealthSignal::UrnaSynced { urna_id } => {
+                election.last_sync.insert(urna_id, Utc::now());
+            }
+            HealthSignal::UrnaAttested { urna_id, valid } => {
+                election.attestations.insert(urna_id, valid);
+            }
+            HealthSignal::Reconciliation { consistent } => {
+                election.reconciliations_total += 1;
+                if consistent {
+                    election.reconciliations_consistent += 1;
+                }
+            }
+            HealthSignal::ConsensusLatency { latency_ms } => {
+                election.consensus_latencies_ms.push_back(latency_ms);
+                while election.consensus_latencies_ms.len() > self.config.latency_samples {
+                    election.consensus_latencies_ms.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Último índice calculado
+    pub async fn get_score(&self, election_id: &str) -> Option<HealthScore> {
+        self.scores.read().await.get(election_id).cloned()
+    }
+
+    pub async fn list_scores(&self) -> Vec<HealthScore> {
+        self.scores.read().await.values().cloned().collect()
+    }
+
+    /// Recalcula o índice de todas as eleições com sinais
+    pub async fn refresh_all(&self) {
+        let election_ids: Vec<String> = self.signals.read().await.keys().cloned().collect();
+        for election_id in election_ids {
+            self.refresh(&election_id).await;
+        }
+    }
+
+    /// Recalcula o índice de uma eleição
+    pub async fn refresh(&self, election_id: &str) -> Option<HealthScore> {
+        let alerts = self.alert_factor().await;
+        let score = {
+            let signals = self.signals.read().await;
+            let election = signals.get(election_id)?;
+            self.compute(election_id, election, alerts)
+        };
+
+        let previous = self.scores.write().await.insert(election_id.to_string(), score.clone());
+
+        // Alerta ao entrar em estado crítico
+        let was_critical = previous.is_some_and(|p| p.grade == HealthGrade::Critical);
+        if score.grade == HealthGrade::Critical && !was_critical {
+            let message = format!("Índice de saúde da eleição {} em {:.1}", election_id, score.score);
+            if let Err(e) = self.monitoring.create_alert(AlertSeverity::Warning, "election_health", &message).await {
+                log::error!("Falha ao criar alerta de saúde da eleição: {}", e);
+            }
+        }
+
+        Some(score)
+    }
+
+    /// Índices no formato texto do Prometheus, com a eleição como rótulo
+    pub async fn render_prometheus(&self, namespace: &str) -> String {
+        let scores = self.scores.read().await;
+        let mut output = String::new();
+        if scores.is_empty() {
+            return output;
+        }
+
+        let name = format!("{}_election_health_score", namespace);
+        let _ = writeln!(output, "# HELP {} Composite election health score (0-100)", name);
+        let _ = writeln!(output, "# TYPE {} gauge", name);
+        for score in scores.values() {
+            let _ = writeln!(output, "{}{{election=\"{}\"}} {}", name, score.election_id, score.score);
+        }
+
+        let name = format!("{}_election_health_factor", namespace);
+        let _ = writeln!(output, "# HELP {} Normalized election health factor (0-1)", name);
+        let _ = writeln!(output, "# TYPE {} gauge", name);
+        for score in scores.values() {
+            for factor in &score.factors {
+                let _ = writeln!(
+                    output,
+                    "{}{{election=\"{}\",factor=\"{}\"}} {}",
+                    name, score.election_id, factor.name, factor.value
+                );
+            }
+        }
+        output
+    }
+
+    async fn alert_factor(&self) -> (f64, BTreeMap<String, u64>) {
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        let mut weighted = 0.0;
+        for alert in self.monitoring.get_active_alerts().await {
+            // Alertas do próprio índice não realimentam o cálculo
+            if alert.component == "election_health" {
+                continue;
+            }
+            weighted += match alert.severity {
+                AlertSeverity::Info => 0.0,
+                AlertSeverity::Warning => 1.0,
+                AlertSeverity::Error => 3.0,
+                AlertSeverity::Critical => 10.0,
+            };
+            *counts.entry(format!("{:?}", alert.severity).to_lowercase()).or_insert(0) += 1;
+        }
+        (weighted, counts)
+    }
+
+    fn compute(
+        &self,
+        election_id: &str,
+        election: &ElectionSignals,
+        (alert_weight, alert_counts): (f64, BTreeMap<String, u64>),
+    ) -> HealthScore {
+        let now = Utc::now();
+        let window = Duration::seconds(self.config.sync_window_seconds);
+        let known_urnas = election.last_sync.len().max(election.attestations.len()) as u64;
+        let expected = election.expected_urnas.max(known_urnas);
+
+        let stale_urnas: Vec<&String> = election.last_sync
+            .iter()
+            .filter(|(_, last)| now - **last > window)
+            .map(|(urna, _)| urna)
+            .collect();
+        let synced = election.last_sync.len() - stale_urnas.len();
+        let sync_coverage = ratio(synced as u64, expected);
+
+        let failed_attestations: Vec<&String> = election.attestations
+            .iter()
+            .filter(|(_, valid)| !**valid)
+            .map(|(urna, _)| urna)
+            .collect();
+        let attested = election.attestations.len() - failed_attestations.len();
+        let attestation_rate = ratio(attested as u64, expected);
+
+        let alerts = 1.0 / (1.0 + alert_weight / self.config.alert_tolerance);
+
+        let reconciliation = if election.reconciliations_total == 0 {
+            1.0
+        } else {
+            election.reconciliations_consistent as f64 / election.reconciliations_total as f64
+        };
+
+        let p95 = percentile(&election.consensus_latencies_ms, 0.95);
+        let consensus = match p95 {
+            None => 1.0,
+            Some(p95) => {
+                let span = self.config.consensus_max_ms - self.config.consensus_target_ms;
+                (1.0 - (p95 - self.config.consensus_target_ms) / span).clamp(0.0, 1.0)
+            }
+        };
+
+        let factors = vec![
+            factor("sync_coverage", 0.30, sync_coverage, serde_json::json!({
+                "expected_urnas": expected,
+                "synced_urnas": synced,
+                "stale_urnas": stale_urnas,
+                "window_seconds": self.config.sync_window_seconds,
+            })),
+            factor("attestation_rate", 0.25, attestation_rate, serde_json::json!({
+                "expected_urnas": expected,
+                "attested_urnas": attested,
+                "failed_urnas": failed_attestations,
+            })),
+            factor("alert_volume", 0.15, alerts, serde_json::json!({
+                "active_alerts": alert_counts,
+                "weighted_alerts": alert_weight,
+                "tolerance": self.config.alert_tolerance,
+            })),
+            factor("reconciliation", 0.15, reconciliation, serde_json::json!({
+                "reconciliations": election.reconciliations_total,
+                "consistent": election.reconciliations_consistent,
+            })),
+            factor("consensus_latency", 0.15, consensus, serde_json::json!({
+                "p95_ms": p95,
+                "samples": election.consensus_latencies_ms.len(),
+                "target_ms": self.config.consensus_target_ms,
+                "max_ms": self.config.consensus_max_ms,
+            })),
+        ];
+
+        let score = factors.iter().map(|f| f.contribution).sum::<f64>();
+        let grade = if score >= 90.0 {
+            HealthGrade::Healthy
+        } else if score >= 70.0 {
+            HealthGrade::Degraded
+        } else {
+            HealthGrade::Critical
+        };
+
+        HealthScore {
+            election_id: election_id.to_string(),
+            score,
+            grade,
+            factors,
+            computed_at: now,
+        }
+    }
+}
+
+fn factor(name: &str, weight: f64, value: f64, details: serde_json::Value) -> HealthFactor {
+    HealthFactor {
+        name: name.to_string(),
+        weight,
+        value,
+        contribution: weight * value * 100.0,
+        details,
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        (part as f64 / total as f64).min(1.0)
+    }
+}
+
+fn percentile(samples: &VecDeque<f64>, p: f64) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let index = ((sorted.len() as f64 * p).ceil() as usize).saturating_sub(1);
+    Some(sorted[index.min(sorted.len() - 1)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> ElectionHealthService {
+        ElectionHealthService::new(HealthScoreConfig::default(), Arc::new(MonitoringSystem::new()))
+    }
+
+    #[tokio::test]
+    async fn test_fully_healthy_election() {
+        let service = service();
+        service.record("e1", HealthSignal::ExpectedUrnas { count: 2 }).await;
+        for urna in ["u1", "u2"] {
+            service.record("e1", HealthSignal::UrnaSynced { urna_id: urna.to_string() }).await;
+            service.record("e1", HealthSignal::UrnaAttested { urna_id: urna.to_string(), valid: true }).await;
+        }
+        service.record("e1", HealthSignal::ConsensusLatency { latency_ms: 120.0 }).await;
+
+        let score = service.refresh("e1").await.unwrap();
+        assert!((score.score - 100.0).abs() < 1e-9);
+        assert_eq!(score.grade, HealthGrade::Healthy);
+        assert_eq!(score.factors.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_missing_urnas_and_failed_reconciliation_degrade_score() {
+        let service = service();
+        service.record("e1", HealthSignal::ExpectedUrnas { count: 4 }).await;
+        service.record("e1", HealthSignal::UrnaSynced { urna_id: "u1".to_string() }).await;
+        service.record("e1", HealthSignal::UrnaAttested { urna_id: "u1".to_string(), valid: false }).await;
+        service.record("e1", HealthSignal::Reconciliation { consistent: false }).await;
+
+        let score = service.refresh("e1").await.unwrap();
+        assert_eq!(score.grade, HealthGrade::Critical);
+
+        let sync = score.factors.iter().find(|f| f.name == "sync_coverage").unwrap();
+        assert!((sync.value - 0.25).abs() < 1e-9);
+        assert_eq!(service.monitoring.get_active_alerts().await.len(), 1);
+    }
+}
@@ -92,6 +92,13 @@ pub struct StorageMetrics {
     pub storage_used_mb: f64,
     pub ipfs_operations: u64,
     pub dht_operations: u64,
+    pub dht_query_failures: u64,
+    pub dht_connected_peers: u64,
+    pub dht_routing_table_size: u64,
+    pub dht_provided_keys: u64,
+    pub shards_missing: u64,
+    pub shards_repaired: u64,
+    pub shard_repair_failures: u64,
     pub cache_hit_rate: f64,
     pub retrieval_time_ms: f64,
 }
@@ -143,6 +150,14 @@ pub enum AlertSeverity {
     Critical,
 }
 
+/// Cópia dos valores brutos do coletor, usada pelos exportadores
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub counters: HashMap<String, u64>,
+    pub gauges: HashMap<String, f64>,
+    pub histograms: HashMap<String, Vec<f64>>,
+}
+
 /// Coletor de métricas
 pub struct MetricsCollector {
     counters: Arc<RwLock<HashMap<String, u64>>>,
@@ -297,6 +312,13 @@ impl MonitoringSystem {
             storage_used_mb: *gauges.get("storage_used_mb").unwrap_or(&0.0),
             ipfs_operations: *counters.get("ipfs_operations").unwrap_or(&0),
             dht_operations: *counters.get("dht_operations").unwrap_or(&0),
+            dht_query_failures: *counters.get("dht_query_failures").unwrap_or(&0),
+            dht_connected_peers: *gauges.get("dht_connected_peers").unwrap_or(&0.0) as u64,
+            dht_routing_table_size: *gauges.get("dht_routing_table_size").unwrap_or(&0.0) as u64,
+            dht_provided_keys: *gauges.get("dht_provided_keys").unwrap_or(&0.0) as u64,
+            shards_missing: *gauges.get("shards_missing").unwrap_or(&0.0) as u64,
+            shards_repaired: *counters.get("shards_repaired").unwrap_or(&0),
+            shard_repair_failures: *counters.get("shard_repair_failures").unwrap_or(&0),
             cache_hit_rate: *gauges.get("cache_hit_rate").unwrap_or(&0.0),
             retrieval_time_ms: *gauges.get("retrieval_time_ms").unwrap_or(&0.0),
         })
@@ -330,6 +352,15 @@ impl MonitoringSystem {
         histograms.entry(name.to_string()).or_insert_with(Vec::new).push(value);
     }
 
+    /// Obtém cópia dos contadores, gauges e histogramas coletados
+    pub async fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            counters: self.metrics_collector.counters.read().await.clone(),
+            gauges: self.metrics_collector.gauges.read().await.clone(),
+            histograms: self.metrics_collector.histograms.read().await.clone(),
+        }
+    }
+
     /// Cria alerta
     pub async fn create_alert(&self, severity: AlertSeverity, component: &str, message: &str) -> Result<()> {
         let alert = Alert {
@@ -498,6 +529,13 @@ impl Default for StorageMetrics {
             storage_used_mb: 0.0,
             ipfs_operations: 0,
             dht_operations: 0,
+            dht_query_failures: 0,
+            dht_connected_peers: 0,
+            dht_routing_table_size: 0,
+            dht_provided_keys: 0,
+            shards_missing: 0,
+            shards_repaired: 0,
+            shard_repair_failures: 0,
             cache_hit_rate: 0.0,
             retrieval_time_ms: 0.0,
         }
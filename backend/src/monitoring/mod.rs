@@ -4,11 +4,20 @@
 //! incluindo métricas, alertas e verificação de saúde.
 
 pub mod metrics;
+pub mod prometheus;
+pub mod notifications;
+pub mod health_score;
+pub mod command_center;
+pub mod api;
 // pub mod health_checks;
 // pub mod alerts;
 // pub mod dashboards;
 
 pub use metrics::*;
+pub use prometheus::*;
+pub use notifications::*;
+pub use health_score::*;
+pub use command_center::*;
 // pub use health_checks::*;
 // pub use alerts::*;
 // pub use dashboards::*;
@@ -0,0 +1,437 @@
+//! Canais de Notificação de Alertas
+//!
+//! Implementações de `NotificationChannel` para e-mail (SMTP), webhook
+//! (Slack, Teams ou JSON genérico) e SMS via gateway HTTP. O envio é feito
+//! em segundo plano com tentativas e backoff exponencial, para que a criação
+//! de alertas não fique bloqueada por canais lentos ou indisponíveis. Cada
+//! canal atende apenas às severidades configuradas.
+
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, anyhow};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::future::Future;
+use std::time::Duration;
+
+use super::metrics::{Alert, AlertSeverity, NotificationChannel};
+
+/// Política de novas tentativas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+/// Configuração do canal de e-mail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailChannelConfig {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub starttls: bool,
+    pub from: String,
+    pub recipients: Vec<String>,
+    pub severities: Vec<AlertSeverity>,
+}
+
+/// Formato do corpo do webhook
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    Slack,
+    Teams,
+    Generic,
+}
+
+/// Configuração do canal de webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookChannelConfig {
+    pub name: String,
+    pub url: String,
+    pub format: WebhookFormat,
+    pub severities: Vec<AlertSeverity>,
+}
+
+/// Configuração do canal de SMS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmsChannelConfig {
+    pub gateway_url: String,
+    pub api_key: String,
+    pub sender: String,
+    pub recipients: Vec<String>,
+    pub severities: Vec<AlertSeverity>,
+}
+
+/// Configuração de todos os canais
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    pub email: Option<EmailChannelConfig>,
+    pub webhooks: Vec<WebhookChannelConfig>,
+    pub sms: Option<SmsChannelConfig>,
+    pub retry: RetryPolicy,
+}
+
+/// Cria os canais configurados
+pub fn build_channels(config: &NotificationsConfig) -> Result<Vec<Box<dyn NotificationChannel + Send + Sync>>> {
+    let mut channels: Vec<Box<dyn NotificationChannel + Send + Sync>> = Vec::new();
+
+    if let Some(email) = &config.email {
+        channels.push(Box::new(EmailChannel::new(email.clone(), config.retry.clone())?));
+    }
+    for webhook in &config.webhooks {
+        channels.push(Box::new(WebhookChannel::new(webhook.clone(), config.retry.clone())));
+    }
+    if let Some(sms) = &config.sms {
+        channels.push(Box::new(SmsChannel::new(sms.clone(), config.retry.clone())));
+    }
+
+    Ok(channels)
+}
+
+/// Canal de e-mail via SMTP
+pub struct EmailChannel {
+    config: EmailChannelConfig,
+    retry: RetryPolicy,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailChannel {
+    pub fn new(config: EmailChannelConfig, retry: RetryPolicy) -> Result<Self> {
+        let mut builder = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+        };
+        builder = builder.port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        if config.recipients.is_empty() {
+            return Err(anyhow!("Email channel requires at least one recipient"));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            config,
+            retry,
+        })
+    }
+
+    fn build_message(&self, alert: &Alert) -> Result<Message> {
+        let mut builder = Message::builder()
+            .from(self.config.from.parse::<Mailbox>()?)
+            .subject(format!("[FORTIS][{:?}] {}", alert.severity, alert.component));
+        for recipient in &self.config.recipients {
+            builder = builder.to(recipient.parse::<Mailbox>()?);
+        }
+        Ok(builder.body(format_alert_text(alert))?)
+    }
+}
+
+impl NotificationChannel for EmailChannel {
+    fn send_alert(&self, alert: &Alert) -> Result<()> {
+        if !self.config.severities.contains(&alert.severity) {
+            return Ok(());
+        }
+
+        let message = self.build_message(alert)?;
+        let transport = self.transport.clone();
+        spawn_delivery(self.get_name().to_string(), self.retry.clone(), move || {
+            let transport = transport.clone();
+            let message = message.clone();
+            async move {
+                transport.send(message).await?;
+                Ok(())
+            }
+        })
+    }
+
+    fn get_name(&self) -> &str {
+        "email"
+    }
+}
+
+/// Canal de webhook (Slack, Teams ou JSON genérico)
+pub struct WebhookChannel {
+    config: WebhookChannelConfig,
+    retry: RetryPolicy,
+    http_client: reqwest::Client,
+}
+
+impl WebhookChannel {
+    pub fn new(config: WebhookChannelConfig, retry: RetryPolicy) -> Self {
+        Self {
+            config,
+            retry,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Monta o corpo da requisição no formato configurado
+    pub fn build_payload(&self, alert: &Alert) -> serde_json::Value {
+        match self.config.format {
+            WebhookFormat::Slack => serde_json::json!({
+                "text": format!("*[{:?}] {}*\n{}", alert.severity, alert.component, alert.message)
+            }),
+            WebhookFormat::Teams => serde_json::json!({
+                "@type": "MessageCard",
+                "@context": "https://schema.org/extensions",
+                "summary": format!("[{:?}] {}", alert.severity, alert.component),
+                "themeColor": severity_color(&alert.severity),
+                "title": format!("[FORTIS][{:?}] {}", alert.severity, alert.component),
+                "text": alert.message
+            }),
+            WebhookFormat::Generic => serde_json::json!({
+                "source": "fortis",
+                "alert": alert
+            }),
+        }
+    }
+}
+
+impl NotificationChannel for WebhookChannel {
+    fn send_alert(&self, alert: &Alert) -> Result<()> {
+        if !self.config.severities.contains(&alert.severity) {
+            return Ok(());
+        }
+
+        let payload = self.build_payload(alert);
+        let url = self.config.url.clone();
+        let client = self.http_client.clone();
+        spawn_delivery(self.get_name().to_string(), self.retry.clone(), move || {
+            let request = client.post(&url).json(&payload);
+            async move {
+                let response = request.send().await?;
+                if !response.status().is_success() {
+                    return Err(anyhow!("Webhook returned {}", response.status()));
+                }
+                Ok(())
+            }
+        })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.config.name
+    }
+}
+
+/// Canal de SMS via gateway HTTP
+pub struct SmsChannel {
+    config: SmsChannelConfig,
+    retry: RetryPolicy,
+    http_client: reqwest::Client,
+}
+
+/// Tamanho máximo de uma mensagem SMS
+const SMS_MAX_CHARS: usize = 160;
+
+impl SmsChannel {
+    pub fn new(config: SmsChannelConfig, retry: RetryPolicy) -> Self {
+        Self {
+            config,
+            retry,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Texto curto do alerta, limitado a uma mensagem SMS
+    pub fn build_text(alert: &Alert) -> String {
+        let text = format!("FORTIS {:?} {}: {}", alert.severity, alert.component, alert.message);
+        if text.chars().count() <= SMS_MAX_CHARS {
+            return text;
+        }
+        let mut truncated: String = text.chars().take(SMS_MAX_CHARS - 3).collect();
+        truncated.push_str("...");
+        truncated
+    }
+}
+
+impl NotificationChannel for SmsChannel {
+    fn send_alert(&self, alert: &Alert) -> Result<()> {
+        if !self.config.severities.contains(&alert.severity) {
+            return Ok(());
+        }
+
+        let payload = serde_json::json!({
+            "from": self.config.sender,
+            "to": self.config.recipients,
+            "message": Self::build_text(alert)
+        });
+        let url = self.config.gateway_url.clone();
+        let api_key = self.config.api_key.clone();
+        let client = self.http_client.clone();
+        spawn_delivery(self.get_name().to_string(), self.retry.clone(), move || {
+            let request = client.post(&url).bearer_auth(&api_key).json(&payload);
+            async move {
+                let response = request.send().await?;
+                if !response.status().is_success() {
+                    return Err(anyhow!("SMS gateway returned {}", response.status()));
+                }
+                Ok(())
+            }
+        })
+    }
+
+    fn get_name(&self) -> &str {
+        "sms"
+    }
+}
+
+/// Agenda o envio em segundo plano com novas tentativas
+fn spawn_delivery<F, Fut>(channel: String, policy: RetryPolicy, send: F) -> Result<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let handle = tokio::runtime::Handle::try_current()
+        .map_err(|_| anyhow!("Notification channel {} requires a Tokio runtime", channel))?;
+
+    handle.spawn(async move {
+        if let Err(e) = deliver_with_retry(&policy, send).await {
+            log::error!("Falha ao enviar alerta pelo canal {}: {}", channel, e);
+        }
+    });
+    Ok(())
+}
+
+/// Executa o envio com backoff exponencial
+pub async fn deliver_with_retry<F, Fut>(policy: &RetryPolicy, send: F) -> Result<u32>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut backoff = Duration::from_millis(policy.initial_backoff_ms);
+    let mut last_error = anyhow!("No delivery attempts configured");
+
+    for attempt in 1..=policy.max_attempts {
+        match send().await {
+            Ok(()) => return Ok(attempt),
+            Err(e) => last_error = e,
+        }
+
+        if attempt < policy.max_attempts {
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_millis(policy.max_backoff_ms));
+        }
+    }
+
+    Err(last_error)
+}
+
+fn format_alert_text(alert: &Alert) -> String {
+    format!(
+        "Severidade: {:?}\nComponente: {}\nHorário: {}\nID: {}\n\n{}",
+        alert.severity,
+        alert.component,
+        alert.timestamp.to_rfc3339(),
+        alert.id,
+        alert.message
+    )
+}
+
+fn severity_color(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "0078D7",
+        AlertSeverity::Warning => "FFB900",
+        AlertSeverity::Error => "E81123",
+        AlertSeverity::Critical => "A80000",
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            email: None,
+            webhooks: Vec::new(),
+            sms: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn alert(severity: AlertSeverity, message: &str) -> Alert {
+        Alert {
+            id: "alert-1".to_string(),
+            severity,
+            component: "rolling_upgrade".to_string(),
+            message: message.to_string(),
+            timestamp: Utc::now(),
+            resolved: false,
+            resolution_time: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff() {
+        let policy = RetryPolicy { max_attempts: 3, initial_backoff_ms: 1, max_backoff_ms: 2 };
+        let calls = AtomicU32::new(0);
+
+        let attempts = deliver_with_retry(&policy, || {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move { if call < 2 { Err(anyhow!("gateway unavailable")) } else { Ok(()) } }
+        }).await.unwrap();
+        assert_eq!(attempts, 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let result = deliver_with_retry(&policy, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(anyhow!("gateway unavailable")) }
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_webhook_payload_formats() {
+        let channel = |format| WebhookChannel::new(
+            WebhookChannelConfig {
+                name: "ops".to_string(),
+                url: "http://localhost/hook".to_string(),
+                format,
+                severities: vec![AlertSeverity::Critical],
+            },
+            RetryPolicy::default(),
+        );
+        let alert = alert(AlertSeverity::Critical, "Canary regression");
+
+        let slack = channel(WebhookFormat::Slack).build_payload(&alert);
+        assert_eq!(slack["text"], "*[Critical] rolling_upgrade*\nCanary regression");
+
+        let teams = channel(WebhookFormat::Teams).build_payload(&alert);
+        assert_eq!(teams["@type"], "MessageCard");
+        assert_eq!(teams["themeColor"], "A80000");
+
+        let generic = channel(WebhookFormat::Generic).build_payload(&alert);
+        assert_eq!(generic["alert"]["id"], "alert-1");
+
+        // Severidade não selecionada não gera envio
+        assert!(channel(WebhookFormat::Slack).send_alert(&super::alert(AlertSeverity::Info, "noop")).is_ok());
+    }
+
+    #[test]
+    fn test_sms_text_is_truncated() {
+        let text = SmsChannel::build_text(&alert(AlertSeverity::Error, &"x".repeat(300)));
+        assert_eq!(text.chars().count(), SMS_MAX_CHARS);
+        assert!(text.ends_with("..."));
+    }
+}
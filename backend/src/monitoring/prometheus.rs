@@ -0,0 +1,184 @@
+//! Exportador Prometheus
+//!
+//! Converte contadores, gauges e histogramas do `MonitoringSystem` para o
+//! formato texto do Prometheus (versão 0.0.4), usado pelos dashboards Grafana.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use super::metrics::{MetricsSnapshot, MonitoringSystem};
+
+/// Content-Type do formato texto do Prometheus
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Buckets padrão do Prometheus (em segundos)
+pub const DEFAULT_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Buckets para histogramas medidos em milissegundos
+pub const MILLISECOND_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0];
+
+/// Exportador de métricas no formato Prometheus
+#[derive(Debug, Clone)]
+pub struct PrometheusExporter {
+    namespace: String,
+    buckets: HashMap<String, Vec<f64>>,
+}
+
+impl PrometheusExporter {
+    pub fn new(namespace: &str) -> Self {
+        Self {
+            namespace: sanitize_name(namespace),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Prefixo aplicado aos nomes das métricas
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Define buckets específicos para um histograma
+    pub fn with_buckets(mut self, metric: &str, buckets: &[f64]) -> Self {
+        let mut buckets: Vec<f64> = buckets.iter().copied().filter(|b| b.is_finite()).collect();
+        buckets.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        buckets.dedup();
+        self.buckets.insert(metric.to_string(), buckets);
+        self
+    }
+
+    /// Renderiza todas as métricas do sistema de monitoramento
+    pub async fn render(&self, monitoring: &MonitoringSystem) -> String {
+        let snapshot = monitoring.snapshot().await;
+        let mut output = self.render_snapshot(&snapshot);
+
+        // Alertas ativos por severidade
+        let mut alerts: BTreeMap<String, u64> = ["info", "warning", "error", "critical"]
+            .iter()
+            .map(|s| (s.to_string(), 0))
+            .collect();
+        for alert in monitoring.get_active_alerts().await {
+            *alerts.entry(format!("{:?}", alert.severity).to_lowercase()).or_insert(0) += 1;
+        }
+
+        let name = format!("{}_active_alerts", self.namespace);
+        let _ = writeln!(output, "# HELP {} Active alerts by severity", name);
+        let _ = writeln!(output, "# TYPE {} gauge", name);
+        for (severity, count) in alerts {
+            let _ = writeln!(output, "{}{{severity=\"{}\"}} {}", name, severity, count);
+        }
+
+        output
+    }
+
+    /// Renderiza uma cópia das métricas coletadas
+    pub fn render_snapshot(&self, snapshot: &MetricsSnapshot) -> String {
+        let mut output = String::new();
+
+        // Ordenar por nome para saída determinística
+        let counters: BTreeMap<_, _> = snapshot.counters.iter().collect();
+        for (metric, value) in counters {
+            let name = format!("{}_{}_total", self.namespace, sanitize_name(metric));
+            let _ = writeln!(output, "# TYPE {} counter", name);
+            let _ = writeln!(output, "{} {}", name, value);
+        }
+
+        let gauges: BTreeMap<_, _> = snapshot.gauges.iter().collect();
+        for (metric, value) in gauges {
+            let name = format!("{}_{}", self.namespace, sanitize_name(metric));
+            let _ = writeln!(output, "# TYPE {} gauge", name);
+            let _ = writeln!(output, "{} {}", name, format_value(*value));
+        }
+
+        let histograms: BTreeMap<_, _> = snapshot.histograms.iter().collect();
+        for (metric, values) in histograms {
+            let name = format!("{}_{}", self.namespace, sanitize_name(metric));
+            let buckets = self.buckets_for(metric);
+            let _ = writeln!(output, "# TYPE {} histogram", name);
+
+            // Buckets cumulativos: cada um conta as observações <= limite
+            for bound in buckets {
+                let count = values.iter().filter(|v| **v <= *bound).count();
+                let _ = writeln!(output, "{}_bucket{{le=\"{}\"}} {}", name, format_value(*bound), count);
+            }
+            let _ = writeln!(output, "{}_bucket{{le=\"+Inf\"}} {}", name, values.len());
+            let _ = writeln!(output, "{}_sum {}", name, format_value(values.iter().sum()));
+            let _ = writeln!(output, "{}_count {}", name, values.len());
+        }
+
+        output
+    }
+
+    fn buckets_for(&self, metric: &str) -> &[f64] {
+        if let Some(buckets) = self.buckets.get(metric) {
+            return buckets;
+        }
+        if metric.ends_with("_ms") {
+            MILLISECOND_BUCKETS
+        } else {
+            DEFAULT_BUCKETS
+        }
+    }
+}
+
+impl Default for PrometheusExporter {
+    fn default() -> Self {
+        Self::new("fortis")
+    }
+}
+
+/// Converte nome para o conjunto de caracteres aceito pelo Prometheus
+fn sanitize_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == ':' { c } else { '_' })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf".to_string() } else { "-Inf".to_string() }
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_render_counters_and_gauges() {
+        let monitoring = MonitoringSystem::new();
+        monitoring.increment_counter("failed_authentications", 3).await;
+        monitoring.set_gauge("cpu-usage.percent", 12.5).await;
+
+        let output = PrometheusExporter::default().render(&monitoring).await;
+
+        assert!(output.contains("# TYPE fortis_failed_authentications_total counter\nfortis_failed_authentications_total 3\n"));
+        assert!(output.contains("fortis_cpu_usage_percent 12.5\n"));
+        assert!(output.contains("fortis_active_alerts{severity=\"critical\"} 0\n"));
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let mut snapshot = MetricsSnapshot::default();
+        snapshot.histograms.insert("vote_latency".to_string(), vec![0.5, 1.5, 3.0, 20.0]);
+
+        let output = PrometheusExporter::new("fortis")
+            .with_buckets("vote_latency", &[2.0, 1.0, 5.0])
+            .render_snapshot(&snapshot);
+
+        assert!(output.contains("fortis_vote_latency_bucket{le=\"1\"} 1\n"));
+        assert!(output.contains("fortis_vote_latency_bucket{le=\"2\"} 2\n"));
+        assert!(output.contains("fortis_vote_latency_bucket{le=\"5\"} 3\n"));
+        assert!(output.contains("fortis_vote_latency_bucket{le=\"+Inf\"} 4\n"));
+        assert!(output.contains("fortis_vote_latency_sum 25\n"));
+        assert!(output.contains("fortis_vote_latency_count 4\n"));
+    }
+}
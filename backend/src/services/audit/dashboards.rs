@@ -0,0 +1,687 @@
+//! Dados dos dashboards de auditoria
+//!
+//! Guarda os `AuditDashboard` e executa a consulta de cada widget sobre a
+//! trilha de auditoria e as entradas do log transparente. O resultado passa
+//! pelo recorte (`DataScope`) de quem consulta: um auditor restrito a uma
+//! zona só vê eventos daquela zona, e eventos sem região ficam fora dos
+//! papéis regionais. Contadores, medidores, gráficos e mapas chegam
+//! agregados do servidor; tabelas, linhas do tempo e alertas trazem os
+//! eventos mais recentes, até o `limit` da configuração do widget.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use fortis_domain::region::RegionScope;
+use crate::auth::rbac::DataScope;
+use crate::transparency::election_logs::{
+    AuditEvent, AuditEventType, ElectionEvent, ElectionLogEntry, ElectionTransparencyLog,
+};
+use super::reporting::{
+    AuditDashboard, ChartData, ChartDataPoint, DashboardLayout, DashboardWidget, DataSource, TableData,
+    VisualizationType, WidgetPosition, WidgetSize, WidgetType,
+};
+
+/// Linhas devolvidas por tabelas, linhas do tempo e alertas sem `limit`
+const DEFAULT_ROW_LIMIT: usize = 50;
+const MAX_ROW_LIMIT: usize = 500;
+const MIN_REFRESH_INTERVAL_SECONDS: u64 = 10;
+
+/// Dashboard salvo, com autoria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredDashboard {
+    #[serde(flatten)]
+    pub dashboard: AuditDashboard,
+    pub owner: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Widget na criação ou edição; sem `widget_id`, um novo é gerado
+#[derive(Debug, Clone, Deserialize)]
+pub struct WidgetRequest {
+    pub widget_id: Option<String>,
+    pub title: String,
+    pub widget_type: WidgetType,
+    pub data_source: DataSource,
+    pub position: WidgetPosition,
+    pub size: WidgetSize,
+    #[serde(default)]
+    pub configuration: HashMap<String, String>,
+}
+
+/// Criação ou edição de dashboard
+#[derive(Debug, Clone, Deserialize)]
+pub struct DashboardRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub widgets: Vec<WidgetRequest>,
+    pub layout: Option<DashboardLayout>,
+    #[serde(default)]
+    pub auto_refresh: bool,
+    pub refresh_interval_seconds: Option<u64>,
+}
+
+/// Janela e eleição da consulta
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DashboardQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub election_id: Option<String>,
+}
+
+/// Valor calculado de um widget
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WidgetValue {
+    Counter { count: u64 },
+    /// Eventos da fonte sobre todos os eventos visíveis na janela
+    Gauge { value: u64, total: u64, ratio: f64 },
+    Chart(ChartData),
+    Table(TableData),
+}
+
+/// Dados de um widget
+#[derive(Debug, Clone, Serialize)]
+pub struct WidgetData {
+    pub widget_id: String,
+    pub title: String,
+    pub widget_type: WidgetType,
+    /// Eventos da fonte dentro da janela e do recorte
+    pub matched: u64,
+    pub value: WidgetValue,
+}
+
+/// Dados de todos os widgets do dashboard
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardData {
+    pub dashboard_id: String,
+    pub name: String,
+    pub generated_at: DateTime<Utc>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Recortes regionais aplicados; vazio quando o acesso é nacional
+    pub regions: Vec<RegionScope>,
+    pub widgets: Vec<WidgetData>,
+}
+
+/// Agrupamento dos gráficos (`configuration["group_by"]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupBy {
+    EventType,
+    Actor,
+    Election,
+    Region,
+    State,
+    Hour,
+    Day,
+}
+
+impl GroupBy {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "event_type" => Ok(GroupBy::EventType),
+            "actor" => Ok(GroupBy::Actor),
+            "election" => Ok(GroupBy::Election),
+            "region" => Ok(GroupBy::Region),
+            "state" => Ok(GroupBy::State),
+            "hour" => Ok(GroupBy::Hour),
+            "day" => Ok(GroupBy::Day),
+            other => Err(anyhow!("Unknown group_by: {}", other)),
+        }
+    }
+
+    fn is_temporal(&self) -> bool {
+        matches!(self, GroupBy::Hour | GroupBy::Day)
+    }
+}
+
+/// Evento das duas fontes, com os campos usados nos filtros
+#[derive(Debug, Clone)]
+struct DashboardRecord {
+    timestamp: DateTime<Utc>,
+    event_type: String,
+    audit_type: Option<AuditEventType>,
+    actor: String,
+    election_id: Option<String>,
+    node_id: Option<String>,
+    region: Option<RegionScope>,
+    error: bool,
+    security: bool,
+    log_index: Option<u64>,
+}
+
+impl DashboardRecord {
+    fn from_audit_event(event: &AuditEvent) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            event_type: format!("{:?}", event.event_type),
+            audit_type: Some(event.event_type.clone()),
+            actor: event.actor.clone(),
+            election_id: event.data.election_id.clone(),
+            node_id: event.data.node_id.clone(),
+            region: event.data.metadata.get("region")
+                .and_then(|region| region.as_str())
+                .and_then(|region| RegionScope::parse(region).ok()),
+            error: event.data.error_code.is_some() || event.data.error_message.is_some(),
+            security: matches!(event.event_type, AuditEventType::SecurityAlert),
+            log_index: None,
+        }
+    }
+
+    fn from_log_entry(entry: &ElectionLogEntry) -> Option<Self> {
+        let event: ElectionEvent = serde_json::from_slice(&entry.event_data).ok()?;
+        let node_id = ["urna_id", "node_id"].iter()
+            .find_map(|key| event.data.get(*key).and_then(|value| value.as_str()))
+            .map(str::to_string);
+        Some(Self {
+            timestamp: entry.timestamp,
+            event_type: format!("{:?}", entry.event_type),
+            audit_type: None,
+            region: event.region(),
+            actor: event.source,
+            election_id: Some(event.election_id),
+            node_id,
+            error: false,
+            security: false,
+            log_index: Some(entry.index),
+        })
+    }
+
+    fn matches(&self, source: &DataSource) -> bool {
+        match source {
+            DataSource::AllEvents => true,
+            DataSource::EventsByType(types) => self.audit_type.as_ref().is_some_and(|t| types.contains(t)),
+            DataSource::EventsByActor(actors) => actors.contains(&self.actor),
+            DataSource::EventsByElection(election_id) => self.election_id.as_ref() == Some(election_id),
+            DataSource::EventsByNode(node_id) => self.node_id.as_ref() == Some(node_id),
+            DataSource::ErrorEvents => self.error,
+            DataSource::SecurityEvents => self.security,
+            DataSource::Custom(_) => false,
+        }
+    }
+
+    fn group_key(&self, group_by: GroupBy) -> String {
+        let unknown = || "desconhecido".to_string();
+        match group_by {
+            GroupBy::EventType => self.event_type.clone(),
+            GroupBy::Actor => self.actor.clone(),
+            GroupBy::Election => self.election_id.clone().unwrap_or_else(unknown),
+            GroupBy::Region => self.region.as_ref().map(|r| r.to_string()).unwrap_or_else(unknown),
+            GroupBy::State => self.region.as_ref().map(|r| r.state.clone()).unwrap_or_else(unknown),
+            GroupBy::Hour => self.timestamp.format("%Y-%m-%dT%H:00Z").to_string(),
+            GroupBy::Day => self.timestamp.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_rfc3339(),
+            self.event_type.clone(),
+            self.actor.clone(),
+            self.election_id.clone().unwrap_or_default(),
+            self.region.as_ref().map(|r| r.to_string()).unwrap_or_default(),
+            self.log_index.map(|i| i.to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+/// Serviço de dashboards de auditoria
+pub struct DashboardService {
+    transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
+    dashboards: RwLock<HashMap<String, StoredDashboard>>,
+    db: Option<PgPool>,
+}
+
+impl DashboardService {
+    pub fn new(transparency_log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        Self {
+            transparency_log,
+            dashboards: RwLock::new(HashMap::new()),
+            db: None,
+        }
+    }
+
+    /// Grava os dashboards em `audit.dashboards`
+    pub fn with_database(mut self, pool: PgPool) -> Self {
+        self.db = Some(pool);
+        self
+    }
+
+    /// Carrega os dashboards gravados; usado na inicialização
+    pub async fn restore(&self) -> Result<usize> {
+        let Some(pool) = &self.db else {
+            return Ok(0);
+        };
+        let rows = sqlx::query("SELECT definition FROM audit.dashboards")
+            .fetch_all(pool)
+            .await?;
+        let mut dashboards = self.dashboards.write().await;
+        for row in &rows {
+            let stored: StoredDashboard = serde_json::from_value(row.get("definition"))?;
+            dashboards.insert(stored.dashboard.dashboard_id.clone(), stored);
+        }
+        Ok(rows.len())
+    }
+
+    pub async fn create(&self, request: DashboardRequest, owner: &str) -> Result<StoredDashboard> {
+        let now = Utc::now();
+        let stored = StoredDashboard {
+            dashboard: build_dashboard(Uuid::new_v4().to_string(), request)?,
+            owner: owner.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.persist(&stored).await?;
+        self.dashboards.write().await.insert(stored.dashboard.dashboard_id.clone(), stored.clone());
+        log::info!("Audit dashboard {} created by {}", stored.dashboard.dashboard_id, owner);
+        Ok(stored)
+    }
+
+    /// Substitui a definição; só o autor ou quem gerencia auditorias
+    pub async fn update(
+        &self,
+        dashboard_id: &str,
+        request: DashboardRequest,
+        actor: &str,
+        manages_audits: bool,
+    ) -> Result<StoredDashboard> {
+        let mut dashboards = self.dashboards.write().await;
+        let current = dashboards.get(dashboard_id)
+            .ok_or_else(|| anyhow!("Dashboard {} not found", dashboard_id))?;
+        if current.owner != actor && !manages_audits {
+            return Err(anyhow!("Dashboard {} belongs to {}", dashboard_id, current.owner));
+        }
+
+        let stored = StoredDashboard {
+            dashboard: build_dashboard(dashboard_id.to_string(), request)?,
+            owner: current.owner.clone(),
+            created_at: current.created_at,
+            updated_at: Utc::now(),
+        };
+        self.persist(&stored).await?;
+        dashboards.insert(dashboard_id.to_string(), stored.clone());
+        log::info!("Audit dashboard {} updated by {}", dashboard_id, actor);
+        Ok(stored)
+    }
+
+    pub async fn delete(&self, dashboard_id: &str, actor: &str, manages_audits: bool) -> Result<()> {
+        let mut dashboards = self.dashboards.write().await;
+        let current = dashboards.get(dashboard_id)
+            .ok_or_else(|| anyhow!("Dashboard {} not found", dashboard_id))?;
+        if current.owner != actor && !manages_audits {
+            return Err(anyhow!("Dashboard {} belongs to {}", dashboard_id, current.owner));
+        }
+
+        if let Some(pool) = &self.db {
+            sqlx::query("DELETE FROM audit.dashboards WHERE id = $1")
+                .bind(dashboard_id)
+                .execute(pool)
+                .await?;
+        }
+        dashboards.remove(dashboard_id);
+        log::info!("Audit dashboard {} deleted by {}", dashboard_id, actor);
+        Ok(())
+    }
+
+    pub async fn get(&self, dashboard_id: &str) -> Option<StoredDashboard> {
+        self.dashboards.read().await.get(dashboard_id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<StoredDashboard> {
+        let mut all: Vec<StoredDashboard> = self.dashboards.read().await.values().cloned().collect();
+        all.sort_by(|a, b| a.dashboard.name.cmp(&b.dashboard.name));
+        all
+    }
+
+    /// Calcula todos os widgets no recorte de quem consulta
+    pub async fn data(&self, dashboard_id: &str, scope: &DataScope, query: &DashboardQuery) -> Result<DashboardData> {
+        let stored = self.get(dashboard_id).await
+            .ok_or_else(|| anyhow!("Dashboard {} not found", dashboard_id))?;
+        let records = self.visible_records(scope, query).await;
+        let widgets = stored.dashboard.widgets.iter()
+            .map(|widget| evaluate(widget, &records))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DashboardData {
+            dashboard_id: stored.dashboard.dashboard_id,
+            name: stored.dashboard.name,
+            generated_at: Utc::now(),
+            from: query.from,
+            to: query.to,
+            regions: scope.regions(),
+            widgets,
+        })
+    }
+
+    /// Calcula um widget, para atualização individual no painel
+    pub async fn widget_data(
+        &self,
+        dashboard_id: &str,
+        widget_id: &str,
+        scope: &DataScope,
+        query: &DashboardQuery,
+    ) -> Result<WidgetData> {
+        let stored = self.get(dashboard_id).await
+            .ok_or_else(|| anyhow!("Dashboard {} not found", dashboard_id))?;
+        let widget = stored.dashboard.widgets.iter()
+            .find(|widget| widget.widget_id == widget_id)
+            .ok_or_else(|| anyhow!("Widget {} not found in dashboard {}", widget_id, dashboard_id))?;
+        evaluate(widget, &self.visible_records(scope, query).await)
+    }
+
+    /// Eventos das duas fontes na janela, na eleição pedida e no recorte
+    async fn visible_records(&self, scope: &DataScope, query: &DashboardQuery) -> Vec<DashboardRecord> {
+        let log = self.transparency_log.read().await;
+        let audit = log.get_audit_trail().iter().map(DashboardRecord::from_audit_event);
+        let entries = log.get_all_entries().iter().filter_map(DashboardRecord::from_log_entry);
+
+        let mut records: Vec<DashboardRecord> = audit.chain(entries)
+            .filter(|record| query.from.map_or(true, |from| record.timestamp >= from))
+            .filter(|record| query.to.map_or(true, |to| record.timestamp <= to))
+            .filter(|record| query.election_id.as_ref().map_or(true, |id| record.election_id.as_ref() == Some(id)))
+            .filter(|record| scope.allows(record.election_id.as_deref(), record.region.as_ref()))
+            .collect();
+        records.sort_by_key(|record| record.timestamp);
+        records
+    }
+
+    async fn persist(&self, stored: &StoredDashboard) -> Result<()> {
+        let Some(pool) = &self.db else {
+            return Ok(());
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO audit.dashboards (id, owner, definition, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (id) DO UPDATE SET definition = EXCLUDED.definition, updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(&stored.dashboard.dashboard_id)
+        .bind(&stored.owner)
+        .bind(serde_json::to_value(stored)?)
+        .bind(stored.created_at)
+        .bind(stored.updated_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Valida o pedido e monta o dashboard
+fn build_dashboard(dashboard_id: String, request: DashboardRequest) -> Result<AuditDashboard> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Dashboard name is required"));
+    }
+    let layout = request.layout.unwrap_or(DashboardLayout { columns: 4, rows: 3, grid_size: 1 });
+    if layout.columns == 0 || layout.rows == 0 {
+        return Err(anyhow!("Dashboard layout must have at least one column and one row"));
+    }
+    let refresh_interval_seconds = request.refresh_interval_seconds.unwrap_or(60);
+    if request.auto_refresh && refresh_interval_seconds < MIN_REFRESH_INTERVAL_SECONDS {
+        return Err(anyhow!("Refresh interval must be at least {} seconds", MIN_REFRESH_INTERVAL_SECONDS));
+    }
+
+    let mut ids = HashSet::new();
+    let mut widgets = Vec::with_capacity(request.widgets.len());
+    for widget in request.widgets {
+        if widget.title.trim().is_empty() {
+            return Err(anyhow!("Widget title is required"));
+        }
+        if let DataSource::Custom(source) = &widget.data_source {
+            return Err(anyhow!("Custom data source {} is not supported", source));
+        }
+        if widget.size.width == 0 || widget.size.height == 0
+            || widget.position.x.saturating_add(widget.size.width) > layout.columns
+            || widget.position.y.saturating_add(widget.size.height) > layout.rows
+        {
+            return Err(anyhow!("Widget {} does not fit the {}x{} layout", widget.title, layout.columns, layout.rows));
+        }
+        group_by(&widget.widget_type, &widget.configuration)?;
+        row_limit(&widget.configuration)?;
+        visualization(&widget.configuration)?;
+
+        let widget_id = widget.widget_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        if !ids.insert(widget_id.clone()) {
+            return Err(anyhow!("Duplicate widget id {}", widget_id));
+        }
+        widgets.push(DashboardWidget {
+            widget_id,
+            title: widget.title,
+            widget_type: widget.widget_type,
+            data_source: widget.data_source,
+            position: widget.position,
+            size: widget.size,
+            configuration: widget.configuration,
+        });
+    }
+
+    Ok(AuditDashboard {
+        dashboard_id,
+        name: name.to_string(),
+        description: request.description,
+        widgets,
+        layout,
+        auto_refresh: request.auto_refresh,
+        refresh_interval_seconds,
+    })
+}
+
+/// Mapas agrupam por UF; gráficos, pelo `group_by` (padrão: tipo de evento)
+fn group_by(widget_type: &WidgetType, configuration: &HashMap<String, String>) -> Result<GroupBy> {
+    match (widget_type, configuration.get("group_by")) {
+        (WidgetType::Map, Some(value)) => match GroupBy::parse(value)? {
+            group @ (GroupBy::State | GroupBy::Region) => Ok(group),
+            _ => Err(anyhow!("Map widgets group by state or region")),
+        },
+        (WidgetType::Map, None) => Ok(GroupBy::State),
+        (_, Some(value)) => GroupBy::parse(value),
+        (_, None) => Ok(GroupBy::EventType),
+    }
+}
+
+fn row_limit(configuration: &HashMap<String, String>) -> Result<usize> {
+    match configuration.get("limit") {
+        Some(value) => {
+            let limit: usize = value.parse().map_err(|_| anyhow!("Invalid limit: {}", value))?;
+            if limit == 0 || limit > MAX_ROW_LIMIT {
+                return Err(anyhow!("Limit must be between 1 and {}", MAX_ROW_LIMIT));
+            }
+            Ok(limit)
+        }
+        None => Ok(DEFAULT_ROW_LIMIT),
+    }
+}
+
+fn visualization(configuration: &HashMap<String, String>) -> Result<Option<VisualizationType>> {
+    configuration.get("chart").map(|value| match value.as_str() {
+        "bar" => Ok(VisualizationType::BarChart),
+        "line" => Ok(VisualizationType::LineChart),
+        "pie" => Ok(VisualizationType::PieChart),
+        "heatmap" => Ok(VisualizationType::Heatmap),
+        other => Err(anyhow!("Unknown chart type: {}", other)),
+    }).transpose()
+}
+
+/// Executa a consulta do widget e agrega conforme o tipo
+fn evaluate(widget: &DashboardWidget, records: &[DashboardRecord]) -> Result<WidgetData> {
+    let matched: Vec<&DashboardRecord> = records.iter()
+        .filter(|record| record.matches(&widget.data_source))
+        .collect();
+    let count = matched.len() as u64;
+
+    let value = match widget.widget_type {
+        WidgetType::Counter => WidgetValue::Counter { count },
+        WidgetType::Gauge => {
+            let total = records.len() as u64;
+            let ratio = if total > 0 { count as f64 / total as f64 } else { 0.0 };
+            WidgetValue::Gauge { value: count, total, ratio }
+        }
+        WidgetType::Chart | WidgetType::Map => {
+            let group_by = group_by(&widget.widget_type, &widget.configuration)?;
+            let mut groups: BTreeMap<String, u64> = BTreeMap::new();
+            for record in &matched {
+                *groups.entry(record.group_key(group_by)).or_default() += 1;
+            }
+            let mut points: Vec<(String, u64)> = groups.into_iter().collect();
+            if !group_by.is_temporal() {
+                points.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            }
+            let chart_type = visualization(&widget.configuration)?.unwrap_or(if group_by.is_temporal() {
+                VisualizationType::LineChart
+            } else {
+                VisualizationType::BarChart
+            });
+            WidgetValue::Chart(ChartData {
+                chart_id: widget.widget_id.clone(),
+                title: widget.title.clone(),
+                chart_type,
+                data: points.into_iter()
+                    .map(|(label, value)| ChartDataPoint { label, value: value as f64, color: None, metadata: None })
+                    .collect(),
+                x_axis_label: None,
+                y_axis_label: Some("Eventos".to_string()),
+            })
+        }
+        WidgetType::Table | WidgetType::Timeline | WidgetType::Alert => {
+            let limit = row_limit(&widget.configuration)?;
+            // Linha do tempo em ordem cronológica; tabelas e alertas, mais recentes primeiro
+            let rows: Vec<Vec<String>> = if matches!(widget.widget_type, WidgetType::Timeline) {
+                matched.iter().skip(matched.len().saturating_sub(limit)).map(|record| record.row()).collect()
+            } else {
+                matched.iter().rev().take(limit).map(|record| record.row()).collect()
+            };
+            WidgetValue::Table(TableData {
+                table_id: widget.widget_id.clone(),
+                title: widget.title.clone(),
+                headers: ["Data/Hora", "Tipo", "Ator", "Eleição", "Região", "Índice no log"]
+                    .iter().map(|header| header.to_string()).collect(),
+                rows,
+                metadata: None,
+            })
+        }
+    };
+
+    Ok(WidgetData {
+        widget_id: widget.widget_id.clone(),
+        title: widget.title.clone(),
+        widget_type: widget.widget_type.clone(),
+        matched: count,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::{Permission, Principal, Role, RoleGrant};
+    use crate::transparency::election_logs::{ElectionEventType, LogConfig};
+
+    fn scope(region: Option<&str>) -> DataScope {
+        let principal = Principal {
+            subject: Some("auditor".to_string()),
+            grants: vec![RoleGrant {
+                role: Role::Auditor,
+                election_id: None,
+                region: region.map(|region| RegionScope::parse(region).unwrap()),
+            }],
+        };
+        let (_, scope) = principal.require_scope(Permission::ReadAudits).unwrap();
+        scope
+    }
+
+    fn widget(widget_type: WidgetType, x: u32, configuration: &[(&str, &str)]) -> WidgetRequest {
+        WidgetRequest {
+            widget_id: None,
+            title: format!("{:?}", widget_type),
+            widget_type,
+            data_source: DataSource::EventsByElection("eleicao-sp".to_string()),
+            position: WidgetPosition { x, y: 0 },
+            size: WidgetSize { width: 1, height: 1 },
+            configuration: configuration.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zonal_auditor_only_sees_own_zone() {
+        let log = Arc::new(RwLock::new(ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        })));
+        for (i, region) in ["SP/71072/0001/0042", "SP/71072/0001/0043", "SP/71072/0002/0007"].iter().enumerate() {
+            log.write().await.append_election_event(ElectionEvent {
+                id: format!("ev-{}", i),
+                event_type: ElectionEventType::VoteCast,
+                election_id: "eleicao-sp".to_string(),
+                data: serde_json::json!({ "region": region, "urna_id": format!("urna-{}", i) }),
+                timestamp: Utc::now(),
+                source: "urna".to_string(),
+            }).unwrap();
+        }
+
+        let service = DashboardService::new(log);
+        let dashboard = service.create(DashboardRequest {
+            name: "Zona 1".to_string(),
+            description: String::new(),
+            widgets: vec![
+                widget(WidgetType::Counter, 0, &[]),
+                widget(WidgetType::Chart, 1, &[("group_by", "region")]),
+                widget(WidgetType::Gauge, 2, &[]),
+            ],
+            layout: None,
+            auto_refresh: true,
+            refresh_interval_seconds: Some(30),
+        }, "auditor").await.unwrap();
+        let id = dashboard.dashboard.dashboard_id.clone();
+
+        let national = service.data(&id, &scope(None), &DashboardQuery::default()).await.unwrap();
+        assert!(matches!(national.widgets[0].value, WidgetValue::Counter { count: 3 }));
+        assert!(national.regions.is_empty());
+
+        let zonal = service.data(&id, &scope(Some("SP/71072/0001")), &DashboardQuery::default()).await.unwrap();
+        assert!(matches!(zonal.widgets[0].value, WidgetValue::Counter { count: 2 }));
+        let WidgetValue::Chart(chart) = &zonal.widgets[1].value else { panic!("chart expected") };
+        assert_eq!(chart.data.len(), 2);
+        assert!(chart.data.iter().all(|point| point.label.starts_with("SP/71072/0001/")));
+        // Eventos da trilha sem região não entram no recorte regional
+        assert!(matches!(zonal.widgets[2].value, WidgetValue::Gauge { value: 2, total: 2, .. }));
+
+        assert!(service.delete(&id, "outro", false).await.is_err());
+        service.delete(&id, "auditor", false).await.unwrap();
+        assert!(service.get(&id).await.is_none());
+    }
+
+    #[test]
+    fn test_widgets_are_validated() {
+        let request = |widgets| DashboardRequest {
+            name: "Painel".to_string(),
+            description: String::new(),
+            widgets,
+            layout: None,
+            auto_refresh: false,
+            refresh_interval_seconds: None,
+        };
+        assert!(build_dashboard("d".to_string(), request(vec![widget(WidgetType::Counter, 4, &[])])).is_err());
+        assert!(build_dashboard("d".to_string(), request(vec![widget(WidgetType::Chart, 0, &[("group_by", "cor")])])).is_err());
+        assert!(build_dashboard("d".to_string(), request(vec![widget(WidgetType::Map, 0, &[("group_by", "actor")])])).is_err());
+        assert!(build_dashboard("d".to_string(), request(vec![widget(WidgetType::Table, 0, &[("limit", "0")])])).is_err());
+        let mut custom = widget(WidgetType::Counter, 0, &[]);
+        custom.data_source = DataSource::Custom("sql".to_string());
+        assert!(build_dashboard("d".to_string(), request(vec![custom])).is_err());
+        assert!(build_dashboard("d".to_string(), request(vec![widget(WidgetType::Timeline, 3, &[("limit", "20")])])).is_ok());
+    }
+}
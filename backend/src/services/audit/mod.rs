@@ -7,10 +7,14 @@
 // pub mod event_logger;
 // pub mod audit_trail;
 // pub mod verification;
-// pub mod reporting;
+pub mod reporting;
+pub mod narrative;
+pub mod dashboards;
 
 // pub use blockchain_audit::BlockchainAuditService;
 // pub use event_logger::EventLogger;
 // pub use audit_trail::AuditTrailService;
 // pub use verification::AuditVerificationService;
-// pub use reporting::AuditReportingService;
+pub use reporting::AuditReportingService;
+pub use narrative::{generate_narrative, CaseTimeline, NarrativeSection, TimelineEntry};
+pub use dashboards::DashboardService;
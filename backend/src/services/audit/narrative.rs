@@ -0,0 +1,366 @@
+//! Narrativa de auditoria em linguagem simples
+//!
+//! Converte a linha do tempo de um caso (eventos, atores, assinaturas e
+//! verificações) em texto corrido em português, com citação do índice de
+//! cada registro no log transparente. O texto entra como seção dos
+//! relatórios judiciais gerados pelo `AuditReportingService`; cada
+//! afirmação remete a um registro que o leitor pode conferir no log.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::transparency::election_logs::{
+    ElectionEvent, ElectionEventType, ElectionLogEntry, VerificationStatus,
+};
+
+/// Linha do tempo de um caso em análise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseTimeline {
+    pub case_id: String,
+    pub title: String,
+    pub entries: Vec<TimelineEntry>,
+}
+
+/// Fato da linha do tempo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    /// Índice no log transparente; `None` para fatos sem registro no log
+    pub log_index: Option<u64>,
+    pub event_hash: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub event_type: Option<ElectionEventType>,
+    pub actor: String,
+    /// Descrição livre, usada quando o tipo de evento não basta
+    pub description: Option<String>,
+    pub signatures: Vec<SignatureRecord>,
+    pub verifications: Vec<VerificationRecord>,
+}
+
+/// Assinatura registrada para o fato
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureRecord {
+    pub signer: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// Verificação independente do fato
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationRecord {
+    pub verifier: String,
+    pub status: VerificationStatus,
+    pub checked_at: DateTime<Utc>,
+    pub detail: Option<String>,
+}
+
+/// Citação de um registro do log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    /// Número da nota no texto, a partir de 1
+    pub reference: usize,
+    pub log_index: u64,
+    pub event_hash: Option<String>,
+}
+
+/// Seção narrativa pronta para o relatório
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NarrativeSection {
+    pub case_id: String,
+    pub title: String,
+    pub paragraphs: Vec<String>,
+    pub citations: Vec<Citation>,
+    /// Fatos sem registro no log transparente
+    pub uncited_facts: usize,
+}
+
+impl TimelineEntry {
+    /// Fato a partir de uma entrada do log, com as assinaturas dos verificadores
+    pub fn from_log_entry(entry: &ElectionLogEntry) -> Result<Self> {
+        let event: ElectionEvent = serde_json::from_slice(&entry.event_data)?;
+        Ok(Self {
+            log_index: Some(entry.index),
+            event_hash: Some(entry.event_hash.clone()),
+            timestamp: entry.timestamp,
+            event_type: Some(entry.event_type.clone()),
+            actor: event.source,
+            description: None,
+            signatures: entry.verifier_signatures.iter()
+                .map(|s| SignatureRecord { signer: s.verifier_id.clone(), signed_at: s.timestamp })
+                .collect(),
+            verifications: Vec::new(),
+        })
+    }
+}
+
+impl CaseTimeline {
+    pub fn new(case_id: &str, title: &str) -> Self {
+        Self {
+            case_id: case_id.to_string(),
+            title: title.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn with_log_entries(mut self, entries: &[&ElectionLogEntry]) -> Result<Self> {
+        for entry in entries {
+            self.entries.push(TimelineEntry::from_log_entry(entry)?);
+        }
+        Ok(self)
+    }
+
+    /// Anexa o resultado de uma verificação ao fato com o índice informado
+    pub fn add_verification(&mut self, log_index: u64, record: VerificationRecord) -> bool {
+        match self.entries.iter_mut().find(|e| e.log_index == Some(log_index)) {
+            Some(entry) => {
+                entry.verifications.push(record);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Gera a narrativa em português a partir da linha do tempo
+pub fn generate_narrative(timeline: &CaseTimeline) -> NarrativeSection {
+    let mut entries: Vec<&TimelineEntry> = timeline.entries.iter().collect();
+    entries.sort_by_key(|e| (e.timestamp, e.log_index));
+
+    let mut paragraphs = Vec::new();
+    let mut citations: Vec<Citation> = Vec::new();
+    let mut uncited_facts = 0;
+
+    let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+        paragraphs.push("Não há fatos registrados para este caso.".to_string());
+        return NarrativeSection {
+            case_id: timeline.case_id.clone(),
+            title: timeline.title.clone(),
+            paragraphs,
+            citations,
+            uncited_facts,
+        };
+    };
+
+    let actors: BTreeSet<&str> = entries.iter().map(|e| e.actor.as_str()).collect();
+    paragraphs.push(format!(
+        "Esta seção descreve {} entre {} e {}, com participação de {}.",
+        plural(entries.len(), "fato", "fatos"),
+        format_moment(first.timestamp),
+        format_moment(last.timestamp),
+        join_list(&actors.into_iter().collect::<Vec<_>>()),
+    ));
+
+    for entry in entries {
+        let mut sentence = format!(
+            "Em {}, {} {}",
+            format_moment(entry.timestamp),
+            entry.actor,
+            describe(entry),
+        );
+
+        match entry.log_index {
+            Some(log_index) => {
+                let existing = citations.iter().position(|c| c.log_index == log_index);
+                let reference = match existing {
+                    Some(position) => citations[position].reference,
+                    None => {
+                        citations.push(Citation {
+                            reference: citations.len() + 1,
+                            log_index,
+                            event_hash: entry.event_hash.clone(),
+                        });
+                        citations.len()
+                    }
+                };
+                sentence.push_str(&format!(" [{}].", reference));
+            }
+            None => {
+                uncited_facts += 1;
+                sentence.push_str(" (fato sem registro no log transparente).");
+            }
+        }
+
+        if !entry.signatures.is_empty() {
+            let signers: Vec<&str> = entry.signatures.iter().map(|s| s.signer.as_str()).collect();
+            sentence.push_str(&format!(
+                " O registro foi assinado por {}: {}.",
+                plural(signers.len(), "verificador", "verificadores"),
+                join_list(&signers),
+            ));
+        }
+
+        for verification in &entry.verifications {
+            sentence.push_str(&format!(
+                " Em {}, {} {}",
+                format_moment(verification.checked_at),
+                verification.verifier,
+                describe_verification(&verification.status),
+            ));
+            match &verification.detail {
+                Some(detail) => sentence.push_str(&format!(" ({}).", detail)),
+                None => sentence.push('.'),
+            }
+        }
+
+        paragraphs.push(sentence);
+    }
+
+    if uncited_facts > 0 {
+        paragraphs.push(format!(
+            "{} não {} registro no log transparente e não {} ser conferido{} de forma independente.",
+            plural(uncited_facts, "fato", "fatos"),
+            if uncited_facts == 1 { "possui" } else { "possuem" },
+            if uncited_facts == 1 { "pode" } else { "podem" },
+            if uncited_facts == 1 { "" } else { "s" },
+        ));
+    }
+
+    NarrativeSection {
+        case_id: timeline.case_id.clone(),
+        title: timeline.title.clone(),
+        paragraphs,
+        citations,
+        uncited_facts,
+    }
+}
+
+/// Texto das citações, uma por linha, para o rodapé do relatório
+pub fn format_citations(section: &NarrativeSection) -> Vec<String> {
+    section.citations.iter()
+        .map(|c| match &c.event_hash {
+            Some(hash) => format!("[{}] Registro nº {} do log transparente, hash {}.", c.reference, c.log_index, hash),
+            None => format!("[{}] Registro nº {} do log transparente.", c.reference, c.log_index),
+        })
+        .collect()
+}
+
+fn describe(entry: &TimelineEntry) -> String {
+    if let Some(description) = &entry.description {
+        return description.clone();
+    }
+    let Some(event_type) = &entry.event_type else {
+        return "registrou um fato".to_string();
+    };
+    match event_type {
+        ElectionEventType::ElectionCreated => "criou a eleição",
+        ElectionEventType::ElectionUpdated => "alterou os dados da eleição em rascunho",
+        ElectionEventType::ElectionDeleted => "removeu a eleição em rascunho",
+        ElectionEventType::ElectionScheduled => "agendou a eleição",
+        ElectionEventType::ElectionStarted => "abriu a votação",
+        ElectionEventType::ElectionEnded => "encerrou a votação",
+        ElectionEventType::ElectionFinalized => "finalizou a apuração",
+        ElectionEventType::ElectionAudited => "concluiu a auditoria da eleição",
+        ElectionEventType::VoteCast => "registrou um voto",
+        ElectionEventType::VoteVerified => "confirmou a verificação de um voto",
+        ElectionEventType::AuditTriggered => "iniciou uma auditoria",
+        ElectionEventType::SecurityAlert => "emitiu um alerta de segurança",
+        ElectionEventType::SystemEvent => "registrou um evento de sistema",
+        ElectionEventType::AuditCameraHealth => "informou o estado da câmera de auditoria",
+        ElectionEventType::AuditCameraSegment => "registrou um trecho de gravação da câmera de auditoria",
+        ElectionEventType::MixnetShuffle => "executou um embaralhamento da mixnet",
+        ElectionEventType::RandomnessCommitted => "comprometeu um sorteio com uma rodada futura do beacon",
+        ElectionEventType::RandomnessDrawn => "sorteou um valor aleatório público",
+        ElectionEventType::VoteBatchRoot => "enviou a raiz assinada de um lote de votos",
+        ElectionEventType::ResultsPublished => "publicou o manifesto assinado do resultado",
+        ElectionEventType::RiskLimitingAuditReport => "publicou o relatório da auditoria de limitação de risco",
+        ElectionEventType::KeyCeremony => "registrou uma etapa da cerimônia de chaves",
+        ElectionEventType::TransmissionClosed => "declarou o fim da transmissão dos boletins de uma zona",
+        ElectionEventType::CountingRoundClosed => "encerrou uma rodada de contagem",
+        ElectionEventType::UrnaDecommissioned => "informou o descomissionamento de uma urna",
+        ElectionEventType::ShadowTallyCompared => "comparou a apuração oficial com a apuração-sombra",
+        ElectionEventType::UrnaUpdatePublished => "publicou uma atualização de software das urnas",
+        ElectionEventType::UrnaKeyRotated => "substituiu a chave pública de uma urna",
+    }
+    .to_string()
+}
+
+fn describe_verification(status: &VerificationStatus) -> &'static str {
+    match status {
+        VerificationStatus::Verified => "confirmou a integridade do registro",
+        VerificationStatus::PartiallyVerified => "confirmou parcialmente o registro",
+        VerificationStatus::Failed => "não conseguiu confirmar o registro",
+        VerificationStatus::Pending => "iniciou a verificação, ainda sem conclusão",
+    }
+}
+
+fn format_moment(moment: DateTime<Utc>) -> String {
+    moment.format("%d/%m/%Y, às %H:%M:%S (UTC)").to_string()
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+fn join_list(items: &[&str]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.to_string(),
+        [rest @ .., last] => format!("{} e {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(log_index: Option<u64>, minute: u32, actor: &str, event_type: ElectionEventType) -> TimelineEntry {
+        TimelineEntry {
+            log_index,
+            event_hash: log_index.map(|i| format!("hash{}", i)),
+            timestamp: Utc.with_ymd_and_hms(2026, 10, 4, 8, minute, 0).unwrap(),
+            event_type: Some(event_type),
+            actor: actor.to_string(),
+            description: None,
+            signatures: Vec::new(),
+            verifications: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_narrative_cites_log_indices_in_order() {
+        let mut timeline = CaseTimeline::new("caso-1", "Contestação da seção 0042");
+        let mut vote = entry(Some(17), 5, "Urna", ElectionEventType::VoteCast);
+        vote.signatures.push(SignatureRecord { signer: "tse-1".to_string(), signed_at: vote.timestamp });
+        vote.signatures.push(SignatureRecord { signer: "tse-2".to_string(), signed_at: vote.timestamp });
+        timeline.entries.push(vote);
+        timeline.entries.push(entry(Some(3), 0, "TSE", ElectionEventType::ElectionStarted));
+        assert!(timeline.add_verification(17, VerificationRecord {
+            verifier: "Auditor independente".to_string(),
+            status: VerificationStatus::Verified,
+            checked_at: Utc.with_ymd_and_hms(2026, 10, 5, 9, 0, 0).unwrap(),
+            detail: None,
+        }));
+
+        let section = generate_narrative(&timeline);
+        assert_eq!(section.paragraphs[0], "Esta seção descreve 2 fatos entre 04/10/2026, às 08:00:00 (UTC) e 04/10/2026, às 08:05:00 (UTC), com participação de TSE e Urna.");
+        assert_eq!(section.paragraphs[1], "Em 04/10/2026, às 08:00:00 (UTC), TSE abriu a votação [1].");
+        assert!(section.paragraphs[2].starts_with("Em 04/10/2026, às 08:05:00 (UTC), Urna registrou um voto [2]. O registro foi assinado por 2 verificadores: tse-1 e tse-2."));
+        assert!(section.paragraphs[2].ends_with("Auditor independente confirmou a integridade do registro."));
+        assert_eq!(section.citations.iter().map(|c| c.log_index).collect::<Vec<_>>(), vec![3, 17]);
+        assert_eq!(format_citations(&section)[1], "[2] Registro nº 17 do log transparente, hash hash17.");
+    }
+
+    #[test]
+    fn test_facts_without_log_record_are_flagged() {
+        let mut timeline = CaseTimeline::new("caso-2", "Relato de mesário");
+        let mut report = entry(None, 10, "Mesário 0042", ElectionEventType::SystemEvent);
+        report.description = Some("relatou falha na impressora".to_string());
+        timeline.entries.push(report);
+
+        let section = generate_narrative(&timeline);
+        assert!(section.citations.is_empty());
+        assert_eq!(section.uncited_facts, 1);
+        assert!(section.paragraphs[1].contains("relatou falha na impressora (fato sem registro no log transparente)."));
+        assert_eq!(
+            section.paragraphs.last().unwrap(),
+            "1 fato não possui registro no log transparente e não pode ser conferido de forma independente."
+        );
+        assert!(!timeline.add_verification(99, VerificationRecord {
+            verifier: "x".to_string(),
+            status: VerificationStatus::Failed,
+            checked_at: Utc::now(),
+            detail: None,
+        }));
+    }
+}
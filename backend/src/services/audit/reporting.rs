@@ -8,6 +8,9 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use crate::transparency::election_logs::{AuditEvent, AuditEventType};
+use fortis_domain::document::locale::{format_datetime, format_decimal, format_integer, format_percent};
+use fortis_domain::document::{Align, Column, Document};
+use super::narrative::{self, CaseTimeline};
 
 /// Serviço de relatórios de auditoria
 pub struct AuditReportingService {
@@ -45,6 +48,8 @@ pub enum ReportSectionType {
     Compliance,
     Recommendations,
     Timeline,
+    /// Narrativa em português com citações ao log, para relatórios judiciais
+    Narrative,
 }
 
 /// Fonte de dados
@@ -128,6 +133,20 @@ pub struct ReportContent {
     pub tables: Vec<TableData>,
 }
 
+/// Estatísticas do período do relatório
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditStatistics {
+    pub total_events: u64,
+    pub events_by_type: HashMap<String, u64>,
+    pub events_by_actor: HashMap<String, u64>,
+    pub events_today: u64,
+    pub events_this_week: u64,
+    pub events_this_month: u64,
+    pub verification_rate: f64,
+    pub error_rate: f64,
+    pub last_updated: DateTime<Utc>,
+}
+
 /// Conteúdo da seção
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReportSectionContent {
@@ -511,6 +530,21 @@ impl AuditReportingService {
         })
     }
 
+    /// Gera a seção narrativa de um caso para relatórios judiciais
+    pub fn generate_case_narrative(&self, timeline: &CaseTimeline) -> Result<ReportSectionContent> {
+        let section = narrative::generate_narrative(timeline);
+        let mut data = serde_json::to_value(&section)?;
+        data["citation_notes"] = serde_json::to_value(narrative::format_citations(&section))?;
+
+        Ok(ReportSectionContent {
+            section_id: format!("narrative_{}", timeline.case_id),
+            title: format!("Narrativa do Caso: {}", timeline.title),
+            content_type: ReportSectionType::Narrative,
+            data,
+            visualization: None,
+        })
+    }
+
     /// Gera resumo do relatório
     async fn generate_report_summary(&self, events: &[AuditEvent]) -> Result<ReportSummary> {
         let total_events = events.len();
@@ -636,7 +670,7 @@ impl AuditReportingService {
             rows.push(vec![
                 event.event_id.clone(),
                 format!("{:?}", event.event_type),
-                event.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                format_datetime(event.timestamp),
                 event.actor.clone(),
                 event.action.clone(),
             ]);
@@ -714,10 +748,7 @@ impl AuditReportingService {
                 // TODO: Implementar geração de HTML
                 Ok(b"HTML export not implemented yet".to_vec())
             },
-            ReportFormat::Pdf => {
-                // TODO: Implementar geração de PDF
-                Ok(b"PDF export not implemented yet".to_vec())
-            },
+            ReportFormat::Pdf => Ok(report_document(report).to_pdf()),
             ReportFormat::Csv => {
                 // TODO: Implementar geração de CSV
                 Ok(b"CSV export not implemented yet".to_vec())
@@ -729,3 +760,68 @@ impl AuditReportingService {
         }
     }
 }
+
+impl ComplianceStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            ComplianceStatus::Compliant => "Conforme",
+            ComplianceStatus::NonCompliant => "Não conforme",
+            ComplianceStatus::RequiresAttention => "Requer atenção",
+            ComplianceStatus::UnderReview => "Em análise",
+        }
+    }
+}
+
+/// Relatório diagramado como documento oficial (PDF em 80 colunas)
+fn report_document(report: &GeneratedReport) -> Document {
+    let period = format!("{} a {}", format_datetime(report.period_start), format_datetime(report.period_end));
+    let mut document = Document::new()
+        .title(&report.title)
+        .field("Relatório", &report.report_id)
+        .field("Período", period)
+        .field("Gerado em", format_datetime(report.generated_at))
+        .field("Gerado por", &report.generated_by);
+
+    if let Some(summary) = &report.content.summary {
+        document = document
+            .rule()
+            .heading("Resumo")
+            .field("Eventos", format_integer(summary.total_events as u64))
+            .field("Alertas de segurança", format_integer(summary.security_alerts as u64))
+            .field("Taxa de erro", format_percent(summary.error_rate, 2))
+            .field("Conformidade", summary.compliance_status.label());
+    }
+    if let Some(statistics) = &report.content.statistics {
+        document = document.field("Taxa de verificação", format_percent(statistics.verification_rate, 2));
+    }
+
+    for section in &report.content.sections {
+        let text = match &section.data {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Number(number) => format_decimal(number.as_f64().unwrap_or_default(), 0),
+            _ => continue,
+        };
+        document = document.rule().heading(&section.title).paragraph(&text);
+    }
+
+    for chart in &report.content.charts {
+        let mut points: Vec<&ChartDataPoint> = chart.data.iter().collect();
+        points.sort_by(|a, b| b.value.total_cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+        let header = chart.y_axis_label.as_deref().unwrap_or("Valor");
+        let rows = points
+            .iter()
+            .map(|point| vec![point.label.clone(), format_decimal(point.value, 0)])
+            .collect();
+        document = document
+            .rule()
+            .heading(&chart.title)
+            .table(vec![Column::new("", Align::Left), Column::new(header, Align::Right)], rows);
+    }
+
+    for table in &report.content.tables {
+        let columns = table.headers.iter().map(|header| Column::new(header, Align::Left)).collect();
+        document = document.rule().heading(&table.title).table(columns, table.rows.clone());
+    }
+
+    document.rule()
+}
@@ -3,6 +3,7 @@
 use crate::models::{AuthRequest, AuthResponse, UserInfo, BiometricData};
 use sqlx::{Pool, Postgres};
 use crate::crypto::CryptoService;
+use crate::auth::jwt::JwtService;
 use anyhow::Result;
 use uuid::Uuid;
 use chrono::{Utc, Duration};
@@ -10,11 +11,12 @@ use chrono::{Utc, Duration};
 pub struct AuthService {
     db: Pool<Postgres>,
     crypto: CryptoService,
+    jwt: JwtService,
 }
 
 impl AuthService {
-    pub fn new(db: Pool<Postgres>, crypto: CryptoService) -> Self {
-        Self { db, crypto }
+    pub fn new(db: Pool<Postgres>, crypto: CryptoService, jwt: JwtService) -> Self {
+        Self { db, crypto, jwt }
     }
     
     /// Autenticar usuário com biometria e certificado digital
@@ -39,38 +41,26 @@ impl AuthService {
             return Err(anyhow::anyhow!("Eleitor não está elegível para votar"));
         }
         
-        // 5. Gerar tokens JWT
-        let access_token = self.generate_access_token(&voter)?;
-        let refresh_token = self.generate_refresh_token(&voter)?;
+        // 5. Abrir sessão e gerar tokens JWT
+        let name = self.crypto.decrypt(&voter.name_encrypted)?;
+        let tokens = self.jwt.start_session(&req.cpf, &name).await?;
         
         // 6. Criar resposta
         Ok(AuthResponse {
-            access_token,
-            refresh_token,
-            expires_in: 3600, // 1 hora
-            token_type: "Bearer".to_string(),
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            expires_in: tokens.expires_in as u64,
+            token_type: tokens.token_type,
             user: UserInfo {
                 id: voter.id,
                 cpf: req.cpf.clone(),
-                name: self.crypto.decrypt(&voter.name_encrypted)?,
+                name,
                 roles: vec!["voter".to_string()],
                 election_eligible: voter.is_eligible,
             },
         })
     }
     
-    /// Refresh token
-    pub async fn refresh_token(&self, refresh_token: &str) -> Result<AuthResponse> {
-        // TODO: Implementar refresh token
-        Err(anyhow::anyhow!("Refresh token não implementado"))
-    }
-    
-    /// Logout
-    pub async fn logout(&self, refresh_token: &str) -> Result<()> {
-        // TODO: Implementar logout
-        Ok(())
-    }
-    
     /// Verificar token
     pub async fn verify_token(&self, token: &str) -> Result<UserInfo> {
         // TODO: Implementar verificação de token
@@ -149,16 +139,4 @@ impl AuthService {
         }
         Ok(())
     }
-    
-    /// Gerar access token
-    fn generate_access_token(&self, voter: &crate::models::Voter) -> Result<String> {
-        // TODO: Implementar geração de JWT
-        Ok("mock_access_token".to_string())
-    }
-    
-    /// Gerar refresh token
-    fn generate_refresh_token(&self, voter: &crate::models::Voter) -> Result<String> {
-        // TODO: Implementar geração de refresh token
-        Ok("mock_refresh_token".to_string())
-    }
 }
@@ -0,0 +1,469 @@
+//! Beacon público de aleatoriedade
+//!
+//! Amostragem de auditoria, ordem de candidatos e sorteios de seções exigem
+//! aleatoriedade imprevisível e verificável por qualquer cidadão. A fonte é o
+//! drand (League of Entropy), cuja assinatura BLS é verificada localmente com
+//! a chave pública fixada na configuração.
+//!
+//! Cada sorteio é comprometido antes com uma rodada futura do drand, e o
+//! compromisso vai para o log transparente. O sorteio só é feito depois que
+//! essa rodada é publicada, e sempre com ela: quem pede o sorteio não escolhe
+//! entre valores já conhecidos. Sem drand não há sorteio; fontes cuja
+//! assinatura não é verificada não entram.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use drand_verify::{derive_randomness, G1Pubkey, Pubkey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::config::BeaconConfig;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Finalidades de sorteio usadas pelos demais módulos
+pub mod purposes {
+    pub const CANDIDATE_ORDER: &str = "candidate_order";
+    pub const AUDIT_SELECTION: &str = "audit_selection";
+
+    pub const ALL: &[&str] = &[CANDIDATE_ORDER, AUDIT_SELECTION];
+}
+
+/// Pulso de uma fonte de aleatoriedade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconPulse {
+    pub source: String,
+    pub round: u64,
+    pub randomness: String,
+    pub signature: String,
+    pub previous_signature: Option<String>,
+    /// Assinatura verificada localmente
+    pub verified: bool,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Valor de aleatoriedade entregue aos consumidores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconValue {
+    pub randomness: String,
+    pub pulse: BeaconPulse,
+}
+
+/// Compromisso de um sorteio com uma rodada futura do drand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconCommitment {
+    pub election_id: Uuid,
+    pub purpose: String,
+    pub round: u64,
+    /// Instante previsto para a publicação da rodada
+    pub round_time: DateTime<Utc>,
+    pub committed_by: String,
+    pub log_index: Option<u64>,
+    pub committed_at: DateTime<Utc>,
+}
+
+/// Sorteio registrado para uma eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconDraw {
+    pub election_id: Uuid,
+    pub purpose: String,
+    pub value: BeaconValue,
+    pub seed: String,
+    /// Entrada do log com o compromisso que fixou a rodada
+    pub commitment_log_index: Option<u64>,
+    pub log_index: Option<u64>,
+    pub drawn_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DrandResponse {
+    round: u64,
+    randomness: String,
+    signature: String,
+    previous_signature: Option<String>,
+}
+
+/// Cliente do beacon com cache de rodadas e compromissos de sorteio
+pub struct RandomnessBeacon {
+    config: BeaconConfig,
+    public_key: G1Pubkey,
+    http_client: reqwest::Client,
+    cache: RwLock<HashMap<u64, BeaconPulse>>,
+    commitments: RwLock<HashMap<(Uuid, String), BeaconCommitment>>,
+    draws: RwLock<HashMap<(Uuid, String), BeaconDraw>>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+}
+
+impl RandomnessBeacon {
+    pub fn new(config: BeaconConfig) -> Result<Self> {
+        let key_bytes: [u8; 48] = hex::decode(&config.drand_public_key)?
+            .try_into()
+            .map_err(|_| anyhow!("drand public key must be 48 bytes"))?;
+        let public_key = G1Pubkey::from_fixed(key_bytes)
+            .map_err(|_| anyhow!("Invalid drand public key"))?;
+
+        Ok(Self {
+            config,
+            public_key,
+            http_client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()?,
+            cache: RwLock::new(HashMap::new()),
+            commitments: RwLock::new(HashMap::new()),
+            draws: RwLock::new(HashMap::new()),
+            transparency_log: None,
+        })
+    }
+
+    /// Registra compromissos e sorteios no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Rodada do drand vigente no instante informado
+    pub fn round_at(&self, time: DateTime<Utc>) -> u64 {
+        let elapsed = (time.timestamp() - self.config.drand_genesis_time).max(0);
+        (elapsed / self.config.drand_period_seconds) as u64 + 1
+    }
+
+    /// Instante em que a rodada informada é publicada
+    pub fn round_time(&self, round: u64) -> DateTime<Utc> {
+        let offset = round.saturating_sub(1) as i64 * self.config.drand_period_seconds;
+        Utc.timestamp_opt(self.config.drand_genesis_time, 0)
+            .single()
+            .unwrap_or_else(Utc::now)
+            + Duration::seconds(offset)
+    }
+
+    /// Aleatoriedade mais recente do drand
+    pub async fn latest(&self) -> Result<BeaconValue> {
+        let pulse = self.drand_round(self.round_at(Utc::now())).await?;
+        Ok(BeaconValue {
+            randomness: pulse.randomness.clone(),
+            pulse,
+        })
+    }
+
+    /// Pulso verificado de uma rodada específica do drand
+    pub async fn drand_round(&self, round: u64) -> Result<BeaconPulse> {
+        if let Some(pulse) = self.cache.read().await.get(&round) {
+            return Ok(pulse.clone());
+        }
+
+        let mut last_error = anyhow!("No drand relays configured");
+        for url in &self.config.drand_urls {
+            let endpoint = format!("{}/{}/public/{}", url, self.config.drand_chain_hash, round);
+            let response = match self.http_client.get(&endpoint).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    last_error = e.into();
+                    continue;
+                }
+            };
+            let body: DrandResponse = match response.error_for_status() {
+                Ok(response) => response.json().await?,
+                Err(e) => {
+                    last_error = e.into();
+                    continue;
+                }
+            };
+
+            // Um relay que entregue pulso inválido é tratado como falha desse relay
+            match self.verify_drand(&body) {
+                Ok(pulse) => {
+                    self.cache.write().await.insert(round, pulse.clone());
+                    return Ok(pulse);
+                }
+                Err(e) => {
+                    log::error!("Pulso drand inválido de {}: {}", url, e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Verifica assinatura BLS e derivação da aleatoriedade
+    fn verify_drand(&self, response: &DrandResponse) -> Result<BeaconPulse> {
+        let signature = hex::decode(&response.signature)?;
+        let previous_signature = response.previous_signature
+            .as_deref()
+            .map(hex::decode)
+            .transpose()?
+            .unwrap_or_default();
+
+        let valid = self.public_key
+            .verify(response.round, &previous_signature, &signature)
+            .map_err(|e| anyhow!("drand verification error: {:?}", e))?;
+        if !valid {
+            return Err(anyhow!("Invalid drand signature for round {}", response.round));
+        }
+        let randomness = hex::encode(derive_randomness(&signature));
+        if randomness != response.randomness {
+            return Err(anyhow!("drand randomness does not match signature for round {}", response.round));
+        }
+
+        Ok(BeaconPulse {
+            source: "drand".to_string(),
+            round: response.round,
+            randomness,
+            signature: response.signature.clone(),
+            previous_signature: response.previous_signature.clone(),
+            verified: true,
+            fetched_at: Utc::now(),
+        })
+    }
+
+    /// Compromete o sorteio de uma finalidade com uma rodada futura do drand
+    ///
+    /// O compromisso é único por eleição e finalidade e fica no log
+    /// transparente antes de a rodada existir.
+    pub async fn commit(&self, election_id: Uuid, purpose: &str, actor: &str) -> Result<BeaconCommitment> {
+        if !purposes::ALL.contains(&purpose) {
+            return Err(anyhow!("Unknown beacon purpose {}", purpose));
+        }
+        let key = (election_id, purpose.to_string());
+        let mut commitments = self.commitments.write().await;
+        if let Some(existing) = commitments.get(&key) {
+            return Err(anyhow!(
+                "Draw {} of election {} is already committed to drand round {}",
+                purpose, election_id, existing.round
+            ));
+        }
+
+        let now = Utc::now();
+        let round = self.round_at(now) + self.config.commit_rounds_ahead.max(1);
+        let mut commitment = BeaconCommitment {
+            election_id,
+            purpose: purpose.to_string(),
+            round,
+            round_time: self.round_time(round),
+            committed_by: actor.to_string(),
+            log_index: None,
+            committed_at: now,
+        };
+        commitment.log_index = self.log_event(
+            election_id,
+            ElectionEventType::RandomnessCommitted,
+            serde_json::json!({
+                "purpose": commitment.purpose,
+                "round": commitment.round,
+                "round_time": commitment.round_time,
+                "chain_hash": self.config.drand_chain_hash,
+                "committed_by": commitment.committed_by,
+            }),
+            now,
+        ).await?;
+
+        commitments.insert(key, commitment.clone());
+        Ok(commitment)
+    }
+
+    pub async fn get_commitments(&self, election_id: Uuid) -> Vec<BeaconCommitment> {
+        let mut commitments: Vec<BeaconCommitment> = self.commitments.read().await
+            .values()
+            .filter(|c| c.election_id == election_id)
+            .cloned()
+            .collect();
+        commitments.sort_by(|a, b| a.committed_at.cmp(&b.committed_at));
+        commitments
+    }
+
+    /// Sorteio de uma eleição para a finalidade informada
+    ///
+    /// Usa a rodada comprometida antes e é recusado enquanto ela não for
+    /// publicada. Como o valor depende só da rodada, chamadas seguintes
+    /// devolvem o mesmo sorteio a todas as regiões e auditores.
+    pub async fn draw(&self, election_id: Uuid, purpose: &str) -> Result<BeaconDraw> {
+        let key = (election_id, purpose.to_string());
+        if let Some(draw) = self.draws.read().await.get(&key) {
+            return Ok(draw.clone());
+        }
+
+        let commitment = self.commitments.read().await
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| anyhow!(
+                "Draw {} of election {} has no committed drand round", purpose, election_id
+            ))?;
+        if self.round_at(Utc::now()) < commitment.round {
+            return Err(anyhow!(
+                "Committed drand round {} is not published yet (expected at {})",
+                commitment.round, commitment.round_time
+            ));
+        }
+
+        let pulse = self.drand_round(commitment.round).await?;
+        let seed = hex::encode(derive_seed(&pulse.randomness, election_id, purpose));
+        let mut draw = BeaconDraw {
+            election_id,
+            purpose: purpose.to_string(),
+            value: BeaconValue {
+                randomness: pulse.randomness.clone(),
+                pulse,
+            },
+            seed,
+            commitment_log_index: commitment.log_index,
+            log_index: None,
+            drawn_at: Utc::now(),
+        };
+        draw.log_index = self.log_event(
+            election_id,
+            ElectionEventType::RandomnessDrawn,
+            serde_json::json!({
+                "purpose": draw.purpose,
+                "round": draw.value.pulse.round,
+                "randomness": draw.value.randomness,
+                "commitment_log_index": draw.commitment_log_index,
+                "seed": draw.seed,
+            }),
+            draw.drawn_at,
+        ).await?;
+
+        let mut draws = self.draws.write().await;
+        Ok(draws.entry(key).or_insert(draw).clone())
+    }
+
+    pub async fn get_draws(&self, election_id: Uuid) -> Vec<BeaconDraw> {
+        let mut draws: Vec<BeaconDraw> = self.draws.read().await
+            .values()
+            .filter(|d| d.election_id == election_id)
+            .cloned()
+            .collect();
+        draws.sort_by(|a, b| a.drawn_at.cmp(&b.drawn_at));
+        draws
+    }
+
+    async fn log_event(
+        &self,
+        election_id: Uuid,
+        event_type: ElectionEventType,
+        data: serde_json::Value,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<u64>> {
+        let Some(log) = &self.transparency_log else {
+            return Ok(None);
+        };
+        let proof = log.write().await.append_election_event(ElectionEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type,
+            election_id: election_id.to_string(),
+            data,
+            timestamp,
+            source: "Beacon".to_string(),
+        })?;
+        Ok(Some(proof.log_index))
+    }
+}
+
+/// Semente por eleição e finalidade, separando domínios de uso
+pub fn derive_seed(randomness: &str, election_id: Uuid, purpose: &str) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(b"FORTIS-BEACON-SEED")
+        .chain_update(randomness.as_bytes())
+        .chain_update(election_id.as_bytes())
+        .chain_update(purpose.as_bytes())
+        .finalize()
+        .into()
+}
+
+/// Gerador determinístico a partir da semente (SHA-256 em modo contador)
+pub struct SeededStream {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl SeededStream {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self { seed, counter: 0 }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let digest = Sha256::new()
+            .chain_update(self.seed)
+            .chain_update(self.counter.to_be_bytes())
+            .finalize();
+        self.counter += 1;
+        u64::from_be_bytes(digest[..8].try_into().expect("digest has 32 bytes"))
+    }
+
+    /// Inteiro uniforme em [0, bound), com rejeição para evitar viés
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be positive");
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    /// Embaralhamento Fisher–Yates reproduzível por qualquer auditor
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Amostra de `count` índices distintos em [0, population)
+    pub fn sample_indices(&mut self, population: usize, count: usize) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..population).collect();
+        let count = count.min(population);
+        for i in 0..count {
+            let j = i + self.below((population - i) as u64) as usize;
+            indices.swap(i, j);
+        }
+        indices.truncate(count);
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_draw_requires_commitment_to_an_unpublished_round() {
+        let beacon = RandomnessBeacon::new(crate::config::Config::new().beacon).unwrap();
+        let election_id = Uuid::new_v4();
+
+        assert!(beacon.draw(election_id, purposes::AUDIT_SELECTION).await.is_err());
+        assert!(beacon.commit(election_id, "grinding", "admin").await.is_err());
+
+        let commitment = beacon.commit(election_id, purposes::AUDIT_SELECTION, "admin").await.unwrap();
+        assert!(commitment.round > beacon.round_at(Utc::now()));
+        assert_eq!(beacon.round_at(commitment.round_time), commitment.round);
+
+        // A rodada ainda não existe e o compromisso não pode ser trocado por outro
+        let err = beacon.draw(election_id, purposes::AUDIT_SELECTION).await.unwrap_err();
+        assert!(err.to_string().contains("not published"));
+        assert!(beacon.commit(election_id, purposes::AUDIT_SELECTION, "admin").await.is_err());
+        assert!(beacon.get_draws(election_id).await.is_empty());
+    }
+
+    #[test]
+    fn test_seeded_stream_is_reproducible() {
+        let election_id = Uuid::new_v4();
+        let seed = derive_seed("ab12", election_id, purposes::CANDIDATE_ORDER);
+        assert_ne!(seed, derive_seed("ab12", election_id, purposes::AUDIT_SELECTION));
+
+        let mut first: Vec<u32> = (0..20).collect();
+        let mut second = first.clone();
+        SeededStream::new(seed).shuffle(&mut first);
+        SeededStream::new(seed).shuffle(&mut second);
+        assert_eq!(first, second);
+
+        let sample = SeededStream::new(seed).sample_indices(100, 10);
+        let mut unique = sample.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), 10);
+        assert!(sample.iter().all(|i| *i < 100));
+    }
+}
@@ -0,0 +1,271 @@
+//! Serviço de registro de candidatos do FORTIS
+//!
+//! Mantém os candidatos de cada eleição (número, partido, coligação, cargo,
+//! região e foto) e fornece a lista por região para sincronização das urnas.
+//! Alterações só são aceitas enquanto a eleição está em rascunho ou agendada.
+
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::Utc;
+use sha2::{Sha256, Digest};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::models::{Candidate, CreateCandidateRequest};
+use crate::services::election::{ElectionService, ElectionStatus};
+
+/// Tamanho máximo da foto do candidato
+pub const MAX_PHOTO_BYTES: usize = 512 * 1024;
+
+pub struct CandidateService {
+    elections: Arc<ElectionService>,
+    candidates: RwLock<HashMap<Uuid, Candidate>>,
+    photos: RwLock<HashMap<Uuid, Vec<u8>>>,
+}
+
+impl CandidateService {
+    pub fn new(elections: Arc<ElectionService>) -> Self {
+        Self {
+            elections,
+            candidates: RwLock::new(HashMap::new()),
+            photos: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn create_candidate(&self, election_id: Uuid, req: CreateCandidateRequest) -> Result<Candidate> {
+        self.ensure_editable(election_id).await?;
+        validate_request(&req)?;
+        let photo = req.photo_base64.as_deref().map(decode_photo).transpose()?;
+
+        let mut candidates = self.candidates.write().await;
+        ensure_number_available(&candidates, election_id, &req, None)?;
+
+        let id = Uuid::new_v4();
+        let candidate = Candidate {
+            id,
+            election_id,
+            name: req.name.trim().to_string(),
+            party: req.party.trim().to_string(),
+            number: req.number,
+            position: req.position,
+            coalition: req.coalition,
+            region: req.region,
+            photo_url: photo.as_ref().map(|_| photo_url(election_id, id)),
+            photo_sha256: photo.as_ref().map(|p| hex::encode(Sha256::digest(p))),
+            updated_at: Utc::now(),
+        };
+        candidates.insert(id, candidate.clone());
+        if let Some(photo) = photo {
+            self.photos.write().await.insert(id, photo);
+        }
+
+        Ok(candidate)
+    }
+
+    pub async fn update_candidate(
+        &self,
+        election_id: Uuid,
+        candidate_id: Uuid,
+        req: CreateCandidateRequest,
+    ) -> Result<Candidate> {
+        self.ensure_editable(election_id).await?;
+        validate_request(&req)?;
+        let photo = req.photo_base64.as_deref().map(decode_photo).transpose()?;
+
+        let mut candidates = self.candidates.write().await;
+        ensure_number_available(&candidates, election_id, &req, Some(candidate_id))?;
+        let candidate = candidates
+            .get_mut(&candidate_id)
+            .filter(|c| c.election_id == election_id)
+            .ok_or_else(|| anyhow!("Candidate not found: {}", candidate_id))?;
+
+        candidate.name = req.name.trim().to_string();
+        candidate.party = req.party.trim().to_string();
+        candidate.number = req.number;
+        candidate.position = req.position;
+        candidate.coalition = req.coalition;
+        candidate.region = req.region;
+        candidate.updated_at = Utc::now();
+
+        // Foto ausente na atualização mantém a foto atual
+        if let Some(photo) = photo {
+            candidate.photo_url = Some(photo_url(election_id, candidate_id));
+            candidate.photo_sha256 = Some(hex::encode(Sha256::digest(&photo)));
+            self.photos.write().await.insert(candidate_id, photo);
+        }
+
+        Ok(candidate.clone())
+    }
+
+    pub async fn delete_candidate(&self, election_id: Uuid, candidate_id: Uuid) -> Result<()> {
+        self.ensure_editable(election_id).await?;
+
+        let mut candidates = self.candidates.write().await;
+        if !candidates.get(&candidate_id).is_some_and(|c| c.election_id == election_id) {
+            return Err(anyhow!("Candidate not found: {}", candidate_id));
+        }
+        candidates.remove(&candidate_id);
+        self.photos.write().await.remove(&candidate_id);
+        Ok(())
+    }
+
+    pub async fn get_candidate(&self, election_id: Uuid, candidate_id: Uuid) -> Option<Candidate> {
+        self.candidates.read().await
+            .get(&candidate_id)
+            .filter(|c| c.election_id == election_id)
+            .cloned()
+    }
+
+    /// Lista candidatos; com região, inclui apenas os cargos nacionais e os daquela região
+    pub async fn list_candidates(&self, election_id: Uuid, region: Option<&str>) -> Vec<Candidate> {
+        let candidates = self.candidates.read().await;
+        let mut result: Vec<Candidate> = candidates
+            .values()
+            .filter(|c| c.election_id == election_id)
+            .filter(|c| match (region, c.region.as_deref()) {
+                (Some(region), Some(candidate_region)) => region_matches(region, candidate_region),
+                _ => true,
+            })
+            .cloned()
+            .collect();
+        result.sort_by(|a, b| a.position.cmp(&b.position).then(a.number.cmp(&b.number)));
+        result
+    }
+
+    pub async fn get_photo(&self, election_id: Uuid, candidate_id: Uuid) -> Option<Vec<u8>> {
+        self.get_candidate(election_id, candidate_id).await?;
+        self.photos.read().await.get(&candidate_id).cloned()
+    }
+
+    async fn ensure_editable(&self, election_id: Uuid) -> Result<()> {
+        let election = self.elections.get_election(election_id).await?
+            .ok_or_else(|| anyhow!("Election not found: {}", election_id))?;
+        match election.status.parse::<ElectionStatus>()? {
+            ElectionStatus::Draft | ElectionStatus::Scheduled => Ok(()),
+            status => Err(anyhow!(
+                "Candidates cannot be changed while election is {}",
+                status.as_str()
+            )),
+        }
+    }
+}
+
+/// Região do município (ex.: "SP-71072") inclui candidatos estaduais ("SP")
+fn region_matches(requested: &str, candidate_region: &str) -> bool {
+    requested == candidate_region
+        || requested.split('-').next() == Some(candidate_region)
+}
+
+fn photo_url(election_id: Uuid, candidate_id: Uuid) -> String {
+    format!("/api/v1/elections/{}/candidates/{}/photo", election_id, candidate_id)
+}
+
+fn validate_request(req: &CreateCandidateRequest) -> Result<()> {
+    if req.name.trim().is_empty() || req.party.trim().is_empty() {
+        return Err(anyhow!("Candidate name and party are required"));
+    }
+
+    if !req.position.accepts_number(req.number) {
+        return Err(anyhow!(
+            "Candidate number for {:?} must have {} digits",
+            req.position, req.position.number_digits()
+        ));
+    }
+
+    match (&req.region, req.position.is_national()) {
+        (Some(_), true) => Err(anyhow!("National positions must not have a region")),
+        (None, false) => Err(anyhow!("Region is required for {:?}", req.position)),
+        _ => Ok(()),
+    }
+}
+
+fn ensure_number_available(
+    candidates: &HashMap<Uuid, Candidate>,
+    election_id: Uuid,
+    req: &CreateCandidateRequest,
+    exclude: Option<Uuid>,
+) -> Result<()> {
+    let taken = candidates.values().any(|c| {
+        Some(c.id) != exclude
+            && c.election_id == election_id
+            && c.position == req.position
+            && c.region == req.region
+            && c.number == req.number
+    });
+    if taken {
+        return Err(anyhow!("Candidate number {} is already registered", req.number));
+    }
+    Ok(())
+}
+
+fn decode_photo(photo_base64: &str) -> Result<Vec<u8>> {
+    let photo = general_purpose::STANDARD.decode(photo_base64)?;
+    if photo.len() > MAX_PHOTO_BYTES {
+        return Err(anyhow!("Candidate photo exceeds {} bytes", MAX_PHOTO_BYTES));
+    }
+    if !photo.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Err(anyhow!("Candidate photo must be a JPEG image"));
+    }
+    Ok(photo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CandidatePosition, CreateElectionRequest};
+    use chrono::Duration;
+
+    fn request(number: u32, position: CandidatePosition, region: Option<&str>) -> CreateCandidateRequest {
+        CreateCandidateRequest {
+            name: "Maria Santos".to_string(),
+            party: "PXX".to_string(),
+            number,
+            position,
+            coalition: None,
+            region: region.map(str::to_string),
+            photo_base64: None,
+        }
+    }
+
+    async fn setup() -> (CandidateService, Arc<ElectionService>, Uuid) {
+        let elections = Arc::new(ElectionService::new());
+        let election = elections.create_election(CreateElectionRequest {
+            title: "Eleições Gerais".to_string(),
+            description: None,
+            start_date: Utc::now() + Duration::days(1),
+            end_date: Utc::now() + Duration::days(2),
+        }, "admin").await.unwrap();
+        (CandidateService::new(elections.clone()), elections, election.id)
+    }
+
+    #[tokio::test]
+    async fn test_number_rules_and_region_filter() {
+        let (service, _, election_id) = setup().await;
+
+        service.create_candidate(election_id, request(13, CandidatePosition::President, None)).await.unwrap();
+        service.create_candidate(election_id, request(45, CandidatePosition::Governor, Some("SP"))).await.unwrap();
+        service.create_candidate(election_id, request(45, CandidatePosition::Governor, Some("RJ"))).await.unwrap();
+
+        assert!(service.create_candidate(election_id, request(45, CandidatePosition::Governor, Some("SP"))).await.is_err());
+        assert!(service.create_candidate(election_id, request(123, CandidatePosition::Governor, Some("SP"))).await.is_err());
+        assert!(service.create_candidate(election_id, request(13, CandidatePosition::Mayor, None)).await.is_err());
+
+        let sp = service.list_candidates(election_id, Some("SP-71072")).await;
+        assert_eq!(sp.len(), 2);
+        assert_eq!(service.list_candidates(election_id, None).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_candidates_frozen_after_election_starts() {
+        let (service, elections, election_id) = setup().await;
+        let candidate = service.create_candidate(election_id, request(13, CandidatePosition::President, None)).await.unwrap();
+
+        elections.transition(election_id, ElectionStatus::Scheduled, "admin", None).await.unwrap();
+        elections.transition(election_id, ElectionStatus::Active, "admin", None).await.unwrap();
+
+        assert!(service.delete_candidate(election_id, candidate.id).await.is_err());
+        assert!(service.create_candidate(election_id, request(22, CandidatePosition::President, None)).await.is_err());
+    }
+}
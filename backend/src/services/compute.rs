@@ -0,0 +1,213 @@
+//! Pool de computação para criptografia pesada
+//!
+//! Verificação de provas, embaralhamentos da mixnet e decifração bloqueiam a
+//! thread por centenas de milissegundos; executadas nos workers do actix,
+//! atrasam todas as requisições atendidas pelo mesmo worker. O pool roda
+//! essas tarefas em threads próprias (rayon), com fila limitada: quando
+//! cheia, a tarefa é rejeitada em vez de acumular latência. Nos nós de
+//! apuração, as threads podem ser fixadas em núcleos reservados.
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{oneshot, Semaphore};
+
+use crate::config::ComputeConfig;
+use crate::monitoring::MonitoringSystem;
+
+/// Estado do pool para métricas e diagnóstico
+#[derive(Debug, Clone, Serialize)]
+pub struct ComputePoolStats {
+    pub threads: usize,
+    pub max_queued: usize,
+    pub queued: usize,
+    pub running: usize,
+    pub completed: u64,
+    pub rejected: u64,
+    pub pinned_cores: Vec<usize>,
+}
+
+/// Pool dedicado às tarefas criptográficas
+pub struct ComputePool {
+    pool: rayon::ThreadPool,
+    permits: Arc<Semaphore>,
+    threads: usize,
+    max_queued: usize,
+    pinned_cores: Vec<usize>,
+    queued: Arc<AtomicUsize>,
+    running: Arc<AtomicUsize>,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+    monitoring: Option<Arc<MonitoringSystem>>,
+}
+
+impl ComputePool {
+    pub fn new(config: &ComputeConfig) -> Result<Self> {
+        let threads = match config.threads {
+            0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            n => n,
+        };
+
+        let available: Vec<usize> = core_affinity::get_core_ids()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|core| core.id)
+            .collect();
+        if let Some(missing) = config.cpu_affinity.iter().find(|core| !available.contains(core)) {
+            return Err(anyhow!("CPU core {} is not available for pinning", missing));
+        }
+
+        let pinned_cores = config.cpu_affinity.clone();
+        let affinity = pinned_cores.clone();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("fortis-compute-{}", i))
+            .start_handler(move |i| {
+                // Threads além dos núcleos listados compartilham os núcleos em rodízio
+                if !affinity.is_empty() {
+                    let id = affinity[i % affinity.len()];
+                    if !core_affinity::set_for_current(core_affinity::CoreId { id }) {
+                        log::warn!("Failed to pin compute thread {} to core {}", i, id);
+                    }
+                }
+            })
+            .build()?;
+
+        Ok(Self {
+            pool,
+            permits: Arc::new(Semaphore::new(threads + config.max_queued)),
+            threads,
+            max_queued: config.max_queued,
+            pinned_cores,
+            queued: Arc::new(AtomicUsize::new(0)),
+            running: Arc::new(AtomicUsize::new(0)),
+            completed: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            monitoring: None,
+        })
+    }
+
+    /// Publica profundidade da fila e tempos das tarefas
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringSystem>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
+    }
+
+    /// Executa a tarefa no pool e aguarda o resultado sem bloquear o worker
+    pub async fn run<T, F>(&self, task: &str, job: F) -> Result<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = match self.permits.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                self.record_counter("compute_tasks_rejected").await;
+                return Err(anyhow!("Compute queue full, rejecting {} task", task));
+            }
+        };
+
+        let queued = self.queued.clone();
+        let running = self.running.clone();
+        let (tx, rx) = oneshot::channel();
+        let submitted_at = Instant::now();
+        queued.fetch_add(1, Ordering::Relaxed);
+        self.pool.spawn(move || {
+            queued.fetch_sub(1, Ordering::Relaxed);
+            running.fetch_add(1, Ordering::Relaxed);
+            let waited = submitted_at.elapsed();
+            let started_at = Instant::now();
+            let outcome = std::panic::catch_unwind(AssertUnwindSafe(job));
+            running.fetch_sub(1, Ordering::Relaxed);
+            drop(permit);
+            let _ = tx.send((outcome, waited, started_at.elapsed()));
+        });
+        self.record_depth().await;
+
+        let (outcome, waited, elapsed) = rx.await
+            .map_err(|_| anyhow!("Compute task {} was dropped", task))?;
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        if let Some(monitoring) = &self.monitoring {
+            monitoring.increment_counter("compute_tasks_completed", 1).await;
+            monitoring.add_to_histogram("compute_queue_wait_ms", waited.as_secs_f64() * 1000.0).await;
+            monitoring.add_to_histogram(&format!("compute_{}_ms", task), elapsed.as_secs_f64() * 1000.0).await;
+        }
+        self.record_depth().await;
+
+        outcome.map_err(|_| anyhow!("Compute task {} panicked", task))
+    }
+
+    pub fn stats(&self) -> ComputePoolStats {
+        ComputePoolStats {
+            threads: self.threads,
+            max_queued: self.max_queued,
+            queued: self.queued.load(Ordering::Relaxed),
+            running: self.running.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            pinned_cores: self.pinned_cores.clone(),
+        }
+    }
+
+    async fn record_depth(&self) {
+        if let Some(monitoring) = &self.monitoring {
+            monitoring.set_gauge("compute_queue_depth", self.queued.load(Ordering::Relaxed) as f64).await;
+            monitoring.set_gauge("compute_running", self.running.load(Ordering::Relaxed) as f64).await;
+        }
+    }
+
+    async fn record_counter(&self, counter: &str) {
+        if let Some(monitoring) = &self.monitoring {
+            monitoring.increment_counter(counter, 1).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn pool(threads: usize, max_queued: usize) -> Arc<ComputePool> {
+        Arc::new(ComputePool::new(&ComputeConfig {
+            threads,
+            max_queued,
+            cpu_affinity: vec![],
+            http_workers: 0,
+        }).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_rejects_instead_of_waiting() {
+        let pool = pool(1, 0);
+        let (release, blocked) = std::sync::mpsc::channel::<()>();
+
+        let busy = pool.clone();
+        let first = tokio::spawn(async move { busy.run("slow", move || blocked.recv().is_ok()).await });
+        while pool.stats().running == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        assert!(pool.run("fast", || 1).await.is_err());
+        assert_eq!(pool.stats().rejected, 1);
+
+        release.send(()).unwrap();
+        assert!(first.await.unwrap().unwrap());
+        assert_eq!(pool.run("fast", || 2).await.unwrap(), 2);
+        assert_eq!(pool.stats().completed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task_returns_error_and_frees_slot() {
+        let pool = pool(1, 0);
+        assert!(pool.run("broken", || -> u32 { panic!("invalid proof encoding") }).await.is_err());
+
+        let stats = pool.stats();
+        assert_eq!((stats.queued, stats.running), (0, 0));
+        assert_eq!(pool.run("sum", || (1..=10).sum::<u32>()).await.unwrap(), 55);
+    }
+}
@@ -0,0 +1,454 @@
+//! Livro de consentimento e finalidade para dados biométricos (LGPD)
+//!
+//! Registra, para cada interação com o eleitor, a base legal, a finalidade e
+//! a retenção de cada categoria de tratamento biométrico (LGPD art. 11).
+//! O método `authorize_use` é o ponto de controle que impede o uso de
+//! templates biométricos fora das finalidades declaradas.
+//!
+//! O titular aparece no livro só como HMAC do identificador, com chave
+//! derivada do segredo do backend: sem ela, o espaço pequeno dos CPFs não
+//! permite recuperar o eleitor a partir do pseudônimo.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc, Duration};
+use anyhow::{Result, anyhow};
+use ring::hmac;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Categoria de dado biométrico tratado
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BiometricCategory {
+    Fingerprint,
+    FacialImage,
+    BiometricTemplate,
+}
+
+/// Finalidade do tratamento
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingPurpose {
+    VoterAuthentication,
+    DuplicateDetection,
+    FraudInvestigation,
+    Audit,
+}
+
+/// Base legal do tratamento (LGPD art. 11)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LegalBasis {
+    /// Art. 11, I - consentimento específico e destacado
+    ExplicitConsent,
+    /// Art. 11, II, a - cumprimento de obrigação legal (Código Eleitoral)
+    LegalObligation,
+    /// Art. 11, II, b - execução de políticas públicas
+    PublicPolicy,
+    /// Art. 11, II, g - prevenção à fraude e segurança do titular
+    FraudPrevention,
+}
+
+/// Finalidade declarada para uma categoria biométrica
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurposeDeclaration {
+    pub category: BiometricCategory,
+    pub purpose: ProcessingPurpose,
+    pub legal_basis: LegalBasis,
+    pub retention_days: i64,
+    pub description: String,
+}
+
+/// Registro de tratamento ou consentimento de um titular
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentRecord {
+    pub id: String,
+    /// Identificador pseudonimizado do eleitor (HMAC-SHA256)
+    pub subject_hash: String,
+    pub category: BiometricCategory,
+    pub purpose: ProcessingPurpose,
+    pub legal_basis: LegalBasis,
+    pub interaction_id: String,
+    pub source: String,
+    pub recorded_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Tentativa de uso de dado biométrico, autorizada ou não
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BiometricUsage {
+    pub id: String,
+    pub subject_hash: String,
+    pub category: BiometricCategory,
+    pub purpose: ProcessingPurpose,
+    pub interaction_id: String,
+    pub allowed: bool,
+    pub reason: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Filtro de consulta para auditorias LGPD
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConsentQuery {
+    pub voter_id: Option<String>,
+    pub category: Option<BiometricCategory>,
+    pub purpose: Option<ProcessingPurpose>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Resumo para relatórios de auditoria LGPD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentAuditSummary {
+    pub total_records: usize,
+    pub active_records: usize,
+    pub expired_records: usize,
+    pub revoked_records: usize,
+    pub records_by_purpose: HashMap<String, usize>,
+    pub total_usages: usize,
+    pub denied_usages: usize,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Livro de consentimento biométrico
+pub struct ConsentLedger {
+    subject_key: hmac::Key,
+    declarations: RwLock<Vec<PurposeDeclaration>>,
+    records: RwLock<Vec<ConsentRecord>>,
+    usages: RwLock<Vec<BiometricUsage>>,
+}
+
+impl ConsentLedger {
+    /// Cria livro com as finalidades declaradas na política de privacidade
+    pub fn new(secret: &[u8]) -> Self {
+        Self::with_declarations(Self::default_declarations(), secret)
+    }
+
+    pub fn with_declarations(declarations: Vec<PurposeDeclaration>, secret: &[u8]) -> Self {
+        let derived = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, secret), b"fortis-consent-subject-v1");
+        Self {
+            subject_key: hmac::Key::new(hmac::HMAC_SHA256, derived.as_ref()),
+            declarations: RwLock::new(declarations),
+            records: RwLock::new(Vec::new()),
+            usages: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Finalidades declaradas por padrão (compliance/lgpd/privacy-policy.md)
+    pub fn default_declarations() -> Vec<PurposeDeclaration> {
+        vec![
+            PurposeDeclaration {
+                category: BiometricCategory::Fingerprint,
+                purpose: ProcessingPurpose::VoterAuthentication,
+                legal_basis: LegalBasis::LegalObligation,
+                retention_days: 90,
+                description: "Identificação do eleitor na seção eleitoral".to_string(),
+            },
+            PurposeDeclaration {
+                category: BiometricCategory::FacialImage,
+                purpose: ProcessingPurpose::VoterAuthentication,
+                legal_basis: LegalBasis::LegalObligation,
+                retention_days: 90,
+                description: "Identificação facial como alternativa à digital".to_string(),
+            },
+            PurposeDeclaration {
+                category: BiometricCategory::BiometricTemplate,
+                purpose: ProcessingPurpose::VoterAuthentication,
+                legal_basis: LegalBasis::LegalObligation,
+                retention_days: 90,
+                description: "Comparação com o template do cadastro eleitoral".to_string(),
+            },
+            PurposeDeclaration {
+                category: BiometricCategory::BiometricTemplate,
+                purpose: ProcessingPurpose::DuplicateDetection,
+                legal_basis: LegalBasis::FraudPrevention,
+                retention_days: 365,
+                description: "Detecção de cadastros biométricos duplicados".to_string(),
+            },
+        ]
+    }
+
+    /// Lista finalidades declaradas
+    pub async fn get_declarations(&self) -> Vec<PurposeDeclaration> {
+        self.declarations.read().await.clone()
+    }
+
+    /// Registra consentimento explícito do titular para uma finalidade
+    pub async fn grant_consent(
+        &self,
+        voter_id: &str,
+        category: BiometricCategory,
+        purpose: ProcessingPurpose,
+        interaction_id: &str,
+        source: &str,
+    ) -> Result<ConsentRecord> {
+        let declaration = self.find_declaration(category, purpose).await
+            .ok_or_else(|| anyhow!("Purpose {:?} not declared for {:?}", purpose, category))?;
+        if declaration.legal_basis != LegalBasis::ExplicitConsent {
+            return Err(anyhow!("Purpose {:?} does not rely on explicit consent", purpose));
+        }
+
+        Ok(self.append_record(voter_id, &declaration, interaction_id, source).await)
+    }
+
+    /// Revoga consentimentos explícitos do titular para uma finalidade
+    pub async fn revoke_consent(
+        &self,
+        voter_id: &str,
+        category: BiometricCategory,
+        purpose: ProcessingPurpose,
+    ) -> Result<usize> {
+        let subject_hash = self.hash_subject(voter_id);
+        let now = Utc::now();
+        let mut records = self.records.write().await;
+
+        let mut revoked = 0;
+        for record in records.iter_mut().filter(|r| {
+            r.subject_hash == subject_hash
+                && r.category == category
+                && r.purpose == purpose
+                && r.legal_basis == LegalBasis::ExplicitConsent
+                && r.revoked_at.is_none()
+        }) {
+            record.revoked_at = Some(now);
+            revoked += 1;
+        }
+
+        if revoked == 0 {
+            return Err(anyhow!("No active consent to revoke"));
+        }
+        Ok(revoked)
+    }
+
+    /// Ponto de controle: autoriza o uso de dado biométrico para uma finalidade
+    /// e registra o tratamento no livro. Usos fora das finalidades declaradas
+    /// (ou sem consentimento vigente, quando exigido) são negados.
+    pub async fn authorize_use(
+        &self,
+        voter_id: &str,
+        category: BiometricCategory,
+        purpose: ProcessingPurpose,
+        interaction_id: &str,
+        source: &str,
+    ) -> Result<ConsentRecord> {
+        let subject_hash = self.hash_subject(voter_id);
+        let decision = self.evaluate(&subject_hash, category, purpose).await;
+
+        self.usages.write().await.push(BiometricUsage {
+            id: Uuid::new_v4().to_string(),
+            subject_hash,
+            category,
+            purpose,
+            interaction_id: interaction_id.to_string(),
+            allowed: decision.is_ok(),
+            reason: decision.as_ref().err().map(|e| e.to_string()),
+            timestamp: Utc::now(),
+        });
+
+        match decision {
+            Ok(declaration) => Ok(self.append_record(voter_id, &declaration, interaction_id, source).await),
+            Err(e) => {
+                log::warn!("Uso biométrico negado ({:?}/{:?}): {}", category, purpose, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Consulta registros de tratamento
+    pub async fn query_records(&self, query: &ConsentQuery) -> Vec<ConsentRecord> {
+        let subject_hash = query.voter_id.as_deref().map(|voter_id| self.hash_subject(voter_id));
+        let records = self.records.read().await;
+
+        records
+            .iter()
+            .filter(|r| subject_hash.as_ref().map_or(true, |h| r.subject_hash == *h))
+            .filter(|r| query.category.map_or(true, |c| r.category == c))
+            .filter(|r| query.purpose.map_or(true, |p| r.purpose == p))
+            .filter(|r| query.from.map_or(true, |from| r.recorded_at >= from))
+            .filter(|r| query.to.map_or(true, |to| r.recorded_at <= to))
+            .cloned()
+            .collect()
+    }
+
+    /// Consulta tentativas de uso, incluindo as negadas
+    pub async fn query_usages(&self, query: &ConsentQuery) -> Vec<BiometricUsage> {
+        let subject_hash = query.voter_id.as_deref().map(|voter_id| self.hash_subject(voter_id));
+        let usages = self.usages.read().await;
+
+        usages
+            .iter()
+            .filter(|u| subject_hash.as_ref().map_or(true, |h| u.subject_hash == *h))
+            .filter(|u| query.category.map_or(true, |c| u.category == c))
+            .filter(|u| query.purpose.map_or(true, |p| u.purpose == p))
+            .filter(|u| query.from.map_or(true, |from| u.timestamp >= from))
+            .filter(|u| query.to.map_or(true, |to| u.timestamp <= to))
+            .cloned()
+            .collect()
+    }
+
+    /// Registros cuja retenção expirou e cujos dados devem ser eliminados
+    pub async fn expired_records(&self, now: DateTime<Utc>) -> Vec<ConsentRecord> {
+        let records = self.records.read().await;
+        records.iter().filter(|r| r.expires_at <= now).cloned().collect()
+    }
+
+    /// Gera resumo para auditoria LGPD
+    pub async fn get_audit_summary(&self) -> ConsentAuditSummary {
+        let now = Utc::now();
+        let records = self.records.read().await;
+        let usages = self.usages.read().await;
+
+        let mut records_by_purpose = HashMap::new();
+        for record in records.iter() {
+            *records_by_purpose.entry(format!("{:?}", record.purpose)).or_insert(0) += 1;
+        }
+
+        ConsentAuditSummary {
+            total_records: records.len(),
+            active_records: records.iter().filter(|r| r.revoked_at.is_none() && r.expires_at > now).count(),
+            expired_records: records.iter().filter(|r| r.expires_at <= now).count(),
+            revoked_records: records.iter().filter(|r| r.revoked_at.is_some()).count(),
+            records_by_purpose,
+            total_usages: usages.len(),
+            denied_usages: usages.iter().filter(|u| !u.allowed).count(),
+            generated_at: now,
+        }
+    }
+
+    async fn evaluate(
+        &self,
+        subject_hash: &str,
+        category: BiometricCategory,
+        purpose: ProcessingPurpose,
+    ) -> Result<PurposeDeclaration> {
+        let declaration = self.find_declaration(category, purpose).await
+            .ok_or_else(|| anyhow!("Biometric {:?} may not be used for undeclared purpose {:?}", category, purpose))?;
+
+        if declaration.legal_basis == LegalBasis::ExplicitConsent {
+            let now = Utc::now();
+            let records = self.records.read().await;
+            let has_consent = records.iter().any(|r| {
+                r.subject_hash == subject_hash
+                    && r.category == category
+                    && r.purpose == purpose
+                    && r.legal_basis == LegalBasis::ExplicitConsent
+                    && r.revoked_at.is_none()
+                    && r.expires_at > now
+            });
+            if !has_consent {
+                return Err(anyhow!("No active consent for {:?}/{:?}", category, purpose));
+            }
+        }
+
+        Ok(declaration)
+    }
+
+    async fn find_declaration(
+        &self,
+        category: BiometricCategory,
+        purpose: ProcessingPurpose,
+    ) -> Option<PurposeDeclaration> {
+        let declarations = self.declarations.read().await;
+        declarations
+            .iter()
+            .find(|d| d.category == category && d.purpose == purpose)
+            .cloned()
+    }
+
+    async fn append_record(
+        &self,
+        voter_id: &str,
+        declaration: &PurposeDeclaration,
+        interaction_id: &str,
+        source: &str,
+    ) -> ConsentRecord {
+        let now = Utc::now();
+        let record = ConsentRecord {
+            id: Uuid::new_v4().to_string(),
+            subject_hash: self.hash_subject(voter_id),
+            category: declaration.category,
+            purpose: declaration.purpose,
+            legal_basis: declaration.legal_basis,
+            interaction_id: interaction_id.to_string(),
+            source: source.to_string(),
+            recorded_at: now,
+            expires_at: now + Duration::days(declaration.retention_days),
+            revoked_at: None,
+        };
+
+        self.records.write().await.push(record.clone());
+        record
+    }
+
+    fn hash_subject(&self, voter_id: &str) -> String {
+        hex::encode(hmac::sign(&self.subject_key, voter_id.as_bytes()).as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_declared_purpose_is_recorded() {
+        let ledger = ConsentLedger::new(b"test-secret");
+
+        let record = ledger.authorize_use(
+            "voter-1",
+            BiometricCategory::Fingerprint,
+            ProcessingPurpose::VoterAuthentication,
+            "session-1",
+            "urna-001",
+        ).await.unwrap();
+
+        assert_eq!(record.legal_basis, LegalBasis::LegalObligation);
+        assert_ne!(record.subject_hash, "voter-1");
+        // Pseudônimo depende da chave: outro backend não o reproduz
+        assert_ne!(record.subject_hash, ConsentLedger::new(b"other-secret").hash_subject("voter-1"));
+
+        let query = ConsentQuery { voter_id: Some("voter-1".to_string()), ..Default::default() };
+        assert_eq!(ledger.query_records(&query).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_undeclared_purpose_is_blocked() {
+        let ledger = ConsentLedger::new(b"test-secret");
+
+        let result = ledger.authorize_use(
+            "voter-1",
+            BiometricCategory::FacialImage,
+            ProcessingPurpose::FraudInvestigation,
+            "session-1",
+            "backoffice",
+        ).await;
+
+        assert!(result.is_err());
+        let summary = ledger.get_audit_summary().await;
+        assert_eq!(summary.total_records, 0);
+        assert_eq!(summary.denied_usages, 1);
+    }
+
+    #[tokio::test]
+    async fn test_explicit_consent_required_and_revocable() {
+        let mut declarations = ConsentLedger::default_declarations();
+        declarations.push(PurposeDeclaration {
+            category: BiometricCategory::FacialImage,
+            purpose: ProcessingPurpose::Audit,
+            legal_basis: LegalBasis::ExplicitConsent,
+            retention_days: 30,
+            description: "Auditoria com participação voluntária".to_string(),
+        });
+        let ledger = ConsentLedger::with_declarations(declarations, b"test-secret");
+        let (category, purpose) = (BiometricCategory::FacialImage, ProcessingPurpose::Audit);
+
+        assert!(ledger.authorize_use("voter-2", category, purpose, "s1", "audit").await.is_err());
+
+        ledger.grant_consent("voter-2", category, purpose, "s1", "audit").await.unwrap();
+        assert!(ledger.authorize_use("voter-2", category, purpose, "s1", "audit").await.is_ok());
+
+        ledger.revoke_consent("voter-2", category, purpose).await.unwrap();
+        assert!(ledger.authorize_use("voter-2", category, purpose, "s2", "audit").await.is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Vigilância de expiração de certificados e chaves
+//!
+//! Mantém o inventário de credenciais do sistema (certificados das urnas,
+//! certificados TLS, chaves dos verificadores e chaves de assinatura JWT),
+//! calcula o horizonte de expiração de cada uma, emite alertas ao cruzar os
+//! prazos de antecedência configurados e impede a abertura de eleição quando
+//! uma credencial crítica expiraria durante a janela de votação.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::monitoring::{AlertSeverity, MonitoringSystem};
+
+/// Tipo de credencial
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialKind {
+    UrnaDeviceCert,
+    TlsCert,
+    VerifierKey,
+    JwtSigningKey,
+    PackageSigningKey,
+    Other,
+}
+
+/// Credencial inventariada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credential {
+    pub id: String,
+    pub kind: CredentialKind,
+    pub subject: String,
+    pub fingerprint: Option<String>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    /// Credenciais críticas bloqueiam a abertura da eleição
+    pub critical: bool,
+    pub source: String,
+}
+
+/// Horizonte de expiração
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "state", content = "lead_time_days")]
+pub enum ExpiryHorizon {
+    Valid,
+    /// Dentro do prazo de antecedência indicado
+    Expiring(i64),
+    Expired,
+    NotYetValid,
+}
+
+/// Situação de expiração de uma credencial
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiryStatus {
+    pub credential: Credential,
+    pub days_remaining: i64,
+    pub horizon: ExpiryHorizon,
+}
+
+/// Política de alertas
+#[derive(Debug, Clone)]
+pub struct ExpiryPolicy {
+    /// Prazos de antecedência em dias (ex.: 90, 30, 7, 1)
+    pub lead_times_days: Vec<i64>,
+    pub check_interval_seconds: u64,
+}
+
+/// Vigilante de expiração de credenciais
+#[derive(Clone)]
+pub struct CredentialWatchdog {
+    policy: ExpiryPolicy,
+    credentials: Arc<RwLock<HashMap<String, Credential>>>,
+    /// Menor prazo já alertado por credencial, para não repetir alertas
+    alerted: Arc<RwLock<HashMap<String, i64>>>,
+    monitoring: Option<Arc<MonitoringSystem>>,
+}
+
+impl CredentialWatchdog {
+    pub fn new(mut policy: ExpiryPolicy) -> Self {
+        policy.lead_times_days.sort_unstable_by(|a, b| b.cmp(a));
+        policy.lead_times_days.dedup();
+        Self {
+            policy,
+            credentials: Arc::new(RwLock::new(HashMap::new())),
+            alerted: Arc::new(RwLock::new(HashMap::new())),
+            monitoring: None,
+        }
+    }
+
+    /// Envia alertas pelo sistema de monitoramento
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringSystem>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
+    }
+
+    /// Verifica periodicamente o inventário
+    pub fn start(&self) {
+        let watchdog = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(
+                std::time::Duration::from_secs(watchdog.policy.check_interval_seconds),
+            );
+            loop {
+                interval.tick().await;
+                if let Err(e) = watchdog.check().await {
+                    log::error!("Erro na verificação de credenciais: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Registra ou substitui credencial (ex.: após renovação)
+    pub async fn register(&self, credential: Credential) -> Result<()> {
+        if credential.not_after <= credential.not_before {
+            return Err(anyhow!("Credential {} has an empty validity period", credential.id));
+        }
+        self.alerted.write().await.remove(&credential.id);
+        self.credentials.write().await.insert(credential.id.clone(), credential);
+        Ok(())
+    }
+
+    pub async fn remove(&self, credential_id: &str) -> Option<Credential> {
+        self.alerted.write().await.remove(credential_id);
+        self.credentials.write().await.remove(credential_id)
+    }
+
+    /// Inventário ordenado pela data de expiração
+    pub async fn inventory(&self) -> Vec<ExpiryStatus> {
+        let now = Utc::now();
+        let credentials = self.credentials.read().await;
+        let mut statuses: Vec<ExpiryStatus> = credentials
+            .values()
+            .map(|c| self.status(c, now))
+            .collect();
+        statuses.sort_by(|a, b| a.credential.not_after.cmp(&b.credential.not_after));
+        statuses
+    }
+
+    /// Emite alertas para credenciais que cruzaram um novo prazo
+    pub async fn check(&self) -> Result<Vec<ExpiryStatus>> {
+        let mut newly_alerted = Vec::new();
+
+        for status in self.inventory().await {
+            let threshold = match status.horizon {
+                ExpiryHorizon::Expiring(lead) => lead,
+                ExpiryHorizon::Expired => 0,
+                ExpiryHorizon::Valid | ExpiryHorizon::NotYetValid => continue,
+            };
+
+            {
+                let mut alerted = self.alerted.write().await;
+                if alerted.get(&status.credential.id).is_some_and(|last| *last <= threshold) {
+                    continue;
+                }
+                alerted.insert(status.credential.id.clone(), threshold);
+            }
+
+            let severity = match status.horizon {
+                ExpiryHorizon::Expired => AlertSeverity::Critical,
+                _ if status.days_remaining <= 1 => AlertSeverity::Critical,
+                _ if status.days_remaining <= 7 => AlertSeverity::Error,
+                _ => AlertSeverity::Warning,
+            };
+            let message = format!(
+                "Credencial {} ({:?}, {}) expira em {} dia(s)",
+                status.credential.id, status.credential.kind, status.credential.subject, status.days_remaining
+            );
+            log::warn!("{}", message);
+            if let Some(monitoring) = &self.monitoring {
+                monitoring.create_alert(severity, "credentials", &message).await?;
+            }
+            newly_alerted.push(status);
+        }
+
+        Ok(newly_alerted)
+    }
+
+    /// Registra a credencial com as datas gravadas em `record_path` quando a
+    /// impressão digital for a mesma; uma credencial nova (ou trocada) tem as
+    /// datas informadas gravadas, e as próximas inicializações as reaproveitam
+    pub async fn register_recorded(&self, record_path: &Path, credential: Credential) -> Result<Credential> {
+        if credential.fingerprint.is_none() {
+            return Err(anyhow!("Credential {} needs a fingerprint to be recorded", credential.id));
+        }
+
+        let recorded = match tokio::fs::read(record_path).await {
+            Ok(bytes) => Some(serde_json::from_slice::<Credential>(&bytes)
+                .map_err(|e| anyhow!("Invalid credential record {}: {}", record_path.display(), e))?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(anyhow!("Cannot read credential record {}: {}", record_path.display(), e)),
+        };
+
+        let credential = match recorded {
+            Some(recorded) if recorded.id == credential.id && recorded.fingerprint == credential.fingerprint => recorded,
+            _ => {
+                if let Some(dir) = record_path.parent() {
+                    tokio::fs::create_dir_all(dir).await?;
+                }
+                tokio::fs::write(record_path, serde_json::to_vec_pretty(&credential)?).await?;
+                log::info!("Credential {} recorded, valid until {}", credential.id, credential.not_after.to_rfc3339());
+                credential
+            }
+        };
+        self.register(credential.clone()).await?;
+        Ok(credential)
+    }
+
+    /// Credenciais críticas que não cobrem toda a janela de votação
+    pub async fn blocking_credentials(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<Credential> {
+        let credentials = self.credentials.read().await;
+        let mut blocking: Vec<Credential> = credentials
+            .values()
+            .filter(|c| c.critical && (c.not_before > start || c.not_after < end))
+            .cloned()
+            .collect();
+        blocking.sort_by(|a, b| a.not_after.cmp(&b.not_after));
+        blocking
+    }
+
+    /// Falha se alguma credencial crítica não cobrir a janela de votação
+    pub async fn ensure_valid_for_window(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<()> {
+        let blocking = self.blocking_credentials(start, end).await;
+        if blocking.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<String> = blocking
+            .iter()
+            .map(|c| format!("{} (expires {})", c.id, c.not_after.to_rfc3339()))
+            .collect();
+        Err(anyhow!(
+            "Critical credentials do not cover the voting window: {}",
+            ids.join(", ")
+        ))
+    }
+
+    fn status(&self, credential: &Credential, now: DateTime<Utc>) -> ExpiryStatus {
+        let days_remaining = (credential.not_after - now).num_days();
+        let horizon = if credential.not_after <= now {
+            ExpiryHorizon::Expired
+        } else if credential.not_before > now {
+            ExpiryHorizon::NotYetValid
+        } else {
+            // Menor prazo de antecedência já atingido
+            self.policy.lead_times_days
+                .iter()
+                .rev()
+                .find(|lead| days_remaining < **lead)
+                .map(|lead| ExpiryHorizon::Expiring(*lead))
+                .unwrap_or(ExpiryHorizon::Valid)
+        };
+
+        ExpiryStatus {
+            credential: credential.clone(),
+            days_remaining,
+            horizon,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn credential(id: &str, days: i64, critical: bool) -> Credential {
+        Credential {
+            id: id.to_string(),
+            kind: CredentialKind::UrnaDeviceCert,
+            subject: format!("CN={}", id),
+            fingerprint: None,
+            not_before: Utc::now() - Duration::days(365),
+            not_after: Utc::now() + Duration::days(days) + Duration::hours(1),
+            critical,
+            source: "test".to_string(),
+        }
+    }
+
+    fn watchdog() -> CredentialWatchdog {
+        CredentialWatchdog::new(ExpiryPolicy {
+            lead_times_days: vec![7, 90, 30],
+            check_interval_seconds: 3600,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_alerts_once_per_lead_time() {
+        let watchdog = watchdog();
+        watchdog.register(credential("urna-1", 20, true)).await.unwrap();
+        watchdog.register(credential("tls-1", 200, false)).await.unwrap();
+
+        let alerted = watchdog.check().await.unwrap();
+        assert_eq!(alerted.len(), 1);
+        assert_eq!(alerted[0].horizon, ExpiryHorizon::Expiring(30));
+        assert!(watchdog.check().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocks_window_when_critical_credential_expires() {
+        let watchdog = watchdog();
+        watchdog.register(credential("urna-1", 2, true)).await.unwrap();
+        watchdog.register(credential("tls-1", 1, false)).await.unwrap();
+
+        let start = Utc::now() + Duration::days(1);
+        let end = start + Duration::days(3);
+        let blocking = watchdog.blocking_credentials(start, end).await;
+
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].id, "urna-1");
+        assert!(watchdog.ensure_valid_for_window(start, start + Duration::hours(10)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_recorded_credential_keeps_its_dates_until_the_key_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt-signing-key.json");
+        let watchdog = watchdog();
+
+        let mut issued = credential("jwt", 60, true);
+        issued.fingerprint = Some("key-1".to_string());
+        watchdog.register_recorded(&path, issued.clone()).await.unwrap();
+
+        // Reinício com a mesma chave: as datas gravadas prevalecem
+        let mut restarted = credential("jwt", 90, true);
+        restarted.fingerprint = Some("key-1".to_string());
+        let kept = watchdog.register_recorded(&path, restarted).await.unwrap();
+        assert_eq!(kept.not_after, issued.not_after);
+        assert_eq!(watchdog.inventory().await[0].credential.not_after, issued.not_after);
+
+        // Chave trocada: novas datas gravadas
+        let mut rotated = credential("jwt", 90, true);
+        rotated.fingerprint = Some("key-2".to_string());
+        let renewed = watchdog.register_recorded(&path, rotated.clone()).await.unwrap();
+        assert_eq!(renewed.not_after, rotated.not_after);
+
+        let mut unfingerprinted = credential("jwt", 90, true);
+        unfingerprinted.fingerprint = None;
+        assert!(watchdog.register_recorded(&path, unfingerprinted).await.is_err());
+    }
+}
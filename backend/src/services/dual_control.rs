@@ -0,0 +1,248 @@
+//! Controle duplo para operações administrativas destrutivas
+//!
+//! As rotas listadas em `DualControlConfig` não executam no primeiro
+//! pedido: o middleware registra o pedido pendente, com o hash do corpo, e
+//! responde 202. Outro sujeito com a mesma permissão aprova dentro do
+//! prazo, e quem pediu reenvia a mesma requisição com o cabeçalho
+//! `X-Dual-Control-Approval`. A aprovação vale para uma única execução do
+//! mesmo método, caminho e corpo; vencido o prazo, o pedido expira.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::auth::rbac::{Permission, Principal};
+use crate::config::{DualControlConfig, DualControlRule};
+
+/// Situação do pedido
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DualControlStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Executed,
+    Expired,
+}
+
+/// Pedido de operação sob controle duplo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DualControlRequest {
+    pub id: String,
+    /// Regra que protege a rota (`DELETE /api/v1/elections/{id}`)
+    pub operation: String,
+    pub method: String,
+    pub path: String,
+    pub body_sha256: String,
+    pub permission: Permission,
+    pub initiator: String,
+    pub approver: Option<String>,
+    pub status: DualControlStatus,
+    pub requested_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+impl DualControlRequest {
+    fn matches(&self, method: &str, path: &str, body: &[u8]) -> bool {
+        self.method.eq_ignore_ascii_case(method) && self.path == path && self.body_sha256 == body_hash(body)
+    }
+}
+
+/// Serviço de controle duplo
+pub struct DualControlService {
+    rules: Vec<DualControlRule>,
+    window: Duration,
+    requests: RwLock<HashMap<String, DualControlRequest>>,
+}
+
+impl DualControlService {
+    pub fn new(config: &DualControlConfig) -> Self {
+        Self {
+            rules: config.operations.clone(),
+            window: Duration::minutes(config.approval_window_minutes),
+            requests: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Regra que protege a rota, se houver
+    pub fn rule_for(&self, method: &str, path: &str) -> Option<&DualControlRule> {
+        self.rules.iter().find(|rule| rule.method.eq_ignore_ascii_case(method) && path_matches(&rule.path, path))
+    }
+
+    /// Registra o pedido; quem pede já precisa ter a permissão da regra
+    pub async fn open(&self, rule: &DualControlRule, method: &str, path: &str, body: &[u8], initiator: &str) -> Result<DualControlRequest> {
+        let now = Utc::now();
+        let request = DualControlRequest {
+            id: Uuid::new_v4().to_string(),
+            operation: format!("{} {}", rule.method.to_uppercase(), rule.path),
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            body_sha256: body_hash(body),
+            permission: rule.permission,
+            initiator: initiator.to_string(),
+            approver: None,
+            status: DualControlStatus::Pending,
+            requested_at: now,
+            expires_at: now + self.window,
+            decided_at: None,
+            executed_at: None,
+        };
+        log::info!("{} requested {} (dual control {})", initiator, request.operation, request.id);
+        self.requests.write().await.insert(request.id.clone(), request.clone());
+        Ok(request)
+    }
+
+    /// Segunda aprovação, de outro sujeito com a mesma permissão
+    pub async fn approve(&self, id: &str, principal: &Principal) -> Result<DualControlRequest> {
+        self.decide(id, principal, DualControlStatus::Approved).await
+    }
+
+    /// Recusa do pedido por outro sujeito com a mesma permissão
+    pub async fn reject(&self, id: &str, principal: &Principal) -> Result<DualControlRequest> {
+        self.decide(id, principal, DualControlStatus::Rejected).await
+    }
+
+    async fn decide(&self, id: &str, principal: &Principal, status: DualControlStatus) -> Result<DualControlRequest> {
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(id).ok_or_else(|| anyhow!("Dual control request {} not found", id))?;
+        let now = Utc::now();
+        expire(request, now);
+        if request.status != DualControlStatus::Pending {
+            return Err(anyhow!("Dual control request {} is {:?}", id, request.status));
+        }
+        let subject = principal.require(request.permission).map_err(|e| anyhow!("{}", e))?;
+        if subject == request.initiator {
+            return Err(anyhow!("The initiator cannot approve their own request"));
+        }
+        request.status = status;
+        request.approver = Some(subject.to_string());
+        request.decided_at = Some(now);
+        log::info!("{} {:?} dual control {} ({})", subject, status, id, request.operation);
+        Ok(request.clone())
+    }
+
+    /// Libera uma única execução do pedido aprovado, pelo mesmo sujeito e com a mesma requisição
+    pub async fn consume(&self, id: &str, subject: &str, method: &str, path: &str, body: &[u8]) -> Result<DualControlRequest> {
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(id)
+            .filter(|r| r.initiator == subject)
+            .ok_or_else(|| anyhow!("Dual control request {} not found", id))?;
+        let now = Utc::now();
+        expire(request, now);
+        if request.status != DualControlStatus::Approved {
+            return Err(anyhow!("Dual control request {} is {:?}", id, request.status));
+        }
+        if !request.matches(method, path, body) {
+            return Err(anyhow!("Request differs from the one approved in {}", id));
+        }
+        request.status = DualControlStatus::Executed;
+        request.executed_at = Some(now);
+        Ok(request.clone())
+    }
+
+    pub async fn get(&self, id: &str) -> Option<DualControlRequest> {
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(id)?;
+        expire(request, Utc::now());
+        Some(request.clone())
+    }
+
+    /// Pedidos, dos mais recentes para os mais antigos
+    pub async fn list(&self, status: Option<DualControlStatus>) -> Vec<DualControlRequest> {
+        let now = Utc::now();
+        let mut requests = self.requests.write().await;
+        let mut result: Vec<DualControlRequest> = requests.values_mut()
+            .map(|request| {
+                expire(request, now);
+                request.clone()
+            })
+            .filter(|request| status.map_or(true, |status| request.status == status))
+            .collect();
+        result.sort_by(|a, b| b.requested_at.cmp(&a.requested_at));
+        result
+    }
+}
+
+fn expire(request: &mut DualControlRequest, now: DateTime<Utc>) {
+    let open = matches!(request.status, DualControlStatus::Pending | DualControlStatus::Approved);
+    if open && now >= request.expires_at {
+        request.status = DualControlStatus::Expired;
+    }
+}
+
+fn body_hash(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+/// Compara o caminho com o padrão, segmento a segmento
+pub(crate) fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_end_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    pattern.len() == path.len() && pattern.iter().zip(&path).all(|(expected, actual)| {
+        (expected.starts_with('{') && expected.ends_with('}') && !actual.is_empty()) || expected == actual
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::{Role, RoleGrant};
+
+    fn admin(subject: &str) -> Principal {
+        Principal {
+            subject: Some(subject.to_string()),
+            grants: vec![RoleGrant { role: Role::TseAdmin, election_id: None, region: None }],
+        }
+    }
+
+    fn service(window_minutes: i64) -> DualControlService {
+        DualControlService::new(&DualControlConfig {
+            approval_window_minutes: window_minutes,
+            operations: vec![DualControlRule {
+                method: "DELETE".to_string(),
+                path: "/api/v1/elections/{id}".to_string(),
+                permission: Permission::ManageElections,
+            }],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_second_distinct_approver_releases_one_execution() {
+        let service = service(30);
+        assert!(service.rule_for("GET", "/api/v1/elections/42").is_none());
+        assert!(service.rule_for("delete", "/api/v1/elections/42/candidates/1").is_none());
+        let rule = service.rule_for("delete", "/api/v1/elections/42").unwrap().clone();
+
+        let request = service.open(&rule, "DELETE", "/api/v1/elections/42", b"", "ana").await.unwrap();
+        assert!(service.consume(&request.id, "ana", "DELETE", "/api/v1/elections/42", b"").await.is_err());
+        assert!(service.approve(&request.id, &admin("ana")).await.is_err());
+        let observer = Principal {
+            subject: Some("bia".to_string()),
+            grants: vec![RoleGrant { role: Role::Observer, election_id: None, region: None }],
+        };
+        assert!(service.approve(&request.id, &observer).await.is_err());
+
+        let approved = service.approve(&request.id, &admin("carla")).await.unwrap();
+        assert_eq!(approved.approver.as_deref(), Some("carla"));
+        assert!(service.consume(&request.id, "carla", "DELETE", "/api/v1/elections/42", b"").await.is_err());
+        assert!(service.consume(&request.id, "ana", "DELETE", "/api/v1/elections/43", b"").await.is_err());
+        service.consume(&request.id, "ana", "DELETE", "/api/v1/elections/42", b"").await.unwrap();
+        assert!(service.consume(&request.id, "ana", "DELETE", "/api/v1/elections/42", b"").await.is_err());
+        assert_eq!(service.get(&request.id).await.unwrap().status, DualControlStatus::Executed);
+    }
+
+    #[tokio::test]
+    async fn test_request_expires_after_window() {
+        let service = service(0);
+        let rule = service.rule_for("DELETE", "/api/v1/elections/42").unwrap().clone();
+        let request = service.open(&rule, "DELETE", "/api/v1/elections/42", b"", "ana").await.unwrap();
+        assert!(service.approve(&request.id, &admin("carla")).await.is_err());
+        assert_eq!(service.list(Some(DualControlStatus::Expired)).await.len(), 1);
+    }
+}
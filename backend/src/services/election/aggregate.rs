@@ -0,0 +1,266 @@
+//! Agregado da eleição reconstruído a partir dos eventos
+//!
+//! Cada mudança na eleição é um `ElectionDomainEvent` numerado na sequência
+//! da eleição. O estado é a dobra dos eventos sobre o último snapshot, e o
+//! mesmo evento vai para o log transparente em `data`, com o nome do
+//! agregado e a versão, o que permite reconstruir tudo a partir do log.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{ElectionStatus, StatusTransition};
+use crate::database::Election;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType};
+
+/// Nome do agregado em `data.aggregate` dos eventos do log
+pub const ELECTION_AGGREGATE: &str = "election";
+
+/// Mudança na eleição
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ElectionDomainEvent {
+    Created {
+        name: String,
+        description: Option<String>,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        actor: String,
+    },
+    Updated {
+        name: String,
+        description: Option<String>,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    },
+    StatusChanged {
+        from: ElectionStatus,
+        to: ElectionStatus,
+        actor: String,
+        reason: Option<String>,
+    },
+    Deleted,
+}
+
+impl ElectionDomainEvent {
+    /// Tipo do evento no log transparente
+    pub fn event_type(&self) -> ElectionEventType {
+        match self {
+            ElectionDomainEvent::Created { .. } => ElectionEventType::ElectionCreated,
+            ElectionDomainEvent::Updated { .. } => ElectionEventType::ElectionUpdated,
+            ElectionDomainEvent::StatusChanged { to, .. } => to.event_type(),
+            ElectionDomainEvent::Deleted => ElectionEventType::ElectionDeleted,
+        }
+    }
+}
+
+/// Evento gravado na sequência da eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub election_id: Uuid,
+    /// Posição na sequência da eleição, a partir de 1
+    pub version: u64,
+    pub event: ElectionDomainEvent,
+    pub recorded_at: DateTime<Utc>,
+    pub log_index: Option<u64>,
+}
+
+impl RecordedEvent {
+    /// `data` do evento no log transparente
+    pub fn log_data(&self) -> serde_json::Value {
+        serde_json::json!({
+            "aggregate": ELECTION_AGGREGATE,
+            "version": self.version,
+            "event": self.event,
+        })
+    }
+
+    /// Evento do agregado lido do log; `None` para eventos de outras origens
+    pub fn from_log(event: &ElectionEvent, log_index: u64) -> Option<Result<Self>> {
+        if event.data.get("aggregate")?.as_str()? != ELECTION_AGGREGATE {
+            return None;
+        }
+        Some(Self::parse_log(event, log_index))
+    }
+
+    fn parse_log(event: &ElectionEvent, log_index: u64) -> Result<Self> {
+        let election_id = Uuid::parse_str(&event.election_id)
+            .map_err(|_| anyhow!("Invalid election id in log entry {}", log_index))?;
+        let version = event.data.get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("Missing event version in log entry {}", log_index))?;
+        let domain_event = serde_json::from_value(event.data.get("event").cloned().unwrap_or_default())
+            .map_err(|e| anyhow!("Invalid election event in log entry {}: {}", log_index, e))?;
+        Ok(Self {
+            election_id,
+            version,
+            event: domain_event,
+            recorded_at: event.timestamp,
+            log_index: Some(log_index),
+        })
+    }
+}
+
+/// Estado da eleição na versão indicada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionAggregate {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub status: ElectionStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub transitions: Vec<StatusTransition>,
+    pub deleted: bool,
+    pub version: u64,
+}
+
+/// Estado congelado para não reaplicar a sequência inteira
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionSnapshot {
+    pub state: ElectionAggregate,
+    pub taken_at: DateTime<Utc>,
+}
+
+impl ElectionAggregate {
+    /// Estado a partir do snapshot (se houver) e dos eventos posteriores a ele
+    pub fn replay(snapshot: Option<&ElectionSnapshot>, events: &[RecordedEvent]) -> Result<Option<Self>> {
+        let mut state = snapshot.map(|s| s.state.clone());
+        let from = state.as_ref().map_or(0, |s| s.version);
+        for recorded in events.iter().filter(|e| e.version > from) {
+            match &mut state {
+                Some(state) => state.apply(recorded)?,
+                None => state = Some(Self::created(recorded)?),
+            }
+        }
+        Ok(state)
+    }
+
+    fn created(recorded: &RecordedEvent) -> Result<Self> {
+        let ElectionDomainEvent::Created { name, description, start_date, end_date, .. } = &recorded.event else {
+            return Err(anyhow!("Election {} stream does not start with its creation", recorded.election_id));
+        };
+        if recorded.version != 1 {
+            return Err(anyhow!("Election {} created at version {}", recorded.election_id, recorded.version));
+        }
+        Ok(Self {
+            id: recorded.election_id,
+            name: name.clone(),
+            description: description.clone(),
+            start_date: *start_date,
+            end_date: *end_date,
+            status: ElectionStatus::Draft,
+            created_at: recorded.recorded_at,
+            updated_at: recorded.recorded_at,
+            transitions: Vec::new(),
+            deleted: false,
+            version: 1,
+        })
+    }
+
+    /// Aplica o próximo evento da sequência
+    pub fn apply(&mut self, recorded: &RecordedEvent) -> Result<()> {
+        if recorded.version != self.version + 1 {
+            return Err(anyhow!(
+                "Election {} expected event version {}, got {}",
+                self.id, self.version + 1, recorded.version
+            ));
+        }
+        if self.deleted {
+            return Err(anyhow!("Election {} has been deleted", self.id));
+        }
+
+        match &recorded.event {
+            ElectionDomainEvent::Created { .. } => {
+                return Err(anyhow!("Election {} created twice", self.id));
+            }
+            ElectionDomainEvent::Updated { name, description, start_date, end_date } => {
+                self.name = name.clone();
+                self.description = description.clone();
+                self.start_date = *start_date;
+                self.end_date = *end_date;
+            }
+            ElectionDomainEvent::StatusChanged { from, to, actor, reason } => {
+                if *from != self.status {
+                    return Err(anyhow!(
+                        "Election {} transition from {} while in {}",
+                        self.id, from.as_str(), self.status.as_str()
+                    ));
+                }
+                self.status = *to;
+                self.transitions.push(StatusTransition {
+                    election_id: self.id,
+                    from: *from,
+                    to: *to,
+                    actor: actor.clone(),
+                    reason: reason.clone(),
+                    transitioned_at: recorded.recorded_at,
+                    log_index: recorded.log_index,
+                });
+            }
+            ElectionDomainEvent::Deleted => self.deleted = true,
+        }
+        self.updated_at = recorded.recorded_at;
+        self.version = recorded.version;
+        Ok(())
+    }
+
+    /// Eleição no formato da projeção do banco
+    pub fn to_election(&self) -> Election {
+        Election {
+            id: self.id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            start_date: self.start_date,
+            end_date: self.end_date,
+            status: self.status.as_str().to_string(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn recorded(version: u64, event: ElectionDomainEvent) -> RecordedEvent {
+        RecordedEvent { election_id: Uuid::nil(), version, event, recorded_at: Utc::now(), log_index: None }
+    }
+
+    #[test]
+    fn test_replay_rejects_gaps_and_applies_snapshot_tail() {
+        let created = recorded(1, ElectionDomainEvent::Created {
+            name: "Eleições Gerais".to_string(),
+            description: None,
+            start_date: Utc::now() + Duration::days(1),
+            end_date: Utc::now() + Duration::days(2),
+            actor: "admin".to_string(),
+        });
+        let scheduled = recorded(2, ElectionDomainEvent::StatusChanged {
+            from: ElectionStatus::Draft,
+            to: ElectionStatus::Scheduled,
+            actor: "admin".to_string(),
+            reason: None,
+        });
+        let events = vec![created.clone(), scheduled.clone()];
+
+        let state = ElectionAggregate::replay(None, &events).unwrap().unwrap();
+        assert_eq!((state.status, state.version), (ElectionStatus::Scheduled, 2));
+
+        let snapshot = ElectionSnapshot {
+            state: ElectionAggregate::replay(None, &events[..1]).unwrap().unwrap(),
+            taken_at: Utc::now(),
+        };
+        let from_snapshot = ElectionAggregate::replay(Some(&snapshot), &events).unwrap().unwrap();
+        assert_eq!(from_snapshot.transitions.len(), 1);
+
+        assert!(ElectionAggregate::replay(None, &[scheduled.clone()]).is_err());
+        let gap = recorded(3, ElectionDomainEvent::Deleted);
+        assert!(ElectionAggregate::replay(None, &[created, gap]).is_err());
+    }
+}
@@ -0,0 +1,568 @@
+//! Serviço de eleições do FORTIS
+//!
+//! Gerencia o ciclo de vida da eleição como máquina de estados
+//! (Rascunho → Agendada → Ativa → Encerrada → Finalizada → Auditada).
+//!
+//! A eleição é um agregado com fonte de eventos: criação, edição,
+//! transições e remoção viram eventos numerados, gravados primeiro no log
+//! transparente e só então aceitos. O estado é reconstruído do último
+//! snapshot (tirado a cada `snapshot_interval` eventos) mais os eventos
+//! posteriores, e o banco de dados é apenas uma projeção atualizada depois
+//! de cada evento. Na inicialização, `replay_log` refaz as sequências a
+//! partir do log e `rebuild_projection` realinha o banco.
+
+pub mod aggregate;
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+pub use aggregate::{ElectionAggregate, ElectionDomainEvent, ElectionSnapshot, RecordedEvent};
+
+use crate::database::{self, Election};
+use crate::models::CreateElectionRequest;
+use crate::services::credentials::CredentialWatchdog;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Eventos entre dois snapshots da mesma eleição
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 20;
+
+/// Status do ciclo de vida da eleição
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ElectionStatus {
+    Draft,
+    Scheduled,
+    Active,
+    Closed,
+    Finalized,
+    Audited,
+}
+
+impl ElectionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ElectionStatus::Draft => "draft",
+            ElectionStatus::Scheduled => "scheduled",
+            ElectionStatus::Active => "active",
+            ElectionStatus::Closed => "closed",
+            ElectionStatus::Finalized => "finalized",
+            ElectionStatus::Audited => "audited",
+        }
+    }
+
+    /// Transições permitidas; apenas eleições agendadas podem voltar a rascunho
+    pub fn can_transition_to(&self, next: ElectionStatus) -> bool {
+        matches!(
+            (self, next),
+            (ElectionStatus::Draft, ElectionStatus::Scheduled)
+                | (ElectionStatus::Scheduled, ElectionStatus::Draft)
+                | (ElectionStatus::Scheduled, ElectionStatus::Active)
+                | (ElectionStatus::Active, ElectionStatus::Closed)
+                | (ElectionStatus::Closed, ElectionStatus::Finalized)
+                | (ElectionStatus::Finalized, ElectionStatus::Audited)
+        )
+    }
+
+    /// Evento do log transparente ao entrar neste status
+    pub fn event_type(&self) -> ElectionEventType {
+        match self {
+            ElectionStatus::Draft => ElectionEventType::SystemEvent,
+            ElectionStatus::Scheduled => ElectionEventType::ElectionScheduled,
+            ElectionStatus::Active => ElectionEventType::ElectionStarted,
+            ElectionStatus::Closed => ElectionEventType::ElectionEnded,
+            ElectionStatus::Finalized => ElectionEventType::ElectionFinalized,
+            ElectionStatus::Audited => ElectionEventType::ElectionAudited,
+        }
+    }
+}
+
+impl FromStr for ElectionStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "draft" => Ok(ElectionStatus::Draft),
+            "scheduled" => Ok(ElectionStatus::Scheduled),
+            "active" => Ok(ElectionStatus::Active),
+            "closed" => Ok(ElectionStatus::Closed),
+            "finalized" => Ok(ElectionStatus::Finalized),
+            "audited" => Ok(ElectionStatus::Audited),
+            other => Err(anyhow!("Unknown election status: {}", other)),
+        }
+    }
+}
+
+/// Registro de transição de status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub election_id: Uuid,
+    pub from: ElectionStatus,
+    pub to: ElectionStatus,
+    pub actor: String,
+    pub reason: Option<String>,
+    pub transitioned_at: DateTime<Utc>,
+    pub log_index: Option<u64>,
+}
+
+/// Eventos de uma eleição e o último snapshot
+#[derive(Debug, Default)]
+struct EventStream {
+    events: Vec<RecordedEvent>,
+    snapshot: Option<ElectionSnapshot>,
+}
+
+impl EventStream {
+    fn state(&self) -> Result<Option<ElectionAggregate>> {
+        ElectionAggregate::replay(self.snapshot.as_ref(), &self.events)
+    }
+}
+
+pub struct ElectionService {
+    streams: RwLock<HashMap<Uuid, EventStream>>,
+    snapshot_interval: u64,
+    pool: Option<PgPool>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+    credential_watchdog: Option<CredentialWatchdog>,
+}
+
+impl ElectionService {
+    pub fn new() -> Self {
+        Self {
+            streams: RwLock::new(HashMap::new()),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            pool: None,
+            transparency_log: None,
+            credential_watchdog: None,
+        }
+    }
+
+    /// Projeta as eleições no banco de dados
+    pub fn with_database(mut self, pool: PgPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Grava os eventos no log transparente, fonte da verdade do agregado
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Impede a abertura se credenciais críticas expirarem durante a votação
+    pub fn with_credential_watchdog(mut self, watchdog: CredentialWatchdog) -> Self {
+        self.credential_watchdog = Some(watchdog);
+        self
+    }
+
+    /// Quantidade de eventos entre snapshots
+    pub fn with_snapshot_interval(mut self, events: u64) -> Self {
+        self.snapshot_interval = events.max(1);
+        self
+    }
+
+    pub async fn create_election(&self, req: CreateElectionRequest, actor: &str) -> Result<Election> {
+        validate_request(&req)?;
+
+        let mut streams = self.streams.write().await;
+        let state = self.append(&mut streams, Uuid::new_v4(), None, ElectionDomainEvent::Created {
+            name: req.title.trim().to_string(),
+            description: req.description,
+            start_date: req.start_date,
+            end_date: req.end_date,
+            actor: actor.to_string(),
+        }).await?;
+        Ok(state.to_election())
+    }
+
+    pub async fn get_election(&self, id: Uuid) -> Result<Option<Election>> {
+        let streams = self.streams.read().await;
+        let Some(stream) = streams.get(&id) else {
+            return Ok(None);
+        };
+        Ok(stream.state()?.filter(|state| !state.deleted).map(|state| state.to_election()))
+    }
+
+    pub async fn list_elections(&self) -> Result<Vec<Election>> {
+        let streams = self.streams.read().await;
+        let mut elections = Vec::new();
+        for stream in streams.values() {
+            if let Some(state) = stream.state()?.filter(|state| !state.deleted) {
+                elections.push(state.to_election());
+            }
+        }
+        elections.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(elections)
+    }
+
+    /// Atualiza dados da eleição; permitido apenas em rascunho
+    pub async fn update_election(&self, id: Uuid, req: CreateElectionRequest) -> Result<Election> {
+        validate_request(&req)?;
+
+        let mut streams = self.streams.write().await;
+        let state = current(&streams, id)?;
+        if state.status != ElectionStatus::Draft {
+            return Err(anyhow!("Election {} can only be edited while in draft", id));
+        }
+
+        let state = self.append(&mut streams, id, Some(&state), ElectionDomainEvent::Updated {
+            name: req.title.trim().to_string(),
+            description: req.description,
+            start_date: req.start_date,
+            end_date: req.end_date,
+        }).await?;
+        Ok(state.to_election())
+    }
+
+    /// Remove eleição; permitido apenas em rascunho
+    pub async fn delete_election(&self, id: Uuid) -> Result<()> {
+        let mut streams = self.streams.write().await;
+        let state = current(&streams, id)?;
+        if state.status != ElectionStatus::Draft {
+            return Err(anyhow!("Election {} can only be deleted while in draft", id));
+        }
+
+        self.append(&mut streams, id, Some(&state), ElectionDomainEvent::Deleted).await?;
+        Ok(())
+    }
+
+    /// Aplica transição de status validando a máquina de estados
+    pub async fn transition(
+        &self,
+        id: Uuid,
+        next: ElectionStatus,
+        actor: &str,
+        reason: Option<String>,
+    ) -> Result<Election> {
+        let mut streams = self.streams.write().await;
+        let state = current(&streams, id)?;
+        let current = state.status;
+
+        if !current.can_transition_to(next) {
+            return Err(anyhow!(
+                "Invalid election transition: {} -> {}",
+                current.as_str(), next.as_str()
+            ));
+        }
+        if next == ElectionStatus::Scheduled && state.start_date <= Utc::now() {
+            return Err(anyhow!("Cannot schedule election {} with a start date in the past", id));
+        }
+        if next == ElectionStatus::Active {
+            if let Some(watchdog) = &self.credential_watchdog {
+                let opening = state.start_date.max(Utc::now());
+                watchdog.ensure_valid_for_window(opening, state.end_date).await?;
+            }
+        }
+
+        let state = self.append(&mut streams, id, Some(&state), ElectionDomainEvent::StatusChanged {
+            from: current,
+            to: next,
+            actor: actor.to_string(),
+            reason,
+        }).await?;
+        Ok(state.to_election())
+    }
+
+    /// Histórico de transições da eleição
+    pub async fn get_transitions(&self, id: Uuid) -> Vec<StatusTransition> {
+        let streams = self.streams.read().await;
+        streams.get(&id)
+            .and_then(|stream| stream.state().ok().flatten())
+            .filter(|state| !state.deleted)
+            .map(|state| state.transitions)
+            .unwrap_or_default()
+    }
+
+    /// Eventos da eleição, em ordem de versão
+    pub async fn events(&self, id: Uuid) -> Vec<RecordedEvent> {
+        self.streams.read().await.get(&id).map(|stream| stream.events.clone()).unwrap_or_default()
+    }
+
+    /// Refaz as sequências de eventos a partir do log transparente
+    ///
+    /// Devolve a quantidade de eleições reconstruídas. Uma sequência com
+    /// lacuna ou evento inválido interrompe a reconstrução.
+    pub async fn replay_log(&self) -> Result<usize> {
+        let Some(log) = &self.transparency_log else {
+            return Ok(0);
+        };
+
+        let mut rebuilt: HashMap<Uuid, EventStream> = HashMap::new();
+        for entry in log.read().await.export_log()? {
+            let event: ElectionEvent = serde_json::from_slice(&entry.event_data)?;
+            if let Some(recorded) = RecordedEvent::from_log(&event, entry.index) {
+                let recorded = recorded?;
+                rebuilt.entry(recorded.election_id).or_default().events.push(recorded);
+            }
+        }
+        for (id, stream) in rebuilt.iter_mut() {
+            stream.events.sort_by_key(|e| e.version);
+            let state = stream.state()?
+                .ok_or_else(|| anyhow!("Election {} has no events", id))?;
+            let last_snapshot = state.version - state.version % self.snapshot_interval;
+            if last_snapshot > 0 {
+                let events = &stream.events[..last_snapshot as usize];
+                stream.snapshot = ElectionAggregate::replay(None, events)?
+                    .map(|state| ElectionSnapshot { state, taken_at: Utc::now() });
+            }
+        }
+
+        let count = rebuilt.len();
+        *self.streams.write().await = rebuilt;
+        log::info!("Rebuilt {} elections from the transparency log", count);
+        Ok(count)
+    }
+
+    /// Realinha o banco com o estado reconstruído dos eventos
+    pub async fn rebuild_projection(&self) -> Result<()> {
+        let Some(pool) = &self.pool else {
+            return Ok(());
+        };
+        let streams = self.streams.read().await;
+        let rows: HashMap<Uuid, Election> = database::get_elections(pool).await?
+            .into_iter()
+            .map(|row| (row.id, row))
+            .collect();
+
+        for stream in streams.values() {
+            let Some(state) = stream.state()? else { continue };
+            let election = state.to_election();
+            match rows.get(&state.id) {
+                Some(_) if state.deleted => database::delete_election(pool, state.id).await?,
+                None if state.deleted => {}
+                None => {
+                    database::create_election(pool, &election).await?;
+                }
+                Some(row) => {
+                    database::update_election(pool, &election).await?;
+                    if row.status != election.status {
+                        database::update_election_status(pool, state.id, &row.status, &election.status, election.updated_at).await?;
+                    }
+                }
+            }
+        }
+        for id in rows.keys().filter(|id| !streams.contains_key(id)) {
+            log::warn!("Election {} exists in the database but has no events in the log", id);
+        }
+        Ok(())
+    }
+
+    /// Grava o evento no log, acrescenta à sequência e atualiza a projeção
+    async fn append(
+        &self,
+        streams: &mut HashMap<Uuid, EventStream>,
+        id: Uuid,
+        state: Option<&ElectionAggregate>,
+        event: ElectionDomainEvent,
+    ) -> Result<ElectionAggregate> {
+        let mut recorded = RecordedEvent {
+            election_id: id,
+            version: state.map_or(0, |state| state.version) + 1,
+            event,
+            recorded_at: Utc::now(),
+            log_index: None,
+        };
+        let apply = |recorded: &RecordedEvent| -> Result<ElectionAggregate> {
+            let snapshot = state.map(|state| ElectionSnapshot { state: state.clone(), taken_at: recorded.recorded_at });
+            ElectionAggregate::replay(snapshot.as_ref(), std::slice::from_ref(recorded))?
+                .ok_or_else(|| anyhow!("Election not found: {}", id))
+        };
+        // Evento inválido não chega ao log
+        apply(&recorded)?;
+
+        if let Some(log) = &self.transparency_log {
+            let proof = log.write().await.append_election_event(ElectionEvent {
+                id: Uuid::new_v4().to_string(),
+                event_type: recorded.event.event_type(),
+                election_id: id.to_string(),
+                data: recorded.log_data(),
+                timestamp: recorded.recorded_at,
+                source: "Sistema".to_string(),
+            })?;
+            recorded.log_index = Some(proof.log_index);
+        }
+        let next = apply(&recorded)?;
+
+        let stream = streams.entry(id).or_default();
+        stream.events.push(recorded.clone());
+        if next.version % self.snapshot_interval == 0 {
+            stream.snapshot = Some(ElectionSnapshot { state: next.clone(), taken_at: Utc::now() });
+        }
+
+        self.project(&next, &recorded).await;
+        Ok(next)
+    }
+
+    /// Atualiza a projeção do banco; falhas não desfazem o evento gravado
+    async fn project(&self, state: &ElectionAggregate, recorded: &RecordedEvent) {
+        let Some(pool) = &self.pool else {
+            return;
+        };
+        let election = state.to_election();
+        let projected = match &recorded.event {
+            ElectionDomainEvent::Created { .. } => database::create_election(pool, &election).await.map(|_| ()),
+            ElectionDomainEvent::Updated { .. } => database::update_election(pool, &election).await,
+            ElectionDomainEvent::StatusChanged { from, to, .. } => {
+                match database::update_election_status(pool, state.id, from.as_str(), to.as_str(), election.updated_at).await {
+                    Ok(true) => Ok(()),
+                    Ok(false) => {
+                        let row = database::get_election(pool, state.id).await.ok().flatten();
+                        Err(anyhow!("database row has status {:?}", row.map(|row| row.status)))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            ElectionDomainEvent::Deleted => database::delete_election(pool, state.id).await,
+        };
+        if let Err(e) = projected {
+            log::warn!(
+                "Election {} projection behind event {} ({}); run rebuild_projection",
+                state.id, recorded.version, e
+            );
+        }
+    }
+}
+
+/// Estado atual da eleição existente
+fn current(streams: &HashMap<Uuid, EventStream>, id: Uuid) -> Result<ElectionAggregate> {
+    streams.get(&id)
+        .map(EventStream::state)
+        .transpose()?
+        .flatten()
+        .filter(|state| !state.deleted)
+        .ok_or_else(|| anyhow!("Election not found: {}", id))
+}
+
+fn validate_request(req: &CreateElectionRequest) -> Result<()> {
+    if req.title.trim().is_empty() {
+        return Err(anyhow!("Election title is required"));
+    }
+    if req.start_date >= req.end_date {
+        return Err(anyhow!("Election start date must be before end date"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn request() -> CreateElectionRequest {
+        CreateElectionRequest {
+            title: "Eleições Municipais".to_string(),
+            description: None,
+            start_date: Utc::now() + Duration::days(1),
+            end_date: Utc::now() + Duration::days(2),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_lifecycle() {
+        let service = ElectionService::new();
+        let election = service.create_election(request(), "admin").await.unwrap();
+
+        for status in [
+            ElectionStatus::Scheduled,
+            ElectionStatus::Active,
+            ElectionStatus::Closed,
+            ElectionStatus::Finalized,
+            ElectionStatus::Audited,
+        ] {
+            let updated = service.transition(election.id, status, "admin", None).await.unwrap();
+            assert_eq!(updated.status, status.as_str());
+        }
+
+        assert_eq!(service.get_transitions(election.id).await.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_transition_is_rejected() {
+        let service = ElectionService::new();
+        let election = service.create_election(request(), "admin").await.unwrap();
+
+        assert!(service.transition(election.id, ElectionStatus::Active, "admin", None).await.is_err());
+        assert!(service.transition(election.id, ElectionStatus::Closed, "admin", None).await.is_err());
+
+        service.transition(election.id, ElectionStatus::Scheduled, "admin", None).await.unwrap();
+        service.transition(election.id, ElectionStatus::Active, "admin", None).await.unwrap();
+        assert!(service.transition(election.id, ElectionStatus::Draft, "admin", None).await.is_err());
+        assert!(service.update_election(election.id, request()).await.is_err());
+        assert!(service.delete_election(election.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_state_is_rebuilt_from_log_and_snapshots() {
+        use crate::transparency::election_logs::LogConfig;
+
+        let log = Arc::new(RwLock::new(ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        })));
+        let service = ElectionService::new().with_transparency_log(log.clone()).with_snapshot_interval(2);
+        let election = service.create_election(request(), "admin").await.unwrap();
+        service.update_election(election.id, CreateElectionRequest {
+            title: "Eleições Suplementares".to_string(),
+            ..request()
+        }).await.unwrap();
+        service.transition(election.id, ElectionStatus::Scheduled, "admin", None).await.unwrap();
+        service.transition(election.id, ElectionStatus::Active, "admin", None).await.unwrap();
+
+        let events = service.events(election.id).await;
+        assert_eq!(events.iter().map(|e| e.version).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert!(events.iter().all(|e| e.log_index.is_some()));
+
+        let draft = service.create_election(request(), "admin").await.unwrap();
+        service.delete_election(draft.id).await.unwrap();
+        assert!(service.get_election(draft.id).await.unwrap().is_none());
+
+        // Outra instância, apenas com o log
+        let restored = ElectionService::new().with_transparency_log(log).with_snapshot_interval(3);
+        assert_eq!(restored.replay_log().await.unwrap(), 2);
+        let rebuilt = restored.get_election(election.id).await.unwrap().unwrap();
+        assert_eq!(rebuilt.name, "Eleições Suplementares");
+        assert_eq!(rebuilt.status, ElectionStatus::Active.as_str());
+        assert_eq!(restored.get_transitions(election.id).await.len(), 2);
+        assert_eq!(restored.list_elections().await.unwrap().len(), 1);
+        let streams = restored.streams.read().await;
+        assert_eq!(streams[&election.id].snapshot.as_ref().unwrap().state.version, 3);
+    }
+
+    #[tokio::test]
+    async fn test_opening_blocked_by_expiring_credential() {
+        use crate::services::credentials::{Credential, CredentialKind, ExpiryPolicy};
+
+        let watchdog = CredentialWatchdog::new(ExpiryPolicy {
+            lead_times_days: vec![30],
+            check_interval_seconds: 3600,
+        });
+        watchdog.register(Credential {
+            id: "tls-api".to_string(),
+            kind: CredentialKind::TlsCert,
+            subject: "CN=api.fortis.gov.br".to_string(),
+            fingerprint: None,
+            not_before: Utc::now() - Duration::days(300),
+            not_after: Utc::now() + Duration::hours(30),
+            critical: true,
+            source: "test".to_string(),
+        }).await.unwrap();
+
+        let service = ElectionService::new().with_credential_watchdog(watchdog);
+        let election = service.create_election(request(), "admin").await.unwrap();
+        service.transition(election.id, ElectionStatus::Scheduled, "admin", None).await.unwrap();
+
+        assert!(service.transition(election.id, ElectionStatus::Active, "admin", None).await.is_err());
+    }
+}
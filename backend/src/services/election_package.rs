@@ -0,0 +1,369 @@
+//! Pacote de eleição para preparação offline das urnas
+//!
+//! Reúne candidatos, layout da cédula, parâmetros da eleição, chaves
+//! públicas e catálogo de mensagens em um pacote assinado (Ed25519). A urna
+//! baixa o pacote antes do dia da eleição, verifica a assinatura com a chave
+//! fixada em sua configuração e passa a operar sem depender do backend.
+//!
+//! A assinatura cobre os bytes exatos do JSON em `payload`, para que a urna
+//! não precise reproduzir a serialização do backend.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use sha2::{Sha256, Digest};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::crypto::hsm::SigningKey;
+use crate::errors::ErrorCatalog;
+use crate::i18n::LanguagePack;
+use crate::models::{Candidate, CandidatePosition};
+use fortis_domain::contest_order;
+use crate::services::beacon::{purposes, RandomnessBeacon, SeededStream};
+use crate::services::candidate::CandidateService;
+use crate::services::election::{ElectionService, ElectionStatus};
+use crate::services::election_templates::ElectionTemplateService;
+use crate::storage::{ContentAddress, DistributedStorage};
+
+/// Versão do formato do pacote
+pub const PACKAGE_FORMAT_VERSION: u32 = 1;
+
+/// Dados da eleição incluídos no pacote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageElection {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Parâmetros de operação da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionParams {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub timezone: String,
+    pub allow_blank_vote: bool,
+    pub allow_null_vote: bool,
+}
+
+/// Tela da cédula para um cargo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BallotPage {
+    pub order: u32,
+    pub position: CandidatePosition,
+    pub title: String,
+    pub number_digits: u32,
+}
+
+/// Conteúdo do pacote de eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionPackage {
+    pub format_version: u32,
+    pub package_id: String,
+    pub election: PackageElection,
+    pub region: Option<String>,
+    pub params: ElectionParams,
+    pub ballot_layout: Vec<BallotPage>,
+    pub candidates: Vec<Candidate>,
+    /// Chaves públicas em hexadecimal, por finalidade
+    pub public_keys: BTreeMap<String, String>,
+    pub error_catalog: ErrorCatalog,
+    /// Idiomas da urna além do catálogo em português
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub language_packs: Vec<LanguagePack>,
+    /// Sorteio público que definiu a ordem de exibição dos candidatos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate_order: Option<CandidateOrder>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Origem da ordem dos candidatos, para reprodução por auditores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateOrder {
+    pub randomness: String,
+    pub seed: String,
+    pub log_index: Option<u64>,
+}
+
+/// Pacote assinado entregue às urnas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedElectionPackage {
+    /// JSON do `ElectionPackage`, exatamente como assinado
+    pub payload: String,
+    pub package_sha256: String,
+    pub signature: String,
+    pub public_key: String,
+    /// Endereço IPFS dos bytes de `payload`, quando publicado
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipfs: Option<ContentAddress>,
+}
+
+/// Serviço de exportação de pacotes de eleição
+pub struct ElectionPackageService {
+    elections: Arc<ElectionService>,
+    candidates: Arc<CandidateService>,
+    signing_key: Box<dyn SigningKey>,
+    public_keys: BTreeMap<String, String>,
+    error_catalog: ErrorCatalog,
+    language_packs: Vec<LanguagePack>,
+    timezone: String,
+    beacon: Option<Arc<RandomnessBeacon>>,
+    storage: Option<Arc<DistributedStorage>>,
+    templates: Option<Arc<ElectionTemplateService>>,
+}
+
+impl ElectionPackageService {
+    pub fn new(
+        elections: Arc<ElectionService>,
+        candidates: Arc<CandidateService>,
+        signing_key: impl SigningKey + 'static,
+        error_catalog: ErrorCatalog,
+    ) -> Self {
+        let mut public_keys = BTreeMap::new();
+        public_keys.insert(
+            "package_signing".to_string(),
+            hex::encode(signing_key.public_key_bytes()),
+        );
+
+        Self {
+            elections,
+            candidates,
+            signing_key: Box::new(signing_key),
+            public_keys,
+            error_catalog,
+            language_packs: Vec::new(),
+            timezone: "America/Sao_Paulo".to_string(),
+            beacon: None,
+            storage: None,
+            templates: None,
+        }
+    }
+
+    /// Pacotes de idioma entregues às urnas com a eleição
+    pub fn with_language_packs(mut self, packs: Vec<LanguagePack>) -> Self {
+        self.language_packs = packs;
+        self
+    }
+
+    /// Ordena candidatos de cada cargo por sorteio do beacon público
+    pub fn with_beacon(mut self, beacon: Arc<RandomnessBeacon>) -> Self {
+        self.beacon = Some(beacon);
+        self
+    }
+
+    /// Publica cada pacote exportado no IPFS, endereçado pelo conteúdo
+    pub fn with_storage(mut self, storage: Arc<DistributedStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Exige conformidade com o modelo e aplica suas regras da cédula
+    pub fn with_templates(mut self, templates: Arc<ElectionTemplateService>) -> Self {
+        self.templates = Some(templates);
+        self
+    }
+
+    /// Inclui chave pública adicional no pacote (ex.: log transparente)
+    pub fn with_public_key(mut self, purpose: &str, public_key_hex: &str) -> Self {
+        self.public_keys.insert(purpose.to_string(), public_key_hex.to_string());
+        self
+    }
+
+    /// Chave pública que as urnas devem fixar para verificar pacotes
+    pub fn signing_public_key(&self) -> String {
+        hex::encode(self.signing_key.public_key_bytes())
+    }
+
+    /// Exporta o pacote assinado da eleição para a região
+    pub async fn export_package(&self, election_id: Uuid, region: Option<&str>) -> Result<SignedElectionPackage> {
+        let election = self.elections.get_election(election_id).await?
+            .ok_or_else(|| anyhow!("Election not found: {}", election_id))?;
+
+        let status: ElectionStatus = election.status.parse()?;
+        if !matches!(status, ElectionStatus::Scheduled | ElectionStatus::Active) {
+            return Err(anyhow!(
+                "Election package requires a scheduled or active election, found {}",
+                status.as_str()
+            ));
+        }
+
+        // Eleição criada de um modelo só sai para as urnas se ainda conferir com ele
+        let instance = match &self.templates {
+            Some(templates) => templates.instance(election_id).await,
+            None => None,
+        };
+        if let (Some(templates), Some(instance)) = (&self.templates, &instance) {
+            let report = templates.check_conformance(election_id).await?;
+            if !report.conforms {
+                return Err(anyhow!(
+                    "Election {} does not conform to template {}: {}",
+                    election_id, instance.template_id, report.violations.join("; ")
+                ));
+            }
+        }
+
+        let mut candidates = self.candidates.list_candidates(election_id, region).await;
+        if candidates.is_empty() {
+            return Err(anyhow!("No candidates registered for election {}", election_id));
+        }
+
+        let candidate_order = match &self.beacon {
+            Some(beacon) => {
+                let draw = beacon.draw(election_id, purposes::CANDIDATE_ORDER).await?;
+                let seed: [u8; 32] = hex::decode(&draw.seed)?
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid beacon seed"))?;
+                // Ordem inicial por número torna o sorteio reproduzível por auditores
+                candidates.sort_by_key(|c| c.number);
+                SeededStream::new(seed).shuffle(&mut candidates);
+                candidates.sort_by_key(|c| c.position.ballot_order());
+                Some(CandidateOrder {
+                    randomness: draw.value.randomness,
+                    seed: draw.seed,
+                    log_index: draw.log_index,
+                })
+            }
+            None => None,
+        };
+
+        let package = ElectionPackage {
+            format_version: PACKAGE_FORMAT_VERSION,
+            package_id: Uuid::new_v4().to_string(),
+            election: PackageElection {
+                id: election.id,
+                name: election.name,
+                description: election.description,
+            },
+            region: region.map(str::to_string),
+            params: ElectionParams {
+                start_date: election.start_date,
+                end_date: election.end_date,
+                timezone: self.timezone.clone(),
+                allow_blank_vote: instance.as_ref().map_or(true, |i| i.rules.allow_blank_vote),
+                allow_null_vote: instance.as_ref().map_or(true, |i| i.rules.allow_null_vote),
+            },
+            ballot_layout: ballot_layout(&candidates),
+            candidates,
+            public_keys: self.public_keys.clone(),
+            error_catalog: self.error_catalog.clone(),
+            language_packs: self.language_packs.clone(),
+            candidate_order,
+            generated_at: Utc::now(),
+        };
+
+        let payload = serde_json::to_string(&package)?;
+        let signature = self.signing_key.try_sign(payload.as_bytes())?;
+        let ipfs = match &self.storage {
+            Some(storage) => Some(storage.store_election_package(&election_id.to_string(), payload.as_bytes()).await?),
+            None => None,
+        };
+        Ok(SignedElectionPackage {
+            package_sha256: hex::encode(Sha256::digest(payload.as_bytes())),
+            signature: hex::encode(signature),
+            public_key: self.signing_public_key(),
+            payload,
+            ipfs,
+        })
+    }
+
+    /// Verifica pacote contra a chave de assinatura deste serviço
+    pub fn verify_package(&self, signed: &SignedElectionPackage) -> Result<ElectionPackage> {
+        verify_package(signed, &self.signing_public_key())
+    }
+}
+
+/// Verifica hash e assinatura do pacote com a chave confiável informada
+pub fn verify_package(signed: &SignedElectionPackage, trusted_public_key: &str) -> Result<ElectionPackage> {
+    if hex::encode(Sha256::digest(signed.payload.as_bytes())) != signed.package_sha256 {
+        return Err(anyhow!("Election package hash mismatch"));
+    }
+
+    let public_key = hex::decode(trusted_public_key)?;
+    let signature = hex::decode(&signed.signature)?;
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(signed.payload.as_bytes(), &signature)
+        .map_err(|_| anyhow!("Invalid election package signature"))?;
+
+    let package: ElectionPackage = serde_json::from_str(&signed.payload)?;
+    if package.format_version != PACKAGE_FORMAT_VERSION {
+        return Err(anyhow!("Unsupported election package format: {}", package.format_version));
+    }
+    Ok(package)
+}
+
+/// Layout da cédula na ordem de votação da urna
+fn ballot_layout(candidates: &[Candidate]) -> Vec<BallotPage> {
+    contest_order(candidates)
+        .into_iter()
+        .enumerate()
+        .map(|(index, position)| BallotPage {
+            order: index as u32 + 1,
+            position,
+            title: position.title().to_string(),
+            number_digits: position.number_digits(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+    use crate::models::{CreateCandidateRequest, CreateElectionRequest};
+    use chrono::Duration;
+
+    async fn setup() -> (ElectionPackageService, Uuid) {
+        let elections = Arc::new(ElectionService::new());
+        let candidates = Arc::new(CandidateService::new(elections.clone()));
+        let election = elections.create_election(CreateElectionRequest {
+            title: "Eleições Gerais".to_string(),
+            description: None,
+            start_date: Utc::now() + Duration::days(1),
+            end_date: Utc::now() + Duration::days(2),
+        }, "admin").await.unwrap();
+
+        for (number, position, region) in [
+            (13, CandidatePosition::President, None),
+            (4501, CandidatePosition::FederalDeputy, Some("SP")),
+        ] {
+            candidates.create_candidate(election.id, CreateCandidateRequest {
+                name: "Candidato".to_string(),
+                party: "PXX".to_string(),
+                number,
+                position,
+                coalition: None,
+                region: region.map(str::to_string),
+                photo_base64: None,
+            }).await.unwrap();
+        }
+        elections.transition(election.id, ElectionStatus::Scheduled, "admin", None).await.unwrap();
+
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+        let service = ElectionPackageService::new(elections, candidates, signing_key, ErrorCatalog::pt_br());
+        (service, election.id)
+    }
+
+    #[tokio::test]
+    async fn test_export_and_verify_package() {
+        let (service, election_id) = setup().await;
+        let signed = service.export_package(election_id, Some("SP")).await.unwrap();
+
+        let package = service.verify_package(&signed).unwrap();
+        assert_eq!(package.candidates.len(), 2);
+        assert_eq!(package.ballot_layout[0].position, CandidatePosition::FederalDeputy);
+        assert_eq!(package.ballot_layout[1].position, CandidatePosition::President);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_package_is_rejected() {
+        let (service, election_id) = setup().await;
+        let mut signed = service.export_package(election_id, Some("SP")).await.unwrap();
+        signed.payload = signed.payload.replace("\"allow_blank_vote\":true", "\"allow_blank_vote\":false");
+        signed.package_sha256 = hex::encode(Sha256::digest(signed.payload.as_bytes()));
+
+        assert!(service.verify_package(&signed).is_err());
+    }
+}
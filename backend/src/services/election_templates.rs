@@ -0,0 +1,485 @@
+//! Modelos de eleição para os tipos recorrentes
+//!
+//! Eleições municipais, estaduais e gerais repetem a mesma estrutura:
+//! cargos, regras da cédula, perfil criptográfico e relatórios. Os modelos
+//! aprovados ficam em arquivos JSON no diretório configurado, além dos três
+//! embutidos, e são instanciados pelos administradores, que só ajustam o que
+//! o modelo permite (cargos opcionais e, se liberado, as regras da cédula).
+//! A instância guarda o resumo SHA-256 do modelo, e a conformidade da
+//! eleição com ele é conferida antes da exportação do pacote para as urnas.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::database::Election;
+use crate::models::{CandidatePosition, CreateElectionRequest};
+use crate::services::candidate::CandidateService;
+use crate::services::election::ElectionService;
+
+/// Cargo previsto no modelo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContestTemplate {
+    pub position: CandidatePosition,
+    /// Vagas em disputa
+    pub seats: u32,
+    /// Cargos opcionais podem ser retirados na instanciação
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// Regras da cédula
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BallotRules {
+    pub allow_blank_vote: bool,
+    pub allow_null_vote: bool,
+    /// Duração máxima da votação, em horas
+    pub max_voting_hours: u32,
+}
+
+/// Perfil criptográfico da eleição
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CryptoProfile {
+    pub vote_encryption: String,
+    pub package_signature: String,
+    /// Cerimônia de chaves: guardiões e limiar de reconstrução
+    pub trustees: u32,
+    pub threshold: u32,
+}
+
+/// Relatório publicado na eleição
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportTemplate {
+    pub id: String,
+    pub title: String,
+    pub format: String,
+}
+
+/// Modelo aprovado de um tipo de eleição
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionTemplate {
+    pub id: String,
+    pub version: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub contests: Vec<ContestTemplate>,
+    pub rules: BallotRules,
+    /// Regras da cédula podem ser alteradas na instanciação
+    #[serde(default)]
+    pub customizable_rules: bool,
+    pub crypto: CryptoProfile,
+    pub reports: Vec<ReportTemplate>,
+}
+
+impl ElectionTemplate {
+    /// SHA-256 (hex) do modelo, registrado na instância
+    pub fn digest(&self) -> Result<String> {
+        Ok(hex::encode(Sha256::digest(serde_json::to_vec(self)?)))
+    }
+
+    /// Confere a consistência do modelo antes de aceitá-lo
+    pub fn validate(&self) -> Result<()> {
+        if self.id.trim().is_empty() || self.contests.is_empty() {
+            return Err(anyhow!("Election template needs an id and at least one contest"));
+        }
+        let positions: BTreeSet<_> = self.contests.iter().map(|c| c.position).collect();
+        if positions.len() != self.contests.len() {
+            return Err(anyhow!("Election template {} repeats a contest", self.id));
+        }
+        if self.contests.iter().any(|c| c.seats == 0) {
+            return Err(anyhow!("Election template {} has a contest without seats", self.id));
+        }
+        if self.crypto.threshold < 2 || self.crypto.threshold > self.crypto.trustees {
+            return Err(anyhow!(
+                "Election template {} has an invalid key ceremony threshold {}-of-{}",
+                self.id, self.crypto.threshold, self.crypto.trustees
+            ));
+        }
+        if self.rules.max_voting_hours == 0 {
+            return Err(anyhow!("Election template {} has no voting window", self.id));
+        }
+        Ok(())
+    }
+}
+
+/// Eleição criada a partir de um modelo, com os ajustes aplicados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateInstance {
+    pub election_id: Uuid,
+    pub template_id: String,
+    pub template_version: u32,
+    pub template_digest: String,
+    pub contests: Vec<ContestTemplate>,
+    pub rules: BallotRules,
+    pub crypto: CryptoProfile,
+    pub reports: Vec<ReportTemplate>,
+    pub instantiated_by: String,
+    pub instantiated_at: DateTime<Utc>,
+}
+
+/// Dados da eleição e ajustes permitidos pelo modelo
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstantiateRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// Cargos opcionais que não serão disputados
+    #[serde(default)]
+    pub omit_contests: Vec<CandidatePosition>,
+    /// Regras da cédula, se o modelo permitir alterá-las
+    #[serde(default)]
+    pub rules: Option<BallotRules>,
+}
+
+/// Resultado da conferência da eleição com o modelo
+#[derive(Debug, Clone, Serialize)]
+pub struct ConformanceReport {
+    pub election_id: Uuid,
+    pub template_id: String,
+    pub template_version: u32,
+    pub conforms: bool,
+    pub violations: Vec<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Modelos embutidos: eleições municipais, estaduais e gerais
+pub fn builtin_templates() -> Vec<ElectionTemplate> {
+    let contest = |position, seats, required| ContestTemplate { position, seats, required };
+    let rules = BallotRules { allow_blank_vote: true, allow_null_vote: true, max_voting_hours: 9 };
+    let crypto = CryptoProfile {
+        vote_encryption: "elgamal-threshold".to_string(),
+        package_signature: "ed25519".to_string(),
+        trustees: 5,
+        threshold: 3,
+    };
+    let report = |id: &str, title: &str| ReportTemplate {
+        id: id.to_string(),
+        title: title.to_string(),
+        format: "pdf+json".to_string(),
+    };
+    let reports = vec![
+        report("zeresima", "Zerésima"),
+        report("boletim_urna", "Boletim de urna"),
+        report("results_manifest", "Manifesto assinado do resultado"),
+        report("rla", "Auditoria de limitação de risco"),
+    ];
+
+    vec![
+        ElectionTemplate {
+            id: "municipal".to_string(),
+            version: 1,
+            name: "Eleições Municipais".to_string(),
+            description: Some("Prefeito e vereadores".to_string()),
+            contests: vec![
+                contest(CandidatePosition::Councilor, 1, true),
+                contest(CandidatePosition::Mayor, 1, true),
+            ],
+            rules: rules.clone(),
+            customizable_rules: false,
+            crypto: crypto.clone(),
+            reports: reports.clone(),
+        },
+        ElectionTemplate {
+            id: "state".to_string(),
+            version: 1,
+            name: "Eleições Estaduais".to_string(),
+            description: Some("Governador, senado e deputados em eleição suplementar".to_string()),
+            contests: vec![
+                contest(CandidatePosition::StateDeputy, 1, false),
+                contest(CandidatePosition::FederalDeputy, 1, false),
+                contest(CandidatePosition::Senator, 1, false),
+                contest(CandidatePosition::Governor, 1, true),
+            ],
+            rules: rules.clone(),
+            customizable_rules: false,
+            crypto: crypto.clone(),
+            reports: reports.clone(),
+        },
+        ElectionTemplate {
+            id: "national".to_string(),
+            version: 1,
+            name: "Eleições Gerais".to_string(),
+            description: Some("Presidente, governador, senado e deputados".to_string()),
+            contests: vec![
+                contest(CandidatePosition::FederalDeputy, 1, true),
+                contest(CandidatePosition::StateDeputy, 1, true),
+                contest(CandidatePosition::Senator, 2, true),
+                contest(CandidatePosition::Governor, 1, true),
+                contest(CandidatePosition::President, 1, true),
+            ],
+            rules,
+            customizable_rules: false,
+            crypto,
+            reports,
+        },
+    ]
+}
+
+/// Lê os modelos `*.json` do diretório; sem diretório, nenhum
+pub fn load_templates(dir: &Path) -> Result<Vec<ElectionTemplate>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::info!("No election templates in {}: {}", dir.display(), e);
+            return Ok(Vec::new());
+        }
+    };
+    let mut templates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let template: ElectionTemplate = serde_json::from_slice(&std::fs::read(&path)?)
+            .map_err(|e| anyhow!("Invalid election template {}: {}", path.display(), e))?;
+        templates.push(template);
+    }
+    Ok(templates)
+}
+
+/// Serviço de modelos de eleição
+pub struct ElectionTemplateService {
+    elections: Arc<ElectionService>,
+    candidates: Arc<CandidateService>,
+    templates: RwLock<BTreeMap<String, ElectionTemplate>>,
+    instances: RwLock<HashMap<Uuid, TemplateInstance>>,
+}
+
+impl ElectionTemplateService {
+    /// Serviço com os modelos embutidos
+    pub fn new(elections: Arc<ElectionService>, candidates: Arc<CandidateService>) -> Self {
+        let templates = builtin_templates()
+            .into_iter()
+            .map(|template| (template.id.clone(), template))
+            .collect();
+        Self {
+            elections,
+            candidates,
+            templates: RwLock::new(templates),
+            instances: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Acrescenta modelos aprovados, substituindo os de mesmo id
+    pub fn with_templates(mut self, templates: Vec<ElectionTemplate>) -> Result<Self> {
+        for template in templates {
+            template.validate()?;
+            log::info!("Election template {} v{} loaded", template.id, template.version);
+            self.templates.get_mut().insert(template.id.clone(), template);
+        }
+        Ok(self)
+    }
+
+    pub async fn list(&self) -> Vec<ElectionTemplate> {
+        self.templates.read().await.values().cloned().collect()
+    }
+
+    pub async fn get(&self, id: &str) -> Option<ElectionTemplate> {
+        self.templates.read().await.get(id).cloned()
+    }
+
+    /// Modelo e ajustes usados na criação da eleição, se houver
+    pub async fn instance(&self, election_id: Uuid) -> Option<TemplateInstance> {
+        self.instances.read().await.get(&election_id).cloned()
+    }
+
+    /// Cria a eleição a partir do modelo, com os ajustes permitidos
+    pub async fn instantiate(
+        &self,
+        template_id: &str,
+        req: InstantiateRequest,
+        actor: &str,
+    ) -> Result<(Election, TemplateInstance)> {
+        let template = self.get(template_id).await
+            .ok_or_else(|| anyhow!("Election template not found: {}", template_id))?;
+
+        let mut contests = template.contests.clone();
+        for position in &req.omit_contests {
+            let contest = contests.iter()
+                .find(|c| c.position == *position)
+                .ok_or_else(|| anyhow!("Template {} has no contest {:?}", template.id, position))?;
+            if contest.required {
+                return Err(anyhow!("Contest {:?} is required by template {}", position, template.id));
+            }
+            contests.retain(|c| c.position != *position);
+        }
+        if contests.is_empty() {
+            return Err(anyhow!("Election needs at least one contest"));
+        }
+
+        let rules = match req.rules {
+            Some(rules) if rules != template.rules && !template.customizable_rules => {
+                return Err(anyhow!("Template {} does not allow changing the ballot rules", template.id));
+            }
+            Some(rules) => rules,
+            None => template.rules.clone(),
+        };
+        check_window(&rules, req.start_date, req.end_date)?;
+
+        let election = self.elections.create_election(CreateElectionRequest {
+            title: req.title,
+            description: req.description,
+            start_date: req.start_date,
+            end_date: req.end_date,
+        }, actor).await?;
+
+        let instance = TemplateInstance {
+            election_id: election.id,
+            template_id: template.id.clone(),
+            template_version: template.version,
+            template_digest: template.digest()?,
+            contests,
+            rules,
+            crypto: template.crypto.clone(),
+            reports: template.reports.clone(),
+            instantiated_by: actor.to_string(),
+            instantiated_at: Utc::now(),
+        };
+        self.instances.write().await.insert(election.id, instance.clone());
+        log::info!("Election {} instantiated from template {} v{}", election.id, template.id, template.version);
+        Ok((election, instance))
+    }
+
+    /// Confere a eleição com o modelo aprovado da instância
+    pub async fn check_conformance(&self, election_id: Uuid) -> Result<ConformanceReport> {
+        let instance = self.instance(election_id).await
+            .ok_or_else(|| anyhow!("Election {} was not created from a template", election_id))?;
+        let election = self.elections.get_election(election_id).await?
+            .ok_or_else(|| anyhow!("Election not found: {}", election_id))?;
+        let mut violations = Vec::new();
+
+        match self.get(&instance.template_id).await {
+            None => violations.push(format!("Template {} is no longer available", instance.template_id)),
+            Some(template) => {
+                if template.digest()? != instance.template_digest {
+                    violations.push(format!(
+                        "Template {} changed since instantiation (v{} -> v{})",
+                        template.id, instance.template_version, template.version
+                    ));
+                }
+                for contest in &template.contests {
+                    match instance.contests.iter().find(|c| c.position == contest.position) {
+                        Some(instantiated) if instantiated != contest => {
+                            violations.push(format!("Contest {:?} differs from the template", contest.position));
+                        }
+                        None if contest.required => {
+                            violations.push(format!("Required contest {:?} is missing", contest.position));
+                        }
+                        _ => {}
+                    }
+                }
+                if instance.contests.iter().any(|c| !template.contests.iter().any(|t| t.position == c.position)) {
+                    violations.push("Election has contests outside the template".to_string());
+                }
+                if instance.rules != template.rules && !template.customizable_rules {
+                    violations.push("Ballot rules differ from the template".to_string());
+                }
+                if instance.crypto != template.crypto {
+                    violations.push("Crypto profile differs from the template".to_string());
+                }
+                if instance.reports != template.reports {
+                    violations.push("Report templates differ from the template".to_string());
+                }
+            }
+        }
+
+        if let Err(e) = check_window(&instance.rules, election.start_date, election.end_date) {
+            violations.push(e.to_string());
+        }
+
+        let candidates = self.candidates.list_candidates(election_id, None).await;
+        let positions: BTreeSet<_> = candidates.iter().map(|c| c.position).collect();
+        for position in &positions {
+            if !instance.contests.iter().any(|c| c.position == *position) {
+                violations.push(format!("Candidates registered for {:?}, which is not in the template", position));
+            }
+        }
+        for contest in &instance.contests {
+            if !positions.contains(&contest.position) {
+                violations.push(format!("Contest {:?} has no candidates", contest.position));
+            }
+        }
+
+        Ok(ConformanceReport {
+            election_id,
+            template_id: instance.template_id,
+            template_version: instance.template_version,
+            conforms: violations.is_empty(),
+            violations,
+            checked_at: Utc::now(),
+        })
+    }
+}
+
+/// Janela de votação dentro do limite das regras
+fn check_window(rules: &BallotRules, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<()> {
+    if end - start > chrono::Duration::hours(rules.max_voting_hours as i64) {
+        return Err(anyhow!("Voting window exceeds {} hours", rules.max_voting_hours));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateCandidateRequest;
+    use chrono::Duration;
+
+    fn request(omit_contests: Vec<CandidatePosition>) -> InstantiateRequest {
+        let start_date = Utc::now() + Duration::days(30);
+        InstantiateRequest {
+            title: "Eleição Suplementar".to_string(),
+            description: None,
+            start_date,
+            end_date: start_date + Duration::hours(9),
+            omit_contests,
+            rules: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instantiate_and_check_conformance() {
+        let elections = Arc::new(ElectionService::new());
+        let candidates = Arc::new(CandidateService::new(elections.clone()));
+        let service = ElectionTemplateService::new(elections, candidates.clone());
+
+        assert!(service.instantiate("state", request(vec![CandidatePosition::Governor]), "admin").await.is_err());
+        let mut changed_rules = request(vec![]);
+        changed_rules.rules = Some(BallotRules { allow_blank_vote: false, allow_null_vote: true, max_voting_hours: 9 });
+        assert!(service.instantiate("state", changed_rules, "admin").await.is_err());
+
+        let omitted = vec![CandidatePosition::StateDeputy, CandidatePosition::FederalDeputy, CandidatePosition::Senator];
+        let (election, instance) = service.instantiate("state", request(omitted), "admin").await.unwrap();
+        assert_eq!(instance.contests.len(), 1);
+
+        let report = service.check_conformance(election.id).await.unwrap();
+        assert_eq!(report.violations, vec!["Contest Governor has no candidates".to_string()]);
+
+        for (number, position) in [(15, CandidatePosition::Governor), (151, CandidatePosition::Senator)] {
+            candidates.create_candidate(election.id, CreateCandidateRequest {
+                name: "Candidato".to_string(),
+                party: "PXX".to_string(),
+                number,
+                position,
+                coalition: None,
+                region: Some("MG".to_string()),
+                photo_base64: None,
+            }).await.unwrap();
+        }
+        let report = service.check_conformance(election.id).await.unwrap();
+        assert!(!report.conforms);
+        assert_eq!(report.violations.len(), 1);
+        assert!(report.violations[0].contains("Senator"));
+    }
+}
@@ -0,0 +1,367 @@
+//! Conferência em lote da elegibilidade dos eleitores de uma zona
+//!
+//! Antes do dia da eleição, a zona confere todo o seu recorte de eleitores
+//! contra o cadastro sincronizado do TSE. Os registros chegam em NDJSON
+//! (um JSON por linha) e cada um recebe de volta um código: elegível ou o
+//! motivo da recusa. O lote é retomável: cada linha tem um número de
+//! sequência, o serviço guarda até onde já conferiu e linhas repetidas de
+//! uma retomada são ignoradas. Ao concluir, o lote vira o relatório da
+//! zona, que o provisionamento exige antes de gerar os pacotes das urnas.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::services::tse::election_sync::{ElectionSyncService, VoterRollEntry};
+
+/// Registro enviado pela zona, uma linha do NDJSON
+#[derive(Debug, Clone, Deserialize)]
+pub struct EligibilityRecord {
+    /// Título de eleitor
+    pub voter_id: String,
+    #[serde(default)]
+    pub cpf: Option<String>,
+    #[serde(default)]
+    pub birth_date: Option<NaiveDate>,
+    /// Zona do registro; sem ela, vale a zona do lote
+    #[serde(default)]
+    pub zone: Option<String>,
+    #[serde(default)]
+    pub section: Option<String>,
+}
+
+/// Resultado da conferência de um registro
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EligibilityCode {
+    Eligible,
+    MalformedRecord,
+    /// Título com tamanho ou dígitos verificadores inválidos
+    InvalidVoterId,
+    DuplicateInBatch,
+    NotInRoll,
+    Inactive,
+    ZoneMismatch,
+    SectionMismatch,
+    CpfMismatch,
+    BirthDateMismatch,
+}
+
+/// Linha de resposta, na ordem das linhas recebidas
+#[derive(Debug, Clone, Serialize)]
+pub struct EligibilityResult {
+    pub seq: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voter_id: Option<String>,
+    pub eligible: bool,
+    pub code: EligibilityCode,
+    /// Seção do eleitor no cadastro, quando encontrado
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Open,
+    Completed,
+}
+
+/// Contagem de uma seção no relatório
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SectionSummary {
+    pub checked: u64,
+    pub eligible: u64,
+}
+
+/// Lote de conferência; concluído, é o relatório da zona
+#[derive(Debug, Clone, Serialize)]
+pub struct EligibilityBatch {
+    pub batch_id: Uuid,
+    pub election_id: Uuid,
+    pub zone: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub status: BatchStatus,
+    /// Próxima linha esperada; a retomada recomeça daqui
+    pub next_seq: u64,
+    pub checked: u64,
+    pub eligible: u64,
+    pub by_code: BTreeMap<EligibilityCode, u64>,
+    pub sections: BTreeMap<String, SectionSummary>,
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    seen: HashSet<String>,
+}
+
+/// Serviço de conferência de elegibilidade em lote
+pub struct EligibilityService {
+    voter_roll: ElectionSyncService,
+    batches: RwLock<HashMap<Uuid, EligibilityBatch>>,
+}
+
+impl EligibilityService {
+    pub fn new(voter_roll: ElectionSyncService) -> Self {
+        Self {
+            voter_roll,
+            batches: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Abre um lote para a zona
+    pub async fn create_batch(&self, election_id: Uuid, zone: &str, created_by: &str) -> EligibilityBatch {
+        let batch = EligibilityBatch {
+            batch_id: Uuid::new_v4(),
+            election_id,
+            zone: zone.to_string(),
+            created_by: created_by.to_string(),
+            created_at: Utc::now(),
+            status: BatchStatus::Open,
+            next_seq: 0,
+            checked: 0,
+            eligible: 0,
+            by_code: BTreeMap::new(),
+            sections: BTreeMap::new(),
+            completed_at: None,
+            seen: HashSet::new(),
+        };
+        self.batches.write().await.insert(batch.batch_id, batch.clone());
+        log::info!("Eligibility batch {} opened for zone {} by {}", batch.batch_id, zone, created_by);
+        batch
+    }
+
+    pub async fn batch(&self, batch_id: Uuid) -> Option<EligibilityBatch> {
+        self.batches.read().await.get(&batch_id).cloned()
+    }
+
+    /// Confere se um envio que começa na linha `offset` pode continuar o lote
+    pub async fn ensure_resumable(&self, batch_id: Uuid, offset: u64) -> Result<()> {
+        let batches = self.batches.read().await;
+        let batch = batches.get(&batch_id).ok_or_else(|| anyhow!("Eligibility batch not found: {}", batch_id))?;
+        if batch.status != BatchStatus::Open {
+            return Err(anyhow!("Eligibility batch {} already completed", batch_id));
+        }
+        if offset > batch.next_seq {
+            return Err(anyhow!("Offset {} skips records; batch {} resumes at {}", offset, batch_id, batch.next_seq));
+        }
+        Ok(())
+    }
+
+    /// Confere a linha `seq`; linhas já conferidas numa tentativa anterior devolvem `None`
+    pub async fn check_line(&self, batch_id: Uuid, seq: u64, line: &[u8]) -> Result<Option<EligibilityResult>> {
+        let zone = {
+            let batches = self.batches.read().await;
+            let batch = batches.get(&batch_id).ok_or_else(|| anyhow!("Eligibility batch not found: {}", batch_id))?;
+            if seq < batch.next_seq {
+                return Ok(None);
+            }
+            batch.zone.clone()
+        };
+
+        let record = serde_json::from_slice::<EligibilityRecord>(line).ok();
+        let entry = match &record {
+            Some(record) if valid_voter_id(&record.voter_id) => self.voter_roll.voter_entry(&record.voter_id).await?,
+            _ => None,
+        };
+
+        let mut batches = self.batches.write().await;
+        let batch = batches.get_mut(&batch_id).ok_or_else(|| anyhow!("Eligibility batch not found: {}", batch_id))?;
+        if batch.status != BatchStatus::Open {
+            return Err(anyhow!("Eligibility batch {} already completed", batch_id));
+        }
+        if seq < batch.next_seq {
+            return Ok(None);
+        }
+        if seq > batch.next_seq {
+            return Err(anyhow!("Record {} out of order; batch {} expects {}", seq, batch_id, batch.next_seq));
+        }
+
+        let code = match &record {
+            Some(record) if !batch.seen.insert(record.voter_id.clone()) => EligibilityCode::DuplicateInBatch,
+            Some(record) => evaluate(record, entry.as_ref(), &zone),
+            None => EligibilityCode::MalformedRecord,
+        };
+        let section = entry.map(|entry| entry.section)
+            .or_else(|| record.as_ref().and_then(|record| record.section.clone()));
+
+        batch.next_seq += 1;
+        batch.checked += 1;
+        *batch.by_code.entry(code).or_default() += 1;
+        let eligible = code == EligibilityCode::Eligible;
+        if eligible {
+            batch.eligible += 1;
+        }
+        if let Some(section) = &section {
+            let summary = batch.sections.entry(section.clone()).or_default();
+            summary.checked += 1;
+            summary.eligible += eligible as u64;
+        }
+
+        Ok(Some(EligibilityResult {
+            seq,
+            voter_id: record.map(|record| record.voter_id),
+            eligible,
+            code,
+            section,
+        }))
+    }
+
+    /// Conclui o lote, que passa a valer como relatório da zona
+    pub async fn complete(&self, batch_id: Uuid) -> Result<EligibilityBatch> {
+        let mut batches = self.batches.write().await;
+        let batch = batches.get_mut(&batch_id).ok_or_else(|| anyhow!("Eligibility batch not found: {}", batch_id))?;
+        if batch.status == BatchStatus::Completed {
+            return Ok(batch.clone());
+        }
+        if batch.checked == 0 {
+            return Err(anyhow!("Eligibility batch {} has no records", batch_id));
+        }
+        batch.status = BatchStatus::Completed;
+        batch.completed_at = Some(Utc::now());
+        log::info!(
+            "Eligibility batch {} completed for zone {}: {} of {} eligible",
+            batch_id, batch.zone, batch.eligible, batch.checked
+        );
+        Ok(batch.clone())
+    }
+
+    /// Relatório mais recente da zona, exigido pelo provisionamento
+    pub async fn zone_report(&self, election_id: Uuid, zone: &str) -> Option<EligibilityBatch> {
+        self.batches.read().await.values()
+            .filter(|batch| batch.election_id == election_id && batch.zone == zone && batch.status == BatchStatus::Completed)
+            .max_by_key(|batch| batch.completed_at)
+            .cloned()
+    }
+
+    /// Lote concluído da zona, ou erro se a conferência não foi feita
+    pub async fn ensure_zone_checked(&self, election_id: Uuid, zone: &str) -> Result<Uuid> {
+        self.zone_report(election_id, zone).await
+            .map(|batch| batch.batch_id)
+            .ok_or_else(|| anyhow!("Zone {} has no completed eligibility check for election {}", zone, election_id))
+    }
+}
+
+/// Código do registro diante da inscrição no cadastro
+pub fn evaluate(record: &EligibilityRecord, entry: Option<&VoterRollEntry>, batch_zone: &str) -> EligibilityCode {
+    if !valid_voter_id(&record.voter_id) {
+        return EligibilityCode::InvalidVoterId;
+    }
+    let Some(entry) = entry else {
+        return EligibilityCode::NotInRoll;
+    };
+    if !entry.active || entry.removed {
+        return EligibilityCode::Inactive;
+    }
+    if entry.zone != record.zone.as_deref().unwrap_or(batch_zone) {
+        return EligibilityCode::ZoneMismatch;
+    }
+    if record.section.as_ref().is_some_and(|section| *section != entry.section) {
+        return EligibilityCode::SectionMismatch;
+    }
+    if record.cpf.as_ref().is_some_and(|cpf| digits(cpf) != digits(&entry.cpf)) {
+        return EligibilityCode::CpfMismatch;
+    }
+    if record.birth_date.is_some_and(|birth_date| birth_date != entry.birth_date) {
+        return EligibilityCode::BirthDateMismatch;
+    }
+    EligibilityCode::Eligible
+}
+
+/// Título de eleitor: 8 dígitos sequenciais, UF (01 a 28) e 2 dígitos verificadores
+pub fn valid_voter_id(voter_id: &str) -> bool {
+    let digits: Vec<u32> = voter_id.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() != 12 || voter_id.len() != 12 {
+        return false;
+    }
+    let uf = digits[8] * 10 + digits[9];
+    if !(1..=28).contains(&uf) {
+        return false;
+    }
+    // SP e MG usam 1 quando o resto é 0
+    let check = |sum: u32| match sum % 11 {
+        10 => 0,
+        0 if uf <= 2 => 1,
+        rest => rest,
+    };
+    let first = check(digits[..8].iter().enumerate().map(|(i, d)| d * (i as u32 + 2)).sum());
+    let second = check(digits[8] * 7 + digits[9] * 8 + first * 9);
+    digits[10] == first && digits[11] == second
+}
+
+fn digits(value: &str) -> String {
+    value.chars().filter(char::is_ascii_digit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn entry(active: bool) -> VoterRollEntry {
+        VoterRollEntry {
+            voter_id: "123456780191".to_string(),
+            cpf: "529.982.247-25".to_string(),
+            name: "Maria".to_string(),
+            birth_date: NaiveDate::from_ymd_opt(1990, 5, 1).unwrap(),
+            zone: "001".to_string(),
+            section: "0042".to_string(),
+            polling_place_id: None,
+            active,
+            removed: false,
+            updated_at: Utc::now(),
+            biometric_template: None,
+        }
+    }
+
+    fn record(line: &str) -> EligibilityRecord {
+        serde_json::from_str(line).unwrap()
+    }
+
+    #[test]
+    fn test_record_codes_against_roll() {
+        assert!(valid_voter_id("123456780191") && valid_voter_id("004215530620") && valid_voter_id("876543212526"));
+        assert!(!valid_voter_id("123456780192") && !valid_voter_id("12345678019") && !valid_voter_id("123456782991"));
+
+        let ok = record(r#"{"voter_id":"123456780191","cpf":"52998224725","section":"0042","birth_date":"1990-05-01"}"#);
+        assert_eq!(evaluate(&ok, Some(&entry(true)), "001"), EligibilityCode::Eligible);
+        assert_eq!(evaluate(&ok, Some(&entry(false)), "001"), EligibilityCode::Inactive);
+        assert_eq!(evaluate(&ok, Some(&entry(true)), "002"), EligibilityCode::ZoneMismatch);
+        assert_eq!(evaluate(&ok, None, "001"), EligibilityCode::NotInRoll);
+
+        let moved = record(r#"{"voter_id":"123456780191","section":"0043"}"#);
+        assert_eq!(evaluate(&moved, Some(&entry(true)), "001"), EligibilityCode::SectionMismatch);
+        let wrong_cpf = record(r#"{"voter_id":"123456780191","cpf":"111.444.777-35"}"#);
+        assert_eq!(evaluate(&wrong_cpf, Some(&entry(true)), "001"), EligibilityCode::CpfMismatch);
+    }
+
+    #[tokio::test]
+    async fn test_batch_resumes_without_double_counting() {
+        let service = EligibilityService::new(ElectionSyncService::new(&Config::new()));
+        let batch = service.create_batch(Uuid::new_v4(), "001", "zona-001").await;
+        let id = batch.batch_id;
+
+        let lines: [&[u8]; 3] = [br#"{"voter_id":"123456780191"}"#, b"not json", br#"{"voter_id":"123456780191"}"#];
+        for (seq, line) in lines.iter().take(2).enumerate() {
+            assert!(service.check_line(id, seq as u64, line).await.unwrap().is_some());
+        }
+
+        // Retomada a partir do início: as duas primeiras linhas são ignoradas
+        service.ensure_resumable(id, 0).await.unwrap();
+        assert!(service.check_line(id, 0, lines[0]).await.unwrap().is_none());
+        let duplicate = service.check_line(id, 2, lines[2]).await.unwrap().unwrap();
+        assert_eq!(duplicate.code, EligibilityCode::DuplicateInBatch);
+        assert!(service.ensure_resumable(id, 5).await.is_err());
+        assert!(service.check_line(id, 5, lines[0]).await.is_err());
+
+        assert!(service.ensure_zone_checked(batch.election_id, "001").await.is_err());
+        let report = service.complete(id).await.unwrap();
+        assert_eq!((report.checked, report.eligible, report.next_seq), (3, 0, 3));
+        assert_eq!(report.by_code[&EligibilityCode::NotInRoll], 1);
+        assert_eq!(report.by_code[&EligibilityCode::MalformedRecord], 1);
+        assert_eq!(service.ensure_zone_checked(batch.election_id, "001").await.unwrap(), id);
+        assert!(service.check_line(id, 3, lines[0]).await.is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! Registro de incidentes de segurança
+//!
+//! Centraliza os incidentes abertos a partir de relatórios de
+//! vulnerabilidade, alertas e ocorrências operacionais, com histórico
+//! de mudanças de estado para auditoria.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Severidade do incidente
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// Estado do incidente
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentStatus {
+    Open,
+    Investigating,
+    Mitigated,
+    Closed,
+}
+
+/// Mudança de estado registrada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentUpdate {
+    pub status: IncidentStatus,
+    pub note: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Incidente de segurança
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Incident {
+    pub id: String,
+    pub title: String,
+    pub severity: IncidentSeverity,
+    pub status: IncidentStatus,
+    /// Origem do incidente (ex.: "bug_bounty", "monitoring")
+    pub source: String,
+    /// Identificador do registro de origem
+    pub reference_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub history: Vec<IncidentUpdate>,
+}
+
+/// Serviço de incidentes
+pub struct IncidentService {
+    incidents: RwLock<HashMap<String, Incident>>,
+}
+
+impl IncidentService {
+    pub fn new() -> Self {
+        Self {
+            incidents: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Abre novo incidente
+    pub async fn open_incident(
+        &self,
+        title: &str,
+        severity: IncidentSeverity,
+        source: &str,
+        reference_id: Option<String>,
+    ) -> Incident {
+        let now = Utc::now();
+        let incident = Incident {
+            id: Uuid::new_v4().to_string(),
+            title: title.to_string(),
+            severity,
+            status: IncidentStatus::Open,
+            source: source.to_string(),
+            reference_id,
+            created_at: now,
+            updated_at: now,
+            history: vec![IncidentUpdate {
+                status: IncidentStatus::Open,
+                note: "Incidente aberto".to_string(),
+                timestamp: now,
+            }],
+        };
+
+        log::warn!("Incidente {:?} aberto ({}): {}", severity, source, title);
+        self.incidents.write().await.insert(incident.id.clone(), incident.clone());
+        incident
+    }
+
+    /// Atualiza estado do incidente
+    pub async fn update_status(&self, incident_id: &str, status: IncidentStatus, note: &str) -> Result<Incident> {
+        let mut incidents = self.incidents.write().await;
+        let incident = incidents
+            .get_mut(incident_id)
+            .ok_or_else(|| anyhow!("Incident not found: {}", incident_id))?;
+
+        if incident.status == IncidentStatus::Closed {
+            return Err(anyhow!("Incident already closed: {}", incident_id));
+        }
+
+        let now = Utc::now();
+        incident.status = status;
+        incident.updated_at = now;
+        incident.history.push(IncidentUpdate {
+            status,
+            note: note.to_string(),
+            timestamp: now,
+        });
+        Ok(incident.clone())
+    }
+
+    /// Obtém incidente
+    pub async fn get_incident(&self, incident_id: &str) -> Option<Incident> {
+        self.incidents.read().await.get(incident_id).cloned()
+    }
+
+    /// Lista incidentes, do mais recente para o mais antigo
+    pub async fn list_incidents(&self, status: Option<IncidentStatus>) -> Vec<Incident> {
+        let incidents = self.incidents.read().await;
+        let mut result: Vec<Incident> = incidents
+            .values()
+            .filter(|i| status.map_or(true, |s| i.status == s))
+            .cloned()
+            .collect();
+        result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        result
+    }
+}
@@ -0,0 +1,566 @@
+//! Cerimônia de chaves da eleição
+//!
+//! O servidor atua como distribuidor (dealer) de um Shamir verificável: gera
+//! o polinômio, cujo termo constante é a chave privada de decifração da
+//! eleição, e entrega a cada um dos N custodiantes a sua parte. Não é uma
+//! geração distribuída: durante a abertura a chave inteira existe no
+//! servidor, e a cerimônia confia que ele não a guardou. Os coeficientes são
+//! apagados ao fim da abertura; partes não entregues ficam em memória só até
+//! a entrega ou o prazo de distribuição, quando são apagadas e a cerimônia é
+//! encerrada. A chave reconstruída é entregue uma única vez a quem a usa e
+//! apagada em seguida.
+//!
+//! O polinômio é comprometido no estilo Feldman (C_j = g^a_j), de modo que
+//! cada custodiante confere sua parte sem revelá-la. A reconstrução exige o
+//! quórum de partes válidas, e cada etapa fica registrada na trilha da
+//! cerimônia e no log transparente.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use rsa::BigUint;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use zeroize::Zeroize;
+
+use crate::services::mixnet::{hex_biguint, MixGroup};
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Prazo padrão para todos os custodiantes receberem suas partes
+const DEFAULT_DISTRIBUTION_HOURS: i64 = 24;
+
+/// Parte de um custodiante
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrusteeShare {
+    pub ceremony_id: Uuid,
+    pub trustee_id: String,
+    /// Ponto de avaliação do polinômio (1..=N)
+    pub index: u32,
+    #[serde(with = "hex_biguint")]
+    pub value: BigUint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CeremonyStatus {
+    /// Partes ainda não entregues a todos os custodiantes
+    Distributing,
+    /// Chave dividida; aguardando quórum para reconstrução
+    Active,
+    Reconstructed,
+    /// Prazo de distribuição vencido; partes não entregues foram apagadas
+    Expired,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CeremonyAction {
+    Opened,
+    ShareDelivered,
+    DistributionExpired,
+    ShareVerified,
+    /// Parte não confere com os compromissos (reclamação do custodiante)
+    ShareRejected,
+    ShareSubmitted,
+    Reconstructed,
+    ReconstructionFailed,
+}
+
+/// Etapa registrada na trilha da cerimônia
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeremonyStep {
+    pub action: CeremonyAction,
+    pub actor: String,
+    pub trustee_id: Option<String>,
+    pub at: DateTime<Utc>,
+    pub log_index: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trustee {
+    pub id: String,
+    pub index: u32,
+    pub share_delivered: bool,
+    /// Resultado da conferência Feldman feita pelo custodiante
+    pub share_verified: Option<bool>,
+    pub share_submitted: bool,
+}
+
+/// Visão pública da cerimônia (sem partes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyCeremony {
+    pub id: Uuid,
+    pub election_id: Uuid,
+    pub threshold: usize,
+    pub trustees: Vec<Trustee>,
+    /// Compromissos Feldman; o primeiro é a chave pública da eleição
+    #[serde(with = "hex_biguint::vec")]
+    pub commitments: Vec<BigUint>,
+    #[serde(with = "hex_biguint")]
+    pub public_key: BigUint,
+    pub status: CeremonyStatus,
+    pub steps: Vec<CeremonyStep>,
+    pub opened_at: DateTime<Utc>,
+    /// Depois deste instante as partes não entregues são apagadas
+    pub distribution_deadline: DateTime<Utc>,
+}
+
+struct CeremonyState {
+    ceremony: KeyCeremony,
+    /// Partes ainda não entregues; removidas na entrega
+    undelivered: HashMap<String, BigUint>,
+    submitted: HashMap<String, TrusteeShare>,
+    /// Chave reconstruída até ser retirada por quem a usa
+    key: Option<BigUint>,
+}
+
+impl CeremonyState {
+    fn wipe_undelivered(&mut self) {
+        for (_, mut value) in self.undelivered.drain() {
+            value.zeroize();
+        }
+    }
+
+    fn wipe_submitted(&mut self) {
+        for (_, mut share) in self.submitted.drain() {
+            share.value.zeroize();
+        }
+    }
+}
+
+impl Drop for CeremonyState {
+    fn drop(&mut self) {
+        self.wipe_undelivered();
+        self.wipe_submitted();
+        if let Some(key) = self.key.as_mut() {
+            key.zeroize();
+        }
+    }
+}
+
+/// Divide, confere e reconstrói a chave de decifração da eleição
+pub struct KeyCeremonyService {
+    group: MixGroup,
+    distribution_timeout: Duration,
+    ceremonies: RwLock<HashMap<Uuid, CeremonyState>>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+}
+
+impl KeyCeremonyService {
+    pub fn new(group: MixGroup) -> Self {
+        Self {
+            group,
+            distribution_timeout: Duration::hours(DEFAULT_DISTRIBUTION_HOURS),
+            ceremonies: RwLock::new(HashMap::new()),
+            transparency_log: None,
+        }
+    }
+
+    /// Registra cada etapa no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Prazo para todos os custodiantes receberem suas partes
+    pub fn with_distribution_timeout(mut self, timeout: Duration) -> Self {
+        self.distribution_timeout = timeout;
+        self
+    }
+
+    /// Gera a chave da eleição e a divide entre os custodiantes
+    pub async fn open(
+        &self,
+        election_id: Uuid,
+        trustees: Vec<String>,
+        threshold: usize,
+        operator: &str,
+    ) -> Result<KeyCeremony> {
+        let mut unique = trustees.clone();
+        unique.sort();
+        unique.dedup();
+        if unique.len() != trustees.len() {
+            return Err(anyhow!("Trustee list has duplicates"));
+        }
+        if threshold < 2 || threshold > trustees.len() {
+            return Err(anyhow!("Threshold must be between 2 and the number of trustees"));
+        }
+
+        // f(x) = a_0 + a_1 x + ... + a_{t-1} x^{t-1}, com a_0 a chave privada
+        let mut coefficients: Vec<BigUint> = (0..threshold).map(|_| self.group.random_exponent()).collect();
+        let commitments: Vec<BigUint> = coefficients.iter()
+            .map(|a| self.group.generator().modpow(a, self.group.modulus()))
+            .collect();
+
+        let undelivered: HashMap<String, BigUint> = trustees.iter().enumerate()
+            .map(|(i, id)| (id.clone(), self.evaluate(&coefficients, i as u32 + 1)))
+            .collect();
+        // Daqui em diante a chave só existe dividida nas partes
+        coefficients.iter_mut().for_each(Zeroize::zeroize);
+
+        let opened_at = Utc::now();
+        let ceremony = KeyCeremony {
+            id: Uuid::new_v4(),
+            election_id,
+            threshold,
+            trustees: trustees.iter().enumerate()
+                .map(|(i, id)| Trustee {
+                    id: id.clone(),
+                    index: i as u32 + 1,
+                    share_delivered: false,
+                    share_verified: None,
+                    share_submitted: false,
+                })
+                .collect(),
+            public_key: commitments[0].clone(),
+            commitments,
+            status: CeremonyStatus::Distributing,
+            steps: Vec::new(),
+            opened_at,
+            distribution_deadline: opened_at + self.distribution_timeout,
+        };
+
+        let mut state = CeremonyState {
+            ceremony,
+            undelivered,
+            submitted: HashMap::new(),
+            key: None,
+        };
+        self.record(&mut state, CeremonyAction::Opened, operator, None).await?;
+
+        let view = state.ceremony.clone();
+        self.ceremonies.write().await.insert(view.id, state);
+        log::info!("Key ceremony {} opened for election {} ({}-of-{})", view.id, election_id, threshold, trustees.len());
+        Ok(view)
+    }
+
+    pub async fn get(&self, ceremony_id: Uuid) -> Option<KeyCeremony> {
+        self.ceremonies.read().await.get(&ceremony_id).map(|state| state.ceremony.clone())
+    }
+
+    /// Entrega a parte ao próprio custodiante, uma única vez
+    pub async fn deliver_share(&self, ceremony_id: Uuid, trustee_id: &str) -> Result<TrusteeShare> {
+        let mut ceremonies = self.ceremonies.write().await;
+        let state = ceremonies.get_mut(&ceremony_id)
+            .ok_or_else(|| anyhow!("Key ceremony not found: {}", ceremony_id))?;
+        let index = Self::trustee(state, trustee_id)?.index;
+        if self.expire_if_overdue(state).await? {
+            return Err(anyhow!("Share distribution of key ceremony {} has expired", ceremony_id));
+        }
+        let value = state.undelivered.remove(trustee_id)
+            .ok_or_else(|| anyhow!("Share for {} was already delivered", trustee_id))?;
+
+        let trustee = Self::trustee_mut(state, trustee_id)?;
+        trustee.share_delivered = true;
+        if state.undelivered.is_empty() {
+            state.ceremony.status = CeremonyStatus::Active;
+        }
+        self.record(state, CeremonyAction::ShareDelivered, trustee_id, Some(trustee_id)).await?;
+
+        Ok(TrusteeShare {
+            ceremony_id,
+            trustee_id: trustee_id.to_string(),
+            index,
+            value,
+        })
+    }
+
+    /// Custodiante confere a parte recebida contra os compromissos publicados
+    pub async fn acknowledge_share(&self, share: &TrusteeShare, trustee_id: &str) -> Result<bool> {
+        let mut ceremonies = self.ceremonies.write().await;
+        let state = ceremonies.get_mut(&share.ceremony_id)
+            .ok_or_else(|| anyhow!("Key ceremony not found: {}", share.ceremony_id))?;
+        Self::check_owner(state, share, trustee_id)?;
+
+        let valid = self.verify_share(&state.ceremony.commitments, share);
+        Self::trustee_mut(state, trustee_id)?.share_verified = Some(valid);
+        let action = if valid { CeremonyAction::ShareVerified } else { CeremonyAction::ShareRejected };
+        self.record(state, action, trustee_id, Some(trustee_id)).await?;
+
+        if !valid {
+            log::warn!("Trustee {} rejected share of key ceremony {}", trustee_id, share.ceremony_id);
+        }
+        Ok(valid)
+    }
+
+    /// Apresenta a parte para reconstrução; partes inválidas são recusadas
+    pub async fn submit_share(&self, share: TrusteeShare, trustee_id: &str) -> Result<usize> {
+        let mut ceremonies = self.ceremonies.write().await;
+        let state = ceremonies.get_mut(&share.ceremony_id)
+            .ok_or_else(|| anyhow!("Key ceremony not found: {}", share.ceremony_id))?;
+        Self::check_owner(state, &share, trustee_id)?;
+
+        if state.ceremony.status != CeremonyStatus::Active {
+            return Err(anyhow!("Key ceremony is not accepting shares ({:?})", state.ceremony.status));
+        }
+        if !self.verify_share(&state.ceremony.commitments, &share) {
+            self.record(state, CeremonyAction::ShareRejected, trustee_id, Some(trustee_id)).await?;
+            return Err(anyhow!("Share does not match the ceremony commitments"));
+        }
+
+        state.submitted.insert(trustee_id.to_string(), share);
+        Self::trustee_mut(state, trustee_id)?.share_submitted = true;
+        self.record(state, CeremonyAction::ShareSubmitted, trustee_id, Some(trustee_id)).await?;
+        Ok(state.submitted.len())
+    }
+
+    /// Reconstrói a chave com o quórum de partes apresentadas
+    pub async fn reconstruct(&self, ceremony_id: Uuid, operator: &str) -> Result<KeyCeremony> {
+        let mut ceremonies = self.ceremonies.write().await;
+        let state = ceremonies.get_mut(&ceremony_id)
+            .ok_or_else(|| anyhow!("Key ceremony not found: {}", ceremony_id))?;
+
+        if state.ceremony.status == CeremonyStatus::Reconstructed {
+            return Ok(state.ceremony.clone());
+        }
+        if state.submitted.len() < state.ceremony.threshold {
+            return Err(anyhow!(
+                "Quorum not reached: {} of {} shares submitted",
+                state.submitted.len(),
+                state.ceremony.threshold
+            ));
+        }
+
+        let shares: Vec<&TrusteeShare> = state.submitted.values().take(state.ceremony.threshold).collect();
+        let secret = self.interpolate(&shares)?;
+
+        if self.group.generator().modpow(&secret, self.group.modulus()) != state.ceremony.public_key {
+            self.record(state, CeremonyAction::ReconstructionFailed, operator, None).await?;
+            return Err(anyhow!("Reconstructed key does not match the election public key"));
+        }
+
+        state.key = Some(secret);
+        // As partes apresentadas bastariam para refazer a chave
+        state.wipe_submitted();
+        state.ceremony.status = CeremonyStatus::Reconstructed;
+        self.record(state, CeremonyAction::Reconstructed, operator, None).await?;
+        log::info!("Key ceremony {} reconstructed the election key", ceremony_id);
+        Ok(state.ceremony.clone())
+    }
+
+    /// Retira a chave reconstruída; só a primeira chamada após o quórum a recebe
+    pub(crate) async fn take_reconstructed_key(&self, ceremony_id: Uuid) -> Option<BigUint> {
+        self.ceremonies.write().await.get_mut(&ceremony_id).and_then(|state| state.key.take())
+    }
+
+    /// Encerra as cerimônias com prazo de distribuição vencido, apagando as partes não entregues
+    pub async fn expire_distributions(&self) -> Result<Vec<Uuid>> {
+        let mut ceremonies = self.ceremonies.write().await;
+        let mut expired = Vec::new();
+        for (id, state) in ceremonies.iter_mut() {
+            if self.expire_if_overdue(state).await? {
+                expired.push(*id);
+            }
+        }
+        Ok(expired)
+    }
+
+    async fn expire_if_overdue(&self, state: &mut CeremonyState) -> Result<bool> {
+        if state.ceremony.status == CeremonyStatus::Expired {
+            return Ok(true);
+        }
+        if state.ceremony.status != CeremonyStatus::Distributing
+            || Utc::now() < state.ceremony.distribution_deadline
+        {
+            return Ok(false);
+        }
+
+        state.wipe_undelivered();
+        state.ceremony.status = CeremonyStatus::Expired;
+        self.record(state, CeremonyAction::DistributionExpired, "system", None).await?;
+        log::warn!(
+            "Key ceremony {} expired before every trustee received a share; undelivered shares were erased",
+            state.ceremony.id
+        );
+        Ok(true)
+    }
+
+    /// Verificação Feldman: g^s_i = Π C_j^(i^j)
+    pub fn verify_share(&self, commitments: &[BigUint], share: &TrusteeShare) -> bool {
+        let p = self.group.modulus();
+        let q = self.group.order();
+        if share.index == 0 || share.value >= *q {
+            return false;
+        }
+
+        let x = BigUint::from(share.index);
+        let mut power = BigUint::from(1u32);
+        let mut expected = BigUint::from(1u32);
+        for commitment in commitments {
+            expected = (expected * commitment.modpow(&power, p)) % p;
+            power = (power * &x) % q;
+        }
+        self.group.generator().modpow(&share.value, p) == expected
+    }
+
+    /// f(x) mod q pelo método de Horner
+    fn evaluate(&self, coefficients: &[BigUint], x: u32) -> BigUint {
+        let q = self.group.order();
+        let x = BigUint::from(x);
+        coefficients.iter().rev()
+            .fold(BigUint::from(0u32), |acc, a| (acc * &x + a) % q)
+    }
+
+    /// Interpolação de Lagrange em x = 0
+    fn interpolate(&self, shares: &[&TrusteeShare]) -> Result<BigUint> {
+        let q = self.group.order();
+        let two = BigUint::from(2u32);
+        let mut secret = BigUint::from(0u32);
+
+        for share in shares {
+            let xi = BigUint::from(share.index);
+            let mut numerator = BigUint::from(1u32);
+            let mut denominator = BigUint::from(1u32);
+            for other in shares.iter().filter(|other| other.index != share.index) {
+                let xj = BigUint::from(other.index);
+                numerator = (numerator * &xj) % q;
+                denominator = (denominator * ((&xj + q - &xi) % q)) % q;
+            }
+            if denominator == BigUint::from(0u32) {
+                return Err(anyhow!("Duplicate share index {}", share.index));
+            }
+            // q é primo: inverso por Fermat
+            let inverse = denominator.modpow(&(q - &two), q);
+            secret = (secret + &share.value * numerator % q * inverse) % q;
+        }
+
+        Ok(secret)
+    }
+
+    fn trustee<'a>(state: &'a CeremonyState, trustee_id: &str) -> Result<&'a Trustee> {
+        state.ceremony.trustees.iter()
+            .find(|t| t.id == trustee_id)
+            .ok_or_else(|| anyhow!("{} is not a trustee of this ceremony", trustee_id))
+    }
+
+    fn trustee_mut<'a>(state: &'a mut CeremonyState, trustee_id: &str) -> Result<&'a mut Trustee> {
+        state.ceremony.trustees.iter_mut()
+            .find(|t| t.id == trustee_id)
+            .ok_or_else(|| anyhow!("{} is not a trustee of this ceremony", trustee_id))
+    }
+
+    /// Só o próprio custodiante confere ou apresenta sua parte
+    fn check_owner(state: &CeremonyState, share: &TrusteeShare, trustee_id: &str) -> Result<()> {
+        let trustee = Self::trustee(state, trustee_id)?;
+        if share.trustee_id != trustee_id || share.index != trustee.index {
+            return Err(anyhow!("Share does not belong to {}", trustee_id));
+        }
+        Ok(())
+    }
+
+    async fn record(
+        &self,
+        state: &mut CeremonyState,
+        action: CeremonyAction,
+        actor: &str,
+        trustee_id: Option<&str>,
+    ) -> Result<()> {
+        let at = Utc::now();
+        let log_index = match &self.transparency_log {
+            Some(log) => Some(log.write().await.append_election_event(ElectionEvent {
+                id: Uuid::new_v4().to_string(),
+                event_type: ElectionEventType::KeyCeremony,
+                election_id: state.ceremony.election_id.to_string(),
+                data: serde_json::json!({
+                    "ceremony_id": state.ceremony.id,
+                    "action": action,
+                    "actor": actor,
+                    "trustee_id": trustee_id,
+                    "threshold": state.ceremony.threshold,
+                    "trustees": state.ceremony.trustees.len(),
+                    "public_key": state.ceremony.public_key.to_str_radix(16),
+                }),
+                timestamp: at,
+                source: "KeyCeremony".to_string(),
+            })?.log_index),
+            None => None,
+        };
+
+        state.ceremony.steps.push(CeremonyStep {
+            action,
+            actor: actor.to_string(),
+            trustee_id: trustee_id.map(str::to_string),
+            at,
+            log_index,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Primo seguro pequeno (p = 2q + 1, q = 1019) para testes rápidos
+    fn service() -> KeyCeremonyService {
+        KeyCeremonyService::new(MixGroup::new(BigUint::from(2039u32), BigUint::from(4u32)).unwrap())
+    }
+
+    async fn distributed(service: &KeyCeremonyService) -> (KeyCeremony, Vec<TrusteeShare>) {
+        let trustees = vec!["alice".to_string(), "bruno".to_string(), "carla".to_string()];
+        let ceremony = service.open(Uuid::new_v4(), trustees.clone(), 2, "tse-admin").await.unwrap();
+        let mut shares = Vec::new();
+        for trustee in &trustees {
+            let share = service.deliver_share(ceremony.id, trustee).await.unwrap();
+            assert!(service.acknowledge_share(&share, trustee).await.unwrap());
+            shares.push(share);
+        }
+        (ceremony, shares)
+    }
+
+    #[tokio::test]
+    async fn test_reconstruction_requires_quorum() {
+        let service = service();
+        let (ceremony, mut shares) = distributed(&service).await;
+        assert!(service.deliver_share(ceremony.id, "alice").await.is_err());
+
+        let carla = shares.pop().unwrap();
+        service.submit_share(carla, "carla").await.unwrap();
+        assert!(service.reconstruct(ceremony.id, "tse-admin").await.is_err());
+
+        let alice = shares.remove(0);
+        service.submit_share(alice, "alice").await.unwrap();
+        let reconstructed = service.reconstruct(ceremony.id, "tse-admin").await.unwrap();
+        assert_eq!(reconstructed.status, CeremonyStatus::Reconstructed);
+
+        let key = service.take_reconstructed_key(ceremony.id).await.unwrap();
+        let group = &service.group;
+        assert_eq!(group.generator().modpow(&key, group.modulus()), ceremony.public_key);
+        assert!(reconstructed.steps.iter().any(|s| s.action == CeremonyAction::Reconstructed));
+
+        // A chave sai uma única vez e as partes apresentadas não ficam guardadas
+        assert!(service.take_reconstructed_key(ceremony.id).await.is_none());
+        assert!(service.ceremonies.read().await[&ceremony.id].submitted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_undelivered_shares_are_erased_after_deadline() {
+        let service = service().with_distribution_timeout(Duration::zero());
+        let trustees = vec!["alice".to_string(), "bruno".to_string()];
+        let ceremony = service.open(Uuid::new_v4(), trustees, 2, "tse-admin").await.unwrap();
+
+        assert_eq!(service.expire_distributions().await.unwrap(), vec![ceremony.id]);
+        assert!(service.ceremonies.read().await[&ceremony.id].undelivered.is_empty());
+        assert!(service.deliver_share(ceremony.id, "alice").await.is_err());
+
+        let expired = service.get(ceremony.id).await.unwrap();
+        assert_eq!(expired.status, CeremonyStatus::Expired);
+        assert!(expired.steps.iter().any(|s| s.action == CeremonyAction::DistributionExpired));
+    }
+
+    #[tokio::test]
+    async fn test_feldman_rejects_tampered_share() {
+        let service = service();
+        let (ceremony, shares) = distributed(&service).await;
+
+        let mut forged = shares[0].clone();
+        forged.value = (forged.value + BigUint::from(1u32)) % service.group.order();
+        assert!(!service.verify_share(&ceremony.commitments, &forged));
+        assert!(service.submit_share(forged, "alice").await.is_err());
+
+        // A parte de outro custodiante não pode ser apresentada em seu nome
+        assert!(service.submit_share(shares[1].clone(), "alice").await.is_err());
+    }
+}
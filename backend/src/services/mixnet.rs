@@ -0,0 +1,624 @@
+//! Mixnet para anonimização das cédulas
+//!
+//! Antes da apuração, as cédulas cifradas com ElGamal passam por uma
+//! sequência de estágios de mistura. Cada estágio re-cifra e permuta as
+//! cédulas e publica uma prova de embaralhamento correto (cut-and-choose no
+//! estilo Sako–Kilian): o misturador gera embaralhamentos-sombra e, conforme
+//! o desafio Fiat–Shamir, revela a ligação sombra→entrada ou sombra→saída.
+//! Um estágio desonesto passa na verificação com probabilidade 2^-rodadas.
+//! Cada estágio é registrado no log transparente para auditoria.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use rsa::BigUint;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::services::compute::ComputePool;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Primo seguro do grupo MODP de 2048 bits (RFC 3526, grupo 14)
+const RFC3526_2048_PRIME: &str = concat!(
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+    "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B",
+    "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718",
+    "3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF",
+);
+
+/// Serialização de inteiros grandes em hexadecimal
+pub(crate) mod hex_biguint {
+    use rsa::BigUint;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_str_radix(16))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigUint, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        BigUint::parse_bytes(hex.as_bytes(), 16).ok_or_else(|| de::Error::custom("invalid hex integer"))
+    }
+
+    pub mod vec {
+        use super::*;
+        use serde::Serialize;
+
+        pub fn serialize<S: Serializer>(values: &[BigUint], serializer: S) -> Result<S::Ok, S::Error> {
+            values.iter().map(|v| v.to_str_radix(16)).collect::<Vec<_>>().serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<BigUint>, D::Error> {
+            Vec::<String>::deserialize(deserializer)?
+                .iter()
+                .map(|hex| BigUint::parse_bytes(hex.as_bytes(), 16)
+                    .ok_or_else(|| de::Error::custom("invalid hex integer")))
+                .collect()
+        }
+    }
+}
+
+/// Grupo de resíduos quadráticos módulo um primo seguro p = 2q + 1
+#[derive(Debug, Clone)]
+pub struct MixGroup {
+    p: BigUint,
+    q: BigUint,
+    g: BigUint,
+}
+
+impl MixGroup {
+    pub fn rfc3526_2048() -> Self {
+        let p = BigUint::parse_bytes(RFC3526_2048_PRIME.as_bytes(), 16).expect("valid RFC 3526 prime");
+        Self::new(p, BigUint::from(4u32)).expect("valid RFC 3526 group")
+    }
+
+    pub fn new(p: BigUint, g: BigUint) -> Result<Self> {
+        let one = BigUint::from(1u32);
+        if p <= BigUint::from(5u32) {
+            return Err(anyhow!("Mix group prime is too small"));
+        }
+        let q = (&p - &one) >> 1;
+        let group = Self { p, q, g };
+        if group.g == one || !group.is_member(&group.g) {
+            return Err(anyhow!("Mix group generator is not in the prime-order subgroup"));
+        }
+        Ok(group)
+    }
+
+    /// Gerador do subgrupo de ordem q
+    pub fn generator(&self) -> &BigUint {
+        &self.g
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.p
+    }
+
+    /// Ordem q do subgrupo, módulo dos expoentes
+    pub fn order(&self) -> &BigUint {
+        &self.q
+    }
+
+    /// Elemento pertence ao subgrupo de ordem q
+    pub(crate) fn is_member(&self, x: &BigUint) -> bool {
+        *x > BigUint::from(0u32) && *x < self.p && x.modpow(&self.q, &self.p) == BigUint::from(1u32)
+    }
+
+    pub(crate) fn random_exponent(&self) -> BigUint {
+        let mut bytes = vec![0u8; (self.q.bits() / 8) + 16];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        BigUint::from_bytes_be(&bytes) % &self.q
+    }
+
+    /// Codifica número de candidato como elemento do grupo
+    pub fn encode(&self, value: u64) -> Result<BigUint> {
+        let m = BigUint::from(value);
+        if value == 0 || m > self.q {
+            return Err(anyhow!("Value {} cannot be encoded in the mix group", value));
+        }
+        Ok(if self.is_member(&m) { m } else { &self.p - m })
+    }
+
+    pub fn decode(&self, element: &BigUint) -> Result<u64> {
+        let m = if *element <= self.q { element.clone() } else { &self.p - element };
+        let bytes = m.to_bytes_be();
+        if bytes.len() > 8 {
+            return Err(anyhow!("Decrypted element is not a valid encoded value"));
+        }
+        Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+    }
+
+    fn encrypt(&self, public_key: &BigUint, message: &BigUint, r: &BigUint) -> Ciphertext {
+        Ciphertext {
+            a: self.g.modpow(r, &self.p),
+            b: (message * public_key.modpow(r, &self.p)) % &self.p,
+        }
+    }
+
+    /// Re-cifra multiplicando por uma cifra de 1
+    fn reencrypt(&self, public_key: &BigUint, ciphertext: &Ciphertext, r: &BigUint) -> Ciphertext {
+        Ciphertext {
+            a: (&ciphertext.a * self.g.modpow(r, &self.p)) % &self.p,
+            b: (&ciphertext.b * public_key.modpow(r, &self.p)) % &self.p,
+        }
+    }
+
+    fn decrypt(&self, secret: &BigUint, ciphertext: &Ciphertext) -> BigUint {
+        // a tem ordem q, então a^(q - x) = a^(-x)
+        let exponent = (&self.q - (secret % &self.q)) % &self.q;
+        (&ciphertext.b * ciphertext.a.modpow(&exponent, &self.p)) % &self.p
+    }
+}
+
+/// Cédula cifrada com ElGamal
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Ciphertext {
+    #[serde(with = "hex_biguint")]
+    pub a: BigUint,
+    #[serde(with = "hex_biguint")]
+    pub b: BigUint,
+}
+
+/// Chave pública de mistura publicada às urnas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MixPublicKey {
+    #[serde(with = "hex_biguint")]
+    pub p: BigUint,
+    #[serde(with = "hex_biguint")]
+    pub g: BigUint,
+    #[serde(with = "hex_biguint")]
+    pub h: BigUint,
+}
+
+/// Abertura de uma rodada da prova, conforme o bit de desafio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "side")]
+pub enum RoundOpening {
+    /// sombra[i] = re-cifra(entrada[permutation[i]], randomness[i])
+    Input {
+        permutation: Vec<usize>,
+        #[serde(with = "hex_biguint::vec")]
+        randomness: Vec<BigUint>,
+    },
+    /// saída[i] = re-cifra(sombra[permutation[i]], randomness[i])
+    Output {
+        permutation: Vec<usize>,
+        #[serde(with = "hex_biguint::vec")]
+        randomness: Vec<BigUint>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowRound {
+    pub shadow: Vec<Ciphertext>,
+    pub opening: RoundOpening,
+}
+
+/// Prova de embaralhamento correto de um estágio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShuffleProof {
+    pub rounds: Vec<ShadowRound>,
+}
+
+/// Estágio de mistura executado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MixStage {
+    pub stage: usize,
+    pub input_hash: String,
+    pub output_hash: String,
+    pub output: Vec<Ciphertext>,
+    pub proof: ShuffleProof,
+    pub log_index: Option<u64>,
+    pub mixed_at: DateTime<Utc>,
+}
+
+/// Lote de cédulas de uma eleição e seus estágios de mistura
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MixBatch {
+    pub election_id: Uuid,
+    pub ballots: Vec<Ciphertext>,
+    pub stages: Vec<MixStage>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MixnetConfig {
+    /// Número de estágios (servidores de mistura)
+    pub stages: usize,
+    /// Rodadas da prova por estágio
+    pub proof_rounds: usize,
+}
+
+impl Default for MixnetConfig {
+    fn default() -> Self {
+        Self {
+            stages: 3,
+            proof_rounds: 40,
+        }
+    }
+}
+
+/// Pipeline de mistura verificável antes da apuração
+pub struct MixnetService {
+    group: MixGroup,
+    config: MixnetConfig,
+    // Em implementação real, a chave seria compartilhada por limiar entre os apuradores
+    secret_key: BigUint,
+    public_key: BigUint,
+    batches: RwLock<HashMap<Uuid, MixBatch>>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+    compute: Option<Arc<ComputePool>>,
+}
+
+/// Parte pública do misturador, enviada ao pool de computação
+#[derive(Clone)]
+struct Mixer {
+    group: MixGroup,
+    public_key: BigUint,
+    proof_rounds: usize,
+}
+
+impl MixnetService {
+    pub fn new(group: MixGroup, config: MixnetConfig) -> Self {
+        let secret_key = group.random_exponent();
+        let public_key = group.g.modpow(&secret_key, &group.p);
+        Self {
+            group,
+            config,
+            secret_key,
+            public_key,
+            batches: RwLock::new(HashMap::new()),
+            transparency_log: None,
+            compute: None,
+        }
+    }
+
+    /// Registra cada estágio no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Executa embaralhamentos, verificações e decifração fora dos workers HTTP
+    pub fn with_compute_pool(mut self, pool: Arc<ComputePool>) -> Self {
+        self.compute = Some(pool);
+        self
+    }
+
+    pub fn public_key(&self) -> MixPublicKey {
+        MixPublicKey {
+            p: self.group.p.clone(),
+            g: self.group.g.clone(),
+            h: self.public_key.clone(),
+        }
+    }
+
+    /// Cifra o número de um candidato com a chave de mistura
+    pub fn encrypt_ballot(&self, candidate_number: u64) -> Result<Ciphertext> {
+        let message = self.group.encode(candidate_number)?;
+        let r = self.group.random_exponent();
+        Ok(self.group.encrypt(&self.public_key, &message, &r))
+    }
+
+    /// Adiciona cédulas cifradas ao lote da eleição
+    pub async fn submit_ballots(&self, election_id: Uuid, ballots: Vec<Ciphertext>) -> Result<usize> {
+        if let Some(invalid) = ballots.iter().position(|c| !self.group.is_member(&c.a) || !self.group.is_member(&c.b)) {
+            return Err(anyhow!("Ballot {} is not a valid ciphertext", invalid));
+        }
+
+        let mut batches = self.batches.write().await;
+        let batch = batches.entry(election_id).or_insert_with(|| MixBatch {
+            election_id,
+            ballots: Vec::new(),
+            stages: Vec::new(),
+        });
+        if !batch.stages.is_empty() {
+            return Err(anyhow!("Election {} ballots have already been mixed", election_id));
+        }
+        batch.ballots.extend(ballots);
+        Ok(batch.ballots.len())
+    }
+
+    /// Executa todos os estágios de mistura
+    pub async fn run(&self, election_id: Uuid) -> Result<MixBatch> {
+        let mut batch = self.get_batch(election_id).await
+            .ok_or_else(|| anyhow!("No ballots submitted for election {}", election_id))?;
+        if !batch.stages.is_empty() {
+            return Err(anyhow!("Election {} ballots have already been mixed", election_id));
+        }
+        if batch.ballots.len() < 2 {
+            return Err(anyhow!("At least two ballots are required to mix"));
+        }
+
+        // Em implementação real, cada estágio seria executado por um servidor de mistura independente
+        for stage in 0..self.config.stages {
+            let input = batch.stages.last().map(|s| &s.output).unwrap_or(&batch.ballots).clone();
+            let input_hash = hash_ciphertexts(&input);
+            let mixer = self.mixer();
+            let (output, proof) = self.offload("mix_shuffle", move || mixer.shuffle(&input)).await?;
+            let mut mix_stage = MixStage {
+                stage,
+                input_hash,
+                output_hash: hash_ciphertexts(&output),
+                output,
+                proof,
+                log_index: None,
+                mixed_at: Utc::now(),
+            };
+            mix_stage.log_index = self.log_stage(election_id, &mix_stage).await?;
+            batch.stages.push(mix_stage);
+        }
+
+        self.batches.write().await.insert(election_id, batch.clone());
+        Ok(batch)
+    }
+
+    /// Verifica a cadeia de estágios e as provas de embaralhamento
+    pub async fn verify(&self, election_id: Uuid) -> Result<()> {
+        let batch = self.get_batch(election_id).await
+            .ok_or_else(|| anyhow!("No ballots submitted for election {}", election_id))?;
+        if batch.stages.len() != self.config.stages {
+            return Err(anyhow!("Election {} has not completed mixing", election_id));
+        }
+
+        let mixer = self.mixer();
+        self.offload("mix_verify", move || mixer.verify_chain(&batch)).await?
+    }
+
+    /// Decifra a saída verificada do último estágio e conta os votos
+    pub async fn tally(&self, election_id: Uuid) -> Result<BTreeMap<u64, u64>> {
+        self.verify(election_id).await?;
+        let batch = self.get_batch(election_id).await
+            .ok_or_else(|| anyhow!("No ballots submitted for election {}", election_id))?;
+        let output = batch.stages.last().expect("verified mix has stages").output.clone();
+
+        let group = self.group.clone();
+        let secret_key = self.secret_key.clone();
+        self.offload("mix_decrypt", move || -> Result<BTreeMap<u64, u64>> {
+            let mut tally = BTreeMap::new();
+            for ciphertext in &output {
+                let candidate = group.decode(&group.decrypt(&secret_key, ciphertext))?;
+                *tally.entry(candidate).or_insert(0) += 1;
+            }
+            Ok(tally)
+        }).await?
+    }
+
+    pub async fn get_batch(&self, election_id: Uuid) -> Option<MixBatch> {
+        self.batches.read().await.get(&election_id).cloned()
+    }
+
+    fn mixer(&self) -> Mixer {
+        Mixer {
+            group: self.group.clone(),
+            public_key: self.public_key.clone(),
+            proof_rounds: self.config.proof_rounds,
+        }
+    }
+
+    async fn offload<T, F>(&self, task: &str, job: F) -> Result<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        match &self.compute {
+            Some(pool) => pool.run(task, job).await,
+            None => Ok(job()),
+        }
+    }
+
+    async fn log_stage(&self, election_id: Uuid, stage: &MixStage) -> Result<Option<u64>> {
+        let Some(log) = &self.transparency_log else {
+            return Ok(None);
+        };
+        let proof_hash = format!("{:x}", Sha256::digest(serde_json::to_vec(&stage.proof)?));
+        let inclusion = log.write().await.append_election_event(ElectionEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type: ElectionEventType::MixnetShuffle,
+            election_id: election_id.to_string(),
+            data: serde_json::json!({
+                "stage": stage.stage,
+                "ballots": stage.output.len(),
+                "input_hash": stage.input_hash,
+                "output_hash": stage.output_hash,
+                "proof_hash": proof_hash,
+                "proof_rounds": stage.proof.rounds.len(),
+            }),
+            timestamp: stage.mixed_at,
+            source: "Mixnet".to_string(),
+        })?;
+        Ok(Some(inclusion.log_index))
+    }
+}
+
+impl Mixer {
+    /// Verifica a cadeia de estágios e as provas de embaralhamento
+    fn verify_chain(&self, batch: &MixBatch) -> Result<()> {
+        let mut input = &batch.ballots;
+        for stage in &batch.stages {
+            if stage.input_hash != hash_ciphertexts(input) || stage.output_hash != hash_ciphertexts(&stage.output) {
+                return Err(anyhow!("Mix stage {} hash mismatch", stage.stage));
+            }
+            self.verify_shuffle(input, &stage.output, &stage.proof)
+                .map_err(|e| anyhow!("Mix stage {} failed verification: {}", stage.stage, e))?;
+            input = &stage.output;
+        }
+        Ok(())
+    }
+
+    fn shuffle(&self, input: &[Ciphertext]) -> (Vec<Ciphertext>, ShuffleProof) {
+        let n = input.len();
+        let (permutation, randomness) = self.random_permutation(n);
+        let output: Vec<Ciphertext> = (0..n)
+            .map(|i| self.group.reencrypt(&self.public_key, &input[permutation[i]], &randomness[i]))
+            .collect();
+
+        let shadows: Vec<(Vec<usize>, Vec<BigUint>, Vec<Ciphertext>)> = (0..self.proof_rounds)
+            .map(|_| {
+                let (shadow_permutation, shadow_randomness) = self.random_permutation(n);
+                let shadow = (0..n)
+                    .map(|i| self.group.reencrypt(&self.public_key, &input[shadow_permutation[i]], &shadow_randomness[i]))
+                    .collect();
+                (shadow_permutation, shadow_randomness, shadow)
+            })
+            .collect();
+
+        let shadow_sets: Vec<&[Ciphertext]> = shadows.iter().map(|(_, _, s)| s.as_slice()).collect();
+        let challenge = challenge_bits(input, &output, &shadow_sets);
+
+        let rounds = shadows.into_iter().zip(challenge).map(|((shadow_permutation, shadow_randomness, shadow), open_output)| {
+            let opening = if open_output {
+                // saída[i] vem da entrada permutation[i], que está na sombra em inverse[permutation[i]]
+                let mut inverse = vec![0; n];
+                for (i, source) in shadow_permutation.iter().enumerate() {
+                    inverse[*source] = i;
+                }
+                let links: Vec<usize> = (0..n).map(|i| inverse[permutation[i]]).collect();
+                let link_randomness = (0..n)
+                    .map(|i| (&randomness[i] + &self.group.q - &shadow_randomness[links[i]]) % &self.group.q)
+                    .collect();
+                RoundOpening::Output { permutation: links, randomness: link_randomness }
+            } else {
+                RoundOpening::Input { permutation: shadow_permutation, randomness: shadow_randomness }
+            };
+            ShadowRound { shadow, opening }
+        }).collect();
+
+        (output, ShuffleProof { rounds })
+    }
+
+    fn verify_shuffle(&self, input: &[Ciphertext], output: &[Ciphertext], proof: &ShuffleProof) -> Result<()> {
+        let n = input.len();
+        if output.len() != n {
+            return Err(anyhow!("output size differs from input"));
+        }
+        if proof.rounds.len() != self.proof_rounds {
+            return Err(anyhow!("expected {} proof rounds", self.proof_rounds));
+        }
+
+        let shadow_sets: Vec<&[Ciphertext]> = proof.rounds.iter().map(|r| r.shadow.as_slice()).collect();
+        let challenge = challenge_bits(input, output, &shadow_sets);
+
+        for (index, (round, open_output)) in proof.rounds.iter().zip(challenge).enumerate() {
+            let (source, target, permutation, randomness) = match (&round.opening, open_output) {
+                (RoundOpening::Input { permutation, randomness }, false) => (input, &round.shadow[..], permutation, randomness),
+                (RoundOpening::Output { permutation, randomness }, true) => (&round.shadow[..], output, permutation, randomness),
+                _ => return Err(anyhow!("round {} opened the wrong side", index)),
+            };
+            if target.len() != n || randomness.len() != n || !is_permutation(permutation, n) {
+                return Err(anyhow!("round {} opening is malformed", index));
+            }
+            for i in 0..n {
+                if self.group.reencrypt(&self.public_key, &source[permutation[i]], &randomness[i]) != target[i] {
+                    return Err(anyhow!("round {} re-encryption {} does not match", index, i));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn random_permutation(&self, n: usize) -> (Vec<usize>, Vec<BigUint>) {
+        let mut permutation: Vec<usize> = (0..n).collect();
+        permutation.shuffle(&mut rand::thread_rng());
+        let randomness = (0..n).map(|_| self.group.random_exponent()).collect();
+        (permutation, randomness)
+    }
+}
+
+fn hash_ciphertexts(ciphertexts: &[Ciphertext]) -> String {
+    let mut hasher = Sha256::new();
+    for c in ciphertexts {
+        hasher.update(c.a.to_bytes_be());
+        hasher.update([0u8]);
+        hasher.update(c.b.to_bytes_be());
+        hasher.update([1u8]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Desafio Fiat–Shamir: um bit por rodada, derivado de entrada, saída e sombras
+fn challenge_bits(input: &[Ciphertext], output: &[Ciphertext], shadows: &[&[Ciphertext]]) -> Vec<bool> {
+    let mut hasher = Sha256::new();
+    hasher.update(hash_ciphertexts(input));
+    hasher.update(hash_ciphertexts(output));
+    for shadow in shadows {
+        hasher.update(hash_ciphertexts(shadow));
+    }
+    let seed = hasher.finalize();
+
+    (0..shadows.len())
+        .map(|round| {
+            let digest = Sha256::new()
+                .chain_update(seed)
+                .chain_update((round as u64).to_be_bytes())
+                .finalize();
+            digest[0] & 1 == 1
+        })
+        .collect()
+}
+
+fn is_permutation(permutation: &[usize], n: usize) -> bool {
+    if permutation.len() != n {
+        return false;
+    }
+    let mut seen = vec![false; n];
+    permutation.iter().all(|&i| i < n && !std::mem::replace(&mut seen[i], true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> MixnetService {
+        // Grupo pequeno (p = 2039, q = 1019) apenas para testes
+        let group = MixGroup::new(BigUint::from(2039u32), BigUint::from(4u32)).unwrap();
+        MixnetService::new(group, MixnetConfig { stages: 3, proof_rounds: 32 })
+    }
+
+    #[tokio::test]
+    async fn test_mix_preserves_tally() {
+        let service = service();
+        let election_id = Uuid::new_v4();
+        let votes = [13u64, 45, 13, 22, 13];
+        let ballots = votes.iter().map(|v| service.encrypt_ballot(*v).unwrap()).collect();
+
+        service.submit_ballots(election_id, ballots).await.unwrap();
+        let batch = service.run(election_id).await.unwrap();
+        assert_eq!(batch.stages.len(), 3);
+        assert_ne!(batch.stages[2].output, batch.ballots);
+
+        let tally = service.tally(election_id).await.unwrap();
+        assert_eq!(tally.get(&13), Some(&3));
+        assert_eq!(tally.get(&45), Some(&1));
+        assert_eq!(tally.get(&22), Some(&1));
+        assert!(service.submit_ballots(election_id, vec![service.encrypt_ballot(13).unwrap()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tampered_stage_fails_verification() {
+        let service = service();
+        let election_id = Uuid::new_v4();
+        let ballots = [13u64, 45, 22].iter().map(|v| service.encrypt_ballot(*v).unwrap()).collect();
+        service.submit_ballots(election_id, ballots).await.unwrap();
+        service.run(election_id).await.unwrap();
+        service.verify(election_id).await.unwrap();
+
+        {
+            let mut batches = service.batches.write().await;
+            let stage = &mut batches.get_mut(&election_id).unwrap().stages[1];
+            stage.output[0] = service.encrypt_ballot(45).unwrap();
+            stage.output_hash = hash_ciphertexts(&stage.output);
+        }
+
+        assert!(service.verify(election_id).await.is_err());
+        assert!(service.tally(election_id).await.is_err());
+    }
+}
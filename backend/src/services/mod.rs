@@ -2,9 +2,33 @@
 
 pub mod auth;
 pub mod election;
+pub mod candidate;
+pub mod election_package;
 pub mod vote;
 // pub mod blockchain;
 pub mod crypto;
 pub mod tse;
 pub mod audit;
 pub mod urna;
+pub mod consent;
+pub mod incident;
+pub mod security_reports;
+pub mod credentials;
+pub mod mixnet;
+pub mod key_ceremony;
+pub mod compute;
+pub mod beacon;
+pub mod receipts;
+pub mod vote_batches;
+pub mod vote_sync;
+pub mod results;
+pub mod support_access;
+pub mod dual_control;
+pub mod provisioning;
+pub mod transmission;
+pub mod verification_limits;
+pub mod eligibility;
+pub mod regions;
+pub mod election_templates;
+pub mod tally;
+pub mod updates;
@@ -0,0 +1,309 @@
+//! Pacotes de provisionamento cifrados para as urnas
+//!
+//! Substitui a configuração em texto claro na mídia removível. Cada pacote
+//! é gerado para uma urna: o conteúdo (configuração, cédula, recorte do
+//! cadastro da seção e arquivos auxiliares) é cifrado com AES-256-GCM e a
+//! chave de conteúdo é cifrada para a chave de selagem registrada da urna,
+//! que só pode ser usada dentro do TPM do equipamento.
+//!
+//! Com o cadastro de regiões, a urna precisa estar designada para a seção
+//! do pedido, e a seção completa (`UF/município/zona/seção`) vai na
+//! configuração como `section`, para a urna marcar os votos.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rsa::pkcs8::{DecodePublicKey, EncodePublicKey};
+use rsa::{Oaep, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+pub use fortis_domain::provisioning::{
+    recipient_key_sha256, BundleAsset, BundleContents, ProvisioningBundle, PROVISIONING_FORMAT_VERSION,
+};
+use crate::crypto::hsm::SigningKey;
+use crate::services::election_package::ElectionPackageService;
+use crate::services::eligibility::EligibilityService;
+use crate::services::regions::RegionService;
+use crate::services::tse::ElectionSyncService;
+
+/// Chave de selagem registrada na preparação da urna
+#[derive(Debug, Clone, Serialize)]
+pub struct SealingKey {
+    pub device_id: String,
+    pub fingerprint: String,
+    pub registered_at: DateTime<Utc>,
+    #[serde(skip)]
+    public_key: RsaPublicKey,
+}
+
+/// Pedido de geração de pacote
+#[derive(Debug, Clone, Deserialize)]
+pub struct BundleRequest {
+    pub election_id: Uuid,
+    pub region: Option<String>,
+    pub zone: String,
+    pub section: String,
+    pub config: serde_json::Value,
+    #[serde(default)]
+    pub assets: Vec<BundleAsset>,
+}
+
+/// Registro de pacote emitido, sem o conteúdo
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedBundle {
+    pub bundle_id: Uuid,
+    pub device_id: String,
+    pub election_id: Uuid,
+    pub zone: String,
+    pub section: String,
+    pub recipient_key_sha256: String,
+    pub manifest: BTreeMap<String, String>,
+    /// Conferência de elegibilidade da zona que liberou o pacote
+    pub eligibility_batch: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Serviço de provisionamento
+pub struct ProvisioningService {
+    packages: Arc<ElectionPackageService>,
+    voter_roll: ElectionSyncService,
+    signing_key: Box<dyn SigningKey>,
+    eligibility: Option<Arc<EligibilityService>>,
+    regions: Option<Arc<RegionService>>,
+    sealing_keys: RwLock<HashMap<String, SealingKey>>,
+    issued: RwLock<Vec<IssuedBundle>>,
+}
+
+impl ProvisioningService {
+    pub fn new(
+        packages: Arc<ElectionPackageService>,
+        voter_roll: ElectionSyncService,
+        signing_key: impl SigningKey + 'static,
+    ) -> Self {
+        Self {
+            packages,
+            voter_roll,
+            signing_key: Box::new(signing_key),
+            eligibility: None,
+            regions: None,
+            sealing_keys: RwLock::new(HashMap::new()),
+            issued: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Exige a conferência de elegibilidade concluída na zona antes de gerar pacotes
+    pub fn with_eligibility(mut self, eligibility: Arc<EligibilityService>) -> Self {
+        self.eligibility = Some(eligibility);
+        self
+    }
+
+    /// Exige a urna designada para a seção do pedido
+    pub fn with_regions(mut self, regions: Arc<RegionService>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    /// Chave pública que as urnas fixam para verificar pacotes de provisionamento
+    pub fn signing_public_key(&self) -> String {
+        hex::encode(self.signing_key.public_key_bytes())
+    }
+
+    /// Registra a chave pública de selagem exportada do TPM da urna
+    pub async fn register_sealing_key(&self, device_id: &str, public_key_pem: &str) -> Result<SealingKey> {
+        let public_key = RsaPublicKey::from_public_key_pem(public_key_pem)
+            .map_err(|e| anyhow!("Invalid sealing key: {}", e))?;
+        let der = public_key.to_public_key_der()?;
+
+        let key = SealingKey {
+            device_id: device_id.to_string(),
+            fingerprint: recipient_key_sha256(der.as_bytes()),
+            registered_at: Utc::now(),
+            public_key,
+        };
+        self.sealing_keys.write().await.insert(device_id.to_string(), key.clone());
+        log::info!("Sealing key {} registered for urna {}", key.fingerprint, device_id);
+        Ok(key)
+    }
+
+    /// Gera o pacote cifrado para a urna
+    pub async fn create_bundle(&self, device_id: &str, mut request: BundleRequest) -> Result<ProvisioningBundle> {
+        let sealing_key = self.sealing_keys.read().await.get(device_id).cloned()
+            .ok_or_else(|| anyhow!("No sealing key registered for urna {}", device_id))?;
+        let eligibility_batch = match &self.eligibility {
+            Some(eligibility) => Some(eligibility.ensure_zone_checked(request.election_id, &request.zone).await?),
+            None => None,
+        };
+        if let Some(regions) = &self.regions {
+            let section = regions.section_of_urna(device_id).await
+                .ok_or_else(|| anyhow!("Urna {} is not assigned to a section", device_id))?;
+            if section.zone != request.zone || section.section != request.section {
+                return Err(anyhow!("Urna {} is assigned to section {}", device_id, section));
+            }
+            let config = request.config.as_object_mut()
+                .ok_or_else(|| anyhow!("Bundle config must be a JSON object"))?;
+            config.insert("section".to_string(), serde_json::Value::String(section.key()));
+        }
+
+        let ballot = self.packages
+            .export_package(request.election_id, request.region.as_deref())
+            .await?;
+        let voter_snapshot = self.voter_roll
+            .voter_snapshot(&request.zone, &request.section)
+            .await?;
+        let contents = BundleContents::new(
+            request.config,
+            serde_json::to_value(&ballot)?,
+            voter_snapshot,
+            request.assets,
+        )?;
+
+        let bundle = seal_bundle(device_id, &sealing_key.public_key, &contents, self.signing_key.as_ref())?;
+        self.issued.write().await.push(IssuedBundle {
+            bundle_id: bundle.bundle_id,
+            device_id: device_id.to_string(),
+            election_id: request.election_id,
+            zone: request.zone.clone(),
+            section: request.section.clone(),
+            recipient_key_sha256: bundle.recipient_key_sha256.clone(),
+            manifest: contents.manifest,
+            eligibility_batch,
+            created_at: bundle.created_at,
+        });
+
+        log::info!("Provisioning bundle {} created for urna {}", bundle.bundle_id, device_id);
+        Ok(bundle)
+    }
+
+    /// Seções com urna provisionada na eleição, por zona
+    pub async fn provisioned_sections(&self, election_id: Uuid) -> BTreeMap<String, BTreeSet<String>> {
+        let mut zones: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for bundle in self.issued.read().await.iter().filter(|b| b.election_id == election_id) {
+            zones.entry(bundle.zone.clone()).or_default().insert(bundle.section.clone());
+        }
+        zones
+    }
+
+    /// Pacotes emitidos para a urna
+    pub async fn issued_bundles(&self, device_id: &str) -> Vec<IssuedBundle> {
+        self.issued.read().await.iter()
+            .filter(|bundle| bundle.device_id == device_id)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Cifra o conteúdo para a chave de selagem e assina o envelope
+pub fn seal_bundle(
+    device_id: &str,
+    sealing_key: &RsaPublicKey,
+    contents: &BundleContents,
+    signing_key: &dyn SigningKey,
+) -> Result<ProvisioningBundle> {
+    let mut content_key = [0u8; 32];
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut content_key);
+    OsRng.fill_bytes(&mut nonce);
+
+    let wrapped_key = sealing_key.encrypt(&mut OsRng, Oaep::new::<Sha256>(), &content_key)?;
+    let mut bundle = ProvisioningBundle {
+        format_version: PROVISIONING_FORMAT_VERSION,
+        bundle_id: Uuid::new_v4(),
+        device_id: device_id.to_string(),
+        recipient_key_sha256: recipient_key_sha256(sealing_key.to_public_key_der()?.as_bytes()),
+        wrapped_key: general_purpose::STANDARD.encode(wrapped_key),
+        nonce: general_purpose::STANDARD.encode(nonce),
+        ciphertext: String::new(),
+        created_at: Utc::now(),
+        signature: String::new(),
+    };
+
+    let cipher = Aes256Gcm::new_from_slice(&content_key)
+        .map_err(|_| anyhow!("Invalid content key"))?;
+    let plaintext = serde_json::to_vec(contents)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &bundle.associated_data() })
+        .map_err(|_| anyhow!("Failed to encrypt provisioning bundle"))?;
+    bundle.ciphertext = general_purpose::STANDARD.encode(ciphertext);
+    bundle.signature = hex::encode(signing_key.try_sign(&bundle.signed_message())?);
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+    use fortis_domain::provisioning::VoterSnapshot;
+    use ring::signature::{UnparsedPublicKey, ED25519};
+    use rsa::RsaPrivateKey;
+
+    fn contents() -> BundleContents {
+        BundleContents::new(
+            serde_json::json!({ "zone": "0001", "section": "0042" }),
+            serde_json::json!({ "payload": "{}" }),
+            VoterSnapshot {
+                zone: "0001".to_string(),
+                section: "0042".to_string(),
+                generated_at: Utc::now(),
+                voters: vec![],
+            },
+            vec![BundleAsset::new("audio/confirma.ogg", "audio/ogg", b"OggS")],
+        )
+        .unwrap()
+    }
+
+    /// Papel do TPM da urna: decifra a chave de conteúdo com a chave privada
+    fn open(bundle: &ProvisioningBundle, private_key: &RsaPrivateKey) -> Result<BundleContents> {
+        let content_key = private_key.decrypt(
+            Oaep::new::<Sha256>(),
+            &general_purpose::STANDARD.decode(&bundle.wrapped_key)?,
+        )?;
+        let cipher = Aes256Gcm::new_from_slice(&content_key).map_err(|_| anyhow!("invalid key"))?;
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&general_purpose::STANDARD.decode(&bundle.nonce)?),
+                Payload {
+                    msg: &general_purpose::STANDARD.decode(&bundle.ciphertext)?,
+                    aad: &bundle.associated_data(),
+                },
+            )
+            .map_err(|_| anyhow!("decryption failed"))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    #[test]
+    fn test_bundle_opens_only_with_device_key() {
+        let device_key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let other_key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let (signing_key, signing_public) = ThresholdUtils::generate_key_pair().unwrap();
+
+        let bundle = seal_bundle("URNA-1", &device_key.to_public_key(), &contents(), &signing_key).unwrap();
+        UnparsedPublicKey::new(&ED25519, hex::decode(signing_public).unwrap())
+            .verify(&bundle.signed_message(), &hex::decode(&bundle.signature).unwrap())
+            .unwrap();
+
+        let opened = open(&bundle, &device_key).unwrap();
+        assert!(opened.verify_manifest().is_ok());
+        assert_eq!(opened.assets[0].bytes().unwrap(), b"OggS");
+        assert!(open(&bundle, &other_key).is_err());
+    }
+
+    #[test]
+    fn test_header_is_bound_to_ciphertext() {
+        let device_key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+
+        let mut bundle = seal_bundle("URNA-1", &device_key.to_public_key(), &contents(), &signing_key).unwrap();
+        // Cabeçalho reapontado para outra urna não decifra, mesmo com a chave certa
+        bundle.device_id = "URNA-2".to_string();
+        assert!(open(&bundle, &device_key).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Comprovantes de votação e verificação pública
+//!
+//! Cada voto recebe um código de rastreamento derivado do identificador do
+//! voto e do hash da cédula cifrada. O código é registrado no log
+//! transparente sem o conteúdo do voto; o eleitor consulta o código e
+//! recebe a prova de inclusão Merkle, confirmando que o voto foi contado
+//! sem revelar a escolha.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use fortis_domain::log_proof::{TreeHead, VoteInclusion};
+use fortis_domain::region::SectionRef;
+pub use fortis_domain::receipt::{
+    ballot_hash, normalize_tracking_code, tracking_code, InclusionData, ReceiptPayload, RECEIPT_PAYLOAD_VERSION,
+};
+
+use crate::transparency::election_logs::{
+    ElectionEvent, ElectionEventType, ElectionTransparencyLog, MerkleProof,
+};
+
+/// Resultado da verificação pública de um código de rastreamento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteVerification {
+    pub tracking_code: String,
+    pub election_id: Uuid,
+    pub counted: bool,
+    pub log_index: u64,
+    pub event_hash: String,
+    /// Prova de inclusão relativa ao tamanho atual do log
+    pub inclusion_proof: MerkleProof,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct RecordedVote {
+    election_id: Uuid,
+    log_index: u64,
+    event_hash: String,
+    recorded_at: DateTime<Utc>,
+}
+
+/// Serviço de comprovantes de votação
+pub struct ReceiptService {
+    transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
+    votes: RwLock<HashMap<String, RecordedVote>>,
+}
+
+impl ReceiptService {
+    pub fn new(transparency_log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        Self {
+            transparency_log,
+            votes: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registra o voto no log transparente e devolve o conteúdo do QR code;
+    /// a seção da urna, quando conhecida, vai no evento como `region`
+    pub async fn record_vote(
+        &self,
+        vote_id: Uuid,
+        election_id: Uuid,
+        ballot_hash: &str,
+        section: Option<&SectionRef>,
+    ) -> Result<ReceiptPayload> {
+        let code = tracking_code(vote_id, ballot_hash);
+        if self.votes.read().await.contains_key(&code) {
+            return Err(anyhow!("Vote {} already recorded", vote_id));
+        }
+
+        let now = Utc::now();
+        let (proof, event_hash) = {
+            let mut log = self.transparency_log.write().await;
+            let mut data = serde_json::json!({
+                "tracking_code": code,
+                "ballot_hash": ballot_hash,
+            });
+            if let Some(section) = section {
+                data["region"] = serde_json::Value::String(section.key());
+            }
+            let proof = log.append_election_event(ElectionEvent {
+                id: vote_id.to_string(),
+                event_type: ElectionEventType::VoteCast,
+                election_id: election_id.to_string(),
+                data,
+                timestamp: now,
+                source: "Urna".to_string(),
+            })?;
+            let event_hash = log.get_log_entry(proof.log_index)
+                .map(|entry| entry.event_hash.clone())
+                .ok_or_else(|| anyhow!("Log entry {} not found", proof.log_index))?;
+            (proof, event_hash)
+        };
+
+        self.votes.write().await.insert(code.clone(), RecordedVote {
+            election_id,
+            log_index: proof.log_index,
+            event_hash: event_hash.clone(),
+            recorded_at: now,
+        });
+
+        Ok(ReceiptPayload {
+            v: RECEIPT_PAYLOAD_VERSION,
+            vote_id,
+            election_id,
+            tracking_code: code,
+            inclusion: Some(InclusionData {
+                log_index: proof.log_index,
+                event_hash,
+                root_hash: proof.merkle_proof.root_hash,
+                tree_size: proof.merkle_proof.tree_size,
+            }),
+        })
+    }
+
+    /// Eleição e posição no log do voto registrado com o código informado
+    pub async fn recorded_entry(&self, tracking_code: &str) -> Option<(Uuid, u64)> {
+        let code = normalize_tracking_code(tracking_code)?;
+        self.votes.read().await.get(&code).map(|vote| (vote.election_id, vote.log_index))
+    }
+
+    /// Provas de inclusão dos votos informados, todas relativas a uma STH
+    /// publicada na hora, para a urna conferir antes de dar o voto por
+    /// sincronizado. Códigos sem voto registrado ficam de fora.
+    pub async fn inclusions(&self, tracking_codes: &[String]) -> Result<HashMap<String, VoteInclusion>> {
+        let votes = self.votes.read().await;
+        let mut log = self.transparency_log.write().await;
+        let sth = log.publish_tree_head()?;
+        let tree_head = TreeHead::from(&sth);
+
+        let mut inclusions = HashMap::new();
+        for code in tracking_codes {
+            let Some(vote) = votes.get(code) else {
+                continue;
+            };
+            let proof = log.inclusion_proof_at(vote.log_index, sth.tree_size)?;
+            inclusions.insert(code.clone(), VoteInclusion {
+                log_index: vote.log_index,
+                event_hash: vote.event_hash.clone(),
+                proof: proof.into(),
+                tree_head: tree_head.clone(),
+            });
+        }
+        Ok(inclusions)
+    }
+
+    /// Verifica se o voto do código informado consta no log
+    pub async fn verify(&self, tracking_code: &str) -> Result<Option<VoteVerification>> {
+        let Some(code) = normalize_tracking_code(tracking_code) else {
+            return Err(anyhow!("Invalid tracking code format"));
+        };
+        let Some(vote) = self.votes.read().await.get(&code).cloned() else {
+            return Ok(None);
+        };
+
+        let inclusion_proof = self.transparency_log.read().await.current_inclusion_proof(vote.log_index)?;
+        Ok(Some(VoteVerification {
+            tracking_code: code,
+            election_id: vote.election_id,
+            counted: true,
+            log_index: vote.log_index,
+            event_hash: vote.event_hash,
+            inclusion_proof,
+            recorded_at: vote.recorded_at,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transparency::election_logs::LogConfig;
+
+    fn service() -> ReceiptService {
+        let log = ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        });
+        ReceiptService::new(Arc::new(RwLock::new(log)))
+    }
+
+    #[tokio::test]
+    async fn test_recorded_vote_is_verifiable_by_tracking_code() {
+        let service = service();
+        let election_id = Uuid::new_v4();
+        let payload = service.record_vote(Uuid::new_v4(), election_id, "abc123", None).await.unwrap();
+        service.record_vote(Uuid::new_v4(), election_id, "def456", None).await.unwrap();
+
+        let typed = payload.tracking_code.to_lowercase().replace('-', "");
+        let verification = service.verify(&typed).await.unwrap().unwrap();
+        assert!(verification.counted);
+        assert_eq!(verification.election_id, election_id);
+        assert_eq!(verification.inclusion_proof.tree_size, 2);
+        assert_eq!(verification.inclusion_proof.leaf_index, payload.inclusion.unwrap().log_index);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_malformed_codes() {
+        let service = service();
+        let code = tracking_code(Uuid::new_v4(), "abc123");
+        assert_eq!(code.len(), 19);
+        assert!(service.verify(&code).await.unwrap().is_none());
+        assert!(service.verify("not-a-code").await.is_err());
+    }
+}
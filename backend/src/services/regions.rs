@@ -0,0 +1,303 @@
+//! Cadastro da hierarquia eleitoral: UF → município → zona → seção
+//!
+//! Cada nível só é cadastrado abaixo de um pai já existente. A seção
+//! recebe no máximo uma urna e a urna fica em uma única seção; a troca
+//! exige liberar a urna antes. A seção da urna é a referência do backend
+//! para o voto: o voto que declara outra seção é recusado, e o boletim de
+//! seção fora do cadastro não entra na apuração.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::RwLock;
+
+pub use fortis_domain::region::{RegionScope, SectionRef};
+
+/// Unidade federativa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FederativeUnit {
+    pub code: String,
+    pub name: String,
+}
+
+/// Município, pelo código TSE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Municipality {
+    pub state: String,
+    pub code: String,
+    pub name: String,
+}
+
+/// Zona eleitoral do município
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectoralZone {
+    pub state: String,
+    pub municipality_code: String,
+    pub number: String,
+    pub name: Option<String>,
+}
+
+/// Seção eleitoral e a urna designada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectoralSection {
+    #[serde(flatten)]
+    pub section: SectionRef,
+    pub polling_place: Option<String>,
+    pub registered_voters: u64,
+    pub urna_id: Option<String>,
+    pub assigned_by: Option<String>,
+    pub assigned_at: Option<DateTime<Utc>>,
+}
+
+/// Recorte do cadastro
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RegionListing {
+    pub states: Vec<FederativeUnit>,
+    pub municipalities: Vec<Municipality>,
+    pub zones: Vec<ElectoralZone>,
+    pub sections: Vec<ElectoralSection>,
+}
+
+#[derive(Default)]
+struct Registry {
+    states: BTreeMap<String, FederativeUnit>,
+    /// Chaves no formato de `RegionScope` (`SP/71072`, `SP/71072/0001`, ...)
+    municipalities: BTreeMap<String, Municipality>,
+    zones: BTreeMap<String, ElectoralZone>,
+    sections: BTreeMap<String, ElectoralSection>,
+    /// Seção de cada urna designada
+    urnas: HashMap<String, String>,
+}
+
+/// Serviço do cadastro de regiões
+pub struct RegionService {
+    registry: RwLock<Registry>,
+}
+
+impl Default for RegionService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegionService {
+    pub fn new() -> Self {
+        Self {
+            registry: RwLock::new(Registry::default()),
+        }
+    }
+
+    pub async fn add_state(&self, code: &str, name: &str) -> Result<FederativeUnit> {
+        let scope = RegionScope::state(code)?;
+        let state = FederativeUnit { code: scope.state, name: name.to_string() };
+        self.registry.write().await.states.insert(state.code.clone(), state.clone());
+        Ok(state)
+    }
+
+    pub async fn add_municipality(&self, state: &str, code: &str, name: &str) -> Result<Municipality> {
+        let scope = RegionScope::parse(&format!("{}/{}", state, code))?;
+        let mut registry = self.registry.write().await;
+        if !registry.states.contains_key(&scope.state) {
+            return Err(anyhow!("Federative unit {} not registered", scope.state));
+        }
+        let municipality = Municipality {
+            state: scope.state.clone(),
+            code: code.trim().to_string(),
+            name: name.to_string(),
+        };
+        registry.municipalities.insert(scope.to_string(), municipality.clone());
+        Ok(municipality)
+    }
+
+    pub async fn add_zone(&self, state: &str, municipality_code: &str, number: &str, name: Option<String>) -> Result<ElectoralZone> {
+        let scope = RegionScope::parse(&format!("{}/{}/{}", state, municipality_code, number))?;
+        let parent = RegionScope { zone: None, ..scope.clone() };
+        let mut registry = self.registry.write().await;
+        if !registry.municipalities.contains_key(&parent.to_string()) {
+            return Err(anyhow!("Municipality {} not registered", parent));
+        }
+        let zone = ElectoralZone {
+            state: scope.state.clone(),
+            municipality_code: municipality_code.trim().to_string(),
+            number: number.trim().to_string(),
+            name,
+        };
+        registry.zones.insert(scope.to_string(), zone.clone());
+        Ok(zone)
+    }
+
+    /// Cadastra ou atualiza a seção, mantendo a urna designada
+    pub async fn add_section(&self, section: SectionRef, polling_place: Option<String>, registered_voters: u64) -> Result<ElectoralSection> {
+        let zone = RegionScope { section: None, ..section.scope() };
+        let mut registry = self.registry.write().await;
+        if !registry.zones.contains_key(&zone.to_string()) {
+            return Err(anyhow!("Electoral zone {} not registered", zone));
+        }
+        let key = section.key();
+        let previous = registry.sections.remove(&key);
+        let entry = ElectoralSection {
+            section,
+            polling_place,
+            registered_voters,
+            urna_id: previous.as_ref().and_then(|s| s.urna_id.clone()),
+            assigned_by: previous.as_ref().and_then(|s| s.assigned_by.clone()),
+            assigned_at: previous.and_then(|s| s.assigned_at),
+        };
+        registry.sections.insert(key, entry.clone());
+        Ok(entry)
+    }
+
+    /// Remove a seção; com urna designada, a urna precisa ser liberada antes
+    pub async fn remove_section(&self, section: &SectionRef) -> Result<()> {
+        let mut registry = self.registry.write().await;
+        match registry.sections.get(&section.key()) {
+            None => Err(anyhow!("Section {} not registered", section)),
+            Some(entry) if entry.urna_id.is_some() => {
+                Err(anyhow!("Section {} still has an urna assigned", section))
+            }
+            Some(_) => {
+                registry.sections.remove(&section.key());
+                Ok(())
+            }
+        }
+    }
+
+    /// Cadastro dentro do recorte; sem recorte, o cadastro inteiro
+    pub async fn listing(&self, scope: Option<&RegionScope>) -> RegionListing {
+        let registry = self.registry.read().await;
+        let within = |key: &str| match (scope, RegionScope::parse(key)) {
+            (None, _) => true,
+            (Some(scope), Ok(entry)) => scope.covers(&entry) || entry.covers(scope),
+            (Some(_), Err(_)) => false,
+        };
+        RegionListing {
+            states: registry.states.iter().filter(|(k, _)| within(k)).map(|(_, v)| v.clone()).collect(),
+            municipalities: registry.municipalities.iter().filter(|(k, _)| within(k)).map(|(_, v)| v.clone()).collect(),
+            zones: registry.zones.iter().filter(|(k, _)| within(k)).map(|(_, v)| v.clone()).collect(),
+            sections: registry.sections.values()
+                .filter(|s| scope.map_or(true, |scope| scope.contains(&s.section)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub async fn section(&self, section: &SectionRef) -> Option<ElectoralSection> {
+        self.registry.read().await.sections.get(&section.key()).cloned()
+    }
+
+    pub async fn is_registered(&self, section: &SectionRef) -> bool {
+        self.registry.read().await.sections.contains_key(&section.key())
+    }
+
+    /// Designa a urna para a seção
+    pub async fn assign_urna(&self, section: &SectionRef, urna_id: &str, assigned_by: &str) -> Result<ElectoralSection> {
+        let mut registry = self.registry.write().await;
+        let key = section.key();
+        if let Some(current) = registry.urnas.get(urna_id) {
+            if *current != key {
+                return Err(anyhow!("Urna {} already assigned to section {}", urna_id, current));
+            }
+        }
+        let entry = registry.sections.get_mut(&key)
+            .ok_or_else(|| anyhow!("Section {} not registered", section))?;
+        match entry.urna_id.as_deref() {
+            Some(current) if current == urna_id => return Ok(entry.clone()),
+            Some(current) => return Err(anyhow!("Section {} already has urna {}", section, current)),
+            None => {}
+        }
+        entry.urna_id = Some(urna_id.to_string());
+        entry.assigned_by = Some(assigned_by.to_string());
+        entry.assigned_at = Some(Utc::now());
+        let entry = entry.clone();
+        registry.urnas.insert(urna_id.to_string(), key);
+        log::info!("Urna {} assigned to section {} by {}", urna_id, section, assigned_by);
+        Ok(entry)
+    }
+
+    /// Libera a urna da seção
+    pub async fn unassign_urna(&self, urna_id: &str) -> Result<SectionRef> {
+        let mut registry = self.registry.write().await;
+        let key = registry.urnas.remove(urna_id)
+            .ok_or_else(|| anyhow!("Urna {} is not assigned to a section", urna_id))?;
+        let entry = registry.sections.get_mut(&key)
+            .ok_or_else(|| anyhow!("Section {} not registered", key))?;
+        entry.urna_id = None;
+        entry.assigned_by = None;
+        entry.assigned_at = None;
+        Ok(entry.section.clone())
+    }
+
+    /// Seção em que a urna está designada
+    pub async fn section_of_urna(&self, urna_id: &str) -> Option<SectionRef> {
+        let registry = self.registry.read().await;
+        let key = registry.urnas.get(urna_id)?;
+        registry.sections.get(key).map(|s| s.section.clone())
+    }
+
+    /// Seção do voto recebido da urna: a da designação, que o voto não pode contradizer
+    pub async fn vote_section(&self, urna_id: &str, declared: Option<&SectionRef>) -> Result<Option<SectionRef>> {
+        let assigned = self.section_of_urna(urna_id).await;
+        if let Some(declared) = declared {
+            match &assigned {
+                Some(section) if section != declared => {
+                    return Err(anyhow!(
+                        "Vote declares section {} but urna {} is assigned to {}",
+                        declared, urna_id, section
+                    ));
+                }
+                None => log::warn!("Urna {} is not assigned; ignoring declared section {}", urna_id, declared),
+                Some(_) => {}
+            }
+        }
+        Ok(assigned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn registry() -> (RegionService, SectionRef) {
+        let service = RegionService::new();
+        service.add_state("sp", "São Paulo").await.unwrap();
+        service.add_municipality("SP", "71072", "São Paulo").await.unwrap();
+        service.add_zone("SP", "71072", "0001", None).await.unwrap();
+        let section = SectionRef::new("SP", "71072", "0001", "0123").unwrap();
+        service.add_section(section.clone(), Some("EE Caetano de Campos".to_string()), 350).await.unwrap();
+        (service, section)
+    }
+
+    #[tokio::test]
+    async fn test_levels_require_registered_parent() {
+        let (service, _) = registry().await;
+        assert!(service.add_municipality("RJ", "60011", "Rio de Janeiro").await.is_err());
+        assert!(service.add_zone("SP", "62910", "0002", None).await.is_err());
+        let orphan = SectionRef::new("SP", "71072", "0002", "0001").unwrap();
+        assert!(service.add_section(orphan, None, 100).await.is_err());
+
+        let listing = service.listing(Some(&RegionScope::parse("SP/71072/0001").unwrap())).await;
+        assert_eq!((listing.states.len(), listing.zones.len(), listing.sections.len()), (1, 1, 1));
+        assert!(service.listing(Some(&RegionScope::parse("RJ").unwrap())).await.sections.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_urna_assignment_is_one_to_one() {
+        let (service, section) = registry().await;
+        let other = SectionRef::new("SP", "71072", "0001", "0124").unwrap();
+        service.add_section(other.clone(), None, 300).await.unwrap();
+
+        service.assign_urna(&section, "URNA-1", "admin").await.unwrap();
+        assert!(service.assign_urna(&section, "URNA-2", "admin").await.is_err());
+        assert!(service.assign_urna(&other, "URNA-1", "admin").await.is_err());
+        assert!(service.remove_section(&section).await.is_err());
+
+        assert_eq!(service.vote_section("URNA-1", None).await.unwrap(), Some(section.clone()));
+        assert!(service.vote_section("URNA-1", Some(&other)).await.is_err());
+        assert_eq!(service.vote_section("URNA-9", Some(&other)).await.unwrap(), None);
+
+        assert_eq!(service.unassign_urna("URNA-1").await.unwrap(), section);
+        service.assign_urna(&other, "URNA-1", "admin").await.unwrap();
+        assert_eq!(service.section_of_urna("URNA-1").await, Some(other));
+    }
+}
@@ -0,0 +1,1057 @@
+//! Apuração e publicação dos resultados
+//!
+//! Soma os boletins de urna (BUs) recebidos após o encerramento da votação,
+//! no total ou por região (UF, município ou recorte da hierarquia até a
+//! seção, como `SP/71072/0001`), e classifica os candidatos de
+//! cada cargo. Empates são desfeitos pela votação do partido no cargo e,
+//! persistindo, pelo menor número; a regra aplicada fica registrada no
+//! resultado. O resultado fica em cache até chegar um novo boletim ou
+//! expirar o prazo configurado. Com o cadastro de regiões, só entram
+//! boletins de seções cadastradas.
+//!
+//! Com a eleição finalizada, o resultado total é publicado em um manifesto
+//! assinado (Ed25519) cujo hash e assinatura entram no log transparente.
+//!
+//! Recontagens e conciliações corrigem boletins já recebidos, sempre com a
+//! referência do registro que justifica a correção (disputa, conciliação
+//! ou auditoria). Cada rodada de contagem congela os boletins do momento, e
+//! a comparação entre duas rodadas lista as diferenças por candidato e por
+//! seção, com as justificativas das correções feitas entre elas.
+//!
+//! Um votável no boletim é o número do candidato (`13`) ou o cargo seguido
+//! do número (`governor:13`), necessário quando o número se repete entre
+//! cargos na mesma seção. Brancos e nulos têm votáveis próprios (`BRANCO`,
+//! `NULO`, `governor:BRANCO`) e são somados por cargo fora dos votos
+//! válidos; sem o cargo, contam no cargo da apuração quando há só um.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use anyhow::{Result, anyhow};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use sha2::{Sha256, Digest};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use fortis_domain::{split_tally_key, Votable};
+
+use crate::analytics::SectionResult;
+use crate::crypto::hsm::SigningKey;
+use crate::models::{Candidate, CandidatePosition};
+use crate::services::candidate::CandidateService;
+use crate::services::election::{ElectionService, ElectionStatus};
+use crate::services::regions::{RegionScope, RegionService};
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Configuração da apuração
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultsConfig {
+    /// Validade do resultado em cache
+    pub cache_ttl_seconds: u64,
+}
+
+/// Regra que desfez um empate
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreakRule {
+    /// Maior votação do partido no cargo
+    PartyVotes,
+    /// Menor número do candidato
+    LowestNumber,
+}
+
+/// Candidato classificado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedCandidate {
+    pub rank: u32,
+    pub candidate_id: Uuid,
+    pub number: u32,
+    pub name: String,
+    pub party: String,
+    pub region: Option<String>,
+    pub votes: u64,
+    /// Percentual dos votos válidos do cargo
+    pub share: f64,
+    /// Regra que o colocou abaixo do anterior, empatado em votos
+    pub tie_broken_by: Option<TieBreakRule>,
+}
+
+/// Classificação de um cargo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionResult {
+    pub position: CandidatePosition,
+    /// Votos nos candidatos do cargo, base dos percentuais
+    pub valid_votes: u64,
+    pub blank_votes: u64,
+    pub null_votes: u64,
+    pub ranking: Vec<RankedCandidate>,
+}
+
+/// Resultado apurado da eleição ou de uma região
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionResults {
+    pub election_id: Uuid,
+    pub region: Option<String>,
+    pub sections: usize,
+    pub registered_voters: u64,
+    pub votes_cast: u64,
+    pub positions: Vec<PositionResult>,
+    /// Votos em branco de todos os cargos
+    pub blank_votes: u64,
+    /// Votos nulos de todos os cargos
+    pub null_votes: u64,
+    /// Votos em votáveis sem candidato correspondente (legenda)
+    pub unmatched_votes: BTreeMap<String, u64>,
+    /// Resumo dos boletins somados, na ordem das seções
+    pub bulletins_digest: String,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Manifesto do resultado publicado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultsManifest {
+    pub election_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub results: ElectionResults,
+}
+
+/// Manifesto assinado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedResultsManifest {
+    pub manifest: ResultsManifest,
+    pub manifest_hash: String,
+    pub signature: String,
+    pub public_key: String,
+    pub log_index: Option<u64>,
+}
+
+/// Totais de um boletim recebido, sem os votos por votável
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulletinTotals {
+    pub state: String,
+    pub municipality_code: String,
+    pub zone: String,
+    pub section: String,
+    pub registered_voters: u64,
+    pub votes_cast: u64,
+    pub bu_digest: String,
+}
+
+/// Origem de uma rodada de contagem
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundKind {
+    /// Primeira contagem dos boletins recebidos
+    Initial,
+    Recount,
+    Reconciliation,
+}
+
+/// Tipo do registro que justifica uma correção
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JustificationKind {
+    /// Impugnação ou disputa registrada fora da FORTIS (processo na Justiça Eleitoral)
+    Dispute,
+    /// Conciliação de estado da urna de contingência
+    Reconciliation,
+    /// Auditoria de limitação de risco
+    RiskLimitingAudit,
+}
+
+/// Referência ao registro que justifica uma correção
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Justification {
+    pub kind: JustificationKind,
+    pub reference: String,
+    /// Endereço do registro; para conciliações e auditorias é derivado da referência
+    pub href: Option<String>,
+    pub note: Option<String>,
+}
+
+impl Justification {
+    fn resolved(mut self, election_id: Uuid) -> Self {
+        if self.href.is_none() {
+            self.href = match self.kind {
+                JustificationKind::Reconciliation => {
+                    Some(format!("/api/v1/urnas/contingency/transfers/{}", self.reference))
+                }
+                JustificationKind::RiskLimitingAudit => {
+                    Some(format!("/api/v1/elections/{}/audits/rla/{}", election_id, self.reference))
+                }
+                JustificationKind::Dispute => None,
+            };
+        }
+        self
+    }
+}
+
+/// Boletim substituído entre duas rodadas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulletinAmendment {
+    pub section_key: String,
+    pub previous_digest: String,
+    pub new_digest: String,
+    pub justification: Justification,
+    pub amended_by: String,
+    pub amended_at: DateTime<Utc>,
+}
+
+/// Rodada de contagem encerrada, com os boletins do momento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountingRound {
+    pub number: u32,
+    pub kind: RoundKind,
+    pub closed_by: String,
+    pub closed_at: DateTime<Utc>,
+    pub justifications: Vec<Justification>,
+    /// Correções feitas desde a rodada anterior
+    pub amendments: Vec<BulletinAmendment>,
+    pub results: ElectionResults,
+    #[serde(skip)]
+    sections: BTreeMap<String, SectionResult>,
+}
+
+impl CountingRound {
+    pub fn label(&self) -> String {
+        format!("round{}", self.number)
+    }
+}
+
+/// Mudança de votos de um candidato entre rodadas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateDelta {
+    pub position: CandidatePosition,
+    pub candidate_id: Uuid,
+    pub number: u32,
+    pub name: String,
+    pub from_votes: u64,
+    pub to_votes: u64,
+    pub delta: i64,
+    pub from_rank: Option<u32>,
+    pub to_rank: Option<u32>,
+}
+
+/// Como a seção mudou entre rodadas
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionChange {
+    Added,
+    Removed,
+    Amended,
+}
+
+/// Mudança no boletim de uma seção entre rodadas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionDelta {
+    pub section_key: String,
+    pub state: String,
+    pub change: SectionChange,
+    pub from_digest: Option<String>,
+    pub to_digest: Option<String>,
+    pub votes_cast_delta: i64,
+    /// Diferença por votável, só os que mudaram
+    pub votable_deltas: BTreeMap<String, i64>,
+    pub justifications: Vec<Justification>,
+}
+
+/// Diferenças entre duas rodadas de contagem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultsDiff {
+    pub election_id: Uuid,
+    pub from: String,
+    pub to: String,
+    pub from_digest: String,
+    pub to_digest: String,
+    pub votes_cast_delta: i64,
+    pub candidates: Vec<CandidateDelta>,
+    pub sections: Vec<SectionDelta>,
+    /// Justificativas das rodadas posteriores à de origem
+    pub justifications: Vec<Justification>,
+    /// Seções alteradas sem correção justificada entre as rodadas
+    pub unjustified_sections: Vec<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Serviço de apuração e publicação dos resultados
+pub struct ResultsService {
+    elections: Arc<ElectionService>,
+    candidates: Arc<CandidateService>,
+    signing_key: Arc<dyn SigningKey>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+    regions: Option<Arc<RegionService>>,
+    config: ResultsConfig,
+    bulletins: RwLock<HashMap<Uuid, BTreeMap<String, SectionResult>>>,
+    cache: RwLock<HashMap<(Uuid, Option<String>), ElectionResults>>,
+    manifests: RwLock<HashMap<Uuid, SignedResultsManifest>>,
+    rounds: RwLock<HashMap<Uuid, Vec<CountingRound>>>,
+    /// Correções ainda não incluídas numa rodada
+    amendments: RwLock<HashMap<Uuid, Vec<BulletinAmendment>>>,
+}
+
+impl ResultsService {
+    pub fn new(
+        elections: Arc<ElectionService>,
+        candidates: Arc<CandidateService>,
+        signing_key: impl SigningKey + 'static,
+        config: ResultsConfig,
+    ) -> Self {
+        Self {
+            elections,
+            candidates,
+            signing_key: Arc::new(signing_key),
+            transparency_log: None,
+            regions: None,
+            config,
+            bulletins: RwLock::new(HashMap::new()),
+            cache: RwLock::new(HashMap::new()),
+            manifests: RwLock::new(HashMap::new()),
+            rounds: RwLock::new(HashMap::new()),
+            amendments: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registra os manifestos publicados no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Aceita só boletins de seções do cadastro de regiões
+    pub fn with_regions(mut self, regions: Arc<RegionService>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    /// Validade do resultado em cache, para o cabeçalho `Cache-Control`
+    pub fn cache_ttl_seconds(&self) -> u64 {
+        self.config.cache_ttl_seconds
+    }
+
+    /// Recebe o boletim de uma seção; reenviar o mesmo boletim não tem efeito
+    pub async fn record_bulletin(&self, election_id: Uuid, bulletin: SectionResult) -> Result<()> {
+        let status = self.status(election_id).await?;
+        if !matches!(status, ElectionStatus::Closed | ElectionStatus::Finalized) {
+            return Err(anyhow!("Bulletins are accepted only after voting closes (status: {})", status.as_str()));
+        }
+        if self.manifests.read().await.contains_key(&election_id) {
+            return Err(anyhow!("Results already published for election {}", election_id));
+        }
+        if let Some(regions) = &self.regions {
+            let section = bulletin.section_ref()?;
+            if !regions.is_registered(&section).await {
+                return Err(anyhow!("Section {} is not registered", section));
+            }
+        }
+
+        let key = section_key(&bulletin);
+        {
+            let mut bulletins = self.bulletins.write().await;
+            let sections = bulletins.entry(election_id).or_default();
+            if let Some(existing) = sections.get(&key) {
+                if existing.bu_digest == bulletin.bu_digest {
+                    return Ok(());
+                }
+                return Err(anyhow!("Conflicting bulletin for section {}", key));
+            }
+            sections.insert(key, bulletin);
+        }
+
+        self.cache.write().await.retain(|(id, _), _| *id != election_id);
+        Ok(())
+    }
+
+    /// Substitui o boletim de uma seção já contada, na recontagem ou conciliação
+    pub async fn amend_bulletin(
+        &self,
+        election_id: Uuid,
+        bulletin: SectionResult,
+        justification: Justification,
+        amended_by: &str,
+    ) -> Result<BulletinAmendment> {
+        if self.manifests.read().await.contains_key(&election_id) {
+            return Err(anyhow!("Results already published for election {}", election_id));
+        }
+        if self.rounds.read().await.get(&election_id).map_or(true, |rounds| rounds.is_empty()) {
+            return Err(anyhow!("Bulletins can only be amended after the initial counting round"));
+        }
+        if justification.reference.trim().is_empty() {
+            return Err(anyhow!("Amendment requires a justifying record"));
+        }
+
+        let key = section_key(&bulletin);
+        // Correção e boletim mudam juntos, para a rodada que fechar levar os dois
+        let mut pending = self.amendments.write().await;
+        let amendment = {
+            let mut bulletins = self.bulletins.write().await;
+            let sections = bulletins.entry(election_id).or_default();
+            let previous = sections.get(&key)
+                .ok_or_else(|| anyhow!("No bulletin to amend for section {}", key))?;
+            if previous.bu_digest == bulletin.bu_digest {
+                return Err(anyhow!("Amended bulletin for section {} is identical to the current one", key));
+            }
+            let amendment = BulletinAmendment {
+                section_key: key.clone(),
+                previous_digest: previous.bu_digest.clone(),
+                new_digest: bulletin.bu_digest.clone(),
+                justification: justification.resolved(election_id),
+                amended_by: amended_by.to_string(),
+                amended_at: Utc::now(),
+            };
+            sections.insert(key, bulletin);
+            amendment
+        };
+
+        pending.entry(election_id).or_default().push(amendment.clone());
+        drop(pending);
+        self.cache.write().await.retain(|(id, _), _| *id != election_id);
+        Ok(amendment)
+    }
+
+    /// Encerra uma rodada de contagem com os boletins atuais e as correções pendentes
+    pub async fn close_round(
+        &self,
+        election_id: Uuid,
+        kind: RoundKind,
+        justifications: Vec<Justification>,
+        closed_by: &str,
+    ) -> Result<CountingRound> {
+        // Com as rodadas travadas, nenhuma correção entra entre a contagem e o registro da rodada
+        let mut rounds = self.rounds.write().await;
+        let results = self.aggregate(election_id, None).await?;
+        let sections = self.bulletins.read().await.get(&election_id).cloned().unwrap_or_default();
+        let election_rounds = rounds.entry(election_id).or_default();
+        match (election_rounds.is_empty(), kind) {
+            (true, RoundKind::Initial) | (false, RoundKind::Recount | RoundKind::Reconciliation) => {}
+            (true, _) => return Err(anyhow!("The first counting round must be the initial count")),
+            (false, _) => return Err(anyhow!("Initial count already closed for election {}", election_id)),
+        }
+
+        let round = CountingRound {
+            number: election_rounds.len() as u32 + 1,
+            kind,
+            closed_by: closed_by.to_string(),
+            closed_at: Utc::now(),
+            justifications: justifications.into_iter().map(|j| j.resolved(election_id)).collect(),
+            amendments: self.amendments.write().await.remove(&election_id).unwrap_or_default(),
+            results,
+            sections,
+        };
+
+        if let Some(log) = &self.transparency_log {
+            log.write().await.append_election_event(ElectionEvent {
+                id: Uuid::new_v4().to_string(),
+                event_type: ElectionEventType::CountingRoundClosed,
+                election_id: election_id.to_string(),
+                data: serde_json::json!({
+                    "round": round.label(),
+                    "kind": round.kind,
+                    "bulletins_digest": round.results.bulletins_digest,
+                    "amendments": round.amendments.len(),
+                }),
+                timestamp: round.closed_at,
+                source: closed_by.to_string(),
+            })?;
+        }
+
+        election_rounds.push(round.clone());
+        Ok(round)
+    }
+
+    /// Rodadas encerradas da eleição, em ordem
+    pub async fn rounds(&self, election_id: Uuid) -> Vec<CountingRound> {
+        self.rounds.read().await.get(&election_id).cloned().unwrap_or_default()
+    }
+
+    /// Diferenças por candidato e por seção entre duas rodadas (`round1`, `round2`, ...)
+    pub async fn diff(&self, election_id: Uuid, from: &str, to: &str) -> Result<ResultsDiff> {
+        let rounds = self.rounds.read().await;
+        let election_rounds = rounds.get(&election_id)
+            .ok_or_else(|| anyhow!("No counting rounds for election {}", election_id))?;
+        let find = |label: &str| -> Result<&CountingRound> {
+            let number = parse_round(label)?;
+            election_rounds.iter().find(|r| r.number == number)
+                .ok_or_else(|| anyhow!("Counting round {} not found", label))
+        };
+        Ok(diff_rounds(find(from)?, find(to)?, election_rounds))
+    }
+
+    /// Diferenças entre a primeira e a última rodada, quando houve mais de uma
+    pub async fn latest_diff(&self, election_id: Uuid) -> Option<ResultsDiff> {
+        let rounds = self.rounds.read().await;
+        let election_rounds = rounds.get(&election_id)?;
+        match (election_rounds.first(), election_rounds.last()) {
+            (Some(first), Some(last)) if first.number != last.number => {
+                Some(diff_rounds(first, last, election_rounds))
+            }
+            _ => None,
+        }
+    }
+
+    /// Resultado da eleição ou da região, a partir do cache quando válido
+    pub async fn results(&self, election_id: Uuid, region: Option<&str>) -> Result<ElectionResults> {
+        let status = self.status(election_id).await?;
+        if !matches!(status, ElectionStatus::Closed | ElectionStatus::Finalized | ElectionStatus::Audited) {
+            return Err(anyhow!("Results are not available before voting closes (status: {})", status.as_str()));
+        }
+
+        let key = (election_id, region.map(str::to_string));
+        let ttl = Duration::seconds(self.config.cache_ttl_seconds as i64);
+        if let Some(cached) = self.cache.read().await.get(&key) {
+            if Utc::now() < cached.computed_at + ttl {
+                return Ok(cached.clone());
+            }
+        }
+
+        let results = self.aggregate(election_id, region).await?;
+        self.cache.write().await.insert(key, results.clone());
+        Ok(results)
+    }
+
+    /// Soma os boletins e classifica os candidatos de cada cargo
+    pub async fn aggregate(&self, election_id: Uuid, region: Option<&str>) -> Result<ElectionResults> {
+        let bulletins: Vec<SectionResult> = self.bulletins.read().await
+            .get(&election_id)
+            .map(|sections| sections.values()
+                .filter(|s| region.map_or(true, |r| in_region(s, r)))
+                .cloned()
+                .collect())
+            .unwrap_or_default();
+        if bulletins.is_empty() {
+            return Err(anyhow!("No bulletins for election {}", election_id));
+        }
+
+        let candidates = self.candidates.list_candidates(election_id, None).await;
+        let mut votes: HashMap<Uuid, u64> = HashMap::new();
+        let mut unmatched_votes = BTreeMap::new();
+        // Brancos e nulos por cargo; `None` para os votáveis sem cargo
+        let mut blank: HashMap<Option<CandidatePosition>, u64> = HashMap::new();
+        let mut null: HashMap<Option<CandidatePosition>, u64> = HashMap::new();
+        let mut digest = Sha256::new();
+        let mut regions = BTreeSet::new();
+
+        for bulletin in &bulletins {
+            digest.update(format!(
+                "{}/{}/{}:{}\n",
+                bulletin.municipality_code, bulletin.zone, bulletin.section, bulletin.bu_digest
+            ));
+            regions.insert(bulletin.state.as_str());
+            regions.insert(bulletin.municipality_code.as_str());
+
+            for (votable, count) in &bulletin.votes_by_candidate {
+                let (position, number) = split_tally_key(votable);
+                let counter = match number {
+                    Votable::Blank => &mut blank,
+                    Votable::Null => &mut null,
+                    Votable::Candidate(_) => {
+                        match resolve_votable(&candidates, bulletin, votable) {
+                            Some(candidate) => *votes.entry(candidate.id).or_insert(0) += count,
+                            None => *unmatched_votes.entry(votable.clone()).or_insert(0) += count,
+                        }
+                        continue;
+                    }
+                };
+                *counter.entry(position.and_then(position_from_code)).or_insert(0) += count;
+            }
+        }
+
+        let mut by_position: BTreeMap<CandidatePosition, Vec<&Candidate>> = BTreeMap::new();
+        for candidate in &candidates {
+            let in_scope = candidate.region.as_deref().map_or(true, |r| regions.contains(r));
+            if in_scope || votes.contains_key(&candidate.id) {
+                by_position.entry(candidate.position).or_default().push(candidate);
+            }
+        }
+
+        // Cédula de cargo único: brancos e nulos sem cargo são desse cargo
+        let single_position = by_position.len() == 1;
+        let marks_of = |counter: &HashMap<Option<CandidatePosition>, u64>, position| {
+            let unscoped = if single_position { counter.get(&None).copied().unwrap_or(0) } else { 0 };
+            counter.get(&Some(position)).copied().unwrap_or(0) + unscoped
+        };
+
+        Ok(ElectionResults {
+            election_id,
+            region: region.map(str::to_string),
+            sections: bulletins.len(),
+            registered_voters: bulletins.iter().map(|s| s.registered_voters).sum(),
+            votes_cast: bulletins.iter().map(|s| s.votes_cast).sum(),
+            positions: by_position.into_iter()
+                .map(|(position, candidates)| PositionResult {
+                    blank_votes: marks_of(&blank, position),
+                    null_votes: marks_of(&null, position),
+                    ..rank_position(position, candidates, &votes)
+                })
+                .collect(),
+            blank_votes: blank.values().sum(),
+            null_votes: null.values().sum(),
+            unmatched_votes,
+            bulletins_digest: hex::encode(digest.finalize()),
+            computed_at: Utc::now(),
+        })
+    }
+
+    /// Assina o resultado total da eleição finalizada e o registra no log transparente
+    pub async fn publish(&self, election_id: Uuid) -> Result<SignedResultsManifest> {
+        let status = self.status(election_id).await?;
+        if !matches!(status, ElectionStatus::Finalized | ElectionStatus::Audited) {
+            return Err(anyhow!("Results can only be published for finalized elections (status: {})", status.as_str()));
+        }
+        if let Some(existing) = self.manifests.read().await.get(&election_id) {
+            return Ok(existing.clone());
+        }
+
+        let manifest = ResultsManifest {
+            election_id,
+            generated_at: Utc::now(),
+            results: self.aggregate(election_id, None).await?,
+        };
+        let mut signed = self.sign_manifest(manifest)?;
+
+        if let Some(log) = &self.transparency_log {
+            let proof = log.write().await.append_election_event(ElectionEvent {
+                id: Uuid::new_v4().to_string(),
+                event_type: ElectionEventType::ResultsPublished,
+                election_id: election_id.to_string(),
+                data: serde_json::json!({
+                    "manifest_hash": signed.manifest_hash,
+                    "signature": signed.signature,
+                    "public_key": signed.public_key,
+                    "bulletins_digest": signed.manifest.results.bulletins_digest,
+                    "sections": signed.manifest.results.sections,
+                }),
+                timestamp: Utc::now(),
+                source: "Sistema".to_string(),
+            })?;
+            signed.log_index = Some(proof.log_index);
+        }
+
+        log::info!("Resultado publicado para a eleição {}: {}", election_id, signed.manifest_hash);
+        self.manifests.write().await.insert(election_id, signed.clone());
+        Ok(signed)
+    }
+
+    /// Manifesto publicado da eleição
+    pub async fn manifest(&self, election_id: Uuid) -> Option<SignedResultsManifest> {
+        self.manifests.read().await.get(&election_id).cloned()
+    }
+
+    /// Totais dos boletins recebidos, para o acompanhamento da apuração
+    pub async fn bulletin_totals(&self, election_id: Uuid) -> Vec<BulletinTotals> {
+        self.bulletin_totals_in(election_id, None).await
+    }
+
+    /// Totais dos boletins recebidos na região
+    pub async fn bulletin_totals_in(&self, election_id: Uuid, region: Option<&str>) -> Vec<BulletinTotals> {
+        self.bulletins.read().await
+            .get(&election_id)
+            .map(|sections| sections.values()
+                .filter(|s| region.map_or(true, |r| in_region(s, r)))
+                .map(|s| BulletinTotals {
+                    state: s.state.clone(),
+                    municipality_code: s.municipality_code.clone(),
+                    zone: s.zone.clone(),
+                    section: s.section.clone(),
+                    registered_voters: s.registered_voters,
+                    votes_cast: s.votes_cast,
+                    bu_digest: s.bu_digest.clone(),
+                })
+                .collect())
+            .unwrap_or_default()
+    }
+
+    /// Verifica hash e assinatura do manifesto
+    pub fn verify_manifest(signed: &SignedResultsManifest) -> Result<bool> {
+        let manifest_bytes = serde_json::to_vec(&signed.manifest)?;
+        if hex::encode(Sha256::digest(&manifest_bytes)) != signed.manifest_hash {
+            return Ok(false);
+        }
+
+        let public_key = hex::decode(&signed.public_key)?;
+        let signature = hex::decode(&signed.signature)?;
+        let verifier = UnparsedPublicKey::new(&ED25519, &public_key);
+        Ok(verifier.verify(&manifest_bytes, &signature).is_ok())
+    }
+
+    async fn status(&self, election_id: Uuid) -> Result<ElectionStatus> {
+        self.elections.get_election(election_id).await?
+            .ok_or_else(|| anyhow!("Election not found: {}", election_id))?
+            .status
+            .parse()
+    }
+
+    fn sign_manifest(&self, manifest: ResultsManifest) -> Result<SignedResultsManifest> {
+        let manifest_bytes = serde_json::to_vec(&manifest)?;
+        let signature = self.signing_key.try_sign(&manifest_bytes)?;
+        Ok(SignedResultsManifest {
+            manifest,
+            manifest_hash: hex::encode(Sha256::digest(&manifest_bytes)),
+            signature: hex::encode(signature),
+            public_key: hex::encode(self.signing_key.public_key_bytes()),
+            log_index: None,
+        })
+    }
+}
+
+/// O boletim está na região: recorte da hierarquia (`SP`, `SP/71072/0001`)
+/// ou código do município
+fn in_region(bulletin: &SectionResult, region: &str) -> bool {
+    match (RegionScope::parse(region), bulletin.section_ref()) {
+        (Ok(scope), Ok(section)) => scope.contains(&section),
+        _ => bulletin.state == region || bulletin.municipality_code == region,
+    }
+}
+
+fn section_key(bulletin: &SectionResult) -> String {
+    format!("{}/{}/{}", bulletin.municipality_code, bulletin.zone, bulletin.section)
+}
+
+/// Número da rodada a partir de `round2` ou `2`
+fn parse_round(label: &str) -> Result<u32> {
+    label.trim().trim_start_matches("round").parse()
+        .map_err(|_| anyhow!("Invalid counting round: {}", label))
+}
+
+fn diff_rounds(from: &CountingRound, to: &CountingRound, rounds: &[CountingRound]) -> ResultsDiff {
+    // Rodadas entre as duas comparadas, excluída a mais antiga
+    let (earlier, later) = if from.number <= to.number { (from.number, to.number) } else { (to.number, from.number) };
+    let between: Vec<&CountingRound> = rounds.iter()
+        .filter(|r| r.number > earlier && r.number <= later)
+        .collect();
+
+    let mut candidates = Vec::new();
+    let ranked = |results: &ElectionResults| -> HashMap<Uuid, (CandidatePosition, RankedCandidate)> {
+        results.positions.iter()
+            .flat_map(|p| p.ranking.iter().map(move |c| (c.candidate_id, (p.position, c.clone()))))
+            .collect()
+    };
+    let (before, after) = (ranked(&from.results), ranked(&to.results));
+    let mut ids: Vec<&Uuid> = before.keys().chain(after.keys()).collect();
+    ids.sort();
+    ids.dedup();
+    for id in ids {
+        let (old, new) = (before.get(id), after.get(id));
+        let Some((position, candidate)) = new.or(old) else { continue };
+        let from_votes = old.map_or(0, |(_, c)| c.votes);
+        let to_votes = new.map_or(0, |(_, c)| c.votes);
+        let (from_rank, to_rank) = (old.map(|(_, c)| c.rank), new.map(|(_, c)| c.rank));
+        if from_votes == to_votes && from_rank == to_rank {
+            continue;
+        }
+        candidates.push(CandidateDelta {
+            position: *position,
+            candidate_id: *id,
+            number: candidate.number,
+            name: candidate.name.clone(),
+            from_votes,
+            to_votes,
+            delta: to_votes as i64 - from_votes as i64,
+            from_rank,
+            to_rank,
+        });
+    }
+    candidates.sort_by(|a, b| a.position.cmp(&b.position).then(b.delta.abs().cmp(&a.delta.abs())));
+
+    let mut sections = Vec::new();
+    let mut unjustified_sections = Vec::new();
+    let keys: BTreeSet<&String> = from.sections.keys().chain(to.sections.keys()).collect();
+    for key in keys {
+        let (old, new) = (from.sections.get(key), to.sections.get(key));
+        let change = match (old, new) {
+            (Some(old), Some(new)) if old.bu_digest == new.bu_digest => continue,
+            (Some(_), Some(_)) => SectionChange::Amended,
+            (None, Some(_)) => SectionChange::Added,
+            (Some(_), None) => SectionChange::Removed,
+            (None, None) => continue,
+        };
+
+        let mut votable_deltas = BTreeMap::new();
+        let empty = BTreeMap::new();
+        let old_votes = old.map_or(&empty, |s| &s.votes_by_candidate);
+        let new_votes = new.map_or(&empty, |s| &s.votes_by_candidate);
+        for votable in old_votes.keys().chain(new_votes.keys()) {
+            let delta = *new_votes.get(votable).unwrap_or(&0) as i64 - *old_votes.get(votable).unwrap_or(&0) as i64;
+            if delta != 0 {
+                votable_deltas.insert(votable.clone(), delta);
+            }
+        }
+
+        let justifications: Vec<Justification> = between.iter()
+            .flat_map(|r| r.amendments.iter())
+            .filter(|a| &a.section_key == key)
+            .map(|a| a.justification.clone())
+            .collect();
+        if justifications.is_empty() && change == SectionChange::Amended {
+            unjustified_sections.push(key.clone());
+        }
+
+        sections.push(SectionDelta {
+            section_key: key.clone(),
+            state: new.or(old).map(|s| s.state.clone()).unwrap_or_default(),
+            change,
+            from_digest: old.map(|s| s.bu_digest.clone()),
+            to_digest: new.map(|s| s.bu_digest.clone()),
+            votes_cast_delta: new.map_or(0, |s| s.votes_cast) as i64 - old.map_or(0, |s| s.votes_cast) as i64,
+            votable_deltas,
+            justifications,
+        });
+    }
+
+    ResultsDiff {
+        election_id: to.results.election_id,
+        from: from.label(),
+        to: to.label(),
+        from_digest: from.results.bulletins_digest.clone(),
+        to_digest: to.results.bulletins_digest.clone(),
+        votes_cast_delta: to.results.votes_cast as i64 - from.results.votes_cast as i64,
+        candidates,
+        sections,
+        justifications: between.iter().flat_map(|r| r.justifications.iter().cloned()).collect(),
+        unjustified_sections,
+        generated_at: Utc::now(),
+    }
+}
+
+/// Candidato do votável, se houver exatamente um na região da seção
+fn resolve_votable<'a>(candidates: &'a [Candidate], bulletin: &SectionResult, votable: &str) -> Option<&'a Candidate> {
+    let (position, Votable::Candidate(number)) = split_tally_key(votable) else {
+        return None;
+    };
+    let position = match position {
+        Some(code) => Some(position_from_code(code)?),
+        None => None,
+    };
+    let number: u32 = number.parse().ok()?;
+
+    let mut matching = candidates.iter().filter(|c| {
+        c.number == number
+            && position.map_or(true, |p| c.position == p)
+            && c.region.as_deref().map_or(true, |r| r == bulletin.state || r == bulletin.municipality_code)
+    });
+    let candidate = matching.next()?;
+    matching.next().is_none().then_some(candidate)
+}
+
+/// Cargo pelo código usado nos votáveis (`governor`)
+fn position_from_code(code: &str) -> Option<CandidatePosition> {
+    serde_json::from_value(serde_json::Value::String(code.to_string())).ok()
+}
+
+/// Ordena por votos, votação do partido no cargo e menor número
+fn rank_position(position: CandidatePosition, candidates: Vec<&Candidate>, votes: &HashMap<Uuid, u64>) -> PositionResult {
+    let votes_of = |c: &Candidate| votes.get(&c.id).copied().unwrap_or(0);
+    let mut party_votes: HashMap<&str, u64> = HashMap::new();
+    for candidate in &candidates {
+        *party_votes.entry(candidate.party.as_str()).or_insert(0) += votes_of(candidate);
+    }
+
+    let mut ordered = candidates;
+    ordered.sort_by(|a, b| {
+        votes_of(b).cmp(&votes_of(a))
+            .then(party_votes[b.party.as_str()].cmp(&party_votes[a.party.as_str()]))
+            .then(a.number.cmp(&b.number))
+    });
+
+    let valid_votes: u64 = ordered.iter().map(|c| votes_of(c)).sum();
+    let ranking = ordered.iter().enumerate()
+        .map(|(i, candidate)| {
+            let tie_broken_by = i.checked_sub(1)
+                .map(|prev| ordered[prev])
+                .filter(|prev| votes_of(prev) == votes_of(candidate))
+                .map(|prev| if party_votes[prev.party.as_str()] != party_votes[candidate.party.as_str()] {
+                    TieBreakRule::PartyVotes
+                } else {
+                    TieBreakRule::LowestNumber
+                });
+            let candidate_votes = votes_of(candidate);
+
+            RankedCandidate {
+                rank: i as u32 + 1,
+                candidate_id: candidate.id,
+                number: candidate.number,
+                name: candidate.name.clone(),
+                party: candidate.party.clone(),
+                region: candidate.region.clone(),
+                votes: candidate_votes,
+                share: if valid_votes == 0 { 0.0 } else { candidate_votes as f64 * 100.0 / valid_votes as f64 },
+                tie_broken_by,
+            }
+        })
+        .collect();
+
+    PositionResult { position, valid_votes, blank_votes: 0, null_votes: 0, ranking }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+    use crate::models::{CreateCandidateRequest, CreateElectionRequest};
+
+    async fn setup() -> (ResultsService, Arc<ElectionService>, Uuid) {
+        let elections = Arc::new(ElectionService::new());
+        let candidates = Arc::new(CandidateService::new(elections.clone()));
+        let election = elections.create_election(CreateElectionRequest {
+            title: "Eleições Gerais".to_string(),
+            description: None,
+            start_date: Utc::now() + Duration::days(1),
+            end_date: Utc::now() + Duration::days(2),
+        }, "admin").await.unwrap();
+
+        for (number, party, position, region) in [
+            (13, "PA", CandidatePosition::President, None),
+            (22, "PB", CandidatePosition::President, None),
+            (13, "PA", CandidatePosition::Governor, Some("SP")),
+            (1301, "PA", CandidatePosition::FederalDeputy, Some("SP")),
+            (4501, "PC", CandidatePosition::FederalDeputy, Some("SP")),
+            (4502, "PC", CandidatePosition::FederalDeputy, Some("SP")),
+        ] {
+            candidates.create_candidate(election.id, CreateCandidateRequest {
+                name: format!("Candidato {}", number),
+                party: party.to_string(),
+                number,
+                position,
+                coalition: None,
+                region: region.map(str::to_string),
+                photo_base64: None,
+            }).await.unwrap();
+        }
+        for status in [ElectionStatus::Scheduled, ElectionStatus::Active, ElectionStatus::Closed] {
+            elections.transition(election.id, status, "admin", None).await.unwrap();
+        }
+
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+        let service = ResultsService::new(elections.clone(), candidates, signing_key, ResultsConfig {
+            cache_ttl_seconds: 60,
+        });
+        (service, elections, election.id)
+    }
+
+    fn bulletin(state: &str, section: &str, votes: &[(&str, u64)]) -> SectionResult {
+        SectionResult {
+            state: state.to_string(),
+            municipality_code: format!("{}001", state),
+            municipality: "Capital".to_string(),
+            zone: "1".to_string(),
+            section: section.to_string(),
+            registered_voters: 300,
+            votes_cast: votes.iter().map(|(_, v)| v).sum(),
+            votes_by_candidate: votes.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            bu_digest: format!("bu-{}-{}", state, section),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ranks_with_tie_break_and_region_filter() {
+        let (service, _, election_id) = setup().await;
+        service.record_bulletin(election_id, bulletin("SP", "1", &[
+            ("president:13", 40), ("president:22", 25), ("governor:13", 50),
+            ("1301", 30), ("4501", 30), ("4502", 10), ("99", 3),
+            ("president:BRANCO", 4), ("president:NULO", 2), ("governor:NULO", 1),
+        ])).await.unwrap();
+        service.record_bulletin(election_id, bulletin("RJ", "1", &[("13", 10), ("22", 5)])).await.unwrap();
+
+        let total = service.results(election_id, None).await.unwrap();
+        let president = total.positions.iter().find(|p| p.position == CandidatePosition::President).unwrap();
+        assert_eq!(president.valid_votes, 80);
+        assert_eq!((president.blank_votes, president.null_votes), (4, 2));
+        assert_eq!(president.ranking[0].number, 13);
+        assert_eq!(president.ranking[0].votes, 50);
+
+        let deputies = total.positions.iter().find(|p| p.position == CandidatePosition::FederalDeputy).unwrap();
+        assert_eq!(deputies.ranking[0].number, 4501);
+        assert_eq!(deputies.ranking[1].number, 1301);
+        assert_eq!(deputies.ranking[1].tie_broken_by, Some(TieBreakRule::PartyVotes));
+        assert_eq!(total.unmatched_votes["99"], 3);
+        assert_eq!((total.blank_votes, total.null_votes), (4, 3));
+        assert!(!total.unmatched_votes.keys().any(|k| k.contains("NULO")));
+
+        let rio = service.results(election_id, Some("RJ")).await.unwrap();
+        assert_eq!(rio.sections, 1);
+        assert!(rio.positions.iter().all(|p| p.position == CandidatePosition::President));
+
+        let mut conflicting = bulletin("RJ", "1", &[("22", 6)]);
+        conflicting.bu_digest = "bu-RJ-1-alterado".to_string();
+        assert!(service.record_bulletin(election_id, conflicting).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_registered_sections_and_hierarchy_scope() {
+        let (service, _, election_id) = setup().await;
+        let regions = Arc::new(RegionService::new());
+        regions.add_state("SP", "São Paulo").await.unwrap();
+        regions.add_municipality("SP", "71072", "São Paulo").await.unwrap();
+        regions.add_zone("SP", "71072", "0001", None).await.unwrap();
+        let section = crate::services::regions::SectionRef::new("SP", "71072", "0001", "0001").unwrap();
+        regions.add_section(section, None, 300).await.unwrap();
+        let service = service.with_regions(regions);
+
+        let in_zone = |section: &str| {
+            let mut bulletin = bulletin("SP", section, &[("president:13", 10)]);
+            bulletin.municipality_code = "71072".to_string();
+            bulletin.zone = "0001".to_string();
+            bulletin
+        };
+        service.record_bulletin(election_id, in_zone("0001")).await.unwrap();
+        assert!(service.record_bulletin(election_id, in_zone("0002")).await.is_err());
+
+        assert_eq!(service.results(election_id, Some("SP/71072/0001")).await.unwrap().sections, 1);
+        assert!(service.results(election_id, Some("SP/71072/0002")).await.is_err());
+        assert_eq!(service.bulletin_totals_in(election_id, Some("71072")).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_diff_between_rounds_links_amendment_justifications() {
+        let (service, _, election_id) = setup().await;
+        service.record_bulletin(election_id, bulletin("SP", "1", &[("president:13", 40), ("president:22", 35)])).await.unwrap();
+        service.record_bulletin(election_id, bulletin("RJ", "1", &[("13", 10), ("22", 5)])).await.unwrap();
+
+        let justification = Justification {
+            kind: JustificationKind::Reconciliation,
+            reference: "transfer-7".to_string(),
+            href: None,
+            note: None,
+        };
+        let mut recounted = bulletin("SP", "1", &[("president:13", 30), ("president:22", 45)]);
+        recounted.bu_digest = "bu-SP-1-recontado".to_string();
+        assert!(service.amend_bulletin(election_id, recounted.clone(), justification.clone(), "junta").await.is_err());
+
+        service.close_round(election_id, RoundKind::Initial, vec![], "junta").await.unwrap();
+        assert!(service.close_round(election_id, RoundKind::Initial, vec![], "junta").await.is_err());
+        service.amend_bulletin(election_id, recounted, justification, "junta").await.unwrap();
+        let round = service.close_round(election_id, RoundKind::Recount, vec![], "junta").await.unwrap();
+        assert_eq!((round.label(), round.amendments.len()), ("round2".to_string(), 1));
+
+        let diff = service.diff(election_id, "round1", "round2").await.unwrap();
+        assert_eq!(diff.votes_cast_delta, 0);
+        let leader = diff.candidates.iter().find(|c| c.number == 22).unwrap();
+        assert_eq!((leader.delta, leader.from_rank, leader.to_rank), (10, Some(2), Some(1)));
+
+        assert_eq!(diff.sections.len(), 1);
+        let section = &diff.sections[0];
+        assert_eq!((section.change, section.section_key.as_str()), (SectionChange::Amended, "SP001/1/1"));
+        assert_eq!(section.votable_deltas["president:13"], -10);
+        assert_eq!(section.justifications[0].href.as_deref(), Some("/api/v1/urnas/contingency/transfers/transfer-7"));
+        assert!(diff.unjustified_sections.is_empty());
+
+        assert_eq!(service.latest_diff(election_id).await.unwrap().to, "round2");
+        assert!(service.diff(election_id, "round1", "round3").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_requires_finalized_and_signs_manifest() {
+        let (service, elections, election_id) = setup().await;
+        service.record_bulletin(election_id, bulletin("SP", "1", &[("president:13", 10)])).await.unwrap();
+        assert!(service.publish(election_id).await.is_err());
+
+        elections.transition(election_id, ElectionStatus::Finalized, "admin", None).await.unwrap();
+        let signed = service.publish(election_id).await.unwrap();
+        assert!(ResultsService::verify_manifest(&signed).unwrap());
+
+        let mut tampered = signed.clone();
+        tampered.manifest.results.votes_cast += 1;
+        assert!(!ResultsService::verify_manifest(&tampered).unwrap());
+        assert!(service.record_bulletin(election_id, bulletin("SP", "2", &[])).await.is_err());
+    }
+}
@@ -0,0 +1,436 @@
+//! Recebimento de relatórios de vulnerabilidade (bug bounty)
+//!
+//! Pesquisadores enviam relatórios em texto ou cifrados com a chave PGP
+//! pública do FORTIS. Relatórios cifrados são armazenados como recebidos e
+//! decifrados fora do backend pela equipe de segurança; o backend apenas
+//! valida o envelope ASCII-armored. Cada relatório recebe confirmação
+//! automática, verificação de duplicidade e passa pelo fluxo de triagem.
+//! Relatórios aceitos com severidade alta ou crítica abrem incidente.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc, Duration};
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose};
+use sha2::{Sha256, Digest};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::incident::{IncidentService, IncidentSeverity};
+
+const PGP_MESSAGE_BEGIN: &str = "-----BEGIN PGP MESSAGE-----";
+const PGP_MESSAGE_END: &str = "-----END PGP MESSAGE-----";
+
+/// Severidade atribuída na triagem
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportSeverity {
+    Unrated,
+    Informational,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// Estado do relatório no fluxo de triagem
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    Received,
+    Triaging,
+    Accepted,
+    Duplicate,
+    Rejected,
+    Resolved,
+}
+
+/// Relatório enviado pelo pesquisador
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubmitReportRequest {
+    pub researcher_contact: Option<String>,
+    pub title: Option<String>,
+    pub affected_component: Option<String>,
+    pub description: Option<String>,
+    /// Mensagem PGP ASCII-armored cifrada com a chave pública do FORTIS
+    pub encrypted_payload: Option<String>,
+}
+
+/// Decisão de triagem
+#[derive(Debug, Clone, Deserialize)]
+pub struct TriageDecision {
+    pub status: ReportStatus,
+    pub severity: Option<ReportSeverity>,
+    pub duplicate_of: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Relatório de vulnerabilidade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityReport {
+    pub id: String,
+    pub tracking_code: String,
+    pub researcher_contact: Option<String>,
+    pub title: Option<String>,
+    pub affected_component: Option<String>,
+    pub description: Option<String>,
+    pub encrypted_payload: Option<String>,
+    pub encrypted: bool,
+    /// Impressão usada na detecção de duplicatas
+    pub fingerprint: String,
+    pub status: ReportStatus,
+    pub severity: ReportSeverity,
+    pub duplicate_of: Option<String>,
+    pub incident_id: Option<String>,
+    pub triage_notes: Vec<String>,
+    pub received_at: DateTime<Utc>,
+    pub triage_due_by: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Confirmação automática enviada ao pesquisador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportAcknowledgment {
+    pub report_id: String,
+    pub tracking_code: String,
+    pub status: ReportStatus,
+    pub duplicate_of: Option<String>,
+    pub received_at: DateTime<Utc>,
+    pub triage_due_by: DateTime<Utc>,
+    pub message: String,
+}
+
+/// Serviço de recebimento de relatórios de vulnerabilidade
+pub struct SecurityReportService {
+    reports: RwLock<HashMap<String, SecurityReport>>,
+    incidents: Arc<IncidentService>,
+    pgp_public_key: Option<String>,
+    triage_sla_hours: i64,
+}
+
+impl SecurityReportService {
+    pub fn new(incidents: Arc<IncidentService>, triage_sla_hours: i64) -> Self {
+        Self {
+            reports: RwLock::new(HashMap::new()),
+            incidents,
+            pgp_public_key: None,
+            triage_sla_hours,
+        }
+    }
+
+    /// Define a chave PGP pública divulgada aos pesquisadores
+    pub fn with_pgp_public_key(mut self, public_key: String) -> Self {
+        self.pgp_public_key = Some(public_key);
+        self
+    }
+
+    /// Obtém a chave PGP pública
+    pub fn pgp_public_key(&self) -> Option<&str> {
+        self.pgp_public_key.as_deref()
+    }
+
+    /// Recebe relatório e retorna confirmação automática
+    pub async fn submit_report(&self, request: SubmitReportRequest) -> Result<ReportAcknowledgment> {
+        let fingerprint = match &request.encrypted_payload {
+            Some(payload) => {
+                let ciphertext = parse_armored_message(payload)?;
+                hex::encode(Sha256::digest(&ciphertext))
+            }
+            None => {
+                let description = request.description.as_deref().unwrap_or("").trim();
+                if description.is_empty() {
+                    return Err(anyhow!("Report must include a description or an encrypted payload"));
+                }
+                let title = request.title.as_deref().unwrap_or(description);
+                plaintext_fingerprint(request.affected_component.as_deref().unwrap_or(""), title)
+            }
+        };
+
+        let now = Utc::now();
+        let mut reports = self.reports.write().await;
+
+        // Duplicata: mesma impressão de um relatório que não foi rejeitado
+        let duplicate_of = reports
+            .values()
+            .filter(|r| r.fingerprint == fingerprint && r.status != ReportStatus::Rejected)
+            .min_by_key(|r| r.received_at)
+            .map(|r| r.duplicate_of.clone().unwrap_or_else(|| r.id.clone()));
+
+        let report = SecurityReport {
+            id: Uuid::new_v4().to_string(),
+            tracking_code: format!("FORTIS-VR-{}", &Uuid::new_v4().simple().to_string()[..8].to_uppercase()),
+            researcher_contact: request.researcher_contact,
+            title: request.title,
+            affected_component: request.affected_component,
+            description: request.description,
+            encrypted: request.encrypted_payload.is_some(),
+            encrypted_payload: request.encrypted_payload,
+            fingerprint,
+            status: if duplicate_of.is_some() { ReportStatus::Duplicate } else { ReportStatus::Received },
+            severity: ReportSeverity::Unrated,
+            duplicate_of: duplicate_of.clone(),
+            incident_id: None,
+            triage_notes: Vec::new(),
+            received_at: now,
+            triage_due_by: now + Duration::hours(self.triage_sla_hours),
+            updated_at: now,
+        };
+
+        let message = match &duplicate_of {
+            Some(original) => format!(
+                "Relatório recebido. Ele corresponde a um relatório já registrado ({}) e será analisado em conjunto.",
+                original
+            ),
+            None => format!(
+                "Relatório recebido. A triagem inicial será concluída em até {} horas.",
+                self.triage_sla_hours
+            ),
+        };
+
+        let acknowledgment = ReportAcknowledgment {
+            report_id: report.id.clone(),
+            tracking_code: report.tracking_code.clone(),
+            status: report.status,
+            duplicate_of,
+            received_at: report.received_at,
+            triage_due_by: report.triage_due_by,
+            message,
+        };
+
+        log::info!("Relatório de vulnerabilidade recebido: {}", report.tracking_code);
+        reports.insert(report.id.clone(), report);
+        Ok(acknowledgment)
+    }
+
+    /// Aplica decisão de triagem
+    pub async fn triage(&self, report_id: &str, decision: TriageDecision) -> Result<SecurityReport> {
+        let mut reports = self.reports.write().await;
+
+        if let Some(original) = &decision.duplicate_of {
+            if original == report_id || !reports.contains_key(original) {
+                return Err(anyhow!("Invalid duplicate reference: {}", original));
+            }
+        }
+
+        let report = reports
+            .get_mut(report_id)
+            .ok_or_else(|| anyhow!("Report not found: {}", report_id))?;
+
+        if !is_valid_transition(report.status, decision.status) {
+            return Err(anyhow!(
+                "Invalid report transition: {:?} -> {:?}",
+                report.status, decision.status
+            ));
+        }
+        if decision.status == ReportStatus::Duplicate && decision.duplicate_of.is_none() && report.duplicate_of.is_none() {
+            return Err(anyhow!("Duplicate reports must reference the original report"));
+        }
+
+        if let Some(severity) = decision.severity {
+            report.severity = severity;
+        }
+        if decision.duplicate_of.is_some() {
+            report.duplicate_of = decision.duplicate_of;
+        }
+        if let Some(notes) = decision.notes {
+            report.triage_notes.push(notes);
+        }
+        report.status = decision.status;
+        report.updated_at = Utc::now();
+
+        // Abrir incidente para vulnerabilidades aceitas de alto impacto
+        if report.status == ReportStatus::Accepted && report.incident_id.is_none() {
+            if let Some(severity) = incident_severity(report.severity) {
+                let title = format!(
+                    "Vulnerabilidade reportada {}: {}",
+                    report.tracking_code,
+                    report.title.as_deref().unwrap_or("relatório cifrado")
+                );
+                let incident = self.incidents
+                    .open_incident(&title, severity, "bug_bounty", Some(report.id.clone()))
+                    .await;
+                report.incident_id = Some(incident.id);
+            }
+        }
+
+        Ok(report.clone())
+    }
+
+    /// Obtém relatório
+    pub async fn get_report(&self, report_id: &str) -> Option<SecurityReport> {
+        self.reports.read().await.get(report_id).cloned()
+    }
+
+    /// Lista relatórios, opcionalmente filtrando por estado
+    pub async fn list_reports(&self, status: Option<ReportStatus>) -> Vec<SecurityReport> {
+        let reports = self.reports.read().await;
+        let mut result: Vec<SecurityReport> = reports
+            .values()
+            .filter(|r| status.map_or(true, |s| r.status == s))
+            .cloned()
+            .collect();
+        result.sort_by(|a, b| a.received_at.cmp(&b.received_at));
+        result
+    }
+
+    /// Relatórios com prazo de triagem vencido
+    pub async fn overdue_reports(&self, now: DateTime<Utc>) -> Vec<SecurityReport> {
+        let reports = self.reports.read().await;
+        reports
+            .values()
+            .filter(|r| r.status == ReportStatus::Received && r.triage_due_by < now)
+            .cloned()
+            .collect()
+    }
+}
+
+fn is_valid_transition(from: ReportStatus, to: ReportStatus) -> bool {
+    use ReportStatus::*;
+    matches!(
+        (from, to),
+        (Received, Triaging)
+            | (Received, Duplicate)
+            | (Received, Rejected)
+            | (Triaging, Accepted)
+            | (Triaging, Duplicate)
+            | (Triaging, Rejected)
+            | (Duplicate, Triaging)
+            | (Accepted, Resolved)
+    )
+}
+
+fn incident_severity(severity: ReportSeverity) -> Option<IncidentSeverity> {
+    match severity {
+        ReportSeverity::Critical => Some(IncidentSeverity::Critical),
+        ReportSeverity::High => Some(IncidentSeverity::High),
+        _ => None,
+    }
+}
+
+/// Impressão de relatório em texto: componente e título normalizados
+fn plaintext_fingerprint(component: &str, title: &str) -> String {
+    let normalize = |s: &str| {
+        s.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(normalize(component).as_bytes());
+    hasher.update(b"|");
+    hasher.update(normalize(title).as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Valida envelope PGP ASCII-armored e retorna o conteúdo binário
+fn parse_armored_message(armored: &str) -> Result<Vec<u8>> {
+    let armored = armored.trim();
+    let body = armored
+        .strip_prefix(PGP_MESSAGE_BEGIN)
+        .and_then(|rest| rest.strip_suffix(PGP_MESSAGE_END))
+        .ok_or_else(|| anyhow!("Encrypted payload must be an ASCII-armored PGP message"))?;
+
+    // Cabeçalhos de armadura terminam na primeira linha em branco
+    let lines: Vec<&str> = body
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| l.is_empty())
+        .collect();
+    let data_start = if lines.first().map_or(false, |l| l.contains(": ")) {
+        lines.iter().position(|l| l.is_empty()).map_or(lines.len(), |p| p + 1)
+    } else {
+        0
+    };
+
+    let data: String = lines[data_start..]
+        .iter()
+        .filter(|l| !l.is_empty() && !l.starts_with('='))
+        .copied()
+        .collect();
+
+    let ciphertext = general_purpose::STANDARD
+        .decode(data)
+        .map_err(|_| anyhow!("Invalid PGP armor encoding"))?;
+    if ciphertext.is_empty() {
+        return Err(anyhow!("Empty PGP message"));
+    }
+    Ok(ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> SecurityReportService {
+        SecurityReportService::new(Arc::new(IncidentService::new()), 72)
+    }
+
+    fn plaintext(title: &str) -> SubmitReportRequest {
+        SubmitReportRequest {
+            researcher_contact: Some("pesquisador@example.com".to_string()),
+            title: Some(title.to_string()),
+            affected_component: Some("api/v1/votes".to_string()),
+            description: Some("Passos para reproduzir".to_string()),
+            encrypted_payload: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_reports_are_linked() {
+        let service = service();
+        let first = service.submit_report(plaintext("SQL injection in vote lookup")).await.unwrap();
+        let second = service.submit_report(plaintext("SQL Injection in vote-lookup!")).await.unwrap();
+
+        assert_eq!(first.status, ReportStatus::Received);
+        assert_eq!(second.status, ReportStatus::Duplicate);
+        assert_eq!(second.duplicate_of, Some(first.report_id));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_payload_requires_pgp_armor() {
+        let service = service();
+        let armored = format!(
+            "{}\nVersion: GnuPG v2\n\nhQEMA8x1c2VjcmV0\n=abcd\n{}",
+            PGP_MESSAGE_BEGIN, PGP_MESSAGE_END
+        );
+
+        let request = SubmitReportRequest {
+            researcher_contact: None,
+            title: None,
+            affected_component: None,
+            description: None,
+            encrypted_payload: Some(armored),
+        };
+        assert!(service.submit_report(request.clone()).await.is_ok());
+
+        let invalid = SubmitReportRequest {
+            encrypted_payload: Some("not encrypted".to_string()),
+            ..request
+        };
+        assert!(service.submit_report(invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_accepted_critical_report_opens_incident() {
+        let incidents = Arc::new(IncidentService::new());
+        let service = SecurityReportService::new(incidents.clone(), 72);
+        let ack = service.submit_report(plaintext("Remote code execution")).await.unwrap();
+
+        let decision = |status| TriageDecision {
+            status,
+            severity: Some(ReportSeverity::Critical),
+            duplicate_of: None,
+            notes: None,
+        };
+        assert!(service.triage(&ack.report_id, decision(ReportStatus::Accepted)).await.is_err());
+
+        service.triage(&ack.report_id, decision(ReportStatus::Triaging)).await.unwrap();
+        let report = service.triage(&ack.report_id, decision(ReportStatus::Accepted)).await.unwrap();
+
+        let incident = incidents.get_incident(report.incident_id.as_deref().unwrap()).await.unwrap();
+        assert_eq!(incident.severity, IncidentSeverity::Critical);
+        assert_eq!(incident.reference_id, Some(report.id));
+    }
+}
@@ -0,0 +1,343 @@
+//! Acesso de suporte com consentimento do administrador
+//!
+//! Em vez de compartilhar credenciais, o engenheiro de suporte solicita um
+//! acesso com escopo e duração definidos. O administrador aprova ou recusa;
+//! aprovado, o suporte recebe uma única vez um token somente leitura,
+//! restrito aos escopos concedidos e válido até o fim do prazo. Cada uso do
+//! token, permitido ou negado, fica registrado e visível ao administrador
+//! que concedeu o acesso, que pode revogá-lo a qualquer momento.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use rand::RngCore;
+use sha2::{Sha256, Digest};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Duração máxima de um acesso de suporte
+pub const MAX_GRANT_MINUTES: i64 = 8 * 60;
+
+/// Área do sistema que o suporte pode consultar
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportScope {
+    Elections,
+    Urnas,
+    Incidents,
+    Security,
+    Analytics,
+    Errors,
+}
+
+impl SupportScope {
+    /// Prefixos de rota cobertos pelo escopo
+    fn path_prefixes(&self) -> &'static [&'static str] {
+        match self {
+            SupportScope::Elections => &["/api/v1/elections"],
+            SupportScope::Urnas => &["/api/v1/urnas"],
+            SupportScope::Incidents => &["/api/v1/security/incidents"],
+            SupportScope::Security => &["/api/v1/security"],
+            SupportScope::Analytics => &["/api/v1/analytics"],
+            SupportScope::Errors => &["/api/v1/errors"],
+        }
+    }
+
+    pub fn covers(&self, path: &str) -> bool {
+        self.path_prefixes().iter().any(|prefix| {
+            path == *prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+}
+
+/// Situação da concessão
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantStatus {
+    Pending,
+    Approved,
+    Denied,
+    Revoked,
+}
+
+/// Concessão de acesso de suporte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportGrant {
+    pub id: String,
+    pub support_engineer: String,
+    /// Administrador cuja visão o suporte precisa ver e que decide o pedido
+    pub admin_id: String,
+    pub scopes: Vec<SupportScope>,
+    pub reason: String,
+    pub duration_minutes: i64,
+    pub status: GrantStatus,
+    pub requested_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    token_hash: Option<String>,
+    #[serde(skip)]
+    unclaimed_token: Option<String>,
+}
+
+impl SupportGrant {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.status == GrantStatus::Approved && self.expires_at.is_some_and(|expires| now < expires)
+    }
+}
+
+/// Uso do token de suporte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportAccessEvent {
+    pub grant_id: Option<String>,
+    pub support_engineer: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub allowed: bool,
+    pub reason: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Serviço de acesso de suporte
+pub struct SupportAccessService {
+    grants: RwLock<HashMap<String, SupportGrant>>,
+    access_log: RwLock<Vec<SupportAccessEvent>>,
+}
+
+impl SupportAccessService {
+    pub fn new() -> Self {
+        Self {
+            grants: RwLock::new(HashMap::new()),
+            access_log: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Suporte solicita acesso à visão de um administrador
+    pub async fn request_access(
+        &self,
+        support_engineer: &str,
+        admin_id: &str,
+        scopes: Vec<SupportScope>,
+        reason: &str,
+        duration_minutes: i64,
+    ) -> Result<SupportGrant> {
+        if scopes.is_empty() {
+            return Err(anyhow!("At least one scope is required"));
+        }
+        if reason.trim().is_empty() {
+            return Err(anyhow!("A reason is required for support access"));
+        }
+        if !(1..=MAX_GRANT_MINUTES).contains(&duration_minutes) {
+            return Err(anyhow!("Duration must be between 1 and {} minutes", MAX_GRANT_MINUTES));
+        }
+        if support_engineer == admin_id {
+            return Err(anyhow!("Support engineer cannot grant access to themselves"));
+        }
+
+        let grant = SupportGrant {
+            id: Uuid::new_v4().to_string(),
+            support_engineer: support_engineer.to_string(),
+            admin_id: admin_id.to_string(),
+            scopes,
+            reason: reason.to_string(),
+            duration_minutes,
+            status: GrantStatus::Pending,
+            requested_at: Utc::now(),
+            decided_at: None,
+            expires_at: None,
+            token_hash: None,
+            unclaimed_token: None,
+        };
+        self.grants.write().await.insert(grant.id.clone(), grant.clone());
+        Ok(grant)
+    }
+
+    /// Administrador aprova o pedido; o prazo começa a contar agora
+    pub async fn approve(&self, grant_id: &str, admin_id: &str) -> Result<SupportGrant> {
+        let mut grants = self.grants.write().await;
+        let grant = Self::pending_for_admin(&mut grants, grant_id, admin_id)?;
+
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        let now = Utc::now();
+        grant.status = GrantStatus::Approved;
+        grant.decided_at = Some(now);
+        grant.expires_at = Some(now + Duration::minutes(grant.duration_minutes));
+        grant.token_hash = Some(hash_token(&token));
+        grant.unclaimed_token = Some(token);
+        Ok(grant.clone())
+    }
+
+    /// Administrador recusa o pedido
+    pub async fn deny(&self, grant_id: &str, admin_id: &str) -> Result<SupportGrant> {
+        let mut grants = self.grants.write().await;
+        let grant = Self::pending_for_admin(&mut grants, grant_id, admin_id)?;
+        grant.status = GrantStatus::Denied;
+        grant.decided_at = Some(Utc::now());
+        Ok(grant.clone())
+    }
+
+    /// Administrador encerra o acesso antes do prazo
+    pub async fn revoke(&self, grant_id: &str, admin_id: &str) -> Result<SupportGrant> {
+        let mut grants = self.grants.write().await;
+        let grant = grants.get_mut(grant_id)
+            .filter(|g| g.admin_id == admin_id)
+            .ok_or_else(|| anyhow!("Grant {} not found", grant_id))?;
+        if grant.status != GrantStatus::Approved {
+            return Err(anyhow!("Grant {} is not active", grant_id));
+        }
+        grant.status = GrantStatus::Revoked;
+        grant.token_hash = None;
+        grant.unclaimed_token = None;
+        Ok(grant.clone())
+    }
+
+    /// Entrega o token ao suporte solicitante, uma única vez
+    pub async fn claim_token(&self, grant_id: &str, support_engineer: &str) -> Result<String> {
+        let mut grants = self.grants.write().await;
+        let grant = grants.get_mut(grant_id)
+            .filter(|g| g.support_engineer == support_engineer)
+            .ok_or_else(|| anyhow!("Grant {} not found", grant_id))?;
+        if !grant.is_active(Utc::now()) {
+            return Err(anyhow!("Grant {} is not active", grant_id));
+        }
+        grant.unclaimed_token.take()
+            .ok_or_else(|| anyhow!("Token for grant {} was already delivered", grant_id))
+    }
+
+    /// Autoriza um uso do token e registra a tentativa
+    pub async fn authorize(&self, token: &str, method: &str, path: &str) -> Result<SupportGrant> {
+        let now = Utc::now();
+        let token_hash = hash_token(token);
+        let grant = self.grants.read().await.values()
+            .find(|g| g.token_hash.as_deref() == Some(token_hash.as_str()))
+            .cloned();
+
+        let outcome = match &grant {
+            None => Err("unknown or revoked token"),
+            Some(grant) if !grant.is_active(now) => Err("grant expired"),
+            Some(_) if !matches!(method, "GET" | "HEAD") => Err("support access is read-only"),
+            Some(grant) if !grant.scopes.iter().any(|scope| scope.covers(path)) => Err("path outside granted scopes"),
+            Some(_) => Ok(()),
+        };
+
+        let event = SupportAccessEvent {
+            grant_id: grant.as_ref().map(|g| g.id.clone()),
+            support_engineer: grant.as_ref().map(|g| g.support_engineer.clone()),
+            method: method.to_string(),
+            path: path.to_string(),
+            allowed: outcome.is_ok(),
+            reason: outcome.err().map(str::to_string),
+            timestamp: now,
+        };
+        log::info!(
+            "Support access {} {} by {:?}: {}",
+            method, path, event.support_engineer,
+            if event.allowed { "allowed" } else { "denied" }
+        );
+        let reason = event.reason.clone();
+        self.access_log.write().await.push(event);
+
+        match (grant, reason) {
+            (Some(grant), None) => Ok(grant),
+            (_, reason) => Err(anyhow!("Support access denied: {}", reason.unwrap_or_default())),
+        }
+    }
+
+    /// Pedidos e concessões de um administrador
+    pub async fn grants_for_admin(&self, admin_id: &str) -> Vec<SupportGrant> {
+        let mut grants: Vec<SupportGrant> = self.grants.read().await.values()
+            .filter(|g| g.admin_id == admin_id)
+            .cloned()
+            .collect();
+        grants.sort_by_key(|g| g.requested_at);
+        grants
+    }
+
+    /// Usos das concessões feitas pelo administrador
+    pub async fn access_log_for_admin(&self, admin_id: &str) -> Vec<SupportAccessEvent> {
+        let grants = self.grants.read().await;
+        self.access_log.read().await.iter()
+            .filter(|event| {
+                event.grant_id.as_ref()
+                    .and_then(|id| grants.get(id))
+                    .is_some_and(|g| g.admin_id == admin_id)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn pending_for_admin<'a>(
+        grants: &'a mut HashMap<String, SupportGrant>,
+        grant_id: &str,
+        admin_id: &str,
+    ) -> Result<&'a mut SupportGrant> {
+        let grant = grants.get_mut(grant_id)
+            .filter(|g| g.admin_id == admin_id)
+            .ok_or_else(|| anyhow!("Grant {} not found", grant_id))?;
+        if grant.support_engineer == admin_id {
+            return Err(anyhow!("Requester cannot decide their own support access"));
+        }
+        if grant.status != GrantStatus::Pending {
+            return Err(anyhow!("Grant {} was already decided", grant_id));
+        }
+        Ok(grant)
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn approved(service: &SupportAccessService) -> (SupportGrant, String) {
+        let grant = service
+            .request_access("suporte-1", "admin-1", vec![SupportScope::Urnas], "Urna sem sincronizar", 60)
+            .await
+            .unwrap();
+        assert!(service.claim_token(&grant.id, "suporte-1").await.is_err());
+        let grant = service.approve(&grant.id, "admin-1").await.unwrap();
+        let token = service.claim_token(&grant.id, "suporte-1").await.unwrap();
+        (grant, token)
+    }
+
+    #[tokio::test]
+    async fn test_token_is_read_only_and_scoped() {
+        let service = SupportAccessService::new();
+        let (grant, token) = approved(&service).await;
+        assert!(service.claim_token(&grant.id, "suporte-1").await.is_err());
+
+        assert!(service.authorize(&token, "GET", "/api/v1/urnas/status/u1").await.is_ok());
+        assert!(service.authorize(&token, "POST", "/api/v1/urnas/register").await.is_err());
+        assert!(service.authorize(&token, "GET", "/api/v1/elections").await.is_err());
+        assert!(service.authorize(&token, "GET", "/api/v1/urnasx").await.is_err());
+
+        let log = service.access_log_for_admin("admin-1").await;
+        assert_eq!(log.len(), 4);
+        assert_eq!(log.iter().filter(|e| e.allowed).count(), 1);
+        assert!(service.access_log_for_admin("admin-2").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_only_granting_admin_decides_and_revocation_stops_access() {
+        let service = SupportAccessService::new();
+        let grant = service
+            .request_access("suporte-1", "admin-1", vec![SupportScope::Errors], "Erro recorrente", 30)
+            .await
+            .unwrap();
+        assert!(service.approve(&grant.id, "admin-2").await.is_err());
+        assert!(service.request_access("suporte-1", "admin-1", vec![], "x", 30).await.is_err());
+        assert!(service.request_access("suporte-1", "admin-1", vec![SupportScope::Errors], "x", MAX_GRANT_MINUTES + 1).await.is_err());
+
+        let (grant, token) = approved(&service).await;
+        service.revoke(&grant.id, "admin-1").await.unwrap();
+        assert!(service.authorize(&token, "GET", "/api/v1/urnas/status/u1").await.is_err());
+    }
+}
@@ -0,0 +1,410 @@
+//! Apuração-sombra para validação cruzada dos algoritmos de contagem
+//!
+//! Antes da certificação do resultado, duas implementações independentes
+//! contam a mesma eleição: a soma dos boletins de urna (`ResultsService`),
+//! que é a apuração oficial, e a decifração e contagem da saída verificada da
+//! mixnet, executada em modo sombra, sem efeito sobre o resultado publicado.
+//! As contagens são comparadas por cargo e por votável; qualquer diferença,
+//! ou falha da contagem-sombra, é uma discrepância que bloqueia a publicação
+//! do manifesto. Eleição sem cédulas na mixnet fica registrada como
+//! comparação indisponível, sem bloquear.
+//!
+//! A cédula da mixnet leva só o número do candidato: o cargo é o do único
+//! candidato com esse número na eleição; números repetidos entre cargos
+//! ficam como não atribuídos. Brancos e nulos só são comparados quando as
+//! duas contagens os informam. Cada comparação entra no log transparente.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::models::CandidatePosition;
+use crate::services::candidate::CandidateService;
+use crate::services::mixnet::MixnetService;
+use crate::services::results::ResultsService;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Implementação de contagem
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TallyEngine {
+    /// Soma dos boletins de urna (apuração oficial)
+    Bulletins,
+    /// Decifração e contagem da saída da mixnet
+    MixnetDecryption,
+}
+
+/// Votos de um cargo em uma contagem
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContestCount {
+    /// Votos por número de candidato
+    pub votes: BTreeMap<u32, u64>,
+    /// Ausente quando a implementação não distingue brancos
+    pub blank: Option<u64>,
+    pub null: Option<u64>,
+}
+
+/// Resultado de uma implementação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineTally {
+    pub engine: TallyEngine,
+    pub contests: BTreeMap<CandidatePosition, ContestCount>,
+    /// Votos sem cargo atribuível, pelo votável
+    pub unmatched: BTreeMap<String, u64>,
+    pub counted_at: DateTime<Utc>,
+}
+
+/// Diferença entre as contagens em um votável
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub contest: CandidatePosition,
+    /// Número do candidato, `BRANCO` ou `NULO`
+    pub votable: String,
+    pub primary: u64,
+    pub shadow: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShadowTallyStatus {
+    Consistent,
+    /// Diferença ou falha da contagem-sombra; bloqueia a certificação
+    Divergent,
+    /// Sem cédulas na mixnet para comparar
+    ShadowUnavailable,
+}
+
+/// Comparação entre a apuração oficial e a sombra
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowTallyReport {
+    pub id: Uuid,
+    pub election_id: Uuid,
+    pub primary: EngineTally,
+    pub shadow: Option<EngineTally>,
+    /// Erro da contagem-sombra, se falhou
+    pub shadow_error: Option<String>,
+    pub discrepancies: Vec<Discrepancy>,
+    pub status: ShadowTallyStatus,
+    pub compared_at: DateTime<Utc>,
+    pub log_index: Option<u64>,
+}
+
+impl ShadowTallyReport {
+    pub fn blocks_certification(&self) -> bool {
+        self.status == ShadowTallyStatus::Divergent
+    }
+}
+
+pub struct ShadowTallyService {
+    results: Arc<ResultsService>,
+    mixnet: Arc<MixnetService>,
+    candidates: Arc<CandidateService>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+    reports: RwLock<HashMap<Uuid, Vec<ShadowTallyReport>>>,
+}
+
+impl ShadowTallyService {
+    pub fn new(results: Arc<ResultsService>, mixnet: Arc<MixnetService>, candidates: Arc<CandidateService>) -> Self {
+        Self {
+            results,
+            mixnet,
+            candidates,
+            transparency_log: None,
+            reports: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registra cada comparação no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Conta a eleição pelas duas implementações e compara por cargo
+    pub async fn run(&self, election_id: Uuid) -> Result<ShadowTallyReport> {
+        let (primary, shadow) = tokio::join!(
+            self.count_bulletins(election_id),
+            self.count_mixnet(election_id),
+        );
+        let primary = primary?;
+
+        let (shadow, shadow_error) = match shadow {
+            Ok(shadow) => (shadow, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let discrepancies = shadow.as_ref().map(|s| compare(&primary, s)).unwrap_or_default();
+        let status = if shadow_error.is_some() || !discrepancies.is_empty() {
+            ShadowTallyStatus::Divergent
+        } else if shadow.is_none() {
+            ShadowTallyStatus::ShadowUnavailable
+        } else {
+            ShadowTallyStatus::Consistent
+        };
+
+        let mut report = ShadowTallyReport {
+            id: Uuid::new_v4(),
+            election_id,
+            primary,
+            shadow,
+            shadow_error,
+            discrepancies,
+            status,
+            compared_at: Utc::now(),
+            log_index: None,
+        };
+        if report.blocks_certification() {
+            log::warn!(
+                "Shadow tally diverges for election {}: {} discrepancies{}",
+                election_id,
+                report.discrepancies.len(),
+                report.shadow_error.as_deref().map(|e| format!(", shadow failed: {}", e)).unwrap_or_default()
+            );
+        }
+        report.log_index = self.log_report(&report).await?;
+
+        self.reports.write().await.entry(election_id).or_default().push(report.clone());
+        Ok(report)
+    }
+
+    /// Compara de novo antes da certificação; divergência bloqueia
+    pub async fn ensure_certifiable(&self, election_id: Uuid) -> Result<ShadowTallyReport> {
+        let report = self.run(election_id).await?;
+        if report.blocks_certification() {
+            return Err(anyhow!(
+                "Shadow tally {} diverges from the official count ({} discrepancies)",
+                report.id,
+                report.discrepancies.len()
+            ));
+        }
+        Ok(report)
+    }
+
+    pub async fn reports(&self, election_id: Uuid) -> Vec<ShadowTallyReport> {
+        self.reports.read().await.get(&election_id).cloned().unwrap_or_default()
+    }
+
+    pub async fn latest(&self, election_id: Uuid) -> Option<ShadowTallyReport> {
+        self.reports.read().await.get(&election_id).and_then(|reports| reports.last().cloned())
+    }
+
+    /// Apuração oficial, somando todos os boletins
+    async fn count_bulletins(&self, election_id: Uuid) -> Result<EngineTally> {
+        let results = self.results.aggregate(election_id, None).await?;
+        let contests = results.positions.iter()
+            .map(|position| (position.position, ContestCount {
+                votes: position.ranking.iter()
+                    .filter(|candidate| candidate.votes > 0)
+                    .map(|candidate| (candidate.number, candidate.votes))
+                    .collect(),
+                blank: Some(position.blank_votes),
+                null: Some(position.null_votes),
+            }))
+            .collect();
+
+        Ok(EngineTally {
+            engine: TallyEngine::Bulletins,
+            contests,
+            unmatched: results.unmatched_votes,
+            counted_at: Utc::now(),
+        })
+    }
+
+    /// Contagem-sombra pela mixnet; `None` sem cédulas na eleição
+    async fn count_mixnet(&self, election_id: Uuid) -> Result<Option<EngineTally>> {
+        if self.mixnet.get_batch(election_id).await.is_none() {
+            return Ok(None);
+        }
+        let tally = self.mixnet.tally(election_id).await?;
+
+        let mut positions: HashMap<u32, BTreeSet<CandidatePosition>> = HashMap::new();
+        for candidate in self.candidates.list_candidates(election_id, None).await {
+            positions.entry(candidate.number).or_default().insert(candidate.position);
+        }
+
+        let mut contests: BTreeMap<CandidatePosition, ContestCount> = BTreeMap::new();
+        let mut unmatched = BTreeMap::new();
+        for (number, count) in tally {
+            let position = u32::try_from(number).ok()
+                .and_then(|n| positions.get(&n))
+                .filter(|positions| positions.len() == 1)
+                .and_then(|positions| positions.iter().next());
+            match position {
+                Some(position) => {
+                    *contests.entry(*position).or_default().votes.entry(number as u32).or_insert(0) += count;
+                }
+                None => *unmatched.entry(number.to_string()).or_insert(0) += count,
+            }
+        }
+
+        Ok(Some(EngineTally {
+            engine: TallyEngine::MixnetDecryption,
+            contests,
+            unmatched,
+            counted_at: Utc::now(),
+        }))
+    }
+
+    async fn log_report(&self, report: &ShadowTallyReport) -> Result<Option<u64>> {
+        let Some(log) = &self.transparency_log else {
+            return Ok(None);
+        };
+        let inclusion = log.write().await.append_election_event(ElectionEvent {
+            id: report.id.to_string(),
+            event_type: ElectionEventType::ShadowTallyCompared,
+            election_id: report.election_id.to_string(),
+            data: serde_json::json!({
+                "primary": report.primary.engine,
+                "shadow": report.shadow.as_ref().map(|s| s.engine),
+                "status": report.status,
+                "discrepancies": report.discrepancies,
+                "shadow_error": report.shadow_error,
+            }),
+            timestamp: report.compared_at,
+            source: "Apuração".to_string(),
+        })?;
+        Ok(Some(inclusion.log_index))
+    }
+}
+
+/// Diferenças por cargo e votável; votável ausente conta zero
+fn compare(primary: &EngineTally, shadow: &EngineTally) -> Vec<Discrepancy> {
+    let empty = ContestCount::default();
+    let contests: BTreeSet<CandidatePosition> = primary.contests.keys().chain(shadow.contests.keys()).copied().collect();
+
+    let mut discrepancies = Vec::new();
+    for contest in contests {
+        let p = primary.contests.get(&contest).unwrap_or(&empty);
+        let s = shadow.contests.get(&contest).unwrap_or(&empty);
+
+        let numbers: BTreeSet<u32> = p.votes.keys().chain(s.votes.keys()).copied().collect();
+        for number in numbers {
+            let (primary_votes, shadow_votes) = (
+                p.votes.get(&number).copied().unwrap_or(0),
+                s.votes.get(&number).copied().unwrap_or(0),
+            );
+            if primary_votes != shadow_votes {
+                discrepancies.push(Discrepancy {
+                    contest,
+                    votable: number.to_string(),
+                    primary: primary_votes,
+                    shadow: shadow_votes,
+                });
+            }
+        }
+
+        for (votable, primary_marks, shadow_marks) in [("BRANCO", p.blank, s.blank), ("NULO", p.null, s.null)] {
+            if let (Some(primary_marks), Some(shadow_marks)) = (primary_marks, shadow_marks) {
+                if primary_marks != shadow_marks {
+                    discrepancies.push(Discrepancy {
+                        contest,
+                        votable: votable.to_string(),
+                        primary: primary_marks,
+                        shadow: shadow_marks,
+                    });
+                }
+            }
+        }
+    }
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rsa::BigUint;
+    use crate::analytics::SectionResult;
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+    use crate::models::{CreateCandidateRequest, CreateElectionRequest};
+    use crate::services::election::{ElectionService, ElectionStatus};
+    use crate::services::mixnet::{MixGroup, MixnetConfig};
+    use crate::services::results::ResultsConfig;
+
+    async fn setup() -> (ShadowTallyService, Arc<MixnetService>, Uuid) {
+        let elections = Arc::new(ElectionService::new());
+        let candidates = Arc::new(CandidateService::new(elections.clone()));
+        let election = elections.create_election(CreateElectionRequest {
+            title: "Eleição Municipal".to_string(),
+            description: None,
+            start_date: Utc::now() + Duration::days(1),
+            end_date: Utc::now() + Duration::days(2),
+        }, "admin").await.unwrap();
+        for number in [13, 45] {
+            candidates.create_candidate(election.id, CreateCandidateRequest {
+                name: format!("Candidato {}", number),
+                party: "PA".to_string(),
+                number,
+                position: CandidatePosition::Mayor,
+                coalition: None,
+                region: None,
+                photo_base64: None,
+            }).await.unwrap();
+        }
+        for status in [ElectionStatus::Scheduled, ElectionStatus::Active, ElectionStatus::Closed] {
+            elections.transition(election.id, status, "admin", None).await.unwrap();
+        }
+
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+        let results = Arc::new(ResultsService::new(elections, candidates.clone(), signing_key, ResultsConfig {
+            cache_ttl_seconds: 60,
+        }));
+        results.record_bulletin(election.id, SectionResult {
+            state: "SP".to_string(),
+            municipality_code: "71072".to_string(),
+            municipality: "São Paulo".to_string(),
+            zone: "1".to_string(),
+            section: "1".to_string(),
+            registered_voters: 10,
+            votes_cast: 3,
+            votes_by_candidate: [("13", 2), ("45", 1)].iter()
+                .map(|(k, v)| (k.to_string(), *v))
+                .collect(),
+            bu_digest: "bu-1".to_string(),
+        }).await.unwrap();
+
+        // Grupo pequeno (p = 2039, q = 1019) apenas para testes
+        let group = MixGroup::new(BigUint::from(2039u32), BigUint::from(4u32)).unwrap();
+        let mixnet = Arc::new(MixnetService::new(group, MixnetConfig { stages: 2, proof_rounds: 16 }));
+        (ShadowTallyService::new(results, mixnet.clone(), candidates), mixnet, election.id)
+    }
+
+    #[tokio::test]
+    async fn test_shadow_tally_matches_official_count() {
+        let (service, mixnet, election_id) = setup().await;
+        let report = service.ensure_certifiable(election_id).await.unwrap();
+        assert_eq!(report.status, ShadowTallyStatus::ShadowUnavailable);
+
+        let ballots = [13u64, 45, 13].iter().map(|n| mixnet.encrypt_ballot(*n).unwrap()).collect();
+        mixnet.submit_ballots(election_id, ballots).await.unwrap();
+        // Lote ainda não misturado: a contagem-sombra falha e bloqueia
+        let report = service.run(election_id).await.unwrap();
+        assert_eq!(report.status, ShadowTallyStatus::Divergent);
+        assert!(report.shadow_error.is_some());
+
+        mixnet.run(election_id).await.unwrap();
+        let report = service.ensure_certifiable(election_id).await.unwrap();
+        assert_eq!(report.status, ShadowTallyStatus::Consistent);
+        assert!(report.discrepancies.is_empty());
+        assert_eq!(service.reports(election_id).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_tally_divergence_blocks_certification() {
+        let (service, mixnet, election_id) = setup().await;
+        let ballots = [13u64, 45, 45].iter().map(|n| mixnet.encrypt_ballot(*n).unwrap()).collect();
+        mixnet.submit_ballots(election_id, ballots).await.unwrap();
+        mixnet.run(election_id).await.unwrap();
+
+        assert!(service.ensure_certifiable(election_id).await.is_err());
+        let report = service.latest(election_id).await.unwrap();
+        assert_eq!(report.status, ShadowTallyStatus::Divergent);
+        assert_eq!(report.discrepancies, vec![
+            Discrepancy { contest: CandidatePosition::Mayor, votable: "13".to_string(), primary: 2, shadow: 1 },
+            Discrepancy { contest: CandidatePosition::Mayor, votable: "45".to_string(), primary: 1, shadow: 2 },
+        ]);
+    }
+}
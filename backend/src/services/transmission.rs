@@ -0,0 +1,388 @@
+//! Declaração de fim de transmissão por zona
+//!
+//! Quando todas as urnas provisionadas de uma zona transmitiram o boletim,
+//! a autoridade zonal assina (Ed25519) a declaração de fim de transmissão,
+//! que referencia o conjunto de hashes dos BUs recebidos. A declaração só é
+//! aceita com a zona completa, é endossada pelo consenso dos nós e entra no
+//! log transparente. A certificação da eleição exige a declaração de todas
+//! as zonas, ainda coerente com os boletins recebidos: uma urna que não
+//! transmitiu deixa a zona sem declaração e não passa despercebida.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::consensus::consensus_service::{ConsensusOperation, ConsensusRequest, ConsensusService, ConsensusUtils};
+use crate::consensus::threshold_signatures::ThresholdSignature;
+use crate::services::provisioning::ProvisioningService;
+use crate::services::results::ResultsService;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Prefixo do que a autoridade zonal assina
+const DECLARATION_CONTEXT: &str = "fortis-end-of-transmission-v1";
+
+/// Declaração de fim de transmissão de uma zona
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EndOfTransmissionDeclaration {
+    pub election_id: Uuid,
+    pub zone: String,
+    /// Hash do BU de cada seção da zona
+    pub bu_hashes: BTreeMap<String, String>,
+    /// Resumo do conjunto de BUs, na ordem das seções
+    pub bu_set_digest: String,
+}
+
+impl EndOfTransmissionDeclaration {
+    pub fn new(election_id: Uuid, zone: &str, bu_hashes: BTreeMap<String, String>) -> Self {
+        let mut digest = Sha256::new();
+        for (section, bu_hash) in &bu_hashes {
+            digest.update(format!("{}:{}\n", section, bu_hash));
+        }
+        Self {
+            election_id,
+            zone: zone.to_string(),
+            bu_hashes,
+            bu_set_digest: hex::encode(digest.finalize()),
+        }
+    }
+
+    pub fn hash(&self) -> Result<String> {
+        Ok(hex::encode(Sha256::digest(serde_json::to_vec(self)?)))
+    }
+}
+
+/// Mensagem assinada pela autoridade zonal
+pub fn signing_input(declaration_hash: &str) -> Vec<u8> {
+    format!("{}|{}", DECLARATION_CONTEXT, declaration_hash).into_bytes()
+}
+
+/// Confere a assinatura da autoridade sobre o hash da declaração
+pub fn verify_authority_signature(public_key_hex: &str, declaration_hash: &str, signature_hex: &str) -> bool {
+    let (Ok(public_key), Ok(signature)) = (hex::decode(public_key_hex), hex::decode(signature_hex)) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(&signing_input(declaration_hash), &signature)
+        .is_ok()
+}
+
+/// Situação da transmissão de uma zona
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneTransmissionStatus {
+    pub zone: String,
+    /// Seções com urna provisionada
+    pub expected: usize,
+    pub received: usize,
+    pub missing: Vec<String>,
+    /// Boletins de seções sem urna provisionada
+    pub unexpected: Vec<String>,
+    /// Declaração a assinar, presente quando a zona está completa
+    pub draft: Option<EndOfTransmissionDeclaration>,
+    pub draft_hash: Option<String>,
+    pub declared: bool,
+}
+
+/// Declaração assinada e endossada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTransmissionDeclaration {
+    pub declaration: EndOfTransmissionDeclaration,
+    pub declaration_hash: String,
+    pub declared_by: String,
+    pub declared_at: DateTime<Utc>,
+    pub authority_public_key: String,
+    pub signature: String,
+    /// Assinatura de limiar dos nós de consenso
+    pub endorsement: ThresholdSignature,
+    pub log_index: Option<u64>,
+}
+
+/// Assinatura da autoridade zonal enviada à API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclarationSubmission {
+    pub declaration_hash: String,
+    pub signature: String,
+}
+
+/// Acompanha a transmissão das zonas e guarda as declarações
+pub struct TransmissionService {
+    results: Arc<ResultsService>,
+    provisioning: Arc<ProvisioningService>,
+    consensus: Option<Arc<ConsensusService>>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+    /// Chave pública (hex) da autoridade de cada zona
+    authorities: RwLock<HashMap<(Uuid, String), String>>,
+    declarations: RwLock<HashMap<(Uuid, String), SignedTransmissionDeclaration>>,
+}
+
+impl TransmissionService {
+    pub fn new(results: Arc<ResultsService>, provisioning: Arc<ProvisioningService>) -> Self {
+        Self {
+            results,
+            provisioning,
+            consensus: None,
+            transparency_log: None,
+            authorities: RwLock::new(HashMap::new()),
+            declarations: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Endossa as declarações pelo consenso dos nós; sem consenso nenhuma é aceita
+    pub fn with_consensus(mut self, consensus: Arc<ConsensusService>) -> Self {
+        self.consensus = Some(consensus);
+        self
+    }
+
+    /// Registra as declarações no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Cadastra a chave Ed25519 da autoridade da zona
+    pub async fn register_authority(&self, election_id: Uuid, zone: &str, public_key_hex: &str) -> Result<()> {
+        let public_key = hex::decode(public_key_hex)
+            .map_err(|e| anyhow!("Invalid authority public key: {}", e))?;
+        if public_key.len() != 32 {
+            return Err(anyhow!("Authority public key must be a 32-byte Ed25519 key"));
+        }
+        let key = (election_id, zone.to_string());
+        if self.declarations.read().await.contains_key(&key) {
+            return Err(anyhow!("Zone {} already declared its end of transmission", zone));
+        }
+        self.authorities.write().await.insert(key, public_key_hex.to_lowercase());
+        Ok(())
+    }
+
+    /// Situação de todas as zonas com urna provisionada ou boletim recebido
+    pub async fn zones(&self, election_id: Uuid) -> Result<Vec<ZoneTransmissionStatus>> {
+        let mut statuses = Vec::new();
+        for zone in self.known_zones(election_id).await {
+            statuses.push(self.status(election_id, &zone).await?);
+        }
+        Ok(statuses)
+    }
+
+    /// Situação da transmissão da zona
+    pub async fn status(&self, election_id: Uuid, zone: &str) -> Result<ZoneTransmissionStatus> {
+        let expected = self.provisioning.provisioned_sections(election_id).await
+            .remove(zone)
+            .unwrap_or_default();
+        let received = self.received_bulletins(election_id, zone).await;
+        let missing: Vec<String> = expected.iter().filter(|s| !received.contains_key(*s)).cloned().collect();
+        let unexpected: Vec<String> = received.keys().filter(|s| !expected.contains(*s)).cloned().collect();
+
+        let draft = (!expected.is_empty() && missing.is_empty())
+            .then(|| EndOfTransmissionDeclaration::new(election_id, zone, received.clone()));
+        let draft_hash = draft.as_ref().map(EndOfTransmissionDeclaration::hash).transpose()?;
+
+        Ok(ZoneTransmissionStatus {
+            zone: zone.to_string(),
+            expected: expected.len(),
+            received: received.len(),
+            missing,
+            unexpected,
+            draft,
+            draft_hash,
+            declared: self.declarations.read().await.contains_key(&(election_id, zone.to_string())),
+        })
+    }
+
+    /// Aceita a declaração assinada pela autoridade e a submete ao consenso
+    pub async fn declare(
+        &self,
+        election_id: Uuid,
+        zone: &str,
+        submission: DeclarationSubmission,
+        declared_by: &str,
+    ) -> Result<SignedTransmissionDeclaration> {
+        let key = (election_id, zone.to_string());
+        if let Some(existing) = self.declarations.read().await.get(&key) {
+            if existing.declaration_hash == submission.declaration_hash {
+                return Ok(existing.clone());
+            }
+            return Err(anyhow!("Zone {} already declared a different set of bulletins", zone));
+        }
+
+        let authority_public_key = self.authorities.read().await.get(&key).cloned()
+            .ok_or_else(|| anyhow!("No authority registered for zone {}", zone))?;
+
+        let status = self.status(election_id, zone).await?;
+        let (Some(declaration), Some(draft_hash)) = (status.draft, status.draft_hash) else {
+            return Err(anyhow!(
+                "Zone {} is still missing bulletins from sections: {}",
+                zone,
+                status.missing.join(", ")
+            ));
+        };
+        if draft_hash != submission.declaration_hash {
+            return Err(anyhow!("Declaration does not match the bulletins received for zone {}", zone));
+        }
+        if !verify_authority_signature(&authority_public_key, &draft_hash, &submission.signature) {
+            return Err(anyhow!("Invalid authority signature for zone {}", zone));
+        }
+
+        let endorsement = self.endorse(&declaration, &draft_hash, declared_by).await?;
+        let mut signed = SignedTransmissionDeclaration {
+            declaration,
+            declaration_hash: draft_hash,
+            declared_by: declared_by.to_string(),
+            declared_at: Utc::now(),
+            authority_public_key,
+            signature: submission.signature,
+            endorsement,
+            log_index: None,
+        };
+
+        if let Some(log) = &self.transparency_log {
+            let proof = log.write().await.append_election_event(ElectionEvent {
+                id: Uuid::new_v4().to_string(),
+                event_type: ElectionEventType::TransmissionClosed,
+                election_id: election_id.to_string(),
+                data: serde_json::json!({
+                    "zone": zone,
+                    "declaration_hash": signed.declaration_hash,
+                    "bu_set_digest": signed.declaration.bu_set_digest,
+                    "sections": signed.declaration.bu_hashes.len(),
+                    "authority_public_key": signed.authority_public_key,
+                    "signature": signed.signature,
+                    "endorsement_id": signed.endorsement.id,
+                }),
+                timestamp: signed.declared_at,
+                source: declared_by.to_string(),
+            })?;
+            signed.log_index = Some(proof.log_index);
+        }
+
+        log::info!("End of transmission declared for zone {} of election {}", zone, election_id);
+        self.declarations.write().await.insert(key, signed.clone());
+        Ok(signed)
+    }
+
+    /// Declaração aceita da zona
+    pub async fn declaration(&self, election_id: Uuid, zone: &str) -> Option<SignedTransmissionDeclaration> {
+        self.declarations.read().await.get(&(election_id, zone.to_string())).cloned()
+    }
+
+    /// Pré-requisito da certificação: toda zona declarada, sem boletim posterior
+    pub async fn ensure_all_declared(&self, election_id: Uuid) -> Result<()> {
+        let zones = self.known_zones(election_id).await;
+        if zones.is_empty() {
+            return Err(anyhow!("No zones provisioned for election {}", election_id));
+        }
+
+        let declarations = self.declarations.read().await;
+        let mut pending = Vec::new();
+        for zone in zones {
+            let received = self.received_bulletins(election_id, &zone).await;
+            let current = EndOfTransmissionDeclaration::new(election_id, &zone, received);
+            match declarations.get(&(election_id, zone.clone())) {
+                Some(signed) if signed.declaration.bu_set_digest == current.bu_set_digest => {}
+                Some(_) => pending.push(format!("{} (bulletins changed after declaration)", zone)),
+                None => pending.push(zone),
+            }
+        }
+
+        if pending.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("End of transmission not declared for zones: {}", pending.join(", ")))
+        }
+    }
+
+    async fn endorse(
+        &self,
+        declaration: &EndOfTransmissionDeclaration,
+        declaration_hash: &str,
+        declared_by: &str,
+    ) -> Result<ThresholdSignature> {
+        let consensus = self.consensus.as_ref()
+            .ok_or_else(|| anyhow!("Consensus service unavailable to endorse the declaration"))?;
+        let operation = ConsensusOperation::ZoneTransmissionClosed;
+        let result = consensus.start_consensus(ConsensusRequest {
+            id: format!("eot_{}", Uuid::new_v4()),
+            priority: ConsensusUtils::calculate_priority(&operation),
+            operation,
+            data: serde_json::json!({
+                "election_id": declaration.election_id,
+                "zone": declaration.zone,
+                "declaration_hash": declaration_hash,
+                "bu_set_digest": declaration.bu_set_digest,
+            }),
+            requester_id: declared_by.to_string(),
+            timeout: None,
+            metadata: HashMap::new(),
+        }).await?;
+
+        match result.threshold_signature {
+            Some(signature) if result.consensus_reached => Ok(signature),
+            _ => Err(anyhow!("Consensus nodes did not endorse the declaration for zone {}", declaration.zone)),
+        }
+    }
+
+    async fn known_zones(&self, election_id: Uuid) -> BTreeSet<String> {
+        let mut zones: BTreeSet<String> = self.provisioning.provisioned_sections(election_id).await
+            .into_keys()
+            .collect();
+        zones.extend(self.results.bulletin_totals(election_id).await.into_iter().map(|b| b.zone));
+        zones
+    }
+
+    /// Hash do BU de cada seção recebida na zona
+    async fn received_bulletins(&self, election_id: Uuid, zone: &str) -> BTreeMap<String, String> {
+        self.results.bulletin_totals(election_id).await
+            .into_iter()
+            .filter(|b| b.zone == zone)
+            .map(|b| (b.section, b.bu_digest))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn bu_hashes() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("0001".to_string(), "aa".repeat(32)),
+            ("0002".to_string(), "bb".repeat(32)),
+        ])
+    }
+
+    #[test]
+    fn test_declaration_digest_covers_every_bulletin() {
+        let election_id = Uuid::new_v4();
+        let declaration = EndOfTransmissionDeclaration::new(election_id, "0042", bu_hashes());
+
+        let mut altered = bu_hashes();
+        altered.insert("0002".to_string(), "cc".repeat(32));
+        let tampered = EndOfTransmissionDeclaration::new(election_id, "0042", altered);
+        assert_ne!(declaration.bu_set_digest, tampered.bu_set_digest);
+
+        let mut partial = bu_hashes();
+        partial.remove("0002");
+        let incomplete = EndOfTransmissionDeclaration::new(election_id, "0042", partial);
+        assert_ne!(declaration.hash().unwrap(), incomplete.hash().unwrap());
+    }
+
+    #[test]
+    fn test_authority_signature_binds_declaration_hash() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = hex::encode(key_pair.public_key().as_ref());
+
+        let hash = EndOfTransmissionDeclaration::new(Uuid::new_v4(), "0042", bu_hashes()).hash().unwrap();
+        let signature = hex::encode(key_pair.sign(&signing_input(&hash)).as_ref());
+
+        assert!(verify_authority_signature(&public_key, &hash, &signature));
+        assert!(!verify_authority_signature(&public_key, &"00".repeat(32), &signature));
+        assert!(!verify_authority_signature(&public_key, &hash, "zz"));
+    }
+}
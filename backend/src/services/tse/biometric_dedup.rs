@@ -0,0 +1,569 @@
+//! Deduplicação biométrica 1:N do cadastro eleitoral
+//!
+//! Cada template recebido na sincronização do cadastro é confrontado com
+//! toda a base já inscrita antes de ser inscrito. O motor de busca é
+//! plugável: sem ABIS configurado, usa o índice vetorial do próprio backend
+//! (vetor invariante a rotação e translação para pré-seleção, confirmada
+//! com `match_score`); com ABIS, delega a busca ao sistema do TSE.
+//!
+//! Coincidências acima do limiar viram suspeitas de inscrição duplicada e
+//! aguardam revisão humana; nada é cancelado automaticamente.
+//!
+//! Antes de pesquisar ou inscrever, o uso do template para detecção de
+//! duplicidade passa pelo livro de consentimento (LGPD). Eleitor sem base
+//! legal vigente não é pesquisado, sai da base de busca e é contado na
+//! execução da sincronização para acompanhamento.
+
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::services::consent::{BiometricCategory, ConsentLedger, ProcessingPurpose};
+use fortis_domain::biometric::FingerprintTemplate;
+
+/// Faixas de distância entre pares de minúcias no vetor do índice
+const DISTANCE_BINS: usize = 8;
+/// Faixas do ângulo relativo entre pares de minúcias
+const ANGLE_BINS: usize = 4;
+/// Pares mais distantes que isso não entram no vetor, em pixels
+const MAX_PAIR_DISTANCE_PX: f32 = 160.0;
+/// Candidatos pré-selecionados pelo vetor antes do pareamento de minúcias
+const SHORTLIST_SIZE: usize = 32;
+/// Similaridade de cosseno mínima para a pré-seleção
+const SHORTLIST_MIN_SIMILARITY: f32 = 0.5;
+/// Candidatos pedidos ao ABIS por busca
+const ABIS_MAX_CANDIDATES: usize = 10;
+
+/// Eleitor inscrito cujo template coincide com o pesquisado
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DedupCandidate {
+    pub voter_id: String,
+    pub score: f32,
+}
+
+/// Motor de busca biométrica 1:N
+pub trait BiometricDeduplicator: Send + Sync {
+    fn name(&self) -> &str;
+    /// Inscreve ou substitui o template do eleitor
+    fn enroll<'a>(&'a self, voter_id: &'a str, template: &'a FingerprintTemplate) -> BoxFuture<'a, Result<()>>;
+    /// Retira o eleitor da base de busca
+    fn remove<'a>(&'a self, voter_id: &'a str) -> BoxFuture<'a, Result<()>>;
+    /// Inscritos com pontuação a partir do limiar, da maior para a menor
+    fn search<'a>(&'a self, template: &'a FingerprintTemplate, threshold: f32) -> BoxFuture<'a, Result<Vec<DedupCandidate>>>;
+}
+
+/// Vetor de pares de minúcias: distância × ângulo relativo, normalizado
+///
+/// Depende só das relações entre minúcias, e não da posição ou rotação
+/// do dedo no leitor.
+pub fn feature_vector(template: &FingerprintTemplate) -> Vec<f32> {
+    let mut vector = vec![0.0f32; DISTANCE_BINS * ANGLE_BINS];
+    let minutiae = &template.minutiae;
+    for (i, a) in minutiae.iter().enumerate() {
+        for b in &minutiae[i + 1..] {
+            let distance = ((a.x as f32 - b.x as f32).powi(2) + (a.y as f32 - b.y as f32).powi(2)).sqrt();
+            if distance >= MAX_PAIR_DISTANCE_PX {
+                continue;
+            }
+            let diff = (a.angle_degrees() - b.angle_degrees()).rem_euclid(360.0);
+            let relative = diff.min(360.0 - diff);
+            let distance_bin = ((distance / MAX_PAIR_DISTANCE_PX) * DISTANCE_BINS as f32) as usize;
+            let angle_bin = ((relative / 180.0) * ANGLE_BINS as f32) as usize;
+            vector[distance_bin.min(DISTANCE_BINS - 1) * ANGLE_BINS + angle_bin.min(ANGLE_BINS - 1)] += 1.0;
+        }
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+struct IndexedTemplate {
+    vector: Vec<f32>,
+    template: FingerprintTemplate,
+}
+
+/// Índice vetorial em memória, motor padrão sem ABIS
+#[derive(Default)]
+pub struct VectorIndexDeduplicator {
+    entries: RwLock<HashMap<String, IndexedTemplate>>,
+}
+
+impl VectorIndexDeduplicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.read().await.is_empty()
+    }
+}
+
+impl BiometricDeduplicator for VectorIndexDeduplicator {
+    fn name(&self) -> &str {
+        "vector-index"
+    }
+
+    fn enroll<'a>(&'a self, voter_id: &'a str, template: &'a FingerprintTemplate) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let entry = IndexedTemplate { vector: feature_vector(template), template: template.clone() };
+            self.entries.write().await.insert(voter_id.to_string(), entry);
+            Ok(())
+        })
+    }
+
+    fn remove<'a>(&'a self, voter_id: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.entries.write().await.remove(voter_id);
+            Ok(())
+        })
+    }
+
+    fn search<'a>(&'a self, template: &'a FingerprintTemplate, threshold: f32) -> BoxFuture<'a, Result<Vec<DedupCandidate>>> {
+        Box::pin(async move {
+            let probe = feature_vector(template);
+            let entries = self.entries.read().await;
+
+            let mut shortlist: Vec<(&String, &IndexedTemplate, f32)> = entries.iter()
+                .map(|(voter_id, entry)| (voter_id, entry, cosine(&probe, &entry.vector)))
+                .filter(|(_, _, similarity)| *similarity >= SHORTLIST_MIN_SIMILARITY)
+                .collect();
+            shortlist.sort_by(|a, b| b.2.total_cmp(&a.2));
+            shortlist.truncate(SHORTLIST_SIZE);
+
+            let mut candidates: Vec<DedupCandidate> = shortlist.into_iter()
+                .map(|(voter_id, entry, _)| DedupCandidate {
+                    voter_id: voter_id.clone(),
+                    score: template.match_score(&entry.template),
+                })
+                .filter(|candidate| candidate.score >= threshold)
+                .collect();
+            candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+            Ok(candidates)
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct AbisEnrollRequest<'a> {
+    subject_id: &'a str,
+    /// Registro ISO/IEC 19794-2 em base64
+    template: String,
+}
+
+#[derive(Serialize)]
+struct AbisIdentifyRequest {
+    template: String,
+    threshold: f32,
+    max_candidates: usize,
+}
+
+#[derive(Deserialize)]
+struct AbisIdentifyResponse {
+    candidates: Vec<AbisCandidate>,
+}
+
+#[derive(Deserialize)]
+struct AbisCandidate {
+    subject_id: String,
+    score: f32,
+}
+
+/// Adaptador para o ABIS (Automated Biometric Identification System) do TSE
+pub struct AbisDeduplicator {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl AbisDeduplicator {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    async fn check(response: reqwest::Response, action: &str) -> Result<reqwest::Response> {
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        Err(anyhow!("ABIS {} failed ({}): {}", action, status, error_text))
+    }
+}
+
+impl BiometricDeduplicator for AbisDeduplicator {
+    fn name(&self) -> &str {
+        "tse-abis"
+    }
+
+    fn enroll<'a>(&'a self, voter_id: &'a str, template: &'a FingerprintTemplate) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let response = self.client
+                .put(format!("{}/subjects/{}", self.base_url, voter_id))
+                .bearer_auth(&self.api_key)
+                .json(&AbisEnrollRequest {
+                    subject_id: voter_id,
+                    template: general_purpose::STANDARD.encode(template.to_iso_19794_2()),
+                })
+                .send()
+                .await?;
+            Self::check(response, "enroll").await?;
+            Ok(())
+        })
+    }
+
+    fn remove<'a>(&'a self, voter_id: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let response = self.client
+                .delete(format!("{}/subjects/{}", self.base_url, voter_id))
+                .bearer_auth(&self.api_key)
+                .send()
+                .await?;
+            // Eleitor nunca inscrito no ABIS
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(());
+            }
+            Self::check(response, "remove").await?;
+            Ok(())
+        })
+    }
+
+    fn search<'a>(&'a self, template: &'a FingerprintTemplate, threshold: f32) -> BoxFuture<'a, Result<Vec<DedupCandidate>>> {
+        Box::pin(async move {
+            let response = self.client
+                .post(format!("{}/identify", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&AbisIdentifyRequest {
+                    template: general_purpose::STANDARD.encode(template.to_iso_19794_2()),
+                    threshold,
+                    max_candidates: ABIS_MAX_CANDIDATES,
+                })
+                .send()
+                .await?;
+            let body: AbisIdentifyResponse = Self::check(response, "identify").await?.json().await?;
+
+            let mut candidates: Vec<DedupCandidate> = body.candidates.into_iter()
+                .filter(|candidate| candidate.score >= threshold)
+                .map(|candidate| DedupCandidate { voter_id: candidate.subject_id, score: candidate.score })
+                .collect();
+            candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+            Ok(candidates)
+        })
+    }
+}
+
+/// Decodifica o template enviado pelo TSE (ISO/IEC 19794-2 em base64)
+pub fn decode_template(encoded: &str) -> Result<FingerprintTemplate> {
+    let bytes = general_purpose::STANDARD.decode(encoded.trim())?;
+    Ok(FingerprintTemplate::from_iso_19794_2(&bytes)?)
+}
+
+/// Situação da suspeita de inscrição duplicada
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchReviewStatus {
+    Pending,
+    ConfirmedDuplicate,
+    Dismissed,
+}
+
+/// Suspeita de inscrição duplicada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateMatch {
+    pub id: Uuid,
+    /// Eleitor cujo template foi pesquisado
+    pub voter_id: String,
+    /// Eleitor já inscrito que coincidiu
+    pub matched_voter_id: String,
+    pub score: f32,
+    pub engine: String,
+    /// Execução da sincronização do cadastro que encontrou a coincidência
+    pub run_id: Option<Uuid>,
+    pub detected_at: DateTime<Utc>,
+    pub status: MatchReviewStatus,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+impl DuplicateMatch {
+    fn involves_pair(&self, a: &str, b: &str) -> bool {
+        (self.voter_id == a && self.matched_voter_id == b) || (self.voter_id == b && self.matched_voter_id == a)
+    }
+}
+
+/// Resultado da busca 1:N de um eleitor
+#[derive(Debug)]
+pub enum Screening {
+    /// Template pesquisado e inscrito; suspeitas novas
+    Screened(Vec<DuplicateMatch>),
+    /// Uso negado pelo livro de consentimento; nada foi pesquisado
+    NotAuthorized(String),
+}
+
+/// Deduplicação biométrica e fila de revisão das suspeitas
+pub struct BiometricDedupService {
+    engine: Arc<dyn BiometricDeduplicator>,
+    threshold: f32,
+    matches: RwLock<Vec<DuplicateMatch>>,
+    consent_ledger: Option<Arc<ConsentLedger>>,
+}
+
+impl BiometricDedupService {
+    pub fn new(engine: Arc<dyn BiometricDeduplicator>, threshold: f32) -> Self {
+        Self {
+            engine,
+            threshold,
+            matches: RwLock::new(Vec::new()),
+            consent_ledger: None,
+        }
+    }
+
+    /// Condiciona a busca e a inscrição ao livro de consentimento
+    pub fn with_consent_ledger(mut self, ledger: Arc<ConsentLedger>) -> Self {
+        self.consent_ledger = Some(ledger);
+        self
+    }
+
+    /// ABIS do TSE quando configurado; senão o índice vetorial local
+    pub fn from_config(config: &Config) -> Self {
+        let engine: Arc<dyn BiometricDeduplicator> = match &config.tse.abis_url {
+            Some(url) => Arc::new(AbisDeduplicator::new(url, &config.tse.api_key)),
+            None => Arc::new(VectorIndexDeduplicator::new()),
+        };
+        Self::new(engine, config.tse.biometric_match_threshold)
+    }
+
+    pub fn engine_name(&self) -> &str {
+        self.engine.name()
+    }
+
+    /// Pesquisa 1:N e inscreve o template; devolve as suspeitas novas
+    ///
+    /// O par já registrado, em qualquer situação, não é aberto de novo, de
+    /// modo que ressincronizar o eleitor não reabre uma suspeita descartada.
+    /// Sem autorização do livro de consentimento, o eleitor não é pesquisado
+    /// e é retirado da base de busca.
+    pub async fn screen(&self, voter_id: &str, template: &FingerprintTemplate, run_id: Option<Uuid>) -> Result<Screening> {
+        if let Some(ledger) = &self.consent_ledger {
+            let interaction_id = run_id.unwrap_or_else(Uuid::new_v4).to_string();
+            let authorized = ledger.authorize_use(
+                voter_id,
+                BiometricCategory::BiometricTemplate,
+                ProcessingPurpose::DuplicateDetection,
+                &interaction_id,
+                "tse-voter-roll-sync",
+            ).await;
+            if let Err(e) = authorized {
+                self.engine.remove(voter_id).await?;
+                return Ok(Screening::NotAuthorized(e.to_string()));
+            }
+        }
+
+        let candidates = self.engine.search(template, self.threshold).await?;
+
+        let mut flagged = Vec::new();
+        {
+            let mut matches = self.matches.write().await;
+            for candidate in candidates.into_iter().filter(|c| c.voter_id != voter_id) {
+                if matches.iter().any(|m| m.involves_pair(voter_id, &candidate.voter_id)) {
+                    continue;
+                }
+                log::warn!(
+                    "Suspected duplicate registration: {} matches {} (score {:.2}, {})",
+                    voter_id, candidate.voter_id, candidate.score, self.engine.name()
+                );
+                let suspected = DuplicateMatch {
+                    id: Uuid::new_v4(),
+                    voter_id: voter_id.to_string(),
+                    matched_voter_id: candidate.voter_id,
+                    score: candidate.score,
+                    engine: self.engine.name().to_string(),
+                    run_id,
+                    detected_at: Utc::now(),
+                    status: MatchReviewStatus::Pending,
+                    reviewed_by: None,
+                    reviewed_at: None,
+                    notes: None,
+                };
+                matches.push(suspected.clone());
+                flagged.push(suspected);
+            }
+        }
+
+        self.engine.enroll(voter_id, template).await?;
+        Ok(Screening::Screened(flagged))
+    }
+
+    /// Retira da base de busca o eleitor removido do cadastro
+    pub async fn withdraw(&self, voter_id: &str) -> Result<()> {
+        self.engine.remove(voter_id).await
+    }
+
+    /// Suspeitas registradas, da mais recente para a mais antiga
+    pub async fn matches(&self, status: Option<MatchReviewStatus>) -> Vec<DuplicateMatch> {
+        let matches = self.matches.read().await;
+        matches.iter().rev()
+            .filter(|m| status.map_or(true, |status| m.status == status))
+            .cloned()
+            .collect()
+    }
+
+    pub async fn get_match(&self, id: Uuid) -> Option<DuplicateMatch> {
+        self.matches.read().await.iter().find(|m| m.id == id).cloned()
+    }
+
+    /// Decide a suspeita pendente; a decisão não pode ser refeita
+    pub async fn review(&self, id: Uuid, decision: MatchReviewStatus, reviewer: &str, notes: Option<String>) -> Result<DuplicateMatch> {
+        if decision == MatchReviewStatus::Pending {
+            return Err(anyhow!("Review decision must confirm or dismiss the match"));
+        }
+        let mut matches = self.matches.write().await;
+        let entry = matches.iter_mut().find(|m| m.id == id)
+            .ok_or_else(|| anyhow!("Duplicate match {} not found", id))?;
+        if entry.status != MatchReviewStatus::Pending {
+            return Err(anyhow!("Duplicate match {} already reviewed ({:?})", id, entry.status));
+        }
+        entry.status = decision;
+        entry.reviewed_by = Some(reviewer.to_string());
+        entry.reviewed_at = Some(Utc::now());
+        entry.notes = notes;
+        log::info!(
+            "Duplicate match {} ({} / {}) reviewed by {}: {:?}",
+            id, entry.voter_id, entry.matched_voter_id, reviewer, decision
+        );
+        Ok(entry.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::consent::{ConsentQuery, LegalBasis, PurposeDeclaration};
+    use fortis_domain::biometric::{Minutia, MinutiaKind};
+
+    /// Template sintético reprodutível a partir da semente
+    fn template(seed: u64, offset: (u16, u16)) -> FingerprintTemplate {
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as u16
+        };
+        let minutiae = (0..30)
+            .map(|_| Minutia {
+                x: 20 + next() % 360 + offset.0,
+                y: 20 + next() % 360 + offset.1,
+                angle: (next() % 256) as u8,
+                kind: MinutiaKind::RidgeEnding,
+                quality: 80,
+            })
+            .collect();
+        FingerprintTemplate { width: 500, height: 500, resolution_ppcm: 197, quality: 80, minutiae }
+    }
+
+    fn screened(screening: Screening) -> Vec<DuplicateMatch> {
+        match screening {
+            Screening::Screened(flagged) => flagged,
+            Screening::NotAuthorized(reason) => panic!("screening not authorized: {}", reason),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vector_index_finds_shifted_capture() {
+        let index = VectorIndexDeduplicator::new();
+        for seed in 1..=20 {
+            index.enroll(&format!("T{}", seed), &template(seed, (0, 0))).await.unwrap();
+        }
+
+        // Mesmo dedo, capturado deslocado no leitor
+        let candidates = index.search(&template(7, (15, 10)), 0.4).await.unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].voter_id, "T7");
+        assert!(index.search(&template(99, (0, 0)), 0.4).await.unwrap().is_empty());
+
+        index.remove("T7").await.unwrap();
+        assert!(index.search(&template(7, (15, 10)), 0.4).await.unwrap().is_empty());
+        assert_eq!(index.len().await, 19);
+    }
+
+    #[tokio::test]
+    async fn test_review_workflow_does_not_reopen_pairs() {
+        let service = BiometricDedupService::new(Arc::new(VectorIndexDeduplicator::new()), 0.4);
+        assert!(screened(service.screen("T1", &template(1, (0, 0)), None).await.unwrap()).is_empty());
+        assert!(screened(service.screen("T2", &template(2, (0, 0)), None).await.unwrap()).is_empty());
+
+        let flagged = screened(service.screen("T3", &template(1, (5, 5)), None).await.unwrap());
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].matched_voter_id, "T1");
+
+        // Ressincronizar o mesmo eleitor não reabre a suspeita
+        assert!(screened(service.screen("T3", &template(1, (5, 5)), None).await.unwrap()).is_empty());
+        assert!(service.review(flagged[0].id, MatchReviewStatus::Pending, "revisor", None).await.is_err());
+
+        let reviewed = service.review(flagged[0].id, MatchReviewStatus::Dismissed, "revisor", Some("Gêmeos".to_string())).await.unwrap();
+        assert_eq!(reviewed.reviewed_by.as_deref(), Some("revisor"));
+        assert!(service.review(flagged[0].id, MatchReviewStatus::ConfirmedDuplicate, "revisor", None).await.is_err());
+        assert!(service.matches(Some(MatchReviewStatus::Pending)).await.is_empty());
+        assert_eq!(service.matches(None).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_voter_without_consent_is_not_screened_or_enrolled() {
+        // Finalidade apoiada só em consentimento explícito
+        let ledger = Arc::new(ConsentLedger::with_declarations(vec![PurposeDeclaration {
+            category: BiometricCategory::BiometricTemplate,
+            purpose: ProcessingPurpose::DuplicateDetection,
+            legal_basis: LegalBasis::ExplicitConsent,
+            retention_days: 365,
+            description: "Detecção de cadastros duplicados".to_string(),
+        }], b"test-secret"));
+        let index = Arc::new(VectorIndexDeduplicator::new());
+        let service = BiometricDedupService::new(index.clone(), 0.4).with_consent_ledger(ledger.clone());
+
+        ledger.grant_consent("T1", BiometricCategory::BiometricTemplate, ProcessingPurpose::DuplicateDetection, "i-1", "tse").await.unwrap();
+        assert!(screened(service.screen("T1", &template(1, (0, 0)), None).await.unwrap()).is_empty());
+
+        // Mesmo dedo de outro eleitor, sem consentimento: nem pesquisa, nem inscrição
+        let screening = service.screen("T2", &template(1, (5, 5)), None).await.unwrap();
+        assert!(matches!(screening, Screening::NotAuthorized(_)));
+        assert!(service.matches(None).await.is_empty());
+        assert_eq!(index.len().await, 1);
+
+        // Consentimento revogado: o eleitor sai da base de busca
+        ledger.revoke_consent("T1", BiometricCategory::BiometricTemplate, ProcessingPurpose::DuplicateDetection).await.unwrap();
+        assert!(matches!(service.screen("T1", &template(1, (0, 0)), None).await.unwrap(), Screening::NotAuthorized(_)));
+        assert!(index.is_empty().await);
+
+        let denied = ledger.query_usages(&ConsentQuery::default()).await;
+        assert_eq!(denied.iter().filter(|u| !u.allowed).count(), 2);
+    }
+
+    #[test]
+    fn test_decode_template_round_trip() {
+        let original = template(3, (0, 0));
+        let encoded = general_purpose::STANDARD.encode(original.to_iso_19794_2());
+        assert_eq!(decode_template(&encoded).unwrap(), original);
+        assert!(decode_template("não é base64").is_err());
+    }
+}
@@ -0,0 +1,519 @@
+//! Qualidade dos dados importados do TSE
+//!
+//! Cada página do cadastro eleitoral e cada lista de candidatos passa pelos
+//! validadores antes de ser aplicada: formato dos campos (título de eleitor
+//! e CPF com dígitos verificadores, UF, zona e seção numéricas, número do
+//! candidato com os dígitos do cargo), duplicidades no lote e na base
+//! (título repetido, CPF em outra inscrição, número já registrado no cargo)
+//! e referências (local de votação conhecido, da mesma zona e que atende a
+//! seção do eleitor). O registro reprovado não é descartado nem importado:
+//! vai para a fila de quarentena, onde pode ser corrigido, aprovado ou
+//! rejeitado. Aprovar registro que ainda falha exige justificativa.
+//!
+//! Versão mais nova do mesmo registro aprovada na sincronização substitui a
+//! que está em quarentena. O template biométrico não é guardado na fila; o
+//! eleitor aprovado fica sem inscrição biométrica até o TSE reenviá-lo.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::models::{Candidate, CandidatePosition, CreateCandidateRequest};
+use crate::utils::is_valid_cpf;
+use super::election_sync::{CandidateData, PollingPlace, VoterRollEntry};
+
+/// Idade mínima para o alistamento
+const MIN_REGISTRATION_AGE: i32 = 15;
+const MAX_AGE: i32 = 130;
+
+/// UFs e `ZZ` (eleitores no exterior)
+const STATES: [&str; 28] = [
+    "AC", "AL", "AP", "AM", "BA", "CE", "DF", "ES", "GO", "MA", "MT", "MS", "MG", "PA",
+    "PB", "PR", "PE", "PI", "RJ", "RN", "RS", "RO", "RR", "SC", "SP", "SE", "TO", "ZZ",
+];
+
+/// Tipo do registro importado
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    Voter,
+    PollingPlace,
+    Candidate,
+}
+
+/// Regra de qualidade violada
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityRule {
+    Required,
+    Format,
+    Range,
+    Duplicate,
+    /// Referência a registro inexistente ou incompatível
+    Reference,
+    /// Recusado pelo cadastro de destino
+    Conflict,
+}
+
+/// Falha de um registro em uma regra
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QualityIssue {
+    pub field: String,
+    pub rule: QualityRule,
+    pub message: String,
+}
+
+impl QualityIssue {
+    pub fn new(field: &str, rule: QualityRule, message: impl Into<String>) -> Self {
+        Self { field: field.to_string(), rule, message: message.into() }
+    }
+}
+
+/// Local de votação já conhecido, para as checagens de referência
+#[derive(Debug, Clone)]
+pub struct PlaceRef {
+    pub zone: String,
+    pub sections: Vec<String>,
+}
+
+impl From<&PollingPlace> for PlaceRef {
+    fn from(place: &PollingPlace) -> Self {
+        Self { zone: place.zone.clone(), sections: place.sections.clone() }
+    }
+}
+
+/// Base já importada, consultada pelos validadores
+#[derive(Debug, Default)]
+pub struct RollContext {
+    pub polling_places: HashMap<String, PlaceRef>,
+    /// Título da inscrição de cada CPF
+    pub cpf_owners: HashMap<String, String>,
+}
+
+/// Título de eleitor: sequencial, UF (01 a 28) e dois dígitos verificadores
+pub fn is_valid_voter_title(title: &str) -> bool {
+    if title.len() != 12 || !title.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let digits: Vec<u32> = title.bytes().map(|b| (b - b'0') as u32).collect();
+    let state = digits[8] * 10 + digits[9];
+    if !(1..=28).contains(&state) {
+        return false;
+    }
+    // SP (01) e MG (02) usam 1 quando o resto é zero
+    let check = |sum: u32| match sum % 11 {
+        10 => 0,
+        0 if state <= 2 => 1,
+        rest => rest,
+    };
+    let first = check((0..8).map(|i| digits[i] * (i as u32 + 2)).sum());
+    let second = check(digits[8] * 7 + digits[9] * 8 + first * 9);
+    digits[10] == first && digits[11] == second
+}
+
+fn is_numeric_code(value: &str, max_len: usize) -> bool {
+    !value.is_empty() && value.len() <= max_len && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+pub fn cpf_digits(cpf: &str) -> String {
+    cpf.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Valida uma página de eleitores; uma lista de falhas por registro
+pub fn check_voters(entries: &[VoterRollEntry], context: &RollContext) -> Vec<Vec<QualityIssue>> {
+    let mut titles: HashMap<&str, usize> = HashMap::new();
+    let mut cpfs: HashMap<String, HashSet<&str>> = HashMap::new();
+    for entry in entries {
+        *titles.entry(entry.voter_id.as_str()).or_insert(0) += 1;
+        if !entry.removed {
+            cpfs.entry(cpf_digits(&entry.cpf)).or_default().insert(entry.voter_id.as_str());
+        }
+    }
+
+    let today = Utc::now().date_naive();
+    entries.iter().map(|entry| {
+        let mut issues = Vec::new();
+        if !is_valid_voter_title(&entry.voter_id) {
+            issues.push(QualityIssue::new("voter_id", QualityRule::Format, "Título de eleitor inválido"));
+        }
+        if titles[entry.voter_id.as_str()] > 1 {
+            issues.push(QualityIssue::new("voter_id", QualityRule::Duplicate, "Título repetido no lote"));
+        }
+        // Cancelamento só precisa identificar a inscrição
+        if entry.removed {
+            return issues;
+        }
+
+        let cpf = cpf_digits(&entry.cpf);
+        if !is_valid_cpf(&entry.cpf) {
+            issues.push(QualityIssue::new("cpf", QualityRule::Format, "CPF inválido"));
+        } else if cpfs[&cpf].len() > 1 {
+            issues.push(QualityIssue::new("cpf", QualityRule::Duplicate, "CPF em mais de uma inscrição do lote"));
+        } else if let Some(owner) = context.cpf_owners.get(&cpf).filter(|owner| **owner != entry.voter_id) {
+            issues.push(QualityIssue::new("cpf", QualityRule::Duplicate, format!("CPF já inscrito no título {}", owner)));
+        }
+        if entry.name.trim().is_empty() {
+            issues.push(QualityIssue::new("name", QualityRule::Required, "Nome obrigatório"));
+        }
+        let age = today.year() - entry.birth_date.year()
+            - i32::from(today.ordinal() < entry.birth_date.ordinal());
+        if entry.birth_date > today || !(MIN_REGISTRATION_AGE..=MAX_AGE).contains(&age) {
+            issues.push(QualityIssue::new(
+                "birth_date",
+                QualityRule::Range,
+                format!("Idade fora do intervalo de {} a {} anos", MIN_REGISTRATION_AGE, MAX_AGE),
+            ));
+        }
+        if !is_numeric_code(&entry.zone, 4) {
+            issues.push(QualityIssue::new("zone", QualityRule::Format, "Zona deve ter até 4 dígitos"));
+        }
+        if !is_numeric_code(&entry.section, 4) {
+            issues.push(QualityIssue::new("section", QualityRule::Format, "Seção deve ter até 4 dígitos"));
+        }
+
+        if let Some(place_id) = &entry.polling_place_id {
+            match context.polling_places.get(place_id) {
+                None => issues.push(QualityIssue::new(
+                    "polling_place_id",
+                    QualityRule::Reference,
+                    format!("Local de votação {} desconhecido", place_id),
+                )),
+                Some(place) if place.zone != entry.zone || !place.sections.contains(&entry.section) => {
+                    issues.push(QualityIssue::new(
+                        "polling_place_id",
+                        QualityRule::Reference,
+                        format!("Local de votação {} não atende a zona {} seção {}", place_id, entry.zone, entry.section),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        issues
+    }).collect()
+}
+
+/// Valida uma página de locais de votação
+pub fn check_polling_places(places: &[PollingPlace]) -> Vec<Vec<QualityIssue>> {
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    for place in places {
+        *ids.entry(place.polling_place_id.as_str()).or_insert(0) += 1;
+    }
+
+    places.iter().map(|place| {
+        let mut issues = Vec::new();
+        if place.polling_place_id.trim().is_empty() {
+            issues.push(QualityIssue::new("polling_place_id", QualityRule::Required, "Identificador obrigatório"));
+        }
+        if ids[place.polling_place_id.as_str()] > 1 {
+            issues.push(QualityIssue::new("polling_place_id", QualityRule::Duplicate, "Local repetido no lote"));
+        }
+        if place.removed {
+            return issues;
+        }
+
+        for (field, value) in [("name", &place.name), ("address", &place.address), ("city", &place.city)] {
+            if value.trim().is_empty() {
+                issues.push(QualityIssue::new(field, QualityRule::Required, "Campo obrigatório"));
+            }
+        }
+        if !STATES.contains(&place.state.as_str()) {
+            issues.push(QualityIssue::new("state", QualityRule::Format, format!("UF desconhecida: {}", place.state)));
+        }
+        if !is_numeric_code(&place.zone, 4) {
+            issues.push(QualityIssue::new("zone", QualityRule::Format, "Zona deve ter até 4 dígitos"));
+        }
+        if place.sections.is_empty() {
+            issues.push(QualityIssue::new("sections", QualityRule::Required, "Local sem seções"));
+        } else if !place.sections.iter().all(|s| is_numeric_code(s, 4)) {
+            issues.push(QualityIssue::new("sections", QualityRule::Format, "Seção deve ter até 4 dígitos"));
+        }
+        issues
+    }).collect()
+}
+
+/// Converte o candidato do TSE no cadastro, com as falhas se não for possível
+pub fn candidate_request(candidate: &CandidateData) -> std::result::Result<CreateCandidateRequest, Vec<QualityIssue>> {
+    let mut issues = Vec::new();
+    let position: Option<CandidatePosition> =
+        serde_json::from_value(serde_json::Value::String(candidate.position.to_lowercase())).ok();
+    if position.is_none() {
+        issues.push(QualityIssue::new("position", QualityRule::Format, format!("Cargo desconhecido: {}", candidate.position)));
+    }
+    let number = candidate.number.trim().parse::<u32>().ok();
+    match (number, position) {
+        (None, _) => issues.push(QualityIssue::new("number", QualityRule::Format, "Número do candidato inválido")),
+        (Some(number), Some(position)) if !position.accepts_number(number) => issues.push(QualityIssue::new(
+            "number",
+            QualityRule::Format,
+            format!("Número deve ter {} dígitos", position.number_digits()),
+        )),
+        _ => {}
+    }
+    for (field, value) in [("name", &candidate.name), ("party", &candidate.party)] {
+        if value.trim().is_empty() {
+            issues.push(QualityIssue::new(field, QualityRule::Required, "Campo obrigatório"));
+        }
+    }
+    if let Some(position) = position {
+        match (&candidate.region, position.is_national()) {
+            (None, false) => issues.push(QualityIssue::new("region", QualityRule::Required, "Região obrigatória para o cargo")),
+            (Some(_), true) => issues.push(QualityIssue::new("region", QualityRule::Reference, "Cargo nacional não tem região")),
+            _ => {}
+        }
+    }
+
+    match (number, position) {
+        (Some(number), Some(position)) if issues.is_empty() => Ok(CreateCandidateRequest {
+            name: candidate.name.trim().to_string(),
+            party: candidate.party.trim().to_string(),
+            number,
+            position,
+            coalition: None,
+            region: candidate.region.clone(),
+            photo_base64: None,
+        }),
+        _ => Err(issues),
+    }
+}
+
+/// Valida a lista de candidatos contra ela mesma e os já cadastrados
+pub fn check_candidates(
+    candidates: &[CandidateData],
+    registered: &[Candidate],
+) -> Vec<std::result::Result<CreateCandidateRequest, Vec<QualityIssue>>> {
+    let converted: Vec<_> = candidates.iter().map(candidate_request).collect();
+    let mut seen: HashMap<(CandidatePosition, Option<String>, u32), usize> = HashMap::new();
+    for request in converted.iter().flatten() {
+        *seen.entry((request.position, request.region.clone(), request.number)).or_insert(0) += 1;
+    }
+
+    converted.into_iter().map(|result| {
+        let request = result?;
+        let key = (request.position, request.region.clone(), request.number);
+        if seen[&key] > 1 {
+            return Err(vec![QualityIssue::new("number", QualityRule::Duplicate, "Número repetido no cargo no lote")]);
+        }
+        if registered.iter().any(|c| c.position == key.0 && c.region == key.1 && c.number == key.2) {
+            return Err(vec![QualityIssue::new("number", QualityRule::Duplicate, "Número já registrado no cargo")]);
+        }
+        Ok(request)
+    }).collect()
+}
+
+/// Chave do registro, para substituir versões em quarentena
+pub fn record_key(kind: ImportKind, record: &serde_json::Value) -> String {
+    let field = |name: &str| record.get(name).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    match kind {
+        ImportKind::Voter => field("voter_id"),
+        ImportKind::PollingPlace => field("polling_place_id"),
+        ImportKind::Candidate => format!("{}:{}:{}", field("position"), field("region"), field("number")),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarantineStatus {
+    Pending,
+    Approved,
+    Rejected,
+    /// Versão mais nova importada sem falhas
+    Superseded,
+}
+
+/// Registro retido para revisão
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineRecord {
+    pub id: Uuid,
+    pub kind: ImportKind,
+    pub key: String,
+    /// Registro como recebido ou corrigido
+    pub record: serde_json::Value,
+    pub issues: Vec<QualityIssue>,
+    /// Execução da sincronização do cadastro que o reteve
+    pub run_id: Option<Uuid>,
+    /// Eleição de destino, para candidatos
+    pub election_id: Option<Uuid>,
+    pub quarantined_at: DateTime<Utc>,
+    pub status: QuarantineStatus,
+    pub corrections: u32,
+    pub corrected_by: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// Fila de quarentena dos registros reprovados
+#[derive(Debug, Default)]
+pub struct QuarantineQueue {
+    records: RwLock<Vec<QuarantineRecord>>,
+}
+
+impl QuarantineQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn quarantine<T: Serialize>(
+        &self,
+        kind: ImportKind,
+        record: &T,
+        issues: Vec<QualityIssue>,
+        run_id: Option<Uuid>,
+        election_id: Option<Uuid>,
+    ) -> Result<QuarantineRecord> {
+        let record = serde_json::to_value(record)?;
+        let entry = QuarantineRecord {
+            id: Uuid::new_v4(),
+            kind,
+            key: record_key(kind, &record),
+            record,
+            issues,
+            run_id,
+            election_id,
+            quarantined_at: Utc::now(),
+            status: QuarantineStatus::Pending,
+            corrections: 0,
+            corrected_by: None,
+            reviewed_by: None,
+            reviewed_at: None,
+            notes: None,
+        };
+        log::warn!(
+            "Quarantined {:?} record {}: {}",
+            kind,
+            entry.key,
+            entry.issues.iter().map(|i| i.message.as_str()).collect::<Vec<_>>().join("; ")
+        );
+        self.records.write().await.push(entry.clone());
+        Ok(entry)
+    }
+
+    /// Encerra as versões pendentes de um registro importado sem falhas
+    pub async fn supersede(&self, kind: ImportKind, key: &str) {
+        for entry in self.records.write().await.iter_mut() {
+            if entry.kind == kind && entry.key == key && entry.status == QuarantineStatus::Pending {
+                entry.status = QuarantineStatus::Superseded;
+                entry.reviewed_at = Some(Utc::now());
+            }
+        }
+    }
+
+    /// Registros retidos, do mais recente para o mais antigo
+    pub async fn list(&self, status: Option<QuarantineStatus>, kind: Option<ImportKind>) -> Vec<QuarantineRecord> {
+        self.records.read().await.iter().rev()
+            .filter(|r| status.map_or(true, |status| r.status == status))
+            .filter(|r| kind.map_or(true, |kind| r.kind == kind))
+            .cloned()
+            .collect()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Option<QuarantineRecord> {
+        self.records.read().await.iter().find(|r| r.id == id).cloned()
+    }
+
+    /// Substitui o registro pendente pela versão corrigida e suas falhas
+    pub async fn correct(&self, id: Uuid, record: serde_json::Value, issues: Vec<QualityIssue>, reviewer: &str) -> Result<QuarantineRecord> {
+        let mut records = self.records.write().await;
+        let entry = pending(&mut records, id)?;
+        entry.key = record_key(entry.kind, &record);
+        entry.record = record;
+        entry.issues = issues;
+        entry.corrections += 1;
+        entry.corrected_by = Some(reviewer.to_string());
+        Ok(entry.clone())
+    }
+
+    /// Decide o registro pendente; aprovar com falhas exige justificativa
+    pub async fn decide(&self, id: Uuid, status: QuarantineStatus, reviewer: &str, notes: Option<String>) -> Result<QuarantineRecord> {
+        if !matches!(status, QuarantineStatus::Approved | QuarantineStatus::Rejected) {
+            return Err(anyhow!("Quarantine decision must approve or reject the record"));
+        }
+        let mut records = self.records.write().await;
+        let entry = pending(&mut records, id)?;
+        let justified = notes.as_deref().is_some_and(|n| !n.trim().is_empty());
+        if status == QuarantineStatus::Approved && !entry.issues.is_empty() && !justified {
+            return Err(anyhow!("Record {} still fails {} checks; approving requires notes", id, entry.issues.len()));
+        }
+        entry.status = status;
+        entry.reviewed_by = Some(reviewer.to_string());
+        entry.reviewed_at = Some(Utc::now());
+        entry.notes = notes;
+        log::info!("Quarantined {:?} record {} {:?} by {}", entry.kind, entry.key, status, reviewer);
+        Ok(entry.clone())
+    }
+}
+
+fn pending(records: &mut [QuarantineRecord], id: Uuid) -> Result<&mut QuarantineRecord> {
+    let entry = records.iter_mut().find(|r| r.id == id)
+        .ok_or_else(|| anyhow!("Quarantined record {} not found", id))?;
+    if entry.status != QuarantineStatus::Pending {
+        return Err(anyhow!("Quarantined record {} already closed ({:?})", id, entry.status));
+    }
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn voter(voter_id: &str, cpf: &str, place: Option<&str>) -> VoterRollEntry {
+        VoterRollEntry {
+            voter_id: voter_id.to_string(),
+            cpf: cpf.to_string(),
+            name: "Maria da Silva".to_string(),
+            birth_date: NaiveDate::from_ymd_opt(1980, 5, 17).unwrap(),
+            zone: "1".to_string(),
+            section: "12".to_string(),
+            polling_place_id: place.map(str::to_string),
+            active: true,
+            removed: false,
+            updated_at: Utc::now(),
+            biometric_template: None,
+        }
+    }
+
+    #[test]
+    fn test_voter_checks_flag_format_duplicates_and_references() {
+        assert!(is_valid_voter_title("102345670183"));
+        assert!(!is_valid_voter_title("102345670184"));
+
+        let mut context = RollContext::default();
+        context.polling_places.insert("P1".to_string(), PlaceRef { zone: "1".to_string(), sections: vec!["12".to_string()] });
+        context.cpf_owners.insert("52998224725".to_string(), "004382912194".to_string());
+
+        let entries = vec![
+            voter("102345670183", "111.444.777-35", Some("P1")),
+            voter("123456780698", "529.982.247-25", None),
+            voter("876543210124", "11144477736", Some("P2")),
+        ];
+        let issues = check_voters(&entries, &context);
+        assert!(issues[0].is_empty());
+        assert_eq!(issues[1][0].rule, QualityRule::Duplicate);
+        assert_eq!(issues[1][0].field, "cpf");
+        let rules: Vec<_> = issues[2].iter().map(|i| (i.field.as_str(), i.rule)).collect();
+        assert_eq!(rules, vec![("cpf", QualityRule::Format), ("polling_place_id", QualityRule::Reference)]);
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_correct_and_approve() {
+        let queue = QuarantineQueue::new();
+        let entry = voter("102345670180", "11144477735", None);
+        let issues = check_voters(std::slice::from_ref(&entry), &RollContext::default()).remove(0);
+        let held = queue.quarantine(ImportKind::Voter, &entry, issues, None, None).await.unwrap();
+        assert_eq!(held.key, "102345670180");
+        assert!(queue.decide(held.id, QuarantineStatus::Approved, "auditor", None).await.is_err());
+
+        let fixed = voter("102345670183", "11144477735", None);
+        let issues = check_voters(std::slice::from_ref(&fixed), &RollContext::default()).remove(0);
+        let corrected = queue.correct(held.id, serde_json::to_value(&fixed).unwrap(), issues, "auditor").await.unwrap();
+        assert!(corrected.issues.is_empty());
+        let approved = queue.decide(held.id, QuarantineStatus::Approved, "auditor", None).await.unwrap();
+        assert_eq!(approved.status, QuarantineStatus::Approved);
+        assert!(queue.decide(held.id, QuarantineStatus::Rejected, "auditor", None).await.is_err());
+        assert!(queue.list(Some(QuarantineStatus::Pending), None).await.is_empty());
+    }
+}
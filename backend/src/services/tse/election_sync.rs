@@ -4,17 +4,50 @@
 
 use crate::config::Config;
 use anyhow::{Result, anyhow};
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgPool, Row};
 use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use fortis_domain::provisioning::{SnapshotVoter, VoterSnapshot};
+use crate::services::candidate::CandidateService;
+use super::biometric_dedup::{self, BiometricDedupService, Screening};
+use super::data_quality::{
+    self, ImportKind, PlaceRef, QualityIssue, QualityRule, QuarantineQueue, QuarantineRecord, QuarantineStatus, RollContext,
+};
+
+/// Tentativas por página quando o TSE limita a taxa de requisições
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+/// Espera máxima entre tentativas, em segundos
+const MAX_RETRY_WAIT_SECONDS: u64 = 60;
 
 /// Serviço de sincronização de eleições
+#[derive(Clone)]
 pub struct ElectionSyncService {
     client: Client,
     tse_base_url: String,
     api_key: String,
     sync_interval: u64, // em segundos
+    voter_roll_interval: u64,
+    voter_roll_page_size: u32,
+    db: Option<PgPool>,
+    /// Cadastro local usado quando não há banco configurado
+    voter_roll: Arc<RwLock<VoterRollStore>>,
+    runs: Arc<RwLock<Vec<VoterRollSyncRun>>>,
+    /// Busca biométrica 1:N dos eleitores recebidos
+    deduplication: Option<Arc<BiometricDedupService>>,
+    /// Registros reprovados na checagem de qualidade
+    quarantine: Arc<QuarantineQueue>,
+    /// Impede duas execuções simultâneas
+    run_lock: Arc<Mutex<()>>,
+    auto_sync: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 /// Dados de eleição para sincronização
@@ -100,6 +133,9 @@ pub struct CandidateData {
     pub party: String,
     pub number: String,
     pub position: String,
+    /// UF ou código do município; ausente para cargos nacionais
+    #[serde(default)]
+    pub region: Option<String>,
     pub photo_url: Option<String>,
     pub biography: Option<String>,
     pub proposals: Vec<String>,
@@ -196,6 +232,184 @@ pub struct SyncError {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Eleitor no cadastro do TSE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoterRollEntry {
+    pub voter_id: String, // Título de eleitor
+    pub cpf: String,
+    pub name: String,
+    pub birth_date: NaiveDate,
+    pub zone: String,
+    pub section: String,
+    pub polling_place_id: Option<String>,
+    pub active: bool,
+    /// Inscrição cancelada ou transferida para fora da base
+    #[serde(default)]
+    pub removed: bool,
+    pub updated_at: DateTime<Utc>,
+    /// Template da digital (ISO/IEC 19794-2 em base64), só para a deduplicação
+    #[serde(default, skip_serializing)]
+    pub biometric_template: Option<String>,
+}
+
+/// Local de votação e seções atendidas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollingPlace {
+    pub polling_place_id: String,
+    pub name: String,
+    pub address: String,
+    pub city: String,
+    pub state: String,
+    pub zone: String,
+    pub sections: Vec<String>,
+    #[serde(default)]
+    pub removed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Página da API do TSE
+#[derive(Debug, Clone, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+/// Cadastro em memória, usado sem banco de dados
+#[derive(Debug, Default)]
+pub struct VoterRollStore {
+    pub voters: HashMap<String, VoterRollEntry>,
+    pub polling_places: HashMap<String, PollingPlace>,
+}
+
+/// Situação de uma execução da sincronização do cadastro
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl SyncRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncRunStatus::Running => "running",
+            SyncRunStatus::Succeeded => "succeeded",
+            SyncRunStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Execução da sincronização do cadastro eleitoral
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoterRollSyncRun {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Alterações a partir desta data foram solicitadas ao TSE
+    pub updated_since: Option<DateTime<Utc>>,
+    pub status: SyncRunStatus,
+    pub pages_fetched: u32,
+    pub voters_upserted: u64,
+    pub voters_deactivated: u64,
+    pub polling_places_upserted: u64,
+    pub polling_places_removed: u64,
+    pub rate_limit_retries: u32,
+    /// Suspeitas de inscrição duplicada abertas na execução
+    #[serde(default)]
+    pub suspected_duplicates: u64,
+    /// Templates não pesquisados por falta de base legal no livro de consentimento
+    #[serde(default)]
+    pub biometrics_without_consent: u64,
+    /// Registros retidos na quarentena pela checagem de qualidade
+    #[serde(default)]
+    pub records_quarantined: u64,
+    pub error: Option<String>,
+}
+
+/// Contagem de alterações aplicadas em uma página
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DeltaCounts {
+    pub upserted: u64,
+    pub removed: u64,
+}
+
+impl VoterRollStore {
+    /// Aplica alterações de eleitores, ignorando versões mais antigas que a local
+    pub fn apply_voters(&mut self, entries: &[VoterRollEntry]) -> DeltaCounts {
+        let mut counts = DeltaCounts::default();
+        for entry in entries {
+            if self.voters.get(&entry.voter_id).is_some_and(|local| local.updated_at >= entry.updated_at) {
+                continue;
+            }
+            if entry.removed {
+                counts.removed += 1;
+            } else {
+                counts.upserted += 1;
+            }
+            let mut entry = entry.clone();
+            entry.active = entry.active && !entry.removed;
+            // O template fica só na base da deduplicação
+            entry.biometric_template = None;
+            self.voters.insert(entry.voter_id.clone(), entry);
+        }
+        counts
+    }
+
+    pub fn apply_polling_places(&mut self, places: &[PollingPlace]) -> DeltaCounts {
+        let mut counts = DeltaCounts::default();
+        for place in places {
+            if self.polling_places.get(&place.polling_place_id).is_some_and(|local| local.updated_at >= place.updated_at) {
+                continue;
+            }
+            if place.removed {
+                self.polling_places.remove(&place.polling_place_id);
+                counts.removed += 1;
+            } else {
+                self.polling_places.insert(place.polling_place_id.clone(), place.clone());
+                counts.upserted += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Busca 1:N dos templates da página; conta na execução as suspeitas abertas
+///
+/// Template ilegível não interrompe a sincronização: o eleitor segue sem
+/// inscrição biométrica até o TSE reenviá-lo. O mesmo vale para o eleitor
+/// sem base legal no livro de consentimento, contado à parte.
+async fn screen_biometrics(deduplication: &BiometricDedupService, entries: &[VoterRollEntry], run: &mut VoterRollSyncRun) -> Result<()> {
+    for entry in entries {
+        if entry.removed {
+            deduplication.withdraw(&entry.voter_id).await?;
+            continue;
+        }
+        let Some(encoded) = &entry.biometric_template else {
+            continue;
+        };
+        match biometric_dedup::decode_template(encoded) {
+            Ok(template) => match deduplication.screen(&entry.voter_id, &template, Some(run.id)).await? {
+                Screening::Screened(flagged) => run.suspected_duplicates += flagged.len() as u64,
+                Screening::NotAuthorized(reason) => {
+                    log::warn!("Skipping biometric screening of voter {}: {}", entry.voter_id, reason);
+                    run.biometrics_without_consent += 1;
+                }
+            },
+            Err(e) => log::warn!("Skipping biometric screening of voter {}: {}", entry.voter_id, e),
+        }
+    }
+    Ok(())
+}
+
+/// Espera pedida pelo TSE no cabeçalho `Retry-After`, ou recuo exponencial
+fn retry_wait_seconds(retry_after: Option<&str>, attempt: u32) -> u64 {
+    retry_after
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| 1u64 << attempt.min(6))
+        .min(MAX_RETRY_WAIT_SECONDS)
+}
+
 impl ElectionSyncService {
     /// Cria nova instância do serviço
     pub fn new(config: &Config) -> Self {
@@ -204,9 +418,30 @@ impl ElectionSyncService {
             tse_base_url: config.tse.base_url.clone(),
             api_key: config.tse.api_key.clone(),
             sync_interval: config.tse.sync_interval,
+            voter_roll_interval: config.tse.voter_roll_sync_interval,
+            voter_roll_page_size: config.tse.voter_roll_page_size,
+            db: None,
+            voter_roll: Arc::new(RwLock::new(VoterRollStore::default())),
+            runs: Arc::new(RwLock::new(Vec::new())),
+            deduplication: None,
+            quarantine: Arc::new(QuarantineQueue::new()),
+            run_lock: Arc::new(Mutex::new(())),
+            auto_sync: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Grava o cadastro em `tse.voters`/`tse.polling_places` e as execuções na trilha de auditoria
+    pub fn with_database(mut self, pool: PgPool) -> Self {
+        self.db = Some(pool);
+        self
+    }
+
+    /// Confronta 1:N a biometria de cada eleitor recebido antes de inscrevê-la
+    pub fn with_deduplication(mut self, deduplication: Arc<BiometricDedupService>) -> Self {
+        self.deduplication = Some(deduplication);
+        self
+    }
+
     /// Sincroniza todas as eleições ativas
     pub async fn sync_all_elections(&self) -> Result<SyncResult> {
         let mut elections_synced = 0;
@@ -366,18 +601,628 @@ impl ElectionSyncService {
         Ok(stats)
     }
 
-    /// Inicia sincronização automática
+    /// Inicia sincronização automática do cadastro eleitoral
     pub async fn start_auto_sync(&self) -> Result<()> {
-        // TODO: Implementar sincronização automática em background
-        // Usar tokio::spawn para executar em paralelo
+        let mut auto_sync = self.auto_sync.lock().await;
+        if auto_sync.is_some() {
+            return Err(anyhow!("Sincronização automática já iniciada"));
+        }
+
+        let service = self.clone();
+        *auto_sync = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(
+                std::time::Duration::from_secs(service.voter_roll_interval),
+            );
+            loop {
+                interval.tick().await;
+                if let Err(e) = service.sync_voter_roll().await {
+                    log::error!("Voter roll sync failed: {}", e);
+                }
+            }
+        }));
         Ok(())
     }
 
     /// Para sincronização automática
     pub async fn stop_auto_sync(&self) -> Result<()> {
-        // TODO: Implementar parada da sincronização automática
+        if let Some(handle) = self.auto_sync.lock().await.take() {
+            handle.abort();
+        }
         Ok(())
     }
+
+    /// Sincroniza eleitores e locais de votação alterados desde a última execução bem-sucedida
+    pub async fn sync_voter_roll(&self) -> Result<VoterRollSyncRun> {
+        let _running = self.run_lock.try_lock()
+            .map_err(|_| anyhow!("Sincronização do cadastro já em andamento"))?;
+
+        let mut run = VoterRollSyncRun {
+            id: Uuid::new_v4(),
+            started_at: Utc::now(),
+            finished_at: None,
+            updated_since: self.last_successful_sync().await?,
+            status: SyncRunStatus::Running,
+            pages_fetched: 0,
+            voters_upserted: 0,
+            voters_deactivated: 0,
+            polling_places_upserted: 0,
+            polling_places_removed: 0,
+            rate_limit_retries: 0,
+            suspected_duplicates: 0,
+            biometrics_without_consent: 0,
+            records_quarantined: 0,
+            error: None,
+        };
+        log::info!("Voter roll sync {} started (updated since {:?})", run.id, run.updated_since);
+
+        let result = self.pull_voter_roll(&mut run).await;
+        run.finished_at = Some(Utc::now());
+        match &result {
+            Ok(()) => run.status = SyncRunStatus::Succeeded,
+            Err(e) => {
+                run.status = SyncRunStatus::Failed;
+                run.error = Some(e.to_string());
+            }
+        }
+        self.record_run(&run).await?;
+
+        log::info!(
+            "Voter roll sync {} {:?}: {} voters, {} deactivated, {} polling places, {} suspected duplicates, {} biometrics without consent, {} quarantined",
+            run.id, run.status, run.voters_upserted, run.voters_deactivated, run.polling_places_upserted,
+            run.suspected_duplicates, run.biometrics_without_consent, run.records_quarantined
+        );
+        result.map(|_| run)
+    }
+
+    /// Recorte do cadastro de uma seção para o provisionamento da urna
+    pub async fn voter_snapshot(&self, zone: &str, section: &str) -> Result<VoterSnapshot> {
+        let voters = match &self.db {
+            Some(pool) => {
+                let rows = sqlx::query(
+                    r#"
+                    SELECT voter_id, name, birth_date, is_active
+                    FROM tse.voters
+                    WHERE zone = $1 AND section = $2
+                    ORDER BY name
+                    "#
+                )
+                .bind(zone)
+                .bind(section)
+                .fetch_all(pool)
+                .await?;
+
+                rows.iter()
+                    .map(|row| SnapshotVoter {
+                        voter_id: row.get("voter_id"),
+                        name: row.get("name"),
+                        birth_date: row.get("birth_date"),
+                        active: row.get::<Option<bool>, _>("is_active").unwrap_or(false),
+                    })
+                    .collect()
+            }
+            None => {
+                let mut voters: Vec<SnapshotVoter> = self.voter_roll.read().await.voters.values()
+                    .filter(|voter| voter.zone == zone && voter.section == section)
+                    .map(|voter| SnapshotVoter {
+                        voter_id: voter.voter_id.clone(),
+                        name: voter.name.clone(),
+                        birth_date: voter.birth_date,
+                        active: voter.active,
+                    })
+                    .collect();
+                voters.sort_by(|a, b| a.name.cmp(&b.name));
+                voters
+            }
+        };
+
+        Ok(VoterSnapshot {
+            zone: zone.to_string(),
+            section: section.to_string(),
+            generated_at: Utc::now(),
+            voters,
+        })
+    }
+
+    /// Inscrição do eleitor no cadastro sincronizado do TSE
+    pub async fn voter_entry(&self, voter_id: &str) -> Result<Option<VoterRollEntry>> {
+        let Some(pool) = &self.db else {
+            return Ok(self.voter_roll.read().await.voters.get(voter_id).cloned());
+        };
+
+        let row = sqlx::query(
+            r#"
+            SELECT voter_id, cpf, name, birth_date, zone, section, polling_place_id, is_active, source_updated_at
+            FROM tse.voters
+            WHERE voter_id = $1
+            "#
+        )
+        .bind(voter_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|row| VoterRollEntry {
+            voter_id: row.get("voter_id"),
+            cpf: row.get("cpf"),
+            name: row.get("name"),
+            birth_date: row.get("birth_date"),
+            zone: row.get("zone"),
+            section: row.get("section"),
+            polling_place_id: row.get("polling_place_id"),
+            active: row.get::<Option<bool>, _>("is_active").unwrap_or(false),
+            removed: false,
+            updated_at: row.get::<Option<DateTime<Utc>>, _>("source_updated_at").unwrap_or_else(Utc::now),
+            biometric_template: None,
+        }))
+    }
+
+    /// Importa os candidatos ativos da eleição do TSE; os reprovados ficam em quarentena
+    pub async fn import_candidates(
+        &self,
+        tse_election_id: &str,
+        election_id: Uuid,
+        candidates: &CandidateService,
+    ) -> Result<CandidateImportReport> {
+        let received = self.sync_candidates(tse_election_id).await?;
+        let registered = candidates.list_candidates(election_id, None).await;
+        let active: Vec<CandidateData> = received.iter()
+            .filter(|c| matches!(c.status, CandidateStatus::Ativo))
+            .cloned()
+            .collect();
+        let mut report = CandidateImportReport {
+            received: received.len(),
+            skipped: received.len() - active.len(),
+            imported: 0,
+            quarantined: 0,
+        };
+
+        let checked = data_quality::check_candidates(&active, &registered);
+        for (candidate, result) in active.iter().zip(checked) {
+            let issues = match result {
+                Ok(request) => match candidates.create_candidate(election_id, request).await {
+                    Ok(_) => {
+                        let key = data_quality::record_key(ImportKind::Candidate, &serde_json::to_value(candidate)?);
+                        self.quarantine.supersede(ImportKind::Candidate, &key).await;
+                        report.imported += 1;
+                        continue;
+                    }
+                    Err(e) => vec![QualityIssue::new("candidate", QualityRule::Conflict, e.to_string())],
+                },
+                Err(issues) => issues,
+            };
+            self.quarantine.quarantine(ImportKind::Candidate, candidate, issues, None, Some(election_id)).await?;
+            report.quarantined += 1;
+        }
+
+        log::info!(
+            "Candidate import from TSE election {}: {} imported, {} quarantined, {} inactive",
+            tse_election_id, report.imported, report.quarantined, report.skipped
+        );
+        Ok(report)
+    }
+
+    /// Registros em quarentena, do mais recente para o mais antigo
+    pub async fn quarantined(&self, status: Option<QuarantineStatus>, kind: Option<ImportKind>) -> Vec<QuarantineRecord> {
+        self.quarantine.list(status, kind).await
+    }
+
+    /// Substitui o registro retido pela versão corrigida e refaz a checagem
+    pub async fn correct_quarantined(
+        &self,
+        id: Uuid,
+        record: serde_json::Value,
+        reviewer: &str,
+        candidates: &CandidateService,
+    ) -> Result<QuarantineRecord> {
+        let entry = self.quarantine.get(id).await
+            .ok_or_else(|| anyhow!("Quarantined record {} not found", id))?;
+        let issues = match entry.kind {
+            ImportKind::Voter => {
+                let voter: VoterRollEntry = serde_json::from_value(record.clone())
+                    .map_err(|e| anyhow!("Corrected record is not a voter entry: {}", e))?;
+                let context = self.roll_context(std::slice::from_ref(&voter)).await?;
+                data_quality::check_voters(std::slice::from_ref(&voter), &context).remove(0)
+            }
+            ImportKind::PollingPlace => {
+                let place: PollingPlace = serde_json::from_value(record.clone())
+                    .map_err(|e| anyhow!("Corrected record is not a polling place: {}", e))?;
+                data_quality::check_polling_places(std::slice::from_ref(&place)).remove(0)
+            }
+            ImportKind::Candidate => {
+                let candidate: CandidateData = serde_json::from_value(record.clone())
+                    .map_err(|e| anyhow!("Corrected record is not a candidate: {}", e))?;
+                let election_id = entry.election_id
+                    .ok_or_else(|| anyhow!("Quarantined candidate {} has no election", id))?;
+                let registered = candidates.list_candidates(election_id, None).await;
+                data_quality::check_candidates(std::slice::from_ref(&candidate), &registered)
+                    .remove(0)
+                    .err()
+                    .unwrap_or_default()
+            }
+        };
+        self.quarantine.correct(id, record, issues, reviewer).await
+    }
+
+    /// Importa o registro retido; com falhas pendentes, só com justificativa
+    pub async fn approve_quarantined(
+        &self,
+        id: Uuid,
+        reviewer: &str,
+        notes: Option<String>,
+        candidates: &CandidateService,
+    ) -> Result<QuarantineRecord> {
+        let entry = self.quarantine.get(id).await
+            .ok_or_else(|| anyhow!("Quarantined record {} not found", id))?;
+        if entry.status != QuarantineStatus::Pending {
+            return Err(anyhow!("Quarantined record {} already closed ({:?})", id, entry.status));
+        }
+        if !entry.issues.is_empty() && notes.as_deref().map_or(true, |n| n.trim().is_empty()) {
+            return Err(anyhow!("Record {} still fails {} checks; approving requires notes", id, entry.issues.len()));
+        }
+
+        match entry.kind {
+            ImportKind::Voter => {
+                let voter: VoterRollEntry = serde_json::from_value(entry.record.clone())?;
+                self.apply_voters(&[voter]).await?;
+            }
+            ImportKind::PollingPlace => {
+                let place: PollingPlace = serde_json::from_value(entry.record.clone())?;
+                self.apply_polling_places(&[place]).await?;
+            }
+            ImportKind::Candidate => {
+                let candidate: CandidateData = serde_json::from_value(entry.record.clone())?;
+                let election_id = entry.election_id
+                    .ok_or_else(|| anyhow!("Quarantined candidate {} has no election", id))?;
+                let request = data_quality::candidate_request(&candidate).map_err(|issues| anyhow!(
+                    "Candidate cannot be registered: {}",
+                    issues.iter().map(|i| i.message.as_str()).collect::<Vec<_>>().join("; ")
+                ))?;
+                candidates.create_candidate(election_id, request).await?;
+            }
+        }
+        self.quarantine.decide(id, QuarantineStatus::Approved, reviewer, notes).await
+    }
+
+    /// Descarta o registro retido
+    pub async fn reject_quarantined(&self, id: Uuid, reviewer: &str, notes: Option<String>) -> Result<QuarantineRecord> {
+        self.quarantine.decide(id, QuarantineStatus::Rejected, reviewer, notes).await
+    }
+
+    /// Execuções registradas, da mais recente para a mais antiga
+    pub async fn voter_roll_runs(&self) -> Vec<VoterRollSyncRun> {
+        let mut runs = self.runs.read().await.clone();
+        runs.reverse();
+        runs
+    }
+
+    async fn pull_voter_roll(&self, run: &mut VoterRollSyncRun) -> Result<()> {
+        // Locais primeiro, para que as atribuições dos eleitores apontem para locais conhecidos
+        let mut cursor = None;
+        loop {
+            let page: Page<PollingPlace> = self.fetch_page("polling-places", run, cursor.as_deref()).await?;
+            let places = self.screen_polling_places(page.items, run).await?;
+            let counts = self.apply_polling_places(&places).await?;
+            run.polling_places_upserted += counts.upserted;
+            run.polling_places_removed += counts.removed;
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut cursor = None;
+        loop {
+            let page: Page<VoterRollEntry> = self.fetch_page("voter-roll", run, cursor.as_deref()).await?;
+            let voters = self.screen_voters(page.items, run).await?;
+            let counts = self.apply_voters(&voters).await?;
+            run.voters_upserted += counts.upserted;
+            run.voters_deactivated += counts.removed;
+            if let Some(deduplication) = &self.deduplication {
+                screen_biometrics(deduplication, &voters, run).await?;
+            }
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Separa os locais aprovados na checagem de qualidade; os demais vão para a quarentena
+    async fn screen_polling_places(&self, places: Vec<PollingPlace>, run: &mut VoterRollSyncRun) -> Result<Vec<PollingPlace>> {
+        let checked = data_quality::check_polling_places(&places);
+        let mut clean = Vec::with_capacity(places.len());
+        for (place, issues) in places.into_iter().zip(checked) {
+            if issues.is_empty() {
+                self.quarantine.supersede(ImportKind::PollingPlace, &place.polling_place_id).await;
+                clean.push(place);
+            } else {
+                self.quarantine.quarantine(ImportKind::PollingPlace, &place, issues, Some(run.id), None).await?;
+                run.records_quarantined += 1;
+            }
+        }
+        Ok(clean)
+    }
+
+    /// Separa os eleitores aprovados na checagem de qualidade; os demais vão para a quarentena
+    async fn screen_voters(&self, entries: Vec<VoterRollEntry>, run: &mut VoterRollSyncRun) -> Result<Vec<VoterRollEntry>> {
+        let context = self.roll_context(&entries).await?;
+        let checked = data_quality::check_voters(&entries, &context);
+        let mut clean = Vec::with_capacity(entries.len());
+        for (entry, issues) in entries.into_iter().zip(checked) {
+            if issues.is_empty() {
+                self.quarantine.supersede(ImportKind::Voter, &entry.voter_id).await;
+                clean.push(entry);
+            } else {
+                self.quarantine.quarantine(ImportKind::Voter, &entry, issues, Some(run.id), None).await?;
+                run.records_quarantined += 1;
+            }
+        }
+        Ok(clean)
+    }
+
+    /// Locais de votação e CPFs já inscritos que os eleitores referenciam
+    async fn roll_context(&self, entries: &[VoterRollEntry]) -> Result<RollContext> {
+        let place_ids: Vec<String> = entries.iter().filter_map(|e| e.polling_place_id.clone()).collect();
+        let cpfs: Vec<String> = entries.iter().map(|e| data_quality::cpf_digits(&e.cpf)).collect();
+        let mut context = RollContext::default();
+
+        let Some(pool) = &self.db else {
+            let store = self.voter_roll.read().await;
+            for id in place_ids {
+                if let Some(place) = store.polling_places.get(&id) {
+                    context.polling_places.insert(id, PlaceRef::from(place));
+                }
+            }
+            for voter in store.voters.values().filter(|v| v.active) {
+                let cpf = data_quality::cpf_digits(&voter.cpf);
+                if cpfs.contains(&cpf) {
+                    context.cpf_owners.insert(cpf, voter.voter_id.clone());
+                }
+            }
+            return Ok(context);
+        };
+
+        let rows = sqlx::query("SELECT id, zone, sections FROM tse.polling_places WHERE id = ANY($1)")
+            .bind(&place_ids)
+            .fetch_all(pool)
+            .await?;
+        for row in rows {
+            context.polling_places.insert(row.get("id"), PlaceRef {
+                zone: row.get("zone"),
+                sections: row.get("sections"),
+            });
+        }
+        let rows = sqlx::query(
+            r#"
+            SELECT regexp_replace(cpf, '[^0-9]', '', 'g') AS cpf, voter_id
+            FROM tse.voters
+            WHERE is_active AND regexp_replace(cpf, '[^0-9]', '', 'g') = ANY($1)
+            "#
+        )
+        .bind(&cpfs)
+        .fetch_all(pool)
+        .await?;
+        for row in rows {
+            context.cpf_owners.insert(row.get("cpf"), row.get("voter_id"));
+        }
+        Ok(context)
+    }
+
+    /// Obtém uma página, aguardando quando o TSE limita a taxa de requisições
+    async fn fetch_page<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        run: &mut VoterRollSyncRun,
+        cursor: Option<&str>,
+    ) -> Result<Page<T>> {
+        let mut query = vec![("limit", self.voter_roll_page_size.to_string())];
+        if let Some(since) = run.updated_since {
+            query.push(("updated_since", since.to_rfc3339()));
+        }
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor.to_string()));
+        }
+
+        let mut attempt = 0;
+        loop {
+            let response = self.client
+                .get(&format!("{}/api/v1/{}", self.tse_base_url, resource))
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .query(&query)
+                .send()
+                .await?;
+
+            let status = response.status();
+            if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+                if attempt >= MAX_RATE_LIMIT_RETRIES {
+                    return Err(anyhow!("Limite de requisições do TSE excedido em {}", resource));
+                }
+                let retry_after = response.headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok());
+                let wait = retry_wait_seconds(retry_after, attempt);
+                attempt += 1;
+                run.rate_limit_retries += 1;
+                log::warn!("TSE rate limited {}; retrying in {}s", resource, wait);
+                tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+                continue;
+            }
+            if !status.is_success() {
+                let error_text = response.text().await?;
+                return Err(anyhow!("Erro ao obter {}: {}", resource, error_text));
+            }
+
+            run.pages_fetched += 1;
+            return Ok(response.json().await?);
+        }
+    }
+
+    async fn apply_voters(&self, entries: &[VoterRollEntry]) -> Result<DeltaCounts> {
+        let Some(pool) = &self.db else {
+            return Ok(self.voter_roll.write().await.apply_voters(entries));
+        };
+
+        let mut counts = DeltaCounts::default();
+        let mut tx = pool.begin().await?;
+        for entry in entries {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO tse.voters (cpf, name, birth_date, voter_id, zone, section, polling_place_id, is_active, source_updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (voter_id) DO UPDATE SET
+                    cpf = EXCLUDED.cpf,
+                    name = EXCLUDED.name,
+                    birth_date = EXCLUDED.birth_date,
+                    zone = EXCLUDED.zone,
+                    section = EXCLUDED.section,
+                    polling_place_id = EXCLUDED.polling_place_id,
+                    is_active = EXCLUDED.is_active,
+                    source_updated_at = EXCLUDED.source_updated_at
+                WHERE tse.voters.source_updated_at IS NULL
+                   OR tse.voters.source_updated_at < EXCLUDED.source_updated_at
+                "#
+            )
+            .bind(&entry.cpf)
+            .bind(&entry.name)
+            .bind(entry.birth_date)
+            .bind(&entry.voter_id)
+            .bind(&entry.zone)
+            .bind(&entry.section)
+            .bind(&entry.polling_place_id)
+            .bind(entry.active && !entry.removed)
+            .bind(entry.updated_at)
+            .execute(&mut *tx)
+            .await?;
+
+            if result.rows_affected() > 0 {
+                if entry.removed {
+                    counts.removed += 1;
+                } else {
+                    counts.upserted += 1;
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(counts)
+    }
+
+    async fn apply_polling_places(&self, places: &[PollingPlace]) -> Result<DeltaCounts> {
+        let Some(pool) = &self.db else {
+            return Ok(self.voter_roll.write().await.apply_polling_places(places));
+        };
+
+        let mut counts = DeltaCounts::default();
+        let mut tx = pool.begin().await?;
+        for place in places {
+            let result = if place.removed {
+                sqlx::query("DELETE FROM tse.polling_places WHERE id = $1 AND source_updated_at < $2")
+                    .bind(&place.polling_place_id)
+                    .bind(place.updated_at)
+                    .execute(&mut *tx)
+                    .await?
+            } else {
+                sqlx::query(
+                    r#"
+                    INSERT INTO tse.polling_places (id, name, address, city, state, zone, sections, source_updated_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                    ON CONFLICT (id) DO UPDATE SET
+                        name = EXCLUDED.name,
+                        address = EXCLUDED.address,
+                        city = EXCLUDED.city,
+                        state = EXCLUDED.state,
+                        zone = EXCLUDED.zone,
+                        sections = EXCLUDED.sections,
+                        source_updated_at = EXCLUDED.source_updated_at
+                    WHERE tse.polling_places.source_updated_at < EXCLUDED.source_updated_at
+                    "#
+                )
+                .bind(&place.polling_place_id)
+                .bind(&place.name)
+                .bind(&place.address)
+                .bind(&place.city)
+                .bind(&place.state)
+                .bind(&place.zone)
+                .bind(&place.sections)
+                .bind(place.updated_at)
+                .execute(&mut *tx)
+                .await?
+            };
+
+            if result.rows_affected() > 0 {
+                if place.removed {
+                    counts.removed += 1;
+                } else {
+                    counts.upserted += 1;
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(counts)
+    }
+
+    /// Início da última execução bem-sucedida, base da sincronização incremental
+    async fn last_successful_sync(&self) -> Result<Option<DateTime<Utc>>> {
+        if let Some(pool) = &self.db {
+            let row = sqlx::query(
+                "SELECT MAX(started_at) AS started_at FROM tse.voter_roll_sync_runs WHERE status = 'succeeded'"
+            )
+            .fetch_one(pool)
+            .await?;
+            return Ok(row.get("started_at"));
+        }
+
+        Ok(self.runs.read().await.iter()
+            .filter(|run| run.status == SyncRunStatus::Succeeded)
+            .map(|run| run.started_at)
+            .max())
+    }
+
+    /// Registra a execução e o evento correspondente na trilha de auditoria
+    async fn record_run(&self, run: &VoterRollSyncRun) -> Result<()> {
+        self.runs.write().await.push(run.clone());
+
+        let Some(pool) = &self.db else {
+            return Ok(());
+        };
+        let status = run.status.as_str();
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO tse.voter_roll_sync_runs (id, started_at, finished_at, status, summary)
+            VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(run.id)
+        .bind(run.started_at)
+        .bind(run.finished_at)
+        .bind(status)
+        .bind(serde_json::to_value(run)?)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO audit.audit_logs (event_type, entity_type, entity_id, action, new_values)
+            VALUES ('VoterRollSync', 'voter_roll_sync_run', $1, $2, $3)
+            "#
+        )
+        .bind(run.id)
+        .bind(status)
+        .bind(serde_json::to_value(run)?)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Resultado da importação de candidatos do TSE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateImportReport {
+    pub received: usize,
+    pub imported: usize,
+    /// Candidatos suspensos, cancelados ou inelegíveis, não importados
+    pub skipped: usize,
+    pub quarantined: usize,
 }
 
 /// Dados de voto para envio
@@ -405,3 +1250,48 @@ pub struct ElectionStats {
     pub real_time_updates: bool,
     pub last_update: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voter(voter_id: &str, minute: u32, removed: bool) -> VoterRollEntry {
+        VoterRollEntry {
+            voter_id: voter_id.to_string(),
+            cpf: "12345678909".to_string(),
+            name: "Eleitora Teste".to_string(),
+            birth_date: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+            zone: "0001".to_string(),
+            section: "0042".to_string(),
+            polling_place_id: Some("LV-1".to_string()),
+            active: true,
+            removed,
+            updated_at: DateTime::from_timestamp(1_760_000_000 + minute as i64 * 60, 0).unwrap(),
+            biometric_template: None,
+        }
+    }
+
+    #[test]
+    fn test_delta_skips_stale_updates_and_deactivates_removed_voters() {
+        let mut store = VoterRollStore::default();
+        assert_eq!(store.apply_voters(&[voter("T1", 5, false), voter("T2", 5, false)]).upserted, 2);
+
+        // Página repetida ou atrasada não sobrescreve versão mais nova
+        assert_eq!(store.apply_voters(&[voter("T1", 4, true)]), DeltaCounts::default());
+        assert!(store.voters["T1"].active);
+
+        let counts = store.apply_voters(&[voter("T1", 6, true)]);
+        assert_eq!(counts, DeltaCounts { upserted: 0, removed: 1 });
+        assert!(!store.voters["T1"].active);
+        assert_eq!(store.voters.len(), 2);
+    }
+
+    #[test]
+    fn test_retry_wait_honors_retry_after_with_cap() {
+        assert_eq!(retry_wait_seconds(Some("7"), 0), 7);
+        assert_eq!(retry_wait_seconds(Some("3600"), 0), MAX_RETRY_WAIT_SECONDS);
+        assert_eq!(retry_wait_seconds(None, 0), 1);
+        assert_eq!(retry_wait_seconds(None, 3), 8);
+        assert_eq!(retry_wait_seconds(Some("Wed, 21 Oct 2026 07:28:00 GMT"), 2), 4);
+    }
+}
@@ -1,21 +1,41 @@
 //! Integração com Gov.br para autenticação digital
 //! 
-//! Implementa OAuth2 e validação de identidade através do Gov.br
+//! Implementa OAuth2/OpenID Connect com o Gov.br (código de autorização com
+//! PKCE), renovação de tokens, verificação do nível da conta (bronze,
+//! prata, ouro) e associação da identidade ao cadastro de eleitores do TSE
+//! para validar a elegibilidade.
 
 use crate::config::Config;
+use crate::services::tse::voter_validation::{VoterData, VoterValidationService};
 use anyhow::{Result, anyhow};
-use reqwest::Client;
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::RwLock;
+
+/// Validade de uma autorização iniciada e ainda não concluída
+const AUTHORIZATION_TTL_MINUTES: i64 = 10;
+
+/// Escopos solicitados ao Gov.br
+const GOV_BR_SCOPES: &str = "openid email profile govbr_confiabilidades";
 
 /// Serviço de integração com Gov.br
 pub struct GovBrService {
     client: Client,
     base_url: String,
+    api_url: String,
     client_id: String,
     client_secret: String,
     redirect_uri: String,
+    min_account_level: AccountLevel,
+    voter_validation: VoterValidationService,
+    /// Autorizações em andamento, por `state`
+    pending: RwLock<HashMap<String, PendingAuthorization>>,
 }
 
 /// Token de acesso do Gov.br
@@ -24,7 +44,11 @@ pub struct GovBrToken {
     pub access_token: String,
     pub token_type: String,
     pub expires_in: u64,
+    #[serde(default)]
     pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub id_token: Option<String>,
+    #[serde(default)]
     pub scope: String,
 }
 
@@ -33,17 +57,93 @@ pub struct GovBrToken {
 pub struct GovBrUser {
     pub sub: String, // CPF
     pub name: String,
+    #[serde(default)]
     pub given_name: String,
+    #[serde(default)]
     pub family_name: String,
     pub email: Option<String>,
     pub phone_number: Option<String>,
     pub birthdate: Option<String>,
+    #[serde(default)]
     pub cpf: String,
     pub pis: Option<String>,
     pub voter_id: Option<String>, // Título de eleitor
+    #[serde(default)]
     pub verified: bool,
 }
 
+/// Nível da conta Gov.br
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountLevel {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+/// Autorização iniciada, aguardando o retorno do Gov.br
+#[derive(Debug, Clone)]
+struct PendingAuthorization {
+    code_verifier: String,
+    nonce: String,
+    created_at: DateTime<Utc>,
+}
+
+/// URL para redirecionar o cidadão ao Gov.br
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+    pub auth_url: String,
+    pub state: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Identidade Gov.br associada ao cadastro eleitoral
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovBrIdentity {
+    pub cpf: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub account_level: AccountLevel,
+    pub voter: Option<VoterData>,
+    /// Conta com nível suficiente e eleitor ativo no cadastro
+    pub eligible: bool,
+    pub ineligibility_reason: Option<String>,
+}
+
+/// Sessão resultante do login pelo Gov.br
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovBrSession {
+    pub token: GovBrToken,
+    pub expires_at: DateTime<Utc>,
+    pub identity: GovBrIdentity,
+}
+
+/// Claims do ID token do Gov.br
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    name: Option<String>,
+    email: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Jwk {
+    kid: Option<String>,
+    n: String,
+    e: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Reliability {
+    id: String,
+}
+
 /// Resposta de validação de eleitor
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoterValidationResponse {
@@ -72,49 +172,134 @@ pub enum VoterStatus {
     Pendente,
 }
 
+impl AccountLevel {
+    /// Nível a partir do identificador de confiabilidade do Gov.br (1, 2 ou 3)
+    pub fn from_reliability_id(id: &str) -> Option<Self> {
+        match id {
+            "1" => Some(Self::Bronze),
+            "2" => Some(Self::Silver),
+            "3" => Some(Self::Gold),
+            _ => None,
+        }
+    }
+
+    pub fn parse(level: &str) -> Option<Self> {
+        match level.to_lowercase().as_str() {
+            "bronze" => Some(Self::Bronze),
+            "silver" | "prata" => Some(Self::Silver),
+            "gold" | "ouro" => Some(Self::Gold),
+            _ => None,
+        }
+    }
+}
+
+/// Verificador PKCE aleatório (RFC 7636, 43 caracteres)
+pub fn generate_code_verifier() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Desafio PKCE S256 do verificador
+pub fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
 impl GovBrService {
     /// Cria nova instância do serviço Gov.br
     pub fn new(config: &Config) -> Self {
         Self {
             client: Client::new(),
             base_url: config.tse.gov_br_base_url.clone(),
+            api_url: config.tse.gov_br_api_url.clone(),
             client_id: config.tse.client_id.clone(),
             client_secret: config.tse.client_secret.clone(),
             redirect_uri: config.tse.redirect_uri.clone(),
+            min_account_level: AccountLevel::parse(&config.tse.gov_br_min_account_level)
+                .unwrap_or(AccountLevel::Silver),
+            voter_validation: VoterValidationService::new(config),
+            pending: RwLock::new(HashMap::new()),
         }
     }
 
-    /// Gera URL de autorização OAuth2
-    pub fn get_authorization_url(&self, state: &str) -> String {
-        let mut params = HashMap::new();
-        params.insert("response_type", "code");
-        params.insert("client_id", &self.client_id);
-        params.insert("redirect_uri", &self.redirect_uri);
-        params.insert("scope", "openid profile cpf");
-        params.insert("state", state);
-        let nonce = uuid::Uuid::new_v4().to_string();
-        params.insert("nonce", &nonce);
+    /// Inicia o fluxo de código de autorização com PKCE
+    pub async fn begin_authorization(&self) -> Result<AuthorizationRequest> {
+        let state = random_token();
+        let nonce = random_token();
+        let code_verifier = generate_code_verifier();
+
+        let auth_url = Url::parse_with_params(
+            &format!("{}/authorize", self.base_url),
+            &[
+                ("response_type", "code"),
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("scope", GOV_BR_SCOPES),
+                ("state", state.as_str()),
+                ("nonce", nonce.as_str()),
+                ("code_challenge", code_challenge(&code_verifier).as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )?;
+
+        let now = Utc::now();
+        let mut pending = self.pending.write().await;
+        pending.retain(|_, p| now - p.created_at < Duration::minutes(AUTHORIZATION_TTL_MINUTES));
+        pending.insert(state.clone(), PendingAuthorization { code_verifier, nonce, created_at: now });
+
+        Ok(AuthorizationRequest {
+            auth_url: auth_url.to_string(),
+            state,
+            expires_at: now + Duration::minutes(AUTHORIZATION_TTL_MINUTES),
+        })
+    }
 
-        let query_string = params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<_>>()
-            .join("&");
+    /// Conclui o login: troca o código, valida o ID token e associa o eleitor
+    pub async fn complete_authorization(&self, code: &str, state: &str) -> Result<GovBrSession> {
+        let pending = self.take_pending(state).await?;
+        let token = self.exchange_code_for_token(code, &pending.code_verifier).await?;
+        let expires_at = Utc::now() + Duration::seconds(token.expires_in as i64);
 
-        format!("{}/authorize?{}", self.base_url, query_string)
+        let id_token = token.id_token.as_deref()
+            .ok_or_else(|| anyhow!("Gov.br não retornou ID token"))?;
+        let claims = self.verify_id_token(id_token, &pending.nonce).await?;
+
+        let user = self.get_user_info(&token.access_token).await?;
+        if user.sub != claims.sub {
+            return Err(anyhow!("Usuário do Gov.br não corresponde ao ID token"));
+        }
+
+        let identity = self.resolve_identity(&claims, &user, &token.access_token).await?;
+        Ok(GovBrSession { token, expires_at, identity })
+    }
+
+    /// Remove a autorização pendente; o `state` só pode ser usado uma vez
+    async fn take_pending(&self, state: &str) -> Result<PendingAuthorization> {
+        let pending = self.pending.write().await.remove(state)
+            .ok_or_else(|| anyhow!("Estado de autorização desconhecido ou já utilizado"))?;
+        if Utc::now() - pending.created_at >= Duration::minutes(AUTHORIZATION_TTL_MINUTES) {
+            return Err(anyhow!("Autorização expirada"));
+        }
+        Ok(pending)
     }
 
     /// Troca código de autorização por token de acesso
-    pub async fn exchange_code_for_token(&self, code: &str) -> Result<GovBrToken> {
+    pub async fn exchange_code_for_token(&self, code: &str, code_verifier: &str) -> Result<GovBrToken> {
         let mut params = HashMap::new();
         params.insert("grant_type", "authorization_code");
         params.insert("code", code);
         params.insert("redirect_uri", &self.redirect_uri);
-        params.insert("client_id", &self.client_id);
-        params.insert("client_secret", &self.client_secret);
+        params.insert("code_verifier", code_verifier);
 
         let response = self.client
             .post(&format!("{}/token", self.base_url))
+            .basic_auth(&self.client_id, Some(&self.client_secret))
             .form(&params)
             .send()
             .await?;
@@ -128,6 +313,75 @@ impl GovBrService {
         Ok(token)
     }
 
+    /// Valida assinatura, emissor, audiência e nonce do ID token
+    async fn verify_id_token(&self, id_token: &str, expected_nonce: &str) -> Result<IdTokenClaims> {
+        let header = decode_header(id_token)?;
+        let jwks: Jwks = self.client
+            .get(&format!("{}/jwk", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let jwk = jwks.keys.iter()
+            .find(|k| header.kid.is_none() || k.kid == header.kid)
+            .ok_or_else(|| anyhow!("Chave do ID token não encontrada no Gov.br"))?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[&self.client_id]);
+        validation.set_issuer(&[format!("{}/", self.base_url)]);
+        let claims = decode::<IdTokenClaims>(
+            id_token,
+            &DecodingKey::from_rsa_components(&jwk.n, &jwk.e)?,
+            &validation,
+        )?.claims;
+
+        if claims.nonce.as_deref() != Some(expected_nonce) {
+            return Err(anyhow!("Nonce do ID token inválido"));
+        }
+        Ok(claims)
+    }
+
+    /// Nível da conta a partir dos selos de confiabilidade do cidadão
+    pub async fn get_account_level(&self, cpf: &str, access_token: &str) -> Result<AccountLevel> {
+        let response = self.client
+            .get(&format!("{}/confiabilidades/v3/contas/{}/niveis", self.api_url, cpf))
+            .query(&[("response-type", "ids")])
+            .bearer_auth(access_token)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow!("Erro ao obter nível da conta: {}", error_text));
+        }
+
+        let levels: Vec<Reliability> = response.json().await?;
+        levels.iter()
+            .filter_map(|level| AccountLevel::from_reliability_id(&level.id))
+            .max()
+            .ok_or_else(|| anyhow!("Conta Gov.br sem nível de confiabilidade"))
+    }
+
+    /// Associa a identidade ao cadastro eleitoral e decide a elegibilidade
+    async fn resolve_identity(&self, claims: &IdTokenClaims, user: &GovBrUser, access_token: &str) -> Result<GovBrIdentity> {
+        let cpf = claims.sub.clone();
+        let account_level = self.get_account_level(&cpf, access_token).await?;
+        let validation = self.voter_validation.validate_voter_by_cpf(&cpf).await?;
+        let voter = validation.voter_data.filter(|_| validation.valid);
+
+        let ineligibility_reason = eligibility_issue(account_level, self.min_account_level, voter.as_ref());
+        Ok(GovBrIdentity {
+            name: claims.name.clone().unwrap_or_else(|| user.name.clone()),
+            email: claims.email.clone().or_else(|| user.email.clone()),
+            cpf,
+            account_level,
+            voter,
+            eligible: ineligibility_reason.is_none(),
+            ineligibility_reason,
+        })
+    }
+
     /// Obtém dados do usuário usando token de acesso
     pub async fn get_user_info(&self, access_token: &str) -> Result<GovBrUser> {
         let response = self.client
@@ -178,11 +432,10 @@ impl GovBrService {
         let mut params = HashMap::new();
         params.insert("grant_type", "refresh_token");
         params.insert("refresh_token", refresh_token);
-        params.insert("client_id", &self.client_id);
-        params.insert("client_secret", &self.client_secret);
 
         let response = self.client
             .post(&format!("{}/token", self.base_url))
+            .basic_auth(&self.client_id, Some(&self.client_secret))
             .form(&params)
             .send()
             .await?;
@@ -217,3 +470,57 @@ impl GovBrService {
         Ok(())
     }
 }
+
+/// Motivo de inelegibilidade, se houver
+fn eligibility_issue(level: AccountLevel, min_level: AccountLevel, voter: Option<&VoterData>) -> Option<String> {
+    use crate::services::tse::voter_validation::VoterStatus as RollStatus;
+
+    if level < min_level {
+        return Some(format!("Conta Gov.br nível {:?} abaixo do mínimo exigido ({:?})", level, min_level));
+    }
+    match voter {
+        None => Some("CPF não encontrado no cadastro eleitoral".to_string()),
+        Some(voter) if !matches!(voter.status, RollStatus::Ativo) => {
+            Some(format!("Inscrição eleitoral com situação {:?}", voter.status))
+        }
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_challenge_matches_rfc7636_example() {
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoe2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(generate_code_verifier().len(), 43);
+    }
+
+    #[tokio::test]
+    async fn test_state_is_single_use_and_url_carries_pkce() {
+        let service = GovBrService::new(&Config::new());
+        let request = service.begin_authorization().await.unwrap();
+
+        let url = Url::parse(&request.auth_url).unwrap();
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(query["state"], request.state);
+        assert_eq!(query["scope"], GOV_BR_SCOPES);
+
+        let pending = service.take_pending(&request.state).await.unwrap();
+        assert_eq!(query["code_challenge"], code_challenge(&pending.code_verifier));
+        assert!(service.take_pending(&request.state).await.is_err());
+    }
+
+    #[test]
+    fn test_bronze_accounts_are_not_eligible() {
+        assert!(AccountLevel::Gold > AccountLevel::Silver);
+        assert_eq!(AccountLevel::from_reliability_id("2"), Some(AccountLevel::Silver));
+        assert!(eligibility_issue(AccountLevel::Bronze, AccountLevel::Silver, None).unwrap().contains("abaixo"));
+        assert!(eligibility_issue(AccountLevel::Gold, AccountLevel::Silver, None).unwrap().contains("cadastro"));
+    }
+}
@@ -7,8 +7,11 @@ pub mod gov_br;
 pub mod voter_validation;
 pub mod digital_certificate;
 pub mod election_sync;
+pub mod biometric_dedup;
+pub mod data_quality;
 
 pub use gov_br::GovBrService;
 pub use voter_validation::VoterValidationService;
 pub use digital_certificate::DigitalCertificateService;
 pub use election_sync::ElectionSyncService;
+pub use biometric_dedup::BiometricDedupService;
@@ -0,0 +1,253 @@
+//! Canal de atualização de firmware e da aplicação das urnas
+//!
+//! O backend assina e hospeda os manifestos de atualização; os artefatos
+//! ficam no repositório de distribuição, referenciados pelo SHA-256. Em
+//! cada canal e componente as versões publicadas só crescem, e cada
+//! publicação vai para o log transparente. As urnas consultam o manifesto
+//! mais recente do seu canal e informam o resultado da instalação,
+//! inclusive a reversão quando a verificação de saúde falha.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+pub use fortis_domain::update::{InstallOutcome, InstallReport, UpdateComponent, UpdateManifest, UpdateVersion};
+use crate::crypto::hsm::SigningKey;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Identificador usado no log transparente para eventos de atualização
+const UPDATE_LOG_SCOPE: &str = "urna-updates";
+
+/// Pedido de publicação de versão
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublishUpdateRequest {
+    pub component: UpdateComponent,
+    pub channel: String,
+    pub version: UpdateVersion,
+    pub min_installed_version: Option<UpdateVersion>,
+    pub artifact_url: String,
+    pub artifact_sha256: String,
+    pub artifact_size: u64,
+    pub release_notes: Option<String>,
+}
+
+/// Publicação registrada, com a posição no log transparente
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedUpdate {
+    pub manifest: UpdateManifest,
+    pub published_by: String,
+    pub log_index: Option<u64>,
+}
+
+/// Serviço do canal de atualização
+pub struct UpdateService {
+    signing_key: Box<dyn SigningKey>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+    published: RwLock<Vec<PublishedUpdate>>,
+    /// Relatórios por urna, do mais antigo ao mais recente
+    reports: RwLock<HashMap<String, Vec<InstallReport>>>,
+}
+
+impl UpdateService {
+    pub fn new(signing_key: impl SigningKey + 'static) -> Self {
+        Self {
+            signing_key: Box::new(signing_key),
+            transparency_log: None,
+            published: RwLock::new(Vec::new()),
+            reports: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registra cada publicação no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Chave pública que as urnas fixam para verificar os manifestos
+    pub fn signing_public_key(&self) -> String {
+        hex::encode(self.signing_key.public_key_bytes())
+    }
+
+    /// Assina e publica o manifesto de uma nova versão
+    pub async fn publish(&self, request: PublishUpdateRequest, actor: &str) -> Result<PublishedUpdate> {
+        let channel = request.channel.trim();
+        if channel.is_empty() {
+            return Err(anyhow!("Update channel is required"));
+        }
+        let url = url::Url::parse(&request.artifact_url)
+            .map_err(|e| anyhow!("Invalid artifact URL: {}", e))?;
+        if url.scheme() != "https" {
+            return Err(anyhow!("Artifact URL must use HTTPS"));
+        }
+        let sha256 = request.artifact_sha256.to_lowercase();
+        if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(anyhow!("Artifact SHA-256 must be 64 hex characters"));
+        }
+        if request.artifact_size == 0 {
+            return Err(anyhow!("Artifact size must be positive"));
+        }
+        if request.min_installed_version.is_some_and(|minimum| minimum >= request.version) {
+            return Err(anyhow!("Minimum installed version must be older than {}", request.version));
+        }
+
+        let mut published = self.published.write().await;
+        if let Some(latest) = latest_in(&published, request.component, channel) {
+            if request.version <= latest.manifest.version {
+                return Err(anyhow!(
+                    "Version {} is not newer than {} already published on {}",
+                    request.version, latest.manifest.version, channel
+                ));
+            }
+        }
+
+        let mut manifest = UpdateManifest {
+            update_id: Uuid::new_v4(),
+            component: request.component,
+            channel: channel.to_string(),
+            version: request.version,
+            min_installed_version: request.min_installed_version,
+            artifact_url: request.artifact_url,
+            artifact_sha256: sha256,
+            artifact_size: request.artifact_size,
+            release_notes: request.release_notes,
+            published_at: Utc::now(),
+            signature: String::new(),
+        };
+        manifest.signature = hex::encode(self.signing_key.try_sign(&manifest.signed_message())?);
+
+        let log_index = self.log_publication(&manifest, actor).await?;
+        let update = PublishedUpdate {
+            manifest,
+            published_by: actor.to_string(),
+            log_index,
+        };
+        published.push(update.clone());
+
+        log::info!(
+            "Update {} {} published on channel {} by {}",
+            update.manifest.component.as_str(), update.manifest.version, update.manifest.channel, actor
+        );
+        Ok(update)
+    }
+
+    /// Manifesto mais recente do canal, se for mais novo que a versão instalada
+    pub async fn check(
+        &self,
+        component: UpdateComponent,
+        channel: &str,
+        installed: Option<UpdateVersion>,
+    ) -> Option<UpdateManifest> {
+        let published = self.published.read().await;
+        latest_in(&published, component, channel)
+            .filter(|latest| installed.map_or(true, |installed| latest.manifest.version > installed))
+            .map(|latest| latest.manifest.clone())
+    }
+
+    /// Publicações, das mais recentes para as mais antigas
+    pub async fn list(&self, channel: Option<&str>) -> Vec<PublishedUpdate> {
+        self.published.read().await.iter()
+            .rev()
+            .filter(|update| channel.map_or(true, |channel| update.manifest.channel == channel))
+            .cloned()
+            .collect()
+    }
+
+    /// Registra o resultado da instalação informado pela urna
+    pub async fn report_installation(&self, urna_id: &str, report: InstallReport) -> Result<InstallReport> {
+        let known = self.published.read().await.iter().any(|update| {
+            update.manifest.update_id == report.update_id
+                && update.manifest.component == report.component
+                && update.manifest.version == report.version
+        });
+        if !known {
+            return Err(anyhow!("Update {} {} was not published", report.update_id, report.version));
+        }
+        match report.outcome {
+            InstallOutcome::Applied => log::info!(
+                "Urna {} applied {} {}", urna_id, report.component.as_str(), report.version
+            ),
+            _ => log::warn!(
+                "Urna {} did not keep {} {} ({:?}): {}",
+                urna_id, report.component.as_str(), report.version, report.outcome,
+                report.error.as_deref().unwrap_or("no error reported")
+            ),
+        }
+        self.reports.write().await.entry(urna_id.to_string()).or_default().push(report.clone());
+        Ok(report)
+    }
+
+    /// Relatórios de instalação da urna
+    pub async fn installations(&self, urna_id: &str) -> Vec<InstallReport> {
+        self.reports.read().await.get(urna_id).cloned().unwrap_or_default()
+    }
+
+    async fn log_publication(&self, manifest: &UpdateManifest, actor: &str) -> Result<Option<u64>> {
+        let Some(log) = &self.transparency_log else {
+            return Ok(None);
+        };
+        let inclusion = log.write().await.append_election_event(ElectionEvent {
+            id: manifest.update_id.to_string(),
+            event_type: ElectionEventType::UrnaUpdatePublished,
+            election_id: UPDATE_LOG_SCOPE.to_string(),
+            data: serde_json::json!({
+                "component": manifest.component,
+                "channel": manifest.channel,
+                "version": manifest.version,
+                "artifact_sha256": manifest.artifact_sha256,
+                "artifact_size": manifest.artifact_size,
+                "published_by": actor,
+            }),
+            timestamp: manifest.published_at,
+            source: "TSE".to_string(),
+        })?;
+        Ok(Some(inclusion.log_index))
+    }
+}
+
+fn latest_in<'a>(published: &'a [PublishedUpdate], component: UpdateComponent, channel: &str) -> Option<&'a PublishedUpdate> {
+    published.iter()
+        .filter(|update| update.manifest.component == component && update.manifest.channel == channel)
+        .max_by_key(|update| update.manifest.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::threshold_signatures::ThresholdUtils;
+
+    fn request(version: &str) -> PublishUpdateRequest {
+        PublishUpdateRequest {
+            component: UpdateComponent::Application,
+            channel: "stable".to_string(),
+            version: version.parse().unwrap(),
+            min_installed_version: None,
+            artifact_url: format!("https://updates.fortis.gov.br/urna/app-{}.tar.zst", version),
+            artifact_sha256: "ab".repeat(32),
+            artifact_size: 1024,
+            release_notes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_published_versions_are_signed_and_monotonic() {
+        let (signing_key, _) = ThresholdUtils::generate_key_pair().unwrap();
+        let service = UpdateService::new(signing_key);
+        let public_key = hex::decode(service.signing_public_key()).unwrap();
+
+        let first = service.publish(request("1.2.0"), "tse-admin").await.unwrap();
+        assert!(first.manifest.verify_signature(&public_key).is_ok());
+        assert!(service.publish(request("1.2.0"), "tse-admin").await.is_err());
+        assert!(service.publish(request("1.1.9"), "tse-admin").await.is_err());
+        service.publish(request("1.3.0"), "tse-admin").await.unwrap();
+
+        let offered = service.check(UpdateComponent::Application, "stable", Some("1.2.0".parse().unwrap())).await;
+        assert_eq!(offered.map(|m| m.version.to_string()), Some("1.3.0".to_string()));
+        assert!(service.check(UpdateComponent::Application, "stable", Some("1.3.0".parse().unwrap())).await.is_none());
+        assert!(service.check(UpdateComponent::Firmware, "stable", None).await.is_none());
+    }
+}
@@ -1,18 +1,205 @@
 //! Serviço de autenticação para urnas eletrônicas
+//!
+//! Além do eleitor, autentica a própria urna: cada equipamento recebe no
+//! credenciamento um certificado X.509 emitido pela CA de dispositivos, e o
+//! certificado apresentado na conexão (mTLS) é mapeado para o ID da urna
+//! registrada. Certificados revogados ou substituídos deixam de valer.
 
 use crate::models::{Urna, UrnaAuthentication, BiometricData, CertificateData, AuthMethod, AuthResult};
+use crate::services::consent::{ConsentLedger, BiometricCategory, ProcessingPurpose};
 use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose};
+use fortis_domain::biometric::FingerprintTemplate;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Mínimo de minúcias para um template ser aceito
+const MIN_TEMPLATE_MINUTIAE: usize = 12;
+
+/// Certificado de dispositivo credenciado para uma urna
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceCertificate {
+    pub urna_id: String,
+    /// SHA-256 (hex) do certificado em DER
+    pub fingerprint: String,
+    pub serial: String,
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub enrolled_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revocation_reason: Option<String>,
+    /// Chave pública do certificado (SubjectPublicKeyInfo em DER)
+    #[serde(skip)]
+    pub public_key: Vec<u8>,
+}
+
+/// Urna identificada pelo certificado apresentado na conexão
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceIdentity {
+    pub urna_id: String,
+    pub fingerprint: String,
+    pub not_after: DateTime<Utc>,
+}
 
 pub struct UrnaAuthService {
     // Em implementação real, teria conexão com banco de dados
     // e serviços de validação biométrica
+    consent_ledger: Option<Arc<ConsentLedger>>,
+    /// Certificados (DER) da CA que emite os certificados de dispositivo
+    device_ca: Vec<Vec<u8>>,
+    /// Certificados credenciados, pela impressão digital
+    devices: RwLock<HashMap<String, DeviceCertificate>>,
 }
 
 impl UrnaAuthService {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            consent_ledger: None,
+            device_ca: Vec::new(),
+            devices: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Define a CA de dispositivos a partir do PEM
+    pub fn with_device_ca(mut self, ca_pem: &str) -> Result<Self> {
+        let certs = rustls_pemfile::certs(&mut ca_pem.as_bytes())?;
+        if certs.is_empty() {
+            return Err(anyhow!("No certificate found in device CA"));
+        }
+        for der in &certs {
+            X509Certificate::from_der(der).map_err(|e| anyhow!("Invalid device CA: {}", e))?;
+        }
+        self.device_ca = certs;
+        Ok(self)
+    }
+
+    /// Exige autorização do livro de consentimento antes de tratar biometria
+    pub fn with_consent_ledger(mut self, ledger: Arc<ConsentLedger>) -> Self {
+        self.consent_ledger = Some(ledger);
+        self
+    }
+
+    /// Credencia o certificado de uma urna, substituindo o anterior
+    pub async fn enroll_device(&self, urna_id: &str, certificate_pem: &str) -> Result<DeviceCertificate> {
+        let der = rustls_pemfile::certs(&mut certificate_pem.as_bytes())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No certificate found"))?;
+        let (_, cert) = X509Certificate::from_der(&der)
+            .map_err(|e| anyhow!("Invalid certificate: {}", e))?;
+
+        let common_name = cert.subject().iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok());
+        if common_name != Some(urna_id) {
+            return Err(anyhow!("Certificate subject does not match urna {}", urna_id));
+        }
+        if !cert.validity().is_valid() {
+            return Err(anyhow!("Certificate is outside its validity period"));
+        }
+        self.verify_device_issuer(&cert)?;
+
+        let now = Utc::now();
+        let enrolled = DeviceCertificate {
+            urna_id: urna_id.to_string(),
+            fingerprint: hex::encode(Sha256::digest(&der)),
+            serial: cert.raw_serial_as_string(),
+            issuer: cert.issuer().to_string(),
+            not_before: asn1_time(cert.validity().not_before.timestamp())?,
+            not_after: asn1_time(cert.validity().not_after.timestamp())?,
+            enrolled_at: now,
+            revoked_at: None,
+            revocation_reason: None,
+            public_key: cert.public_key().raw.to_vec(),
+        };
+
+        let mut devices = self.devices.write().await;
+        for previous in devices.values_mut() {
+            if previous.urna_id == urna_id && previous.revoked_at.is_none() {
+                previous.revoked_at = Some(now);
+                previous.revocation_reason = Some("superseded".to_string());
+            }
+        }
+        devices.insert(enrolled.fingerprint.clone(), enrolled.clone());
+
+        log::info!("Device certificate {} enrolled for urna {}", enrolled.fingerprint, urna_id);
+        Ok(enrolled)
+    }
+
+    /// Mapeia o certificado da conexão (DER) para a urna registrada
+    pub async fn identify_device(&self, certificate_der: &[u8]) -> Result<DeviceIdentity> {
+        let fingerprint = hex::encode(Sha256::digest(certificate_der));
+        let devices = self.devices.read().await;
+        let device = devices.get(&fingerprint)
+            .ok_or_else(|| anyhow!("Certificate not enrolled"))?;
+
+        if let Some(revoked_at) = device.revoked_at {
+            return Err(anyhow!("Certificate revoked at {}", revoked_at));
+        }
+        if device.not_after < Utc::now() {
+            return Err(anyhow!("Certificate expired at {}", device.not_after));
+        }
+
+        Ok(DeviceIdentity {
+            urna_id: device.urna_id.clone(),
+            fingerprint,
+            not_after: device.not_after,
+        })
+    }
+
+    /// Revoga os certificados ativos da urna
+    pub async fn revoke_device(&self, urna_id: &str, reason: &str) -> Vec<DeviceCertificate> {
+        let now = Utc::now();
+        let mut revoked = Vec::new();
+        for device in self.devices.write().await.values_mut() {
+            if device.urna_id == urna_id && device.revoked_at.is_none() {
+                device.revoked_at = Some(now);
+                device.revocation_reason = Some(reason.to_string());
+                revoked.push(device.clone());
+            }
+        }
+        log::warn!("{} device certificate(s) revoked for urna {}: {}", revoked.len(), urna_id, reason);
+        revoked
+    }
+
+    /// Chave pública (SPKI em DER) do certificado ativo da urna
+    pub async fn active_device_key(&self, urna_id: &str) -> Result<Vec<u8>> {
+        let now = Utc::now();
+        self.devices.read().await.values()
+            .find(|device| device.urna_id == urna_id && device.revoked_at.is_none() && device.not_after >= now)
+            .map(|device| device.public_key.clone())
+            .ok_or_else(|| anyhow!("No active device certificate for urna {}", urna_id))
+    }
+
+    /// Histórico de certificados da urna
+    pub async fn device_certificates(&self, urna_id: &str) -> Vec<DeviceCertificate> {
+        let mut certificates: Vec<_> = self.devices.read().await.values()
+            .filter(|device| device.urna_id == urna_id)
+            .cloned()
+            .collect();
+        certificates.sort_by_key(|device| device.enrolled_at);
+        certificates
+    }
+
+    fn verify_device_issuer(&self, cert: &X509Certificate<'_>) -> Result<()> {
+        if self.device_ca.is_empty() {
+            return Err(anyhow!("Device CA not configured"));
+        }
+        for der in &self.device_ca {
+            let (_, ca) = X509Certificate::from_der(der)
+                .map_err(|e| anyhow!("Invalid device CA: {}", e))?;
+            if ca.subject() == cert.issuer() && cert.verify_signature(Some(ca.public_key())).is_ok() {
+                return Ok(());
+            }
+        }
+        Err(anyhow!("Certificate not issued by the device CA"))
     }
 
     pub async fn authenticate_voter(
@@ -21,6 +208,12 @@ impl UrnaAuthService {
         biometric_data: &BiometricData,
         certificate_data: Option<&CertificateData>,
     ) -> Result<UrnaAuthentication> {
+        // Em implementação real, buscaria o voter_id do banco de dados
+        let voter_id = Uuid::new_v4();
+
+        // Registrar finalidade do tratamento biométrico (LGPD)
+        self.authorize_biometric_use(urna, &voter_id).await?;
+
         // Validar dados biométricos
         let biometric_valid = self.validate_biometric_data(biometric_data).await?;
         
@@ -50,9 +243,6 @@ impl UrnaAuthService {
             (false, false) => AuthResult::Error,
         };
 
-        // Em implementação real, buscaria o voter_id do banco de dados
-        let voter_id = Uuid::new_v4();
-
         Ok(UrnaAuthentication {
             urna_id: urna.id,
             voter_id,
@@ -64,12 +254,46 @@ impl UrnaAuthService {
         })
     }
 
+    async fn authorize_biometric_use(&self, urna: &Urna, voter_id: &Uuid) -> Result<()> {
+        let Some(ledger) = &self.consent_ledger else {
+            return Ok(());
+        };
+
+        let interaction_id = Uuid::new_v4().to_string();
+        let source = format!("urna:{}", urna.id);
+        for category in [BiometricCategory::Fingerprint, BiometricCategory::FacialImage] {
+            ledger
+                .authorize_use(
+                    &voter_id.to_string(),
+                    category,
+                    ProcessingPurpose::VoterAuthentication,
+                    &interaction_id,
+                    &source,
+                )
+                .await
+                .map_err(|e| anyhow!("Biometric processing not authorized: {}", e))?;
+        }
+
+        Ok(())
+    }
+
     async fn validate_biometric_data(&self, biometric_data: &BiometricData) -> Result<bool> {
-        // Em implementação real, validaria com sistema biométrico
-        // Por enquanto, simula validação
-        Ok(!biometric_data.fingerprint.is_empty() && 
-           !biometric_data.fingerprint_hash.is_empty() &&
-           !biometric_data.face_id.is_empty() &&
+        // Template ISO/IEC 19794-2 extraído pela urna, em base64
+        let Ok(record) = general_purpose::STANDARD.decode(&biometric_data.fingerprint) else {
+            return Ok(false);
+        };
+        let Ok(template) = FingerprintTemplate::from_iso_19794_2(&record) else {
+            return Ok(false);
+        };
+        if template.fingerprint_hash() != biometric_data.fingerprint_hash
+            || template.minutiae.len() < MIN_TEMPLATE_MINUTIAE
+        {
+            return Ok(false);
+        }
+
+        // Em implementação real, compararia com o template cadastrado do
+        // eleitor via `FingerprintTemplate::match_score`
+        Ok(!biometric_data.face_id.is_empty() &&
            !biometric_data.biometric_hash.is_empty())
     }
 
@@ -97,3 +321,64 @@ impl UrnaAuthService {
         Ok(false)
     }
 }
+
+fn asn1_time(timestamp: i64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp(timestamp, 0).ok_or_else(|| anyhow!("Invalid certificate time"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType, IsCa};
+
+    fn certificate(common_name: &str, is_ca: bool) -> Certificate {
+        let mut params = CertificateParams::new(vec![]);
+        params.distinguished_name = DistinguishedName::new();
+        params.distinguished_name.push(DnType::CommonName, common_name);
+        if is_ca {
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        }
+        Certificate::from_params(params).unwrap()
+    }
+
+    fn service(ca: &Certificate) -> UrnaAuthService {
+        UrnaAuthService::new().with_device_ca(&ca.serialize_pem().unwrap()).unwrap()
+    }
+
+    fn der(pem: &str) -> Vec<u8> {
+        rustls_pemfile::certs(&mut pem.as_bytes()).unwrap().remove(0)
+    }
+
+    #[tokio::test]
+    async fn test_enrolled_certificate_maps_to_urna_until_revoked() {
+        let ca = certificate("FORTIS Device CA", true);
+        let auth = service(&ca);
+        let pem = certificate("URNA-1", false).serialize_pem_with_signer(&ca).unwrap();
+
+        auth.enroll_device("URNA-1", &pem).await.unwrap();
+        assert_eq!(auth.identify_device(&der(&pem)).await.unwrap().urna_id, "URNA-1");
+
+        // Novo credenciamento substitui o certificado anterior
+        let renewed = certificate("URNA-1", false).serialize_pem_with_signer(&ca).unwrap();
+        auth.enroll_device("URNA-1", &renewed).await.unwrap();
+        assert!(auth.identify_device(&der(&pem)).await.is_err());
+
+        assert_eq!(auth.revoke_device("URNA-1", "stolen").await.len(), 1);
+        assert!(auth.identify_device(&der(&renewed)).await.is_err());
+        assert_eq!(auth.device_certificates("URNA-1").await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_enrollment_requires_device_ca_and_matching_subject() {
+        let ca = certificate("FORTIS Device CA", true);
+        let rogue_ca = certificate("Rogue CA", true);
+        let auth = service(&ca);
+
+        let rogue = certificate("URNA-1", false).serialize_pem_with_signer(&rogue_ca).unwrap();
+        assert!(auth.enroll_device("URNA-1", &rogue).await.is_err());
+
+        let other = certificate("URNA-2", false).serialize_pem_with_signer(&ca).unwrap();
+        assert!(auth.enroll_device("URNA-1", &other).await.is_err());
+        assert!(auth.identify_device(&der(&other)).await.is_err());
+    }
+}
@@ -0,0 +1,603 @@
+//! Ativação de urna de contingência com transferência de estado
+//!
+//! Quando uma urna falha durante a votação, o estado lacrado (comparecimento,
+//! BU parcial e eleitores já atendidos) é exportado da mídia da urna com
+//! defeito, assinado pela chave RSA dessa urna. A importação na urna
+//! substituta exige aprovação de dois mesários distintos, e o backend
+//! concilia o estado lacrado com os votos que já havia recebido.
+//!
+//! Como os votos chegam cifrados, a contagem do backend é a de cédulas
+//! registradas por urna e eleição; o BU parcial lacrado só é conferido
+//! quanto ao total de cédulas.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose};
+use rsa::{RsaPublicKey, Pkcs1v15Sign};
+use rsa::pkcs8::{DecodePublicKey, EncodePublicKey};
+use sha2::{Sha256, Digest};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use fortis_domain::votes_per_contest;
+
+use crate::monitoring::{ElectionHealthService, HealthSignal};
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Aprovações de mesários exigidas para importar o estado
+pub const REQUIRED_APPROVALS: usize = 2;
+
+/// Escopo no log transparente das trocas de chave das urnas
+const URNA_KEY_LOG_SCOPE: &str = "urna-keys";
+
+/// Estado da urna no momento da falha
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrnaStateSnapshot {
+    pub failed_urna_id: String,
+    pub election_id: String,
+    pub zone: String,
+    pub section: String,
+    pub attendance_count: u64,
+    /// BU parcial: votos por número do votável (inclui brancos e nulos)
+    pub votes_by_candidate: BTreeMap<String, u64>,
+    /// Hashes dos eleitores já atendidos, para impedir voto duplo
+    pub attended_voter_hashes: Vec<String>,
+    pub last_vote_sequence: u64,
+    pub failure_reason: String,
+    pub sealed_at: DateTime<Utc>,
+}
+
+/// Estado lacrado exportado da mídia da urna com defeito
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealedUrnaState {
+    pub snapshot: UrnaStateSnapshot,
+    /// SHA-256 do snapshot serializado, em hexadecimal
+    pub snapshot_hash: String,
+    /// Assinatura RSA PKCS#1 v1.5 (SHA-256) da urna, em base64
+    pub signature: String,
+}
+
+/// Estado da transferência
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    PendingApproval,
+    Approved,
+    Imported,
+    Reconciled,
+}
+
+/// Aprovação de mesário
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MesarioApproval {
+    pub mesario_id: String,
+    pub approved_at: DateTime<Utc>,
+}
+
+/// Cédulas da urna que o backend registrou na eleição
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SectionTally {
+    pub ballots_recorded: u64,
+}
+
+/// Resultado da conciliação entre estado lacrado e backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub consistent: bool,
+    /// Cédulas lacradas que ainda não haviam chegado ao backend
+    pub unsynced_votes: u64,
+    /// Contagem do backend usada na conciliação
+    pub backend_tally: SectionTally,
+    pub reconciled_by: String,
+    pub discrepancies: Vec<String>,
+    pub reconciled_at: DateTime<Utc>,
+}
+
+/// Base com a qual a urna substituta continua a votação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplacementBaseline {
+    pub transfer_id: String,
+    pub replacement_urna_id: String,
+    pub attendance_count: u64,
+    pub votes_by_candidate: BTreeMap<String, u64>,
+    pub attended_voter_hashes: Vec<String>,
+    pub next_vote_sequence: u64,
+}
+
+/// Transferência de estado para urna de contingência
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransfer {
+    pub id: String,
+    pub sealed_state: SealedUrnaState,
+    pub replacement_urna_id: String,
+    pub status: TransferStatus,
+    pub approvals: Vec<MesarioApproval>,
+    pub created_at: DateTime<Utc>,
+    pub imported_at: Option<DateTime<Utc>>,
+    pub reconciliation: Option<ReconciliationReport>,
+}
+
+/// Serviço de contingência de urnas
+pub struct ContingencyService {
+    urna_keys: RwLock<HashMap<String, RsaPublicKey>>,
+    transfers: RwLock<HashMap<String, StateTransfer>>,
+    /// Urnas substituídas e a transferência correspondente
+    retired_urnas: RwLock<HashMap<String, String>>,
+    /// Cédulas registradas por (urna, eleição)
+    recorded: RwLock<HashMap<(String, String), SectionTally>>,
+    transparency_log: Option<Arc<RwLock<ElectionTransparencyLog>>>,
+    health: Option<ElectionHealthService>,
+}
+
+impl ContingencyService {
+    pub fn new() -> Self {
+        Self {
+            urna_keys: RwLock::new(HashMap::new()),
+            transfers: RwLock::new(HashMap::new()),
+            retired_urnas: RwLock::new(HashMap::new()),
+            recorded: RwLock::new(HashMap::new()),
+            transparency_log: None,
+            health: None,
+        }
+    }
+
+    /// Registra as transferências no log transparente
+    pub fn with_transparency_log(mut self, log: Arc<RwLock<ElectionTransparencyLog>>) -> Self {
+        self.transparency_log = Some(log);
+        self
+    }
+
+    /// Informa o resultado das conciliações ao índice de saúde da eleição
+    pub fn with_health_score(mut self, health: ElectionHealthService) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Registra chave pública (PEM) de uma urna
+    ///
+    /// A chave precisa ser a do certificado de dispositivo ativo da urna
+    /// (`device_key`, SPKI em DER). Uma chave já registrada não é substituída
+    /// por aqui; a troca passa por [`Self::rotate_urna_key`].
+    pub async fn register_urna_key(&self, urna_id: &str, public_key_pem: &str, device_key: &[u8]) -> Result<()> {
+        let key = bound_key(urna_id, public_key_pem, device_key)?;
+        let mut keys = self.urna_keys.write().await;
+        match keys.get(urna_id) {
+            Some(current) if *current == key => Ok(()),
+            Some(_) => Err(anyhow!("Urna {} already has a public key; use key rotation", urna_id)),
+            None => {
+                keys.insert(urna_id.to_string(), key);
+                log::info!("Public key registered for urna {}", urna_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Substitui a chave registrada da urna, com registro no log transparente
+    pub async fn rotate_urna_key(
+        &self,
+        urna_id: &str,
+        public_key_pem: &str,
+        device_key: &[u8],
+        reason: &str,
+        actor: &str,
+    ) -> Result<()> {
+        if reason.trim().is_empty() {
+            return Err(anyhow!("Key rotation requires a reason"));
+        }
+        let key = bound_key(urna_id, public_key_pem, device_key)?;
+        let mut keys = self.urna_keys.write().await;
+        let previous = keys.get(urna_id)
+            .ok_or_else(|| anyhow!("No public key registered for urna {}", urna_id))?;
+        if *previous == key {
+            return Err(anyhow!("New key for urna {} is the current key", urna_id));
+        }
+        let previous_sha256 = key_fingerprint(previous)?;
+        let new_sha256 = key_fingerprint(&key)?;
+
+        if let Some(log) = &self.transparency_log {
+            log.write().await.append_election_event(ElectionEvent {
+                id: Uuid::new_v4().to_string(),
+                event_type: ElectionEventType::UrnaKeyRotated,
+                election_id: URNA_KEY_LOG_SCOPE.to_string(),
+                data: serde_json::json!({
+                    "urna_id": urna_id,
+                    "previous_key_sha256": previous_sha256,
+                    "key_sha256": new_sha256,
+                    "reason": reason,
+                    "rotated_by": actor,
+                }),
+                timestamp: Utc::now(),
+                source: "TSE".to_string(),
+            })?;
+        }
+        keys.insert(urna_id.to_string(), key);
+        log::warn!("Public key of urna {} rotated by {}: {}", urna_id, actor, reason);
+        Ok(())
+    }
+
+    /// Verifica assinatura RSA PKCS#1 v1.5 (SHA-256, base64) feita pela urna
+    pub async fn verify_urna_signature(&self, urna_id: &str, data: &[u8], signature_b64: &str) -> Result<()> {
+        let keys = self.urna_keys.read().await;
+        let key = keys
+            .get(urna_id)
+            .ok_or_else(|| anyhow!("No public key registered for urna {}", urna_id))?;
+        let signature = general_purpose::STANDARD.decode(signature_b64)?;
+        key.verify(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(data), &signature)
+            .map_err(|_| anyhow!("Invalid signature for urna {}", urna_id))
+    }
+
+    /// Verifica lacre e consistência interna do estado exportado
+    pub async fn verify_seal(&self, sealed: &SealedUrnaState) -> Result<()> {
+        let snapshot = &sealed.snapshot;
+        let snapshot_bytes = serde_json::to_vec(snapshot)?;
+        let digest = Sha256::digest(&snapshot_bytes);
+        if hex::encode(digest) != sealed.snapshot_hash.to_lowercase() {
+            return Err(anyhow!("Sealed state hash mismatch"));
+        }
+
+        self.verify_urna_signature(&snapshot.failed_urna_id, &snapshot_bytes, &sealed.signature)
+            .await
+            .map_err(|e| anyhow!("Invalid seal: {}", e))?;
+
+        let distinct_voters: HashSet<&String> = snapshot.attended_voter_hashes.iter().collect();
+        if distinct_voters.len() != snapshot.attended_voter_hashes.len()
+            || distinct_voters.len() as u64 != snapshot.attendance_count
+        {
+            return Err(anyhow!("Attendance count does not match attended voters"));
+        }
+        // Cada eleitor vota uma vez em cada cargo da cédula
+        for (contest, votes) in votes_per_contest(&snapshot.votes_by_candidate) {
+            if votes != snapshot.attendance_count {
+                return Err(anyhow!(
+                    "Partial BU has {} votes in contest '{}' for {} attendees",
+                    votes, contest, snapshot.attendance_count
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Inicia transferência para a urna substituta
+    pub async fn begin_transfer(&self, sealed: SealedUrnaState, replacement_urna_id: &str) -> Result<StateTransfer> {
+        self.verify_seal(&sealed).await?;
+
+        let failed_urna_id = sealed.snapshot.failed_urna_id.clone();
+        if failed_urna_id == replacement_urna_id {
+            return Err(anyhow!("Replacement urna must differ from the failed urna"));
+        }
+        if self.retired_urnas.read().await.contains_key(replacement_urna_id) {
+            return Err(anyhow!("Replacement urna {} was already retired", replacement_urna_id));
+        }
+
+        let mut transfers = self.transfers.write().await;
+        if transfers.values().any(|t| t.sealed_state.snapshot.failed_urna_id == failed_urna_id) {
+            return Err(anyhow!("State transfer already exists for urna {}", failed_urna_id));
+        }
+
+        let transfer = StateTransfer {
+            id: Uuid::new_v4().to_string(),
+            sealed_state: sealed,
+            replacement_urna_id: replacement_urna_id.to_string(),
+            status: TransferStatus::PendingApproval,
+            approvals: Vec::new(),
+            created_at: Utc::now(),
+            imported_at: None,
+            reconciliation: None,
+        };
+        transfers.insert(transfer.id.clone(), transfer.clone());
+        Ok(transfer)
+    }
+
+    /// Registra aprovação de mesário (são exigidos dois mesários distintos)
+    pub async fn approve_transfer(&self, transfer_id: &str, mesario_id: &str) -> Result<StateTransfer> {
+        let mut transfers = self.transfers.write().await;
+        let transfer = transfers
+            .get_mut(transfer_id)
+            .ok_or_else(|| anyhow!("State transfer not found: {}", transfer_id))?;
+
+        if transfer.status != TransferStatus::PendingApproval {
+            return Err(anyhow!("State transfer {} is not awaiting approval", transfer_id));
+        }
+        if transfer.approvals.iter().any(|a| a.mesario_id == mesario_id) {
+            return Err(anyhow!("Mesário {} already approved this transfer", mesario_id));
+        }
+
+        transfer.approvals.push(MesarioApproval {
+            mesario_id: mesario_id.to_string(),
+            approved_at: Utc::now(),
+        });
+        if transfer.approvals.len() >= REQUIRED_APPROVALS {
+            transfer.status = TransferStatus::Approved;
+        }
+        Ok(transfer.clone())
+    }
+
+    /// Importa o estado na urna substituta e desativa a urna com defeito
+    pub async fn import_state(&self, transfer_id: &str) -> Result<ReplacementBaseline> {
+        let transfer = {
+            let mut transfers = self.transfers.write().await;
+            let transfer = transfers
+                .get_mut(transfer_id)
+                .ok_or_else(|| anyhow!("State transfer not found: {}", transfer_id))?;
+
+            if transfer.status != TransferStatus::Approved {
+                return Err(anyhow!(
+                    "State transfer {} requires {} mesário approvals before import",
+                    transfer_id, REQUIRED_APPROVALS
+                ));
+            }
+            transfer.status = TransferStatus::Imported;
+            transfer.imported_at = Some(Utc::now());
+            transfer.clone()
+        };
+
+        let snapshot = &transfer.sealed_state.snapshot;
+        self.retired_urnas.write().await
+            .insert(snapshot.failed_urna_id.clone(), transfer.id.clone());
+
+        self.log_event(&snapshot.election_id, &transfer.replacement_urna_id, serde_json::json!({
+            "kind": "contingency_state_imported",
+            "transfer_id": transfer.id,
+            "failed_urna_id": snapshot.failed_urna_id,
+            "replacement_urna_id": transfer.replacement_urna_id,
+            "snapshot_hash": transfer.sealed_state.snapshot_hash,
+            "attendance_count": snapshot.attendance_count,
+            "approved_by": transfer.approvals.iter().map(|a| a.mesario_id.clone()).collect::<Vec<_>>(),
+        })).await?;
+
+        Ok(ReplacementBaseline {
+            transfer_id: transfer.id.clone(),
+            replacement_urna_id: transfer.replacement_urna_id.clone(),
+            attendance_count: snapshot.attendance_count,
+            votes_by_candidate: snapshot.votes_by_candidate.clone(),
+            attended_voter_hashes: snapshot.attended_voter_hashes.clone(),
+            next_vote_sequence: snapshot.last_vote_sequence + 1,
+        })
+    }
+
+    /// Conta uma cédula da urna registrada pelo backend
+    pub async fn record_ballot(&self, urna_id: &str, election_id: &str) {
+        self.recorded.write().await
+            .entry((urna_id.to_string(), election_id.to_string()))
+            .or_default()
+            .ballots_recorded += 1;
+    }
+
+    /// Cédulas da urna que o backend registrou na eleição
+    pub async fn recorded_tally(&self, urna_id: &str, election_id: &str) -> SectionTally {
+        self.recorded.read().await
+            .get(&(urna_id.to_string(), election_id.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Concilia o estado lacrado com as cédulas que o backend registrou da
+    /// urna com defeito
+    pub async fn reconcile(&self, transfer_id: &str, actor: &str) -> Result<ReconciliationReport> {
+        let mut transfers = self.transfers.write().await;
+        let transfer = transfers
+            .get_mut(transfer_id)
+            .ok_or_else(|| anyhow!("State transfer not found: {}", transfer_id))?;
+
+        if transfer.status != TransferStatus::Imported {
+            return Err(anyhow!("State transfer {} has not been imported", transfer_id));
+        }
+
+        let snapshot = &transfer.sealed_state.snapshot;
+        let backend = self.recorded_tally(&snapshot.failed_urna_id, &snapshot.election_id).await;
+        let mut discrepancies = Vec::new();
+
+        // O backend pode estar atrasado, mas nunca à frente do estado lacrado
+        if backend.ballots_recorded > snapshot.attendance_count {
+            discrepancies.push(format!(
+                "Backend recorded {} ballots but sealed attendance is {}",
+                backend.ballots_recorded, snapshot.attendance_count
+            ));
+        }
+        let unsynced_votes = snapshot.attendance_count.saturating_sub(backend.ballots_recorded);
+
+        let report = ReconciliationReport {
+            consistent: discrepancies.is_empty(),
+            unsynced_votes,
+            backend_tally: backend,
+            reconciled_by: actor.to_string(),
+            discrepancies,
+            reconciled_at: Utc::now(),
+        };
+        transfer.status = TransferStatus::Reconciled;
+        transfer.reconciliation = Some(report.clone());
+
+        let election_id = snapshot.election_id.clone();
+        let source = transfer.replacement_urna_id.clone();
+        let data = serde_json::json!({
+            "kind": "contingency_state_reconciled",
+            "transfer_id": transfer.id,
+            "consistent": report.consistent,
+            "unsynced_votes": report.unsynced_votes,
+            "ballots_recorded": report.backend_tally.ballots_recorded,
+            "discrepancies": report.discrepancies,
+            "reconciled_by": actor,
+        });
+        drop(transfers);
+
+        if !report.consistent {
+            log::warn!("Conciliação de contingência inconsistente: {}", transfer_id);
+        }
+        if let Some(health) = &self.health {
+            health.record(&election_id, HealthSignal::Reconciliation { consistent: report.consistent }).await;
+        }
+        self.log_event(&election_id, &source, data).await?;
+        Ok(report)
+    }
+
+    /// Indica se a urna foi substituída e não pode mais enviar votos
+    pub async fn is_retired(&self, urna_id: &str) -> bool {
+        self.retired_urnas.read().await.contains_key(urna_id)
+    }
+
+    /// Obtém transferência
+    pub async fn get_transfer(&self, transfer_id: &str) -> Option<StateTransfer> {
+        self.transfers.read().await.get(transfer_id).cloned()
+    }
+
+    /// Lista transferências
+    pub async fn list_transfers(&self) -> Vec<StateTransfer> {
+        let transfers = self.transfers.read().await;
+        let mut result: Vec<StateTransfer> = transfers.values().cloned().collect();
+        result.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        result
+    }
+
+    async fn log_event(&self, election_id: &str, source: &str, data: serde_json::Value) -> Result<()> {
+        if let Some(log) = &self.transparency_log {
+            log.write().await.append_election_event(ElectionEvent {
+                id: Uuid::new_v4().to_string(),
+                event_type: ElectionEventType::SystemEvent,
+                election_id: election_id.to_string(),
+                data,
+                timestamp: Utc::now(),
+                source: source.to_string(),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Lê a chave e confere que é a do certificado de dispositivo da urna
+fn bound_key(urna_id: &str, public_key_pem: &str, device_key: &[u8]) -> Result<RsaPublicKey> {
+    let key = RsaPublicKey::from_public_key_pem(public_key_pem)
+        .map_err(|e| anyhow!("Invalid public key for urna {}: {}", urna_id, e))?;
+    let certified = RsaPublicKey::from_public_key_der(device_key)
+        .map_err(|e| anyhow!("Device certificate of urna {} has no RSA key: {}", urna_id, e))?;
+    if key != certified {
+        return Err(anyhow!("Public key does not match the device certificate of urna {}", urna_id));
+    }
+    Ok(key)
+}
+
+fn key_fingerprint(key: &RsaPublicKey) -> Result<String> {
+    Ok(hex::encode(Sha256::digest(key.to_public_key_der()?.as_bytes())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::RsaPrivateKey;
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+
+    fn sealed_state(private_key: &RsaPrivateKey) -> SealedUrnaState {
+        let snapshot = UrnaStateSnapshot {
+            failed_urna_id: "urna-1".to_string(),
+            election_id: "e1".to_string(),
+            zone: "0001".to_string(),
+            section: "0042".to_string(),
+            attendance_count: 3,
+            votes_by_candidate: [("13".to_string(), 2), ("BRANCO".to_string(), 1)].into_iter().collect(),
+            attended_voter_hashes: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            last_vote_sequence: 3,
+            failure_reason: "Falha no leitor biométrico".to_string(),
+            sealed_at: Utc::now(),
+        };
+        let digest = Sha256::digest(serde_json::to_vec(&snapshot).unwrap());
+        let signature = private_key.sign(Pkcs1v15Sign::new::<Sha256>(), &digest).unwrap();
+        SealedUrnaState {
+            snapshot,
+            snapshot_hash: hex::encode(digest),
+            signature: general_purpose::STANDARD.encode(signature),
+        }
+    }
+
+    async fn service_with_key() -> (ContingencyService, RsaPrivateKey) {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        let pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+        let service = ContingencyService::new();
+        service.register_urna_key("urna-1", &pem, public_key.to_public_key_der().unwrap().as_bytes()).await.unwrap();
+        (service, private_key)
+    }
+
+    #[tokio::test]
+    async fn test_registered_key_is_bound_to_device_certificate_and_only_rotated() {
+        let (service, private_key) = service_with_key().await;
+        let device_key = RsaPublicKey::from(&private_key).to_public_key_der().unwrap();
+        let other = RsaPublicKey::from(&RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap());
+        let other_pem = other.to_public_key_pem(LineEnding::LF).unwrap();
+        let other_der = other.to_public_key_der().unwrap();
+
+        // Chave diferente da do certificado de dispositivo
+        assert!(service.register_urna_key("urna-2", &other_pem, device_key.as_bytes()).await.is_err());
+        // Chave registrada não é sobrescrita, mesmo com certificado novo
+        assert!(service.register_urna_key("urna-1", &other_pem, other_der.as_bytes()).await.is_err());
+        assert!(service.verify_seal(&sealed_state(&private_key)).await.is_ok());
+
+        assert!(service.rotate_urna_key("urna-1", &other_pem, other_der.as_bytes(), " ", "admin").await.is_err());
+        service.rotate_urna_key("urna-1", &other_pem, other_der.as_bytes(), "troca da placa", "admin").await.unwrap();
+        assert!(service.verify_seal(&sealed_state(&private_key)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_requires_dual_approval() {
+        let (service, private_key) = service_with_key().await;
+        let transfer = service.begin_transfer(sealed_state(&private_key), "urna-2").await.unwrap();
+
+        service.approve_transfer(&transfer.id, "mesario-1").await.unwrap();
+        assert!(service.approve_transfer(&transfer.id, "mesario-1").await.is_err());
+        assert!(service.import_state(&transfer.id).await.is_err());
+
+        service.approve_transfer(&transfer.id, "mesario-2").await.unwrap();
+        let baseline = service.import_state(&transfer.id).await.unwrap();
+
+        assert_eq!(baseline.attendance_count, 3);
+        assert_eq!(baseline.next_vote_sequence, 4);
+        assert!(service.is_retired("urna-1").await);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_state_is_rejected() {
+        let (service, private_key) = service_with_key().await;
+        let mut sealed = sealed_state(&private_key);
+        sealed.snapshot.votes_by_candidate.insert("13".to_string(), 3);
+
+        assert!(service.begin_transfer(sealed, "urna-2").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconciliation_counts_ballots_recorded_from_the_failed_urna() {
+        let (service, private_key) = service_with_key().await;
+        let transfer = service.begin_transfer(sealed_state(&private_key), "urna-2").await.unwrap();
+        service.approve_transfer(&transfer.id, "mesario-1").await.unwrap();
+        service.approve_transfer(&transfer.id, "mesario-2").await.unwrap();
+        service.import_state(&transfer.id).await.unwrap();
+
+        service.record_ballot("urna-1", "e1").await;
+        // Cédulas de outra urna ou eleição não entram na conciliação
+        service.record_ballot("urna-2", "e1").await;
+        service.record_ballot("urna-1", "e2").await;
+
+        let report = service.reconcile(&transfer.id, "mesario-1").await.unwrap();
+        assert!(report.consistent);
+        assert_eq!(report.backend_tally.ballots_recorded, 1);
+        assert_eq!(report.unsynced_votes, 2);
+        assert!(service.reconcile(&transfer.id, "mesario-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconciliation_flags_ballots_beyond_the_sealed_attendance() {
+        let (service, private_key) = service_with_key().await;
+        let transfer = service.begin_transfer(sealed_state(&private_key), "urna-2").await.unwrap();
+        service.approve_transfer(&transfer.id, "mesario-1").await.unwrap();
+        service.approve_transfer(&transfer.id, "mesario-2").await.unwrap();
+        service.import_state(&transfer.id).await.unwrap();
+        for _ in 0..4 {
+            service.record_ballot("urna-1", "e1").await;
+        }
+
+        let report = service.reconcile(&transfer.id, "mesario-1").await.unwrap();
+        assert!(!report.consistent);
+        assert_eq!(report.unsynced_votes, 0);
+        assert_eq!(report.discrepancies.len(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! Descomissionamento das urnas ao fim do ciclo
+//!
+//! A urna envia o relatório assinado depois de exportar o pacote final de
+//! provas e apagar chaves e dados. O backend confere a assinatura com a
+//! chave registrada da urna e compara os lotes e a quantidade de votos
+//! declarados com os lotes que recebeu. Divergência não recusa o relatório
+//! (a urna já foi apagada): fica registrada para a auditoria. A urna sai da
+//! seção e deixa de enviar votos e lotes.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+pub use fortis_domain::decommission::{DecommissionReport, SignedDecommissionReport};
+
+use crate::services::regions::RegionService;
+use crate::services::urna::ContingencyService;
+use crate::services::vote_batches::VoteBatchService;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Resultado da conferência do relatório
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecommissionStatus {
+    Completed,
+    CompletedWithDiscrepancies,
+}
+
+/// Descomissionamento registrado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecommissionRecord {
+    pub report: DecommissionReport,
+    pub signature: String,
+    pub status: DecommissionStatus,
+    pub discrepancies: Vec<String>,
+    /// Votos selados nos lotes recebidos pelo backend
+    pub sealed_votes: u64,
+    /// Posição do evento no log transparente, por eleição
+    pub log_indices: HashMap<Uuid, u64>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Serviço de descomissionamento da frota
+pub struct DecommissionService {
+    contingency: Arc<ContingencyService>,
+    batches: Arc<VoteBatchService>,
+    transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
+    regions: Option<Arc<RegionService>>,
+    records: RwLock<HashMap<String, DecommissionRecord>>,
+}
+
+impl DecommissionService {
+    pub fn new(
+        contingency: Arc<ContingencyService>,
+        batches: Arc<VoteBatchService>,
+        transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
+    ) -> Self {
+        Self {
+            contingency,
+            batches,
+            transparency_log,
+            regions: None,
+            records: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Libera a seção da urna descomissionada
+    pub fn with_regions(mut self, regions: Arc<RegionService>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    /// Confere e registra o relatório; o reenvio do mesmo relatório é aceito
+    pub async fn record(&self, signed: SignedDecommissionReport) -> Result<DecommissionRecord> {
+        let SignedDecommissionReport { report, signature } = signed;
+        self.contingency
+            .verify_urna_signature(&report.urna_id, &report.signed_message(), &signature)
+            .await?;
+
+        let mut records = self.records.write().await;
+        if let Some(existing) = records.get(&report.urna_id) {
+            if existing.report == report {
+                return Ok(existing.clone());
+            }
+            return Err(anyhow!("Urna {} was already decommissioned", report.urna_id));
+        }
+
+        // Lotes declarados pela urna contra os recebidos
+        let stored = self.batches.batches_for_urna(&report.urna_id).await;
+        let mut discrepancies = Vec::new();
+        let mut last_sequences: HashMap<Uuid, u64> = HashMap::new();
+        for batch in &stored {
+            let last = last_sequences.entry(batch.batch.election_id).or_default();
+            *last = (*last).max(batch.batch.sequence);
+        }
+        for (election_id, declared) in &report.batch_sequences {
+            let received = last_sequences.get(election_id).copied().unwrap_or(0);
+            if received != *declared {
+                discrepancies.push(format!(
+                    "Election {}: urna sealed {} batches, backend received {}",
+                    election_id, declared, received
+                ));
+            }
+        }
+        for election_id in last_sequences.keys().filter(|id| !report.batch_sequences.contains_key(id)) {
+            discrepancies.push(format!("Election {}: batches received but missing from the report", election_id));
+        }
+        let sealed_votes: u64 = stored.iter().map(|b| b.batch.tracking_codes.len() as u64).sum();
+        if sealed_votes != report.vote_count {
+            discrepancies.push(format!(
+                "Proof bundle has {} votes, backend batches seal {}",
+                report.vote_count, sealed_votes
+            ));
+        }
+        let status = if discrepancies.is_empty() {
+            DecommissionStatus::Completed
+        } else {
+            log::warn!("Urna {} decommissioned with discrepancies: {:?}", report.urna_id, discrepancies);
+            DecommissionStatus::CompletedWithDiscrepancies
+        };
+
+        if let Some(regions) = &self.regions {
+            if let Ok(section) = regions.unassign_urna(&report.urna_id).await {
+                log::info!("Urna {} released from section {}", report.urna_id, section);
+            }
+        }
+
+        let now = Utc::now();
+        let mut log_indices = HashMap::new();
+        {
+            let mut log = self.transparency_log.write().await;
+            for election_id in report.batch_sequences.keys() {
+                let proof = log.append_election_event(ElectionEvent {
+                    id: Uuid::new_v4().to_string(),
+                    event_type: ElectionEventType::UrnaDecommissioned,
+                    election_id: election_id.to_string(),
+                    data: serde_json::json!({
+                        "urna_id": report.urna_id,
+                        "bundle_sha256": report.bundle_sha256,
+                        "vote_count": report.vote_count,
+                        "erased": report.erased,
+                        "completed_at": report.completed_at,
+                        "status": status,
+                        "discrepancies": discrepancies,
+                    }),
+                    timestamp: now,
+                    source: "Urna".to_string(),
+                })?;
+                log_indices.insert(*election_id, proof.log_index);
+            }
+        }
+
+        log::info!(
+            "Urna {} decommissioned: {} votes, bundle {}",
+            report.urna_id, report.vote_count, report.bundle_sha256
+        );
+        let record = DecommissionRecord {
+            report,
+            signature,
+            status,
+            discrepancies,
+            sealed_votes,
+            log_indices,
+            received_at: now,
+        };
+        records.insert(record.report.urna_id.clone(), record.clone());
+        Ok(record)
+    }
+
+    /// Indica se a urna já foi apagada e não pode mais enviar votos
+    pub async fn is_decommissioned(&self, urna_id: &str) -> bool {
+        self.records.read().await.contains_key(urna_id)
+    }
+
+    pub async fn get(&self, urna_id: &str) -> Option<DecommissionRecord> {
+        self.records.read().await.get(urna_id).cloned()
+    }
+
+    /// Descomissionamentos recebidos, dos mais antigos para os mais novos
+    pub async fn list(&self) -> Vec<DecommissionRecord> {
+        let mut records: Vec<DecommissionRecord> = self.records.read().await.values().cloned().collect();
+        records.sort_by_key(|record| record.received_at);
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::receipts::{ballot_hash, ReceiptService};
+    use crate::services::vote_batches::{SignedVoteBatch, VoteBatch};
+    use crate::transparency::election_logs::LogConfig;
+    use base64::{Engine as _, engine::general_purpose};
+    use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+    use sha2::{Digest, Sha256};
+    use std::collections::BTreeMap;
+
+    fn sign(private_key: &RsaPrivateKey, message: &[u8]) -> String {
+        let signature = private_key.sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(message)).unwrap();
+        general_purpose::STANDARD.encode(signature)
+    }
+
+    #[tokio::test]
+    async fn test_report_is_checked_against_received_batches() {
+        let log = Arc::new(RwLock::new(ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        })));
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        let pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+        let contingency = Arc::new(ContingencyService::new());
+        contingency.register_urna_key("urna-1", &pem, public_key.to_public_key_der().unwrap().as_bytes()).await.unwrap();
+        let receipts = Arc::new(ReceiptService::new(log.clone()));
+        let batches = Arc::new(VoteBatchService::new(log.clone(), contingency.clone(), receipts.clone()));
+        let service = DecommissionService::new(contingency, batches.clone(), log);
+
+        let election_id = Uuid::new_v4();
+        let code = receipts.record_vote(Uuid::new_v4(), election_id, &ballot_hash(b"a"), None).await.unwrap().tracking_code;
+        let batch = VoteBatch::new("urna-1", election_id, 1, vec![code]).unwrap();
+        let signature = sign(&private_key, &batch.signed_message());
+        batches.submit(SignedVoteBatch { batch, signature }).await.unwrap();
+
+        let report = |vote_count| DecommissionReport {
+            urna_id: "urna-1".to_string(),
+            bundle_sha256: "ab".repeat(32),
+            vote_count,
+            batch_sequences: BTreeMap::from([(election_id, 1)]),
+            erased: vec!["tpm_sealing_key".to_string(), "outbox".to_string()],
+            completed_at: Utc::now(),
+        };
+
+        let forged = SignedDecommissionReport { report: report(1), signature: sign(&private_key, b"other") };
+        assert!(service.record(forged).await.is_err());
+        assert!(!service.is_decommissioned("urna-1").await);
+
+        let short = report(2);
+        let signed = SignedDecommissionReport { signature: sign(&private_key, &short.signed_message()), report: short };
+        let record = service.record(signed.clone()).await.unwrap();
+        assert_eq!(record.status, DecommissionStatus::CompletedWithDiscrepancies);
+        assert_eq!(record.sealed_votes, 1);
+        assert!(record.log_indices.contains_key(&election_id));
+        assert!(service.is_decommissioned("urna-1").await);
+
+        // Reenvio do mesmo relatório após queda do link
+        assert_eq!(service.record(signed).await.unwrap().received_at, record.received_at);
+        let other = report(1);
+        let signed = SignedDecommissionReport { signature: sign(&private_key, &other.signed_message()), report: other };
+        assert!(service.record(signed).await.is_err());
+    }
+}
@@ -0,0 +1,378 @@
+//! Diagnóstico remoto da frota pelos códigos de falha
+//!
+//! As contagens por código que chegam nos heartbeats são guardadas com o
+//! modelo da urna, vindo do cadastro de provisionamento, e com a seção. A
+//! frequência de cada código por modelo mostra onde a falha se concentra
+//! (um lote de leitores, um modelo de impressora), e cada código traz os
+//! passos de campo do catálogo de `fortis_domain::diagnostics`. Códigos fora
+//! do catálogo são contados do mesmo jeito, sem categoria nem runbook.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use tokio::sync::RwLock;
+
+use fortis_domain::{fault_code, FaultCategory, FaultCode, FaultReport, FaultSeverity, SectionRef, UrnaHeartbeat};
+use crate::auth::rbac::DataScope;
+
+/// Janela das consultas sem `since`
+const DEFAULT_WINDOW_HOURS: i64 = 24;
+
+/// Ocorrências mantidas em memória
+const RETENTION_DAYS: i64 = 30;
+
+/// Ocorrências de um código reportadas em um heartbeat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaultSample {
+    pub urna_id: String,
+    pub model: Option<String>,
+    pub section: Option<SectionRef>,
+    #[serde(flatten)]
+    pub report: FaultReport,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Frequência de um código em um modelo de urna
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeFrequency {
+    pub code: String,
+    pub model: Option<String>,
+    /// `None` para código fora do catálogo
+    pub category: Option<FaultCategory>,
+    pub severity: Option<FaultSeverity>,
+    pub occurrences: u64,
+    /// Urnas distintas que reportaram o código
+    pub urnas: usize,
+    pub last_seen: DateTime<Utc>,
+    pub runbook: &'static [&'static str],
+}
+
+/// Código reportado por uma urna, com a entrada do catálogo
+#[derive(Debug, Clone, Serialize)]
+pub struct UrnaFaultSummary {
+    pub code: String,
+    pub occurrences: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub fault: Option<&'static FaultCode>,
+}
+
+/// Filtro da frequência
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FrequencyQuery {
+    pub since: Option<DateTime<Utc>>,
+    pub model: Option<String>,
+    pub category: Option<FaultCategory>,
+    pub code: Option<String>,
+}
+
+/// Serviço de diagnóstico da frota
+pub struct DiagnosticsService {
+    samples: RwLock<VecDeque<FaultSample>>,
+    db: Option<PgPool>,
+}
+
+impl Default for DiagnosticsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiagnosticsService {
+    pub fn new() -> Self {
+        Self {
+            samples: RwLock::new(VecDeque::new()),
+            db: None,
+        }
+    }
+
+    /// Grava as ocorrências em `monitoring.urna_faults`
+    pub fn with_database(mut self, pool: PgPool) -> Self {
+        self.db = Some(pool);
+        self
+    }
+
+    /// Carrega as ocorrências do período de retenção; usado na inicialização
+    pub async fn restore(&self) -> Result<usize> {
+        let Some(pool) = &self.db else {
+            return Ok(0);
+        };
+        let rows = sqlx::query(
+            r#"
+            SELECT urna_id, model, state, municipality_code, zone, section,
+                   code, occurrences, first_seen, last_seen, received_at
+            FROM monitoring.urna_faults
+            WHERE received_at >= $1
+            ORDER BY received_at
+            "#,
+        )
+        .bind(Utc::now() - Duration::days(RETENTION_DAYS))
+        .fetch_all(pool)
+        .await?;
+
+        let mut samples = self.samples.write().await;
+        for row in &rows {
+            let section = match (
+                row.get::<Option<String>, _>("state"),
+                row.get::<Option<String>, _>("municipality_code"),
+                row.get::<Option<String>, _>("zone"),
+                row.get::<Option<String>, _>("section"),
+            ) {
+                (Some(state), Some(municipality_code), Some(zone), Some(section)) => {
+                    SectionRef::new(&state, &municipality_code, &zone, &section).ok()
+                }
+                _ => None,
+            };
+            samples.push_back(FaultSample {
+                urna_id: row.get("urna_id"),
+                model: row.get("model"),
+                section,
+                report: FaultReport {
+                    code: row.get("code"),
+                    count: row.get::<i64, _>("occurrences") as u64,
+                    first_seen: row.get("first_seen"),
+                    last_seen: row.get("last_seen"),
+                },
+                received_at: row.get("received_at"),
+            });
+        }
+        Ok(rows.len())
+    }
+
+    /// Registra as contagens do heartbeat; devolve quantos códigos foram reportados
+    pub async fn record(
+        &self,
+        heartbeat: &UrnaHeartbeat,
+        model: Option<String>,
+        section: Option<&SectionRef>,
+    ) -> Result<usize> {
+        let received_at = Utc::now();
+        let samples: Vec<FaultSample> = heartbeat.faults.iter()
+            .filter(|report| report.count > 0)
+            .map(|report| FaultSample {
+                urna_id: heartbeat.urna_id.clone(),
+                model: model.clone(),
+                section: section.cloned(),
+                report: report.clone(),
+                received_at,
+            })
+            .collect();
+
+        for sample in &samples {
+            if fault_code(&sample.report.code).is_none() {
+                log::warn!("Urna {} reported uncatalogued fault code {}", sample.urna_id, sample.report.code);
+            }
+            self.persist(sample).await?;
+        }
+
+        let recorded = samples.len();
+        let mut stored = self.samples.write().await;
+        stored.extend(samples);
+        let cutoff = received_at - Duration::days(RETENTION_DAYS);
+        while stored.front().is_some_and(|sample| sample.received_at < cutoff) {
+            stored.pop_front();
+        }
+        Ok(recorded)
+    }
+
+    /// Frequência de cada código por modelo no recorte, da mais alta para a mais baixa
+    pub async fn frequency(&self, scope: &DataScope, query: &FrequencyQuery) -> Vec<CodeFrequency> {
+        let since = query.since.unwrap_or_else(|| Utc::now() - Duration::hours(DEFAULT_WINDOW_HOURS));
+        let mut groups: BTreeMap<(String, Option<String>), (u64, HashSet<String>, DateTime<Utc>)> = BTreeMap::new();
+
+        let samples = self.samples.read().await;
+        for sample in samples.iter().filter(|sample| sample.received_at >= since && visible(scope, sample)) {
+            let code = &sample.report.code;
+            let category = fault_code(code).map(|fault| fault.category);
+            if query.model.as_deref().is_some_and(|model| sample.model.as_deref() != Some(model))
+                || query.code.as_deref().is_some_and(|wanted| wanted != code)
+                || query.category.is_some_and(|wanted| category != Some(wanted))
+            {
+                continue;
+            }
+            let group = groups
+                .entry((code.clone(), sample.model.clone()))
+                .or_insert_with(|| (0, HashSet::new(), sample.report.last_seen));
+            group.0 += sample.report.count;
+            group.1.insert(sample.urna_id.clone());
+            group.2 = group.2.max(sample.report.last_seen);
+        }
+
+        let mut frequencies: Vec<CodeFrequency> = groups.into_iter()
+            .map(|((code, model), (occurrences, urnas, last_seen))| {
+                let fault = fault_code(&code);
+                CodeFrequency {
+                    category: fault.map(|fault| fault.category),
+                    severity: fault.map(|fault| fault.severity),
+                    runbook: fault.map(|fault| fault.runbook).unwrap_or_default(),
+                    code,
+                    model,
+                    occurrences,
+                    urnas: urnas.len(),
+                    last_seen,
+                }
+            })
+            .collect();
+        frequencies.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.code.cmp(&b.code)));
+        frequencies
+    }
+
+    /// Códigos reportados pela urna desde `since`, do mais frequente ao menos
+    pub async fn urna(&self, urna_id: &str, since: Option<DateTime<Utc>>) -> Vec<UrnaFaultSummary> {
+        let since = since.unwrap_or_else(|| Utc::now() - Duration::hours(DEFAULT_WINDOW_HOURS));
+        let mut codes: BTreeMap<String, UrnaFaultSummary> = BTreeMap::new();
+
+        let samples = self.samples.read().await;
+        for sample in samples.iter().filter(|sample| sample.urna_id == urna_id && sample.received_at >= since) {
+            let report = &sample.report;
+            codes.entry(report.code.clone())
+                .and_modify(|summary| {
+                    summary.occurrences += report.count;
+                    summary.first_seen = summary.first_seen.min(report.first_seen);
+                    summary.last_seen = summary.last_seen.max(report.last_seen);
+                })
+                .or_insert_with(|| UrnaFaultSummary {
+                    code: report.code.clone(),
+                    occurrences: report.count,
+                    first_seen: report.first_seen,
+                    last_seen: report.last_seen,
+                    fault: fault_code(&report.code),
+                });
+        }
+
+        let mut summaries: Vec<UrnaFaultSummary> = codes.into_values().collect();
+        summaries.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.code.cmp(&b.code)));
+        summaries
+    }
+
+    async fn persist(&self, sample: &FaultSample) -> Result<()> {
+        let Some(pool) = &self.db else {
+            return Ok(());
+        };
+        let section = sample.section.as_ref();
+        sqlx::query(
+            r#"
+            INSERT INTO monitoring.urna_faults
+                (urna_id, model, state, municipality_code, zone, section,
+                 code, occurrences, first_seen, last_seen, received_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+        )
+        .bind(&sample.urna_id)
+        .bind(&sample.model)
+        .bind(section.map(|section| section.state.clone()))
+        .bind(section.map(|section| section.municipality_code.clone()))
+        .bind(section.map(|section| section.zone.clone()))
+        .bind(section.map(|section| section.section.clone()))
+        .bind(&sample.report.code)
+        .bind(sample.report.count as i64)
+        .bind(sample.report.first_seen)
+        .bind(sample.report.last_seen)
+        .bind(sample.received_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+fn visible(scope: &DataScope, sample: &FaultSample) -> bool {
+    scope.allows(None, sample.section.as_ref().map(SectionRef::scope).as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::{Permission, Principal, Role, RoleGrant};
+    use fortis_domain::diagnostics::codes;
+    use fortis_domain::RegionScope;
+
+    fn scope(region: Option<&str>) -> DataScope {
+        let principal = Principal {
+            subject: Some("suporte".to_string()),
+            grants: vec![RoleGrant {
+                role: Role::Auditor,
+                election_id: None,
+                region: region.map(|region| RegionScope::parse(region).unwrap()),
+            }],
+        };
+        let (_, scope) = principal.require_scope(Permission::ReadAudits).unwrap();
+        scope
+    }
+
+    fn heartbeat(urna_id: &str, faults: &[(&str, u64)]) -> UrnaHeartbeat {
+        let now = Utc::now();
+        UrnaHeartbeat {
+            urna_id: urna_id.to_string(),
+            timestamp: now,
+            battery_level: None,
+            on_battery: None,
+            storage_usage: None,
+            network_connectivity: true,
+            errors: Vec::new(),
+            pending_votes: 0,
+            last_sync: None,
+            printer: None,
+            last_error: None,
+            faults: faults.iter()
+                .map(|(code, count)| FaultReport {
+                    code: code.to_string(),
+                    count: *count,
+                    first_seen: now - Duration::minutes(1),
+                    last_seen: now,
+                })
+                .collect(),
+            section: None,
+            endpoints: Vec::new(),
+            failovers: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_frequency_groups_codes_by_model() {
+        let diagnostics = DiagnosticsService::new();
+        let zone_1 = SectionRef::new("SP", "71072", "0001", "0001").unwrap();
+        let zone_2 = SectionRef::new("SP", "71072", "0002", "0001").unwrap();
+        let ue2022 = Some("UE2022".to_string());
+
+        diagnostics.record(&heartbeat("URNA-A", &[(codes::FINGERPRINT_TIMEOUT, 4)]), ue2022.clone(), Some(&zone_1)).await.unwrap();
+        diagnostics.record(&heartbeat("URNA-B", &[(codes::FINGERPRINT_TIMEOUT, 3), (codes::PRINTER_PAPER_OUT, 1)]), ue2022.clone(), Some(&zone_2)).await.unwrap();
+        diagnostics.record(&heartbeat("URNA-C", &[(codes::FINGERPRINT_TIMEOUT, 1), ("LEGACY_FAULT", 2)]), Some("UE2020".to_string()), Some(&zone_2)).await.unwrap();
+
+        let frequency = diagnostics.frequency(&scope(None), &FrequencyQuery::default()).await;
+        let top = &frequency[0];
+        assert_eq!((top.code.as_str(), top.model.as_deref(), top.occurrences, top.urnas), (codes::FINGERPRINT_TIMEOUT, Some("UE2022"), 7, 2));
+        assert_eq!(top.category, Some(FaultCategory::Biometric));
+        assert!(!top.runbook.is_empty());
+
+        let legacy = frequency.iter().find(|entry| entry.code == "LEGACY_FAULT").unwrap();
+        assert_eq!((legacy.category, legacy.runbook.len()), (None, 0));
+
+        let hardware = diagnostics.frequency(&scope(None), &FrequencyQuery {
+            category: Some(FaultCategory::Hardware),
+            ..Default::default()
+        }).await;
+        assert_eq!(hardware.len(), 1);
+        assert_eq!(hardware[0].code, codes::PRINTER_PAPER_OUT);
+
+        // Recorte da zona 0001 só enxerga a URNA-A
+        let zonal = diagnostics.frequency(&scope(Some("SP/71072/0001")), &FrequencyQuery::default()).await;
+        assert_eq!(zonal.len(), 1);
+        assert_eq!((zonal[0].occurrences, zonal[0].urnas), (4, 1));
+    }
+
+    #[tokio::test]
+    async fn test_urna_summary_accumulates_heartbeats() {
+        let diagnostics = DiagnosticsService::new();
+        diagnostics.record(&heartbeat("URNA-A", &[(codes::SYNC_BACKEND_UNREACHABLE, 2)]), None, None).await.unwrap();
+        diagnostics.record(&heartbeat("URNA-A", &[(codes::SYNC_BACKEND_UNREACHABLE, 5), (codes::PRINTER_OFFLINE, 1)]), None, None).await.unwrap();
+        diagnostics.record(&heartbeat("URNA-B", &[(codes::PRINTER_OFFLINE, 9)]), None, None).await.unwrap();
+        assert_eq!(diagnostics.record(&heartbeat("URNA-A", &[]), None, None).await.unwrap(), 0);
+
+        let summary = diagnostics.urna("URNA-A", None).await;
+        assert_eq!(summary.len(), 2);
+        assert_eq!((summary[0].code.as_str(), summary[0].occurrences), (codes::SYNC_BACKEND_UNREACHABLE, 7));
+        assert_eq!(summary[1].fault.unwrap().severity, FaultSeverity::Critical);
+    }
+}
@@ -0,0 +1,553 @@
+//! Saúde da frota de urnas
+//!
+//! Cada heartbeat recebido vira uma amostra da série temporal da urna,
+//! gravada em `monitoring.urna_heartbeats` e mantida em memória para o
+//! painel. A situação da urna vem da amostra mais recente: sem heartbeat
+//! dentro do prazo ela está `offline`; com bateria baixa ou em uso,
+//! impressora indisponível, fila de votos acima do limite, falha recente ou
+//! sem conexão, `degraded`. O painel agrupa por zona eleitoral as urnas
+//! designadas no cadastro de regiões, e urna designada que nunca enviou
+//! heartbeat conta como offline.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use fortis_domain::{HeartbeatError, PrinterState, SectionRef, UrnaHeartbeat};
+use crate::auth::rbac::DataScope;
+use crate::services::regions::RegionService;
+
+const DEFAULT_HISTORY_LIMIT: usize = 500;
+const MAX_HISTORY_LIMIT: usize = 5000;
+
+/// Limites da classificação das urnas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FleetPolicy {
+    /// Sem heartbeat há mais que isso, a urna está offline (três intervalos da urna)
+    pub offline_after_seconds: i64,
+    /// Carga mínima da bateria, em porcentagem
+    pub battery_level_min: f32,
+    pub max_pending_votes: u64,
+    /// Falha mais recente que isso deixa a urna degradada
+    pub error_window_seconds: i64,
+    /// Amostras mantidas em memória por urna (24h a um heartbeat por minuto)
+    pub samples_per_urna: usize,
+}
+
+impl Default for FleetPolicy {
+    fn default() -> Self {
+        Self {
+            offline_after_seconds: 180,
+            battery_level_min: 20.0,
+            max_pending_votes: 100,
+            error_window_seconds: 600,
+            samples_per_urna: 1440,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FleetStatus {
+    Online,
+    Degraded,
+    Offline,
+}
+
+/// Motivo de uma urna estar degradada
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DegradedReason {
+    LowBattery,
+    OnBattery,
+    PrinterUnavailable,
+    SyncBacklog,
+    RecentError,
+    NoConnectivity,
+}
+
+/// Amostra da série temporal de uma urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatSample {
+    pub received_at: DateTime<Utc>,
+    /// Seção designada no cadastro ou, na falta dela, a declarada pela urna
+    pub section: Option<SectionRef>,
+    pub heartbeat: UrnaHeartbeat,
+}
+
+/// Situação de uma urna no painel
+#[derive(Debug, Clone, Serialize)]
+pub struct UrnaFleetEntry {
+    pub urna_id: String,
+    pub section: Option<SectionRef>,
+    pub status: FleetStatus,
+    pub reasons: Vec<DegradedReason>,
+    pub last_seen: Option<DateTime<Utc>>,
+    pub battery_level: Option<f32>,
+    pub printer: Option<PrinterState>,
+    pub pending_votes: Option<u64>,
+    pub last_sync: Option<DateTime<Utc>>,
+    pub last_error: Option<HeartbeatError>,
+}
+
+/// Urnas de uma zona eleitoral
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneFleetSummary {
+    pub state: String,
+    pub municipality_code: String,
+    pub zone: String,
+    pub total: usize,
+    pub online: usize,
+    pub degraded: usize,
+    pub offline: usize,
+    /// Urnas offline e degradadas, as offline primeiro
+    pub attention: Vec<UrnaFleetEntry>,
+}
+
+/// Filtro da lista de urnas
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FleetQuery {
+    pub state: Option<String>,
+    pub zone: Option<String>,
+    pub status: Option<FleetStatus>,
+}
+
+/// Janela da série temporal de uma urna
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+/// Serviço do painel da frota
+pub struct FleetService {
+    policy: FleetPolicy,
+    regions: Option<Arc<RegionService>>,
+    samples: RwLock<HashMap<String, VecDeque<HeartbeatSample>>>,
+    db: Option<PgPool>,
+}
+
+impl FleetService {
+    pub fn new(policy: FleetPolicy) -> Self {
+        Self {
+            policy,
+            regions: None,
+            samples: RwLock::new(HashMap::new()),
+            db: None,
+        }
+    }
+
+    /// Seções e urnas designadas vêm do cadastro de regiões
+    pub fn with_regions(mut self, regions: Arc<RegionService>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    /// Grava a série temporal em `monitoring.urna_heartbeats`
+    pub fn with_database(mut self, pool: PgPool) -> Self {
+        self.db = Some(pool);
+        self
+    }
+
+    /// Carrega a amostra mais recente de cada urna; usado na inicialização
+    pub async fn restore(&self) -> Result<usize> {
+        let Some(pool) = &self.db else {
+            return Ok(0);
+        };
+        let rows = sqlx::query(
+            "SELECT DISTINCT ON (urna_id) sample FROM monitoring.urna_heartbeats ORDER BY urna_id, received_at DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut samples = self.samples.write().await;
+        for row in &rows {
+            let sample: HeartbeatSample = serde_json::from_value(row.get("sample"))?;
+            samples.entry(sample.heartbeat.urna_id.clone()).or_default().push_back(sample);
+        }
+        Ok(rows.len())
+    }
+
+    /// Registra o heartbeat e devolve a situação atual da urna
+    pub async fn record(&self, heartbeat: UrnaHeartbeat) -> Result<UrnaFleetEntry> {
+        let received_at = Utc::now();
+        let urna_id = heartbeat.urna_id.clone();
+        let section = match self.assigned_section(&urna_id).await {
+            Some(section) => Some(section),
+            None => heartbeat.section.clone(),
+        };
+        let sample = HeartbeatSample { received_at, section, heartbeat };
+        self.persist(&sample).await?;
+
+        let entry = self.entry(&urna_id, Some(&sample), received_at);
+        let mut samples = self.samples.write().await;
+        let series = samples.entry(urna_id).or_default();
+        series.push_back(sample);
+        while series.len() > self.policy.samples_per_urna.max(1) {
+            series.pop_front();
+        }
+        Ok(entry)
+    }
+
+    /// Seção da urna: a designada no cadastro ou a do último heartbeat
+    pub async fn section(&self, urna_id: &str) -> Option<SectionRef> {
+        if let Some(section) = self.assigned_section(urna_id).await {
+            return Some(section);
+        }
+        self.samples.read().await
+            .get(urna_id)
+            .and_then(|series| series.back())
+            .and_then(|sample| sample.section.clone())
+    }
+
+    /// Série temporal da urna na janela, da amostra mais antiga à mais recente
+    pub async fn history(&self, urna_id: &str, query: &HistoryQuery) -> Result<Vec<HeartbeatSample>> {
+        let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT);
+        if let Some(pool) = &self.db {
+            let rows = sqlx::query(
+                r#"
+                SELECT sample FROM monitoring.urna_heartbeats
+                WHERE urna_id = $1
+                  AND ($2::timestamptz IS NULL OR received_at >= $2)
+                  AND ($3::timestamptz IS NULL OR received_at <= $3)
+                ORDER BY received_at DESC
+                LIMIT $4
+                "#,
+            )
+            .bind(urna_id)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(limit as i64)
+            .fetch_all(pool)
+            .await?;
+            let mut samples = rows.iter()
+                .map(|row| serde_json::from_value(row.get("sample")))
+                .collect::<Result<Vec<HeartbeatSample>, _>>()?;
+            samples.reverse();
+            return Ok(samples);
+        }
+
+        let samples = self.samples.read().await;
+        let window: Vec<HeartbeatSample> = samples.get(urna_id)
+            .map(|series| {
+                series.iter()
+                    .filter(|sample| query.from.is_none_or(|from| sample.received_at >= from))
+                    .filter(|sample| query.to.is_none_or(|to| sample.received_at <= to))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let skip = window.len().saturating_sub(limit);
+        Ok(window.into_iter().skip(skip).collect())
+    }
+
+    /// Situação das urnas conhecidas (designadas no cadastro ou com heartbeat) no recorte
+    pub async fn urnas(&self, scope: &DataScope, query: &FleetQuery) -> Vec<UrnaFleetEntry> {
+        let now = Utc::now();
+        let mut known: BTreeMap<String, Option<SectionRef>> = BTreeMap::new();
+        if let Some(regions) = &self.regions {
+            for section in regions.listing(None).await.sections {
+                if let Some(urna_id) = section.urna_id {
+                    known.insert(urna_id, Some(section.section));
+                }
+            }
+        }
+
+        let samples = self.samples.read().await;
+        for (urna_id, series) in samples.iter() {
+            let section = series.back().and_then(|sample| sample.section.clone());
+            known.entry(urna_id.clone()).or_insert(section);
+        }
+
+        known.into_iter()
+            .map(|(urna_id, section)| {
+                let latest = samples.get(&urna_id).and_then(|series| series.back());
+                let mut entry = self.entry(&urna_id, latest, now);
+                entry.section = section;
+                entry
+            })
+            .filter(|entry| scope.allows(None, entry.section.as_ref().map(SectionRef::scope).as_ref()))
+            .filter(|entry| matches_query(entry, query))
+            .collect()
+    }
+
+    /// Urnas por zona eleitoral; urnas sem seção conhecida ficam fora do agrupamento
+    pub async fn zones(&self, scope: &DataScope, state: Option<&str>) -> Vec<ZoneFleetSummary> {
+        let query = FleetQuery { state: state.map(str::to_string), ..Default::default() };
+        let mut zones: BTreeMap<(String, String, String), ZoneFleetSummary> = BTreeMap::new();
+        for entry in self.urnas(scope, &query).await {
+            let Some(section) = &entry.section else {
+                continue;
+            };
+            let summary = zones
+                .entry((section.state.clone(), section.municipality_code.clone(), section.zone.clone()))
+                .or_insert_with(|| ZoneFleetSummary {
+                    state: section.state.clone(),
+                    municipality_code: section.municipality_code.clone(),
+                    zone: section.zone.clone(),
+                    total: 0,
+                    online: 0,
+                    degraded: 0,
+                    offline: 0,
+                    attention: Vec::new(),
+                });
+            summary.total += 1;
+            match entry.status {
+                FleetStatus::Online => summary.online += 1,
+                FleetStatus::Degraded => summary.degraded += 1,
+                FleetStatus::Offline => summary.offline += 1,
+            }
+            if entry.status != FleetStatus::Online {
+                summary.attention.push(entry);
+            }
+        }
+
+        zones.into_values()
+            .map(|mut summary| {
+                summary.attention.sort_by(|a, b| b.status.cmp(&a.status).then_with(|| a.urna_id.cmp(&b.urna_id)));
+                summary
+            })
+            .collect()
+    }
+
+    async fn assigned_section(&self, urna_id: &str) -> Option<SectionRef> {
+        match &self.regions {
+            Some(regions) => regions.section_of_urna(urna_id).await,
+            None => None,
+        }
+    }
+
+    fn entry(&self, urna_id: &str, latest: Option<&HeartbeatSample>, now: DateTime<Utc>) -> UrnaFleetEntry {
+        let Some(sample) = latest else {
+            return UrnaFleetEntry {
+                urna_id: urna_id.to_string(),
+                section: None,
+                status: FleetStatus::Offline,
+                reasons: Vec::new(),
+                last_seen: None,
+                battery_level: None,
+                printer: None,
+                pending_votes: None,
+                last_sync: None,
+                last_error: None,
+            };
+        };
+
+        let heartbeat = &sample.heartbeat;
+        let (status, reasons) = if now - sample.received_at > Duration::seconds(self.policy.offline_after_seconds) {
+            (FleetStatus::Offline, Vec::new())
+        } else {
+            let reasons = self.degraded_reasons(heartbeat);
+            let status = if reasons.is_empty() { FleetStatus::Online } else { FleetStatus::Degraded };
+            (status, reasons)
+        };
+        UrnaFleetEntry {
+            urna_id: urna_id.to_string(),
+            section: sample.section.clone(),
+            status,
+            reasons,
+            last_seen: Some(sample.received_at),
+            battery_level: heartbeat.battery_level,
+            printer: heartbeat.printer,
+            pending_votes: Some(heartbeat.pending_votes),
+            last_sync: heartbeat.last_sync,
+            last_error: heartbeat.last_error.clone(),
+        }
+    }
+
+    fn degraded_reasons(&self, heartbeat: &UrnaHeartbeat) -> Vec<DegradedReason> {
+        let mut reasons = Vec::new();
+        if heartbeat.battery_level.is_some_and(|level| level < self.policy.battery_level_min) {
+            reasons.push(DegradedReason::LowBattery);
+        }
+        if heartbeat.on_battery == Some(true) {
+            reasons.push(DegradedReason::OnBattery);
+        }
+        if heartbeat.printer.is_some_and(|printer| !printer.can_print()) {
+            reasons.push(DegradedReason::PrinterUnavailable);
+        }
+        if heartbeat.pending_votes > self.policy.max_pending_votes {
+            reasons.push(DegradedReason::SyncBacklog);
+        }
+        // Os dois instantes vêm do relógio da urna
+        let window = Duration::seconds(self.policy.error_window_seconds);
+        if heartbeat.last_error.as_ref().is_some_and(|error| heartbeat.timestamp - error.occurred_at <= window) {
+            reasons.push(DegradedReason::RecentError);
+        }
+        if !heartbeat.network_connectivity {
+            reasons.push(DegradedReason::NoConnectivity);
+        }
+        reasons
+    }
+
+    async fn persist(&self, sample: &HeartbeatSample) -> Result<()> {
+        let Some(pool) = &self.db else {
+            return Ok(());
+        };
+        let heartbeat = &sample.heartbeat;
+        let section = sample.section.as_ref();
+        sqlx::query(
+            r#"
+            INSERT INTO monitoring.urna_heartbeats
+                (urna_id, received_at, reported_at, state, municipality_code, zone, section,
+                 battery_level, on_battery, printer, pending_votes, last_error_code, sample)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+        )
+        .bind(&heartbeat.urna_id)
+        .bind(sample.received_at)
+        .bind(heartbeat.timestamp)
+        .bind(section.map(|section| section.state.clone()))
+        .bind(section.map(|section| section.municipality_code.clone()))
+        .bind(section.map(|section| section.zone.clone()))
+        .bind(section.map(|section| section.section.clone()))
+        .bind(heartbeat.battery_level)
+        .bind(heartbeat.on_battery)
+        .bind(heartbeat.printer.map(|printer| printer.as_str()))
+        .bind(heartbeat.pending_votes as i64)
+        .bind(heartbeat.last_error.as_ref().map(|error| error.code.clone()))
+        .bind(serde_json::to_value(sample)?)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+fn matches_query(entry: &UrnaFleetEntry, query: &FleetQuery) -> bool {
+    let section = entry.section.as_ref();
+    query.state.as_deref().is_none_or(|state| section.is_some_and(|s| s.state.eq_ignore_ascii_case(state)))
+        && query.zone.as_deref().is_none_or(|zone| section.is_some_and(|s| s.zone == zone.trim()))
+        && query.status.is_none_or(|status| entry.status == status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::{Permission, Principal, Role, RoleGrant};
+    use fortis_domain::RegionScope;
+
+    fn scope(region: Option<&str>) -> DataScope {
+        let principal = Principal {
+            subject: Some("operador".to_string()),
+            grants: vec![RoleGrant {
+                role: Role::Auditor,
+                election_id: None,
+                region: region.map(|region| RegionScope::parse(region).unwrap()),
+            }],
+        };
+        let (_, scope) = principal.require_scope(Permission::ReadAudits).unwrap();
+        scope
+    }
+
+    fn heartbeat(urna_id: &str) -> UrnaHeartbeat {
+        UrnaHeartbeat {
+            urna_id: urna_id.to_string(),
+            timestamp: Utc::now(),
+            battery_level: Some(95.0),
+            on_battery: Some(false),
+            storage_usage: Some(12.0),
+            network_connectivity: true,
+            errors: Vec::new(),
+            pending_votes: 0,
+            last_sync: Some(Utc::now()),
+            printer: Some(PrinterState::Ready),
+            last_error: None,
+            faults: Vec::new(),
+            section: None,
+            endpoints: Vec::new(),
+            failovers: 0,
+        }
+    }
+
+    async fn regions() -> Arc<RegionService> {
+        let regions = Arc::new(RegionService::new());
+        regions.add_state("SP", "São Paulo").await.unwrap();
+        regions.add_municipality("SP", "71072", "São Paulo").await.unwrap();
+        for zone in ["0001", "0002"] {
+            regions.add_zone("SP", "71072", zone, None).await.unwrap();
+        }
+        for (zone, section, urna_id) in [("0001", "0001", "URNA-A"), ("0001", "0002", "URNA-B"), ("0002", "0001", "URNA-C")] {
+            let section = SectionRef::new("SP", "71072", zone, section).unwrap();
+            regions.add_section(section.clone(), None, 300).await.unwrap();
+            regions.assign_urna(&section, urna_id, "admin").await.unwrap();
+        }
+        regions
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_classifies_degraded_urna() {
+        let fleet = FleetService::new(FleetPolicy::default());
+
+        let entry = fleet.record(heartbeat("URNA-A")).await.unwrap();
+        assert_eq!(entry.status, FleetStatus::Online);
+        assert!(entry.reasons.is_empty());
+
+        let mut degraded = heartbeat("URNA-A");
+        degraded.battery_level = Some(12.0);
+        degraded.on_battery = Some(true);
+        degraded.printer = Some(PrinterState::PaperOut);
+        degraded.pending_votes = 250;
+        degraded.last_error = Some(HeartbeatError {
+            code: "HW-PRINTER".to_string(),
+            message: "Sem papel".to_string(),
+            occurred_at: degraded.timestamp - Duration::seconds(30),
+        });
+        let entry = fleet.record(degraded).await.unwrap();
+        assert_eq!(entry.status, FleetStatus::Degraded);
+        assert_eq!(entry.reasons, vec![
+            DegradedReason::LowBattery,
+            DegradedReason::OnBattery,
+            DegradedReason::PrinterUnavailable,
+            DegradedReason::SyncBacklog,
+            DegradedReason::RecentError,
+        ]);
+
+        let history = fleet.history("URNA-A", &HistoryQuery::default()).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].heartbeat.pending_votes, 250);
+    }
+
+    #[tokio::test]
+    async fn test_zone_summary_counts_silent_and_stale_urnas_as_offline() {
+        let fleet = FleetService::new(FleetPolicy::default()).with_regions(regions().await);
+        fleet.record(heartbeat("URNA-A")).await.unwrap();
+        fleet.record(heartbeat("URNA-C")).await.unwrap();
+
+        // Último heartbeat da URNA-C há dez minutos
+        fleet.samples.write().await.get_mut("URNA-C").unwrap()[0].received_at = Utc::now() - Duration::minutes(10);
+
+        let zones = fleet.zones(&scope(None), Some("sp")).await;
+        assert_eq!(zones.len(), 2);
+        assert_eq!((zones[0].zone.as_str(), zones[0].total, zones[0].online, zones[0].offline), ("0001", 2, 1, 1));
+        assert_eq!(zones[0].attention.len(), 1);
+        assert_eq!(zones[0].attention[0].urna_id, "URNA-B");
+        assert_eq!(zones[0].attention[0].last_seen, None);
+        assert_eq!((zones[1].zone.as_str(), zones[1].offline), ("0002", 1));
+
+        let offline = fleet.urnas(&scope(None), &FleetQuery {
+            status: Some(FleetStatus::Offline),
+            ..Default::default()
+        }).await;
+        let ids: Vec<&str> = offline.iter().map(|entry| entry.urna_id.as_str()).collect();
+        assert_eq!(ids, vec!["URNA-B", "URNA-C"]);
+    }
+
+    #[tokio::test]
+    async fn test_registry_section_prevails_and_scope_limits_view() {
+        let fleet = FleetService::new(FleetPolicy::default()).with_regions(regions().await);
+        let mut declared = heartbeat("URNA-C");
+        declared.section = Some(SectionRef::new("SP", "71072", "0001", "0001").unwrap());
+        let entry = fleet.record(declared).await.unwrap();
+        assert_eq!(entry.section.unwrap().zone, "0002");
+
+        let zonal = fleet.urnas(&scope(Some("SP/71072/0002")), &FleetQuery::default()).await;
+        assert_eq!(zonal.len(), 1);
+        assert_eq!(zonal[0].urna_id, "URNA-C");
+        assert!(fleet.zones(&scope(Some("RJ")), None).await.is_empty());
+    }
+}
@@ -4,16 +4,26 @@
 //! das urnas eletrônicas do sistema FORTIS, organizados por funcionalidade.
 
 pub mod auth;
+pub mod contingency;
+pub mod decommission;
+pub mod diagnostics;
+pub mod fleet;
 // pub mod blockchain;
 pub mod monitoring;
+pub mod provisioning;
 pub mod security;
 pub mod sync;
 pub mod service;
 
 // Re-exportar os serviços principais para facilitar o uso
 pub use auth::UrnaAuthService;
+pub use contingency::ContingencyService;
+pub use decommission::DecommissionService;
+pub use diagnostics::DiagnosticsService;
+pub use fleet::FleetService;
 // pub use blockchain::UrnaBlockchainService;
 pub use monitoring::UrnaMonitoringService;
+pub use provisioning::UrnaProvisioningService;
 pub use security::UrnaSecurityService;
 pub use sync::UrnaSyncService;
 pub use service::UrnaService;
@@ -4,6 +4,8 @@ use crate::models::{
     Urna, UrnaHealthCheck, UrnaStatus, PerformanceMetrics, UrnaAuditLog, AuditEventType
 };
 use anyhow::{Result, anyhow};
+use fortis_domain::{EndpointMetrics, EndpointTier};
+use serde::Serialize;
 use uuid::Uuid;
 use chrono::{DateTime, Utc, Duration};
 use std::collections::HashMap;
@@ -13,6 +15,8 @@ use serde_json::json;
 pub struct UrnaMonitoringService {
     pub health_checks: RwLock<HashMap<Uuid, UrnaHealthCheck>>,
     pub performance_metrics: RwLock<HashMap<Uuid, Vec<PerformanceMetrics>>>,
+    /// Backends vistos por cada urna no último heartbeat
+    pub endpoint_reports: RwLock<HashMap<Uuid, EndpointReport>>,
     pub alert_thresholds: AlertThresholds,
     pub monitoring_interval: Duration,
 }
@@ -47,6 +51,7 @@ impl UrnaMonitoringService {
         Self {
             health_checks: RwLock::new(HashMap::new()),
             performance_metrics: RwLock::new(HashMap::new()),
+            endpoint_reports: RwLock::new(HashMap::new()),
             alert_thresholds: AlertThresholds::default(),
             monitoring_interval: Duration::minutes(5),
         }
@@ -78,6 +83,78 @@ impl UrnaMonitoringService {
         Ok(())
     }
 
+    /// Registra a saúde informada pela própria urna
+    pub async fn record_heartbeat(&self, health: UrnaHealthCheck) -> Result<()> {
+        self.performance_metrics.write().await
+            .entry(health.urna_id)
+            .or_insert_with(Vec::new)
+            .push(health.performance_metrics.clone());
+        self.health_checks.write().await.insert(health.urna_id, health);
+        Ok(())
+    }
+
+    /// Registra as medições de backends informadas no heartbeat
+    pub async fn record_endpoints(&self, urna_id: Uuid, endpoints: Vec<EndpointMetrics>, failovers: u64) {
+        let previous_active = self.endpoint_reports.read().await
+            .get(&urna_id)
+            .and_then(|report| report.active().map(str::to_string));
+        let report = EndpointReport {
+            urna_id,
+            reported_at: Utc::now(),
+            endpoints,
+            failovers,
+        };
+        if let (Some(before), Some(now)) = (previous_active, report.active()) {
+            if before != now {
+                log::warn!("Urna {} failed over from {} to {}", urna_id, before, now);
+            }
+        }
+        self.endpoint_reports.write().await.insert(urna_id, report);
+    }
+
+    /// Situação de cada backend segundo os últimos heartbeats das urnas
+    pub async fn backend_reachability(&self) -> Vec<BackendReachability> {
+        let reports = self.endpoint_reports.read().await;
+        let mut backends: Vec<BackendReachability> = Vec::new();
+        let mut latency_sums: HashMap<String, (f64, usize)> = HashMap::new();
+
+        for endpoint in reports.values().flat_map(|report| &report.endpoints) {
+            let index = match backends.iter().position(|b| b.url == endpoint.url) {
+                Some(index) => index,
+                None => {
+                    backends.push(BackendReachability {
+                        url: endpoint.url.clone(),
+                        tier: endpoint.tier,
+                        reporting_urnas: 0,
+                        active_urnas: 0,
+                        unavailable_urnas: 0,
+                        average_latency_ms: None,
+                    });
+                    backends.len() - 1
+                }
+            };
+            let backend = &mut backends[index];
+            backend.reporting_urnas += 1;
+            if endpoint.active {
+                backend.active_urnas += 1;
+            }
+            if !endpoint.available {
+                backend.unavailable_urnas += 1;
+            }
+            if let Some(latency) = endpoint.latency_ms {
+                let sum = latency_sums.entry(endpoint.url.clone()).or_insert((0.0, 0));
+                sum.0 += latency;
+                sum.1 += 1;
+            }
+        }
+
+        for backend in &mut backends {
+            backend.average_latency_ms = latency_sums.get(&backend.url)
+                .map(|(sum, count)| sum / *count as f64);
+        }
+        backends
+    }
+
     pub async fn perform_health_check(&self, urna_id: Uuid) -> Result<UrnaHealthCheck> {
         // Realizar verificação de saúde da urna
         let health_check = UrnaHealthCheck {
@@ -350,12 +427,41 @@ impl Clone for UrnaMonitoringService {
         Self {
             health_checks: RwLock::new(HashMap::new()),
             performance_metrics: RwLock::new(HashMap::new()),
+            endpoint_reports: RwLock::new(HashMap::new()),
             alert_thresholds: self.alert_thresholds.clone(),
             monitoring_interval: self.monitoring_interval,
         }
     }
 }
 
+/// Último relatório de backends de uma urna
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointReport {
+    pub urna_id: Uuid,
+    pub reported_at: DateTime<Utc>,
+    pub endpoints: Vec<EndpointMetrics>,
+    pub failovers: u64,
+}
+
+impl EndpointReport {
+    pub fn active(&self) -> Option<&str> {
+        self.endpoints.iter().find(|e| e.active).map(|e| e.url.as_str())
+    }
+}
+
+/// Backend visto pelo conjunto das urnas
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendReachability {
+    pub url: String,
+    pub tier: EndpointTier,
+    pub reporting_urnas: usize,
+    /// Urnas que sincronizam por este backend
+    pub active_urnas: usize,
+    /// Urnas que afastaram o backend após falhas seguidas
+    pub unavailable_urnas: usize,
+    pub average_latency_ms: Option<f64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct HealthReport {
     pub total_urnas: usize,
@@ -364,3 +470,48 @@ pub struct HealthReport {
     pub urna_with_issues: usize,
     pub generated_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(url: &str, tier: EndpointTier, active: bool, available: bool, latency_ms: Option<f64>) -> EndpointMetrics {
+        EndpointMetrics {
+            url: url.to_string(),
+            tier,
+            active,
+            available,
+            latency_ms,
+            successes: 0,
+            failures: 0,
+            consecutive_failures: if available { 0 } else { 3 },
+            last_error: None,
+            last_success: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_regional_outage_shows_urnas_failed_over_to_national() {
+        let monitoring = UrnaMonitoringService::new();
+        let regional = "https://sp.api.fortis.gov.br";
+        let national = "https://api.fortis.gov.br";
+
+        monitoring.record_endpoints(Uuid::new_v4(), vec![
+            endpoint(regional, EndpointTier::Regional, false, false, Some(40.0)),
+            endpoint(national, EndpointTier::National, true, true, Some(180.0)),
+        ], 1).await;
+        monitoring.record_endpoints(Uuid::new_v4(), vec![
+            endpoint(regional, EndpointTier::Regional, true, true, Some(60.0)),
+            endpoint(national, EndpointTier::National, false, true, None),
+        ], 0).await;
+
+        let backends = monitoring.backend_reachability().await;
+        let regional = backends.iter().find(|b| b.url == regional).unwrap();
+        assert_eq!((regional.reporting_urnas, regional.active_urnas, regional.unavailable_urnas), (2, 1, 1));
+        assert_eq!(regional.average_latency_ms, Some(50.0));
+
+        let national = backends.iter().find(|b| b.url == national).unwrap();
+        assert_eq!((national.active_urnas, national.unavailable_urnas), (1, 0));
+        assert_eq!(national.average_latency_ms, Some(180.0));
+    }
+}
@@ -0,0 +1,416 @@
+//! Provisionamento remoto das urnas
+//!
+//! Acompanha cada urna do registro até a configuração aplicada:
+//!
+//! 1. `Registered`: o equipamento é cadastrado pelo número de série;
+//! 2. `CredentialsIssued`: é emitida uma credencial de uso único, entregue
+//!    ao técnico que prepara a urna (só o resumo fica no backend);
+//! 3. `Enrolled`: a urna apresenta a credencial com o certificado de
+//!    dispositivo e a chave de selagem do TPM, que são credenciados;
+//! 4. `ConfigurationPushed`: é gerado o pacote de provisionamento com o
+//!    pacote da eleição, as funcionalidades habilitadas e os backends de
+//!    sincronização;
+//! 5. `Provisioned` ou `Failed`: a urna confirma (ou não) a aplicação do
+//!    pacote. Após falha, ou para reconfigurar, um novo pacote pode ser
+//!    enviado.
+//!
+//! Em qualquer etapa a urna pode ser revogada, o que revoga também os
+//! certificados de dispositivo; a revogação é definitiva.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use fortis_domain::EndpointTier;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::services::provisioning::{BundleRequest, ProvisioningBundle, ProvisioningService};
+use crate::services::urna::auth::UrnaAuthService;
+
+/// Validade da credencial de credenciamento
+const CREDENTIAL_TTL_HOURS: i64 = 72;
+
+/// Etapa do provisionamento da urna
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvisioningState {
+    Registered,
+    CredentialsIssued,
+    Enrolled,
+    ConfigurationPushed,
+    Provisioned,
+    Failed,
+    Revoked,
+}
+
+impl ProvisioningState {
+    /// Transições permitidas pela máquina de estados
+    pub fn can_transition(self, to: ProvisioningState) -> bool {
+        use ProvisioningState::*;
+        match (self, to) {
+            (Revoked, _) => false,
+            (_, Revoked) => true,
+            // Nova credencial: perdida, expirada ou troca de certificado
+            (Registered | CredentialsIssued | Enrolled | Provisioned | Failed, CredentialsIssued) => true,
+            (CredentialsIssued, Enrolled) => true,
+            (Enrolled | Provisioned | Failed | ConfigurationPushed, ConfigurationPushed) => true,
+            (ConfigurationPushed, Provisioned | Failed) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Backend de sincronização entregue à urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncEndpoint {
+    pub url: String,
+    pub tier: EndpointTier,
+}
+
+/// Cadastro de uma urna para provisionamento
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterUrnaRequest {
+    pub urna_id: String,
+    pub serial_number: String,
+    pub model: Option<String>,
+}
+
+/// Credenciamento feito pela própria urna com a credencial emitida
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnrollUrnaRequest {
+    pub credential: String,
+    pub certificate_pem: String,
+    pub sealing_key_pem: String,
+}
+
+/// Configuração a enviar para a urna
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushConfigurationRequest {
+    #[serde(flatten)]
+    pub bundle: BundleRequest,
+    #[serde(default)]
+    pub feature_flags: BTreeMap<String, bool>,
+    pub sync_endpoints: Vec<SyncEndpoint>,
+}
+
+/// Confirmação da urna sobre o pacote aplicado
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProvisioningAck {
+    pub bundle_id: Uuid,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Credencial emitida; o segredo só aparece nesta resposta
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedCredential {
+    pub urna_id: String,
+    pub credential: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Mudança de etapa registrada no histórico da urna
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvisioningTransition {
+    pub from: Option<ProvisioningState>,
+    pub to: ProvisioningState,
+    pub actor: String,
+    pub detail: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// Situação do provisionamento de uma urna
+#[derive(Debug, Clone, Serialize)]
+pub struct UrnaProvisioning {
+    pub urna_id: String,
+    pub serial_number: String,
+    pub model: Option<String>,
+    pub state: ProvisioningState,
+    pub credential_expires_at: Option<DateTime<Utc>>,
+    /// Impressão digital do certificado de dispositivo credenciado
+    pub certificate_fingerprint: Option<String>,
+    pub sealing_key_fingerprint: Option<String>,
+    pub election_id: Option<Uuid>,
+    pub bundle_id: Option<Uuid>,
+    pub feature_flags: BTreeMap<String, bool>,
+    pub sync_endpoints: Vec<SyncEndpoint>,
+    pub last_error: Option<String>,
+    pub history: Vec<ProvisioningTransition>,
+    pub registered_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip)]
+    credential_sha256: Option<String>,
+}
+
+impl UrnaProvisioning {
+    fn transition(&mut self, to: ProvisioningState, actor: &str, detail: Option<String>) -> Result<()> {
+        if !self.state.can_transition(to) {
+            return Err(anyhow!(
+                "Urna {} cannot go from {:?} to {:?}",
+                self.urna_id, self.state, to
+            ));
+        }
+        let now = Utc::now();
+        self.history.push(ProvisioningTransition {
+            from: Some(self.state),
+            to,
+            actor: actor.to_string(),
+            detail,
+            at: now,
+        });
+        self.state = to;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+/// Serviço de provisionamento remoto das urnas
+pub struct UrnaProvisioningService {
+    provisioning: Arc<ProvisioningService>,
+    auth: Arc<UrnaAuthService>,
+    urnas: RwLock<HashMap<String, UrnaProvisioning>>,
+}
+
+impl UrnaProvisioningService {
+    pub fn new(provisioning: Arc<ProvisioningService>, auth: Arc<UrnaAuthService>) -> Self {
+        Self {
+            provisioning,
+            auth,
+            urnas: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Cadastra a urna para provisionamento
+    pub async fn register(&self, request: RegisterUrnaRequest, actor: &str) -> Result<UrnaProvisioning> {
+        if request.urna_id.trim().is_empty() || request.serial_number.trim().is_empty() {
+            return Err(anyhow!("Urna ID and serial number are required"));
+        }
+        let mut urnas = self.urnas.write().await;
+        if urnas.contains_key(&request.urna_id) {
+            return Err(anyhow!("Urna {} already registered", request.urna_id));
+        }
+        if let Some(other) = urnas.values().find(|urna| urna.serial_number == request.serial_number) {
+            return Err(anyhow!("Serial number {} already registered for urna {}", request.serial_number, other.urna_id));
+        }
+
+        let now = Utc::now();
+        let urna = UrnaProvisioning {
+            urna_id: request.urna_id.clone(),
+            serial_number: request.serial_number,
+            model: request.model,
+            state: ProvisioningState::Registered,
+            credential_expires_at: None,
+            certificate_fingerprint: None,
+            sealing_key_fingerprint: None,
+            election_id: None,
+            bundle_id: None,
+            feature_flags: BTreeMap::new(),
+            sync_endpoints: Vec::new(),
+            last_error: None,
+            history: vec![ProvisioningTransition {
+                from: None,
+                to: ProvisioningState::Registered,
+                actor: actor.to_string(),
+                detail: None,
+                at: now,
+            }],
+            registered_at: now,
+            updated_at: now,
+            credential_sha256: None,
+        };
+        urnas.insert(request.urna_id.clone(), urna.clone());
+        log::info!("Urna {} registered for provisioning by {}", urna.urna_id, actor);
+        Ok(urna)
+    }
+
+    /// Emite a credencial de uso único, invalidando a anterior
+    pub async fn issue_credentials(&self, urna_id: &str, actor: &str) -> Result<IssuedCredential> {
+        let mut urnas = self.urnas.write().await;
+        let urna = urnas.get_mut(urna_id)
+            .ok_or_else(|| anyhow!("Urna {} not registered", urna_id))?;
+
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        let credential = hex::encode(secret);
+        let expires_at = Utc::now() + Duration::hours(CREDENTIAL_TTL_HOURS);
+
+        urna.transition(ProvisioningState::CredentialsIssued, actor, None)?;
+        urna.credential_sha256 = Some(credential_sha256(&credential));
+        urna.credential_expires_at = Some(expires_at);
+        log::info!("Provisioning credential issued for urna {} by {}", urna_id, actor);
+
+        Ok(IssuedCredential {
+            urna_id: urna_id.to_string(),
+            credential,
+            expires_at,
+        })
+    }
+
+    /// Credencia o certificado e a chave de selagem apresentados pela urna
+    pub async fn enroll(&self, urna_id: &str, request: EnrollUrnaRequest) -> Result<UrnaProvisioning> {
+        let mut urnas = self.urnas.write().await;
+        let urna = urnas.get_mut(urna_id)
+            .ok_or_else(|| anyhow!("Urna {} not registered", urna_id))?;
+        if urna.state != ProvisioningState::CredentialsIssued {
+            return Err(anyhow!("Urna {} has no pending credential", urna_id));
+        }
+        if urna.credential_expires_at.map_or(true, |expires| expires < Utc::now()) {
+            return Err(anyhow!("Provisioning credential expired"));
+        }
+        let expected = urna.credential_sha256.as_deref()
+            .ok_or_else(|| anyhow!("Urna {} has no pending credential", urna_id))?;
+        if !constant_time_eq(&credential_sha256(&request.credential), expected) {
+            log::warn!("Invalid provisioning credential presented for urna {}", urna_id);
+            return Err(anyhow!("Invalid provisioning credential"));
+        }
+
+        let certificate = self.auth.enroll_device(urna_id, &request.certificate_pem).await?;
+        let sealing_key = self.provisioning.register_sealing_key(urna_id, &request.sealing_key_pem).await?;
+
+        urna.transition(ProvisioningState::Enrolled, urna_id, Some(certificate.fingerprint.clone()))?;
+        urna.credential_sha256 = None;
+        urna.credential_expires_at = None;
+        urna.certificate_fingerprint = Some(certificate.fingerprint);
+        urna.sealing_key_fingerprint = Some(sealing_key.fingerprint);
+        Ok(urna.clone())
+    }
+
+    /// Gera e registra o pacote de configuração da urna
+    pub async fn push_configuration(
+        &self,
+        urna_id: &str,
+        request: PushConfigurationRequest,
+        actor: &str,
+    ) -> Result<ProvisioningBundle> {
+        if request.sync_endpoints.is_empty() {
+            return Err(anyhow!("At least one sync endpoint is required"));
+        }
+        for endpoint in &request.sync_endpoints {
+            let url = url::Url::parse(&endpoint.url)
+                .map_err(|e| anyhow!("Invalid sync endpoint {}: {}", endpoint.url, e))?;
+            if url.scheme() != "https" {
+                return Err(anyhow!("Sync endpoint {} must use HTTPS", endpoint.url));
+            }
+        }
+        {
+            let urnas = self.urnas.read().await;
+            let urna = urnas.get(urna_id)
+                .ok_or_else(|| anyhow!("Urna {} not registered", urna_id))?;
+            if !urna.state.can_transition(ProvisioningState::ConfigurationPushed) {
+                return Err(anyhow!("Urna {} is {:?} and cannot receive configuration", urna_id, urna.state));
+            }
+        }
+
+        let mut bundle_request = request.bundle;
+        let config = bundle_request.config.as_object_mut()
+            .ok_or_else(|| anyhow!("Bundle config must be a JSON object"))?;
+        config.insert("feature_flags".to_string(), serde_json::to_value(&request.feature_flags)?);
+        config.insert("sync_endpoints".to_string(), serde_json::to_value(&request.sync_endpoints)?);
+        let election_id = bundle_request.election_id;
+
+        let bundle = self.provisioning.create_bundle(urna_id, bundle_request).await?;
+
+        let mut urnas = self.urnas.write().await;
+        let urna = urnas.get_mut(urna_id)
+            .ok_or_else(|| anyhow!("Urna {} not registered", urna_id))?;
+        urna.transition(ProvisioningState::ConfigurationPushed, actor, Some(bundle.bundle_id.to_string()))?;
+        urna.election_id = Some(election_id);
+        urna.bundle_id = Some(bundle.bundle_id);
+        urna.feature_flags = request.feature_flags;
+        urna.sync_endpoints = request.sync_endpoints;
+        urna.last_error = None;
+        log::info!("Configuration bundle {} pushed to urna {} by {}", bundle.bundle_id, urna_id, actor);
+        Ok(bundle)
+    }
+
+    /// Registra a confirmação da urna sobre o último pacote enviado
+    pub async fn acknowledge(&self, urna_id: &str, ack: ProvisioningAck) -> Result<UrnaProvisioning> {
+        let mut urnas = self.urnas.write().await;
+        let urna = urnas.get_mut(urna_id)
+            .ok_or_else(|| anyhow!("Urna {} not registered", urna_id))?;
+        if urna.bundle_id != Some(ack.bundle_id) {
+            return Err(anyhow!("Bundle {} is not the latest pushed to urna {}", ack.bundle_id, urna_id));
+        }
+
+        if ack.success {
+            urna.transition(ProvisioningState::Provisioned, urna_id, None)?;
+            log::info!("Urna {} applied bundle {}", urna_id, ack.bundle_id);
+        } else {
+            let error = ack.error.unwrap_or_else(|| "unspecified".to_string());
+            urna.transition(ProvisioningState::Failed, urna_id, Some(error.clone()))?;
+            log::warn!("Urna {} failed to apply bundle {}: {}", urna_id, ack.bundle_id, error);
+            urna.last_error = Some(error);
+        }
+        Ok(urna.clone())
+    }
+
+    /// Revoga a urna e os certificados de dispositivo
+    pub async fn revoke(&self, urna_id: &str, reason: &str, actor: &str) -> Result<UrnaProvisioning> {
+        let mut urnas = self.urnas.write().await;
+        let urna = urnas.get_mut(urna_id)
+            .ok_or_else(|| anyhow!("Urna {} not registered", urna_id))?;
+        urna.transition(ProvisioningState::Revoked, actor, Some(reason.to_string()))?;
+        urna.credential_sha256 = None;
+        urna.credential_expires_at = None;
+        self.auth.revoke_device(urna_id, reason).await;
+        Ok(urna.clone())
+    }
+
+    pub async fn get(&self, urna_id: &str) -> Option<UrnaProvisioning> {
+        self.urnas.read().await.get(urna_id).cloned()
+    }
+
+    /// Urnas cadastradas, opcionalmente numa etapa
+    pub async fn list(&self, state: Option<ProvisioningState>) -> Vec<UrnaProvisioning> {
+        let mut urnas: Vec<_> = self.urnas.read().await.values()
+            .filter(|urna| state.map_or(true, |state| urna.state == state))
+            .cloned()
+            .collect();
+        urnas.sort_by(|a, b| a.urna_id.cmp(&b.urna_id));
+        urnas
+    }
+}
+
+fn credential_sha256(credential: &str) -> String {
+    hex::encode(Sha256::digest(credential.as_bytes()))
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ProvisioningState::*;
+
+    #[test]
+    fn state_machine_follows_provisioning_steps() {
+        assert!(Registered.can_transition(CredentialsIssued));
+        assert!(CredentialsIssued.can_transition(Enrolled));
+        assert!(Enrolled.can_transition(ConfigurationPushed));
+        assert!(ConfigurationPushed.can_transition(Provisioned));
+        assert!(ConfigurationPushed.can_transition(Failed));
+        assert!(Failed.can_transition(ConfigurationPushed));
+        assert!(Provisioned.can_transition(ConfigurationPushed));
+
+        assert!(!Registered.can_transition(Enrolled));
+        assert!(!Registered.can_transition(ConfigurationPushed));
+        assert!(!CredentialsIssued.can_transition(ConfigurationPushed));
+        assert!(!Enrolled.can_transition(Provisioned));
+    }
+
+    #[test]
+    fn revocation_is_final() {
+        for state in [Registered, CredentialsIssued, Enrolled, ConfigurationPushed, Provisioned, Failed] {
+            assert!(state.can_transition(Revoked));
+            assert!(!Revoked.can_transition(state));
+        }
+        assert!(!Revoked.can_transition(Revoked));
+    }
+}
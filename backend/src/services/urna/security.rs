@@ -1,12 +1,37 @@
 //! Serviço de segurança para urnas eletrônicas
+//!
+//! Inclui a atestação remota do boot: a urna registra a chave de
+//! atestação do seu TPM, pede um desafio de uso único na abertura da
+//! sessão e envia a citação dos PCRs. Relatório com assinatura, desafio
+//! ou PCRs divergentes da linha de base é reprovado, gera evento de
+//! segurança crítico e alerta, e a urna não abre a sessão de votação.
+//!
+//! Os eventos de violação do equipamento (chave antiviolação, gabinete
+//! aberto, USB estranho) chegam da urna já travada; cada evento novo vira
+//! evento de segurança crítico e alerta, e os reenvios são descartados
+//! pelo identificador.
 
 use crate::models::{Urna, UrnaAuditLog, AuditEventType, UrnaStatus};
+use crate::monitoring::{AlertSeverity, MonitoringSystem};
 use anyhow::{Result, anyhow};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sha2::{Sha256, Digest};
 use base64::{Engine as _, engine::general_purpose};
-use std::collections::HashMap;
+use fortis_domain::attestation::{
+    AttestationBaseline, AttestationChallenge, AttestationError, AttestationReport, AttestationResult,
+    AttestationVerdict, ATTESTATION_CHALLENGE_TTL_SECONDS, ATTESTED_PCRS,
+};
+use fortis_domain::tamper::TamperEvent;
+use fortis_domain::update::UpdateVersion;
+use ring::rand::{SecureRandom, SystemRandom};
+use rsa::pkcs1v15::{Signature, VerifyingKey};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::signature::Verifier;
+use rsa::RsaPublicKey;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use tokio::sync::RwLock;
 
 pub struct UrnaSecurityService {
@@ -16,6 +41,25 @@ pub struct UrnaSecurityService {
     pub hsm_module: HSMModule,
     pub audit_logs: RwLock<Vec<UrnaAuditLog>>,
     pub security_events: RwLock<HashMap<Uuid, Vec<SecurityEvent>>>,
+    monitoring: Option<Arc<MonitoringSystem>>,
+    /// Chave de atestação (AK) do TPM de cada urna
+    attestation_keys: RwLock<HashMap<String, RsaPublicKey>>,
+    baselines: RwLock<Vec<AttestationBaseline>>,
+    /// Desafio pendente por urna, consumido no relatório
+    challenges: RwLock<HashMap<String, AttestationChallenge>>,
+    /// Última verificação de cada urna
+    attestations: RwLock<HashMap<String, AttestationResult>>,
+    /// Eventos de violação recebidos de cada urna
+    tamper_events: RwLock<HashMap<String, Vec<TamperEvent>>>,
+}
+
+/// Cadastro de linha de base
+#[derive(Debug, Clone, Deserialize)]
+pub struct BaselineRequest {
+    pub firmware_version: UpdateVersion,
+    pub application_version: UpdateVersion,
+    pub pcrs: BTreeMap<u32, String>,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +109,7 @@ pub enum SecurityEventType {
     HardwareFailure,
     SoftwareAnomaly,
     KeyCompromise,
+    AttestationFailure,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -100,9 +145,21 @@ impl UrnaSecurityService {
             },
             audit_logs: RwLock::new(Vec::new()),
             security_events: RwLock::new(HashMap::new()),
+            monitoring: None,
+            attestation_keys: RwLock::new(HashMap::new()),
+            baselines: RwLock::new(Vec::new()),
+            challenges: RwLock::new(HashMap::new()),
+            attestations: RwLock::new(HashMap::new()),
+            tamper_events: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Envia as reprovações da atestação e as violações aos canais de alerta
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringSystem>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
+    }
+
     pub async fn initialize_secure_environment(&mut self, urna: &Urna) -> Result<()> {
         // Verificar integridade do hardware
         self.check_hardware_integrity().await?;
@@ -333,6 +390,7 @@ impl UrnaSecurityService {
                 SecurityEventType::HardwareFailure => AuditEventType::Error,
                 SecurityEventType::SoftwareAnomaly => AuditEventType::Error,
                 SecurityEventType::KeyCompromise => AuditEventType::SecurityAlert,
+                SecurityEventType::AttestationFailure => AuditEventType::SecurityAlert,
             },
             event_data: serde_json::json!({
                 "severity": format!("{:?}", severity),
@@ -406,6 +464,211 @@ impl UrnaSecurityService {
         Ok(true)
     }
 
+    /// Registra a chave pública de atestação exportada do TPM da urna
+    pub async fn register_attestation_key(&self, urna_id: &str, public_key_pem: &str) -> Result<()> {
+        let public_key = RsaPublicKey::from_public_key_pem(public_key_pem)
+            .map_err(|e| anyhow!("Invalid attestation key: {}", e))?;
+        self.attestation_keys.write().await.insert(urna_id.to_string(), public_key);
+        log::info!("Attestation key registered for urna {}", urna_id);
+        Ok(())
+    }
+
+    /// Cadastra os PCRs conhecidos de uma combinação de firmware e aplicação
+    pub async fn register_baseline(&self, request: BaselineRequest, actor: &str) -> Result<AttestationBaseline> {
+        if request.pcrs.is_empty() {
+            return Err(anyhow!("Baseline must list at least one PCR"));
+        }
+        for (index, value) in &request.pcrs {
+            if !ATTESTED_PCRS.contains(index) {
+                return Err(anyhow!("PCR {} is not quoted by the urnas", index));
+            }
+            if value.len() != 64 || hex::decode(value).is_err() {
+                return Err(anyhow!("PCR {} must be a SHA-256 value in hex", index));
+            }
+        }
+
+        let baseline = AttestationBaseline {
+            baseline_id: Uuid::new_v4(),
+            firmware_version: request.firmware_version,
+            application_version: request.application_version,
+            pcrs: request.pcrs.into_iter().map(|(index, value)| (index, value.to_lowercase())).collect(),
+            description: request.description,
+            created_by: actor.to_string(),
+            created_at: Utc::now(),
+        };
+        self.baselines.write().await.push(baseline.clone());
+        log::info!(
+            "Attestation baseline {} registered for firmware {} and application {}",
+            baseline.baseline_id, baseline.firmware_version, baseline.application_version
+        );
+        Ok(baseline)
+    }
+
+    pub async fn baselines(&self) -> Vec<AttestationBaseline> {
+        self.baselines.read().await.clone()
+    }
+
+    pub async fn remove_baseline(&self, baseline_id: Uuid) -> Result<()> {
+        let mut baselines = self.baselines.write().await;
+        let before = baselines.len();
+        baselines.retain(|baseline| baseline.baseline_id != baseline_id);
+        if baselines.len() == before {
+            return Err(anyhow!("Baseline {} not found", baseline_id));
+        }
+        Ok(())
+    }
+
+    /// Emite desafio de uso único; substitui o pendente da mesma urna
+    pub async fn issue_challenge(&self, urna_id: &str) -> Result<AttestationChallenge> {
+        let mut nonce = [0u8; 32];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| anyhow!("Failed to generate attestation nonce"))?;
+        let issued_at = Utc::now();
+        let challenge = AttestationChallenge {
+            urna_id: urna_id.to_string(),
+            nonce: hex::encode(nonce),
+            issued_at,
+            expires_at: issued_at + Duration::seconds(ATTESTATION_CHALLENGE_TTL_SECONDS),
+        };
+        self.challenges.write().await.insert(urna_id.to_string(), challenge.clone());
+        Ok(challenge)
+    }
+
+    /// Verifica o relatório da urna; reprovação gera evento crítico e alerta
+    pub async fn verify_attestation(&self, report: AttestationReport) -> Result<AttestationResult> {
+        let urna_uuid = Uuid::parse_str(&report.urna_id)
+            .map_err(|_| anyhow!("Invalid urna id {}", report.urna_id))?;
+        let challenge = self.challenges.write().await.remove(&report.urna_id);
+
+        let outcome = self.evaluate(&report, challenge).await;
+        let result = AttestationResult {
+            urna_id: report.urna_id.clone(),
+            election_id: report.election_id,
+            verdict: if outcome.is_ok() { AttestationVerdict::Trusted } else { AttestationVerdict::Rejected },
+            baseline_id: outcome.as_ref().ok().copied(),
+            reason: outcome.as_ref().err().map(|e| e.to_string()),
+            verified_at: Utc::now(),
+        };
+        self.attestations.write().await.insert(report.urna_id.clone(), result.clone());
+
+        match &outcome {
+            Ok(baseline_id) => log::info!("Urna {} attested against baseline {}", report.urna_id, baseline_id),
+            Err(reason) => {
+                self.log_security_event(
+                    urna_uuid,
+                    SecurityEventType::AttestationFailure,
+                    SecuritySeverity::Critical,
+                    "Atestação remota reprovada",
+                    serde_json::json!({
+                        "election_id": report.election_id,
+                        "reason": reason.to_string(),
+                        "firmware_version": report.firmware_version,
+                        "application_version": report.application_version,
+                        "pcrs": report.pcrs,
+                        "secure_boot_enabled": report.secure_boot_enabled,
+                    }),
+                ).await?;
+                if let Some(monitoring) = &self.monitoring {
+                    let message = format!(
+                        "Urna {} reprovada na atestação remota da eleição {}: {}",
+                        report.urna_id, report.election_id, reason
+                    );
+                    monitoring.create_alert(AlertSeverity::Critical, "urna_attestation", &message).await?;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Última verificação da urna
+    pub async fn attestation(&self, urna_id: &str) -> Option<AttestationResult> {
+        self.attestations.read().await.get(urna_id).cloned()
+    }
+
+    /// Registra os eventos de violação da urna; devolve quantos eram novos
+    pub async fn record_tamper_events(&self, urna_id: &str, events: Vec<TamperEvent>) -> Result<usize> {
+        let urna_uuid = Uuid::parse_str(urna_id).map_err(|_| anyhow!("Invalid urna id {}", urna_id))?;
+        if let Some(event) = events.iter().find(|event| event.urna_id != urna_id) {
+            return Err(anyhow!("Tamper event {} belongs to urna {}", event.event_id, event.urna_id));
+        }
+
+        let fresh: Vec<TamperEvent> = {
+            let mut recorded = self.tamper_events.write().await;
+            let known = recorded.entry(urna_id.to_string()).or_default();
+            let mut fresh = Vec::new();
+            for event in events {
+                if known.iter().chain(fresh.iter()).all(|existing: &TamperEvent| existing.event_id != event.event_id) {
+                    fresh.push(event);
+                }
+            }
+            known.extend(fresh.iter().cloned());
+            fresh
+        };
+
+        for event in &fresh {
+            self.log_security_event(
+                urna_uuid,
+                SecurityEventType::TamperDetected,
+                SecuritySeverity::Critical,
+                "Violação do equipamento detectada",
+                serde_json::to_value(event)?,
+            ).await?;
+            if let Some(monitoring) = &self.monitoring {
+                let message = format!(
+                    "Urna {} bloqueada por violação ({}): {} em {}",
+                    urna_id, event.kind.as_str(), event.source, event.detected_at
+                );
+                monitoring.create_alert(AlertSeverity::Critical, "urna_tamper", &message).await?;
+            }
+        }
+        Ok(fresh.len())
+    }
+
+    /// Eventos de violação recebidos da urna, do mais antigo ao mais recente
+    pub async fn tamper_events(&self, urna_id: &str) -> Vec<TamperEvent> {
+        self.tamper_events.read().await.get(urna_id).cloned().unwrap_or_default()
+    }
+
+    /// Confere desafio, assinatura, citação e linha de base; devolve a linha de base aceita
+    async fn evaluate(&self, report: &AttestationReport, challenge: Option<AttestationChallenge>) -> Result<Uuid> {
+        let challenge = challenge.ok_or_else(|| anyhow!("No attestation challenge pending for the urna"))?;
+        if challenge.nonce != report.nonce.to_lowercase() {
+            return Err(AttestationError::NonceMismatch.into());
+        }
+        if Utc::now() > challenge.expires_at {
+            return Err(anyhow!("Attestation challenge expired"));
+        }
+
+        let public_key = self.attestation_keys.read().await.get(&report.urna_id).cloned()
+            .ok_or_else(|| anyhow!("No attestation key registered for the urna"))?;
+        let signature = hex::decode(&report.signature)
+            .ok()
+            .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+            .ok_or_else(|| anyhow!("Malformed quote signature"))?;
+        VerifyingKey::<Sha256>::new(public_key)
+            .verify(&report.quote_bytes()?, &signature)
+            .map_err(|_| anyhow!("Quote signature is invalid"))?;
+        report.check_quote()?;
+
+        let baselines = self.baselines.read().await;
+        let mut applicable = baselines.iter().filter(|baseline| baseline.applies_to(report)).peekable();
+        if applicable.peek().is_none() {
+            return Err(AttestationError::NoBaseline {
+                firmware: report.firmware_version,
+                application: report.application_version,
+            }.into());
+        }
+        let mut mismatch = None;
+        for baseline in applicable {
+            match baseline.check(report) {
+                Ok(()) => return Ok(baseline.baseline_id),
+                Err(e) => {
+                    mismatch.get_or_insert(e);
+                }
+            }
+        }
+        Err(mismatch.map(anyhow::Error::from).unwrap_or_else(|| anyhow!("No baseline matched")))
+    }
+
     pub async fn rotate_encryption_key(&mut self) -> Result<()> {
         // Gerar nova chave de criptografia
         let new_key = self.generate_new_encryption_key().await?;
@@ -431,3 +694,130 @@ impl UrnaSecurityService {
         Ok(general_purpose::STANDARD.encode(hash))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+    use rsa::pkcs1v15::SigningKey;
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+    use rsa::signature::{SignatureEncoding, Signer};
+    use rsa::RsaPrivateKey;
+
+    const URNA_ID: &str = "6f1c2a4e-8b1d-4c3a-9e2f-0a1b2c3d4e5f";
+
+    fn pcrs(fill: u8) -> BTreeMap<u32, String> {
+        ATTESTED_PCRS.iter().map(|index| (*index, hex::encode([fill; 32]))).collect()
+    }
+
+    /// Papel do TPM: citação `TPMS_ATTEST` dos PCRs com o desafio, assinada pela AK
+    fn report(ak: &RsaPrivateKey, nonce: &str, pcrs: BTreeMap<u32, String>) -> AttestationReport {
+        let nonce_bytes = hex::decode(nonce).unwrap();
+        let digest = fortis_domain::attestation::pcr_digest(&pcrs).unwrap();
+        let mut quote = Vec::new();
+        quote.extend_from_slice(&0xff54_4347u32.to_be_bytes());
+        quote.extend_from_slice(&0x8018u16.to_be_bytes());
+        quote.extend_from_slice(&0u16.to_be_bytes());
+        quote.extend_from_slice(&(nonce_bytes.len() as u16).to_be_bytes());
+        quote.extend_from_slice(&nonce_bytes);
+        quote.extend_from_slice(&[0u8; 25]);
+        quote.extend_from_slice(&1u32.to_be_bytes());
+        quote.extend_from_slice(&0x000bu16.to_be_bytes());
+        quote.extend_from_slice(&[3, 0b1001_0101, 0, 0]);
+        quote.extend_from_slice(&(digest.len() as u16).to_be_bytes());
+        quote.extend_from_slice(&digest);
+        let signature = SigningKey::<Sha256>::new(ak.clone()).sign(&quote);
+
+        AttestationReport {
+            urna_id: URNA_ID.to_string(),
+            election_id: Uuid::new_v4(),
+            nonce: nonce.to_string(),
+            secure_boot_enabled: true,
+            firmware_version: "2.1.0".parse().unwrap(),
+            application_version: "1.4.0".parse().unwrap(),
+            pcrs,
+            quote: hex::encode(quote),
+            signature: hex::encode(signature.to_bytes()),
+            reported_at: Utc::now(),
+        }
+    }
+
+    async fn service(ak: &RsaPrivateKey) -> UrnaSecurityService {
+        let service = UrnaSecurityService::new();
+        let pem = ak.to_public_key().to_public_key_pem(LineEnding::LF).unwrap();
+        service.register_attestation_key(URNA_ID, &pem).await.unwrap();
+        service.register_baseline(BaselineRequest {
+            firmware_version: "2.1.0".parse().unwrap(),
+            application_version: "1.4.0".parse().unwrap(),
+            pcrs: pcrs(1),
+            description: None,
+        }, "admin").await.unwrap();
+        service
+    }
+
+    #[tokio::test]
+    async fn test_attestation_matching_baseline_is_trusted() {
+        let ak = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let service = service(&ak).await;
+
+        let challenge = service.issue_challenge(URNA_ID).await.unwrap();
+        let result = service.verify_attestation(report(&ak, &challenge.nonce, pcrs(1))).await.unwrap();
+        assert_eq!(result.verdict, AttestationVerdict::Trusted);
+        assert!(result.baseline_id.is_some());
+        assert!(service.get_security_events(Uuid::parse_str(URNA_ID).unwrap()).await.unwrap().is_empty());
+
+        // O desafio é de uso único
+        let replayed = service.verify_attestation(report(&ak, &challenge.nonce, pcrs(1))).await.unwrap();
+        assert_eq!(replayed.verdict, AttestationVerdict::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_attestation_mismatch_is_rejected_with_critical_event() {
+        let ak = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let service = service(&ak).await;
+
+        let challenge = service.issue_challenge(URNA_ID).await.unwrap();
+        let result = service.verify_attestation(report(&ak, &challenge.nonce, pcrs(2))).await.unwrap();
+        assert_eq!(result.verdict, AttestationVerdict::Rejected);
+        assert!(result.reason.unwrap().contains("baseline expects"));
+        assert_eq!(service.attestation(URNA_ID).await.unwrap().verdict, AttestationVerdict::Rejected);
+
+        let events = service.get_security_events(Uuid::parse_str(URNA_ID).unwrap()).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0].severity, SecuritySeverity::Critical));
+        assert!(matches!(events[0].event_type, SecurityEventType::AttestationFailure));
+
+        // Citação assinada por outra chave
+        let other = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let challenge = service.issue_challenge(URNA_ID).await.unwrap();
+        let forged = service.verify_attestation(report(&other, &challenge.nonce, pcrs(1))).await.unwrap();
+        assert_eq!(forged.reason.as_deref(), Some("Quote signature is invalid"));
+    }
+
+    #[tokio::test]
+    async fn test_tamper_events_are_recorded_once() {
+        let service = UrnaSecurityService::new();
+        let event = TamperEvent {
+            event_id: Uuid::new_v4(),
+            urna_id: URNA_ID.to_string(),
+            kind: fortis_domain::tamper::TamperKind::CaseOpen,
+            source: "case_open".to_string(),
+            detail: "Sensor triggered (value 1)".to_string(),
+            election_id: None,
+            detected_at: Utc::now(),
+        };
+
+        assert_eq!(service.record_tamper_events(URNA_ID, vec![event.clone()]).await.unwrap(), 1);
+        // Reenvio da urna sem confirmação
+        assert_eq!(service.record_tamper_events(URNA_ID, vec![event.clone()]).await.unwrap(), 0);
+        assert_eq!(service.tamper_events(URNA_ID).await.len(), 1);
+
+        let events = service.get_security_events(Uuid::parse_str(URNA_ID).unwrap()).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0].severity, SecuritySeverity::Critical));
+        assert!(matches!(events[0].event_type, SecurityEventType::TamperDetected));
+
+        let foreign = TamperEvent { event_id: Uuid::new_v4(), urna_id: Uuid::new_v4().to_string(), ..event };
+        assert!(service.record_tamper_events(URNA_ID, vec![foreign]).await.is_err());
+    }
+}
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc, Duration};
 use std::collections::HashMap;
 use tokio::sync::RwLock;
+use tracing::Instrument;
 
 pub struct UrnaSyncService {
     pub sync_queue: RwLock<HashMap<Uuid, Vec<UrnaVote>>>,
@@ -27,6 +28,7 @@ impl UrnaSyncService {
         }
     }
 
+    #[tracing::instrument(name = "urna_sync.start", skip_all, fields(urna_id = %request.urna_id))]
     pub async fn start_sync(&self, request: UrnaSyncRequest) -> Result<UrnaSyncResponse> {
         let sync_id = Uuid::new_v4();
         let sync = UrnaSync {
@@ -53,7 +55,7 @@ impl UrnaSyncService {
             if let Err(e) = service.execute_sync(sync_id, request).await {
                 log::error!("Sync failed for urna {}: {:?}", urna_id, e);
             }
-        });
+        }.in_current_span());
 
         Ok(UrnaSyncResponse {
             sync_id,
@@ -64,6 +66,7 @@ impl UrnaSyncService {
         })
     }
 
+    #[tracing::instrument(name = "urna_sync.execute", skip_all, fields(sync_id = %sync_id))]
     async fn execute_sync(&self, sync_id: Uuid, request: UrnaSyncRequest) -> Result<()> {
         // Atualizar status para em progresso
         self.update_sync_status(sync_id, SyncStatus::InProgress).await?;
@@ -94,6 +97,7 @@ impl UrnaSyncService {
         Ok(())
     }
 
+    #[tracing::instrument(name = "urna_sync.vote", level = "debug", skip_all, fields(vote_id = %vote.id))]
     async fn sync_vote(&self, vote: &UrnaVote) -> Result<()> {
         // Validar voto localmente
         self.validate_vote_locally(vote).await?;
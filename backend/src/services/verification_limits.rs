@@ -0,0 +1,535 @@
+//! Limite de consultas ao portal de verificação de comprovantes
+//!
+//! O portal público responde se um código de rastreamento consta no log;
+//! sem limite, um robô poderia varrer códigos e correlacionar comprovantes.
+//! Cada cliente tem um balde de fichas (token bucket): consultar um código
+//! novo gasta uma ficha, repetir um código já consultado no período não
+//! gasta, e um código inexistente gasta fichas extras, de modo que o
+//! eleitor que confere o próprio comprovante nunca é barrado e a varredura
+//! esgota o balde logo. Cada par (cliente, código) também tem o seu balde,
+//! contra a consulta insistente de um mesmo comprovante; como o balde é do
+//! par, um terceiro martelando o código alheio não barra o dono dele.
+//!
+//! Nada identificável é guardado: IPs e códigos viram HMACs com uma chave
+//! sorteada a cada período, descartada na rotação junto com os baldes, e
+//! nenhum deles vai para os logs.
+//!
+//! Quem divide o IP (escolas, redes móveis) pode usar fichas anônimas no
+//! estilo Privacy Pass: o eleitor autenticado obtém assinaturas RSA cegas
+//! sobre números que só ele conhece e depois apresenta cada par (número,
+//! assinatura) no cabeçalho `Authorization: PrivateToken token=...`. O
+//! servidor confere a assinatura e gasta a ficha sem conseguir ligá-la à
+//! emissão, nem ao eleitor que a obteve.
+//!
+//! A chave das fichas fica no HSM, uma por época (`{prefixo}-{época}`), e
+//! cada ficha carrega a época em que foi assinada. Valem as fichas da época
+//! atual e da anterior; ao virar a época, a chave mais antiga é apagada do
+//! HSM e o conjunto das fichas gastas com ela é descartado, de modo que a
+//! memória guarda no máximo duas épocas de fichas gastas. Esse conjunto não
+//! sobrevive a um reinício: depois dele, cada ficha ainda válida pode ser
+//! usada mais uma vez.
+
+use anyhow::{Result, anyhow};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use rand::RngCore;
+use ring::hmac;
+use rsa::BigUint;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::config::VerificationLimitConfig;
+use crate::crypto::hsm::{Hsm, HsmBlindKey};
+
+/// Esquema do cabeçalho `Authorization` com a ficha anônima
+pub const PRIVATE_TOKEN_SCHEME: &str = "PrivateToken";
+
+/// Tamanho do número escolhido pelo cliente para cada ficha
+pub const TOKEN_NONCE_LEN: usize = 32;
+
+/// Tamanho da época no início da ficha (u64 big-endian)
+const TOKEN_EPOCH_LEN: usize = 8;
+
+/// Contexto do hash das fichas
+const TOKEN_CONTEXT: &[u8] = b"fortis-verification-token-v1";
+
+type HashedKey = [u8; 16];
+
+/// Balde de fichas com reposição contínua
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_second: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: u32, refill_every: Duration, now: Instant) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_second: 1.0 / refill_every.as_secs_f64().max(f64::EPSILON),
+            tokens: capacity as f64,
+            updated_at: now,
+        }
+    }
+
+    /// Gasta `cost` fichas se houver; senão devolve a espera até haver
+    pub fn take(&mut self, cost: u32, now: Instant) -> std::result::Result<(), Duration> {
+        self.refill(now);
+        let cost = cost as f64;
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64((cost - self.tokens) / self.refill_per_second))
+    }
+
+    /// Gasta até `cost` fichas, sem recusar; o saldo não fica negativo
+    pub fn drain(&mut self, cost: u32, now: Instant) {
+        self.refill(now);
+        self.tokens = (self.tokens - cost as f64).max(0.0);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.updated_at = now;
+    }
+}
+
+/// Quem consulta: o IP, reduzido a um HMAC, ou uma ficha anônima já gasta
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationClient {
+    Address(HashedKey),
+    Anonymous,
+}
+
+/// Resposta do limite a uma consulta
+#[derive(Debug, Clone, PartialEq)]
+pub enum Admission {
+    Allowed,
+    Throttled { retry_after: Duration },
+}
+
+/// Chave pública das fichas anônimas da época atual, em hex
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenKey {
+    pub epoch: u64,
+    pub modulus: String,
+    pub exponent: String,
+    /// Fichas que cada eleitor autenticado pode obter por período
+    pub tokens_per_period: u32,
+}
+
+#[derive(Default)]
+struct Period {
+    clients: HashMap<HashedKey, TokenBucket>,
+    /// Códigos já consultados por cada cliente no período
+    seen: HashMap<HashedKey, HashSet<HashedKey>>,
+    trackers: HashMap<HashedKey, TokenBucket>,
+    /// Fichas emitidas por eleitor (HMAC do sujeito)
+    issued: HashMap<HashedKey, u32>,
+}
+
+struct PeriodState {
+    key: hmac::Key,
+    started_at: Instant,
+    period: Period,
+}
+
+/// Chave das fichas de uma época e os resumos das fichas gastas com ela
+struct TokenEpoch {
+    epoch: u64,
+    key: HsmBlindKey,
+    spent: HashSet<[u8; 32]>,
+}
+
+/// Limite do portal de verificação, sem guardar dados identificáveis
+pub struct VerificationLimiter {
+    config: VerificationLimitConfig,
+    hsm: Hsm,
+    key_prefix: String,
+    state: RwLock<PeriodState>,
+    /// Época atual e a anterior, no máximo
+    epochs: RwLock<Vec<TokenEpoch>>,
+}
+
+impl VerificationLimiter {
+    pub fn new(config: VerificationLimitConfig, hsm: Hsm, key_prefix: &str) -> Result<Self> {
+        let mut limiter = Self {
+            config,
+            hsm,
+            key_prefix: key_prefix.to_string(),
+            state: RwLock::new(PeriodState {
+                key: period_key(),
+                started_at: Instant::now(),
+                period: Period::default(),
+            }),
+            epochs: RwLock::new(Vec::new()),
+        };
+        let mut epochs = Vec::new();
+        limiter.roll(&mut epochs, limiter.current_epoch())?;
+        *limiter.epochs.get_mut() = epochs;
+        Ok(limiter)
+    }
+
+    /// Cliente identificado pelo HMAC do endereço no período
+    pub async fn client_for_address(&self, address: &str) -> VerificationClient {
+        let mut state = self.state.write().await;
+        self.rotate(&mut state);
+        VerificationClient::Address(hashed(&state.key, b"client", address.as_bytes()))
+    }
+
+    /// Decide se a consulta do código pode seguir
+    pub async fn admit(&self, client: &VerificationClient, tracking_code: &str) -> Admission {
+        let now = Instant::now();
+        let mut state = self.state.write().await;
+        self.rotate(&mut state);
+        // Fichas anônimas são de uso único; o balde do par só vale para IPs
+        let VerificationClient::Address(client) = client else {
+            return Admission::Allowed;
+        };
+        let tracker = hashed(&state.key, b"tracker", tracking_code.as_bytes());
+        let pair = hashed(&state.key, b"client-tracker", &[client.as_slice(), tracker.as_slice()].concat());
+        let period = &mut state.period;
+
+        let tracker_bucket = period.trackers.entry(pair).or_insert_with(|| {
+            TokenBucket::new(self.config.tracker_burst, Duration::from_secs(self.config.tracker_refill_seconds), now)
+        });
+        if let Err(retry_after) = tracker_bucket.take(1, now) {
+            return Admission::Throttled { retry_after };
+        }
+
+        if period.seen.get(client).is_some_and(|seen| seen.contains(&tracker)) {
+            return Admission::Allowed;
+        }
+        let client_bucket = period.clients.entry(*client).or_insert_with(|| {
+            TokenBucket::new(self.config.client_burst, Duration::from_secs(self.config.client_refill_seconds), now)
+        });
+        match client_bucket.take(1, now) {
+            Ok(()) => {
+                period.seen.entry(*client).or_default().insert(tracker);
+                Admission::Allowed
+            }
+            Err(retry_after) => Admission::Throttled { retry_after },
+        }
+    }
+
+    /// Código inexistente: custa fichas extras ao cliente, sinal de varredura
+    pub async fn record_miss(&self, client: &VerificationClient) {
+        let VerificationClient::Address(client) = client else {
+            return;
+        };
+        let now = Instant::now();
+        let mut state = self.state.write().await;
+        if let Some(bucket) = state.period.clients.get_mut(client) {
+            bucket.drain(self.config.miss_cost, now);
+        }
+    }
+
+    /// Chave pública da época atual para o cliente cegar e conferir as fichas
+    pub async fn token_key(&self) -> Result<TokenKey> {
+        self.token_key_in(self.current_epoch()).await
+    }
+
+    /// Assina às cegas os números enviados pelo eleitor autenticado, dentro da cota do período
+    pub async fn issue(&self, subject: &str, epoch: u64, blinded: &[String]) -> Result<Vec<String>> {
+        self.issue_in(subject, epoch, blinded, self.current_epoch()).await
+    }
+
+    /// Confere e gasta a ficha do cabeçalho `PrivateToken`
+    pub async fn redeem(&self, token: &str) -> Result<VerificationClient> {
+        self.redeem_in(token, self.current_epoch()).await
+    }
+
+    async fn token_key_in(&self, current: u64) -> Result<TokenKey> {
+        let mut epochs = self.epochs.write().await;
+        self.roll(&mut epochs, current)?;
+        let key = &epochs.iter()
+            .find(|e| e.epoch == current)
+            .ok_or_else(|| anyhow!("No verification token key for epoch {}", current))?
+            .key;
+        Ok(TokenKey {
+            epoch: current,
+            modulus: key.modulus().to_str_radix(16),
+            exponent: key.exponent().to_str_radix(16),
+            tokens_per_period: self.config.tokens_per_period,
+        })
+    }
+
+    async fn issue_in(&self, subject: &str, epoch: u64, blinded: &[String], current: u64) -> Result<Vec<String>> {
+        if epoch != current {
+            return Err(anyhow!("Token epoch {} is not the current epoch {}", epoch, current));
+        }
+        let key = {
+            let mut epochs = self.epochs.write().await;
+            self.roll(&mut epochs, current)?;
+            epochs.iter()
+                .find(|e| e.epoch == current)
+                .map(|e| e.key.clone())
+                .ok_or_else(|| anyhow!("No verification token key for epoch {}", current))?
+        };
+
+        let mut state = self.state.write().await;
+        self.rotate(&mut state);
+        let holder = hashed(&state.key, b"subject", subject.as_bytes());
+        let issued = state.period.issued.get(&holder).copied().unwrap_or(0);
+        if issued as usize + blinded.len() > self.config.tokens_per_period as usize {
+            return Err(anyhow!(
+                "Token quota exceeded: {} of {} already issued in this period",
+                issued,
+                self.config.tokens_per_period
+            ));
+        }
+
+        let signatures = blinded.iter()
+            .map(|message| {
+                let message = BigUint::parse_bytes(message.as_bytes(), 16)
+                    .filter(|m| m < key.modulus())
+                    .ok_or_else(|| anyhow!("Invalid blinded message"))?;
+                Ok(key.blind_sign(&message)?.to_str_radix(16))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        state.period.issued.insert(holder, issued + signatures.len() as u32);
+        Ok(signatures)
+    }
+
+    async fn redeem_in(&self, token: &str, current: u64) -> Result<VerificationClient> {
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(token.trim())
+            .map_err(|_| anyhow!("Malformed verification token"))?;
+        if bytes.len() <= TOKEN_EPOCH_LEN + TOKEN_NONCE_LEN {
+            return Err(anyhow!("Malformed verification token"));
+        }
+        let (epoch, rest) = bytes.split_at(TOKEN_EPOCH_LEN);
+        let (nonce, signature) = rest.split_at(TOKEN_NONCE_LEN);
+        let epoch = u64::from_be_bytes(epoch.try_into()?);
+
+        let mut epochs = self.epochs.write().await;
+        self.roll(&mut epochs, current)?;
+        let token_epoch = epochs.iter_mut()
+            .find(|e| e.epoch == epoch)
+            .ok_or_else(|| anyhow!("Verification token epoch {} is not accepted", epoch))?;
+
+        let signature = BigUint::from_bytes_be(signature);
+        if !token_epoch.key.verify(&signature, &token_message(nonce, token_epoch.key.modulus())) {
+            return Err(anyhow!("Invalid verification token"));
+        }
+
+        let digest: [u8; 32] = Sha256::digest(nonce).into();
+        if !token_epoch.spent.insert(digest) {
+            return Err(anyhow!("Verification token already spent"));
+        }
+        Ok(VerificationClient::Anonymous)
+    }
+
+    fn current_epoch(&self) -> u64 {
+        Utc::now().timestamp().max(0) as u64 / self.config.token_epoch_seconds.max(1)
+    }
+
+    /// Mantém só a época atual e a anterior; a chave das demais é apagada do HSM
+    fn roll(&self, epochs: &mut Vec<TokenEpoch>, current: u64) -> Result<()> {
+        let mut retired = Vec::new();
+        epochs.retain(|e| {
+            let live = e.epoch == current || e.epoch + 1 == current;
+            if !live {
+                retired.push(e.key.label().to_string());
+            }
+            live
+        });
+        for label in retired {
+            self.hsm.destroy_blind_key(&label)?;
+        }
+
+        if !epochs.iter().any(|e| e.epoch == current) {
+            let label = format!("{}-{}", self.key_prefix, current);
+            let key = self.hsm.blind_key_or_generate(&label, self.config.token_key_bits)
+                .map_err(|e| anyhow!("Failed to load verification token key {}: {}", label, e))?;
+            epochs.push(TokenEpoch { epoch: current, key, spent: HashSet::new() });
+        }
+        Ok(())
+    }
+
+    /// Troca a chave do HMAC e descarta os baldes ao fim do período
+    fn rotate(&self, state: &mut PeriodState) {
+        if state.started_at.elapsed() < Duration::from_secs(self.config.key_rotation_seconds) {
+            return;
+        }
+        *state = PeriodState {
+            key: period_key(),
+            started_at: Instant::now(),
+            period: Period::default(),
+        };
+    }
+}
+
+/// Ficha no cabeçalho `Authorization: PrivateToken token=<base64url(época || número || assinatura)>`
+pub fn private_token(authorization: &str) -> Option<&str> {
+    let (scheme, params) = authorization.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case(PRIVATE_TOKEN_SCHEME) {
+        return None;
+    }
+    params.split(',')
+        .filter_map(|param| param.trim().split_once('='))
+        .find(|(name, _)| *name == "token")
+        .map(|(_, value)| value.trim_matches('"'))
+}
+
+/// Hash de domínio completo do número da ficha, reduzido ao módulo
+pub fn token_message(nonce: &[u8], modulus: &BigUint) -> BigUint {
+    let len = (modulus.bits() + 7) / 8;
+    let mut expanded = Vec::with_capacity(len + 32);
+    let mut counter = 0u32;
+    while expanded.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(TOKEN_CONTEXT);
+        hasher.update(counter.to_be_bytes());
+        hasher.update(nonce);
+        expanded.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    BigUint::from_bytes_be(&expanded[..len]) % modulus
+}
+
+fn period_key() -> hmac::Key {
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    hmac::Key::new(hmac::HMAC_SHA256, &secret)
+}
+
+fn hashed(key: &hmac::Key, label: &[u8], value: &[u8]) -> HashedKey {
+    let mut context = hmac::Context::with_key(key);
+    context.update(label);
+    context.update(b"|");
+    context.update(value);
+    let mut out = [0u8; 16];
+    out.copy_from_slice(&context.sign().as_ref()[..16]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> VerificationLimitConfig {
+        VerificationLimitConfig {
+            client_burst: 3,
+            client_refill_seconds: 60,
+            tracker_burst: 20,
+            tracker_refill_seconds: 1,
+            miss_cost: 2,
+            key_rotation_seconds: 86_400,
+            tokens_per_period: 2,
+            token_key_bits: 1024,
+            token_epoch_seconds: 86_400,
+        }
+    }
+
+    fn limiter() -> VerificationLimiter {
+        VerificationLimiter::new(config(), Hsm::software(), "verification-tokens").unwrap()
+    }
+
+    fn mod_inverse(a: &BigUint, n: &BigUint) -> BigUint {
+        let zero = BigUint::from(0u32);
+        let (mut r0, mut r1) = (n.clone(), a % n);
+        let (mut t0, mut t1) = (zero.clone(), BigUint::from(1u32));
+        while r1 != zero {
+            let q = &r0 / &r1;
+            let r2 = &r0 - &q * &r1;
+            let t2 = (&t0 + n - (&q * &t1) % n) % n;
+            r0 = std::mem::replace(&mut r1, r2);
+            t0 = std::mem::replace(&mut t1, t2);
+        }
+        t0
+    }
+
+    /// Cega, obtém a assinatura e tira o fator, como o cliente faria
+    async fn obtain_token(limiter: &VerificationLimiter, subject: &str, current: u64, nonce: [u8; TOKEN_NONCE_LEN]) -> String {
+        let key = limiter.token_key_in(current).await.unwrap();
+        let n = BigUint::parse_bytes(key.modulus.as_bytes(), 16).unwrap();
+        let e = BigUint::parse_bytes(key.exponent.as_bytes(), 16).unwrap();
+
+        let r = BigUint::from(0x1234_5678_9abc_def1u64);
+        let blinded = (token_message(&nonce, &n) * r.modpow(&e, &n)) % &n;
+        let signed = limiter.issue_in(subject, key.epoch, &[blinded.to_str_radix(16)], current).await.unwrap();
+
+        let blind_signature = BigUint::parse_bytes(signed[0].as_bytes(), 16).unwrap();
+        let signature = (blind_signature * mod_inverse(&r, &n)) % &n;
+        let mut token = key.epoch.to_be_bytes().to_vec();
+        token.extend(nonce);
+        token.extend(signature.to_bytes_be());
+        general_purpose::URL_SAFE_NO_PAD.encode(token)
+    }
+
+    #[tokio::test]
+    async fn test_repeat_lookups_are_free_and_enumeration_is_throttled() {
+        let limiter = limiter();
+        let voter = limiter.client_for_address("203.0.113.7").await;
+        for _ in 0..10 {
+            assert_eq!(limiter.admit(&voter, "ABCD-EFGH-IJKL").await, Admission::Allowed);
+        }
+
+        let scraper = limiter.client_for_address("198.51.100.9").await;
+        assert_eq!(limiter.admit(&scraper, "AAAA-0001").await, Admission::Allowed);
+        limiter.record_miss(&scraper).await;
+        assert!(matches!(limiter.admit(&scraper, "AAAA-0002").await, Admission::Throttled { .. }));
+        assert_eq!(limiter.admit(&voter, "ABCD-EFGH-IJKL").await, Admission::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_third_party_cannot_throttle_the_receipt_owner() {
+        let limiter = VerificationLimiter::new(
+            VerificationLimitConfig { tracker_burst: 2, tracker_refill_seconds: 3_600, ..config() },
+            Hsm::software(),
+            "verification-tokens",
+        ).unwrap();
+        let attacker = limiter.client_for_address("198.51.100.9").await;
+        let owner = limiter.client_for_address("203.0.113.7").await;
+
+        for _ in 0..2 {
+            assert_eq!(limiter.admit(&attacker, "ABCD-EFGH-IJKL").await, Admission::Allowed);
+        }
+        assert!(matches!(limiter.admit(&attacker, "ABCD-EFGH-IJKL").await, Admission::Throttled { .. }));
+        assert_eq!(limiter.admit(&owner, "ABCD-EFGH-IJKL").await, Admission::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_blind_token_redeems_once_within_quota() {
+        let limiter = limiter();
+        let current = limiter.current_epoch();
+        let token = obtain_token(&limiter, "voter-1", current, [7u8; TOKEN_NONCE_LEN]).await;
+
+        let header = format!("PrivateToken token=\"{}\"", token);
+        assert_eq!(limiter.redeem(private_token(&header).unwrap()).await.unwrap(), VerificationClient::Anonymous);
+        assert!(limiter.redeem(&token).await.is_err());
+
+        let over_quota = vec!["2".to_string(); 2];
+        assert!(limiter.issue("voter-1", current, &over_quota).await.is_err());
+        assert!(limiter.issue("voter-2", current + 1, &over_quota[..1]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_epoch_roll_forgets_spent_tokens_and_retires_old_keys() {
+        let limiter = limiter();
+        let first = limiter.current_epoch();
+        let old = obtain_token(&limiter, "voter-1", first, [1u8; TOKEN_NONCE_LEN]).await;
+        let spent = obtain_token(&limiter, "voter-2", first, [2u8; TOKEN_NONCE_LEN]).await;
+        let first_key = limiter.epochs.read().await[0].key.clone();
+        limiter.redeem_in(&spent, first).await.unwrap();
+
+        // Na época seguinte a ficha anterior ainda vale e continua gasta
+        assert!(limiter.redeem_in(&spent, first + 1).await.is_err());
+        assert_eq!(limiter.epochs.read().await.len(), 2);
+
+        // Duas épocas depois a chave antiga sai do HSM e os resumos com ela
+        assert!(limiter.redeem_in(&old, first + 2).await.is_err());
+        let epochs = limiter.epochs.read().await;
+        assert!(epochs.iter().all(|e| e.epoch >= first + 1));
+        assert!(epochs.iter().all(|e| e.spent.is_empty()));
+        assert!(first_key.blind_sign(&BigUint::from(2u32)).is_err());
+    }
+}
@@ -0,0 +1,327 @@
+//! Sub-raízes Merkle de lotes de votos assinadas pelas urnas
+//!
+//! A urna envia cada lote com a raiz Merkle dos códigos de rastreamento,
+//! assinada com a sua chave RSA. O backend confere a assinatura e a raiz,
+//! exige sequência contínua por urna e eleição, concilia os códigos com os
+//! votos já registrados e grava a sub-raiz no log transparente principal
+//! junto com as posições dos votos no log. Em uma contestação, a sub-raiz
+//! assinada e o log principal indicam onde a discrepância surgiu.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+pub use fortis_domain::batch::{verify_batch_proof, BatchProof, SignedVoteBatch, VoteBatch};
+
+use crate::services::receipts::{normalize_tracking_code, ReceiptService};
+use crate::services::urna::ContingencyService;
+use crate::transparency::election_logs::{ElectionEvent, ElectionEventType, ElectionTransparencyLog};
+
+/// Lote aceito e registrado no log principal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredVoteBatch {
+    pub batch: VoteBatch,
+    pub signature: String,
+    /// Posição do evento da sub-raiz no log principal
+    pub log_index: u64,
+    /// Posições dos votos do lote no log principal, na ordem das folhas
+    pub vote_log_indices: Vec<Option<u64>>,
+    /// Códigos assinados pela urna que não constam no log principal
+    pub missing_from_log: Vec<String>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Origem provável de uma discrepância
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyOrigin {
+    /// Voto no lote assinado e no log principal
+    Consistent,
+    /// A urna assinou o voto, mas ele não chegou ao log principal
+    Backend,
+    /// O voto está no log principal sem ter sido assinado em lote pela urna
+    Urna,
+    /// Código desconhecido para a urna e para o backend
+    Unknown,
+}
+
+/// Resultado da consulta de um código em contestação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeFinding {
+    pub tracking_code: String,
+    pub origin: DiscrepancyOrigin,
+    pub urna_id: Option<String>,
+    pub batch_sequence: Option<u64>,
+    /// Posição da sub-raiz do lote no log principal
+    pub batch_log_index: Option<u64>,
+    /// Prova de inclusão do código na sub-raiz assinada
+    pub batch_proof: Option<BatchProof>,
+    pub vote_log_index: Option<u64>,
+}
+
+/// Serviço de lotes de votos
+pub struct VoteBatchService {
+    transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
+    contingency: Arc<ContingencyService>,
+    receipts: Arc<ReceiptService>,
+    /// Lotes por (urna, eleição), em ordem de sequência
+    batches: RwLock<HashMap<(String, Uuid), Vec<StoredVoteBatch>>>,
+    /// Código de rastreamento -> (urna, eleição, posição do lote)
+    code_index: RwLock<HashMap<String, (String, Uuid, usize)>>,
+}
+
+impl VoteBatchService {
+    pub fn new(
+        transparency_log: Arc<RwLock<ElectionTransparencyLog>>,
+        contingency: Arc<ContingencyService>,
+        receipts: Arc<ReceiptService>,
+    ) -> Self {
+        Self {
+            transparency_log,
+            contingency,
+            receipts,
+            batches: RwLock::new(HashMap::new()),
+            code_index: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Confere e registra um lote assinado pela urna
+    pub async fn submit(&self, signed: SignedVoteBatch) -> Result<StoredVoteBatch> {
+        let SignedVoteBatch { batch, signature } = signed;
+
+        if batch.tracking_codes.is_empty() || !batch.verify_root() {
+            return Err(anyhow!("Merkle root does not match the batch tracking codes"));
+        }
+        self.contingency
+            .verify_urna_signature(&batch.urna_id, &batch.signed_message(), &signature)
+            .await?;
+
+        let key = (batch.urna_id.clone(), batch.election_id);
+        let mut batches = self.batches.write().await;
+        let urna_batches = batches.entry(key.clone()).or_default();
+
+        // Reenvio do mesmo lote após falha de rede
+        if let Some(existing) = urna_batches.iter().find(|b| b.batch.sequence == batch.sequence) {
+            if existing.batch.merkle_root == batch.merkle_root {
+                return Ok(existing.clone());
+            }
+            return Err(anyhow!(
+                "Urna {} sent a different root for batch {}",
+                batch.urna_id, batch.sequence
+            ));
+        }
+        let expected = urna_batches.len() as u64 + 1;
+        if batch.sequence != expected {
+            return Err(anyhow!(
+                "Urna {} sent batch {} but batch {} was expected",
+                batch.urna_id, batch.sequence, expected
+            ));
+        }
+
+        let mut code_index = self.code_index.write().await;
+        let mut vote_log_indices = Vec::with_capacity(batch.tracking_codes.len());
+        let mut missing_from_log = Vec::new();
+        for code in &batch.tracking_codes {
+            if let Some((other_urna, _, _)) = code_index.get(code) {
+                return Err(anyhow!("Tracking code {} already sealed by urna {}", code, other_urna));
+            }
+            match self.receipts.recorded_entry(code).await {
+                Some((election_id, log_index)) if election_id == batch.election_id => {
+                    vote_log_indices.push(Some(log_index));
+                }
+                _ => {
+                    vote_log_indices.push(None);
+                    missing_from_log.push(code.clone());
+                }
+            }
+        }
+
+        let now = Utc::now();
+        let proof = self.transparency_log.write().await.append_election_event(ElectionEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type: ElectionEventType::VoteBatchRoot,
+            election_id: batch.election_id.to_string(),
+            data: serde_json::json!({
+                "urna_id": batch.urna_id,
+                "sequence": batch.sequence,
+                "merkle_root": batch.merkle_root,
+                "vote_count": batch.tracking_codes.len(),
+                "closed_at": batch.closed_at,
+                "signature": signature,
+                "vote_log_indices": vote_log_indices,
+                "missing_from_log": missing_from_log,
+            }),
+            timestamp: now,
+            source: "Urna".to_string(),
+        })?;
+
+        if !missing_from_log.is_empty() {
+            log::warn!(
+                "Batch {} from urna {} has {} votes missing from the transparency log",
+                batch.sequence, batch.urna_id, missing_from_log.len()
+            );
+        }
+
+        let position = urna_batches.len();
+        for code in &batch.tracking_codes {
+            code_index.insert(code.clone(), (key.0.clone(), key.1, position));
+        }
+        let stored = StoredVoteBatch {
+            batch,
+            signature,
+            log_index: proof.log_index,
+            vote_log_indices,
+            missing_from_log,
+            received_at: now,
+        };
+        urna_batches.push(stored.clone());
+        Ok(stored)
+    }
+
+    /// Lotes recebidos de uma urna, em todas as eleições
+    pub async fn batches_for_urna(&self, urna_id: &str) -> Vec<StoredVoteBatch> {
+        let batches = self.batches.read().await;
+        let mut result: Vec<StoredVoteBatch> = batches
+            .iter()
+            .filter(|((id, _), _)| id == urna_id)
+            .flat_map(|(_, list)| list.iter().cloned())
+            .collect();
+        result.sort_by_key(|b| (b.batch.election_id, b.batch.sequence));
+        result
+    }
+
+    /// Indica se a discrepância de um código surgiu na urna ou no backend
+    pub async fn locate_discrepancy(&self, tracking_code: &str) -> Result<DisputeFinding> {
+        let code = normalize_tracking_code(tracking_code)
+            .ok_or_else(|| anyhow!("Invalid tracking code format"))?;
+
+        let sealed = match self.code_index.read().await.get(&code).cloned() {
+            Some((urna_id, election_id, position)) => self
+                .batches
+                .read()
+                .await
+                .get(&(urna_id, election_id))
+                .and_then(|list| list.get(position).cloned()),
+            None => None,
+        };
+        let vote_log_index = self.receipts.recorded_entry(&code).await.map(|(_, index)| index);
+
+        let origin = match (&sealed, vote_log_index) {
+            (Some(_), Some(_)) => DiscrepancyOrigin::Consistent,
+            (Some(_), None) => DiscrepancyOrigin::Backend,
+            (None, Some(_)) => DiscrepancyOrigin::Urna,
+            (None, None) => DiscrepancyOrigin::Unknown,
+        };
+
+        Ok(DisputeFinding {
+            origin,
+            urna_id: sealed.as_ref().map(|s| s.batch.urna_id.clone()),
+            batch_sequence: sealed.as_ref().map(|s| s.batch.sequence),
+            batch_log_index: sealed.as_ref().map(|s| s.log_index),
+            batch_proof: sealed.as_ref().and_then(|s| s.batch.inclusion_proof(&code)),
+            vote_log_index,
+            tracking_code: code,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::receipts::{ballot_hash, tracking_code};
+    use crate::transparency::election_logs::LogConfig;
+    use base64::{Engine as _, engine::general_purpose};
+    use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+    use sha2::{Digest, Sha256};
+
+    struct Fixture {
+        service: VoteBatchService,
+        receipts: Arc<ReceiptService>,
+        private_key: RsaPrivateKey,
+        election_id: Uuid,
+    }
+
+    async fn fixture() -> Fixture {
+        let log = Arc::new(RwLock::new(ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        })));
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        let pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+        let contingency = Arc::new(ContingencyService::new());
+        contingency.register_urna_key("urna-1", &pem, public_key.to_public_key_der().unwrap().as_bytes()).await.unwrap();
+        let receipts = Arc::new(ReceiptService::new(log.clone()));
+
+        Fixture {
+            service: VoteBatchService::new(log, contingency, receipts.clone()),
+            receipts,
+            private_key,
+            election_id: Uuid::new_v4(),
+        }
+    }
+
+    impl Fixture {
+        async fn record(&self, label: &str) -> String {
+            let hash = ballot_hash(label.as_bytes());
+            self.receipts.record_vote(Uuid::new_v4(), self.election_id, &hash, None).await.unwrap().tracking_code
+        }
+
+        fn sign(&self, batch: VoteBatch) -> SignedVoteBatch {
+            let digest = Sha256::digest(batch.signed_message());
+            let signature = self.private_key.sign(Pkcs1v15Sign::new::<Sha256>(), &digest).unwrap();
+            SignedVoteBatch { batch, signature: general_purpose::STANDARD.encode(signature) }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_is_cross_linked_and_dispute_points_to_origin() {
+        let f = fixture().await;
+        let recorded = f.record("a").await;
+        let lost = tracking_code(Uuid::new_v4(), "perdido");
+        let unsealed = f.record("b").await;
+
+        let batch = VoteBatch::new("urna-1", f.election_id, 1, vec![recorded.clone(), lost.clone()]).unwrap();
+        let stored = f.service.submit(f.sign(batch)).await.unwrap();
+        assert_eq!(stored.missing_from_log, vec![lost.clone()]);
+        assert!(stored.vote_log_indices[0].is_some());
+
+        let finding = f.service.locate_discrepancy(&recorded).await.unwrap();
+        assert_eq!(finding.origin, DiscrepancyOrigin::Consistent);
+        assert!(verify_batch_proof(&recorded, finding.batch_proof.as_ref().unwrap()));
+        assert_eq!(finding.batch_log_index, Some(stored.log_index));
+
+        assert_eq!(f.service.locate_discrepancy(&lost).await.unwrap().origin, DiscrepancyOrigin::Backend);
+        assert_eq!(f.service.locate_discrepancy(&unsealed).await.unwrap().origin, DiscrepancyOrigin::Urna);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_signature_and_sequence_gaps() {
+        let f = fixture().await;
+        let code = f.record("a").await;
+
+        let mut forged = f.sign(VoteBatch::new("urna-1", f.election_id, 1, vec![code.clone()]).unwrap());
+        forged.batch.sequence = 2;
+        assert!(f.service.submit(forged).await.is_err());
+
+        let skipped = f.sign(VoteBatch::new("urna-1", f.election_id, 2, vec![code.clone()]).unwrap());
+        assert!(f.service.submit(skipped).await.is_err());
+
+        let first = f.sign(VoteBatch::new("urna-1", f.election_id, 1, vec![code]).unwrap());
+        let stored = f.service.submit(first.clone()).await.unwrap();
+        // Reenvio idempotente
+        assert_eq!(f.service.submit(first).await.unwrap().log_index, stored.log_index);
+        assert_eq!(f.service.batches_for_urna("urna-1").await.len(), 1);
+    }
+}
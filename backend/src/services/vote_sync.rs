@@ -0,0 +1,306 @@
+//! Recebimento de votos em lotes enviados pelas urnas
+//!
+//! Cada parte traz vários votos e uma chave de idempotência. A mesma chave
+//! devolve a confirmação já emitida, sem registrar nada de novo, e uma
+//! chave reaproveitada com outro conteúdo é recusada. Dentro da parte cada
+//! voto segue as mesmas regras do envio individual: urna ativa, assinatura
+//! da urna e registro pelo `ReceiptService`. Os votos registrados voltam
+//! com a prova de inclusão relativa a uma STH publicada ao fim da parte.
+//!
+//! Cada etapa (registro dos votos, provas de inclusão) roda em um span
+//! próprio; por voto, a conferência da assinatura e o registro têm spans
+//! de nível debug, filhos do rastro do atendimento na urna quando a parte
+//! traz o `traceparent` do voto.
+//!
+//! Com o cadastro de regiões, a seção do voto é a da urna designada; o voto
+//! que declara outra seção é recusado.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+pub use fortis_domain::vote_sync::{
+    VoteSyncAck, VoteSyncChunk, VoteSyncResult, VoteSyncStatusCode, IDEMPOTENCY_KEY_HEADER,
+};
+use crate::services::receipts::{tracking_code, ReceiptService};
+use crate::services::regions::RegionService;
+use crate::services::urna::ContingencyService;
+use crate::telemetry;
+
+/// Votos por parte aceitos pelo backend
+pub const MAX_VOTES_PER_CHUNK: usize = 5000;
+
+/// Tempo de guarda das confirmações para reenvio
+const IDEMPOTENCY_TTL_HOURS: i64 = 72;
+
+struct StoredAck {
+    content_digest: String,
+    ack: VoteSyncAck,
+    stored_at: DateTime<Utc>,
+}
+
+/// Serviço de recebimento de votos em lote
+pub struct VoteSyncService {
+    receipts: Arc<ReceiptService>,
+    contingency: Arc<ContingencyService>,
+    regions: Option<Arc<RegionService>>,
+    acks: RwLock<HashMap<String, StoredAck>>,
+}
+
+impl VoteSyncService {
+    pub fn new(receipts: Arc<ReceiptService>, contingency: Arc<ContingencyService>) -> Self {
+        Self {
+            receipts,
+            contingency,
+            regions: None,
+            acks: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registra os votos com a seção da urna designada
+    pub fn with_regions(mut self, regions: Arc<RegionService>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    /// Registra a parte ou devolve a confirmação já emitida para a chave
+    #[tracing::instrument(
+        name = "vote_sync.ingest",
+        skip_all,
+        fields(urna_id = %chunk.urna_id, upload_id = %chunk.upload_id, chunk = chunk.chunk_index, votes = chunk.votes.len()),
+    )]
+    pub async fn ingest(&self, idempotency_key: &str, chunk: VoteSyncChunk) -> Result<VoteSyncAck> {
+        if idempotency_key != chunk.idempotency_key() {
+            return Err(anyhow!("Idempotency key does not match chunk {}", chunk.chunk_index));
+        }
+        if chunk.votes.len() > MAX_VOTES_PER_CHUNK {
+            return Err(anyhow!("Chunk exceeds {} votes", MAX_VOTES_PER_CHUNK));
+        }
+
+        let content_digest = chunk.content_digest();
+        if let Some(stored) = self.acks.read().await.get(idempotency_key) {
+            if stored.content_digest != content_digest {
+                return Err(anyhow!("Idempotency key {} reused with different votes", idempotency_key));
+            }
+            return Ok(VoteSyncAck { replayed: true, ..stored.ack.clone() });
+        }
+
+        if self.contingency.is_retired(&chunk.urna_id).await {
+            return Err(anyhow!("Urna {} retired by contingency transfer", chunk.urna_id));
+        }
+
+        let mut results = Vec::with_capacity(chunk.votes.len());
+        async {
+            for vote in &chunk.votes {
+                let span = tracing::debug_span!("vote_sync.vote", vote_id = %vote.id);
+                if let Some(traceparent) = chunk.trace_contexts.get(&vote.id) {
+                    span.set_parent(telemetry::context_from_traceparent(traceparent));
+                }
+                results.push(self.record(&chunk.urna_id, vote).instrument(span).await);
+            }
+        }
+        .instrument(tracing::info_span!("vote_sync.record_votes"))
+        .await;
+        self.attach_inclusions(&mut results)
+            .instrument(tracing::info_span!("vote_sync.inclusion_proofs"))
+            .await;
+
+        let ack = VoteSyncAck {
+            upload_id: chunk.upload_id,
+            chunk_index: chunk.chunk_index,
+            results,
+            replayed: false,
+        };
+
+        let mut acks = self.acks.write().await;
+        let cutoff = Utc::now() - Duration::hours(IDEMPOTENCY_TTL_HOURS);
+        acks.retain(|_, stored| stored.stored_at > cutoff);
+        acks.insert(idempotency_key.to_string(), StoredAck {
+            content_digest,
+            ack: ack.clone(),
+            stored_at: Utc::now(),
+        });
+
+        log::info!(
+            "Vote chunk {}/{} of upload {} from urna {}: {} votes",
+            chunk.chunk_index + 1, chunk.chunk_count, chunk.upload_id, chunk.urna_id, chunk.votes.len(),
+        );
+        Ok(ack)
+    }
+
+    /// Anexa as provas de inclusão; sem elas a urna reenvia mais tarde
+    async fn attach_inclusions(&self, results: &mut [VoteSyncResult]) {
+        let codes: Vec<String> = results.iter()
+            .filter(|result| result.is_synced())
+            .filter_map(|result| result.tracking_code.clone())
+            .collect();
+        if codes.is_empty() {
+            return;
+        }
+
+        match self.receipts.inclusions(&codes).await {
+            Ok(mut inclusions) => {
+                for result in results.iter_mut() {
+                    if let Some(code) = &result.tracking_code {
+                        result.inclusion = inclusions.remove(code);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Failed to build inclusion proofs for synced votes: {}", e),
+        }
+    }
+
+    async fn record(&self, urna_id: &str, vote: &fortis_domain::EncryptedVote) -> VoteSyncResult {
+        let rejected = |error: String| VoteSyncResult {
+            vote_id: vote.id,
+            status: VoteSyncStatusCode::Rejected,
+            tracking_code: None,
+            error: Some(error),
+            inclusion: None,
+        };
+
+        if let Err(e) = self.contingency
+            .verify_urna_signature(urna_id, &vote.encrypted_data, &vote.signature)
+            .instrument(tracing::debug_span!("vote.verify_signature", vote_id = %vote.id))
+            .await
+        {
+            return rejected(e.to_string());
+        }
+
+        let ballot_hash = vote.ballot_hash();
+        let code = tracking_code(vote.id, &ballot_hash);
+        if self.receipts.recorded_entry(&code).await.is_some() {
+            return VoteSyncResult {
+                vote_id: vote.id,
+                status: VoteSyncStatusCode::Duplicate,
+                tracking_code: Some(code),
+                error: None,
+                inclusion: None,
+            };
+        }
+
+        let section = match &self.regions {
+            Some(regions) => match regions.vote_section(urna_id, vote.section.as_ref()).await {
+                Ok(section) => section,
+                Err(e) => return rejected(e.to_string()),
+            },
+            None => None,
+        };
+
+        match self.receipts
+            .record_vote(vote.id, vote.election_id, &ballot_hash, section.as_ref())
+            .instrument(tracing::debug_span!("vote.record", vote_id = %vote.id))
+            .await
+        {
+            Ok(payload) => {
+                self.contingency.record_ballot(urna_id, &vote.election_id.to_string()).await;
+                VoteSyncResult {
+                    vote_id: vote.id,
+                    status: VoteSyncStatusCode::Recorded,
+                    tracking_code: Some(payload.tracking_code),
+                    error: None,
+                    inclusion: None,
+                }
+            }
+            Err(e) => rejected(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transparency::election_logs::{ElectionTransparencyLog, LogConfig};
+    use base64::{engine::general_purpose, Engine as _};
+    use fortis_domain::EncryptedVote;
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+    use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+    use sha2::{Digest, Sha256};
+    use uuid::Uuid;
+
+    async fn fixture() -> (VoteSyncService, RsaPrivateKey) {
+        let log = Arc::new(RwLock::new(ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        })));
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        let pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+        let contingency = Arc::new(ContingencyService::new());
+        contingency.register_urna_key("urna-1", &pem, public_key.to_public_key_der().unwrap().as_bytes()).await.unwrap();
+
+        (VoteSyncService::new(Arc::new(ReceiptService::new(log)), contingency), private_key)
+    }
+
+    fn vote(key: &RsaPrivateKey, data: &[u8]) -> EncryptedVote {
+        let signature = key.sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(data)).unwrap();
+        EncryptedVote {
+            id: Uuid::new_v4(),
+            election_id: Uuid::new_v4(),
+            voter_id: Uuid::new_v4(),
+            candidate_id: Uuid::new_v4(),
+            choices: Vec::new(),
+            encrypted_data: data.to_vec(),
+            zk_proof: String::new(),
+            signature: general_purpose::STANDARD.encode(signature),
+            timestamp: Utc::now(),
+            section: None,
+        }
+    }
+
+    fn chunk(index: u32, votes: Vec<EncryptedVote>) -> VoteSyncChunk {
+        VoteSyncChunk {
+            urna_id: "urna-1".to_string(),
+            upload_id: Uuid::nil(),
+            chunk_index: index,
+            chunk_count: 2,
+            votes,
+            trace_contexts: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replayed_chunk_returns_same_ack() {
+        let (service, key) = fixture().await;
+        let first = chunk(0, vec![vote(&key, b"a"), vote(&key, b"b")]);
+
+        let ack = service.ingest(&first.idempotency_key(), first.clone()).await.unwrap();
+        assert!(ack.results.iter().all(|r| r.status == VoteSyncStatusCode::Recorded));
+        assert!(ack.results.iter().all(|r| r.inclusion.is_some()));
+
+        let replay = service.ingest(&first.idempotency_key(), first.clone()).await.unwrap();
+        assert!(replay.replayed);
+        assert_eq!(replay.results[0].tracking_code, ack.results[0].tracking_code);
+
+        let mut other = first.clone();
+        other.votes.pop();
+        assert!(service.ingest(&first.idempotency_key(), other).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resent_vote_is_duplicate_and_forged_vote_rejected() {
+        let (service, key) = fixture().await;
+        let recorded = vote(&key, b"a");
+        let first = chunk(0, vec![recorded.clone()]);
+        service.ingest(&first.idempotency_key(), first).await.unwrap();
+
+        let mut forged = vote(&key, b"b");
+        forged.encrypted_data = b"c".to_vec();
+        let second = chunk(1, vec![recorded, forged]);
+        let ack = service.ingest(&second.idempotency_key(), second).await.unwrap();
+
+        assert_eq!(ack.results[0].status, VoteSyncStatusCode::Duplicate);
+        assert!(ack.results[0].is_synced());
+        assert_eq!(ack.results[1].status, VoteSyncStatusCode::Rejected);
+    }
+}
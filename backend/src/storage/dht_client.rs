@@ -0,0 +1,412 @@
+//! Cliente Kademlia (libp2p) para metadados do armazenamento
+//!
+//! Cada nó regional anuncia na DHT onde estão os artefatos da eleição:
+//! sob a chave lógica (`package:<eleição>`, `audit:<id>`, ...) fica um
+//! registro com os CIDs publicados, e cada CID tem registros de provedor
+//! apontando os nós que guardam o conteúdo. O conteúdo em si continua no
+//! IPFS e é sempre conferido pelo endereço; a DHT só diz onde procurar.
+//!
+//! O swarm roda numa tarefa própria e recebe comandos por canal; as
+//! consultas respondem quando a consulta Kademlia termina.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use libp2p::{
+    identity, kad, kad::store::RecordStore, multiaddr::Protocol, noise, swarm::SwarmEvent, tcp, yamux, Multiaddr, PeerId,
+    StreamProtocol, Swarm,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, RwLock};
+
+use crate::config::StorageConfig;
+use crate::monitoring::MonitoringSystem;
+
+/// Protocolo próprio, para não misturar a DHT da FORTIS com a DHT pública do IPFS
+const FORTIS_KAD_PROTOCOL: &str = "/fortis/kad/1.0.0";
+
+/// Registro de localização publicado sob uma chave lógica
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocationRecord {
+    pub cids: BTreeSet<String>,
+    pub regions: BTreeSet<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl LocationRecord {
+    /// Une registros vindos de nós diferentes para a mesma chave
+    pub fn merge(&mut self, other: LocationRecord) {
+        self.cids.extend(other.cids);
+        self.regions.extend(other.regions);
+        self.updated_at = self.updated_at.max(other.updated_at);
+    }
+}
+
+/// Nó que declarou guardar um CID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentProvider {
+    pub peer_id: String,
+    pub addresses: Vec<String>,
+}
+
+/// Conteúdo localizado: CID, regiões que o anunciaram e nós que o guardam
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentLocation {
+    pub cid: String,
+    pub regions: BTreeSet<String>,
+    pub providers: Vec<ContentProvider>,
+}
+
+/// Saúde da DHT, exposta nas métricas de armazenamento
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DhtHealth {
+    pub peer_id: String,
+    pub region: String,
+    pub connected_peers: usize,
+    pub routing_table_size: usize,
+    pub provided_keys: usize,
+    pub queries_succeeded: u64,
+    pub queries_failed: u64,
+    pub last_bootstrap: Option<DateTime<Utc>>,
+}
+
+/// Nó de bootstrap: endereço que termina em `/p2p/<peer id>`
+pub fn parse_bootstrap_node(address: &str) -> Result<(PeerId, Multiaddr)> {
+    let addr: Multiaddr = address.parse()
+        .map_err(|e| anyhow!("Invalid bootstrap address {}: {}", address, e))?;
+    match addr.iter().last() {
+        Some(Protocol::P2p(peer_id)) => Ok((peer_id, addr)),
+        _ => Err(anyhow!("Bootstrap address {} has no /p2p/<peer id>", address)),
+    }
+}
+
+enum Command {
+    Announce { key: String, cid: String, reply: oneshot::Sender<Result<()>> },
+    Resolve { key: String, reply: oneshot::Sender<Result<LocationRecord>> },
+    Providers { cid: String, reply: oneshot::Sender<Result<Vec<ContentProvider>>> },
+}
+
+enum Pending {
+    Put(oneshot::Sender<Result<()>>),
+    Get(LocationRecord, oneshot::Sender<Result<LocationRecord>>),
+    Providers(BTreeSet<PeerId>, oneshot::Sender<Result<Vec<ContentProvider>>>),
+}
+
+/// Cliente DHT para descoberta de dados
+pub struct DhtClient {
+    commands: mpsc::Sender<Command>,
+    health: Arc<RwLock<DhtHealth>>,
+}
+
+impl DhtClient {
+    /// Sobe o swarm, escuta no endereço configurado e inicia o bootstrap
+    pub fn start(config: &StorageConfig, monitoring: Option<Arc<MonitoringSystem>>) -> Result<Self> {
+        let bootstrap = config.dht_bootstrap_nodes.iter()
+            .map(|address| parse_bootstrap_node(address))
+            .collect::<Result<Vec<_>>>()?;
+        let listen = config.dht_listen_address.as_deref()
+            .ok_or_else(|| anyhow!("DHT listen address not configured"))?;
+        let listen: Multiaddr = listen.parse()
+            .map_err(|e| anyhow!("Invalid DHT listen address {}: {}", listen, e))?;
+
+        let provider_ttl = Duration::from_secs(config.dht_provider_ttl_seconds);
+        let republish = Duration::from_secs(config.dht_provider_republish_seconds);
+        let mut swarm = libp2p::SwarmBuilder::with_existing_identity(identity::Keypair::generate_ed25519())
+            .with_tokio()
+            .with_tcp(tcp::Config::default(), noise::Config::new, yamux::Config::default)?
+            .with_behaviour(|key| {
+                let peer_id = key.public().to_peer_id();
+                let mut kad_config = kad::Config::default();
+                kad_config
+                    .set_protocol_names(vec![StreamProtocol::new(FORTIS_KAD_PROTOCOL)])
+                    .set_provider_record_ttl(Some(provider_ttl))
+                    .set_provider_publication_interval(Some(republish))
+                    .set_record_ttl(Some(provider_ttl))
+                    .set_publication_interval(Some(republish));
+                kad::Behaviour::with_config(peer_id, kad::store::MemoryStore::new(peer_id), kad_config)
+            })?
+            .with_swarm_config(|c| c.with_idle_connection_timeout(Duration::from_secs(60)))
+            .build();
+
+        swarm.behaviour_mut().set_mode(Some(kad::Mode::Server));
+        swarm.listen_on(listen)?;
+        for (peer_id, addr) in bootstrap {
+            swarm.behaviour_mut().add_address(&peer_id, addr);
+        }
+
+        let health = Arc::new(RwLock::new(DhtHealth {
+            peer_id: swarm.local_peer_id().to_string(),
+            region: config.region.clone(),
+            ..DhtHealth::default()
+        }));
+        let (commands, receiver) = mpsc::channel(64);
+        let worker = DhtWorker {
+            swarm,
+            region: config.region.clone(),
+            pending: HashMap::new(),
+            health: health.clone(),
+            monitoring,
+        };
+        tokio::spawn(worker.run(receiver, Duration::from_secs(config.dht_bootstrap_interval_seconds)));
+
+        Ok(Self { commands, health })
+    }
+
+    /// Registra o CID sob a chave e anuncia este nó como provedor do conteúdo
+    pub async fn announce(&self, key: &str, cid: &str) -> Result<()> {
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Announce { key: key.to_string(), cid: cid.to_string(), reply }).await?;
+        response.await.map_err(|_| anyhow!("DHT worker stopped"))?
+    }
+
+    /// CIDs conhecidos sob a chave, unidos de todos os nós que responderam
+    pub async fn resolve(&self, key: &str) -> Result<LocationRecord> {
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Resolve { key: key.to_string(), reply }).await?;
+        response.await.map_err(|_| anyhow!("DHT worker stopped"))?
+    }
+
+    /// Nós que guardam o CID
+    pub async fn providers(&self, cid: &str) -> Result<Vec<ContentProvider>> {
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Providers { cid: cid.to_string(), reply }).await?;
+        response.await.map_err(|_| anyhow!("DHT worker stopped"))?
+    }
+
+    pub async fn health(&self) -> DhtHealth {
+        self.health.read().await.clone()
+    }
+
+    async fn send(&self, command: Command) -> Result<()> {
+        self.commands.send(command).await.map_err(|_| anyhow!("DHT worker stopped"))
+    }
+}
+
+struct DhtWorker {
+    swarm: Swarm<kad::Behaviour<kad::store::MemoryStore>>,
+    region: String,
+    pending: HashMap<kad::QueryId, Pending>,
+    health: Arc<RwLock<DhtHealth>>,
+    monitoring: Option<Arc<MonitoringSystem>>,
+}
+
+impl DhtWorker {
+    async fn run(mut self, mut commands: mpsc::Receiver<Command>, bootstrap_interval: Duration) {
+        let mut bootstrap = tokio::time::interval(bootstrap_interval);
+        loop {
+            tokio::select! {
+                _ = bootstrap.tick() => {
+                    if let Err(e) = self.swarm.behaviour_mut().bootstrap() {
+                        log::warn!("DHT bootstrap skipped: {:?}", e);
+                    }
+                }
+                command = commands.recv() => match command {
+                    Some(command) => self.handle_command(command),
+                    None => break,
+                },
+                event = self.swarm.select_next_some() => self.handle_event(event).await,
+            }
+        }
+    }
+
+    fn handle_command(&mut self, command: Command) {
+        let kademlia = self.swarm.behaviour_mut();
+        match command {
+            Command::Announce { key, cid, reply } => {
+                // Une com o que este nó já publicou sob a chave; a DHT guarda um valor por chave
+                let record_key = kad::RecordKey::new(&key);
+                let mut location = kademlia.store_mut().get(&record_key)
+                    .and_then(|record| serde_json::from_slice::<LocationRecord>(&record.value).ok())
+                    .unwrap_or_default();
+                location.cids.insert(cid.clone());
+                location.regions.insert(self.region.clone());
+                location.updated_at = Some(Utc::now());
+
+                if let Err(e) = kademlia.start_providing(kad::RecordKey::new(&cid)) {
+                    let _ = reply.send(Err(anyhow!("Failed to provide {}: {:?}", cid, e)));
+                    return;
+                }
+                let value = serde_json::to_vec(&location).unwrap_or_default();
+                match kademlia.put_record(kad::Record::new(record_key, value), kad::Quorum::One) {
+                    Ok(query) => {
+                        self.pending.insert(query, Pending::Put(reply));
+                    }
+                    Err(e) => {
+                        let _ = reply.send(Err(anyhow!("Failed to store location of {}: {:?}", key, e)));
+                    }
+                }
+            }
+            Command::Resolve { key, reply } => {
+                let record_key = kad::RecordKey::new(&key);
+                let local = kademlia.store_mut().get(&record_key)
+                    .and_then(|record| serde_json::from_slice::<LocationRecord>(&record.value).ok())
+                    .unwrap_or_default();
+                let query = kademlia.get_record(record_key);
+                self.pending.insert(query, Pending::Get(local, reply));
+            }
+            Command::Providers { cid, reply } => {
+                let query = kademlia.get_providers(kad::RecordKey::new(&cid));
+                self.pending.insert(query, Pending::Providers(BTreeSet::new(), reply));
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, event: SwarmEvent<kad::Event>) {
+        match event {
+            SwarmEvent::Behaviour(kad::Event::OutboundQueryProgressed { id, result, step, .. }) => {
+                self.handle_query(id, result, step.last).await;
+            }
+            SwarmEvent::Behaviour(kad::Event::RoutingUpdated { .. })
+            | SwarmEvent::ConnectionEstablished { .. }
+            | SwarmEvent::ConnectionClosed { .. } => {}
+            SwarmEvent::NewListenAddr { address, .. } => {
+                log::info!("DHT listening on {}/p2p/{}", address, self.swarm.local_peer_id());
+                return;
+            }
+            _ => return,
+        }
+        self.refresh_health().await;
+    }
+
+    async fn handle_query(&mut self, id: kad::QueryId, result: kad::QueryResult, last: bool) {
+        let succeeded = match result {
+            kad::QueryResult::Bootstrap(outcome) => {
+                if outcome.as_ref().map(|ok| ok.num_remaining == 0).unwrap_or(true) {
+                    self.health.write().await.last_bootstrap = Some(Utc::now());
+                }
+                outcome.is_ok()
+            }
+            kad::QueryResult::StartProviding(outcome) | kad::QueryResult::RepublishProvider(outcome) => {
+                if let Err(e) = &outcome {
+                    log::warn!("DHT provider record not replicated: {:?}", e);
+                }
+                outcome.is_ok()
+            }
+            kad::QueryResult::PutRecord(outcome) => {
+                let ok = outcome.is_ok();
+                if let Some(Pending::Put(reply)) = self.pending.remove(&id) {
+                    let _ = reply.send(outcome.map(|_| ()).map_err(|e| anyhow!("DHT put failed: {:?}", e)));
+                }
+                ok
+            }
+            kad::QueryResult::GetRecord(outcome) => {
+                let ok = outcome.is_ok();
+                if let (Ok(kad::GetRecordOk::FoundRecord(found)), Some(Pending::Get(location, _))) =
+                    (&outcome, self.pending.get_mut(&id))
+                {
+                    if let Ok(record) = serde_json::from_slice::<LocationRecord>(&found.record.value) {
+                        location.merge(record);
+                    }
+                }
+                // Chave ausente não é falha: devolve o que houver localmente
+                if last || outcome.is_err() {
+                    if let Some(Pending::Get(location, reply)) = self.pending.remove(&id) {
+                        let _ = reply.send(Ok(location));
+                    }
+                }
+                ok
+            }
+            kad::QueryResult::GetProviders(outcome) => {
+                let ok = outcome.is_ok();
+                if let (Ok(kad::GetProvidersOk::FoundProviders { providers, .. }), Some(Pending::Providers(found, _))) =
+                    (&outcome, self.pending.get_mut(&id))
+                {
+                    found.extend(providers.iter().copied());
+                }
+                if last || outcome.is_err() {
+                    if let Some(Pending::Providers(found, reply)) = self.pending.remove(&id) {
+                        let providers = found.into_iter().map(|peer| self.provider(peer)).collect();
+                        let _ = reply.send(Ok(providers));
+                    }
+                }
+                ok
+            }
+            _ => return,
+        };
+
+        if let Some(monitoring) = &self.monitoring {
+            monitoring.increment_counter("dht_operations", 1).await;
+            if !succeeded {
+                monitoring.increment_counter("dht_query_failures", 1).await;
+            }
+        }
+        let mut health = self.health.write().await;
+        if succeeded {
+            health.queries_succeeded += 1;
+        } else {
+            health.queries_failed += 1;
+        }
+    }
+
+    fn provider(&mut self, peer: PeerId) -> ContentProvider {
+        let addresses = if peer == *self.swarm.local_peer_id() {
+            self.swarm.listeners().map(|a| a.to_string()).collect()
+        } else {
+            self.swarm.behaviour_mut().kbucket(peer)
+                .and_then(|bucket| {
+                    bucket.iter()
+                        .find(|entry| entry.node.key.preimage() == &peer)
+                        .map(|entry| entry.node.value.iter().map(|a| a.to_string()).collect())
+                })
+                .unwrap_or_default()
+        };
+        ContentProvider { peer_id: peer.to_string(), addresses }
+    }
+
+    async fn refresh_health(&mut self) {
+        let connected_peers = self.swarm.connected_peers().count();
+        let kademlia = self.swarm.behaviour_mut();
+        let routing_table_size: usize = kademlia.kbuckets().map(|bucket| bucket.num_entries()).sum();
+        let provided_keys = kademlia.store_mut().provided().count();
+
+        {
+            let mut health = self.health.write().await;
+            health.connected_peers = connected_peers;
+            health.routing_table_size = routing_table_size;
+            health.provided_keys = provided_keys;
+        }
+        if let Some(monitoring) = &self.monitoring {
+            monitoring.set_gauge("dht_connected_peers", connected_peers as f64).await;
+            monitoring.set_gauge("dht_routing_table_size", routing_table_size as f64).await;
+            monitoring.set_gauge("dht_provided_keys", provided_keys as f64).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bootstrap_node_requires_peer_id() {
+        let peer = identity::Keypair::generate_ed25519().public().to_peer_id();
+        let (parsed, addr) = parse_bootstrap_node(&format!("/ip4/10.0.0.5/tcp/4001/p2p/{}", peer)).unwrap();
+        assert_eq!(parsed, peer);
+        assert!(addr.to_string().starts_with("/ip4/10.0.0.5/tcp/4001"));
+
+        assert!(parse_bootstrap_node("/ip4/10.0.0.5/tcp/4001").is_err());
+        assert!(parse_bootstrap_node("10.0.0.5:4001").is_err());
+    }
+
+    #[test]
+    fn test_location_records_merge_across_regions() {
+        let mut sp = LocationRecord {
+            cids: ["bafkreia".to_string()].into(),
+            regions: ["sp".to_string()].into(),
+            updated_at: Some(Utc::now() - chrono::Duration::minutes(5)),
+        };
+        let df = LocationRecord {
+            cids: ["bafkreia".to_string(), "bafkreib".to_string()].into(),
+            regions: ["df".to_string()].into(),
+            updated_at: Some(Utc::now()),
+        };
+        sp.merge(df.clone());
+
+        assert_eq!(sp.cids.len(), 2);
+        assert_eq!(sp.regions.len(), 2);
+        assert_eq!(sp.updated_at, df.updated_at);
+    }
+}
@@ -4,299 +4,248 @@
 //! e IPFS, seguindo os princípios do Prof. Marcos Simplicio de usar
 //! tecnologias apropriadas para cada problema, evitando replicação
 //! completa desnecessária.
+//!
+//! Com codificação por apagamento configurada, cada artefato publicado é
+//! dividido em fragmentos Reed–Solomon distribuídos entre os nós de
+//! armazenamento; o reparo periódico recria os fragmentos perdidos. O índice
+//! dos artefatos codificados (manifesto e nó de cada fragmento) fica em disco,
+//! de modo que o reparo continua cobrindo o que foi publicado antes de um
+//! reinício.
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use anyhow::{Result, anyhow};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::sync::RwLock;
 
-/// Cliente IPFS para armazenamento descentralizado
-pub struct IpfsClient {
-    endpoint: String,
-    client: reqwest::Client,
+use crate::monitoring::MonitoringSystem;
+use super::dht_client::{ContentLocation, DhtClient};
+use super::erasure::{self, ErasurePolicy, ShardManifest, ShardRef};
+use super::ipfs_client::{ContentAddress, IpfsClient};
+use super::local_cache::{InvalidationHook, LocalCache};
+
+/// Validade padrão do que é guardado no cache local
+const DEFAULT_CACHE_TTL_HOURS: i64 = 24;
+/// Localizações resolvidas na DHT mudam com republicações; ficam pouco tempo no cache
+const DHT_CACHE_TTL_SECONDS: i64 = 60;
+
+/// Nó de armazenamento que recebe fragmentos
+pub struct StorageNode {
+    pub name: String,
+    pub client: IpfsClient,
 }
 
-impl IpfsClient {
-    pub fn new(endpoint: String) -> Self {
-        Self {
-            endpoint,
-            client: reqwest::Client::new(),
-        }
-    }
-
-    /// Adiciona dados ao IPFS
-    pub async fn add_data(&self, data: &[u8]) -> Result<String> {
-        let form = reqwest::multipart::Form::new()
-            .part("file", reqwest::multipart::Part::bytes(data.to_vec()));
-
-        let response = self.client
-            .post(&format!("{}/api/v0/add", self.endpoint))
-            .multipart(form)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!("Failed to add data to IPFS: {}", response.status()));
-        }
-
-        let result: serde_json::Value = response.json().await?;
-        let hash = result["Hash"]
-            .as_str()
-            .ok_or_else(|| anyhow!("Invalid IPFS response"))?;
-
-        Ok(hash.to_string())
-    }
-
-    /// Recupera dados do IPFS
-    pub async fn get_data(&self, hash: &str) -> Result<Vec<u8>> {
-        let response = self.client
-            .get(&format!("{}/api/v0/cat?arg={}", self.endpoint, hash))
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!("Failed to get data from IPFS: {}", response.status()));
-        }
-
-        let data = response.bytes().await?;
-        Ok(data.to_vec())
-    }
-
-    /// Verifica se dados existem no IPFS
-    pub async fn exists(&self, hash: &str) -> Result<bool> {
-        let response = self.client
-            .post(&format!("{}/api/v0/pin/ls?arg={}", self.endpoint, hash))
-            .send()
-            .await?;
+/// Artefato codificado publicado por este nó e onde está cada fragmento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ShardedArtifact {
+    manifest: ShardManifest,
+    locations: BTreeMap<usize, String>,
+}
 
-        Ok(response.status().is_success())
-    }
+/// Resultado do reparo dos fragmentos de um artefato
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardRepairReport {
+    pub key: String,
+    pub manifest_cid: String,
+    pub missing: Vec<usize>,
+    /// Fragmento recriado e o nó que passou a guardá-lo
+    pub repaired: Vec<(usize, String)>,
+    pub error: Option<String>,
 }
 
-/// Cliente DHT para descoberta de dados
-pub struct DhtClient {
-    nodes: RwLock<HashMap<String, DhtNode>>,
+/// Sistema de armazenamento distribuído principal
+pub struct DistributedStorage {
+    ipfs_client: IpfsClient,
+    dht_client: Option<Arc<DhtClient>>,
+    erasure: Option<ErasurePolicy>,
+    storage_nodes: Vec<StorageNode>,
+    /// Artefatos codificados publicados por este nó, pelo CID do manifesto
+    sharded: RwLock<HashMap<String, ShardedArtifact>>,
+    /// Arquivo com o índice de `sharded`, lido na subida
+    shard_index_path: Option<PathBuf>,
+    monitoring: Option<Arc<MonitoringSystem>>,
+    local_cache: LocalCache,
+    cache_ttl: chrono::Duration,
+    /// Último CID publicado por este nó sob cada chave
+    published: RwLock<HashMap<String, String>>,
     local_node_id: String,
 }
 
-#[derive(Debug, Clone)]
-pub struct DhtNode {
-    pub id: String,
-    pub address: String,
-    pub port: u16,
-    pub last_seen: DateTime<Utc>,
-}
+impl DistributedStorage {
+    pub fn new(ipfs_endpoint: String, local_node_id: String, cache_size: usize) -> Self {
+        Self::with_ipfs_client(IpfsClient::new(ipfs_endpoint), local_node_id, cache_size)
+    }
 
-impl DhtClient {
-    pub fn new(local_node_id: String) -> Self {
+    /// Usa um cliente IPFS já configurado (timeout, gateways)
+    pub fn with_ipfs_client(ipfs_client: IpfsClient, local_node_id: String, cache_size: usize) -> Self {
         Self {
-            nodes: RwLock::new(HashMap::new()),
+            ipfs_client,
+            dht_client: None,
+            erasure: None,
+            storage_nodes: Vec::new(),
+            sharded: RwLock::new(HashMap::new()),
+            shard_index_path: None,
+            monitoring: None,
+            local_cache: LocalCache::new(cache_size),
+            cache_ttl: chrono::Duration::hours(DEFAULT_CACHE_TTL_HOURS),
+            published: RwLock::new(HashMap::new()),
             local_node_id,
         }
     }
 
-    /// Registra um nó na DHT
-    pub async fn register_node(&self, node: DhtNode) {
-        let mut nodes = self.nodes.write().await;
-        nodes.insert(node.id.clone(), node);
+    /// Substitui o cache em memória por um já configurado (limites, disco, métricas)
+    pub fn with_cache(mut self, cache: LocalCache, ttl: chrono::Duration) -> Self {
+        self.local_cache = cache;
+        self.cache_ttl = ttl;
+        self
     }
 
-    /// Descobre nós próximos a uma chave
-    pub async fn find_nodes(&self, key: &str) -> Result<Vec<DhtNode>> {
-        let nodes = self.nodes.read().await;
-        let mut closest_nodes = Vec::new();
-
-        // Simulação de descoberta de nós próximos
-        // Em implementação real, usaria algoritmo de roteamento DHT
-        for (_, node) in nodes.iter() {
-            if self.is_close_key(&node.id, key) {
-                closest_nodes.push(node.clone());
-            }
-        }
-
-        // Ordenar por proximidade
-        closest_nodes.sort_by(|a, b| {
-            self.distance(&a.id, key).cmp(&self.distance(&b.id, key))
-        });
-
-        Ok(closest_nodes.into_iter().take(8).collect())
+    /// Gancho chamado a cada entrada invalidada, inclusive por republicação
+    pub fn on_invalidate(&self, hook: InvalidationHook) {
+        self.local_cache.on_invalidate(hook);
     }
 
-    /// Registra um boletim de urna na DHT
-    pub async fn register_ballot(&self, election_id: &str, ballot_hash: &str) -> Result<()> {
-        let key = format!("ballot:{}:{}", election_id, ballot_hash);
-        let nodes = self.find_nodes(&key).await?;
-
-        // Registrar em múltiplos nós para redundância
-        for node in nodes {
-            self.register_ballot_at_node(&node, &key, ballot_hash).await?;
-        }
-
-        Ok(())
+    /// Anuncia e resolve localizações na DHT entre os nós regionais
+    pub fn with_dht(mut self, dht_client: Arc<DhtClient>) -> Self {
+        self.dht_client = Some(dht_client);
+        self
     }
 
-    /// Descobre boletins de urna para uma eleição
-    pub async fn discover_ballots(&self, election_id: &str) -> Result<Vec<String>> {
-        let key = format!("ballot:{}", election_id);
-        let nodes = self.find_nodes(&key).await?;
-
-        let mut ballots = Vec::new();
-        for node in nodes {
-            let node_ballots = self.get_ballots_from_node(&node, election_id).await?;
-            ballots.extend(node_ballots);
+    /// Publica os artefatos em fragmentos Reed–Solomon distribuídos entre os nós;
+    /// sem nós informados, os fragmentos ficam no nó IPFS principal
+    pub fn with_erasure(mut self, policy: ErasurePolicy, storage_nodes: Vec<StorageNode>) -> Self {
+        if !storage_nodes.is_empty() && storage_nodes.len() < policy.total_shards() {
+            log::warn!(
+                "{} storage nodes for {} shards: losing one node may lose more than one shard",
+                storage_nodes.len(), policy.total_shards()
+            );
         }
-
-        // Remover duplicatas
-        ballots.sort();
-        ballots.dedup();
-
-        Ok(ballots)
-    }
-
-    /// Verifica se uma chave está próxima a um nó
-    fn is_close_key(&self, node_id: &str, key: &str) -> bool {
-        // Simulação de proximidade baseada em hash
-        let node_hash = self.hash_string(node_id);
-        let key_hash = self.hash_string(key);
-        self.distance(&node_hash, &key_hash) < 1000
-    }
-
-    /// Calcula distância entre duas chaves
-    fn distance(&self, key1: &str, key2: &str) -> u64 {
-        let hash1 = self.hash_string(key1);
-        let hash2 = self.hash_string(key2);
-        hash1.chars().zip(hash2.chars())
-            .map(|(c1, c2)| (c1 as u64).wrapping_sub(c2 as u64))
-            .sum()
+        self.erasure = Some(policy);
+        self.storage_nodes = storage_nodes;
+        self
     }
 
-    /// Calcula hash de uma string
-    fn hash_string(&self, input: &str) -> String {
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(input.as_bytes());
-        format!("{:x}", hasher.finalize())
+    /// Mantém o índice dos artefatos codificados no arquivo, carregando o que já existe
+    pub fn with_shard_index(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        match std::fs::read(&path) {
+            Ok(contents) => {
+                let index: HashMap<String, ShardedArtifact> = serde_json::from_slice(&contents)
+                    .map_err(|e| anyhow!("Invalid shard index {}: {}", path.display(), e))?;
+                log::info!("Loaded {} sharded artifacts from {}", index.len(), path.display());
+                *self.sharded.get_mut() = index;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(anyhow!("Failed to read shard index {}: {}", path.display(), e)),
+        }
+        self.shard_index_path = Some(path);
+        Ok(self)
     }
 
-    /// Registra boletim em um nó específico
-    async fn register_ballot_at_node(&self, node: &DhtNode, key: &str, ballot_hash: &str) -> Result<()> {
-        // Em implementação real, faria chamada HTTP para o nó
-        // Por enquanto, apenas simula
-        log::info!("Registering ballot {} at node {}", ballot_hash, node.id);
-        Ok(())
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringSystem>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
     }
 
-    /// Obtém boletins de um nó específico
-    async fn get_ballots_from_node(&self, node: &DhtNode, election_id: &str) -> Result<Vec<String>> {
-        // Em implementação real, faria chamada HTTP para o nó
-        // Por enquanto, apenas simula
-        log::info!("Getting ballots for election {} from node {}", election_id, node.id);
-        Ok(vec![])
+    pub fn dht(&self) -> Option<&Arc<DhtClient>> {
+        self.dht_client.as_ref()
     }
-}
 
-/// Cache local para performance
-pub struct LocalCache {
-    cache: RwLock<HashMap<String, CachedItem>>,
-    max_size: usize,
-}
-
-#[derive(Debug, Clone)]
-pub struct CachedItem {
-    pub data: Vec<u8>,
-    pub timestamp: DateTime<Utc>,
-    pub ttl: chrono::Duration,
-}
-
-impl LocalCache {
-    pub fn new(max_size: usize) -> Self {
-        Self {
-            cache: RwLock::new(HashMap::new()),
-            max_size,
+    /// Onde estão os conteúdos publicados sob a chave: CIDs e nós que os guardam
+    pub async fn locate(&self, key: &str) -> Result<Vec<ContentLocation>> {
+        let Some(dht) = &self.dht_client else {
+            return Ok(Vec::new());
+        };
+        let record = dht.resolve(key).await?;
+        let mut locations = Vec::with_capacity(record.cids.len());
+        for cid in record.cids {
+            let providers = dht.providers(&cid).await?;
+            locations.push(ContentLocation { cid, regions: record.regions.clone(), providers });
         }
+        Ok(locations)
     }
 
-    /// Armazena item no cache
-    pub async fn put(&self, key: &str, data: Vec<u8>, ttl: chrono::Duration) -> Result<()> {
-        let mut cache = self.cache.write().await;
+    /// Publica um conteúdo endereçado pelo próprio hash e o registra na DHT sob a chave
+    pub async fn publish(&self, key: &str, data: &[u8]) -> Result<ContentAddress> {
+        let address = match &self.erasure {
+            Some(policy) => self.publish_shards(key, data, policy).await?,
+            None => self.ipfs_client.add(data).await?,
+        };
+        self.announce(key, &address.cid).await;
 
-        // Verificar se cache está cheio
-        if cache.len() >= self.max_size {
-            self.evict_oldest(&mut cache).await?;
+        // Republicação: o conteúdo anterior e a localização em cache deixam de valer
+        let previous = self.published.write().await.insert(key.to_string(), address.cid.clone());
+        if let Some(previous) = previous.filter(|cid| *cid != address.cid) {
+            self.local_cache.invalidate(&format!("cid:{}", previous)).await?;
         }
+        self.local_cache.invalidate(&format!("dht:{}", key)).await?;
 
-        let item = CachedItem {
-            data,
-            timestamp: Utc::now(),
-            ttl,
-        };
-
-        cache.insert(key.to_string(), item);
-        Ok(())
+        self.local_cache.put(&format!("cid:{}", address.cid), data.to_vec(), self.cache_ttl).await?;
+        Ok(address)
     }
 
-    /// Recupera item do cache
-    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
-        let mut cache = self.cache.write().await;
-
-        if let Some(item) = cache.get(key) {
-            // Verificar se item expirou
-            if Utc::now() - item.timestamp > item.ttl {
-                cache.remove(key);
-                return Ok(None);
+    /// Lê um conteúdo publicado, conferido com o endereço
+    pub async fn fetch(&self, address: &ContentAddress) -> Result<Vec<u8>> {
+        let cache_key = format!("cid:{}", address.cid);
+        if let Some(cached) = self.local_cache.get(&cache_key).await? {
+            if address.verify(&cached).is_ok() {
+                return Ok(cached);
             }
-
-            Ok(Some(item.data.clone()))
-        } else {
-            Ok(None)
+            self.local_cache.remove(&cache_key).await?;
         }
-    }
 
-    /// Remove item do cache
-    pub async fn remove(&self, key: &str) -> Result<()> {
-        let mut cache = self.cache.write().await;
-        cache.remove(key);
-        Ok(())
+        let data = match self.shard_manifest(address).await {
+            Some(manifest) => self.reassemble(&manifest).await?,
+            None => self.ipfs_client.cat(address).await?,
+        };
+        self.local_cache.put(&cache_key, data.clone(), self.cache_ttl).await?;
+        Ok(data)
     }
 
-    /// Remove item mais antigo do cache
-    async fn evict_oldest(&self, cache: &mut HashMap<String, CachedItem>) -> Result<()> {
-        let oldest_key = cache.iter()
-            .min_by_key(|(_, item)| item.timestamp)
-            .map(|(key, _)| key.clone());
-
-        if let Some(key) = oldest_key {
-            cache.remove(&key);
+    /// Verifica os fragmentos dos artefatos publicados por este nó e recria os perdidos
+    pub async fn repair_shards(&self) -> Vec<ShardRepairReport> {
+        let artifacts: Vec<(String, ShardedArtifact)> = self.sharded.read().await
+            .iter()
+            .map(|(cid, artifact)| (cid.clone(), artifact.clone()))
+            .collect();
+
+        let mut reports = Vec::with_capacity(artifacts.len());
+        let mut missing_total = 0;
+        for (manifest_cid, artifact) in artifacts {
+            let report = self.repair_artifact(&manifest_cid, artifact).await;
+            missing_total += report.missing.len() - report.repaired.len();
+            if !report.repaired.is_empty() || report.error.is_some() {
+                log::info!(
+                    "Shard repair of {}: {} missing, {} repaired{}",
+                    report.key, report.missing.len(), report.repaired.len(),
+                    report.error.as_deref().map(|e| format!(", {}", e)).unwrap_or_default()
+                );
+            }
+            reports.push(report);
         }
 
-        Ok(())
+        if let Some(monitoring) = &self.monitoring {
+            let repaired: usize = reports.iter().map(|r| r.repaired.len()).sum();
+            let failed = reports.iter().filter(|r| r.error.is_some()).count();
+            monitoring.increment_counter("shards_repaired", repaired as u64).await;
+            monitoring.increment_counter("shard_repair_failures", failed as u64).await;
+            monitoring.set_gauge("shards_missing", missing_total as f64).await;
+        }
+        reports
     }
 
-    /// Limpa cache expirado
-    pub async fn cleanup_expired(&self) -> Result<()> {
-        let mut cache = self.cache.write().await;
-        let now = Utc::now();
-
-        cache.retain(|_, item| now - item.timestamp <= item.ttl);
-        Ok(())
+    /// Publica o pacote de eleição assinado, endereçado pelo conteúdo
+    pub async fn store_election_package(&self, election_id: &str, package: &[u8]) -> Result<ContentAddress> {
+        self.publish(&format!("package:{}", election_id), package).await
     }
-}
-
-/// Sistema de armazenamento distribuído principal
-pub struct DistributedStorage {
-    ipfs_client: IpfsClient,
-    dht_client: DhtClient,
-    local_cache: LocalCache,
-}
 
-impl DistributedStorage {
-    pub fn new(ipfs_endpoint: String, local_node_id: String, cache_size: usize) -> Self {
-        Self {
-            ipfs_client: IpfsClient::new(ipfs_endpoint),
-            dht_client: DhtClient::new(local_node_id),
-            local_cache: LocalCache::new(cache_size),
-        }
+    /// Remove a fixação de um conteúdo publicado
+    pub async fn release(&self, address: &ContentAddress) -> Result<()> {
+        self.local_cache.invalidate(&format!("cid:{}", address.cid)).await?;
+        self.ipfs_client.unpin(&address.cid).await
     }
 
     /// Armazena boletim de urna
@@ -316,10 +265,10 @@ impl DistributedStorage {
         let ipfs_hash = self.ipfs_client.add_data(&ballot_data).await?;
 
         // Registrar na DHT para descoberta
-        self.dht_client.register_ballot(&ballot.election_id, &ipfs_hash).await?;
+        self.announce(&format!("ballot:{}", ballot.election_id), &ipfs_hash).await;
 
         // Armazenar no cache local
-        self.local_cache.put(&cache_key, ballot_data, chrono::Duration::hours(24)).await?;
+        self.local_cache.put(&cache_key, ballot_data, self.cache_ttl).await?;
 
         Ok(ipfs_hash)
     }
@@ -335,14 +284,14 @@ impl DistributedStorage {
 
         // Buscar na DHT
         let election_id = self.extract_election_id_from_ballot_id(ballot_id)?;
-        let ballot_hashes = self.dht_client.discover_ballots(&election_id).await?;
+        let ballot_hashes = self.discover(&format!("ballot:{}", election_id)).await?;
 
         for hash in ballot_hashes {
             if let Ok(ballot_data) = self.ipfs_client.get_data(&hash).await {
                 if let Ok(ballot) = serde_json::from_slice::<Ballot>(&ballot_data) {
                     if ballot.id == ballot_id {
                         // Armazenar no cache
-                        self.local_cache.put(&cache_key, ballot_data, chrono::Duration::hours(24)).await?;
+                        self.local_cache.put(&cache_key, ballot_data, self.cache_ttl).await?;
                         return Ok(Some(ballot));
                     }
                 }
@@ -355,19 +304,18 @@ impl DistributedStorage {
     /// Armazena prova de auditoria
     pub async fn store_audit_proof(&self, proof: &AuditProof) -> Result<String> {
         let proof_data = serde_json::to_vec(proof)?;
-        let ipfs_hash = self.ipfs_client.add_data(&proof_data).await?;
-
-        // Registrar na DHT
-        let key = format!("audit:{}", proof.audit_id);
-        self.dht_client.register_ballot(&key, &ipfs_hash).await?;
+        let address = self.publish(&format!("audit:{}", proof.audit_id), &proof_data).await?;
+        Ok(address.cid)
+    }
 
-        Ok(ipfs_hash)
+    /// Publica um pacote de auditoria exportado (JSON, CSV, manifesto assinado)
+    pub async fn store_audit_bundle(&self, election_id: &str, bundle: &[u8]) -> Result<ContentAddress> {
+        self.publish(&format!("audit:{}", election_id), bundle).await
     }
 
     /// Recupera prova de auditoria
     pub async fn get_audit_proof(&self, audit_id: &str) -> Result<Option<AuditProof>> {
-        let key = format!("audit:{}", audit_id);
-        let hashes = self.dht_client.discover_ballots(&key).await?;
+        let hashes = self.discover(&format!("audit:{}", audit_id)).await?;
 
         for hash in hashes {
             if let Ok(proof_data) = self.ipfs_client.get_data(&hash).await {
@@ -384,7 +332,7 @@ impl DistributedStorage {
 
     /// Lista todos os boletins de uma eleição
     pub async fn list_ballots(&self, election_id: &str) -> Result<Vec<Ballot>> {
-        let ballot_hashes = self.dht_client.discover_ballots(election_id).await?;
+        let ballot_hashes = self.discover(&format!("ballot:{}", election_id)).await?;
         let mut ballots = Vec::new();
 
         for hash in ballot_hashes {
@@ -408,6 +356,233 @@ impl DistributedStorage {
         self.local_cache.cleanup_expired().await
     }
 
+    /// Divide o conteúdo em fragmentos, um por nó, e publica o manifesto no nó principal
+    async fn publish_shards(&self, key: &str, data: &[u8], policy: &ErasurePolicy) -> Result<ContentAddress> {
+        let shards = erasure::encode(policy, data)?;
+        let shard_size = shards[0].len();
+
+        let mut refs = Vec::with_capacity(shards.len());
+        let mut locations = BTreeMap::new();
+        for (index, shard) in shards.iter().enumerate() {
+            let (name, client) = self.shard_target(index, &HashSet::new());
+            let address = client.add(shard).await
+                .map_err(|e| anyhow!("Failed to store shard {} of {} on {}: {}", index, key, name, e))?;
+            refs.push(ShardRef { index, cid: address.cid, sha256: address.sha256 });
+            locations.insert(index, name.to_string());
+        }
+
+        let manifest = ShardManifest::new(key, *policy, data, shard_size, refs);
+        let manifest_address = self.ipfs_client.add(&serde_json::to_vec(&manifest)?).await?;
+        for shard in &manifest.shards {
+            self.announce(&format!("shard:{}", manifest_address.cid), &shard.cid).await;
+        }
+        self.track_artifact(&manifest_address.cid, ShardedArtifact { manifest, locations }).await?;
+
+        Ok(ContentAddress {
+            cid: manifest_address.cid,
+            sha256: hex::encode(Sha256::digest(data)),
+            size: data.len(),
+        })
+    }
+
+    /// Manifesto quando o endereço aponta para um artefato codificado
+    async fn shard_manifest(&self, address: &ContentAddress) -> Option<ShardManifest> {
+        if let Some(artifact) = self.sharded.read().await.get(&address.cid) {
+            return Some(artifact.manifest.clone());
+        }
+        self.erasure?;
+        let data = self.ipfs_client.get_data(&address.cid).await.ok()?;
+        ShardManifest::parse(&data).ok()
+            .filter(|manifest| manifest.sha256 == address.sha256 && manifest.size == address.size)
+    }
+
+    /// Lê `k` fragmentos conferidos e reconstrói o conteúdo
+    async fn reassemble(&self, manifest: &ShardManifest) -> Result<Vec<u8>> {
+        let locations = self.sharded.read().await
+            .values()
+            .find(|artifact| artifact.manifest == *manifest)
+            .map(|artifact| artifact.locations.clone())
+            .unwrap_or_default();
+
+        let mut shards: Vec<Option<Vec<u8>>> = vec![None; manifest.shards.len()];
+        let mut found = 0;
+        for shard in &manifest.shards {
+            if found == manifest.policy.data_shards {
+                break;
+            }
+            if let Some(data) = self.read_shard(manifest, shard, locations.get(&shard.index)).await {
+                shards[shard.index] = Some(data);
+                found += 1;
+            }
+        }
+        erasure::join(manifest, shards)
+    }
+
+    /// Fragmento pelo nó onde foi guardado e, se ele falhar, pelos demais
+    async fn read_shard(&self, manifest: &ShardManifest, shard: &ShardRef, location: Option<&String>) -> Option<Vec<u8>> {
+        let mut sources: Vec<&IpfsClient> = Vec::new();
+        if let Some(node) = location.and_then(|name| self.storage_nodes.iter().find(|node| node.name == *name)) {
+            sources.push(&node.client);
+        }
+        sources.extend(self.storage_nodes.iter().map(|node| &node.client));
+        sources.push(&self.ipfs_client);
+
+        for client in sources {
+            match client.get_data(&shard.cid).await {
+                Ok(data) if manifest.verify_shard(shard.index, &data).is_ok() => return Some(data),
+                Ok(_) => log::warn!("Shard {} of {} does not match the manifest", shard.index, manifest.key),
+                Err(_) => {}
+            }
+        }
+        None
+    }
+
+    async fn repair_artifact(&self, manifest_cid: &str, mut artifact: ShardedArtifact) -> ShardRepairReport {
+        let manifest = artifact.manifest.clone();
+        let mut report = ShardRepairReport {
+            key: manifest.key.clone(),
+            manifest_cid: manifest_cid.to_string(),
+            missing: Vec::new(),
+            repaired: Vec::new(),
+            error: None,
+        };
+
+        // Fragmento ausente do seu nó; nó que não responde não recebe fragmentos recriados
+        let mut unreachable = HashSet::new();
+        let mut held = Vec::new();
+        for shard in &manifest.shards {
+            let location = artifact.locations.get(&shard.index).cloned().unwrap_or_else(|| self.local_node_id.clone());
+            match self.node_client(&location).exists(&shard.cid).await {
+                Ok(true) => held.push((shard, location)),
+                Ok(false) => report.missing.push(shard.index),
+                Err(_) => {
+                    report.missing.push(shard.index);
+                    unreachable.insert(location);
+                }
+            }
+        }
+        if report.missing.is_empty() {
+            return report;
+        }
+
+        let mut present: Vec<Option<Vec<u8>>> = vec![None; manifest.shards.len()];
+        for (shard, location) in held {
+            match self.node_client(&location).get_data(&shard.cid).await {
+                Ok(data) if manifest.verify_shard(shard.index, &data).is_ok() => present[shard.index] = Some(data),
+                _ => {
+                    log::warn!("Shard {} of {} pinned on {} but unreadable", shard.index, manifest.key, location);
+                    report.missing.push(shard.index);
+                }
+            }
+        }
+
+        let shards = match erasure::reconstruct(&manifest.policy, present) {
+            Ok(shards) => shards,
+            Err(e) => {
+                log::error!("Shards of {} cannot be rebuilt: {}", manifest.key, e);
+                report.error = Some(e.to_string());
+                return report;
+            }
+        };
+
+        for &index in &report.missing {
+            let mut excluded = unreachable.clone();
+            excluded.extend(artifact.locations.iter().filter(|(i, _)| **i != index).map(|(_, name)| name.clone()));
+            let (name, client) = self.shard_target(index, &excluded);
+            match client.add(&shards[index]).await {
+                Ok(address) if address.cid == manifest.shards[index].cid => {
+                    self.announce(&format!("shard:{}", manifest_cid), &address.cid).await;
+                    artifact.locations.insert(index, name.to_string());
+                    report.repaired.push((index, name.to_string()));
+                }
+                Ok(address) => {
+                    report.error = Some(format!("Rebuilt shard {} stored as {}", index, address.cid));
+                }
+                Err(e) => {
+                    log::warn!("Failed to store rebuilt shard {} of {} on {}: {}", index, manifest.key, name, e);
+                    report.error = Some(e.to_string());
+                }
+            }
+        }
+
+        if let Some(tracked) = self.sharded.write().await.get_mut(manifest_cid) {
+            tracked.locations = artifact.locations;
+        }
+        if !report.repaired.is_empty() {
+            if let Err(e) = self.save_shard_index().await {
+                log::error!("Failed to save shard index after repairing {}: {}", manifest.key, e);
+            }
+        }
+        report
+    }
+
+    async fn track_artifact(&self, manifest_cid: &str, artifact: ShardedArtifact) -> Result<()> {
+        self.sharded.write().await.insert(manifest_cid.to_string(), artifact);
+        self.save_shard_index().await
+    }
+
+    /// Grava o índice em arquivo temporário e o renomeia, sem deixar índice pela metade
+    async fn save_shard_index(&self) -> Result<()> {
+        let Some(path) = &self.shard_index_path else {
+            return Ok(());
+        };
+        let contents = serde_json::to_vec(&*self.sharded.read().await)?;
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let temporary = path.with_extension("tmp");
+        tokio::fs::write(&temporary, contents).await?;
+        tokio::fs::rename(&temporary, path).await?;
+        Ok(())
+    }
+
+    /// Nó do fragmento: rodízio entre os nós, evitando os excluídos quando possível
+    fn shard_target(&self, index: usize, excluded: &HashSet<String>) -> (&str, &IpfsClient) {
+        if self.storage_nodes.is_empty() {
+            return (&self.local_node_id, &self.ipfs_client);
+        }
+        let count = self.storage_nodes.len();
+        let node = (0..count)
+            .map(|offset| &self.storage_nodes[(index + offset) % count])
+            .find(|node| !excluded.contains(&node.name))
+            .unwrap_or(&self.storage_nodes[index % count]);
+        (&node.name, &node.client)
+    }
+
+    fn node_client(&self, name: &str) -> &IpfsClient {
+        self.storage_nodes.iter()
+            .find(|node| node.name == name)
+            .map(|node| &node.client)
+            .unwrap_or(&self.ipfs_client)
+    }
+
+    /// Registra o CID na DHT; sem DHT, ou com ela fora do ar, o conteúdo segue só no IPFS
+    async fn announce(&self, key: &str, cid: &str) {
+        if let Some(dht) = &self.dht_client {
+            if let Err(e) = dht.announce(key, cid).await {
+                log::warn!("Node {} could not announce {} under {}: {}", self.local_node_id, cid, key, e);
+            }
+        }
+    }
+
+    /// CIDs registrados sob a chave, com a resposta da DHT guardada por pouco tempo
+    async fn discover(&self, key: &str) -> Result<Vec<String>> {
+        let Some(dht) = &self.dht_client else {
+            return Ok(Vec::new());
+        };
+        let cache_key = format!("dht:{}", key);
+        if let Some(cached) = self.local_cache.get(&cache_key).await? {
+            if let Ok(cids) = serde_json::from_slice::<Vec<String>>(&cached) {
+                return Ok(cids);
+            }
+        }
+
+        let cids: Vec<String> = dht.resolve(key).await?.cids.into_iter().collect();
+        let ttl = self.cache_ttl.min(chrono::Duration::seconds(DHT_CACHE_TTL_SECONDS));
+        self.local_cache.put(&cache_key, serde_json::to_vec(&cids)?, ttl).await?;
+        Ok(cids)
+    }
+
     /// Extrai ID da eleição do ID do boletim
     fn extract_election_id_from_ballot_id(&self, ballot_id: &str) -> Result<String> {
         // Assumindo formato: election_id:ballot_id
@@ -473,6 +648,61 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_fresh_instance_repairs_shards_from_persisted_index() {
+        use crate::storage::ipfs_client::raw_cid;
+
+        let policy = ErasurePolicy::new(2, 1).unwrap();
+        let data = b"pacote de eleicao assinado".to_vec();
+        let shards = erasure::encode(&policy, &data).unwrap();
+        let refs: Vec<ShardRef> = shards.iter().enumerate()
+            .map(|(index, shard)| ShardRef { index, cid: raw_cid(shard), sha256: hex::encode(Sha256::digest(shard)) })
+            .collect();
+        let manifest = ShardManifest::new("package:eleicao-2026", policy, &data, shards[0].len(), refs.clone());
+
+        let mut servers = Vec::new();
+        for _ in 0..3 {
+            servers.push(mockito::Server::new_async().await);
+        }
+        let names: Vec<String> = servers.iter().map(|server| server.url()).collect();
+        let storage = || DistributedStorage::new(names[0].clone(), "node1".to_string(), 10)
+            .with_erasure(policy, names.iter()
+                .map(|name| StorageNode { name: name.clone(), client: IpfsClient::new(name.clone()) })
+                .collect());
+
+        let dir = tempfile::tempdir().unwrap();
+        let index_path = dir.path().join("shards").join("index.json");
+        {
+            let publisher = storage().with_shard_index(&index_path).unwrap();
+            let locations = (0..3).map(|index| (index, names[index].clone())).collect();
+            publisher.track_artifact("manifest-cid", ShardedArtifact { manifest, locations }).await.unwrap();
+        }
+
+        // Depois do reinício, o nó 1 perdeu o fragmento; os nós 0 e 2 ainda os guardam
+        let mut mocks = Vec::new();
+        for (index, server) in servers.iter_mut().enumerate() {
+            let arg = mockito::Matcher::UrlEncoded("arg".to_string(), refs[index].cid.clone());
+            if index == 1 {
+                mocks.push(server.mock("POST", "/api/v0/pin/ls").match_query(arg).with_status(500).create_async().await);
+                mocks.push(server.mock("POST", "/api/v0/add")
+                    .match_query(mockito::Matcher::Any)
+                    .with_body(serde_json::json!({ "Hash": refs[1].cid }).to_string())
+                    .create_async().await);
+            } else {
+                mocks.push(server.mock("POST", "/api/v0/pin/ls").match_query(arg.clone()).create_async().await);
+                mocks.push(server.mock("POST", "/api/v0/cat").match_query(arg).with_body(shards[index].clone()).create_async().await);
+            }
+        }
+
+        let restarted = storage().with_shard_index(&index_path).unwrap();
+        let reports = restarted.repair_shards().await;
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].manifest_cid, "manifest-cid");
+        assert_eq!(reports[0].missing, vec![1]);
+        assert_eq!(reports[0].repaired, vec![(1, names[1].clone())]);
+        assert!(reports[0].error.is_none(), "{:?}", reports[0].error);
+    }
+
     #[tokio::test]
     async fn test_local_cache() {
         let cache = LocalCache::new(10);
@@ -0,0 +1,205 @@
+//! Codificação por apagamento (Reed–Solomon) dos artefatos eleitorais
+//!
+//! Em vez de replicar o artefato inteiro em cada nó, o conteúdo é dividido
+//! em `k` fragmentos de dados e `m` de paridade, cada um publicado em um nó
+//! de armazenamento. Quaisquer `k` dos `k + m` fragmentos reconstroem o
+//! original, com custo de `(k + m) / k` vezes o tamanho em vez de uma cópia
+//! por nó. O manifesto, pequeno, lista os fragmentos e é o que fica
+//! endereçado pelo `ContentAddress` do artefato.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+
+/// Identifica o manifesto entre os conteúdos publicados
+pub const SHARD_MANIFEST_FORMAT: &str = "fortis-shards/1";
+
+/// Limite do corpo de Galois GF(2^8)
+const MAX_TOTAL_SHARDS: usize = 256;
+
+/// Fragmentos de dados e de paridade por artefato
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasurePolicy {
+    pub data_shards: usize,
+    pub parity_shards: usize,
+}
+
+impl ErasurePolicy {
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self> {
+        if data_shards == 0 || parity_shards == 0 {
+            return Err(anyhow!("Erasure coding needs at least one data and one parity shard"));
+        }
+        if data_shards + parity_shards > MAX_TOTAL_SHARDS {
+            return Err(anyhow!("At most {} shards per artifact", MAX_TOTAL_SHARDS));
+        }
+        Ok(Self { data_shards, parity_shards })
+    }
+
+    /// Formato `k+m`, ex.: `4+2`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (data, parity) = spec.split_once('+')
+            .ok_or_else(|| anyhow!("Invalid erasure coding {}, expected k+m", spec))?;
+        Self::new(data.trim().parse()?, parity.trim().parse()?)
+    }
+
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    fn codec(&self) -> Result<ReedSolomon> {
+        ReedSolomon::new(self.data_shards, self.parity_shards)
+            .map_err(|e| anyhow!("Invalid Reed-Solomon parameters: {:?}", e))
+    }
+}
+
+/// Fragmento publicado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardRef {
+    pub index: usize,
+    pub cid: String,
+    pub sha256: String,
+}
+
+/// Manifesto do artefato codificado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardManifest {
+    pub format: String,
+    pub key: String,
+    pub size: usize,
+    pub sha256: String,
+    pub policy: ErasurePolicy,
+    pub shard_size: usize,
+    pub shards: Vec<ShardRef>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShardManifest {
+    pub fn new(key: &str, policy: ErasurePolicy, data: &[u8], shard_size: usize, shards: Vec<ShardRef>) -> Self {
+        Self {
+            format: SHARD_MANIFEST_FORMAT.to_string(),
+            key: key.to_string(),
+            size: data.len(),
+            sha256: hex::encode(Sha256::digest(data)),
+            policy,
+            shard_size,
+            shards,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Manifesto publicado por outro nó, conferido antes do uso
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let manifest: Self = serde_json::from_slice(data)?;
+        if manifest.format != SHARD_MANIFEST_FORMAT {
+            return Err(anyhow!("Unknown shard manifest format {}", manifest.format));
+        }
+        let indexes: BTreeSet<usize> = manifest.shards.iter().map(|shard| shard.index).collect();
+        if manifest.shards.len() != manifest.policy.total_shards()
+            || indexes != (0..manifest.policy.total_shards()).collect()
+        {
+            return Err(anyhow!("Shard manifest for {} does not list every shard", manifest.key));
+        }
+        Ok(manifest)
+    }
+
+    /// Confere o fragmento lido com o manifesto
+    pub fn verify_shard(&self, index: usize, data: &[u8]) -> Result<()> {
+        let shard = self.shards.get(index).ok_or_else(|| anyhow!("No shard {}", index))?;
+        if data.len() != self.shard_size || hex::encode(Sha256::digest(data)) != shard.sha256 {
+            return Err(anyhow!("Shard {} of {} does not match the manifest", index, self.key));
+        }
+        Ok(())
+    }
+}
+
+/// Divide o conteúdo em fragmentos de mesmo tamanho e calcula a paridade
+pub fn encode(policy: &ErasurePolicy, data: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let shard_size = data.len().div_ceil(policy.data_shards).max(1);
+    let mut shards: Vec<Vec<u8>> = (0..policy.total_shards())
+        .map(|index| {
+            let start = (index * shard_size).min(data.len());
+            let end = ((index + 1) * shard_size).min(data.len());
+            let mut shard = if index < policy.data_shards { data[start..end].to_vec() } else { Vec::new() };
+            shard.resize(shard_size, 0);
+            shard
+        })
+        .collect();
+    policy.codec()?.encode(&mut shards).map_err(|e| anyhow!("Reed-Solomon encoding failed: {:?}", e))?;
+    Ok(shards)
+}
+
+/// Reconstrói todos os fragmentos a partir de pelo menos `k` presentes
+pub fn reconstruct(policy: &ErasurePolicy, mut shards: Vec<Option<Vec<u8>>>) -> Result<Vec<Vec<u8>>> {
+    let present = shards.iter().filter(|shard| shard.is_some()).count();
+    if present < policy.data_shards {
+        return Err(anyhow!("Only {} of the {} shards needed are available", present, policy.data_shards));
+    }
+    policy.codec()?.reconstruct(&mut shards).map_err(|e| anyhow!("Reed-Solomon reconstruction failed: {:?}", e))?;
+    shards.into_iter()
+        .map(|shard| shard.ok_or_else(|| anyhow!("Shard missing after reconstruction")))
+        .collect()
+}
+
+/// Conteúdo original a partir dos fragmentos, conferido com o manifesto
+pub fn join(manifest: &ShardManifest, shards: Vec<Option<Vec<u8>>>) -> Result<Vec<u8>> {
+    let shards = reconstruct(&manifest.policy, shards)?;
+    let mut data: Vec<u8> = shards.into_iter().take(manifest.policy.data_shards).flatten().collect();
+    data.truncate(manifest.size);
+    if hex::encode(Sha256::digest(&data)) != manifest.sha256 {
+        return Err(anyhow!("Reassembled content does not match {}", manifest.key));
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest_for(policy: ErasurePolicy, data: &[u8], shards: &[Vec<u8>]) -> ShardManifest {
+        let refs = shards.iter().enumerate()
+            .map(|(index, shard)| ShardRef {
+                index,
+                cid: format!("shard-{}", index),
+                sha256: hex::encode(Sha256::digest(shard)),
+            })
+            .collect();
+        ShardManifest::new("package:eleicao-2026", policy, data, shards[0].len(), refs)
+    }
+
+    #[test]
+    fn test_any_k_shards_rebuild_content() {
+        let policy = ErasurePolicy::parse("4+2").unwrap();
+        let data: Vec<u8> = (0..1001u32).map(|i| (i * 7 % 251) as u8).collect();
+        let shards = encode(&policy, &data).unwrap();
+        assert_eq!(shards.len(), 6);
+        let manifest = manifest_for(policy, &data, &shards);
+
+        // Perde um fragmento de dados e um de paridade
+        let mut partial: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+        partial[1] = None;
+        partial[5] = None;
+        assert_eq!(join(&manifest, partial).unwrap(), data);
+
+        let rebuilt = reconstruct(&policy, shards.iter().cloned().enumerate()
+            .map(|(i, shard)| (i != 0).then_some(shard))
+            .collect()).unwrap();
+        manifest.verify_shard(0, &rebuilt[0]).unwrap();
+    }
+
+    #[test]
+    fn test_fewer_than_k_shards_fail() {
+        let policy = ErasurePolicy::new(3, 2).unwrap();
+        let data = b"boletim de urna".to_vec();
+        let shards = encode(&policy, &data).unwrap();
+
+        let partial: Vec<Option<Vec<u8>>> = shards.into_iter().enumerate()
+            .map(|(i, shard)| (i < 2).then_some(shard))
+            .collect();
+        assert!(reconstruct(&policy, partial).is_err());
+        assert!(ErasurePolicy::parse("4").is_err());
+        assert!(ErasurePolicy::new(200, 100).is_err());
+    }
+}
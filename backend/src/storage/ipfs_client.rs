@@ -0,0 +1,262 @@
+//! Cliente da API HTTP do IPFS (Kubo)
+//!
+//! Publica com CIDv1 e folhas `raw`: até o tamanho de um bloco o CID é
+//! `raw` + SHA-256 do próprio conteúdo, calculado aqui e conferido com o
+//! que o nó devolve, de modo que um nó adulterado não troca o conteúdo sem
+//! ser notado. Conteúdo maior vira um DAG cujo CID depende da divisão em
+//! blocos; nesse caso vale o SHA-256 guardado no `ContentAddress`.
+//!
+//! A leitura tenta o nó configurado e, se ele falhar, os gateways
+//! públicos, sempre conferindo o conteúdo antes de devolvê-lo.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::config::StorageConfig;
+
+/// Tamanho do bloco na publicação; conteúdo até esse tamanho tem CID calculável localmente
+pub const IPFS_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Prefixo multibase base32 dos CIDs v1
+const CIDV1_BASE32_PREFIX: char = 'b';
+/// Versão 1, codec `raw` (0x55), multihash sha2-256 (0x12) de 32 bytes
+const RAW_SHA256_CID_HEADER: [u8; 4] = [0x01, 0x55, 0x12, 0x20];
+/// Início comum a todo CID com esse cabeçalho
+const RAW_SHA256_CID_PREFIX: &str = "bafkrei";
+
+/// Endereço de um conteúdo publicado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentAddress {
+    pub cid: String,
+    pub sha256: String,
+    pub size: usize,
+}
+
+impl ContentAddress {
+    /// Confere se os bytes são o conteúdo endereçado
+    pub fn verify(&self, data: &[u8]) -> Result<()> {
+        if data.len() != self.size || hex::encode(Sha256::digest(data)) != self.sha256 {
+            return Err(anyhow!("Content does not match {}", self.cid));
+        }
+        Ok(())
+    }
+}
+
+/// CIDv1 `raw` com SHA-256, em base32, do conteúdo de um único bloco
+pub fn raw_cid(data: &[u8]) -> String {
+    let mut bytes = RAW_SHA256_CID_HEADER.to_vec();
+    bytes.extend_from_slice(&Sha256::digest(data));
+    format!("{}{}", CIDV1_BASE32_PREFIX, base32_lower(&bytes))
+}
+
+/// Cliente IPFS para armazenamento descentralizado
+pub struct IpfsClient {
+    endpoint: String,
+    gateways: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl IpfsClient {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            gateways: Vec::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn from_config(config: &StorageConfig, endpoint: &str) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.ipfs_timeout_seconds))
+            .build()?;
+        Ok(Self {
+            client,
+            ..Self::new(endpoint.to_string())
+        }
+        .with_gateways(config.ipfs_gateways.clone()))
+    }
+
+    /// Gateways HTTP usados na leitura quando o nó não responde
+    pub fn with_gateways(mut self, gateways: Vec<String>) -> Self {
+        self.gateways = gateways.into_iter().map(|g| g.trim_end_matches('/').to_string()).collect();
+        self
+    }
+
+    /// Publica e fixa o conteúdo no nó
+    pub async fn add(&self, data: &[u8]) -> Result<ContentAddress> {
+        let form = reqwest::multipart::Form::new()
+            .part("file", reqwest::multipart::Part::bytes(data.to_vec()));
+
+        let response = self.client
+            .post(format!("{}/api/v0/add", self.endpoint))
+            .query(&[
+                ("cid-version", "1"),
+                ("raw-leaves", "true"),
+                ("hash", "sha2-256"),
+                ("pin", "true"),
+            ])
+            .query(&[("chunker", format!("size-{}", IPFS_CHUNK_SIZE))])
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to add data to IPFS: {}", response.status()));
+        }
+
+        let result: serde_json::Value = response.json().await?;
+        let cid = result["Hash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid IPFS response"))?
+            .to_string();
+
+        if data.len() <= IPFS_CHUNK_SIZE && cid != raw_cid(data) {
+            return Err(anyhow!("IPFS node returned {} for content addressed as {}", cid, raw_cid(data)));
+        }
+
+        Ok(ContentAddress {
+            cid,
+            sha256: hex::encode(Sha256::digest(data)),
+            size: data.len(),
+        })
+    }
+
+    /// Adiciona dados ao IPFS, devolvendo só o CID
+    pub async fn add_data(&self, data: &[u8]) -> Result<String> {
+        Ok(self.add(data).await?.cid)
+    }
+
+    /// Lê o conteúdo endereçado, pelo nó ou pelos gateways
+    pub async fn cat(&self, address: &ContentAddress) -> Result<Vec<u8>> {
+        let mut last_error = None;
+        for source in self.sources(&address.cid) {
+            match self.fetch(&source).await.and_then(|data| address.verify(&data).map(|_| data)) {
+                Ok(data) => return Ok(data),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No IPFS source configured")))
+    }
+
+    /// Recupera dados do IPFS pelo CID; CIDs `raw` são conferidos com o conteúdo
+    pub async fn get_data(&self, cid: &str) -> Result<Vec<u8>> {
+        let mut last_error = None;
+        for source in self.sources(cid) {
+            match self.fetch(&source).await {
+                Ok(data) if cid.starts_with(RAW_SHA256_CID_PREFIX) && raw_cid(&data) != cid => {
+                    last_error = Some(anyhow!("Content does not match {}", cid));
+                }
+                Ok(data) => return Ok(data),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No IPFS source configured")))
+    }
+
+    /// Fixa o CID no nó, impedindo a coleta de lixo
+    pub async fn pin(&self, cid: &str) -> Result<()> {
+        self.rpc("pin/add", cid).await
+    }
+
+    /// Remove a fixação do CID
+    pub async fn unpin(&self, cid: &str) -> Result<()> {
+        self.rpc("pin/rm", cid).await
+    }
+
+    /// Verifica se dados existem (fixados) no IPFS
+    pub async fn exists(&self, cid: &str) -> Result<bool> {
+        let response = self.client
+            .post(format!("{}/api/v0/pin/ls", self.endpoint))
+            .query(&[("arg", cid)])
+            .send()
+            .await?;
+
+        Ok(response.status().is_success())
+    }
+
+    async fn rpc(&self, command: &str, cid: &str) -> Result<()> {
+        let response = self.client
+            .post(format!("{}/api/v0/{}", self.endpoint, command))
+            .query(&[("arg", cid)])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("IPFS {} failed for {}: {}", command, cid, response.status()));
+        }
+        Ok(())
+    }
+
+    /// Nó primeiro (RPC `cat`), depois cada gateway
+    fn sources(&self, cid: &str) -> Vec<Source> {
+        std::iter::once(Source::Node(format!("{}/api/v0/cat?arg={}", self.endpoint, cid)))
+            .chain(self.gateways.iter().map(|gateway| Source::Gateway(format!("{}/ipfs/{}", gateway, cid))))
+            .collect()
+    }
+
+    async fn fetch(&self, source: &Source) -> Result<Vec<u8>> {
+        let request = match source {
+            Source::Node(url) => self.client.post(url),
+            Source::Gateway(url) => self.client.get(url),
+        };
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to get data from IPFS: {}", response.status()));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+enum Source {
+    Node(String),
+    Gateway(String),
+}
+
+/// Base32 RFC 4648 minúsculo, sem preenchimento (multibase `b`)
+fn base32_lower(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut out = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_cid_matches_ipfs() {
+        assert_eq!(raw_cid(b""), "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku");
+        assert_eq!(raw_cid(b"hello world"), "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e");
+    }
+
+    #[tokio::test]
+    async fn test_cat_rejects_tampered_content_from_every_source() {
+        let address = ContentAddress {
+            cid: raw_cid(b"pacote"),
+            sha256: hex::encode(Sha256::digest(b"pacote")),
+            size: 6,
+        };
+        assert!(address.verify(b"pacote").is_ok());
+        assert!(address.verify(b"pacotE").is_err());
+
+        // Sem nó nem gateway alcançável, a leitura falha em vez de devolver qualquer coisa
+        let client = IpfsClient::new("http://127.0.0.1:9".to_string())
+            .with_gateways(vec!["http://127.0.0.1:9/".to_string()]);
+        assert_eq!(client.sources(&address.cid).len(), 2);
+        assert!(client.cat(&address).await.is_err());
+    }
+}
@@ -0,0 +1,475 @@
+//! Cache local em duas camadas na frente do IPFS e da DHT
+//!
+//! A camada em memória é LRU, limitada em entradas e em bytes; o que sai
+//! dela ainda válido vai para o disco, também limitado em bytes e
+//! descartado do menos usado para o mais usado. Um acerto no disco volta
+//! para a memória. Cada arquivo guarda a chave e a validade num cabeçalho,
+//! de modo que o cache em disco sobrevive a reinícios.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::config::StorageConfig;
+use crate::monitoring::MonitoringSystem;
+
+/// Extensão dos arquivos do cache em disco
+const DISK_ENTRY_EXTENSION: &str = "cache";
+
+/// Chamado com a chave de cada entrada invalidada
+pub type InvalidationHook = Box<dyn Fn(&str) + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct CachedItem {
+    pub data: Vec<u8>,
+    pub timestamp: DateTime<Utc>,
+    pub ttl: chrono::Duration,
+}
+
+impl CachedItem {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.timestamp + self.ttl
+    }
+}
+
+/// Uso e eficácia do cache
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub memory_entries: usize,
+    pub memory_bytes: usize,
+    pub disk_entries: usize,
+    pub disk_bytes: u64,
+    pub memory_hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub hit_rate: f64,
+}
+
+/// Cabeçalho de cada arquivo do cache em disco, numa linha antes dos dados
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskHeader {
+    key: String,
+    expires_at: DateTime<Utc>,
+}
+
+struct MemoryEntry {
+    item: CachedItem,
+    last_used: u64,
+}
+
+struct DiskEntry {
+    path: PathBuf,
+    size: u64,
+    expires_at: DateTime<Utc>,
+    last_used: u64,
+}
+
+struct DiskTier {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    memory: HashMap<String, MemoryEntry>,
+    memory_order: BTreeMap<u64, String>,
+    memory_bytes: usize,
+    disk: HashMap<String, DiskEntry>,
+    disk_order: BTreeMap<u64, String>,
+    disk_bytes: u64,
+    tick: u64,
+    memory_hits: u64,
+    disk_hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl CacheState {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn hit_rate(&self) -> f64 {
+        let lookups = self.memory_hits + self.disk_hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+        (self.memory_hits + self.disk_hits) as f64 / lookups as f64
+    }
+
+    fn take_memory(&mut self, key: &str) -> Option<CachedItem> {
+        let entry = self.memory.remove(key)?;
+        self.memory_order.remove(&entry.last_used);
+        self.memory_bytes -= entry.item.data.len();
+        Some(entry.item)
+    }
+
+    fn take_disk(&mut self, key: &str) -> Option<DiskEntry> {
+        let entry = self.disk.remove(key)?;
+        self.disk_order.remove(&entry.last_used);
+        self.disk_bytes -= entry.size;
+        Some(entry)
+    }
+
+    fn insert_memory(&mut self, key: String, item: CachedItem) {
+        let last_used = self.next_tick();
+        self.memory_bytes += item.data.len();
+        self.memory_order.insert(last_used, key.clone());
+        self.memory.insert(key, MemoryEntry { item, last_used });
+    }
+}
+
+/// Cache local para performance
+pub struct LocalCache {
+    state: Mutex<CacheState>,
+    max_entries: usize,
+    max_memory_bytes: usize,
+    disk: Option<DiskTier>,
+    hooks: std::sync::RwLock<Vec<InvalidationHook>>,
+    monitoring: Option<Arc<MonitoringSystem>>,
+}
+
+impl LocalCache {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            state: Mutex::new(CacheState::default()),
+            max_entries: max_size.max(1),
+            max_memory_bytes: usize::MAX,
+            disk: None,
+            hooks: std::sync::RwLock::new(Vec::new()),
+            monitoring: None,
+        }
+    }
+
+    /// Limites da configuração, com a camada em disco quando há diretório configurado
+    pub fn from_config(config: &StorageConfig) -> Result<Self> {
+        let cache = Self::new(config.cache_size).with_memory_limit(config.cache_memory_bytes);
+        match &config.cache_disk_path {
+            Some(dir) => cache.with_disk(dir, config.cache_disk_bytes),
+            None => Ok(cache),
+        }
+    }
+
+    pub fn with_memory_limit(mut self, max_bytes: usize) -> Self {
+        self.max_memory_bytes = max_bytes;
+        self
+    }
+
+    /// Camada em disco; reaproveita as entradas ainda válidas já presentes no diretório
+    pub fn with_disk(mut self, dir: impl AsRef<Path>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+
+        let now = Utc::now();
+        let mut found = Vec::new();
+        for file in std::fs::read_dir(&dir)? {
+            let path = file?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(DISK_ENTRY_EXTENSION) {
+                continue;
+            }
+            match read_disk_entry(&path) {
+                Ok((header, data)) if header.expires_at > now => {
+                    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                    found.push((modified, header, data.len() as u64, path));
+                }
+                _ => {
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+
+        // Os usados mais recentemente por último, para serem os últimos a sair
+        found.sort_by_key(|(modified, ..)| *modified);
+        let state = self.state.get_mut();
+        for (_, header, size, path) in found {
+            let last_used = state.next_tick();
+            state.disk_bytes += size;
+            state.disk_order.insert(last_used, header.key.clone());
+            state.disk.insert(header.key, DiskEntry { path, size, expires_at: header.expires_at, last_used });
+        }
+
+        self.disk = Some(DiskTier { dir, max_bytes });
+        Ok(self)
+    }
+
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringSystem>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
+    }
+
+    /// Registra um gancho chamado a cada invalidação
+    pub fn on_invalidate(&self, hook: InvalidationHook) {
+        self.hooks.write().unwrap_or_else(|e| e.into_inner()).push(hook);
+    }
+
+    /// Armazena item no cache
+    pub async fn put(&self, key: &str, data: Vec<u8>, ttl: chrono::Duration) -> Result<()> {
+        let item = CachedItem { data, timestamp: Utc::now(), ttl };
+        let mut state = self.state.lock().await;
+        state.take_memory(key);
+        if let Some(stale) = state.take_disk(key) {
+            let _ = tokio::fs::remove_file(&stale.path).await;
+        }
+
+        if item.data.len() > self.max_memory_bytes {
+            // Grande demais para a memória: vai direto para o disco, se houver
+            return self.spill(&mut state, key.to_string(), item).await;
+        }
+
+        state.insert_memory(key.to_string(), item);
+        self.shrink_memory(&mut state).await
+    }
+
+    /// Recupera item do cache
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let now = Utc::now();
+        let mut state = self.state.lock().await;
+
+        if let Some(item) = state.take_memory(key) {
+            if item.expires_at() <= now {
+                state.misses += 1;
+                return self.finish_lookup(state, None).await;
+            }
+            let data = item.data.clone();
+            state.insert_memory(key.to_string(), item);
+            state.memory_hits += 1;
+            return self.finish_lookup(state, Some(data)).await;
+        }
+
+        if let Some(entry) = state.take_disk(key) {
+            let read = match entry.expires_at > now {
+                true => tokio::fs::read(&entry.path).await.map_err(|e| anyhow!(e)).and_then(|raw| split_disk_entry(&raw)),
+                false => Err(anyhow!("Cache entry {} expired", key)),
+            };
+            let _ = tokio::fs::remove_file(&entry.path).await;
+            if let Ok((header, data)) = read {
+                if header.key == key {
+                    state.disk_hits += 1;
+                    let item = CachedItem { data: data.clone(), timestamp: now, ttl: entry.expires_at - now };
+                    if item.data.len() <= self.max_memory_bytes {
+                        state.insert_memory(key.to_string(), item);
+                        self.shrink_memory(&mut state).await?;
+                    } else {
+                        self.spill(&mut state, key.to_string(), item).await?;
+                    }
+                    return self.finish_lookup(state, Some(data)).await;
+                }
+            }
+        }
+
+        state.misses += 1;
+        self.finish_lookup(state, None).await
+    }
+
+    /// Remove item do cache
+    pub async fn remove(&self, key: &str) -> Result<()> {
+        let mut state = self.state.lock().await;
+        state.take_memory(key);
+        if let Some(entry) = state.take_disk(key) {
+            let _ = tokio::fs::remove_file(&entry.path).await;
+        }
+        Ok(())
+    }
+
+    /// Remove o item e avisa os ganchos de invalidação
+    pub async fn invalidate(&self, key: &str) -> Result<()> {
+        self.remove(key).await?;
+        self.notify(key);
+        Ok(())
+    }
+
+    /// Invalida todas as entradas cuja chave começa com o prefixo
+    pub async fn invalidate_prefix(&self, prefix: &str) -> Result<usize> {
+        let keys: Vec<String> = {
+            let state = self.state.lock().await;
+            state.memory.keys().chain(state.disk.keys())
+                .filter(|key| key.starts_with(prefix))
+                .cloned()
+                .collect()
+        };
+        for key in &keys {
+            self.invalidate(key).await?;
+        }
+        Ok(keys.len())
+    }
+
+    /// Limpa cache expirado
+    pub async fn cleanup_expired(&self) -> Result<()> {
+        let now = Utc::now();
+        let mut state = self.state.lock().await;
+        let expired: Vec<String> = state.memory.iter()
+            .filter(|(_, entry)| entry.item.expires_at() <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            state.take_memory(&key);
+        }
+        let expired: Vec<String> = state.disk.iter()
+            .filter(|(_, entry)| entry.expires_at <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            if let Some(entry) = state.take_disk(&key) {
+                let _ = tokio::fs::remove_file(&entry.path).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn stats(&self) -> CacheStats {
+        let state = self.state.lock().await;
+        CacheStats {
+            memory_entries: state.memory.len(),
+            memory_bytes: state.memory_bytes,
+            disk_entries: state.disk.len(),
+            disk_bytes: state.disk_bytes,
+            memory_hits: state.memory_hits,
+            disk_hits: state.disk_hits,
+            misses: state.misses,
+            evictions: state.evictions,
+            hit_rate: state.hit_rate(),
+        }
+    }
+
+    /// Tira da memória os menos usados até caber nos limites, mandando ao disco os ainda válidos
+    async fn shrink_memory(&self, state: &mut CacheState) -> Result<()> {
+        while state.memory.len() > self.max_entries || state.memory_bytes > self.max_memory_bytes {
+            let Some((_, oldest)) = state.memory_order.pop_first() else { break };
+            let Some(entry) = state.memory.remove(&oldest) else { continue };
+            state.memory_bytes -= entry.item.data.len();
+            state.evictions += 1;
+            if entry.item.expires_at() > Utc::now() {
+                self.spill(state, oldest, entry.item).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Grava no disco e descarta os menos usados até caber no limite
+    async fn spill(&self, state: &mut CacheState, key: String, item: CachedItem) -> Result<()> {
+        let Some(disk) = &self.disk else {
+            return Ok(());
+        };
+        let size = item.data.len() as u64;
+        if size > disk.max_bytes {
+            return Ok(());
+        }
+
+        let path = disk.dir.join(format!("{}.{}", hex::encode(Sha256::digest(key.as_bytes())), DISK_ENTRY_EXTENSION));
+        let header = DiskHeader { key: key.clone(), expires_at: item.expires_at() };
+        let mut raw = serde_json::to_vec(&header)?;
+        raw.push(b'\n');
+        raw.extend_from_slice(&item.data);
+        tokio::fs::write(&path, raw).await?;
+
+        let last_used = state.next_tick();
+        state.disk_bytes += size;
+        state.disk_order.insert(last_used, key.clone());
+        state.disk.insert(key, DiskEntry { path, size, expires_at: header.expires_at, last_used });
+
+        while state.disk_bytes > disk.max_bytes {
+            let Some((_, oldest)) = state.disk_order.first_key_value().map(|(t, k)| (*t, k.clone())) else { break };
+            if let Some(entry) = state.take_disk(&oldest) {
+                state.evictions += 1;
+                let _ = tokio::fs::remove_file(&entry.path).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn finish_lookup(
+        &self,
+        state: tokio::sync::MutexGuard<'_, CacheState>,
+        result: Option<Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>> {
+        let hit_rate = state.hit_rate();
+        drop(state);
+        if let Some(monitoring) = &self.monitoring {
+            let counter = if result.is_some() { "cache_hits" } else { "cache_misses" };
+            monitoring.increment_counter(counter, 1).await;
+            monitoring.set_gauge("cache_hit_rate", hit_rate).await;
+        }
+        Ok(result)
+    }
+
+    fn notify(&self, key: &str) {
+        for hook in self.hooks.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            hook(key);
+        }
+    }
+}
+
+fn read_disk_entry(path: &Path) -> Result<(DiskHeader, Vec<u8>)> {
+    split_disk_entry(&std::fs::read(path)?)
+}
+
+fn split_disk_entry(raw: &[u8]) -> Result<(DiskHeader, Vec<u8>)> {
+    let end = raw.iter().position(|byte| *byte == b'\n')
+        .ok_or_else(|| anyhow!("Cache entry without header"))?;
+    let header: DiskHeader = serde_json::from_slice(&raw[..end])?;
+    Ok((header, raw[end + 1..].to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_lru_spills_to_disk_and_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("fortis-cache-{}", uuid::Uuid::new_v4()));
+        let ttl = chrono::Duration::hours(1);
+        let cache = LocalCache::new(2).with_disk(&dir, 1024).unwrap();
+
+        cache.put("a", b"pacote-a".to_vec(), ttl).await.unwrap();
+        cache.put("b", b"pacote-b".to_vec(), ttl).await.unwrap();
+        cache.get("a").await.unwrap();
+        cache.put("c", b"pacote-c".to_vec(), ttl).await.unwrap();
+
+        // "b" foi o menos usado e saiu da memória para o disco
+        let stats = cache.stats().await;
+        assert_eq!((stats.memory_entries, stats.disk_entries), (2, 1));
+
+        // Volta para a memória no acerto, empurrando "a" para o disco
+        assert_eq!(cache.get("b").await.unwrap(), Some(b"pacote-b".to_vec()));
+        let stats = cache.stats().await;
+        assert_eq!((stats.disk_hits, stats.memory_entries, stats.disk_entries), (1, 2, 1));
+
+        // O que está em disco é reaproveitado por um novo processo
+        let reopened = LocalCache::new(2).with_disk(&dir, 1024).unwrap();
+        assert_eq!(reopened.stats().await.disk_entries, 1);
+        assert_eq!(reopened.get("a").await.unwrap(), Some(b"pacote-a".to_vec()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalidation_hooks_and_hit_rate() {
+        let cache = LocalCache::new(10);
+        let invalidated = Arc::new(AtomicUsize::new(0));
+        let counter = invalidated.clone();
+        cache.on_invalidate(Box::new(move |_| { counter.fetch_add(1, Ordering::SeqCst); }));
+
+        cache.put("cid:x", b"x".to_vec(), chrono::Duration::hours(1)).await.unwrap();
+        cache.put("cid:y", b"y".to_vec(), chrono::Duration::hours(1)).await.unwrap();
+        cache.put("dht:package:1", b"[]".to_vec(), chrono::Duration::hours(1)).await.unwrap();
+        assert!(cache.get("cid:x").await.unwrap().is_some());
+        assert!(cache.get("cid:z").await.unwrap().is_none());
+
+        assert_eq!(cache.invalidate_prefix("cid:").await.unwrap(), 2);
+        assert_eq!(invalidated.load(Ordering::SeqCst), 2);
+        assert!(cache.get("cid:y").await.unwrap().is_none());
+        assert!(cache.get("dht:package:1").await.unwrap().is_some());
+
+        let stats = cache.stats().await;
+        assert_eq!((stats.memory_hits, stats.misses), (2, 2));
+        assert_eq!(stats.hit_rate, 0.5);
+    }
+}
@@ -6,8 +6,13 @@
 //! completa desnecessária.
 
 pub mod distributed_storage;
-// pub mod ipfs_client;
-// pub mod dht_client;
-// pub mod local_cache;
+pub mod ipfs_client;
+pub mod dht_client;
+pub mod erasure;
+pub mod local_cache;
 
 pub use distributed_storage::*;
+pub use ipfs_client::{ContentAddress, IpfsClient};
+pub use dht_client::{ContentLocation, DhtClient, DhtHealth};
+pub use erasure::{ErasurePolicy, ShardManifest};
+pub use local_cache::{CacheStats, LocalCache};
@@ -0,0 +1,235 @@
+//! Endpoint WebSocket `/api/v1/stream`
+//!
+//! A assinatura inicial vem da query string (`?topics=log_entries,alerts`
+//! e `election_id=...`; sem `topics`, todos os tópicos) e pode ser alterada
+//! pelo cliente com mensagens `{"action": "subscribe" | "unsubscribe",
+//! "topics": [...]}`. Consumidores que acumulam mais descartes que o limite
+//! configurado são desconectados.
+
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_ws::{CloseCode, CloseReason, Message, MessageStream, Session};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::hub::{EventHub, StreamEvent, Subscription, Topic};
+use crate::config::Config;
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    pub topics: Option<String>,
+    pub election_id: Option<String>,
+}
+
+/// Mensagens enviadas pelo cliente
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe {
+        topics: Vec<Topic>,
+        #[serde(default)]
+        election_id: Option<String>,
+    },
+    Unsubscribe {
+        topics: Vec<Topic>,
+    },
+}
+
+/// Mensagens enviadas ao cliente
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    Subscribed {
+        topics: Vec<Topic>,
+        election_id: Option<&'a str>,
+    },
+    Event(&'a StreamEvent),
+    Lagged {
+        skipped: u64,
+        total_skipped: u64,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Abre a conexão WebSocket do painel de observação
+pub async fn open_stream(
+    req: HttpRequest,
+    body: web::Payload,
+    hub: web::Data<EventHub>,
+    config: web::Data<Config>,
+    query: web::Query<StreamQuery>,
+) -> Result<HttpResponse> {
+    let topics = match &query.topics {
+        None => Topic::ALL.into_iter().collect(),
+        Some(list) => {
+            let mut topics = std::collections::HashSet::new();
+            for name in list.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                match Topic::parse(name) {
+                    Some(topic) => { topics.insert(topic); }
+                    None => {
+                        return Ok(HttpResponse::BadRequest().json(json!({
+                            "success": false,
+                            "message": format!("Tópico desconhecido: {}", name)
+                        })));
+                    }
+                }
+            }
+            topics
+        }
+    };
+    let subscription = Subscription {
+        topics,
+        election_id: query.election_id.clone(),
+    };
+
+    let (response, session, messages) = actix_ws::handle(&req, body)?;
+    actix_web::rt::spawn(run_session(
+        hub.get_ref().clone(),
+        subscription,
+        session,
+        messages,
+        config.stream.clone(),
+    ));
+    Ok(response)
+}
+
+/// Estatísticas do distribuidor (assinantes, descartes)
+pub async fn get_stream_stats(hub: web::Data<EventHub>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "stats": hub.stats()
+    })))
+}
+
+async fn run_session(
+    hub: EventHub,
+    mut subscription: Subscription,
+    mut session: Session,
+    mut messages: MessageStream,
+    config: crate::config::StreamConfig,
+) {
+    let mut events = hub.subscribe();
+    let mut heartbeat = tokio::time::interval(Duration::from_secs(config.heartbeat_seconds));
+    let client_timeout = Duration::from_secs(config.client_timeout_seconds);
+    let mut last_seen = Instant::now();
+    let mut total_skipped = 0u64;
+
+    if send(&mut session, &subscribed(&subscription)).await.is_err() {
+        return;
+    }
+
+    let reason = loop {
+        tokio::select! {
+            received = events.recv() => match received {
+                Ok(event) => {
+                    if subscription.matches(&event)
+                        && send(&mut session, &ServerMessage::Event(&event)).await.is_err()
+                    {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    hub.record_lag(skipped);
+                    total_skipped += skipped;
+                    if total_skipped > config.max_lagged_events {
+                        hub.record_slow_disconnect();
+                        log::warn!("Closing stream consumer after skipping {} events", total_skipped);
+                        break Some(CloseReason {
+                            code: CloseCode::Policy,
+                            description: Some("consumer too slow".to_string()),
+                        });
+                    }
+                    let notice = ServerMessage::Lagged { skipped, total_skipped };
+                    if send(&mut session, &notice).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Closed) => break Some(CloseCode::Away.into()),
+            },
+            message = messages.next() => {
+                last_seen = Instant::now();
+                match message {
+                    Some(Ok(Message::Text(text))) => {
+                        let reply = match serde_json::from_str::<ClientMessage>(&text) {
+                            Ok(command) => {
+                                apply(&mut subscription, command);
+                                subscribed(&subscription)
+                            }
+                            Err(e) => ServerMessage::Error {
+                                message: format!("Mensagem inválida: {}", e),
+                            },
+                        };
+                        if send(&mut session, &reply).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Close(reason))) => break reason,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break None,
+                }
+            },
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > client_timeout {
+                    break Some(CloseReason {
+                        code: CloseCode::Away,
+                        description: Some("heartbeat timeout".to_string()),
+                    });
+                }
+                if session.ping(b"").await.is_err() {
+                    return;
+                }
+            },
+        }
+    };
+
+    let _ = session.close(reason).await;
+}
+
+fn apply(subscription: &mut Subscription, command: ClientMessage) {
+    match command {
+        ClientMessage::Subscribe { topics, election_id } => {
+            subscription.topics.extend(topics);
+            if election_id.is_some() {
+                subscription.election_id = election_id;
+            }
+        }
+        ClientMessage::Unsubscribe { topics } => {
+            for topic in topics {
+                subscription.topics.remove(&topic);
+            }
+        }
+    }
+}
+
+fn subscribed(subscription: &Subscription) -> ServerMessage<'_> {
+    let mut topics: Vec<Topic> = subscription.topics.iter().copied().collect();
+    topics.sort_by_key(|topic| Topic::ALL.iter().position(|t| t == topic));
+    ServerMessage::Subscribed {
+        topics,
+        election_id: subscription.election_id.as_deref(),
+    }
+}
+
+async fn send(session: &mut Session, message: &ServerMessage<'_>) -> std::result::Result<(), actix_ws::Closed> {
+    match serde_json::to_string(message) {
+        Ok(text) => session.text(text).await,
+        Err(e) => {
+            log::error!("Failed to serialize stream message: {}", e);
+            Ok(())
+        }
+    }
+}
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/v1/stream", web::get().to(open_stream))
+        .route("/api/v1/stream/stats", web::get().to(get_stream_stats));
+}
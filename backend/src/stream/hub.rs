@@ -0,0 +1,194 @@
+//! Distribuidor de eventos por tópico
+//!
+//! Os produtores publicam num canal `broadcast` de capacidade limitada; cada
+//! conexão mantém seu próprio cursor. Um consumidor lento não atrasa os
+//! produtores nem os demais: quando fica mais de `capacity` eventos para
+//! trás, perde os mais antigos e é avisado de quantos foram descartados.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::monitoring::{Alert, NotificationChannel};
+
+/// Tópicos disponíveis para assinatura
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    LogEntries,
+    Alerts,
+    Consensus,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 3] = [Topic::LogEntries, Topic::Alerts, Topic::Consensus];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "log_entries" => Some(Topic::LogEntries),
+            "alerts" => Some(Topic::Alerts),
+            "consensus" => Some(Topic::Consensus),
+            _ => None,
+        }
+    }
+}
+
+/// Evento entregue aos assinantes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamEvent {
+    pub seq: u64,
+    pub topic: Topic,
+    pub timestamp: DateTime<Utc>,
+    pub election_id: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+/// Filtro de uma conexão: tópicos assinados e, opcionalmente, uma eleição
+#[derive(Debug, Clone, Default)]
+pub struct Subscription {
+    pub topics: HashSet<Topic>,
+    pub election_id: Option<String>,
+}
+
+impl Subscription {
+    /// Eventos sem eleição associada (alertas, STHs) passam pelo filtro de eleição
+    pub fn matches(&self, event: &StreamEvent) -> bool {
+        if !self.topics.contains(&event.topic) {
+            return false;
+        }
+        match (&self.election_id, &event.election_id) {
+            (Some(wanted), Some(actual)) => wanted == actual,
+            _ => true,
+        }
+    }
+}
+
+/// Estatísticas do distribuidor
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamStats {
+    pub capacity: usize,
+    pub subscribers: usize,
+    pub published: u64,
+    pub lagged_events: u64,
+    pub slow_disconnects: u64,
+}
+
+#[derive(Default)]
+struct HubCounters {
+    published: AtomicU64,
+    lagged_events: AtomicU64,
+    slow_disconnects: AtomicU64,
+}
+
+/// Distribuidor de eventos compartilhado entre produtores e conexões
+#[derive(Clone)]
+pub struct EventHub {
+    sender: broadcast::Sender<Arc<StreamEvent>>,
+    capacity: usize,
+    counters: Arc<HubCounters>,
+}
+
+impl EventHub {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            sender,
+            capacity: capacity.max(1),
+            counters: Arc::new(HubCounters::default()),
+        }
+    }
+
+    /// Publica um evento; sem assinantes, o evento é simplesmente descartado
+    pub fn publish(&self, topic: Topic, election_id: Option<String>, payload: serde_json::Value) -> u64 {
+        let seq = self.counters.published.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = self.sender.send(Arc::new(StreamEvent {
+            seq,
+            topic,
+            timestamp: Utc::now(),
+            election_id,
+            payload,
+        }));
+        seq
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<StreamEvent>> {
+        self.sender.subscribe()
+    }
+
+    pub fn record_lag(&self, skipped: u64) {
+        self.counters.lagged_events.fetch_add(skipped, Ordering::Relaxed);
+    }
+
+    pub fn record_slow_disconnect(&self) {
+        self.counters.slow_disconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> StreamStats {
+        StreamStats {
+            capacity: self.capacity,
+            subscribers: self.sender.receiver_count(),
+            published: self.counters.published.load(Ordering::Relaxed),
+            lagged_events: self.counters.lagged_events.load(Ordering::Relaxed),
+            slow_disconnects: self.counters.slow_disconnects.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Alertas do `MonitoringSystem` chegam ao tópico `alerts` como canal de notificação
+impl NotificationChannel for EventHub {
+    fn send_alert(&self, alert: &Alert) -> Result<()> {
+        self.publish(Topic::Alerts, None, serde_json::to_value(alert)?);
+        Ok(())
+    }
+
+    fn get_name(&self) -> &str {
+        "websocket-stream"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_subscription_filters_topic_and_election() {
+        let hub = EventHub::new(16);
+        let mut receiver = hub.subscribe();
+        hub.publish(Topic::LogEntries, Some("eleicao-2026".to_string()), json!({"index": 0}));
+        hub.publish(Topic::LogEntries, Some("eleicao-2024".to_string()), json!({"index": 1}));
+        hub.publish(Topic::Alerts, None, json!({"message": "latência alta"}));
+        hub.publish(Topic::Consensus, None, json!({"tree_size": 2}));
+
+        let subscription = Subscription {
+            topics: [Topic::LogEntries, Topic::Alerts].into_iter().collect(),
+            election_id: Some("eleicao-2026".to_string()),
+        };
+        let delivered: Vec<u64> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .filter(|event| subscription.matches(event))
+            .map(|event| event.seq)
+            .collect();
+        assert_eq!(delivered, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_slow_subscriber_loses_oldest_events_only() {
+        let hub = EventHub::new(4);
+        let mut slow = hub.subscribe();
+        for index in 0..10 {
+            hub.publish(Topic::LogEntries, None, json!({"index": index}));
+        }
+
+        match slow.try_recv() {
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => assert_eq!(skipped, 6),
+            other => panic!("expected lag notice, got {:?}", other.map(|e| e.seq)),
+        }
+        let remaining: Vec<u64> = std::iter::from_fn(|| slow.try_recv().ok()).map(|e| e.seq).collect();
+        assert_eq!(remaining, vec![7, 8, 9, 10]);
+        assert_eq!(hub.stats().published, 10);
+    }
+}
@@ -0,0 +1,10 @@
+//! Fluxo de Eventos em Tempo Real
+//!
+//! Entrega aos painéis de observadores, via WebSocket, as entradas do log
+//! transparente, os alertas do monitoramento e os eventos de consenso
+//! (quórum de verificadores e cossinatura das STHs) à medida que ocorrem.
+
+pub mod hub;
+pub mod api;
+
+pub use hub::*;
@@ -0,0 +1,167 @@
+//! Logs estruturados e correlação de requisições
+//!
+//! Os logs saem pelo `tracing`, em JSON com uma linha por evento; as macros
+//! `log::` usadas no restante do backend são encaminhadas ao mesmo
+//! subscriber. Cada requisição HTTP roda dentro de um span com o seu
+//! identificador de correlação (`X-Request-Id`), que também fica disponível
+//! para a tarefa via `current_request_id` e assim chega aos eventos do log
+//! transparente. Com `span_timing`, cada span emite a sua duração ao fechar.
+//!
+//! Com um coletor OTLP configurado, os spans também são exportados como
+//! rastros OpenTelemetry. O contexto W3C (`traceparent`) recebido da urna,
+//! no cabeçalho da chamada ou junto de cada voto da parte sincronizada,
+//! vira o pai dos spans do backend: um voto pode ser seguido do registro
+//! na urna até a validação, a entrada no log transparente e o consenso.
+
+use actix_web::http::header::HeaderMap;
+use anyhow::{Result, anyhow};
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use std::collections::HashMap;
+use std::future::Future;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+use crate::config::LoggingConfig;
+pub use fortis_domain::REQUEST_ID_HEADER;
+
+/// Tamanho máximo aceito para um identificador vindo do cliente
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Nome do serviço nos rastros exportados
+const SERVICE_NAME: &str = "fortis-backend";
+
+/// Instala o subscriber global; o `RUST_LOG` tem precedência sobre o filtro configurado
+pub fn init(config: &LoggingConfig) -> Result<()> {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&config.filter))
+        .map_err(|e| anyhow!("Invalid log filter {}: {}", config.filter, e))?;
+    let span_events = if config.span_timing { FmtSpan::CLOSE } else { FmtSpan::NONE };
+
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_span_events(span_events)
+        .with_target(true);
+    let fmt_layer = if config.json {
+        fmt_layer.json().with_current_span(true).with_span_list(false).with_filter(filter).boxed()
+    } else {
+        fmt_layer.with_filter(filter).boxed()
+    };
+
+    let otel_layer = match &config.otlp_endpoint {
+        Some(endpoint) => {
+            let trace_filter = EnvFilter::try_new(&config.trace_filter)
+                .map_err(|e| anyhow!("Invalid trace filter {}: {}", config.trace_filter, e))?;
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+                .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+                    opentelemetry_sdk::Resource::new(vec![KeyValue::new("service.name", SERVICE_NAME)]),
+                ))
+                .install_batch(opentelemetry_sdk::runtime::Tokio)
+                .map_err(|e| anyhow!("Failed to start OTLP exporter for {}: {}", endpoint, e))?;
+            Some(tracing_opentelemetry::layer().with_tracer(tracer).with_filter(trace_filter))
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(otel_layer)
+        .try_init()
+        .map_err(|e| anyhow!("Failed to install log subscriber: {}", e))
+}
+
+/// Envia os spans ainda em buffer ao coletor
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Contexto de rastreamento propagado nos cabeçalhos da requisição
+pub fn remote_context(headers: &HeaderMap) -> Context {
+    TraceContextPropagator::new().extract(&HeaderExtractor(headers))
+}
+
+/// Contexto de rastreamento a partir de um `traceparent` avulso
+pub fn context_from_traceparent(traceparent: &str) -> Context {
+    let carrier = HashMap::from([("traceparent".to_string(), traceparent.to_string())]);
+    TraceContextPropagator::new().extract(&carrier)
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Identificador recebido no cabeçalho, se for aceitável, ou um novo
+pub fn request_id_or_new(header: Option<&str>) -> String {
+    header
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Executa o futuro com o identificador de correlação da requisição
+pub async fn with_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// Identificador de correlação da requisição em andamento na tarefa
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_header_is_kept_only_when_safe() {
+        assert_eq!(request_id_or_new(Some("urna-0042:7f3a")), "urna-0042:7f3a");
+
+        let injected = request_id_or_new(Some("abc\n{\"level\":\"ERROR\"}"));
+        assert_ne!(injected, "abc\n{\"level\":\"ERROR\"}");
+        assert!(Uuid::parse_str(&injected).is_ok());
+        assert!(Uuid::parse_str(&request_id_or_new(Some(&"a".repeat(MAX_REQUEST_ID_LEN + 1)))).is_ok());
+        assert!(Uuid::parse_str(&request_id_or_new(None)).is_ok());
+    }
+
+    #[test]
+    fn test_traceparent_becomes_remote_parent() {
+        use opentelemetry::trace::TraceContextExt;
+
+        let context = context_from_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01");
+        let span_context = context.span().span_context().clone();
+        assert!(span_context.is_remote());
+        assert_eq!(span_context.trace_id().to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+
+        assert!(!context_from_traceparent("not-a-traceparent").span().span_context().is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_scoped_to_the_task() {
+        assert_eq!(current_request_id(), None);
+        let inside = with_request_id("req-1".to_string(), async { current_request_id() }).await;
+        assert_eq!(inside.as_deref(), Some("req-1"));
+        assert_eq!(current_request_id(), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Política de transporte (TLS) do backend
+//!
+//! Monta as configurações rustls do servidor HTTP e dos clientes internos
+//! a partir do perfil configurado, validando tudo na inicialização: um
+//! perfil desconhecido, certificado ilegível ou chave fora da política
+//! impedem o servidor de subir.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use rustls::server::{
+    AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, ClientCertVerifier, NoClientAuth,
+};
+use rustls::{ClientConfig, RootCertStore, ServerConfig, SignatureAlgorithm};
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+use crate::config::TransportConfig;
+pub use fortis_domain::transport::{CipherPolicy, TlsProfile, TlsVersion};
+
+/// Canal de comunicação coberto pela política
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelPolicy {
+    pub channel: String,
+    pub tls_enabled: bool,
+    pub versions: Vec<TlsVersion>,
+    pub cipher_suites: Vec<String>,
+    pub key_exchange_groups: Vec<String>,
+}
+
+/// Relatório da política criptográfica ativa, para auditoria
+#[derive(Debug, Clone, Serialize)]
+pub struct CryptoPolicyReport {
+    pub profile: TlsProfile,
+    pub policy: CipherPolicy,
+    pub channels: Vec<ChannelPolicy>,
+    pub server_certificate_chain_length: usize,
+    pub validated_at: DateTime<Utc>,
+}
+
+/// Política de transporte validada na inicialização
+#[derive(Clone)]
+pub struct TransportPolicy {
+    policy: CipherPolicy,
+    config: TransportConfig,
+    server: Option<Arc<ServerConfig>>,
+    chain_length: usize,
+    validated_at: DateTime<Utc>,
+}
+
+impl TransportPolicy {
+    /// Valida o perfil e, com TLS habilitado, carrega certificado e chave
+    pub fn from_config(config: &TransportConfig) -> Result<Self> {
+        let profile: TlsProfile = config.tls_profile.parse()?;
+        let policy = profile.policy();
+
+        // Mesmo sem TLS no servidor, os clientes internos usam a política
+        policy.rustls_parameters()?;
+
+        let (server, chain_length) = if config.tls_enabled {
+            let (server, chain_length) = build_server_config(&policy, config, device_client_verifier(config)?)?;
+            (Some(Arc::new(server)), chain_length)
+        } else {
+            (None, 0)
+        };
+
+        Ok(Self {
+            policy,
+            config: config.clone(),
+            server,
+            chain_length,
+            validated_at: Utc::now(),
+        })
+    }
+
+    pub fn profile(&self) -> TlsProfile {
+        self.policy.profile
+    }
+
+    /// Configuração do servidor HTTP, se o TLS estiver habilitado
+    pub fn server_config(&self) -> Option<ServerConfig> {
+        self.server.as_deref().cloned()
+    }
+
+    pub fn internal_tls_enabled(&self) -> bool {
+        self.config.internal_tls_enabled
+    }
+
+    /// Rotas de urnas exigem certificado de dispositivo (só com TLS no servidor)
+    pub fn device_certificates_required(&self) -> bool {
+        self.server.is_some() && self.config.require_device_certificates
+    }
+
+    /// Configuração do servidor gRPC: exige certificado de cliente emitido pela CA interna
+    pub fn mtls_server_config(&self) -> Result<ServerConfig> {
+        let verifier = AllowAnyAuthenticatedClient::new(read_roots(&self.config.ca_path)?).boxed();
+        let (server, _) = build_server_config(&self.policy, &self.config, verifier)?;
+        Ok(server)
+    }
+
+    /// Configuração de cliente para chamadas internas (gRPC, serviços do cluster)
+    pub fn client_config(&self) -> Result<ClientConfig> {
+        Ok(self.policy.rustls_client_config(read_roots(&self.config.ca_path)?)?)
+    }
+
+    /// Relatório da política efetiva por canal
+    pub fn report(&self) -> CryptoPolicyReport {
+        let channel = |name: &str, tls_enabled: bool| ChannelPolicy {
+            channel: name.to_string(),
+            tls_enabled,
+            versions: self.policy.versions.clone(),
+            cipher_suites: self.policy.cipher_suites.clone(),
+            key_exchange_groups: self.policy.key_exchange_groups.clone(),
+        };
+
+        CryptoPolicyReport {
+            profile: self.policy.profile,
+            policy: self.policy.clone(),
+            channels: vec![
+                channel("http_server", self.server.is_some()),
+                // A urna recebe o mesmo perfil e monta o cliente localmente
+                channel("urna_client", true),
+                channel("grpc_internal", self.config.internal_tls_enabled),
+            ],
+            server_certificate_chain_length: self.chain_length,
+            validated_at: self.validated_at,
+        }
+    }
+}
+
+fn build_server_config(
+    policy: &CipherPolicy,
+    config: &TransportConfig,
+    client_verifier: Arc<dyn ClientCertVerifier>,
+) -> Result<(ServerConfig, usize)> {
+    let certs: Vec<rustls::Certificate> = read_certs(&config.cert_path)?;
+    if certs.is_empty() {
+        return Err(anyhow!("No certificate found in {}", config.cert_path));
+    }
+    let key = read_private_key(&config.key_path)?;
+
+    let signing_key = rustls::sign::any_supported_type(&key)
+        .map_err(|_| anyhow!("Unsupported private key in {}", config.key_path))?;
+    if policy.profile == TlsProfile::Restricted && signing_key.algorithm() == SignatureAlgorithm::ED25519 {
+        return Err(anyhow!("Ed25519 server keys are not allowed by the restricted profile"));
+    }
+
+    let params = policy.rustls_parameters()?;
+    let chain_length = certs.len();
+    let server = ServerConfig::builder()
+        .with_cipher_suites(&params.cipher_suites)
+        .with_kx_groups(&params.kx_groups)
+        .with_protocol_versions(&params.versions)?
+        .with_client_cert_verifier(client_verifier)
+        .with_single_cert(certs, key)?;
+    Ok((server, chain_length))
+}
+
+/// Servidor HTTP: certificado de cliente opcional no handshake, validado
+/// contra a CA de dispositivos; as rotas de urnas exigem a presença dele
+fn device_client_verifier(config: &TransportConfig) -> Result<Arc<dyn ClientCertVerifier>> {
+    match read_roots(&config.device_ca_path) {
+        Ok(roots) => Ok(AllowAnyAnonymousOrAuthenticatedClient::new(roots).boxed()),
+        Err(e) if config.require_device_certificates => Err(e),
+        Err(e) => {
+            log::warn!("Device CA unavailable, urna client certificates disabled: {}", e);
+            Ok(NoClientAuth::boxed())
+        }
+    }
+}
+
+fn read_roots(path: &str) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in read_certs(path)? {
+        roots.add(&cert)?;
+    }
+    Ok(roots)
+}
+
+fn read_certs(path: &str) -> Result<Vec<rustls::Certificate>> {
+    let file = File::open(path).map_err(|e| anyhow!("Cannot read certificate {}: {}", path, e))?;
+    Ok(rustls_pemfile::certs(&mut BufReader::new(file))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect())
+}
+
+fn read_private_key(path: &str) -> Result<rustls::PrivateKey> {
+    let file = File::open(path).map_err(|e| anyhow!("Cannot read private key {}: {}", path, e))?;
+    let mut reader = BufReader::new(file);
+    loop {
+        match rustls_pemfile::read_one(&mut reader)? {
+            Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(rustls::PrivateKey(key)),
+            Some(_) => continue,
+            None => return Err(anyhow!("No private key found in {}", path)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(profile: &str) -> TransportConfig {
+        TransportConfig {
+            tls_profile: profile.to_string(),
+            tls_enabled: false,
+            internal_tls_enabled: false,
+            grpc_enabled: false,
+            grpc_port: 0,
+            cert_path: String::new(),
+            key_path: String::new(),
+            ca_path: String::new(),
+            device_ca_path: String::new(),
+            require_device_certificates: true,
+        }
+    }
+
+    #[test]
+    fn test_every_profile_maps_to_rustls() {
+        for profile in ["modern", "intermediate", "restricted"] {
+            let policy = TransportPolicy::from_config(&config(profile)).unwrap();
+            let report = policy.report();
+            assert_eq!(report.profile.as_str(), profile);
+            assert_eq!(report.channels.len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_unknown_profile_fails_validation() {
+        assert!(TransportPolicy::from_config(&config("legacy")).is_err());
+    }
+}
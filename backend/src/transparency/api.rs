@@ -5,15 +5,27 @@
 //! blockchain não é necessário para transparência eleitoral.
 
 use actix_web::{web, HttpResponse, Result, HttpRequest};
+use sha2::{Digest, Sha256};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use fortis_domain::region::RegionScope;
 
 use crate::transparency::election_logs::{
     ElectionTransparencyLog, ElectionEvent, ElectionEventType, 
     LogConfig, LogStats, DetailedLogStats, SearchCriteria,
-    InclusionProof, ExportFormat, ConfigValidationResult
+    InclusionProof, ExportFormat, ConfigValidationResult, ConsistencyProof
+};
+use crate::transparency::tree_heads::{SignedTreeHead, ObservationResult};
+use crate::transparency::witness::{CosignReply, CosignRequest};
+use crate::transparency::visibility::{EntryView, ViewLevel};
+use crate::config::Config;
+use crate::auth::rbac::{Permission, Principal};
+use crate::middleware::device_identity::connection_device;
+use crate::transparency::signature_collector::{SignatureCollector, QuorumOutcome};
+use crate::transparency::audit_camera::{
+    AuditCameraHooks, CameraRegistration, CameraHealthReport, RecordingSegment
 };
 
 /// Estado compartilhado do sistema de logs
@@ -36,6 +48,9 @@ pub struct SearchEventsRequest {
     pub end_time: Option<DateTime<Utc>>,
     pub election_id: Option<String>,
     pub verification_status: Option<String>,
+    /// Recorte da hierarquia eleitoral (`SP`, `SP/71072/0001`)
+    #[serde(default)]
+    pub region: Option<String>,
 }
 
 /// Dados de configuração do log
@@ -51,11 +66,54 @@ pub struct LogConfigRequest {
     pub verification_timeout_seconds: u64,
 }
 
+/// Visão pedida das entradas; `full` exige chave de auditor
+#[derive(Debug, Deserialize)]
+pub struct ViewQuery {
+    pub view: Option<ViewLevel>,
+}
+
+/// Parâmetros da prova de consistência
+#[derive(Debug, Deserialize)]
+pub struct ConsistencyQuery {
+    pub old_size: u64,
+    pub new_size: Option<u64>,
+}
+
+/// Parâmetros da STH mais recente
+#[derive(Debug, Deserialize)]
+pub struct LatestTreeHeadQuery {
+    /// Exige ao menos este número de cossinaturas de testemunhas
+    pub min_witnesses: Option<usize>,
+}
+
+/// Parâmetros do histórico de STHs
+#[derive(Debug, Deserialize)]
+pub struct TreeHeadHistoryQuery {
+    pub min_tree_size: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// STH observada submetida por monitor externo
+#[derive(Debug, Deserialize)]
+pub struct SubmitTreeHeadRequest {
+    pub monitor_id: String,
+    pub sth: SignedTreeHead,
+}
+
+/// Verificação de arquivo de gravação da câmera de auditoria
+#[derive(Debug, Deserialize)]
+pub struct VerifySegmentRequest {
+    pub camera_id: String,
+    pub segment_index: u64,
+    pub sha256: String,
+}
+
 /// Resposta de criação de evento
 #[derive(Debug, Serialize)]
 pub struct CreateEventResponse {
     pub success: bool,
     pub inclusion_proof: Option<InclusionProof>,
+    pub quorum: Option<QuorumOutcome>,
     pub message: String,
 }
 
@@ -87,11 +145,12 @@ pub struct ConfigResponse {
 
 /// Cria um novo evento eleitoral no log transparente
 pub async fn create_event(
+    principal: Principal,
     req: web::Json<CreateEventRequest>,
     log_state: web::Data<LogState>,
+    collector: web::Data<SignatureCollector>,
 ) -> Result<HttpResponse> {
-    let mut log = log_state.write().await;
-    
+    principal.require(Permission::ManageTransparencyLog)?;
     let event = ElectionEvent {
         id: uuid::Uuid::new_v4().to_string(),
         event_type: req.event_type.clone(),
@@ -101,18 +160,28 @@ pub async fn create_event(
         source: req.source.clone(),
     };
 
-    match log.append_election_event(event) {
-        Ok(inclusion_proof) => {
+    match collector.append_event(log_state.get_ref(), event).await {
+        Ok((inclusion_proof, quorum)) => {
+            let message = if quorum.quorum_reached {
+                "Event created successfully".to_string()
+            } else {
+                format!(
+                    "Event created with partial quorum ({} verifier failures)",
+                    quorum.failures.len()
+                )
+            };
             Ok(HttpResponse::Ok().json(CreateEventResponse {
                 success: true,
                 inclusion_proof: Some(inclusion_proof),
-                message: "Event created successfully".to_string(),
+                quorum: Some(quorum),
+                message,
             }))
         }
         Err(e) => {
             Ok(HttpResponse::BadRequest().json(CreateEventResponse {
                 success: false,
                 inclusion_proof: None,
+                quorum: None,
                 message: format!("Failed to create event: {}", e),
             }))
         }
@@ -121,9 +190,26 @@ pub async fn create_event(
 
 /// Busca eventos no log transparente
 pub async fn search_events(
+    http_req: HttpRequest,
+    view: web::Query<ViewQuery>,
     req: web::Json<SearchEventsRequest>,
     log_state: web::Data<LogState>,
 ) -> Result<HttpResponse> {
+    let level = match authorize_view(&http_req, view.view) {
+        Ok(level) => level,
+        Err(response) => return Ok(response),
+    };
+    let region = match req.region.as_deref().map(RegionScope::parse).transpose() {
+        Ok(region) => region,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(SearchEventsResponse {
+                success: false,
+                events: vec![],
+                total_count: 0,
+                message: format!("Invalid region: {}", e),
+            }));
+        }
+    };
     let log = log_state.read().await;
     
     let criteria = SearchCriteria {
@@ -132,6 +218,7 @@ pub async fn search_events(
         end_time: req.end_time,
         election_id: req.election_id.clone(),
         verification_status: None, // Seria necessário implementar conversão
+        region,
     };
 
     match log.search_events(criteria) {
@@ -142,6 +229,8 @@ pub async fn search_events(
                     "timestamp": entry.timestamp,
                     "event_type": entry.event_type,
                     "event_hash": entry.event_hash,
+                    "event": EntryView::new(entry, level).ok().map(|view| view.event),
+                    "redacted_fields": entry.redactions.iter().map(|r| r.path.clone()).collect::<Vec<_>>(),
                     "verification_status": if entry.verifier_signatures.len() >= 2 { "verified" } else { "pending" },
                     "verifier_count": entry.verifier_signatures.len()
                 }))
@@ -197,9 +286,11 @@ pub async fn get_config(
 
 /// Atualiza configuração do log transparente
 pub async fn update_config(
+    principal: Principal,
     req: web::Json<LogConfigRequest>,
     log_state: web::Data<LogState>,
 ) -> Result<HttpResponse> {
+    principal.require(Permission::ManageTransparencyLog)?;
     let mut log = log_state.write().await;
     
     let new_config = LogConfig {
@@ -235,26 +326,30 @@ pub async fn update_config(
 
 /// Obtém entrada específica do log
 pub async fn get_log_entry(
+    req: HttpRequest,
     path: web::Path<u64>,
+    view: web::Query<ViewQuery>,
     log_state: web::Data<LogState>,
 ) -> Result<HttpResponse> {
+    let level = match authorize_view(&req, view.view) {
+        Ok(level) => level,
+        Err(response) => return Ok(response),
+    };
     let log = log_state.read().await;
     
     match log.get_log_entry(path.into_inner()) {
-        Some(entry) => {
-            Ok(HttpResponse::Ok().json(serde_json::json!({
+        Some(entry) => match EntryView::new(entry, level) {
+            Ok(view) => Ok(HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
-                "entry": {
-                    "index": entry.index,
-                    "timestamp": entry.timestamp,
-                    "event_type": entry.event_type,
-                    "event_hash": entry.event_hash,
-                    "verifier_signatures": entry.verifier_signatures,
-                    "merkle_proof": entry.merkle_proof
-                },
+                "entry": view,
                 "message": "Entry retrieved successfully"
-            })))
-        }
+            }))),
+            Err(e) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "entry": null,
+                "message": format!("Failed to build entry view: {}", e)
+            }))),
+        },
         None => {
             Ok(HttpResponse::NotFound().json(serde_json::json!({
                 "success": false,
@@ -267,9 +362,19 @@ pub async fn get_log_entry(
 
 /// Exporta log para auditoria
 pub async fn export_log(
+    req: HttpRequest,
     query: web::Query<std::collections::HashMap<String, String>>,
     log_state: web::Data<LogState>,
 ) -> Result<HttpResponse> {
+    let requested = match query.get("view").map(|s| s.as_str()) {
+        Some("full") => Some(ViewLevel::Full),
+        Some("public") => Some(ViewLevel::Public),
+        _ => None,
+    };
+    let level = match authorize_view(&req, requested) {
+        Ok(level) => level,
+        Err(response) => return Ok(response),
+    };
     let log = log_state.read().await;
     
     let format = match query.get("format").map(|s| s.as_str()) {
@@ -277,7 +382,7 @@ pub async fn export_log(
         _ => ExportFormat::Json,
     };
 
-    match log.export_for_audit(format) {
+    match log.export_view(format, level) {
         Ok(data) => {
             let content_type = match format {
                 ExportFormat::Json => "application/json",
@@ -297,6 +402,49 @@ pub async fn export_log(
     }
 }
 
+/// Política de visibilidade dos campos dos eventos
+pub async fn get_visibility_policy(
+    log_state: web::Data<LogState>,
+) -> Result<HttpResponse> {
+    let log = log_state.read().await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "policy": log.visibility_policy(),
+        "message": "Visibility policy retrieved successfully"
+    })))
+}
+
+/// Resolve a visão pedida: pública por padrão, completa só com chave de auditor
+/// (`Authorization: Bearer <chave>`)
+fn authorize_view(req: &HttpRequest, requested: Option<ViewLevel>) -> std::result::Result<ViewLevel, HttpResponse> {
+    match requested.unwrap_or(ViewLevel::Public) {
+        ViewLevel::Public => Ok(ViewLevel::Public),
+        ViewLevel::Full => {
+            let key_hash = req.headers()
+                .get("Authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(|key| hex::encode(Sha256::digest(key.as_bytes())));
+            let authorized = match (key_hash, req.app_data::<web::Data<Config>>()) {
+                (Some(key_hash), Some(config)) => config.transparency.auditor_key_hashes
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(&key_hash)),
+                _ => false,
+            };
+
+            if authorized {
+                Ok(ViewLevel::Full)
+            } else {
+                Err(HttpResponse::Forbidden().json(serde_json::json!({
+                    "success": false,
+                    "message": "Full view requires auditor credentials"
+                })))
+            }
+        }
+    }
+}
+
 /// Verifica integridade do log
 pub async fn verify_integrity(
     log_state: web::Data<LogState>,
@@ -322,8 +470,10 @@ pub async fn verify_integrity(
 
 /// Limpa logs antigos
 pub async fn cleanup_logs(
+    principal: Principal,
     log_state: web::Data<LogState>,
 ) -> Result<HttpResponse> {
+    principal.require(Permission::ManageTransparencyLog)?;
     let mut log = log_state.write().await;
     
     match log.cleanup_old_logs() {
@@ -343,10 +493,178 @@ pub async fn cleanup_logs(
     }
 }
 
+/// Gera prova de consistência entre dois tamanhos do log
+pub async fn get_consistency_proof(
+    log_state: web::Data<LogState>,
+    query: web::Query<ConsistencyQuery>,
+) -> Result<HttpResponse> {
+    let log = log_state.read().await;
+    let new_size = query.new_size.unwrap_or_else(|| log.tree_size());
+
+    match log.generate_consistency_proof(query.old_size, new_size) {
+        Ok(proof) => {
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "proof": proof,
+                "message": "Consistency proof generated successfully"
+            })))
+        }
+        Err(e) => {
+            Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": format!("Failed to generate consistency proof: {}", e)
+            })))
+        }
+    }
+}
+
+/// Verifica prova de consistência enviada por auditor externo
+pub async fn verify_consistency_proof(
+    log_state: web::Data<LogState>,
+    req: web::Json<ConsistencyProof>,
+) -> Result<HttpResponse> {
+    let log = log_state.read().await;
+
+    match log.verify_consistency_proof(&req) {
+        Ok(valid) => {
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "valid": valid,
+                "message": if valid { "Log is append-only between the given sizes" } else { "Consistency proof is invalid" }
+            })))
+        }
+        Err(e) => {
+            Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": format!("Consistency verification failed: {}", e)
+            })))
+        }
+    }
+}
+
+/// Obtém a STH mais recente
+pub async fn get_latest_tree_head(
+    log_state: web::Data<LogState>,
+    query: web::Query<LatestTreeHeadQuery>,
+) -> Result<HttpResponse> {
+    let log = log_state.read().await;
+    let latest = match query.min_witnesses {
+        Some(min_witnesses) => log.latest_witnessed_tree_head(min_witnesses),
+        None => log.latest_tree_head(),
+    };
+
+    match latest {
+        Some(sth) => {
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "sth": sth,
+                "public_key": log.log_public_key(),
+                "message": "Latest signed tree head retrieved successfully"
+            })))
+        }
+        None => {
+            Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "No signed tree head published yet"
+            })))
+        }
+    }
+}
+
+/// Lista STHs históricas
+pub async fn get_tree_head_history(
+    log_state: web::Data<LogState>,
+    query: web::Query<TreeHeadHistoryQuery>,
+) -> Result<HttpResponse> {
+    let log = log_state.read().await;
+    let history = log.tree_head_history(
+        query.min_tree_size.unwrap_or(0),
+        query.limit.unwrap_or(100).min(1000),
+    );
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "sths": history,
+        "total_count": history.len(),
+        "message": "Signed tree head history retrieved successfully"
+    })))
+}
+
+/// Cossina a STH de outro backend regional (este nó como testemunha)
+pub async fn cosign_tree_head(
+    log_state: web::Data<LogState>,
+    req: web::Json<CosignRequest>,
+) -> Result<HttpResponse> {
+    let mut log = log_state.write().await;
+    let req = req.into_inner();
+
+    match log.cosign_peer_tree_head(&req) {
+        Ok(signature) => Ok(HttpResponse::Ok().json(CosignReply {
+            signature: Some(signature),
+            known_tree_size: Some(req.sth.tree_size),
+            error: None,
+        })),
+        Err(e) => {
+            log::warn!("Cossinatura recusada para o log {}: {}", req.sth.log_id, e);
+            Ok(HttpResponse::Conflict().json(CosignReply {
+                signature: None,
+                known_tree_size: log.witnessed_tree_size(&req.sth.log_id),
+                error: Some(e.to_string()),
+            }))
+        }
+    }
+}
+
+/// Recebe STH observada por outro monitor para detecção de split view
+pub async fn submit_observed_tree_head(
+    log_state: web::Data<LogState>,
+    req: web::Json<SubmitTreeHeadRequest>,
+) -> Result<HttpResponse> {
+    let mut log = log_state.write().await;
+    let req = req.into_inner();
+
+    match log.submit_observed_tree_head(&req.monitor_id, req.sth) {
+        Ok(result) => {
+            let split_view = result == ObservationResult::SplitView;
+            if split_view {
+                log::error!("Split view detectado a partir do monitor {}", req.monitor_id);
+            }
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "result": result,
+                "message": if split_view { "Split view detected" } else { "STH is consistent with log history" }
+            })))
+        }
+        Err(e) => {
+            Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": format!("STH rejected: {}", e)
+            })))
+        }
+    }
+}
+
+/// Lista evidências de split view
+pub async fn get_split_views(
+    log_state: web::Data<LogState>,
+) -> Result<HttpResponse> {
+    let log = log_state.read().await;
+    let split_views = log.get_split_views();
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "split_views": split_views,
+        "total_count": split_views.len(),
+        "message": "Split view evidence retrieved successfully"
+    })))
+}
+
 /// Obtém trilha de auditoria
 pub async fn get_audit_trail(
+    principal: Principal,
     log_state: web::Data<LogState>,
 ) -> Result<HttpResponse> {
+    principal.require(Permission::ReadAudits)?;
     let log = log_state.read().await;
     let audit_trail = log.get_audit_trail();
 
@@ -396,6 +714,135 @@ pub async fn health_check(
 }
 
 /// Configura as rotas da API de logs transparentes
+/// Registra câmera de auditoria de uma urna
+pub async fn register_audit_camera(
+    principal: Principal,
+    req: web::Json<CameraRegistration>,
+    log_state: web::Data<LogState>,
+    hooks: web::Data<AuditCameraHooks>,
+) -> Result<HttpResponse> {
+    let registration = req.into_inner();
+    let actor = principal.require_for(Permission::ManageElections, &registration.election_id)?;
+    log::info!("Audit camera {} for urna {} registered by {}", registration.camera_id, registration.urna_id, actor);
+    match hooks.register_camera(log_state.get_ref(), registration).await {
+        Ok(proof) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "inclusion_proof": proof,
+            "message": "Audit camera registered"
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": format!("Failed to register audit camera: {}", e)
+        }))),
+    }
+}
+
+/// Registra relatório de saúde da câmera de auditoria, enviado pela urna
+/// identificada pelo certificado da conexão
+pub async fn report_audit_camera_health(
+    http_req: HttpRequest,
+    req: web::Json<CameraHealthReport>,
+    log_state: web::Data<LogState>,
+    hooks: web::Data<AuditCameraHooks>,
+) -> Result<HttpResponse> {
+    let device = match connection_device(&http_req).await {
+        Ok(device) => device,
+        Err(e) => return Ok(camera_device_rejected(e)),
+    };
+    match hooks.report_health(log_state.get_ref(), &device.urna_id, req.into_inner()).await {
+        Ok(proof) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "inclusion_proof": proof,
+            "message": "Camera health recorded"
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": format!("Failed to record camera health: {}", e)
+        }))),
+    }
+}
+
+/// Registra hash de segmento de gravação, enviado pela urna identificada
+/// pelo certificado da conexão
+pub async fn record_audit_camera_segment(
+    http_req: HttpRequest,
+    req: web::Json<RecordingSegment>,
+    log_state: web::Data<LogState>,
+    hooks: web::Data<AuditCameraHooks>,
+) -> Result<HttpResponse> {
+    let device = match connection_device(&http_req).await {
+        Ok(device) => device,
+        Err(e) => return Ok(camera_device_rejected(e)),
+    };
+    match hooks.record_segment(log_state.get_ref(), &device.urna_id, req.into_inner()).await {
+        Ok(segment) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "segment": segment,
+            "message": if segment.gap_detected {
+                "Segment recorded with continuity gap"
+            } else {
+                "Segment recorded"
+            }
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": format!("Failed to record segment: {}", e)
+        }))),
+    }
+}
+
+fn camera_device_rejected(error: anyhow::Error) -> HttpResponse {
+    log::warn!("Audit camera report rejected without urna identity: {}", error);
+    HttpResponse::Unauthorized().json(serde_json::json!({
+        "success": false,
+        "message": format!("Urna certificate required: {}", error)
+    }))
+}
+
+/// Verifica arquivo de gravação contra o hash registrado
+pub async fn verify_audit_camera_segment(
+    req: web::Json<VerifySegmentRequest>,
+    log_state: web::Data<LogState>,
+    hooks: web::Data<AuditCameraHooks>,
+) -> Result<HttpResponse> {
+    match hooks.verify_segment(log_state.get_ref(), &req.camera_id, req.segment_index, &req.sha256).await {
+        Ok(verification) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "verification": verification,
+            "message": if verification.hash_matches {
+                "Footage matches the logged hash"
+            } else {
+                "Footage does not match the logged hash"
+            }
+        }))),
+        Err(e) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": format!("Failed to verify segment: {}", e)
+        }))),
+    }
+}
+
+/// Lista segmentos registrados de uma câmera
+pub async fn get_audit_camera_segments(
+    path: web::Path<String>,
+    hooks: web::Data<AuditCameraHooks>,
+) -> Result<HttpResponse> {
+    let camera_id = path.into_inner();
+    match hooks.get_segments(&camera_id).await {
+        Some(segments) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "camera_id": camera_id,
+            "health": hooks.get_health(&camera_id).await,
+            "segments": segments,
+            "message": "Segments retrieved"
+        }))),
+        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": format!("Camera not registered: {}", camera_id)
+        }))),
+    }
+}
+
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg
         .service(
@@ -407,7 +854,20 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 .route("/config", web::get().to(get_config))
                 .route("/config", web::put().to(update_config))
                 .route("/export", web::get().to(export_log))
+                .route("/visibility", web::get().to(get_visibility_policy))
                 .route("/verify", web::post().to(verify_integrity))
+                .route("/consistency", web::get().to(get_consistency_proof))
+                .route("/consistency/verify", web::post().to(verify_consistency_proof))
+                .route("/sth", web::get().to(get_latest_tree_head))
+                .route("/sth/history", web::get().to(get_tree_head_history))
+                .route("/sth/observed", web::post().to(submit_observed_tree_head))
+                .route("/sth/cosign", web::post().to(cosign_tree_head))
+                .route("/sth/split-views", web::get().to(get_split_views))
+                .route("/audit-camera/cameras", web::post().to(register_audit_camera))
+                .route("/audit-camera/health", web::post().to(report_audit_camera_health))
+                .route("/audit-camera/segments", web::post().to(record_audit_camera_segment))
+                .route("/audit-camera/segments/verify", web::post().to(verify_audit_camera_segment))
+                .route("/audit-camera/{camera_id}/segments", web::get().to(get_audit_camera_segments))
                 .route("/cleanup", web::post().to(cleanup_logs))
                 .route("/audit", web::get().to(get_audit_trail))
                 .route("/metrics", web::get().to(get_performance_metrics))
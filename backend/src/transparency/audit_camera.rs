@@ -0,0 +1,425 @@
+//! Ganchos Opcionais da Câmera de Auditoria
+//!
+//! Algumas jurisdições filmam a urna (nunca o eleitor) durante a votação.
+//! O FORTIS não recebe o vídeo: apenas registra no log transparente a saúde
+//! da câmera e o hash de cada segmento gravado, encadeado ao segmento
+//! anterior. Assim, a integridade da gravação pode ser verificada depois,
+//! comparando o hash do arquivo com o registrado no log.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::election_logs::{
+    ElectionTransparencyLog, ElectionEvent, ElectionEventType, InclusionProof, VerificationStatus
+};
+
+/// Registro da câmera junto à urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraRegistration {
+    pub camera_id: String,
+    pub urna_id: String,
+    pub election_id: String,
+    pub section: String,
+    /// Responsável que atestou que a câmera enquadra apenas a urna
+    pub framing_attested_by: String,
+}
+
+/// Estado reportado pela câmera
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CameraStatus {
+    Recording,
+    Idle,
+    Degraded,
+    Obstructed,
+    Offline,
+}
+
+/// Relatório de saúde da câmera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraHealthReport {
+    pub camera_id: String,
+    pub status: CameraStatus,
+    pub storage_free_mb: u64,
+    pub message: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Segmento de gravação finalizado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSegment {
+    pub camera_id: String,
+    pub segment_index: u64,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// SHA-256 do arquivo do segmento, em hexadecimal
+    pub sha256: String,
+    pub size_bytes: u64,
+    /// Hash do segmento anterior (vazio no primeiro segmento)
+    pub previous_sha256: String,
+}
+
+/// Segmento registrado no log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedSegment {
+    pub segment: RecordingSegment,
+    pub log_index: u64,
+    /// Falha de continuidade em relação ao segmento anterior
+    pub gap_detected: bool,
+}
+
+/// Resultado da verificação de um arquivo de gravação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentVerification {
+    pub camera_id: String,
+    pub segment_index: u64,
+    pub log_index: u64,
+    pub logged_sha256: String,
+    pub hash_matches: bool,
+    pub log_entry_status: VerificationStatus,
+}
+
+/// Estado de uma câmera registrada
+#[derive(Debug, Clone)]
+struct CameraState {
+    registration: CameraRegistration,
+    last_health: Option<CameraHealthReport>,
+    segments: Vec<LoggedSegment>,
+}
+
+/// Ganchos da câmera de auditoria
+pub struct AuditCameraHooks {
+    enabled: bool,
+    cameras: RwLock<HashMap<String, CameraState>>,
+}
+
+impl AuditCameraHooks {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            cameras: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Registra câmera de auditoria de uma urna
+    pub async fn register_camera(
+        &self,
+        log: &Arc<RwLock<ElectionTransparencyLog>>,
+        registration: CameraRegistration,
+    ) -> Result<InclusionProof> {
+        self.ensure_enabled()?;
+        if registration.framing_attested_by.trim().is_empty() {
+            return Err(anyhow!("Camera framing must be attested before registration"));
+        }
+
+        let mut cameras = self.cameras.write().await;
+        if cameras.contains_key(&registration.camera_id) {
+            return Err(anyhow!("Camera already registered: {}", registration.camera_id));
+        }
+
+        let proof = append(log, &registration.election_id, ElectionEventType::SystemEvent, serde_json::json!({
+            "kind": "audit_camera_registered",
+            "camera_id": registration.camera_id,
+            "urna_id": registration.urna_id,
+            "section": registration.section,
+            "framing_attested_by": registration.framing_attested_by,
+        }), &registration.urna_id).await?;
+
+        cameras.insert(registration.camera_id.clone(), CameraState {
+            registration,
+            last_health: None,
+            segments: Vec::new(),
+        });
+        Ok(proof)
+    }
+
+    /// Registra relatório de saúde da câmera, enviado pela urna à qual ela
+    /// foi registrada
+    pub async fn report_health(
+        &self,
+        log: &Arc<RwLock<ElectionTransparencyLog>>,
+        urna_id: &str,
+        report: CameraHealthReport,
+    ) -> Result<InclusionProof> {
+        self.ensure_enabled()?;
+        let mut cameras = self.cameras.write().await;
+        let camera = camera_of_urna(&mut cameras, &report.camera_id, urna_id)?;
+
+        let event_type = match report.status {
+            CameraStatus::Recording | CameraStatus::Idle => ElectionEventType::AuditCameraHealth,
+            _ => ElectionEventType::SecurityAlert,
+        };
+        let proof = append(log, &camera.registration.election_id, event_type, serde_json::json!({
+            "kind": "audit_camera_health",
+            "camera_id": report.camera_id,
+            "urna_id": camera.registration.urna_id,
+            "status": report.status,
+            "storage_free_mb": report.storage_free_mb,
+            "message": report.message,
+            "reported_at": report.timestamp,
+        }), &camera.registration.urna_id).await?;
+
+        camera.last_health = Some(report);
+        Ok(proof)
+    }
+
+    /// Registra hash de segmento de gravação, enviado pela urna à qual a
+    /// câmera foi registrada, verificando a continuidade
+    pub async fn record_segment(
+        &self,
+        log: &Arc<RwLock<ElectionTransparencyLog>>,
+        urna_id: &str,
+        segment: RecordingSegment,
+    ) -> Result<LoggedSegment> {
+        self.ensure_enabled()?;
+        if segment.sha256.len() != 64 || hex::decode(&segment.sha256).is_err() {
+            return Err(anyhow!("Segment hash must be a hex-encoded SHA-256 digest"));
+        }
+        if segment.ended_at < segment.started_at {
+            return Err(anyhow!("Segment ends before it starts"));
+        }
+
+        let mut cameras = self.cameras.write().await;
+        let camera = camera_of_urna(&mut cameras, &segment.camera_id, urna_id)?;
+
+        let (expected_index, expected_previous) = match camera.segments.last() {
+            Some(last) => {
+                if segment.segment_index <= last.segment.segment_index {
+                    return Err(anyhow!(
+                        "Segment {} already recorded for camera {}",
+                        segment.segment_index, segment.camera_id
+                    ));
+                }
+                (last.segment.segment_index + 1, last.segment.sha256.clone())
+            }
+            None => (0, String::new()),
+        };
+        let gap_detected = segment.segment_index != expected_index
+            || !segment.previous_sha256.eq_ignore_ascii_case(&expected_previous);
+
+        let proof = append(log, &camera.registration.election_id, ElectionEventType::AuditCameraSegment, serde_json::json!({
+            "kind": "audit_camera_segment",
+            "camera_id": segment.camera_id,
+            "urna_id": camera.registration.urna_id,
+            "segment_index": segment.segment_index,
+            "started_at": segment.started_at,
+            "ended_at": segment.ended_at,
+            "sha256": segment.sha256.to_lowercase(),
+            "size_bytes": segment.size_bytes,
+            "previous_sha256": segment.previous_sha256.to_lowercase(),
+            "gap_detected": gap_detected,
+        }), &camera.registration.urna_id).await?;
+
+        if gap_detected {
+            log::warn!(
+                "Descontinuidade na gravação da câmera {}: esperado segmento {}, recebido {}",
+                segment.camera_id, expected_index, segment.segment_index
+            );
+            append(log, &camera.registration.election_id, ElectionEventType::SecurityAlert, serde_json::json!({
+                "kind": "audit_camera_gap",
+                "camera_id": segment.camera_id,
+                "expected_segment_index": expected_index,
+                "received_segment_index": segment.segment_index,
+            }), &camera.registration.urna_id).await?;
+        }
+
+        let logged = LoggedSegment {
+            segment,
+            log_index: proof.log_index,
+            gap_detected,
+        };
+        camera.segments.push(logged.clone());
+        Ok(logged)
+    }
+
+    /// Verifica um arquivo de gravação contra o hash registrado no log
+    pub async fn verify_segment(
+        &self,
+        log: &Arc<RwLock<ElectionTransparencyLog>>,
+        camera_id: &str,
+        segment_index: u64,
+        file_sha256: &str,
+    ) -> Result<SegmentVerification> {
+        let log_index = {
+            let cameras = self.cameras.read().await;
+            cameras
+                .get(camera_id)
+                .and_then(|c| c.segments.iter().find(|s| s.segment.segment_index == segment_index))
+                .map(|s| s.log_index)
+                .ok_or_else(|| anyhow!("Segment {} not recorded for camera {}", segment_index, camera_id))?
+        };
+
+        // O hash é lido da entrada do log, e não do estado em memória
+        let log = log.read().await;
+        let entry = log
+            .get_log_entry(log_index)
+            .ok_or_else(|| anyhow!("Log entry not found: {}", log_index))?;
+        let event: ElectionEvent = serde_json::from_slice(&entry.event_data)?;
+        let logged_sha256 = event.data["sha256"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Log entry {} is not a camera segment", log_index))?
+            .to_string();
+
+        Ok(SegmentVerification {
+            camera_id: camera_id.to_string(),
+            segment_index,
+            log_index,
+            hash_matches: logged_sha256.eq_ignore_ascii_case(file_sha256),
+            logged_sha256,
+            log_entry_status: log.verify_event_integrity(entry)?,
+        })
+    }
+
+    /// Lista segmentos registrados da câmera
+    pub async fn get_segments(&self, camera_id: &str) -> Option<Vec<LoggedSegment>> {
+        self.cameras.read().await.get(camera_id).map(|c| c.segments.clone())
+    }
+
+    /// Último relatório de saúde da câmera
+    pub async fn get_health(&self, camera_id: &str) -> Option<CameraHealthReport> {
+        self.cameras.read().await.get(camera_id).and_then(|c| c.last_health.clone())
+    }
+
+    fn ensure_enabled(&self) -> Result<()> {
+        if !self.enabled {
+            return Err(anyhow!("Audit camera hooks are disabled"));
+        }
+        Ok(())
+    }
+}
+
+/// Câmera registrada para a urna informada
+fn camera_of_urna<'a>(
+    cameras: &'a mut HashMap<String, CameraState>,
+    camera_id: &str,
+    urna_id: &str,
+) -> Result<&'a mut CameraState> {
+    let camera = cameras
+        .get_mut(camera_id)
+        .ok_or_else(|| anyhow!("Camera not registered: {}", camera_id))?;
+    if camera.registration.urna_id != urna_id {
+        return Err(anyhow!("Camera {} is not registered to urna {}", camera_id, urna_id));
+    }
+    Ok(camera)
+}
+
+async fn append(
+    log: &Arc<RwLock<ElectionTransparencyLog>>,
+    election_id: &str,
+    event_type: ElectionEventType,
+    data: serde_json::Value,
+    source: &str,
+) -> Result<InclusionProof> {
+    let event = ElectionEvent {
+        id: uuid::Uuid::new_v4().to_string(),
+        event_type,
+        election_id: election_id.to_string(),
+        data,
+        timestamp: Utc::now(),
+        source: source.to_string(),
+    };
+    log.write().await.append_election_event(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transparency::election_logs::LogConfig;
+    use sha2::{Sha256, Digest};
+
+    fn log() -> Arc<RwLock<ElectionTransparencyLog>> {
+        Arc::new(RwLock::new(ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        })))
+    }
+
+    fn segment(index: u64, content: &[u8], previous: &str) -> RecordingSegment {
+        RecordingSegment {
+            camera_id: "cam-1".to_string(),
+            segment_index: index,
+            started_at: Utc::now(),
+            ended_at: Utc::now(),
+            sha256: hex::encode(Sha256::digest(content)),
+            size_bytes: content.len() as u64,
+            previous_sha256: previous.to_string(),
+        }
+    }
+
+    async fn registered_hooks(log: &Arc<RwLock<ElectionTransparencyLog>>) -> AuditCameraHooks {
+        let hooks = AuditCameraHooks::new(true);
+        hooks.register_camera(log, CameraRegistration {
+            camera_id: "cam-1".to_string(),
+            urna_id: "urna-1".to_string(),
+            election_id: "e1".to_string(),
+            section: "0001".to_string(),
+            framing_attested_by: "presidente-mesa".to_string(),
+        }).await.unwrap();
+        hooks
+    }
+
+    #[tokio::test]
+    async fn test_segment_hashes_verify_footage() {
+        let log = log();
+        let hooks = registered_hooks(&log).await;
+
+        let first = hooks.record_segment(&log, "urna-1", segment(0, b"video-0", "")).await.unwrap();
+        let second = hooks.record_segment(&log, "urna-1", segment(1, b"video-1", &first.segment.sha256)).await.unwrap();
+        assert!(!second.gap_detected);
+
+        let original = hex::encode(Sha256::digest(b"video-1"));
+        let edited = hex::encode(Sha256::digest(b"video-1-edited"));
+        assert!(hooks.verify_segment(&log, "cam-1", 1, &original).await.unwrap().hash_matches);
+        assert!(!hooks.verify_segment(&log, "cam-1", 1, &edited).await.unwrap().hash_matches);
+    }
+
+    #[tokio::test]
+    async fn test_only_the_registered_urna_reports_for_its_camera() {
+        let log = log();
+        let hooks = registered_hooks(&log).await;
+
+        assert!(hooks.record_segment(&log, "urna-2", segment(0, b"video-0", "")).await.is_err());
+        assert!(hooks.report_health(&log, "urna-2", CameraHealthReport {
+            camera_id: "cam-1".to_string(),
+            status: CameraStatus::Recording,
+            storage_free_mb: 1024,
+            message: None,
+            timestamp: Utc::now(),
+        }).await.is_err());
+        assert!(hooks.get_segments("cam-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_segment_is_flagged() {
+        let log = log();
+        let hooks = registered_hooks(&log).await;
+
+        let first = hooks.record_segment(&log, "urna-1", segment(0, b"video-0", "")).await.unwrap();
+        let third = hooks.record_segment(&log, "urna-1", segment(2, b"video-2", &first.segment.sha256)).await.unwrap();
+
+        assert!(third.gap_detected);
+        assert_eq!(log.read().await.get_events_by_type(&ElectionEventType::SecurityAlert).len(), 1);
+        assert!(hooks.record_segment(&log, "urna-1", segment(1, b"video-1", "")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hooks_are_optional() {
+        let log = log();
+        let hooks = AuditCameraHooks::new(false);
+        assert!(hooks.record_segment(&log, "urna-1", segment(0, b"video-0", "")).await.is_err());
+    }
+}
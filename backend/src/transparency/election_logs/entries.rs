@@ -0,0 +1,173 @@
+//! Entradas e eventos do log transparente
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use fortis_domain::region::RegionScope;
+
+use super::merkle::MerkleProof;
+use super::verifiers::VerifierSignature;
+use crate::transparency::visibility::FieldRedaction;
+
+/// Entrada de log eleitoral transparente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionLogEntry {
+    pub index: u64,
+    pub timestamp: DateTime<Utc>,
+    pub event_type: ElectionEventType,
+    pub event_data: Vec<u8>,
+    pub event_hash: String,
+    pub merkle_proof: MerkleProof,
+    pub verifier_signatures: Vec<VerifierSignature>,
+    /// Campos restritos; `event_hash` é calculado sobre a versão redigida
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<FieldRedaction>,
+    /// Correlação com a requisição que originou o evento; fora do hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Tipos de eventos eleitorais
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ElectionEventType {
+    ElectionCreated,
+    ElectionUpdated,
+    ElectionDeleted,
+    ElectionScheduled,
+    ElectionStarted,
+    ElectionEnded,
+    ElectionFinalized,
+    ElectionAudited,
+    VoteCast,
+    VoteVerified,
+    AuditTriggered,
+    SecurityAlert,
+    SystemEvent,
+    AuditCameraHealth,
+    AuditCameraSegment,
+    MixnetShuffle,
+    RandomnessCommitted,
+    RandomnessDrawn,
+    VoteBatchRoot,
+    ResultsPublished,
+    RiskLimitingAuditReport,
+    KeyCeremony,
+    TransmissionClosed,
+    CountingRoundClosed,
+    UrnaDecommissioned,
+    ShadowTallyCompared,
+    UrnaUpdatePublished,
+    UrnaKeyRotated,
+}
+
+/// Dados do evento eleitoral
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectionEvent {
+    pub id: String,
+    pub event_type: ElectionEventType,
+    pub election_id: String,
+    pub data: serde_json::Value,
+    pub timestamp: DateTime<Utc>,
+    pub source: String, // Urna, TSE, Sistema, etc.
+}
+
+impl ElectionEvent {
+    /// Região do evento, em `data.region` (`UF/município/zona/seção` ou recorte)
+    pub fn region(&self) -> Option<RegionScope> {
+        self.data.get("region")?.as_str().and_then(|region| RegionScope::parse(region).ok())
+    }
+}
+
+/// Prova de inclusão no log transparente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub log_index: u64,
+    pub merkle_proof: MerkleProof,
+    pub verifier_signatures: Vec<VerifierSignature>,
+    pub verification_status: VerificationStatus,
+}
+
+/// Status de verificação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VerificationStatus {
+    Pending,
+    Verified,
+    Failed,
+    PartiallyVerified,
+}
+
+/// Critérios de busca para eventos
+#[derive(Debug, Clone)]
+pub struct SearchCriteria {
+    pub event_type: Option<ElectionEventType>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub election_id: Option<String>,
+    pub verification_status: Option<VerificationStatus>,
+    /// Só eventos cuja região está dentro do recorte
+    pub region: Option<RegionScope>,
+}
+
+impl SearchCriteria {
+    /// A entrada atende a todos os critérios informados
+    pub fn matches(&self, entry: &ElectionLogEntry) -> bool {
+        if let Some(event_type) = &self.event_type {
+            if entry.event_type != *event_type {
+                return false;
+            }
+        }
+
+        if let Some(start_time) = self.start_time {
+            if entry.timestamp < start_time {
+                return false;
+            }
+        }
+
+        if let Some(end_time) = self.end_time {
+            if entry.timestamp > end_time {
+                return false;
+            }
+        }
+
+        if let Some(election_id) = &self.election_id {
+            if let Ok(event) = serde_json::from_slice::<ElectionEvent>(&entry.event_data) {
+                if event.election_id != *election_id {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(scope) = &self.region {
+            let region = serde_json::from_slice::<ElectionEvent>(&entry.event_data)
+                .ok()
+                .and_then(|event| event.region());
+            if !region.is_some_and(|region| scope.covers(&region)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Formatos de exportação
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl Default for ElectionLogEntry {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            timestamp: Utc::now(),
+            event_type: ElectionEventType::SystemEvent,
+            event_data: Vec::new(),
+            event_hash: String::new(),
+            merkle_proof: MerkleProof::default(),
+            verifier_signatures: Vec::new(),
+            redactions: Vec::new(),
+            request_id: None,
+        }
+    }
+}
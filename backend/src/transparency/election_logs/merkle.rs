@@ -0,0 +1,401 @@
+//! Árvore Merkle do log transparente (RFC 6962 / RFC 9162)
+//!
+//! Não depende do restante do backend: as provas de inclusão e de
+//! consistência podem ser verificadas por clientes independentes com as
+//! funções estáticas `MerkleTree::verify_inclusion` e
+//! `MerkleTree::verify_consistency`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use anyhow::{Result, anyhow};
+use fortis_domain::log_proof::{verify_inclusion_path, LogInclusionProof};
+
+/// Prova Merkle para inclusão no log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub leaf_index: u64,
+    pub path: Vec<String>,
+    pub root_hash: String,
+    pub tree_size: u64,
+}
+
+impl Default for MerkleProof {
+    fn default() -> Self {
+        Self {
+            leaf_index: 0,
+            path: Vec::new(),
+            root_hash: String::new(),
+            tree_size: 0,
+        }
+    }
+}
+
+impl From<MerkleProof> for LogInclusionProof {
+    fn from(proof: MerkleProof) -> Self {
+        Self {
+            leaf_index: proof.leaf_index,
+            path: proof.path,
+            root_hash: proof.root_hash,
+            tree_size: proof.tree_size,
+        }
+    }
+}
+
+/// Árvore Merkle incremental (append-only) para logs transparentes
+///
+/// Segue o modelo de Certificate Transparency (RFC 6962): cada folha é
+/// adicionada em O(log n) mantendo apenas os nós de subárvores perfeitas
+/// já completas. A "fronteira" (último nó de cada nível com tamanho ímpar)
+/// determina a raiz atual sem reconstruir a árvore inteira.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// `levels[k][i]` é o hash da subárvore perfeita que cobre as folhas
+    /// `[i * 2^k, (i + 1) * 2^k)`; `levels[0]` contém os hashes das folhas
+    levels: Vec<Vec<String>>,
+    root: Option<String>,
+}
+
+/// Prova de consistência entre dois tamanhos da árvore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsistencyProof {
+    pub old_size: u64,
+    pub new_size: u64,
+    pub old_root: String,
+    pub new_root: String,
+    pub path: Vec<String>,
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        Self {
+            levels: vec![Vec::new()],
+            root: None,
+        }
+    }
+
+    pub fn add_leaf(&mut self, data: &str) -> u64 {
+        let leaf_hash = Self::hash_leaf(data);
+        let index = self.levels[0].len() as u64;
+        self.levels[0].push(leaf_hash);
+
+        // Propagar apenas as subárvores perfeitas que acabaram de se completar
+        let mut level = 0;
+        while self.levels[level].len() % 2 == 0 {
+            let len = self.levels[level].len();
+            let parent = Self::hash_children(&self.levels[level][len - 2], &self.levels[level][len - 1]);
+
+            if self.levels.len() == level + 1 {
+                self.levels.push(Vec::new());
+            }
+            self.levels[level + 1].push(parent);
+            level += 1;
+        }
+
+        self.root = self.compute_root();
+        index
+    }
+
+    /// Gera prova de inclusão para o tamanho atual da árvore
+    pub fn generate_proof(&self, leaf_index: u64) -> Result<MerkleProof> {
+        self.generate_proof_at(leaf_index, self.size())
+    }
+
+    /// Gera prova de inclusão relativa a um tamanho anterior da árvore
+    pub fn generate_proof_at(&self, leaf_index: u64, tree_size: u64) -> Result<MerkleProof> {
+        if tree_size > self.size() {
+            return Err(anyhow!("Tree size {} exceeds current size {}", tree_size, self.size()));
+        }
+        if leaf_index >= tree_size {
+            return Err(anyhow!("Leaf index out of bounds"));
+        }
+
+        let mut path = Vec::new();
+        self.inclusion_path(leaf_index as usize, 0, tree_size as usize, &mut path);
+
+        Ok(MerkleProof {
+            leaf_index,
+            path,
+            root_hash: self.root_at(tree_size)?,
+            tree_size,
+        })
+    }
+
+    /// Verifica prova de inclusão contra a folha armazenada e a raiz histórica
+    pub fn verify_proof(&self, proof: &MerkleProof) -> Result<bool> {
+        if proof.leaf_index >= self.size() || proof.tree_size > self.size() {
+            return Ok(false);
+        }
+
+        // A raiz da prova deve ser a raiz que esta árvore teve naquele tamanho
+        if self.root_at(proof.tree_size)? != proof.root_hash {
+            return Ok(false);
+        }
+
+        let leaf_hash = &self.levels[0][proof.leaf_index as usize];
+        Ok(Self::verify_inclusion(leaf_hash, proof))
+    }
+
+    /// Verifica prova de inclusão sem acesso à árvore (RFC 9162, 2.1.3.2)
+    pub fn verify_inclusion(leaf_hash: &str, proof: &MerkleProof) -> bool {
+        verify_inclusion_path(leaf_hash, proof.leaf_index, proof.tree_size, &proof.path, &proof.root_hash)
+    }
+
+    /// Gera prova de consistência entre `old_size` e `new_size` (RFC 6962, 2.1.2)
+    pub fn generate_consistency_proof(&self, old_size: u64, new_size: u64) -> Result<ConsistencyProof> {
+        if old_size == 0 || old_size > new_size {
+            return Err(anyhow!("Invalid consistency range: {} -> {}", old_size, new_size));
+        }
+        if new_size > self.size() {
+            return Err(anyhow!("Tree size {} exceeds current size {}", new_size, self.size()));
+        }
+
+        let mut path = Vec::new();
+        self.consistency_subproof(old_size as usize, 0, new_size as usize, true, &mut path);
+
+        Ok(ConsistencyProof {
+            old_size,
+            new_size,
+            old_root: self.root_at(old_size)?,
+            new_root: self.root_at(new_size)?,
+            path,
+        })
+    }
+
+    /// Verifica prova de consistência sem acesso à árvore (RFC 9162, 2.1.4.2)
+    pub fn verify_consistency(proof: &ConsistencyProof) -> bool {
+        let (m, n) = (proof.old_size, proof.new_size);
+        if m == 0 || m > n {
+            return false;
+        }
+        if m == n {
+            return proof.path.is_empty() && proof.old_root == proof.new_root;
+        }
+
+        // Se o tamanho antigo é potência de 2, a raiz antiga é o primeiro nó do caminho
+        let mut path: Vec<&str> = Vec::with_capacity(proof.path.len() + 1);
+        if m.is_power_of_two() {
+            path.push(&proof.old_root);
+        }
+        path.extend(proof.path.iter().map(|s| s.as_str()));
+
+        let Some((first, rest)) = path.split_first() else {
+            return false;
+        };
+
+        let mut fn_ = m - 1;
+        let mut sn = n - 1;
+        while fn_ & 1 == 1 {
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+
+        let mut old_hash = first.to_string();
+        let mut new_hash = first.to_string();
+
+        for node in rest {
+            if sn == 0 {
+                return false;
+            }
+
+            if fn_ & 1 == 1 || fn_ == sn {
+                old_hash = Self::hash_children(node, &old_hash);
+                new_hash = Self::hash_children(node, &new_hash);
+                if fn_ & 1 == 0 {
+                    while fn_ & 1 == 0 && fn_ != 0 {
+                        fn_ >>= 1;
+                        sn >>= 1;
+                    }
+                }
+            } else {
+                new_hash = Self::hash_children(&new_hash, node);
+            }
+
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+
+        sn == 0 && old_hash == proof.old_root && new_hash == proof.new_root
+    }
+
+    pub fn root(&self) -> Option<String> {
+        self.root.clone()
+    }
+
+    /// Raiz da árvore quando ela possuía `tree_size` folhas
+    pub fn root_at(&self, tree_size: u64) -> Result<String> {
+        if tree_size == 0 || tree_size > self.size() {
+            return Err(anyhow!("Invalid tree size: {}", tree_size));
+        }
+        Ok(self.subtree_hash(0, tree_size as usize))
+    }
+
+    pub fn size(&self) -> u64 {
+        self.levels[0].len() as u64
+    }
+
+    /// Calcula a raiz a partir da fronteira (O(log n))
+    fn compute_root(&self) -> Option<String> {
+        let mut root: Option<String> = None;
+
+        for level in &self.levels {
+            if level.len() % 2 == 1 {
+                let node = level.last()?;
+                root = Some(match root {
+                    Some(right) => Self::hash_children(node, &right),
+                    None => node.clone(),
+                });
+            }
+        }
+
+        root
+    }
+
+    /// Hash de `[start, end)`, reaproveitando subárvores perfeitas já calculadas
+    fn subtree_hash(&self, start: usize, end: usize) -> String {
+        let n = end - start;
+        if n.is_power_of_two() && start % n == 0 {
+            let level = n.trailing_zeros() as usize;
+            return self.levels[level][start / n].clone();
+        }
+
+        let k = Self::largest_power_of_two_below(n);
+        Self::hash_children(
+            &self.subtree_hash(start, start + k),
+            &self.subtree_hash(start + k, end),
+        )
+    }
+
+    /// PATH(m, D[start:end]) da RFC 6962
+    fn inclusion_path(&self, m: usize, start: usize, end: usize, path: &mut Vec<String>) {
+        let n = end - start;
+        if n <= 1 {
+            return;
+        }
+
+        let k = Self::largest_power_of_two_below(n);
+        if m < k {
+            self.inclusion_path(m, start, start + k, path);
+            path.push(self.subtree_hash(start + k, end));
+        } else {
+            self.inclusion_path(m - k, start + k, end, path);
+            path.push(self.subtree_hash(start, start + k));
+        }
+    }
+
+    /// SUBPROOF(m, D[start:end], b) da RFC 6962
+    fn consistency_subproof(&self, m: usize, start: usize, end: usize, complete: bool, path: &mut Vec<String>) {
+        let n = end - start;
+        if m == n {
+            if !complete {
+                path.push(self.subtree_hash(start, end));
+            }
+            return;
+        }
+
+        let k = Self::largest_power_of_two_below(n);
+        if m <= k {
+            self.consistency_subproof(m, start, start + k, complete, path);
+            path.push(self.subtree_hash(start + k, end));
+        } else {
+            self.consistency_subproof(m - k, start + k, end, false, path);
+            path.push(self.subtree_hash(start, start + k));
+        }
+    }
+
+    /// Maior potência de 2 estritamente menor que `n` (n >= 2)
+    fn largest_power_of_two_below(n: usize) -> usize {
+        1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+    }
+
+    fn hash_leaf(data: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update([0x00]);
+        hasher.update(data.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn hash_children(left: &str, right: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update([0x01]);
+        hasher.update(left.as_bytes());
+        hasher.update(right.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_tree_operations() {
+        let mut tree = MerkleTree::new();
+        let index1 = tree.add_leaf("data1");
+        let index2 = tree.add_leaf("data2");
+        
+        assert_eq!(index1, 0);
+        assert_eq!(index2, 1);
+        assert!(tree.root().is_some());
+    }
+
+    #[test]
+    fn test_merkle_proof_generation() {
+        let mut tree = MerkleTree::new();
+        tree.add_leaf("data1");
+        tree.add_leaf("data2");
+        tree.add_leaf("data3");
+
+        let proof = tree.generate_proof(0).unwrap();
+        assert_eq!(proof.leaf_index, 0);
+        assert!(!proof.path.is_empty());
+    }
+
+    #[test]
+    fn test_incremental_root_matches_full_computation() {
+        let mut tree = MerkleTree::new();
+        for i in 0..33 {
+            tree.add_leaf(&format!("data{}", i));
+            let size = tree.size() as usize;
+            assert_eq!(tree.root().unwrap(), tree.subtree_hash(0, size));
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proofs_for_all_sizes() {
+        let mut tree = MerkleTree::new();
+        for i in 0..20 {
+            tree.add_leaf(&format!("data{}", i));
+        }
+
+        for size in 1..=tree.size() {
+            for index in 0..size {
+                let proof = tree.generate_proof_at(index, size).unwrap();
+                assert!(tree.verify_proof(&proof).unwrap(), "leaf {} size {}", index, size);
+            }
+        }
+
+        let mut tampered = tree.generate_proof(3).unwrap();
+        tampered.leaf_index = 4;
+        assert!(!tree.verify_proof(&tampered).unwrap());
+    }
+
+    #[test]
+    fn test_consistency_proofs() {
+        let mut tree = MerkleTree::new();
+        for i in 0..17 {
+            tree.add_leaf(&format!("data{}", i));
+        }
+
+        for new_size in 1..=tree.size() {
+            for old_size in 1..=new_size {
+                let proof = tree.generate_consistency_proof(old_size, new_size).unwrap();
+                assert!(MerkleTree::verify_consistency(&proof), "{} -> {}", old_size, new_size);
+            }
+        }
+
+        let mut forged = tree.generate_consistency_proof(5, 12).unwrap();
+        forged.old_root = tree.root_at(6).unwrap();
+        assert!(!MerkleTree::verify_consistency(&forged));
+        assert!(tree.generate_consistency_proof(0, 3).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Estatísticas, métricas de desempenho e trilha de auditoria do log
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Dados do evento de auditoria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEventData {
+    pub election_id: Option<String>,
+    pub voter_id: Option<String>,
+    pub node_id: Option<String>,
+    pub candidate_id: Option<String>,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    pub metadata: serde_json::Value,
+    pub previous_hash: Option<String>,
+}
+
+/// Evento de auditoria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub event_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub event_type: AuditEventType,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub data: AuditEventData,
+    pub hash: String,
+    pub signature: String,
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<String>,
+    // Campos adicionais para compatibilidade
+    pub id: String,
+    pub user_id: Option<String>,
+    pub details: serde_json::Value,
+    pub severity: AuditSeverity,
+}
+
+/// Tipos de eventos de auditoria
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AuditEventType {
+    LogEntryCreated,
+    LogEntryVerified,
+    LogEntryFailed,
+    VerifierAdded,
+    VerifierRemoved,
+    ConfigChanged,
+    SecurityAlert,
+    PerformanceAlert,
+    SystemError,
+    VoteCast,
+    VoteVerified,
+    AuditCreated,
+}
+
+/// Severidade do evento de auditoria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AuditSeverity {
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+/// Métricas de performance do log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    pub total_operations: u64,
+    pub average_verification_time_ms: f64,
+    pub average_append_time_ms: f64,
+    pub success_rate: f64,
+    pub error_rate: f64,
+    pub last_updated: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    /// Evento de auditoria gerado pelo próprio log
+    pub fn system(event_type: AuditEventType, details: serde_json::Value, severity: AuditSeverity) -> Self {
+        Self {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            event_type,
+            actor: "system".to_string(),
+            action: "audit_event".to_string(),
+            target: "election_log".to_string(),
+            data: AuditEventData {
+                election_id: None,
+                voter_id: None,
+                node_id: None,
+                candidate_id: None,
+                error_code: None,
+                error_message: None,
+                metadata: details.clone(),
+                previous_hash: None,
+            },
+            hash: String::new(),
+            signature: String::new(),
+            block_number: None,
+            transaction_hash: None,
+            // Campos adicionais para compatibilidade
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: None,
+            details,
+            severity,
+        }
+    }
+}
+
+impl Default for PerformanceMetrics {
+    fn default() -> Self {
+        Self {
+            total_operations: 0,
+            average_verification_time_ms: 0.0,
+            average_append_time_ms: 0.0,
+            success_rate: 100.0,
+            error_rate: 0.0,
+            last_updated: Utc::now(),
+        }
+    }
+}
+
+impl PerformanceMetrics {
+    /// Registra uma operação de escrita no log
+    pub fn record(&mut self, operation_time_ms: f64, success: bool) {
+        self.total_operations += 1;
+
+        // Atualizar tempo médio de operação
+        let total_ops = self.total_operations as f64;
+        self.average_append_time_ms =
+            (self.average_append_time_ms * (total_ops - 1.0) + operation_time_ms) / total_ops;
+
+        // Atualizar taxa de sucesso
+        if success {
+            self.success_rate = (self.success_rate * (total_ops - 1.0) + 100.0) / total_ops;
+        } else {
+            self.success_rate = (self.success_rate * (total_ops - 1.0)) / total_ops;
+        }
+
+        self.error_rate = 100.0 - self.success_rate;
+        self.last_updated = Utc::now();
+    }
+}
+
+/// Estatísticas do log transparente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogStats {
+    pub total_events: usize,
+    pub verified_events: usize,
+    pub total_verifiers: usize,
+    pub active_verifiers: usize,
+    pub tree_size: u64,
+    pub root_hash: String,
+}
+
+/// Relatório de integridade do log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogIntegrityReport {
+    pub total_entries: usize,
+    pub verified_entries: usize,
+    pub failed_entries: usize,
+    pub partially_verified_entries: usize,
+    pub integrity_score: f64,
+    pub issues: Vec<String>,
+}
+
+/// Estatísticas detalhadas do log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailedLogStats {
+    pub basic_stats: LogStats,
+    pub event_type_counts: HashMap<String, usize>,
+    pub verification_status_counts: HashMap<String, usize>,
+    pub average_verification_time: f64,
+    pub total_operations: u64,
+    pub success_rate: f64,
+    pub error_rate: f64,
+}
+
+/// Resultado de validação de configuração
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigValidationResult {
+    pub is_valid: bool,
+    pub severity: String,
+    pub issues: Vec<String>,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,117 @@
+//! Verificadores do log e suas assinaturas
+//!
+//! A assinatura passa pelo trait `VerifierSigner`, de modo que a chave do
+//! verificador pode viver no keystore em memória ou em outro dispositivo.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Result, anyhow};
+
+use crate::transparency::keystore::VerifierKeystore;
+
+/// Verificador de log
+#[derive(Debug, Clone)]
+pub struct LogVerifier {
+    pub id: String,
+    pub name: String,
+    pub public_key: Vec<u8>,
+    pub is_active: bool,
+    pub trust_level: u8, // 0-100
+    /// URL do verificador remoto; `None` para verificadores locais
+    pub endpoint: Option<String>,
+}
+
+/// Assinatura de verificador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifierSignature {
+    pub verifier_id: String,
+    pub signature: String,
+    pub public_key: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Origem das chaves dos verificadores locais
+pub trait VerifierSigner {
+    /// Há chave deste verificador neste nó
+    fn holds_key(&self, verifier_id: &str) -> bool;
+
+    /// Chave pública Ed25519 do verificador
+    fn public_key(&self, verifier_id: &str) -> Option<Vec<u8>>;
+
+    /// Assina a mensagem com a chave do verificador
+    fn sign(&self, verifier_id: &str, message: &[u8]) -> Result<Vec<u8>>;
+}
+
+impl VerifierSigner for VerifierKeystore {
+    fn holds_key(&self, verifier_id: &str) -> bool {
+        self.contains(verifier_id)
+    }
+
+    fn public_key(&self, verifier_id: &str) -> Option<Vec<u8>> {
+        VerifierKeystore::public_key(self, verifier_id)
+    }
+
+    fn sign(&self, verifier_id: &str, message: &[u8]) -> Result<Vec<u8>> {
+        VerifierKeystore::sign(self, verifier_id, message)
+    }
+}
+
+/// Assina o hash de uma entrada em nome do verificador
+pub fn sign_entry(
+    signer: &dyn VerifierSigner,
+    verifier: &LogVerifier,
+    event_hash: &str,
+) -> Result<VerifierSignature> {
+    if signer.public_key(&verifier.id).as_deref() != Some(verifier.public_key.as_slice()) {
+        return Err(anyhow!("Signer key does not match registered public key for {}", verifier.id));
+    }
+
+    let signature = signer.sign(&verifier.id, event_hash.as_bytes())?;
+
+    Ok(VerifierSignature {
+        verifier_id: verifier.id.clone(),
+        signature: hex::encode(signature),
+        public_key: hex::encode(&verifier.public_key),
+        timestamp: Utc::now(),
+    })
+}
+
+/// Verifica assinatura Ed25519 com a chave pública registrada do verificador
+pub fn verify_signature(verifier: &LogVerifier, message: &str, signature: &str) -> bool {
+    VerifierKeystore::verify(&verifier.public_key, message.as_bytes(), signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_verifier(keystore: &mut VerifierKeystore, id: &str) -> LogVerifier {
+        LogVerifier {
+            id: id.to_string(),
+            name: id.to_string(),
+            public_key: keystore.generate(id).unwrap(),
+            is_active: true,
+            trust_level: 100,
+            endpoint: None,
+        }
+    }
+
+    #[test]
+    fn test_sign_entry_round_trip() {
+        let mut keystore = VerifierKeystore::new();
+        let verifier = local_verifier(&mut keystore, "tse");
+
+        let signature = sign_entry(&keystore, &verifier, "abc123").unwrap();
+        assert!(verify_signature(&verifier, "abc123", &signature.signature));
+        assert!(!verify_signature(&verifier, "abc124", &signature.signature));
+    }
+
+    #[test]
+    fn test_sign_entry_rejects_mismatched_key() {
+        let mut keystore = VerifierKeystore::new();
+        let mut verifier = local_verifier(&mut keystore, "tse");
+        verifier.public_key = vec![0u8; 32];
+
+        assert!(sign_entry(&keystore, &verifier, "abc123").is_err());
+    }
+}
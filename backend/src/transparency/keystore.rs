@@ -0,0 +1,77 @@
+//! Keystore dos Verificadores do Log
+//!
+//! Guarda os pares de chaves Ed25519 dos verificadores hospedados neste nó.
+//! A verificação de assinaturas usa apenas as chaves públicas registradas
+//! em `LogVerifier`, de modo que verificadores remotos não precisam de
+//! entrada no keystore.
+
+use anyhow::{Result, anyhow};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Keystore de verificadores locais
+#[derive(Clone, Default)]
+pub struct VerifierKeystore {
+    keys: HashMap<String, Arc<Ed25519KeyPair>>,
+}
+
+impl VerifierKeystore {
+    pub fn new() -> Self {
+        Self {
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Gera novo par de chaves para o verificador e retorna a chave pública
+    pub fn generate(&mut self, verifier_id: &str) -> Result<Vec<u8>> {
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng)
+            .map_err(|_| anyhow!("Failed to generate key for verifier {}", verifier_id))?;
+        self.import_pkcs8(verifier_id, pkcs8.as_ref())
+    }
+
+    /// Importa chave privada PKCS#8 e retorna a chave pública
+    pub fn import_pkcs8(&mut self, verifier_id: &str, pkcs8: &[u8]) -> Result<Vec<u8>> {
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8)
+            .map_err(|_| anyhow!("Invalid PKCS#8 key for verifier {}", verifier_id))?;
+        let public_key = key_pair.public_key().as_ref().to_vec();
+        self.keys.insert(verifier_id.to_string(), Arc::new(key_pair));
+        Ok(public_key)
+    }
+
+    /// Chave pública de um verificador local
+    pub fn public_key(&self, verifier_id: &str) -> Option<Vec<u8>> {
+        self.keys
+            .get(verifier_id)
+            .map(|k| k.public_key().as_ref().to_vec())
+    }
+
+    pub fn contains(&self, verifier_id: &str) -> bool {
+        self.keys.contains_key(verifier_id)
+    }
+
+    /// Assina mensagem com a chave do verificador
+    pub fn sign(&self, verifier_id: &str, message: &[u8]) -> Result<Vec<u8>> {
+        let key_pair = self.keys
+            .get(verifier_id)
+            .ok_or_else(|| anyhow!("No signing key for verifier {}", verifier_id))?;
+        Ok(key_pair.sign(message).as_ref().to_vec())
+    }
+
+    /// Verifica assinatura hex contra chave pública Ed25519
+    pub fn verify(public_key: &[u8], message: &[u8], signature: &str) -> bool {
+        let Ok(signature_bytes) = hex::decode(signature) else {
+            return false;
+        };
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(message, &signature_bytes)
+            .is_ok()
+    }
+
+    /// Remove chave do verificador
+    pub fn remove(&mut self, verifier_id: &str) -> bool {
+        self.keys.remove(verifier_id).is_some()
+    }
+}
@@ -5,4 +5,10 @@
 //! blockchain não é necessário para transparência eleitoral.
 
 pub mod election_logs;
+pub mod tree_heads;
+pub mod witness;
+pub mod visibility;
+pub mod keystore;
+pub mod signature_collector;
+pub mod audit_camera;
 pub mod api;
@@ -0,0 +1,364 @@
+//! Coleta Assíncrona de Assinaturas de Verificadores
+//!
+//! Solicita assinaturas a verificadores remotos em paralelo, com tentativas
+//! e backoff exponencial por verificador e um prazo global
+//! (`verification_timeout_seconds`). Quando o prazo expira, as assinaturas
+//! já obtidas são mantidas e o quórum parcial é reportado.
+
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use anyhow::{Result, anyhow};
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use super::election_logs::{
+    ElectionTransparencyLog, ElectionEvent, InclusionProof, LogVerifier, VerifierSignature
+};
+use super::keystore::VerifierKeystore;
+
+const TIMEOUT_ERROR: &str = "Verification timeout";
+
+/// Configuração da coleta de assinaturas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectorConfig {
+    pub timeout_seconds: u64,
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+/// Falha de um verificador durante a coleta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifierFailure {
+    pub verifier_id: String,
+    pub attempts: u32,
+    pub error: String,
+}
+
+/// Resultado da coleta de assinaturas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuorumOutcome {
+    pub signatures: Vec<VerifierSignature>,
+    pub failures: Vec<VerifierFailure>,
+    pub threshold: usize,
+    pub quorum_reached: bool,
+    pub timed_out: bool,
+}
+
+/// Requisição enviada ao verificador remoto
+#[derive(Debug, Serialize)]
+struct SignRequest<'a> {
+    log_index: u64,
+    event_hash: &'a str,
+}
+
+/// Resposta do verificador remoto
+#[derive(Debug, Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+/// Coletor de assinaturas de verificadores remotos
+#[derive(Clone)]
+pub struct SignatureCollector {
+    config: CollectorConfig,
+    http_client: reqwest::Client,
+}
+
+impl SignatureCollector {
+    pub fn new(config: CollectorConfig) -> Self {
+        Self {
+            config,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Registra evento no log e coleta assinaturas remotas sem manter o log
+    /// bloqueado durante as chamadas de rede
+    pub async fn append_event(
+        &self,
+        log_state: &Arc<RwLock<ElectionTransparencyLog>>,
+        event: ElectionEvent,
+    ) -> Result<(InclusionProof, QuorumOutcome)> {
+        let (proof, remote_verifiers, event_hash, threshold) = {
+            let mut log = log_state.write().await;
+            let proof = log.append_election_event(event)?;
+            let event_hash = log.get_log_entry(proof.log_index)
+                .map(|entry| entry.event_hash.clone())
+                .ok_or_else(|| anyhow!("Log entry not found: {}", proof.log_index))?;
+            (proof, log.remote_verifiers(), event_hash, log.config.signature_threshold)
+        };
+
+        let local_signatures = proof.verifier_signatures.len();
+        if remote_verifiers.is_empty() {
+            let outcome = QuorumOutcome {
+                signatures: Vec::new(),
+                failures: Vec::new(),
+                threshold,
+                quorum_reached: local_signatures >= threshold,
+                timed_out: false,
+            };
+            return Ok((proof, outcome));
+        }
+
+        let mut outcome = self
+            .collect(proof.log_index, &event_hash, remote_verifiers, threshold.saturating_sub(local_signatures))
+            .await;
+        outcome.threshold = threshold;
+
+        if !outcome.quorum_reached {
+            log::warn!(
+                "Quórum de verificadores não atingido para entrada {}: {} de {} assinaturas",
+                proof.log_index,
+                local_signatures + outcome.signatures.len(),
+                threshold
+            );
+        }
+
+        let proof = log_state.write().await
+            .attach_verifier_signatures(proof.log_index, outcome.signatures.clone())?;
+        Ok((proof, outcome))
+    }
+
+    /// Solicita assinaturas via HTTP (`POST {endpoint}/sign`)
+    pub async fn collect(
+        &self,
+        log_index: u64,
+        event_hash: &str,
+        verifiers: Vec<LogVerifier>,
+        required: usize,
+    ) -> QuorumOutcome {
+        self.collect_with(event_hash, verifiers, required, |verifier| {
+            self.request_signature(verifier, log_index, event_hash)
+        })
+        .await
+    }
+
+    /// Coleta assinaturas usando a função de assinatura informada
+    pub async fn collect_with<F, Fut>(
+        &self,
+        event_hash: &str,
+        verifiers: Vec<LogVerifier>,
+        required: usize,
+        sign_fn: F,
+    ) -> QuorumOutcome
+    where
+        F: Fn(LogVerifier) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let deadline = Instant::now() + Duration::from_secs(self.config.timeout_seconds);
+        let mut pending: HashSet<String> = verifiers.iter().map(|v| v.id.clone()).collect();
+        let sign_fn = &sign_fn;
+
+        let mut requests: FuturesUnordered<_> = verifiers
+            .into_iter()
+            .map(|verifier| async move {
+                let result = self.sign_with_retry(&verifier, sign_fn, deadline).await;
+                (verifier, result)
+            })
+            .collect();
+
+        let mut signatures = Vec::new();
+        let mut failures = Vec::new();
+        let mut timed_out = false;
+
+        loop {
+            match tokio::time::timeout_at(deadline, requests.next()).await {
+                Ok(Some((verifier, result))) => {
+                    pending.remove(&verifier.id);
+                    match result {
+                        Ok((signature, attempts)) => {
+                            if VerifierKeystore::verify(&verifier.public_key, event_hash.as_bytes(), &signature) {
+                                signatures.push(VerifierSignature {
+                                    verifier_id: verifier.id.clone(),
+                                    signature,
+                                    public_key: hex::encode(&verifier.public_key),
+                                    timestamp: Utc::now(),
+                                });
+                            } else {
+                                failures.push(VerifierFailure {
+                                    verifier_id: verifier.id.clone(),
+                                    attempts,
+                                    error: "Invalid signature".to_string(),
+                                });
+                            }
+                        }
+                        Err((error, attempts)) => {
+                            timed_out |= error == TIMEOUT_ERROR;
+                            failures.push(VerifierFailure {
+                                verifier_id: verifier.id.clone(),
+                                attempts,
+                                error,
+                            });
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    timed_out = true;
+                    break;
+                }
+            }
+        }
+
+        let mut pending: Vec<String> = pending.into_iter().collect();
+        timed_out |= !pending.is_empty();
+        pending.sort();
+        for verifier_id in pending {
+            failures.push(VerifierFailure {
+                verifier_id,
+                attempts: 0,
+                error: TIMEOUT_ERROR.to_string(),
+            });
+        }
+
+        QuorumOutcome {
+            quorum_reached: signatures.len() >= required,
+            threshold: required,
+            signatures,
+            failures,
+            timed_out,
+        }
+    }
+
+    /// Tenta obter a assinatura com backoff exponencial até o prazo global
+    async fn sign_with_retry<F, Fut>(
+        &self,
+        verifier: &LogVerifier,
+        sign_fn: &F,
+        deadline: Instant,
+    ) -> std::result::Result<(String, u32), (String, u32)>
+    where
+        F: Fn(LogVerifier) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let mut backoff = Duration::from_millis(self.config.initial_backoff_ms);
+        let mut last_error = String::new();
+
+        for attempt in 1..=self.config.max_retries + 1 {
+            match tokio::time::timeout_at(deadline, sign_fn(verifier.clone())).await {
+                Ok(Ok(signature)) => return Ok((signature, attempt)),
+                Ok(Err(e)) => last_error = e.to_string(),
+                Err(_) => return Err((TIMEOUT_ERROR.to_string(), attempt)),
+            }
+
+            if attempt <= self.config.max_retries {
+                if Instant::now() + backoff >= deadline {
+                    break;
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(Duration::from_millis(self.config.max_backoff_ms));
+            }
+        }
+
+        Err((last_error, self.config.max_retries + 1))
+    }
+
+    async fn request_signature(&self, verifier: LogVerifier, log_index: u64, event_hash: &str) -> Result<String> {
+        let endpoint = verifier.endpoint
+            .ok_or_else(|| anyhow!("Verifier {} has no endpoint", verifier.id))?;
+
+        let response = self.http_client
+            .post(format!("{}/sign", endpoint.trim_end_matches('/')))
+            .json(&SignRequest { log_index, event_hash })
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Verifier {} returned {}", verifier.id, response.status()));
+        }
+
+        Ok(response.json::<SignResponse>().await?.signature)
+    }
+}
+
+impl Default for CollectorConfig {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 30,
+            max_retries: 3,
+            initial_backoff_ms: 200,
+            max_backoff_ms: 5_000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn remote_verifier(keystore: &mut VerifierKeystore, id: &str) -> LogVerifier {
+        LogVerifier {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            public_key: keystore.generate(id).unwrap(),
+            is_active: true,
+            trust_level: 80,
+            endpoint: Some(format!("http://{}.example", id)),
+        }
+    }
+
+    fn collector(timeout_seconds: u64) -> SignatureCollector {
+        SignatureCollector::new(CollectorConfig {
+            timeout_seconds,
+            max_retries: 2,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 5,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let mut keystore = VerifierKeystore::new();
+        let verifiers = vec![remote_verifier(&mut keystore, "a"), remote_verifier(&mut keystore, "b")];
+        let calls = AtomicU32::new(0);
+
+        let outcome = collector(5).collect_with("hash", verifiers, 2, |v| {
+            let attempt = calls.fetch_add(1, Ordering::SeqCst);
+            let result = if v.id == "b" && attempt < 2 {
+                Err(anyhow!("connection refused"))
+            } else {
+                keystore.sign(&v.id, b"hash").map(hex::encode)
+            };
+            async move { result }
+        }).await;
+
+        assert!(outcome.quorum_reached);
+        assert_eq!(outcome.signatures.len(), 2);
+        assert!(outcome.failures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_partial_quorum_on_timeout() {
+        let mut keystore = VerifierKeystore::new();
+        let verifiers = vec![
+            remote_verifier(&mut keystore, "fast"),
+            remote_verifier(&mut keystore, "slow"),
+            remote_verifier(&mut keystore, "forger"),
+        ];
+
+        let outcome = collector(1).collect_with("hash", verifiers, 2, |v| {
+            let signature = keystore.sign(if v.id == "forger" { "fast" } else { &v.id }, b"hash").map(hex::encode);
+            async move {
+                if v.id == "slow" {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                }
+                signature
+            }
+        }).await;
+
+        assert!(outcome.timed_out);
+        assert!(!outcome.quorum_reached);
+        assert_eq!(outcome.signatures.len(), 1);
+
+        let mut failed: Vec<&str> = outcome.failures.iter().map(|f| f.verifier_id.as_str()).collect();
+        failed.sort();
+        assert_eq!(failed, vec!["forger", "slow"]);
+    }
+}
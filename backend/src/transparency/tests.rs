@@ -580,10 +580,32 @@ mod tests {
             end_time: None,
             election_id: Some("election1".to_string()),
             verification_status: None,
+            region: None,
         };
         
         let results = log.search_events(criteria).unwrap();
         assert_eq!(results.len(), 1);
+
+        // Evento com a seção da urna
+        log.append_election_event(ElectionEvent {
+            id: "event2".to_string(),
+            event_type: ElectionEventType::VoteCast,
+            election_id: "election1".to_string(),
+            data: json!({"tracking_code": "ABC", "region": "SP/71072/0001/0123"}),
+            timestamp: Utc::now(),
+            source: "urna2".to_string(),
+        }).unwrap();
+
+        let regional = |region: &str| SearchCriteria {
+            event_type: None,
+            start_time: None,
+            end_time: None,
+            election_id: None,
+            verification_status: None,
+            region: Some(fortis_domain::RegionScope::parse(region).unwrap()),
+        };
+        assert_eq!(log.search_events(regional("SP/71072")).unwrap().len(), 1);
+        assert!(log.search_events(regional("RJ")).unwrap().is_empty());
     }
 
     /// Testa exportação de log
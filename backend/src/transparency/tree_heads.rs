@@ -0,0 +1,271 @@
+//! Signed Tree Heads (STH)
+//!
+//! Cabeças de árvore assinadas periodicamente pelo log transparente, no
+//! modelo de Certificate Transparency. Monitores externos podem reenviar as
+//! STHs que observaram para detectar "split view" — o log apresentando
+//! raízes diferentes para o mesmo tamanho a observadores distintos.
+//!
+//! Cada STH publicada pode levar a assinatura agregada das testemunhas
+//! (backends regionais e testemunhas externas, ver `witness`), para que o
+//! cliente exija o endosso de várias partes em vez de confiar só no operador.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use fortis_domain::log_proof::{self, TreeHead};
+use ring::signature::{Ed25519KeyPair, UnparsedPublicKey, ED25519};
+use std::collections::HashMap;
+
+use super::witness::WitnessKind;
+use crate::crypto::hsm::SigningKey;
+
+/// Cabeça de árvore assinada pelo log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignedTreeHead {
+    pub tree_size: u64,
+    pub root_hash: String,
+    pub timestamp: DateTime<Utc>,
+    /// SHA-256 da chave pública do log, em hex
+    pub log_id: String,
+    pub signature: String,
+    /// Cossinaturas das testemunhas, anexadas após a publicação
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness: Option<WitnessSignature>,
+}
+
+/// Cossinatura de uma testemunha sobre a STH
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WitnessCosignature {
+    pub witness_id: String,
+    pub kind: WitnessKind,
+    /// Chave pública Ed25519 da testemunha, em hex
+    pub public_key: String,
+    pub signature: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Assinatura agregada das testemunhas
+///
+/// As assinaturas Ed25519 seguem individuais; `signers_mask` marca, na
+/// ordem do rol de testemunhas, quem cossinou.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WitnessSignature {
+    pub roster_size: usize,
+    pub threshold: usize,
+    pub signers_mask: String,
+    pub cosignatures: Vec<WitnessCosignature>,
+    pub quorum_reached: bool,
+}
+
+/// STH observada por um monitor externo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservedTreeHead {
+    pub monitor_id: String,
+    pub sth: SignedTreeHead,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Evidência de split view: duas STHs válidas e incompatíveis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitViewEvidence {
+    pub monitor_id: String,
+    pub observed: SignedTreeHead,
+    pub expected_root: Option<String>,
+    pub reason: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Resultado da submissão de uma STH observada
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ObservationResult {
+    Consistent,
+    SplitView,
+}
+
+/// Histórico de STHs publicadas e observadas
+#[derive(Debug, Clone)]
+pub struct TreeHeadStore {
+    published: Vec<SignedTreeHead>,
+    observations: Vec<ObservedTreeHead>,
+    split_views: Vec<SplitViewEvidence>,
+    max_history: usize,
+}
+
+impl SignedTreeHead {
+    /// Cria e assina uma nova STH
+    pub fn sign(key_pair: &Ed25519KeyPair, tree_size: u64, root_hash: String, log_id: String) -> Self {
+        Self::sign_with(key_pair, tree_size, root_hash, log_id)
+            .expect("Ed25519 signing in memory does not fail")
+    }
+
+    /// Cria e assina uma nova STH com uma chave possivelmente residente em HSM
+    pub fn sign_with(key: &dyn SigningKey, tree_size: u64, root_hash: String, log_id: String) -> anyhow::Result<Self> {
+        let timestamp = Utc::now();
+        let message = Self::signing_input(tree_size, &root_hash, &timestamp);
+        let signature = hex::encode(key.try_sign(&message)?);
+
+        Ok(Self {
+            tree_size,
+            root_hash,
+            timestamp,
+            log_id,
+            signature,
+            witness: None,
+        })
+    }
+
+    /// Verifica a assinatura com a chave pública do log
+    pub fn verify(&self, public_key: &[u8]) -> bool {
+        if Self::log_id_for(public_key) != self.log_id {
+            return false;
+        }
+        let Ok(signature) = hex::decode(&self.signature) else {
+            return false;
+        };
+
+        let message = Self::signing_input(self.tree_size, &self.root_hash, &self.timestamp);
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&message, &signature)
+            .is_ok()
+    }
+
+    /// Identificador do log derivado da chave pública
+    pub fn log_id_for(public_key: &[u8]) -> String {
+        log_proof::log_id_for(public_key)
+    }
+
+    /// Mensagem canônica cossinada pelas testemunhas; inclui o log para que
+    /// a cossinatura não sirva para a STH de outro operador
+    pub fn cosigning_input(&self) -> Vec<u8> {
+        format!(
+            "fortis-sth-cosign-v1|{}|{}|{}|{}",
+            self.log_id,
+            self.tree_size,
+            self.root_hash,
+            self.timestamp.timestamp_millis()
+        ).into_bytes()
+    }
+
+    /// Conta cossinaturas válidas de testemunhas confiáveis distintas
+    /// (`witness_id` → chave pública)
+    pub fn valid_cosignatures(&self, trusted: &HashMap<String, Vec<u8>>) -> usize {
+        let Some(witness) = &self.witness else {
+            return 0;
+        };
+        let message = self.cosigning_input();
+
+        let mut signers: Vec<&str> = witness.cosignatures
+            .iter()
+            .filter(|c| {
+                let Some(public_key) = trusted.get(&c.witness_id) else {
+                    return false;
+                };
+                let Ok(signature) = hex::decode(&c.signature) else {
+                    return false;
+                };
+                UnparsedPublicKey::new(&ED25519, public_key)
+                    .verify(&message, &signature)
+                    .is_ok()
+            })
+            .map(|c| c.witness_id.as_str())
+            .collect();
+        signers.sort();
+        signers.dedup();
+        signers.len()
+    }
+
+    /// Mensagem canônica assinada pelo log
+    fn signing_input(tree_size: u64, root_hash: &str, timestamp: &DateTime<Utc>) -> Vec<u8> {
+        log_proof::tree_head_signing_input(tree_size, root_hash, timestamp)
+    }
+}
+
+impl From<&SignedTreeHead> for TreeHead {
+    /// STH no formato verificado pela urna, sem as cossinaturas
+    fn from(sth: &SignedTreeHead) -> Self {
+        Self {
+            tree_size: sth.tree_size,
+            root_hash: sth.root_hash.clone(),
+            timestamp: sth.timestamp,
+            log_id: sth.log_id.clone(),
+            signature: sth.signature.clone(),
+        }
+    }
+}
+
+impl TreeHeadStore {
+    pub fn new(max_history: usize) -> Self {
+        Self {
+            published: Vec::new(),
+            observations: Vec::new(),
+            split_views: Vec::new(),
+            max_history,
+        }
+    }
+
+    /// Registra STH publicada, descartando as mais antigas além do limite
+    pub fn record_published(&mut self, sth: SignedTreeHead) {
+        self.published.push(sth);
+        if self.published.len() > self.max_history {
+            let excess = self.published.len() - self.max_history;
+            self.published.drain(..excess);
+        }
+    }
+
+    pub fn record_observation(&mut self, observation: ObservedTreeHead) {
+        self.observations.push(observation);
+        if self.observations.len() > self.max_history {
+            let excess = self.observations.len() - self.max_history;
+            self.observations.drain(..excess);
+        }
+    }
+
+    pub fn record_split_view(&mut self, evidence: SplitViewEvidence) {
+        self.split_views.push(evidence);
+    }
+
+    /// Obtém a STH mais recente
+    pub fn latest(&self) -> Option<&SignedTreeHead> {
+        self.published.last()
+    }
+
+    /// STH mais recente com pelo menos `min_cosignatures` cossinaturas
+    pub fn latest_witnessed(&self, min_cosignatures: usize) -> Option<&SignedTreeHead> {
+        self.published.iter().rev().find(|sth| {
+            sth.witness.as_ref().map_or(0, |w| w.cosignatures.len()) >= min_cosignatures
+        })
+    }
+
+    /// Anexa a assinatura agregada das testemunhas à STH publicada
+    pub fn attach_witness(
+        &mut self,
+        tree_size: u64,
+        root_hash: &str,
+        timestamp: DateTime<Utc>,
+        witness: WitnessSignature,
+    ) -> Option<SignedTreeHead> {
+        let sth = self.published.iter_mut().rev().find(|sth| {
+            sth.tree_size == tree_size && sth.root_hash == root_hash && sth.timestamp == timestamp
+        })?;
+        sth.witness = Some(witness);
+        Some(sth.clone())
+    }
+
+    /// Lista STHs publicadas a partir de um tamanho mínimo de árvore
+    pub fn history(&self, min_tree_size: u64, limit: usize) -> Vec<SignedTreeHead> {
+        self.published
+            .iter()
+            .rev()
+            .filter(|sth| sth.tree_size >= min_tree_size)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn observations(&self) -> &Vec<ObservedTreeHead> {
+        &self.observations
+    }
+
+    pub fn split_views(&self) -> &Vec<SplitViewEvidence> {
+        &self.split_views
+    }
+}
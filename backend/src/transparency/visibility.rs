@@ -0,0 +1,322 @@
+//! Visibilidade por campo dos eventos do log transparente
+//!
+//! Alguns eventos carregam metadados operacionais (identificação de
+//! mesários, topologia dos nós, estado interno de equipamentos) que não
+//! devem ir para a versão pública do log. A política classifica campos dos
+//! eventos como públicos ou restritos, por caminho JSON Pointer
+//! (`/data/approved_by`).
+//!
+//! Ao registrar o evento, cada campo restrito presente recebe um sal
+//! aleatório e é trocado, na versão pública, por um compromisso
+//! `SHA-256(domínio ‖ caminho ‖ sal ‖ valor)`. O hash da entrada (folha da
+//! árvore Merkle) é calculado sobre essa versão pública, de modo que
+//! qualquer pessoa verifica a inclusão do que recebeu. O auditor autorizado
+//! recebe a versão completa com os sais, recalcula cada compromisso a partir
+//! do valor e confirma que a redação corresponde à entrada registrada.
+
+use anyhow::{Result, anyhow};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::election_logs::{ElectionEvent, ElectionEventType, ElectionLogEntry, MerkleProof, VerifierSignature};
+
+/// Chave do objeto que substitui um campo restrito na versão pública
+pub const REDACTED_MARKER: &str = "$redacted";
+
+const COMMITMENT_DOMAIN: &[u8] = b"fortis-redaction-v1";
+
+/// Classificação de um campo
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldVisibility {
+    Public,
+    Restricted,
+}
+
+/// Regra de visibilidade; sem `event_type`, vale para todos os tipos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldRule {
+    pub event_type: Option<ElectionEventType>,
+    pub path: String,
+    pub visibility: FieldVisibility,
+    pub reason: String,
+}
+
+/// Política de visibilidade dos campos dos eventos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisibilityPolicy {
+    pub rules: Vec<FieldRule>,
+}
+
+/// Campo redigido de uma entrada
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldRedaction {
+    pub path: String,
+    pub commitment: String,
+    /// Sal do compromisso; presente apenas na versão completa
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+}
+
+impl FieldRedaction {
+    /// Compromisso sem o sal, para a versão pública
+    pub fn public(&self) -> Self {
+        Self { salt: None, ..self.clone() }
+    }
+}
+
+/// Nível de acesso de uma visão do log
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewLevel {
+    Public,
+    Full,
+}
+
+/// Entrada do log na visão pública ou completa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryView {
+    pub index: u64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub event_type: ElectionEventType,
+    pub event_hash: String,
+    pub view: ViewLevel,
+    pub event: serde_json::Value,
+    pub redactions: Vec<FieldRedaction>,
+    pub merkle_proof: MerkleProof,
+    pub verifier_signatures: Vec<VerifierSignature>,
+}
+
+impl Default for VisibilityPolicy {
+    fn default() -> Self {
+        let restricted = |event_type: Option<ElectionEventType>, path: &str, reason: &str| FieldRule {
+            event_type,
+            path: path.to_string(),
+            visibility: FieldVisibility::Restricted,
+            reason: reason.to_string(),
+        };
+
+        Self {
+            rules: vec![
+                restricted(None, "/data/approved_by", "Identificação dos mesários que aprovaram a operação"),
+                restricted(None, "/data/framing_attested_by", "Identificação de quem atestou o enquadramento da câmera"),
+                restricted(None, "/data/participating_nodes", "Topologia dos nós de consenso"),
+                restricted(None, "/data/consensus_time_ms", "Desempenho interno do consenso"),
+                restricted(Some(ElectionEventType::AuditCameraHealth), "/data/storage_free_mb", "Estado interno do equipamento"),
+                restricted(Some(ElectionEventType::AuditCameraHealth), "/data/message", "Mensagem de diagnóstico do equipamento"),
+            ],
+        }
+    }
+}
+
+impl VisibilityPolicy {
+    /// Classificação do campo; a regra do tipo de evento prevalece sobre a geral
+    pub fn classify(&self, event_type: &ElectionEventType, path: &str) -> FieldVisibility {
+        let matching = |specific: bool| self.rules.iter().find(|rule| {
+            rule.path == path && match &rule.event_type {
+                Some(rule_type) => specific && rule_type == event_type,
+                None => !specific,
+            }
+        });
+
+        matching(true)
+            .or_else(|| matching(false))
+            .map(|rule| rule.visibility)
+            .unwrap_or(FieldVisibility::Public)
+    }
+
+    /// Caminhos restritos para o tipo de evento
+    pub fn restricted_paths(&self, event_type: &ElectionEventType) -> Vec<&str> {
+        let mut paths: Vec<&str> = self.rules.iter()
+            .map(|rule| rule.path.as_str())
+            .filter(|path| self.classify(event_type, path) == FieldVisibility::Restricted)
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        paths
+    }
+
+    /// Sorteia os sais e calcula os compromissos dos campos restritos presentes no evento
+    pub fn redact(&self, event: &ElectionEvent) -> Result<Vec<FieldRedaction>> {
+        let value = serde_json::to_value(event)?;
+        let mut redactions = Vec::new();
+
+        for path in self.restricted_paths(&event.event_type) {
+            let Some(field) = value.pointer(path) else {
+                continue;
+            };
+            let mut salt = [0u8; 16];
+            rand::thread_rng().fill_bytes(&mut salt);
+            redactions.push(FieldRedaction {
+                path: path.to_string(),
+                commitment: commit_field(path, &salt, field)?,
+                salt: Some(hex::encode(salt)),
+            });
+        }
+
+        Ok(redactions)
+    }
+}
+
+/// Compromisso de um campo: `SHA-256(domínio ‖ caminho ‖ sal ‖ valor)`
+pub fn commit_field(path: &str, salt: &[u8], value: &serde_json::Value) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMITMENT_DOMAIN);
+    hasher.update((path.len() as u64).to_be_bytes());
+    hasher.update(path.as_bytes());
+    hasher.update(salt);
+    hasher.update(serde_json::to_vec(value)?);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Versão pública do evento, sobre a qual o hash da entrada é calculado
+///
+/// O evento é reserializado a partir do JSON, para que quem recebe a visão
+/// chegue aos mesmos bytes sem conhecer a ordem dos campos na origem.
+pub fn public_payload(event_data: &[u8], redactions: &[FieldRedaction]) -> Result<Vec<u8>> {
+    let mut value: serde_json::Value = serde_json::from_slice(event_data)?;
+    for redaction in redactions {
+        let field = value.pointer_mut(&redaction.path)
+            .ok_or_else(|| anyhow!("Redacted field {} missing from event", redaction.path))?;
+        *field = serde_json::json!({ REDACTED_MARKER: redaction.commitment });
+    }
+    Ok(serde_json::to_vec(&value)?)
+}
+
+/// Confere, com os sais da versão completa, que cada compromisso corresponde ao valor registrado
+pub fn verify_redactions(event_data: &[u8], redactions: &[FieldRedaction]) -> Result<()> {
+    let value: serde_json::Value = serde_json::from_slice(event_data)?;
+    for redaction in redactions {
+        let salt = redaction.salt.as_deref()
+            .ok_or_else(|| anyhow!("Salt for {} not available in this view", redaction.path))?;
+        let field = value.pointer(&redaction.path)
+            .ok_or_else(|| anyhow!("Redacted field {} missing from event", redaction.path))?;
+        if commit_field(&redaction.path, &hex::decode(salt)?, field)? != redaction.commitment {
+            return Err(anyhow!("Commitment mismatch for {}", redaction.path));
+        }
+    }
+    Ok(())
+}
+
+impl EntryView {
+    pub fn new(entry: &ElectionLogEntry, level: ViewLevel) -> Result<Self> {
+        let (event, redactions) = match level {
+            ViewLevel::Public => (
+                serde_json::from_slice(&public_payload(&entry.event_data, &entry.redactions)?)?,
+                entry.redactions.iter().map(FieldRedaction::public).collect(),
+            ),
+            ViewLevel::Full => (
+                serde_json::from_slice(&entry.event_data)?,
+                entry.redactions.clone(),
+            ),
+        };
+
+        Ok(Self {
+            index: entry.index,
+            timestamp: entry.timestamp,
+            event_type: entry.event_type.clone(),
+            event_hash: entry.event_hash.clone(),
+            view: level,
+            event,
+            redactions,
+            merkle_proof: entry.merkle_proof.clone(),
+            verifier_signatures: entry.verifier_signatures.clone(),
+        })
+    }
+
+    /// Recalcula o hash da entrada a partir da visão, ligando-a à folha registrada
+    ///
+    /// Na visão completa, também confere cada compromisso com o valor e o sal.
+    pub fn verify(&self) -> Result<()> {
+        let event_data = serde_json::to_vec(&self.event)?;
+        let committed = match self.view {
+            ViewLevel::Public => event_data,
+            ViewLevel::Full => {
+                verify_redactions(&event_data, &self.redactions)?;
+                public_payload(&event_data, &self.redactions)?
+            }
+        };
+
+        let digest = format!("{:x}", Sha256::digest(&committed));
+        if digest != self.event_hash {
+            return Err(anyhow!("View of entry {} does not match committed hash", self.index));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transparency::election_logs::{ElectionTransparencyLog, LogConfig};
+    use chrono::Utc;
+
+    fn log() -> ElectionTransparencyLog {
+        ElectionTransparencyLog::new(LogConfig {
+            min_verifiers: 1,
+            max_verifiers: 10,
+            signature_threshold: 1,
+            retention_days: 365,
+            enable_audit_trail: true,
+            enable_performance_metrics: true,
+            max_entries_per_batch: 100,
+            verification_timeout_seconds: 30,
+        })
+    }
+
+    fn event(data: serde_json::Value) -> ElectionEvent {
+        ElectionEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            event_type: ElectionEventType::SystemEvent,
+            election_id: "eleicao-1".to_string(),
+            data,
+            timestamp: Utc::now(),
+            source: "URNA-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_public_view_hides_restricted_fields_and_matches_committed_hash() {
+        let mut log = log();
+        let proof = log.append_election_event(event(serde_json::json!({
+            "kind": "contingency_state_imported",
+            "snapshot_hash": "abc",
+            "approved_by": ["mesario-1", "mesario-2"],
+        }))).unwrap();
+        let entry = log.get_log_entry(proof.log_index).unwrap();
+
+        let public = EntryView::new(entry, ViewLevel::Public).unwrap();
+        assert_eq!(public.event["data"]["snapshot_hash"], "abc");
+        assert!(public.event["data"]["approved_by"].get(REDACTED_MARKER).is_some());
+        assert!(public.redactions.iter().all(|r| r.salt.is_none()));
+        public.verify().unwrap();
+
+        let full = EntryView::new(entry, ViewLevel::Full).unwrap();
+        assert_eq!(full.event["data"]["approved_by"][0], "mesario-1");
+        full.verify().unwrap();
+    }
+
+    #[test]
+    fn test_altered_restricted_value_breaks_commitment() {
+        let mut log = log();
+        let proof = log.append_election_event(event(serde_json::json!({
+            "participating_nodes": ["node-a", "node-b"],
+            "consensus_reached": true,
+        }))).unwrap();
+        let entry = log.get_log_entry(proof.log_index).unwrap();
+
+        let mut full = EntryView::new(entry, ViewLevel::Full).unwrap();
+        full.event["data"]["participating_nodes"] = serde_json::json!(["node-c"]);
+        assert!(full.verify().is_err());
+
+        let mut public = EntryView::new(entry, ViewLevel::Public).unwrap();
+        public.event["data"]["consensus_reached"] = serde_json::json!(false);
+        assert!(public.verify().is_err());
+
+        let unrestricted = log.append_election_event(event(serde_json::json!({"seq": 1}))).unwrap();
+        let entry = log.get_log_entry(unrestricted.log_index).unwrap();
+        assert!(entry.redactions.is_empty());
+        EntryView::new(entry, ViewLevel::Public).unwrap().verify().unwrap();
+    }
+}
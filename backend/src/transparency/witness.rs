@@ -0,0 +1,395 @@
+//! Cossinatura de STHs por testemunhas
+//!
+//! A cada STH publicada, o log pede a cossinatura de um rol de testemunhas:
+//! os demais backends regionais e testemunhas externas escolhidas. Cada
+//! testemunha só cossina se a nova STH for consistente com a última que
+//! cossinou para o mesmo log (mesmo tamanho, mesma raiz; tamanho maior,
+//! prova de consistência), de modo que um operador não consegue obter
+//! endosso para duas visões diferentes do log. As cossinaturas são coletadas
+//! pelo `SignatureCollector` e gravadas junto com a STH.
+//!
+//! Cada backend regional também atua como testemunha dos logs dos demais
+//! (`POST /api/v1/transparency/sth/cosign`).
+
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::election_logs::{ConsistencyProof, ElectionTransparencyLog, LogVerifier, MerkleTree};
+use super::signature_collector::SignatureCollector;
+use super::tree_heads::{SignedTreeHead, WitnessCosignature, WitnessSignature};
+
+/// Tolerância de relógio para STHs recebidas
+const MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+
+/// Tipo de testemunha
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WitnessKind {
+    /// Backend regional, que também opera um log
+    Regional,
+    /// Testemunha externa (academia, partidos, imprensa)
+    External,
+}
+
+/// Testemunha do rol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WitnessPeer {
+    pub id: String,
+    pub kind: WitnessKind,
+    /// Chave pública Ed25519, em hex; nos backends regionais, a chave do log
+    pub public_key: String,
+    /// URL base da API da testemunha
+    pub endpoint: String,
+}
+
+/// Pedido de cossinatura enviado à testemunha
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosignRequest {
+    pub sth: SignedTreeHead,
+    /// Consistência desde a última STH cossinada pela testemunha
+    pub consistency_proof: Option<ConsistencyProof>,
+}
+
+/// Resposta da testemunha; em recusa, informa o tamanho que já cossinou
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CosignReply {
+    pub signature: Option<String>,
+    pub known_tree_size: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Estado da testemunha: logs confiáveis e última STH cossinada de cada um
+#[derive(Debug, Clone, Default)]
+pub struct WitnessState {
+    trusted_logs: HashMap<String, Vec<u8>>,
+    last_cosigned: HashMap<String, SignedTreeHead>,
+}
+
+impl WitnessState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Passa a testemunhar o log com a chave pública informada
+    pub fn trust_log(&mut self, public_key: Vec<u8>) -> String {
+        let log_id = SignedTreeHead::log_id_for(&public_key);
+        self.trusted_logs.insert(log_id.clone(), public_key);
+        log_id
+    }
+
+    /// Tamanho da última STH cossinada para o log
+    pub fn known_tree_size(&self, log_id: &str) -> Option<u64> {
+        self.last_cosigned.get(log_id).map(|sth| sth.tree_size)
+    }
+
+    /// Confere se a STH pode ser cossinada
+    pub fn check(&self, request: &CosignRequest) -> Result<()> {
+        let sth = &request.sth;
+        let public_key = self.trusted_logs
+            .get(&sth.log_id)
+            .ok_or_else(|| anyhow!("Log {} is not witnessed by this node", sth.log_id))?;
+        if !sth.verify(public_key) {
+            return Err(anyhow!("Invalid STH signature"));
+        }
+        if sth.timestamp > Utc::now() + Duration::seconds(MAX_CLOCK_SKEW_SECONDS) {
+            return Err(anyhow!("STH timestamp is in the future"));
+        }
+
+        let Some(previous) = self.last_cosigned.get(&sth.log_id) else {
+            return Ok(());
+        };
+        if sth.timestamp < previous.timestamp {
+            return Err(anyhow!("STH is older than the last cosigned head"));
+        }
+        if sth.tree_size < previous.tree_size {
+            return Err(anyhow!(
+                "Tree size {} is smaller than cosigned size {}",
+                sth.tree_size,
+                previous.tree_size
+            ));
+        }
+        if sth.tree_size == previous.tree_size {
+            if sth.root_hash != previous.root_hash {
+                return Err(anyhow!("Root hash differs from the cosigned head of the same size"));
+            }
+            return Ok(());
+        }
+        if previous.tree_size == 0 {
+            return Ok(());
+        }
+
+        let proof = request.consistency_proof
+            .as_ref()
+            .ok_or_else(|| anyhow!("Consistency proof from size {} required", previous.tree_size))?;
+        let matches = proof.old_size == previous.tree_size
+            && proof.old_root == previous.root_hash
+            && proof.new_size == sth.tree_size
+            && proof.new_root == sth.root_hash;
+        if !matches || !MerkleTree::verify_consistency(proof) {
+            return Err(anyhow!("Invalid consistency proof from size {}", previous.tree_size));
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, sth: &SignedTreeHead) {
+        self.last_cosigned.insert(sth.log_id.clone(), sth.clone());
+    }
+}
+
+/// Coleta das cossinaturas do rol de testemunhas
+#[derive(Clone)]
+pub struct WitnessCollector {
+    roster: Vec<WitnessPeer>,
+    threshold: usize,
+    collector: SignatureCollector,
+    http_client: reqwest::Client,
+    /// Último tamanho cossinado por testemunha, para montar a prova de consistência
+    known_sizes: Arc<RwLock<HashMap<String, u64>>>,
+}
+
+impl WitnessCollector {
+    pub fn new(roster: Vec<WitnessPeer>, threshold: usize, collector: SignatureCollector) -> Self {
+        Self {
+            roster,
+            threshold,
+            collector,
+            http_client: reqwest::Client::new(),
+            known_sizes: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Publica a STH atual e anexa a assinatura agregada das testemunhas
+    pub async fn publish(&self, log_state: &Arc<RwLock<ElectionTransparencyLog>>) -> Result<SignedTreeHead> {
+        let sth = log_state.write().await.publish_tree_head()?;
+        if self.roster.is_empty() {
+            return Ok(sth);
+        }
+
+        let witness = self.cosign(log_state, &sth).await;
+        if !witness.quorum_reached {
+            log::warn!(
+                "STH {} cosigned by {} of {} required witnesses",
+                sth.tree_size,
+                witness.cosignatures.len(),
+                witness.threshold
+            );
+        }
+
+        log_state.write().await
+            .attach_tree_head_witness(&sth, witness)
+    }
+
+    /// Pede as cossinaturas em paralelo e monta a assinatura agregada
+    pub async fn cosign(
+        &self,
+        log_state: &Arc<RwLock<ElectionTransparencyLog>>,
+        sth: &SignedTreeHead,
+    ) -> WitnessSignature {
+        let witnesses: Vec<LogVerifier> = self.roster
+            .iter()
+            .filter_map(|peer| match hex::decode(&peer.public_key) {
+                Ok(public_key) => Some(LogVerifier {
+                    id: peer.id.clone(),
+                    name: peer.id.clone(),
+                    public_key,
+                    is_active: true,
+                    trust_level: 100,
+                    endpoint: Some(peer.endpoint.clone()),
+                }),
+                Err(_) => {
+                    log::warn!("Witness {} has an invalid public key", peer.id);
+                    None
+                }
+            })
+            .collect();
+
+        let message = String::from_utf8_lossy(&sth.cosigning_input()).into_owned();
+        let outcome = self.collector
+            .collect_with(&message, witnesses, self.threshold, |witness| {
+                self.request_cosignature(witness, log_state, sth)
+            })
+            .await;
+
+        for failure in &outcome.failures {
+            log::warn!("Witness {} did not cosign STH {}: {}", failure.verifier_id, sth.tree_size, failure.error);
+        }
+        self.aggregate(outcome.signatures.into_iter().map(|s| (s.verifier_id, s.public_key, s.signature, s.timestamp)))
+    }
+
+    fn aggregate(
+        &self,
+        signatures: impl Iterator<Item = (String, String, String, chrono::DateTime<Utc>)>,
+    ) -> WitnessSignature {
+        let mut mask = vec![0u8; self.roster.len().div_ceil(8)];
+        let mut cosignatures = Vec::new();
+
+        for (witness_id, public_key, signature, timestamp) in signatures {
+            let Some(position) = self.roster.iter().position(|peer| peer.id == witness_id) else {
+                continue;
+            };
+            mask[position / 8] |= 1 << (position % 8);
+            cosignatures.push(WitnessCosignature {
+                witness_id,
+                kind: self.roster[position].kind,
+                public_key,
+                signature,
+                timestamp,
+            });
+        }
+
+        WitnessSignature {
+            roster_size: self.roster.len(),
+            threshold: self.threshold,
+            signers_mask: hex::encode(mask),
+            quorum_reached: cosignatures.len() >= self.threshold,
+            cosignatures,
+        }
+    }
+
+    /// Pede a cossinatura; se a testemunha conhece outro tamanho, reenvia
+    /// uma vez com a prova de consistência a partir dele
+    async fn request_cosignature(
+        &self,
+        witness: LogVerifier,
+        log_state: &Arc<RwLock<ElectionTransparencyLog>>,
+        sth: &SignedTreeHead,
+    ) -> Result<String> {
+        let known = self.known_sizes.read().await.get(&witness.id).copied();
+        let mut reply = self.send(&witness, sth, proof_from(log_state, known, sth).await?).await?;
+
+        if reply.signature.is_none() {
+            if let Some(size) = reply.known_tree_size.filter(|size| Some(*size) != known) {
+                reply = self.send(&witness, sth, proof_from(log_state, Some(size), sth).await?).await?;
+            }
+        }
+
+        let signature = reply.signature.ok_or_else(|| {
+            anyhow!("Witness refused: {}", reply.error.unwrap_or_else(|| "no reason given".to_string()))
+        })?;
+        self.known_sizes.write().await.insert(witness.id.clone(), sth.tree_size);
+        Ok(signature)
+    }
+
+    async fn send(
+        &self,
+        witness: &LogVerifier,
+        sth: &SignedTreeHead,
+        consistency_proof: Option<ConsistencyProof>,
+    ) -> Result<CosignReply> {
+        let endpoint = witness.endpoint
+            .as_deref()
+            .ok_or_else(|| anyhow!("Witness {} has no endpoint", witness.id))?;
+        let response = self.http_client
+            .post(format!("{}/api/v1/transparency/sth/cosign", endpoint.trim_end_matches('/')))
+            .json(&CosignRequest { sth: sth.clone(), consistency_proof })
+            .send()
+            .await?;
+
+        let status = response.status();
+        response.json::<CosignReply>().await
+            .map_err(|_| anyhow!("Witness {} returned {}", witness.id, status))
+    }
+}
+
+/// Prova de consistência do tamanho conhecido pela testemunha até a STH
+async fn proof_from(
+    log_state: &Arc<RwLock<ElectionTransparencyLog>>,
+    known_size: Option<u64>,
+    sth: &SignedTreeHead,
+) -> Result<Option<ConsistencyProof>> {
+    match known_size {
+        Some(size) if size > 0 && size < sth.tree_size => {
+            Ok(Some(log_state.read().await.generate_consistency_proof(size, sth.tree_size)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    fn leaves(count: u64) -> MerkleTree {
+        let mut tree = MerkleTree::new();
+        for i in 0..count {
+            tree.add_leaf(&format!("entry-{}", i));
+        }
+        tree
+    }
+
+    #[test]
+    fn test_witness_requires_consistency_and_rejects_equivocation() {
+        let log_key = key_pair();
+        let mut witness = WitnessState::new();
+        let log_id = witness.trust_log(log_key.public_key().as_ref().to_vec());
+
+        let tree = leaves(6);
+        let first = SignedTreeHead::sign(&log_key, 3, tree.root_at(3).unwrap(), log_id.clone());
+        let request = CosignRequest { sth: first.clone(), consistency_proof: None };
+        witness.check(&request).unwrap();
+        witness.record(&first);
+
+        let equivocation = SignedTreeHead::sign(&log_key, 3, "00".repeat(32), log_id.clone());
+        assert!(witness.check(&CosignRequest { sth: equivocation, consistency_proof: None }).is_err());
+
+        let next = SignedTreeHead::sign(&log_key, 6, tree.root_at(6).unwrap(), log_id.clone());
+        assert!(witness.check(&CosignRequest { sth: next.clone(), consistency_proof: None }).is_err());
+        assert_eq!(witness.known_tree_size(&log_id), Some(3));
+
+        let proof = tree.generate_consistency_proof(3, 6).unwrap();
+        witness.check(&CosignRequest { sth: next, consistency_proof: Some(proof) }).unwrap();
+
+        let unknown = SignedTreeHead::sign(&key_pair(), 3, tree.root_at(3).unwrap(), "other".to_string());
+        assert!(witness.check(&CosignRequest { sth: unknown, consistency_proof: None }).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_counts_only_trusted_cosignatures() {
+        let log_key = key_pair();
+        let witnesses: Vec<(String, Ed25519KeyPair)> = ["sp", "rj", "ufmg"]
+            .iter()
+            .map(|id| (id.to_string(), key_pair()))
+            .collect();
+        let roster: Vec<WitnessPeer> = witnesses
+            .iter()
+            .map(|(id, key)| WitnessPeer {
+                id: id.clone(),
+                kind: if id == "ufmg" { WitnessKind::External } else { WitnessKind::Regional },
+                public_key: hex::encode(key.public_key().as_ref()),
+                endpoint: format!("https://{}.example", id),
+            })
+            .collect();
+        let collector = WitnessCollector::new(roster.clone(), 2, SignatureCollector::new(Default::default()));
+
+        let mut sth = SignedTreeHead::sign(&log_key, 0, hex::encode([0u8; 32]), "log".to_string());
+        let message = sth.cosigning_input();
+        let signatures = witnesses.iter().skip(1).map(|(id, key)| {
+            (id.clone(), hex::encode(key.public_key().as_ref()), hex::encode(key.sign(&message).as_ref()), Utc::now())
+        });
+        let aggregate = collector.aggregate(signatures);
+        assert!(aggregate.quorum_reached);
+        assert_eq!(aggregate.signers_mask, "06");
+        sth.witness = Some(aggregate);
+
+        let trusted: HashMap<String, Vec<u8>> = roster
+            .iter()
+            .map(|peer| (peer.id.clone(), hex::decode(&peer.public_key).unwrap()))
+            .collect();
+        assert_eq!(sth.valid_cosignatures(&trusted), 2);
+
+        let mut moved = sth.clone();
+        moved.tree_size = 1;
+        assert_eq!(moved.valid_cosignatures(&trusted), 0);
+    }
+}
@@ -2,12 +2,14 @@ pub mod circuits;
 pub mod prover;
 pub mod verifier;
 pub mod nullifier;
+pub mod nullifier_set;
 pub mod commitment;
 
 pub use circuits::*;
 pub use prover::*;
 pub use verifier::*;
 pub use nullifier::*;
+pub use nullifier_set::{NullifierSet, NullifierSetConfig, NullifierSetStats};
 pub use commitment::*;
 
 use serde::{Deserialize, Serialize};
@@ -23,4 +23,8 @@ impl NullifierManager {
     pub fn is_nullifier_used(&self, nullifier: &str) -> bool {
         self.contains_nullifier(nullifier)
     }
+
+    pub fn nullifiers(&self) -> impl Iterator<Item = &String> {
+        self.nullifiers.iter()
+    }
 }
@@ -0,0 +1,501 @@
+//! Conjunto de nullifiers em duas camadas
+//!
+//! Para centenas de milhões de eleitores a verificação de nullifier não pode
+//! ir ao banco a cada voto. Um filtro de Bloom (em memória ou no Redis,
+//! compartilhado entre instâncias) responde rapidamente quando o nullifier
+//! certamente não foi usado; só os positivos consultam a tabela
+//! `voting.vote_nullifiers`, cujo índice único é a fonte de verdade. A
+//! inserção sempre passa pelo índice, então um filtro desatualizado nunca
+//! permite voto duplo: no pior caso gera consultas extras.
+//!
+//! Após restaurar o banco de um backup o filtro pode conter nullifiers que
+//! não existem mais (falsos positivos a mais) ou, com Redis, perder bits.
+//! O procedimento de recuperação é chamar `rebuild`, que monta um filtro novo
+//! a partir da tabela e o troca atomicamente. Durante a reconstrução as
+//! consultas desta instância vão direto à tabela.
+
+use anyhow::{Result, anyhow};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Mutex, OnceCell, RwLock};
+use uuid::Uuid;
+
+use super::NullifierManager;
+use crate::monitoring::MonitoringSystem;
+
+/// Chave do filtro no Redis
+pub const REDIS_BLOOM_KEY: &str = "fortis:nullifiers:bloom";
+
+/// Nullifiers inseridos por comando durante a reconstrução
+const REBUILD_BATCH: usize = 10_000;
+
+/// Dimensionamento do filtro
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NullifierSetConfig {
+    /// Quantidade esperada de nullifiers (eleitorado com folga)
+    pub expected_items: u64,
+    /// Taxa de falsos positivos alvo
+    pub false_positive_rate: f64,
+}
+
+impl Default for NullifierSetConfig {
+    fn default() -> Self {
+        Self {
+            expected_items: 160_000_000,
+            false_positive_rate: 0.001,
+        }
+    }
+}
+
+/// Parâmetros do filtro de Bloom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BloomParams {
+    /// Tamanho em bits
+    pub bits: u64,
+    /// Número de funções de hash
+    pub hashes: u32,
+}
+
+impl BloomParams {
+    /// m = -n ln p / (ln 2)², k = (m / n) ln 2
+    pub fn for_capacity(expected_items: u64, false_positive_rate: f64) -> Result<Self> {
+        if expected_items == 0 || !(0.0..1.0).contains(&false_positive_rate) || false_positive_rate == 0.0 {
+            return Err(anyhow!("Invalid Bloom filter sizing"));
+        }
+        let n = expected_items as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-n * false_positive_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        // Strings do Redis têm no máximo 512 MiB
+        if bits > 512 * 1024 * 1024 * 8 {
+            return Err(anyhow!("Bloom filter of {} bits exceeds the Redis string limit", bits));
+        }
+        let hashes = ((bits as f64 / n) * ln2).round().max(1.0) as u32;
+        Ok(Self { bits, hashes })
+    }
+
+    /// Posições dos bits por hash duplo (Kirsch–Mitzenmacher) sobre SHA-256
+    pub fn positions(&self, nullifier: &str) -> Vec<u64> {
+        let digest = Sha256::digest(nullifier.as_bytes());
+        let h1 = u64::from_be_bytes(digest[0..8].try_into().unwrap());
+        let h2 = u64::from_be_bytes(digest[8..16].try_into().unwrap()) | 1;
+        (0..self.hashes as u64)
+            .map(|i| h1.wrapping_add(i.wrapping_mul(h2)) % self.bits)
+            .collect()
+    }
+}
+
+/// Filtro de Bloom em memória
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    params: BloomParams,
+    words: Vec<u64>,
+}
+
+impl BloomFilter {
+    pub fn new(params: BloomParams) -> Self {
+        Self {
+            params,
+            words: vec![0; params.bits.div_ceil(64) as usize],
+        }
+    }
+
+    pub fn insert(&mut self, nullifier: &str) {
+        for bit in self.params.positions(nullifier) {
+            self.words[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// `false` garante que o nullifier nunca foi inserido
+    pub fn may_contain(&self, nullifier: &str) -> bool {
+        self.params
+            .positions(nullifier)
+            .into_iter()
+            .all(|bit| self.words[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+}
+
+/// Onde o filtro é mantido
+enum BloomBackend {
+    Memory(RwLock<BloomFilter>),
+    Redis {
+        client: redis::Client,
+        connection: OnceCell<redis::aio::MultiplexedConnection>,
+    },
+}
+
+/// Estatísticas do conjunto
+#[derive(Debug, Clone, Serialize)]
+pub struct NullifierSetStats {
+    pub checks: u64,
+    /// Respondidas só pelo filtro
+    pub bloom_negatives: u64,
+    /// Positivos do filtro confirmados na tabela
+    pub confirmed_positives: u64,
+    /// Positivos do filtro ausentes da tabela
+    pub false_positives: u64,
+    /// Falsos positivos sobre consultas de nullifiers não usados
+    pub observed_false_positive_rate: f64,
+    pub target_false_positive_rate: f64,
+    pub bloom_bits: u64,
+    pub bloom_hashes: u32,
+    pub rebuilding: bool,
+}
+
+/// Conjunto de nullifiers com filtro de Bloom e tabela autoritativa
+pub struct NullifierSet {
+    config: NullifierSetConfig,
+    params: BloomParams,
+    bloom: BloomBackend,
+    pool: Option<PgPool>,
+    /// Usado sem banco (desenvolvimento e testes)
+    fallback: RwLock<NullifierManager>,
+    monitoring: Option<Arc<MonitoringSystem>>,
+    rebuilding: AtomicBool,
+    /// Inserções recebidas durante a reconstrução
+    pending: Mutex<Vec<String>>,
+    checks: AtomicU64,
+    bloom_negatives: AtomicU64,
+    confirmed_positives: AtomicU64,
+    false_positives: AtomicU64,
+}
+
+impl NullifierSet {
+    pub fn new(config: NullifierSetConfig) -> Result<Self> {
+        let params = BloomParams::for_capacity(config.expected_items, config.false_positive_rate)?;
+        Ok(Self {
+            config,
+            params,
+            bloom: BloomBackend::Memory(RwLock::new(BloomFilter::new(params))),
+            pool: None,
+            fallback: RwLock::new(NullifierManager::new()),
+            monitoring: None,
+            rebuilding: AtomicBool::new(false),
+            pending: Mutex::new(Vec::new()),
+            checks: AtomicU64::new(0),
+            bloom_negatives: AtomicU64::new(0),
+            confirmed_positives: AtomicU64::new(0),
+            false_positives: AtomicU64::new(0),
+        })
+    }
+
+    /// Tabela `voting.vote_nullifiers` como fonte de verdade
+    pub fn with_database(mut self, pool: PgPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Filtro compartilhado no Redis em vez de memória local
+    pub fn with_redis(mut self, client: redis::Client) -> Self {
+        self.bloom = BloomBackend::Redis { client, connection: OnceCell::new() };
+        self
+    }
+
+    /// Publica contadores e taxa de falsos positivos
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringSystem>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
+    }
+
+    /// Verifica se o nullifier já foi usado
+    pub async fn contains(&self, nullifier: &str) -> Result<bool> {
+        self.checks.fetch_add(1, Ordering::Relaxed);
+
+        // Filtro incompleto durante a reconstrução: consulta direta
+        if self.rebuilding.load(Ordering::Acquire) {
+            return self.stored(nullifier).await;
+        }
+
+        if !self.bloom_may_contain(nullifier).await? {
+            self.bloom_negatives.fetch_add(1, Ordering::Relaxed);
+            self.record("nullifier_bloom_negatives").await;
+            return Ok(false);
+        }
+
+        let stored = self.stored(nullifier).await?;
+        if stored {
+            self.confirmed_positives.fetch_add(1, Ordering::Relaxed);
+            self.record("nullifier_confirmed_positives").await;
+        } else {
+            self.false_positives.fetch_add(1, Ordering::Relaxed);
+            self.record("nullifier_bloom_false_positives").await;
+        }
+        Ok(stored)
+    }
+
+    /// Registra o nullifier; `false` se já havia sido usado
+    ///
+    /// O índice único decide: duas inserções concorrentes do mesmo
+    /// nullifier nunca são aceitas, mesmo que o filtro esteja desatualizado.
+    pub async fn insert(&self, nullifier: &str, election_id: Option<Uuid>) -> Result<bool> {
+        let inserted = match &self.pool {
+            Some(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO voting.vote_nullifiers (nullifier, election_id)
+                    VALUES ($1, $2)
+                    ON CONFLICT (nullifier) DO NOTHING
+                    "#
+                )
+                .bind(nullifier)
+                .bind(election_id)
+                .execute(pool)
+                .await?
+                .rows_affected() == 1
+            }
+            None => self.fallback.write().await.add_nullifier(nullifier.to_string()),
+        };
+
+        if self.rebuilding.load(Ordering::Acquire) {
+            self.pending.lock().await.push(nullifier.to_string());
+        }
+        self.bloom_insert(&[nullifier]).await?;
+        Ok(inserted)
+    }
+
+    /// Reconstrói o filtro a partir da tabela (procedimento pós-restauração)
+    ///
+    /// Devolve a quantidade de nullifiers carregados.
+    pub async fn rebuild(&self) -> Result<u64> {
+        if self.rebuilding.swap(true, Ordering::AcqRel) {
+            return Err(anyhow!("Nullifier filter rebuild already in progress"));
+        }
+        let result = self.rebuild_filter().await;
+        self.rebuilding.store(false, Ordering::Release);
+
+        let count = result?;
+        for counter in [&self.bloom_negatives, &self.confirmed_positives, &self.false_positives] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        log::info!("Nullifier Bloom filter rebuilt with {} entries", count);
+        Ok(count)
+    }
+
+    pub fn stats(&self) -> NullifierSetStats {
+        NullifierSetStats {
+            checks: self.checks.load(Ordering::Relaxed),
+            bloom_negatives: self.bloom_negatives.load(Ordering::Relaxed),
+            confirmed_positives: self.confirmed_positives.load(Ordering::Relaxed),
+            false_positives: self.false_positives.load(Ordering::Relaxed),
+            observed_false_positive_rate: self.observed_false_positive_rate(),
+            target_false_positive_rate: self.config.false_positive_rate,
+            bloom_bits: self.params.bits,
+            bloom_hashes: self.params.hashes,
+            rebuilding: self.rebuilding.load(Ordering::Relaxed),
+        }
+    }
+
+    fn observed_false_positive_rate(&self) -> f64 {
+        let false_positives = self.false_positives.load(Ordering::Relaxed);
+        let unused = self.bloom_negatives.load(Ordering::Relaxed) + false_positives;
+        if unused == 0 {
+            0.0
+        } else {
+            false_positives as f64 / unused as f64
+        }
+    }
+
+    async fn record(&self, counter: &str) {
+        if let Some(monitoring) = &self.monitoring {
+            monitoring.increment_counter(counter, 1).await;
+            monitoring
+                .set_gauge("nullifier_bloom_false_positive_rate", self.observed_false_positive_rate())
+                .await;
+        }
+    }
+
+    /// Consulta autoritativa
+    async fn stored(&self, nullifier: &str) -> Result<bool> {
+        match &self.pool {
+            Some(pool) => {
+                let row = sqlx::query("SELECT 1 FROM voting.vote_nullifiers WHERE nullifier = $1")
+                    .bind(nullifier)
+                    .fetch_optional(pool)
+                    .await?;
+                Ok(row.is_some())
+            }
+            None => Ok(self.fallback.read().await.contains_nullifier(nullifier)),
+        }
+    }
+
+    async fn redis_connection(
+        client: &redis::Client,
+        connection: &OnceCell<redis::aio::MultiplexedConnection>,
+    ) -> Result<redis::aio::MultiplexedConnection> {
+        Ok(connection
+            .get_or_try_init(|| client.get_multiplexed_tokio_connection())
+            .await?
+            .clone())
+    }
+
+    async fn bloom_may_contain(&self, nullifier: &str) -> Result<bool> {
+        match &self.bloom {
+            BloomBackend::Memory(filter) => Ok(filter.read().await.may_contain(nullifier)),
+            BloomBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                let mut pipe = redis::pipe();
+                for bit in self.params.positions(nullifier) {
+                    pipe.cmd("GETBIT").arg(REDIS_BLOOM_KEY).arg(bit);
+                }
+                let bits: Vec<u8> = pipe.query_async(&mut conn).await?;
+                Ok(bits.iter().all(|b| *b == 1))
+            }
+        }
+    }
+
+    async fn bloom_insert(&self, nullifiers: &[&str]) -> Result<()> {
+        self.bloom_insert_into(REDIS_BLOOM_KEY, nullifiers).await
+    }
+
+    async fn bloom_insert_into(&self, key: &str, nullifiers: &[&str]) -> Result<()> {
+        match &self.bloom {
+            BloomBackend::Memory(filter) => {
+                let mut filter = filter.write().await;
+                for nullifier in nullifiers {
+                    filter.insert(nullifier);
+                }
+            }
+            BloomBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                let mut pipe = redis::pipe();
+                for nullifier in nullifiers {
+                    for bit in self.params.positions(nullifier) {
+                        pipe.cmd("SETBIT").arg(key).arg(bit).arg(1).ignore();
+                    }
+                }
+                pipe.query_async::<_, ()>(&mut conn).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Monta um filtro novo e só então substitui o atual
+    async fn rebuild_filter(&self) -> Result<u64> {
+        let rebuild_key = format!("{}:rebuild", REDIS_BLOOM_KEY);
+        let mut fresh = BloomFilter::new(self.params);
+        let mut batch: Vec<String> = Vec::with_capacity(REBUILD_BATCH);
+        let mut count = 0u64;
+        self.pending.lock().await.clear();
+
+        if let BloomBackend::Redis { client, connection } = &self.bloom {
+            let mut conn = Self::redis_connection(client, connection).await?;
+            redis::cmd("DEL").arg(&rebuild_key).query_async::<_, ()>(&mut conn).await?;
+        }
+
+        match &self.pool {
+            Some(pool) => {
+                let mut rows = sqlx::query("SELECT nullifier FROM voting.vote_nullifiers").fetch(pool);
+                while let Some(row) = rows.try_next().await? {
+                    batch.push(row.get("nullifier"));
+                    if batch.len() == REBUILD_BATCH {
+                        count += self.rebuild_batch(&rebuild_key, &mut batch, &mut fresh).await?;
+                    }
+                }
+            }
+            None => batch.extend(self.fallback.read().await.nullifiers().cloned()),
+        }
+        count += self.rebuild_batch(&rebuild_key, &mut batch, &mut fresh).await?;
+
+        // Inserções feitas durante a leitura da tabela entram antes da troca
+        let mut pending = self.pending.lock().await;
+        batch.append(&mut pending);
+        self.rebuild_batch(&rebuild_key, &mut batch, &mut fresh).await?;
+
+        match &self.bloom {
+            BloomBackend::Memory(filter) => *filter.write().await = fresh,
+            BloomBackend::Redis { client, connection } => {
+                let mut conn = Self::redis_connection(client, connection).await?;
+                // Sem nullifiers a chave nova não existe e RENAME falharia
+                let mut command = if count == 0 {
+                    redis::cmd("DEL")
+                } else {
+                    let mut rename = redis::cmd("RENAME");
+                    rename.arg(&rebuild_key);
+                    rename
+                };
+                command.arg(REDIS_BLOOM_KEY).query_async::<_, ()>(&mut conn).await?;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn rebuild_batch(&self, rebuild_key: &str, batch: &mut Vec<String>, fresh: &mut BloomFilter) -> Result<u64> {
+        let count = batch.len() as u64;
+        match &self.bloom {
+            BloomBackend::Memory(_) => batch.iter().for_each(|nullifier| fresh.insert(nullifier)),
+            BloomBackend::Redis { .. } if count > 0 => {
+                let refs: Vec<&str> = batch.iter().map(String::as_str).collect();
+                self.bloom_insert_into(rebuild_key, &refs).await?;
+            }
+            BloomBackend::Redis { .. } => {}
+        }
+        batch.clear();
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_config() -> NullifierSetConfig {
+        NullifierSetConfig {
+            expected_items: 1_000,
+            false_positive_rate: 0.01,
+        }
+    }
+
+    #[test]
+    fn test_bloom_sizing_and_membership() {
+        let params = BloomParams::for_capacity(1_000, 0.01).unwrap();
+        assert_eq!(params.hashes, 7);
+        assert!(params.bits > 9_000 && params.bits < 10_000);
+
+        let mut filter = BloomFilter::new(params);
+        for i in 0..1_000 {
+            filter.insert(&format!("nullifier-{}", i));
+        }
+        assert!((0..1_000).all(|i| filter.may_contain(&format!("nullifier-{}", i))));
+
+        let false_positives = (0..10_000)
+            .filter(|i| filter.may_contain(&format!("unused-{}", i)))
+            .count();
+        assert!(false_positives < 300, "false positives: {}", false_positives);
+    }
+
+    #[tokio::test]
+    async fn test_insert_is_unique_and_counts_checks() {
+        let set = NullifierSet::new(small_config()).unwrap();
+
+        assert!(!set.contains("abc").await.unwrap());
+        assert!(set.insert("abc", None).await.unwrap());
+        assert!(!set.insert("abc", None).await.unwrap());
+        assert!(set.contains("abc").await.unwrap());
+
+        let stats = set.stats();
+        assert_eq!(stats.checks, 2);
+        assert_eq!(stats.bloom_negatives, 1);
+        assert_eq!(stats.confirmed_positives, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_restores_filter_from_store() {
+        let set = NullifierSet::new(small_config()).unwrap();
+        set.insert("n1", None).await.unwrap();
+        set.insert("n2", None).await.unwrap();
+
+        // Simula filtro perdido após restauração
+        if let BloomBackend::Memory(filter) = &set.bloom {
+            *filter.write().await = BloomFilter::new(set.params);
+        }
+        assert!(!set.contains("n1").await.unwrap());
+
+        assert_eq!(set.rebuild().await.unwrap(), 2);
+        assert!(set.contains("n1").await.unwrap());
+        assert!(set.contains("n2").await.unwrap());
+        assert!(!set.stats().rebuilding);
+    }
+}
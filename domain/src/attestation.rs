@@ -0,0 +1,368 @@
+//! Atestação remota do boot seguro da urna
+//!
+//! Na abertura da sessão de votação a urna pede um desafio ao backend e
+//! responde com uma citação (`quote`) do TPM sobre os PCRs do boot,
+//! assinada pela chave de atestação da urna e com o desafio no campo
+//! `extraData`. O backend confere a assinatura, o desafio e o resumo dos
+//! PCRs citados, e compara os valores com a linha de base conhecida para
+//! as versões de firmware e da aplicação informadas. Qualquer divergência
+//! impede a abertura da sessão.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::update::UpdateVersion;
+
+/// PCRs citados: firmware, opções de firmware, carregador de boot e política do boot seguro
+pub const ATTESTED_PCRS: [u32; 4] = [0, 2, 4, 7];
+
+/// Validade do desafio emitido pelo backend
+pub const ATTESTATION_CHALLENGE_TTL_SECONDS: i64 = 120;
+
+/// `TPM_GENERATED_VALUE`, início de toda estrutura gerada pelo TPM
+const TPM_GENERATED_VALUE: u32 = 0xff54_4347;
+const TPM_ST_ATTEST_QUOTE: u16 = 0x8018;
+const TPM_ALG_SHA256: u16 = 0x000b;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AttestationError {
+    #[error("Malformed TPM quote: {0}")]
+    MalformedQuote(&'static str),
+    #[error("Invalid value for PCR {0}")]
+    InvalidPcr(u32),
+    #[error("Quote does not carry the challenge nonce")]
+    NonceMismatch,
+    #[error("Quote covers PCRs {quoted:?}, report lists {reported:?}")]
+    SelectionMismatch { quoted: Vec<u32>, reported: Vec<u32> },
+    #[error("Quoted PCR digest does not match the reported values")]
+    DigestMismatch,
+    #[error("Secure boot is disabled")]
+    SecureBootDisabled,
+    #[error("No baseline for firmware {firmware} and application {application}")]
+    NoBaseline { firmware: UpdateVersion, application: UpdateVersion },
+    #[error("PCR {0} is missing from the report")]
+    MissingPcr(u32),
+    #[error("PCR {index} is {actual}, baseline expects {expected}")]
+    PcrMismatch { index: u32, expected: String, actual: String },
+}
+
+/// Desafio de uso único emitido para a urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationChallenge {
+    pub urna_id: String,
+    /// 32 bytes aleatórios (hex), citados pelo TPM em `extraData`
+    pub nonce: String,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Medições do boot enviadas pela urna na abertura da sessão
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationReport {
+    pub urna_id: String,
+    pub election_id: Uuid,
+    pub nonce: String,
+    pub secure_boot_enabled: bool,
+    pub firmware_version: UpdateVersion,
+    pub application_version: UpdateVersion,
+    /// Valores SHA-256 (hex) de cada PCR citado
+    pub pcrs: BTreeMap<u32, String>,
+    /// Estrutura `TPMS_ATTEST` (hex) devolvida pelo `tpm2_quote`
+    pub quote: String,
+    /// Assinatura da chave de atestação (hex) sobre `quote`
+    pub signature: String,
+    pub reported_at: DateTime<Utc>,
+}
+
+impl AttestationReport {
+    pub fn quote_bytes(&self) -> Result<Vec<u8>, AttestationError> {
+        hex::decode(&self.quote).map_err(|_| AttestationError::MalformedQuote("not hex"))
+    }
+
+    /// Confere se a citação cobre o desafio e exatamente os PCRs informados
+    pub fn check_quote(&self) -> Result<(), AttestationError> {
+        let info = QuoteInfo::parse(&self.quote_bytes()?)?;
+        let nonce = hex::decode(&self.nonce).map_err(|_| AttestationError::NonceMismatch)?;
+        if info.extra_data != nonce {
+            return Err(AttestationError::NonceMismatch);
+        }
+        let reported: Vec<u32> = self.pcrs.keys().copied().collect();
+        if info.selected != reported {
+            return Err(AttestationError::SelectionMismatch { quoted: info.selected, reported });
+        }
+        if info.pcr_digest != pcr_digest(&self.pcrs)? {
+            return Err(AttestationError::DigestMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Resumo dos PCRs como o TPM calcula na citação: SHA-256 da concatenação
+/// dos valores, em ordem crescente de índice
+pub fn pcr_digest(pcrs: &BTreeMap<u32, String>) -> Result<Vec<u8>, AttestationError> {
+    let mut hasher = Sha256::new();
+    for (index, value) in pcrs {
+        let value = hex::decode(value).map_err(|_| AttestationError::InvalidPcr(*index))?;
+        if value.len() != 32 {
+            return Err(AttestationError::InvalidPcr(*index));
+        }
+        hasher.update(&value);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Campos da citação `TPMS_ATTEST` usados na verificação
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteInfo {
+    pub extra_data: Vec<u8>,
+    /// PCRs selecionados no banco SHA-256
+    pub selected: Vec<u32>,
+    pub pcr_digest: Vec<u8>,
+}
+
+impl QuoteInfo {
+    pub fn parse(quote: &[u8]) -> Result<Self, AttestationError> {
+        let mut reader = QuoteReader { data: quote };
+        if reader.u32()? != TPM_GENERATED_VALUE {
+            return Err(AttestationError::MalformedQuote("not generated by a TPM"));
+        }
+        if reader.u16()? != TPM_ST_ATTEST_QUOTE {
+            return Err(AttestationError::MalformedQuote("not a quote"));
+        }
+        reader.sized()?; // qualifiedSigner
+        let extra_data = reader.sized()?.to_vec();
+        reader.take(17)?; // clockInfo
+        reader.take(8)?; // firmwareVersion
+
+        let mut selected = Vec::new();
+        for _ in 0..reader.u32()? {
+            let hash = reader.u16()?;
+            let size = reader.take(1)?[0] as usize;
+            let bitmap = reader.take(size)?;
+            if hash != TPM_ALG_SHA256 {
+                if bitmap.iter().any(|byte| *byte != 0) {
+                    return Err(AttestationError::MalformedQuote("PCRs quoted outside the SHA-256 bank"));
+                }
+                continue;
+            }
+            for (byte_index, byte) in bitmap.iter().enumerate() {
+                for bit in 0..8 {
+                    if byte & (1 << bit) != 0 {
+                        selected.push((byte_index * 8 + bit) as u32);
+                    }
+                }
+            }
+        }
+        let pcr_digest = reader.sized()?.to_vec();
+        if !reader.data.is_empty() {
+            return Err(AttestationError::MalformedQuote("trailing bytes"));
+        }
+        Ok(Self { extra_data, selected, pcr_digest })
+    }
+}
+
+struct QuoteReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> QuoteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AttestationError> {
+        if self.data.len() < len {
+            return Err(AttestationError::MalformedQuote("truncated"));
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16, AttestationError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, AttestationError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Estrutura `TPM2B`: tamanho de dois bytes seguido do conteúdo
+    fn sized(&mut self) -> Result<&'a [u8], AttestationError> {
+        let len = self.u16()? as usize;
+        self.take(len)
+    }
+}
+
+/// Valores conhecidos dos PCRs para uma combinação de firmware e aplicação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationBaseline {
+    pub baseline_id: Uuid,
+    pub firmware_version: UpdateVersion,
+    pub application_version: UpdateVersion,
+    pub pcrs: BTreeMap<u32, String>,
+    pub description: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AttestationBaseline {
+    pub fn applies_to(&self, report: &AttestationReport) -> bool {
+        self.firmware_version == report.firmware_version && self.application_version == report.application_version
+    }
+
+    /// Compara o boot seguro e cada PCR da linha de base com o relatório
+    pub fn check(&self, report: &AttestationReport) -> Result<(), AttestationError> {
+        if !report.secure_boot_enabled {
+            return Err(AttestationError::SecureBootDisabled);
+        }
+        for (index, expected) in &self.pcrs {
+            let actual = report.pcrs.get(index).ok_or(AttestationError::MissingPcr(*index))?;
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(AttestationError::PcrMismatch {
+                    index: *index,
+                    expected: expected.to_lowercase(),
+                    actual: actual.to_lowercase(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resultado da verificação
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AttestationVerdict {
+    Trusted,
+    /// A urna não pode abrir a sessão de votação
+    Rejected,
+}
+
+/// Resposta do backend ao relatório da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AttestationResult {
+    pub urna_id: String,
+    pub election_id: Uuid,
+    pub verdict: AttestationVerdict,
+    pub baseline_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub verified_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcrs(fill: u8) -> BTreeMap<u32, String> {
+        ATTESTED_PCRS.iter().map(|index| (*index, hex::encode([fill + *index as u8; 32]))).collect()
+    }
+
+    /// Papel do TPM: monta a `TPMS_ATTEST` da citação
+    fn quote(nonce: &[u8], pcrs: &BTreeMap<u32, String>) -> Vec<u8> {
+        let mut bitmap = [0u8; 3];
+        for index in pcrs.keys() {
+            bitmap[*index as usize / 8] |= 1 << (index % 8);
+        }
+        let digest = pcr_digest(pcrs).unwrap();
+
+        let mut quote = Vec::new();
+        quote.extend_from_slice(&TPM_GENERATED_VALUE.to_be_bytes());
+        quote.extend_from_slice(&TPM_ST_ATTEST_QUOTE.to_be_bytes());
+        quote.extend_from_slice(&4u16.to_be_bytes());
+        quote.extend_from_slice(b"name");
+        quote.extend_from_slice(&(nonce.len() as u16).to_be_bytes());
+        quote.extend_from_slice(nonce);
+        quote.extend_from_slice(&[0u8; 17]);
+        quote.extend_from_slice(&[0u8; 8]);
+        quote.extend_from_slice(&1u32.to_be_bytes());
+        quote.extend_from_slice(&TPM_ALG_SHA256.to_be_bytes());
+        quote.push(bitmap.len() as u8);
+        quote.extend_from_slice(&bitmap);
+        quote.extend_from_slice(&(digest.len() as u16).to_be_bytes());
+        quote.extend_from_slice(&digest);
+        quote
+    }
+
+    fn report(nonce: &[u8], pcrs: BTreeMap<u32, String>) -> AttestationReport {
+        AttestationReport {
+            urna_id: "urna-0042".to_string(),
+            election_id: Uuid::new_v4(),
+            nonce: hex::encode(nonce),
+            secure_boot_enabled: true,
+            firmware_version: "2.1.0".parse().unwrap(),
+            application_version: "1.4.0".parse().unwrap(),
+            quote: hex::encode(quote(nonce, &pcrs)),
+            pcrs,
+            signature: String::new(),
+            reported_at: Utc::now(),
+        }
+    }
+
+    fn baseline(pcrs: BTreeMap<u32, String>) -> AttestationBaseline {
+        AttestationBaseline {
+            baseline_id: Uuid::new_v4(),
+            firmware_version: "2.1.0".parse().unwrap(),
+            application_version: "1.4.0".parse().unwrap(),
+            pcrs,
+            description: None,
+            created_by: "admin".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_quote_binds_nonce_and_reported_pcrs() {
+        let nonce = [7u8; 32];
+        let report = report(&nonce, pcrs(1));
+        assert_eq!(report.check_quote(), Ok(()));
+
+        let info = QuoteInfo::parse(&report.quote_bytes().unwrap()).unwrap();
+        assert_eq!(info.selected, ATTESTED_PCRS.to_vec());
+        assert_eq!(info.extra_data, nonce.to_vec());
+
+        let mut replayed = report.clone();
+        replayed.nonce = hex::encode([8u8; 32]);
+        assert_eq!(replayed.check_quote(), Err(AttestationError::NonceMismatch));
+
+        let mut edited = report.clone();
+        edited.pcrs.insert(7, hex::encode([0u8; 32]));
+        assert_eq!(edited.check_quote(), Err(AttestationError::DigestMismatch));
+
+        let mut partial = report.clone();
+        partial.pcrs.remove(&7);
+        assert!(matches!(partial.check_quote(), Err(AttestationError::SelectionMismatch { .. })));
+
+        let mut truncated = report;
+        truncated.quote.truncate(truncated.quote.len() - 2);
+        assert_eq!(truncated.check_quote(), Err(AttestationError::MalformedQuote("truncated")));
+    }
+
+    #[test]
+    fn test_baseline_requires_secure_boot_and_matching_pcrs() {
+        let report = report(&[7u8; 32], pcrs(1));
+        let known = baseline(pcrs(1));
+        assert!(known.applies_to(&report));
+        assert_eq!(known.check(&report), Ok(()));
+
+        let mut disabled = report.clone();
+        disabled.secure_boot_enabled = false;
+        assert_eq!(known.check(&disabled), Err(AttestationError::SecureBootDisabled));
+
+        let mut other = pcrs(1);
+        other.insert(4, hex::encode([9u8; 32]));
+        assert!(matches!(baseline(other).check(&report), Err(AttestationError::PcrMismatch { index: 4, .. })));
+
+        let mut extended = pcrs(1);
+        extended.insert(9, hex::encode([9u8; 32]));
+        assert_eq!(baseline(extended).check(&report), Err(AttestationError::MissingPcr(9)));
+
+        let mut newer = report;
+        newer.application_version = "1.5.0".parse().unwrap();
+        assert!(!known.applies_to(&newer));
+    }
+}
@@ -0,0 +1,310 @@
+//! Cédula com vários cargos
+//!
+//! A eleição define a lista ordenada de cargos (a ordem de votação da
+//! urna) e o eleitor faz uma escolha em cada um. A cédula só é válida com
+//! exatamente uma escolha por cargo, na ordem da lista, e cada escolha
+//! precisa ser de um candidato do próprio cargo, um voto em branco ou um
+//! voto nulo. Brancos e nulos são votos legítimos, com contadores próprios
+//! por cargo (`cargo:BRANCO` e `cargo:NULO`), e não entram nos válidos.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::candidate::{Candidate, CandidatePosition};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BallotError {
+    #[error("Ballot has no contests")]
+    NoContests,
+    #[error("Expected a choice for {expected:?}, found {found:?}")]
+    ContestMismatch {
+        expected: CandidatePosition,
+        found: Option<CandidatePosition>,
+    },
+    #[error("Ballot has {0} choices beyond the last contest")]
+    ExtraChoices(usize),
+    #[error("Candidate {0} not found")]
+    UnknownCandidate(Uuid),
+    #[error("Candidate {candidate_id} does not run for {position:?}")]
+    WrongPosition {
+        candidate_id: Uuid,
+        position: CandidatePosition,
+    },
+    #[error("Choice for {0:?} has no candidate")]
+    MissingCandidate(CandidatePosition),
+    #[error("Blank or null choice for {0:?} names a candidate")]
+    UnexpectedCandidate(CandidatePosition),
+}
+
+/// Votável dos votos em branco nos contadores
+pub const BLANK_VOTABLE: &str = "BRANCO";
+
+/// Votável dos votos nulos nos contadores
+pub const NULL_VOTABLE: &str = "NULO";
+
+/// Tipo da escolha em um cargo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ChoiceKind {
+    #[default]
+    Candidate,
+    /// Tecla BRANCO confirmada
+    Blank,
+    /// Número sem candidato no cargo, confirmado pelo eleitor
+    Null,
+}
+
+/// Escolha do eleitor em um cargo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContestChoice {
+    pub position: CandidatePosition,
+    #[serde(default)]
+    pub kind: ChoiceKind,
+    /// Candidato escolhido; ausente nos votos em branco e nulos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate_id: Option<Uuid>,
+}
+
+impl ContestChoice {
+    pub fn candidate(position: CandidatePosition, candidate_id: Uuid) -> Self {
+        Self { position, kind: ChoiceKind::Candidate, candidate_id: Some(candidate_id) }
+    }
+
+    pub fn blank(position: CandidatePosition) -> Self {
+        Self { position, kind: ChoiceKind::Blank, candidate_id: None }
+    }
+
+    pub fn null(position: CandidatePosition) -> Self {
+        Self { position, kind: ChoiceKind::Null, candidate_id: None }
+    }
+}
+
+/// Votável de uma chave dos contadores
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Votable<'a> {
+    /// Número ou identificador do candidato (ou da legenda)
+    Candidate(&'a str),
+    Blank,
+    Null,
+}
+
+/// Cargos disputados pelos candidatos, na ordem de votação da urna
+pub fn contest_order(candidates: &[Candidate]) -> Vec<CandidatePosition> {
+    let positions: BTreeSet<(u32, CandidatePosition)> = candidates
+        .iter()
+        .map(|c| (c.position.ballot_order(), c.position))
+        .collect();
+    positions.into_iter().map(|(_, position)| position).collect()
+}
+
+/// Confere uma escolha por cargo, na ordem, com candidatos do próprio cargo
+pub fn validate_choices(
+    contests: &[CandidatePosition],
+    choices: &[ContestChoice],
+    candidates: &[Candidate],
+) -> Result<(), BallotError> {
+    if contests.is_empty() {
+        return Err(BallotError::NoContests);
+    }
+    for (index, expected) in contests.iter().enumerate() {
+        let choice = choices.get(index).filter(|choice| choice.position == *expected);
+        let Some(choice) = choice else {
+            return Err(BallotError::ContestMismatch {
+                expected: *expected,
+                found: choices.get(index).map(|choice| choice.position),
+            });
+        };
+        let candidate_id = match (choice.kind, choice.candidate_id) {
+            (ChoiceKind::Candidate, Some(candidate_id)) => candidate_id,
+            (ChoiceKind::Candidate, None) => return Err(BallotError::MissingCandidate(choice.position)),
+            (_, Some(_)) => return Err(BallotError::UnexpectedCandidate(choice.position)),
+            (_, None) => continue,
+        };
+        let candidate = candidates
+            .iter()
+            .find(|c| c.id == candidate_id)
+            .ok_or(BallotError::UnknownCandidate(candidate_id))?;
+        if candidate.position != choice.position {
+            return Err(BallotError::WrongPosition {
+                candidate_id: candidate.id,
+                position: choice.position,
+            });
+        }
+    }
+    if choices.len() > contests.len() {
+        return Err(BallotError::ExtraChoices(choices.len() - contests.len()));
+    }
+    Ok(())
+}
+
+/// Chave da escolha nos contadores da urna: `cargo:candidato`,
+/// `cargo:BRANCO` ou `cargo:NULO`
+pub fn tally_key(choice: &ContestChoice) -> String {
+    let votable = match (choice.kind, choice.candidate_id) {
+        (ChoiceKind::Candidate, Some(candidate_id)) => candidate_id.to_string(),
+        (ChoiceKind::Candidate, None) | (ChoiceKind::Null, _) => NULL_VOTABLE.to_string(),
+        (ChoiceKind::Blank, _) => BLANK_VOTABLE.to_string(),
+    };
+    format!("{}:{}", choice.position.code(), votable)
+}
+
+/// Separa a chave dos contadores em cargo (se houver) e votável
+pub fn split_tally_key(key: &str) -> (Option<&str>, Votable<'_>) {
+    let (position, votable) = match key.split_once(':') {
+        Some((position, votable)) => (Some(position), votable.trim()),
+        None => (None, key.trim()),
+    };
+    let votable = if votable.eq_ignore_ascii_case(BLANK_VOTABLE) {
+        Votable::Blank
+    } else if votable.eq_ignore_ascii_case(NULL_VOTABLE) {
+        Votable::Null
+    } else {
+        Votable::Candidate(votable)
+    };
+    (position, votable)
+}
+
+/// Votos somados por cargo a partir dos contadores da urna
+///
+/// Chaves sem cargo, de cédulas de cargo único, ficam juntas sob `""`.
+/// Cada eleitor atendido vota uma vez em cada cargo.
+pub fn votes_per_contest(tally: &BTreeMap<String, u64>) -> BTreeMap<String, u64> {
+    let mut contests = BTreeMap::new();
+    for (key, votes) in tally {
+        let (contest, _) = split_tally_key(key);
+        *contests.entry(contest.unwrap_or("").to_string()).or_insert(0) += votes;
+    }
+    contests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn candidate(number: u32, position: CandidatePosition) -> Candidate {
+        Candidate {
+            id: Uuid::new_v4(),
+            election_id: Uuid::nil(),
+            name: format!("Candidato {}", number),
+            party: "PARTIDO".to_string(),
+            number,
+            position,
+            coalition: None,
+            region: None,
+            photo_url: None,
+            photo_sha256: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn choice(candidate: &Candidate) -> ContestChoice {
+        ContestChoice::candidate(candidate.position, candidate.id)
+    }
+
+    #[test]
+    fn test_contests_follow_urna_voting_order() {
+        let candidates = vec![
+            candidate(13, CandidatePosition::President),
+            candidate(456, CandidatePosition::Senator),
+            candidate(22, CandidatePosition::President),
+            candidate(45, CandidatePosition::Governor),
+        ];
+        assert_eq!(
+            contest_order(&candidates),
+            vec![CandidatePosition::Senator, CandidatePosition::Governor, CandidatePosition::President]
+        );
+    }
+
+    #[test]
+    fn test_ballot_needs_one_choice_per_contest_in_order() {
+        let president = candidate(13, CandidatePosition::President);
+        let governor = candidate(45, CandidatePosition::Governor);
+        let senator = candidate(456, CandidatePosition::Senator);
+        let candidates = vec![president.clone(), governor.clone(), senator.clone()];
+        let contests = contest_order(&candidates);
+
+        let ballot = vec![choice(&senator), choice(&governor), choice(&president)];
+        assert_eq!(validate_choices(&contests, &ballot, &candidates), Ok(()));
+
+        let missing = vec![choice(&senator), choice(&president)];
+        assert!(matches!(
+            validate_choices(&contests, &missing, &candidates),
+            Err(BallotError::ContestMismatch { expected: CandidatePosition::Governor, .. })
+        ));
+
+        let extra = vec![choice(&senator), choice(&governor), choice(&president), choice(&president)];
+        assert_eq!(validate_choices(&contests, &extra, &candidates), Err(BallotError::ExtraChoices(1)));
+
+        let swapped = vec![
+            choice(&senator),
+            ContestChoice::candidate(CandidatePosition::Governor, president.id),
+            choice(&president),
+        ];
+        assert!(matches!(
+            validate_choices(&contests, &swapped, &candidates),
+            Err(BallotError::WrongPosition { .. })
+        ));
+        assert_eq!(validate_choices(&[], &ballot, &candidates), Err(BallotError::NoContests));
+    }
+
+    #[test]
+    fn test_votes_are_summed_per_contest() {
+        let president = candidate(13, CandidatePosition::President);
+        let senator = candidate(456, CandidatePosition::Senator);
+        let tally = BTreeMap::from([
+            (tally_key(&choice(&president)), 2),
+            (tally_key(&choice(&senator)), 2),
+            ("BRANCO".to_string(), 1),
+        ]);
+        let contests = votes_per_contest(&tally);
+        assert_eq!(contests.get("president"), Some(&2));
+        assert_eq!(contests.get("senator"), Some(&2));
+        assert_eq!(contests.get(""), Some(&1));
+    }
+
+    #[test]
+    fn test_blank_and_null_choices_have_their_own_counters() {
+        let president = candidate(13, CandidatePosition::President);
+        let senator = candidate(456, CandidatePosition::Senator);
+        let candidates = vec![president.clone(), senator.clone()];
+        let contests = contest_order(&candidates);
+
+        let ballot = vec![ContestChoice::blank(CandidatePosition::Senator), ContestChoice::null(CandidatePosition::President)];
+        assert_eq!(validate_choices(&contests, &ballot, &candidates), Ok(()));
+        assert_eq!(tally_key(&ballot[0]), "senator:BRANCO");
+        assert_eq!(tally_key(&ballot[1]), "president:NULO");
+
+        let named = vec![
+            ContestChoice { candidate_id: Some(senator.id), ..ContestChoice::blank(CandidatePosition::Senator) },
+            choice(&president),
+        ];
+        assert_eq!(
+            validate_choices(&contests, &named, &candidates),
+            Err(BallotError::UnexpectedCandidate(CandidatePosition::Senator))
+        );
+        let unnamed = vec![
+            choice(&senator),
+            ContestChoice { kind: ChoiceKind::Candidate, ..ContestChoice::null(CandidatePosition::President) },
+        ];
+        assert_eq!(
+            validate_choices(&contests, &unnamed, &candidates),
+            Err(BallotError::MissingCandidate(CandidatePosition::President))
+        );
+
+        assert_eq!(split_tally_key("senator:BRANCO"), (Some("senator"), Votable::Blank));
+        assert_eq!(split_tally_key("nulo"), (None, Votable::Null));
+        assert_eq!(split_tally_key("governor:13"), (Some("governor"), Votable::Candidate("13")));
+
+        // Choice serializado antes dos brancos e nulos
+        let legacy: ContestChoice = serde_json::from_value(serde_json::json!({
+            "position": "president",
+            "candidate_id": president.id,
+        })).unwrap();
+        assert_eq!(legacy, choice(&president));
+    }
+}
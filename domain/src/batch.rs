@@ -0,0 +1,223 @@
+//! Lotes de votos com sub-raiz Merkle assinada pela urna
+//!
+//! A cada envio a urna calcula a raiz Merkle dos códigos de rastreamento do
+//! lote e assina essa raiz com a própria chave. O backend guarda a sub-raiz
+//! e a registra na árvore principal do log transparente. Em uma contestação,
+//! a prova de inclusão na sub-raiz mostra que a urna emitiu o voto; a
+//! ausência no log principal aponta o backend, e a ausência no lote aponta
+//! a urna. O hash é o mesmo da árvore do log (RFC 6962 sobre hashes em
+//! hexadecimal), de modo que as duas árvores podem ser conferidas com o
+//! mesmo código.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::log_proof::verify_inclusion_path;
+
+/// Contexto da mensagem assinada, evita reaproveitar assinaturas de outros formatos
+pub const BATCH_SIGNATURE_CONTEXT: &str = "FORTIS-VOTE-BATCH-V1";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BatchError {
+    #[error("vote batch is empty")]
+    Empty,
+    #[error("tracking code {0} appears more than once in the batch")]
+    Duplicate(String),
+}
+
+/// Lote de votos enviado pela urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VoteBatch {
+    pub urna_id: String,
+    pub election_id: Uuid,
+    /// Sequência do lote na urna, sem lacunas, a partir de 1
+    pub sequence: u64,
+    /// Códigos de rastreamento na ordem das folhas
+    pub tracking_codes: Vec<String>,
+    pub merkle_root: String,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// Lote com a assinatura da urna (base64)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SignedVoteBatch {
+    pub batch: VoteBatch,
+    pub signature: String,
+}
+
+/// Prova de inclusão de um voto na sub-raiz do lote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BatchProof {
+    pub leaf_index: u64,
+    pub batch_size: u64,
+    pub path: Vec<String>,
+    pub merkle_root: String,
+}
+
+impl VoteBatch {
+    pub fn new(
+        urna_id: &str,
+        election_id: Uuid,
+        sequence: u64,
+        tracking_codes: Vec<String>,
+    ) -> Result<Self, BatchError> {
+        let mut seen = std::collections::HashSet::new();
+        if let Some(duplicate) = tracking_codes.iter().find(|code| !seen.insert(code.as_str())) {
+            return Err(BatchError::Duplicate(duplicate.clone()));
+        }
+        let merkle_root = merkle_root(&tracking_codes).ok_or(BatchError::Empty)?;
+
+        Ok(Self {
+            urna_id: urna_id.to_string(),
+            election_id,
+            sequence,
+            tracking_codes,
+            merkle_root,
+            closed_at: Utc::now(),
+        })
+    }
+
+    /// Mensagem assinada pela urna
+    pub fn signed_message(&self) -> Vec<u8> {
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}",
+            BATCH_SIGNATURE_CONTEXT,
+            self.urna_id,
+            self.election_id,
+            self.sequence,
+            self.tracking_codes.len(),
+            self.merkle_root,
+            self.closed_at.to_rfc3339(),
+        )
+        .into_bytes()
+    }
+
+    /// Confere a raiz declarada com os códigos do lote
+    pub fn verify_root(&self) -> bool {
+        merkle_root(&self.tracking_codes).as_deref() == Some(self.merkle_root.as_str())
+    }
+
+    /// Prova de que o código faz parte do lote assinado
+    pub fn inclusion_proof(&self, tracking_code: &str) -> Option<BatchProof> {
+        let index = self.tracking_codes.iter().position(|code| code == tracking_code)?;
+        let leaves: Vec<String> = self.tracking_codes.iter().map(|code| leaf_hash(code)).collect();
+        let mut path = Vec::new();
+        inclusion_path(index, &leaves, &mut path);
+
+        Some(BatchProof {
+            leaf_index: index as u64,
+            batch_size: leaves.len() as u64,
+            path,
+            merkle_root: self.merkle_root.clone(),
+        })
+    }
+}
+
+/// Raiz RFC 6962 dos códigos de rastreamento
+pub fn merkle_root(tracking_codes: &[String]) -> Option<String> {
+    let leaves: Vec<String> = tracking_codes.iter().map(|code| leaf_hash(code)).collect();
+    (!leaves.is_empty()).then(|| subtree_root(&leaves))
+}
+
+/// Verifica a prova sem o lote completo (RFC 9162, 2.1.3.2)
+pub fn verify_batch_proof(tracking_code: &str, proof: &BatchProof) -> bool {
+    verify_inclusion_path(
+        &leaf_hash(tracking_code),
+        proof.leaf_index,
+        proof.batch_size,
+        &proof.path,
+        &proof.merkle_root,
+    )
+}
+
+pub fn leaf_hash(data: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(data.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn node_hash(left: &str, right: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn subtree_root(leaves: &[String]) -> String {
+    if leaves.len() == 1 {
+        return leaves[0].clone();
+    }
+    let k = split_point(leaves.len());
+    node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+}
+
+/// PATH(m, D[n]) da RFC 6962
+fn inclusion_path(m: usize, leaves: &[String], path: &mut Vec<String>) {
+    if leaves.len() <= 1 {
+        return;
+    }
+    let k = split_point(leaves.len());
+    if m < k {
+        inclusion_path(m, &leaves[..k], path);
+        path.push(subtree_root(&leaves[k..]));
+    } else {
+        inclusion_path(m - k, &leaves[k..], path);
+        path.push(subtree_root(&leaves[..k]));
+    }
+}
+
+/// Maior potência de dois estritamente menor que `n`
+fn split_point(n: usize) -> usize {
+    1 << (usize::BITS - (n - 1).leading_zeros() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("CODE-{:04}", i)).collect()
+    }
+
+    #[test]
+    fn test_every_vote_has_a_valid_proof() {
+        for size in [1, 2, 3, 5, 8, 13] {
+            let batch = VoteBatch::new("urna-1", Uuid::new_v4(), 1, codes(size)).unwrap();
+            assert!(batch.verify_root());
+            for code in &batch.tracking_codes {
+                let proof = batch.inclusion_proof(code).unwrap();
+                assert!(verify_batch_proof(code, &proof), "size {} code {}", size, code);
+                assert!(!verify_batch_proof("OUTRO", &proof));
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_rejects_empty_and_duplicates_and_binds_sequence() {
+        let election_id = Uuid::new_v4();
+        assert_eq!(VoteBatch::new("urna-1", election_id, 1, vec![]).unwrap_err(), BatchError::Empty);
+        let duplicated = vec!["A".to_string(), "B".to_string(), "A".to_string()];
+        assert_eq!(
+            VoteBatch::new("urna-1", election_id, 1, duplicated).unwrap_err(),
+            BatchError::Duplicate("A".to_string())
+        );
+
+        let first = VoteBatch::new("urna-1", election_id, 1, codes(3)).unwrap();
+        let mut second = first.clone();
+        second.sequence = 2;
+        assert_eq!(first.merkle_root, second.merkle_root);
+        assert_ne!(first.signed_message(), second.signed_message());
+
+        let mut tampered = first.clone();
+        tampered.tracking_codes.pop();
+        assert!(!tampered.verify_root());
+    }
+}
@@ -0,0 +1,247 @@
+//! Templates de impressão digital
+//!
+//! A urna extrai as minúcias da imagem capturada e envia apenas o template,
+//! no formato ISO/IEC 19794-2:2005, nunca a imagem. O backend decodifica o
+//! mesmo formato e compara templates com `match_score`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Tolerâncias do pareamento de minúcias
+const MATCH_DISTANCE_PX: f32 = 12.0;
+const MATCH_ANGLE_DEG: f32 = 20.0;
+
+/// Tamanho do cabeçalho do registro e de cada minúcia
+const ISO_HEADER_LEN: usize = 24;
+const ISO_VIEW_HEADER_LEN: usize = 4;
+const ISO_MINUTIA_LEN: usize = 6;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("Invalid fingerprint template: {0}")]
+    Invalid(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MinutiaKind {
+    RidgeEnding,
+    Bifurcation,
+    Other,
+}
+
+/// Minúcia com ângulo em unidades ISO (360/256 graus)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Minutia {
+    pub x: u16,
+    pub y: u16,
+    pub angle: u8,
+    pub kind: MinutiaKind,
+    pub quality: u8,
+}
+
+impl Minutia {
+    pub fn angle_degrees(&self) -> f32 {
+        self.angle as f32 * 360.0 / 256.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FingerprintTemplate {
+    pub width: u16,
+    pub height: u16,
+    /// Resolução em pixels por centímetro
+    pub resolution_ppcm: u16,
+    /// Qualidade da captura (0-100)
+    pub quality: u8,
+    pub minutiae: Vec<Minutia>,
+}
+
+impl FingerprintTemplate {
+    /// Codifica no formato ISO/IEC 19794-2:2005 com uma única vista
+    pub fn to_iso_19794_2(&self) -> Vec<u8> {
+        let minutiae = &self.minutiae[..self.minutiae.len().min(u8::MAX as usize)];
+        let total_len = ISO_HEADER_LEN + ISO_VIEW_HEADER_LEN + minutiae.len() * ISO_MINUTIA_LEN + 2;
+
+        let mut out = Vec::with_capacity(total_len);
+        out.extend_from_slice(b"FMR\0");
+        out.extend_from_slice(b" 20\0");
+        out.extend_from_slice(&(total_len as u32).to_be_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(&self.width.to_be_bytes());
+        out.extend_from_slice(&self.height.to_be_bytes());
+        out.extend_from_slice(&self.resolution_ppcm.to_be_bytes());
+        out.extend_from_slice(&self.resolution_ppcm.to_be_bytes());
+        out.extend_from_slice(&[1, 0]);
+
+        // Posição do dedo desconhecida, vista 0, impressão ao vivo
+        out.extend_from_slice(&[0, 0, self.quality, minutiae.len() as u8]);
+        for m in minutiae {
+            let kind = match m.kind {
+                MinutiaKind::Other => 0u16,
+                MinutiaKind::RidgeEnding => 1,
+                MinutiaKind::Bifurcation => 2,
+            };
+            out.extend_from_slice(&((kind << 14) | (m.x & 0x3fff)).to_be_bytes());
+            out.extend_from_slice(&(m.y & 0x3fff).to_be_bytes());
+            out.push(m.angle);
+            out.push(m.quality);
+        }
+        out.extend_from_slice(&[0, 0]);
+        out
+    }
+
+    pub fn from_iso_19794_2(data: &[u8]) -> Result<Self, TemplateError> {
+        if data.len() < ISO_HEADER_LEN + ISO_VIEW_HEADER_LEN || &data[..4] != b"FMR\0" {
+            return Err(TemplateError::Invalid("missing FMR header"));
+        }
+        if &data[4..8] != b" 20\0" {
+            return Err(TemplateError::Invalid("unsupported version"));
+        }
+        let total_len = u32::from_be_bytes([data[8], data[9], data[10], data[11]]) as usize;
+        if total_len != data.len() {
+            return Err(TemplateError::Invalid("record length mismatch"));
+        }
+        let be16 = |i: usize| u16::from_be_bytes([data[i], data[i + 1]]);
+        if data[22] == 0 {
+            return Err(TemplateError::Invalid("no finger views"));
+        }
+
+        let view = ISO_HEADER_LEN;
+        let quality = data[view + 2];
+        let count = data[view + 3] as usize;
+        if data.len() < view + ISO_VIEW_HEADER_LEN + count * ISO_MINUTIA_LEN + 2 {
+            return Err(TemplateError::Invalid("truncated minutiae"));
+        }
+
+        let minutiae = (0..count)
+            .map(|i| {
+                let at = view + ISO_VIEW_HEADER_LEN + i * ISO_MINUTIA_LEN;
+                let word = be16(at);
+                Minutia {
+                    x: word & 0x3fff,
+                    y: be16(at + 2) & 0x3fff,
+                    angle: data[at + 4],
+                    kind: match word >> 14 {
+                        1 => MinutiaKind::RidgeEnding,
+                        2 => MinutiaKind::Bifurcation,
+                        _ => MinutiaKind::Other,
+                    },
+                    quality: data[at + 5],
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            width: be16(14),
+            height: be16(16),
+            resolution_ppcm: be16(18),
+            quality,
+            minutiae,
+        })
+    }
+
+    /// SHA-256 do registro ISO, usado em auditoria e integridade
+    pub fn fingerprint_hash(&self) -> String {
+        hex::encode(Sha256::digest(self.to_iso_19794_2()))
+    }
+
+    /// Similaridade entre dois templates (0.0 a 1.0)
+    ///
+    /// Cada par de minúcias é testado como âncora de alinhamento (rotação
+    /// e translação); vale o alinhamento com mais minúcias pareadas.
+    pub fn match_score(&self, other: &FingerprintTemplate) -> f32 {
+        if self.minutiae.is_empty() || other.minutiae.is_empty() {
+            return 0.0;
+        }
+
+        let mut best = 0usize;
+        for a in &self.minutiae {
+            for b in &other.minutiae {
+                let rotation = (b.angle_degrees() - a.angle_degrees()).to_radians();
+                let (sin, cos) = rotation.sin_cos();
+                let transform = |m: &Minutia| {
+                    let dx = m.x as f32 - a.x as f32;
+                    let dy = m.y as f32 - a.y as f32;
+                    (
+                        b.x as f32 + dx * cos - dy * sin,
+                        b.y as f32 + dx * sin + dy * cos,
+                        m.angle_degrees() + rotation.to_degrees(),
+                    )
+                };
+
+                let mut used = vec![false; other.minutiae.len()];
+                let mut paired = 0;
+                for m in &self.minutiae {
+                    let (x, y, angle) = transform(m);
+                    let candidate = other.minutiae.iter().enumerate().find(|(j, o)| {
+                        !used[*j]
+                            && ((o.x as f32 - x).powi(2) + (o.y as f32 - y).powi(2)).sqrt() <= MATCH_DISTANCE_PX
+                            && angle_difference(o.angle_degrees(), angle) <= MATCH_ANGLE_DEG
+                    });
+                    if let Some((j, _)) = candidate {
+                        used[j] = true;
+                        paired += 1;
+                    }
+                }
+                best = best.max(paired);
+            }
+        }
+
+        (best * best) as f32 / (self.minutiae.len() * other.minutiae.len()) as f32
+    }
+}
+
+fn angle_difference(a: f32, b: f32) -> f32 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(offset: u16) -> FingerprintTemplate {
+        let points = [(40, 52, 10), (88, 61, 64), (120, 140, 200), (60, 180, 33), (150, 90, 128), (101, 210, 90)];
+        FingerprintTemplate {
+            width: 256,
+            height: 360,
+            resolution_ppcm: 197,
+            quality: 80,
+            minutiae: points
+                .iter()
+                .enumerate()
+                .map(|(i, (x, y, angle))| Minutia {
+                    x: x + offset,
+                    y: y + offset,
+                    angle: *angle,
+                    kind: if i % 2 == 0 { MinutiaKind::RidgeEnding } else { MinutiaKind::Bifurcation },
+                    quality: 60,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_iso_record_roundtrip() {
+        let original = template(0);
+        let record = original.to_iso_19794_2();
+        assert_eq!(&record[..4], b"FMR\0");
+        assert_eq!(FingerprintTemplate::from_iso_19794_2(&record).unwrap(), original);
+        assert!(FingerprintTemplate::from_iso_19794_2(&record[..record.len() - 3]).is_err());
+    }
+
+    #[test]
+    fn test_match_score_tolerates_translation() {
+        let enrolled = template(0);
+        assert!(enrolled.match_score(&template(15)) > 0.9);
+
+        let mut other = template(0);
+        for (i, m) in other.minutiae.iter_mut().enumerate() {
+            m.x = (i as u16 * 37) % 200;
+            m.y = 300 - i as u16 * 41;
+            m.angle = m.angle.wrapping_add(97);
+        }
+        assert!(enrolled.match_score(&other) < 0.3);
+    }
+}
@@ -0,0 +1,104 @@
+//! Candidatos e cargos em disputa
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Candidate {
+    pub id: Uuid,
+    pub election_id: Uuid,
+    pub name: String,
+    pub party: String,
+    pub number: u32,
+    pub position: CandidatePosition,
+    #[serde(default)]
+    pub coalition: Option<String>,
+    /// UF ou código do município; ausente para cargos nacionais
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub photo_url: Option<String>,
+    #[serde(default)]
+    pub photo_sha256: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Cargo disputado pelo candidato
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CandidatePosition {
+    President,
+    Governor,
+    Senator,
+    FederalDeputy,
+    StateDeputy,
+    Mayor,
+    Councilor,
+}
+
+impl CandidatePosition {
+    /// Quantidade de dígitos do número na urna
+    pub fn number_digits(&self) -> u32 {
+        match self {
+            CandidatePosition::President
+            | CandidatePosition::Governor
+            | CandidatePosition::Mayor => 2,
+            CandidatePosition::Senator => 3,
+            CandidatePosition::FederalDeputy => 4,
+            CandidatePosition::StateDeputy | CandidatePosition::Councilor => 5,
+        }
+    }
+
+    /// Cargos disputados em todo o país
+    pub fn is_national(&self) -> bool {
+        matches!(self, CandidatePosition::President)
+    }
+
+    /// Ordem de votação na urna: proporcionais antes dos majoritários
+    pub fn ballot_order(&self) -> u32 {
+        match self {
+            CandidatePosition::FederalDeputy => 1,
+            CandidatePosition::StateDeputy => 2,
+            CandidatePosition::Senator => 3,
+            CandidatePosition::Governor => 4,
+            CandidatePosition::President => 5,
+            CandidatePosition::Councilor => 6,
+            CandidatePosition::Mayor => 7,
+        }
+    }
+
+    /// Identificador do cargo, igual ao da serialização
+    pub fn code(&self) -> &'static str {
+        match self {
+            CandidatePosition::President => "president",
+            CandidatePosition::Governor => "governor",
+            CandidatePosition::Senator => "senator",
+            CandidatePosition::FederalDeputy => "federal_deputy",
+            CandidatePosition::StateDeputy => "state_deputy",
+            CandidatePosition::Mayor => "mayor",
+            CandidatePosition::Councilor => "councilor",
+        }
+    }
+
+    /// Título do cargo na tela da urna
+    pub fn title(&self) -> &'static str {
+        match self {
+            CandidatePosition::President => "PRESIDENTE",
+            CandidatePosition::Governor => "GOVERNADOR",
+            CandidatePosition::Senator => "SENADOR",
+            CandidatePosition::FederalDeputy => "DEPUTADO FEDERAL",
+            CandidatePosition::StateDeputy => "DEPUTADO ESTADUAL",
+            CandidatePosition::Mayor => "PREFEITO",
+            CandidatePosition::Councilor => "VEREADOR",
+        }
+    }
+
+    /// Verifica se o número tem a quantidade de dígitos do cargo
+    pub fn accepts_number(&self, number: u32) -> bool {
+        let digits = self.number_digits();
+        number >= 10_u32.pow(digits - 1) && number < 10_u32.pow(digits)
+    }
+}
@@ -0,0 +1,182 @@
+//! Descomissionamento da urna ao fim do ciclo eleitoral
+//!
+//! Antes de apagar a urna, todos os votos precisam ter saído da fila com a
+//! prova de inclusão conferida. A urna reúne essas provas no pacote final,
+//! assinado com a sua chave e gravado na mídia removível, apaga chaves e
+//! dados locais e envia à gestão da frota o relatório assinado, que aponta
+//! para o pacote pelo hash.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::log_proof::VoteInclusion;
+
+/// Contexto da mensagem assinada do relatório
+pub const DECOMMISSION_SIGNATURE_CONTEXT: &str = "FORTIS-URNA-DECOMMISSION-V1";
+
+/// Nome do pacote final de provas na mídia
+pub const PROOF_BUNDLE_FILE: &str = "fortis_provas_finais.json";
+
+/// Nome do relatório de descomissionamento na mídia
+pub const DECOMMISSION_REPORT_FILE: &str = "fortis_descomissionamento.json";
+
+/// Prova de inclusão conferida pela urna para um voto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VoteProof {
+    pub vote_id: Uuid,
+    pub election_id: Uuid,
+    pub tracking_code: Option<String>,
+    pub inclusion: VoteInclusion,
+}
+
+/// Pacote final de provas da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FinalProofBundle {
+    pub urna_id: String,
+    /// Último lote assinado em cada eleição
+    pub batch_sequences: BTreeMap<Uuid, u64>,
+    /// Provas em ordem de posição no log
+    pub proofs: Vec<VoteProof>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Pacote com o hash e a assinatura da urna (base64) sobre o JSON do pacote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SignedProofBundle {
+    pub bundle: FinalProofBundle,
+    pub bundle_sha256: String,
+    pub signature: String,
+}
+
+impl FinalProofBundle {
+    /// Bytes assinados pela urna
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("proof bundle serializes")
+    }
+
+    pub fn sha256(&self) -> String {
+        hex::encode(Sha256::digest(self.canonical_bytes()))
+    }
+
+    pub fn vote_count(&self) -> u64 {
+        self.proofs.len() as u64
+    }
+}
+
+impl SignedProofBundle {
+    /// Confere o hash declarado com o conteúdo lido da mídia
+    pub fn verify_digest(&self) -> bool {
+        self.bundle.sha256() == self.bundle_sha256.to_lowercase()
+    }
+}
+
+/// Relatório de conclusão enviado à gestão da frota
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DecommissionReport {
+    pub urna_id: String,
+    pub bundle_sha256: String,
+    pub vote_count: u64,
+    pub batch_sequences: BTreeMap<Uuid, u64>,
+    /// Chaves e áreas de dados apagadas
+    pub erased: Vec<String>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Relatório com a assinatura da urna (base64)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SignedDecommissionReport {
+    pub report: DecommissionReport,
+    pub signature: String,
+}
+
+impl DecommissionReport {
+    pub fn new(signed: &SignedProofBundle, erased: Vec<String>) -> Self {
+        Self {
+            urna_id: signed.bundle.urna_id.clone(),
+            bundle_sha256: signed.bundle_sha256.clone(),
+            vote_count: signed.bundle.vote_count(),
+            batch_sequences: signed.bundle.batch_sequences.clone(),
+            erased,
+            completed_at: Utc::now(),
+        }
+    }
+
+    /// Mensagem assinada pela urna
+    pub fn signed_message(&self) -> Vec<u8> {
+        let sequences: Vec<String> = self.batch_sequences.iter()
+            .map(|(election_id, sequence)| format!("{}:{}", election_id, sequence))
+            .collect();
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}",
+            DECOMMISSION_SIGNATURE_CONTEXT,
+            self.urna_id,
+            self.bundle_sha256,
+            self.vote_count,
+            sequences.join(","),
+            self.erased.join(","),
+            self.completed_at.to_rfc3339(),
+        )
+        .into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_proof::{LogInclusionProof, TreeHead};
+
+    fn bundle() -> SignedProofBundle {
+        let election_id = Uuid::new_v4();
+        let bundle = FinalProofBundle {
+            urna_id: "URNA-1".to_string(),
+            batch_sequences: BTreeMap::from([(election_id, 2)]),
+            proofs: vec![VoteProof {
+                vote_id: Uuid::new_v4(),
+                election_id,
+                tracking_code: Some("ABCD-EFGH".to_string()),
+                inclusion: VoteInclusion {
+                    log_index: 7,
+                    event_hash: "00".repeat(32),
+                    proof: LogInclusionProof { leaf_index: 7, path: Vec::new(), root_hash: "11".repeat(32), tree_size: 8 },
+                    tree_head: TreeHead {
+                        tree_size: 8,
+                        root_hash: "11".repeat(32),
+                        timestamp: Utc::now(),
+                        log_id: "22".repeat(32),
+                        signature: String::new(),
+                    },
+                },
+            }],
+            generated_at: Utc::now(),
+        };
+        SignedProofBundle { bundle_sha256: bundle.sha256(), bundle, signature: String::new() }
+    }
+
+    #[test]
+    fn test_bundle_digest_detects_changes() {
+        let mut signed = bundle();
+        assert!(signed.verify_digest());
+        signed.bundle.proofs.clear();
+        assert!(!signed.verify_digest());
+    }
+
+    #[test]
+    fn test_report_message_covers_bundle_and_erasure() {
+        let signed = bundle();
+        let report = DecommissionReport::new(&signed, vec!["outbox".to_string()]);
+        assert_eq!(report.vote_count, 1);
+
+        let mut changed = report.clone();
+        changed.erased.push("tpm_sealing_key".to_string());
+        assert_ne!(report.signed_message(), changed.signed_message());
+        assert!(String::from_utf8(report.signed_message()).unwrap().starts_with(DECOMMISSION_SIGNATURE_CONTEXT));
+    }
+}
@@ -0,0 +1,246 @@
+//! Catálogo de códigos de falha da urna
+//!
+//! Toda falha da urna tem um código estável, com categoria (hardware,
+//! biometria, sincronização, criptografia, configuração, atendimento),
+//! severidade e o procedimento de campo do runbook. A urna conta as
+//! ocorrências de cada código e as envia no heartbeat; o backend agrega as
+//! contagens por modelo de equipamento, o que orienta a manutenção da frota
+//! sem depender de logs em texto livre.
+//!
+//! Os mesmos códigos identificam as mensagens ao eleitor no catálogo de
+//! mensagens publicado pelo backend.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Códigos de falha da urna
+pub mod codes {
+    // Hardware
+    pub const PRINTER_PAPER_OUT: &str = "PRINTER_PAPER_OUT";
+    pub const PRINTER_NOT_READY: &str = "PRINTER_NOT_READY";
+    pub const PRINTER_OFFLINE: &str = "PRINTER_OFFLINE";
+    pub const STORAGE_INSUFFICIENT: &str = "STORAGE_INSUFFICIENT";
+    pub const FEEDBACK_CHECK_FAILED: &str = "FEEDBACK_CHECK_FAILED";
+
+    // Biometria
+    pub const BIOMETRIC_NOT_RECOGNIZED: &str = "BIOMETRIC_NOT_RECOGNIZED";
+    pub const FINGERPRINT_TIMEOUT: &str = "FINGERPRINT_TIMEOUT";
+    pub const FINGERPRINT_LOW_QUALITY: &str = "FINGERPRINT_LOW_QUALITY";
+    pub const FINGERPRINT_LIVENESS_FAILED: &str = "FINGERPRINT_LIVENESS_FAILED";
+
+    // Sincronização
+    pub const SYNC_BACKEND_UNREACHABLE: &str = "SYNC_BACKEND_UNREACHABLE";
+    pub const SYNC_VOTE_REJECTED: &str = "SYNC_VOTE_REJECTED";
+    pub const SYNC_INCLUSION_PROOF_INVALID: &str = "SYNC_INCLUSION_PROOF_INVALID";
+    pub const SYNC_BATCH_UPLOAD_FAILED: &str = "SYNC_BATCH_UPLOAD_FAILED";
+
+    // Criptografia
+    pub const ATTESTATION_FAILED: &str = "ATTESTATION_FAILED";
+    pub const CONFIG_SEAL_VIOLATION: &str = "CONFIG_SEAL_VIOLATION";
+    pub const CRYPTO_KEY_INTEGRITY_FAILED: &str = "CRYPTO_KEY_INTEGRITY_FAILED";
+    pub const CRYPTO_ENCRYPTION_FAILED: &str = "CRYPTO_ENCRYPTION_FAILED";
+
+    // Configuração
+    pub const CONFIG_SEALED: &str = "CONFIG_SEALED";
+
+    // Atendimento ao eleitor
+    pub const VOTER_NOT_ELIGIBLE: &str = "VOTER_NOT_ELIGIBLE";
+    pub const VOTER_ALREADY_VOTED: &str = "VOTER_ALREADY_VOTED";
+    pub const VOTER_NOT_ADMITTED: &str = "VOTER_NOT_ADMITTED";
+    pub const ELECTION_NOT_ACTIVE: &str = "ELECTION_NOT_ACTIVE";
+    pub const CANDIDATE_NOT_FOUND: &str = "CANDIDATE_NOT_FOUND";
+    pub const RECEIPT_ALREADY_PRINTED: &str = "RECEIPT_ALREADY_PRINTED";
+    pub const CERTIFICATE_INVALID: &str = "CERTIFICATE_INVALID";
+    pub const CERTIFICATE_MISMATCH: &str = "CERTIFICATE_MISMATCH";
+
+    /// Falha sem código associado
+    pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FaultCategory {
+    Hardware,
+    Biometric,
+    Sync,
+    Crypto,
+    Configuration,
+    /// Situações do atendimento, não defeitos da urna
+    Voter,
+    Internal,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FaultSeverity {
+    /// Esperada na operação; só a frequência anormal interessa
+    Info,
+    /// A urna segue votando com restrição
+    Warning,
+    /// A urna não consegue receber votos sem intervenção
+    Critical,
+}
+
+/// Entrada do catálogo
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FaultCode {
+    pub code: &'static str,
+    pub category: FaultCategory,
+    pub severity: FaultSeverity,
+    pub description: &'static str,
+    /// Passos do runbook, na ordem
+    pub runbook: &'static [&'static str],
+}
+
+/// Ocorrências de um código desde o último heartbeat aceito
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FaultReport {
+    pub code: String,
+    pub count: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+const fn fault(
+    code: &'static str,
+    category: FaultCategory,
+    severity: FaultSeverity,
+    description: &'static str,
+    runbook: &'static [&'static str],
+) -> FaultCode {
+    FaultCode { code, category, severity, description, runbook }
+}
+
+use FaultCategory::*;
+use FaultSeverity::*;
+
+/// Catálogo completo, agrupado por categoria
+pub const FAULT_CODES: &[FaultCode] = &[
+    fault(codes::PRINTER_PAPER_OUT, Hardware, Warning, "Impressora de comprovantes sem papel", &[
+        "Abrir a tampa e trocar a bobina térmica",
+        "Fechar a tampa e conferir a impressão de teste no terminal do mesário",
+    ]),
+    fault(codes::PRINTER_NOT_READY, Hardware, Warning, "Impressora com tampa aberta ou em erro", &[
+        "Fechar a tampa da impressora",
+        "Persistindo, desligar e religar a impressora pelo terminal do mesário",
+    ]),
+    fault(codes::PRINTER_OFFLINE, Hardware, Critical, "Impressora sem resposta", &[
+        "Conferir cabo e alimentação da impressora",
+        "Reiniciar a urna pelo terminal do mesário",
+        "Persistindo, substituir a impressora pela de contingência",
+    ]),
+    fault(codes::STORAGE_INSUFFICIENT, Hardware, Critical, "Espaço em disco abaixo do mínimo para a votação", &[
+        "Conferir no terminal do mesário o relatório de espaço",
+        "Acionar o suporte da zona para substituir a urna",
+    ]),
+    fault(codes::FEEDBACK_CHECK_FAILED, Hardware, Warning, "Autoteste de som ou tela reprovado", &[
+        "Conferir o alto-falante e a conexão da tela",
+        "Refazer o autoteste; o mesário pode liberar a abertura com o PIN",
+    ]),
+    fault(codes::BIOMETRIC_NOT_RECOGNIZED, Biometric, Info, "Digital ou face do eleitor não reconhecida", &[
+        "Orientar o eleitor a limpar o dedo e tentar outro dedo cadastrado",
+        "Frequência alta na urna: limpar o sensor e acionar o suporte",
+    ]),
+    fault(codes::FINGERPRINT_TIMEOUT, Biometric, Info, "Leitor de digitais sem captura no tempo limite", &[
+        "Orientar o eleitor a manter o dedo sobre o sensor",
+        "Frequência alta na urna: conferir a conexão do leitor",
+    ]),
+    fault(codes::FINGERPRINT_LOW_QUALITY, Biometric, Info, "Captura de digital abaixo da qualidade mínima", &[
+        "Limpar o sensor do leitor de digitais",
+        "Frequência alta no modelo: revisar o limiar de qualidade do leitor",
+    ]),
+    fault(codes::FINGERPRINT_LIVENESS_FAILED, Biometric, Warning, "Prova de vida da digital reprovada", &[
+        "Chamar o presidente da mesa para identificar o eleitor pelo documento",
+        "Registrar a ocorrência na ata da mesa",
+    ]),
+    fault(codes::SYNC_BACKEND_UNREACHABLE, Sync, Warning, "Nenhum backend respondeu à sincronização", &[
+        "Conferir o link de dados da seção",
+        "Os votos ficam na fila da urna e são reenviados automaticamente",
+    ]),
+    fault(codes::SYNC_VOTE_REJECTED, Sync, Warning, "Voto recusado pelo backend", &[
+        "Consultar no backend o motivo da recusa pelo identificador do voto",
+        "O voto permanece na fila da urna até a reconciliação",
+    ]),
+    fault(codes::SYNC_INCLUSION_PROOF_INVALID, Sync, Critical, "Prova de inclusão no log transparente não confere", &[
+        "Abrir incidente de integridade no centro de operações",
+        "Comparar a raiz do log com a dos nós de auditoria",
+    ]),
+    fault(codes::SYNC_BATCH_UPLOAD_FAILED, Sync, Warning, "Lote assinado de votos não enviado", &[
+        "O lote fica gravado e é reenviado na próxima sincronização",
+        "Persistindo, conferir o certificado de dispositivo da urna",
+    ]),
+    fault(codes::ATTESTATION_FAILED, Crypto, Critical, "Medições do boot não conferem com a linha de base", &[
+        "Não abrir a votação nesta urna",
+        "Substituir a urna e encaminhar a original para perícia",
+    ]),
+    fault(codes::CONFIG_SEAL_VIOLATION, Crypto, Critical, "Configuração lacrada alterada ou lacre do TPM inválido", &[
+        "Não abrir a votação nesta urna",
+        "Substituir a urna e encaminhar a original para perícia",
+    ]),
+    fault(codes::CRYPTO_KEY_INTEGRITY_FAILED, Crypto, Critical, "Chaves de cifragem dos votos não passaram na verificação", &[
+        "Reiniciar a urna pelo terminal do mesário",
+        "Persistindo, substituir a urna",
+    ]),
+    fault(codes::CRYPTO_ENCRYPTION_FAILED, Crypto, Critical, "Falha ao cifrar o voto", &[
+        "Reiniciar a urna pelo terminal do mesário",
+        "Persistindo, substituir a urna",
+    ]),
+    fault(codes::CONFIG_SEALED, Configuration, Info, "Alteração de configuração com a urna já lacrada", &[
+        "Nenhuma ação na seção; alterações só no cartório eleitoral",
+    ]),
+    fault(codes::VOTER_NOT_ELIGIBLE, Voter, Info, "Eleitor fora do cadastro da seção", &[
+        "Orientar o eleitor a procurar a seção correta",
+    ]),
+    fault(codes::VOTER_ALREADY_VOTED, Voter, Info, "Eleitor já votou", &[
+        "Registrar a ocorrência na ata da mesa",
+    ]),
+    fault(codes::VOTER_NOT_ADMITTED, Voter, Info, "Eleitor identificado diferente do liberado pelo mesário", &[
+        "Refazer a liberação no terminal do mesário",
+    ]),
+    fault(codes::ELECTION_NOT_ACTIVE, Voter, Info, "Votação fora do horário ou não aberta", &[
+        "Conferir a abertura da sessão no terminal do mesário",
+    ]),
+    fault(codes::CANDIDATE_NOT_FOUND, Voter, Info, "Número digitado sem candidato", &[
+        "Nenhuma ação; o eleitor pode corrigir ou votar nulo",
+    ]),
+    fault(codes::RECEIPT_ALREADY_PRINTED, Voter, Info, "Segunda via de comprovante recusada", &[
+        "Nenhuma ação; o comprovante tem via única",
+    ]),
+    fault(codes::CERTIFICATE_INVALID, Voter, Info, "Certificado do eleitor expirado, revogado ou ilegível", &[
+        "Identificar o eleitor pela biometria ou pelo documento",
+    ]),
+    fault(codes::CERTIFICATE_MISMATCH, Voter, Warning, "Certificado de outro eleitor", &[
+        "Chamar o presidente da mesa para identificar o eleitor pelo documento",
+        "Registrar a ocorrência na ata da mesa",
+    ]),
+    fault(codes::INTERNAL_ERROR, Internal, Warning, "Falha sem código associado", &[
+        "Coletar o log de auditoria da urna",
+        "Frequência alta no modelo: abrir chamado para a equipe da aplicação",
+    ]),
+];
+
+/// Entrada do catálogo para o código
+pub fn fault_code(code: &str) -> Option<&'static FaultCode> {
+    FAULT_CODES.iter().find(|fault| fault.code == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_catalog_codes_are_unique_and_have_runbook() {
+        let mut seen = HashSet::new();
+        for fault in FAULT_CODES {
+            assert!(seen.insert(fault.code), "{} duplicado", fault.code);
+            assert!(!fault.runbook.is_empty(), "{} sem runbook", fault.code);
+        }
+        assert_eq!(fault_code(codes::PRINTER_PAPER_OUT).unwrap().category, FaultCategory::Hardware);
+        assert_eq!(fault_code(codes::SYNC_INCLUSION_PROOF_INVALID).unwrap().severity, FaultSeverity::Critical);
+        assert!(fault_code("NO_SUCH_CODE").is_none());
+    }
+}
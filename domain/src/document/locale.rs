@@ -0,0 +1,270 @@
+//! Convenções brasileiras de números e datas
+//!
+//! Milhar com ponto e decimal com vírgula (`1.234.567,89`), datas
+//! `dd/mm/aaaa` e horas `17h05` no horário de Brasília (UTC−3, sem horário
+//! de verão desde 2019). As atas trazem as quantidades também por extenso,
+//! entre parênteses, e a data no formato "aos dezesseis dias do mês de…".
+
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+
+/// Deslocamento do horário de Brasília
+pub const BRASILIA_OFFSET_SECONDS: i32 = -3 * 3600;
+
+const MONTHS: [&str; 12] = [
+    "janeiro", "fevereiro", "março", "abril", "maio", "junho",
+    "julho", "agosto", "setembro", "outubro", "novembro", "dezembro",
+];
+
+const UNITS: [&str; 20] = [
+    "zero", "um", "dois", "três", "quatro", "cinco", "seis", "sete", "oito", "nove",
+    "dez", "onze", "doze", "treze", "quatorze", "quinze", "dezesseis", "dezessete", "dezoito", "dezenove",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "vinte", "trinta", "quarenta", "cinquenta", "sessenta", "setenta", "oitenta", "noventa",
+];
+
+const HUNDREDS: [&str; 10] = [
+    "", "cento", "duzentos", "trezentos", "quatrocentos", "quinhentos",
+    "seiscentos", "setecentos", "oitocentos", "novecentos",
+];
+
+const HUNDREDS_FEMININE: [&str; 10] = [
+    "", "cento", "duzentas", "trezentas", "quatrocentas", "quinhentas",
+    "seiscentas", "setecentas", "oitocentas", "novecentas",
+];
+
+/// Escalas acima do milhar: (singular, plural)
+const SCALES: [(&str, &str); 4] = [
+    ("mil", "mil"),
+    ("milhão", "milhões"),
+    ("bilhão", "bilhões"),
+    ("trilhão", "trilhões"),
+];
+
+/// Momento no horário de Brasília
+pub fn brasilia(moment: DateTime<Utc>) -> DateTime<FixedOffset> {
+    let offset = FixedOffset::east_opt(BRASILIA_OFFSET_SECONDS).expect("Brasília offset is valid");
+    moment.with_timezone(&offset)
+}
+
+/// Inteiro com separador de milhar (`1.234.567`)
+pub fn format_integer(value: u64) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push('.');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Número com vírgula decimal e separador de milhar (`1.234,57`)
+pub fn format_decimal(value: f64, decimals: usize) -> String {
+    let rounded = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = rounded.split_once('.').unwrap_or((&rounded, ""));
+    let mut formatted = String::new();
+    // Sem "-0,00" para valores que arredondam para zero
+    if value < 0.0 && rounded.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        formatted.push('-');
+    }
+    formatted.push_str(&format_integer(integer.parse().unwrap_or(0)));
+    if !fraction.is_empty() {
+        formatted.push(',');
+        formatted.push_str(fraction);
+    }
+    formatted
+}
+
+/// Razão como percentual (`0.8345` → `83,45%`)
+pub fn format_percent(ratio: f64, decimals: usize) -> String {
+    format!("{}%", format_decimal(ratio * 100.0, decimals))
+}
+
+/// Data no horário de Brasília (`16/10/2026`)
+pub fn format_date(moment: DateTime<Utc>) -> String {
+    brasilia(moment).format("%d/%m/%Y").to_string()
+}
+
+/// Data e hora no horário de Brasília (`16/10/2026 17:05:09`)
+pub fn format_datetime(moment: DateTime<Utc>) -> String {
+    brasilia(moment).format("%d/%m/%Y %H:%M:%S").to_string()
+}
+
+/// Hora das atas (`17h05`)
+pub fn format_hour(moment: DateTime<Utc>) -> String {
+    brasilia(moment).format("%Hh%M").to_string()
+}
+
+/// Data por extenso, com ordinal no primeiro dia (`1º de outubro de 2026`)
+pub fn format_date_long(moment: DateTime<Utc>) -> String {
+    let local = brasilia(moment);
+    let day = if local.day() == 1 { "1º".to_string() } else { local.day().to_string() };
+    format!("{} de {} de {}", day, MONTHS[local.month0() as usize], local.year())
+}
+
+/// Abertura das atas (`Aos dezesseis dias do mês de outubro do ano de dois mil e vinte e seis`)
+pub fn minutes_date(moment: DateTime<Utc>) -> String {
+    let local = brasilia(moment);
+    let day = match local.day() {
+        1 => "Ao primeiro dia".to_string(),
+        day => format!("Aos {} dias", integer_in_words(day as u64)),
+    };
+    format!(
+        "{} do mês de {} do ano de {}",
+        day,
+        MONTHS[local.month0() as usize],
+        integer_in_words(local.year().max(0) as u64),
+    )
+}
+
+/// Quantidade com o extenso entre parênteses (`250 (duzentos e cinquenta)`)
+pub fn format_count(value: u64) -> String {
+    format!("{} ({})", format_integer(value), integer_in_words(value))
+}
+
+/// Quantidade de substantivo feminino (`1.032 (mil e trinta e duas)`)
+pub fn format_count_feminine(value: u64) -> String {
+    format!("{} ({})", format_integer(value), integer_in_words_feminine(value))
+}
+
+/// Inteiro por extenso, no masculino (`1.234` → `mil duzentos e trinta e quatro`)
+pub fn integer_in_words(value: u64) -> String {
+    in_words(value, false)
+}
+
+/// Inteiro por extenso, no feminino (`2.202` → `duas mil duzentas e duas`)
+///
+/// Milhões e acima concordam com a escala, que é masculina (`dois milhões`).
+pub fn integer_in_words_feminine(value: u64) -> String {
+    in_words(value, true)
+}
+
+fn in_words(value: u64, feminine: bool) -> String {
+    if value == 0 {
+        return UNITS[0].to_string();
+    }
+
+    // Grupos de três dígitos não nulos, do mais alto ao mais baixo: (valor, escala)
+    let mut groups = Vec::new();
+    let mut remaining = value;
+    let mut scale = 0;
+    while remaining > 0 {
+        let group = remaining % 1000;
+        if group > 0 {
+            groups.push((group, scale));
+        }
+        remaining /= 1000;
+        scale += 1;
+    }
+    groups.reverse();
+
+    let mut words = String::new();
+    for (index, (group, scale)) in groups.iter().enumerate() {
+        if index > 0 {
+            let last = index == groups.len() - 1;
+            // "mil e cem", "um milhão e duzentos mil", "mil duzentos e trinta"
+            words.push_str(if last && (*group < 100 || group % 100 == 0) {
+                " e "
+            } else if groups[index - 1].1 == 1 {
+                " "
+            } else {
+                ", "
+            });
+        }
+        let feminine = feminine && *scale < 2;
+        match scale {
+            0 => words.push_str(&hundreds_in_words(*group, feminine)),
+            1 if *group == 1 => words.push_str(SCALES[0].0),
+            _ => {
+                let (singular, plural) = SCALES[(scale - 1).min(SCALES.len() - 1)];
+                words.push_str(&hundreds_in_words(*group, feminine));
+                words.push(' ');
+                words.push_str(if *group == 1 { singular } else { plural });
+            }
+        }
+    }
+    words
+}
+
+/// Extenso de 1 a 999
+fn hundreds_in_words(value: u64, feminine: bool) -> String {
+    if value == 100 {
+        return "cem".to_string();
+    }
+    let unit = |digit: u64| match (feminine, digit) {
+        (true, 1) => "uma",
+        (true, 2) => "duas",
+        _ => UNITS[digit as usize],
+    };
+    let mut parts = Vec::new();
+    if value >= 100 {
+        let hundreds = if feminine { &HUNDREDS_FEMININE } else { &HUNDREDS };
+        parts.push(hundreds[(value / 100) as usize]);
+    }
+    let rest = value % 100;
+    if rest >= 20 {
+        parts.push(TENS[(rest / 10) as usize]);
+        if !rest.is_multiple_of(10) {
+            parts.push(unit(rest % 10));
+        }
+    } else if rest > 0 {
+        parts.push(unit(rest));
+    }
+    parts.join(" e ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_numbers_use_brazilian_separators() {
+        assert_eq!(format_integer(0), "0");
+        assert_eq!(format_integer(999), "999");
+        assert_eq!(format_integer(1_234_567), "1.234.567");
+        assert_eq!(format_decimal(1234.567, 2), "1.234,57");
+        assert_eq!(format_decimal(-0.001, 2), "0,00");
+        assert_eq!(format_decimal(-1500.0, 1), "-1.500,0");
+        assert_eq!(format_percent(0.8345, 2), "83,45%");
+    }
+
+    #[test]
+    fn test_integers_in_words() {
+        let cases = [
+            (1, "um"),
+            (16, "dezesseis"),
+            (100, "cem"),
+            (101, "cento e um"),
+            (250, "duzentos e cinquenta"),
+            (1_000, "mil"),
+            (1_100, "mil e cem"),
+            (1_234, "mil duzentos e trinta e quatro"),
+            (2_026, "dois mil e vinte e seis"),
+            (1_200_000, "um milhão e duzentos mil"),
+            (2_000_500, "dois milhões e quinhentos"),
+            (1_234_567, "um milhão, duzentos e trinta e quatro mil quinhentos e sessenta e sete"),
+        ];
+        for (value, words) in cases {
+            assert_eq!(integer_in_words(value), words, "{}", value);
+        }
+
+        assert_eq!(integer_in_words_feminine(1), "uma");
+        assert_eq!(integer_in_words_feminine(1_032), "mil e trinta e duas");
+        assert_eq!(integer_in_words_feminine(2_202), "duas mil duzentas e duas");
+        assert_eq!(integer_in_words_feminine(2_000_001), "dois milhões e uma");
+    }
+
+    #[test]
+    fn test_dates_in_brasilia_time() {
+        // 02:30 UTC ainda é o dia anterior em Brasília
+        let moment = Utc.with_ymd_and_hms(2026, 10, 2, 2, 30, 0).unwrap();
+        assert_eq!(format_date(moment), "01/10/2026");
+        assert_eq!(format_hour(moment), "23h30");
+        assert_eq!(format_date_long(moment), "1º de outubro de 2026");
+        assert_eq!(minutes_date(moment), "Ao primeiro dia do mês de outubro do ano de dois mil e vinte e seis");
+    }
+}
@@ -0,0 +1,345 @@
+//! Documentos oficiais em texto diagramado
+//!
+//! Boletim de urna, ata da mesa receptora, certidões e relatórios de
+//! auditoria são montados como uma sequência de blocos (título, campos,
+//! parágrafos, tabelas, assinaturas) e diagramados em largura fixa: 32
+//! colunas na bobina térmica da urna e 80 colunas na página A4 em Courier.
+//! A mesma diagramação serve à impressora, ao arquivo texto e ao PDF, de
+//! modo que o documento impresso na seção e o publicado são idênticos.
+//!
+//! Números e datas seguem as convenções brasileiras de [`locale`]; os
+//! modelos dos documentos estão em [`templates`], com a saída esperada de
+//! cada um versionada em `golden/`.
+
+pub mod locale;
+pub mod pdf;
+pub mod templates;
+
+/// Colunas da bobina térmica da urna
+pub const THERMAL_WIDTH: usize = 32;
+
+/// Colunas da página A4 em Courier
+pub const PAGE_WIDTH: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// Coluna de tabela
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub header: String,
+    pub align: Align,
+}
+
+impl Column {
+    pub fn new(header: &str, align: Align) -> Self {
+        Self { header: header.to_string(), align }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Block {
+    /// Centralizado, em maiúsculas, entre réguas duplas
+    Title(String),
+    /// Em maiúsculas, alinhado à esquerda
+    Heading(String),
+    /// Rótulo e valor unidos por pontilhado
+    Field { label: String, value: String },
+    /// Texto justificado
+    Paragraph(String),
+    /// Colunas ajustadas à largura; a primeira absorve a sobra
+    Table { columns: Vec<Column>, rows: Vec<Vec<String>> },
+    /// Linha de assinatura com nome e função
+    Signature { name: String, role: String },
+    Rule,
+    Blank,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    blocks: Vec<Block>,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(self, text: &str) -> Self {
+        self.block(Block::Title(text.to_string()))
+    }
+
+    pub fn heading(self, text: &str) -> Self {
+        self.block(Block::Heading(text.to_string()))
+    }
+
+    pub fn field(self, label: &str, value: impl ToString) -> Self {
+        self.block(Block::Field { label: label.to_string(), value: value.to_string() })
+    }
+
+    pub fn paragraph(self, text: &str) -> Self {
+        self.block(Block::Paragraph(text.to_string()))
+    }
+
+    pub fn table(self, columns: Vec<Column>, rows: Vec<Vec<String>>) -> Self {
+        self.block(Block::Table { columns, rows })
+    }
+
+    pub fn signature(self, name: &str, role: &str) -> Self {
+        self.block(Block::Signature { name: name.to_string(), role: role.to_string() })
+    }
+
+    pub fn rule(self) -> Self {
+        self.block(Block::Rule)
+    }
+
+    pub fn blank(self) -> Self {
+        self.block(Block::Blank)
+    }
+
+    pub fn block(mut self, block: Block) -> Self {
+        self.blocks.push(block);
+        self
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Linhas diagramadas em `width` colunas, nenhuma mais larga que isso
+    pub fn lines(&self, width: usize) -> Vec<String> {
+        let width = width.max(8);
+        let mut lines = Vec::new();
+        for block in &self.blocks {
+            match block {
+                Block::Title(text) => {
+                    lines.push("=".repeat(width));
+                    for line in wrap(&text.to_uppercase(), width) {
+                        lines.push(align(&line, width, Align::Center));
+                    }
+                    lines.push("=".repeat(width));
+                }
+                Block::Heading(text) => lines.extend(wrap(&text.to_uppercase(), width)),
+                Block::Field { label, value } => lines.extend(field(label, value, width)),
+                Block::Paragraph(text) => lines.extend(justify(text, width)),
+                Block::Table { columns, rows } => lines.extend(table(columns, rows, width)),
+                Block::Signature { name, role } => {
+                    let line = width * 3 / 4;
+                    lines.push(String::new());
+                    lines.push(String::new());
+                    lines.push(align(&"_".repeat(line), width, Align::Center));
+                    for text in wrap(name, width).into_iter().chain(wrap(role, width)) {
+                        lines.push(align(&text, width, Align::Center));
+                    }
+                }
+                Block::Rule => lines.push("-".repeat(width)),
+                Block::Blank => lines.push(String::new()),
+            }
+        }
+        lines
+    }
+
+    /// Texto diagramado, uma linha por quebra, terminando em quebra
+    pub fn render(&self, width: usize) -> String {
+        let mut text = self.lines(width).join("\n");
+        text.push('\n');
+        text
+    }
+
+    /// PDF A4 com a diagramação de 80 colunas
+    pub fn to_pdf(&self) -> Vec<u8> {
+        pdf::render(&self.lines(PAGE_WIDTH), pdf::PdfStyle::OFFICIAL)
+    }
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Corta no limite de colunas
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+fn align(text: &str, width: usize, alignment: Align) -> String {
+    let text = truncate(text, width);
+    let slack = width - text_width(&text);
+    match alignment {
+        Align::Left => format!("{}{}", text, " ".repeat(slack)).trim_end().to_string(),
+        Align::Right => format!("{}{}", " ".repeat(slack), text),
+        Align::Center => format!("{}{}", " ".repeat(slack / 2), text).trim_end().to_string(),
+    }
+}
+
+/// Palavras em linhas de até `width` colunas; palavra maior que a linha é partida
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if word.is_empty() {
+            continue;
+        }
+        if !current.is_empty() && text_width(&current) + 1 + text_width(&word) > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Quebra e distribui os espaços da esquerda para a direita; a última linha fica à esquerda
+fn justify(text: &str, width: usize) -> Vec<String> {
+    let mut lines = wrap(text, width);
+    let last = lines.len().saturating_sub(1);
+    for line in lines.iter_mut().take(last) {
+        let words: Vec<&str> = line.split(' ').collect();
+        let gaps = words.len() - 1;
+        if gaps == 0 {
+            continue;
+        }
+        let spaces = width - words.iter().map(|word| text_width(word)).sum::<usize>();
+        let mut justified = String::with_capacity(width);
+        for (index, word) in words.iter().enumerate() {
+            justified.push_str(word);
+            if index < gaps {
+                let extra = usize::from(index < spaces % gaps);
+                justified.push_str(&" ".repeat(spaces / gaps + extra));
+            }
+        }
+        *line = justified;
+    }
+    lines
+}
+
+/// `Rótulo ..... valor`; sem espaço para o pontilhado, o valor vai à linha seguinte, à direita
+fn field(label: &str, value: &str, width: usize) -> Vec<String> {
+    let (label_width, value_width) = (text_width(label), text_width(value));
+    if label_width + value_width + 3 <= width {
+        let leader = width - label_width - value_width - 2;
+        return vec![format!("{} {} {}", label, ".".repeat(leader), value)];
+    }
+    let mut lines = wrap(label, width);
+    let value: Vec<char> = value.chars().collect();
+    for chunk in value.chunks(width) {
+        lines.push(align(&chunk.iter().collect::<String>(), width, Align::Right));
+    }
+    lines
+}
+
+/// Cabeçalho, régua e linhas; colunas largas demais encolhem e cortam o texto
+fn table(columns: &[Column], rows: &[Vec<String>], width: usize) -> Vec<String> {
+    if columns.is_empty() {
+        return Vec::new();
+    }
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .filter_map(|row| row.get(index))
+                .map(|cell| text_width(cell))
+                .chain(std::iter::once(text_width(&column.header)))
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        })
+        .collect();
+
+    let available = width.saturating_sub(columns.len() - 1);
+    while widths.iter().sum::<usize>() > available {
+        let (widest, _) = widths.iter().enumerate().max_by_key(|(index, w)| (**w, usize::MAX - index)).unwrap();
+        if widths[widest] == 1 {
+            break;
+        }
+        widths[widest] -= 1;
+    }
+    widths[0] += available.saturating_sub(widths.iter().sum());
+
+    let render_row = |cells: &mut dyn Iterator<Item = (&str, Align)>| {
+        let row: Vec<String> = cells
+            .zip(&widths)
+            .map(|((cell, alignment), width)| {
+                let text = truncate(cell, *width);
+                let slack = width - text_width(&text);
+                match alignment {
+                    Align::Left => format!("{}{}", text, " ".repeat(slack)),
+                    Align::Right => format!("{}{}", " ".repeat(slack), text),
+                    Align::Center => format!("{}{}{}", " ".repeat(slack / 2), text, " ".repeat(slack - slack / 2)),
+                }
+            })
+            .collect();
+        row.join(" ").trim_end().to_string()
+    };
+
+    let mut lines = vec![render_row(&mut columns.iter().map(|column| (column.header.as_str(), column.align)))];
+    lines.push("-".repeat(width));
+    for row in rows {
+        let mut cells = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| (row.get(index).map(String::as_str).unwrap_or(""), column.align));
+        lines.push(render_row(&mut cells));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_never_exceed_width() {
+        let document = Document::new()
+            .title("Boletim de urna com um título longo demais para a bobina")
+            .field("Identificador da urna", "URNA-0123456789-ABCDEF-0123456789")
+            .paragraph("Aos dezesseis dias do mês de outubro a mesa receptora encerrou a votação da seção.")
+            .table(
+                vec![Column::new("Candidato", Align::Left), Column::new("Votos", Align::Right)],
+                vec![vec!["Nome muito comprido de candidato".to_string(), "1.234.567".to_string()]],
+            )
+            .signature("Maria da Silva", "Presidente da mesa");
+
+        for width in [THERMAL_WIDTH, PAGE_WIDTH] {
+            for line in document.lines(width) {
+                assert!(text_width(&line) <= width, "{:?} excede {} colunas", line, width);
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_and_paragraph_layout() {
+        assert_eq!(field("Seção", "123", 20), vec!["Seção .......... 123"]);
+        assert_eq!(field("Urna", "0123456789abcdef0", 20), vec!["Urna", "   0123456789abcdef0"]);
+
+        let lines = justify("um dois três quatro cinco seis", 14);
+        assert_eq!(lines, vec!["um  dois  três", "quatro   cinco", "seis"]);
+    }
+
+    #[test]
+    fn test_table_first_column_takes_slack() {
+        let lines = table(
+            &[Column::new("Nº", Align::Left), Column::new("Votos", Align::Right)],
+            &[vec!["13".to_string(), "120".to_string()]],
+            16,
+        );
+        assert_eq!(lines, vec!["Nº         Votos", "----------------", "13           120"]);
+    }
+}
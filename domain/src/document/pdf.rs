@@ -0,0 +1,100 @@
+//! PDF mínimo de texto (PDF 1.4, A4)
+//!
+//! Uma linha de texto por linha do documento, paginada, com fonte Type1
+//! padrão em WinAnsi; os documentos oficiais usam Courier, para manter o
+//! alinhamento das colunas do leiaute em texto.
+
+/// Fonte, corpo e paginação
+#[derive(Debug, Clone, Copy)]
+pub struct PdfStyle {
+    pub font: &'static str,
+    pub font_size: u32,
+    /// Entrelinha em pontos
+    pub leading: u32,
+    pub lines_per_page: usize,
+}
+
+impl PdfStyle {
+    /// Documentos oficiais: 80 colunas em Courier 9
+    pub const OFFICIAL: PdfStyle = PdfStyle {
+        font: "Courier",
+        font_size: 9,
+        leading: 11,
+        lines_per_page: 66,
+    };
+}
+
+/// Monta o PDF com as linhas, em páginas de `lines_per_page` linhas
+pub fn render(lines: &[String], style: PdfStyle) -> Vec<u8> {
+    let pages: Vec<&[String]> = if lines.is_empty() {
+        vec![&[]]
+    } else {
+        lines.chunks(style.lines_per_page.max(1)).collect()
+    };
+
+    // Objetos: 1 catálogo, 2 páginas, 3 fonte, depois (página, conteúdo) por página
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 4 + i * 2)).collect();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes());
+    objects.push(format!(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+        style.font,
+    ).into_bytes());
+
+    for (index, page) in pages.iter().enumerate() {
+        let mut stream = format!("BT /F1 {} Tf {} TL 50 800 Td\n", style.font_size, style.leading).into_bytes();
+        for line in page.iter() {
+            stream.push(b'(');
+            stream.extend(pdf_text(line));
+            stream.extend(b") Tj T*\n");
+        }
+        stream.extend(b"ET");
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            5 + index * 2,
+        ).into_bytes());
+        let mut content = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        content.extend(stream);
+        content.extend(b"\nendstream");
+        objects.push(content);
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+
+    let xref_offset = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset,
+    ).into_bytes());
+    pdf
+}
+
+/// Texto em WinAnsi (Latin-1) com parênteses e barras escapados
+fn pdf_text(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                bytes.push(c as u8);
+            }
+            c if (c as u32) < 0x100 => bytes.push(c as u32 as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
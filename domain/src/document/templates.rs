@@ -0,0 +1,361 @@
+//! Modelos dos documentos oficiais
+//!
+//! Cada modelo recebe os dados já apurados e devolve o [`Document`]; a
+//! diagramação fica a cargo de quem imprime (bobina de 32 colunas na urna,
+//! página de 80 colunas no backend). Os textos corridos usam `{nome}` como
+//! marcador, preenchido por [`crate::i18n::render`].
+
+use super::locale::{
+    format_count, format_count_feminine, format_date, format_datetime, format_hour, format_integer, format_percent,
+    minutes_date,
+};
+use super::{Align, Column, Document};
+use crate::ballot::{contest_order, split_tally_key, Votable};
+use crate::candidate::Candidate;
+use crate::i18n::render;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+const MINUTES_OPENING: &str = "{data}, às {hora}, na seção {secao} da zona eleitoral {zona}, \
+    {municipio}, a mesa receptora encerrou a votação da eleição {eleicao}. \
+    Dos {aptos} eleitores aptos, compareceram {votantes} e faltaram {faltosos}; \
+    foram registradas {justificativas} justificativas de ausência.";
+
+const MINUTES_CLOSING: &str = "Nada mais havendo a registrar, lavrou-se a presente ata, \
+    que vai assinada pelo presidente da mesa receptora.";
+
+const CERTIFICATION_TEXT: &str = "Certifico, para os devidos fins, que o resultado da eleição \
+    {eleicao} foi certificado por {autoridade} em {data}, às {hora}, com {secoes} seções \
+    totalizadas, {aptos} eleitores aptos e comparecimento de {votantes} eleitores ({percentual}).";
+
+/// Votação de um cargo no boletim
+#[derive(Debug, Clone)]
+pub struct BulletinContest {
+    /// Cargo (`PRESIDENTE`)
+    pub title: String,
+    /// (número do candidato ou legenda, votos), do mais votado ao menos votado
+    pub votes: Vec<(String, u64)>,
+    pub blank: u64,
+    pub null: u64,
+}
+
+impl BulletinContest {
+    pub fn total(&self) -> u64 {
+        self.votes.iter().map(|(_, votes)| votes).sum::<u64>() + self.blank + self.null
+    }
+}
+
+/// Dados do boletim de urna
+#[derive(Debug, Clone)]
+pub struct BulletinData {
+    pub election: String,
+    pub state: String,
+    pub municipality_code: String,
+    pub zone: String,
+    pub section: String,
+    pub urna_id: String,
+    pub registered_voters: u64,
+    pub voters_attended: u64,
+    pub contests: Vec<BulletinContest>,
+    pub emitted_at: DateTime<Utc>,
+}
+
+/// Cargos do boletim a partir dos contadores da urna (`tally_key`), na ordem da cédula
+///
+/// Os candidatos aparecem pelo número; contadores de cargos fora da lista de
+/// candidatos vêm ao final, para que nenhum voto fique de fora do boletim.
+pub fn bulletin_contests(candidates: &[Candidate], tallies: &BTreeMap<String, u64>) -> Vec<BulletinContest> {
+    let mut contests: Vec<(String, BulletinContest)> = contest_order(candidates)
+        .into_iter()
+        .map(|position| {
+            let contest = BulletinContest { title: position.title().to_string(), votes: Vec::new(), blank: 0, null: 0 };
+            (position.code().to_string(), contest)
+        })
+        .collect();
+
+    for (key, votes) in tallies {
+        let (code, votable) = split_tally_key(key);
+        let code = code.unwrap_or("");
+        let index = match contests.iter().position(|(contest, _)| contest == code) {
+            Some(index) => index,
+            None => {
+                let contest = BulletinContest { title: code.to_uppercase(), votes: Vec::new(), blank: 0, null: 0 };
+                contests.push((code.to_string(), contest));
+                contests.len() - 1
+            }
+        };
+        let contest = &mut contests[index].1;
+        match votable {
+            Votable::Blank => contest.blank += votes,
+            Votable::Null => contest.null += votes,
+            Votable::Candidate(id) => {
+                let number = candidates
+                    .iter()
+                    .find(|candidate| candidate.id.to_string() == id)
+                    .map(|candidate| candidate.number.to_string())
+                    .unwrap_or_else(|| id.to_string());
+                contest.votes.push((number, *votes));
+            }
+        }
+    }
+
+    contests
+        .into_iter()
+        .map(|(_, mut contest)| {
+            contest.votes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            contest
+        })
+        .collect()
+}
+
+/// Boletim de urna impresso no encerramento da seção
+pub fn bulletin(data: &BulletinData) -> Document {
+    let mut document = Document::new()
+        .title("Boletim de urna")
+        .field("Eleição", &data.election)
+        .field("UF", &data.state)
+        .field("Município", &data.municipality_code)
+        .field("Zona", &data.zone)
+        .field("Seção", &data.section)
+        .field("Urna", &data.urna_id)
+        .field("Emissão", format_datetime(data.emitted_at))
+        .rule()
+        .field("Eleitores aptos", format_integer(data.registered_voters))
+        .field("Comparecimento", format_integer(data.voters_attended))
+        .field("Faltosos", format_integer(data.registered_voters.saturating_sub(data.voters_attended)));
+
+    for contest in &data.contests {
+        let mut rows: Vec<Vec<String>> = contest
+            .votes
+            .iter()
+            .map(|(number, votes)| vec![number.clone(), format_integer(*votes)])
+            .collect();
+        rows.push(vec!["Brancos".to_string(), format_integer(contest.blank)]);
+        rows.push(vec!["Nulos".to_string(), format_integer(contest.null)]);
+        rows.push(vec!["Total apurado".to_string(), format_integer(contest.total())]);
+        document = document
+            .rule()
+            .heading(&contest.title)
+            .table(vec![Column::new("Número", Align::Left), Column::new("Votos", Align::Right)], rows);
+    }
+
+    document.rule()
+}
+
+/// Dados da ata da mesa receptora
+#[derive(Debug, Clone)]
+pub struct SectionMinutesData {
+    pub election: String,
+    pub state: String,
+    pub municipality_code: String,
+    pub zone: String,
+    pub section: String,
+    pub registered_voters: u64,
+    pub voted: u64,
+    pub absent: u64,
+    /// Números de ordem dos admitidos que não concluíram o voto
+    pub admitted_without_vote: Vec<u32>,
+    pub justifications: u64,
+    pub occurrences: Vec<String>,
+    pub president: String,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// Ata da mesa receptora, com as quantidades por extenso
+pub fn section_minutes(data: &SectionMinutesData) -> Document {
+    let municipality = format!("município {}/{}", data.municipality_code, data.state);
+    let opening = render(MINUTES_OPENING, &[
+        ("data", &minutes_date(data.closed_at)),
+        ("hora", &format_hour(data.closed_at)),
+        ("secao", &data.section),
+        ("zona", &data.zone),
+        ("municipio", &municipality),
+        ("eleicao", &data.election),
+        ("aptos", &format_count(data.registered_voters)),
+        ("votantes", &format_count(data.voted)),
+        ("faltosos", &format_count(data.absent)),
+        ("justificativas", &format_count_feminine(data.justifications)),
+    ]);
+
+    let mut document = Document::new().title("Ata da mesa receptora").blank().paragraph(&opening).blank();
+    if data.admitted_without_vote.is_empty() {
+        document = document.paragraph("Todos os eleitores admitidos concluíram o voto.");
+    } else {
+        let sequences: Vec<String> = data.admitted_without_vote.iter().map(|sequence| sequence.to_string()).collect();
+        document = document.paragraph(&format!(
+            "Eleitores admitidos que não concluíram o voto, pelo número de ordem: {}.",
+            sequences.join(", "),
+        ));
+    }
+    document = document.blank();
+    if data.occurrences.is_empty() {
+        document = document.paragraph("Não houve ocorrências.");
+    } else {
+        document = document.heading("Ocorrências");
+        for occurrence in &data.occurrences {
+            document = document.paragraph(occurrence);
+        }
+    }
+
+    document
+        .blank()
+        .paragraph(MINUTES_CLOSING)
+        .signature(&data.president, "Presidente da mesa receptora")
+}
+
+/// Dados da certidão de resultado
+#[derive(Debug, Clone)]
+pub struct CertificationData {
+    pub election: String,
+    pub certified_by: String,
+    pub certified_at: DateTime<Utc>,
+    pub sections: u64,
+    pub registered_voters: u64,
+    pub votes_cast: u64,
+    pub issued_at: DateTime<Utc>,
+}
+
+/// Certidão do resultado certificado, publicada com os dados abertos
+pub fn certification(data: &CertificationData) -> Document {
+    let turnout = if data.registered_voters > 0 {
+        data.votes_cast as f64 / data.registered_voters as f64
+    } else {
+        0.0
+    };
+    let text = render(CERTIFICATION_TEXT, &[
+        ("eleicao", &data.election),
+        ("autoridade", &data.certified_by),
+        ("data", &format_date(data.certified_at)),
+        ("hora", &format_hour(data.certified_at)),
+        ("secoes", &format_count_feminine(data.sections)),
+        ("aptos", &format_count(data.registered_voters)),
+        ("votantes", &format_count(data.votes_cast)),
+        ("percentual", &format_percent(turnout, 2)),
+    ]);
+
+    Document::new()
+        .title("Certidão de resultado")
+        .blank()
+        .paragraph(&text)
+        .blank()
+        .field("Emitida em", format_datetime(data.issued_at))
+        .signature(&data.certified_by, "Autoridade certificadora")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{PAGE_WIDTH, THERMAL_WIDTH};
+    use super::*;
+    use crate::candidate::CandidatePosition;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    /// Com `FORTIS_UPDATE_GOLDEN=1` os arquivos de referência são regravados
+    fn assert_golden(name: &str, expected: &str, actual: &str) {
+        if std::env::var_os("FORTIS_UPDATE_GOLDEN").is_some() {
+            let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/document/golden").join(name);
+            std::fs::write(path, actual).unwrap();
+            return;
+        }
+        assert_eq!(actual, expected, "{} mudou; revise e regrave com FORTIS_UPDATE_GOLDEN=1", name);
+    }
+
+    fn moment() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 4, 20, 5, 9).unwrap()
+    }
+
+    fn candidate(number: u32, position: CandidatePosition) -> Candidate {
+        Candidate {
+            id: Uuid::new_v4(),
+            election_id: Uuid::nil(),
+            name: format!("Candidato {}", number),
+            party: "PARTIDO".to_string(),
+            number,
+            position,
+            coalition: None,
+            region: None,
+            photo_url: None,
+            photo_sha256: None,
+            updated_at: moment(),
+        }
+    }
+
+    fn bulletin_data() -> BulletinData {
+        let candidates = vec![
+            candidate(1234, CandidatePosition::FederalDeputy),
+            candidate(13, CandidatePosition::President),
+            candidate(22, CandidatePosition::President),
+        ];
+        let mut tallies = BTreeMap::new();
+        tallies.insert(format!("president:{}", candidates[1].id), 1_180);
+        tallies.insert(format!("president:{}", candidates[2].id), 1_204);
+        tallies.insert("president:BRANCO".to_string(), 31);
+        tallies.insert("president:NULO".to_string(), 12);
+        tallies.insert(format!("federal_deputy:{}", candidates[0].id), 1_950);
+        tallies.insert("federal_deputy:BRANCO".to_string(), 477);
+
+        BulletinData {
+            election: "Eleições Gerais 2026 - 1º turno".to_string(),
+            state: "SP".to_string(),
+            municipality_code: "71072".to_string(),
+            zone: "1".to_string(),
+            section: "123".to_string(),
+            urna_id: "URNA-SP-0001-2026".to_string(),
+            registered_voters: 2_600,
+            voters_attended: 2_427,
+            contests: bulletin_contests(&candidates, &tallies),
+            emitted_at: moment(),
+        }
+    }
+
+    #[test]
+    fn test_bulletin_contests_follow_ballot_order() {
+        let contests = bulletin_data().contests;
+        let titles: Vec<&str> = contests.iter().map(|contest| contest.title.as_str()).collect();
+        assert_eq!(titles, vec!["DEPUTADO FEDERAL", "PRESIDENTE"]);
+        assert_eq!(contests[1].votes, vec![("22".to_string(), 1_204), ("13".to_string(), 1_180)]);
+        assert_eq!(contests[1].total(), 2_427);
+    }
+
+    #[test]
+    fn test_bulletin_golden() {
+        let rendered = bulletin(&bulletin_data()).render(THERMAL_WIDTH);
+        assert_golden("boletim_urna.txt", include_str!("golden/boletim_urna.txt"), &rendered);
+    }
+
+    #[test]
+    fn test_section_minutes_golden() {
+        let data = SectionMinutesData {
+            election: "Eleições Gerais 2026 - 1º turno".to_string(),
+            state: "SP".to_string(),
+            municipality_code: "71072".to_string(),
+            zone: "1".to_string(),
+            section: "123".to_string(),
+            registered_voters: 2_600,
+            voted: 2_427,
+            absent: 173,
+            admitted_without_vote: vec![17, 402],
+            justifications: 3,
+            occurrences: vec!["Urna reiniciada às 10h12 por queda de energia.".to_string()],
+            president: "Maria da Silva".to_string(),
+            closed_at: moment(),
+        };
+        let rendered = section_minutes(&data).render(THERMAL_WIDTH);
+        assert_golden("ata_mesa.txt", include_str!("golden/ata_mesa.txt"), &rendered);
+    }
+
+    #[test]
+    fn test_certification_golden() {
+        let data = CertificationData {
+            election: "Eleições Gerais 2026 - 1º turno".to_string(),
+            certified_by: "Tribunal Regional Eleitoral de São Paulo".to_string(),
+            certified_at: moment(),
+            sections: 1_032,
+            registered_voters: 1_234_567,
+            votes_cast: 1_030_211,
+            issued_at: moment(),
+        };
+        let rendered = certification(&data).render(PAGE_WIDTH);
+        assert_golden("certidao.txt", include_str!("golden/certidao.txt"), &rendered);
+    }
+}
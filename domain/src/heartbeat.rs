@@ -0,0 +1,120 @@
+//! Heartbeat da urna
+//!
+//! Além da saúde do equipamento, a urna informa como enxerga cada backend
+//! conhecido (regional e nacional): latência medida, falhas recentes e qual
+//! deles está em uso. Com isso o centro de operações percebe a queda de um
+//! backend regional pelo failover das urnas antes de elas ficarem sem destino.
+//!
+//! Bateria, impressora, fila de sincronização e a última falha alimentam o
+//! painel da frota, que separa por zona eleitoral as urnas sem heartbeat
+//! recente e as que funcionam com restrições.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostics::FaultReport;
+use crate::region::SectionRef;
+
+/// Camada do backend na ordem de preferência da urna
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointTier {
+    Regional,
+    /// Contingência nacional, usada quando nenhum regional responde bem
+    National,
+}
+
+/// Situação de um backend do ponto de vista da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EndpointMetrics {
+    pub url: String,
+    pub tier: EndpointTier,
+    /// Backend em uso para a sincronização
+    pub active: bool,
+    /// Falso enquanto o backend cumpre o período de afastamento após falhas seguidas
+    pub available: bool,
+    /// Média móvel exponencial da latência das requisições bem-sucedidas
+    pub latency_ms: Option<f64>,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+/// Situação da impressora de comprovantes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PrinterState {
+    Ready,
+    /// Imprime, mas a bobina precisa ser trocada em breve
+    PaperNearEnd,
+    PaperOut,
+    CoverOpen,
+    Error,
+    /// Sem resposta ou não inicializada
+    Offline,
+}
+
+impl PrinterState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrinterState::Ready => "ready",
+            PrinterState::PaperNearEnd => "paper_near_end",
+            PrinterState::PaperOut => "paper_out",
+            PrinterState::CoverOpen => "cover_open",
+            PrinterState::Error => "error",
+            PrinterState::Offline => "offline",
+        }
+    }
+
+    pub fn can_print(&self) -> bool {
+        matches!(self, PrinterState::Ready | PrinterState::PaperNearEnd)
+    }
+}
+
+/// Última falha registrada pela urna
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HeartbeatError {
+    /// Código de [`crate::diagnostics::codes`]
+    pub code: String,
+    pub message: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Heartbeat enviado periodicamente pela urna (`POST /api/v1/urnas/{urna_id}/heartbeat`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UrnaHeartbeat {
+    pub urna_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Carga da bateria do nobreak, em porcentagem
+    pub battery_level: Option<f32>,
+    /// Urna alimentada pela bateria, sem energia da rede
+    #[serde(default)]
+    pub on_battery: Option<bool>,
+    pub storage_usage: Option<f32>,
+    pub network_connectivity: bool,
+    pub errors: Vec<String>,
+    /// Votos ainda não aceitos pelo backend
+    pub pending_votes: u64,
+    #[serde(default)]
+    pub last_sync: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub printer: Option<PrinterState>,
+    #[serde(default)]
+    pub last_error: Option<HeartbeatError>,
+    /// Ocorrências por código desde o último heartbeat aceito
+    #[serde(default)]
+    pub faults: Vec<FaultReport>,
+    /// Seção do pacote de provisionamento; o backend prefere a designação do cadastro
+    #[serde(default)]
+    pub section: Option<SectionRef>,
+    pub endpoints: Vec<EndpointMetrics>,
+    /// Trocas de backend desde a inicialização da urna
+    pub failovers: u64,
+}
@@ -0,0 +1,234 @@
+//! Textos da urna e negociação de idioma
+//!
+//! As telas, as falas do guia por voz e o comprovante impresso usam as
+//! chaves de `keys`, com os textos do pacote de idioma ativo. Português
+//! (`pt-BR`) é o idioma padrão e o espanhol (`es`) acompanha o software;
+//! outros pacotes, como os das línguas indígenas, chegam no pacote de
+//! eleição. Uma chave ausente no pacote cai no texto em português.
+//!
+//! Os textos podem ter parâmetros nomeados entre chaves (`{title}`),
+//! substituídos por `render`.
+
+use std::collections::BTreeMap;
+
+/// Idioma padrão da urna e das mensagens da API
+pub const DEFAULT_LOCALE: &str = "pt-BR";
+
+/// Chaves dos textos da urna
+pub mod keys {
+    pub const WELCOME_TITLE: &str = "welcome.title";
+    pub const WELCOME_SUBTITLE: &str = "welcome.subtitle";
+    pub const AUTH_BIOMETRIC: &str = "auth.biometric";
+    pub const AUTH_BIOMETRIC_HINT: &str = "auth.biometric_hint";
+    pub const AUTH_FACIAL: &str = "auth.facial";
+    pub const AUTH_FACIAL_HINT: &str = "auth.facial_hint";
+    pub const AUTH_CERTIFICATE: &str = "auth.certificate";
+    pub const AUTH_CERTIFICATE_HINT: &str = "auth.certificate_hint";
+    pub const ACCESSIBILITY_INTRO: &str = "accessibility.intro";
+    /// `{title}`
+    pub const CONTEST_TITLE: &str = "contest.title";
+    /// `{title}`, `{digits}`
+    pub const CONTEST_SPOKEN: &str = "contest.spoken";
+    /// `{title}`, `{number}`, `{name}`, `{party}`
+    pub const CONTEST_CANDIDATE_SPOKEN: &str = "contest.candidate_spoken";
+    pub const CONTEST_BLANK: &str = "contest.blank";
+    pub const CONTEST_WRONG_NUMBER: &str = "contest.wrong_number";
+    pub const CONTEST_NULL: &str = "contest.null";
+    pub const CONTEST_NULL_SPOKEN: &str = "contest.null_spoken";
+    pub const CONFIRM_INSTRUCTIONS: &str = "confirm.instructions";
+    pub const CONFIRM_SPOKEN: &str = "confirm.spoken";
+    pub const CONFIRM_CORRECTED: &str = "confirm.corrected";
+    pub const CONFIRM_INVALID: &str = "confirm.invalid";
+    pub const SUCCESS_TITLE: &str = "success.title";
+    /// `{vote_id}`
+    pub const SUCCESS_VOTE_ID: &str = "success.vote_id";
+    pub const SUCCESS_PRINTING: &str = "success.printing";
+    pub const ERROR_TITLE: &str = "error.title";
+    pub const ERROR_CONTINUE: &str = "error.continue";
+    pub const KEY_ENTER: &str = "key.enter";
+    pub const KEY_CANCEL: &str = "key.cancel";
+    pub const KEY_BLANK: &str = "key.blank";
+    pub const RECEIPT_HEADER: &str = "receipt.header";
+    /// `{vote_id}`
+    pub const RECEIPT_VOTE_ID: &str = "receipt.vote_id";
+    /// `{election_id}`
+    pub const RECEIPT_ELECTION: &str = "receipt.election";
+    /// `{timestamp}`
+    pub const RECEIPT_TIMESTAMP: &str = "receipt.timestamp";
+    pub const RECEIPT_TRACKING_CODE: &str = "receipt.tracking_code";
+    /// `{hash}`
+    pub const RECEIPT_BLOCKCHAIN_HASH: &str = "receipt.blockchain_hash";
+    pub const RECEIPT_FOOTER_SYSTEM: &str = "receipt.footer_system";
+    pub const RECEIPT_FOOTER_MOTTO: &str = "receipt.footer_motto";
+}
+
+use keys::*;
+
+const PT_BR: &[(&str, &str)] = &[
+    (WELCOME_TITLE, "Bem-vindo ao Sistema de Votação FORTIS"),
+    (WELCOME_SUBTITLE, "Sistema seguro e transparente"),
+    (AUTH_BIOMETRIC, "Autenticação Biométrica"),
+    (AUTH_BIOMETRIC_HINT, "Coloque o dedo no leitor"),
+    (AUTH_FACIAL, "Reconhecimento Facial"),
+    (AUTH_FACIAL_HINT, "Olhe para a câmera"),
+    (AUTH_CERTIFICATE, "Certificado Digital (Opcional)"),
+    (AUTH_CERTIFICATE_HINT, "Insira o certificado ou pressione ENTER para pular"),
+    (ACCESSIBILITY_INTRO, "Guia por voz ativado. Use o teclado numérico; a tecla 5 tem um ponto em relevo."),
+    (CONTEST_TITLE, "SEU VOTO PARA {title}"),
+    (CONTEST_SPOKEN, "Seu voto para {title}. Digite os {digits} números do candidato ou aperte Branco."),
+    (CONTEST_CANDIDATE_SPOKEN, "{title}, número {number}, {name}, {party}"),
+    (CONTEST_BLANK, "VOTO EM BRANCO"),
+    (CONTEST_WRONG_NUMBER, "NÚMERO ERRADO"),
+    (CONTEST_NULL, "VOTO NULO"),
+    (CONTEST_NULL_SPOKEN, "Número errado, voto nulo"),
+    (CONFIRM_INSTRUCTIONS, "Digite 1 para CONFIRMAR ou 2 para CORRIGIR"),
+    (CONFIRM_SPOKEN, "Aperte Confirma para confirmar este voto ou Corrige para reiniciar o cargo"),
+    (CONFIRM_CORRECTED, "Voto corrigido"),
+    (CONFIRM_INVALID, "Opção inválida"),
+    (SUCCESS_TITLE, "Voto registrado com sucesso!"),
+    (SUCCESS_VOTE_ID, "ID do voto: {vote_id}"),
+    (SUCCESS_PRINTING, "Aguarde a impressão do comprovante..."),
+    (ERROR_TITLE, "ERRO"),
+    (ERROR_CONTINUE, "Pressione ENTER para continuar"),
+    (KEY_ENTER, "Confirma"),
+    (KEY_CANCEL, "Corrige"),
+    (KEY_BLANK, "Branco"),
+    (RECEIPT_HEADER, "COMPROVANTE DE VOTAÇÃO FORTIS"),
+    (RECEIPT_VOTE_ID, "ID do Voto: {vote_id}"),
+    (RECEIPT_ELECTION, "Eleição: {election_id}"),
+    (RECEIPT_TIMESTAMP, "Data/Hora: {timestamp}"),
+    (RECEIPT_TRACKING_CODE, "Código de rastreamento:"),
+    (RECEIPT_BLOCKCHAIN_HASH, "Hash Blockchain: {hash}"),
+    (RECEIPT_FOOTER_SYSTEM, "Sistema de Votação Eletrônica"),
+    (RECEIPT_FOOTER_MOTTO, "FORTIS - Democracia Digital"),
+];
+
+// As teclas físicas continuam com os rótulos em português
+const ES: &[(&str, &str)] = &[
+    (WELCOME_TITLE, "Bienvenido al Sistema de Votación FORTIS"),
+    (WELCOME_SUBTITLE, "Sistema seguro y transparente"),
+    (AUTH_BIOMETRIC, "Autenticación biométrica"),
+    (AUTH_BIOMETRIC_HINT, "Coloque el dedo en el lector"),
+    (AUTH_FACIAL, "Reconocimiento facial"),
+    (AUTH_FACIAL_HINT, "Mire a la cámara"),
+    (AUTH_CERTIFICATE, "Certificado digital (opcional)"),
+    (AUTH_CERTIFICATE_HINT, "Inserte el certificado o presione ENTER para omitir"),
+    (ACCESSIBILITY_INTRO, "Guía por voz activada. Use el teclado numérico; la tecla 5 tiene un punto en relieve."),
+    (CONTEST_TITLE, "SU VOTO PARA {title}"),
+    (CONTEST_SPOKEN, "Su voto para {title}. Digite los {digits} números del candidato o presione Branco."),
+    (CONTEST_CANDIDATE_SPOKEN, "{title}, número {number}, {name}, {party}"),
+    (CONTEST_BLANK, "VOTO EN BLANCO"),
+    (CONTEST_WRONG_NUMBER, "NÚMERO EQUIVOCADO"),
+    (CONTEST_NULL, "VOTO NULO"),
+    (CONTEST_NULL_SPOKEN, "Número equivocado, voto nulo"),
+    (CONFIRM_INSTRUCTIONS, "Digite 1 para CONFIRMAR o 2 para CORREGIR"),
+    (CONFIRM_SPOKEN, "Presione Confirma para confirmar este voto o Corrige para reiniciar el cargo"),
+    (CONFIRM_CORRECTED, "Voto corregido"),
+    (CONFIRM_INVALID, "Opción inválida"),
+    (SUCCESS_TITLE, "¡Voto registrado con éxito!"),
+    (SUCCESS_VOTE_ID, "ID del voto: {vote_id}"),
+    (SUCCESS_PRINTING, "Espere la impresión del comprobante..."),
+    (ERROR_TITLE, "ERROR"),
+    (ERROR_CONTINUE, "Presione ENTER para continuar"),
+    (KEY_ENTER, "Confirma"),
+    (KEY_CANCEL, "Corrige"),
+    (KEY_BLANK, "Branco"),
+    (RECEIPT_HEADER, "COMPROBANTE DE VOTACIÓN FORTIS"),
+    (RECEIPT_VOTE_ID, "ID del voto: {vote_id}"),
+    (RECEIPT_ELECTION, "Elección: {election_id}"),
+    (RECEIPT_TIMESTAMP, "Fecha/Hora: {timestamp}"),
+    (RECEIPT_TRACKING_CODE, "Código de seguimiento:"),
+    (RECEIPT_BLOCKCHAIN_HASH, "Hash blockchain: {hash}"),
+    (RECEIPT_FOOTER_SYSTEM, "Sistema de Votación Electrónica"),
+    (RECEIPT_FOOTER_MOTTO, "FORTIS - Democracia Digital"),
+];
+
+/// Textos que acompanham o software, para `pt-BR` e `es`
+pub fn builtin_texts(locale: &str) -> Option<BTreeMap<String, String>> {
+    let table = match locale {
+        l if l.eq_ignore_ascii_case(DEFAULT_LOCALE) => PT_BR,
+        l if l.eq_ignore_ascii_case("es") => ES,
+        _ => return None,
+    };
+    Some(table.iter().map(|(key, text)| (key.to_string(), text.to_string())).collect())
+}
+
+/// Chaves sem texto no pacote, que cairão no português
+pub fn missing_keys(texts: &BTreeMap<String, String>) -> Vec<&'static str> {
+    PT_BR.iter().map(|(key, _)| *key).filter(|key| !texts.contains_key(*key)).collect()
+}
+
+/// Substitui os parâmetros `{nome}` do texto
+pub fn render(template: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Idiomas do `Accept-Language`, do preferido ao menos preferido
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut ranges: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            if tag.is_empty() {
+                return None;
+            }
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (quality > 0.0).then(|| (tag.to_string(), quality))
+        })
+        .collect();
+    // Ordenação estável: empates mantêm a ordem do cabeçalho
+    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranges.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Idioma disponível que melhor atende o `Accept-Language`
+///
+/// Vale o idioma exato e depois o idioma principal (`es-AR` atende com
+/// `es`, `pt` com `pt-BR`); sem correspondência, `None` (idioma padrão).
+pub fn negotiate<'a>(header: &str, available: &[&'a str]) -> Option<&'a str> {
+    let primary = |tag: &str| tag.split('-').next().unwrap_or(tag).to_ascii_lowercase();
+    for requested in parse_accept_language(header) {
+        if requested == "*" {
+            return None;
+        }
+        if let Some(found) = available.iter().find(|locale| locale.eq_ignore_ascii_case(&requested)) {
+            return Some(found);
+        }
+        if let Some(found) = available.iter().find(|locale| primary(locale) == primary(&requested)) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_packs_cover_every_key() {
+        assert!(missing_keys(&builtin_texts("pt-BR").unwrap()).is_empty());
+        assert!(missing_keys(&builtin_texts("es").unwrap()).is_empty());
+        assert!(builtin_texts("gn").is_none());
+
+        let texts = builtin_texts("es").unwrap();
+        assert_eq!(render(&texts[CONTEST_TITLE], &[("title", "PRESIDENTE")]), "SU VOTO PARA PRESIDENTE");
+    }
+
+    #[test]
+    fn test_accept_language_negotiation() {
+        let available = ["pt-BR", "es", "gn"];
+        assert_eq!(parse_accept_language("es-AR, pt;q=0.8, en;q=0"), vec!["es-AR", "pt"]);
+        assert_eq!(negotiate("es-AR,pt;q=0.8", &available), Some("es"));
+        assert_eq!(negotiate("en;q=0.9, pt;q=0.5", &available), Some("pt-BR"));
+        assert_eq!(negotiate("GN", &available), Some("gn"));
+        assert_eq!(negotiate("en, *;q=0.1", &available), None);
+        assert_eq!(negotiate("", &available), None);
+    }
+}
@@ -0,0 +1,73 @@
+//! FORTIS - Modelo de domínio compartilhado
+//!
+//! Tipos canônicos de candidatos, cédulas, votos, comprovantes, templates
+//! biométricos, da hierarquia eleitoral, do descomissionamento, das
+//! atualizações, da atestação do boot e das violações da urna, o catálogo
+//! de códigos de falha, os textos da urna por idioma, a diagramação dos
+//! documentos oficiais e a verificação de provas do log transparente,
+//! usados pelo backend e pela urna. Ambos os binários dependem deste crate,
+//! de modo que o formato trocado entre eles tem uma única definição.
+//!
+//! Com a feature `openapi` os tipos também implementam `utoipa::ToSchema`
+//! para a documentação da API do backend.
+
+pub mod attestation;
+pub mod ballot;
+pub mod batch;
+pub mod biometric;
+pub mod candidate;
+pub mod decommission;
+pub mod diagnostics;
+pub mod document;
+pub mod heartbeat;
+pub mod i18n;
+pub mod log_proof;
+pub mod provisioning;
+pub mod receipt;
+pub mod region;
+pub mod schema;
+pub mod tamper;
+pub mod transport;
+pub mod update;
+pub mod vote;
+pub mod vote_sync;
+
+pub use attestation::{
+    AttestationBaseline, AttestationChallenge, AttestationError, AttestationReport, AttestationResult,
+    AttestationVerdict, ATTESTED_PCRS,
+};
+pub use ballot::{
+    contest_order, split_tally_key, tally_key, validate_choices, votes_per_contest, BallotError, ChoiceKind,
+    ContestChoice, Votable, BLANK_VOTABLE, NULL_VOTABLE,
+};
+pub use batch::{
+    merkle_root, verify_batch_proof, BatchError, BatchProof, SignedVoteBatch, VoteBatch,
+    BATCH_SIGNATURE_CONTEXT,
+};
+pub use biometric::{FingerprintTemplate, Minutia, MinutiaKind};
+pub use candidate::{Candidate, CandidatePosition};
+pub use decommission::{
+    DecommissionReport, FinalProofBundle, SignedDecommissionReport, SignedProofBundle, VoteProof,
+    DECOMMISSION_REPORT_FILE, DECOMMISSION_SIGNATURE_CONTEXT, PROOF_BUNDLE_FILE,
+};
+pub use diagnostics::{fault_code, FaultCategory, FaultCode, FaultReport, FaultSeverity, FAULT_CODES};
+pub use heartbeat::{EndpointMetrics, EndpointTier, HeartbeatError, PrinterState, UrnaHeartbeat};
+pub use log_proof::{
+    verify_inclusion_path, LogInclusionProof, ProofError, TreeHead, VoteInclusion, TRANSPARENCY_LOG_KEY_PURPOSE,
+};
+pub use provisioning::{
+    BundleAsset, BundleContents, ProvisioningBundle, ProvisioningError, SnapshotVoter, VoterSnapshot,
+    PROVISIONING_FORMAT_VERSION,
+};
+pub use receipt::{InclusionData, ReceiptPayload, RECEIPT_PAYLOAD_VERSION};
+pub use region::{federative_unit, RegionError, RegionScope, SectionRef, FEDERATIVE_UNITS};
+pub use schema::{SchemaError, Versioned, SCHEMA_VERSION};
+pub use tamper::{TamperEvent, TamperKind};
+pub use transport::{CipherPolicy, TlsProfile, TlsVersion, TransportError};
+#[cfg(feature = "rustls")]
+pub use transport::RustlsParameters;
+pub use update::{InstallOutcome, InstallReport, UpdateComponent, UpdateError, UpdateManifest, UpdateVersion, UPDATE_SIGNATURE_CONTEXT};
+pub use vote::{EncryptedVote, EncryptedVoteData, Vote, VoteSyncStatus};
+pub use vote_sync::{
+    VoteSyncAck, VoteSyncChunk, VoteSyncResult, VoteSyncStatusCode, IDEMPOTENCY_KEY_HEADER, REQUEST_ID_HEADER,
+};
@@ -0,0 +1,204 @@
+//! Verificação de inclusão no log transparente
+//!
+//! Núcleo compartilhado entre backend e urna: o caminho Merkle (RFC 9162,
+//! 2.1.3.2) e a mensagem assinada da cabeça de árvore (STH). Depois de
+//! sincronizar um voto, a urna recebe a prova de inclusão junto com a STH
+//! que a cobre e confere as duas localmente, com a chave do log fixada no
+//! pacote da eleição, antes de considerar o voto sincronizado.
+
+use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::batch::{leaf_hash, node_hash};
+
+/// Finalidade da chave do log em `public_keys` do pacote da eleição
+pub const TRANSPARENCY_LOG_KEY_PURPOSE: &str = "transparency_log";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ProofError {
+    #[error("tree head was signed by an unknown log")]
+    UnknownLog,
+    #[error("tree head signature is invalid")]
+    InvalidSignature,
+    #[error("proof is for tree size {proof} but the tree head covers {tree_head}")]
+    TreeHeadMismatch { proof: u64, tree_head: u64 },
+    #[error("Merkle path does not lead to the tree head root")]
+    InvalidPath,
+}
+
+/// Prova de inclusão de uma folha do log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LogInclusionProof {
+    pub leaf_index: u64,
+    pub path: Vec<String>,
+    pub root_hash: String,
+    pub tree_size: u64,
+}
+
+/// Cabeça de árvore assinada, como publicada pelo log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TreeHead {
+    pub tree_size: u64,
+    pub root_hash: String,
+    pub timestamp: DateTime<Utc>,
+    /// SHA-256 da chave pública do log, em hex
+    pub log_id: String,
+    pub signature: String,
+}
+
+/// Inclusão de um voto sincronizado, com a STH que a cobre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VoteInclusion {
+    pub log_index: u64,
+    pub event_hash: String,
+    pub proof: LogInclusionProof,
+    pub tree_head: TreeHead,
+}
+
+impl TreeHead {
+    /// Confere que a STH foi assinada pelo log com a chave informada
+    pub fn verify(&self, log_public_key: &[u8]) -> Result<(), ProofError> {
+        if log_id_for(log_public_key) != self.log_id {
+            return Err(ProofError::UnknownLog);
+        }
+        let signature = hex::decode(&self.signature).map_err(|_| ProofError::InvalidSignature)?;
+        let message = tree_head_signing_input(self.tree_size, &self.root_hash, &self.timestamp);
+        UnparsedPublicKey::new(&ED25519, log_public_key)
+            .verify(&message, &signature)
+            .map_err(|_| ProofError::InvalidSignature)
+    }
+}
+
+impl VoteInclusion {
+    /// STH assinada pelo log, prova relativa a ela e caminho até a raiz
+    pub fn verify(&self, log_public_key: &[u8]) -> Result<(), ProofError> {
+        self.tree_head.verify(log_public_key)?;
+
+        if self.proof.tree_size != self.tree_head.tree_size || self.proof.root_hash != self.tree_head.root_hash {
+            return Err(ProofError::TreeHeadMismatch {
+                proof: self.proof.tree_size,
+                tree_head: self.tree_head.tree_size,
+            });
+        }
+
+        let valid = verify_inclusion_path(
+            &leaf_hash(&self.event_hash),
+            self.proof.leaf_index,
+            self.proof.tree_size,
+            &self.proof.path,
+            &self.tree_head.root_hash,
+        );
+        if valid { Ok(()) } else { Err(ProofError::InvalidPath) }
+    }
+}
+
+/// Mensagem assinada pelo log em cada STH
+pub fn tree_head_signing_input(tree_size: u64, root_hash: &str, timestamp: &DateTime<Utc>) -> Vec<u8> {
+    format!("fortis-sth-v1|{}|{}|{}", tree_size, root_hash, timestamp.timestamp_millis()).into_bytes()
+}
+
+/// Identificador do log derivado da chave pública
+pub fn log_id_for(public_key: &[u8]) -> String {
+    hex::encode(Sha256::digest(public_key))
+}
+
+/// Recalcula a raiz a partir da folha e do caminho (RFC 9162, 2.1.3.2)
+pub fn verify_inclusion_path(leaf_hash: &str, leaf_index: u64, tree_size: u64, path: &[String], root_hash: &str) -> bool {
+    if leaf_index >= tree_size {
+        return false;
+    }
+
+    let (mut fn_, mut sn) = (leaf_index, tree_size - 1);
+    let mut hash = leaf_hash.to_string();
+    for sibling in path {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            hash = node_hash(sibling, &hash);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            hash = node_hash(&hash, sibling);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && hash == root_hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::VoteBatch;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use uuid::Uuid;
+
+    fn signed_inclusion(key_pair: &Ed25519KeyPair) -> VoteInclusion {
+        // A árvore do lote usa o mesmo hash do log
+        let events: Vec<String> = (0..5).map(|i| format!("event-{}", i)).collect();
+        let batch = VoteBatch::new("urna-1", Uuid::new_v4(), 1, events).unwrap();
+        let proof = batch.inclusion_proof("event-3").unwrap();
+
+        let timestamp = Utc::now();
+        let message = tree_head_signing_input(proof.batch_size, &proof.merkle_root, &timestamp);
+        VoteInclusion {
+            log_index: 3,
+            event_hash: "event-3".to_string(),
+            proof: LogInclusionProof {
+                leaf_index: proof.leaf_index,
+                path: proof.path,
+                root_hash: proof.merkle_root.clone(),
+                tree_size: proof.batch_size,
+            },
+            tree_head: TreeHead {
+                tree_size: proof.batch_size,
+                root_hash: proof.merkle_root,
+                timestamp,
+                log_id: log_id_for(key_pair.public_key().as_ref()),
+                signature: hex::encode(key_pair.sign(&message).as_ref()),
+            },
+        }
+    }
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn test_valid_inclusion_verifies() {
+        let key_pair = key_pair();
+        let inclusion = signed_inclusion(&key_pair);
+        assert_eq!(inclusion.verify(key_pair.public_key().as_ref()), Ok(()));
+
+        let other_log = self::key_pair();
+        assert_eq!(inclusion.verify(other_log.public_key().as_ref()), Err(ProofError::UnknownLog));
+    }
+
+    #[test]
+    fn test_tampered_inclusion_is_rejected() {
+        let key_pair = key_pair();
+        let public_key = key_pair.public_key().as_ref().to_vec();
+
+        let mut other_event = signed_inclusion(&key_pair);
+        other_event.event_hash = "event-4".to_string();
+        assert_eq!(other_event.verify(&public_key), Err(ProofError::InvalidPath));
+
+        let mut forged_root = signed_inclusion(&key_pair);
+        forged_root.tree_head.root_hash = "00".repeat(32);
+        assert_eq!(forged_root.verify(&public_key), Err(ProofError::InvalidSignature));
+
+        let mut stale = signed_inclusion(&key_pair);
+        stale.proof.tree_size -= 1;
+        assert!(matches!(stale.verify(&public_key), Err(ProofError::TreeHeadMismatch { .. })));
+    }
+}
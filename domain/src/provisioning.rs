@@ -0,0 +1,260 @@
+//! Pacote de provisionamento cifrado para preparação das urnas
+//!
+//! Configuração, cédula (pacote de eleição assinado), recorte do cadastro da
+//! seção e arquivos auxiliares viajam em mídia removível dentro de um
+//! envelope cifrado com AES-256-GCM. A chave de conteúdo é cifrada (RSA-OAEP)
+//! para a chave de selagem da urna de destino, que nunca sai do TPM; o
+//! cabeçalho e o hash do texto cifrado são assinados pelo backend (Ed25519).
+//!
+//! A urna confere a assinatura com a chave fixada em sua configuração antes
+//! de decifrar, e confere o manifesto depois.
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Versão do formato do pacote de provisionamento
+pub const PROVISIONING_FORMAT_VERSION: u32 = 1;
+
+/// Contexto de domínio da assinatura do cabeçalho
+pub const PROVISIONING_SIGNATURE_CONTEXT: &str = "FORTIS-PROVISIONING-V1";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProvisioningError {
+    #[error("Unsupported provisioning bundle format: {0}")]
+    UnsupportedFormat(u32),
+    #[error("Bundle is addressed to device {0}")]
+    WrongDevice(String),
+    #[error("Bundle is encrypted for another sealing key")]
+    WrongRecipient,
+    #[error("Manifest mismatch for {0}")]
+    ManifestMismatch(String),
+    #[error("Invalid bundle contents: {0}")]
+    InvalidContents(String),
+}
+
+/// Envelope gravado na mídia de provisionamento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningBundle {
+    pub format_version: u32,
+    pub bundle_id: Uuid,
+    pub device_id: String,
+    /// SHA-256 (hex) da chave pública de selagem em DER
+    pub recipient_key_sha256: String,
+    /// Chave AES-256 cifrada com RSA-OAEP-SHA256, em base64
+    pub wrapped_key: String,
+    /// Nonce do AES-GCM, em base64
+    pub nonce: String,
+    /// `BundleContents` cifrado, em base64
+    pub ciphertext: String,
+    pub created_at: DateTime<Utc>,
+    /// Assinatura Ed25519 (hex) de `signed_message`
+    pub signature: String,
+}
+
+impl ProvisioningBundle {
+    /// Mensagem assinada: cabeçalho e hash do texto cifrado
+    pub fn signed_message(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            PROVISIONING_SIGNATURE_CONTEXT,
+            self.format_version,
+            self.bundle_id,
+            self.device_id,
+            self.recipient_key_sha256,
+            self.wrapped_key,
+            self.nonce,
+            hex::encode(Sha256::digest(self.ciphertext.as_bytes())),
+            self.created_at.to_rfc3339(),
+        )
+        .into_bytes()
+    }
+
+    /// Dados associados do AES-GCM: o texto cifrado só vale para este cabeçalho
+    pub fn associated_data(&self) -> Vec<u8> {
+        format!("{}\n{}\n{}", self.bundle_id, self.device_id, self.recipient_key_sha256).into_bytes()
+    }
+
+    /// Confere versão, destino e chave de selagem antes de decifrar
+    pub fn check_recipient(&self, device_id: &str, sealing_key_der: &[u8]) -> Result<(), ProvisioningError> {
+        if self.format_version != PROVISIONING_FORMAT_VERSION {
+            return Err(ProvisioningError::UnsupportedFormat(self.format_version));
+        }
+        if self.device_id != device_id {
+            return Err(ProvisioningError::WrongDevice(self.device_id.clone()));
+        }
+        if self.recipient_key_sha256 != recipient_key_sha256(sealing_key_der) {
+            return Err(ProvisioningError::WrongRecipient);
+        }
+        Ok(())
+    }
+}
+
+/// Impressão digital da chave de selagem
+pub fn recipient_key_sha256(sealing_key_der: &[u8]) -> String {
+    hex::encode(Sha256::digest(sealing_key_der))
+}
+
+/// Eleitor da seção, sem CPF
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotVoter {
+    pub voter_id: String,
+    pub name: String,
+    pub birth_date: NaiveDate,
+    pub active: bool,
+}
+
+/// Recorte do cadastro para a seção da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoterSnapshot {
+    pub zone: String,
+    pub section: String,
+    pub generated_at: DateTime<Utc>,
+    pub voters: Vec<SnapshotVoter>,
+}
+
+/// Arquivo auxiliar (fotos, áudio, fontes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleAsset {
+    pub name: String,
+    pub media_type: String,
+    /// Conteúdo em base64
+    pub data: String,
+}
+
+impl BundleAsset {
+    pub fn new(name: &str, media_type: &str, data: &[u8]) -> Self {
+        Self {
+            name: name.to_string(),
+            media_type: media_type.to_string(),
+            data: general_purpose::STANDARD.encode(data),
+        }
+    }
+
+    pub fn bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        general_purpose::STANDARD.decode(&self.data)
+    }
+}
+
+/// Conteúdo decifrado do pacote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleContents {
+    /// Configuração da urna
+    pub config: serde_json::Value,
+    /// Pacote de eleição assinado, verificado à parte com a chave fixada
+    pub ballot: serde_json::Value,
+    pub voter_snapshot: VoterSnapshot,
+    pub assets: Vec<BundleAsset>,
+    /// SHA-256 (hex) de cada item, para auditoria da preparação
+    pub manifest: BTreeMap<String, String>,
+}
+
+impl BundleContents {
+    /// Monta o conteúdo e calcula o manifesto
+    pub fn new(
+        config: serde_json::Value,
+        ballot: serde_json::Value,
+        voter_snapshot: VoterSnapshot,
+        assets: Vec<BundleAsset>,
+    ) -> Result<Self, ProvisioningError> {
+        let mut contents = Self { config, ballot, voter_snapshot, assets, manifest: BTreeMap::new() };
+        contents.manifest = contents.compute_manifest()?;
+        Ok(contents)
+    }
+
+    /// Hash de cada item; arquivos auxiliares pelo conteúdo decodificado
+    pub fn compute_manifest(&self) -> Result<BTreeMap<String, String>, ProvisioningError> {
+        let mut manifest = BTreeMap::new();
+        manifest.insert("config".to_string(), json_sha256(&self.config)?);
+        manifest.insert("ballot".to_string(), json_sha256(&self.ballot)?);
+        manifest.insert("voter_snapshot".to_string(), json_sha256(&self.voter_snapshot)?);
+        for asset in &self.assets {
+            let bytes = asset.bytes()
+                .map_err(|_| ProvisioningError::InvalidContents(format!("asset {}", asset.name)))?;
+            manifest.insert(format!("assets/{}", asset.name), hex::encode(Sha256::digest(&bytes)));
+        }
+        Ok(manifest)
+    }
+
+    /// Confere o manifesto contra o conteúdo decifrado
+    pub fn verify_manifest(&self) -> Result<(), ProvisioningError> {
+        let computed = self.compute_manifest()?;
+        for (item, hash) in computed.iter() {
+            if self.manifest.get(item) != Some(hash) {
+                return Err(ProvisioningError::ManifestMismatch(item.clone()));
+            }
+        }
+        if let Some(extra) = self.manifest.keys().find(|item| !computed.contains_key(*item)) {
+            return Err(ProvisioningError::ManifestMismatch(extra.clone()));
+        }
+        Ok(())
+    }
+}
+
+fn json_sha256<T: Serialize>(value: &T) -> Result<String, ProvisioningError> {
+    let bytes = serde_json::to_vec(value)
+        .map_err(|e| ProvisioningError::InvalidContents(e.to_string()))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents() -> BundleContents {
+        BundleContents::new(
+            serde_json::json!({ "zone": "0001", "section": "0042" }),
+            serde_json::json!({ "payload": "{}", "signature": "00" }),
+            VoterSnapshot {
+                zone: "0001".to_string(),
+                section: "0042".to_string(),
+                generated_at: Utc::now(),
+                voters: vec![],
+            },
+            vec![BundleAsset::new("photos/13.jpg", "image/jpeg", &[0xff, 0xd8])],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_manifest_detects_replaced_asset() {
+        let mut contents = contents();
+        assert!(contents.verify_manifest().is_ok());
+
+        contents.assets[0] = BundleAsset::new("photos/13.jpg", "image/jpeg", &[0x00]);
+        assert_eq!(
+            contents.verify_manifest(),
+            Err(ProvisioningError::ManifestMismatch("assets/photos/13.jpg".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_signed_message_covers_destination_and_ciphertext() {
+        let bundle = ProvisioningBundle {
+            format_version: PROVISIONING_FORMAT_VERSION,
+            bundle_id: Uuid::new_v4(),
+            device_id: "URNA-1".to_string(),
+            recipient_key_sha256: recipient_key_sha256(b"key"),
+            wrapped_key: "d3JhcHBlZA==".to_string(),
+            nonce: "bm9uY2U=".to_string(),
+            ciphertext: "Y2lwaGVy".to_string(),
+            created_at: Utc::now(),
+            signature: String::new(),
+        };
+
+        let mut redirected = bundle.clone();
+        redirected.device_id = "URNA-2".to_string();
+        assert_ne!(bundle.signed_message(), redirected.signed_message());
+
+        let mut swapped = bundle.clone();
+        swapped.ciphertext = "b3V0cm8=".to_string();
+        assert_ne!(bundle.signed_message(), swapped.signed_message());
+
+        assert!(bundle.check_recipient("URNA-1", b"key").is_ok());
+        assert_eq!(bundle.check_recipient("URNA-1", b"other"), Err(ProvisioningError::WrongRecipient));
+    }
+}
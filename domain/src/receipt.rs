@@ -0,0 +1,110 @@
+//! Comprovante de votação
+//!
+//! O QR code do comprovante traz o identificador do voto, o código de
+//! rastreamento e os dados de inclusão no log transparente, nunca a escolha
+//! do eleitor. Urna e backend calculam o mesmo código a partir do
+//! identificador do voto e do hash da cédula cifrada.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Versão do conteúdo do QR code
+pub const RECEIPT_PAYLOAD_VERSION: u8 = 1;
+
+/// Alfabeto Base32 de Crockford (sem I, L, O e U)
+const TRACKING_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Dados de inclusão Merkle no momento do registro
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InclusionData {
+    pub log_index: u64,
+    pub event_hash: String,
+    pub root_hash: String,
+    pub tree_size: u64,
+}
+
+/// Conteúdo codificado no QR code do comprovante
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReceiptPayload {
+    pub v: u8,
+    pub vote_id: Uuid,
+    pub election_id: Uuid,
+    pub tracking_code: String,
+    pub inclusion: Option<InclusionData>,
+}
+
+impl ReceiptPayload {
+    pub fn new(vote_id: Uuid, election_id: Uuid, ballot_hash: &str) -> Self {
+        Self {
+            v: RECEIPT_PAYLOAD_VERSION,
+            vote_id,
+            election_id,
+            tracking_code: tracking_code(vote_id, ballot_hash),
+            inclusion: None,
+        }
+    }
+}
+
+/// Código de rastreamento no formato XXXX-XXXX-XXXX-XXXX
+pub fn tracking_code(vote_id: Uuid, ballot_hash: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(b"FORTIS-TRACKING-V1")
+        .chain_update(vote_id.as_bytes())
+        .chain_update(ballot_hash.as_bytes())
+        .finalize();
+
+    // 80 bits = 16 símbolos de 5 bits
+    let bits = digest[..10].iter().fold(0u128, |acc, b| (acc << 8) | *b as u128);
+    let symbols: Vec<char> = (0..16)
+        .rev()
+        .map(|i| TRACKING_ALPHABET[((bits >> (i * 5)) & 0x1f) as usize] as char)
+        .collect();
+    group_symbols(&symbols)
+}
+
+/// Normaliza código digitado pelo eleitor (minúsculas, sem hífens, O→0, I/L→1)
+pub fn normalize_tracking_code(input: &str) -> Option<String> {
+    let symbols: Vec<char> = input
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        })
+        .collect();
+    if symbols.len() != 16 || !symbols.iter().all(|c| TRACKING_ALPHABET.contains(&(*c as u8))) {
+        return None;
+    }
+    Some(group_symbols(&symbols))
+}
+
+/// Hash da cédula cifrada usado no código de rastreamento
+pub fn ballot_hash(encrypted_data: &[u8]) -> String {
+    hex::encode(Sha256::digest(encrypted_data))
+}
+
+fn group_symbols(symbols: &[char]) -> String {
+    symbols.chunks(4).map(|c| c.iter().collect::<String>()).collect::<Vec<_>>().join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracking_code_is_stable_and_normalizable() {
+        let vote_id = Uuid::new_v4();
+        let code = tracking_code(vote_id, "abc123");
+        assert_eq!(code, tracking_code(vote_id, "abc123"));
+        assert_ne!(code, tracking_code(vote_id, "abc124"));
+        assert_eq!(code.len(), 19);
+
+        let typed = code.to_lowercase().replace('-', "");
+        assert_eq!(normalize_tracking_code(&typed), Some(code));
+        assert_eq!(normalize_tracking_code("not-a-code"), None);
+    }
+}
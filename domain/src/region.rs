@@ -0,0 +1,207 @@
+//! Hierarquia eleitoral: UF → município → zona → seção
+//!
+//! A seção é identificada pela UF, pelo código TSE do município, pelo
+//! número da zona e pelo número da seção. A forma textual segue a
+//! hierarquia separada por `/` (`SP/71072/0001/0123`); um recorte da
+//! hierarquia (`SP`, `SP/71072`, `SP/71072/0001`) delimita consultas de
+//! resultados, auditorias e eventos do log transparente.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RegionError {
+    #[error("Invalid federative unit: {0}")]
+    InvalidState(String),
+    #[error("Invalid region: {0}")]
+    InvalidRegion(String),
+}
+
+/// As 27 unidades federativas
+pub const FEDERATIVE_UNITS: [&str; 27] = [
+    "AC", "AL", "AP", "AM", "BA", "CE", "DF", "ES", "GO", "MA", "MT", "MS", "MG", "PA",
+    "PB", "PR", "PE", "PI", "RJ", "RN", "RS", "RO", "RR", "SC", "SP", "SE", "TO",
+];
+
+/// Sigla da UF em maiúsculas, recusando o que não for uma das 27 UFs
+pub fn federative_unit(value: &str) -> Result<&'static str, RegionError> {
+    let state = value.trim().to_uppercase();
+    FEDERATIVE_UNITS
+        .iter()
+        .find(|uf| **uf == state)
+        .copied()
+        .ok_or(RegionError::InvalidState(state))
+}
+
+/// Seção eleitoral
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SectionRef {
+    pub state: String,
+    pub municipality_code: String,
+    pub zone: String,
+    pub section: String,
+}
+
+impl SectionRef {
+    pub fn new(state: &str, municipality_code: &str, zone: &str, section: &str) -> Result<Self, RegionError> {
+        let scope = RegionScope::parse(&format!("{}/{}/{}/{}", state, municipality_code, zone, section))?;
+        scope.section_ref().ok_or_else(|| RegionError::InvalidRegion(scope.to_string()))
+    }
+
+    /// `UF/município/zona/seção`
+    pub fn key(&self) -> String {
+        format!("{}/{}/{}/{}", self.state, self.municipality_code, self.zone, self.section)
+    }
+
+    pub fn scope(&self) -> RegionScope {
+        RegionScope {
+            state: self.state.clone(),
+            municipality_code: Some(self.municipality_code.clone()),
+            zone: Some(self.zone.clone()),
+            section: Some(self.section.clone()),
+        }
+    }
+}
+
+impl fmt::Display for SectionRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key())
+    }
+}
+
+/// Recorte da hierarquia, da UF até uma seção
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegionScope {
+    pub state: String,
+    pub municipality_code: Option<String>,
+    pub zone: Option<String>,
+    pub section: Option<String>,
+}
+
+impl RegionScope {
+    pub fn state(state: &str) -> Result<Self, RegionError> {
+        Self::parse(state)
+    }
+
+    /// `UF[/município[/zona[/seção]]]`; a UF é normalizada em maiúsculas
+    pub fn parse(value: &str) -> Result<Self, RegionError> {
+        let mut parts = value.trim().split('/');
+        let state = parts.next().unwrap_or_default().trim().to_uppercase();
+        if state.len() != 2 || !state.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(RegionError::InvalidState(state));
+        }
+
+        let mut levels: Vec<String> = Vec::with_capacity(3);
+        for part in parts {
+            let part = part.trim();
+            if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) || levels.len() == 3 {
+                return Err(RegionError::InvalidRegion(value.to_string()));
+            }
+            levels.push(part.to_string());
+        }
+        let mut levels = levels.into_iter();
+
+        Ok(Self {
+            state,
+            municipality_code: levels.next(),
+            zone: levels.next(),
+            section: levels.next(),
+        })
+    }
+
+    /// Seção, quando o recorte chega até ela
+    pub fn section_ref(&self) -> Option<SectionRef> {
+        Some(SectionRef {
+            state: self.state.clone(),
+            municipality_code: self.municipality_code.clone()?,
+            zone: self.zone.clone()?,
+            section: self.section.clone()?,
+        })
+    }
+
+    /// O outro recorte está inteiramente dentro deste
+    pub fn covers(&self, other: &RegionScope) -> bool {
+        fn level(outer: &Option<String>, inner: &Option<String>) -> bool {
+            match (outer, inner) {
+                (None, _) => true,
+                (Some(outer), Some(inner)) => outer == inner,
+                (Some(_), None) => false,
+            }
+        }
+        self.state == other.state
+            && level(&self.municipality_code, &other.municipality_code)
+            && level(&self.zone, &other.zone)
+            && level(&self.section, &other.section)
+    }
+
+    pub fn contains(&self, section: &SectionRef) -> bool {
+        self.covers(&section.scope())
+    }
+}
+
+impl fmt::Display for RegionScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.state)?;
+        for level in [&self.municipality_code, &self.zone, &self.section].into_iter().flatten() {
+            write!(f, "/{}", level)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for RegionScope {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RegionScope {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_parse_and_display() {
+        let scope = RegionScope::parse("sp/71072/0001").unwrap();
+        assert_eq!(scope.state, "SP");
+        assert_eq!(scope.zone.as_deref(), Some("0001"));
+        assert_eq!(scope.to_string(), "SP/71072/0001");
+        assert!(scope.section_ref().is_none());
+
+        assert!(RegionScope::parse("71072").is_err());
+        assert!(RegionScope::parse("SP//0001").is_err());
+        assert!(RegionScope::parse("SP/1/2/3/4").is_err());
+    }
+
+    #[test]
+    fn test_federative_unit_is_normalized_and_checked() {
+        assert_eq!(federative_unit(" sp ").unwrap(), "SP");
+        assert_eq!(federative_unit("Df").unwrap(), "DF");
+        assert_eq!(federative_unit("xx"), Err(RegionError::InvalidState("XX".to_string())));
+        assert!(federative_unit("ZZ").is_err());
+        assert!(federative_unit("SP/71072").is_err());
+    }
+
+    #[test]
+    fn test_scope_covers_nested_levels() {
+        let section = SectionRef::new("SP", "71072", "0001", "0123").unwrap();
+        assert_eq!(section.key(), "SP/71072/0001/0123");
+
+        assert!(RegionScope::parse("SP").unwrap().contains(&section));
+        assert!(RegionScope::parse("SP/71072/0001").unwrap().contains(&section));
+        assert!(!RegionScope::parse("SP/71072/0002").unwrap().contains(&section));
+        assert!(!RegionScope::parse("RJ").unwrap().contains(&section));
+
+        let zone = RegionScope::parse("SP/71072/0001").unwrap();
+        assert!(RegionScope::parse("SP/71072").unwrap().covers(&zone));
+        assert!(!section.scope().covers(&zone));
+    }
+}
@@ -0,0 +1,84 @@
+//! Versionamento dos esquemas trocados entre urna e backend
+//!
+//! Dados persistidos ou enviados fora de um pacote assinado (cache de
+//! candidatos, filas de votos) são gravados dentro de um envelope com a
+//! versão do esquema, para que uma urna atualizada não leia silenciosamente
+//! dados gravados por outra versão.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Versão atual do esquema dos tipos deste crate
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SchemaError {
+    #[error("Unsupported schema version {found} (supported: {supported})")]
+    Unsupported { found: u32, supported: u32 },
+}
+
+/// Envelope com a versão do esquema do conteúdo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub schema_version: u32,
+    pub data: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn new(data: T) -> Self {
+        Self { schema_version: SCHEMA_VERSION, data }
+    }
+
+    /// Devolve o conteúdo se a versão for a atual
+    pub fn into_current(self) -> Result<T, SchemaError> {
+        if self.schema_version != SCHEMA_VERSION {
+            return Err(SchemaError::Unsupported {
+                found: self.schema_version,
+                supported: SCHEMA_VERSION,
+            });
+        }
+        Ok(self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Candidate, CandidatePosition};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn candidate() -> Candidate {
+        Candidate {
+            id: Uuid::new_v4(),
+            election_id: Uuid::new_v4(),
+            name: "Maria Souza".to_string(),
+            party: "PXX".to_string(),
+            number: 13,
+            position: CandidatePosition::President,
+            coalition: None,
+            region: None,
+            photo_url: None,
+            photo_sha256: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_versioned_roundtrip() {
+        let json = serde_json::to_string(&Versioned::new(vec![candidate()])).unwrap();
+        let decoded: Versioned<Vec<Candidate>> = serde_json::from_str(&json).unwrap();
+        let candidates = decoded.into_current().unwrap();
+        assert_eq!(candidates[0].number, 13);
+        assert_eq!(candidates[0].position, CandidatePosition::President);
+    }
+
+    #[test]
+    fn test_other_schema_version_is_rejected() {
+        let stale = Versioned { schema_version: SCHEMA_VERSION + 1, data: candidate() };
+        assert_eq!(
+            stale.into_current().unwrap_err(),
+            SchemaError::Unsupported { found: SCHEMA_VERSION + 1, supported: SCHEMA_VERSION }
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Eventos de violação do equipamento da urna
+//!
+//! A urna acompanha a chave antiviolação, o sensor de abertura do gabinete
+//! e os dispositivos USB conectados. Cada ocorrência vira um evento com
+//! identificador próprio, que bloqueia a votação na urna e é enviado ao
+//! backend (`POST /api/v1/urnas/{urna_id}/tamper`) até ser aceito; o
+//! backend descarta os reenvios pelo identificador.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Sensor que detectou a violação
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TamperKind {
+    TamperSwitch,
+    CaseOpen,
+    /// Dispositivo USB fora da lista de periféricos da urna
+    UnexpectedUsb,
+}
+
+impl TamperKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TamperKind::TamperSwitch => "tamper_switch",
+            TamperKind::CaseOpen => "case_open",
+            TamperKind::UnexpectedUsb => "unexpected_usb",
+        }
+    }
+}
+
+/// Ocorrência de violação registrada pela urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TamperEvent {
+    pub event_id: Uuid,
+    pub urna_id: String,
+    pub kind: TamperKind,
+    /// Sensor ou dispositivo (por exemplo `usb 1-1.3 0781:5567`)
+    pub source: String,
+    pub detail: String,
+    /// Eleição com sessão aberta no momento da violação
+    pub election_id: Option<Uuid>,
+    pub detected_at: DateTime<Utc>,
+}
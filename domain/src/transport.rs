@@ -0,0 +1,302 @@
+//! Perfis de política TLS compartilhados entre backend e urna
+//!
+//! Cada perfil fixa versões, suítes de cifra, grupos de troca de chaves e
+//! esquemas de assinatura. O servidor HTTP, o cliente da urna e o gRPC
+//! interno montam suas configurações a partir da mesma política, e o
+//! relatório de auditoria lista exatamente o que foi aplicado.
+//!
+//! - `modern`: somente TLS 1.3.
+//! - `intermediate`: TLS 1.2 e 1.3 com ECDHE e AEAD.
+//! - `restricted`: semelhante ao FIPS 140; somente AES-GCM, curvas NIST
+//!   P-256/P-384 e assinaturas ECDSA/RSA-PSS/PKCS#1 com SHA-2.
+//!
+//! Com a feature `rustls` a política também é convertida nos parâmetros do
+//! rustls, usados pelo backend e pela urna.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TransportError {
+    #[error("Unknown TLS profile: {0}")]
+    UnknownProfile(String),
+    #[error("Cipher suite {suite} not allowed by the {profile} profile")]
+    SuiteNotAllowed { profile: TlsProfile, suite: String },
+    #[error("Key exchange group {group} not allowed by the {profile} profile")]
+    GroupNotAllowed { profile: TlsProfile, group: String },
+    #[error("Protocol version {version} not allowed by the {profile} profile")]
+    VersionNotAllowed { profile: TlsProfile, version: TlsVersion },
+    #[error("The {0} profile leaves no cipher suite enabled")]
+    NoCipherSuites(TlsProfile),
+    #[error("{0} unavailable in this TLS build")]
+    Unavailable(String),
+}
+
+/// Perfil de política de transporte
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TlsProfile {
+    Modern,
+    Intermediate,
+    Restricted,
+}
+
+/// Versão do protocolo TLS
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TlsVersion {
+    #[serde(rename = "TLSv1.2")]
+    Tls12,
+    #[serde(rename = "TLSv1.3")]
+    Tls13,
+}
+
+/// Suítes TLS 1.3, em ordem de preferência (nomes IANA)
+const TLS13_SUITES: &[&str] = &[
+    "TLS13_AES_256_GCM_SHA384",
+    "TLS13_AES_128_GCM_SHA256",
+    "TLS13_CHACHA20_POLY1305_SHA256",
+];
+
+/// Suítes TLS 1.2 com ECDHE e AEAD, em ordem de preferência
+const TLS12_SUITES: &[&str] = &[
+    "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+    "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+];
+
+const ALL_GROUPS: &[&str] = &["X25519", "secp384r1", "secp256r1"];
+const NIST_GROUPS: &[&str] = &["secp384r1", "secp256r1"];
+
+const ALL_SIGNATURE_SCHEMES: &[&str] = &[
+    "ecdsa_secp384r1_sha384",
+    "ecdsa_secp256r1_sha256",
+    "ed25519",
+    "rsa_pss_rsae_sha512",
+    "rsa_pss_rsae_sha384",
+    "rsa_pss_rsae_sha256",
+    "rsa_pkcs1_sha512",
+    "rsa_pkcs1_sha384",
+    "rsa_pkcs1_sha256",
+];
+
+/// Política efetiva de um perfil
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CipherPolicy {
+    pub profile: TlsProfile,
+    pub versions: Vec<TlsVersion>,
+    pub cipher_suites: Vec<String>,
+    pub key_exchange_groups: Vec<String>,
+    pub signature_schemes: Vec<String>,
+}
+
+impl TlsProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TlsProfile::Modern => "modern",
+            TlsProfile::Intermediate => "intermediate",
+            TlsProfile::Restricted => "restricted",
+        }
+    }
+
+    /// Política completa do perfil
+    pub fn policy(&self) -> CipherPolicy {
+        let owned = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let aes_gcm_only = |items: &[&str]| {
+            items.iter()
+                .filter(|s| s.contains("_AES_") && s.contains("GCM"))
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        match self {
+            TlsProfile::Modern => CipherPolicy {
+                profile: *self,
+                versions: vec![TlsVersion::Tls13],
+                cipher_suites: owned(TLS13_SUITES),
+                key_exchange_groups: owned(ALL_GROUPS),
+                signature_schemes: owned(ALL_SIGNATURE_SCHEMES),
+            },
+            TlsProfile::Intermediate => CipherPolicy {
+                profile: *self,
+                versions: vec![TlsVersion::Tls12, TlsVersion::Tls13],
+                cipher_suites: owned(TLS13_SUITES).into_iter().chain(owned(TLS12_SUITES)).collect(),
+                key_exchange_groups: owned(ALL_GROUPS),
+                signature_schemes: owned(ALL_SIGNATURE_SCHEMES),
+            },
+            TlsProfile::Restricted => CipherPolicy {
+                profile: *self,
+                versions: vec![TlsVersion::Tls12, TlsVersion::Tls13],
+                cipher_suites: aes_gcm_only(TLS13_SUITES).into_iter().chain(aes_gcm_only(TLS12_SUITES)).collect(),
+                key_exchange_groups: owned(NIST_GROUPS),
+                signature_schemes: ALL_SIGNATURE_SCHEMES.iter()
+                    .filter(|s| **s != "ed25519")
+                    .map(|s| s.to_string())
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl CipherPolicy {
+    /// Confere que uma configuração montada só usa o que o perfil permite
+    pub fn check(
+        &self,
+        versions: &[TlsVersion],
+        suites: &[String],
+        groups: &[String],
+    ) -> Result<(), TransportError> {
+        if let Some(version) = versions.iter().find(|v| !self.versions.contains(v)) {
+            return Err(TransportError::VersionNotAllowed { profile: self.profile, version: *version });
+        }
+        if let Some(suite) = suites.iter().find(|s| !self.cipher_suites.contains(s)) {
+            return Err(TransportError::SuiteNotAllowed { profile: self.profile, suite: suite.clone() });
+        }
+        if let Some(group) = groups.iter().find(|g| !self.key_exchange_groups.contains(g)) {
+            return Err(TransportError::GroupNotAllowed { profile: self.profile, group: group.clone() });
+        }
+        if suites.is_empty() {
+            return Err(TransportError::NoCipherSuites(self.profile));
+        }
+        Ok(())
+    }
+}
+
+/// Versões, suítes e grupos do rustls, na ordem de preferência da política
+#[cfg(feature = "rustls")]
+pub struct RustlsParameters {
+    pub versions: Vec<&'static rustls::SupportedProtocolVersion>,
+    pub cipher_suites: Vec<rustls::SupportedCipherSuite>,
+    pub kx_groups: Vec<&'static rustls::SupportedKxGroup>,
+}
+
+#[cfg(feature = "rustls")]
+impl CipherPolicy {
+    /// Seleciona os parâmetros do rustls e confere que nada fora da política entrou
+    pub fn rustls_parameters(&self) -> Result<RustlsParameters, TransportError> {
+        let versions = self.versions.iter()
+            .map(|version| match version {
+                TlsVersion::Tls12 => &rustls::version::TLS12,
+                TlsVersion::Tls13 => &rustls::version::TLS13,
+            })
+            .collect();
+
+        let cipher_suites = self.cipher_suites.iter()
+            .map(|name| {
+                rustls::ALL_CIPHER_SUITES.iter()
+                    .find(|suite| format!("{:?}", suite.suite()) == *name)
+                    .copied()
+                    .ok_or_else(|| TransportError::Unavailable(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let kx_groups = self.key_exchange_groups.iter()
+            .map(|name| {
+                rustls::ALL_KX_GROUPS.iter()
+                    .find(|group| format!("{:?}", group.name) == *name)
+                    .copied()
+                    .ok_or_else(|| TransportError::Unavailable(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.check(
+            &self.versions,
+            &cipher_suites.iter().map(|suite| format!("{:?}", suite.suite())).collect::<Vec<_>>(),
+            &kx_groups.iter().map(|group| format!("{:?}", group.name)).collect::<Vec<_>>(),
+        )?;
+        Ok(RustlsParameters { versions, cipher_suites, kx_groups })
+    }
+
+    /// Configuração de cliente restrita à política
+    pub fn rustls_client_config(&self, roots: rustls::RootCertStore) -> Result<rustls::ClientConfig, TransportError> {
+        Ok(self.rustls_client_builder(roots)?.with_no_client_auth())
+    }
+
+    /// Configuração de cliente que se apresenta com certificado (mTLS)
+    pub fn rustls_mtls_client_config(
+        &self,
+        roots: rustls::RootCertStore,
+        cert_chain: Vec<rustls::Certificate>,
+        key: rustls::PrivateKey,
+    ) -> Result<rustls::ClientConfig, TransportError> {
+        self.rustls_client_builder(roots)?
+            .with_client_auth_cert(cert_chain, key)
+            .map_err(|e| TransportError::Unavailable(e.to_string()))
+    }
+
+    fn rustls_client_builder(
+        &self,
+        roots: rustls::RootCertStore,
+    ) -> Result<rustls::ConfigBuilder<rustls::ClientConfig, rustls::client::WantsTransparencyPolicyOrClientCert>, TransportError> {
+        let params = self.rustls_parameters()?;
+        Ok(rustls::ClientConfig::builder()
+            .with_cipher_suites(&params.cipher_suites)
+            .with_kx_groups(&params.kx_groups)
+            .with_protocol_versions(&params.versions)
+            .map_err(|e| TransportError::Unavailable(e.to_string()))?
+            .with_root_certificates(roots))
+    }
+}
+
+impl FromStr for TlsProfile {
+    type Err = TransportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "modern" => Ok(TlsProfile::Modern),
+            "intermediate" => Ok(TlsProfile::Intermediate),
+            "restricted" | "fips" => Ok(TlsProfile::Restricted),
+            other => Err(TransportError::UnknownProfile(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for TlsProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsVersion::Tls12 => f.write_str("TLSv1.2"),
+            TlsVersion::Tls13 => f.write_str("TLSv1.3"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restricted_profile_is_aes_gcm_on_nist_curves() {
+        let policy = TlsProfile::Restricted.policy();
+        assert!(policy.cipher_suites.iter().all(|s| s.contains("AES") && s.contains("GCM")));
+        assert!(!policy.key_exchange_groups.contains(&"X25519".to_string()));
+        assert!(!policy.signature_schemes.contains(&"ed25519".to_string()));
+
+        let chacha = vec!["TLS13_CHACHA20_POLY1305_SHA256".to_string()];
+        assert!(matches!(
+            policy.check(&[TlsVersion::Tls13], &chacha, &[]),
+            Err(TransportError::SuiteNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_modern_profile_rejects_tls12() {
+        let policy = TlsProfile::Modern.policy();
+        let suites = vec!["TLS13_AES_128_GCM_SHA256".to_string()];
+        assert!(policy.check(&[TlsVersion::Tls13], &suites, &["X25519".to_string()]).is_ok());
+        assert_eq!(
+            policy.check(&[TlsVersion::Tls12], &suites, &[]),
+            Err(TransportError::VersionNotAllowed { profile: TlsProfile::Modern, version: TlsVersion::Tls12 })
+        );
+        assert_eq!("FIPS".parse::<TlsProfile>(), Ok(TlsProfile::Restricted));
+    }
+}
@@ -0,0 +1,275 @@
+//! Atualizações de firmware e da aplicação da urna
+//!
+//! O backend publica um manifesto por versão, assinado com Ed25519, que
+//! aponta para o artefato pelo SHA-256 e pelo tamanho. A urna só aceita o
+//! manifesto assinado pela chave fixada em sua configuração, endereçado ao
+//! seu canal e com versão estritamente maior que a instalada: uma versão
+//! antiga, ainda que assinada, nunca volta a ser aplicada.
+
+use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Contexto de domínio da assinatura do manifesto
+pub const UPDATE_SIGNATURE_CONTEXT: &str = "FORTIS-UPDATE-V1";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum UpdateError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Update manifest signature is invalid")]
+    InvalidSignature,
+    #[error("Update is for channel {0}")]
+    WrongChannel(String),
+    #[error("Version {offered} is not newer than installed {installed}")]
+    NotNewer { offered: UpdateVersion, installed: UpdateVersion },
+    #[error("Installed version {installed} is older than the minimum {minimum} for this update")]
+    BelowMinimum { installed: UpdateVersion, minimum: UpdateVersion },
+    #[error("Artifact size {actual} does not match manifest ({expected})")]
+    SizeMismatch { expected: u64, actual: u64 },
+    #[error("Artifact hash does not match manifest")]
+    HashMismatch,
+}
+
+/// Versão `major.minor.patch`, comparada numericamente
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UpdateVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl FromStr for UpdateVersion {
+    type Err = UpdateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('.').collect();
+        let [major, minor, patch] = parts.as_slice() else {
+            return Err(UpdateError::InvalidVersion(s.to_string()));
+        };
+        let number = |part: &str| part.parse::<u32>().map_err(|_| UpdateError::InvalidVersion(s.to_string()));
+        Ok(Self { major: number(major)?, minor: number(minor)?, patch: number(patch)? })
+    }
+}
+
+impl fmt::Display for UpdateVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl Serialize for UpdateVersion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for UpdateVersion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parte do software da urna coberta pela atualização
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateComponent {
+    Firmware,
+    Application,
+}
+
+impl UpdateComponent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateComponent::Firmware => "firmware",
+            UpdateComponent::Application => "application",
+        }
+    }
+}
+
+/// Manifesto assinado de uma versão publicada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateManifest {
+    pub update_id: Uuid,
+    pub component: UpdateComponent,
+    /// Canal de distribuição (por exemplo `stable` ou `pilot`)
+    pub channel: String,
+    pub version: UpdateVersion,
+    /// Versão instalada mínima para aplicar esta atualização
+    pub min_installed_version: Option<UpdateVersion>,
+    pub artifact_url: String,
+    pub artifact_sha256: String,
+    pub artifact_size: u64,
+    pub release_notes: Option<String>,
+    pub published_at: DateTime<Utc>,
+    /// Assinatura Ed25519 (hex) de `signed_message`
+    pub signature: String,
+}
+
+impl UpdateManifest {
+    /// Mensagem assinada: todos os campos, exceto a assinatura
+    pub fn signed_message(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            UPDATE_SIGNATURE_CONTEXT,
+            self.update_id,
+            self.component.as_str(),
+            self.channel,
+            self.version,
+            self.min_installed_version.map(|v| v.to_string()).unwrap_or_default(),
+            self.artifact_url,
+            self.artifact_sha256.to_lowercase(),
+            self.artifact_size,
+            hex::encode(Sha256::digest(self.release_notes.as_deref().unwrap_or_default().as_bytes())),
+            self.published_at.to_rfc3339(),
+        )
+        .into_bytes()
+    }
+
+    /// Confere a assinatura com a chave pública fixada
+    pub fn verify_signature(&self, public_key: &[u8]) -> Result<(), UpdateError> {
+        let signature = hex::decode(&self.signature).map_err(|_| UpdateError::InvalidSignature)?;
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&self.signed_message(), &signature)
+            .map_err(|_| UpdateError::InvalidSignature)
+    }
+
+    /// Confere assinatura, canal e monotonicidade da versão
+    pub fn check_applicable(&self, public_key: &[u8], channel: &str, installed: UpdateVersion) -> Result<(), UpdateError> {
+        self.verify_signature(public_key)?;
+        if self.channel != channel {
+            return Err(UpdateError::WrongChannel(self.channel.clone()));
+        }
+        if self.version <= installed {
+            return Err(UpdateError::NotNewer { offered: self.version, installed });
+        }
+        if let Some(minimum) = self.min_installed_version {
+            if installed < minimum {
+                return Err(UpdateError::BelowMinimum { installed, minimum });
+            }
+        }
+        Ok(())
+    }
+
+    /// Confere tamanho e SHA-256 do artefato baixado
+    pub fn verify_artifact(&self, artifact: &[u8]) -> Result<(), UpdateError> {
+        if artifact.len() as u64 != self.artifact_size {
+            return Err(UpdateError::SizeMismatch { expected: self.artifact_size, actual: artifact.len() as u64 });
+        }
+        if hex::encode(Sha256::digest(artifact)) != self.artifact_sha256.to_lowercase() {
+            return Err(UpdateError::HashMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Resultado da instalação na urna
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum InstallOutcome {
+    Applied,
+    /// Aplicada, mas revertida pela verificação de saúde
+    RolledBack,
+    /// Recusada antes de aplicar (assinatura, versão ou artefato)
+    Rejected,
+}
+
+/// Relatório da urna sobre uma atualização
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallReport {
+    pub update_id: Uuid,
+    pub component: UpdateComponent,
+    pub version: UpdateVersion,
+    pub outcome: InstallOutcome,
+    /// Versão em uso após a tentativa
+    pub running_version: UpdateVersion,
+    pub error: Option<String>,
+    pub reported_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    fn manifest(key_pair: &Ed25519KeyPair, version: &str, artifact: &[u8]) -> UpdateManifest {
+        let mut manifest = UpdateManifest {
+            update_id: Uuid::new_v4(),
+            component: UpdateComponent::Application,
+            channel: "stable".to_string(),
+            version: version.parse().unwrap(),
+            min_installed_version: Some("1.0.0".parse().unwrap()),
+            artifact_url: "https://updates.fortis.gov.br/urna/app.tar.zst".to_string(),
+            artifact_sha256: hex::encode(Sha256::digest(artifact)),
+            artifact_size: artifact.len() as u64,
+            release_notes: Some("Correções".to_string()),
+            published_at: Utc::now(),
+            signature: String::new(),
+        };
+        manifest.signature = hex::encode(key_pair.sign(&manifest.signed_message()).as_ref());
+        manifest
+    }
+
+    #[test]
+    fn test_versions_compare_numerically() {
+        let v = |s: &str| s.parse::<UpdateVersion>().unwrap();
+        assert!(v("1.10.0") > v("1.9.7"));
+        assert!(v("2.0.0") > v("1.99.99"));
+        assert_eq!(v("1.2.3").to_string(), "1.2.3");
+        assert!("1.2".parse::<UpdateVersion>().is_err());
+        assert!("1.2.x".parse::<UpdateVersion>().is_err());
+    }
+
+    #[test]
+    fn test_manifest_requires_signature_channel_and_newer_version() {
+        let key_pair = key_pair();
+        let public_key = key_pair.public_key().as_ref();
+        let manifest = manifest(&key_pair, "1.4.0", b"artifact");
+        let installed = "1.3.2".parse().unwrap();
+
+        assert_eq!(manifest.check_applicable(public_key, "stable", installed), Ok(()));
+        assert_eq!(
+            manifest.check_applicable(public_key, "pilot", installed),
+            Err(UpdateError::WrongChannel("stable".to_string()))
+        );
+        assert!(matches!(
+            manifest.check_applicable(public_key, "stable", "1.4.0".parse().unwrap()),
+            Err(UpdateError::NotNewer { .. })
+        ));
+        assert!(matches!(
+            manifest.check_applicable(public_key, "stable", "0.9.0".parse().unwrap()),
+            Err(UpdateError::BelowMinimum { .. })
+        ));
+
+        let mut tampered = manifest.clone();
+        tampered.artifact_url = "https://example.com/app.tar.zst".to_string();
+        assert_eq!(tampered.check_applicable(public_key, "stable", installed), Err(UpdateError::InvalidSignature));
+
+        let other = self::key_pair();
+        assert_eq!(
+            manifest.check_applicable(other.public_key().as_ref(), "stable", installed),
+            Err(UpdateError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_artifact_is_checked_against_manifest() {
+        let manifest = manifest(&key_pair(), "1.4.0", b"artifact");
+        assert_eq!(manifest.verify_artifact(b"artifact"), Ok(()));
+        assert_eq!(manifest.verify_artifact(b"artefato"), Err(UpdateError::HashMismatch));
+        assert!(matches!(manifest.verify_artifact(b"short"), Err(UpdateError::SizeMismatch { .. })));
+    }
+}
@@ -0,0 +1,103 @@
+//! Votos em claro e cifrados
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::ballot::ContestChoice;
+use crate::region::SectionRef;
+
+/// Voto em claro, existe apenas dentro da urna antes da cifragem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vote {
+    pub id: Uuid,
+    pub election_id: Uuid,
+    pub voter_id: Uuid,
+    /// Uma escolha por cargo, na ordem da cédula
+    pub choices: Vec<ContestChoice>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Voto cifrado, assinado e com prova ZK, como sai da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EncryptedVote {
+    pub id: Uuid,
+    pub election_id: Uuid,
+    pub voter_id: Uuid,
+    /// Escolha do primeiro cargo, para os consumidores de cédula de cargo único;
+    /// `Uuid::nil()` quando o voto no cargo é branco ou nulo
+    pub candidate_id: Uuid,
+    /// Uma escolha por cargo, na ordem da cédula; vazia em votos de cargo único
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<ContestChoice>,
+    /// Cédula cifrada, em base64 no JSON
+    #[serde(with = "base64_bytes")]
+    #[cfg_attr(feature = "openapi", schema(value_type = String, format = Byte))]
+    pub encrypted_data: Vec<u8>,
+    pub zk_proof: String,
+    pub signature: String,
+    pub timestamp: DateTime<Utc>,
+    /// Seção da urna que registrou o voto; fora da assinatura
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<SectionRef>,
+}
+
+impl EncryptedVote {
+    /// Hash da cédula cifrada, base do código de rastreamento
+    pub fn ballot_hash(&self) -> String {
+        hex::encode(Sha256::digest(&self.encrypted_data))
+    }
+
+    /// Conteúdo cifrado no formato armazenado pelo backend
+    pub fn to_vote_data(&self, encryption_key_id: &str) -> EncryptedVoteData {
+        EncryptedVoteData {
+            encrypted_content: general_purpose::STANDARD.encode(&self.encrypted_data),
+            encryption_key_id: encryption_key_id.to_string(),
+            signature: self.signature.clone(),
+            zk_proof: self.zk_proof.clone(),
+        }
+    }
+}
+
+/// Conteúdo cifrado de um voto recebido da urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EncryptedVoteData {
+    pub encrypted_content: String,
+    pub encryption_key_id: String,
+    pub signature: String,
+    pub zk_proof: String,
+}
+
+impl EncryptedVoteData {
+    pub fn encrypted_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        general_purpose::STANDARD.decode(&self.encrypted_content)
+    }
+}
+
+/// Estado de sincronização de um voto entre urna e backend
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum VoteSyncStatus {
+    Pending,
+    Synced,
+    Failed,
+    Confirmed,
+}
+
+mod base64_bytes {
+    use base64::{engine::general_purpose, Engine as _};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        general_purpose::STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,156 @@
+//! Envio de votos em lotes comprimidos, com retomada
+//!
+//! A urna divide os votos pendentes em partes de tamanho configurável,
+//! comprime cada parte com zstd (`Content-Encoding: zstd`) e envia com uma
+//! chave de idempotência derivada do envio e da posição da parte. Se o
+//! link cair, a urna retoma da primeira parte sem confirmação; o backend
+//! devolve a mesma confirmação para uma parte repetida, e um voto já
+//! registrado por outro caminho volta como `duplicate`, nunca como erro.
+//!
+//! Cada voto registrado volta com a prova de inclusão no log e a STH que a
+//! cobre; a urna só o dá por sincronizado depois de conferir as duas.
+//!
+//! A parte pode levar o contexto de rastreamento (W3C `traceparent`) do
+//! atendimento em que cada voto foi registrado, para que o processamento
+//! no backend continue o mesmo rastro distribuído.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::log_proof::VoteInclusion;
+use crate::vote::EncryptedVote;
+
+/// Cabeçalho HTTP com a chave de idempotência da parte
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Cabeçalho HTTP com o identificador de correlação da chamada
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Parte de um envio de votos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VoteSyncChunk {
+    pub urna_id: String,
+    /// Envio ao qual a parte pertence; fixo até a última parte ser confirmada
+    pub upload_id: Uuid,
+    pub chunk_index: u32,
+    pub chunk_count: u32,
+    pub votes: Vec<EncryptedVote>,
+    /// `traceparent` do registro de cada voto; fora do resumo do conteúdo
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub trace_contexts: BTreeMap<Uuid, String>,
+}
+
+impl VoteSyncChunk {
+    /// Chave de idempotência: a mesma parte do mesmo envio gera sempre a mesma chave
+    pub fn idempotency_key(&self) -> String {
+        format!("{}:{}:{}", self.urna_id, self.upload_id, self.chunk_index)
+    }
+
+    /// Resumo do conteúdo, para recusar outra parte enviada com a mesma chave
+    pub fn content_digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for vote in &self.votes {
+            hasher.update(vote.id.as_bytes());
+            hasher.update(vote.ballot_hash().as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Situação de um voto da parte
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum VoteSyncStatusCode {
+    Recorded,
+    /// Já registrado antes (reenvio ou outro caminho); conta como sincronizado
+    Duplicate,
+    Rejected,
+}
+
+/// Resultado de um voto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VoteSyncResult {
+    pub vote_id: Uuid,
+    pub status: VoteSyncStatusCode,
+    pub tracking_code: Option<String>,
+    pub error: Option<String>,
+    /// Prova de inclusão no log, presente para votos registrados
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inclusion: Option<VoteInclusion>,
+}
+
+impl VoteSyncResult {
+    /// Voto que a urna pode retirar da fila
+    pub fn is_synced(&self) -> bool {
+        matches!(self.status, VoteSyncStatusCode::Recorded | VoteSyncStatusCode::Duplicate)
+    }
+}
+
+/// Confirmação de uma parte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VoteSyncAck {
+    pub upload_id: Uuid,
+    pub chunk_index: u32,
+    pub results: Vec<VoteSyncResult>,
+    /// Verdadeiro quando a confirmação veio do registro de idempotência
+    pub replayed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn vote(data: &[u8]) -> EncryptedVote {
+        EncryptedVote {
+            id: Uuid::new_v4(),
+            election_id: Uuid::new_v4(),
+            voter_id: Uuid::new_v4(),
+            candidate_id: Uuid::new_v4(),
+            choices: Vec::new(),
+            encrypted_data: data.to_vec(),
+            zk_proof: String::new(),
+            signature: String::new(),
+            timestamp: Utc::now(),
+            section: None,
+        }
+    }
+
+    #[test]
+    fn test_idempotency_key_identifies_chunk_position() {
+        let chunk = VoteSyncChunk {
+            urna_id: "URNA-1".to_string(),
+            upload_id: Uuid::new_v4(),
+            chunk_index: 3,
+            chunk_count: 10,
+            votes: vec![vote(b"a")],
+            trace_contexts: BTreeMap::new(),
+        };
+        let mut next = chunk.clone();
+        next.chunk_index = 4;
+
+        assert_eq!(chunk.idempotency_key(), chunk.clone().idempotency_key());
+        assert_ne!(chunk.idempotency_key(), next.idempotency_key());
+    }
+
+    #[test]
+    fn test_content_digest_changes_with_votes() {
+        let chunk = VoteSyncChunk {
+            urna_id: "URNA-1".to_string(),
+            upload_id: Uuid::new_v4(),
+            chunk_index: 0,
+            chunk_count: 1,
+            votes: vec![vote(b"a"), vote(b"b")],
+            trace_contexts: BTreeMap::new(),
+        };
+        let mut tampered = chunk.clone();
+        tampered.votes[1].encrypted_data = b"c".to_vec();
+        assert_ne!(chunk.content_digest(), tampered.content_digest());
+    }
+}
@@ -0,0 +1,272 @@
+//! Acessibilidade no atendimento ao eleitor
+//!
+//! Cada atendimento tem um perfil próprio: guia por voz (síntese de fala na
+//! saída de áudio da urna, em geral o fone de ouvido), alto contraste e
+//! fonte ampliada. O mesário escolhe o perfil ao liberar o eleitor e pode
+//! alterá-lo durante o atendimento; conectar o fone liga o guia por voz.
+//! Ao fim do atendimento o perfil volta ao padrão, sem passar ao próximo
+//! eleitor.
+//!
+//! Com o guia por voz, a urna fala as instruções de cada cargo, cada tecla
+//! pressionada (o teclado tem as marcações em Braille e o ponto em relevo
+//! na tecla 5) e o candidato ou o voto em branco ou nulo antes da
+//! confirmação, com a voz do idioma do atendimento.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::RwLock;
+
+/// Caminho da política no pacote de configuração
+pub const ACCESSIBILITY_POLICY_PATH: &str = "/etc/fortis/bundle/accessibility_policy.json";
+
+/// Cores do modo de alto contraste
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContrastPalette {
+    pub foreground: String,
+    pub background: String,
+    /// Cor do campo em edição e do candidato selecionado
+    pub highlight: String,
+}
+
+/// Parâmetros de acessibilidade, publicados no pacote de configuração
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityPolicy {
+    /// Sintetizador de fala (espeak-ng ou compatível)
+    pub tts_command: String,
+    pub tts_voice: String,
+    /// Velocidade da fala, em palavras por minuto
+    pub speech_rate_wpm: u32,
+    /// Amplitude da fala, de 0 a 200
+    pub speech_amplitude: u32,
+    pub normal_palette: ContrastPalette,
+    pub high_contrast_palette: ContrastPalette,
+    pub normal_font_px: u32,
+    /// Fator da fonte ampliada
+    pub large_font_scale: f32,
+    /// Estado do conector do fone (`1` quando conectado)
+    pub headphone_jack: PathBuf,
+    /// Liga o guia por voz quando o fone está conectado no início do atendimento
+    pub audio_on_headphones: bool,
+}
+
+impl Default for AccessibilityPolicy {
+    fn default() -> Self {
+        Self {
+            tts_command: "espeak-ng".to_string(),
+            tts_voice: "pt-br".to_string(),
+            speech_rate_wpm: 150,
+            speech_amplitude: 120,
+            normal_palette: ContrastPalette {
+                foreground: "#1A1A1A".to_string(),
+                background: "#F2F2F2".to_string(),
+                highlight: "#1565C0".to_string(),
+            },
+            high_contrast_palette: ContrastPalette {
+                foreground: "#FFFFFF".to_string(),
+                background: "#000000".to_string(),
+                highlight: "#FFFF00".to_string(),
+            },
+            normal_font_px: 28,
+            large_font_scale: 1.75,
+            headphone_jack: PathBuf::from("/sys/class/fortis/headphone/state"),
+            audio_on_headphones: true,
+        }
+    }
+}
+
+impl AccessibilityPolicy {
+    /// Carrega a política do pacote, com os valores padrão se indisponível
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| Ok(serde_json::from_slice(&data)?)) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::warn!("Accessibility policy unavailable, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Recursos ligados no atendimento
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct AccessibilityProfile {
+    pub audio_guidance: bool,
+    pub high_contrast: bool,
+    pub large_font: bool,
+}
+
+/// Estilo de renderização da tela para o perfil
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderStyle {
+    pub font_px: u32,
+    pub palette: ContrastPalette,
+}
+
+/// Síntese de fala na saída de áudio da urna
+pub struct TextToSpeech {
+    command: String,
+    voice: String,
+    rate_wpm: u32,
+    amplitude: u32,
+}
+
+impl TextToSpeech {
+    pub fn new(policy: &AccessibilityPolicy) -> Self {
+        Self {
+            command: policy.tts_command.clone(),
+            voice: policy.tts_voice.clone(),
+            rate_wpm: policy.speech_rate_wpm,
+            amplitude: policy.speech_amplitude.min(200),
+        }
+    }
+
+    /// Confere se o sintetizador responde
+    pub async fn check(&self) -> Result<()> {
+        let status = Command::new(&self.command).arg("--version").output().await?.status;
+        if !status.success() {
+            return Err(anyhow!("{} exited with {}", self.command, status));
+        }
+        Ok(())
+    }
+
+    /// Fala o texto e aguarda o fim da reprodução; `voice` troca a voz padrão
+    pub async fn speak(&self, text: &str, voice: Option<&str>) -> Result<()> {
+        let status = Command::new(&self.command)
+            .args(["-v", voice.unwrap_or(&self.voice)])
+            .args(["-s", &self.rate_wpm.to_string()])
+            .args(["-a", &self.amplitude.to_string()])
+            .arg("--")
+            .arg(text)
+            .kill_on_drop(true)
+            .status()
+            .await?;
+        if !status.success() {
+            return Err(anyhow!("{} exited with {}", self.command, status));
+        }
+        Ok(())
+    }
+}
+
+pub struct AccessibilityManager {
+    policy: AccessibilityPolicy,
+    tts: TextToSpeech,
+    profile: RwLock<AccessibilityProfile>,
+    /// Voz do idioma do atendimento
+    voice: RwLock<Option<String>>,
+}
+
+impl AccessibilityManager {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_policy(AccessibilityPolicy::load(Path::new(ACCESSIBILITY_POLICY_PATH))))
+    }
+
+    pub fn with_policy(policy: AccessibilityPolicy) -> Self {
+        Self {
+            tts: TextToSpeech::new(&policy),
+            policy,
+            profile: RwLock::new(AccessibilityProfile::default()),
+            voice: RwLock::new(None),
+        }
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing accessibility manager");
+        // Sem sintetizador a urna segue sem guia por voz, como sem fone
+        if let Err(e) = self.tts.check().await {
+            log::warn!("Text-to-speech unavailable ({}): {}", self.policy.tts_command, e);
+        }
+        Ok(())
+    }
+
+    /// Perfil do atendimento que começa; o fone conectado liga o guia por voz
+    pub async fn start_session(&self, requested: Option<AccessibilityProfile>) -> AccessibilityProfile {
+        let mut profile = requested.unwrap_or_default();
+        if self.policy.audio_on_headphones && self.headphones_connected().await {
+            profile.audio_guidance = true;
+        }
+        *self.profile.write().await = profile;
+        log::info!("Voter session accessibility: {:?}", profile);
+        profile
+    }
+
+    /// Troca o perfil durante o atendimento
+    pub async fn set_profile(&self, profile: AccessibilityProfile) {
+        log::info!("Accessibility changed during session: {:?}", profile);
+        *self.profile.write().await = profile;
+    }
+
+    /// Voz do idioma escolhido; `None` volta à voz da política
+    pub async fn set_voice(&self, voice: Option<String>) {
+        *self.voice.write().await = voice;
+    }
+
+    /// Volta ao padrão ao fim do atendimento
+    pub async fn end_session(&self) {
+        *self.profile.write().await = AccessibilityProfile::default();
+        *self.voice.write().await = None;
+    }
+
+    pub async fn profile(&self) -> AccessibilityProfile {
+        *self.profile.read().await
+    }
+
+    /// Estilo da tela para o perfil atual
+    pub async fn style(&self) -> RenderStyle {
+        let profile = self.profile().await;
+        let font_px = if profile.large_font {
+            (self.policy.normal_font_px as f32 * self.policy.large_font_scale).round() as u32
+        } else {
+            self.policy.normal_font_px
+        };
+        let palette = if profile.high_contrast {
+            self.policy.high_contrast_palette.clone()
+        } else {
+            self.policy.normal_palette.clone()
+        };
+        RenderStyle { font_px, palette }
+    }
+
+    /// Fala o texto quando o guia por voz está ligado
+    ///
+    /// Falha da síntese não interrompe o atendimento: o texto continua na tela.
+    pub async fn speak(&self, text: &str) {
+        if !self.profile().await.audio_guidance {
+            return;
+        }
+        let voice = self.voice.read().await.clone();
+        if let Err(e) = self.tts.speak(text, voice.as_deref()).await {
+            log::warn!("Text-to-speech failed: {}", e);
+        }
+    }
+
+    async fn headphones_connected(&self) -> bool {
+        match tokio::fs::read_to_string(&self.policy.headphone_jack).await {
+            Ok(state) => state.trim() == "1",
+            Err(_) => false,
+        }
+    }
+}
+
+/// Dígitos falados um a um (`13` vira `1, 3`)
+pub fn spoken_digits(number: u32) -> String {
+    let digits: Vec<String> = number.to_string().chars().map(String::from).collect();
+    digits.join(", ")
+}
+
+/// Cela Braille da tecla, com o sinal de número antes dos dígitos
+pub fn braille_label(label: &str) -> String {
+    const DIGITS: [char; 10] = ['⠚', '⠁', '⠃', '⠉', '⠙', '⠑', '⠋', '⠛', '⠓', '⠊'];
+    match label.parse::<usize>() {
+        Ok(digit) if digit < 10 => format!("⠼{}", DIGITS[digit]),
+        _ => match label {
+            "ENTER" => "⠉⠕⠝⠋⠊⠗⠍⠁".to_string(),
+            "CANCEL" => "⠉⠕⠗⠗⠊⠛⠑".to_string(),
+            "BRANCO" => "⠃⠗⠁⠝⠉⠕".to_string(),
+            other => other.to_string(),
+        },
+    }
+}
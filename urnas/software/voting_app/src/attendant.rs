@@ -0,0 +1,144 @@
+//! Tempo limite do atendimento e liberação pelo mesário
+//!
+//! Sem interação do eleitor por `idle_timeout_seconds`, o atendimento é
+//! cancelado e a urna fica bloqueada, com alerta sonoro, até o mesário
+//! pressionar a tecla de liberação no terminal e digitar o PIN. O PIN é
+//! conferido pelo resumo salgado do pacote de configuração; tentativas
+//! erradas em excesso bloqueiam novas tentativas por um período.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+/// Política do tempo limite no pacote de configuração
+pub const SESSION_TIMEOUT_POLICY_PATH: &str = "/etc/fortis/bundle/session_timeout.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionTimeoutPolicy {
+    /// Inatividade do eleitor que encerra o atendimento
+    pub idle_timeout_seconds: u64,
+    pub max_pin_attempts: u32,
+    pub pin_lockout_seconds: i64,
+    /// Sal do PIN do mesário
+    pub pin_salt: String,
+    /// SHA-256 (hex) de sal + PIN; sem ele a urna não pode ser liberada
+    pub pin_sha256: Option<String>,
+}
+
+impl Default for SessionTimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            idle_timeout_seconds: 90,
+            max_pin_attempts: 3,
+            pin_lockout_seconds: 300,
+            pin_salt: String::new(),
+            pin_sha256: None,
+        }
+    }
+}
+
+impl SessionTimeoutPolicy {
+    /// Carrega a política do pacote, com os valores padrão se indisponível
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| Ok(serde_json::from_slice(&data)?)) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::warn!("Session timeout policy unavailable, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn idle_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.idle_timeout_seconds)
+    }
+}
+
+/// Momento da última interação do eleitor
+#[derive(Debug)]
+pub struct ActivityClock {
+    last: std::sync::Mutex<Instant>,
+}
+
+impl ActivityClock {
+    pub fn new() -> Self {
+        Self { last: std::sync::Mutex::new(Instant::now()) }
+    }
+
+    pub fn touch(&self) {
+        if let Ok(mut last) = self.last.lock() {
+            *last = Instant::now();
+        }
+    }
+
+    pub fn idle(&self) -> std::time::Duration {
+        self.last.lock().map(|last| last.elapsed()).unwrap_or_default()
+    }
+}
+
+impl Default for ActivityClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default)]
+struct PinAttempts {
+    failures: u32,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+/// Conferência do PIN do mesário para liberar a urna
+#[derive(Debug)]
+pub struct AttendantOverride {
+    pub policy: SessionTimeoutPolicy,
+    attempts: Mutex<PinAttempts>,
+}
+
+impl AttendantOverride {
+    pub fn new(policy: SessionTimeoutPolicy) -> Self {
+        Self { policy, attempts: Mutex::new(PinAttempts::default()) }
+    }
+
+    /// Confere o PIN, contando as tentativas erradas
+    pub async fn verify_pin(&self, pin: &str) -> Result<()> {
+        let mut attempts = self.attempts.lock().await;
+        if let Some(until) = attempts.locked_until {
+            if Utc::now() < until {
+                return Err(anyhow!("Mesário PIN entry locked until {}", until));
+            }
+            attempts.locked_until = None;
+        }
+        let expected = self.policy.pin_sha256.as_deref()
+            .ok_or_else(|| anyhow!("No mesário PIN configured"))?;
+
+        if pin_matches(&self.policy.pin_salt, pin, expected) {
+            *attempts = PinAttempts::default();
+            return Ok(());
+        }
+
+        attempts.failures += 1;
+        if attempts.failures >= self.policy.max_pin_attempts {
+            let until = Utc::now() + Duration::seconds(self.policy.pin_lockout_seconds);
+            *attempts = PinAttempts { failures: 0, locked_until: Some(until) };
+            return Err(anyhow!("Invalid mesário PIN, entry locked until {}", until));
+        }
+        Err(anyhow!(
+            "Invalid mesário PIN ({} attempts left)",
+            self.policy.max_pin_attempts - attempts.failures
+        ))
+    }
+}
+
+/// Confere o PIN com o SHA-256 (hex) de sal + PIN, em tempo constante
+pub fn pin_matches(salt: &str, pin: &str, expected_sha256: &str) -> bool {
+    let digest = hex::encode(Sha256::digest(format!("{}{}", salt, pin).as_bytes()));
+    let expected = expected_sha256.to_ascii_lowercase();
+    digest.len() == expected.len()
+        && digest.bytes().zip(expected.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,162 @@
+//! Atestação remota do boot na abertura da sessão
+//!
+//! A urna pede ao backend um desafio de uso único, cita pelo TPM os PCRs
+//! do boot com a chave de atestação (AK) e envia a citação com as versões
+//! de firmware e da aplicação em uso. Só com o veredito `trusted` a sessão
+//! de votação é aberta; a reprovação bloqueia a abertura e fica na trilha
+//! de auditoria. Sem conexão com o backend, a política decide se a urna
+//! abre a sessão com a atestação adiada.
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use fortis_domain::attestation::{AttestationChallenge, AttestationReport, ATTESTED_PCRS};
+use fortis_domain::update::UpdateVersion;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::lockdown::tpm2;
+
+/// Política de atestação no pacote de configuração
+pub const ATTESTATION_POLICY_PATH: &str = "/etc/fortis/bundle/attestation_policy.json";
+
+/// Handle persistente da chave de atestação no TPM
+pub const ATTESTATION_KEY_HANDLE: &str = "0x81010003";
+
+/// Variável EFI `SecureBoot` (quatro bytes de atributos e o estado)
+pub const SECURE_BOOT_EFIVAR: &str = "/sys/firmware/efi/efivars/SecureBoot-8be4df61-93ca-11d2-aa0d-00e098032b8c";
+
+/// Arquivos temporários da citação
+const QUOTE_WORK_DIR: &str = "/run/fortis/attestation";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttestationPolicy {
+    pub enabled: bool,
+    /// Abre a sessão sem atestação quando o backend está inacessível
+    pub allow_offline_open: bool,
+}
+
+impl Default for AttestationPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allow_offline_open: true,
+        }
+    }
+}
+
+impl AttestationPolicy {
+    /// Carrega a política do pacote, com os valores padrão se indisponível
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| Ok(serde_json::from_slice(&data)?)) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::warn!("Attestation policy unavailable, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Citação do TPM sobre os PCRs do boot
+#[derive(Debug)]
+pub struct BootAttestation {
+    pub policy: AttestationPolicy,
+    work_dir: PathBuf,
+}
+
+impl BootAttestation {
+    pub fn new(policy: AttestationPolicy) -> Self {
+        Self {
+            policy,
+            work_dir: PathBuf::from(QUOTE_WORK_DIR),
+        }
+    }
+
+    /// Monta o relatório para o desafio recebido
+    pub async fn report(
+        &self,
+        challenge: &AttestationChallenge,
+        election_id: Uuid,
+        firmware_version: UpdateVersion,
+        application_version: UpdateVersion,
+    ) -> Result<AttestationReport> {
+        let selection = format!(
+            "sha256:{}",
+            ATTESTED_PCRS.iter().map(|index| index.to_string()).collect::<Vec<_>>().join(",")
+        );
+        let pcrs = parse_pcr_values(&String::from_utf8_lossy(&tpm2(&["tpm2_pcrread", &selection], None).await?))?;
+
+        tokio::fs::create_dir_all(&self.work_dir).await?;
+        let message = self.work_dir.join("quote.msg");
+        let signature = self.work_dir.join("quote.sig");
+        let quoted = tpm2(
+            &[
+                "tpm2_quote",
+                "-c", ATTESTATION_KEY_HANDLE,
+                "-l", &selection,
+                "-q", &challenge.nonce,
+                "-g", "sha256",
+                "-f", "plain",
+                "-m", &message.to_string_lossy(),
+                "-s", &signature.to_string_lossy(),
+            ],
+            None,
+        )
+        .await;
+        let files = match quoted {
+            Ok(_) => tokio::try_join!(tokio::fs::read(&message), tokio::fs::read(&signature)).map_err(anyhow::Error::from),
+            Err(e) => Err(e),
+        };
+        for path in [&message, &signature] {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        let (quote, signature) = files?;
+
+        Ok(AttestationReport {
+            urna_id: challenge.urna_id.clone(),
+            election_id,
+            nonce: challenge.nonce.clone(),
+            secure_boot_enabled: secure_boot_enabled().await,
+            firmware_version,
+            application_version,
+            pcrs,
+            quote: hex::encode(quote),
+            signature: hex::encode(signature),
+            reported_at: Utc::now(),
+        })
+    }
+}
+
+/// Estado do boot seguro; sem a variável EFI conta como desabilitado
+async fn secure_boot_enabled() -> bool {
+    match tokio::fs::read(SECURE_BOOT_EFIVAR).await {
+        Ok(data) => data.get(4) == Some(&1),
+        Err(e) => {
+            log::warn!("Secure boot state unavailable: {}", e);
+            false
+        }
+    }
+}
+
+/// Valores da saída do `tpm2_pcrread` (`  7 : 0x…`)
+fn parse_pcr_values(output: &str) -> Result<BTreeMap<u32, String>> {
+    let mut pcrs = BTreeMap::new();
+    for line in output.lines() {
+        let Some((index, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (Ok(index), Some(value)) = (index.trim().parse::<u32>(), value.trim().strip_prefix("0x")) else {
+            continue;
+        };
+        pcrs.insert(index, value.to_lowercase());
+    }
+    for index in ATTESTED_PCRS {
+        if !pcrs.contains_key(&index) {
+            return Err(anyhow!("PCR {} missing from tpm2_pcrread output", index));
+        }
+    }
+    Ok(pcrs)
+}
@@ -1,27 +1,44 @@
 //! Módulo de autenticação biométrica para urna eletrônica
+//!
+//! A impressão digital é o método principal. Se a captura da digital falha
+//! várias vezes seguidas (dedo gasto, ferido ou leitor com problema), a
+//! urna passa ao reconhecimento facial com verificação de vivacidade. Toda
+//! passagem para o reconhecimento facial é registrada na auditoria.
 
 use anyhow::{Result, anyhow};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use sha2::{Sha256, Digest};
 use base64::{Engine as _, engine::general_purpose};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
 
-#[derive(Debug, Clone)]
-pub struct BiometricData {
-    pub fingerprint: Vec<u8>,
-    pub fingerprint_hash: String,
-    pub facial_data: Vec<u8>,
-    pub facial_hash: String,
-    pub timestamp: DateTime<Utc>,
+use crate::audit::AuditLogger;
+use crate::messages::{UrnaError, codes};
+use crate::hardware::HardwareManager;
+use crate::hardware::camera::face_similarity;
+
+pub use crate::hardware::BiometricData;
+pub use crate::hardware::smartcard::VoterCertificate;
+
+/// Vetores faciais cadastrados dos eleitores da seção
+pub const FACE_ENROLLMENT_PATH: &str = "/var/lib/fortis/voters/faces.json";
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct FaceEnrollment {
+    voter_id: Uuid,
+    embedding: Vec<f32>,
 }
 
-#[derive(Debug, Clone)]
-pub struct CertificateData {
-    pub certificate: Vec<u8>,
-    pub certificate_hash: String,
-    pub issuer: String,
-    pub valid_until: DateTime<Utc>,
-    pub serial_number: String,
+/// Identificação civil dos eleitores da seção, usada no segundo fator
+pub const VOTER_IDENTITY_PATH: &str = "/var/lib/fortis/voters/identities.json";
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct VoterIdentity {
+    voter_id: Uuid,
+    cpf: Option<String>,
+    voter_registration: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,12 +54,21 @@ pub enum AuthMethod {
     BiometricOnly,
     BiometricAndCertificate,
     CertificateOnly,
+    FacialFallback,
 }
 
 pub struct BiometricAuth {
     pub threshold: f32,
     pub max_attempts: u32,
     pub lockout_duration: u64,
+    /// Falhas de captura da digital antes de usar o reconhecimento facial
+    pub fingerprint_failures_before_fallback: u32,
+    /// Similaridade mínima entre o rosto capturado e o cadastrado
+    pub face_threshold: f32,
+    pub face_enrollment_path: PathBuf,
+    face_enrollments: RwLock<HashMap<Uuid, Vec<f32>>>,
+    pub voter_identity_path: PathBuf,
+    voter_identities: RwLock<Vec<VoterIdentity>>,
 }
 
 impl BiometricAuth {
@@ -51,6 +77,12 @@ impl BiometricAuth {
             threshold: 0.85,
             max_attempts: 3,
             lockout_duration: 300, // 5 minutos
+            fingerprint_failures_before_fallback: 3,
+            face_threshold: 0.6,
+            face_enrollment_path: PathBuf::from(FACE_ENROLLMENT_PATH),
+            face_enrollments: RwLock::new(HashMap::new()),
+            voter_identity_path: PathBuf::from(VOTER_IDENTITY_PATH),
+            voter_identities: RwLock::new(Vec::new()),
         })
     }
 
@@ -87,14 +119,34 @@ impl BiometricAuth {
     }
 
     async fn initialize_facial_reader(&self) -> Result<()> {
-        // Em implementação real, inicializaria hardware real
-        log::info!("Facial reader initialized");
+        // Sem cadastro facial a urna opera só com a digital
+        let enrollments: Vec<FaceEnrollment> = match tokio::fs::read(&self.face_enrollment_path).await {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) => {
+                log::warn!("Face enrollments unavailable, facial fallback disabled: {}", e);
+                Vec::new()
+            }
+        };
+
+        let mut faces = self.face_enrollments.write().await;
+        faces.clear();
+        faces.extend(enrollments.into_iter().map(|e| (e.voter_id, e.embedding)));
+        log::info!("Facial reader initialized with {} enrollments", faces.len());
         Ok(())
     }
 
     async fn initialize_certificate_reader(&self) -> Result<()> {
-        // Em implementação real, inicializaria hardware real
-        log::info!("Certificate reader initialized");
+        // Sem a identificação civil o cartão não pode confirmar o eleitor
+        let identities: Vec<VoterIdentity> = match tokio::fs::read(&self.voter_identity_path).await {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) => {
+                log::warn!("Voter identities unavailable, certificate second factor disabled: {}", e);
+                Vec::new()
+            }
+        };
+
+        log::info!("Certificate reader initialized with {} voter identities", identities.len());
+        *self.voter_identities.write().await = identities;
         Ok(())
     }
 
@@ -108,7 +160,7 @@ impl BiometricAuth {
     pub async fn authenticate_voter(
         &self,
         biometric_data: &BiometricData,
-        certificate_data: Option<&CertificateData>,
+        certificate_data: Option<&VoterCertificate>,
     ) -> Result<Uuid> {
         log::info!("Starting voter authentication");
 
@@ -118,37 +170,19 @@ impl BiometricAuth {
         // Autenticar por biometria
         let biometric_result = self.authenticate_biometric(biometric_data).await?;
         
-        // Autenticar por certificado (se fornecido)
-        let certificate_result = if let Some(cert) = certificate_data {
-            Some(self.authenticate_certificate(cert).await?)
-        } else {
-            None
-        };
+        if biometric_result.confidence_score < self.threshold {
+            return Err(anyhow!("Biometric authentication failed"));
+        }
+        let voter_id = biometric_result.voter_id;
 
-        // Determinar método de autenticação
-        let auth_method = if certificate_result.is_some() {
+        // Autenticação dupla: o cartão precisa ser do eleitor da biometria
+        let auth_method = if let Some(cert) = certificate_data {
+            self.authenticate_certificate(cert, voter_id).await?;
             AuthMethod::BiometricAndCertificate
         } else {
             AuthMethod::BiometricOnly
         };
 
-        // Verificar se autenticação foi bem-sucedida
-        let voter_id = if let Some(cert_result) = certificate_result {
-            // Autenticação dupla: biometria + certificado
-            if biometric_result.confidence_score >= self.threshold && cert_result {
-                biometric_result.voter_id
-            } else {
-                return Err(anyhow!("Biometric and certificate authentication failed"));
-            }
-        } else {
-            // Autenticação apenas por biometria
-            if biometric_result.confidence_score >= self.threshold {
-                biometric_result.voter_id
-            } else {
-                return Err(anyhow!("Biometric authentication failed"));
-            }
-        };
-
         // Log de autenticação bem-sucedida
         self.log_successful_auth(voter_id, &auth_method).await?;
 
@@ -156,21 +190,96 @@ impl BiometricAuth {
         Ok(voter_id)
     }
 
+    /// Autentica pela digital e, após falhas seguidas de captura, pelo rosto
+    pub async fn authenticate_with_fallback(
+        &self,
+        hardware: &HardwareManager,
+        audit: &AuditLogger,
+        certificate_data: Option<&VoterCertificate>,
+    ) -> Result<Uuid> {
+        let mut failures = Vec::new();
+        for attempt in 1..=self.fingerprint_failures_before_fallback {
+            match hardware.capture_biometric_data().await {
+                Ok(biometric_data) => return self.authenticate_voter(&biometric_data, certificate_data).await,
+                Err(e) => {
+                    log::warn!("Fingerprint capture failed ({}/{}): {}", attempt, self.fingerprint_failures_before_fallback, e);
+                    failures.push(e.to_string());
+                }
+            }
+        }
+
+        log::warn!("Falling back to facial recognition");
+        audit.log_event(
+            "BiometricFallbackStarted",
+            &serde_json::json!({
+                "fingerprint_failures": failures,
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        let result = self.authenticate_face(hardware, certificate_data).await;
+        audit.log_event(
+            "BiometricFallbackCompleted",
+            &serde_json::json!({
+                "success": result.is_ok(),
+                "voter_id": result.as_ref().ok().map(|r| r.voter_id),
+                "match_score": result.as_ref().ok().map(|r| r.confidence_score),
+                "error": result.as_ref().err().map(|e| e.to_string()),
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        let result = result?;
+        self.log_successful_auth(result.voter_id, &result.auth_method).await?;
+        Ok(result.voter_id)
+    }
+
+    async fn authenticate_face(
+        &self,
+        hardware: &HardwareManager,
+        certificate_data: Option<&VoterCertificate>,
+    ) -> Result<AuthenticationResult> {
+        self.check_auth_attempts().await?;
+
+        // Vivacidade verificada na captura; rosto parado ou fraude é recusado
+        let face = hardware.biometric_reader.capture_face().await?;
+        let (voter_id, score) = self.find_voter_by_face(&face.embedding).await?;
+        if score < self.face_threshold {
+            return Err(anyhow!(
+                "Facial match score {:.2} below threshold {:.2} (liveness {:.2})",
+                score, self.face_threshold, face.liveness_score
+            ));
+        }
+
+        if let Some(cert) = certificate_data {
+            self.authenticate_certificate(cert, voter_id).await?;
+        }
+
+        Ok(AuthenticationResult {
+            voter_id,
+            auth_method: AuthMethod::FacialFallback,
+            confidence_score: score,
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Eleitor cadastrado com maior similaridade facial
+    async fn find_voter_by_face(&self, embedding: &[f32]) -> Result<(Uuid, f32)> {
+        self.face_enrollments
+            .read()
+            .await
+            .iter()
+            .map(|(voter_id, enrolled)| (*voter_id, face_similarity(embedding, enrolled)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .ok_or_else(|| anyhow!("No face enrollments available"))
+    }
+
     async fn authenticate_biometric(&self, biometric_data: &BiometricData) -> Result<AuthenticationResult> {
         // Verificar impressão digital
-        let fingerprint_match = self.verify_fingerprint(&biometric_data.fingerprint).await?;
-        
-        // Verificar reconhecimento facial
-        let facial_match = self.verify_facial(&biometric_data.facial_data).await?;
-        
-        // Calcular score de confiança
-        let confidence_score = (fingerprint_match + facial_match) / 2.0;
+        let confidence_score = self.verify_fingerprint(&biometric_data.fingerprint).await?;
         
         // Buscar eleitor no banco de dados
-        let voter_id = self.find_voter_by_biometrics(
-            &biometric_data.fingerprint_hash,
-            &biometric_data.facial_hash
-        ).await?;
+        let voter_id = self.find_voter_by_biometrics(&biometric_data.fingerprint_hash).await?;
 
         Ok(AuthenticationResult {
             voter_id,
@@ -198,65 +307,42 @@ impl BiometricAuth {
         Ok(score)
     }
 
-    async fn verify_facial(&self, facial_data: &[u8]) -> Result<f32> {
-        // Em implementação real, faria verificação real do rosto
-        // Por enquanto, simula verificação
-        let hash = self.calculate_facial_hash(facial_data)?;
-        
-        // Simular score baseado no hash
-        let score = if hash.starts_with("match") {
-            0.92
-        } else if hash.starts_with("partial") {
-            0.70
-        } else {
-            0.20
-        };
-
-        log::debug!("Facial verification score: {}", score);
-        Ok(score)
-    }
-
-    async fn authenticate_certificate(&self, certificate_data: &CertificateData) -> Result<bool> {
-        // Verificar validade do certificado
-        if Utc::now() > certificate_data.valid_until {
-            return Err(anyhow!("Certificate expired"));
+    /// Segundo fator: certificado válido cujo titular é o eleitor identificado
+    ///
+    /// A cadeia até a raiz ICP-Brasil já foi validada na leitura do cartão.
+    async fn authenticate_certificate(&self, certificate: &VoterCertificate, voter_id: Uuid) -> Result<()> {
+        if Utc::now() > certificate.valid_until {
+            return Err(UrnaError::new(codes::CERTIFICATE_INVALID, "Certificate expired").into());
         }
 
-        // Verificar emissor do certificado
-        if !self.is_valid_issuer(&certificate_data.issuer).await? {
-            return Err(anyhow!("Invalid certificate issuer"));
+        if self.is_certificate_revoked(&certificate.serial_number).await? {
+            return Err(UrnaError::new(codes::CERTIFICATE_INVALID, "Certificate revoked").into());
         }
 
-        // Verificar assinatura do certificado
-        if !self.verify_certificate_signature(certificate_data).await? {
-            return Err(anyhow!("Invalid certificate signature"));
-        }
-
-        // Verificar se certificado não foi revogado
-        if self.is_certificate_revoked(&certificate_data.serial_number).await? {
-            return Err(anyhow!("Certificate revoked"));
+        let holder = self.find_voter_by_certificate(certificate).await.ok_or_else(|| {
+            UrnaError::new(codes::CERTIFICATE_MISMATCH, "Certificate holder is not a voter of this section")
+        })?;
+        if holder != voter_id {
+            return Err(UrnaError::new(
+                codes::CERTIFICATE_MISMATCH,
+                "Certificate holder differs from the biometric match",
+            ).into());
         }
 
         log::debug!("Certificate authentication successful");
-        Ok(true)
-    }
-
-    async fn is_valid_issuer(&self, issuer: &str) -> Result<bool> {
-        let valid_issuers = vec![
-            "ICP-Brasil",
-            "AC Raiz v1",
-            "AC Raiz v2",
-            "AC Raiz v3",
-            "AC Raiz v4",
-            "AC Raiz v5",
-        ];
-        Ok(valid_issuers.contains(&issuer))
+        Ok(())
     }
 
-    async fn verify_certificate_signature(&self, certificate_data: &CertificateData) -> Result<bool> {
-        // Em implementação real, faria verificação real da assinatura
-        // Por enquanto, simula verificação
-        Ok(certificate_data.signature.len() > 0)
+    /// Eleitor pelo título (e-Título) ou, na falta dele, pelo CPF (e-CPF)
+    async fn find_voter_by_certificate(&self, certificate: &VoterCertificate) -> Option<Uuid> {
+        let identities = self.voter_identities.read().await;
+        let by_registration = certificate.voter_registration.as_ref().and_then(|registration| {
+            identities.iter().find(|v| v.voter_registration.as_ref() == Some(registration))
+        });
+        let by_cpf = || certificate.cpf.as_ref().and_then(|cpf| {
+            identities.iter().find(|v| v.cpf.as_ref() == Some(cpf))
+        });
+        by_registration.or_else(by_cpf).map(|v| v.voter_id)
     }
 
     async fn is_certificate_revoked(&self, serial_number: &str) -> Result<bool> {
@@ -265,14 +351,10 @@ impl BiometricAuth {
         Ok(false)
     }
 
-    async fn find_voter_by_biometrics(
-        &self,
-        fingerprint_hash: &str,
-        facial_hash: &str,
-    ) -> Result<Uuid> {
+    async fn find_voter_by_biometrics(&self, fingerprint_hash: &str) -> Result<Uuid> {
         // Em implementação real, buscaria no banco de dados
         // Por enquanto, simula busca
-        if fingerprint_hash == "dummy_fingerprint_hash" && facial_hash == "dummy_facial_hash" {
+        if fingerprint_hash == "dummy_fingerprint_hash" {
             Ok(Uuid::new_v4())
         } else {
             Err(anyhow!("Voter not found"))
@@ -316,11 +398,4 @@ impl BiometricAuth {
         let hash = hasher.finalize();
         Ok(general_purpose::STANDARD.encode(hash))
     }
-
-    fn calculate_facial_hash(&self, facial_data: &[u8]) -> Result<String> {
-        let mut hasher = Sha256::new();
-        hasher.update(facial_data);
-        let hash = hasher.finalize();
-        Ok(general_purpose::STANDARD.encode(hash))
-    }
 }
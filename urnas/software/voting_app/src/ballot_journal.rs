@@ -0,0 +1,262 @@
+//! Diário do atendimento em curso
+//!
+//! Cada atendimento grava a sua fase em disco, com `fsync`, antes de
+//! avançar: eleitor liberado, eleitor autenticado, voto em registro e voto
+//! registrado. Se a energia cair no meio do atendimento, o diário
+//! encontrado na inicialização diz o que aconteceu. Voto que já chegou à
+//! fila de saída vale, e o eleitor fica como votante. A fila prevalece
+//! sobre o diário: se a gravação da fase "registrado" falhar depois do
+//! enfileiramento, o voto continua valendo, ainda que já entregue ao
+//! backend e fora da fila. Em qualquer outra
+//! fase o atendimento é anulado, sem voto, e a anulação vai para a trilha
+//! de auditoria. O diário anulado continua gravado até o mesário autorizar
+//! o recomeço, de modo que um novo reinício não libera a urna sozinho; o
+//! eleitor então é admitido de novo, desde a identificação.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::outbox::VoteOutbox;
+
+/// Diário do atendimento em curso
+pub const BALLOT_JOURNAL_PATH: &str = "/var/lib/fortis/journal/ballot.json";
+
+/// Fase do atendimento
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BallotPhase {
+    Released,
+    Authenticated,
+    /// Voto montado, ainda sem confirmação de gravação na fila de saída
+    Committing,
+    Recorded,
+    /// Interrompido e anulado; aguarda a autorização do mesário
+    Voided,
+}
+
+/// Registro do atendimento no diário
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BallotJournalEntry {
+    pub session_id: Uuid,
+    pub election_id: Uuid,
+    /// Número de ordem do eleitor admitido no terminal
+    pub sequence: u32,
+    pub phase: BallotPhase,
+    /// Resumo do identificador do eleitor, nunca o identificador
+    pub voter_hash: Option<String>,
+    pub vote_id: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BallotJournalEntry {
+    /// O voto do atendimento chegou à fila de saída, mesmo que o diário
+    /// tenha parado antes da fase "registrado"
+    pub async fn vote_recorded(&self, outbox: &VoteOutbox) -> bool {
+        match self.vote_id {
+            Some(_) if self.phase == BallotPhase::Recorded => true,
+            Some(vote_id) => outbox.holds(vote_id).await,
+            None => false,
+        }
+    }
+}
+
+/// Atendimento interrompido encontrado na inicialização
+#[derive(Debug, Clone)]
+pub enum RecoveredBallot {
+    /// O voto chegou à fila de saída e vale
+    Recorded(BallotJournalEntry),
+    /// Anulado; a urna aguarda a autorização do mesário
+    Voided(BallotJournalEntry),
+}
+
+/// Diário gravado a cada mudança de fase
+#[derive(Debug)]
+pub struct BallotJournal {
+    path: PathBuf,
+    current: Mutex<Option<BallotJournalEntry>>,
+}
+
+impl BallotJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Abre o diário ao liberar o eleitor; recusa se outro atendimento ficou aberto
+    pub async fn begin(&self, election_id: Uuid, sequence: u32) -> Result<Uuid> {
+        let mut current = self.current.lock().await;
+        if let Some(open) = current.as_ref().cloned().or(self.read().await?) {
+            return Err(anyhow!("Ballot session {} is still open in the journal", open.session_id));
+        }
+        let now = Utc::now();
+        let entry = BallotJournalEntry {
+            session_id: Uuid::new_v4(),
+            election_id,
+            sequence,
+            phase: BallotPhase::Released,
+            voter_hash: None,
+            vote_id: None,
+            started_at: now,
+            updated_at: now,
+        };
+        self.write(&entry).await?;
+        let session_id = entry.session_id;
+        *current = Some(entry);
+        Ok(session_id)
+    }
+
+    pub async fn authenticated(&self, voter_hash: &str) -> Result<()> {
+        self.advance(BallotPhase::Authenticated, |entry| entry.voter_hash = Some(voter_hash.to_string())).await
+    }
+
+    /// Grava o id do voto antes de ele ir para a fila de saída
+    pub async fn committing(&self, vote_id: Uuid) -> Result<()> {
+        self.advance(BallotPhase::Committing, |entry| entry.vote_id = Some(vote_id)).await
+    }
+
+    pub async fn recorded(&self, vote_id: Uuid) -> Result<()> {
+        self.advance(BallotPhase::Recorded, |entry| entry.vote_id = Some(vote_id)).await
+    }
+
+    /// Marca o atendimento interrompido como anulado
+    pub async fn void(&self, mut entry: BallotJournalEntry) -> Result<BallotJournalEntry> {
+        entry.phase = BallotPhase::Voided;
+        entry.updated_at = Utc::now();
+        self.write(&entry).await?;
+        *self.current.lock().await = None;
+        Ok(entry)
+    }
+
+    /// Encerra o atendimento; o diário é apagado
+    pub async fn close(&self) -> Result<()> {
+        let mut current = self.current.lock().await;
+        match tokio::fs::remove_file(&self.path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        sync_dir(&self.path).await?;
+        *current = None;
+        Ok(())
+    }
+
+    /// Fase do atendimento em curso
+    pub async fn phase(&self) -> Option<BallotPhase> {
+        self.current.lock().await.as_ref().map(|entry| entry.phase)
+    }
+
+    /// Atendimento deixado por um reinício, se houver
+    pub async fn interrupted(&self) -> Result<Option<BallotJournalEntry>> {
+        if self.current.lock().await.is_some() {
+            return Ok(None);
+        }
+        self.read().await
+    }
+
+    async fn advance(&self, phase: BallotPhase, update: impl FnOnce(&mut BallotJournalEntry)) -> Result<()> {
+        let mut current = self.current.lock().await;
+        let entry = current.as_mut().ok_or_else(|| anyhow!("No ballot session open in the journal"))?;
+        update(entry);
+        entry.phase = phase;
+        entry.updated_at = Utc::now();
+        self.write(entry).await
+    }
+
+    async fn read(&self) -> Result<Option<BallotJournalEntry>> {
+        match tokio::fs::read(&self.path).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)
+                .map_err(|e| anyhow!("Ballot journal unreadable: {}", e))?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Grava em arquivo temporário, sincroniza e troca por `rename`
+    async fn write(&self, entry: &BallotJournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let temporary = self.path.with_extension("json.tmp");
+        let mut file = tokio::fs::File::create(&temporary).await?;
+        file.write_all(&serde_json::to_vec(entry)?).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&temporary, &self.path).await?;
+        sync_dir(&self.path).await
+    }
+}
+
+async fn sync_dir(path: &Path) -> Result<()> {
+    let Some(parent) = path.parent() else {
+        return Ok(());
+    };
+    match tokio::fs::File::open(parent).await {
+        Ok(dir) => Ok(dir.sync_all().await?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outbox::BackoffPolicy;
+    use crate::EncryptedVote;
+
+    fn vote(id: Uuid, election_id: Uuid) -> EncryptedVote {
+        EncryptedVote {
+            id,
+            election_id,
+            voter_id: Uuid::new_v4(),
+            candidate_id: Uuid::new_v4(),
+            choices: Vec::new(),
+            encrypted_data: vec![1, 2, 3],
+            zk_proof: String::new(),
+            signature: String::new(),
+            timestamp: Utc::now(),
+            section: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delivered_vote_survives_failed_recorded_phase() {
+        let dir = std::env::temp_dir().join(format!("fortis-journal-{}", Uuid::new_v4()));
+        let journal_path = dir.join("journal/ballot.json");
+        let outbox_dir = dir.join("outbox");
+        let election_id = Uuid::new_v4();
+
+        let journal = BallotJournal::new(&journal_path);
+        let outbox = VoteOutbox::new(&outbox_dir, BackoffPolicy::default());
+        outbox.initialize().await.unwrap();
+
+        // Voto enfileirado e já entregue; a fase "registrado" nunca foi gravada
+        journal.begin(election_id, 1).await.unwrap();
+        journal.authenticated("voter-hash").await.unwrap();
+        let delivered = Uuid::new_v4();
+        journal.committing(delivered).await.unwrap();
+        outbox.enqueue(&vote(delivered, election_id)).await.unwrap();
+        outbox.acknowledge(delivered).await.unwrap();
+
+        // Reinício: diário e fila relidos do disco
+        let journal = BallotJournal::new(&journal_path);
+        let outbox = VoteOutbox::new(&outbox_dir, BackoffPolicy::default());
+        outbox.initialize().await.unwrap();
+        let entry = journal.interrupted().await.unwrap().unwrap();
+        assert_eq!(entry.phase, BallotPhase::Committing);
+        assert!(entry.vote_recorded(&outbox).await);
+        assert_eq!(outbox.depth().await, 0);
+
+        // Voto que não chegou à fila continua sendo anulado
+        let lost = BallotJournalEntry { vote_id: Some(Uuid::new_v4()), ..entry };
+        assert!(!lost.vote_recorded(&outbox).await);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+//! Módulo de sincronização de candidatos para urna eletrônica
+//!
+//! Busca a lista de candidatos da eleição para a região da urna na API do
+//! backend e mantém uma cópia em disco para operação offline.
+
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use sha2::{Sha256, Digest};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::Candidate;
+use fortis_domain::Versioned;
+
+/// Resposta padrão da API do backend
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    success: bool,
+    data: Option<T>,
+    message: Option<String>,
+}
+
+pub struct CandidateSync {
+    pub api_url: String,
+    pub cache_dir: PathBuf,
+    client: reqwest::Client,
+}
+
+impl CandidateSync {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            api_url: "https://api.fortis.gov.br".to_string(),
+            cache_dir: PathBuf::from("/var/lib/fortis/candidates"),
+            client: crate::transport::client_builder()?
+                .timeout(std::time::Duration::from_secs(10))
+                .build()?,
+        })
+    }
+
+    /// Busca candidatos da eleição para a região, usando o cache se offline
+    pub async fn fetch_candidates(&self, election_id: Uuid, region: Option<&str>) -> Result<Vec<Candidate>> {
+        match self.fetch_remote(election_id, region).await {
+            Ok(candidates) => {
+                if let Err(e) = self.store_cache(election_id, region, &candidates).await {
+                    log::warn!("Failed to cache candidate list: {}", e);
+                }
+                Ok(candidates)
+            }
+            Err(e) => {
+                log::warn!("Failed to fetch candidates, using local cache: {}", e);
+                self.load_cache(election_id, region).await
+            }
+        }
+    }
+
+    /// Baixa a foto do candidato e confere o hash publicado
+    pub async fn fetch_photo(&self, candidate: &Candidate) -> Result<Vec<u8>> {
+        let (Some(url), Some(expected_hash)) = (&candidate.photo_url, &candidate.photo_sha256) else {
+            return Err(anyhow!("Candidate {} has no photo", candidate.number));
+        };
+
+        let photo = self.client
+            .get(format!("{}{}", self.api_url, url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        if &format!("{:x}", Sha256::digest(&photo)) != expected_hash {
+            return Err(anyhow!("Photo hash mismatch for candidate {}", candidate.number));
+        }
+        Ok(photo.to_vec())
+    }
+
+    async fn fetch_remote(&self, election_id: Uuid, region: Option<&str>) -> Result<Vec<Candidate>> {
+        let mut request = self.client
+            .get(format!("{}/api/v1/elections/{}/candidates", self.api_url, election_id));
+        if let Some(region) = region {
+            request = request.query(&[("region", region)]);
+        }
+
+        let response: ApiResponse<Vec<Candidate>> = request
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if !response.success {
+            return Err(anyhow!(
+                "Candidate API error: {}",
+                response.message.unwrap_or_default()
+            ));
+        }
+        let candidates = response.data.unwrap_or_default();
+        if candidates.is_empty() {
+            return Err(anyhow!("No candidates registered for election {}", election_id));
+        }
+        Ok(candidates)
+    }
+
+    fn cache_path(&self, election_id: Uuid, region: Option<&str>) -> PathBuf {
+        self.cache_dir.join(format!(
+            "{}_{}.json",
+            election_id,
+            region.unwrap_or("BR")
+        ))
+    }
+
+    async fn store_cache(&self, election_id: Uuid, region: Option<&str>, candidates: &[Candidate]) -> Result<()> {
+        tokio::fs::create_dir_all(&self.cache_dir).await?;
+        tokio::fs::write(
+            self.cache_path(election_id, region),
+            serde_json::to_vec(&Versioned::new(candidates))?,
+        ).await?;
+        Ok(())
+    }
+
+    async fn load_cache(&self, election_id: Uuid, region: Option<&str>) -> Result<Vec<Candidate>> {
+        let data = tokio::fs::read(self.cache_path(election_id, region)).await
+            .map_err(|_| anyhow!("No cached candidate list for election {}", election_id))?;
+        // Cache gravado por outra versão da urna é descartado
+        let cached: Versioned<Vec<Candidate>> = serde_json::from_slice(&data)?;
+        Ok(cached.into_current()?)
+    }
+}
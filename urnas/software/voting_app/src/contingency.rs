@@ -0,0 +1,108 @@
+//! Módulo de contingência para substituição de urna
+//!
+//! Exporta o estado lacrado da urna com defeito para a mídia removível e
+//! importa esse estado na urna substituta, mediante aprovação de dois
+//! mesários distintos. O formato é o mesmo verificado pelo backend.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::crypto::VoteEncryption;
+use fortis_domain::votes_per_contest;
+
+/// Aprovações de mesários exigidas para importar o estado
+pub const REQUIRED_APPROVALS: usize = 2;
+
+/// Nome do arquivo de estado lacrado na mídia
+pub const SEALED_STATE_FILE: &str = "fortis_estado_lacrado.json";
+
+/// Estado da urna no momento da falha
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrnaStateSnapshot {
+    pub failed_urna_id: String,
+    pub election_id: String,
+    pub zone: String,
+    pub section: String,
+    pub attendance_count: u64,
+    pub votes_by_candidate: BTreeMap<String, u64>,
+    pub attended_voter_hashes: Vec<String>,
+    pub last_vote_sequence: u64,
+    pub failure_reason: String,
+    pub sealed_at: DateTime<Utc>,
+}
+
+/// Estado lacrado gravado na mídia
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealedUrnaState {
+    pub snapshot: UrnaStateSnapshot,
+    pub snapshot_hash: String,
+    pub signature: String,
+}
+
+/// Lacra o estado com a chave da urna e grava na mídia
+pub async fn export_sealed_state(
+    crypto: &VoteEncryption,
+    snapshot: UrnaStateSnapshot,
+    media_path: &Path,
+) -> Result<SealedUrnaState> {
+    let snapshot_bytes = serde_json::to_vec(&snapshot)?;
+    let snapshot_hash = format!("{:x}", Sha256::digest(&snapshot_bytes));
+    let signature = crypto.sign_vote(&snapshot_bytes).await?;
+
+    let sealed = SealedUrnaState {
+        snapshot,
+        snapshot_hash,
+        signature,
+    };
+
+    tokio::fs::create_dir_all(media_path).await?;
+    tokio::fs::write(
+        media_path.join(SEALED_STATE_FILE),
+        serde_json::to_vec_pretty(&sealed)?,
+    ).await?;
+
+    Ok(sealed)
+}
+
+/// Lê o estado lacrado da mídia e valida antes da importação
+///
+/// A assinatura da urna com defeito é verificada pelo backend na
+/// conciliação; aqui são conferidos o hash, a consistência do BU parcial
+/// e as aprovações dos mesários.
+pub async fn import_sealed_state(media_path: &Path, mesario_ids: &[String]) -> Result<SealedUrnaState> {
+    let distinct: HashSet<&String> = mesario_ids.iter().collect();
+    if distinct.len() < REQUIRED_APPROVALS {
+        return Err(anyhow!(
+            "State import requires {} distinct mesário approvals",
+            REQUIRED_APPROVALS
+        ));
+    }
+
+    let data = tokio::fs::read(media_path.join(SEALED_STATE_FILE)).await?;
+    let sealed: SealedUrnaState = serde_json::from_slice(&data)?;
+
+    let snapshot = &sealed.snapshot;
+    let snapshot_hash = format!("{:x}", Sha256::digest(serde_json::to_vec(snapshot)?));
+    if snapshot_hash != sealed.snapshot_hash {
+        return Err(anyhow!("Sealed state hash mismatch"));
+    }
+
+    // Cada eleitor vota uma vez em cada cargo da cédula
+    let contests = votes_per_contest(&snapshot.votes_by_candidate);
+    if contests.values().any(|votes| *votes != snapshot.attendance_count)
+        || snapshot.attended_voter_hashes.len() as u64 != snapshot.attendance_count
+    {
+        return Err(anyhow!("Sealed state counters are inconsistent"));
+    }
+
+    Ok(sealed)
+}
+
+/// Hash do eleitor atendido, sem expor o identificador
+pub fn voter_hash(voter_id: &uuid::Uuid) -> String {
+    format!("{:x}", Sha256::digest(voter_id.as_bytes()))
+}
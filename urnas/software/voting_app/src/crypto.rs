@@ -11,6 +11,7 @@ use base64::{Engine as _, engine::general_purpose};
 use rand::rngs::OsRng;
 
 use crate::Vote;
+use crate::messages::{UrnaError, codes};
 
 pub struct VoteEncryption {
     pub aes_key: Aes256Gcm,
@@ -54,7 +55,7 @@ impl VoteEncryption {
         let decrypted = self.decrypt_data(&encrypted).await?;
         
         if test_data != decrypted.as_slice() {
-            return Err(anyhow::anyhow!("Key integrity verification failed"));
+            return Err(UrnaError::new(codes::CRYPTO_KEY_INTEGRITY_FAILED, "Key integrity verification failed").into());
         }
 
         log::debug!("Key integrity verified successfully");
@@ -115,12 +116,13 @@ impl VoteEncryption {
 
         // Em implementação real, geraria prova Zero-Knowledge real
         // Por enquanto, simula geração de prova
+        let choices: Vec<String> = vote.choices.iter().map(fortis_domain::tally_key).collect();
         let proof_data = format!(
             "zk_proof_{}_{}_{}_{}",
             vote.id,
             vote.election_id,
             vote.voter_id,
-            vote.candidate_id
+            choices.join(",")
         );
 
         let mut hasher = Sha256::new();
@@ -201,7 +203,7 @@ impl VoteEncryption {
 
         // Criptografar dados
         let ciphertext = self.aes_key.encrypt(nonce, data)
-            .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+            .map_err(|e| UrnaError::new(codes::CRYPTO_ENCRYPTION_FAILED, &format!("Encryption failed: {}", e)))?;
 
         // Combinar nonce + ciphertext
         let mut result = Vec::new();
@@ -0,0 +1,155 @@
+//! Descomissionamento da urna ao fim do ciclo
+//!
+//! Cada voto confirmado deixa sua prova de inclusão no livro de provas. No
+//! descomissionamento as provas viram o pacote final assinado, gravado e
+//! relido da mídia removível; só então as chaves e os dados locais são
+//! apagados. O relatório assinado fica gravado fora das áreas apagadas até
+//! a gestão da frota confirmar o recebimento.
+
+use anyhow::{Result, anyhow};
+use rand::RngCore;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+use crate::lockdown::tpm2;
+use crate::{outbox, provisioning, sync, terminal};
+pub use fortis_domain::decommission::{
+    DecommissionReport, FinalProofBundle, SignedDecommissionReport, SignedProofBundle, VoteProof,
+    DECOMMISSION_REPORT_FILE, PROOF_BUNDLE_FILE,
+};
+
+/// Provas de inclusão dos votos confirmados, uma por arquivo
+pub const PROOF_LEDGER_DIR: &str = "/var/lib/fortis/proofs";
+
+/// Mídia removível que recebe o pacote final e o relatório
+pub const DECOMMISSION_MEDIA_PATH: &str = "/media/fortis";
+
+/// Relatório ainda não confirmado pela gestão da frota (não é apagado)
+pub const DECOMMISSION_DIR: &str = "/var/lib/fortis/decommission";
+
+/// Áreas de dados apagadas, com o nome registrado no relatório
+pub const ERASABLE_DIRS: &[(&str, &str)] = &[
+    ("outbox", outbox::OUTBOX_DIR),
+    ("proof_ledger", PROOF_LEDGER_DIR),
+    ("vote_batches", sync::VOTE_BATCH_DIR),
+    ("vote_sync", sync::VOTE_SYNC_DIR),
+    ("election_packages", "/var/lib/fortis/packages"),
+    ("provisioning", provisioning::PROVISIONING_DIR),
+    ("mesario_terminal", terminal::TERMINAL_DIR),
+];
+
+/// Livro de provas dos votos confirmados
+#[derive(Debug)]
+pub struct ProofLedger {
+    dir: PathBuf,
+}
+
+impl ProofLedger {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Grava a prova conferida antes de o voto sair da fila
+    pub async fn record(&self, proof: &VoteProof) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.dir.join(format!("{}.json", proof.vote_id));
+        let temporary = path.with_extension("json.tmp");
+        tokio::fs::write(&temporary, serde_json::to_vec(proof)?).await?;
+        tokio::fs::rename(&temporary, &path).await?;
+        Ok(())
+    }
+
+    /// Provas gravadas, em ordem de posição no log
+    pub async fn load_all(&self) -> Result<Vec<VoteProof>> {
+        let mut proofs = Vec::new();
+        let mut dir = match tokio::fs::read_dir(&self.dir).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(proofs),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(file) = dir.next_entry().await? {
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let proof: VoteProof = serde_json::from_slice(&tokio::fs::read(&path).await?)
+                .map_err(|e| anyhow!("Unreadable proof {}: {}", path.display(), e))?;
+            proofs.push(proof);
+        }
+        proofs.sort_by_key(|proof| proof.inclusion.log_index);
+        Ok(proofs)
+    }
+}
+
+/// Grava o pacote na mídia e o relê, para não apagar a urna com uma cópia ruim
+pub async fn export_bundle(signed: &SignedProofBundle, media_path: &Path) -> Result<()> {
+    tokio::fs::create_dir_all(media_path).await?;
+    let path = media_path.join(PROOF_BUNDLE_FILE);
+    tokio::fs::write(&path, serde_json::to_vec_pretty(signed)?).await?;
+
+    let written: SignedProofBundle = serde_json::from_slice(&tokio::fs::read(&path).await?)?;
+    if !written.verify_digest() || written.bundle_sha256 != signed.bundle_sha256 {
+        return Err(anyhow!("Proof bundle read back from {} does not match", path.display()));
+    }
+    Ok(())
+}
+
+/// Sobrescreve cada arquivo com bytes aleatórios antes de removê-lo
+///
+/// Devolve `false` se a área não existia.
+pub async fn shred_dir(dir: &Path) -> Result<bool> {
+    if !tokio::fs::try_exists(dir).await? {
+        return Ok(false);
+    }
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&current).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let metadata = entry.metadata().await?;
+            if metadata.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let mut noise = vec![0u8; metadata.len() as usize];
+            rand::thread_rng().fill_bytes(&mut noise);
+            let mut file = tokio::fs::OpenOptions::new().write(true).open(&path).await?;
+            file.write_all(&noise).await?;
+            file.sync_all().await?;
+        }
+    }
+    tokio::fs::remove_dir_all(dir).await?;
+    Ok(true)
+}
+
+/// Remove do TPM a chave de selagem; os pacotes cifrados para ela deixam
+/// de poder ser abertos
+pub async fn evict_sealing_key() -> Result<()> {
+    tpm2(&["tpm2_evictcontrol", "-C", "o", "-c", provisioning::SEALING_KEY_HANDLE], None).await?;
+    match tokio::fs::remove_file(provisioning::SEALING_PUBLIC_KEY_PATH).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Relatório assinado que aguarda confirmação da gestão da frota
+pub async fn pending_report() -> Result<Option<SignedDecommissionReport>> {
+    match tokio::fs::read(Path::new(DECOMMISSION_DIR).join("report.json")).await {
+        Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn store_pending_report(signed: &SignedDecommissionReport) -> Result<()> {
+    tokio::fs::create_dir_all(DECOMMISSION_DIR).await?;
+    tokio::fs::write(Path::new(DECOMMISSION_DIR).join("report.json"), serde_json::to_vec(signed)?).await?;
+    Ok(())
+}
+
+pub async fn clear_pending_report() -> Result<()> {
+    match tokio::fs::remove_file(Path::new(DECOMMISSION_DIR).join("report.json")).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,300 @@
+//! Seleção do backend de sincronização com failover
+//!
+//! A urna conhece vários backends: os regionais, preferidos, e o nacional
+//! de contingência. Cada requisição registra latência e resultado no
+//! backend usado. O backend ativo só é trocado após falhas seguidas (que o
+//! afastam por um período) ou numa reavaliação periódica em que outro se
+//! mostre claramente melhor, para que as urnas não oscilem entre backends
+//! com desempenho parecido.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use fortis_domain::{EndpointMetrics, EndpointTier};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Backends conhecidos, gravados na preparação da urna
+pub const ENDPOINTS_PATH: &str = "/etc/fortis/endpoints.json";
+
+/// Backend conhecido pela urna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendEndpoint {
+    pub url: String,
+    pub tier: EndpointTier,
+}
+
+/// Parâmetros da seleção e do failover
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailoverPolicy {
+    /// Falhas seguidas que afastam o backend e disparam a troca
+    pub failover_after: u32,
+    /// Tempo de afastamento antes de o backend voltar a ser tentado
+    pub cooldown_seconds: i64,
+    /// Intervalo entre reavaliações do backend ativo
+    pub reevaluate_seconds: i64,
+    /// Vantagem mínima (fração da pontuação do ativo) para trocar na reavaliação
+    pub switch_margin: f64,
+    /// Peso da última medição na média de latência
+    pub latency_smoothing: f64,
+    /// Acréscimo à pontuação do backend nacional, para preferir os regionais
+    pub national_penalty_ms: f64,
+    /// Pontuação de um backend ainda sem medições
+    pub unmeasured_latency_ms: f64,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            failover_after: 3,
+            cooldown_seconds: 60,
+            reevaluate_seconds: 300,
+            switch_margin: 0.3,
+            latency_smoothing: 0.3,
+            national_penalty_ms: 250.0,
+            unmeasured_latency_ms: 2_000.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EndpointState {
+    endpoint: BackendEndpoint,
+    latency_ms: Option<f64>,
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    last_success: Option<DateTime<Utc>>,
+    down_until: Option<DateTime<Utc>>,
+}
+
+impl EndpointState {
+    fn available(&self, now: DateTime<Utc>) -> bool {
+        self.down_until.map_or(true, |until| now >= until)
+    }
+
+    fn score(&self, policy: &FailoverPolicy) -> f64 {
+        let latency = self.latency_ms.unwrap_or(policy.unmeasured_latency_ms);
+        match self.endpoint.tier {
+            EndpointTier::Regional => latency,
+            EndpointTier::National => latency + policy.national_penalty_ms,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PoolState {
+    endpoints: Vec<EndpointState>,
+    active: usize,
+    last_evaluation: DateTime<Utc>,
+    failovers: u64,
+}
+
+impl PoolState {
+    fn position(&self, url: &str) -> Option<usize> {
+        self.endpoints.iter().position(|state| state.endpoint.url == url)
+    }
+
+    /// Melhor backend disponível, exceto o informado
+    fn best_available(&self, policy: &FailoverPolicy, now: DateTime<Utc>, except: Option<usize>) -> Option<usize> {
+        self.endpoints.iter()
+            .enumerate()
+            .filter(|(index, state)| Some(*index) != except && state.available(now))
+            .min_by(|(_, a), (_, b)| a.score(policy).total_cmp(&b.score(policy)))
+            .map(|(index, _)| index)
+    }
+
+    fn switch_to(&mut self, index: usize, reason: &str) {
+        if index == self.active {
+            return;
+        }
+        log::warn!(
+            "Switching sync backend from {} to {} ({})",
+            self.endpoints[self.active].endpoint.url,
+            self.endpoints[index].endpoint.url,
+            reason
+        );
+        self.active = index;
+        self.failovers += 1;
+    }
+}
+
+/// Backends da urna com suas medições
+#[derive(Debug)]
+pub struct EndpointPool {
+    policy: FailoverPolicy,
+    state: Mutex<PoolState>,
+}
+
+impl EndpointPool {
+    pub fn new(endpoints: Vec<BackendEndpoint>, policy: FailoverPolicy) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(anyhow!("At least one backend endpoint is required"));
+        }
+        // Começa pelo primeiro regional da lista, na ordem gravada na preparação
+        let active = endpoints.iter()
+            .position(|endpoint| endpoint.tier == EndpointTier::Regional)
+            .unwrap_or(0);
+        let endpoints = endpoints.into_iter()
+            .map(|endpoint| EndpointState {
+                endpoint,
+                latency_ms: None,
+                successes: 0,
+                failures: 0,
+                consecutive_failures: 0,
+                last_error: None,
+                last_success: None,
+                down_until: None,
+            })
+            .collect();
+
+        Ok(Self {
+            policy,
+            state: Mutex::new(PoolState {
+                endpoints,
+                active,
+                last_evaluation: Utc::now(),
+                failovers: 0,
+            }),
+        })
+    }
+
+    /// Carrega a lista gravada na preparação; sem lista, usa só o backend padrão
+    pub fn load(path: &Path, default_url: &str, policy: FailoverPolicy) -> Result<Self> {
+        let endpoints = match std::fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![BackendEndpoint {
+                url: default_url.to_string(),
+                tier: EndpointTier::Regional,
+            }],
+            Err(e) => return Err(e.into()),
+        };
+        Self::new(endpoints, policy)
+    }
+
+    /// URLs de todos os backends, na ordem gravada (cobertas pelo lacre)
+    pub fn urls(&self) -> Vec<String> {
+        self.lock().endpoints.iter().map(|state| state.endpoint.url.clone()).collect()
+    }
+
+    /// Backend em uso
+    pub fn active(&self) -> String {
+        let state = self.lock();
+        state.endpoints[state.active].endpoint.url.clone()
+    }
+
+    /// Ordem de tentativa de uma requisição: o ativo, os demais disponíveis
+    /// pela pontuação e, por último, os afastados, para nunca ficar sem destino
+    pub fn candidates(&self) -> Vec<String> {
+        let now = Utc::now();
+        let mut state = self.lock();
+        if now - state.last_evaluation >= Duration::seconds(self.policy.reevaluate_seconds) {
+            self.reevaluate(&mut state, now);
+        }
+
+        let active = state.active;
+        let mut others: Vec<&EndpointState> = state.endpoints.iter()
+            .enumerate()
+            .filter(|(index, _)| *index != active)
+            .map(|(_, endpoint)| endpoint)
+            .collect();
+        others.sort_by(|a, b| {
+            b.available(now).cmp(&a.available(now))
+                .then(a.score(&self.policy).total_cmp(&b.score(&self.policy)))
+        });
+
+        std::iter::once(&state.endpoints[active])
+            .chain(others)
+            .map(|endpoint| endpoint.endpoint.url.clone())
+            .collect()
+    }
+
+    pub fn record_success(&self, url: &str, latency: std::time::Duration) {
+        let mut state = self.lock();
+        let Some(index) = state.position(url) else { return };
+        let sample = latency.as_secs_f64() * 1000.0;
+        let smoothing = self.policy.latency_smoothing;
+
+        let endpoint = &mut state.endpoints[index];
+        endpoint.latency_ms = Some(match endpoint.latency_ms {
+            Some(average) => average + smoothing * (sample - average),
+            None => sample,
+        });
+        endpoint.successes += 1;
+        endpoint.consecutive_failures = 0;
+        endpoint.last_success = Some(Utc::now());
+        if endpoint.down_until.take().is_some() {
+            log::info!("Sync backend {} is reachable again", url);
+        }
+    }
+
+    pub fn record_failure(&self, url: &str, error: &str) {
+        let now = Utc::now();
+        let mut state = self.lock();
+        let Some(index) = state.position(url) else { return };
+
+        let endpoint = &mut state.endpoints[index];
+        endpoint.failures += 1;
+        endpoint.consecutive_failures += 1;
+        endpoint.last_error = Some(error.to_string());
+        if endpoint.consecutive_failures < self.policy.failover_after {
+            return;
+        }
+        endpoint.down_until = Some(now + Duration::seconds(self.policy.cooldown_seconds));
+        log::warn!("Sync backend {} marked down after {} consecutive failures: {}", url, endpoint.consecutive_failures, error);
+
+        if index == state.active {
+            if let Some(next) = state.best_available(&self.policy, now, Some(index)) {
+                state.switch_to(next, "active backend down");
+                state.last_evaluation = now;
+            }
+        }
+    }
+
+    /// Medições de cada backend para o heartbeat
+    pub fn metrics(&self) -> Vec<EndpointMetrics> {
+        let now = Utc::now();
+        let state = self.lock();
+        state.endpoints.iter()
+            .enumerate()
+            .map(|(index, endpoint)| EndpointMetrics {
+                url: endpoint.endpoint.url.clone(),
+                tier: endpoint.endpoint.tier,
+                active: index == state.active,
+                available: endpoint.available(now),
+                latency_ms: endpoint.latency_ms,
+                successes: endpoint.successes,
+                failures: endpoint.failures,
+                consecutive_failures: endpoint.consecutive_failures,
+                last_error: endpoint.last_error.clone(),
+                last_success: endpoint.last_success,
+            })
+            .collect()
+    }
+
+    pub fn failovers(&self) -> u64 {
+        self.lock().failovers
+    }
+
+    /// Troca o ativo apenas se ele estiver afastado ou se outro backend for
+    /// melhor pela margem configurada; de volta ao regional quando ele se recupera
+    fn reevaluate(&self, state: &mut PoolState, now: DateTime<Utc>) {
+        state.last_evaluation = now;
+        let Some(best) = state.best_available(&self.policy, now, None) else { return };
+
+        let active = &state.endpoints[state.active];
+        if !active.available(now) {
+            state.switch_to(best, "active backend still down");
+            return;
+        }
+        let active_score = active.score(&self.policy);
+        if state.endpoints[best].score(&self.policy) < active_score * (1.0 - self.policy.switch_margin) {
+            state.switch_to(best, "better backend on re-evaluation");
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,76 @@
+//! Contagem das falhas por código para o heartbeat
+//!
+//! Cada falha da urna é contada pelo seu código do catálogo e a contagem
+//! acumulada segue no próximo heartbeat. Só o que o backend confirmou é
+//! descontado, de modo que um heartbeat perdido não apaga ocorrências. A
+//! última falha fica guardada para o painel da frota.
+
+use chrono::Utc;
+use std::collections::BTreeMap;
+use tokio::sync::Mutex;
+
+use crate::messages;
+use fortis_domain::{FaultReport, HeartbeatError};
+
+#[derive(Default)]
+pub struct FaultLog {
+    pending: Mutex<BTreeMap<String, FaultReport>>,
+    last: Mutex<Option<HeartbeatError>>,
+}
+
+impl FaultLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn record(&self, code: &str, detail: &str) {
+        let now = Utc::now();
+        self.pending.lock().await
+            .entry(code.to_string())
+            .and_modify(|report| {
+                report.count += 1;
+                report.last_seen = now;
+            })
+            .or_insert_with(|| FaultReport {
+                code: code.to_string(),
+                count: 1,
+                first_seen: now,
+                last_seen: now,
+            });
+        *self.last.lock().await = Some(HeartbeatError {
+            code: code.to_string(),
+            message: detail.to_string(),
+            occurred_at: now,
+        });
+    }
+
+    /// Conta o erro pelo seu código; sem código, como erro interno
+    pub async fn record_error(&self, error: &anyhow::Error) {
+        self.record(messages::error_code(error), &error.to_string()).await;
+    }
+
+    /// Contagens ainda não confirmadas pelo backend
+    pub async fn pending(&self) -> Vec<FaultReport> {
+        self.pending.lock().await.values().cloned().collect()
+    }
+
+    /// Desconta as contagens de um heartbeat aceito; o que ocorreu depois do envio permanece
+    pub async fn acknowledge(&self, sent: &[FaultReport]) {
+        let mut pending = self.pending.lock().await;
+        for report in sent {
+            let Some(current) = pending.get_mut(&report.code) else {
+                continue;
+            };
+            if current.count <= report.count {
+                pending.remove(&report.code);
+            } else {
+                current.count -= report.count;
+                current.first_seen = report.last_seen;
+            }
+        }
+    }
+
+    pub async fn last_error(&self) -> Option<HeartbeatError> {
+        self.last.lock().await.clone()
+    }
+}
@@ -1,569 +0,0 @@
-//! Módulo de gerenciamento de hardware para urna eletrônica
-
-use anyhow::Result;
-use uuid::Uuid;
-use chrono::{DateTime, Utc};
-
-use crate::VoteReceipt;
-
-pub struct HardwareManager {
-    pub biometric_reader: BiometricReader,
-    pub certificate_reader: CertificateReader,
-    pub printer: Printer,
-    pub display: Display,
-    pub keypad: Keypad,
-    pub network: NetworkInterface,
-    pub hsm: HSM,
-    pub ups: UPS,
-}
-
-impl HardwareManager {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            biometric_reader: BiometricReader::new()?,
-            certificate_reader: CertificateReader::new()?,
-            printer: Printer::new()?,
-            display: Display::new()?,
-            keypad: Keypad::new()?,
-            network: NetworkInterface::new()?,
-            hsm: HSM::new()?,
-            ups: UPS::new()?,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing hardware manager");
-
-        // Inicializar todos os componentes
-        self.biometric_reader.initialize().await?;
-        self.certificate_reader.initialize().await?;
-        self.printer.initialize().await?;
-        self.display.initialize().await?;
-        self.keypad.initialize().await?;
-        self.network.initialize().await?;
-        self.hsm.initialize().await?;
-        self.ups.initialize().await?;
-
-        // Verificar integridade do hardware
-        self.verify_hardware_integrity().await?;
-
-        log::info!("Hardware manager initialized successfully");
-        Ok(())
-    }
-
-    async fn verify_hardware_integrity(&self) -> Result<()> {
-        log::debug!("Verifying hardware integrity");
-
-        // Verificar cada componente
-        self.biometric_reader.self_test().await?;
-        self.certificate_reader.self_test().await?;
-        self.printer.self_test().await?;
-        self.display.self_test().await?;
-        self.keypad.self_test().await?;
-        self.network.self_test().await?;
-        self.hsm.self_test().await?;
-        self.ups.self_test().await?;
-
-        log::debug!("Hardware integrity verified");
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Verificar se todos os componentes estão prontos
-        let biometric_ready = self.biometric_reader.is_ready().await?;
-        let printer_ready = self.printer.is_ready().await?;
-        let display_ready = self.display.is_ready().await?;
-        let keypad_ready = self.keypad.is_ready().await?;
-        let hsm_ready = self.hsm.is_ready().await?;
-
-        Ok(biometric_ready && printer_ready && display_ready && keypad_ready && hsm_ready)
-    }
-
-    pub async fn capture_biometric_data(&self) -> Result<BiometricData> {
-        log::info!("Capturing biometric data");
-
-        // Capturar impressão digital
-        let fingerprint = self.biometric_reader.capture_fingerprint().await?;
-        
-        // Capturar dados faciais
-        let facial_data = self.biometric_reader.capture_facial().await?;
-
-        Ok(BiometricData {
-            fingerprint,
-            fingerprint_hash: self.calculate_hash(&fingerprint),
-            facial_data,
-            facial_hash: self.calculate_hash(&facial_data),
-            timestamp: Utc::now(),
-        })
-    }
-
-    pub async fn read_certificate(&self) -> Result<Option<CertificateData>> {
-        log::info!("Reading certificate");
-
-        // Tentar ler certificado
-        match self.certificate_reader.read_certificate().await? {
-            Some(cert_data) => {
-                Ok(Some(CertificateData {
-                    certificate: cert_data,
-                    certificate_hash: "cert_hash".to_string(),
-                    issuer: "ICP-Brasil".to_string(),
-                    valid_until: Utc::now() + chrono::Duration::days(365),
-                    serial_number: "123456789".to_string(),
-                }))
-            }
-            None => Ok(None),
-        }
-    }
-
-    pub async fn print_receipt(&self, receipt: &VoteReceipt) -> Result<()> {
-        log::info!("Printing receipt for vote: {}", receipt.vote_id);
-
-        // Preparar dados para impressão
-        let print_data = self.format_receipt(receipt).await?;
-
-        // Imprimir comprovante
-        self.printer.print(&print_data).await?;
-
-        log::info!("Receipt printed successfully");
-        Ok(())
-    }
-
-    async fn format_receipt(&self, receipt: &VoteReceipt) -> Result<String> {
-        let formatted = format!(
-            "================================\n\
-             COMPROVANTE DE VOTAÇÃO FORTIS\n\
-             ================================\n\
-             \n\
-             ID do Voto: {}\n\
-             Eleição: {}\n\
-             Candidato: {} - {}\n\
-             Data/Hora: {}\n\
-             \n\
-             QR Code: {}\n\
-             \n\
-             Hash Blockchain: {}\n\
-             \n\
-             ================================\n\
-             Sistema de Votação Eletrônica\n\
-             FORTIS - Democracia Digital\n\
-             ================================",
-            receipt.vote_id,
-            receipt.election_id,
-            receipt.candidate_number,
-            receipt.candidate_name,
-            receipt.timestamp.format("%d/%m/%Y %H:%M:%S"),
-            receipt.qr_code,
-            receipt.blockchain_hash.as_deref().unwrap_or("N/A")
-        );
-
-        Ok(formatted)
-    }
-
-    fn calculate_hash(&self, data: &[u8]) -> String {
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        let hash = hasher.finalize();
-        base64::encode(hash)
-    }
-
-    pub async fn get_hardware_status(&self) -> Result<HardwareStatus> {
-        Ok(HardwareStatus {
-            biometric_reader: self.biometric_reader.get_status().await?,
-            certificate_reader: self.certificate_reader.get_status().await?,
-            printer: self.printer.get_status().await?,
-            display: self.display.get_status().await?,
-            keypad: self.keypad.get_status().await?,
-            network: self.network.get_status().await?,
-            hsm: self.hsm.get_status().await?,
-            ups: self.ups.get_status().await?,
-            timestamp: Utc::now(),
-        })
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct BiometricData {
-    pub fingerprint: Vec<u8>,
-    pub fingerprint_hash: String,
-    pub facial_data: Vec<u8>,
-    pub facial_hash: String,
-    pub timestamp: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone)]
-pub struct CertificateData {
-    pub certificate: Vec<u8>,
-    pub certificate_hash: String,
-    pub issuer: String,
-    pub valid_until: DateTime<Utc>,
-    pub serial_number: String,
-}
-
-#[derive(Debug, Clone)]
-pub struct HardwareStatus {
-    pub biometric_reader: ComponentStatus,
-    pub certificate_reader: ComponentStatus,
-    pub printer: ComponentStatus,
-    pub display: ComponentStatus,
-    pub keypad: ComponentStatus,
-    pub network: ComponentStatus,
-    pub hsm: ComponentStatus,
-    pub ups: ComponentStatus,
-    pub timestamp: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone)]
-pub struct ComponentStatus {
-    pub is_ready: bool,
-    pub is_healthy: bool,
-    pub last_error: Option<String>,
-    pub uptime: u64,
-}
-
-// Implementações dos componentes de hardware
-
-pub struct BiometricReader {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl BiometricReader {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-BR-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing biometric reader: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running biometric reader self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn capture_fingerprint(&self) -> Result<Vec<u8>> {
-        log::debug!("Capturing fingerprint");
-        // Em implementação real, capturaria impressão real
-        Ok(vec![1, 2, 3, 4, 5])
-    }
-
-    pub async fn capture_facial(&self) -> Result<Vec<u8>> {
-        log::debug!("Capturing facial data");
-        // Em implementação real, capturaria dados faciais reais
-        Ok(vec![6, 7, 8, 9, 10])
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
-
-pub struct CertificateReader {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl CertificateReader {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-CR-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing certificate reader: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running certificate reader self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn read_certificate(&self) -> Result<Option<Vec<u8>>> {
-        log::debug!("Reading certificate");
-        // Em implementação real, leria certificado real
-        Ok(Some(vec![11, 12, 13, 14, 15]))
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
-
-pub struct Printer {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl Printer {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-PR-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing printer: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running printer self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn print(&self, data: &str) -> Result<()> {
-        log::debug!("Printing data");
-        // Em implementação real, imprimiria dados reais
-        log::info!("Printed: {}", data);
-        Ok(())
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
-
-pub struct Display {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl Display {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-DP-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing display: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running display self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
-
-pub struct Keypad {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl Keypad {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-KP-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing keypad: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running keypad self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
-
-pub struct NetworkInterface {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl NetworkInterface {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-NI-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing network interface: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running network interface self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
-
-pub struct HSM {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl HSM {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-HSM-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing HSM: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running HSM self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
-
-pub struct UPS {
-    pub model: String,
-    pub is_initialized: bool,
-}
-
-impl UPS {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            model: "FORTIS-UPS-001".to_string(),
-            is_initialized: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing UPS: {}", self.model);
-        // Em implementação real, inicializaria hardware real
-        Ok(())
-    }
-
-    pub async fn self_test(&self) -> Result<()> {
-        log::debug!("Running UPS self-test");
-        // Em implementação real, faria teste real
-        Ok(())
-    }
-
-    pub async fn is_ready(&self) -> Result<bool> {
-        // Em implementação real, verificaria status real
-        Ok(true)
-    }
-
-    pub async fn get_status(&self) -> Result<ComponentStatus> {
-        Ok(ComponentStatus {
-            is_ready: true,
-            is_healthy: true,
-            last_error: None,
-            uptime: 3600,
-        })
-    }
-}
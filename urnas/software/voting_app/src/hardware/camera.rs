@@ -0,0 +1,269 @@
+//! Câmera para reconhecimento facial da urna
+//!
+//! Usada apenas quando a impressão digital falha. Os quadros são lidos da
+//! câmera V4L2 (canal de luminância), recortados na área do guia de
+//! posicionamento mostrado na tela e processados por dois modelos ONNX
+//! locais: detecção de ataque de apresentação (foto, tela, máscara) e
+//! extração do vetor facial. A vivacidade combina a pontuação do modelo com
+//! o movimento natural entre quadros; uma foto parada não tem movimento.
+
+use anyhow::{Result, anyhow};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tract_onnx::prelude::*;
+
+/// Lado da imagem de entrada dos modelos
+const MODEL_INPUT: usize = 112;
+
+/// Fração central do quadro coberta pelo guia de posicionamento do rosto
+const GUIDE_FRACTION: f32 = 0.6;
+
+/// Diferença média entre quadros abaixo da qual a imagem é considerada parada
+const STATIC_FRAME_DIFF: f32 = 0.8;
+
+#[derive(Debug, Clone)]
+pub struct FacePolicy {
+    pub device: PathBuf,
+    pub frames: usize,
+    pub frame_interval: Duration,
+    pub capture_timeout: Duration,
+    pub min_liveness_score: f32,
+    pub embedding_model: PathBuf,
+    pub anti_spoof_model: PathBuf,
+}
+
+impl Default for FacePolicy {
+    fn default() -> Self {
+        Self {
+            device: PathBuf::from("/dev/video0"),
+            frames: 5,
+            frame_interval: Duration::from_millis(200),
+            capture_timeout: Duration::from_secs(10),
+            min_liveness_score: 0.7,
+            embedding_model: PathBuf::from("/etc/fortis/models/face_embedding.onnx"),
+            anti_spoof_model: PathBuf::from("/etc/fortis/models/face_antispoof.onnx"),
+        }
+    }
+}
+
+/// Quadro em tons de cinza
+#[derive(Debug, Clone)]
+pub struct FaceFrame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+/// Resultado de uma captura facial viva
+#[derive(Debug, Clone)]
+pub struct FaceCapture {
+    /// Vetor facial normalizado (norma 1)
+    pub embedding: Vec<f32>,
+    pub liveness_score: f32,
+    pub frames: usize,
+}
+
+type Model = TypedRunnableModel<TypedModel>;
+
+struct FaceModels {
+    embedding: Model,
+    anti_spoof: Model,
+}
+
+pub struct FaceCamera {
+    pub policy: FacePolicy,
+    models: OnceCell<Arc<FaceModels>>,
+}
+
+impl FaceCamera {
+    pub fn new(policy: FacePolicy) -> Self {
+        Self { policy, models: OnceCell::new() }
+    }
+
+    /// Carrega os modelos e confirma que a câmera responde
+    pub async fn initialize(&self) -> Result<()> {
+        self.models().await?;
+        let device = self.policy.device.clone();
+        tokio::task::spawn_blocking(move || video::capture_frames(&device, 1, Duration::ZERO)).await??;
+        Ok(())
+    }
+
+    async fn models(&self) -> Result<Arc<FaceModels>> {
+        let policy = self.policy.clone();
+        self.models
+            .get_or_try_init(|| async move {
+                tokio::task::spawn_blocking(move || -> Result<Arc<FaceModels>> {
+                    Ok(Arc::new(FaceModels {
+                        embedding: load_model(&policy.embedding_model)?,
+                        anti_spoof: load_model(&policy.anti_spoof_model)?,
+                    }))
+                })
+                .await?
+            })
+            .await
+            .cloned()
+    }
+
+    /// Captura quadros, verifica vivacidade e extrai o vetor facial
+    pub async fn capture(&self) -> Result<FaceCapture> {
+        let models = self.models().await?;
+        let device = self.policy.device.clone();
+        let (count, interval) = (self.policy.frames.max(2), self.policy.frame_interval);
+
+        let frames = tokio::time::timeout(
+            self.policy.capture_timeout,
+            tokio::task::spawn_blocking(move || video::capture_frames(&device, count, interval)),
+        )
+        .await
+        .map_err(|_| anyhow!("Face capture timed out"))???;
+
+        let min_liveness = self.policy.min_liveness_score;
+        tokio::task::spawn_blocking(move || {
+            let crops: Vec<Vec<u8>> = frames.iter().map(guide_crop).collect();
+
+            let liveness_score = liveness_score(&models.anti_spoof, &crops)?;
+            if liveness_score < min_liveness {
+                return Err(anyhow!("Face liveness score {:.2} below {:.2}", liveness_score, min_liveness));
+            }
+
+            // Vetor do quadro central, com o rosto mais estável
+            let embedding = run_model(&models.embedding, &crops[crops.len() / 2])?;
+            let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm == 0.0 {
+                return Err(anyhow!("Face embedding model returned an empty vector"));
+            }
+
+            Ok(FaceCapture {
+                embedding: embedding.iter().map(|v| v / norm).collect(),
+                liveness_score,
+                frames: crops.len(),
+            })
+        })
+        .await?
+    }
+}
+
+/// Similaridade de cosseno entre vetores normalizados
+pub fn face_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn load_model(path: &std::path::Path) -> Result<Model> {
+    Ok(tract_onnx::onnx()
+        .model_for_path(path)
+        .map_err(|e| anyhow!("Failed to load model {}: {}", path.display(), e))?
+        .with_input_fact(0, f32::fact([1, 3, MODEL_INPUT, MODEL_INPUT]).into())?
+        .into_optimized()?
+        .into_runnable()?)
+}
+
+/// Executa o modelo com a imagem replicada nos três canais
+fn run_model(model: &Model, crop: &[u8]) -> Result<Vec<f32>> {
+    let input: Tensor = tract_ndarray::Array4::from_shape_fn((1, 3, MODEL_INPUT, MODEL_INPUT), |(_, _, y, x)| {
+        (crop[y * MODEL_INPUT + x] as f32 - 127.5) / 128.0
+    })
+    .into();
+    let output = model.run(tvec!(input.into()))?;
+    Ok(output[0].to_array_view::<f32>()?.iter().copied().collect())
+}
+
+/// Pontuação do modelo antifraude (média dos quadros) zerada se não há movimento
+fn liveness_score(model: &Model, crops: &[Vec<u8>]) -> Result<f32> {
+    let motion = crops
+        .windows(2)
+        .map(|pair| {
+            pair[0].iter().zip(&pair[1]).map(|(a, b)| (*a as f32 - *b as f32).abs()).sum::<f32>()
+                / pair[0].len() as f32
+        })
+        .sum::<f32>()
+        / (crops.len() - 1) as f32;
+    if motion < STATIC_FRAME_DIFF {
+        log::warn!("Face frames are static (motion {:.2})", motion);
+        return Ok(0.0);
+    }
+
+    let mut total = 0.0;
+    for crop in crops {
+        // Saída [fraude, vivo] em logits
+        let logits = run_model(model, crop)?;
+        let &[spoof, live] = logits.as_slice() else {
+            return Err(anyhow!("Unexpected anti-spoofing model output"));
+        };
+        total += 1.0 / (1.0 + (spoof - live).exp());
+    }
+    Ok(total / crops.len() as f32)
+}
+
+/// Recorta o quadrado central do guia e reduz para a entrada dos modelos
+fn guide_crop(frame: &FaceFrame) -> Vec<u8> {
+    let side = (frame.width.min(frame.height) as f32 * GUIDE_FRACTION) as usize;
+    let (x0, y0) = ((frame.width - side) / 2, (frame.height - side) / 2);
+
+    let mut crop = Vec::with_capacity(MODEL_INPUT * MODEL_INPUT);
+    for y in 0..MODEL_INPUT {
+        for x in 0..MODEL_INPUT {
+            let sx = x0 + x * side / MODEL_INPUT;
+            let sy = y0 + y * side / MODEL_INPUT;
+            crop.push(frame.pixels[sy * frame.width + sx]);
+        }
+    }
+    crop
+}
+
+#[cfg(feature = "hardware")]
+mod video {
+    use anyhow::{Result, anyhow};
+    use std::path::Path;
+    use std::time::Duration;
+    use v4l::buffer::Type;
+    use v4l::io::traits::CaptureStream;
+    use v4l::prelude::*;
+    use v4l::video::Capture;
+    use v4l::FourCC;
+
+    use super::FaceFrame;
+
+    /// Lê quadros YUYV e mantém só a luminância
+    pub fn capture_frames(device: &Path, count: usize, interval: Duration) -> Result<Vec<FaceFrame>> {
+        let device = Device::with_path(device)?;
+        let mut format = device.format()?;
+        format.width = 640;
+        format.height = 480;
+        format.fourcc = FourCC::new(b"YUYV");
+        let format = device.set_format(&format)?;
+        if format.fourcc != FourCC::new(b"YUYV") {
+            return Err(anyhow!("Camera does not support YUYV capture"));
+        }
+
+        let mut stream = MmapStream::with_buffers(&device, Type::VideoCapture, 4)?;
+        let mut frames = Vec::with_capacity(count);
+        while frames.len() < count {
+            let (buffer, _) = stream.next()?;
+            frames.push(FaceFrame {
+                width: format.width as usize,
+                height: format.height as usize,
+                pixels: buffer.iter().step_by(2).copied().collect(),
+            });
+            std::thread::sleep(interval);
+        }
+        Ok(frames)
+    }
+}
+
+#[cfg(not(feature = "hardware"))]
+mod video {
+    use anyhow::{Result, anyhow};
+    use std::path::Path;
+    use std::time::Duration;
+
+    use super::FaceFrame;
+
+    pub fn capture_frames(_device: &Path, _count: usize, _interval: Duration) -> Result<Vec<FaceFrame>> {
+        Err(anyhow!("Built without camera support"))
+    }
+}
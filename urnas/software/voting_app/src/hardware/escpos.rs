@@ -0,0 +1,241 @@
+//! Driver ESC/POS para impressora térmica da urna
+//!
+//! Suporta impressoras USB (classe de impressora, via driver `usblp` em
+//! /dev/usb/lp*) e seriais. O estado do papel, da tampa e de erros é lido
+//! com os comandos de status em tempo real (DLE EOT) antes de cada
+//! impressão, e o texto é convertido para a página de código PC860
+//! (português).
+
+use anyhow::{Result, anyhow};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::messages::{UrnaError, codes};
+
+const ESC: u8 = 0x1b;
+const GS: u8 = 0x1d;
+const DLE: u8 = 0x10;
+const EOT: u8 = 0x04;
+
+/// Tempo máximo de resposta aos comandos de status
+const STATUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Conexão física da impressora
+#[derive(Debug, Clone)]
+pub enum PrinterConnection {
+    Usb { device: PathBuf },
+    Serial { port: String, baud_rate: u32 },
+}
+
+impl Default for PrinterConnection {
+    fn default() -> Self {
+        PrinterConnection::Usb { device: PathBuf::from("/dev/usb/lp0") }
+    }
+}
+
+/// Estado lido da impressora
+#[derive(Debug, Clone, Default)]
+pub struct PrinterStatus {
+    pub online: bool,
+    pub cover_open: bool,
+    pub paper_out: bool,
+    pub paper_near_end: bool,
+    pub error: bool,
+}
+
+impl PrinterStatus {
+    pub fn can_print(&self) -> bool {
+        self.online && !self.cover_open && !self.paper_out && !self.error
+    }
+}
+
+enum Transport {
+    Usb(tokio::fs::File),
+    Serial(Box<dyn serialport::SerialPort>),
+}
+
+/// Impressora ESC/POS conectada
+pub struct EscPosPrinter {
+    transport: Transport,
+}
+
+impl EscPosPrinter {
+    pub async fn open(connection: &PrinterConnection) -> Result<Self> {
+        let transport = match connection {
+            PrinterConnection::Usb { device } => Transport::Usb(
+                tokio::fs::OpenOptions::new().read(true).write(true).open(device).await?
+            ),
+            PrinterConnection::Serial { port, baud_rate } => Transport::Serial(
+                serialport::new(port, *baud_rate).timeout(STATUS_TIMEOUT).open()?
+            ),
+        };
+
+        let mut printer = Self { transport };
+        printer.write(&ReceiptBuilder::new().bytes).await?;
+        Ok(printer)
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        match &mut self.transport {
+            Transport::Usb(file) => {
+                file.write_all(bytes).await?;
+                file.flush().await?;
+            }
+            Transport::Serial(port) => {
+                port.write_all(bytes)?;
+                port.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Envia DLE EOT n e lê o byte de resposta
+    async fn query(&mut self, n: u8) -> Result<u8> {
+        self.write(&[DLE, EOT, n]).await?;
+        let mut buf = [0u8; 1];
+        match &mut self.transport {
+            Transport::Usb(file) => {
+                tokio::time::timeout(STATUS_TIMEOUT, file.read_exact(&mut buf))
+                    .await
+                    .map_err(|_| anyhow!("Printer status timeout"))??;
+            }
+            Transport::Serial(port) => port.read_exact(&mut buf)?,
+        }
+        Ok(buf[0])
+    }
+
+    pub async fn status(&mut self) -> Result<PrinterStatus> {
+        let printer = self.query(1).await?;
+        let offline = self.query(2).await?;
+        let paper = self.query(4).await?;
+
+        Ok(PrinterStatus {
+            // Bit 3 do status da impressora: offline
+            online: printer & 0x08 == 0,
+            cover_open: offline & 0x04 != 0,
+            paper_out: offline & 0x20 != 0 || paper & 0x60 != 0,
+            paper_near_end: paper & 0x0c != 0,
+            error: offline & 0x40 != 0,
+        })
+    }
+
+    /// Imprime documento após confirmar papel e tampa
+    pub async fn print(&mut self, document: &ReceiptBuilder) -> Result<()> {
+        let status = self.status().await?;
+        if status.paper_out {
+            return Err(UrnaError::new(codes::PRINTER_PAPER_OUT, "Printer is out of paper").into());
+        }
+        if !status.online {
+            return Err(UrnaError::new(codes::PRINTER_OFFLINE, "Printer is offline").into());
+        }
+        if !status.can_print() {
+            return Err(UrnaError::new(codes::PRINTER_NOT_READY, &format!("Printer not ready: {:?}", status)).into());
+        }
+        if status.paper_near_end {
+            log::warn!("Printer paper near end");
+        }
+
+        self.write(&document.bytes).await?;
+
+        // Papel pode acabar durante a impressão
+        if self.status().await?.paper_out {
+            return Err(UrnaError::new(codes::PRINTER_PAPER_OUT, "Printer ran out of paper while printing").into());
+        }
+        Ok(())
+    }
+
+    /// Autoteste: status e página curta de verificação
+    pub async fn self_test(&mut self) -> Result<PrinterStatus> {
+        let status = self.status().await?;
+        if !status.can_print() {
+            return Err(anyhow!("Printer self-test failed: {:?}", status));
+        }
+
+        let page = ReceiptBuilder::new()
+            .center(true)
+            .line("AUTOTESTE DA IMPRESSORA")
+            .line(&chrono::Local::now().format("%d/%m/%Y %H:%M:%S").to_string())
+            .feed(2)
+            .cut();
+        self.write(&page.bytes).await?;
+        Ok(status)
+    }
+}
+
+/// Montagem dos comandos ESC/POS de um documento
+#[derive(Debug, Clone)]
+pub struct ReceiptBuilder {
+    bytes: Vec<u8>,
+}
+
+impl ReceiptBuilder {
+    /// Inicializa a impressora e seleciona a página de código PC860
+    pub fn new() -> Self {
+        Self { bytes: vec![ESC, b'@', ESC, b't', 3] }
+    }
+
+    pub fn line(mut self, text: &str) -> Self {
+        self.bytes.extend(encode_pc860(text));
+        self.bytes.push(b'\n');
+        self
+    }
+
+    /// Uma linha por item, na ordem
+    pub fn lines<I: IntoIterator<Item = String>>(self, lines: I) -> Self {
+        lines.into_iter().fold(self, |builder, text| builder.line(&text))
+    }
+
+    pub fn bold(mut self, enabled: bool) -> Self {
+        self.bytes.extend([ESC, b'E', enabled as u8]);
+        self
+    }
+
+    pub fn center(mut self, enabled: bool) -> Self {
+        self.bytes.extend([ESC, b'a', enabled as u8]);
+        self
+    }
+
+    /// QR code nativo (GS ( k), modelo 2, correção M
+    pub fn qr(mut self, data: &str) -> Self {
+        let data = data.as_bytes();
+        let store_len = data.len() + 3;
+        self.bytes.extend([GS, b'(', b'k', 4, 0, 49, 65, 50, 0]);
+        self.bytes.extend([GS, b'(', b'k', 3, 0, 49, 67, 5]);
+        self.bytes.extend([GS, b'(', b'k', 3, 0, 49, 69, 49]);
+        self.bytes.extend([GS, b'(', b'k', (store_len & 0xff) as u8, (store_len >> 8) as u8, 49, 80, 48]);
+        self.bytes.extend(data);
+        self.bytes.extend([GS, b'(', b'k', 3, 0, 49, 81, 48]);
+        self
+    }
+
+    pub fn feed(mut self, lines: u8) -> Self {
+        self.bytes.extend([ESC, b'd', lines]);
+        self
+    }
+
+    /// Corte parcial após avanço do papel
+    pub fn cut(mut self) -> Self {
+        self.bytes.extend([GS, b'V', 66, 0]);
+        self
+    }
+}
+
+/// Converte texto para a página de código PC860 (português)
+fn encode_pc860(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            c if c.is_ascii() => c as u8,
+            'Ç' => 0x80, 'ü' => 0x81, 'é' => 0x82, 'â' => 0x83, 'ã' => 0x84,
+            'à' => 0x85, 'Á' => 0x86, 'ç' => 0x87, 'ê' => 0x88, 'Ê' => 0x89,
+            'è' => 0x8a, 'Í' => 0x8b, 'Ô' => 0x8c, 'ì' => 0x8d, 'Ã' => 0x8e,
+            'Â' => 0x8f, 'É' => 0x90, 'À' => 0x91, 'È' => 0x92, 'ô' => 0x93,
+            'õ' => 0x94, 'ò' => 0x95, 'Ú' => 0x96, 'ù' => 0x97, 'Ì' => 0x98,
+            'Õ' => 0x99, 'Ü' => 0x9a, 'Ó' => 0x9f, 'á' => 0xa0, 'í' => 0xa1,
+            'ó' => 0xa2, 'ú' => 0xa3, 'ñ' => 0xa4, 'Ñ' => 0xa5, 'ª' => 0xa6,
+            'º' => 0xa7,
+            _ => b'?',
+        })
+        .collect()
+}
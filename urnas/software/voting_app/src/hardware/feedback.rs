@@ -0,0 +1,410 @@
+//! Autoteste do retorno sonoro e visual da urna
+//!
+//! A legislação eleitoral define o som de confirmação do voto e o
+//! comportamento da tela. Antes da abertura da sessão a urna verifica:
+//!
+//! - tom de confirmação: o tom configurado é tocado no alto-falante e a
+//!   reprodução é conferida pelo microfone (retorno acústico, com a energia
+//!   concentrada na frequência do tom) ou pela confirmação do amplificador;
+//! - brilho: nível atual da luz de fundo em relação ao máximo do painel;
+//! - contraste: o sensor de luz frontal mede um campo branco e um campo
+//!   preto desenhados no framebuffer.
+//!
+//! Falha em qualquer verificação bloqueia a abertura até a liberação pelo
+//! mesário, que vale apenas para as verificações que falharam no relatório
+//! liberado.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use uuid::Uuid;
+
+/// Caminho da política no pacote de configuração
+pub const FEEDBACK_POLICY_PATH: &str = "/etc/fortis/bundle/feedback_policy.json";
+
+/// Captura do microfone além da duração do tom (latência da placa de som)
+const LOOPBACK_MARGIN: Duration = Duration::from_millis(300);
+
+/// Forma de conferir que o tom foi reproduzido
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneVerification {
+    MicLoopback,
+    HardwareAck,
+}
+
+/// Parâmetros do autoteste, publicados no pacote de configuração
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedbackPolicy {
+    pub tone_frequency_hz: f32,
+    pub tone_duration_ms: u64,
+    /// Amplitude do tom, de 0 a 1
+    pub tone_volume: f32,
+    pub verification: ToneVerification,
+    /// Nível RMS mínimo captado pelo microfone, de 0 a 1
+    pub min_loopback_level: f32,
+    /// Fração mínima da energia captada concentrada na frequência do tom
+    pub min_tone_ratio: f32,
+    /// Linha de confirmação do amplificador (`ACK` quando reproduziu)
+    pub ack_device: PathBuf,
+    pub ack_timeout_ms: u64,
+    pub backlight: PathBuf,
+    /// Brilho mínimo, em percentual do máximo do painel
+    pub min_brightness_percent: f32,
+    pub framebuffer: PathBuf,
+    pub framebuffer_sysfs: PathBuf,
+    pub light_sensor: PathBuf,
+    /// Tempo para o painel estabilizar antes da leitura do sensor
+    pub settle_ms: u64,
+    pub min_contrast_ratio: f32,
+}
+
+impl Default for FeedbackPolicy {
+    fn default() -> Self {
+        Self {
+            tone_frequency_hz: 1000.0,
+            tone_duration_ms: 600,
+            tone_volume: 0.7,
+            verification: ToneVerification::MicLoopback,
+            min_loopback_level: 0.02,
+            min_tone_ratio: 0.5,
+            ack_device: PathBuf::from("/dev/fortis/audio_ack"),
+            ack_timeout_ms: 2000,
+            backlight: PathBuf::from("/sys/class/backlight/fortis"),
+            min_brightness_percent: 60.0,
+            framebuffer: PathBuf::from("/dev/fb0"),
+            framebuffer_sysfs: PathBuf::from("/sys/class/graphics/fb0"),
+            light_sensor: PathBuf::from("/sys/bus/iio/devices/iio:device0/in_illuminance_raw"),
+            settle_ms: 250,
+            min_contrast_ratio: 100.0,
+        }
+    }
+}
+
+impl FeedbackPolicy {
+    /// Carrega a política do pacote, com os valores padrão se indisponível
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| Ok(serde_json::from_slice(&data)?)) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::warn!("Feedback policy unavailable, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Verificação do autoteste
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackCheckKind {
+    ConfirmationTone,
+    Brightness,
+    Contrast,
+}
+
+/// Resultado de uma verificação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackCheck {
+    pub check: FeedbackCheckKind,
+    pub passed: bool,
+    pub measured: Option<f32>,
+    pub required: f32,
+    pub detail: Option<String>,
+}
+
+impl FeedbackCheck {
+    fn measured(check: FeedbackCheckKind, measured: f32, required: f32) -> Self {
+        Self { check, passed: measured >= required, measured: Some(measured), required, detail: None }
+    }
+
+    fn failed(check: FeedbackCheckKind, required: f32, error: anyhow::Error) -> Self {
+        Self { check, passed: false, measured: None, required, detail: Some(error.to_string()) }
+    }
+}
+
+/// Relatório registrado na auditoria a cada abertura
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackReport {
+    pub report_id: Uuid,
+    pub verification: ToneVerification,
+    pub checks: Vec<FeedbackCheck>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl FeedbackReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    pub fn failed_checks(&self) -> Vec<FeedbackCheckKind> {
+        self.checks.iter().filter(|check| !check.passed).map(|check| check.check).collect()
+    }
+}
+
+/// Liberação do mesário para abrir a sessão apesar das falhas do relatório
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackOverride {
+    pub report_id: Uuid,
+    pub failed_checks: Vec<FeedbackCheckKind>,
+    pub mesario_id: String,
+    pub reason: String,
+    pub granted_at: DateTime<Utc>,
+}
+
+impl FeedbackOverride {
+    pub fn new(report: &FeedbackReport, mesario_id: &str, reason: &str) -> Self {
+        Self {
+            report_id: report.report_id,
+            failed_checks: report.failed_checks(),
+            mesario_id: mesario_id.to_string(),
+            reason: reason.to_string(),
+            granted_at: Utc::now(),
+        }
+    }
+
+    /// Falha nova, não vista pelo mesário, continua bloqueando
+    pub fn covers(&self, report: &FeedbackReport) -> bool {
+        report.failed_checks().iter().all(|check| self.failed_checks.contains(check))
+    }
+}
+
+pub struct FeedbackSelfTest {
+    pub policy: FeedbackPolicy,
+}
+
+impl FeedbackSelfTest {
+    pub fn new(policy: FeedbackPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// Executa todas as verificações; erros de leitura contam como falha
+    pub async fn run(&self) -> FeedbackReport {
+        let started_at = Utc::now();
+        let checks = vec![self.check_tone().await, self.check_brightness().await, self.check_contrast().await];
+        let report = FeedbackReport {
+            report_id: Uuid::new_v4(),
+            verification: self.policy.verification,
+            checks,
+            started_at,
+            finished_at: Utc::now(),
+        };
+        if report.passed() {
+            log::info!("Feedback self-test passed");
+        } else {
+            log::warn!("Feedback self-test failed: {:?}", report.failed_checks());
+        }
+        report
+    }
+
+    async fn check_tone(&self) -> FeedbackCheck {
+        let kind = FeedbackCheckKind::ConfirmationTone;
+        match self.policy.verification {
+            ToneVerification::MicLoopback => {
+                let policy = self.policy.clone();
+                let capture = tokio::task::spawn_blocking(move || audio::play_and_capture(&policy)).await;
+                match capture.map_err(anyhow::Error::from).and_then(|capture| capture) {
+                    Ok(capture) => {
+                        let level = rms(&capture.samples);
+                        let ratio = tone_ratio(&capture.samples, self.policy.tone_frequency_hz, capture.sample_rate);
+                        let mut check = FeedbackCheck::measured(kind, ratio, self.policy.min_tone_ratio);
+                        if level < self.policy.min_loopback_level {
+                            check.passed = false;
+                            check.detail = Some(format!("Loopback level {:.3} below {:.3}", level, self.policy.min_loopback_level));
+                        }
+                        check
+                    }
+                    Err(e) => FeedbackCheck::failed(kind, self.policy.min_tone_ratio, e),
+                }
+            }
+            ToneVerification::HardwareAck => match self.play_with_ack().await {
+                Ok(()) => FeedbackCheck::measured(kind, 1.0, 1.0),
+                Err(e) => FeedbackCheck::failed(kind, 1.0, e),
+            },
+        }
+    }
+
+    /// Toca o tom e aguarda a linha de confirmação do amplificador
+    async fn play_with_ack(&self) -> Result<()> {
+        let file = tokio::fs::File::open(&self.policy.ack_device).await?;
+        let mut lines = BufReader::new(file).lines();
+
+        let policy = self.policy.clone();
+        let playback = tokio::task::spawn_blocking(move || audio::play(&policy));
+        let timeout = Duration::from_millis(self.policy.tone_duration_ms + self.policy.ack_timeout_ms);
+        let ack = tokio::time::timeout(timeout, lines.next_line()).await;
+        playback.await??;
+
+        match ack {
+            Ok(Ok(Some(line))) if line.trim().starts_with("ACK") => Ok(()),
+            Ok(Ok(Some(line))) => Err(anyhow!("Amplifier reported {}", line.trim())),
+            Ok(Ok(None)) => Err(anyhow!("Amplifier acknowledgement line closed")),
+            Ok(Err(e)) => Err(e.into()),
+            Err(_) => Err(anyhow!("No amplifier acknowledgement")),
+        }
+    }
+
+    async fn check_brightness(&self) -> FeedbackCheck {
+        let kind = FeedbackCheckKind::Brightness;
+        let required = self.policy.min_brightness_percent;
+        let current = read_number(&self.policy.backlight.join("actual_brightness")).await;
+        let max = read_number(&self.policy.backlight.join("max_brightness")).await;
+        match (current, max) {
+            (Ok(current), Ok(max)) if max > 0.0 => FeedbackCheck::measured(kind, current * 100.0 / max, required),
+            (Ok(_), Ok(_)) => FeedbackCheck::failed(kind, required, anyhow!("Backlight reports zero maximum")),
+            (Err(e), _) | (_, Err(e)) => FeedbackCheck::failed(kind, required, e),
+        }
+    }
+
+    async fn check_contrast(&self) -> FeedbackCheck {
+        let kind = FeedbackCheckKind::Contrast;
+        match self.measure_contrast().await {
+            Ok(ratio) => FeedbackCheck::measured(kind, ratio, self.policy.min_contrast_ratio),
+            Err(e) => FeedbackCheck::failed(kind, self.policy.min_contrast_ratio, e),
+        }
+    }
+
+    /// Razão entre a luminância do campo branco e a do campo preto
+    async fn measure_contrast(&self) -> Result<f32> {
+        self.fill_screen(0xFF).await?;
+        let white = self.read_light().await?;
+        self.fill_screen(0x00).await?;
+        let black = self.read_light().await?;
+
+        if white <= 0.0 {
+            return Err(anyhow!("Light sensor reads nothing on the white field"));
+        }
+        // Sensor sem leitura no preto equivale ao menor valor mensurável
+        Ok(white / black.max(1.0))
+    }
+
+    async fn read_light(&self) -> Result<f32> {
+        tokio::time::sleep(Duration::from_millis(self.policy.settle_ms)).await;
+        read_number(&self.policy.light_sensor).await
+    }
+
+    /// Preenche o framebuffer inteiro com o mesmo byte
+    async fn fill_screen(&self, value: u8) -> Result<()> {
+        let size = tokio::fs::read_to_string(self.policy.framebuffer_sysfs.join("virtual_size")).await?;
+        let (width, height) = size.trim().split_once(',')
+            .and_then(|(w, h)| Some((w.parse::<usize>().ok()?, h.parse::<usize>().ok()?)))
+            .ok_or_else(|| anyhow!("Invalid framebuffer size {}", size.trim()))?;
+        let bits = read_number(&self.policy.framebuffer_sysfs.join("bits_per_pixel")).await? as usize;
+
+        let frame = vec![value; width * height * bits.div_ceil(8)];
+        let mut fb = tokio::fs::OpenOptions::new().write(true).open(&self.policy.framebuffer).await?;
+        fb.write_all(&frame).await?;
+        fb.flush().await?;
+        Ok(())
+    }
+}
+
+async fn read_number(path: &Path) -> Result<f32> {
+    let value = tokio::fs::read_to_string(path).await
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    value.trim().parse::<f32>().map_err(|e| anyhow!("{}: {}", path.display(), e))
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Fração da energia na frequência do tom (Goertzel); 1 para um tom puro
+fn tone_ratio(samples: &[f32], frequency: f32, sample_rate: f32) -> f32 {
+    let energy: f32 = samples.iter().map(|s| s * s).sum();
+    if energy <= 0.0 {
+        return 0.0;
+    }
+    let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / sample_rate).cos();
+    let (mut previous, mut before) = (0.0f32, 0.0f32);
+    for sample in samples {
+        let current = sample + coefficient * previous - before;
+        before = previous;
+        previous = current;
+    }
+    let power = previous * previous + before * before - coefficient * previous * before;
+    (2.0 * power / (samples.len() as f32 * energy)).min(1.0)
+}
+
+/// Captura do microfone, canal único
+struct LoopbackCapture {
+    samples: Vec<f32>,
+    sample_rate: f32,
+}
+
+mod audio {
+    use super::*;
+
+    /// Reproduz o tom no dispositivo de saída padrão
+    pub fn play(policy: &FeedbackPolicy) -> Result<()> {
+        let host = cpal::default_host();
+        let output = host.default_output_device().ok_or_else(|| anyhow!("No audio output device"))?;
+        let config = output.default_output_config()?;
+        if config.sample_format() != cpal::SampleFormat::F32 {
+            return Err(anyhow!("Unsupported output sample format {:?}", config.sample_format()));
+        }
+        let sample_rate = config.sample_rate().0 as f32;
+        let channels = config.channels() as usize;
+        let step = 2.0 * std::f32::consts::PI * policy.tone_frequency_hz / sample_rate;
+        let volume = policy.tone_volume.clamp(0.0, 1.0);
+        let mut phase = 0.0f32;
+
+        let stream = output.build_output_stream(
+            &config.into(),
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                for frame in data.chunks_mut(channels) {
+                    let value = volume * phase.sin();
+                    phase = (phase + step) % (2.0 * std::f32::consts::PI);
+                    frame.iter_mut().for_each(|sample| *sample = value);
+                }
+            },
+            |e| log::warn!("Audio output error: {}", e),
+            None,
+        )?;
+        stream.play()?;
+        std::thread::sleep(Duration::from_millis(policy.tone_duration_ms));
+        Ok(())
+    }
+
+    /// Grava o microfone enquanto o tom é reproduzido
+    pub(super) fn play_and_capture(policy: &FeedbackPolicy) -> Result<LoopbackCapture> {
+        let host = cpal::default_host();
+        let input = host.default_input_device().ok_or_else(|| anyhow!("No audio input device"))?;
+        let config = input.default_input_config()?;
+        if config.sample_format() != cpal::SampleFormat::F32 {
+            return Err(anyhow!("Unsupported input sample format {:?}", config.sample_format()));
+        }
+        let sample_rate = config.sample_rate().0 as f32;
+        let channels = config.channels() as usize;
+
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let recorded = samples.clone();
+        let stream = input.build_input_stream(
+            &config.into(),
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                if let Ok(mut recorded) = recorded.lock() {
+                    recorded.extend(data.chunks(channels).map(|frame| frame[0]));
+                }
+            },
+            |e| log::warn!("Audio input error: {}", e),
+            None,
+        )?;
+        stream.play()?;
+        play(policy)?;
+        std::thread::sleep(LOOPBACK_MARGIN);
+        drop(stream);
+
+        let samples = std::mem::take(&mut *samples.lock().map_err(|_| anyhow!("Loopback buffer poisoned"))?);
+        Ok(LoopbackCapture { samples, sample_rate })
+    }
+}
@@ -0,0 +1,513 @@
+//! Leitor de impressão digital da urna
+//!
+//! A captura usa a libfprint (leitores de imagem) e cada tentativa passa
+//! por três etapas antes de gerar o template:
+//!
+//! - qualidade: fração da imagem coberta pelo dedo e coerência da
+//!   orientação das cristas, de 0 a 100;
+//! - vivacidade: dois quadros consecutivos do mesmo dedo são comparados.
+//!   Quadros idênticos indicam imagem injetada ou molde estático; em dedo
+//!   vivo a transpiração altera a intensidade das cristas entre os quadros;
+//! - extração de minúcias (terminações e bifurcações) no formato
+//!   ISO/IEC 19794-2, o mesmo decodificado pelo backend.
+//!
+//! A imagem nunca sai deste módulo; apenas o template é repassado.
+
+use anyhow::{Result, anyhow};
+use std::time::Duration;
+
+use fortis_domain::biometric::{FingerprintTemplate, Minutia, MinutiaKind};
+
+use crate::messages::{UrnaError, codes};
+
+/// Tamanho do bloco usado em segmentação e orientação
+const BLOCK: usize = 16;
+
+/// Limites para considerar dois quadros do mesmo dedo
+const SAME_FINGER_CORRELATION: f32 = 0.6;
+const STATIC_FRAME_DIFF: f32 = 0.5;
+
+/// Máximo de minúcias mantidas no template
+const MAX_MINUTIAE: usize = 60;
+
+/// Parâmetros de captura
+#[derive(Debug, Clone)]
+pub struct CapturePolicy {
+    pub max_attempts: u32,
+    pub capture_timeout: Duration,
+    /// Intervalo entre os dois quadros usados na vivacidade
+    pub liveness_interval: Duration,
+    pub min_quality: u8,
+    pub min_minutiae: usize,
+    pub min_liveness_score: f32,
+}
+
+impl Default for CapturePolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            capture_timeout: Duration::from_secs(10),
+            liveness_interval: Duration::from_millis(800),
+            min_quality: 40,
+            min_minutiae: 12,
+            min_liveness_score: 0.5,
+        }
+    }
+}
+
+/// Imagem em tons de cinza (0 = preto), uma linha após a outra
+#[derive(Debug, Clone)]
+pub struct FingerprintImage {
+    pub width: usize,
+    pub height: usize,
+    /// Resolução em pixels por centímetro
+    pub ppcm: u16,
+    pub pixels: Vec<u8>,
+}
+
+impl FingerprintImage {
+    fn at(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.width + x]
+    }
+}
+
+/// Resultado de uma captura aceita
+#[derive(Debug, Clone)]
+pub struct FingerprintCapture {
+    pub template: FingerprintTemplate,
+    pub quality: u8,
+    pub liveness_score: f32,
+    pub attempts: u32,
+}
+
+pub struct FingerprintReader {
+    pub device_index: usize,
+    pub policy: CapturePolicy,
+}
+
+impl FingerprintReader {
+    pub fn new(policy: CapturePolicy) -> Self {
+        Self { device_index: 0, policy }
+    }
+
+    /// Verifica se há leitor compatível conectado
+    pub async fn detect(&self) -> Result<String> {
+        let index = self.device_index;
+        tokio::task::spawn_blocking(move || sensor::device_name(index)).await?
+    }
+
+    /// Captura com novas tentativas até obter imagem de qualidade e viva
+    pub async fn capture(&self) -> Result<FingerprintCapture> {
+        let mut last_error = None;
+
+        for attempt in 1..=self.policy.max_attempts {
+            let frames = tokio::time::timeout(self.policy.capture_timeout, self.capture_frames()).await;
+            let (first, second) = match frames {
+                Ok(Ok(frames)) => frames,
+                Ok(Err(e)) => {
+                    log::warn!("Fingerprint capture attempt {} failed: {}", attempt, e);
+                    last_error = Some(e);
+                    continue;
+                }
+                Err(_) => {
+                    log::warn!("Fingerprint capture attempt {} timed out", attempt);
+                    last_error = Some(UrnaError::new(codes::FINGERPRINT_TIMEOUT, "Fingerprint capture timed out").into());
+                    continue;
+                }
+            };
+
+            let orientation = OrientationField::compute(&first);
+            let quality = orientation.quality();
+            if quality < self.policy.min_quality {
+                log::warn!("Fingerprint quality {} below minimum on attempt {}", quality, attempt);
+                last_error = Some(UrnaError::new(codes::FINGERPRINT_LOW_QUALITY, &format!("Quality {}", quality)).into());
+                continue;
+            }
+
+            let liveness_score = liveness_score(&first, &second, &orientation);
+            if liveness_score < self.policy.min_liveness_score {
+                log::warn!("Fingerprint liveness score {:.2} rejected on attempt {}", liveness_score, attempt);
+                last_error = Some(UrnaError::new(
+                    codes::FINGERPRINT_LIVENESS_FAILED,
+                    &format!("Liveness score {:.2}", liveness_score),
+                ).into());
+                continue;
+            }
+
+            let template = extract_template(&first, &orientation, quality);
+            if template.minutiae.len() < self.policy.min_minutiae {
+                log::warn!("Only {} minutiae extracted on attempt {}", template.minutiae.len(), attempt);
+                last_error = Some(UrnaError::new(
+                    codes::FINGERPRINT_LOW_QUALITY,
+                    &format!("{} minutiae", template.minutiae.len()),
+                ).into());
+                continue;
+            }
+
+            log::info!(
+                "Fingerprint captured: quality {}, liveness {:.2}, {} minutiae",
+                quality, liveness_score, template.minutiae.len()
+            );
+            return Ok(FingerprintCapture { template, quality, liveness_score, attempts: attempt });
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("No fingerprint capture attempts configured")))
+    }
+
+    /// Primeiro quadro aguarda o dedo; o segundo é lido com o dedo ainda no leitor
+    async fn capture_frames(&self) -> Result<(FingerprintImage, FingerprintImage)> {
+        let index = self.device_index;
+        let first = tokio::task::spawn_blocking(move || sensor::capture(index, true)).await??;
+        tokio::time::sleep(self.policy.liveness_interval).await;
+        let second = tokio::task::spawn_blocking(move || sensor::capture(index, false)).await??;
+
+        if (first.width, first.height) != (second.width, second.height) {
+            return Err(anyhow!("Fingerprint frames have different dimensions"));
+        }
+        Ok((first, second))
+    }
+}
+
+#[cfg(feature = "hardware")]
+mod sensor {
+    use anyhow::{Result, anyhow};
+    use libfprint_rs::FpContext;
+
+    use super::FingerprintImage;
+
+    pub fn device_name(index: usize) -> Result<String> {
+        let context = FpContext::new();
+        let devices = context.devices();
+        let device = devices.get(index).ok_or_else(|| anyhow!("Fingerprint reader not found"))?;
+        Ok(device.name())
+    }
+
+    pub fn capture(index: usize, wait_for_finger: bool) -> Result<FingerprintImage> {
+        let context = FpContext::new();
+        let devices = context.devices();
+        let device = devices.get(index).ok_or_else(|| anyhow!("Fingerprint reader not found"))?;
+
+        device.open_sync(None)?;
+        let image = device.capture_sync(wait_for_finger, None);
+        device.close_sync(None)?;
+        let image = image?;
+
+        Ok(FingerprintImage {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            // libfprint informa pixels por milímetro
+            ppcm: (image.ppmm() * 10.0).round() as u16,
+            pixels: image.data().to_vec(),
+        })
+    }
+}
+
+#[cfg(not(feature = "hardware"))]
+mod sensor {
+    use anyhow::{Result, anyhow};
+
+    use super::FingerprintImage;
+
+    pub fn device_name(_index: usize) -> Result<String> {
+        Err(anyhow!("Built without fingerprint reader support"))
+    }
+
+    pub fn capture(_index: usize, _wait_for_finger: bool) -> Result<FingerprintImage> {
+        Err(anyhow!("Built without fingerprint reader support"))
+    }
+}
+
+/// Orientação das cristas por bloco, com máscara de área do dedo
+struct OrientationField {
+    blocks_x: usize,
+    blocks_y: usize,
+    /// Ângulo da crista em radianos (0..π)
+    angle: Vec<f32>,
+    coherence: Vec<f32>,
+    foreground: Vec<bool>,
+}
+
+impl OrientationField {
+    fn compute(image: &FingerprintImage) -> Self {
+        let blocks_x = image.width / BLOCK;
+        let blocks_y = image.height / BLOCK;
+        let mut angle = vec![0.0; blocks_x * blocks_y];
+        let mut coherence = vec![0.0; blocks_x * blocks_y];
+        let mut foreground = vec![false; blocks_x * blocks_y];
+
+        for by in 0..blocks_y {
+            for bx in 0..blocks_x {
+                let (mut gxx, mut gyy, mut gxy) = (0.0f32, 0.0f32, 0.0f32);
+                let (mut sum, mut sum_sq) = (0.0f32, 0.0f32);
+
+                for y in by * BLOCK..(by + 1) * BLOCK {
+                    for x in bx * BLOCK..(bx + 1) * BLOCK {
+                        let value = image.at(x, y) as f32;
+                        sum += value;
+                        sum_sq += value * value;
+                        if x == 0 || y == 0 || x + 1 >= image.width || y + 1 >= image.height {
+                            continue;
+                        }
+                        let gx = image.at(x + 1, y) as f32 - image.at(x - 1, y) as f32;
+                        let gy = image.at(x, y + 1) as f32 - image.at(x, y - 1) as f32;
+                        gxx += gx * gx;
+                        gyy += gy * gy;
+                        gxy += gx * gy;
+                    }
+                }
+
+                let n = (BLOCK * BLOCK) as f32;
+                let variance = sum_sq / n - (sum / n).powi(2);
+                let i = by * blocks_x + bx;
+                // Fundo do leitor é uniforme; cristas têm variância alta
+                foreground[i] = variance > 300.0;
+                // Direção da crista é perpendicular ao gradiente
+                angle[i] = (0.5 * (2.0 * gxy).atan2(gxx - gyy) + std::f32::consts::FRAC_PI_2)
+                    .rem_euclid(std::f32::consts::PI);
+                let energy = gxx + gyy;
+                coherence[i] = if energy > 0.0 {
+                    ((gxx - gyy).powi(2) + 4.0 * gxy * gxy).sqrt() / energy
+                } else {
+                    0.0
+                };
+            }
+        }
+
+        Self { blocks_x, blocks_y, angle, coherence, foreground }
+    }
+
+    fn block_of(&self, x: usize, y: usize) -> Option<usize> {
+        let (bx, by) = (x / BLOCK, y / BLOCK);
+        (bx < self.blocks_x && by < self.blocks_y).then(|| by * self.blocks_x + bx)
+    }
+
+    /// Bloco no dedo e cercado por blocos do dedo (longe da borda)
+    fn is_interior(&self, x: usize, y: usize) -> bool {
+        let (bx, by) = ((x / BLOCK) as isize, (y / BLOCK) as isize);
+        (-1..=1).all(|dy| {
+            (-1..=1).all(|dx| {
+                let (nx, ny) = (bx + dx, by + dy);
+                nx >= 0
+                    && ny >= 0
+                    && (nx as usize) < self.blocks_x
+                    && (ny as usize) < self.blocks_y
+                    && self.foreground[ny as usize * self.blocks_x + nx as usize]
+            })
+        })
+    }
+
+    /// Qualidade de 0 a 100: área coberta × coerência média das cristas
+    fn quality(&self) -> u8 {
+        let total = self.foreground.len();
+        let covered: Vec<usize> = (0..total).filter(|i| self.foreground[*i]).collect();
+        if total == 0 || covered.is_empty() {
+            return 0;
+        }
+        let area = covered.len() as f32 / total as f32;
+        let coherence = covered.iter().map(|i| self.coherence[*i]).sum::<f32>() / covered.len() as f32;
+        // Dedo cobrindo 60% do leitor já é captura completa
+        ((area / 0.6).min(1.0) * coherence * 100.0).round().clamp(0.0, 100.0) as u8
+    }
+}
+
+/// Pontuação de vivacidade (0.0 a 1.0) a partir de dois quadros
+fn liveness_score(first: &FingerprintImage, second: &FingerprintImage, field: &OrientationField) -> f32 {
+    let pixels: Vec<usize> = (0..first.pixels.len())
+        .filter(|i| {
+            let (x, y) = (i % first.width, i / first.width);
+            field.block_of(x, y).map(|b| field.foreground[b]).unwrap_or(false)
+        })
+        .collect();
+    if pixels.is_empty() {
+        return 0.0;
+    }
+
+    let n = pixels.len() as f32;
+    let mean = |image: &FingerprintImage| pixels.iter().map(|i| image.pixels[*i] as f32).sum::<f32>() / n;
+    let (mean_a, mean_b) = (mean(first), mean(second));
+
+    let (mut cov, mut var_a, mut var_b, mut abs_diff) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    let (mut ridge_change, mut ridge_count) = (0.0f32, 0usize);
+    for &i in &pixels {
+        let (a, b) = (first.pixels[i] as f32, second.pixels[i] as f32);
+        cov += (a - mean_a) * (b - mean_b);
+        var_a += (a - mean_a).powi(2);
+        var_b += (b - mean_b).powi(2);
+        abs_diff += (a - b).abs();
+        if a < mean_a {
+            ridge_change += b - a;
+            ridge_count += 1;
+        }
+    }
+
+    // Mesmo quadro repetido: imagem injetada ou molde sem transpiração
+    if abs_diff / n < STATIC_FRAME_DIFF {
+        return 0.0;
+    }
+    // Quadros de dedos diferentes (dedo trocado durante a captura)
+    let correlation = cov / (var_a.sqrt() * var_b.sqrt()).max(f32::EPSILON);
+    if correlation < SAME_FINGER_CORRELATION {
+        return 0.0;
+    }
+
+    // Transpiração escurece as cristas; alteração global de brilho não conta
+    let ridge_darkening = (mean_b - mean_a) - ridge_change / ridge_count.max(1) as f32;
+    (ridge_darkening.max(0.0) / 4.0).min(1.0)
+}
+
+/// Extrai minúcias da imagem por binarização, afinamento e número de cruzamento
+fn extract_template(image: &FingerprintImage, field: &OrientationField, quality: u8) -> FingerprintTemplate {
+    let skeleton = thin(&binarize(image, field), image.width, image.height);
+    let (w, h) = (image.width, image.height);
+    let ridge = |x: usize, y: usize| skeleton[y * w + x];
+
+    let mut minutiae = Vec::new();
+    for y in 1..h.saturating_sub(1) {
+        for x in 1..w.saturating_sub(1) {
+            if !ridge(x, y) || !field.is_interior(x, y) {
+                continue;
+            }
+            let ring = neighbours(x, y).map(|(nx, ny)| ridge(nx, ny));
+            let crossings = (0..8).filter(|k| ring[*k] != ring[(k + 1) % 8]).count() / 2;
+            let kind = match crossings {
+                1 => MinutiaKind::RidgeEnding,
+                3 => MinutiaKind::Bifurcation,
+                _ => continue,
+            };
+
+            let block = field.block_of(x, y).expect("interior pixel has a block");
+            let angle = match kind {
+                MinutiaKind::RidgeEnding => ending_direction(&skeleton, w, x, y)
+                    .unwrap_or(field.angle[block]),
+                _ => field.angle[block],
+            };
+            minutiae.push(Minutia {
+                x: x as u16,
+                y: y as u16,
+                angle: (angle.rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU * 256.0) as u8,
+                kind,
+                quality: (field.coherence[block] * 100.0) as u8,
+            });
+        }
+    }
+
+    // Minúcias muito próximas costumam ser ruído (quebras e pontes de crista)
+    let spurious: Vec<bool> = minutiae
+        .iter()
+        .map(|m| {
+            minutiae.iter().any(|o| {
+                !std::ptr::eq(m, o)
+                    && (m.x as i32 - o.x as i32).pow(2) + (m.y as i32 - o.y as i32).pow(2) < 36
+            })
+        })
+        .collect();
+    let mut minutiae: Vec<Minutia> = minutiae
+        .into_iter()
+        .zip(spurious)
+        .filter_map(|(m, spurious)| (!spurious).then_some(m))
+        .collect();
+    minutiae.sort_by(|a, b| b.quality.cmp(&a.quality));
+    minutiae.truncate(MAX_MINUTIAE);
+
+    FingerprintTemplate {
+        width: w as u16,
+        height: h as u16,
+        resolution_ppcm: image.ppcm,
+        quality,
+        minutiae,
+    }
+}
+
+/// Crista = pixel mais escuro que a média do seu bloco
+fn binarize(image: &FingerprintImage, field: &OrientationField) -> Vec<bool> {
+    let mut block_mean = vec![0.0f32; field.blocks_x * field.blocks_y];
+    for (b, mean) in block_mean.iter_mut().enumerate() {
+        let (bx, by) = (b % field.blocks_x, b / field.blocks_x);
+        let mut sum = 0u32;
+        for y in by * BLOCK..(by + 1) * BLOCK {
+            for x in bx * BLOCK..(bx + 1) * BLOCK {
+                sum += image.at(x, y) as u32;
+            }
+        }
+        *mean = sum as f32 / (BLOCK * BLOCK) as f32;
+    }
+
+    (0..image.pixels.len())
+        .map(|i| {
+            let (x, y) = (i % image.width, i / image.width);
+            match field.block_of(x, y) {
+                Some(b) if field.foreground[b] => (image.pixels[i] as f32) < block_mean[b],
+                _ => false,
+            }
+        })
+        .collect()
+}
+
+/// Afinamento de Zhang-Suen até cristas com um pixel de largura
+fn thin(binary: &[bool], w: usize, h: usize) -> Vec<bool> {
+    let mut image = binary.to_vec();
+    loop {
+        let mut changed = false;
+        for step in 0..2 {
+            let mut remove = Vec::new();
+            for y in 1..h.saturating_sub(1) {
+                for x in 1..w.saturating_sub(1) {
+                    if !image[y * w + x] {
+                        continue;
+                    }
+                    // P2..P9 no sentido horário a partir do norte
+                    let p = neighbours(x, y).map(|(nx, ny)| image[ny * w + nx]);
+                    let count = p.iter().filter(|v| **v).count();
+                    let transitions = (0..8).filter(|k| !p[*k] && p[(k + 1) % 8]).count();
+                    let (north, east, south, west) = (p[0], p[2], p[4], p[6]);
+                    let condition = if step == 0 {
+                        !(north && east && south) && !(east && south && west)
+                    } else {
+                        !(north && east && west) && !(north && south && west)
+                    };
+                    if (2..=6).contains(&count) && transitions == 1 && condition {
+                        remove.push(y * w + x);
+                    }
+                }
+            }
+            changed |= !remove.is_empty();
+            for i in remove {
+                image[i] = false;
+            }
+        }
+        if !changed {
+            return image;
+        }
+    }
+}
+
+/// Vizinhos no sentido horário a partir do norte
+fn neighbours(x: usize, y: usize) -> [(usize, usize); 8] {
+    [
+        (x, y - 1), (x + 1, y - 1), (x + 1, y), (x + 1, y + 1),
+        (x, y + 1), (x - 1, y + 1), (x - 1, y), (x - 1, y - 1),
+    ]
+}
+
+/// Direção de uma terminação: do ponto seguido ao longo da crista até a ponta
+fn ending_direction(skeleton: &[bool], w: usize, x: usize, y: usize) -> Option<f32> {
+    let h = skeleton.len() / w;
+    let (mut cx, mut cy) = (x, y);
+    let (mut px, mut py) = (x, y);
+
+    for _ in 0..10 {
+        if cx == 0 || cy == 0 || cx + 1 >= w || cy + 1 >= h {
+            break;
+        }
+        let next = neighbours(cx, cy)
+            .into_iter()
+            .find(|&(nx, ny)| skeleton[ny * w + nx] && (nx, ny) != (px, py));
+        let Some((nx, ny)) = next else { break };
+        (px, py, cx, cy) = (cx, cy, nx, ny);
+    }
+
+    if (cx, cy) == (x, y) {
+        return None;
+    }
+    Some((y as f32 - cy as f32).atan2(x as f32 - cx as f32))
+}
@@ -0,0 +1,787 @@
+//! Módulo de gerenciamento de hardware para urna eletrônica
+
+pub mod camera;
+pub mod escpos;
+pub mod feedback;
+pub mod fingerprint;
+pub mod smartcard;
+pub mod tamper;
+pub mod update;
+
+use anyhow::{Result, anyhow};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::VoteReceipt;
+use crate::i18n::Translator;
+use fortis_domain::PrinterState;
+use fortis_domain::document::{Document, THERMAL_WIDTH};
+use fortis_domain::i18n::keys;
+use crate::messages::{UrnaError, codes};
+use camera::{FaceCamera, FaceCapture, FacePolicy};
+use escpos::{EscPosPrinter, PrinterConnection, ReceiptBuilder};
+use feedback::{FeedbackPolicy, FeedbackSelfTest};
+use fingerprint::{CapturePolicy, FingerprintCapture, FingerprintReader};
+use smartcard::{CardPolicy, SmartcardReader, VoterCertificate};
+use tamper::{TamperPolicy, TamperSensors};
+use update::{UpdateAgent, UpdatePolicy};
+use crate::sync::TransparencySync;
+use fortis_domain::update::{InstallOutcome, InstallReport, UpdateManifest, UpdateVersion};
+
+pub struct HardwareManager {
+    pub biometric_reader: BiometricReader,
+    pub certificate_reader: CertificateReader,
+    pub printer: Printer,
+    pub display: Display,
+    pub keypad: Keypad,
+    pub network: NetworkInterface,
+    pub hsm: HSM,
+    pub ups: UPS,
+    /// Autoteste do som de confirmação e da tela, antes da abertura
+    pub feedback: FeedbackSelfTest,
+    /// Atualizações de firmware e aplicação, aplicadas com a urna ociosa
+    pub updates: UpdateAgent,
+    /// Chave antiviolação, abertura do gabinete e USB estranho
+    pub tamper: TamperSensors,
+}
+
+impl HardwareManager {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            biometric_reader: BiometricReader::new()?,
+            certificate_reader: CertificateReader::new()?,
+            printer: Printer::new()?,
+            display: Display::new()?,
+            keypad: Keypad::new()?,
+            network: NetworkInterface::new()?,
+            hsm: HSM::new()?,
+            ups: UPS::new()?,
+            feedback: FeedbackSelfTest::new(FeedbackPolicy::load(std::path::Path::new(feedback::FEEDBACK_POLICY_PATH))),
+            updates: UpdateAgent::new(UpdatePolicy::load(std::path::Path::new(update::UPDATE_POLICY_PATH))),
+            tamper: TamperSensors::new(TamperPolicy::load(std::path::Path::new(tamper::TAMPER_POLICY_PATH))),
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing hardware manager");
+
+        // Inicializar todos os componentes
+        self.biometric_reader.initialize().await?;
+        self.certificate_reader.initialize().await?;
+        self.printer.initialize().await?;
+        self.display.initialize().await?;
+        self.keypad.initialize().await?;
+        self.network.initialize().await?;
+        self.hsm.initialize().await?;
+        self.ups.initialize().await?;
+        self.tamper.initialize().await?;
+
+        // Verificar integridade do hardware
+        self.verify_hardware_integrity().await?;
+
+        log::info!("Hardware manager initialized successfully");
+        Ok(())
+    }
+
+    async fn verify_hardware_integrity(&self) -> Result<()> {
+        log::debug!("Verifying hardware integrity");
+
+        // Verificar cada componente
+        self.biometric_reader.self_test().await?;
+        self.certificate_reader.self_test().await?;
+        self.printer.self_test().await?;
+        self.display.self_test().await?;
+        self.keypad.self_test().await?;
+        self.network.self_test().await?;
+        self.hsm.self_test().await?;
+        self.ups.self_test().await?;
+
+        log::debug!("Hardware integrity verified");
+        Ok(())
+    }
+
+    /// Consulta e aplica as atualizações na janela ociosa
+    ///
+    /// `idle` indica urna sem sessão de votação e sem lacre; os relatórios
+    /// retornados devem ser enviados ao backend e auditados.
+    pub async fn check_for_updates(&self, sync: &TransparencySync, idle: bool) -> Result<Vec<InstallReport>> {
+        if !self.updates.due(idle).await {
+            return Ok(Vec::new());
+        }
+        let trusted_key = self.updates.trusted_key().await?;
+
+        let mut reports = Vec::new();
+        for component in update::COMPONENTS {
+            let installed = self.updates.installed_version(component).await;
+            let Some(manifest) = sync.fetch_update_manifest(component, &self.updates.policy.channel, installed).await? else {
+                continue;
+            };
+            let (outcome, error) = self.apply_update(sync, &manifest, &trusted_key, installed).await?;
+            reports.push(InstallReport {
+                update_id: manifest.update_id,
+                component,
+                version: manifest.version,
+                outcome,
+                running_version: if outcome == InstallOutcome::Applied { manifest.version } else { installed },
+                error,
+                reported_at: Utc::now(),
+            });
+        }
+        Ok(reports)
+    }
+
+    /// Verifica, instala e confere a saúde; erro só para falhas transitórias
+    /// (download, gravação), que ficam para a próxima janela
+    async fn apply_update(
+        &self,
+        sync: &TransparencySync,
+        manifest: &UpdateManifest,
+        trusted_key: &[u8],
+        installed: UpdateVersion,
+    ) -> Result<(InstallOutcome, Option<String>)> {
+        let component = manifest.component;
+        if let Err(e) = manifest.check_applicable(trusted_key, &self.updates.policy.channel, installed) {
+            log::warn!("Update {} {} rejected: {}", component.as_str(), manifest.version, e);
+            return Ok((InstallOutcome::Rejected, Some(e.to_string())));
+        }
+
+        let artifact = sync.download_update_artifact(manifest, self.updates.policy.max_artifact_bytes).await?;
+        if let Err(e) = manifest.verify_artifact(&artifact) {
+            log::warn!("Update {} {} artifact rejected: {}", component.as_str(), manifest.version, e);
+            return Ok((InstallOutcome::Rejected, Some(e.to_string())));
+        }
+
+        let previous = self.updates.install(manifest, &artifact).await?;
+        let health = match self.updates.health_check(component).await {
+            Ok(()) => self.verify_hardware_integrity().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = health {
+            log::error!("Update {} {} failed health check: {}", component.as_str(), manifest.version, e);
+            self.updates.rollback(component, previous).await?;
+            return Ok((InstallOutcome::RolledBack, Some(e.to_string())));
+        }
+
+        self.updates.commit(component, manifest.version).await?;
+        log::info!("Update {} {} applied", component.as_str(), manifest.version);
+        Ok((InstallOutcome::Applied, None))
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        // Verificar se todos os componentes estão prontos
+        let biometric_ready = self.biometric_reader.is_ready().await?;
+        let printer_ready = self.printer.is_ready().await?;
+        let display_ready = self.display.is_ready().await?;
+        let keypad_ready = self.keypad.is_ready().await?;
+        let hsm_ready = self.hsm.is_ready().await?;
+
+        Ok(biometric_ready && printer_ready && display_ready && keypad_ready && hsm_ready)
+    }
+
+    pub async fn capture_biometric_data(&self) -> Result<BiometricData> {
+        log::info!("Capturing biometric data");
+
+        // Capturar impressão digital (qualidade, vivacidade e template)
+        let capture = self.biometric_reader.capture_fingerprint().await?;
+
+        Ok(BiometricData {
+            fingerprint: capture.template.to_iso_19794_2(),
+            fingerprint_hash: capture.template.fingerprint_hash(),
+            fingerprint_quality: capture.quality,
+            liveness_score: capture.liveness_score,
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Certificado ICP-Brasil do cartão do eleitor, com a cadeia validada
+    pub async fn read_certificate(&self) -> Result<Option<VoterCertificate>> {
+        log::info!("Reading certificate");
+
+        let certificate = self.certificate_reader.read_certificate().await
+            .map_err(|e| UrnaError::new(codes::CERTIFICATE_INVALID, &e.to_string()))?;
+        if let Some(certificate) = &certificate {
+            log::info!("Certificate {} validated up to {:?}", certificate.serial_number, certificate.chain.last());
+        }
+        Ok(certificate)
+    }
+
+    /// Imprime o comprovante no idioma do atendimento
+    pub async fn print_receipt(&self, receipt: &VoteReceipt, i18n: &Translator) -> Result<()> {
+        log::info!("Printing receipt for vote: {}", receipt.vote_id);
+
+        // Preparar dados para impressão
+        let document = self.format_receipt(receipt, i18n);
+
+        // Imprimir comprovante
+        self.printer.print_receipt(receipt.vote_id, &document).await?;
+
+        log::info!("Receipt printed successfully");
+        Ok(())
+    }
+
+    fn format_receipt(&self, receipt: &VoteReceipt, i18n: &Translator) -> ReceiptBuilder {
+        let timestamp = receipt.timestamp.format("%d/%m/%Y %H:%M:%S").to_string();
+        let blockchain_hash = receipt.blockchain_hash.as_deref().unwrap_or("N/A");
+        ReceiptBuilder::new()
+            .center(true)
+            .line("================================")
+            .bold(true)
+            .line(&i18n.text(keys::RECEIPT_HEADER))
+            .bold(false)
+            .line("================================")
+            .center(false)
+            .line(&i18n.render(keys::RECEIPT_VOTE_ID, &[("vote_id", &receipt.vote_id.to_string())]))
+            .line(&i18n.render(keys::RECEIPT_ELECTION, &[("election_id", &receipt.election_id.to_string())]))
+            .lines(receipt.choices.iter().map(|choice| match choice.candidate_number {
+                Some(number) => format!("{}: {} - {}", choice.title, number, choice.candidate_name),
+                None => format!("{}: {}", choice.title, choice.candidate_name),
+            }))
+            .line(&i18n.render(keys::RECEIPT_TIMESTAMP, &[("timestamp", &timestamp)]))
+            .line("")
+            .center(true)
+            .line(&i18n.text(keys::RECEIPT_TRACKING_CODE))
+            .bold(true)
+            .line(&receipt.tracking_code)
+            .bold(false)
+            .qr(&receipt.qr_payload)
+            .center(false)
+            .line(&i18n.render(keys::RECEIPT_BLOCKCHAIN_HASH, &[("hash", blockchain_hash)]))
+            .center(true)
+            .line("================================")
+            .line(&i18n.text(keys::RECEIPT_FOOTER_SYSTEM))
+            .line(&i18n.text(keys::RECEIPT_FOOTER_MOTTO))
+            .line("================================")
+            .feed(3)
+            .cut()
+    }
+
+    /// Imprime um documento oficial (boletim de urna, ata) na largura da bobina
+    pub async fn print_document(&self, document: &Document) -> Result<()> {
+        let receipt = ReceiptBuilder::new()
+            .lines(document.lines(THERMAL_WIDTH))
+            .feed(3)
+            .cut();
+        self.printer.print_document(&receipt).await
+    }
+
+    pub async fn get_hardware_status(&self) -> Result<HardwareStatus> {
+        Ok(HardwareStatus {
+            biometric_reader: self.biometric_reader.get_status().await?,
+            certificate_reader: self.certificate_reader.get_status().await?,
+            printer: self.printer.get_status().await?,
+            display: self.display.get_status().await?,
+            keypad: self.keypad.get_status().await?,
+            network: self.network.get_status().await?,
+            hsm: self.hsm.get_status().await?,
+            ups: self.ups.get_status().await?,
+            timestamp: Utc::now(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BiometricData {
+    /// Template ISO/IEC 19794-2 (a imagem não sai do leitor)
+    pub fingerprint: Vec<u8>,
+    pub fingerprint_hash: String,
+    pub fingerprint_quality: u8,
+    pub liveness_score: f32,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HardwareStatus {
+    pub biometric_reader: ComponentStatus,
+    pub certificate_reader: ComponentStatus,
+    pub printer: ComponentStatus,
+    pub display: ComponentStatus,
+    pub keypad: ComponentStatus,
+    pub network: ComponentStatus,
+    pub hsm: ComponentStatus,
+    pub ups: ComponentStatus,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ComponentStatus {
+    pub is_ready: bool,
+    pub is_healthy: bool,
+    pub last_error: Option<String>,
+    pub uptime: u64,
+}
+
+// Implementações dos componentes de hardware
+
+pub struct BiometricReader {
+    pub model: String,
+    pub is_initialized: bool,
+    pub fingerprint: FingerprintReader,
+    /// Reconhecimento facial, usado só quando a digital falha
+    pub camera: FaceCamera,
+}
+
+impl BiometricReader {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-BR-001".to_string(),
+            is_initialized: false,
+            fingerprint: FingerprintReader::new(CapturePolicy::default()),
+            camera: FaceCamera::new(FacePolicy::default()),
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing biometric reader: {}", self.model);
+        let device = self.fingerprint.detect().await?;
+        log::info!("Fingerprint reader detected: {}", device);
+
+        // Sem câmera a urna funciona, mas sem alternativa à digital
+        if let Err(e) = self.camera.initialize().await {
+            log::warn!("Facial recognition fallback unavailable: {}", e);
+        }
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running biometric reader self-test");
+        self.fingerprint.detect().await?;
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        Ok(self.fingerprint.detect().await.is_ok())
+    }
+
+    pub async fn capture_fingerprint(&self) -> Result<FingerprintCapture> {
+        log::debug!("Capturing fingerprint");
+        self.fingerprint.capture().await
+    }
+
+    pub async fn capture_face(&self) -> Result<FaceCapture> {
+        log::debug!("Capturing face");
+        self.camera.capture().await
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        let detected = self.fingerprint.detect().await;
+        Ok(ComponentStatus {
+            is_ready: detected.is_ok(),
+            is_healthy: detected.is_ok(),
+            last_error: detected.err().map(|e| e.to_string()),
+            uptime: 3600,
+        })
+    }
+}
+
+pub struct CertificateReader {
+    pub model: String,
+    pub is_initialized: bool,
+    pub smartcard: SmartcardReader,
+}
+
+impl CertificateReader {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-CR-001".to_string(),
+            is_initialized: false,
+            smartcard: SmartcardReader::new(CardPolicy::default()),
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing certificate reader: {}", self.model);
+        // O certificado é segundo fator opcional; sem leitor a urna segue só com biometria
+        match self.smartcard.initialize().await {
+            Ok(reader) => log::info!("Smartcard reader detected: {}", reader),
+            Err(e) => log::warn!("Smartcard second factor unavailable: {}", e),
+        }
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running certificate reader self-test");
+        if let Err(e) = self.smartcard.detect().await {
+            log::warn!("Smartcard reader self-test failed: {}", e);
+        }
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        Ok(self.smartcard.detect().await.is_ok())
+    }
+
+    pub async fn read_certificate(&self) -> Result<Option<VoterCertificate>> {
+        log::debug!("Reading certificate");
+        self.smartcard.read().await
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        let detected = self.smartcard.detect().await;
+        Ok(ComponentStatus {
+            is_ready: detected.is_ok(),
+            is_healthy: detected.is_ok(),
+            last_error: detected.err().map(|e| e.to_string()),
+            uptime: 3600,
+        })
+    }
+}
+
+/// Bateria do nobreak exposta pelo kernel (`capacity` e `status`)
+const UPS_POWER_SUPPLY_PATH: &str = "/sys/class/power_supply/fortis_ups";
+
+/// Registro de comprovantes já impressos, preservado entre reinicializações
+const PRINTED_RECEIPTS_PATH: &str = "/var/lib/fortis/printed_receipts";
+
+pub struct Printer {
+    pub model: String,
+    pub is_initialized: bool,
+    pub connection: PrinterConnection,
+    driver: Mutex<Option<EscPosPrinter>>,
+    printed_receipts: Mutex<HashSet<Uuid>>,
+}
+
+impl Printer {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-PR-001".to_string(),
+            is_initialized: false,
+            connection: PrinterConnection::default(),
+            driver: Mutex::new(None),
+            printed_receipts: Mutex::new(HashSet::new()),
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing printer: {} ({:?})", self.model, self.connection);
+
+        *self.driver.lock().await = Some(EscPosPrinter::open(&self.connection).await?);
+
+        // Comprovantes impressos antes de uma reinicialização não podem ser reimpressos
+        if let Ok(journal) = tokio::fs::read_to_string(PRINTED_RECEIPTS_PATH).await {
+            let printed: HashSet<Uuid> = journal.lines().filter_map(|l| l.parse().ok()).collect();
+            log::info!("Loaded {} printed receipts", printed.len());
+            *self.printed_receipts.lock().await = printed;
+        }
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running printer self-test");
+        let mut driver = self.driver.lock().await;
+        let driver = driver.as_mut().ok_or_else(|| UrnaError::new(codes::PRINTER_OFFLINE, "Printer not initialized"))?;
+        let status = driver.self_test().await?;
+        if status.paper_near_end {
+            log::warn!("Printer self-test passed with paper near end");
+        }
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        let mut driver = self.driver.lock().await;
+        match driver.as_mut() {
+            Some(driver) => Ok(driver.status().await?.can_print()),
+            None => Ok(false),
+        }
+    }
+
+    /// Imprime o comprovante de um voto, uma única vez
+    pub async fn print_receipt(&self, vote_id: Uuid, document: &ReceiptBuilder) -> Result<()> {
+        let mut printed = self.printed_receipts.lock().await;
+        if printed.contains(&vote_id) {
+            return Err(UrnaError::new(codes::RECEIPT_ALREADY_PRINTED, "Receipt already printed").into());
+        }
+
+        {
+            let mut driver = self.driver.lock().await;
+            let driver = driver.as_mut().ok_or_else(|| UrnaError::new(codes::PRINTER_OFFLINE, "Printer not initialized"))?;
+            driver.print(document).await?;
+        }
+
+        printed.insert(vote_id);
+        let mut journal = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(PRINTED_RECEIPTS_PATH)
+            .await?;
+        journal.write_all(format!("{}\n", vote_id).as_bytes()).await?;
+        journal.sync_data().await?;
+        Ok(())
+    }
+
+    /// Imprime um documento avulso, sem o controle de via única dos comprovantes
+    pub async fn print_document(&self, document: &ReceiptBuilder) -> Result<()> {
+        let mut driver = self.driver.lock().await;
+        let driver = driver.as_mut().ok_or_else(|| UrnaError::new(codes::PRINTER_OFFLINE, "Printer not initialized"))?;
+        driver.print(document).await
+    }
+
+    /// Situação reportada no heartbeat
+    pub async fn state(&self) -> PrinterState {
+        let status = match self.driver.lock().await.as_mut() {
+            Some(driver) => driver.status().await,
+            None => return PrinterState::Offline,
+        };
+        match status {
+            Ok(status) if !status.online => PrinterState::Offline,
+            Ok(status) if status.error => PrinterState::Error,
+            Ok(status) if status.cover_open => PrinterState::CoverOpen,
+            Ok(status) if status.paper_out => PrinterState::PaperOut,
+            Ok(status) if status.paper_near_end => PrinterState::PaperNearEnd,
+            Ok(_) => PrinterState::Ready,
+            Err(e) => {
+                log::warn!("Printer status unavailable: {}", e);
+                PrinterState::Offline
+            }
+        }
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        let status = match self.driver.lock().await.as_mut() {
+            Some(driver) => driver.status().await,
+            None => Err(anyhow!("Printer not initialized")),
+        };
+
+        Ok(match status {
+            Ok(status) => ComponentStatus {
+                is_ready: status.can_print(),
+                is_healthy: !status.error,
+                last_error: if status.paper_out {
+                    Some("Paper out".to_string())
+                } else if status.cover_open {
+                    Some("Cover open".to_string())
+                } else {
+                    None
+                },
+                uptime: 3600,
+            },
+            Err(e) => ComponentStatus {
+                is_ready: false,
+                is_healthy: false,
+                last_error: Some(e.to_string()),
+                uptime: 0,
+            },
+        })
+    }
+}
+
+pub struct Display {
+    pub model: String,
+    pub is_initialized: bool,
+}
+
+impl Display {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-DP-001".to_string(),
+            is_initialized: false,
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing display: {}", self.model);
+        // Em implementação real, inicializaria hardware real
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running display self-test");
+        // Em implementação real, faria teste real
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        // Em implementação real, verificaria status real
+        Ok(true)
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        Ok(ComponentStatus {
+            is_ready: true,
+            is_healthy: true,
+            last_error: None,
+            uptime: 3600,
+        })
+    }
+}
+
+pub struct Keypad {
+    pub model: String,
+    pub is_initialized: bool,
+}
+
+impl Keypad {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-KP-001".to_string(),
+            is_initialized: false,
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing keypad: {}", self.model);
+        // Em implementação real, inicializaria hardware real
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running keypad self-test");
+        // Em implementação real, faria teste real
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        // Em implementação real, verificaria status real
+        Ok(true)
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        Ok(ComponentStatus {
+            is_ready: true,
+            is_healthy: true,
+            last_error: None,
+            uptime: 3600,
+        })
+    }
+}
+
+pub struct NetworkInterface {
+    pub model: String,
+    pub is_initialized: bool,
+}
+
+impl NetworkInterface {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-NI-001".to_string(),
+            is_initialized: false,
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing network interface: {}", self.model);
+        // Em implementação real, inicializaria hardware real
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running network interface self-test");
+        // Em implementação real, faria teste real
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        // Em implementação real, verificaria status real
+        Ok(true)
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        Ok(ComponentStatus {
+            is_ready: true,
+            is_healthy: true,
+            last_error: None,
+            uptime: 3600,
+        })
+    }
+}
+
+pub struct HSM {
+    pub model: String,
+    pub is_initialized: bool,
+}
+
+impl HSM {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-HSM-001".to_string(),
+            is_initialized: false,
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing HSM: {}", self.model);
+        // Em implementação real, inicializaria hardware real
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running HSM self-test");
+        // Em implementação real, faria teste real
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        // Em implementação real, verificaria status real
+        Ok(true)
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        Ok(ComponentStatus {
+            is_ready: true,
+            is_healthy: true,
+            last_error: None,
+            uptime: 3600,
+        })
+    }
+}
+
+pub struct UPS {
+    pub model: String,
+    pub is_initialized: bool,
+}
+
+impl UPS {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            model: "FORTIS-UPS-001".to_string(),
+            is_initialized: false,
+        })
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        log::info!("Initializing UPS: {}", self.model);
+        // Em implementação real, inicializaria hardware real
+        Ok(())
+    }
+
+    pub async fn self_test(&self) -> Result<()> {
+        log::debug!("Running UPS self-test");
+        // Em implementação real, faria teste real
+        Ok(())
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        // Em implementação real, verificaria status real
+        Ok(true)
+    }
+
+    /// Carga da bateria e se a urna está sem energia da rede; `None` sem leitura do kernel
+    pub async fn power(&self) -> Option<PowerReading> {
+        let dir = std::path::Path::new(UPS_POWER_SUPPLY_PATH);
+        let capacity = tokio::fs::read_to_string(dir.join("capacity")).await.ok()?;
+        let status = tokio::fs::read_to_string(dir.join("status")).await.ok()?;
+        Some(PowerReading {
+            battery_level: capacity.trim().parse().ok()?,
+            on_battery: status.trim() == "Discharging",
+        })
+    }
+
+    pub async fn get_status(&self) -> Result<ComponentStatus> {
+        Ok(ComponentStatus {
+            is_ready: true,
+            is_healthy: true,
+            last_error: None,
+            uptime: 3600,
+        })
+    }
+}
+
+/// Leitura do nobreak
+#[derive(Debug, Clone, Copy)]
+pub struct PowerReading {
+    /// Porcentagem da carga
+    pub battery_level: f32,
+    pub on_battery: bool,
+}
@@ -0,0 +1,396 @@
+//! Leitura de certificado ICP-Brasil em cartão inteligente
+//!
+//! O eleitor pode apresentar o e-Título ou um e-CPF A3 como segundo fator.
+//! O certificado é lido do cartão via PC/SC (aplicação PKCS#15, arquivo do
+//! certificado de autenticação), a cadeia é validada contra as ACs raiz da
+//! ICP-Brasil instaladas na urna e os dados do titular (CPF e título de
+//! eleitor) são extraídos das extensões `otherName` definidas pelo DOC-ICP-04.
+//! A urna não usa a chave privada do cartão: o certificado só identifica o
+//! eleitor, que continua precisando da biometria.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use sha2::{Sha256, Digest};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+use x509_parser::pem::Pem;
+use x509_parser::prelude::*;
+
+/// ACs raiz da ICP-Brasil aceitas pela urna
+pub const ICP_BRASIL_ROOTS_DIR: &str = "/etc/fortis/certs/icp-brasil/roots";
+
+/// ACs intermediárias distribuídas no pacote (o cartão só guarda o certificado final)
+pub const ICP_BRASIL_INTERMEDIATES_DIR: &str = "/etc/fortis/certs/icp-brasil/intermediates";
+
+/// Dados de pessoa física: nascimento, CPF, NIS, RG e órgão expedidor
+const OID_ICP_PF_DATA: &str = "2.16.76.1.3.1";
+
+/// Título de eleitor: número, zona, seção e município/UF
+const OID_ICP_VOTER_REGISTRATION: &str = "2.16.76.1.3.5";
+
+/// Profundidade máxima da cadeia (final, intermediárias e raiz)
+const MAX_CHAIN_DEPTH: usize = 6;
+
+#[derive(Debug, Clone)]
+pub struct CardPolicy {
+    /// Identificador do arquivo do certificado dentro da aplicação PKCS#15
+    pub certificate_file: [u8; 2],
+    pub roots_dir: PathBuf,
+    pub intermediates_dir: PathBuf,
+}
+
+impl Default for CardPolicy {
+    fn default() -> Self {
+        Self {
+            certificate_file: [0x43, 0x01],
+            roots_dir: PathBuf::from(ICP_BRASIL_ROOTS_DIR),
+            intermediates_dir: PathBuf::from(ICP_BRASIL_INTERMEDIATES_DIR),
+        }
+    }
+}
+
+/// Certificado do eleitor com a cadeia já validada
+#[derive(Debug, Clone)]
+pub struct VoterCertificate {
+    pub der: Vec<u8>,
+    pub sha256: String,
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+    pub holder_name: Option<String>,
+    pub cpf: Option<String>,
+    pub birth_date: Option<NaiveDate>,
+    /// Número do título de eleitor (12 dígitos)
+    pub voter_registration: Option<String>,
+    pub electoral_zone: Option<String>,
+    pub electoral_section: Option<String>,
+    /// Titulares da cadeia, do certificado final à raiz
+    pub chain: Vec<String>,
+}
+
+/// ACs confiáveis carregadas do disco
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    roots: Vec<Vec<u8>>,
+    intermediates: Vec<Vec<u8>>,
+}
+
+impl TrustStore {
+    pub async fn load(roots_dir: &Path, intermediates_dir: &Path) -> Result<Self> {
+        let roots = read_certificates(roots_dir).await?;
+        if roots.is_empty() {
+            return Err(anyhow!("No ICP-Brasil root certificates in {}", roots_dir.display()));
+        }
+        // Intermediárias são opcionais: o cartão pode não precisar delas
+        let intermediates = read_certificates(intermediates_dir).await.unwrap_or_default();
+        Ok(Self { roots, intermediates })
+    }
+
+    /// Valida assinaturas, validade e restrições básicas até uma raiz confiável
+    pub fn validate(&self, leaf: &[u8], now: DateTime<Utc>) -> Result<Vec<String>> {
+        let now = ASN1Time::from_timestamp(now.timestamp())
+            .map_err(|e| anyhow!("Invalid validation time: {}", e))?;
+        let mut current = parse(leaf)?;
+        let mut chain = Vec::new();
+
+        for depth in 0..MAX_CHAIN_DEPTH {
+            if !current.validity().is_valid_at(now) {
+                return Err(anyhow!("Certificate {} is outside its validity period", current.subject()));
+            }
+            if depth > 0 && !is_ca(&current) {
+                return Err(anyhow!("Issuer {} is not a certification authority", current.subject()));
+            }
+            chain.push(current.subject().to_string());
+
+            if self.is_trusted_root(&current) {
+                return Ok(chain);
+            }
+
+            current = self.issuer_of(&current)?;
+        }
+        Err(anyhow!("Certificate chain longer than {} without a trusted root", MAX_CHAIN_DEPTH))
+    }
+
+    /// Raiz instalada com o mesmo titular e a mesma chave
+    fn is_trusted_root(&self, cert: &X509Certificate<'_>) -> bool {
+        self.roots.iter().filter_map(|der| parse(der).ok()).any(|root| {
+            root.subject().as_raw() == cert.subject().as_raw()
+                && root.public_key().raw == cert.public_key().raw
+        })
+    }
+
+    fn issuer_of<'a>(&'a self, cert: &X509Certificate<'_>) -> Result<X509Certificate<'a>> {
+        self.roots
+            .iter()
+            .chain(&self.intermediates)
+            .filter_map(|der| parse(der).ok())
+            .find(|candidate| {
+                candidate.subject().as_raw() == cert.issuer().as_raw()
+                    && cert.verify_signature(Some(candidate.public_key())).is_ok()
+            })
+            .ok_or_else(|| anyhow!("Issuer {} not trusted", cert.issuer()))
+    }
+}
+
+/// Leitor de cartão inteligente PC/SC
+pub struct SmartcardReader {
+    pub policy: CardPolicy,
+    trust: RwLock<TrustStore>,
+}
+
+impl SmartcardReader {
+    pub fn new(policy: CardPolicy) -> Self {
+        Self { policy, trust: RwLock::new(TrustStore::default()) }
+    }
+
+    /// Carrega as ACs raiz e confirma que há leitor conectado
+    pub async fn initialize(&self) -> Result<String> {
+        let store = TrustStore::load(&self.policy.roots_dir, &self.policy.intermediates_dir).await?;
+        log::info!(
+            "Loaded {} ICP-Brasil roots and {} intermediates",
+            store.roots.len(),
+            store.intermediates.len()
+        );
+        *self.trust.write().await = store;
+        self.detect().await
+    }
+
+    pub async fn detect(&self) -> Result<String> {
+        tokio::task::spawn_blocking(card::reader_name).await?
+    }
+
+    /// Lê e valida o certificado do cartão; `None` sem cartão inserido
+    pub async fn read(&self) -> Result<Option<VoterCertificate>> {
+        let file = self.policy.certificate_file;
+        let Some(der) = tokio::task::spawn_blocking(move || card::read_certificate(file)).await?? else {
+            return Ok(None);
+        };
+
+        let chain = self.trust.read().await.validate(&der, Utc::now())?;
+        let mut certificate = parse_voter_certificate(&der)?;
+        certificate.chain = chain;
+        Ok(Some(certificate))
+    }
+}
+
+/// Extrai os dados do titular sem validar a cadeia
+pub fn parse_voter_certificate(der: &[u8]) -> Result<VoterCertificate> {
+    let cert = parse(der)?;
+    let mut certificate = VoterCertificate {
+        der: der.to_vec(),
+        sha256: hex::encode(Sha256::digest(der)),
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        serial_number: hex::encode(cert.raw_serial()),
+        valid_from: timestamp(cert.validity().not_before.timestamp())?,
+        valid_until: timestamp(cert.validity().not_after.timestamp())?,
+        // CN da ICP-Brasil para pessoa física: "NOME:CPF"
+        holder_name: cert
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(|cn| cn.split(':').next().unwrap_or(cn).to_string()),
+        cpf: None,
+        birth_date: None,
+        voter_registration: None,
+        electoral_zone: None,
+        electoral_section: None,
+        chain: Vec::new(),
+    };
+
+    let Ok(Some(san)) = cert.subject_alternative_name() else {
+        return Ok(certificate);
+    };
+    for name in &san.value.general_names {
+        let GeneralName::OtherName(oid, value) = name else {
+            continue;
+        };
+        let Some(value) = der_string(value) else {
+            continue;
+        };
+        match oid.to_id_string().as_str() {
+            // ddmmaaaa + CPF(11) + NIS(11) + RG(15) + órgão/UF(6)
+            OID_ICP_PF_DATA => {
+                certificate.birth_date = field(&value, 0, 8)
+                    .and_then(|d| NaiveDate::parse_from_str(&d, "%d%m%Y").ok());
+                certificate.cpf = field(&value, 8, 19);
+            }
+            // título(12) + zona(3) + seção(4) + município/UF(22)
+            OID_ICP_VOTER_REGISTRATION => {
+                certificate.voter_registration = field(&value, 0, 12);
+                certificate.electoral_zone = field(&value, 12, 15);
+                certificate.electoral_section = field(&value, 15, 19);
+            }
+            _ => {}
+        }
+    }
+    Ok(certificate)
+}
+
+fn parse(der: &[u8]) -> Result<X509Certificate<'_>> {
+    let (_, cert) = X509Certificate::from_der(der)
+        .map_err(|e| anyhow!("Invalid X.509 certificate: {}", e))?;
+    Ok(cert)
+}
+
+fn is_ca(cert: &X509Certificate<'_>) -> bool {
+    matches!(cert.basic_constraints(), Ok(Some(constraints)) if constraints.value.ca)
+}
+
+fn timestamp(seconds: i64) -> Result<DateTime<Utc>> {
+    Utc.timestamp_opt(seconds, 0).single().ok_or_else(|| anyhow!("Invalid certificate time"))
+}
+
+/// Campo de posição fixa; campos preenchidos só com zeros são ausentes
+fn field(value: &str, start: usize, end: usize) -> Option<String> {
+    let field = value.get(start..end)?.trim();
+    (!field.is_empty() && field.chars().any(|c| c != '0')).then(|| field.to_string())
+}
+
+/// Conteúdo textual de um valor `otherName` (OCTET STRING ou string ASN.1,
+/// possivelmente dentro da marcação explícita [0])
+fn der_string(mut bytes: &[u8]) -> Option<String> {
+    loop {
+        let (&tag, rest) = bytes.split_first()?;
+        let (&first, rest) = rest.split_first()?;
+        let (len, rest) = match first {
+            len if len < 0x80 => (len as usize, rest),
+            0x81 => (*rest.first()? as usize, rest.get(1..)?),
+            0x82 => (u16::from_be_bytes([*rest.first()?, *rest.get(1)?]) as usize, rest.get(2..)?),
+            _ => return None,
+        };
+        let content = rest.get(..len)?;
+        match tag {
+            0xa0 => bytes = content,
+            0x04 | 0x0c | 0x13 | 0x16 => return Some(String::from_utf8_lossy(content).into_owned()),
+            _ => return None,
+        }
+    }
+}
+
+async fn read_certificates(dir: &Path) -> Result<Vec<Vec<u8>>> {
+    let mut entries = tokio::fs::read_dir(dir).await
+        .map_err(|e| anyhow!("Cannot read certificates from {}: {}", dir.display(), e))?;
+    let mut certificates = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let data = tokio::fs::read(entry.path()).await?;
+        if data.starts_with(b"-----BEGIN") {
+            for pem in Pem::iter_from_buffer(&data) {
+                certificates.push(pem?.contents);
+            }
+        } else {
+            parse(&data)?;
+            certificates.push(data);
+        }
+    }
+    Ok(certificates)
+}
+
+#[cfg(feature = "hardware")]
+mod card {
+    use anyhow::{Result, anyhow};
+    use pcsc::{Context, Disposition, Protocols, Scope, ShareMode, MAX_BUFFER_SIZE};
+
+    /// AID da aplicação PKCS#15
+    const PKCS15_AID: [u8; 12] = [0xa0, 0x00, 0x00, 0x00, 0x63, 0x50, 0x4b, 0x43, 0x53, 0x2d, 0x31, 0x35];
+
+    /// Bytes por READ BINARY
+    const READ_CHUNK: u16 = 0xe0;
+
+    fn first_reader(context: &Context) -> Result<std::ffi::CString> {
+        let mut buffer = [0u8; 2048];
+        context
+            .list_readers(&mut buffer)?
+            .next()
+            .map(|name| name.to_owned())
+            .ok_or_else(|| anyhow!("No smartcard reader connected"))
+    }
+
+    pub fn reader_name() -> Result<String> {
+        let context = Context::establish(Scope::System)?;
+        Ok(first_reader(&context)?.to_string_lossy().into_owned())
+    }
+
+    pub fn read_certificate(file: [u8; 2]) -> Result<Option<Vec<u8>>> {
+        let context = Context::establish(Scope::System)?;
+        // O cartão é opcional: sem leitor, segue só com a biometria
+        let reader = match first_reader(&context) {
+            Ok(reader) => reader,
+            Err(e) => {
+                log::debug!("Smartcard unavailable: {}", e);
+                return Ok(None);
+            }
+        };
+        let card = match context.connect(&reader, ShareMode::Shared, Protocols::ANY) {
+            Ok(card) => card,
+            Err(pcsc::Error::NoSmartcard | pcsc::Error::RemovedCard) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let transmit = |apdu: &[u8]| -> Result<Vec<u8>> {
+            let mut buffer = [0u8; MAX_BUFFER_SIZE];
+            let response = card.transmit(apdu, &mut buffer)?;
+            let (data, status) = response.split_at(response.len().saturating_sub(2));
+            match status {
+                [0x90, 0x00] => Ok(data.to_vec()),
+                [0x6a, 0x82] => Err(anyhow!("Certificate file not found on card")),
+                [sw1, sw2] => Err(anyhow!("Card returned status {:02X}{:02X}", sw1, sw2)),
+                _ => Err(anyhow!("Card returned an empty response")),
+            }
+        };
+
+        let mut select_app = vec![0x00, 0xa4, 0x04, 0x0c, PKCS15_AID.len() as u8];
+        select_app.extend(PKCS15_AID);
+        transmit(&select_app)?;
+        transmit(&[0x00, 0xa4, 0x02, 0x0c, 0x02, file[0], file[1]])?;
+
+        // O tamanho total vem do cabeçalho DER do próprio certificado
+        let mut der = Vec::new();
+        let mut total = None;
+        while total.map_or(true, |total| der.len() < total) {
+            let offset = der.len() as u16;
+            let chunk = transmit(&[0x00, 0xb0, (offset >> 8) as u8, offset as u8, READ_CHUNK as u8])?;
+            if chunk.is_empty() {
+                break;
+            }
+            der.extend(chunk);
+            if total.is_none() {
+                total = Some(der_length(&der)?);
+            }
+        }
+        let _ = card.disconnect(Disposition::LeaveCard);
+
+        let total = total.ok_or_else(|| anyhow!("Empty certificate file"))?;
+        if der.len() < total {
+            return Err(anyhow!("Certificate truncated: {} of {} bytes", der.len(), total));
+        }
+        der.truncate(total);
+        Ok(Some(der))
+    }
+
+    /// Tamanho total de uma SEQUENCE DER a partir do cabeçalho
+    fn der_length(header: &[u8]) -> Result<usize> {
+        match header {
+            [0x30, len, ..] if *len < 0x80 => Ok(2 + *len as usize),
+            [0x30, 0x81, len, ..] => Ok(3 + *len as usize),
+            [0x30, 0x82, hi, lo, ..] => Ok(4 + u16::from_be_bytes([*hi, *lo]) as usize),
+            _ => Err(anyhow!("Card file is not a DER certificate")),
+        }
+    }
+}
+
+#[cfg(not(feature = "hardware"))]
+mod card {
+    use anyhow::{Result, anyhow};
+
+    pub fn reader_name() -> Result<String> {
+        Err(anyhow!("Built without smartcard support"))
+    }
+
+    pub fn read_certificate(_file: [u8; 2]) -> Result<Option<Vec<u8>>> {
+        Err(anyhow!("Built without smartcard support"))
+    }
+}
@@ -0,0 +1,255 @@
+//! Sensores de violação do equipamento
+//!
+//! A chave antiviolação e o sensor de abertura do gabinete são lidos nos
+//! valores GPIO exportados em sysfs (`1` = acionado); sensor ilegível conta
+//! como acionado. Os dispositivos USB conectados são comparados com a lista
+//! de periféricos da urna no pacote de configuração. Cada sensor acionado
+//! ou dispositivo estranho gera um único evento enquanto a condição durar.
+//!
+//! O primeiro evento trava a urna. O bloqueio e os eventos ainda não
+//! aceitos pelo backend ficam gravados em disco, de modo que um reinício
+//! não libera a votação nem perde o envio; o bloqueio só é retirado com os
+//! sensores normalizados.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use fortis_domain::tamper::{TamperEvent, TamperKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Política dos sensores no pacote de configuração
+pub const TAMPER_POLICY_PATH: &str = "/etc/fortis/bundle/tamper_policy.json";
+
+/// Bloqueio e eventos pendentes de envio
+pub const TAMPER_STATE_PATH: &str = "/var/lib/fortis/tamper/state.json";
+
+/// Dispositivo USB permitido; sem produto, vale qualquer um do fabricante
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsbId {
+    pub vendor_id: String,
+    pub product_id: Option<String>,
+}
+
+impl UsbId {
+    fn matches(&self, vendor_id: &str, product_id: &str) -> bool {
+        self.vendor_id.eq_ignore_ascii_case(vendor_id)
+            && self.product_id.as_deref().map_or(true, |product| product.eq_ignore_ascii_case(product_id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TamperPolicy {
+    pub enabled: bool,
+    pub tamper_switch_path: PathBuf,
+    pub case_open_path: PathBuf,
+    pub usb_devices_dir: PathBuf,
+    /// Periféricos da urna (impressora, leitores, teclado)
+    pub allowed_usb: Vec<UsbId>,
+    pub poll_interval_seconds: u64,
+}
+
+impl Default for TamperPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tamper_switch_path: PathBuf::from("/sys/class/gpio/fortis_tamper/value"),
+            case_open_path: PathBuf::from("/sys/class/gpio/fortis_case/value"),
+            usb_devices_dir: PathBuf::from("/sys/bus/usb/devices"),
+            // Hubs raiz do próprio kernel
+            allowed_usb: vec![UsbId { vendor_id: "1d6b".to_string(), product_id: None }],
+            poll_interval_seconds: 2,
+        }
+    }
+}
+
+impl TamperPolicy {
+    /// Carrega a política do pacote, com os valores padrão se indisponível
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| Ok(serde_json::from_slice(&data)?)) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::warn!("Tamper policy unavailable, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Estado gravado: bloqueio e eventos ainda não aceitos pelo backend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TamperState {
+    pub locked_at: Option<DateTime<Utc>>,
+    pub unreported: Vec<TamperEvent>,
+}
+
+/// Condição de violação presente em uma leitura
+struct Condition {
+    kind: TamperKind,
+    source: String,
+    detail: String,
+}
+
+#[derive(Debug)]
+pub struct TamperSensors {
+    pub policy: TamperPolicy,
+    state_path: PathBuf,
+    /// Condições já registradas, para não repetir o evento
+    active: Mutex<HashSet<String>>,
+    state: Mutex<TamperState>,
+}
+
+impl TamperSensors {
+    pub fn new(policy: TamperPolicy) -> Self {
+        Self {
+            policy,
+            state_path: PathBuf::from(TAMPER_STATE_PATH),
+            active: Mutex::new(HashSet::new()),
+            state: Mutex::new(TamperState::default()),
+        }
+    }
+
+    /// Recupera o bloqueio e os eventos pendentes de antes do reinício
+    pub async fn initialize(&self) -> Result<()> {
+        let state: TamperState = match tokio::fs::read(&self.state_path).await {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| anyhow!("Tamper state unreadable: {}", e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TamperState::default(),
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(locked_at) = state.locked_at {
+            log::warn!("Urna locked by tamper detection since {}", locked_at);
+        }
+        *self.state.lock().await = state;
+        Ok(())
+    }
+
+    /// Lê os sensores; devolve os eventos novos, já gravados e com a urna travada
+    pub async fn poll(&self, urna_id: &str, election_id: Option<Uuid>) -> Result<Vec<TamperEvent>> {
+        if !self.policy.enabled {
+            return Ok(Vec::new());
+        }
+        let conditions = self.read_conditions().await;
+
+        let mut active = self.active.lock().await;
+        active.retain(|source| conditions.iter().any(|condition| &condition.source == source));
+        let now = Utc::now();
+        let events: Vec<TamperEvent> = conditions
+            .into_iter()
+            .filter(|condition| active.insert(condition.source.clone()))
+            .map(|condition| TamperEvent {
+                event_id: Uuid::new_v4(),
+                urna_id: urna_id.to_string(),
+                kind: condition.kind,
+                source: condition.source,
+                detail: condition.detail,
+                election_id,
+                detected_at: now,
+            })
+            .collect();
+        if events.is_empty() {
+            return Ok(events);
+        }
+
+        let mut state = self.state.lock().await;
+        state.locked_at.get_or_insert(now);
+        state.unreported.extend(events.iter().cloned());
+        self.save(&state).await?;
+        Ok(events)
+    }
+
+    pub async fn is_locked(&self) -> bool {
+        self.state.lock().await.locked_at.is_some()
+    }
+
+    /// Eventos ainda não aceitos pelo backend
+    pub async fn unreported(&self) -> Vec<TamperEvent> {
+        self.state.lock().await.unreported.clone()
+    }
+
+    pub async fn mark_reported(&self, event_ids: &[Uuid]) -> Result<()> {
+        let mut state = self.state.lock().await;
+        state.unreported.retain(|event| !event_ids.contains(&event.event_id));
+        self.save(&state).await
+    }
+
+    /// Retira o bloqueio; recusa enquanto algum sensor continuar acionado
+    pub async fn clear(&self) -> Result<DateTime<Utc>> {
+        let conditions = self.read_conditions().await;
+        if !conditions.is_empty() {
+            let sources: Vec<&str> = conditions.iter().map(|condition| condition.source.as_str()).collect();
+            return Err(anyhow!("Tamper sensors still triggered: {}", sources.join(", ")));
+        }
+        let mut state = self.state.lock().await;
+        let locked_at = state.locked_at.take().ok_or_else(|| anyhow!("Urna is not locked by tamper detection"))?;
+        self.save(&state).await?;
+        Ok(locked_at)
+    }
+
+    async fn read_conditions(&self) -> Vec<Condition> {
+        let mut conditions = Vec::new();
+        let switches = [
+            (TamperKind::TamperSwitch, &self.policy.tamper_switch_path),
+            (TamperKind::CaseOpen, &self.policy.case_open_path),
+        ];
+        for (kind, path) in switches {
+            let detail = match tokio::fs::read_to_string(path).await {
+                Ok(value) if value.trim() == "0" => continue,
+                Ok(value) => format!("Sensor triggered (value {})", value.trim()),
+                Err(e) => format!("Sensor unreadable: {}", e),
+            };
+            conditions.push(Condition { kind, source: kind.as_str().to_string(), detail });
+        }
+
+        match self.unexpected_usb().await {
+            Ok(devices) => conditions.extend(devices),
+            Err(e) => conditions.push(Condition {
+                kind: TamperKind::UnexpectedUsb,
+                source: "usb".to_string(),
+                detail: format!("USB bus unreadable: {}", e),
+            }),
+        }
+        conditions
+    }
+
+    /// Dispositivos conectados fora da lista de periféricos
+    async fn unexpected_usb(&self) -> Result<Vec<Condition>> {
+        let mut devices = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.policy.usb_devices_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let Ok(vendor_id) = tokio::fs::read_to_string(path.join("idVendor")).await else {
+                // Interfaces não têm identificação própria
+                continue;
+            };
+            let product_id = tokio::fs::read_to_string(path.join("idProduct")).await.unwrap_or_default();
+            let (vendor_id, product_id) = (vendor_id.trim(), product_id.trim());
+            if self.policy.allowed_usb.iter().any(|allowed| allowed.matches(vendor_id, product_id)) {
+                continue;
+            }
+            let product = tokio::fs::read_to_string(path.join("product")).await.unwrap_or_default();
+            devices.push(Condition {
+                kind: TamperKind::UnexpectedUsb,
+                source: format!("usb {} {}:{}", entry.file_name().to_string_lossy(), vendor_id, product_id),
+                detail: format!("Unexpected USB device {}", product.trim()),
+            });
+        }
+        Ok(devices)
+    }
+
+    /// Grava em arquivo temporário, sincroniza e troca por `rename`
+    async fn save(&self, state: &TamperState) -> Result<()> {
+        if let Some(parent) = self.state_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let temporary = self.state_path.with_extension("json.tmp");
+        let mut file = tokio::fs::File::create(&temporary).await?;
+        file.write_all(&serde_json::to_vec(state)?).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&temporary, &self.state_path).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+//! Atualização de firmware e da aplicação pela rede
+//!
+//! Com a urna ociosa (sem sessão de votação aberta e sem lacre da
+//! configuração) por `min_idle_seconds`, o `HardwareManager` consulta o
+//! backend pelo manifesto mais recente do canal da urna. O manifesto só é
+//! aceito com a assinatura Ed25519 da chave fixada e versão maior que a
+//! instalada; o artefato baixado é conferido pelo tamanho e pelo SHA-256.
+//!
+//! Cada componente tem dois slots (A e B) e um link `current` para o slot
+//! em uso. O artefato é gravado no slot inativo e o link é trocado por
+//! `rename`, de forma atômica. Se a verificação de saúde falhar, o link
+//! volta para o slot anterior; a versão instalada só é registrada depois
+//! da verificação aprovada. O slot novo passa a valer na próxima
+//! inicialização da urna.
+
+use anyhow::{Result, anyhow};
+use fortis_domain::update::{UpdateComponent, UpdateManifest, UpdateVersion};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+/// Política de atualização no pacote de configuração
+pub const UPDATE_POLICY_PATH: &str = "/etc/fortis/bundle/update_policy.json";
+
+/// Chave pública confiável para manifestos de atualização
+pub const TRUSTED_UPDATE_KEY_PATH: &str = "/etc/fortis/keys/update_signing.pub";
+
+/// Slots A/B de cada componente
+pub const UPDATE_SLOTS_DIR: &str = "/opt/fortis/slots";
+
+/// Versões instaladas e aprovadas na verificação de saúde
+pub const INSTALLED_VERSIONS_PATH: &str = "/var/lib/fortis/updates/installed.json";
+
+/// Componentes verificados a cada consulta, nesta ordem
+pub const COMPONENTS: [UpdateComponent; 2] = [UpdateComponent::Firmware, UpdateComponent::Application];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdatePolicy {
+    pub enabled: bool,
+    pub channel: String,
+    /// Intervalo mínimo entre consultas ao backend
+    pub check_interval_seconds: u64,
+    /// Ociosidade contínua exigida antes de consultar e aplicar
+    pub min_idle_seconds: u64,
+    /// Comando de verificação de saúde; recebe o componente e o slot novo
+    pub health_check_command: PathBuf,
+    pub health_check_timeout_seconds: u64,
+    pub max_artifact_bytes: u64,
+}
+
+impl Default for UpdatePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            channel: "stable".to_string(),
+            check_interval_seconds: 3600,
+            min_idle_seconds: 600,
+            health_check_command: PathBuf::from("/usr/lib/fortis/healthcheck"),
+            health_check_timeout_seconds: 120,
+            max_artifact_bytes: 512 * 1024 * 1024,
+        }
+    }
+}
+
+impl UpdatePolicy {
+    /// Carrega a política do pacote, com os valores padrão se indisponível
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| Ok(serde_json::from_slice(&data)?)) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::warn!("Update policy unavailable, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Slot de instalação
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    A,
+    B,
+}
+
+impl Slot {
+    fn name(&self) -> &'static str {
+        match self {
+            Slot::A => "a",
+            Slot::B => "b",
+        }
+    }
+
+    fn other(&self) -> Slot {
+        match self {
+            Slot::A => Slot::B,
+            Slot::B => Slot::A,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct InstalledVersions {
+    versions: HashMap<UpdateComponent, UpdateVersion>,
+}
+
+/// Slots, versões instaladas e janela de ociosidade
+#[derive(Debug)]
+pub struct UpdateAgent {
+    pub policy: UpdatePolicy,
+    slots_dir: PathBuf,
+    installed_path: PathBuf,
+    idle_since: Mutex<Option<Instant>>,
+    last_check: Mutex<Option<Instant>>,
+}
+
+impl UpdateAgent {
+    pub fn new(policy: UpdatePolicy) -> Self {
+        Self {
+            policy,
+            slots_dir: PathBuf::from(UPDATE_SLOTS_DIR),
+            installed_path: PathBuf::from(INSTALLED_VERSIONS_PATH),
+            idle_since: Mutex::new(None),
+            last_check: Mutex::new(None),
+        }
+    }
+
+    /// Marca o estado da urna e diz se a janela ociosa permite consultar agora
+    pub async fn due(&self, idle: bool) -> bool {
+        let mut idle_since = self.idle_since.lock().await;
+        if !self.policy.enabled || !idle {
+            *idle_since = None;
+            return false;
+        }
+        let since = *idle_since.get_or_insert_with(Instant::now);
+        if since.elapsed() < Duration::from_secs(self.policy.min_idle_seconds) {
+            return false;
+        }
+
+        let mut last_check = self.last_check.lock().await;
+        if last_check.is_some_and(|last| last.elapsed() < Duration::from_secs(self.policy.check_interval_seconds)) {
+            return false;
+        }
+        *last_check = Some(Instant::now());
+        true
+    }
+
+    /// Versão em uso; sem registro, a aplicação vale a versão deste binário
+    pub async fn installed_version(&self, component: UpdateComponent) -> UpdateVersion {
+        let recorded = self.load_installed().await.versions.get(&component).copied();
+        recorded.unwrap_or_else(|| match component {
+            UpdateComponent::Application => env!("CARGO_PKG_VERSION").parse().unwrap_or(UpdateVersion { major: 0, minor: 0, patch: 0 }),
+            UpdateComponent::Firmware => UpdateVersion { major: 0, minor: 0, patch: 0 },
+        })
+    }
+
+    /// Chave fixada para os manifestos
+    pub async fn trusted_key(&self) -> Result<Vec<u8>> {
+        let key = tokio::fs::read_to_string(TRUSTED_UPDATE_KEY_PATH).await
+            .map_err(|e| anyhow!("Trusted update key unavailable: {}", e))?;
+        Ok(hex::decode(key.trim())?)
+    }
+
+    /// Grava o artefato no slot inativo e troca o link; retorna o slot anterior
+    pub async fn install(&self, manifest: &UpdateManifest, artifact: &[u8]) -> Result<Slot> {
+        let component_dir = self.slots_dir.join(manifest.component.as_str());
+        let previous = self.active_slot(&component_dir).await?;
+        let target = previous.other();
+        let target_dir = component_dir.join(target.name());
+
+        match tokio::fs::remove_dir_all(&target_dir).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        tokio::fs::create_dir_all(&target_dir).await?;
+        write_synced(&target_dir.join("image"), artifact).await?;
+        write_synced(&target_dir.join("manifest.json"), &serde_json::to_vec_pretty(manifest)?).await?;
+
+        self.switch_slot(&component_dir, target).await?;
+        log::info!(
+            "{} {} installed in slot {}",
+            manifest.component.as_str(), manifest.version, target.name()
+        );
+        Ok(previous)
+    }
+
+    /// Executa a verificação de saúde do slot em uso
+    pub async fn health_check(&self, component: UpdateComponent) -> Result<()> {
+        let slot = self.slots_dir.join(component.as_str()).join("current");
+        let run = Command::new(&self.policy.health_check_command)
+            .arg(component.as_str())
+            .arg(&slot)
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(Duration::from_secs(self.policy.health_check_timeout_seconds), run)
+            .await
+            .map_err(|_| anyhow!("Health check timed out"))?
+            .map_err(|e| anyhow!("Failed to run health check: {}", e))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Health check failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Volta o link para o slot anterior
+    pub async fn rollback(&self, component: UpdateComponent, previous: Slot) -> Result<()> {
+        self.switch_slot(&self.slots_dir.join(component.as_str()), previous).await?;
+        log::warn!("{} rolled back to slot {}", component.as_str(), previous.name());
+        Ok(())
+    }
+
+    /// Registra a versão aprovada na verificação de saúde
+    pub async fn commit(&self, component: UpdateComponent, version: UpdateVersion) -> Result<()> {
+        let mut installed = self.load_installed().await;
+        installed.versions.insert(component, version);
+        if let Some(parent) = self.installed_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let temp = self.installed_path.with_extension("json.tmp");
+        write_synced(&temp, &serde_json::to_vec_pretty(&installed)?).await?;
+        tokio::fs::rename(&temp, &self.installed_path).await?;
+        Ok(())
+    }
+
+    async fn load_installed(&self) -> InstalledVersions {
+        match tokio::fs::read(&self.installed_path).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                log::warn!("Installed versions unreadable: {}", e);
+                InstalledVersions::default()
+            }),
+            Err(_) => InstalledVersions::default(),
+        }
+    }
+
+    /// Slot apontado por `current`; sem link, a instalação de fábrica está em A
+    async fn active_slot(&self, component_dir: &Path) -> Result<Slot> {
+        match tokio::fs::read_link(component_dir.join("current")).await {
+            Ok(target) if target == Path::new(Slot::B.name()) => Ok(Slot::B),
+            Ok(target) if target == Path::new(Slot::A.name()) => Ok(Slot::A),
+            Ok(target) => Err(anyhow!("Unexpected slot link: {}", target.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Slot::A),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Troca atômica do link `current`
+    async fn switch_slot(&self, component_dir: &Path, slot: Slot) -> Result<()> {
+        let next = component_dir.join("current.next");
+        match tokio::fs::remove_file(&next).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        tokio::fs::symlink(slot.name(), &next).await?;
+        tokio::fs::rename(&next, component_dir.join("current")).await?;
+        tokio::fs::File::open(component_dir).await?.sync_all().await?;
+        Ok(())
+    }
+}
+
+async fn write_synced(path: &Path, data: &[u8]) -> Result<()> {
+    let mut file = tokio::fs::File::create(path).await?;
+    file.write_all(data).await?;
+    file.sync_all().await?;
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! Idioma das telas, do guia por voz, do comprovante e das mensagens de erro
+//!
+//! O português é o padrão, com os textos embutidos no software e o catálogo
+//! de erros do pacote de configuração. Os demais idiomas (espanhol e os
+//! pacotes de línguas indígenas) chegam no pacote de eleição e ficam no
+//! pacote de configuração. O mesário escolhe o idioma ao liberar o eleitor
+//! e ele volta ao português ao fim do atendimento. Texto ausente no pacote
+//! cai no português.
+
+use anyhow::{Result, anyhow};
+use fortis_domain::i18n::{self as texts, DEFAULT_LOCALE};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::messages::{self, ErrorCatalog, VoterMessage};
+use crate::package::ElectionPackage;
+
+/// Pacotes de idioma no pacote de configuração
+pub const LANGUAGE_PACKS_PATH: &str = "/etc/fortis/bundle/language_packs.json";
+
+/// Pacote de um idioma, como publicado pelo backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguagePack {
+    pub locale: String,
+    pub name: String,
+    /// Voz do sintetizador para o guia por voz
+    #[serde(default)]
+    pub tts_voice: Option<String>,
+    #[serde(default)]
+    pub texts: BTreeMap<String, String>,
+    pub errors: ErrorCatalog,
+}
+
+/// Textos no idioma do atendimento
+pub struct Translator {
+    default_texts: BTreeMap<String, String>,
+    default_errors: ErrorCatalog,
+    packs: BTreeMap<String, LanguagePack>,
+    active: RwLock<Option<String>>,
+}
+
+impl Translator {
+    /// Carrega o catálogo em português e os pacotes instalados
+    pub fn load() -> Self {
+        let packs = match Self::read_packs(Path::new(LANGUAGE_PACKS_PATH)) {
+            Ok(packs) => packs,
+            Err(e) => {
+                log::warn!("Language packs unavailable, using {} only: {}", DEFAULT_LOCALE, e);
+                Vec::new()
+            }
+        };
+        Self::new(ErrorCatalog::load(Path::new(messages::ERROR_CATALOG_PATH)), packs)
+    }
+
+    pub fn new(default_errors: ErrorCatalog, packs: Vec<LanguagePack>) -> Self {
+        let mut default_texts = texts::builtin_texts(DEFAULT_LOCALE).unwrap_or_default();
+        let mut by_locale = BTreeMap::new();
+        for pack in packs {
+            // O pacote em português só atualiza os textos padrão
+            if pack.locale.eq_ignore_ascii_case(DEFAULT_LOCALE) {
+                default_texts.extend(pack.texts);
+                continue;
+            }
+            by_locale.insert(pack.locale.to_ascii_lowercase(), pack);
+        }
+        log::info!("Languages available: {}, {:?}", DEFAULT_LOCALE, by_locale.keys().collect::<Vec<_>>());
+        Self {
+            default_texts,
+            default_errors,
+            packs: by_locale,
+            active: RwLock::new(None),
+        }
+    }
+
+    fn read_packs(path: &Path) -> Result<Vec<LanguagePack>> {
+        let data = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Idioma do atendimento; `None` ou o português voltam ao padrão
+    pub fn set_language(&self, locale: Option<&str>) -> Result<()> {
+        let active = match locale {
+            None => None,
+            Some(locale) if locale.eq_ignore_ascii_case(DEFAULT_LOCALE) => None,
+            Some(locale) => {
+                let key = locale.to_ascii_lowercase();
+                if !self.packs.contains_key(&key) {
+                    return Err(anyhow!("Language {} is not installed", locale));
+                }
+                Some(key)
+            }
+        };
+        *self.active.write().map_err(|_| anyhow!("Language state poisoned"))? = active;
+        Ok(())
+    }
+
+    pub fn reset(&self) {
+        if let Ok(mut active) = self.active.write() {
+            *active = None;
+        }
+    }
+
+    fn with_pack<T>(&self, f: impl FnOnce(Option<&LanguagePack>) -> T) -> T {
+        let active = self.active.read().ok().and_then(|active| active.clone());
+        f(active.and_then(|locale| self.packs.get(&locale)))
+    }
+
+    pub fn locale(&self) -> String {
+        self.with_pack(|pack| pack.map_or_else(|| DEFAULT_LOCALE.to_string(), |pack| pack.locale.clone()))
+    }
+
+    /// Voz do sintetizador para o idioma, se o pacote indicar
+    pub fn tts_voice(&self) -> Option<String> {
+        self.with_pack(|pack| pack.and_then(|pack| pack.tts_voice.clone()))
+    }
+
+    /// Texto da chave no idioma ativo
+    pub fn text(&self, key: &str) -> String {
+        self.with_pack(|pack| {
+            pack.and_then(|pack| pack.texts.get(key))
+                .or_else(|| self.default_texts.get(key))
+                .cloned()
+                .unwrap_or_else(|| key.to_string())
+        })
+    }
+
+    /// Texto com os parâmetros substituídos
+    pub fn render(&self, key: &str, args: &[(&str, &str)]) -> String {
+        texts::render(&self.text(key), args)
+    }
+
+    /// Mensagem ao eleitor para o código de erro, no idioma ativo
+    pub fn voter_message(&self, code: &str) -> VoterMessage {
+        self.with_pack(|pack| match pack {
+            Some(pack) if pack.errors.messages.contains_key(code) => pack.errors.voter_message(code).clone(),
+            _ => self.default_errors.voter_message(code).clone(),
+        })
+    }
+}
+
+/// Grava o catálogo e os pacotes de idioma do pacote de eleição
+///
+/// Passam a valer na próxima inicialização da urna.
+pub async fn install(package: &ElectionPackage) -> Result<()> {
+    tokio::fs::write(
+        messages::ERROR_CATALOG_PATH,
+        serde_json::to_vec(&package.error_catalog)?,
+    ).await?;
+    tokio::fs::write(
+        LANGUAGE_PACKS_PATH,
+        serde_json::to_vec(&package.language_packs)?,
+    ).await?;
+    Ok(())
+}
@@ -0,0 +1,451 @@
+//! Perfis de latência e falhas do hardware simulado
+//!
+//! O `SessionSimulator` roda sem hardware; com um perfil, cada etapa passa
+//! a levar o tempo dos dispositivos que usa (leitor lento, impressora
+//! instável, tela com atraso) e os dispositivos falham na taxa configurada.
+//! O sorteio é semeado pelo perfil, de modo que a mesma simulação dá sempre
+//! os mesmos tempos e as mesmas falhas.
+//!
+//! Os `UxRequirements` fixam o tempo máximo de cada tela; `simulate_session`
+//! conduz uma seção inteira e devolve as telas que estouraram o limite.
+
+use anyhow::{Result, anyhow};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::messages::codes;
+use crate::replay::{InputSource, ReplayTarget, SessionSimulator, SessionStep, TraceEvent};
+
+/// Dispositivo simulado
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Device {
+    FingerprintReader,
+    Keypad,
+    Display,
+    Printer,
+}
+
+impl Device {
+    /// Código de erro quando o dispositivo falha
+    fn fault_code(self) -> &'static str {
+        match self {
+            Device::FingerprintReader => codes::FINGERPRINT_TIMEOUT,
+            Device::Printer => codes::PRINTER_PAPER_OUT,
+            Device::Keypad | Device::Display => codes::INTERNAL_ERROR,
+        }
+    }
+
+    /// Entrada do rastro em que a falha aparece
+    fn input_source(self) -> Option<InputSource> {
+        match self {
+            Device::FingerprintReader => Some(InputSource::Biometric),
+            Device::Keypad => Some(InputSource::Keypad),
+            Device::Printer => Some(InputSource::Printer),
+            Device::Display => None,
+        }
+    }
+}
+
+/// Comportamento de um dispositivo no perfil
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceBehavior {
+    pub latency_ms: u64,
+    /// Variação máxima somada à latência, sorteada a cada uso
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// Probabilidade de falha em cada uso, entre 0 e 1
+    #[serde(default)]
+    pub fault_rate: f64,
+    /// Tempo até o dispositivo desistir quando falha
+    #[serde(default)]
+    pub fault_latency_ms: u64,
+}
+
+impl DeviceBehavior {
+    fn nominal(latency_ms: u64) -> Self {
+        Self { latency_ms, jitter_ms: latency_ms / 5, fault_rate: 0.0, fault_latency_ms: 0 }
+    }
+}
+
+/// Perfil de latência e falhas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyProfile {
+    pub name: String,
+    pub seed: u64,
+    pub devices: BTreeMap<Device, DeviceBehavior>,
+}
+
+impl LatencyProfile {
+    /// Hardware em bom estado, tempos medidos nas urnas de homologação
+    pub fn nominal() -> Self {
+        Self {
+            name: "nominal".to_string(),
+            seed: 1,
+            devices: BTreeMap::from([
+                (Device::FingerprintReader, DeviceBehavior::nominal(1500)),
+                (Device::Keypad, DeviceBehavior::nominal(50)),
+                (Device::Display, DeviceBehavior::nominal(120)),
+                (Device::Printer, DeviceBehavior::nominal(2500)),
+            ]),
+        }
+    }
+
+    /// Leitor biométrico lento, com leituras que esgotam o tempo
+    pub fn slow_fingerprint() -> Self {
+        Self::nominal().with_device(Device::FingerprintReader, DeviceBehavior {
+            latency_ms: 4500,
+            jitter_ms: 2500,
+            fault_rate: 0.15,
+            fault_latency_ms: 10_000,
+        }).named("slow_fingerprint")
+    }
+
+    /// Impressora que perde comunicação no meio do comprovante
+    pub fn flaky_printer() -> Self {
+        Self::nominal().with_device(Device::Printer, DeviceBehavior {
+            latency_ms: 3000,
+            jitter_ms: 4000,
+            fault_rate: 0.1,
+            fault_latency_ms: 8000,
+        }).named("flaky_printer")
+    }
+
+    /// Tela com atraso de atualização
+    pub fn laggy_display() -> Self {
+        Self::nominal().with_device(Device::Display, DeviceBehavior {
+            latency_ms: 900,
+            jitter_ms: 600,
+            fault_rate: 0.0,
+            fault_latency_ms: 0,
+        }).named("laggy_display")
+    }
+
+    /// Perfil embutido pelo nome
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "nominal" => Some(Self::nominal()),
+            "slow_fingerprint" => Some(Self::slow_fingerprint()),
+            "flaky_printer" => Some(Self::flaky_printer()),
+            "laggy_display" => Some(Self::laggy_display()),
+            _ => None,
+        }
+    }
+
+    /// Perfil embutido ou arquivo JSON com um perfil próprio
+    pub async fn load(name_or_path: &str) -> Result<Self> {
+        if let Some(profile) = Self::by_name(name_or_path) {
+            return Ok(profile);
+        }
+        let data = tokio::fs::read(Path::new(name_or_path)).await
+            .map_err(|e| anyhow!("Unknown latency profile {}: {}", name_or_path, e))?;
+        let profile: Self = serde_json::from_slice(&data)?;
+        if let Some((device, _)) = profile.devices.iter().find(|(_, b)| !(0.0..=1.0).contains(&b.fault_rate)) {
+            return Err(anyhow!("Fault rate of {:?} must be between 0 and 1", device));
+        }
+        Ok(profile)
+    }
+
+    pub fn with_device(mut self, device: Device, behavior: DeviceBehavior) -> Self {
+        self.devices.insert(device, behavior);
+        self
+    }
+
+    fn named(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+/// Resultado sorteado para o uso de um dispositivo
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceOutcome {
+    pub device: Device,
+    pub elapsed_ms: u64,
+    pub fault: Option<&'static str>,
+}
+
+/// Sorteio semeado dos tempos e falhas de um perfil
+#[derive(Debug)]
+pub struct SimulatedHardware {
+    profile: LatencyProfile,
+    rng: StdRng,
+}
+
+impl SimulatedHardware {
+    pub fn new(profile: LatencyProfile) -> Self {
+        let rng = StdRng::seed_from_u64(profile.seed);
+        Self { profile, rng }
+    }
+
+    /// Usa o dispositivo; sem comportamento no perfil, responde na hora
+    pub fn operate(&mut self, device: Device) -> DeviceOutcome {
+        let Some(behavior) = self.profile.devices.get(&device) else {
+            return DeviceOutcome { device, elapsed_ms: 0, fault: None };
+        };
+
+        if behavior.fault_rate > 0.0 && self.rng.gen_bool(behavior.fault_rate) {
+            return DeviceOutcome {
+                device,
+                elapsed_ms: behavior.fault_latency_ms.max(behavior.latency_ms),
+                fault: Some(device.fault_code()),
+            };
+        }
+        let jitter = if behavior.jitter_ms > 0 { self.rng.gen_range(0..=behavior.jitter_ms) } else { 0 };
+        DeviceOutcome { device, elapsed_ms: behavior.latency_ms + jitter, fault: None }
+    }
+}
+
+/// Dispositivos usados por cada tela
+pub fn devices_for(step: SessionStep) -> &'static [Device] {
+    match step {
+        SessionStep::Authenticate => &[Device::Display, Device::FingerprintReader],
+        SessionStep::SelectCandidate => &[Device::Display, Device::Keypad, Device::Display],
+        SessionStep::CastVote => &[Device::Keypad, Device::Display],
+        SessionStep::PrintReceipt => &[Device::Display, Device::Printer],
+        SessionStep::StartSession | SessionStep::EndSession => &[Device::Display],
+        // A sincronização corre em segundo plano, fora das telas
+        SessionStep::SyncVotes => &[],
+    }
+}
+
+/// Tempo de uma tela na simulação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenTiming {
+    pub step: SessionStep,
+    pub elapsed_ms: u64,
+    pub error_code: Option<String>,
+}
+
+/// Tela acima do tempo máximo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimingViolation {
+    pub step: SessionStep,
+    pub elapsed_ms: u64,
+    pub limit_ms: u64,
+}
+
+/// Tempo máximo por tela exigido para a experiência do eleitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UxRequirements {
+    pub max_seconds_per_screen: BTreeMap<String, u64>,
+}
+
+impl Default for UxRequirements {
+    fn default() -> Self {
+        Self {
+            max_seconds_per_screen: BTreeMap::from([
+                ("authenticate".to_string(), 15),
+                ("select_candidate".to_string(), 5),
+                ("cast_vote".to_string(), 3),
+                ("print_receipt".to_string(), 10),
+            ]),
+        }
+    }
+}
+
+impl UxRequirements {
+    fn limit_ms(&self, step: SessionStep) -> Option<u64> {
+        let key = serde_json::to_value(step).ok()?;
+        self.max_seconds_per_screen.get(key.as_str()?).map(|seconds| seconds * 1000)
+    }
+
+    /// Telas que passaram do limite; telas sem limite não são conferidas
+    pub fn check(&self, timings: &[ScreenTiming]) -> Vec<TimingViolation> {
+        timings.iter()
+            .filter_map(|timing| {
+                let limit_ms = self.limit_ms(timing.step)?;
+                (timing.elapsed_ms > limit_ms).then(|| TimingViolation {
+                    step: timing.step,
+                    elapsed_ms: timing.elapsed_ms,
+                    limit_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Resultado de uma seção simulada com um perfil
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub profile: String,
+    pub voters: usize,
+    pub votes_completed: usize,
+    pub timings: Vec<ScreenTiming>,
+    pub violations: Vec<TimingViolation>,
+}
+
+impl SimulationReport {
+    pub fn meets_requirements(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Conduz a seção com `voters` eleitores sobre o hardware do perfil
+///
+/// Cada eleitor percorre autenticação, escolha, confirmação e comprovante;
+/// uma falha encerra o atendimento, como na urna.
+pub fn simulate_session(profile: LatencyProfile, voters: usize, requirements: &UxRequirements) -> SimulationReport {
+    let name = profile.name.clone();
+    let mut simulator = SessionSimulator::new().with_devices(SimulatedHardware::new(profile));
+    let mut clock_ms = 0;
+    let mut votes_completed = 0;
+
+    simulator.inject(clock_ms, &TraceEvent::Hardware { component: "simulated".to_string(), ready: true });
+    clock_ms += run(&mut simulator, clock_ms, SessionStep::StartSession);
+
+    for _ in 0..voters {
+        let mut completed = true;
+        for step in [SessionStep::Authenticate, SessionStep::SelectCandidate, SessionStep::CastVote, SessionStep::PrintReceipt] {
+            clock_ms += run(&mut simulator, clock_ms, step);
+            if simulator.timings().last().is_some_and(|timing| timing.error_code.is_some()) {
+                completed = step == SessionStep::PrintReceipt;
+                break;
+            }
+        }
+        // O voto vale mesmo sem comprovante
+        if completed {
+            votes_completed += 1;
+        }
+    }
+    run(&mut simulator, clock_ms, SessionStep::EndSession);
+
+    let timings = simulator.timings().to_vec();
+    SimulationReport {
+        profile: name,
+        voters,
+        votes_completed,
+        violations: requirements.check(&timings),
+        timings,
+    }
+}
+
+fn run(simulator: &mut SessionSimulator, clock_ms: u64, step: SessionStep) -> u64 {
+    simulator.run_step(clock_ms, step);
+    simulator.timings().last().map_or(0, |timing| timing.elapsed_ms)
+}
+
+/// Entrada de falha a injetar antes da etapa
+pub(crate) fn fault_input(outcome: &DeviceOutcome) -> Option<TraceEvent> {
+    let fault = outcome.fault?;
+    let source = outcome.device.input_source()?;
+    Some(TraceEvent::Input { source, error_code: Some(fault.to_string()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn always_failing(latency_ms: u64, fault_latency_ms: u64) -> DeviceBehavior {
+        DeviceBehavior { latency_ms, jitter_ms: 0, fault_rate: 1.0, fault_latency_ms }
+    }
+
+    fn timing(step: SessionStep, elapsed_ms: u64) -> ScreenTiming {
+        ScreenTiming { step, elapsed_ms, error_code: None }
+    }
+
+    #[test]
+    fn test_requirements_flag_only_screens_over_their_limit() {
+        let requirements = UxRequirements::default();
+        let violations = requirements.check(&[
+            timing(SessionStep::Authenticate, 15_000),
+            timing(SessionStep::Authenticate, 15_001),
+            timing(SessionStep::CastVote, 2_999),
+            timing(SessionStep::PrintReceipt, 30_000),
+            // Sem limite definido: não é conferida
+            timing(SessionStep::SyncVotes, 600_000),
+        ]);
+
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].step, SessionStep::Authenticate);
+        assert_eq!((violations[0].elapsed_ms, violations[0].limit_ms), (15_001, 15_000));
+        assert_eq!(violations[1].step, SessionStep::PrintReceipt);
+        assert_eq!(violations[1].limit_ms, 10_000);
+    }
+
+    #[test]
+    fn test_sampled_latency_stays_within_jitter_and_is_reproducible() {
+        let mut first = SimulatedHardware::new(LatencyProfile::slow_fingerprint());
+        let mut second = SimulatedHardware::new(LatencyProfile::slow_fingerprint());
+        let first_draws: Vec<_> = (0..500).map(|_| first.operate(Device::Printer)).collect();
+        let second_draws: Vec<_> = (0..500).map(|_| second.operate(Device::Printer)).collect();
+        assert_eq!(first_draws, second_draws);
+
+        // Impressora nominal: 2500 ms mais até 500 ms de variação
+        let mut samples: Vec<u64> = first_draws.iter().map(|outcome| outcome.elapsed_ms).collect();
+        samples.sort_unstable();
+        let percentile = |p: usize| samples[(samples.len() - 1) * p / 100];
+        assert!(percentile(0) >= 2500);
+        assert!(percentile(100) <= 3000);
+        assert!((2650..=2850).contains(&percentile(50)));
+        assert!(percentile(95) >= 2900);
+        assert!(first_draws.iter().all(|outcome| outcome.fault.is_none()));
+    }
+
+    #[test]
+    fn test_failing_device_takes_fault_latency_and_injects_its_input() {
+        let profile = LatencyProfile::nominal()
+            .with_device(Device::FingerprintReader, always_failing(1500, 10_000))
+            .with_device(Device::Display, always_failing(900, 0));
+        let mut hardware = SimulatedHardware::new(profile);
+
+        let reader = hardware.operate(Device::FingerprintReader);
+        assert_eq!(reader.elapsed_ms, 10_000);
+        assert_eq!(reader.fault, Some(codes::FINGERPRINT_TIMEOUT));
+        assert!(matches!(
+            fault_input(&reader),
+            Some(TraceEvent::Input { source: InputSource::Biometric, error_code: Some(code) }) if code == codes::FINGERPRINT_TIMEOUT
+        ));
+
+        // Falha mais rápida que a latência normal não encurta o uso
+        let display = hardware.operate(Device::Display);
+        assert_eq!(display.elapsed_ms, 900);
+        assert!(fault_input(&display).is_none());
+
+        let keypad = hardware.operate(Device::Keypad);
+        assert!(keypad.fault.is_none());
+        assert!(fault_input(&keypad).is_none());
+    }
+
+    #[test]
+    fn test_device_missing_from_profile_answers_immediately() {
+        let mut profile = LatencyProfile::nominal();
+        profile.devices.remove(&Device::Keypad);
+        let outcome = SimulatedHardware::new(profile).operate(Device::Keypad);
+        assert_eq!(outcome, DeviceOutcome { device: Device::Keypad, elapsed_ms: 0, fault: None });
+    }
+
+    #[test]
+    fn test_simulated_session_counts_votes_and_violations() {
+        let report = simulate_session(LatencyProfile::nominal(), 10, &UxRequirements::default());
+        assert_eq!(report.votes_completed, 10);
+        assert!(report.meets_requirements());
+
+        // Sem comprovante o voto vale; sem leitura biométrica, não há voto
+        let no_paper = LatencyProfile::nominal().with_device(Device::Printer, always_failing(2500, 8000));
+        assert_eq!(simulate_session(no_paper, 3, &UxRequirements::default()).votes_completed, 3);
+
+        let no_reader = LatencyProfile::nominal().with_device(Device::FingerprintReader, always_failing(1500, 20_000));
+        let report = simulate_session(no_reader, 3, &UxRequirements::default());
+        assert_eq!(report.votes_completed, 0);
+        assert_eq!(report.violations.len(), 3);
+        assert!(report.violations.iter().all(|v| v.step == SessionStep::Authenticate && v.elapsed_ms > v.limit_ms));
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_fault_rate_outside_unit_interval() {
+        assert_eq!(LatencyProfile::load("flaky_printer").await.unwrap().name, "flaky_printer");
+
+        let mut profile = LatencyProfile::nominal().named("custom");
+        profile.devices.get_mut(&Device::Printer).unwrap().fault_rate = 1.5;
+        let path = std::env::temp_dir().join(format!("fortis-latency-{}.json", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, serde_json::to_vec(&profile).unwrap()).await.unwrap();
+
+        assert!(LatencyProfile::load(path.to_str().unwrap()).await.is_err());
+        assert!(LatencyProfile::load("/nonexistent/profile.json").await.is_err());
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
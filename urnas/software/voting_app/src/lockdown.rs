@@ -0,0 +1,266 @@
+//! Lacre da configuração da urna durante a votação
+//!
+//! Na abertura da sessão o hash da configuração em uso (cédula, limiares
+//! biométricos e endereços de rede) é gravado em um índice NV do TPM. A
+//! partir daí toda operação recalcula o hash e compara com o valor lacrado,
+//! e qualquer tentativa de alterar a configuração é recusada. O lacre só é
+//! removido no encerramento, com autorização assinada pela Justiça
+//! Eleitoral para aquela eleição e aquele lacre.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use std::path::Path;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::Candidate;
+use crate::messages::{UrnaError, codes};
+
+/// Índice NV do TPM reservado para o lacre
+pub const SEAL_NV_INDEX: &str = "0x1500016";
+
+/// Chave pública que autoriza o encerramento da sessão
+pub const TRUSTED_CLOSE_KEY_PATH: &str = "/etc/fortis/keys/session_close.pub";
+
+/// Autorização de encerramento entregue ao mesário na mídia
+pub const CLOSE_AUTHORIZATION_PATH: &str = "/media/fortis/encerramento.json";
+
+/// Conteúdo do índice NV: hash SHA-256 seguido do identificador da eleição
+const HASH_LEN: usize = 32;
+const SEAL_LEN: usize = HASH_LEN + 16;
+
+/// Configuração coberta pelo lacre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrnaConfiguration {
+    pub election_id: Uuid,
+    pub region: Option<String>,
+    /// SHA-256 da lista de candidatos em uso
+    pub ballot_sha256: String,
+    pub min_fingerprint_quality: u8,
+    pub min_liveness_score: f32,
+    pub fingerprint_attempts: u32,
+    /// Backends de sincronização, na ordem de preferência gravada
+    pub backend_endpoints: Vec<String>,
+    pub log_url: String,
+    pub verification_nodes: Vec<String>,
+    pub candidates_api_url: String,
+}
+
+impl UrnaConfiguration {
+    pub fn ballot_hash(candidates: &[Candidate]) -> Result<String> {
+        Ok(format!("{:x}", Sha256::digest(serde_json::to_vec(candidates)?)))
+    }
+
+    pub fn seal_hash(&self) -> Result<[u8; HASH_LEN]> {
+        Ok(Sha256::digest(serde_json::to_vec(self)?).into())
+    }
+}
+
+/// Autorização assinada para remover o lacre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseAuthorization {
+    pub election_id: Uuid,
+    /// Hash do lacre em hexadecimal
+    pub seal_hash: String,
+    pub issued_at: DateTime<Utc>,
+    /// Assinatura Ed25519 (hex) de "election_id|seal_hash|issued_at"
+    pub signature: String,
+}
+
+impl CloseAuthorization {
+    fn signed_message(&self) -> String {
+        format!("{}|{}|{}", self.election_id, self.seal_hash, self.issued_at.to_rfc3339())
+    }
+
+    pub async fn load(path: &Path) -> Result<Self> {
+        let data = tokio::fs::read(path).await
+            .map_err(|e| anyhow!("Close authorization not found at {}: {}", path.display(), e))?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ActiveSeal {
+    election_id: Uuid,
+    hash: [u8; HASH_LEN],
+    sealed_at: DateTime<Utc>,
+}
+
+impl ActiveSeal {
+    fn to_nv(&self) -> [u8; SEAL_LEN] {
+        let mut data = [0u8; SEAL_LEN];
+        data[..HASH_LEN].copy_from_slice(&self.hash);
+        data[HASH_LEN..].copy_from_slice(self.election_id.as_bytes());
+        data
+    }
+}
+
+/// Lacre da configuração no TPM (via tpm2-tools)
+pub struct ConfigLockdown {
+    pub nv_index: String,
+    pub trusted_key_path: String,
+    seal: Mutex<Option<ActiveSeal>>,
+}
+
+impl ConfigLockdown {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            nv_index: SEAL_NV_INDEX.to_string(),
+            trusted_key_path: TRUSTED_CLOSE_KEY_PATH.to_string(),
+            seal: Mutex::new(None),
+        })
+    }
+
+    /// Recupera lacre que sobreviveu a reinício durante a votação
+    ///
+    /// Devolve a eleição da sessão lacrada, que continua lacrada.
+    pub async fn initialize(&self) -> Result<Option<Uuid>> {
+        let Some(data) = self.read_nv().await? else {
+            return Ok(None);
+        };
+
+        let mut hash = [0u8; HASH_LEN];
+        hash.copy_from_slice(&data[..HASH_LEN]);
+        let election_id = Uuid::from_slice(&data[HASH_LEN..])?;
+        log::warn!("Configuration seal found in TPM, resuming sealed session for election {}", election_id);
+
+        *self.seal.lock().await = Some(ActiveSeal { election_id, hash, sealed_at: Utc::now() });
+        Ok(Some(election_id))
+    }
+
+    pub async fn is_sealed(&self) -> bool {
+        self.seal.lock().await.is_some()
+    }
+
+    /// Grava o hash da configuração no TPM na abertura da sessão
+    pub async fn seal(&self, config: &UrnaConfiguration) -> Result<String> {
+        let mut seal = self.seal.lock().await;
+        if seal.is_some() || self.read_nv().await?.is_some() {
+            return Err(UrnaError::new(codes::CONFIG_SEALED, "Configuration is already sealed").into());
+        }
+
+        let active = ActiveSeal {
+            election_id: config.election_id,
+            hash: config.seal_hash()?,
+            sealed_at: Utc::now(),
+        };
+        self.write_nv(&active.to_nv()).await?;
+        let hash = hex::encode(active.hash);
+        *seal = Some(active);
+
+        log::info!("Configuration sealed for election {}", config.election_id);
+        Ok(hash)
+    }
+
+    /// Compara a configuração atual com o lacre do TPM
+    pub async fn verify(&self, config: &UrnaConfiguration) -> Result<()> {
+        let seal = self.seal.lock().await;
+        let Some(active) = seal.as_ref() else {
+            return Err(anyhow!("No voting session is sealed"));
+        };
+
+        if self.read_nv().await? != Some(active.to_nv()) {
+            return Err(UrnaError::new(codes::CONFIG_SEAL_VIOLATION, "TPM seal does not match the sealed session").into());
+        }
+        if config.election_id != active.election_id || config.seal_hash()? != active.hash {
+            return Err(UrnaError::new(codes::CONFIG_SEAL_VIOLATION, "Configuration changed after session open").into());
+        }
+        Ok(())
+    }
+
+    /// Recusa alterações de configuração com a sessão lacrada
+    pub async fn ensure_unsealed(&self, what: &str) -> Result<()> {
+        if self.is_sealed().await {
+            return Err(UrnaError::new(
+                codes::CONFIG_SEALED,
+                &format!("Cannot change {} while the voting session is open", what),
+            ).into());
+        }
+        Ok(())
+    }
+
+    /// Remove o lacre mediante autorização assinada
+    pub async fn unseal(&self, authorization: &CloseAuthorization) -> Result<DateTime<Utc>> {
+        let mut seal = self.seal.lock().await;
+        let Some(active) = seal.as_ref() else {
+            return Err(anyhow!("No voting session is sealed"));
+        };
+
+        if authorization.election_id != active.election_id
+            || authorization.seal_hash != hex::encode(active.hash)
+        {
+            return Err(anyhow!("Close authorization does not match the sealed session"));
+        }
+
+        let public_key = hex::decode(tokio::fs::read_to_string(&self.trusted_key_path).await?.trim())?;
+        let signature = hex::decode(&authorization.signature)?;
+        UnparsedPublicKey::new(&ED25519, &public_key)
+            .verify(authorization.signed_message().as_bytes(), &signature)
+            .map_err(|_| anyhow!("Invalid close authorization signature"))?;
+
+        tpm2(&["tpm2_nvundefine", &self.nv_index, "-C", "o"], None).await?;
+        let sealed_at = active.sealed_at;
+        *seal = None;
+
+        log::info!("Configuration seal removed for election {}", authorization.election_id);
+        Ok(sealed_at)
+    }
+
+    async fn read_nv(&self) -> Result<Option<[u8; SEAL_LEN]>> {
+        // Índice inexistente: nenhuma sessão lacrada
+        let defined = tpm2(&["tpm2_nvreadpublic", &self.nv_index], None).await.is_ok();
+        if !defined {
+            return Ok(None);
+        }
+
+        let data = tpm2(
+            &["tpm2_nvread", &self.nv_index, "-C", "o", "-s", &SEAL_LEN.to_string()],
+            None,
+        ).await?;
+        let data: [u8; SEAL_LEN] = data.try_into()
+            .map_err(|_| anyhow!("Unexpected TPM seal size"))?;
+        Ok(Some(data))
+    }
+
+    async fn write_nv(&self, data: &[u8; SEAL_LEN]) -> Result<()> {
+        tpm2(
+            &["tpm2_nvdefine", &self.nv_index, "-C", "o", "-s", &SEAL_LEN.to_string(), "-a", "ownerread|ownerwrite"],
+            None,
+        ).await?;
+        tpm2(&["tpm2_nvwrite", &self.nv_index, "-C", "o", "-i", "-"], Some(data)).await?;
+        Ok(())
+    }
+}
+
+/// Executa um comando do tpm2-tools e devolve a saída padrão
+pub(crate) async fn tpm2(args: &[&str], input: Option<&[u8]>) -> Result<Vec<u8>> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut child = Command::new(args[0])
+        .args(&args[1..])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to run {}: {}", args[0], e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(input) = input {
+            stdin.write_all(input).await?;
+        }
+    }
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
@@ -6,20 +6,64 @@ use tokio::sync::Mutex;
 use anyhow::Result;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 mod auth;
 mod ui;
+mod accessibility;
+mod attendant;
+mod attestation;
+mod ballot_journal;
+mod i18n;
 mod crypto;
 mod sync;
+mod endpoints;
+mod faults;
 mod audit;
 mod hardware;
+mod latency;
+mod contingency;
+mod decommission;
+mod candidates;
+mod messages;
+mod package;
+mod receipt;
+mod lockdown;
+mod transport;
+mod provisioning;
+mod outbox;
+mod replay;
+mod supervisor;
+mod storage;
+mod terminal;
+mod telemetry;
 
 use auth::BiometricAuth;
 use ui::VotingInterface;
 use crypto::VoteEncryption;
-use sync::BlockchainSync;
+use sync::{BlockchainSync, InclusionCheck};
 use audit::AuditLogger;
+use attendant::{AttendantOverride, SessionTimeoutPolicy};
+use attestation::{AttestationPolicy, BootAttestation};
+use ballot_journal::{BallotJournal, BallotPhase, RecoveredBallot};
+use candidates::CandidateSync;
+use faults::FaultLog;
+use messages::{UrnaError, codes};
 use hardware::{HardwareManager, UrnaHardware};
+use hardware::feedback::{FeedbackOverride, FeedbackReport};
+use lockdown::{CloseAuthorization, ConfigLockdown, UrnaConfiguration};
+use outbox::{BackoffPolicy, VoteOutbox};
+use decommission::{DecommissionReport, FinalProofBundle, ProofLedger, SignedDecommissionReport, SignedProofBundle, VoteProof};
+use replay::{InputSource, SessionStep, TraceEvent, TraceRecorder};
+use storage::{StorageGuard, StoragePolicy, StorageReport, StorageStatus};
+
+pub use fortis_domain::{Candidate, EncryptedVote, SignedVoteBatch, Vote, VoteBatch, VoteSyncStatus};
+use fortis_domain::i18n::keys;
+use fortis_domain::{contest_order, tally_key, validate_choices, AttestationVerdict, ChoiceKind, ContestChoice, RegionScope, SectionRef};
+use fortis_domain::tamper::TamperEvent;
+use fortis_domain::update::UpdateComponent;
+use package::BallotPage;
 
 #[derive(Debug, Clone)]
 pub struct VotingApp {
@@ -29,6 +73,21 @@ pub struct VotingApp {
     pub crypto: Arc<VoteEncryption>,
     pub sync: Arc<BlockchainSync>,
     pub audit: Arc<AuditLogger>,
+    pub candidates: Arc<CandidateSync>,
+    pub lockdown: Arc<ConfigLockdown>,
+    pub outbox: Arc<VoteOutbox>,
+    /// Provas de inclusão dos votos confirmados, para o pacote final
+    pub proofs: Arc<ProofLedger>,
+    pub trace: Arc<TraceRecorder>,
+    pub storage: Arc<StorageGuard>,
+    /// Tempo limite do atendimento e PIN do mesário para liberar a urna
+    pub attendant: Arc<AttendantOverride>,
+    /// Fase do atendimento em curso, gravada para sobreviver a uma queda de energia
+    pub journal: Arc<BallotJournal>,
+    /// Citação dos PCRs do boot exigida na abertura da sessão
+    pub attestation: Arc<BootAttestation>,
+    /// Falhas por código, levadas no heartbeat
+    pub faults: Arc<FaultLog>,
     pub state: Arc<Mutex<AppState>>,
 }
 
@@ -39,7 +98,32 @@ pub struct AppState {
     pub is_voting: bool,
     pub is_online: bool,
     pub last_sync: Option<DateTime<Utc>>,
-    pub pending_votes: Vec<Uuid>,
+    pub attendance_count: u64,
+    pub votes_by_candidate: BTreeMap<String, u64>,
+    pub attended_voter_hashes: Vec<String>,
+    pub vote_sequence: u64,
+    pub current_region: Option<String>,
+    /// Seção da urna, vinda do pacote de provisionamento
+    pub current_section: Option<SectionRef>,
+    pub candidate_list: Vec<Candidate>,
+    /// Cargos da cédula na ordem de votação, carregados com os candidatos
+    pub ballot_layout: Vec<BallotPage>,
+    pub vote_receipts: HashMap<Uuid, receipt::ReceiptPayload>,
+    /// Códigos de votos sincronizados que ainda não entraram em um lote
+    pub unbatched_codes: Vec<String>,
+    /// Último autoteste de som e tela reprovado, aguardando o mesário
+    pub failed_feedback_report: Option<FeedbackReport>,
+    /// Liberação do mesário, consumida na próxima abertura
+    pub feedback_override: Option<FeedbackOverride>,
+    /// Última verificação de espaço, mostrada ao mesário se houver pouca folga
+    pub storage_report: Option<StorageReport>,
+}
+
+/// Autoteste aceito na abertura da sessão
+#[derive(Debug, Clone)]
+struct FeedbackOutcome {
+    report_id: Uuid,
+    overridden_by: Option<FeedbackOverride>,
 }
 
 impl VotingApp {
@@ -50,6 +134,19 @@ impl VotingApp {
         let crypto = Arc::new(VoteEncryption::new()?);
         let sync = Arc::new(BlockchainSync::new()?);
         let audit = Arc::new(AuditLogger::new()?);
+        let candidates = Arc::new(CandidateSync::new()?);
+        let lockdown = Arc::new(ConfigLockdown::new()?);
+        let outbox = Arc::new(VoteOutbox::new(outbox::OUTBOX_DIR, BackoffPolicy::default()));
+        let proofs = Arc::new(ProofLedger::new(decommission::PROOF_LEDGER_DIR));
+        let trace = Arc::new(TraceRecorder::new());
+        let storage = Arc::new(StorageGuard::new(StoragePolicy::load(Path::new(storage::STORAGE_POLICY_PATH))));
+        let attendant = Arc::new(AttendantOverride::new(
+            SessionTimeoutPolicy::load(Path::new(attendant::SESSION_TIMEOUT_POLICY_PATH))
+        ));
+        let journal = Arc::new(BallotJournal::new(ballot_journal::BALLOT_JOURNAL_PATH));
+        let attestation = Arc::new(BootAttestation::new(
+            AttestationPolicy::load(Path::new(attestation::ATTESTATION_POLICY_PATH))
+        ));
         
         let state = Arc::new(Mutex::new(AppState {
             current_election: None,
@@ -57,7 +154,19 @@ impl VotingApp {
             is_voting: false,
             is_online: false,
             last_sync: None,
-            pending_votes: Vec::new(),
+            attendance_count: 0,
+            votes_by_candidate: BTreeMap::new(),
+            attended_voter_hashes: Vec::new(),
+            vote_sequence: 0,
+            current_region: None,
+            current_section: None,
+            candidate_list: Vec::new(),
+            ballot_layout: Vec::new(),
+            vote_receipts: HashMap::new(),
+            unbatched_codes: Vec::new(),
+            failed_feedback_report: None,
+            feedback_override: None,
+            storage_report: None,
         }));
 
         Ok(Self {
@@ -67,6 +176,16 @@ impl VotingApp {
             crypto,
             sync,
             audit,
+            candidates,
+            lockdown,
+            outbox,
+            proofs,
+            trace,
+            storage,
+            attendant,
+            journal,
+            attestation,
+            faults: Arc::new(FaultLog::new()),
             state,
         })
     }
@@ -88,10 +207,26 @@ impl VotingApp {
         
         // Inicializar sincronização
         self.sync.initialize().await?;
+
+        // Votos que ficaram na fila de saída antes de um reinício
+        self.outbox.initialize().await?;
         
         // Inicializar auditoria
         self.audit.initialize().await?;
 
+        // Sessão lacrada antes de um reinício continua lacrada
+        if let Some(election_id) = self.lockdown.initialize().await? {
+            let mut state = self.state.lock().await;
+            state.current_election = Some(election_id);
+            state.is_voting = true;
+        }
+
+        // Mídia de preparação com pacote cifrado para esta urna
+        let bundle_path = Path::new(provisioning::PROVISIONING_MEDIA_PATH);
+        if !self.lockdown.is_sealed().await && bundle_path.exists() {
+            self.apply_provisioning_bundle(bundle_path).await?;
+        }
+
         // Verificar conectividade
         self.check_connectivity().await?;
 
@@ -105,28 +240,50 @@ impl VotingApp {
     pub async fn start_voting_session(&self, election_id: Uuid) -> Result<()> {
         log::info!("Starting voting session for election: {}", election_id);
 
+        // Rastro da sessão para reprodução forense
+        self.trace.start(&self.sync.urna_id().await.unwrap_or_default(), election_id).await;
+
         // Verificar se a urna está pronta
-        if !self.hardware.is_ready().await? {
+        let hardware_ready = self.hardware.is_ready().await?;
+        self.trace.record(TraceEvent::Hardware { component: "urna".to_string(), ready: hardware_ready }).await;
+        if !hardware_ready {
             return Err(anyhow::anyhow!("Hardware not ready"));
         }
 
+        // Som de confirmação e tela conforme a legislação, antes de abrir
+        let feedback = self.check_feedback_compliance().await?;
+
+        // Espaço para os votos e logs da seção inteira
+        self.check_storage(Some(election_id), 0).await?;
+
         // Verificar conectividade
         if !self.is_online().await {
             log::warn!("Urna is offline, will sync when connection is restored");
         }
 
+        // Medições do boot conferidas pelo backend antes de abrir
+        self.attest_boot(election_id).await?;
+
         // Atualizar estado
         {
             let mut state = self.state.lock().await;
             state.current_election = Some(election_id);
+            state.candidate_list.clear();
             state.is_voting = true;
         }
 
+        // Lacrar configuração no TPM antes do primeiro eleitor
+        let config = self.current_configuration().await?;
+        let config_seal = self.lockdown.seal(&config).await?;
+
         // Log de início da sessão
         self.audit.log_event(
             "VotingSessionStarted",
             &serde_json::json!({
                 "election_id": election_id,
+                "config_seal": config_seal,
+                "feedback_report_id": feedback.report_id,
+                "feedback_override": feedback.overridden_by,
                 "timestamp": Utc::now()
             })
         ).await?;
@@ -137,33 +294,162 @@ impl VotingApp {
         Ok(())
     }
 
+    /// Autoteste de som e tela; falha bloqueia a abertura sem liberação do mesário
+    async fn check_feedback_compliance(&self) -> Result<FeedbackOutcome> {
+        let report = self.hardware.feedback.run().await;
+        self.trace.record(TraceEvent::Hardware { component: "feedback".to_string(), ready: report.passed() }).await;
+        self.audit.log_event("FeedbackSelfTest", &serde_json::json!({
+            "report": report,
+            "timestamp": Utc::now()
+        })).await?;
+
+        let mut state = self.state.lock().await;
+        let granted = state.feedback_override.take();
+        if report.passed() {
+            state.failed_feedback_report = None;
+            return Ok(FeedbackOutcome { report_id: report.report_id, overridden_by: None });
+        }
+
+        match granted {
+            Some(granted) if granted.covers(&report) => {
+                log::warn!(
+                    "Opening session with failed feedback checks {:?}, overridden by mesário {}",
+                    report.failed_checks(), granted.mesario_id
+                );
+                state.failed_feedback_report = None;
+                Ok(FeedbackOutcome { report_id: report.report_id, overridden_by: Some(granted) })
+            }
+            _ => {
+                let detail = format!("Failed checks {:?}", report.failed_checks());
+                state.failed_feedback_report = Some(report);
+                Err(UrnaError::new(codes::FEEDBACK_CHECK_FAILED, &detail).into())
+            }
+        }
+    }
+
+    /// Atestação remota do boot; reprovação impede a abertura da sessão
+    async fn attest_boot(&self, election_id: Uuid) -> Result<()> {
+        let policy = &self.attestation.policy;
+        if !policy.enabled {
+            return Ok(());
+        }
+        if !self.is_online().await {
+            if !policy.allow_offline_open {
+                return Err(UrnaError::new(codes::ATTESTATION_FAILED, "Backend unreachable for boot attestation").into());
+            }
+            log::warn!("Boot attestation deferred: urna is offline");
+            self.audit.log_event("BootAttestationDeferred", &serde_json::json!({
+                "election_id": election_id,
+                "timestamp": Utc::now()
+            })).await?;
+            return Ok(());
+        }
+
+        let challenge = self.sync.request_attestation_challenge().await?;
+        let report = self.attestation.report(
+            &challenge,
+            election_id,
+            self.hardware.updates.installed_version(UpdateComponent::Firmware).await,
+            self.hardware.updates.installed_version(UpdateComponent::Application).await,
+        ).await?;
+        let result = self.sync.report_attestation(&report).await?;
+        self.audit.log_event("BootAttestationVerified", &serde_json::json!({
+            "result": result,
+            "pcrs": report.pcrs,
+            "secure_boot_enabled": report.secure_boot_enabled,
+            "timestamp": Utc::now()
+        })).await?;
+
+        if result.verdict != AttestationVerdict::Trusted {
+            let reason = result.reason.unwrap_or_else(|| "rejected by the backend".to_string());
+            log::error!("Boot attestation rejected: {}", reason);
+            return Err(UrnaError::new(codes::ATTESTATION_FAILED, &reason).into());
+        }
+        Ok(())
+    }
+
+    /// Confere o espaço da seção; falta de espaço impede a preparação e a abertura
+    async fn check_storage(&self, keep_election: Option<Uuid>, incoming_bytes: u64) -> Result<()> {
+        let report = self.storage.check(keep_election, incoming_bytes).await?;
+        self.audit.log_event("StorageCapacityChecked", &serde_json::json!({
+            "report": report,
+            "timestamp": Utc::now()
+        })).await?;
+
+        let status = report.status;
+        if status != StorageStatus::Sufficient {
+            log::warn!("Storage {:?}: {}", status, report.summary());
+        }
+        let summary = report.summary();
+        self.state.lock().await.storage_report = Some(report);
+        if status == StorageStatus::Insufficient {
+            return Err(UrnaError::new(codes::STORAGE_INSUFFICIENT, &summary).into());
+        }
+        Ok(())
+    }
+
+    /// Liberação do mesário para as falhas do último autoteste reprovado
+    pub async fn override_feedback_check(&self, mesario_id: &str, reason: &str) -> Result<()> {
+        if reason.trim().is_empty() {
+            return Err(anyhow::anyhow!("Feedback check override requires a reason"));
+        }
+        let granted = {
+            let mut state = self.state.lock().await;
+            let report = state.failed_feedback_report.as_ref()
+                .ok_or_else(|| anyhow::anyhow!("No failed feedback self-test to override"))?;
+            let granted = FeedbackOverride::new(report, mesario_id, reason);
+            state.feedback_override = Some(granted.clone());
+            granted
+        };
+
+        log::warn!("Feedback checks {:?} overridden by mesário {}", granted.failed_checks, mesario_id);
+        self.audit.log_event("FeedbackCheckOverridden", &serde_json::json!({
+            "override": granted,
+            "timestamp": Utc::now()
+        })).await?;
+        Ok(())
+    }
+
     pub async fn authenticate_voter(&self) -> Result<Uuid> {
         log::info!("Starting voter authentication");
+        self.verify_config_seal().await?;
 
         // Mostrar tela de autenticação
         self.ui.show_authentication_screen().await?;
 
-        // Capturar dados biométricos
-        let biometric_data = self.hardware.capture_biometric_data().await?;
-
         // Verificar certificado digital (opcional)
         let certificate_data = self.hardware.read_certificate().await?;
 
-        // Autenticar eleitor
-        let voter_id = self.auth.authenticate_voter(
-            &biometric_data,
+        // Autenticar eleitor pela digital, com reconhecimento facial como alternativa
+        let authenticated: Result<Uuid> = self.auth.authenticate_with_fallback(
+            &self.hardware,
+            &self.audit,
             certificate_data.as_ref()
-        ).await?;
+        ).await.map_err(|e| match e.downcast::<UrnaError>() {
+            // Falhas do cartão mantêm o próprio código
+            Ok(urna_error) => urna_error.into(),
+            Err(e) => UrnaError::new(codes::BIOMETRIC_NOT_RECOGNIZED, &e.to_string()).into(),
+        });
+        self.trace.input(InputSource::Biometric, &authenticated).await;
+        let voter_id = authenticated?;
 
         // Verificar elegibilidade
-        if !self.auth.is_voter_eligible(voter_id, self.get_current_election().await?).await? {
-            return Err(anyhow::anyhow!("Voter not eligible for this election"));
-        }
+        let eligible: Result<()> = match self.auth.is_voter_eligible(voter_id, self.get_current_election().await?).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(UrnaError::new(codes::VOTER_NOT_ELIGIBLE, "Voter not eligible for this election").into()),
+            Err(e) => Err(e),
+        };
+        self.trace.input(InputSource::Eligibility, &eligible).await;
+        eligible?;
 
         // Verificar se já votou
-        if self.auth.has_voter_voted(voter_id, self.get_current_election().await?).await? {
-            return Err(anyhow::anyhow!("Voter has already voted"));
-        }
+        let not_voted: Result<()> = match self.auth.has_voter_voted(voter_id, self.get_current_election().await?).await {
+            Ok(false) => Ok(()),
+            Ok(true) => Err(UrnaError::new(codes::VOTER_ALREADY_VOTED, "Voter has already voted").into()),
+            Err(e) => Err(e),
+        };
+        self.trace.input(InputSource::VoterRegistry, &not_voted).await;
+        not_voted?;
 
         // Atualizar estado
         {
@@ -185,39 +471,69 @@ impl VotingApp {
         Ok(voter_id)
     }
 
-    pub async fn show_candidate_selection(&self) -> Result<Uuid> {
+    /// Uma escolha confirmada por cargo, na ordem da cédula
+    pub async fn show_candidate_selection(&self) -> Result<Vec<ContestChoice>> {
         log::info!("Showing candidate selection");
 
-        // Obter lista de candidatos
         let candidates = self.get_candidates().await?;
-
-        // Mostrar interface de seleção
-        let candidate_id = self.ui.show_candidate_selection(candidates).await?;
-
-        // Confirmar seleção
-        let confirmed = self.ui.confirm_vote_selection(candidate_id).await?;
-        if !confirmed {
-            return Err(anyhow::anyhow!("Vote selection cancelled"));
+        let layout = self.state.lock().await.ballot_layout.clone();
+
+        let mut choices = Vec::with_capacity(layout.len());
+        for page in &layout {
+            let contest_candidates: Vec<Candidate> = candidates.iter()
+                .filter(|c| c.position == page.position)
+                .cloned()
+                .collect();
+
+            // CORRIGE volta ao início do mesmo cargo
+            let selection = loop {
+                let selection = self.ui.show_contest_selection(page, &contest_candidates).await?;
+                if self.ui.confirm_contest_selection(page, &selection).await? {
+                    break selection;
+                }
+            };
+            let choice = selection.choice(page.position);
+            log::info!("{} selected: {:?}", page.title, choice.kind);
+            choices.push(choice);
         }
 
-        log::info!("Candidate selected: {}", candidate_id);
-        Ok(candidate_id)
+        let contests: Vec<_> = layout.iter().map(|page| page.position).collect();
+        validate_choices(&contests, &choices, &candidates)?;
+        Ok(choices)
     }
 
-    pub async fn cast_vote(&self, candidate_id: Uuid) -> Result<Uuid> {
-        log::info!("Casting vote for candidate: {}", candidate_id);
+    #[tracing::instrument(name = "urna.cast_vote", skip_all, fields(vote_id = tracing::field::Empty))]
+    pub async fn cast_vote(&self, choices: Vec<ContestChoice>) -> Result<Uuid> {
+        self.verify_config_seal().await?;
+        log::info!("Casting vote for {} contests", choices.len());
+        let candidate_id = choices.first()
+            .map(|choice| choice.candidate_id.unwrap_or(Uuid::nil()))
+            .ok_or_else(|| anyhow::anyhow!("Ballot has no choices"))?;
 
         let election_id = self.get_current_election().await?;
         let voter_id = self.get_current_voter().await?;
+        let voter_hash = contingency::voter_hash(&voter_id);
+
+        // Eleitor já atendido (inclusive antes de substituição da urna)
+        {
+            let state = self.state.lock().await;
+            if state.attended_voter_hashes.contains(&voter_hash) {
+                return Err(UrnaError::new(codes::VOTER_ALREADY_VOTED, "Voter has already voted in this section").into());
+            }
+        }
 
         // Criar voto
         let vote = Vote {
             id: Uuid::new_v4(),
             election_id,
             voter_id,
-            candidate_id,
+            choices: choices.clone(),
             timestamp: Utc::now(),
         };
+        tracing::Span::current().record("vote_id", tracing::field::display(vote.id));
+
+        // O id do voto vai para o diário antes da fila de saída
+        self.journal.committing(vote.id).await?;
 
         // Criptografar voto
         let encrypted_vote = self.crypto.encrypt_vote(&vote).await?;
@@ -233,36 +549,55 @@ impl VotingApp {
             id: vote.id,
             election_id: vote.election_id,
             voter_id: vote.voter_id,
-            candidate_id: vote.candidate_id,
+            candidate_id,
+            choices: vote.choices.clone(),
             encrypted_data: encrypted_vote,
             zk_proof,
             signature,
             timestamp: vote.timestamp,
+            section: self.state.lock().await.current_section.clone(),
         };
 
         // Registrar voto localmente
         self.store_vote_locally(&final_vote).await?;
+        if let Err(e) = self.journal.recorded(vote.id).await {
+            // O voto já está na fila; na recuperação a fila prevalece sobre o diário
+            log::error!("Failed to journal recorded vote {}: {}", vote.id, e);
+        }
+
+        // Código de rastreamento do comprovante, sem a escolha do eleitor
+        let receipt_payload = receipt::ReceiptPayload::new(
+            vote.id,
+            election_id,
+            &final_vote.ballot_hash(),
+        );
+        self.state.lock().await.vote_receipts.insert(vote.id, receipt_payload);
 
         // Sincronizar com blockchain (se online)
         if self.is_online().await {
             match self.sync.sync_vote(&final_vote).await {
                 Ok(blockchain_hash) => {
                     log::info!("Vote synced to blockchain: {}", blockchain_hash);
-                    self.update_vote_status(vote.id, VoteStatus::Synced).await?;
+                    self.update_vote_status(vote.id, VoteSyncStatus::Synced).await?;
                 }
                 Err(e) => {
                     log::warn!("Failed to sync vote to blockchain: {}", e);
-                    self.update_vote_status(vote.id, VoteStatus::Pending).await?;
+                    self.update_vote_status(vote.id, VoteSyncStatus::Pending).await?;
                 }
             }
         } else {
-            self.update_vote_status(vote.id, VoteStatus::Pending).await?;
+            self.update_vote_status(vote.id, VoteSyncStatus::Pending).await?;
         }
 
-        // Adicionar à fila de sincronização
+        // O voto já está na fila de saída (store_vote_locally)
         {
             let mut state = self.state.lock().await;
-            state.pending_votes.push(vote.id);
+            state.attendance_count += 1;
+            for choice in &choices {
+                *state.votes_by_candidate.entry(tally_key(choice)).or_insert(0) += 1;
+            }
+            state.attended_voter_hashes.push(voter_hash);
+            state.vote_sequence += 1;
         }
 
         // Log de voto
@@ -272,7 +607,7 @@ impl VotingApp {
                 "vote_id": vote.id,
                 "election_id": election_id,
                 "voter_id": voter_id,
-                "candidate_id": candidate_id,
+                "choices": choices,
                 "timestamp": Utc::now()
             })
         ).await?;
@@ -283,24 +618,48 @@ impl VotingApp {
 
     pub async fn print_receipt(&self, vote_id: Uuid) -> Result<()> {
         log::info!("Printing receipt for vote: {}", vote_id);
+        self.verify_config_seal().await?;
 
         // Obter dados do voto
         let vote = self.get_vote(vote_id).await?;
-        let candidate = self.get_candidate(vote.candidate_id).await?;
+        let layout = self.state.lock().await.ballot_layout.clone();
+        let mut choices = Vec::with_capacity(vote.choices.len());
+        for choice in &vote.choices {
+            let title = layout.iter()
+                .find(|page| page.position == choice.position)
+                .map_or_else(|| choice.position.title().to_string(), |page| page.title.clone());
+            let (candidate_number, candidate_name) = match (choice.kind, choice.candidate_id) {
+                (ChoiceKind::Candidate, Some(candidate_id)) => {
+                    let candidate = self.get_candidate(candidate_id).await?;
+                    (Some(candidate.number), candidate.name)
+                }
+                (ChoiceKind::Blank, _) => (None, self.ui.i18n.text(keys::CONTEST_BLANK)),
+                _ => (None, self.ui.i18n.text(keys::CONTEST_NULL)),
+            };
+            choices.push(ReceiptChoice {
+                title,
+                candidate_number,
+                candidate_name,
+            });
+        }
+        let payload = self.receipt_payload(vote_id).await?;
 
         // Criar comprovante
         let receipt = VoteReceipt {
             vote_id,
             election_id: vote.election_id,
-            candidate_number: candidate.number,
-            candidate_name: candidate.name,
+            choices,
             timestamp: vote.timestamp,
-            qr_code: self.generate_qr_code(vote_id).await?,
+            tracking_code: payload.tracking_code.clone(),
+            qr_code: receipt::render_qr(&payload)?,
+            qr_payload: serde_json::to_string(&payload)?,
             blockchain_hash: self.get_vote_blockchain_hash(vote_id).await?,
         };
 
         // Imprimir comprovante
-        self.hardware.print_receipt(&receipt).await?;
+        let printed = self.hardware.print_receipt(&receipt, &self.ui.i18n).await;
+        self.trace.input(InputSource::Printer, &printed).await;
+        printed?;
 
         // Log de impressão
         self.audit.log_event(
@@ -315,16 +674,20 @@ impl VotingApp {
         Ok(())
     }
 
-    pub async fn end_voting_session(&self) -> Result<()> {
+    pub async fn end_voting_session(&self, authorization: &CloseAuthorization) -> Result<()> {
         log::info!("Ending voting session");
 
         // Sincronizar votos pendentes
         self.sync_pending_votes().await?;
 
+        // Remover lacre somente com autorização de encerramento
+        let sealed_at = self.lockdown.unseal(authorization).await?;
+
         // Atualizar estado
         {
             let mut state = self.state.lock().await;
             state.current_election = None;
+            state.candidate_list.clear();
             state.current_voter = None;
             state.is_voting = false;
         }
@@ -333,6 +696,9 @@ impl VotingApp {
         self.audit.log_event(
             "VotingSessionEnded",
             &serde_json::json!({
+                "election_id": authorization.election_id,
+                "config_seal": authorization.seal_hash,
+                "sealed_at": sealed_at,
                 "timestamp": Utc::now()
             })
         ).await?;
@@ -341,6 +707,314 @@ impl VotingApp {
         Ok(())
     }
 
+    /// Atendimento interrompido por um reinício: o voto gravado vale, o resto é anulado
+    pub async fn recover_interrupted_ballot(&self) -> Result<Option<RecoveredBallot>> {
+        let Some(entry) = self.journal.interrupted().await? else {
+            return Ok(None);
+        };
+        // Anulação já registrada antes de outro reinício
+        if entry.phase == BallotPhase::Voided {
+            return Ok(Some(RecoveredBallot::Voided(entry)));
+        }
+
+        // A fila, e não o diário, diz se o voto saiu do atendimento
+        let recorded = entry.vote_recorded(&self.outbox).await;
+
+        if recorded {
+            log::warn!("Interrupted ballot session {} had its vote recorded", entry.session_id);
+            if let Some(voter_hash) = &entry.voter_hash {
+                let mut state = self.state.lock().await;
+                if !state.attended_voter_hashes.contains(voter_hash) {
+                    state.attended_voter_hashes.push(voter_hash.clone());
+                }
+            }
+            self.audit.log_event("InterruptedBallotRecorded", &serde_json::json!({
+                "session_id": entry.session_id,
+                "election_id": entry.election_id,
+                "sequence": entry.sequence,
+                "vote_id": entry.vote_id,
+                "phase": entry.phase,
+                "timestamp": Utc::now()
+            })).await?;
+            return Ok(Some(RecoveredBallot::Recorded(entry)));
+        }
+
+        // Nada do atendimento chegou à fila de saída; só resta o estado em memória
+        log::warn!("Interrupted ballot session {} voided at phase {:?}", entry.session_id, entry.phase);
+        {
+            let mut state = self.state.lock().await;
+            state.current_voter = None;
+            if let Some(vote_id) = entry.vote_id {
+                state.vote_receipts.remove(&vote_id);
+            }
+        }
+        self.audit.log_event("InterruptedBallotVoided", &serde_json::json!({
+            "session_id": entry.session_id,
+            "election_id": entry.election_id,
+            "sequence": entry.sequence,
+            "phase": entry.phase,
+            "started_at": entry.started_at,
+            "interrupted_at": entry.updated_at,
+            "timestamp": Utc::now()
+        })).await?;
+        let entry = self.journal.void(entry).await?;
+        Ok(Some(RecoveredBallot::Voided(entry)))
+    }
+
+    /// Exporta o estado lacrado para a mídia antes da troca da urna
+    pub async fn export_contingency_state(
+        &self,
+        urna_id: &str,
+        zone: &str,
+        section: &str,
+        failure_reason: &str,
+        media_path: &Path,
+    ) -> Result<()> {
+        log::info!("Exporting sealed state for contingency replacement");
+
+        let snapshot = {
+            let state = self.state.lock().await;
+            contingency::UrnaStateSnapshot {
+                failed_urna_id: urna_id.to_string(),
+                election_id: state.current_election
+                    .ok_or_else(|| anyhow::anyhow!("No active election"))?
+                    .to_string(),
+                zone: zone.to_string(),
+                section: section.to_string(),
+                attendance_count: state.attendance_count,
+                votes_by_candidate: state.votes_by_candidate.clone(),
+                attended_voter_hashes: state.attended_voter_hashes.clone(),
+                last_vote_sequence: state.vote_sequence,
+                failure_reason: failure_reason.to_string(),
+                sealed_at: Utc::now(),
+            }
+        };
+
+        let sealed = contingency::export_sealed_state(&self.crypto, snapshot, media_path).await?;
+
+        self.audit.log_event(
+            "ContingencyStateExported",
+            &serde_json::json!({
+                "snapshot_hash": sealed.snapshot_hash,
+                "attendance_count": sealed.snapshot.attendance_count,
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        log::info!("Sealed state exported: {}", sealed.snapshot_hash);
+        Ok(())
+    }
+
+    /// Ativa esta urna como substituta, continuando a contagem do estado lacrado
+    pub async fn activate_as_replacement(&self, media_path: &Path, mesario_ids: &[String]) -> Result<()> {
+        log::info!("Activating urna as contingency replacement");
+
+        let sealed = contingency::import_sealed_state(media_path, mesario_ids).await?;
+        let snapshot = &sealed.snapshot;
+        let election_id = Uuid::parse_str(&snapshot.election_id)?;
+
+        {
+            let mut state = self.state.lock().await;
+            if state.attendance_count > 0 {
+                return Err(anyhow::anyhow!("Replacement urna already has recorded votes"));
+            }
+            state.current_election = Some(election_id);
+            state.attendance_count = snapshot.attendance_count;
+            state.votes_by_candidate = snapshot.votes_by_candidate.clone();
+            state.attended_voter_hashes = snapshot.attended_voter_hashes.clone();
+            state.vote_sequence = snapshot.last_vote_sequence;
+        }
+
+        self.audit.log_event(
+            "ContingencyStateImported",
+            &serde_json::json!({
+                "failed_urna_id": snapshot.failed_urna_id,
+                "snapshot_hash": sealed.snapshot_hash,
+                "attendance_count": snapshot.attendance_count,
+                "approved_by": mesario_ids,
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        log::info!("Contingency state imported from urna {}", snapshot.failed_urna_id);
+        Ok(())
+    }
+
+    /// Descomissiona a urna ao fim do ciclo
+    ///
+    /// Exige a fila vazia e todos os votos com prova de inclusão conferida.
+    /// O pacote final de provas é assinado, gravado e relido da mídia antes
+    /// de qualquer apagamento; o relatório assinado lista o que foi apagado.
+    pub async fn decommission(&self, media_path: &Path) -> Result<SignedDecommissionReport> {
+        log::info!("Decommissioning urna");
+        self.lockdown.ensure_unsealed("decommission").await?;
+
+        // Última tentativa de esvaziar a fila antes da conferência
+        if let Err(e) = self.sync_pending_votes().await {
+            log::warn!("Final vote sync before decommission failed: {}", e);
+        }
+        let blockers = self.decommission_blockers().await?;
+        if !blockers.is_empty() {
+            return Err(anyhow::anyhow!("Urna cannot be decommissioned: {}", blockers.join("; ")));
+        }
+
+        // Provas conferidas de novo com a chave do log de cada eleição
+        let proofs = self.proofs.load_all().await?;
+        let mut log_keys: HashMap<Uuid, Vec<u8>> = HashMap::new();
+        for proof in &proofs {
+            if !log_keys.contains_key(&proof.election_id) {
+                log_keys.insert(proof.election_id, self.sync.transparency_log_key(proof.election_id).await?);
+            }
+            proof.inclusion.verify(&log_keys[&proof.election_id])
+                .map_err(|e| anyhow::anyhow!("Inclusion proof of vote {} is invalid: {}", proof.vote_id, e))?;
+        }
+
+        // A urna numera os lotes em uma única sequência por ciclo
+        let last_sequence = self.sync.last_batch_sequence().await?;
+        let bundle = FinalProofBundle {
+            urna_id: self.sync.urna_id().await?,
+            batch_sequences: log_keys.keys().map(|election_id| (*election_id, last_sequence)).collect(),
+            proofs,
+            generated_at: Utc::now(),
+        };
+        let signature = self.crypto.sign_vote(&bundle.canonical_bytes()).await?;
+        let signed_bundle = SignedProofBundle { bundle_sha256: bundle.sha256(), bundle, signature };
+        decommission::export_bundle(&signed_bundle, media_path).await?;
+
+        self.audit.log_event(
+            "ProofBundleExported",
+            &serde_json::json!({
+                "bundle_sha256": signed_bundle.bundle_sha256,
+                "vote_count": signed_bundle.bundle.vote_count(),
+                "batch_sequences": signed_bundle.bundle.batch_sequences,
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        // Chave de selagem primeiro: o que restar cifrado para ela fica ilegível
+        let mut erased = Vec::new();
+        decommission::evict_sealing_key().await?;
+        erased.push("tpm_sealing_key".to_string());
+        for (name, dir) in decommission::ERASABLE_DIRS {
+            if decommission::shred_dir(Path::new(dir)).await? {
+                erased.push(name.to_string());
+            }
+        }
+
+        let report = DecommissionReport::new(&signed_bundle, erased);
+        let signature = self.crypto.sign_vote(&report.signed_message()).await?;
+        let signed_report = SignedDecommissionReport { report, signature };
+        tokio::fs::write(
+            media_path.join(decommission::DECOMMISSION_REPORT_FILE),
+            serde_json::to_vec_pretty(&signed_report)?,
+        ).await?;
+        decommission::store_pending_report(&signed_report).await?;
+
+        self.audit.log_event(
+            "UrnaDecommissioned",
+            &serde_json::json!({
+                "bundle_sha256": signed_report.report.bundle_sha256,
+                "erased": signed_report.report.erased,
+                "timestamp": signed_report.report.completed_at
+            })
+        ).await?;
+
+        // Sem rede, o monitoramento reenvia o relatório
+        self.report_decommission().await?;
+        log::info!("Urna decommissioned: {} votes in bundle {}", signed_report.report.vote_count, signed_report.report.bundle_sha256);
+        Ok(signed_report)
+    }
+
+    /// Pendências que impedem o descomissionamento
+    async fn decommission_blockers(&self) -> Result<Vec<String>> {
+        let mut blockers = Vec::new();
+        let depth = self.outbox.depth().await;
+        if depth > 0 {
+            blockers.push(format!("{} votes not acknowledged by the backend", depth));
+        }
+        if !self.sync.resumable_vote_ids().await?.is_empty() {
+            blockers.push("vote upload interrupted".to_string());
+        }
+        if self.sync.pending_vote_batch().await?.is_some() {
+            blockers.push("signed vote batch not acknowledged".to_string());
+        }
+        let unbatched = self.state.lock().await.unbatched_codes.len();
+        if unbatched > 0 {
+            blockers.push(format!("{} synced votes not sealed in a batch", unbatched));
+        }
+        Ok(blockers)
+    }
+
+    /// Envia o relatório pendente; fica gravado enquanto a frota não confirmar
+    async fn report_decommission(&self) -> Result<()> {
+        let Some(signed) = decommission::pending_report().await? else {
+            return Ok(());
+        };
+        match self.sync.report_decommission(&signed).await {
+            Ok(()) => {
+                decommission::clear_pending_report().await?;
+                log::info!("Decommission report delivered to the fleet API");
+            }
+            Err(e) => log::warn!("Decommission report not delivered: {}", e),
+        }
+        Ok(())
+    }
+
+    /// Audita e informa ao backend cada atualização tentada
+    async fn apply_updates(&self, idle: bool) -> Result<()> {
+        let reports = match self.hardware.check_for_updates(&self.sync, idle).await {
+            Ok(reports) => reports,
+            Err(e) => {
+                log::warn!("Update check failed: {}", e);
+                return Ok(());
+            }
+        };
+        for report in reports {
+            self.audit.log_event("UpdateInstallation", &serde_json::to_value(&report)?).await?;
+            if let Err(e) = self.sync.report_update(&report).await {
+                log::warn!("Update report not delivered: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Lê os sensores de violação; os eventos novos vão para a auditoria e para o backend
+    pub async fn check_tamper(&self) -> Result<Vec<TamperEvent>> {
+        let urna_id = self.sync.urna_id().await.unwrap_or_default();
+        let election_id = self.state.lock().await.current_election;
+        let events = self.hardware.tamper.poll(&urna_id, election_id).await?;
+        for event in &events {
+            log::error!("Tamper detected by {}: {}", event.source, event.detail);
+            self.audit.log_event("TamperDetected", &serde_json::to_value(event)?).await?;
+        }
+        if !events.is_empty() {
+            let app = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = app.report_tamper_events().await {
+                    log::warn!("Failed to report tamper events: {}", e);
+                }
+            });
+        }
+        Ok(events)
+    }
+
+    /// Envia os eventos de violação ainda não aceitos pelo backend
+    async fn report_tamper_events(&self) -> Result<()> {
+        let events = self.hardware.tamper.unreported().await;
+        if events.is_empty() {
+            return Ok(());
+        }
+        match self.sync.report_tamper_events(&events).await {
+            Ok(()) => {
+                let event_ids: Vec<Uuid> = events.iter().map(|event| event.event_id).collect();
+                self.hardware.tamper.mark_reported(&event_ids).await?;
+                log::info!("{} tamper events delivered to the backend", event_ids.len());
+            }
+            Err(e) => log::warn!("Tamper events not delivered: {}", e),
+        }
+        Ok(())
+    }
+
     async fn get_current_election(&self) -> Result<Uuid> {
         let state = self.state.lock().await;
         state.current_election.ok_or_else(|| anyhow::anyhow!("No active election"))
@@ -357,6 +1031,7 @@ impl VotingApp {
 
     async fn check_connectivity(&self) -> Result<()> {
         let is_online = self.sync.check_connectivity().await?;
+        self.trace.record(TraceEvent::Connectivity { online: is_online }).await;
         {
             let mut state = self.state.lock().await;
             state.is_online = is_online;
@@ -384,9 +1059,41 @@ impl VotingApp {
 
         // Sincronizar votos pendentes se online
         if self.is_online().await {
-            self.sync_pending_votes().await?;
+            self.traced(SessionStep::SyncVotes, self.sync_pending_votes()).await?;
         }
 
+        // Profundidade da fila de saída
+        let metrics = self.outbox.metrics().await;
+        if metrics.depth > 0 {
+            log::info!(
+                "Vote outbox: {} pending ({} due, {} backing off, max {} attempts)",
+                metrics.depth, metrics.due, metrics.backing_off, metrics.max_attempts
+            );
+        }
+        let stale = metrics.oldest_enqueued_at
+            .is_some_and(|oldest| Utc::now() - oldest > chrono::Duration::seconds(outbox::OUTBOX_STALE_SECONDS));
+        if stale {
+            log::warn!("Vote outbox has votes waiting for more than {}s", outbox::OUTBOX_STALE_SECONDS);
+            self.audit.log_event("VoteOutboxStale", &serde_json::to_value(&metrics)?).await?;
+        }
+
+        // Relatório de descomissionamento ainda não entregue
+        if self.is_online().await {
+            self.report_decommission().await?;
+        }
+
+        // Eventos de violação ainda não entregues
+        if self.is_online().await {
+            self.report_tamper_events().await?;
+        }
+
+        // Atualizações de firmware e aplicação, só com a urna ociosa
+        let idle = {
+            let state = self.state.lock().await;
+            !state.is_voting && state.current_voter.is_none()
+        } && !self.lockdown.is_sealed().await;
+        self.apply_updates(idle).await?;
+
         // Verificar integridade do hardware
         if !self.hardware.is_ready().await? {
             log::warn!("Hardware not ready");
@@ -395,55 +1102,351 @@ impl VotingApp {
         Ok(())
     }
 
+    #[tracing::instrument(name = "urna.sync_pending_votes", skip_all)]
     async fn sync_pending_votes(&self) -> Result<()> {
-        let pending_votes = {
-            let state = self.state.lock().await;
-            state.pending_votes.clone()
-        };
+        // Votos com a espera vencida, mais os do envio interrompido
+        let mut votes = self.outbox.votes(&self.sync.resumable_vote_ids().await?).await;
+        for vote in self.outbox.due(Utc::now()).await {
+            if !votes.iter().any(|v| v.id == vote.id) {
+                votes.push(vote);
+            }
+        }
 
-        for vote_id in pending_votes {
-            match self.sync.sync_vote_by_id(vote_id).await {
-                Ok(_) => {
-                    // Remover da lista de pendentes
-                    let mut state = self.state.lock().await;
-                    state.pending_votes.retain(|&id| id != vote_id);
+        // Votos enviados em partes comprimidas; o envio interrompido é retomado
+        if !votes.is_empty() {
+            let vote_ids: Vec<Uuid> = votes.iter().map(|v| v.id).collect();
+            let trace_contexts = self.outbox.trace_contexts(&vote_ids).await;
+            let synced = self.sync.sync_votes(&votes, &trace_contexts).await;
+            self.trace.input(InputSource::Backend, &synced).await;
+            match synced {
+                Ok(results) => {
+                    self.state.lock().await.last_sync = Some(Utc::now());
+                    // Só sai da fila o voto cuja inclusão no log confere localmente
+                    let mut checks = self.sync.verify_inclusions(&votes, &results).await;
+                    for result in results {
+                        if !result.is_synced() {
+                            let error = result.error.unwrap_or_else(|| "rejected".to_string());
+                            log::warn!("Vote {} rejected by backend: {}", result.vote_id, error);
+                            self.faults.record(codes::SYNC_VOTE_REJECTED, &error).await;
+                            self.outbox.record_failure(&[result.vote_id], &error).await?;
+                            continue;
+                        }
+
+                        match checks.remove(&result.vote_id) {
+                            Some(InclusionCheck::Verified) => {
+                                // A prova conferida entra no pacote final do descomissionamento
+                                let vote = votes.iter().find(|v| v.id == result.vote_id);
+                                if let (Some(vote), Some(inclusion)) = (vote, &result.inclusion) {
+                                    self.proofs.record(&VoteProof {
+                                        vote_id: result.vote_id,
+                                        election_id: vote.election_id,
+                                        tracking_code: result.tracking_code.clone(),
+                                        inclusion: inclusion.clone(),
+                                    }).await?;
+                                }
+                            }
+                            Some(InclusionCheck::Invalid(e)) => {
+                                log::error!("Invalid inclusion proof for vote {}: {}", result.vote_id, e);
+                                self.faults.record(codes::SYNC_INCLUSION_PROOF_INVALID, &e.to_string()).await;
+                                self.audit.log_event(
+                                    "InclusionProofInvalid",
+                                    &serde_json::json!({
+                                        "vote_id": result.vote_id,
+                                        "tracking_code": result.tracking_code,
+                                        "reason": e.to_string(),
+                                        "timestamp": Utc::now()
+                                    })
+                                ).await?;
+                                self.outbox.record_failure(&[result.vote_id], &e.to_string()).await?;
+                                self.update_vote_status(result.vote_id, VoteSyncStatus::Pending).await?;
+                                continue;
+                            }
+                            Some(InclusionCheck::Unverified(reason)) => {
+                                log::warn!("Inclusion of vote {} not verified: {}", result.vote_id, reason);
+                                self.outbox.record_failure(&[result.vote_id], &reason).await?;
+                                self.update_vote_status(result.vote_id, VoteSyncStatus::Pending).await?;
+                                continue;
+                            }
+                            None => {
+                                log::warn!("Vote {} accepted but not part of this sync", result.vote_id);
+                                continue;
+                            }
+                        }
+                        self.outbox.acknowledge(result.vote_id).await?;
+                        self.update_vote_status(result.vote_id, VoteSyncStatus::Synced).await?;
+
+                        let mut state = self.state.lock().await;
+                        let code = result.tracking_code.or_else(|| {
+                            state.vote_receipts.get(&result.vote_id).map(|r| r.tracking_code.clone())
+                        });
+                        if let Some(code) = code {
+                            state.unbatched_codes.push(code);
+                        }
+                    }
                 }
                 Err(e) => {
-                    log::warn!("Failed to sync vote {}: {}", vote_id, e);
+                    log::warn!("Failed to sync pending votes: {}", e);
+                    self.faults.record(codes::SYNC_BACKEND_UNREACHABLE, &e.to_string()).await;
+                    let vote_ids: Vec<Uuid> = votes.iter().map(|v| v.id).collect();
+                    self.outbox.record_failure(&vote_ids, &e.to_string()).await?;
                 }
             }
         }
 
+        // O lote fica gravado e é reenviado na próxima sincronização
+        if let Err(e) = self.upload_vote_batch().await {
+            log::warn!("Failed to upload vote batch: {}", e);
+            self.faults.record(codes::SYNC_BATCH_UPLOAD_FAILED, &e.to_string()).await;
+        }
+
+        Ok(())
+    }
+
+    /// Fecha um lote com os votos sincronizados e envia a sub-raiz assinada
+    async fn upload_vote_batch(&self) -> Result<()> {
+        let signed = match self.sync.pending_vote_batch().await? {
+            Some(signed) => signed,
+            None => {
+                let (election_id, codes) = {
+                    let state = self.state.lock().await;
+                    (state.current_election, state.unbatched_codes.clone())
+                };
+                let Some(election_id) = election_id else {
+                    return Ok(());
+                };
+                if codes.is_empty() {
+                    return Ok(());
+                }
+
+                let sequence = self.sync.last_batch_sequence().await? + 1;
+                let batch = VoteBatch::new(&self.sync.urna_id().await?, election_id, sequence, codes)?;
+                let signature = self.crypto.sign_vote(&batch.signed_message()).await?;
+                let signed = SignedVoteBatch { batch, signature };
+                self.sync.store_pending_batch(&signed).await?;
+
+                let mut state = self.state.lock().await;
+                state.unbatched_codes.retain(|code| !signed.batch.tracking_codes.contains(code));
+                signed
+            }
+        };
+
+        let log_index = self.sync.upload_vote_batch(&signed).await?;
+
+        self.audit.log_event(
+            "VoteBatchSealed",
+            &serde_json::json!({
+                "urna_id": signed.batch.urna_id,
+                "election_id": signed.batch.election_id,
+                "sequence": signed.batch.sequence,
+                "merkle_root": signed.batch.merkle_root,
+                "vote_count": signed.batch.tracking_codes.len(),
+                "log_index": log_index,
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        log::info!("Vote batch {} recorded at log index {}", signed.batch.sequence, log_index);
+        Ok(())
+    }
+
+    /// Exibe ao eleitor a mensagem do catálogo correspondente ao erro
+    /// Executa a etapa registrando início e resultado no rastro da sessão
+    pub async fn traced<T>(
+        &self,
+        step: SessionStep,
+        operation: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        self.trace.record(TraceEvent::StepStarted { step }).await;
+        let result = operation.await;
+        let error_code = result.as_ref().err().map(|e| messages::error_code(e).to_string());
+        self.trace.record(TraceEvent::StepFinished { step, error_code }).await;
+        result
+    }
+
+    pub async fn show_voter_error(&self, error: &anyhow::Error) -> Result<()> {
+        let code = messages::error_code(error);
+        log::warn!("Voter-facing error {}: {}", code, error);
+
+        let message = self.ui.i18n.voter_message(code);
+        self.ui.show_error(&message.message).await
+    }
+
+    /// Prepara a urna antes do dia da eleição com o pacote assinado
+    pub async fn prepare_election(&self, election_id: Uuid, region: &str) -> Result<()> {
+        log::info!("Preparing urna for election {} ({})", election_id, region);
+        self.lockdown.ensure_unsealed("the election package").await?;
+
+        let package = self.sync.download_election_package(election_id, Some(region)).await?;
+        self.check_storage(Some(election_id), 0).await?;
+
+        // Catálogo de mensagens e idiomas passam a valer na próxima inicialização
+        i18n::install(&package).await?;
+
+        self.set_region(region).await?;
+
+        self.audit.log_event(
+            "ElectionPackageInstalled",
+            &serde_json::json!({
+                "election_id": election_id,
+                "package_id": package.package_id,
+                "region": region,
+                "candidates": package.candidates.len(),
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        log::info!("Election package {} installed", package.package_id);
+        Ok(())
+    }
+
+    /// Prepara a urna a partir do pacote de provisionamento cifrado na mídia
+    pub async fn apply_provisioning_bundle(&self, path: &Path) -> Result<()> {
+        self.lockdown.ensure_unsealed("the provisioning bundle").await?;
+
+        let bundle = provisioning::load_bundle(path).await?;
+        let urna_id = self.sync.urna_id().await?;
+        let contents = provisioning::open_bundle(&bundle, &urna_id).await?;
+        self.check_storage(None, bundle.ciphertext.len() as u64).await?;
+
+        // A cédula continua assinada à parte e é verificada com a chave do pacote de eleição
+        let signed: package::SignedElectionPackage = serde_json::from_value(contents.ballot.clone())?;
+        let package = self.sync.install_election_package(None, &signed).await?;
+        i18n::install(&package).await?;
+
+        provisioning::store_contents(&contents, Path::new(provisioning::PROVISIONING_DIR)).await?;
+        if let Some(region) = contents.config.get("region").and_then(|r| r.as_str()) {
+            self.set_region(region).await?;
+        }
+        if let Some(section) = contents.config.get("section").and_then(|s| s.as_str()) {
+            let section = RegionScope::parse(section)?
+                .section_ref()
+                .ok_or_else(|| anyhow::anyhow!("Provisioned section {} is incomplete", section))?;
+            self.state.lock().await.current_section = Some(section);
+        }
+
+        self.audit.log_event(
+            "ProvisioningBundleApplied",
+            &serde_json::json!({
+                "bundle_id": bundle.bundle_id,
+                "election_id": package.election.id,
+                "package_id": package.package_id,
+                "zone": contents.voter_snapshot.zone,
+                "section": contents.voter_snapshot.section,
+                "voters": contents.voter_snapshot.voters.len(),
+                "manifest": contents.manifest,
+                "timestamp": Utc::now()
+            })
+        ).await?;
+
+        log::info!("Provisioning bundle {} applied", bundle.bundle_id);
+        Ok(())
+    }
+
+    /// Define a região da urna (UF ou UF-código do município)
+    pub async fn set_region(&self, region: &str) -> Result<()> {
+        self.lockdown.ensure_unsealed("the urna region").await?;
+        let mut state = self.state.lock().await;
+        state.current_region = Some(region.to_string());
+        state.candidate_list.clear();
+        Ok(())
+    }
+
+    /// Configuração em uso, coberta pelo lacre da sessão
+    async fn current_configuration(&self) -> Result<UrnaConfiguration> {
+        let candidates = self.get_candidates().await?;
+        let (election_id, region) = {
+            let state = self.state.lock().await;
+            (
+                state.current_election.ok_or_else(|| anyhow::anyhow!("No active election"))?,
+                state.current_region.clone(),
+            )
+        };
+        let policy = &self.hardware.biometric_reader.fingerprint.policy;
+
+        Ok(UrnaConfiguration {
+            election_id,
+            region,
+            ballot_sha256: UrnaConfiguration::ballot_hash(&candidates)?,
+            min_fingerprint_quality: policy.min_quality,
+            min_liveness_score: policy.min_liveness_score,
+            fingerprint_attempts: policy.max_attempts,
+            backend_endpoints: self.sync.endpoints.urls(),
+            log_url: self.sync.log_url.clone(),
+            verification_nodes: self.sync.verification_nodes.clone(),
+            candidates_api_url: self.candidates.api_url.clone(),
+        })
+    }
+
+    /// Confere a configuração atual com o lacre antes de cada operação
+    async fn verify_config_seal(&self) -> Result<()> {
+        let config = self.current_configuration().await?;
+        if let Err(e) = self.lockdown.verify(&config).await {
+            log::error!("Configuration seal check failed: {}", e);
+            self.audit.log_event(
+                "ConfigSealViolation",
+                &serde_json::json!({
+                    "election_id": config.election_id,
+                    "error": e.to_string(),
+                    "timestamp": Utc::now()
+                })
+            ).await?;
+            return Err(e);
+        }
         Ok(())
     }
 
     async fn get_candidates(&self) -> Result<Vec<Candidate>> {
-        // Em implementação real, buscaria do banco de dados
-        Ok(vec![
-            Candidate {
-                id: Uuid::new_v4(),
-                name: "João Silva".to_string(),
-                party: "PT".to_string(),
-                number: 13,
-            },
-            Candidate {
-                id: Uuid::new_v4(),
-                name: "Maria Santos".to_string(),
-                party: "PSDB".to_string(),
-                number: 45,
-            },
-        ])
+        let (election_id, region) = {
+            let state = self.state.lock().await;
+            if !state.candidate_list.is_empty() {
+                return Ok(state.candidate_list.clone());
+            }
+            (
+                state.current_election.ok_or_else(|| anyhow::anyhow!("No active election"))?,
+                state.current_region.clone(),
+            )
+        };
+
+        // Pacote de eleição verificado tem prioridade sobre a API
+        let (candidates, layout) = match self.sync.load_election_package(election_id).await {
+            Ok(package) => (package.candidates, Some(package.ballot_layout)),
+            Err(e) => {
+                log::warn!("Election package unavailable, fetching candidates: {}", e);
+                let candidates = self.candidates
+                    .fetch_candidates(election_id, region.as_deref())
+                    .await?;
+                (candidates, None)
+            }
+        };
+        log::info!("Loaded {} candidates for election {}", candidates.len(), election_id);
+
+        let mut state = self.state.lock().await;
+        state.ballot_layout = match layout {
+            Some(mut layout) => {
+                layout.sort_by_key(|page| page.order);
+                layout
+            }
+            None => contest_order(&candidates).into_iter()
+                .enumerate()
+                .map(|(index, position)| BallotPage::for_position(index as u32 + 1, position))
+                .collect(),
+        };
+        state.candidate_list = candidates.clone();
+        Ok(candidates)
     }
 
     async fn get_candidate(&self, candidate_id: Uuid) -> Result<Candidate> {
         let candidates = self.get_candidates().await?;
         candidates.into_iter()
             .find(|c| c.id == candidate_id)
-            .ok_or_else(|| anyhow::anyhow!("Candidate not found"))
+            .ok_or_else(|| UrnaError::new(codes::CANDIDATE_NOT_FOUND, "Candidate not found").into())
     }
 
     async fn store_vote_locally(&self, vote: &EncryptedVote) -> Result<()> {
-        // Em implementação real, salvaria no banco de dados local
+        // Fica na fila de saída até o backend confirmar
+        if !self.outbox.enqueue(vote).await? {
+            log::warn!("Vote {} already in outbox", vote.id);
+        }
         log::info!("Vote stored locally: {}", vote.id);
         Ok(())
     }
@@ -454,12 +1457,12 @@ impl VotingApp {
             id: vote_id,
             election_id: Uuid::new_v4(),
             voter_id: Uuid::new_v4(),
-            candidate_id: Uuid::new_v4(),
+            choices: Vec::new(),
             timestamp: Utc::now(),
         })
     }
 
-    async fn update_vote_status(&self, vote_id: Uuid, status: VoteStatus) -> Result<()> {
+    async fn update_vote_status(&self, vote_id: Uuid, status: VoteSyncStatus) -> Result<()> {
         // Em implementação real, atualizaria no banco de dados
         log::info!("Vote {} status updated to {:?}", vote_id, status);
         Ok(())
@@ -470,96 +1473,94 @@ impl VotingApp {
         Ok(Some("0x1234567890abcdef".to_string()))
     }
 
-    async fn generate_qr_code(&self, vote_id: Uuid) -> Result<String> {
-        // Em implementação real, geraria QR code real
-        Ok(format!("QR_CODE_{}", vote_id))
+    /// Conteúdo do QR code, com dados de inclusão quando já sincronizado
+    async fn receipt_payload(&self, vote_id: Uuid) -> Result<receipt::ReceiptPayload> {
+        let mut payload = self.state.lock().await
+            .vote_receipts
+            .get(&vote_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No receipt data for vote {}", vote_id))?;
+
+        if payload.inclusion.is_none() && self.is_online().await {
+            match self.sync.fetch_vote_inclusion(&payload.tracking_code).await {
+                Ok(inclusion) => payload.inclusion = inclusion,
+                Err(e) => log::warn!("Inclusion data unavailable for vote {}: {}", vote_id, e),
+            }
+            self.state.lock().await.vote_receipts.insert(vote_id, payload.clone());
+        }
+        Ok(payload)
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Vote {
-    pub id: Uuid,
-    pub election_id: Uuid,
-    pub voter_id: Uuid,
-    pub candidate_id: Uuid,
-    pub timestamp: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone)]
-pub struct EncryptedVote {
-    pub id: Uuid,
-    pub election_id: Uuid,
-    pub voter_id: Uuid,
-    pub candidate_id: Uuid,
-    pub encrypted_data: Vec<u8>,
-    pub zk_proof: String,
-    pub signature: String,
-    pub timestamp: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone)]
-pub struct Candidate {
-    pub id: Uuid,
-    pub name: String,
-    pub party: String,
-    pub number: u32,
-}
-
 #[derive(Debug, Clone)]
 pub struct VoteReceipt {
     pub vote_id: Uuid,
     pub election_id: Uuid,
-    pub candidate_number: u32,
-    pub candidate_name: String,
+    /// Escolhas na ordem da cédula
+    pub choices: Vec<ReceiptChoice>,
     pub timestamp: DateTime<Utc>,
+    pub tracking_code: String,
+    /// QR code renderizado em texto, para exibição na tela
     pub qr_code: String,
+    /// Conteúdo do QR code, impresso com o comando nativo da impressora
+    pub qr_payload: String,
     pub blockchain_hash: Option<String>,
 }
 
+/// Escolha de um cargo no comprovante
 #[derive(Debug, Clone)]
-pub enum VoteStatus {
-    Pending,
-    Synced,
-    Confirmed,
-    Failed,
+pub struct ReceiptChoice {
+    pub title: String,
+    /// Ausente nos votos em branco e nulos
+    pub candidate_number: Option<u32>,
+    pub candidate_name: String,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Inicializar logging
-    env_logger::init();
+    // Logs no console e, com coletor configurado, rastros OTLP
+    telemetry::init();
+
+    // Reprodução de um rastro exportado no simulador
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--replay") {
+        let path = args.get(2).ok_or_else(|| anyhow::anyhow!("Usage: fortis-voting-app --replay <trace.json>"))?;
+        let trace = replay::SessionTrace::load(Path::new(path)).await?;
+        let report = replay::ReplayRunner::new(&trace).run(&mut replay::SessionSimulator::new());
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.is_exact() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Seção simulada com perfil de latência, conferindo o tempo de cada tela
+    if args.get(1).map(String::as_str) == Some("--simulate") {
+        let usage = || anyhow::anyhow!("Usage: fortis-voting-app --simulate <profile|profile.json> [voters]");
+        let profile = latency::LatencyProfile::load(args.get(2).ok_or_else(usage)?).await?;
+        let voters = match args.get(3) {
+            Some(voters) => voters.parse().map_err(|_| usage())?,
+            None => 100,
+        };
+        let report = latency::simulate_session(profile, voters, &latency::UxRequirements::default());
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.meets_requirements() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Criar aplicação
     let app = VotingApp::new()?;
 
-    // Inicializar aplicação
-    app.initialize().await?;
-
-    // Iniciar loop principal de votação
-    loop {
-        // Aguardar início de sessão de votação
-        // Em implementação real, seria controlado por sistema externo
-        
-        // Simular sessão de votação
-        let election_id = Uuid::new_v4();
-        app.start_voting_session(election_id).await?;
-
-        // Autenticar eleitor
-        let voter_id = app.authenticate_voter().await?;
-
-        // Mostrar seleção de candidatos
-        let candidate_id = app.show_candidate_selection().await?;
-
-        // Registrar voto
-        let vote_id = app.cast_vote(candidate_id).await?;
-
-        // Imprimir comprovante
-        app.print_receipt(vote_id).await?;
-
-        // Finalizar sessão
-        app.end_voting_session().await?;
-
-        // Aguardar próxima sessão
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-    }
+    // Modos e sessões dirigidos pelos comandos do mesário
+    let policy = supervisor::WatchdogPolicy::default();
+    let (commands_tx, commands_rx) = tokio::sync::mpsc::channel(32);
+    supervisor::spawn_command_reader(supervisor::MESARIO_COMMAND_PATH.into(), commands_tx);
+    supervisor::spawn_heartbeat(app.clone(), policy.heartbeat_interval_seconds);
+    let result = supervisor::SessionSupervisor::new(app, commands_rx, policy)
+        .run()
+        .await;
+    telemetry::shutdown();
+    result
 }
@@ -0,0 +1,93 @@
+//! Módulo de mensagens de erro ao eleitor
+//!
+//! Traduz os códigos de erro (os mesmos do `FortisError` do backend) para a
+//! mensagem exibida na tela da urna. O catálogo vem no pacote de
+//! configuração, de modo que novas mensagens não exigem nova versão da urna.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+
+/// Caminho do catálogo no pacote de configuração
+pub const ERROR_CATALOG_PATH: &str = "/etc/fortis/bundle/error_catalog.json";
+
+/// Códigos do catálogo de falhas, compartilhado com o backend
+pub use fortis_domain::diagnostics::codes;
+
+/// Erro com código do catálogo
+#[derive(Debug, Error)]
+#[error("{code}: {detail}")]
+pub struct UrnaError {
+    pub code: String,
+    pub detail: String,
+}
+
+impl UrnaError {
+    pub fn new(code: &str, detail: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            detail: detail.to_string(),
+        }
+    }
+}
+
+/// Código do erro, ou erro interno quando não houver código associado
+pub fn error_code(error: &anyhow::Error) -> &str {
+    error.downcast_ref::<UrnaError>()
+        .map(|e| e.code.as_str())
+        .unwrap_or(codes::INTERNAL_ERROR)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoterMessage {
+    pub message: String,
+    pub action: String,
+}
+
+/// Catálogo de mensagens publicado pelo backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorCatalog {
+    pub locale: String,
+    pub version: String,
+    pub fallback: VoterMessage,
+    pub messages: BTreeMap<String, VoterMessage>,
+}
+
+impl ErrorCatalog {
+    /// Carrega o catálogo do pacote, com mensagem genérica se indisponível
+    pub fn load(path: &Path) -> Self {
+        match Self::read(path) {
+            Ok(catalog) => {
+                log::info!("Loaded error catalog {} ({})", catalog.version, catalog.locale);
+                catalog
+            }
+            Err(e) => {
+                log::warn!("Error catalog unavailable, using generic message: {}", e);
+                Self::generic()
+            }
+        }
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    fn generic() -> Self {
+        Self {
+            locale: "pt-BR".to_string(),
+            version: "builtin".to_string(),
+            fallback: VoterMessage {
+                message: "Ocorreu um erro, procure o mesário".to_string(),
+                action: "call_mesario".to_string(),
+            },
+            messages: BTreeMap::new(),
+        }
+    }
+
+    pub fn voter_message(&self, code: &str) -> &VoterMessage {
+        self.messages.get(code).unwrap_or(&self.fallback)
+    }
+}
@@ -0,0 +1,287 @@
+//! Fila de saída durável dos votos (store-and-forward)
+//!
+//! Cada voto cifrado fica gravado em disco até o backend confirmar o
+//! recebimento. A fila guarda as tentativas de envio de cada voto e adia o
+//! próximo envio com espera exponencial e jitter, para que urnas de uma
+//! mesma zona não voltem todas ao mesmo tempo depois de uma queda do link.
+//! Um voto já presente na fila não é enfileirado de novo.
+//!
+//! Cada entrada guarda o `traceparent` do atendimento que registrou o voto,
+//! enviado junto na sincronização para continuar o mesmo rastro.
+//!
+//! O voto confirmado deixa um marcador `<id>.delivered` no lugar da entrada,
+//! para que a recuperação de um atendimento interrompido saiba que o voto
+//! saiu da urna mesmo depois de ele deixar a fila.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::EncryptedVote;
+use crate::telemetry;
+
+/// Diretório da fila de saída
+pub const OUTBOX_DIR: &str = "/var/lib/fortis/outbox";
+
+/// Espera a partir da qual o monitoramento registra alerta de fila parada
+pub const OUTBOX_STALE_SECONDS: i64 = 3600;
+
+/// Espera entre tentativas de envio de um voto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackoffPolicy {
+    pub base_seconds: u64,
+    pub max_seconds: u64,
+    /// Fração da espera sorteada (0.0 a 1.0)
+    pub jitter: f64,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            base_seconds: 5,
+            max_seconds: 900,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// Espera após `attempts` falhas: exponencial, limitada, com parte sorteada
+    pub fn delay(&self, attempts: u32) -> Duration {
+        let ceiling = self.base_seconds
+            .saturating_mul(1u64 << attempts.min(32))
+            .min(self.max_seconds) as f64;
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 - jitter * rand::thread_rng().gen::<f64>();
+        Duration::milliseconds((ceiling * factor * 1000.0) as i64)
+    }
+}
+
+/// Voto aguardando confirmação do backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub vote: EncryptedVote,
+    pub enqueued_at: DateTime<Utc>,
+    pub attempts: u32,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<String>,
+}
+
+/// Situação da fila, lida pelo laço de monitoramento
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutboxMetrics {
+    pub depth: usize,
+    /// Votos cuja espera já terminou
+    pub due: usize,
+    /// Votos em espera após falha
+    pub backing_off: usize,
+    pub oldest_enqueued_at: Option<DateTime<Utc>>,
+    pub max_attempts: u32,
+    pub enqueued_total: u64,
+    pub delivered_total: u64,
+    pub duplicates_ignored: u64,
+}
+
+#[derive(Debug, Default)]
+struct OutboxInner {
+    entries: HashMap<Uuid, OutboxEntry>,
+    /// Votos já confirmados pelo backend
+    delivered: HashSet<Uuid>,
+    enqueued_total: u64,
+    delivered_total: u64,
+    duplicates_ignored: u64,
+}
+
+/// Fila de saída gravada em disco, um arquivo por voto
+#[derive(Debug)]
+pub struct VoteOutbox {
+    dir: PathBuf,
+    policy: BackoffPolicy,
+    inner: Mutex<OutboxInner>,
+}
+
+impl VoteOutbox {
+    pub fn new(dir: impl Into<PathBuf>, policy: BackoffPolicy) -> Self {
+        Self {
+            dir: dir.into(),
+            policy,
+            inner: Mutex::new(OutboxInner::default()),
+        }
+    }
+
+    /// Recarrega os votos gravados antes de um reinício
+    pub async fn initialize(&self) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let mut inner = self.inner.lock().await;
+
+        let mut dir = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(file) = dir.next_entry().await? {
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("delivered") {
+                if let Some(vote_id) = path.file_stem().and_then(|s| s.to_str()).and_then(|s| Uuid::parse_str(s).ok()) {
+                    inner.delivered.insert(vote_id);
+                }
+                continue;
+            }
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match serde_json::from_slice::<OutboxEntry>(&tokio::fs::read(&path).await?) {
+                Ok(entry) => {
+                    inner.entries.insert(entry.vote.id, entry);
+                }
+                Err(e) => log::error!("Unreadable outbox entry {}: {}", path.display(), e),
+            }
+        }
+        // Queda entre o marcador e a remoção da entrada: o voto já foi entregue
+        let delivered: Vec<Uuid> = inner.delivered.iter().copied().collect();
+        for vote_id in delivered {
+            inner.entries.remove(&vote_id);
+        }
+
+        log::info!("Vote outbox loaded with {} pending votes", inner.entries.len());
+        Ok(())
+    }
+
+    /// Enfileira o voto; devolve `false` se ele já estava na fila
+    pub async fn enqueue(&self, vote: &EncryptedVote) -> Result<bool> {
+        let mut inner = self.inner.lock().await;
+        if inner.entries.contains_key(&vote.id) {
+            inner.duplicates_ignored += 1;
+            return Ok(false);
+        }
+
+        let now = Utc::now();
+        let entry = OutboxEntry {
+            vote: vote.clone(),
+            enqueued_at: now,
+            attempts: 0,
+            last_attempt_at: None,
+            next_attempt_at: now,
+            last_error: None,
+            trace_context: telemetry::current_traceparent(),
+        };
+        self.persist(&entry).await?;
+        inner.entries.insert(vote.id, entry);
+        inner.enqueued_total += 1;
+        Ok(true)
+    }
+
+    /// Votos cuja espera terminou, dos mais antigos para os mais novos
+    pub async fn due(&self, now: DateTime<Utc>) -> Vec<EncryptedVote> {
+        let inner = self.inner.lock().await;
+        let mut due: Vec<&OutboxEntry> = inner.entries
+            .values()
+            .filter(|entry| entry.next_attempt_at <= now)
+            .collect();
+        due.sort_by_key(|entry| entry.enqueued_at);
+        due.into_iter().map(|entry| entry.vote.clone()).collect()
+    }
+
+    /// Votos da fila com os ids informados, independentemente da espera
+    pub async fn votes(&self, vote_ids: &[Uuid]) -> Vec<EncryptedVote> {
+        let inner = self.inner.lock().await;
+        vote_ids.iter()
+            .filter_map(|id| inner.entries.get(id).map(|entry| entry.vote.clone()))
+            .collect()
+    }
+
+    /// O voto está na fila ou já foi confirmado pelo backend
+    pub async fn holds(&self, vote_id: Uuid) -> bool {
+        let inner = self.inner.lock().await;
+        inner.entries.contains_key(&vote_id) || inner.delivered.contains(&vote_id)
+    }
+
+    /// `traceparent` do registro de cada voto que o tem
+    pub async fn trace_contexts(&self, vote_ids: &[Uuid]) -> BTreeMap<Uuid, String> {
+        let inner = self.inner.lock().await;
+        vote_ids.iter()
+            .filter_map(|id| {
+                let context = inner.entries.get(id)?.trace_context.clone()?;
+                Some((*id, context))
+            })
+            .collect()
+    }
+
+    /// Registra a falha de envio e agenda a próxima tentativa de cada voto
+    pub async fn record_failure(&self, vote_ids: &[Uuid], error: &str) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        let now = Utc::now();
+        for vote_id in vote_ids {
+            let Some(entry) = inner.entries.get_mut(vote_id) else {
+                continue;
+            };
+            entry.attempts += 1;
+            entry.last_attempt_at = Some(now);
+            entry.next_attempt_at = now + self.policy.delay(entry.attempts);
+            entry.last_error = Some(error.to_string());
+            self.persist(entry).await?;
+        }
+        Ok(())
+    }
+
+    /// Retira da fila o voto confirmado pelo backend
+    pub async fn acknowledge(&self, vote_id: Uuid) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        if !inner.entries.contains_key(&vote_id) {
+            return Ok(());
+        }
+        // Marcador antes da remoção, para a entrega nunca sumir do disco
+        let marker = tokio::fs::File::create(self.delivered_path(vote_id)).await?;
+        marker.sync_all().await?;
+        inner.entries.remove(&vote_id);
+        inner.delivered.insert(vote_id);
+        inner.delivered_total += 1;
+        match tokio::fs::remove_file(self.entry_path(vote_id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn depth(&self) -> usize {
+        self.inner.lock().await.entries.len()
+    }
+
+    pub async fn metrics(&self) -> OutboxMetrics {
+        let inner = self.inner.lock().await;
+        let now = Utc::now();
+        let due = inner.entries.values().filter(|e| e.next_attempt_at <= now).count();
+
+        OutboxMetrics {
+            depth: inner.entries.len(),
+            due,
+            backing_off: inner.entries.len() - due,
+            oldest_enqueued_at: inner.entries.values().map(|e| e.enqueued_at).min(),
+            max_attempts: inner.entries.values().map(|e| e.attempts).max().unwrap_or(0),
+            enqueued_total: inner.enqueued_total,
+            delivered_total: inner.delivered_total,
+            duplicates_ignored: inner.duplicates_ignored,
+        }
+    }
+
+    /// Grava a entrada em arquivo temporário e renomeia, para não deixar
+    /// um voto pela metade em caso de queda de energia
+    async fn persist(&self, entry: &OutboxEntry) -> Result<()> {
+        let path = self.entry_path(entry.vote.id);
+        let temporary = path.with_extension("json.tmp");
+        tokio::fs::write(&temporary, serde_json::to_vec(entry)?).await?;
+        tokio::fs::rename(&temporary, &path).await?;
+        Ok(())
+    }
+
+    fn entry_path(&self, vote_id: Uuid) -> PathBuf {
+        Path::new(&self.dir).join(format!("{}.json", vote_id))
+    }
+
+    fn delivered_path(&self, vote_id: Uuid) -> PathBuf {
+        Path::new(&self.dir).join(format!("{}.delivered", vote_id))
+    }
+}
@@ -0,0 +1,103 @@
+//! Módulo do pacote de eleição para urna eletrônica
+//!
+//! O pacote assinado pelo backend traz candidatos, layout da cédula,
+//! parâmetros e chaves da eleição. A assinatura é verificada com a chave
+//! fixada na configuração da urna, nunca com a chave enviada no pacote.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::Candidate;
+use fortis_domain::CandidatePosition;
+use crate::i18n::LanguagePack;
+use crate::messages::ErrorCatalog;
+
+/// Versão do formato suportada por esta urna
+pub const PACKAGE_FORMAT_VERSION: u32 = 1;
+
+/// Chave pública confiável para pacotes de eleição
+pub const TRUSTED_PACKAGE_KEY_PATH: &str = "/etc/fortis/keys/package_signing.pub";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedElectionPackage {
+    pub payload: String,
+    pub package_sha256: String,
+    pub signature: String,
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageElection {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ElectionParams {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub timezone: String,
+    pub allow_blank_vote: bool,
+    pub allow_null_vote: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BallotPage {
+    pub order: u32,
+    pub position: CandidatePosition,
+    pub title: String,
+    pub number_digits: u32,
+}
+
+impl BallotPage {
+    /// Tela do cargo quando a lista vem da API, sem o pacote
+    pub fn for_position(order: u32, position: CandidatePosition) -> Self {
+        Self {
+            order,
+            position,
+            title: position.title().to_string(),
+            number_digits: position.number_digits(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ElectionPackage {
+    pub format_version: u32,
+    pub package_id: String,
+    pub election: PackageElection,
+    pub region: Option<String>,
+    pub params: ElectionParams,
+    pub ballot_layout: Vec<BallotPage>,
+    pub candidates: Vec<Candidate>,
+    pub public_keys: BTreeMap<String, String>,
+    pub error_catalog: ErrorCatalog,
+    #[serde(default)]
+    pub language_packs: Vec<LanguagePack>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Verifica hash, assinatura e formato do pacote
+pub fn verify_package(signed: &SignedElectionPackage, trusted_public_key: &str) -> Result<ElectionPackage> {
+    if format!("{:x}", Sha256::digest(signed.payload.as_bytes())) != signed.package_sha256 {
+        return Err(anyhow!("Election package hash mismatch"));
+    }
+
+    let public_key = hex::decode(trusted_public_key.trim())?;
+    let signature = hex::decode(&signed.signature)?;
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(signed.payload.as_bytes(), &signature)
+        .map_err(|_| anyhow!("Invalid election package signature"))?;
+
+    let package: ElectionPackage = serde_json::from_str(&signed.payload)?;
+    if package.format_version != PACKAGE_FORMAT_VERSION {
+        return Err(anyhow!("Unsupported election package format: {}", package.format_version));
+    }
+    Ok(package)
+}
@@ -0,0 +1,107 @@
+//! Pacote de provisionamento cifrado da urna
+//!
+//! A mídia de preparação traz um único envelope cifrado para esta urna. A
+//! assinatura é conferida com a chave fixada antes de qualquer decifração; a
+//! chave de conteúdo só é recuperada pelo TPM, com a chave de selagem
+//! persistente, e o manifesto é conferido depois de decifrar.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{Result, anyhow};
+use base64::{engine::general_purpose, Engine as _};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use rsa::pkcs8::{DecodePublicKey, EncodePublicKey};
+use rsa::RsaPublicKey;
+use std::path::{Component, Path};
+
+use crate::lockdown::tpm2;
+pub use fortis_domain::provisioning::{BundleContents, ProvisioningBundle, VoterSnapshot};
+
+/// Chave pública confiável para pacotes de provisionamento
+pub const TRUSTED_PROVISIONING_KEY_PATH: &str = "/etc/fortis/keys/provisioning_signing.pub";
+
+/// Handle persistente da chave de selagem no TPM
+pub const SEALING_KEY_HANDLE: &str = "0x81010002";
+
+/// Chave pública de selagem exportada na preparação (PEM)
+pub const SEALING_PUBLIC_KEY_PATH: &str = "/etc/fortis/keys/device_sealing.pem";
+
+/// Pacote entregue na mídia de preparação
+pub const PROVISIONING_MEDIA_PATH: &str = "/media/fortis/provisioning.bundle";
+
+/// Destino da configuração, do cadastro da seção e dos arquivos auxiliares
+pub const PROVISIONING_DIR: &str = "/var/lib/fortis/provisioning";
+
+/// Lê o envelope gravado na mídia
+pub async fn load_bundle(path: &Path) -> Result<ProvisioningBundle> {
+    let data = tokio::fs::read(path).await
+        .map_err(|e| anyhow!("Provisioning bundle not found at {}: {}", path.display(), e))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+/// Confere assinatura e destino, decifra no TPM e confere o manifesto
+pub async fn open_bundle(bundle: &ProvisioningBundle, urna_id: &str) -> Result<BundleContents> {
+    let trusted_key = tokio::fs::read_to_string(TRUSTED_PROVISIONING_KEY_PATH).await
+        .map_err(|e| anyhow!("Trusted provisioning key unavailable: {}", e))?;
+    UnparsedPublicKey::new(&ED25519, hex::decode(trusted_key.trim())?)
+        .verify(&bundle.signed_message(), &hex::decode(&bundle.signature)?)
+        .map_err(|_| anyhow!("Invalid provisioning bundle signature"))?;
+
+    let sealing_key_pem = tokio::fs::read_to_string(SEALING_PUBLIC_KEY_PATH).await
+        .map_err(|e| anyhow!("Sealing public key unavailable: {}", e))?;
+    let sealing_key_der = RsaPublicKey::from_public_key_pem(&sealing_key_pem)
+        .map_err(|e| anyhow!("Invalid sealing public key: {}", e))?
+        .to_public_key_der()
+        .map_err(|e| anyhow!("Invalid sealing public key: {}", e))?;
+    bundle.check_recipient(urna_id, sealing_key_der.as_bytes())?;
+
+    // A chave privada de selagem nunca sai do TPM
+    let content_key = tpm2(
+        &["tpm2_rsadecrypt", "-c", SEALING_KEY_HANDLE, "-s", "oaep"],
+        Some(&general_purpose::STANDARD.decode(&bundle.wrapped_key)?),
+    ).await?;
+
+    let cipher = Aes256Gcm::new_from_slice(&content_key)
+        .map_err(|_| anyhow!("Invalid content key length"))?;
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&general_purpose::STANDARD.decode(&bundle.nonce)?),
+            Payload {
+                msg: &general_purpose::STANDARD.decode(&bundle.ciphertext)?,
+                aad: &bundle.associated_data(),
+            },
+        )
+        .map_err(|_| anyhow!("Failed to decrypt provisioning bundle"))?;
+
+    let contents: BundleContents = serde_json::from_slice(&plaintext)?;
+    contents.verify_manifest()?;
+    Ok(contents)
+}
+
+/// Cadastro da seção gravado na preparação
+pub async fn load_voter_snapshot(dir: &Path) -> Result<VoterSnapshot> {
+    let data = tokio::fs::read(dir.join("voter_snapshot.json")).await
+        .map_err(|e| anyhow!("Section voter snapshot unavailable: {}", e))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+/// Grava configuração, cadastro da seção e arquivos auxiliares
+pub async fn store_contents(contents: &BundleContents, dir: &Path) -> Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join("config.json"), serde_json::to_vec(&contents.config)?).await?;
+    tokio::fs::write(dir.join("voter_snapshot.json"), serde_json::to_vec(&contents.voter_snapshot)?).await?;
+    tokio::fs::write(dir.join("manifest.json"), serde_json::to_vec(&contents.manifest)?).await?;
+
+    for asset in &contents.assets {
+        let relative = Path::new(&asset.name);
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(anyhow!("Invalid asset name: {}", asset.name));
+        }
+        let path = dir.join("assets").join(relative);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, asset.bytes()?).await?;
+    }
+    Ok(())
+}
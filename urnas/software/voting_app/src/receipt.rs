@@ -0,0 +1,24 @@
+//! Módulo de comprovante de votação para urna eletrônica
+//!
+//! O QR code do comprovante traz o identificador do voto, o código de
+//! rastreamento e os dados de inclusão no log transparente. Não contém a
+//! escolha do eleitor: a verificação em /api/v1/votes/verify/{código} só
+//! confirma que o voto foi contado.
+
+use anyhow::Result;
+use qrcode::{EcLevel, QrCode};
+
+pub use fortis_domain::receipt::{
+    ballot_hash, tracking_code, InclusionData, ReceiptPayload, RECEIPT_PAYLOAD_VERSION,
+};
+
+/// Gera o QR code como texto para a impressora térmica
+pub fn render_qr(payload: &ReceiptPayload) -> Result<String> {
+    let data = serde_json::to_vec(payload)?;
+    let code = QrCode::with_error_correction_level(&data, EcLevel::M)?;
+    Ok(code
+        .render::<char>()
+        .quiet_zone(true)
+        .module_dimensions(2, 1)
+        .build())
+}
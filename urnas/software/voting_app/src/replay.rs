@@ -0,0 +1,401 @@
+//! Rastro determinístico das sessões para reprodução forense
+//!
+//! Durante a sessão a urna grava um rastro com as etapas executadas, o
+//! resultado de cada entrada não determinística (leitor biométrico,
+//! cadastro, impressora, backend), os eventos de hardware e de conexão e o
+//! instante relativo de cada um. O rastro nunca contém a escolha do
+//! eleitor, o eleitor ou o conteúdo das entradas: só códigos de resultado.
+//!
+//! No simulador, o `ReplayRunner` reinjeta as entradas na mesma ordem e no
+//! mesmo relógio virtual e compara o resultado de cada etapa com o gravado,
+//! reproduzindo a falha relatada em campo.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::latency::{self, ScreenTiming, SimulatedHardware};
+use crate::messages::codes;
+
+/// Diretório dos rastros de sessão
+pub const TRACE_DIR: &str = "/var/lib/fortis/traces";
+
+/// Versão do formato de exportação
+pub const TRACE_FORMAT_VERSION: u32 = 1;
+
+/// Etapa da sessão
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStep {
+    StartSession,
+    Authenticate,
+    SelectCandidate,
+    CastVote,
+    PrintReceipt,
+    SyncVotes,
+    EndSession,
+}
+
+/// Origem de uma entrada não determinística
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InputSource {
+    Biometric,
+    Eligibility,
+    VoterRegistry,
+    Keypad,
+    Printer,
+    Backend,
+}
+
+impl SessionStep {
+    /// Entradas consumidas pela etapa; a sincronização em segundo plano pode
+    /// gravar entradas do backend no meio de outra etapa
+    fn inputs(self) -> &'static [InputSource] {
+        match self {
+            SessionStep::Authenticate => &[InputSource::Biometric, InputSource::Eligibility, InputSource::VoterRegistry],
+            SessionStep::SelectCandidate => &[InputSource::Keypad],
+            SessionStep::PrintReceipt => &[InputSource::Printer],
+            SessionStep::SyncVotes => &[InputSource::Backend],
+            SessionStep::StartSession | SessionStep::CastVote | SessionStep::EndSession => &[],
+        }
+    }
+}
+
+/// Evento do rastro; só códigos de resultado, nunca conteúdo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceEvent {
+    StepStarted { step: SessionStep },
+    StepFinished { step: SessionStep, error_code: Option<String> },
+    Input { source: InputSource, error_code: Option<String> },
+    Hardware { component: String, ready: bool },
+    Connectivity { online: bool },
+}
+
+/// Evento com posição e instante relativo ao início da sessão
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TraceRecord {
+    pub seq: u64,
+    pub offset_ms: u64,
+    pub event: TraceEvent,
+}
+
+/// Rastro de uma sessão, no formato de exportação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTrace {
+    pub format_version: u32,
+    pub trace_id: Uuid,
+    pub urna_id: String,
+    pub software_version: String,
+    pub election_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub records: Vec<TraceRecord>,
+    /// SHA-256 do conteúdo, conferido na importação
+    pub digest: String,
+}
+
+impl SessionTrace {
+    fn compute_digest(&self) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(format!(
+            "fortis-trace-v{}|{}|{}|{}|{}|{}|",
+            self.format_version,
+            self.trace_id,
+            self.urna_id,
+            self.software_version,
+            self.election_id,
+            self.started_at.timestamp_millis()
+        ));
+        hasher.update(serde_json::to_vec(&self.records)?);
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Serializa o rastro para entrega ao suporte
+    pub fn export(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    /// Lê um rastro exportado, conferindo versão, resumo e ordem dos eventos
+    pub fn import(data: &[u8]) -> Result<Self> {
+        let trace: SessionTrace = serde_json::from_slice(data)?;
+        if trace.format_version != TRACE_FORMAT_VERSION {
+            return Err(anyhow!("Unsupported trace format version {}", trace.format_version));
+        }
+        if trace.compute_digest()? != trace.digest {
+            return Err(anyhow!("Trace digest mismatch"));
+        }
+
+        let mut last_offset = 0;
+        for (position, record) in trace.records.iter().enumerate() {
+            if record.seq != position as u64 {
+                return Err(anyhow!("Trace record {} out of sequence", record.seq));
+            }
+            if record.offset_ms < last_offset {
+                return Err(anyhow!("Trace record {} goes back in time", record.seq));
+            }
+            last_offset = record.offset_ms;
+        }
+        Ok(trace)
+    }
+
+    pub async fn load(path: &Path) -> Result<Self> {
+        Self::import(&tokio::fs::read(path).await?)
+    }
+}
+
+/// Gravador do rastro da sessão em andamento
+#[derive(Debug, Default)]
+pub struct TraceRecorder {
+    current: Mutex<Option<(SessionTrace, Instant)>>,
+}
+
+impl TraceRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn start(&self, urna_id: &str, election_id: Uuid) {
+        let trace = SessionTrace {
+            format_version: TRACE_FORMAT_VERSION,
+            trace_id: Uuid::new_v4(),
+            urna_id: urna_id.to_string(),
+            software_version: env!("CARGO_PKG_VERSION").to_string(),
+            election_id,
+            started_at: Utc::now(),
+            records: Vec::new(),
+            digest: String::new(),
+        };
+        *self.current.lock().await = Some((trace, Instant::now()));
+    }
+
+    /// Acrescenta o evento; fora de uma sessão, é ignorado
+    pub async fn record(&self, event: TraceEvent) {
+        let mut current = self.current.lock().await;
+        let Some((trace, started)) = current.as_mut() else {
+            return;
+        };
+        trace.records.push(TraceRecord {
+            seq: trace.records.len() as u64,
+            offset_ms: started.elapsed().as_millis() as u64,
+            event,
+        });
+    }
+
+    /// Resultado de uma entrada não determinística
+    pub async fn input<T>(&self, source: InputSource, result: &Result<T>) {
+        let error_code = result.as_ref().err().map(|e| crate::messages::error_code(e).to_string());
+        self.record(TraceEvent::Input { source, error_code }).await;
+    }
+
+    /// Encerra o rastro e grava em `dir`
+    pub async fn finish(&self, dir: &Path) -> Result<Option<PathBuf>> {
+        let Some((mut trace, _)) = self.current.lock().await.take() else {
+            return Ok(None);
+        };
+        trace.digest = trace.compute_digest()?;
+
+        tokio::fs::create_dir_all(dir).await?;
+        let path = dir.join(format!("{}.json", trace.trace_id));
+        tokio::fs::write(&path, trace.export()?).await?;
+        log::info!("Session trace {} stored ({} events)", trace.trace_id, trace.records.len());
+        Ok(Some(path))
+    }
+}
+
+/// Alvo da reprodução: recebe os eventos e executa as etapas
+pub trait ReplayTarget {
+    /// Aplica evento de hardware, conexão ou entrada
+    fn inject(&mut self, offset_ms: u64, event: &TraceEvent);
+
+    /// Executa a etapa com as entradas já injetadas; devolve o código de erro
+    fn run_step(&mut self, offset_ms: u64, step: SessionStep) -> Option<String>;
+}
+
+/// Etapa cujo resultado reproduzido difere do gravado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+    pub seq: u64,
+    pub step: SessionStep,
+    pub recorded: Option<String>,
+    pub replayed: Option<String>,
+}
+
+/// Resultado da reprodução
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub trace_id: Uuid,
+    pub steps_replayed: usize,
+    /// Etapas que falharam em campo e falharam igualmente na reprodução
+    pub failures_reproduced: Vec<TraceRecord>,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReplayReport {
+    pub fn is_exact(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Reprodução de um rastro sobre um alvo, no relógio virtual do rastro
+pub struct ReplayRunner<'a> {
+    trace: &'a SessionTrace,
+}
+
+impl<'a> ReplayRunner<'a> {
+    pub fn new(trace: &'a SessionTrace) -> Self {
+        Self { trace }
+    }
+
+    pub fn run(&self, target: &mut impl ReplayTarget) -> ReplayReport {
+        let mut report = ReplayReport {
+            trace_id: self.trace.trace_id,
+            steps_replayed: 0,
+            failures_reproduced: Vec::new(),
+            divergences: Vec::new(),
+        };
+
+        for record in &self.trace.records {
+            match &record.event {
+                TraceEvent::StepStarted { .. } => {}
+                TraceEvent::StepFinished { step, error_code } => {
+                    let replayed = target.run_step(record.offset_ms, *step);
+                    report.steps_replayed += 1;
+                    if replayed != *error_code {
+                        report.divergences.push(Divergence {
+                            seq: record.seq,
+                            step: *step,
+                            recorded: error_code.clone(),
+                            replayed,
+                        });
+                    } else if error_code.is_some() {
+                        report.failures_reproduced.push(record.clone());
+                    }
+                }
+                event => target.inject(record.offset_ms, event),
+            }
+        }
+        report
+    }
+}
+
+/// Modelo da sessão da urna usado pelo simulador
+///
+/// Segue as mesmas regras da aplicação: a sessão só abre com o hardware
+/// pronto, cada etapa exige a anterior e a primeira entrada com erro
+/// determina o código da etapa. Com um perfil de latência
+/// (`with_devices`), cada etapa também consome o tempo e as falhas sorteadas
+/// dos dispositivos que usa, registrados em `timings`.
+#[derive(Debug, Default)]
+pub struct SessionSimulator {
+    hardware: BTreeMap<String, bool>,
+    session_open: bool,
+    authenticated: bool,
+    selected: bool,
+    cast: bool,
+    pending_inputs: Vec<(InputSource, Option<String>)>,
+    devices: Option<SimulatedHardware>,
+    timings: Vec<ScreenTiming>,
+}
+
+impl SessionSimulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hardware simulado com latência e falhas do perfil
+    pub fn with_devices(mut self, devices: SimulatedHardware) -> Self {
+        self.devices = Some(devices);
+        self
+    }
+
+    /// Tempo de cada etapa executada com hardware simulado
+    pub fn timings(&self) -> &[ScreenTiming] {
+        &self.timings
+    }
+
+    /// Opera os dispositivos da etapa; a primeira falha vira entrada da etapa
+    fn operate_devices(&mut self, step: SessionStep) -> Option<u64> {
+        let devices = self.devices.as_mut()?;
+        let mut elapsed_ms = 0;
+        for device in latency::devices_for(step) {
+            let outcome = devices.operate(*device);
+            elapsed_ms += outcome.elapsed_ms;
+            if let Some(TraceEvent::Input { source, error_code }) = latency::fault_input(&outcome) {
+                self.pending_inputs.push((source, error_code));
+                break;
+            }
+        }
+        Some(elapsed_ms)
+    }
+
+    fn hardware_ready(&self) -> bool {
+        self.hardware.values().all(|ready| *ready)
+    }
+}
+
+impl ReplayTarget for SessionSimulator {
+    fn inject(&mut self, _offset_ms: u64, event: &TraceEvent) {
+        match event {
+            TraceEvent::Hardware { component, ready } => {
+                self.hardware.insert(component.clone(), *ready);
+            }
+            TraceEvent::Input { source, error_code } => self.pending_inputs.push((*source, error_code.clone())),
+            // Conexão fica no rastro para leitura; o envio já grava a entrada do backend
+            TraceEvent::Connectivity { .. } | TraceEvent::StepStarted { .. } | TraceEvent::StepFinished { .. } => {}
+        }
+    }
+
+    fn run_step(&mut self, _offset_ms: u64, step: SessionStep) -> Option<String> {
+        let elapsed_ms = self.operate_devices(step);
+        let sources = step.inputs();
+        let (consumed, kept) = std::mem::take(&mut self.pending_inputs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(source, _)| sources.contains(source));
+        self.pending_inputs = kept;
+        let input_error = consumed.into_iter().find_map(|(_, error_code)| error_code);
+        let internal = || Some(codes::INTERNAL_ERROR.to_string());
+
+        let result = match step {
+            SessionStep::StartSession if !self.hardware_ready() => internal(),
+            SessionStep::StartSession => input_error,
+            SessionStep::Authenticate if !self.session_open => Some(codes::ELECTION_NOT_ACTIVE.to_string()),
+            SessionStep::SelectCandidate | SessionStep::CastVote if !self.authenticated => internal(),
+            SessionStep::PrintReceipt if !self.cast => internal(),
+            // Falha de envio fica na fila de saída, não é erro da etapa
+            SessionStep::SyncVotes => None,
+            _ => input_error,
+        };
+
+        if result.is_none() {
+            match step {
+                SessionStep::StartSession => self.session_open = true,
+                SessionStep::Authenticate => self.authenticated = true,
+                SessionStep::SelectCandidate => self.selected = true,
+                SessionStep::CastVote => self.cast = self.selected,
+                SessionStep::PrintReceipt => {
+                    self.authenticated = false;
+                    self.selected = false;
+                    self.cast = false;
+                }
+                SessionStep::EndSession => self.session_open = false,
+                SessionStep::SyncVotes => {}
+            }
+        } else if matches!(step, SessionStep::Authenticate | SessionStep::SelectCandidate | SessionStep::CastVote) {
+            // Erro ao eleitor encerra o atendimento
+            self.authenticated = false;
+            self.selected = false;
+        }
+
+        if let Some(elapsed_ms) = elapsed_ms {
+            self.timings.push(ScreenTiming { step, elapsed_ms, error_code: result.clone() });
+        }
+        result
+    }
+}
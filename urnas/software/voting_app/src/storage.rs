@@ -0,0 +1,214 @@
+//! Capacidade de armazenamento da urna
+//!
+//! Na aplicação do provisionamento e na abertura da sessão a urna compara o
+//! espaço livre da partição de dados com o que a seção deve consumir: os
+//! registros de cada voto (fila de saída, prova de inclusão, envio e rastro)
+//! e o crescimento do log de auditoria, vezes os eleitores esperados, mais
+//! uma reserva fixa. Com pouca folga, apaga primeiro os arquivos em cache que
+//! não são essenciais (listas de candidatos e pacotes de outras eleições) e
+//! mede de novo. Se ainda faltar espaço a sessão não abre; com folga pequena
+//! abre com aviso ao mesário.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use sysinfo::{DiskExt, System, SystemExt};
+use uuid::Uuid;
+
+use crate::provisioning;
+
+/// Política de armazenamento no pacote de configuração
+pub const STORAGE_POLICY_PATH: &str = "/etc/fortis/bundle/storage_policy.json";
+
+/// Partição com os dados da urna
+pub const DATA_DIR: &str = "/var/lib/fortis";
+
+/// Caches que podem ser refeitos: listas de candidatos e pacotes de eleição
+pub const PRUNABLE_DIRS: &[&str] = &["/var/lib/fortis/candidates", "/var/lib/fortis/packages"];
+
+const MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoragePolicy {
+    /// Registros de um voto: fila de saída, prova, envio, comprovante e rastro
+    pub bytes_per_vote: u64,
+    /// Crescimento do log de auditoria por eleitor atendido
+    pub log_bytes_per_vote: u64,
+    /// Reserva para o pacote final, os relatórios e o sistema
+    pub reserve_bytes: u64,
+    /// Eleitores esperados quando o cadastro da seção não estiver na urna
+    pub default_expected_voters: u64,
+    /// Folga, em fração do previsto, abaixo da qual o mesário é avisado
+    pub warning_margin: f64,
+}
+
+impl Default for StoragePolicy {
+    fn default() -> Self {
+        Self {
+            bytes_per_vote: 32 * 1024,
+            log_bytes_per_vote: 8 * 1024,
+            reserve_bytes: 256 * MB,
+            default_expected_voters: 500,
+            warning_margin: 0.5,
+        }
+    }
+}
+
+impl StoragePolicy {
+    /// Carrega a política do pacote, com os valores padrão se indisponível
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| Ok(serde_json::from_slice(&data)?)) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::warn!("Storage policy unavailable, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Espaço previsto para a seção, mais o que ainda será gravado
+    pub fn required_bytes(&self, expected_voters: u64, incoming_bytes: u64) -> u64 {
+        expected_voters
+            .saturating_mul(self.bytes_per_vote + self.log_bytes_per_vote)
+            .saturating_add(self.reserve_bytes)
+            .saturating_add(incoming_bytes)
+    }
+
+    fn comfortable_bytes(&self, required: u64) -> u64 {
+        required.saturating_add((required as f64 * self.warning_margin) as u64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageStatus {
+    Sufficient,
+    /// Cabe, mas com folga menor que a da política
+    Low,
+    Insufficient,
+}
+
+/// Resultado da verificação, registrado na auditoria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageReport {
+    pub checked_at: DateTime<Utc>,
+    pub expected_voters: u64,
+    pub required_bytes: u64,
+    pub available_bytes: u64,
+    pub pruned_files: usize,
+    pub pruned_bytes: u64,
+    pub status: StorageStatus,
+}
+
+impl StorageReport {
+    /// Resumo para a tela do mesário
+    pub fn summary(&self) -> String {
+        format!(
+            "{} MB livres para {} MB previstos ({} eleitores)",
+            self.available_bytes / MB,
+            self.required_bytes / MB,
+            self.expected_voters
+        )
+    }
+}
+
+/// Verificação de espaço e limpeza dos caches
+#[derive(Debug)]
+pub struct StorageGuard {
+    pub policy: StoragePolicy,
+    data_dir: PathBuf,
+}
+
+impl StorageGuard {
+    pub fn new(policy: StoragePolicy) -> Self {
+        Self {
+            policy,
+            data_dir: PathBuf::from(DATA_DIR),
+        }
+    }
+
+    /// Confere o espaço para a seção, limpando os caches se a folga for pequena
+    ///
+    /// Arquivos da eleição em `keep_election` nunca são apagados.
+    pub async fn check(&self, keep_election: Option<Uuid>, incoming_bytes: u64) -> Result<StorageReport> {
+        let expected_voters = self.expected_voters().await;
+        let required_bytes = self.policy.required_bytes(expected_voters, incoming_bytes);
+        let mut available_bytes = self.available_bytes()?;
+
+        let (mut pruned_files, mut pruned_bytes) = (0, 0);
+        if available_bytes < self.policy.comfortable_bytes(required_bytes) {
+            (pruned_files, pruned_bytes) = self.prune(keep_election).await?;
+            if pruned_files > 0 {
+                log::info!("Pruned {} cached files ({} bytes)", pruned_files, pruned_bytes);
+                available_bytes = self.available_bytes()?;
+            }
+        }
+
+        let status = if available_bytes < required_bytes {
+            StorageStatus::Insufficient
+        } else if available_bytes < self.policy.comfortable_bytes(required_bytes) {
+            StorageStatus::Low
+        } else {
+            StorageStatus::Sufficient
+        };
+
+        Ok(StorageReport {
+            checked_at: Utc::now(),
+            expected_voters,
+            required_bytes,
+            available_bytes,
+            pruned_files,
+            pruned_bytes,
+            status,
+        })
+    }
+
+    /// Eleitores do cadastro da seção, ou o padrão da política
+    async fn expected_voters(&self) -> u64 {
+        match provisioning::load_voter_snapshot(Path::new(provisioning::PROVISIONING_DIR)).await {
+            Ok(snapshot) => snapshot.voters.len() as u64,
+            Err(_) => self.policy.default_expected_voters,
+        }
+    }
+
+    /// Espaço livre na partição que contém o diretório de dados
+    fn available_bytes(&self) -> Result<u64> {
+        let mut system = System::new();
+        system.refresh_disks_list();
+        system.disks()
+            .iter()
+            .filter(|disk| self.data_dir.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len())
+            .map(|disk| disk.available_space())
+            .ok_or_else(|| anyhow!("No disk found for {}", self.data_dir.display()))
+    }
+
+    /// Apaga os caches de outras eleições; retorna arquivos e bytes liberados
+    async fn prune(&self, keep_election: Option<Uuid>) -> Result<(usize, u64)> {
+        let keep = keep_election.map(|id| id.to_string());
+        let (mut files, mut bytes) = (0, 0);
+        for dir in PRUNABLE_DIRS {
+            let mut entries = match tokio::fs::read_dir(dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if keep.as_deref().is_some_and(|keep| name.starts_with(keep)) {
+                    continue;
+                }
+                let metadata = entry.metadata().await?;
+                if !metadata.is_file() {
+                    continue;
+                }
+                tokio::fs::remove_file(entry.path()).await?;
+                files += 1;
+                bytes += metadata.len();
+            }
+        }
+        Ok((files, bytes))
+    }
+}
@@ -0,0 +1,902 @@
+//! Supervisor das sessões da urna
+//!
+//! A urna opera em três modos, trocados somente por comandos do mesário:
+//! aguardando (sem sessão aberta), votação (sessão lacrada, eleitores
+//! liberados um a um) e manutenção (atendimento suspenso). Cada eleitor é
+//! atendido em uma tarefa própria: erro ou pânico no atendimento mostra a
+//! tela de recuperação e a urna volta a aguardar o próximo eleitor, sem
+//! derrubar a aplicação. Um watchdog verifica periodicamente os subsistemas
+//! e reinicia os que falharem; acima do limite de reinícios, a urna entra
+//! em manutenção. Em manutenção, ao fim do ciclo, o mesário pode
+//! descomissionar a urna; depois disso ela só aceita o desligamento.
+//!
+//! Ao liberar o eleitor, o mesário pode pedir o idioma e o modo de
+//! acessibilidade (guia por voz, alto contraste, fonte ampliada); o modo
+//! também pode ser trocado durante o atendimento, e ambos voltam ao padrão
+//! ao final dele.
+//!
+//! Atendimento sem interação do eleitor além do tempo limite é cancelado e
+//! a urna fica bloqueada, com alerta sonoro a cada verificação do watchdog,
+//! até o mesário pressionar a tecla de liberação e digitar o PIN.
+//!
+//! Os comandos de operação exigem o mesário identificado no terminal
+//! (`terminal`): o eleitor é admitido pelo número de ordem antes de ser
+//! liberado, e o fechamento da sessão passa pelo resumo do dia. No
+//! fechamento, a urna imprime o boletim de urna e a ata da mesa receptora;
+//! falha da impressora não impede o encerramento.
+//!
+//! Cada atendimento é acompanhado pelo diário (`ballot_journal`). Na
+//! inicialização, um atendimento interrompido com voto gravado é dado como
+//! concluído; sem voto, é anulado e a urna fica bloqueada até o mesário
+//! autorizar o recomeço do eleitor.
+//!
+//! Os sensores de violação (`hardware::tamper`) são lidos a cada ciclo e
+//! durante o atendimento. Violação detectada interrompe o atendimento que
+//! ainda não chegou ao registro do voto e trava a urna, mesmo depois de um
+//! reinício; só o presidente da mesa retira o bloqueio, com os sensores
+//! normalizados e a justificativa na trilha de auditoria.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use fortis_domain::document::templates::{self, BulletinData, SectionMinutesData};
+use fortis_domain::document::Document;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::VotingApp;
+use crate::accessibility::AccessibilityProfile;
+use crate::ballot_journal::{BallotPhase, RecoveredBallot};
+use crate::contingency;
+use crate::decommission;
+use crate::lockdown::{self, CloseAuthorization};
+use crate::messages::{self, UrnaError, codes};
+use crate::provisioning;
+use crate::replay::{self, SessionStep};
+use crate::storage::StorageStatus;
+use crate::sync::UrnaHealth;
+use crate::terminal::{Admission, ClosingReport, MesarioRole, MesarioTerminal};
+
+/// Canal do terminal do mesário (FIFO, um comando JSON por linha)
+pub const MESARIO_COMMAND_PATH: &str = "/run/fortis/mesario.cmd";
+
+/// Modo de operação da urna
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UrnaMode {
+    Idle,
+    Voting,
+    Maintenance,
+    /// Atendimento expirado; sessão aberta, aguardando o PIN do mesário
+    AwaitingAttendant,
+    /// Atendimento interrompido por um reinício e anulado; aguarda o mesário
+    InterruptedBallot,
+    /// Violação do equipamento detectada; votação suspensa até o presidente da mesa liberar
+    TamperLockdown,
+    /// Chaves e dados apagados; só aceita o desligamento
+    Decommissioned,
+}
+
+/// Subsistema reiniciável pelo watchdog ou pelo mesário
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Hardware,
+    Auth,
+    Ui,
+    Crypto,
+    Sync,
+    Outbox,
+    Audit,
+}
+
+/// Subsistemas com verificação de saúde periódica
+const WATCHED_SUBSYSTEMS: [Subsystem; 2] = [Subsystem::Hardware, Subsystem::Sync];
+
+/// Comando do mesário
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum MesarioCommand {
+    /// Identifica o mesário no terminal
+    Login { mesario_id: String, pin: String },
+    Logout,
+    OpenSession { election_id: Uuid },
+    /// Libera a abertura apesar das falhas do último autoteste de som e tela
+    OverrideFeedbackCheck { mesario_id: String, reason: String },
+    /// Admite o eleitor pelo número de ordem no cadastro da seção
+    AdmitVoter { sequence: u32 },
+    /// Libera o eleitor admitido, com o perfil de acessibilidade e o idioma pedidos
+    ReleaseVoter {
+        #[serde(default)]
+        accessibility: Option<AccessibilityProfile>,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    /// Troca o perfil de acessibilidade do atendimento em andamento
+    SetAccessibility { profile: AccessibilityProfile },
+    /// Encerra o atendimento travado, antes do registro do voto
+    CancelVoter,
+    /// Tecla de liberação do mesário após o tempo limite, com o PIN
+    ResetSessionTimeout { mesario_id: String, pin: String },
+    /// Autoriza o recomeço do eleitor cujo atendimento foi anulado após um reinício
+    AuthorizeBallotRestart,
+    /// Retira o bloqueio por violação, pelo presidente da mesa
+    ClearTamperLockdown { reason: String },
+    /// Justificativa de ausência de eleitor de outra seção
+    RecordJustification { voter_title: String, reason: String },
+    /// Resumo do dia e fechamento da sessão, pelo presidente da mesa
+    CloseSession,
+    EnterMaintenance { reason: String },
+    ExitMaintenance,
+    RestartSubsystem { subsystem: Subsystem },
+    /// Exporta o pacote final de provas e apaga a urna ao fim do ciclo
+    Decommission,
+    Shutdown,
+}
+
+impl MesarioCommand {
+    /// Comandos aceitos sem mesário identificado no terminal
+    fn is_open(&self) -> bool {
+        matches!(
+            self,
+            MesarioCommand::Login { .. }
+                | MesarioCommand::Logout
+                | MesarioCommand::ResetSessionTimeout { .. }
+                | MesarioCommand::Shutdown
+        )
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MesarioCommand::Login { .. } => "login",
+            MesarioCommand::Logout => "logout",
+            MesarioCommand::OpenSession { .. } => "open_session",
+            MesarioCommand::OverrideFeedbackCheck { .. } => "override_feedback_check",
+            MesarioCommand::AdmitVoter { .. } => "admit_voter",
+            MesarioCommand::ReleaseVoter { .. } => "release_voter",
+            MesarioCommand::SetAccessibility { .. } => "set_accessibility",
+            MesarioCommand::CancelVoter => "cancel_voter",
+            MesarioCommand::ResetSessionTimeout { .. } => "reset_session_timeout",
+            MesarioCommand::AuthorizeBallotRestart => "authorize_ballot_restart",
+            MesarioCommand::ClearTamperLockdown { .. } => "clear_tamper_lockdown",
+            MesarioCommand::RecordJustification { .. } => "record_justification",
+            MesarioCommand::CloseSession => "close_session",
+            MesarioCommand::EnterMaintenance { .. } => "enter_maintenance",
+            MesarioCommand::ExitMaintenance => "exit_maintenance",
+            MesarioCommand::RestartSubsystem { .. } => "restart_subsystem",
+            MesarioCommand::Decommission => "decommission",
+            MesarioCommand::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Limites do watchdog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogPolicy {
+    pub check_interval_seconds: u64,
+    /// Reinícios automáticos de um subsistema dentro da janela
+    pub max_restarts: usize,
+    pub restart_window_seconds: i64,
+    /// Atendimentos seguidos com falha que antecipam a verificação
+    pub max_consecutive_session_failures: u32,
+    pub init_retry_seconds: u64,
+    /// Intervalo do heartbeat enviado ao backend
+    pub heartbeat_interval_seconds: u64,
+}
+
+impl Default for WatchdogPolicy {
+    fn default() -> Self {
+        Self {
+            check_interval_seconds: 15,
+            max_restarts: 3,
+            restart_window_seconds: 600,
+            max_consecutive_session_failures: 3,
+            init_retry_seconds: 30,
+            heartbeat_interval_seconds: 60,
+        }
+    }
+}
+
+/// Máquina de estados da urna dirigida pelos comandos do mesário
+pub struct SessionSupervisor {
+    app: VotingApp,
+    commands: mpsc::Receiver<MesarioCommand>,
+    policy: WatchdogPolicy,
+    mode: UrnaMode,
+    restarts: HashMap<Subsystem, Vec<DateTime<Utc>>>,
+    session_failures: u32,
+    /// Comandos recebidos durante um atendimento, tratados ao final dele
+    deferred: VecDeque<MesarioCommand>,
+    terminal: MesarioTerminal,
+}
+
+impl SessionSupervisor {
+    pub fn new(app: VotingApp, commands: mpsc::Receiver<MesarioCommand>, policy: WatchdogPolicy) -> Self {
+        Self {
+            app,
+            commands,
+            policy,
+            mode: UrnaMode::Maintenance,
+            restarts: HashMap::new(),
+            session_failures: 0,
+            deferred: VecDeque::new(),
+            terminal: MesarioTerminal::load(),
+        }
+    }
+
+    /// Inicializa a urna e atende os comandos até o desligamento
+    pub async fn run(mut self) -> Result<()> {
+        self.start().await;
+
+        let mut watchdog = tokio::time::interval(std::time::Duration::from_secs(self.policy.check_interval_seconds));
+        let mut tamper_check = tokio::time::interval(std::time::Duration::from_secs(
+            self.app.hardware.tamper.policy.poll_interval_seconds.max(1),
+        ));
+        loop {
+            let command = match self.deferred.pop_front() {
+                Some(command) => command,
+                None => tokio::select! {
+                    command = self.commands.recv() => match command {
+                        Some(command) => command,
+                        None => return Err(anyhow!("Mesário command channel closed")),
+                    },
+                    _ = watchdog.tick() => {
+                        self.check_subsystems().await;
+                        if self.mode == UrnaMode::AwaitingAttendant {
+                            self.alert_attendant().await;
+                        }
+                        continue;
+                    }
+                    _ = tamper_check.tick() => {
+                        self.check_tamper().await;
+                        continue;
+                    }
+                },
+            };
+
+            if let MesarioCommand::Shutdown = command {
+                if matches!(self.mode, UrnaMode::Voting | UrnaMode::AwaitingAttendant | UrnaMode::InterruptedBallot) {
+                    log::warn!("Shutdown refused while the voting session is open");
+                    continue;
+                }
+                log::info!("Shutting down at mesário request");
+                return Ok(());
+            }
+
+            if let Err(e) = self.handle(command).await {
+                log::warn!("Mesário command failed: {}", e);
+                self.show_mode().await;
+            }
+        }
+    }
+
+    /// Inicialização com novas tentativas; a urna fica em manutenção até concluir
+    async fn start(&mut self) {
+        let mut attempt = 0;
+        while let Err(e) = self.app.initialize().await {
+            attempt += 1;
+            log::error!("Initialization failed (attempt {}): {}", attempt, e);
+            self.show_mode().await;
+            tokio::time::sleep(std::time::Duration::from_secs(self.policy.init_retry_seconds)).await;
+        }
+
+        // Sessão lacrada antes de um reinício continua em votação, salvo atendimento interrompido
+        let mode = match self.recover_interrupted_ballot().await {
+            Some(mode) => mode,
+            None => self.resume_mode().await,
+        };
+        self.set_mode(mode, "initialized").await;
+
+        // Bloqueio por violação sobrevive ao reinício
+        self.check_tamper().await;
+        if self.app.hardware.tamper.is_locked().await {
+            self.set_mode(UrnaMode::TamperLockdown, "tamper lockdown still in effect").await;
+        }
+    }
+
+    /// Resolve o atendimento deixado pelo reinício; devolve o modo que ele impõe
+    async fn recover_interrupted_ballot(&mut self) -> Option<UrnaMode> {
+        match self.app.recover_interrupted_ballot().await {
+            Ok(None) => None,
+            Ok(Some(RecoveredBallot::Recorded(entry))) => {
+                // O diário só é apagado depois de o livro do dia marcar o votante
+                match self.terminal.mark_voted(entry.sequence) {
+                    Ok(()) => {
+                        if let Err(e) = self.app.journal.close().await {
+                            log::error!("Failed to close ballot journal: {}", e);
+                        }
+                    }
+                    Err(e) => log::error!("Failed to mark voter {} as voted: {}", entry.sequence, e),
+                }
+                None
+            }
+            Ok(Some(RecoveredBallot::Voided(entry))) => {
+                log::warn!("Voter {} must restart with mesário authorization", entry.sequence);
+                Some(UrnaMode::InterruptedBallot)
+            }
+            Err(e) => {
+                log::error!("Interrupted ballot recovery failed: {}", e);
+                self.audit("InterruptedBallotRecoveryFailed", serde_json::json!({
+                    "error": e.to_string(),
+                    "timestamp": Utc::now()
+                })).await;
+                Some(UrnaMode::Maintenance)
+            }
+        }
+    }
+
+    async fn handle(&mut self, command: MesarioCommand) -> Result<()> {
+        let name = command.name();
+        let operator = self.terminal.operator().ok().map(|operator| operator.mesario_id.clone());
+        if !command.is_open() && operator.is_none() {
+            self.audit("MesarioCommandRejected", serde_json::json!({
+                "command": name,
+                "reason": "not logged in",
+                "timestamp": Utc::now()
+            })).await;
+            return Err(anyhow!("Command {} requires a mesário logged in at the terminal", name));
+        }
+
+        let result = self.dispatch(command).await;
+        self.audit("MesarioCommand", serde_json::json!({
+            "command": name,
+            "mesario_id": operator,
+            "mode": self.mode,
+            "success": result.is_ok(),
+            "error": result.as_ref().err().map(|e| e.to_string()),
+            "timestamp": Utc::now()
+        })).await;
+        result
+    }
+
+    async fn dispatch(&mut self, command: MesarioCommand) -> Result<()> {
+        match (self.mode, command) {
+            (_, MesarioCommand::Login { mesario_id, pin }) => {
+                let session = self.terminal.login(&mesario_id, &pin)?;
+                log::info!("Mesário {} ({:?}) logged in", session.mesario_id, session.role);
+                Ok(())
+            }
+            (_, MesarioCommand::Logout) => {
+                if let Some(session) = self.terminal.logout() {
+                    log::info!("Mesário {} logged out", session.mesario_id);
+                }
+                Ok(())
+            }
+            (UrnaMode::Idle, MesarioCommand::OpenSession { election_id }) => {
+                let opened = self.app.traced(SessionStep::StartSession, self.app.start_voting_session(election_id)).await;
+                let mode = self.resume_mode().await;
+                self.set_mode(mode, "session opened").await;
+                opened
+            }
+            (UrnaMode::Idle, MesarioCommand::OverrideFeedbackCheck { mesario_id, reason }) => {
+                self.app.override_feedback_check(&mesario_id, &reason).await
+            }
+            (UrnaMode::Voting, MesarioCommand::AdmitVoter { sequence }) => {
+                let snapshot = provisioning::load_voter_snapshot(Path::new(provisioning::PROVISIONING_DIR)).await?;
+                let admission = self.terminal.admit(sequence, &snapshot)?;
+                log::info!("Voter {} admitted by mesário {}", admission.sequence, admission.admitted_by);
+                Ok(())
+            }
+            (UrnaMode::Voting, MesarioCommand::ReleaseVoter { accessibility, language }) => {
+                let admission = self.terminal.pending().cloned()
+                    .ok_or_else(|| anyhow!("No voter admitted at the terminal"))?;
+                let election_id = self.app.state.lock().await.current_election
+                    .ok_or_else(|| anyhow!("No election open"))?;
+                self.app.journal.begin(election_id, admission.sequence).await?;
+                self.attend_voter(admission, accessibility, language).await;
+                Ok(())
+            }
+            (UrnaMode::Idle | UrnaMode::Voting, MesarioCommand::RecordJustification { voter_title, reason }) => {
+                let snapshot = provisioning::load_voter_snapshot(Path::new(provisioning::PROVISIONING_DIR)).await?;
+                let justification = self.terminal.record_justification(&voter_title, &reason, &snapshot)?;
+                self.audit("AbsenceJustified", serde_json::json!({ "justification": justification })).await;
+                Ok(())
+            }
+            (UrnaMode::AwaitingAttendant, MesarioCommand::ResetSessionTimeout { mesario_id, pin }) => {
+                let verified = self.app.attendant.verify_pin(&pin).await;
+                self.audit("AttendantOverride", serde_json::json!({
+                    "mesario_id": mesario_id,
+                    "accepted": verified.is_ok(),
+                    "timestamp": Utc::now()
+                })).await;
+                verified?;
+                self.set_mode(UrnaMode::Voting, "reset by mesário after timeout").await;
+                Ok(())
+            }
+            (UrnaMode::InterruptedBallot, MesarioCommand::AuthorizeBallotRestart) => {
+                let entry = self.app.journal.interrupted().await?
+                    .filter(|entry| entry.phase == BallotPhase::Voided)
+                    .ok_or_else(|| anyhow!("No voided ballot session awaiting authorization"))?;
+                self.app.journal.close().await?;
+                self.audit("BallotRestartAuthorized", serde_json::json!({
+                    "session_id": entry.session_id,
+                    "sequence": entry.sequence,
+                    "mesario_id": self.terminal.operator()?.mesario_id,
+                    "timestamp": Utc::now()
+                })).await;
+                let mode = self.resume_mode().await;
+                self.set_mode(mode, "ballot restart authorized by mesário").await;
+                Ok(())
+            }
+            (UrnaMode::TamperLockdown, MesarioCommand::ClearTamperLockdown { reason }) => {
+                let operator = self.terminal.operator()?.clone();
+                if operator.role != MesarioRole::President {
+                    return Err(anyhow!("Only the section president can clear the tamper lockdown"));
+                }
+                let locked_at = self.app.hardware.tamper.clear().await?;
+                self.audit("TamperLockdownCleared", serde_json::json!({
+                    "mesario_id": operator.mesario_id,
+                    "reason": reason,
+                    "locked_at": locked_at,
+                    "timestamp": Utc::now()
+                })).await;
+                let mode = self.resume_mode().await;
+                self.set_mode(mode, "tamper lockdown cleared by the section president").await;
+                Ok(())
+            }
+            (UrnaMode::Voting, MesarioCommand::CloseSession) => {
+                let snapshot = provisioning::load_voter_snapshot(Path::new(provisioning::PROVISIONING_DIR)).await?;
+                let report = self.terminal.closing_report(&snapshot)?;
+                let authorization = CloseAuthorization::load(Path::new(lockdown::CLOSE_AUTHORIZATION_PATH)).await?;
+                // Os candidatos são descartados no encerramento
+                let documents = self.closing_documents(&report).await;
+                self.app.traced(SessionStep::EndSession, self.app.end_voting_session(&authorization)).await?;
+                if let Err(e) = self.app.trace.finish(Path::new(replay::TRACE_DIR)).await {
+                    log::warn!("Failed to export session trace: {}", e);
+                }
+                if let Err(e) = self.terminal.store_closing_report(&report) {
+                    log::warn!("Failed to store closing report: {}", e);
+                }
+                match documents {
+                    Ok(documents) => {
+                        for document in &documents {
+                            if let Err(e) = self.app.hardware.print_document(document).await {
+                                log::warn!("Failed to print closing document: {}", e);
+                            }
+                        }
+                    }
+                    Err(e) => log::warn!("Closing documents unavailable: {}", e),
+                }
+                self.audit("SectionClosed", serde_json::json!({
+                    "report": report,
+                    "timestamp": Utc::now()
+                })).await;
+                self.set_mode(UrnaMode::Idle, "session closed").await;
+                Ok(())
+            }
+            (UrnaMode::Idle | UrnaMode::Voting, MesarioCommand::EnterMaintenance { reason }) => {
+                self.set_mode(UrnaMode::Maintenance, &reason).await;
+                Ok(())
+            }
+            (UrnaMode::Maintenance, MesarioCommand::ExitMaintenance) => {
+                let mode = self.resume_mode().await;
+                self.set_mode(mode, "maintenance finished").await;
+                Ok(())
+            }
+            (UrnaMode::Maintenance, MesarioCommand::RestartSubsystem { subsystem }) => {
+                self.restart(subsystem, "mesário").await
+            }
+            (UrnaMode::Maintenance, MesarioCommand::Decommission) => {
+                self.app.decommission(Path::new(decommission::DECOMMISSION_MEDIA_PATH)).await?;
+                self.set_mode(UrnaMode::Decommissioned, "decommissioned").await;
+                Ok(())
+            }
+            (_, MesarioCommand::CancelVoter | MesarioCommand::SetAccessibility { .. }) => {
+                Err(anyhow!("No voter session in progress"))
+            }
+            (mode, command) => Err(anyhow!("Command {} not allowed in {:?} mode", command.name(), mode)),
+        }
+    }
+
+    /// Atende o eleitor admitido em tarefa isolada, aceitando o cancelamento pelo mesário
+    async fn attend_voter(
+        &mut self,
+        admission: Admission,
+        accessibility: Option<AccessibilityProfile>,
+        language: Option<String>,
+    ) {
+        self.audit("VoterReleased", serde_json::json!({
+            "sequence": admission.sequence,
+            "admitted_by": admission.admitted_by,
+            "timestamp": Utc::now()
+        })).await;
+        let app = self.app.clone();
+        let committing = Arc::new(AtomicBool::new(false));
+        let session_committing = committing.clone();
+        self.app.ui.input.activity.touch();
+        let mut session = tokio::spawn(async move {
+            voter_session(app, admission, accessibility, language, session_committing).await
+        });
+
+        let idle_timeout = self.app.attendant.policy.idle_timeout();
+        let mut idle_check = tokio::time::interval(std::time::Duration::from_secs(1));
+        let mut timed_out = false;
+        let mut tampered = false;
+        let outcome = loop {
+            let command = tokio::select! {
+                joined = &mut session => break joined,
+                command = self.commands.recv() => Some(command),
+                _ = idle_check.tick() => None,
+            };
+            let Some(command) = command else {
+                match self.app.check_tamper().await {
+                    Ok(events) if !events.is_empty() => tampered = true,
+                    Ok(_) => {}
+                    Err(e) => log::error!("Tamper sensors check failed: {}", e),
+                }
+                // Depois da confirmação o voto termina de ser registrado
+                if tampered && !committing.load(Ordering::SeqCst) {
+                    session.abort();
+                    break (&mut session).await;
+                }
+                if !committing.load(Ordering::SeqCst) && self.app.ui.input.activity.idle() >= idle_timeout {
+                    timed_out = true;
+                    session.abort();
+                    break (&mut session).await;
+                }
+                continue;
+            };
+            match command {
+                Some(MesarioCommand::CancelVoter) if committing.load(Ordering::SeqCst) => {
+                    log::warn!("Vote is being recorded, cancel ignored");
+                }
+                Some(MesarioCommand::CancelVoter) => {
+                    session.abort();
+                    break (&mut session).await;
+                }
+                Some(MesarioCommand::SetAccessibility { profile }) => {
+                    if let Err(e) = self.app.ui.set_accessibility(profile).await {
+                        log::warn!("Failed to change accessibility profile: {}", e);
+                    }
+                }
+                Some(command) => self.deferred.push_back(command),
+                None => break (&mut session).await,
+            }
+        };
+        self.app.state.lock().await.current_voter = None;
+        if let Err(e) = self.app.ui.end_voter_session().await {
+            log::warn!("Failed to reset accessibility profile: {}", e);
+        }
+        // Sem voto registrado, o eleitor pode ser admitido de novo; voto na
+        // fila de saída conta mesmo que o comprovante tenha falhado. Se o
+        // livro do dia não for gravado, o diário fica aberto e bloqueia a
+        // próxima liberação até o reinício resolver o atendimento
+        let recorded = matches!(outcome, Ok(Ok(_))) || self.app.journal.phase().await == Some(BallotPhase::Recorded);
+        if let Err(e) = self.terminal.finish_admission(recorded) {
+            log::warn!("Failed to record admission outcome: {}", e);
+        } else if let Err(e) = self.app.journal.close().await {
+            log::error!("Failed to close ballot journal: {}", e);
+        }
+
+        match outcome {
+            Ok(Ok(vote_id)) => {
+                self.session_failures = 0;
+                log::info!("Voter session finished with vote {}", vote_id);
+                self.show_mode().await;
+            }
+            Ok(Err(e)) => self.session_failed(&e).await,
+            Err(e) if e.is_cancelled() && tampered => {
+                log::warn!("Voter session interrupted by tamper detection");
+                self.audit("VoterSessionTamperAborted", serde_json::json!({ "timestamp": Utc::now() })).await;
+            }
+            Err(e) if e.is_cancelled() && timed_out => self.session_timed_out().await,
+            Err(e) if e.is_cancelled() => {
+                log::info!("Voter session cancelled by mesário");
+                self.audit("VoterSessionCancelled", serde_json::json!({ "timestamp": Utc::now() })).await;
+                self.show_mode().await;
+            }
+            Err(e) => self.session_failed(&anyhow!("Voter session panicked: {}", e)).await,
+        }
+        if tampered {
+            self.set_mode(UrnaMode::TamperLockdown, "tamper detected during voter session").await;
+        }
+    }
+
+    /// Lê os sensores de violação e trava a urna ao primeiro evento
+    async fn check_tamper(&mut self) {
+        // Urna apagada não guarda mais nada a proteger
+        if self.mode == UrnaMode::Decommissioned {
+            return;
+        }
+        match self.app.check_tamper().await {
+            Ok(events) if !events.is_empty() => {
+                let sources: Vec<&str> = events.iter().map(|event| event.source.as_str()).collect();
+                self.set_mode(UrnaMode::TamperLockdown, &format!("tamper detected: {}", sources.join(", "))).await;
+                self.alert_attendant().await;
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Tamper sensors check failed: {}", e),
+        }
+    }
+
+    /// Bloqueia a urna até a liberação do mesário
+    async fn session_timed_out(&mut self) {
+        let idle_timeout = self.app.attendant.policy.idle_timeout_seconds;
+        log::warn!("Voter session timed out after {}s without input", idle_timeout);
+        self.audit("VoterSessionTimedOut", serde_json::json!({
+            "idle_timeout_seconds": idle_timeout,
+            "timestamp": Utc::now()
+        })).await;
+        self.set_mode(UrnaMode::AwaitingAttendant, "voter session timed out").await;
+        self.alert_attendant().await;
+    }
+
+    /// Alerta sonoro para chamar o mesário
+    async fn alert_attendant(&self) {
+        if let Err(e) = self.app.ui.audio.play_beep().await {
+            log::warn!("Failed to sound attendant alert: {}", e);
+        }
+    }
+
+    /// Tela de recuperação e contagem de falhas seguidas
+    async fn session_failed(&mut self, error: &anyhow::Error) {
+        self.session_failures += 1;
+        log::error!("Voter session failed ({} in a row): {}", self.session_failures, error);
+        self.audit("VoterSessionFailed", serde_json::json!({
+            "error_code": messages::error_code(error),
+            "consecutive_failures": self.session_failures,
+            "timestamp": Utc::now()
+        })).await;
+        self.app.faults.record_error(error).await;
+
+        if let Err(e) = self.app.show_voter_error(error).await {
+            log::error!("Recovery screen failed: {}", e);
+            if let Err(e) = self.watchdog_restart(Subsystem::Ui, "recovery screen failed").await {
+                log::error!("{}", e);
+            }
+        }
+
+        if self.session_failures >= self.policy.max_consecutive_session_failures {
+            self.check_subsystems().await;
+        }
+        self.show_mode().await;
+    }
+
+    /// Verificação periódica do watchdog
+    async fn check_subsystems(&mut self) {
+        // Urna apagada não tem mais o que reiniciar
+        if self.mode == UrnaMode::Decommissioned {
+            return;
+        }
+        for subsystem in WATCHED_SUBSYSTEMS {
+            if let Err(e) = self.probe(subsystem).await {
+                log::warn!("Watchdog: {:?} failed health check: {}", subsystem, e);
+                if let Err(e) = self.watchdog_restart(subsystem, &e.to_string()).await {
+                    log::error!("{}", e);
+                }
+            }
+        }
+    }
+
+    async fn probe(&self, subsystem: Subsystem) -> Result<()> {
+        match subsystem {
+            Subsystem::Hardware => {
+                if self.app.hardware.is_ready().await? {
+                    Ok(())
+                } else {
+                    Err(anyhow!("hardware not ready"))
+                }
+            }
+            // Urna offline não é falha; só o erro da verificação
+            Subsystem::Sync => self.app.sync.check_connectivity().await.map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reinício automático, limitado por janela; acima do limite, manutenção
+    async fn watchdog_restart(&mut self, subsystem: Subsystem, reason: &str) -> Result<()> {
+        let now = Utc::now();
+        let window = Duration::seconds(self.policy.restart_window_seconds);
+        let history = self.restarts.entry(subsystem).or_default();
+        history.retain(|at| now - *at < window);
+        if history.len() >= self.policy.max_restarts {
+            self.set_mode(UrnaMode::Maintenance, &format!("{:?} exceeded restart limit", subsystem)).await;
+            return Err(anyhow!(
+                "{:?} restarted {} times in {}s, waiting for the mesário",
+                subsystem, self.policy.max_restarts, self.policy.restart_window_seconds
+            ));
+        }
+        history.push(now);
+        self.restart(subsystem, reason).await
+    }
+
+    async fn restart(&mut self, subsystem: Subsystem, reason: &str) -> Result<()> {
+        log::warn!("Restarting {:?}: {}", subsystem, reason);
+        let restarted = match subsystem {
+            Subsystem::Hardware => self.app.hardware.initialize().await,
+            Subsystem::Auth => self.app.auth.initialize().await,
+            Subsystem::Ui => self.app.ui.initialize().await,
+            Subsystem::Crypto => self.app.crypto.initialize().await,
+            Subsystem::Sync => self.app.sync.initialize().await,
+            Subsystem::Outbox => self.app.outbox.initialize().await,
+            Subsystem::Audit => self.app.audit.initialize().await,
+        };
+        self.audit("SubsystemRestarted", serde_json::json!({
+            "subsystem": subsystem,
+            "reason": reason,
+            "success": restarted.is_ok(),
+            "timestamp": Utc::now()
+        })).await;
+        restarted
+    }
+
+    /// Votação enquanto a configuração estiver lacrada
+    async fn resume_mode(&self) -> UrnaMode {
+        if self.app.lockdown.is_sealed().await {
+            UrnaMode::Voting
+        } else {
+            UrnaMode::Idle
+        }
+    }
+
+    async fn set_mode(&mut self, mode: UrnaMode, reason: &str) {
+        if self.mode == mode {
+            return;
+        }
+        log::info!("Urna mode {:?} -> {:?}: {}", self.mode, mode, reason);
+        self.audit("UrnaModeChanged", serde_json::json!({
+            "from": self.mode,
+            "to": mode,
+            "reason": reason,
+            "timestamp": Utc::now()
+        })).await;
+        self.mode = mode;
+        self.show_mode().await;
+    }
+
+    async fn show_mode(&self) {
+        let (title, detail) = match self.mode {
+            UrnaMode::Idle => ("URNA AGUARDANDO", "Aguardando abertura da sessão pelo mesário"),
+            UrnaMode::Voting => ("URNA PRONTA", "Aguarde a liberação pelo mesário"),
+            UrnaMode::Maintenance => ("URNA EM MANUTENÇÃO", "Procure o mesário"),
+            UrnaMode::AwaitingAttendant => ("TEMPO ESGOTADO", "Mesário: pressione a tecla de liberação e digite o PIN"),
+            UrnaMode::InterruptedBallot => ("VOTAÇÃO INTERROMPIDA", "Atendimento anulado sem voto. Mesário: autorize o recomeço e admita o eleitor novamente"),
+            UrnaMode::TamperLockdown => ("URNA BLOQUEADA", "Violação do equipamento detectada. Votação suspensa; chame o presidente da mesa"),
+            UrnaMode::Decommissioned => ("URNA DESCOMISSIONADA", "Dados apagados; pode ser desligada"),
+        };
+        let detail = match &self.app.state.lock().await.storage_report {
+            Some(report) if report.status != StorageStatus::Sufficient && self.mode != UrnaMode::Decommissioned => {
+                format!("{}\nPouco espaço de armazenamento: {}", detail, report.summary())
+            }
+            _ => detail.to_string(),
+        };
+        if let Err(e) = self.app.ui.show_mode_screen(title, &detail).await {
+            log::warn!("Failed to show {:?} screen: {}", self.mode, e);
+        }
+    }
+
+    /// Boletim de urna e ata da mesa receptora do encerramento
+    async fn closing_documents(&self, report: &ClosingReport) -> Result<[Document; 2]> {
+        let urna_id = self.app.sync.urna_id().await.unwrap_or_default();
+        let state = self.app.state.lock().await;
+        let election_id = state.current_election.ok_or_else(|| anyhow!("No voting session in progress"))?;
+        let section = state.current_section.clone().ok_or_else(|| anyhow!("Urna has no provisioned section"))?;
+
+        let bulletin = templates::bulletin(&BulletinData {
+            election: election_id.to_string(),
+            state: section.state.clone(),
+            municipality_code: section.municipality_code.clone(),
+            zone: section.zone.clone(),
+            section: section.section.clone(),
+            urna_id,
+            registered_voters: report.registered_voters as u64,
+            voters_attended: state.attendance_count,
+            contests: templates::bulletin_contests(&state.candidate_list, &state.votes_by_candidate),
+            emitted_at: report.closed_at,
+        });
+        let president = self.terminal.mesario_name(&report.closed_by).unwrap_or(&report.closed_by);
+        let minutes = templates::section_minutes(&SectionMinutesData {
+            election: election_id.to_string(),
+            state: section.state,
+            municipality_code: section.municipality_code,
+            zone: report.zone.clone(),
+            section: report.section.clone(),
+            registered_voters: report.registered_voters as u64,
+            voted: report.voted as u64,
+            absent: report.absent as u64,
+            admitted_without_vote: report.admitted_without_vote.clone(),
+            justifications: report.justifications as u64,
+            occurrences: Vec::new(),
+            president: president.to_string(),
+            closed_at: report.closed_at,
+        });
+        Ok([bulletin, minutes])
+    }
+
+    /// Falha do log de auditoria não interrompe o supervisor
+    async fn audit(&self, event_type: &str, data: serde_json::Value) {
+        if let Err(e) = self.app.audit.log_event(event_type, &data).await {
+            log::warn!("Failed to record {}: {}", event_type, e);
+        }
+    }
+}
+
+/// Atendimento de um eleitor, da identificação ao comprovante
+async fn voter_session(
+    app: VotingApp,
+    admission: Admission,
+    accessibility: Option<AccessibilityProfile>,
+    language: Option<String>,
+    committing: Arc<AtomicBool>,
+) -> Result<Uuid> {
+    app.ui.start_voter_session(accessibility, language.as_deref()).await?;
+    let voter_id = app.traced(SessionStep::Authenticate, app.authenticate_voter()).await?;
+    if voter_id.to_string() != admission.voter_id {
+        return Err(UrnaError::new(
+            codes::VOTER_NOT_ADMITTED,
+            &format!("Authenticated voter is not voter {} admitted at the terminal", admission.sequence),
+        ).into());
+    }
+    app.journal.authenticated(&contingency::voter_hash(&voter_id)).await?;
+    let choices = app.traced(SessionStep::SelectCandidate, app.show_candidate_selection()).await?;
+
+    // A partir daqui o voto é registrado; o mesário não pode mais cancelar
+    committing.store(true, Ordering::SeqCst);
+    let vote_id = app.traced(SessionStep::CastVote, app.cast_vote(choices)).await?;
+    app.traced(SessionStep::PrintReceipt, app.print_receipt(vote_id)).await?;
+    Ok(vote_id)
+}
+
+/// Envia o heartbeat periodicamente (bateria, impressora, fila e falhas por código), independente do modo e dos atendimentos
+pub fn spawn_heartbeat(app: VotingApp, interval_seconds: u64) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+        loop {
+            interval.tick().await;
+            let power = app.hardware.ups.power().await;
+            let (last_sync, section) = {
+                let state = app.state.lock().await;
+                (state.last_sync, state.current_section.clone())
+            };
+            let faults = app.faults.pending().await;
+            let health = UrnaHealth {
+                pending_votes: app.outbox.depth().await as u64,
+                errors: Vec::new(),
+                battery_level: power.map(|power| power.battery_level),
+                on_battery: power.map(|power| power.on_battery),
+                printer: Some(app.hardware.printer.state().await),
+                last_sync,
+                last_error: app.faults.last_error().await,
+                faults: faults.clone(),
+                section,
+            };
+            match app.sync.send_heartbeat(health).await {
+                Ok(()) => app.faults.acknowledge(&faults).await,
+                Err(e) => log::warn!("Heartbeat failed on every backend: {}", e),
+            }
+        }
+    });
+}
+
+/// Lê os comandos do terminal do mesário e reabre o canal quando fechado
+pub fn spawn_command_reader(path: PathBuf, commands: mpsc::Sender<MesarioCommand>) {
+    tokio::spawn(async move {
+        loop {
+            match tokio::fs::File::open(&path).await {
+                Ok(file) => {
+                    let mut lines = BufReader::new(file).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        if line.trim().is_empty() {
+                            continue;
+                        }
+                        match serde_json::from_str::<MesarioCommand>(&line) {
+                            Ok(command) => {
+                                if commands.send(command).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(e) => log::warn!("Invalid mesário command: {}", e),
+                        }
+                    }
+                }
+                Err(e) => log::warn!("Mesário command channel {} unavailable: {}", path.display(), e),
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    });
+}
@@ -1,11 +1,74 @@
 //! Módulo de sincronização com logs transparentes para urna eletrônica
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::{EncryptedVote, VoteSyncStatus};
+use crate::endpoints::{self, EndpointPool, FailoverPolicy};
+use crate::package::{self, ElectionPackage, SignedElectionPackage};
+use crate::receipt::InclusionData;
+use crate::telemetry;
+use fortis_domain::{
+    FaultReport, HeartbeatError, PrinterState, ProofError, SectionRef, SignedDecommissionReport, SignedVoteBatch, UrnaHeartbeat,
+    REQUEST_ID_HEADER, TRANSPARENCY_LOG_KEY_PURPOSE,
+};
+use fortis_domain::attestation::{AttestationChallenge, AttestationReport, AttestationResult};
+use fortis_domain::tamper::TamperEvent;
+use fortis_domain::update::{InstallReport, UpdateComponent, UpdateManifest, UpdateVersion};
+use fortis_domain::vote_sync::{VoteSyncAck, VoteSyncChunk, VoteSyncResult, IDEMPOTENCY_KEY_HEADER};
+
+/// Identificador da urna gravado na preparação
+pub const URNA_ID_PATH: &str = "/etc/fortis/urna_id";
+
+/// Sequência e lote assinado ainda não confirmado pelo backend
+pub const VOTE_BATCH_DIR: &str = "/var/lib/fortis/batches";
+
+/// Estado do envio em partes em andamento
+pub const VOTE_SYNC_DIR: &str = "/var/lib/fortis/vote_sync";
+
+/// Backend usado quando a preparação não gravou a lista de backends
+pub const DEFAULT_API_URL: &str = "https://api.fortis.gov.br";
+
+/// Saúde da urna levada no heartbeat
+#[derive(Debug, Clone, Default)]
+pub struct UrnaHealth {
+    pub pending_votes: u64,
+    pub errors: Vec<String>,
+    pub battery_level: Option<f32>,
+    pub on_battery: Option<bool>,
+    pub printer: Option<PrinterState>,
+    pub last_sync: Option<DateTime<Utc>>,
+    pub last_error: Option<HeartbeatError>,
+    pub faults: Vec<FaultReport>,
+    pub section: Option<SectionRef>,
+}
+
+/// Envio em partes ainda não concluído, retomado após queda do link
+#[derive(Debug, Serialize, Deserialize)]
+struct VoteUpload {
+    upload_id: Uuid,
+    batch_size: usize,
+    vote_ids: Vec<Uuid>,
+    acknowledged: BTreeSet<u32>,
+    results: Vec<VoteSyncResult>,
+}
 
-use crate::EncryptedVote;
+/// Conferência local da inclusão de um voto aceito pelo backend
+#[derive(Debug)]
+pub enum InclusionCheck {
+    /// STH assinada pelo log e caminho Merkle até a raiz conferem
+    Verified,
+    /// Sem prova ou sem chave do log para conferir; o voto segue pendente
+    Unverified(String),
+    /// Prova inválida: o voto volta a pendente e o evento é auditado
+    Invalid(ProofError),
+}
 
 pub struct TransparencySync {
     pub log_url: String,
@@ -14,6 +77,14 @@ pub struct TransparencySync {
     pub is_online: bool,
     pub retry_count: u32,
     pub max_retries: u32,
+    /// Backends regionais e nacional, com failover
+    pub endpoints: EndpointPool,
+    pub package_dir: PathBuf,
+    /// Votos por parte no envio em lote
+    pub vote_batch_size: usize,
+    /// Nível zstd das partes enviadas
+    pub compression_level: i32,
+    client: reqwest::Client,
 }
 
 impl TransparencySync {
@@ -29,6 +100,15 @@ impl TransparencySync {
             is_online: false,
             retry_count: 0,
             max_retries: 3,
+            endpoints: EndpointPool::load(
+                Path::new(endpoints::ENDPOINTS_PATH),
+                DEFAULT_API_URL,
+                FailoverPolicy::default(),
+            )?,
+            package_dir: PathBuf::from("/var/lib/fortis/packages"),
+            vote_batch_size: 500,
+            compression_level: 3,
+            client: crate::transport::client_builder()?.build()?,
         })
     }
 
@@ -49,12 +129,102 @@ impl TransparencySync {
         log::debug!("Checking transparency connectivity");
 
         // Em implementação real, faria ping para logs
-        let is_online = self.ping_logs().await?;
-        
+        let logs_online = self.ping_logs().await?;
+        let backend_online = self.probe_endpoints().await;
+
+        let is_online = logs_online && backend_online;
         log::debug!("Transparency connectivity: {}", is_online);
         Ok(is_online)
     }
 
+    /// Mede todos os backends conhecidos; alimenta a reavaliação do ativo
+    async fn probe_endpoints(&self) -> bool {
+        let mut any_online = false;
+        for url in self.endpoints.urls() {
+            let started = Instant::now();
+            let result = async {
+                self.client
+                    .get(format!("{}/health", url))
+                    .timeout(std::time::Duration::from_secs(5))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                Ok::<(), anyhow::Error>(())
+            }.await;
+            match result {
+                Ok(()) => {
+                    self.endpoints.record_success(&url, started.elapsed());
+                    any_online = true;
+                }
+                Err(e) => self.endpoints.record_failure(&url, &e.to_string()),
+            }
+        }
+        any_online
+    }
+
+    /// Envia a requisição ao backend ativo e, se ele não responder ou
+    /// responder com erro 5xx, aos demais na ordem do `EndpointPool`.
+    /// Respostas 4xx são da aplicação e não contam contra o backend.
+    ///
+    /// Todas as tentativas levam o mesmo `X-Request-Id`, que correlaciona a
+    /// chamada com os logs e eventos de auditoria do backend, e o
+    /// `traceparent` do span em andamento.
+    async fn send_with_failover<F>(&self, build: F) -> Result<reqwest::Response>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let request_id = Uuid::new_v4().to_string();
+        let mut last_error = None;
+        for url in self.endpoints.candidates() {
+            let started = Instant::now();
+            let request = telemetry::inject(build(&url).header(REQUEST_ID_HEADER, &request_id));
+            let error = match request.send().await {
+                Ok(response) if !response.status().is_server_error() => {
+                    self.endpoints.record_success(&url, started.elapsed());
+                    return Ok(response);
+                }
+                Ok(response) => anyhow!("HTTP {}", response.status()),
+                Err(e) => e.into(),
+            };
+            log::warn!("Sync backend {} failed (request {}): {}", url, request_id, error);
+            self.endpoints.record_failure(&url, &error.to_string());
+            last_error = Some(error);
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No backend endpoint configured")))
+    }
+
+    /// Envia o heartbeat com a saúde da urna e as medições de cada backend
+    pub async fn send_heartbeat(&self, health: UrnaHealth) -> Result<()> {
+        let urna_id = self.urna_id().await?;
+        let endpoints = self.endpoints.metrics();
+        let heartbeat = UrnaHeartbeat {
+            urna_id: urna_id.clone(),
+            timestamp: Utc::now(),
+            battery_level: health.battery_level,
+            on_battery: health.on_battery,
+            storage_usage: None,
+            network_connectivity: endpoints.iter().any(|e| e.available && e.consecutive_failures == 0),
+            errors: health.errors,
+            pending_votes: health.pending_votes,
+            last_sync: health.last_sync,
+            printer: health.printer,
+            last_error: health.last_error,
+            faults: health.faults,
+            section: health.section,
+            endpoints,
+            failovers: self.endpoints.failovers(),
+        };
+
+        self.send_with_failover(|api| {
+            self.client
+                .post(format!("{}/api/v1/urnas/{}/heartbeat", api, urna_id))
+                .json(&heartbeat)
+        })
+        .await?
+        .error_for_status()?;
+        Ok(())
+    }
+
     async fn ping_logs(&self) -> Result<bool> {
         // Em implementação real, faria requisição real para logs
         // Por enquanto, simula conectividade
@@ -112,6 +282,7 @@ impl TransparencySync {
             "electionId": vote.election_id,
             "voterId": vote.voter_id,
             "candidateId": vote.candidate_id,
+            "choices": vote.choices,
             "encryptedData": general_purpose::STANDARD.encode(&vote.encrypted_data),
             "zkProof": vote.zk_proof,
             "signature": vote.signature,
@@ -141,12 +312,12 @@ impl TransparencySync {
         Ok(true)
     }
 
-    pub async fn get_vote_status(&self, vote_id: Uuid) -> Result<VoteStatus> {
+    pub async fn get_vote_status(&self, vote_id: Uuid) -> Result<VoteSyncStatus> {
         log::debug!("Getting vote status: {}", vote_id);
 
         // Em implementação real, consultaria logs transparentes
         // Por enquanto, simula status
-        Ok(VoteStatus::Confirmed)
+        Ok(VoteSyncStatus::Confirmed)
     }
 
     pub async fn get_election_results(&self, election_id: Uuid) -> Result<ElectionResults> {
@@ -171,6 +342,439 @@ impl TransparencySync {
         })
     }
 
+    /// Baixa e verifica o pacote da eleição, gravando-o para uso offline
+    /// Dados de inclusão do voto no log, consultados pelo código de rastreamento
+    pub async fn fetch_vote_inclusion(&self, tracking_code: &str) -> Result<Option<InclusionData>> {
+        let response = self
+            .send_with_failover(|api| self.client.get(format!("{}/api/v1/votes/verify/{}", api, tracking_code)))
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let body: serde_json::Value = response.error_for_status()?.json().await?;
+        let data = body.get("data").ok_or_else(|| anyhow!("Empty vote verification response"))?;
+        let proof = data.get("inclusion_proof").ok_or_else(|| anyhow!("Missing inclusion proof"))?;
+
+        Ok(Some(InclusionData {
+            log_index: data["log_index"].as_u64().ok_or_else(|| anyhow!("Missing log index"))?,
+            event_hash: data["event_hash"].as_str().unwrap_or_default().to_string(),
+            root_hash: proof["root_hash"].as_str().unwrap_or_default().to_string(),
+            tree_size: proof["tree_size"].as_u64().unwrap_or_default(),
+        }))
+    }
+
+    pub async fn download_election_package(&self, election_id: Uuid, region: Option<&str>) -> Result<ElectionPackage> {
+        log::info!("Downloading election package: {}", election_id);
+
+        let response: serde_json::Value = self
+            .send_with_failover(|api| {
+                let request = self.client.get(format!("{}/api/v1/elections/{}/package", api, election_id));
+                match region {
+                    Some(region) => request.query(&[("region", region)]),
+                    None => request,
+                }
+            })
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let signed: SignedElectionPackage = serde_json::from_value(
+            response.get("data").cloned().ok_or_else(|| anyhow!("Empty election package response"))?
+        )?;
+
+        self.install_election_package(Some(election_id), &signed).await
+    }
+
+    /// Verifica e grava um pacote assinado recebido pela rede ou por mídia
+    pub async fn install_election_package(
+        &self,
+        expected_election: Option<Uuid>,
+        signed: &SignedElectionPackage,
+    ) -> Result<ElectionPackage> {
+        let package = self.verify_election_package(signed).await?;
+        if expected_election.is_some_and(|id| id != package.election.id) {
+            return Err(anyhow!("Election package is for election {}", package.election.id));
+        }
+        let election_id = package.election.id;
+
+        tokio::fs::create_dir_all(&self.package_dir).await?;
+        tokio::fs::write(
+            self.package_path(election_id),
+            serde_json::to_vec(signed)?,
+        ).await?;
+
+        log::info!("Election package {} stored ({})", package.package_id, signed.package_sha256);
+        Ok(package)
+    }
+
+    /// Carrega o pacote gravado, verificando novamente a assinatura
+    pub async fn load_election_package(&self, election_id: Uuid) -> Result<ElectionPackage> {
+        let data = tokio::fs::read(self.package_path(election_id)).await
+            .map_err(|_| anyhow!("No election package stored for election {}", election_id))?;
+        let signed: SignedElectionPackage = serde_json::from_slice(&data)?;
+        self.verify_election_package(&signed).await
+    }
+
+    async fn verify_election_package(&self, signed: &SignedElectionPackage) -> Result<ElectionPackage> {
+        let trusted_key = tokio::fs::read_to_string(package::TRUSTED_PACKAGE_KEY_PATH).await
+            .map_err(|e| anyhow!("Trusted package key unavailable: {}", e))?;
+        package::verify_package(signed, &trusted_key)
+    }
+
+    fn package_path(&self, election_id: Uuid) -> PathBuf {
+        self.package_dir.join(format!("{}.json", election_id))
+    }
+
+    pub async fn urna_id(&self) -> Result<String> {
+        let id = tokio::fs::read_to_string(URNA_ID_PATH).await
+            .map_err(|e| anyhow!("Urna id unavailable: {}", e))?;
+        Ok(id.trim().to_string())
+    }
+
+    /// Última sequência de lote assinada pela urna
+    pub async fn last_batch_sequence(&self) -> Result<u64> {
+        match tokio::fs::read_to_string(Path::new(VOTE_BATCH_DIR).join("sequence")).await {
+            Ok(sequence) => Ok(sequence.trim().parse()?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Lote assinado cujo envio ainda não foi confirmado
+    pub async fn pending_vote_batch(&self) -> Result<Option<SignedVoteBatch>> {
+        match tokio::fs::read(Path::new(VOTE_BATCH_DIR).join("pending.json")).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Grava o lote assinado antes do envio, para que a sequência nunca se repita
+    pub async fn store_pending_batch(&self, signed: &SignedVoteBatch) -> Result<()> {
+        let dir = Path::new(VOTE_BATCH_DIR);
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(dir.join("pending.json"), serde_json::to_vec(signed)?).await?;
+        tokio::fs::write(dir.join("sequence"), signed.batch.sequence.to_string()).await?;
+        Ok(())
+    }
+
+    /// Envia a sub-raiz assinada e devolve a posição do registro no log
+    pub async fn upload_vote_batch(&self, signed: &SignedVoteBatch) -> Result<u64> {
+        log::info!("Uploading vote batch {} ({} votes)", signed.batch.sequence, signed.batch.tracking_codes.len());
+
+        let body: serde_json::Value = self
+            .send_with_failover(|api| self.client.post(format!("{}/api/v1/urnas/batches", api)).json(signed))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let data = body.get("data").ok_or_else(|| anyhow!("Empty vote batch response"))?;
+        let log_index = data["log_index"].as_u64().ok_or_else(|| anyhow!("Missing log index"))?;
+
+        let missing = data["missing_from_log"].as_array().map(|m| m.len()).unwrap_or_default();
+        if missing > 0 {
+            log::warn!("Backend reports {} votes of batch {} missing from the log", missing, signed.batch.sequence);
+        }
+
+        match tokio::fs::remove_file(Path::new(VOTE_BATCH_DIR).join("pending.json")).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        Ok(log_index)
+    }
+
+    /// Envia à gestão da frota o relatório assinado de descomissionamento
+    pub async fn report_decommission(&self, signed: &SignedDecommissionReport) -> Result<()> {
+        log::info!("Reporting decommission of urna {}", signed.report.urna_id);
+
+        self.send_with_failover(|api| {
+            self.client
+                .post(format!("{}/api/v1/urnas/{}/decommission", api, signed.report.urna_id))
+                .json(signed)
+        })
+        .await?
+        .error_for_status()?;
+        Ok(())
+    }
+
+    /// Manifesto mais recente do canal, se mais novo que a versão instalada
+    pub async fn fetch_update_manifest(
+        &self,
+        component: UpdateComponent,
+        channel: &str,
+        installed: UpdateVersion,
+    ) -> Result<Option<UpdateManifest>> {
+        let installed = installed.to_string();
+        let response: serde_json::Value = self
+            .send_with_failover(|api| {
+                self.client
+                    .get(format!("{}/api/v1/urnas/updates/latest", api))
+                    .query(&[("component", component.as_str()), ("channel", channel), ("installed_version", &installed)])
+            })
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match response.get("data") {
+            Some(data) if !data.is_null() => Ok(Some(serde_json::from_value(data.clone())?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Baixa o artefato do repositório de distribuição, com limite de tamanho
+    pub async fn download_update_artifact(&self, manifest: &UpdateManifest, max_bytes: u64) -> Result<Vec<u8>> {
+        if manifest.artifact_size > max_bytes {
+            return Err(anyhow!("Update artifact of {} bytes exceeds the limit", manifest.artifact_size));
+        }
+        log::info!("Downloading {} {}", manifest.component.as_str(), manifest.version);
+
+        let mut response = self.client
+            .get(&manifest.artifact_url)
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut artifact = Vec::with_capacity(manifest.artifact_size as usize);
+        while let Some(chunk) = response.chunk().await? {
+            if (artifact.len() + chunk.len()) as u64 > manifest.artifact_size {
+                return Err(anyhow!("Update artifact larger than the manifest"));
+            }
+            artifact.extend_from_slice(&chunk);
+        }
+        Ok(artifact)
+    }
+
+    /// Informa o resultado da instalação de uma atualização
+    pub async fn report_update(&self, report: &InstallReport) -> Result<()> {
+        let urna_id = self.urna_id().await?;
+        self.send_with_failover(|api| {
+            self.client
+                .post(format!("{}/api/v1/urnas/{}/updates", api, urna_id))
+                .json(report)
+        })
+        .await?
+        .error_for_status()?;
+        Ok(())
+    }
+
+    /// Desafio de uso único para a atestação do boot
+    pub async fn request_attestation_challenge(&self) -> Result<AttestationChallenge> {
+        let urna_id = self.urna_id().await?;
+        let body: serde_json::Value = self
+            .send_with_failover(|api| self.client.post(format!("{}/api/v1/urnas/{}/attestation/challenge", api, urna_id)))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let data = body.get("data").ok_or_else(|| anyhow!("Empty attestation challenge response"))?;
+        Ok(serde_json::from_value(data.clone())?)
+    }
+
+    /// Envia as medições do boot e devolve o veredito do backend
+    pub async fn report_attestation(&self, report: &AttestationReport) -> Result<AttestationResult> {
+        let body: serde_json::Value = self
+            .send_with_failover(|api| {
+                self.client
+                    .post(format!("{}/api/v1/urnas/{}/attestation", api, report.urna_id))
+                    .json(report)
+            })
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let data = body.get("data").ok_or_else(|| anyhow!("Empty attestation response"))?;
+        Ok(serde_json::from_value(data.clone())?)
+    }
+
+    /// Envia os eventos de violação; o backend descarta os já recebidos
+    pub async fn report_tamper_events(&self, events: &[TamperEvent]) -> Result<()> {
+        let urna_id = self.urna_id().await?;
+        self.send_with_failover(|api| {
+            self.client
+                .post(format!("{}/api/v1/urnas/{}/tamper", api, urna_id))
+                .json(events)
+        })
+        .await?
+        .error_for_status()?;
+        Ok(())
+    }
+
+    /// Votos do envio em partes interrompido, a incluir na próxima
+    /// sincronização para que ele seja retomado
+    pub async fn resumable_vote_ids(&self) -> Result<Vec<Uuid>> {
+        Ok(self.load_vote_upload().await?.map(|upload| upload.vote_ids).unwrap_or_default())
+    }
+
+    /// Envia os votos em partes comprimidas, retomando um envio interrompido.
+    ///
+    /// O progresso é gravado após cada parte confirmada; se o link cair, a
+    /// próxima chamada reenvia só as partes sem confirmação, com as mesmas
+    /// chaves de idempotência. Votos fora do envio retomado ficam para o
+    /// envio seguinte.
+    ///
+    /// `trace_contexts` leva o `traceparent` do registro de cada voto, que o
+    /// backend usa como pai do processamento do voto.
+    #[tracing::instrument(name = "urna.sync_votes", skip_all, fields(votes = votes.len()))]
+    pub async fn sync_votes(
+        &self,
+        votes: &[EncryptedVote],
+        trace_contexts: &BTreeMap<Uuid, String>,
+    ) -> Result<Vec<VoteSyncResult>> {
+        let mut upload = match self.load_vote_upload().await? {
+            Some(upload) if upload.vote_ids.iter().all(|id| votes.iter().any(|v| v.id == *id)) => {
+                log::info!(
+                    "Resuming vote upload {} ({}/{} chunks acknowledged)",
+                    upload.upload_id,
+                    upload.acknowledged.len(),
+                    upload.vote_ids.len().div_ceil(upload.batch_size),
+                );
+                upload
+            }
+            _ => {
+                if votes.is_empty() {
+                    return Ok(Vec::new());
+                }
+                VoteUpload {
+                    upload_id: Uuid::new_v4(),
+                    batch_size: self.vote_batch_size.max(1),
+                    vote_ids: votes.iter().map(|v| v.id).collect(),
+                    acknowledged: BTreeSet::new(),
+                    results: Vec::new(),
+                }
+            }
+        };
+        self.store_vote_upload(&upload).await?;
+
+        let urna_id = self.urna_id().await?;
+        let ordered: Vec<&EncryptedVote> = upload.vote_ids.iter()
+            .filter_map(|id| votes.iter().find(|v| v.id == *id))
+            .collect();
+        let chunk_count = ordered.len().div_ceil(upload.batch_size) as u32;
+
+        for (index, part) in ordered.chunks(upload.batch_size).enumerate() {
+            let index = index as u32;
+            if upload.acknowledged.contains(&index) {
+                continue;
+            }
+            let chunk = VoteSyncChunk {
+                urna_id: urna_id.clone(),
+                upload_id: upload.upload_id,
+                chunk_index: index,
+                chunk_count,
+                votes: part.iter().map(|v| (*v).clone()).collect(),
+                trace_contexts: part.iter()
+                    .filter_map(|v| trace_contexts.get(&v.id).map(|context| (v.id, context.clone())))
+                    .collect(),
+            };
+            let ack = self.send_vote_chunk(&chunk).await?;
+
+            upload.acknowledged.insert(index);
+            upload.results.extend(ack.results);
+            self.store_vote_upload(&upload).await?;
+        }
+
+        match tokio::fs::remove_file(Path::new(VOTE_SYNC_DIR).join("upload.json")).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        log::info!("Vote upload {} complete: {} votes in {} chunks", upload.upload_id, ordered.len(), chunk_count);
+        Ok(upload.results)
+    }
+
+    /// Envia uma parte, com novas tentativas e espera crescente entre elas
+    #[tracing::instrument(name = "urna.send_vote_chunk", skip_all, fields(chunk = chunk.chunk_index, votes = chunk.votes.len()))]
+    async fn send_vote_chunk(&self, chunk: &VoteSyncChunk) -> Result<VoteSyncAck> {
+        let body = zstd::encode_all(serde_json::to_vec(chunk)?.as_slice(), self.compression_level)?;
+        let key = chunk.idempotency_key();
+
+        let mut attempt = 0;
+        loop {
+            let result = async {
+                let response: serde_json::Value = self
+                    .send_with_failover(|api| {
+                        self.client
+                            .post(format!("{}/api/v1/urnas/votes/batch", api))
+                            .header(reqwest::header::CONTENT_TYPE, "application/json")
+                            .header(reqwest::header::CONTENT_ENCODING, "zstd")
+                            .header(IDEMPOTENCY_KEY_HEADER, &key)
+                            .body(body.clone())
+                    })
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                let data = response.get("data").cloned().ok_or_else(|| anyhow!("Empty vote sync response"))?;
+                Ok::<VoteSyncAck, anyhow::Error>(serde_json::from_value(data)?)
+            }.await;
+
+            match result {
+                Ok(ack) => return Ok(ack),
+                Err(e) if attempt < self.max_retries => {
+                    attempt += 1;
+                    log::warn!("Vote chunk {} failed (attempt {}): {}", key, attempt, e);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(1 << attempt)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn load_vote_upload(&self) -> Result<Option<VoteUpload>> {
+        match tokio::fs::read(Path::new(VOTE_SYNC_DIR).join("upload.json")).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn store_vote_upload(&self, upload: &VoteUpload) -> Result<()> {
+        tokio::fs::create_dir_all(VOTE_SYNC_DIR).await?;
+        tokio::fs::write(Path::new(VOTE_SYNC_DIR).join("upload.json"), serde_json::to_vec(upload)?).await?;
+        Ok(())
+    }
+
+    /// Chave do log transparente fixada no pacote da eleição
+    pub async fn transparency_log_key(&self, election_id: Uuid) -> Result<Vec<u8>> {
+        let package = self.load_election_package(election_id).await?;
+        let key = package.public_keys.get(TRANSPARENCY_LOG_KEY_PURPOSE)
+            .ok_or_else(|| anyhow!("Election package {} has no transparency log key", package.package_id))?;
+        Ok(hex::decode(key)?)
+    }
+
+    /// Confere localmente as provas de inclusão dos votos aceitos
+    ///
+    /// A chave do log vem do pacote da eleição de cada voto, carregado uma
+    /// vez por eleição; votos recusados pelo backend não entram no resultado.
+    pub async fn verify_inclusions(
+        &self,
+        votes: &[EncryptedVote],
+        results: &[VoteSyncResult],
+    ) -> HashMap<Uuid, InclusionCheck> {
+        let mut log_keys: HashMap<Uuid, std::result::Result<Vec<u8>, String>> = HashMap::new();
+        let mut checks = HashMap::new();
+
+        for result in results.iter().filter(|result| result.is_synced()) {
+            let Some(vote) = votes.iter().find(|vote| vote.id == result.vote_id) else {
+                continue;
+            };
+            if !log_keys.contains_key(&vote.election_id) {
+                let key = self.transparency_log_key(vote.election_id).await.map_err(|e| e.to_string());
+                log_keys.insert(vote.election_id, key);
+            }
+
+            let check = match (&log_keys[&vote.election_id], &result.inclusion) {
+                (Err(e), _) => InclusionCheck::Unverified(e.clone()),
+                (Ok(_), None) => InclusionCheck::Unverified("backend returned no inclusion proof".to_string()),
+                (Ok(key), Some(inclusion)) => match inclusion.verify(key) {
+                    Ok(()) => InclusionCheck::Verified,
+                    Err(e) => InclusionCheck::Invalid(e),
+                },
+            };
+            checks.insert(result.vote_id, check);
+        }
+        checks
+    }
+
     pub async fn retry_failed_syncs(&self) -> Result<()> {
         log::info!("Retrying failed syncs");
 
@@ -181,13 +785,6 @@ impl TransparencySync {
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum VoteStatus {
-    Pending,
-    Confirmed,
-    Failed,
-}
-
 #[derive(Debug, Clone)]
 pub struct ElectionResults {
     pub election_id: Uuid,
@@ -0,0 +1,85 @@
+//! Logs e rastros distribuídos da urna
+//!
+//! Os logs continuam em texto no console, agora pelo `tracing` (as macros
+//! `log::` são encaminhadas a ele). Com `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! definido, os spans do atendimento e da sincronização também são
+//! exportados por OTLP; sem rede, o exportador descarta o que não couber no
+//! buffer e a votação segue.
+//!
+//! O contexto W3C (`traceparent`) do span em andamento vai em cada chamada
+//! ao backend, e o do registro de cada voto fica na fila de saída para
+//! acompanhar o voto quando ele for sincronizado.
+
+use anyhow::{Result, anyhow};
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use std::collections::HashMap;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+/// Variável com o endereço do coletor OTLP (gRPC)
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Nome do serviço nos rastros exportados
+const SERVICE_NAME: &str = "fortis-urna";
+
+/// Instala o subscriber; falha do exportador não impede a urna de subir
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let fmt_layer = tracing_subscriber::fmt::layer().with_filter(filter);
+
+    let (otel_layer, otel_error) = match std::env::var(OTLP_ENDPOINT_ENV) {
+        Ok(endpoint) => match otlp_tracer(&endpoint) {
+            Ok(tracer) => (
+                Some(tracing_opentelemetry::layer().with_tracer(tracer).with_filter(EnvFilter::new("info,fortis_voting_app=debug"))),
+                None,
+            ),
+            Err(e) => (None, Some(e)),
+        },
+        Err(_) => (None, None),
+    };
+
+    if let Err(e) = tracing_subscriber::registry().with(fmt_layer).with(otel_layer).try_init() {
+        eprintln!("Failed to install log subscriber: {}", e);
+    }
+    if let Some(e) = otel_error {
+        log::warn!("Distributed tracing disabled: {}", e);
+    }
+}
+
+/// Envia os spans ainda em buffer ao coletor
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// `traceparent` do span em andamento, se houver rastro ativo
+pub fn current_traceparent() -> Option<String> {
+    trace_headers().remove("traceparent")
+}
+
+/// Acrescenta à chamada os cabeçalhos do rastro em andamento
+pub fn inject(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    trace_headers()
+        .into_iter()
+        .fold(request, |request, (name, value)| request.header(name, value))
+}
+
+fn trace_headers() -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+    TraceContextPropagator::new().inject_context(&tracing::Span::current().context(), &mut headers);
+    headers
+}
+
+fn otlp_tracer(endpoint: &str) -> Result<opentelemetry_sdk::trace::Tracer> {
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+            opentelemetry_sdk::Resource::new(vec![KeyValue::new("service.name", SERVICE_NAME)]),
+        ))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .map_err(|e| anyhow!("Failed to start OTLP exporter for {}: {}", endpoint, e))
+}
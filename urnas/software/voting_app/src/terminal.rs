@@ -0,0 +1,274 @@
+//! Modo do terminal do mesário
+//!
+//! O mesário se identifica com o PIN do cadastro de mesários da seção antes
+//! de qualquer operação. Com a sessão aberta, admite o eleitor pelo número
+//! de ordem no cadastro da seção e só então o libera para a tela de votação;
+//! a biometria precisa confirmar o eleitor admitido. Eleitores de outras
+//! seções registram aqui a justificativa de ausência. No encerramento do dia
+//! o presidente da mesa gera o resumo da seção (comparecimento, faltosos e
+//! justificativas) antes de fechar a sessão. O livro do dia é gravado a cada
+//! operação para sobreviver a um reinício.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::attendant::pin_matches;
+use crate::provisioning::VoterSnapshot;
+
+/// Cadastro dos mesários da seção no pacote de configuração
+pub const MESARIO_ROSTER_PATH: &str = "/etc/fortis/bundle/mesarios.json";
+
+/// Livro do dia e resumo do encerramento
+pub const TERMINAL_DIR: &str = "/var/lib/fortis/terminal";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MesarioRole {
+    President,
+    Secretary,
+    #[default]
+    Mesario,
+}
+
+/// Mesário habilitado na seção
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mesario {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub role: MesarioRole,
+    pub pin_salt: String,
+    /// SHA-256 (hex) de sal + PIN
+    pub pin_sha256: String,
+}
+
+/// Mesário identificado no terminal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorSession {
+    pub mesario_id: String,
+    pub role: MesarioRole,
+    pub logged_in_at: DateTime<Utc>,
+}
+
+/// Eleitor admitido pelo número de ordem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Admission {
+    pub sequence: u32,
+    pub voter_id: String,
+    pub admitted_by: String,
+    pub admitted_at: DateTime<Utc>,
+}
+
+/// Justificativa de ausência de eleitor de outra seção
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Justification {
+    pub voter_title: String,
+    pub reason: String,
+    pub recorded_by: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Operações do dia na seção
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DayLedger {
+    admissions: Vec<Admission>,
+    /// Números de ordem com voto registrado
+    voted: BTreeSet<u32>,
+    justifications: Vec<Justification>,
+}
+
+/// Resumo da seção no encerramento do dia
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosingReport {
+    pub zone: String,
+    pub section: String,
+    pub registered_voters: usize,
+    pub admissions: usize,
+    pub voted: usize,
+    pub absent: usize,
+    /// Admitidos que não concluíram o voto
+    pub admitted_without_vote: Vec<u32>,
+    pub justifications: usize,
+    pub closed_by: String,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// Estado do terminal do mesário
+pub struct MesarioTerminal {
+    roster: Vec<Mesario>,
+    dir: PathBuf,
+    operator: Option<OperatorSession>,
+    pending: Option<Admission>,
+    ledger: DayLedger,
+}
+
+impl MesarioTerminal {
+    /// Carrega o cadastro de mesários e o livro do dia, se houver
+    pub fn load() -> Self {
+        let roster = match read_json::<Vec<Mesario>>(Path::new(MESARIO_ROSTER_PATH)) {
+            Ok(roster) => roster,
+            Err(e) => {
+                log::warn!("Mesário roster unavailable, terminal login disabled: {}", e);
+                Vec::new()
+            }
+        };
+        let dir = PathBuf::from(TERMINAL_DIR);
+        let ledger = read_json(&dir.join("day.json")).unwrap_or_default();
+        Self { roster, dir, operator: None, pending: None, ledger }
+    }
+
+    /// Identifica o mesário pelo PIN
+    pub fn login(&mut self, mesario_id: &str, pin: &str) -> Result<OperatorSession> {
+        let mesario = self.roster.iter()
+            .find(|m| m.id == mesario_id)
+            .filter(|m| pin_matches(&m.pin_salt, pin, &m.pin_sha256))
+            .ok_or_else(|| anyhow!("Invalid mesário credentials for {}", mesario_id))?;
+        let session = OperatorSession {
+            mesario_id: mesario.id.clone(),
+            role: mesario.role,
+            logged_in_at: Utc::now(),
+        };
+        self.operator = Some(session.clone());
+        Ok(session)
+    }
+
+    pub fn logout(&mut self) -> Option<OperatorSession> {
+        self.operator.take()
+    }
+
+    pub fn operator(&self) -> Result<&OperatorSession> {
+        self.operator.as_ref().ok_or_else(|| anyhow!("No mesário logged in at the terminal"))
+    }
+
+    /// Nome do mesário no cadastro
+    pub fn mesario_name(&self, mesario_id: &str) -> Option<&str> {
+        self.roster.iter().find(|mesario| mesario.id == mesario_id).map(|mesario| mesario.name.as_str())
+    }
+
+    /// Eleitor admitido aguardando a liberação ou em atendimento
+    pub fn pending(&self) -> Option<&Admission> {
+        self.pending.as_ref()
+    }
+
+    /// Admite o eleitor pelo número de ordem no cadastro da seção
+    pub fn admit(&mut self, sequence: u32, snapshot: &VoterSnapshot) -> Result<Admission> {
+        let admitted_by = self.operator()?.mesario_id.clone();
+        if let Some(pending) = &self.pending {
+            return Err(anyhow!("Voter {} is already admitted", pending.sequence));
+        }
+        let voter = sequence.checked_sub(1)
+            .and_then(|index| snapshot.voters.get(index as usize))
+            .ok_or_else(|| anyhow!("No voter with sequence number {} in this section", sequence))?;
+        if !voter.active {
+            return Err(anyhow!("Voter {} is not active in this section", sequence));
+        }
+        if self.ledger.voted.contains(&sequence) {
+            return Err(anyhow!("Voter {} has already voted", sequence));
+        }
+
+        let admission = Admission {
+            sequence,
+            voter_id: voter.voter_id.clone(),
+            admitted_by,
+            admitted_at: Utc::now(),
+        };
+        self.ledger.admissions.push(admission.clone());
+        self.save()?;
+        self.pending = Some(admission.clone());
+        Ok(admission)
+    }
+
+    /// Encerra a admissão; sem voto, o eleitor pode ser admitido de novo
+    pub fn finish_admission(&mut self, voted: bool) -> Result<()> {
+        if let Some(admission) = self.pending.take() {
+            if voted {
+                self.ledger.voted.insert(admission.sequence);
+                self.save()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Marca como votante o eleitor cujo voto foi gravado antes de um reinício
+    pub fn mark_voted(&mut self, sequence: u32) -> Result<()> {
+        if self.ledger.voted.insert(sequence) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Registra a justificativa de ausência de eleitor de outra seção
+    pub fn record_justification(&mut self, voter_title: &str, reason: &str, snapshot: &VoterSnapshot) -> Result<Justification> {
+        let recorded_by = self.operator()?.mesario_id.clone();
+        if voter_title.trim().is_empty() || reason.trim().is_empty() {
+            return Err(anyhow!("Justification requires the voter title and a reason"));
+        }
+        if snapshot.voters.iter().any(|v| v.voter_id == voter_title) {
+            return Err(anyhow!("Voter belongs to this section and must vote here"));
+        }
+        if self.ledger.justifications.iter().any(|j| j.voter_title == voter_title) {
+            return Err(anyhow!("Absence already justified for this voter"));
+        }
+
+        let justification = Justification {
+            voter_title: voter_title.to_string(),
+            reason: reason.trim().to_string(),
+            recorded_by,
+            recorded_at: Utc::now(),
+        };
+        self.ledger.justifications.push(justification.clone());
+        self.save()?;
+        Ok(justification)
+    }
+
+    /// Resumo do dia; só o presidente da mesa encerra, sem eleitor em atendimento
+    pub fn closing_report(&self, snapshot: &VoterSnapshot) -> Result<ClosingReport> {
+        let operator = self.operator()?;
+        if operator.role != MesarioRole::President {
+            return Err(anyhow!("Only the section president can close the day"));
+        }
+        if let Some(pending) = &self.pending {
+            return Err(anyhow!("Voter {} is still admitted", pending.sequence));
+        }
+
+        let admitted: BTreeSet<u32> = self.ledger.admissions.iter().map(|a| a.sequence).collect();
+        Ok(ClosingReport {
+            zone: snapshot.zone.clone(),
+            section: snapshot.section.clone(),
+            registered_voters: snapshot.voters.len(),
+            admissions: self.ledger.admissions.len(),
+            voted: self.ledger.voted.len(),
+            absent: snapshot.voters.len().saturating_sub(self.ledger.voted.len()),
+            admitted_without_vote: admitted.difference(&self.ledger.voted).copied().collect(),
+            justifications: self.ledger.justifications.len(),
+            closed_by: operator.mesario_id.clone(),
+            closed_at: Utc::now(),
+        })
+    }
+
+    /// Grava o resumo do encerramento ao lado do livro do dia
+    pub fn store_closing_report(&self, report: &ClosingReport) -> Result<()> {
+        write_json(&self.dir.join("closing_report.json"), report)
+    }
+
+    fn save(&self) -> Result<()> {
+        write_json(&self.dir.join("day.json"), &self.ledger)
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    std::fs::write(&temporary, serde_json::to_vec(value)?)?;
+    std::fs::rename(&temporary, path)?;
+    Ok(())
+}
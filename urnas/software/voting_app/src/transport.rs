@@ -0,0 +1,75 @@
+//! Cliente HTTP da urna restrito ao perfil TLS da eleição
+//!
+//! O perfil e a autoridade aceita são gravados na preparação da urna; sem
+//! perfil gravado vale o `restricted`. Com o certificado de dispositivo
+//! emitido no credenciamento, a urna se identifica ao backend (mTLS).
+
+use anyhow::{anyhow, Result};
+use fortis_domain::TlsProfile;
+use std::io::BufReader;
+
+/// Perfil TLS gravado na preparação
+pub const TLS_PROFILE_PATH: &str = "/etc/fortis/tls/profile";
+
+/// Autoridade que emite o certificado do backend
+pub const TLS_CA_PATH: &str = "/etc/fortis/tls/ca.crt";
+
+/// Certificado de dispositivo emitido no credenciamento da urna
+pub const DEVICE_CERT_PATH: &str = "/etc/fortis/tls/device.crt";
+
+/// Chave privada do certificado de dispositivo
+pub const DEVICE_KEY_PATH: &str = "/etc/fortis/tls/device.key";
+
+/// Perfil ativo na urna
+pub fn active_profile() -> Result<TlsProfile> {
+    match std::fs::read_to_string(TLS_PROFILE_PATH) {
+        Ok(profile) => Ok(profile.parse()?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TlsProfile::Restricted),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Cliente HTTP que só negocia versões e cifras permitidas pelo perfil
+pub fn client_builder() -> Result<reqwest::ClientBuilder> {
+    let profile = active_profile()?;
+
+    let file = std::fs::File::open(TLS_CA_PATH)
+        .map_err(|e| anyhow!("Trusted CA unavailable: {}", e))?;
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut BufReader::new(file))? {
+        roots.add(&rustls::Certificate(cert))?;
+    }
+
+    let tls = match device_identity()? {
+        Some((chain, key)) => profile.policy().rustls_mtls_client_config(roots, chain, key)?,
+        None => {
+            log::warn!("No device certificate enrolled; backend will reject urna routes");
+            profile.policy().rustls_client_config(roots)?
+        }
+    };
+    log::info!("Using TLS profile {}", profile);
+    Ok(reqwest::Client::builder()
+        .use_preconfigured_tls(tls)
+        .https_only(true))
+}
+
+/// Certificado e chave do dispositivo, se a urna já foi credenciada
+fn device_identity() -> Result<Option<(Vec<rustls::Certificate>, rustls::PrivateKey)>> {
+    let cert_file = match std::fs::File::open(DEVICE_CERT_PATH) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let chain: Vec<rustls::Certificate> = rustls_pemfile::certs(&mut BufReader::new(cert_file))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+
+    let key_file = std::fs::File::open(DEVICE_KEY_PATH)
+        .map_err(|e| anyhow!("Device key unavailable: {}", e))?;
+    let key = rustls_pemfile::pkcs8_private_keys(&mut BufReader::new(key_file))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No PKCS#8 key in {}", DEVICE_KEY_PATH))?;
+    Ok(Some((chain, rustls::PrivateKey(key))))
+}
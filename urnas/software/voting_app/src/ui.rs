@@ -5,12 +5,48 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 use crate::Candidate;
+use crate::attendant::ActivityClock;
+use crate::accessibility::{braille_label, spoken_digits, AccessibilityManager, AccessibilityProfile, RenderStyle};
+use crate::i18n::Translator;
+use crate::package::BallotPage;
+use fortis_domain::i18n::keys;
+use fortis_domain::{CandidatePosition, ContestChoice};
+
+/// Tecla BRANCO do teclado da urna
+pub const BLANK_KEY: u32 = 12;
+
+/// Entrada do eleitor na tela de um cargo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeypadEntry {
+    Number(u32),
+    Blank,
+}
+
+/// Seleção do eleitor em um cargo, antes da confirmação
+#[derive(Debug, Clone)]
+pub enum ContestSelection {
+    Candidate(Candidate),
+    Blank,
+    /// Número digitado sem candidato no cargo
+    Null(u32),
+}
+
+impl ContestSelection {
+    pub fn choice(&self, position: CandidatePosition) -> ContestChoice {
+        match self {
+            Self::Candidate(candidate) => ContestChoice::candidate(position, candidate.id),
+            Self::Blank => ContestChoice::blank(position),
+            Self::Null(_) => ContestChoice::null(position),
+        }
+    }
+}
 
 pub struct VotingInterface {
     pub display: DisplayManager,
     pub input: InputManager,
     pub audio: AudioManager,
     pub accessibility: AccessibilityManager,
+    pub i18n: Translator,
 }
 
 impl VotingInterface {
@@ -20,6 +56,7 @@ impl VotingInterface {
             input: InputManager::new()?,
             audio: AudioManager::new()?,
             accessibility: AccessibilityManager::new()?,
+            i18n: Translator::load(),
         })
     }
 
@@ -42,12 +79,49 @@ impl VotingInterface {
         Ok(())
     }
 
+    /// Aplica o idioma e o perfil de acessibilidade do eleitor liberado
+    pub async fn start_voter_session(
+        &self,
+        requested: Option<AccessibilityProfile>,
+        language: Option<&str>,
+    ) -> Result<AccessibilityProfile> {
+        if let Err(e) = self.i18n.set_language(language) {
+            log::warn!("Keeping default language: {}", e);
+        }
+        self.input.activity.touch();
+        self.accessibility.set_voice(self.i18n.tts_voice()).await;
+        let profile = self.accessibility.start_session(requested).await;
+        self.display.apply_style(&self.accessibility.style().await).await?;
+        self.accessibility.speak(&self.i18n.text(keys::ACCESSIBILITY_INTRO)).await;
+        Ok(profile)
+    }
+
+    /// Troca o perfil durante o atendimento, a pedido do eleitor
+    pub async fn set_accessibility(&self, profile: AccessibilityProfile) -> Result<()> {
+        self.accessibility.set_profile(profile).await;
+        self.display.apply_style(&self.accessibility.style().await).await
+    }
+
+    /// Volta a tela, o áudio e o idioma ao padrão para o próximo eleitor
+    pub async fn end_voter_session(&self) -> Result<()> {
+        self.i18n.reset();
+        self.accessibility.end_session().await;
+        self.display.apply_style(&self.accessibility.style().await).await
+    }
+
+    /// Mostra a mensagem e a fala com o guia por voz ligado
+    async fn say(&self, message: &str) -> Result<()> {
+        self.display.show_message(message).await?;
+        self.accessibility.speak(message).await;
+        Ok(())
+    }
+
     pub async fn show_welcome_screen(&self) -> Result<()> {
         log::info!("Showing welcome screen");
 
         // Mostrar tela de boas-vindas
-        self.display.show_message("Bem-vindo ao Sistema de Votação FORTIS").await?;
-        self.display.show_message("Sistema seguro e transparente").await?;
+        self.display.show_message(&self.i18n.text(keys::WELCOME_TITLE)).await?;
+        self.display.show_message(&self.i18n.text(keys::WELCOME_SUBTITLE)).await?;
         
         // Aguardar confirmação
         self.input.wait_for_confirmation().await?;
@@ -60,22 +134,22 @@ impl VotingInterface {
         log::info!("Showing authentication screen");
 
         // Mostrar instruções de autenticação
-        self.display.show_message("Autenticação Biométrica").await?;
-        self.display.show_message("Coloque o dedo no leitor").await?;
+        self.display.show_message(&self.i18n.text(keys::AUTH_BIOMETRIC)).await?;
+        self.display.show_message(&self.i18n.text(keys::AUTH_BIOMETRIC_HINT)).await?;
         
         // Aguardar leitura biométrica
         self.input.wait_for_biometric_input().await?;
 
         // Mostrar instruções de reconhecimento facial
-        self.display.show_message("Reconhecimento Facial").await?;
-        self.display.show_message("Olhe para a câmera").await?;
+        self.display.show_message(&self.i18n.text(keys::AUTH_FACIAL)).await?;
+        self.display.show_message(&self.i18n.text(keys::AUTH_FACIAL_HINT)).await?;
         
         // Aguardar reconhecimento facial
         self.input.wait_for_facial_input().await?;
 
         // Mostrar opção de certificado digital
-        self.display.show_message("Certificado Digital (Opcional)").await?;
-        self.display.show_message("Insira o certificado ou pressione ENTER para pular").await?;
+        self.display.show_message(&self.i18n.text(keys::AUTH_CERTIFICATE)).await?;
+        self.display.show_message(&self.i18n.text(keys::AUTH_CERTIFICATE_HINT)).await?;
         
         // Aguardar certificado (opcional)
         self.input.wait_for_certificate_input().await?;
@@ -84,13 +158,18 @@ impl VotingInterface {
         Ok(())
     }
 
-    pub async fn show_candidate_selection(&self, candidates: Vec<Candidate>) -> Result<Uuid> {
-        log::info!("Showing candidate selection screen");
+    /// Tela de um cargo da cédula; número sem candidato vira voto nulo
+    pub async fn show_contest_selection(&self, page: &BallotPage, candidates: &[Candidate]) -> Result<ContestSelection> {
+        log::info!("Showing contest {}: {}", page.order, page.title);
 
-        // Mostrar lista de candidatos
-        self.display.show_message("Selecione seu candidato").await?;
-        
-        for candidate in &candidates {
+        self.display.clear_screen().await?;
+        self.display.show_message(&self.i18n.render(keys::CONTEST_TITLE, &[("title", &page.title)])).await?;
+        self.accessibility.speak(&self.i18n.render(keys::CONTEST_SPOKEN, &[
+            ("title", &page.title),
+            ("digits", &page.number_digits.to_string()),
+        ])).await;
+
+        for candidate in candidates {
             self.display.show_candidate(
                 candidate.number,
                 &candidate.name,
@@ -98,39 +177,79 @@ impl VotingInterface {
             ).await?;
         }
 
-        // Aguardar seleção
-        let candidate_number = self.input.wait_for_candidate_selection().await?;
-
-        // Encontrar candidato selecionado
-        let candidate = candidates.iter()
-            .find(|c| c.number == candidate_number)
-            .ok_or_else(|| anyhow::anyhow!("Candidato não encontrado"))?;
+        // Aguardar os dígitos do cargo ou a tecla BRANCO
+        let entry = self.input.wait_for_candidate_selection(page.number_digits).await?;
+        let spoken = match entry {
+            KeypadEntry::Blank => self.i18n.text(keys::KEY_BLANK),
+            KeypadEntry::Number(number) => spoken_digits(number),
+        };
+        self.accessibility.speak(&spoken).await;
+        let number = match entry {
+            KeypadEntry::Blank => {
+                log::info!("Blank vote selected for {}", page.title);
+                return Ok(ContestSelection::Blank);
+            }
+            KeypadEntry::Number(number) => number,
+        };
 
-        log::info!("Candidate selected: {} - {}", candidate.number, candidate.name);
-        Ok(candidate.id)
+        match candidates.iter().find(|c| c.number == number) {
+            Some(candidate) => {
+                log::info!("Candidate selected for {}: {} - {}", page.title, candidate.number, candidate.name);
+                Ok(ContestSelection::Candidate(candidate.clone()))
+            }
+            None => {
+                log::info!("Number {} has no candidate for {}, null vote", number, page.title);
+                Ok(ContestSelection::Null(number))
+            }
+        }
     }
 
-    pub async fn confirm_vote_selection(&self, candidate_id: Uuid) -> Result<bool> {
-        log::info!("Showing vote confirmation screen");
+    /// Confirmação do cargo; `false` quando o eleitor corrige
+    pub async fn confirm_contest_selection(&self, page: &BallotPage, selection: &ContestSelection) -> Result<bool> {
+        log::info!("Showing confirmation for {}", page.title);
 
-        // Mostrar confirmação
-        self.display.show_message("Confirme seu voto").await?;
-        self.display.show_message("Digite 1 para CONFIRMAR ou 2 para CANCELAR").await?;
+        match selection {
+            ContestSelection::Candidate(candidate) => {
+                self.display.show_message(&format!("{}: {} - {}", page.title, candidate.number, candidate.name)).await?;
+                self.accessibility.speak(&self.i18n.render(keys::CONTEST_CANDIDATE_SPOKEN, &[
+                    ("title", &page.title),
+                    ("number", &candidate.number.to_string()),
+                    ("name", &candidate.name),
+                    ("party", &candidate.party),
+                ])).await;
+            }
+            ContestSelection::Blank => {
+                self.say(&format!("{}: {}", page.title, self.i18n.text(keys::CONTEST_BLANK))).await?;
+            }
+            ContestSelection::Null(number) => {
+                self.display.show_message(&format!(
+                    "{}: {} - {}", page.title, number, self.i18n.text(keys::CONTEST_WRONG_NUMBER)
+                )).await?;
+                self.display.show_message(&self.i18n.text(keys::CONTEST_NULL)).await?;
+                self.accessibility.speak(&self.i18n.text(keys::CONTEST_NULL_SPOKEN)).await;
+            }
+        }
+        self.display.show_message(&self.i18n.text(keys::CONFIRM_INSTRUCTIONS)).await?;
+        self.accessibility.speak(&self.i18n.text(keys::CONFIRM_SPOKEN)).await;
 
-        // Aguardar confirmação
         let confirmation = self.input.wait_for_confirmation_input().await?;
+        let pressed = match confirmation {
+            1 => keys::KEY_ENTER,
+            _ => keys::KEY_CANCEL,
+        };
+        self.accessibility.speak(&self.i18n.text(pressed)).await;
 
         match confirmation {
             1 => {
-                self.display.show_message("Voto confirmado!").await?;
+                self.audio.play_beep().await?;
                 Ok(true)
             }
             2 => {
-                self.display.show_message("Voto cancelado").await?;
+                self.say(&self.i18n.text(keys::CONFIRM_CORRECTED)).await?;
                 Ok(false)
             }
             _ => {
-                self.display.show_message("Opção inválida").await?;
+                self.say(&self.i18n.text(keys::CONFIRM_INVALID)).await?;
                 Ok(false)
             }
         }
@@ -139,9 +258,20 @@ impl VotingInterface {
     pub async fn show_vote_success(&self, vote_id: Uuid) -> Result<()> {
         log::info!("Showing vote success screen");
 
-        self.display.show_message("Voto registrado com sucesso!").await?;
-        self.display.show_message(&format!("ID do voto: {}", vote_id)).await?;
-        self.display.show_message("Aguarde a impressão do comprovante...").await?;
+        self.say(&self.i18n.text(keys::SUCCESS_TITLE)).await?;
+        self.display.show_message(&self.i18n.render(keys::SUCCESS_VOTE_ID, &[("vote_id", &vote_id.to_string())])).await?;
+        self.display.show_message(&self.i18n.text(keys::SUCCESS_PRINTING)).await?;
+
+        Ok(())
+    }
+
+    /// Tela fixa do modo da urna, fora do atendimento ao eleitor
+    pub async fn show_mode_screen(&self, title: &str, detail: &str) -> Result<()> {
+        log::info!("Showing mode screen: {}", title);
+
+        self.display.clear_screen().await?;
+        self.display.show_message(title).await?;
+        self.display.show_message(detail).await?;
 
         Ok(())
     }
@@ -149,9 +279,9 @@ impl VotingInterface {
     pub async fn show_error(&self, message: &str) -> Result<()> {
         log::error!("Showing error screen: {}", message);
 
-        self.display.show_message("ERRO").await?;
+        self.display.show_message(&self.i18n.text(keys::ERROR_TITLE)).await?;
         self.display.show_message(message).await?;
-        self.display.show_message("Pressione ENTER para continuar").await?;
+        self.display.show_message(&self.i18n.text(keys::ERROR_CONTINUE)).await?;
 
         self.input.wait_for_confirmation().await?;
         Ok(())
@@ -202,9 +332,21 @@ impl DisplayManager {
         // Em implementação real, ajustaria brilho real
         Ok(())
     }
+
+    /// Fonte e cores usadas a partir da próxima tela
+    pub async fn apply_style(&self, style: &RenderStyle) -> Result<()> {
+        log::debug!(
+            "Display style: {}px, {} on {}",
+            style.font_px, style.palette.foreground, style.palette.background
+        );
+        // Em implementação real, redesenharia a tela com o novo estilo
+        Ok(())
+    }
 }
 
 pub struct InputManager {
+    /// Última interação do eleitor, para o tempo limite do atendimento
+    pub activity: ActivityClock,
     pub keypad: KeypadManager,
     pub touch: TouchManager,
     pub biometric: BiometricInputManager,
@@ -213,6 +355,7 @@ pub struct InputManager {
 impl InputManager {
     pub fn new() -> Result<Self> {
         Ok(Self {
+            activity: ActivityClock::new(),
             keypad: KeypadManager::new()?,
             touch: TouchManager::new()?,
             biometric: BiometricInputManager::new()?,
@@ -233,18 +376,21 @@ impl InputManager {
         log::debug!("Waiting for confirmation");
         // Em implementação real, aguardaria input real
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        self.activity.touch();
         Ok(())
     }
 
     pub async fn wait_for_biometric_input(&self) -> Result<()> {
         log::debug!("Waiting for biometric input");
         self.biometric.wait_for_fingerprint().await?;
+        self.activity.touch();
         Ok(())
     }
 
     pub async fn wait_for_facial_input(&self) -> Result<()> {
         log::debug!("Waiting for facial input");
         self.biometric.wait_for_facial().await?;
+        self.activity.touch();
         Ok(())
     }
 
@@ -252,18 +398,22 @@ impl InputManager {
         log::debug!("Waiting for certificate input");
         // Em implementação real, aguardaria inserção de certificado
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        self.activity.touch();
         Ok(())
     }
 
-    pub async fn wait_for_candidate_selection(&self) -> Result<u32> {
-        log::debug!("Waiting for candidate selection");
+    /// Dígitos do candidato ou a tecla BRANCO
+    pub async fn wait_for_candidate_selection(&self, digits: u32) -> Result<KeypadEntry> {
+        log::debug!("Waiting for candidate selection ({} digits)", digits);
         // Em implementação real, aguardaria input real
-        Ok(13) // Simula seleção do candidato 13
+        self.activity.touch();
+        Ok(KeypadEntry::Number(13)) // Simula seleção do candidato 13
     }
 
     pub async fn wait_for_confirmation_input(&self) -> Result<u32> {
         log::debug!("Waiting for confirmation input");
         // Em implementação real, aguardaria input real
+        self.activity.touch();
         Ok(1) // Simula confirmação
     }
 }
@@ -288,6 +438,7 @@ impl KeypadManager {
                 Key::new("9", 9),
                 Key::new("ENTER", 10),
                 Key::new("CANCEL", 11),
+                Key::new("BRANCO", BLANK_KEY),
             ],
         })
     }
@@ -383,12 +534,6 @@ impl AudioManager {
         Ok(())
     }
 
-    pub async fn play_message(&self, message: &str) -> Result<()> {
-        log::debug!("Playing audio message: {}", message);
-        // Em implementação real, reproduziria áudio real
-        Ok(())
-    }
-
     pub async fn play_beep(&self) -> Result<()> {
         log::debug!("Playing beep");
         // Em implementação real, reproduziria beep real
@@ -396,46 +541,12 @@ impl AudioManager {
     }
 }
 
-pub struct AccessibilityManager {
-    pub high_contrast: bool,
-    pub large_font: bool,
-    pub audio_guidance: bool,
-    pub voice_commands: bool,
-}
-
-impl AccessibilityManager {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            high_contrast: false,
-            large_font: false,
-            audio_guidance: true,
-            voice_commands: false,
-        })
-    }
-
-    pub async fn initialize(&self) -> Result<()> {
-        log::info!("Initializing accessibility manager");
-        // Em implementação real, configuraria acessibilidade real
-        Ok(())
-    }
-
-    pub async fn enable_high_contrast(&self) -> Result<()> {
-        log::info!("Enabling high contrast mode");
-        // Em implementação real, ativaria contraste alto
-        Ok(())
-    }
-
-    pub async fn enable_large_font(&self) -> Result<()> {
-        log::info!("Enabling large font mode");
-        // Em implementação real, ativaria fonte grande
-        Ok(())
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct Key {
     pub label: String,
     pub value: u32,
+    /// Marcação em Braille da tecla
+    pub braille: String,
 }
 
 impl Key {
@@ -443,6 +554,7 @@ impl Key {
         Self {
             label: label.to_string(),
             value,
+            braille: braille_label(label),
         }
     }
 }